@@ -0,0 +1,671 @@
+### German
+
+## Toolbar
+
+toolbar-hide-console = Konsole ausblenden
+toolbar-show-console = Konsole einblenden
+toolbar-settings = Einstellungen
+toolbar-analyzer = Analysator
+toolbar-analyzer-hover = Nur-Mithören-Modus: einen Logger zu einem Funkgerät weiterleiten und den Verkehr dekodieren
+toolbar-switch-history = Umschaltverlauf
+toolbar-switch-history-hover = Warum wurde umgeschaltet? Die Gründe hinter jedem Wechsel des aktiven Funkgeräts
+toolbar-tx-stats = TX-Statistik
+toolbar-tx-stats-hover = Sendezeit und Einschaltdauer je Funkgerät und Band
+toolbar-report-problem = Problem melden
+toolbar-report-problem-hover = Aktuellen Verkehr, Diagnosen und Einstellungen speichern, um sie einem Issue anzuhängen
+toolbar-active = Aktiv
+toolbar-no-radio = Kein Funkgerät
+toolbar-amp-connected = Endstufe: verbunden
+toolbar-amp-disconnected = Endstufe: getrennt
+toolbar-amp-simulated = Endstufe: simuliert
+toolbar-amp-backup = Endstufe: Reserve aktiv
+toolbar-amp-bypassed = Endstufe: überbrückt
+
+## Radios
+
+radios-heading = Funkgeräte
+radios-none = Keine Funkgeräte. In den Einstellungen ein Funkgerät hinzufügen.
+radio-duty = Einschaltdauer { $percent } %
+
+## Settings panel
+
+amplifier-heading = Endstufe
+band-decoder-heading = Banddecoder
+switching-heading = Umschaltung
+add-radio-heading = Funkgerät hinzufügen
+settings-heading = Einstellungen
+smoothing-immediate = Sofort
+smoothing-trailing = Wenn stabil für
+smoothing-sample = Abtasten alle
+virtual-ports-add = Hinzufügen:
+virtual-ports-name = Name
+virtual-ports-name-taken = Name ist bereits vergeben
+settings-config-path = Konfiguration: { $path }
+
+## General settings
+
+settings-language = Sprache:
+settings-language-system = System ({ $language })
+settings-lockout = Sperrzeit (ms):
+settings-stale = Inaktiv nach (s):
+settings-stale-hover = So lange stumme Funkgeräte werden abgeblendet und nie automatisch gewählt. 0 (Standard) schaltet ab.
+settings-ptt-lead = PTT-Vorlauf (ms):
+settings-ptt-lead-hover = Die Endstufe so lange vor dem Funkgerät tasten. Gilt beim Tasten über Catapult.
+settings-ptt-tail = PTT-Nachlauf (ms):
+settings-ptt-tail-hover = Die Endstufe so lange nach dem Enttasten des Funkgeräts getastet halten.
+settings-amp-coalescing = Endstufen-Bündelung (ms):
+settings-amp-coalescing-hover = Eine Meldungsfolge eines Funkgeräts (z. B. Yaesu IF/FA/MD) zu einer Endstufenaktualisierung zusammenfassen. 0 (Standard) schaltet ab.
+settings-translation = Übersetzung:
+settings-translation-hover = Streng schickt der Endstufe nur, was Catapult übersetzen kann. Tolerant leitet auch unbekannte Frames unverändert weiter, wenn Funkgerät und Endstufe dieselbe Protokollfamilie sprechen. Eine Endstufe kann dies überschreiben.
+settings-amp-frequency = Endstufenfrequenz:
+settings-amp-frequency-hover = Wie oft ein VFO-Dreh die Endstufe erreicht. Abtasten sendet den ersten Schritt sofort, dann den jeweils letzten einmal pro Periode.
+settings-band-decoder-frequency = Banddecoderfrequenz:
+settings-band-decoder-frequency-hover = Wie oft ein VFO-Dreh den Banddecoder erreicht. Warten, bis das Funkgerät zur Ruhe kommt, schont die Antennenrelais.
+settings-meter-attack = Anzeige-Anstieg (ms):
+settings-meter-attack-hover = Wie schnell die Anzeigen im Funkgerätefeld auf einen neuen Wert steigen.
+settings-meter-decay = Anzeige-Abfall (ms):
+settings-meter-decay-hover = Zeit, in der eine Anzeige von Vollausschlag auf null fällt.
+settings-meter-peak-hold = Spitzenwert halten (ms):
+settings-meter-peak-hold-hover = Wie lange die Spitzenmarke stehen bleibt. 0 schaltet ab.
+settings-traffic-history = Verkehrsverlauf:
+settings-memory-budget = Speicherbudget (MiB):
+settings-memory-budget-hover = Verkehrsverlauf, Dekodier-Caches, Umschaltverlauf und DX-Spots aus einer Obergrenze bemessen, für tagelang laufende Stationen. 0 schaltet ab.
+settings-history-capped = Verlauf auf { $limit } begrenzt
+settings-show-hex = Hex anzeigen:
+settings-show-decoded = Dekodiert anzeigen:
+settings-frequency-format = Frequenzformat:
+settings-frequency-format-hover = Wie Frequenzen in Feldern, im Verkehrsmonitor, in Exporten und Meldungen angezeigt werden.
+settings-decimal-separator = Dezimaltrennzeichen:
+settings-crate-levels = Diagnosestufen je Crate
+settings-crate-levels-description = Die Diagnosestufe für einzelne Komponenten überschreiben
+virtual-ports-heading = Virtuelle Schnittstellen
+virtual-ports-description = Simulierte Funkgeräte einrichten, die in der Schnittstellenliste erscheinen
+settings-band-plan = Bandplan:
+settings-band-plan-hover = Amateurfunkbänder, in denen eine zum Senden eingegebene Frequenz liegen muss: ITU-Region 1 (Europa, Afrika), 2 (Amerika) oder 3 (Asien-Pazifik).
+
+## Settings sections
+
+analyzer-heading = Analysator
+analyzer-description = Leitet CAT-Verkehr zwischen einem Logger und einem Funkgerät weiter und dekodiert ihn
+bookmarks-heading = Lesezeichen
+bookmarks-description = „Das ist gerade passiert“-Momente im Verkehrsmonitor markieren, ohne die Tastatur zu berühren
+bundle-heading = Konfigurationspaket
+bundle-description = Eine Datei für Supportanfragen oder den Umzug auf einen anderen Rechner; Geheimnisse werden geschwärzt
+capture-heading = Mitschnittordner
+capture-description = Rohe Mitschnittdateien (oder .hex-Dumps) anderer Werkzeuge in den Verkehrsmonitor dekodieren
+contact-log-heading = Frequenzprotokoll
+contact-log-description = Zeit, Frequenz und Betriebsart notieren, sobald das aktive Funkgerät nicht mehr sendet, auch ohne Logger
+disk-log-heading = Datenträgerprotokoll
+disk-log-description = Den gesamten Verkehr in komprimierte Tagesprotokolle schreiben und später wieder laden
+dx-cluster-heading = DX-Cluster
+dx-cluster-description = Einem DX-Cluster oder Skimmer folgen und melden, wenn das aktive Funkgerät auf einer gespotteten Frequenz ist
+emulated-radio-heading = Emuliertes Funkgerät
+emulated-radio-description = Software, die nur ein Funkgerät unterstützt, eines vorspielen; es folgt dem jeweils aktiven Funkgerät
+grpc-heading = gRPC
+grpc-description = Integratoren den Mux über eine typisierte API abfragen, steuern und seine Ereignisse streamen lassen
+metrics-heading = Metriken
+metrics-description = Prometheus-Metriken (Frames, Fehler, Umschaltungen, PTT-Zeit) unter /metrics bereitstellen
+status-page-heading = Statusseite
+status-page-description = Eine schreibgeschützte Statusseite fürs Handy bereitstellen und kritische Warnungen (TX-Watchdog, Verriegelungen, Endstufenfehler, getrennte Funkgeräte) an ntfy oder einen Webhook senden
+status-page-address = Adresse:
+status-page-address-hover = Auf 0.0.0.0 lauschen, um die Seite von anderen Geräten zu erreichen
+status-page-serve = Bereitstellen
+status-page-serving = Aktiv
+status-page-ntfy = ntfy-Thema:
+status-page-webhook = Webhook:
+peer-sync-heading = Multi-OP-Abgleich
+peer-sync-description = Frequenz und Sendezustand mit anderen Catapult-Instanzen teilen und die Endstufe nie auf einem Band tasten, auf dem ein Partner sendet
+ptt-switch-heading = PTT-Schalter
+ptt-switch-description = Das aktive Funkgerät und mit ihm die Endstufe über einen Fußschalter an einer freien seriellen Schnittstelle tasten
+rigctl-heading = Hamlib rigctl
+rigctl-description = Logger über Hamlibs NET-rigctl-Modell verbinden, um das aktive Funkgerät zu lesen und zu steuern
+watches-heading = Überwachungen
+watches-description = Melden, wenn Funkgeräte oder Endstufe einen bestimmten Zustand erreichen, z. B. mode != USB && freq in 14.150..14.350
+
+## Serial pin switches (bookmarks and PTT)
+
+pin-switch-on = Schalter an
+pin-of = von
+select-port = Schnittstelle wählen
+pin-watching = Wird überwacht
+bookmarks-double-tap = PTT-Doppeltippen innerhalb
+bookmarks-select-port-first = Zuerst eine Schnittstelle für den Lesezeichenschalter wählen
+bookmarks-log-failed = Lesezeichenprotokoll konnte nicht geschrieben werden: { $error }
+bookmarks-status = Lesezeichen: { $note }
+ptt-switch-inverted = Öffner
+ptt-switch-inverted-hint = Der Schalter öffnet den Kontakt beim Drücken
+ptt-switch-shares-bookmark-pin = Der Lesezeichenschalter liegt auf demselben Pin
+ptt-switch-select-port-first = Zuerst eine Schnittstelle für den PTT-Schalter wählen
+
+## Traffic monitor
+
+traffic-heading = Verkehrsmonitor
+search-hint = Suchen
+search-hover =
+    Den gesamten Verlauf durchsuchen; Enter für den nächsten Treffer, Umschalt+Enter für den vorigen.
+    Text findet dekodierte Frames und Diagnosen; hex:FE FE 94 findet Bytes; cmd:freq (oder mode, ptt, vfo, status, power, meter, keyer, id, ai) findet eine Befehlsklasse
+search-previous = Voriger Treffer
+search-next = Nächster Treffer
+search-follow = Folgen
+search-follow-hover = Zu jedem neuen Treffer springen, sobald er eintrifft
+search-searching = Suche ... { $found } gefunden
+search-no-matches = Keine Treffer
+search-matches = { $count } Treffer
+search-clear = Suche löschen
+goto-time-hover = Zum ersten Eintrag zu oder nach dieser Zeit (UTC) springen
+timestamp-inspect-hover = Den Mux-Zustand zu diesem Zeitpunkt anzeigen
+traffic-auto-scroll = Automatisch scrollen
+traffic-resume = Fortsetzen
+traffic-pause = Pause
+traffic-export = Exportieren
+traffic-copy = In die Zwischenablage kopieren
+traffic-save = In Datei speichern...
+traffic-bookmarks = Lesezeichen ({ $count })
+traffic-filter = Filter:
+traffic-filter-all = Alle
+traffic-filter-in = Ein
+traffic-filter-out = Aus
+traffic-logs = Protokoll: { $level }
+log-level-off = Aus
+log-level-error = Fehler
+log-level-warning = Warnung
+log-level-info = Info
+log-level-debug = Debug
+traffic-dry-run-hover = Nicht gesendet: der Verstärkerkanal ist im Probelauf
+traffic-cause-hover = Wegen Frame { $frame } gesendet: zum Hervorheben klicken
+traffic-log-copied = Protokoll in die Zwischenablage kopiert
+traffic-log-saved = Protokoll unter { $path } gespeichert
+
+## Transmit statistics
+
+tx-stats-title = Sendestatistik
+tx-stats-none = In dieser Sitzung noch keine Sendungen.
+tx-stats-radio = Funkgerät
+tx-stats-band = Band
+tx-stats-tx = TX
+tx-stats-total = Gesamt
+tx-stats-longest = Längste
+tx-stats-duty = Einschaltdauer
+tx-stats-duty-note = Die Einschaltdauer ist der Sendeanteil der letzten { $minutes } min
+tx-stats-alert-above = Warnen über
+tx-stats-alert-hover = Warnen, wenn ein Funkgerät mehr als diesen Anteil des Zeitfensters sendet, z. B. bei langen Digitalbetrieb-Sitzungen mit voller Leistung
+tx-stats-over = in
+tx-stats-reset = Zurücksetzen
+tx-stats-reset-hover = Wieder bei null zu zählen beginnen
+tx-stats-duty-alert = { $radio } hat { $percent } % der letzten { $minutes } min gesendet
+
+## Disk log
+
+browse = Durchsuchen ...
+disk-log-folder = Ordner:
+disk-log-record = Aufzeichnen
+disk-log-recording = Zeichnet auf
+disk-log-open = Protokoll öffnen ...
+disk-log-file = { $name } ({ $frames } Frames)
+disk-log-from = Ab:
+disk-log-load = In den Monitor laden
+
+## Status messages
+
+status-amp-state-save-failed = Endstufenzustand konnte nicht gespeichert werden: { $error }
+status-layout-reset = Layout zurückgesetzt
+status-ports-enumerate-failed = Ports konnten nicht aufgelistet werden: { $error }
+status-amp-port-gone = Endstufe getrennt: Port nicht mehr verfügbar
+disk-log-empty = { $path }: Log ist leer
+disk-log-no-folder = Kein Ordner zum Schreiben der Logs
+disk-log-logging-to = Verkehr wird in { $folder } aufgezeichnet
+contact-log-write-failed = Schreiben fehlgeschlagen: { $error }
+invalid-address = Ungültige Adresse: { $address }
+status-page-serving-on = Statusseite wird unter http://{ $address }/ bereitgestellt
+
+## Settings window
+
+close = Schließen
+settings-reset-layout = Layout zurücksetzen
+settings-reset-layout-hover = Fenster und Bereiche wieder so anordnen wie nach einer Neuinstallation
+
+## Switch history
+
+switch-audit-title = Warum wurde umgeschaltet?
+switch-audit-none = In dieser Sitzung wurde noch nicht umgeschaltet.
+switch-audit-hint = Neueste zuerst. Auf einen Wechsel klicken, um die Gründe zu sehen.
+
+## Frequency log
+
+contact-log-file = Datei:
+contact-log-enabled = Bei jedem Loslassen der PTT protokollieren
+
+## Common
+
+address = Adresse:
+serve = Bereitstellen
+serving = Aktiv
+listening = Lauscht
+folder = Ordner:
+protocol = Protokoll:
+stop = Stopp
+connect = Verbinden
+connected = Verbunden
+rigctl-serving-on = Hamlib rigctl wird auf { $address } bereitgestellt
+grpc-serving-on = gRPC wird auf { $address } bereitgestellt
+grpc-not-built = In diesem Build nicht enthalten (mit --features grpc bauen)
+switch-confirm-title = Während des Sendens umschalten?
+switch-confirm-question = { $current } sendet. Endstufe auf { $requested } umschalten?
+switch-confirm-countdown = Bleibt in { $seconds } s auf { $radio }
+switch-confirm-switch = Umschalten
+switch-confirm-stay = Bleiben
+capture-watch = Beobachten
+capture-watching = Wird beobachtet
+capture-watching-folder = { $folder } wird auf { $protocol }-Mitschnitte beobachtet
+dx-cluster-address-hint = Host:Port
+dx-cluster-callsign = Rufzeichen:
+dx-cluster-needs-address = Zuerst Adresse und Rufzeichen eintragen
+dx-cluster-connecting = Verbindung zum DX-Cluster wird aufgebaut
+
+## Band decoder
+
+band-decoder-description = OTRSP-Banddaten und TX/RX-Routing (microHAM, SO2R-Boxen)
+port = Port:
+none = Keiner
+disconnect = Trennen
+band-decoder-no-port = Kein Port für den Banddecoder gewählt
+band-decoder-connecting = Verbindung zum Banddecoder an { $port } wird aufgebaut
+
+## Radio connections
+
+radio-disabled = { $port } ist deaktiviert
+radio-port-unavailable = { $port } nicht verfügbar
+radio-virtual-added = Virtuelles Funkgerät hinzugefügt: { $id }
+radio-virtual-removed = Virtuelles Funkgerät entfernt: { $id }
+radio-removed = Funkgerät entfernt
+radio-latency-timer-set = Latenz-Timer von { $port } auf { $ms } ms gesetzt (bis der Adapter neu eingesteckt wird)
+radio-not-connected = { $radio } ist nicht verbunden
+radio-detecting-model = Modell an { $port } wird mit dem Protokoll { $protocol } erkannt...
+radio-tuning = { $radio } wird auf { $frequency } abgestimmt
+radio-connected = { $model } an { $port } verbunden
+radio-profiled = { $radio } vermessen: { $summary }
+traffic-loaded = { $count } Frames aus { $file } geladen
+mux-restarted = Multiplexer { $reason }; neu gestartet ({ $restarts }. Neustart)
+radio-protocol-reparsed = { $radio } verwendet jetzt { $protocol }; { $frames } gepufferte Frames neu ausgewertet
+radio-protocol-changed = { $radio } verwendet jetzt { $protocol }
+amp-output-backing-up = Ausgabe staut sich ({ $bytes } Bytes in der Warteschlange); Frequenz-/Modusänderungen werden zurückgehalten
+amp-output-backing-up-cts = Ausgabe staut sich ({ $bytes } Bytes in der Warteschlange); Frequenz-/Modusänderungen werden zurückgehalten. Bei RTS/CTS-Flusskontrolle prüfen, ob der Verstärker CTS setzt
+amp-output-backing-up-xoff = Ausgabe staut sich ({ $bytes } Bytes in der Warteschlange); Frequenz-/Modusänderungen werden zurückgehalten. Bei XON/XOFF-Flusskontrolle hat der Verstärker eventuell XOFF gesendet
+amp-output-caught-up = Ausgabe wieder aufgeholt
+amp-failed-over = { $from } ausgefallen ({ $reason }); Ausgabe auf { $to } umgelegt
+amp-primary-restored = Hauptverstärker wieder in Betrieb
+
+## Emulated radio
+
+emulated-radio-radio = Funkgerät:
+emulated-radio-endpoint = Endpunkt:
+emulated-radio-endpoint-hover = host:port zum Lauschen, oder ein serieller Port wie ein Ende eines com0com- oder socat-Paars
+baud = Baud:
+emulated-radio-no-endpoint = Kein Endpunkt eingetragen
+emulated-radio-emulating = { $radio } wird auf { $endpoint } nachgebildet
+
+## Multi-Op Sync
+
+peer-sync-station = Stationsname:
+peer-sync-station-hint = Run
+peer-sync-udp-port = UDP-Port:
+peer-sync-peers = Partner:
+peer-sync-peers-hint = Host:Port, Host:Port
+peer-sync-sync = Synchronisieren
+peer-sync-syncing = Synchronisiert
+peer-sync-no-peers = Keine Partner gehört
+peer-sync-needs-peer = Zuerst mindestens eine Partneradresse eintragen
+peer-sync-started = Synchronisierung gestartet
+
+## Watches
+
+watch-firing = Ausgelöst
+remove = Entfernen
+watch-alert = Hinweis
+watch-sound = Ton
+watch-webhook = Webhook:
+watch-add = Überwachung hinzufügen
+watch-add-preset = Vorlage hinzufügen
+watch-cleared = { $watch }: aufgehoben
+dismiss = Ausblenden
+
+## Port scan
+
+scan-no-free-ports = Keine freien seriellen Ports zum Durchsuchen
+scan-scanning = { $count } Port(s) werden nach Funkgeräten durchsucht...
+scan-cancel = Suche abbrechen
+scan-start = Nach Funkgeräten suchen
+scan-start-hover = Jeden freien seriellen Port nach einem Funkgerät abfragen
+clear = Leeren
+scan-progress = { $done }/{ $total } Ports
+scan-probing = Abfrage von { $ports }
+scan-added = hinzugefügt
+scan-add = Hinzufügen...
+
+## State inspector
+
+state-inspector-title = Zustand um { $time }
+state-inspector-description = Worauf der Mux beim Protokollieren dieses Eintrags reagierte; seine Änderungen darauf folgen direkt danach
+state-inspector-too-old = Älter als der aufbewahrte Zustandsverlauf.
+state-inspector-switching-mode = Umschaltmodus: { $mode }
+state-inspector-active-radio = Aktives Funkgerät: { $radio }
+state-inspector-no-radios = Noch keine Funkgeräte
+offline = offline
+state-inspector-last-change = Letzte Änderung um { $time } ({ $ms } ms vorher)
+state-inspector-last-switch = Letzte Umschaltung #{ $id } um { $time }
+
+## CI-V sweep
+
+civ-sweep-sweeping = CI-V-Bus an { $port } wird abgesucht...
+civ-sweep-cancelled = CI-V-Suche abgebrochen: { $count } Gerät(e) gefunden
+civ-sweep-none = Keine CI-V-Geräte an { $port } haben geantwortet. Kabel prüfen und ob CI-V eingeschaltet ist.
+civ-sweep-found = CI-V-Suche hat { $count } Gerät(e) an { $port } gefunden
+civ-sweep-asking = Abfrage von { $address } mit { $baud } Baud
+cancel = Abbrechen
+civ-sweep-start = CI-V-Bus absuchen
+civ-sweep-start-hover = Jede übliche CI-V-Adresse bei jeder Baudrate nach ihrer Kennung fragen
+civ-sweep-no-devices = Keine Geräte haben geantwortet
+civ-sweep-unknown-radio = Unbekanntes Icom-Funkgerät
+civ-sweep-default-address = (Standard { $address })
+civ-sweep-use = Übernehmen
+civ-sweep-all = Alle Adressen
+civ-sweep-all-hover = Auch alle übrigen Adressen abfragen, für ein Funkgerät mit geänderter Adresse. Dauert etwa eine halbe Minute pro Baudrate.
+
+## Configuration bundle
+
+bundle-export = Exportieren...
+bundle-include-logs = Verkehrsprotokoll einschließen
+bundle-import = Importieren...
+bundle-import-radios = Funkgeräte aus OmniRig / HRD importieren...
+bundle-exported = Konfiguration nach { $path } exportiert
+bundle-not-imported = Nicht importiert: { $radio }
+bundle-import-file = { $file } importieren
+bundle-import-counts = { $radios } Funkgeräte, { $virtual_radios } virtuelle Funkgeräte, { $virtual_ports } virtuelle Ports
+bundle-redacted = { $count } geschwärzte Werte behalten ihre aktuelle Einstellung
+apply = Übernehmen
+bundle-imported = Konfiguration importiert; catapult neu starten, um Funkgeräte und Verstärker neu zu verbinden
+
+## Analyzer
+
+analyzer-mode-on = Analysemodus: Umschalten und Verstärker deaktiviert
+analyzer-mode-off = Analysemodus aus
+start = Start
+running = Läuft
+analyzer-statistics = Statistik
+reset = Zurücksetzen
+copy = Kopieren
+analyzer-statistics-copied = Analysestatistik in die Zwischenablage kopiert
+analyzer-frames = Frames
+analyzer-bytes = Bytes
+analyzer-unknown = Unbekannt
+analyzer-running = Analyse { $logger } <-> { $radio }
+
+## Problem report
+
+bug-report-title = Problem melden
+bug-report-description = Was ist schiefgegangen, und was haben Sie gerade getan?
+bug-report-include = Einschließen:
+bug-report-traffic = Verkehr
+bug-report-diagnostics = Diagnose
+bug-report-settings = Einstellungen
+bug-report-ports = Serielle Ports
+bug-report-debug = Diagnose auf Debug-Stufe aufzeichnen, solange dies geöffnet ist
+bug-report-debug-hover = Das Problem jetzt nachstellen und dann Aktualisieren drücken, damit der Bericht alle Details enthält
+bug-report-privacy = Geheimnisse in den Einstellungen werden geschwärzt, USB-Seriennummern weggelassen und Ihr Benutzerordner als ~ angezeigt
+bug-report-bytes = { $bytes } Bytes
+bug-report-refresh = Aktualisieren
+bug-report-truncated = Die ersten { $shown } KiB von { $total } KiB werden angezeigt
+bug-report-consent = Ich habe diese { $files } Dateien ({ $kib } KiB) geprüft und möchte sie speichern
+bug-report-save = Bericht speichern...
+bug-report-save-hover = Eine ZIP-Datei zum Anhängen an ein GitHub-Issue schreiben
+bug-report-saved = Problembericht unter { $path } gespeichert
+
+## Safe mode
+
+safe-mode-banner = ABGESICHERTER MODUS
+safe-mode-recovery = Wiederherstellung...
+safe-mode-title = Abgesicherter Modus
+safe-mode-failed-starts = Catapult konnte die letzten { $count } Male nicht fertig starten.
+safe-mode-explanation = Es wurde ohne Verbindung zu Funkgeräten und mit Standardeinstellungen gestartet. Änderungen im abgesicherten Modus werden nicht gespeichert.
+safe-mode-suspect = Beim Abbruch wurde gerade { $radio } verbunden.
+safe-mode-disable-radio = Funkgerät deaktivieren und normal starten
+safe-mode-disable-radio-hover = { $radio } eingerichtet lassen, aber nicht verbinden
+safe-mode-start-normally = Normal starten
+safe-mode-stay = Im abgesicherten Modus bleiben
+safe-mode-unreadable-title = Einstellungen konnten nicht geladen werden
+safe-mode-unreadable-explanation = Catapult wurde ohne Verbindung zu Funkgeräten und mit Standardeinstellungen gestartet. Änderungen werden jetzt nicht gespeichert.
+safe-mode-backup-loads = Eine Sicherung vom { $time } lässt sich laden.
+safe-mode-no-backup = Auch keine Sicherung lässt sich laden.
+safe-mode-restore-backup = Sicherung wiederherstellen
+safe-mode-start-defaults = Mit Standardwerten starten
+safe-mode-start-defaults-hover = Die unlesbare Datei bleibt als settings.json.broken neben der neuen erhalten
+safe-mode-decide-later = Später entscheiden
+safe-mode-disabled = { $radio } deaktiviert
+safe-mode-left = Abgesicherten Modus verlassen
+
+## Amplifier
+
+amp-com-port = COM-Port
+amp-simulated = Simuliert
+amp-auto-info = Auto-Info
+amp-polling = Abfrage
+select-port-menu = Port wählen...
+amp-connection = Verbindung:
+baud-rate = Baudrate:
+amp-simulation-mode = Simulationsmodus:
+amp-flow-software = Software (XON/XOFF)
+amp-flow-hardware = Hardware (RTS/CTS)
+disconnected = Getrennt
+amp-respond-as = Antworten als:
+amp-respond-as-hover = Für Verstärker, die ein Protokoll senden, aber Antworten in einem anderen erwarten
+amp-same-protocol = Wie Protokoll
+amp-translation = Übersetzung:
+amp-translation-hover = „Tolerant“ leitet Frames, die Catapult nicht erkennt, weiter, wenn dieser Verstärker die Protokollfamilie des Funkgeräts spricht. Gilt sofort.
+amp-translation-global = Global ({ $strictness })
+amp-flow-control = Flusskontrolle:
+civ-address = CI-V-Adresse:
+amp-auto-info-hover = Vom Funkgerät gesendete Zustandsänderungen empfangen
+amp-polling-hover = Funkgerät aktiv nach der Frequenz abfragen
+amp-frequency = Freq.:
+amp-dry-run = Probelauf
+amp-dry-run-hover = Alles für den Verstärker übersetzen und im Verkehrsmonitor anzeigen, aber nichts an seinen Port schreiben
+amp-dry-run-active = An den Verstärker wird nichts gesendet
+amp-transition = Während eine Umschaltung zurückgehalten wird:
+amp-transition-hover = Ein Funkgerät will den Verstärker während der Umschaltsperre oder sendet auf dem Band des aktiven Funkgeräts. „Besetzt melden“ beantwortet Frequenz- und Modusabfragen des Verstärkers mit ?; (CI-V: NG)
+amp-gapless-handover = Verstärker vor dem Tasten nach einer Umschaltung abgleichen
+amp-gapless-handover-hover = Frequenz und Modus des neuen Funkgeräts an den Verstärker senden und das Tasten zurückhalten, bis sie geschrieben sind (höchstens 500 ms)
+amp-personality = Verstärker:
+amp-bypass = Außerhalb des Bereichs überbrücken
+amp-bypass-hover = Verstärker auf Standby schalten, wenn das aktive Funkgerät seinen Bereich verlässt
+amp-bypass-range = Bereich (kHz):
+amp-forward-meters = S-Meter des Funkgeräts am Verstärker anzeigen
+amp-forward-meters-hover = Meterabfragen des Verstärkers mit dem letzten Wert des aktiven Funkgeräts beantworten
+amp-ptt-latency = PTT-Latenz: { $last } ms (Mittel { $mean }, max. { $max })
+amp-ptt-latency-hover = Vom Tasten des Funkgeräts bis zur Meldung an den Verstärker über { $edges } Flanken; { $over } über dem Budget von { $budget } ms
+amp-queries = Verstärkerabfragen: { $queries }
+amp-no-port = Kein Verstärkerport gewählt
+amp-connecting = Verbindung zum Verstärker an { $port } mit { $baud } Baud wird aufgebaut
+amp-virtual-connected = Mit virtuellem Verstärker verbunden (Protokoll: { $protocol })
+amp-disconnected = Verstärker getrennt
+shadow-amp-heading = A/B-Schattenverstärker
+shadow-amp-description = Den Verstärkerdatenstrom an einen zweiten Verstärker spiegeln und Abweichungen melden
+shadow-amp-needs-primary = Zuerst den Hauptverstärker verbinden
+shadow-amp-comparing = Vergleicht
+shadow-amp-stats = { $windows } Fenster verglichen, { $mismatches } Abweichungen
+shadow-amp-save-report = Bericht speichern...
+shadow-amp-report-saved = Schattenbericht unter { $path } gespeichert
+shadow-amp-connecting = Verstärker wird mit dem Schatten an { $port } verglichen
+shadow-amp-simulated = Verstärker wird mit einem simulierten Schatten verglichen
+backup-amp-heading = Ersatzverstärker
+backup-amp-description = Ausgabe auf einen Bereitschaftsverstärker umlegen oder überbrücken, wenn der aktive Verstärker ausfällt
+backup-amp-failover = Automatisch umschalten
+backup-amp-failover-hover = Wenn der Port des Verstärkers einen Fehler meldet oder der Verstärker eine Störung meldet, auf den Ersatzverstärker umschalten, oder alle Verstärker überbrücken, wenn es keinen gibt
+backup-amp-standing-by = In Bereitschaft
+backup-amp-failed-over = Ausgabe auf { $amp } ({ $reason })
+backup-amp-acknowledge = Bestätigen
+backup-amp-acknowledge-hover = Hauptverstärker wieder in Betrieb nehmen (zuerst neu verbinden, falls sein Port ausgefallen ist)
+backup-amp-connecting = Ersatzverstärker an { $port } in Bereitschaft
+backup-amp-simulated = Simulierter Ersatzverstärker in Bereitschaft
+
+## Radio dialog
+
+radio-dialog-needs-flrig-address = Adresse von flrig als Host:Port eingeben
+radio-dialog-needs-server-address = Adresse des seriellen Servers als Host:Port eingeben
+radio-dialog-needs-port = Einen Port wählen
+radio-dialog-port-in-use = { $port } wird bereits verwendet
+radio-dialog-not-host-port = { $address } ist keine Host:Port-Adresse
+radio-dialog-civ-reserved = CI-V-Adresse { $address } ist reserviert (01 bis DF verwenden)
+radio-dialog-civ-not-hex = CI-V-Adresse muss hexadezimal sein, z. B. 94
+radio-dialog-needs-8-bits = { $protocol } sendet binäre Frames und braucht 8 Datenbits
+radio-dialog-rts-with-hardware-flow = RTS kann bei Hardware-Flusskontrolle nicht gesetzt werden
+radio-dialog-flrig-needs-polling = Funkgeräte hinter flrig müssen abgefragt werden
+radio-dialog-poll-too-fast = Abfrageintervall muss mindestens { $ms } ms betragen (oder 0 für keine Abfrage)
+radio-dialog-settle-too-long = Wartezeit darf höchstens { $ms } ms betragen
+radio-dialog-gap-too-long = Befehlsabstand darf höchstens { $ms } ms betragen
+radio-dialog-calibration-too-large = Kalibrierung darf höchstens { $hz } Hz in jede Richtung betragen
+radio-dialog-transverter-not-mhz = Transverter-Versatz muss in MHz angegeben werden, z. B. 116
+radio-dialog-save = Speichern
+radio-dialog-add = Funkgerät hinzufügen
+radio-dialog-virtual-port-missing = Virtueller Port „{ $port }“ nicht gefunden
+radio-dialog-updated = Funkgerät aktualisiert: { $port }
+radio-dialog-connection = Verbindung
+radio-dialog-transport = Übertragung:
+radio-dialog-serial-port = Serieller Port
+radio-dialog-tcp-hover = Ein serieller Server wie ser2net oder eine entfernte Station
+radio-dialog-flrig-hover = Ein Funkgerät, das flrig bereits steuert, verfolgt über dessen XML-RPC-Server
+host-port-hint = Host:Port
+radio-dialog-flrig-push = Änderungen an flrig senden
+radio-dialog-flrig-push-hover = Funkgerät über flrig abstimmen, Modus wechseln und tasten, wenn der Mux es verlangt. Aus: das Funkgerät wird nur verfolgt.
+radio-dialog-bluetooth-pair = Bluetooth-Verbindung: Das Funkgerät muss gekoppelt sein
+radio-dialog-bluetooth-baud = Bei Bluetooth nicht verwendet
+radio-dialog-format = Format:
+radio-dialog-parity-odd = Ungerade
+radio-dialog-parity-even = Gerade
+radio-dialog-stop-bits = { $bits } Stopp
+radio-dialog-flow-control = Flusskontr.:
+radio-dialog-flow-software = Software (XON/XOFF)
+radio-dialog-flow-hardware = Hardware (RTS/CTS)
+radio-dialog-dtr-hover = Manche Interfaces versorgen das Funkgerät über DTR oder tasten es damit
+radio-dialog-rts-hover = Manche Interfaces tasten das Funkgerät über RTS
+radio-dialog-settle = Wartezeit:
+radio-dialog-settle-hover = Wie lange nach dem Öffnen des Ports gewartet wird, bevor mit dem Funkgerät gesprochen wird. Manche USB-Adapter (CH340) verwerfen einige hundert ms lang, was geschrieben wird.
+radio-dialog-settle-custom = Eigene
+radio-dialog-settle-default = Standard
+radio-dialog-radio = Funkgerät
+radio-dialog-virtual = Simuliertes Funkgerät; das Protokoll kommt vom virtuellen Port
+radio-dialog-model = Modell:
+radio-dialog-detect = Erkennen
+radio-dialog-detect-hover = Das Funkgerät mit dem gewählten Protokoll nach seinem Modell fragen
+radio-dialog-alias = Alias:
+radio-dialog-alias-hint = z. B. Run, Mult
+radio-dialog-polling = Abfrage und Taktung
+radio-dialog-poll-idle = Abfrage im Leerlauf:
+radio-dialog-poll-idle-hover = Nach so langer Zeit ohne Verkehr die Frequenz abfragen. 0 fragt nie ab.
+radio-dialog-command-gap = Befehlsabstand:
+radio-dialog-command-gap-hover = Mindestzeit zwischen Befehlen, für Funkgeräte, die direkt aufeinanderfolgende Befehle verwerfen
+radio-dialog-frequency = Frequenz
+radio-dialog-calibration = Kalibrierung:
+radio-dialog-calibration-hover = Wird zur Frequenz des Funkgeräts addiert, um eine abweichende Referenz auszugleichen
+radio-dialog-transverter = Transverter-LO:
+radio-dialog-transverter-hover = Wird zur Anzeige des Funkgeräts addiert, damit Verstärker und Banddecoder die Sendefrequenz sehen (z. B. 116 für 2 m an einer 28-MHz-ZF)
+radio-dialog-transverter-hint = keiner
+radio-dialog-kat500 = KAT500-Tuner an dieser Leitung
+radio-dialog-kat500-hover = Den Tuner bei jedem Bandwechsel des Funkgeräts auf dessen Band schalten (~BN)
+
+## Radio panel
+
+radio-peer-block = Verstärker gesperrt: { $peer } belegt das Band
+radio-dx-spot = Auf DX-Spot: { $spot }
+radio-disabled-tag = deaktiviert
+radio-disabled-hover = Nicht verbunden, bis es aktiviert wird
+radio-rx-only = Nur RX
+radio-rx-only-following-hover = Monitorkanal: steuert nie den Verstärker, folgt der Frequenz des aktiven Funkgeräts
+radio-rx-only-hover = Monitorkanal: steuert nie den Verstärker
+radio-profiled-tag = vermessen
+radio-no-data-for = Seit { $seconds } s keine Daten
+radio-no-data = Keine Daten empfangen
+radio-idle = untätig
+radio-idle-hover = { $ago } - wird beim automatischen Umschalten übersprungen
+radio-same-band = gleiches Band ({ $band })
+radio-same-band-hover = Auf das Band des aktiven Funkgeräts abgestimmt
+radio-duty-hover = Sendet über der Warnschwelle für die Einschaltdauer
+radio-syncing = Abgleich { $step }/{ $total }
+radio-syncing-hover = Anfangszustand wird vom Funkgerät gelesen
+radio-select = Auswählen
+radio-less = Weniger
+radio-more = Mehr
+radio-power-hover = Eingestellte Ausgangsleistung
+sim-band = Band:
+sim-tune = Abstimmen:
+sim-split = Split
+sim-split-hover = Auf dem anderen VFO senden
+sim-equalize-hover = Den gewählten VFO auf den anderen kopieren
+sim-rules = Regeln ({ $count })
+sim-faults = Fehler
+sim-mode = Modus:
+radio-tune-to = Abstimmen auf:
+radio-tune-to-hint = 14,074, 7074 kHz
+radio-tune-to-hover = MHz, kHz oder Hz; wird mit Bereich und Abstimmschritt des Funkgeräts und beim Senden mit den Amateurfunkbändern abgeglichen
+radio-tune = Abstimmen
+radio-protocol-hover = Protokoll ohne Neuverbindung wechseln; der letzte Verkehr wird mit dem neuen neu ausgewertet
+radio-input-throttle = Eingangsdrossel (ms):
+radio-input-throttle-hover = Schübe von Frequenz-, Modus- und Meterberichten (z. B. eines IC-7610 im Transceive-Modus beim Abstimmen) zu einer Aktualisierung pro Fenster zusammenfassen. 0 schaltet ab.
+radio-receive-only = Nur Empfang
+radio-receive-only-hover = Ein Monitorkanal wie ein SDR: wird hier angezeigt, aber nie zum aktiven Funkgerät, daher folgt ihm der Verstärker nie
+radio-follow-active = Aktivem Funkgerät folgen
+radio-follow-active-hover = Dieses Funkgerät bei jeder Änderung auf die Frequenz des aktiven Funkgeräts abstimmen
+radio-usb-serial = USB-Seriennummer: { $serial }
+radio-split-frames = Geteilte Frames: { $percent } % (bis zu { $reads } Lesevorgänge, { $ms } ms)
+radio-split-frames-hover = Frames, die in mehr als einem Lesevorgang ankamen, meist vom Latenz-Timer des USB-Adapters zurückgehalten
+radio-latency-timer = Latenz-Timer: { $ms } ms
+radio-latency-timer-unknown = Latenz-Timer: unbekannt
+radio-latency-timer-fix = Auf { $ms } ms setzen
+radio-profile = Profil: { $summary }
+radio-reprofile = Neu vermessen
+radio-reprofile-hover = Vergessen, was über dieses Funkgerät gelernt wurde, und es neu befragen (verbindet neu)
+radio-civ-echo-back = CI-V USB Echo Back ist an (Echos werden gefiltert)
+radio-edit = Bearbeiten...
+radio-edit-hover = Port, Leitungseinstellungen, Abfrage und Versätze
+radio-enable = Aktivieren
+radio-tune-transmit = TX
+radio-tune-transmit-hover = Zum Senden: außerhalb der Amateurfunkbänder des Bandplans in den Einstellungen abgelehnt
+
+## Switching
+
+switching-mode = Modus:
+switching-confirm-tx = Umschalten weg von einem sendenden Funkgerät bestätigen
+switching-confirm-tx-hover = Die Auswahl eines anderen Funkgeräts während eines Durchgangs wartet auf Ihre Bestätigung
+switching-carry-keyer-speed = Keyer-Geschwindigkeit auf das neu aktive Funkgerät übertragen
+switching-carry-keyer-speed-hover = Bei jeder Umschaltung die CW-Geschwindigkeit des neuen Funkgeräts auf die zuletzt verwendete setzen
+switching-band-mode-memory = Zuletzt verwendeten Modus je Band wiederherstellen
+switching-band-mode-memory-hover = Wechselt das aktive Funkgerät das Band, auf den dort zuletzt verwendeten Modus schalten, wie Bandstapelregister
+switching-suppress-repeats = Berichte ignorieren, die den Zustand eines Funkgeräts wiederholen
+switching-suppress-repeats-hover = Beantwortet ein nicht aktives Funkgerät eine Abfrage mit gleicher Frequenz, gleichem Modus oder gleicher Leistung, wird das im Verkehrsmonitor angezeigt, aber nicht erneut verarbeitet. Berichte des aktiven Funkgeräts erreichen immer den Verstärker, und PTT wird nie ignoriert.
+switching-same-band = Gleiches Band:
+switching-same-band-hover = Was geschehen soll, wenn ein zweites Funkgerät auf das Band des aktiven abgestimmt ist
+switching-per-mode = CW, Sprache und Digital getrennt zählen
+switching-per-mode-hover = Field Day: ein Sender pro Band und Modus
+
+## Simulation
+
+sim-clock = Simulation:
+sim-pause-hover = Periodische Berichte und Abfragen auf virtuellen Geräten anhalten
+sim-step = Schritt
+sim-step-hover = Angehaltene Uhr um { $seconds } s weiterstellen
+add-radio-description = Ein serieller Port, ein serieller TCP-Server oder ein virtueller Port
+add-radio = Funkgerät hinzufügen...
+add-radio-hover = Ein Funkgerät einrichten und verbinden
+menu-item = Menü:
+menu-read = Lesen
+menu-value = Wert: { $value }
+menu-write = Schreiben
+sim-go-silent = Verstummen
+sim-go-silent-hover = Alle Befehle ignorieren und nichts senden, wie ein ausgeschaltetes Funkgerät
+sim-go-silent-for = für
+sim-malformed = Fehlerhafter Frame
+sim-malformed-hover = Ein Frequenzbericht, der sich nicht dekodieren lässt, oder ein abgeschnittener Binärframe
+sim-report-frequency = { $frequency } melden
+sim-report-frequency-hover = Diese Frequenz melden, ohne darauf abzustimmen
+sim-wrong-mode = Falscher Modus
+sim-wrong-mode-hover = { $mode } melden, ohne den Modus zu wechseln
+sim-remove-rule = Regel entfernen
@@ -0,0 +1,671 @@
+### English (the reference: every message is defined here first)
+
+## Toolbar
+
+toolbar-hide-console = Hide Console
+toolbar-show-console = Show Console
+toolbar-settings = Settings
+toolbar-analyzer = Analyzer
+toolbar-analyzer-hover = Monitor-only mode: relay a logger to a radio and decode the traffic
+toolbar-switch-history = Switch History
+toolbar-switch-history-hover = Why did it switch? The causes behind each change of active radio
+toolbar-tx-stats = TX Stats
+toolbar-tx-stats-hover = Time on the air and duty cycle per radio and band
+toolbar-report-problem = Report a Problem
+toolbar-report-problem-hover = Save recent traffic, diagnostics and settings to attach to an issue
+toolbar-active = Active
+toolbar-no-radio = No radio
+toolbar-amp-connected = Amp: Connected
+toolbar-amp-disconnected = Amp: Disconnected
+toolbar-amp-simulated = Amp: Simulated
+toolbar-amp-backup = Amp: On Backup
+toolbar-amp-bypassed = Amp: Bypassed
+
+## Radios
+
+radios-heading = Radios
+radios-none = No radios. Open Settings to add a radio.
+radio-duty = duty { $percent }%
+
+## Settings panel
+
+amplifier-heading = Amplifier
+band-decoder-heading = Band Decoder
+switching-heading = Switching
+add-radio-heading = Add Radio
+settings-heading = Settings
+smoothing-immediate = Immediate
+smoothing-trailing = When stable for
+smoothing-sample = Sample every
+virtual-ports-add = Add:
+virtual-ports-name = Name
+virtual-ports-name-taken = Name already exists
+settings-config-path = Config: { $path }
+
+## General settings
+
+settings-language = Language:
+settings-language-system = System ({ $language })
+settings-lockout = Lockout time (ms):
+settings-stale = Stale radio after (s):
+settings-stale-hover = Radios silent this long are dimmed and never auto-selected. 0 (the default) disables.
+settings-ptt-lead = PTT lead (ms):
+settings-ptt-lead-hover = Key the amplifier this long before the radio. Applies when keying through Catapult.
+settings-ptt-tail = PTT tail (ms):
+settings-ptt-tail-hover = Hold the amplifier keyed this long after the radio unkeys.
+settings-amp-coalescing = Amp coalescing (ms):
+settings-amp-coalescing-hover = Merge a radio's burst of reports (e.g. Yaesu IF/FA/MD) into one amplifier update. 0 (the default) disables.
+settings-translation = Translation:
+settings-translation-hover = Strict sends the amplifier only what Catapult can translate. Permissive also forwards frames it doesn't recognise, unchanged, when the radio and amplifier speak the same protocol family. An amplifier can override this.
+settings-amp-frequency = Amp frequency:
+settings-amp-frequency-hover = How often a VFO spin reaches the amplifier. Sampling sends the first step at once, then the latest once per period.
+settings-band-decoder-frequency = Band decoder frequency:
+settings-band-decoder-frequency-hover = How often a VFO spin reaches the band decoder. Waiting for the radio to settle keeps antenna relays quiet.
+settings-meter-attack = Meter attack (ms):
+settings-meter-attack-hover = How quickly radio panel meters rise to a new reading.
+settings-meter-decay = Meter decay (ms):
+settings-meter-decay-hover = Time for a meter to fall from full scale to zero.
+settings-meter-peak-hold = Meter peak hold (ms):
+settings-meter-peak-hold-hover = How long the peak marker stays up. 0 disables.
+settings-traffic-history = Traffic history:
+settings-memory-budget = Memory budget (MiB):
+settings-memory-budget-hover = Size traffic history, decode caches, switch history and DX spots from one ceiling, for stations left running for days. 0 turns it off.
+settings-history-capped = history capped at { $limit }
+settings-show-hex = Show hex:
+settings-show-decoded = Show decoded:
+settings-frequency-format = Frequency format:
+settings-frequency-format-hover = How frequencies are shown in panels, the traffic monitor, exports and alerts.
+settings-decimal-separator = Decimal separator:
+settings-crate-levels = Diagnostic levels by crate
+settings-crate-levels-description = Override the diagnostics level for individual components
+virtual-ports-heading = Virtual Ports
+virtual-ports-description = Configure simulated radios that appear in the port dropdown
+settings-band-plan = Band plan:
+settings-band-plan-hover = Amateur bands a frequency typed for transmit must be in: ITU Region 1 (Europe, Africa), 2 (the Americas) or 3 (Asia-Pacific).
+
+## Settings sections
+
+analyzer-heading = Analyzer
+analyzer-description = Relays CAT traffic between a logger and a radio and decodes it
+bookmarks-heading = Bookmarks
+bookmarks-description = Mark "that just happened" moments in the traffic monitor without touching the keyboard
+bundle-heading = Configuration Bundle
+bundle-description = One file for support requests or moving to another machine; secrets are redacted
+capture-heading = Capture Folder
+capture-description = Decode raw capture files (or .hex dumps) from other tools into the traffic monitor
+contact-log-heading = Frequency Log
+contact-log-description = Note the time, frequency and mode each time the active radio unkeys, even with no logger attached
+disk-log-heading = Disk Log
+disk-log-description = Record all traffic to compressed daily logs, and load them back later
+dx-cluster-heading = DX Cluster
+dx-cluster-description = Follow a DX cluster or skimmer and flag when the active radio is on a spotted frequency
+emulated-radio-heading = Emulated Radio
+emulated-radio-description = Present one radio to software that only supports one; it follows whichever radio is active
+grpc-heading = gRPC
+grpc-description = Let integrators query and control the mux and stream its events over a typed API
+metrics-heading = Metrics
+metrics-description = Serve Prometheus metrics (frames, errors, switches, PTT time) at /metrics
+status-page-heading = Status Page
+status-page-description = Serve a read-only status page for a phone, and push critical alerts (TX watchdog, interlocks, amp faults, radio disconnects) to ntfy or a webhook
+status-page-address = Address:
+status-page-address-hover = Listen on 0.0.0.0 to reach the page from other devices
+status-page-serve = Serve
+status-page-serving = Serving
+status-page-ntfy = ntfy topic:
+status-page-webhook = Webhook:
+peer-sync-heading = Multi-Op Sync
+peer-sync-description = Share frequency and TX state with other Catapult instances and never key the amp on a band a peer is transmitting on
+ptt-switch-heading = PTT Switch
+ptt-switch-description = Key the active radio, and the amplifier with it, from a foot switch on a spare serial port
+rigctl-heading = Hamlib rigctl
+rigctl-description = Let loggers connect with Hamlib's NET rigctl model to read and control the active radio
+watches-heading = Watches
+watches-description = Alert when the radios or amplifier get into a state you care about, e.g. mode != USB && freq in 14.150..14.350
+
+## Serial pin switches (bookmarks and PTT)
+
+pin-switch-on = Switch on
+pin-of = of
+select-port = Select port
+pin-watching = Watching
+bookmarks-double-tap = PTT double-tap within
+bookmarks-select-port-first = Select a port for the bookmark switch first
+bookmarks-log-failed = Could not write bookmark log: { $error }
+bookmarks-status = Bookmark: { $note }
+ptt-switch-inverted = Normally closed
+ptt-switch-inverted-hint = The switch opens the contact when pressed
+ptt-switch-shares-bookmark-pin = The bookmark switch is on the same pin
+ptt-switch-select-port-first = Select a port for the PTT switch first
+
+## Traffic monitor
+
+traffic-heading = Traffic Monitor
+search-hint = Find
+search-hover =
+    Search the whole history; Enter for the next match, Shift+Enter for the previous.
+    Text matches decoded frames and diagnostics; hex:FE FE 94 matches bytes; cmd:freq (or mode, ptt, vfo, status, power, meter, keyer, id, ai) matches a command class
+search-previous = Previous match
+search-next = Next match
+search-follow = Follow
+search-follow-hover = Jump to each new match as it arrives
+search-searching = Searching... { $found } found
+search-no-matches = No matches
+search-matches = { $count } matches
+search-clear = Clear search
+goto-time-hover = Jump to the first entry at or after this time (UTC)
+timestamp-inspect-hover = Show the mux state at this moment
+traffic-auto-scroll = Auto-scroll
+traffic-resume = Resume
+traffic-pause = Pause
+traffic-export = Export
+traffic-copy = Copy to Clipboard
+traffic-save = Save to File...
+traffic-bookmarks = Bookmarks ({ $count })
+traffic-filter = Filter:
+traffic-filter-all = All
+traffic-filter-in = In
+traffic-filter-out = Out
+traffic-logs = Logs: { $level }
+log-level-off = Off
+log-level-error = Error
+log-level-warning = Warning
+log-level-info = Info
+log-level-debug = Debug
+traffic-dry-run-hover = Not sent: the amplifier channel is in dry run
+traffic-cause-hover = Sent because of frame { $frame }: click to highlight it
+traffic-log-copied = Log copied to clipboard
+traffic-log-saved = Log saved to { $path }
+
+## Transmit statistics
+
+tx-stats-title = Transmit Statistics
+tx-stats-none = No transmissions yet this session.
+tx-stats-radio = Radio
+tx-stats-band = Band
+tx-stats-tx = TX
+tx-stats-total = Total
+tx-stats-longest = Longest
+tx-stats-duty = Duty
+tx-stats-duty-note = Duty is the share of the last { $minutes } min spent transmitting
+tx-stats-alert-above = Alert above
+tx-stats-alert-hover = Warn when a radio transmits more than this share of the window, e.g. long digital-mode sessions at full power
+tx-stats-over = over
+tx-stats-reset = Reset
+tx-stats-reset-hover = Start counting again from zero
+tx-stats-duty-alert = { $radio } transmitted { $percent }% of the last { $minutes } min
+
+## Disk log
+
+browse = Browse...
+disk-log-folder = Folder:
+disk-log-record = Record
+disk-log-recording = Recording
+disk-log-open = Open Log...
+disk-log-file = { $name } ({ $frames } frames)
+disk-log-from = From:
+disk-log-load = Load into Monitor
+
+## Status messages
+
+status-amp-state-save-failed = Could not save amp state: { $error }
+status-layout-reset = Layout reset
+status-ports-enumerate-failed = Failed to enumerate ports: { $error }
+status-amp-port-gone = Amplifier disconnected: port no longer available
+disk-log-empty = { $path }: log is empty
+disk-log-no-folder = No folder to write logs to
+disk-log-logging-to = Logging traffic to { $folder }
+contact-log-write-failed = Could not write: { $error }
+invalid-address = Invalid address: { $address }
+status-page-serving-on = Serving the status page on http://{ $address }/
+
+## Settings window
+
+close = Close
+settings-reset-layout = Reset Layout
+settings-reset-layout-hover = Put the window and panels back to where they start on a fresh install
+
+## Switch history
+
+switch-audit-title = Why did it switch?
+switch-audit-none = No switches yet this session.
+switch-audit-hint = Newest first. Click a switch to see the causes behind it.
+
+## Frequency log
+
+contact-log-file = File:
+contact-log-enabled = Log on each PTT release
+
+## Common
+
+address = Address:
+serve = Serve
+serving = Serving
+listening = Listening
+folder = Folder:
+protocol = Protocol:
+stop = Stop
+connect = Connect
+connected = Connected
+rigctl-serving-on = Serving Hamlib rigctl on { $address }
+grpc-serving-on = Serving gRPC on { $address }
+grpc-not-built = Not in this build (build with --features grpc)
+switch-confirm-title = Switch while transmitting?
+switch-confirm-question = { $current } is transmitting. Switch the amplifier to { $requested }?
+switch-confirm-countdown = Stays on { $radio } in { $seconds }s
+switch-confirm-switch = Switch
+switch-confirm-stay = Stay
+capture-watch = Watch
+capture-watching = Watching
+capture-watching-folder = Watching { $folder } for { $protocol } captures
+dx-cluster-address-hint = host:port
+dx-cluster-callsign = Callsign:
+dx-cluster-needs-address = Set an address and callsign first
+dx-cluster-connecting = Connecting to DX cluster
+
+## Band decoder
+
+band-decoder-description = OTRSP band data and TX/RX routing (microHAM, SO2R boxes)
+port = Port:
+none = None
+disconnect = Disconnect
+band-decoder-no-port = No band decoder port selected
+band-decoder-connecting = Connecting to band decoder on { $port }
+
+## Radio connections
+
+radio-disabled = { $port } is disabled
+radio-port-unavailable = { $port } not available
+radio-virtual-added = Virtual radio added: { $id }
+radio-virtual-removed = Virtual radio removed: { $id }
+radio-removed = Radio removed
+radio-latency-timer-set = { $port } latency timer set to { $ms } ms (until the adapter is replugged)
+radio-not-connected = { $radio } is not connected
+radio-detecting-model = Detecting model on { $port } using { $protocol } protocol...
+radio-tuning = Tuning { $radio } to { $frequency }
+radio-connected = Connected { $model } on { $port }
+radio-profiled = Profiled { $radio }: { $summary }
+traffic-loaded = Loaded { $count } frames from { $file }
+mux-restarted = Multiplexer { $reason }; restarted it (restart { $restarts })
+radio-protocol-reparsed = { $radio } now uses { $protocol }; re-parsed { $frames } buffered frames
+radio-protocol-changed = { $radio } now uses { $protocol }
+amp-output-backing-up = Output backing up ({ $bytes } bytes queued); holding frequency/mode updates
+amp-output-backing-up-cts = Output backing up ({ $bytes } bytes queued); holding frequency/mode updates. With RTS/CTS flow control, check that the amp drives CTS
+amp-output-backing-up-xoff = Output backing up ({ $bytes } bytes queued); holding frequency/mode updates. With XON/XOFF flow control, the amp may have sent XOFF
+amp-output-caught-up = Output caught up
+amp-failed-over = The { $from } failed ({ $reason }); output moved to { $to }
+amp-primary-restored = Primary amplifier back in service
+
+## Emulated radio
+
+emulated-radio-radio = Radio:
+emulated-radio-endpoint = Endpoint:
+emulated-radio-endpoint-hover = host:port to listen on, or a serial port such as one end of a com0com or socat pair
+baud = Baud:
+emulated-radio-no-endpoint = No endpoint set
+emulated-radio-emulating = Emulating a { $radio } on { $endpoint }
+
+## Multi-Op Sync
+
+peer-sync-station = Station name:
+peer-sync-station-hint = run
+peer-sync-udp-port = UDP port:
+peer-sync-peers = Peers:
+peer-sync-peers-hint = host:port, host:port
+peer-sync-sync = Sync
+peer-sync-syncing = Syncing
+peer-sync-no-peers = No peers heard
+peer-sync-needs-peer = Add at least one peer address first
+peer-sync-started = Peer sync started
+
+## Watches
+
+watch-firing = Firing
+remove = Remove
+watch-alert = Alert
+watch-sound = Sound
+watch-webhook = Webhook:
+watch-add = Add watch
+watch-add-preset = Add preset
+watch-cleared = { $watch }: cleared
+dismiss = Dismiss
+
+## Port scan
+
+scan-no-free-ports = No free serial ports to scan
+scan-scanning = Scanning { $count } port(s) for radios...
+scan-cancel = Cancel Scan
+scan-start = Scan for Radios
+scan-start-hover = Probe every free serial port for a radio
+clear = Clear
+scan-progress = { $done }/{ $total } ports
+scan-probing = Probing { $ports }
+scan-added = added
+scan-add = Add...
+
+## State inspector
+
+state-inspector-title = State at { $time }
+state-inspector-description = What the mux was acting on when this entry was logged; changes it made in response come just after
+state-inspector-too-old = Older than the state history kept.
+state-inspector-switching-mode = Switching mode: { $mode }
+state-inspector-active-radio = Active radio: { $radio }
+state-inspector-no-radios = No radios yet
+offline = offline
+state-inspector-last-change = Last change seen at { $time } ({ $ms } ms before)
+state-inspector-last-switch = Last switch #{ $id } at { $time }
+
+## CI-V sweep
+
+civ-sweep-sweeping = Sweeping the CI-V bus on { $port }...
+civ-sweep-cancelled = CI-V sweep cancelled: { $count } device(s) found
+civ-sweep-none = No CI-V devices answered on { $port }. Check the cable and that CI-V is enabled.
+civ-sweep-found = CI-V sweep found { $count } device(s) on { $port }
+civ-sweep-asking = Asking { $address } at { $baud } baud
+cancel = Cancel
+civ-sweep-start = Scan CI-V bus
+civ-sweep-start-hover = Ask every common CI-V address at each baud rate to identify itself
+civ-sweep-no-devices = No devices answered
+civ-sweep-unknown-radio = Unknown Icom radio
+civ-sweep-default-address = (default { $address })
+civ-sweep-use = Use
+civ-sweep-all = All addresses
+civ-sweep-all-hover = Also ask every other address, for a radio moved off its default. Takes about half a minute per baud rate.
+
+## Configuration bundle
+
+bundle-export = Export...
+bundle-include-logs = Include traffic log
+bundle-import = Import...
+bundle-import-radios = Import radios from OmniRig / HRD...
+bundle-exported = Configuration exported to { $path }
+bundle-not-imported = Not imported: { $radio }
+bundle-import-file = Import { $file }
+bundle-import-counts = { $radios } radios, { $virtual_radios } virtual radios, { $virtual_ports } virtual ports
+bundle-redacted = { $count } redacted values keep their current setting
+apply = Apply
+bundle-imported = Configuration imported; restart catapult to reconnect radios and the amplifier
+
+## Analyzer
+
+analyzer-mode-on = Analyzer mode: switching and amplifier disabled
+analyzer-mode-off = Analyzer mode off
+start = Start
+running = Running
+analyzer-statistics = Statistics
+reset = Reset
+copy = Copy
+analyzer-statistics-copied = Analyzer statistics copied to clipboard
+analyzer-frames = Frames
+analyzer-bytes = Bytes
+analyzer-unknown = Unknown
+analyzer-running = Analyzing { $logger } <-> { $radio }
+
+## Problem report
+
+bug-report-title = Report a Problem
+bug-report-description = What went wrong, and what were you doing at the time?
+bug-report-include = Include:
+bug-report-traffic = Traffic
+bug-report-diagnostics = Diagnostics
+bug-report-settings = Settings
+bug-report-ports = Serial ports
+bug-report-debug = Capture diagnostics at Debug while this is open
+bug-report-debug-hover = Reproduce the problem now, then press Refresh so the report has full detail
+bug-report-privacy = Secrets in settings are redacted, USB serial numbers are left out and your home folder is shown as ~
+bug-report-bytes = { $bytes } bytes
+bug-report-refresh = Refresh
+bug-report-truncated = Showing the first { $shown } KiB of { $total } KiB
+bug-report-consent = I have reviewed these { $files } files ({ $kib } KiB) and want to save them
+bug-report-save = Save Report...
+bug-report-save-hover = Write a zip to attach to a GitHub issue
+bug-report-saved = Problem report saved to { $path }
+
+## Safe mode
+
+safe-mode-banner = SAFE MODE
+safe-mode-recovery = Recovery...
+safe-mode-title = Safe Mode
+safe-mode-failed-starts = Catapult did not finish starting the last { $count } times.
+safe-mode-explanation = It started without connecting radios and with default settings. Changes made in safe mode are not saved.
+safe-mode-suspect = It was connecting { $radio } when it stopped.
+safe-mode-disable-radio = Disable radio and start normally
+safe-mode-disable-radio-hover = Keep { $radio } configured but don't connect it
+safe-mode-start-normally = Start normally
+safe-mode-stay = Stay in safe mode
+safe-mode-unreadable-title = Settings Could Not Be Loaded
+safe-mode-unreadable-explanation = Catapult started without connecting radios and with default settings. Changes made now are not saved.
+safe-mode-backup-loads = A backup from { $time } loads.
+safe-mode-no-backup = No backup loads either.
+safe-mode-restore-backup = Restore backup
+safe-mode-start-defaults = Start with defaults
+safe-mode-start-defaults-hover = The unreadable file is kept beside the new one as settings.json.broken
+safe-mode-decide-later = Decide later
+safe-mode-disabled = Disabled { $radio }
+safe-mode-left = Left safe mode
+
+## Amplifier
+
+amp-com-port = COM Port
+amp-simulated = Simulated
+amp-auto-info = Auto-Info
+amp-polling = Polling
+select-port-menu = Select port...
+amp-connection = Connection:
+baud-rate = Baud Rate:
+amp-simulation-mode = Simulation Mode:
+amp-flow-software = Software (XON/XOFF)
+amp-flow-hardware = Hardware (RTS/CTS)
+disconnected = Disconnected
+amp-respond-as = Respond as:
+amp-respond-as-hover = For amps that send one protocol but expect replies in another
+amp-same-protocol = Same as protocol
+amp-translation = Translation:
+amp-translation-hover = Permissive forwards radio frames Catapult doesn't recognise when this amp speaks the radio's protocol family. Applies immediately.
+amp-translation-global = Global ({ $strictness })
+amp-flow-control = Flow Control:
+civ-address = CI-V Address:
+amp-auto-info-hover = Receive state updates pushed from radio
+amp-polling-hover = Actively poll radio for frequency
+amp-frequency = Freq:
+amp-dry-run = Dry run
+amp-dry-run-hover = Translate everything for the amplifier and show it in the traffic monitor, but write nothing to its port
+amp-dry-run-active = Nothing is sent to the amplifier
+amp-transition = While a switch is held off:
+amp-transition-hover = A radio wants the amp during the switch lockout, or keyed on the active radio's band. "Answer busy" replies ?; (CI-V: NG) to the amp's frequency and mode queries
+amp-gapless-handover = Sync the amp before it keys on a switch
+amp-gapless-handover-hover = Send the new radio's frequency and mode to the amp and hold off keying it until they are written (at most 500 ms)
+amp-personality = Amplifier:
+amp-bypass = Bypass when out of range
+amp-bypass-hover = Put the amplifier in standby when the active radio leaves its range
+amp-bypass-range = Range (kHz):
+amp-forward-meters = Show the radio's S-meter on the amp
+amp-forward-meters-hover = Answer the amplifier's meter polls with the active radio's latest reading
+amp-ptt-latency = PTT latency: { $last } ms (avg { $mean }, max { $max })
+amp-ptt-latency-hover = Radio key-up to amplifier notify over { $edges } edges; { $over } over the { $budget } ms budget
+amp-queries = Amp queries: { $queries }
+amp-no-port = No amplifier port selected
+amp-connecting = Connecting to amplifier on { $port } @ { $baud } baud
+amp-virtual-connected = Connected to virtual amplifier (protocol: { $protocol })
+amp-disconnected = Amplifier disconnected
+shadow-amp-heading = A/B Shadow Amplifier
+shadow-amp-description = Mirror the amplifier stream to a second amp and report where they differ
+shadow-amp-needs-primary = Connect the primary amplifier first
+shadow-amp-comparing = Comparing
+shadow-amp-stats = { $windows } windows compared, { $mismatches } mismatches
+shadow-amp-save-report = Save Report...
+shadow-amp-report-saved = Shadow report saved to { $path }
+shadow-amp-connecting = Comparing amplifier against shadow on { $port }
+shadow-amp-simulated = Comparing amplifier against a simulated shadow
+backup-amp-heading = Backup Amplifier
+backup-amp-description = Move output to a standby amp, or bypass, when the amp in service fails
+backup-amp-failover = Fail over automatically
+backup-amp-failover-hover = When the amplifier's port errors out or it reports a fault, switch to the backup amp, or bypass all amps if there is none
+backup-amp-standing-by = Standing by
+backup-amp-failed-over = Output on the { $amp } ({ $reason })
+backup-amp-acknowledge = Acknowledge
+backup-amp-acknowledge-hover = Put the primary amplifier back in service (reconnect it first if its port failed)
+backup-amp-connecting = Backup amplifier standing by on { $port }
+backup-amp-simulated = Simulated backup amplifier standing by
+
+## Radio dialog
+
+radio-dialog-needs-flrig-address = Enter flrig's address as host:port
+radio-dialog-needs-server-address = Enter the serial server's address as host:port
+radio-dialog-needs-port = Choose a port
+radio-dialog-port-in-use = { $port } is already in use
+radio-dialog-not-host-port = { $address } is not a host:port address
+radio-dialog-civ-reserved = CI-V address { $address } is reserved (use 01 to DF)
+radio-dialog-civ-not-hex = CI-V address must be hex, e.g. 94
+radio-dialog-needs-8-bits = { $protocol } sends binary frames and needs 8 data bits
+radio-dialog-rts-with-hardware-flow = RTS can't be set with hardware flow control
+radio-dialog-flrig-needs-polling = Radios behind flrig must be polled
+radio-dialog-poll-too-fast = Poll interval must be at least { $ms } ms (or 0 for no polling)
+radio-dialog-settle-too-long = Settle time can be at most { $ms } ms
+radio-dialog-gap-too-long = Command gap can be at most { $ms } ms
+radio-dialog-calibration-too-large = Calibration can be at most { $hz } Hz either way
+radio-dialog-transverter-not-mhz = Transverter offset must be in MHz, e.g. 116
+radio-dialog-save = Save
+radio-dialog-add = Add Radio
+radio-dialog-virtual-port-missing = Virtual port '{ $port }' not found
+radio-dialog-updated = Radio updated: { $port }
+radio-dialog-connection = Connection
+radio-dialog-transport = Transport:
+radio-dialog-serial-port = Serial port
+radio-dialog-tcp-hover = A serial server such as ser2net, or a remote station
+radio-dialog-flrig-hover = A radio flrig already controls, followed through its XML-RPC server
+host-port-hint = host:port
+radio-dialog-flrig-push = Send changes to flrig
+radio-dialog-flrig-push-hover = Retune, change mode and key the radio through flrig when the mux asks. Off, the radio is only followed.
+radio-dialog-bluetooth-pair = Bluetooth link: the radio must be paired
+radio-dialog-bluetooth-baud = Not used over Bluetooth
+radio-dialog-format = Format:
+radio-dialog-parity-odd = Odd
+radio-dialog-parity-even = Even
+radio-dialog-stop-bits = { $bits } stop
+radio-dialog-flow-control = Flow Ctrl:
+radio-dialog-flow-software = Software (XON/XOFF)
+radio-dialog-flow-hardware = Hardware (RTS/CTS)
+radio-dialog-dtr-hover = Some interfaces power the radio or key it from DTR
+radio-dialog-rts-hover = Some interfaces key the radio from RTS
+radio-dialog-settle = Settle:
+radio-dialog-settle-hover = How long to wait after opening the port before talking to the radio. Some USB adapters (CH340) drop what is written for several hundred ms.
+radio-dialog-settle-custom = Custom
+radio-dialog-settle-default = default
+radio-dialog-radio = Radio
+radio-dialog-virtual = Simulated radio; protocol comes from the virtual port
+radio-dialog-model = Model:
+radio-dialog-detect = Detect
+radio-dialog-detect-hover = Query the radio for its model using the selected protocol
+radio-dialog-alias = Alias:
+radio-dialog-alias-hint = e.g. Run, Mult
+radio-dialog-polling = Polling and Pacing
+radio-dialog-poll-idle = Poll when idle:
+radio-dialog-poll-idle-hover = Ask for the frequency after this long without traffic. 0 never polls.
+radio-dialog-command-gap = Command gap:
+radio-dialog-command-gap-hover = Minimum time between commands, for radios that drop commands sent back to back
+radio-dialog-frequency = Frequency
+radio-dialog-calibration = Calibration:
+radio-dialog-calibration-hover = Added to the radio's frequency to correct an off-frequency reference
+radio-dialog-transverter = Transverter LO:
+radio-dialog-transverter-hover = Added to the radio's dial, so the amplifier and band decoder see the on-air frequency (e.g. 116 for 2 m on a 28 MHz IF)
+radio-dialog-transverter-hint = none
+radio-dialog-kat500 = KAT500 tuner on this line
+radio-dialog-kat500-hover = Switch the tuner to the radio's band (~BN) whenever the radio changes band
+
+## Radio panel
+
+radio-peer-block = Amp locked out: { $peer } holds the band
+radio-dx-spot = On DX spot: { $spot }
+radio-disabled-tag = disabled
+radio-disabled-hover = Not connected until enabled
+radio-rx-only = RX only
+radio-rx-only-following-hover = Monitor channel: never drives the amplifier, follows the active radio's frequency
+radio-rx-only-hover = Monitor channel: never drives the amplifier
+radio-profiled-tag = profiled
+radio-no-data-for = No data for { $seconds }s
+radio-no-data = No data received
+radio-idle = idle
+radio-idle-hover = { $ago } - skipped by auto-switching
+radio-same-band = same band ({ $band })
+radio-same-band-hover = Tuned to the active radio's band
+radio-duty-hover = Transmitting above the duty-cycle alert threshold
+radio-syncing = syncing { $step }/{ $total }
+radio-syncing-hover = Reading initial state from the radio
+radio-select = Select
+radio-less = Less
+radio-more = More
+radio-power-hover = Output power setting
+sim-band = Band:
+sim-tune = Tune:
+sim-split = Split
+sim-split-hover = Transmit on the other VFO
+sim-equalize-hover = Copy the selected VFO to the other
+sim-rules = Rules ({ $count })
+sim-faults = Faults
+sim-mode = Mode:
+radio-tune-to = Tune to:
+radio-tune-to-hint = 14.074, 7074 kHz
+radio-tune-to-hover = MHz, kHz or Hz; checked against the radio's range and tuning step, and the amateur bands for transmit
+radio-tune = Tune
+radio-protocol-hover = Switch protocols without reconnecting; recent traffic is re-parsed with the new one
+radio-input-throttle = Input throttle (ms):
+radio-input-throttle-hover = Collapse bursts of frequency, mode and meter reports (e.g. an IC-7610 in transceive mode while tuning) into one update per window. 0 disables.
+radio-receive-only = Receive only
+radio-receive-only-hover = A monitor channel such as an SDR: shown here but never made the active radio, so the amplifier never follows it
+radio-follow-active = Follow active radio
+radio-follow-active-hover = Tune this radio to the active radio's frequency whenever it changes
+radio-usb-serial = USB serial: { $serial }
+radio-split-frames = Split frames: { $percent }% (up to { $reads } reads, { $ms } ms)
+radio-split-frames-hover = Frames that reached the host in more than one read, typically held back by the USB adapter's latency timer
+radio-latency-timer = Latency timer: { $ms } ms
+radio-latency-timer-unknown = Latency timer: unknown
+radio-latency-timer-fix = Set to { $ms } ms
+radio-profile = Profile: { $summary }
+radio-reprofile = Re-profile
+radio-reprofile-hover = Forget what was learned about this radio and ask it again (reconnects it)
+radio-civ-echo-back = CI-V USB Echo Back is on (echoes filtered)
+radio-edit = Edit...
+radio-edit-hover = Port, line settings, polling and offsets
+radio-enable = Enable
+radio-tune-transmit = TX
+radio-tune-transmit-hover = To transmit on: refused outside the amateur bands of the band plan in Settings
+
+## Switching
+
+switching-mode = Mode:
+switching-confirm-tx = Confirm switching away from a transmitting radio
+switching-confirm-tx-hover = Selecting another radio mid-over waits for you to confirm
+switching-carry-keyer-speed = Carry keyer speed to the newly active radio
+switching-carry-keyer-speed-hover = On each switch, set the new radio's CW speed to the one you were sending at
+switching-band-mode-memory = Restore the last mode used on each band
+switching-band-mode-memory-hover = When the active radio changes band, switch it to the mode you last used there, like band stacking registers
+switching-suppress-repeats = Ignore reports that repeat a radio's state
+switching-suppress-repeats-hover = A poll of a radio other than the active one answered with the same frequency, mode or power is still shown in the traffic monitor, but isn't acted on again. The active radio's reports always reach the amplifier, and PTT is never ignored.
+switching-same-band = Same band:
+switching-same-band-hover = What to do when a second radio is tuned to the active radio's band
+switching-per-mode = Count CW, phone and digital separately
+switching-per-mode-hover = Field Day: one transmitter per band and mode
+
+## Simulation
+
+sim-clock = Simulation:
+sim-pause-hover = Freeze periodic reports and polling on virtual devices
+sim-step = Step
+sim-step-hover = Advance the paused clock by { $seconds }s
+add-radio-description = A serial port, a TCP serial server, or a virtual port
+add-radio = Add Radio...
+add-radio-hover = Configure and connect a radio
+menu-item = Menu:
+menu-read = Read
+menu-value = Value: { $value }
+menu-write = Write
+sim-go-silent = Go silent
+sim-go-silent-hover = Ignore every command and send nothing, like a radio switched off
+sim-go-silent-for = for
+sim-malformed = Malformed frame
+sim-malformed-hover = A frequency report that doesn't decode, or a binary frame cut short
+sim-report-frequency = Report { $frequency }
+sim-report-frequency-hover = Report this frequency without tuning to it
+sim-wrong-mode = Wrong mode
+sim-wrong-mode-hover = Report { $mode } without changing mode
+sim-remove-rule = Remove rule
@@ -0,0 +1,671 @@
+### Japanese
+
+## Toolbar
+
+toolbar-hide-console = コンソールを隠す
+toolbar-show-console = コンソールを表示
+toolbar-settings = 設定
+toolbar-analyzer = アナライザー
+toolbar-analyzer-hover = モニター専用モード: ロガーを無線機に中継し、トラフィックをデコードします
+toolbar-switch-history = 切り替え履歴
+toolbar-switch-history-hover = なぜ切り替わったのか? アクティブな無線機が変わるたびの理由
+toolbar-tx-stats = 送信統計
+toolbar-tx-stats-hover = 無線機・バンドごとの送信時間とデューティサイクル
+toolbar-report-problem = 問題を報告
+toolbar-report-problem-hover = 最近のトラフィック、診断、設定を保存して Issue に添付します
+toolbar-active = アクティブ
+toolbar-no-radio = 無線機なし
+toolbar-amp-connected = アンプ: 接続
+toolbar-amp-disconnected = アンプ: 未接続
+toolbar-amp-simulated = アンプ: シミュレーション
+toolbar-amp-backup = アンプ: 予備で運用中
+toolbar-amp-bypassed = アンプ: バイパス中
+
+## Radios
+
+radios-heading = 無線機
+radios-none = 無線機がありません。設定から無線機を追加してください。
+radio-duty = デューティ { $percent }%
+
+## Settings panel
+
+amplifier-heading = アンプ
+band-decoder-heading = バンドデコーダー
+switching-heading = 切り替え
+add-radio-heading = 無線機の追加
+settings-heading = 設定
+smoothing-immediate = 即時
+smoothing-trailing = 安定後
+smoothing-sample = サンプル間隔
+virtual-ports-add = 追加:
+virtual-ports-name = 名前
+virtual-ports-name-taken = その名前は既に存在します
+settings-config-path = 設定ファイル: { $path }
+
+## General settings
+
+settings-language = 言語:
+settings-language-system = システム ({ $language })
+settings-lockout = ロックアウト時間 (ms):
+settings-stale = 無応答と見なすまで (s):
+settings-stale-hover = この時間応答のない無線機は淡色表示され、自動選択されません。0(既定)で無効。
+settings-ptt-lead = PTTリード (ms):
+settings-ptt-lead-hover = 無線機より先にこの時間だけアンプをキーします。Catapult 経由でキーする場合に適用されます。
+settings-ptt-tail = PTTテール (ms):
+settings-ptt-tail-hover = 無線機がキーを離した後、この時間だけアンプをキーしたままにします。
+settings-amp-coalescing = アンプ更新の集約 (ms):
+settings-amp-coalescing-hover = 無線機の連続した報告(例: Yaesu IF/FA/MD)を1回のアンプ更新にまとめます。0(既定)で無効。
+settings-translation = 変換:
+settings-translation-hover = 厳格では Catapult が変換できるものだけをアンプに送ります。寛容では、無線機とアンプが同じプロトコル系の場合、認識できないフレームもそのまま転送します。アンプごとに上書きできます。
+settings-amp-frequency = アンプへの周波数:
+settings-amp-frequency-hover = VFOを回したときにアンプへ送る頻度。サンプリングでは最初の変化をすぐに送り、その後は周期ごとに最新の値を送ります。
+settings-band-decoder-frequency = バンドデコーダーへの周波数:
+settings-band-decoder-frequency-hover = VFOを回したときにバンドデコーダーへ送る頻度。無線機が落ち着くのを待つとアンテナリレーが静かになります。
+settings-meter-attack = メーターの立ち上がり (ms):
+settings-meter-attack-hover = 無線機パネルのメーターが新しい値まで上がる速さ。
+settings-meter-decay = メーターの減衰 (ms):
+settings-meter-decay-hover = メーターがフルスケールからゼロまで下がる時間。
+settings-meter-peak-hold = ピークホールド (ms):
+settings-meter-peak-hold-hover = ピークマーカーを表示し続ける時間。0で無効。
+settings-traffic-history = トラフィック履歴:
+settings-memory-budget = メモリ上限 (MiB):
+settings-memory-budget-hover = トラフィック履歴、デコードキャッシュ、切り替え履歴、DXスポットを1つの上限から割り当てます。数日間動かし続ける局向け。0で無効。
+settings-history-capped = 履歴は { $limit } 件まで
+settings-show-hex = 16進表示:
+settings-show-decoded = デコード表示:
+settings-frequency-format = 周波数の形式:
+settings-frequency-format-hover = パネル、トラフィックモニター、エクスポート、通知での周波数の表示方法。
+settings-decimal-separator = 小数点:
+settings-crate-levels = クレートごとの診断レベル
+settings-crate-levels-description = 個々のコンポーネントの診断レベルを上書きします
+virtual-ports-heading = 仮想ポート
+virtual-ports-description = ポート一覧に表示されるシミュレーション無線機を設定します
+settings-band-plan = バンドプラン:
+settings-band-plan-hover = 送信用に入力した周波数が含まれるべきアマチュアバンド: ITU第1地域(欧州・アフリカ)、第2地域(南北アメリカ)、第3地域(アジア太平洋)。
+
+## Settings sections
+
+analyzer-heading = アナライザー
+analyzer-description = ロガーと無線機の間のCAT通信を中継してデコードします
+bookmarks-heading = ブックマーク
+bookmarks-description = キーボードに触れずに、トラフィックモニターに「今の」瞬間を記録します
+bundle-heading = 設定バンドル
+bundle-description = サポート依頼や別のマシンへの移行用の1つのファイル。秘密情報は伏せられます
+capture-heading = キャプチャフォルダー
+capture-description = 他のツールの生キャプチャファイル(または .hex ダンプ)をトラフィックモニターにデコードします
+contact-log-heading = 周波数ログ
+contact-log-description = ロガーが接続されていなくても、アクティブな無線機が送信を終えるたびに時刻・周波数・モードを記録します
+disk-log-heading = ディスクログ
+disk-log-description = すべてのトラフィックを圧縮した日別ログに記録し、後で読み込みます
+dx-cluster-heading = DXクラスター
+dx-cluster-description = DXクラスターやスキマーを追跡し、アクティブな無線機がスポットされた周波数にいるときに知らせます
+emulated-radio-heading = エミュレート無線機
+emulated-radio-description = 無線機を1台しか扱えないソフトに1台の無線機として見せます。アクティブな無線機に追従します
+grpc-heading = gRPC
+grpc-description = 型付きAPIでマルチプレクサーの照会・制御とイベントのストリーミングを可能にします
+metrics-heading = メトリクス
+metrics-description = Prometheus メトリクス(フレーム、エラー、切り替え、PTT時間)を /metrics で提供します
+status-page-heading = ステータスページ
+status-page-description = スマートフォン向けの読み取り専用ステータスページを提供し、重大な警告(TXウォッチドッグ、インターロック、アンプ故障、無線機の切断)を ntfy または Webhook に送信します
+status-page-address = アドレス:
+status-page-address-hover = 他の機器からページを開くには 0.0.0.0 で待ち受けます
+status-page-serve = 提供
+status-page-serving = 提供中
+status-page-ntfy = ntfy トピック:
+status-page-webhook = Webhook:
+peer-sync-heading = マルチオペ同期
+peer-sync-description = 周波数と送信状態を他の Catapult と共有し、他局が送信中のバンドではアンプをキーしません
+ptt-switch-heading = PTTスイッチ
+ptt-switch-description = 空いているシリアルポートのフットスイッチで、アクティブな無線機とアンプをキーします
+rigctl-heading = Hamlib rigctl
+rigctl-description = Hamlib の NET rigctl モデルでロガーを接続し、アクティブな無線機を読み取り・制御します
+watches-heading = ウォッチ
+watches-description = 無線機やアンプが指定した状態になったら通知します。例: mode != USB && freq in 14.150..14.350
+
+## Serial pin switches (bookmarks and PTT)
+
+pin-switch-on = スイッチ:
+pin-of = /
+select-port = ポートを選択
+pin-watching = 監視中
+bookmarks-double-tap = PTTダブルタップの間隔
+bookmarks-select-port-first = 先にブックマークスイッチのポートを選択してください
+bookmarks-log-failed = ブックマークログを書き込めませんでした: { $error }
+bookmarks-status = ブックマーク: { $note }
+ptt-switch-inverted = ノーマルクローズ
+ptt-switch-inverted-hint = 押すと接点が開くスイッチ
+ptt-switch-shares-bookmark-pin = ブックマークスイッチが同じピンを使っています
+ptt-switch-select-port-first = 先にPTTスイッチのポートを選択してください
+
+## Traffic monitor
+
+traffic-heading = トラフィックモニター
+search-hint = 検索
+search-hover =
+    履歴全体を検索します。Enterで次、Shift+Enterで前の一致へ。
+    テキストはデコード済みフレームと診断に一致し、hex:FE FE 94 はバイト列に、cmd:freq(または mode, ptt, vfo, status, power, meter, keyer, id, ai)はコマンド種別に一致します
+search-previous = 前の一致
+search-next = 次の一致
+search-follow = 追従
+search-follow-hover = 新しい一致が届くたびにそこへ移動します
+search-searching = 検索中… { $found } 件
+search-no-matches = 一致なし
+search-matches = { $count } 件
+search-clear = 検索をクリア
+goto-time-hover = この時刻(UTC)以降の最初のエントリーへ移動します
+timestamp-inspect-hover = この時点のマルチプレクサーの状態を表示します
+traffic-auto-scroll = 自動スクロール
+traffic-resume = 再開
+traffic-pause = 一時停止
+traffic-export = エクスポート
+traffic-copy = クリップボードにコピー
+traffic-save = ファイルに保存...
+traffic-bookmarks = ブックマーク ({ $count })
+traffic-filter = フィルター:
+traffic-filter-all = すべて
+traffic-filter-in = 受信
+traffic-filter-out = 送信
+traffic-logs = ログ: { $level }
+log-level-off = オフ
+log-level-error = エラー
+log-level-warning = 警告
+log-level-info = 情報
+log-level-debug = デバッグ
+traffic-dry-run-hover = 未送信: アンプのチャンネルはドライラン中です
+traffic-cause-hover = フレーム { $frame } により送信: クリックで強調表示
+traffic-log-copied = ログをクリップボードにコピーしました
+traffic-log-saved = ログを { $path } に保存しました
+
+## Transmit statistics
+
+tx-stats-title = 送信統計
+tx-stats-none = このセッションではまだ送信していません。
+tx-stats-radio = 無線機
+tx-stats-band = バンド
+tx-stats-tx = 送信回数
+tx-stats-total = 合計
+tx-stats-longest = 最長
+tx-stats-duty = デューティ
+tx-stats-duty-note = デューティは直近 { $minutes } 分間に送信していた割合です
+tx-stats-alert-above = 警告する割合
+tx-stats-alert-hover = 無線機が時間枠のうちこの割合を超えて送信したら警告します。例: フルパワーでの長時間のデジタルモード運用
+tx-stats-over = 時間枠
+tx-stats-reset = リセット
+tx-stats-reset-hover = ゼロから数え直します
+tx-stats-duty-alert = { $radio } は直近 { $minutes } 分間の { $percent }% を送信しました
+
+## Disk log
+
+browse = 参照...
+disk-log-folder = フォルダー:
+disk-log-record = 記録する
+disk-log-recording = 記録中
+disk-log-open = ログを開く...
+disk-log-file = { $name }({ $frames } フレーム)
+disk-log-from = 開始位置:
+disk-log-load = モニターに読み込む
+
+## Status messages
+
+status-amp-state-save-failed = アンプの状態を保存できませんでした: { $error }
+status-layout-reset = レイアウトをリセットしました
+status-ports-enumerate-failed = ポートを列挙できませんでした: { $error }
+status-amp-port-gone = アンプが切断されました: ポートが使用できなくなりました
+disk-log-empty = { $path }: ログが空です
+disk-log-no-folder = ログを書き込むフォルダーがありません
+disk-log-logging-to = { $folder } にトラフィックを記録中
+contact-log-write-failed = 書き込めませんでした: { $error }
+invalid-address = 無効なアドレス: { $address }
+status-page-serving-on = ステータスページを http://{ $address }/ で公開中
+
+## Settings window
+
+close = 閉じる
+settings-reset-layout = レイアウトをリセット
+settings-reset-layout-hover = ウィンドウとパネルを初回インストール時の位置に戻します
+
+## Switch history
+
+switch-audit-title = なぜ切り替わったのか?
+switch-audit-none = このセッションではまだ切り替えがありません。
+switch-audit-hint = 新しい順。切り替えをクリックすると理由が表示されます。
+
+## Frequency log
+
+contact-log-file = ファイル:
+contact-log-enabled = PTT を離すたびに記録
+
+## Common
+
+address = アドレス:
+serve = 公開
+serving = 公開中
+listening = 待ち受け中
+folder = フォルダー:
+protocol = プロトコル:
+stop = 停止
+connect = 接続
+connected = 接続済み
+rigctl-serving-on = Hamlib rigctl を { $address } で公開中
+grpc-serving-on = gRPC を { $address } で公開中
+grpc-not-built = このビルドには含まれていません (--features grpc でビルドしてください)
+switch-confirm-title = 送信中に切り替えますか?
+switch-confirm-question = { $current } が送信中です。アンプを { $requested } に切り替えますか?
+switch-confirm-countdown = { $seconds } 秒後に { $radio } のままになります
+switch-confirm-switch = 切り替える
+switch-confirm-stay = そのまま
+capture-watch = 監視
+capture-watching = 監視中
+capture-watching-folder = { $folder } で { $protocol } のキャプチャを監視中
+dx-cluster-address-hint = ホスト:ポート
+dx-cluster-callsign = コールサイン:
+dx-cluster-needs-address = 先にアドレスとコールサインを設定してください
+dx-cluster-connecting = DX クラスターに接続中
+
+## Band decoder
+
+band-decoder-description = OTRSP バンドデータと送受信の切り替え (microHAM、SO2R ボックス)
+port = ポート:
+none = なし
+disconnect = 切断
+band-decoder-no-port = バンドデコーダーのポートが選択されていません
+band-decoder-connecting = { $port } のバンドデコーダーに接続中
+
+## Radio connections
+
+radio-disabled = { $port } は無効です
+radio-port-unavailable = { $port } は使用できません
+radio-virtual-added = 仮想無線機を追加しました: { $id }
+radio-virtual-removed = 仮想無線機を削除しました: { $id }
+radio-removed = 無線機を削除しました
+radio-latency-timer-set = { $port } のレイテンシタイマーを { $ms } ms に設定しました (アダプターを差し直すまで)
+radio-not-connected = { $radio } は接続されていません
+radio-detecting-model = { $port } で { $protocol } プロトコルを使って機種を検出中...
+radio-tuning = { $radio } を { $frequency } に同調中
+radio-connected = { $port } の { $model } に接続しました
+radio-profiled = { $radio } をプロファイルしました: { $summary }
+traffic-loaded = { $file } から { $count } フレームを読み込みました
+mux-restarted = マルチプレクサ: { $reason }。再起動しました (再起動 { $restarts } 回目)
+radio-protocol-reparsed = { $radio } は { $protocol } を使うようになりました。バッファ内の { $frames } フレームを再解析しました
+radio-protocol-changed = { $radio } は { $protocol } を使うようになりました
+amp-output-backing-up = 出力が滞っています ({ $bytes } バイト待機中)。周波数/モードの更新を保留します
+amp-output-backing-up-cts = 出力が滞っています ({ $bytes } バイト待機中)。周波数/モードの更新を保留します。RTS/CTS フロー制御では、アンプが CTS を出しているか確認してください
+amp-output-backing-up-xoff = 出力が滞っています ({ $bytes } バイト待機中)。周波数/モードの更新を保留します。XON/XOFF フロー制御では、アンプが XOFF を送った可能性があります
+amp-output-caught-up = 出力が追いつきました
+amp-failed-over = { $from } が故障しました ({ $reason })。出力を { $to } に切り替えました
+amp-primary-restored = メインのアンプが復帰しました
+
+## Emulated radio
+
+emulated-radio-radio = 無線機:
+emulated-radio-endpoint = 接続先:
+emulated-radio-endpoint-hover = 待ち受ける host:port、または com0com や socat のペアの片側などのシリアルポート
+baud = ボーレート:
+emulated-radio-no-endpoint = 接続先が設定されていません
+emulated-radio-emulating = { $endpoint } で { $radio } をエミュレート中
+
+## Multi-Op Sync
+
+peer-sync-station = 局名:
+peer-sync-station-hint = ラン
+peer-sync-udp-port = UDP ポート:
+peer-sync-peers = ピア:
+peer-sync-peers-hint = ホスト:ポート, ホスト:ポート
+peer-sync-sync = 同期
+peer-sync-syncing = 同期中
+peer-sync-no-peers = ピアの応答がありません
+peer-sync-needs-peer = 先にピアのアドレスを 1 つ以上追加してください
+peer-sync-started = ピア同期を開始しました
+
+## Watches
+
+watch-firing = 発動中
+remove = 削除
+watch-alert = 通知
+watch-sound = 音
+watch-webhook = Webhook:
+watch-add = ウォッチを追加
+watch-add-preset = プリセットを追加
+watch-cleared = { $watch }: 解除
+dismiss = 閉じる
+
+## Port scan
+
+scan-no-free-ports = スキャンできる空きシリアルポートがありません
+scan-scanning = { $count } 個のポートで無線機を検索中...
+scan-cancel = スキャンを中止
+scan-start = 無線機を検索
+scan-start-hover = 空いているすべてのシリアルポートで無線機を探します
+clear = クリア
+scan-progress = { $done }/{ $total } ポート
+scan-probing = { $ports } を調査中
+scan-added = 追加済み
+scan-add = 追加...
+
+## State inspector
+
+state-inspector-title = { $time } の状態
+state-inspector-description = このエントリが記録された時点で mux が基にしていた状態です。それに応じた変更はこの直後に続きます
+state-inspector-too-old = 保持している状態履歴より古い時刻です。
+state-inspector-switching-mode = 切り替えモード: { $mode }
+state-inspector-active-radio = アクティブな無線機: { $radio }
+state-inspector-no-radios = まだ無線機がありません
+offline = オフライン
+state-inspector-last-change = 最後の変更は { $time } ({ $ms } ms 前)
+state-inspector-last-switch = 最後の切り替え #{ $id } ({ $time })
+
+## CI-V sweep
+
+civ-sweep-sweeping = { $port } の CI-V バスをスキャン中...
+civ-sweep-cancelled = CI-V スキャンを中止しました: { $count } 台見つかりました
+civ-sweep-none = { $port } で応答した CI-V 機器はありません。ケーブルと CI-V が有効かを確認してください。
+civ-sweep-found = CI-V スキャンで { $port } に { $count } 台見つかりました
+civ-sweep-asking = { $baud } baud で { $address } に問い合わせ中
+cancel = キャンセル
+civ-sweep-start = CI-V バスをスキャン
+civ-sweep-start-hover = よく使われる CI-V アドレスに各ボーレートで ID を問い合わせます
+civ-sweep-no-devices = 応答した機器はありません
+civ-sweep-unknown-radio = 不明な Icom 無線機
+civ-sweep-default-address = (既定 { $address })
+civ-sweep-use = 使用
+civ-sweep-all = 全アドレス
+civ-sweep-all-hover = 既定から変更された無線機のために、他のすべてのアドレスにも問い合わせます。ボーレートごとに約30秒かかります。
+
+## Configuration bundle
+
+bundle-export = エクスポート...
+bundle-include-logs = 通信ログを含める
+bundle-import = インポート...
+bundle-import-radios = OmniRig / HRD から無線機をインポート...
+bundle-exported = 設定を { $path } にエクスポートしました
+bundle-not-imported = インポートしませんでした: { $radio }
+bundle-import-file = { $file } をインポート
+bundle-import-counts = 無線機 { $radios } 台、仮想無線機 { $virtual_radios } 台、仮想ポート { $virtual_ports } 個
+bundle-redacted = 伏せ字の { $count } 項目は現在の設定のままです
+apply = 適用
+bundle-imported = 設定をインポートしました。無線機とアンプを再接続するには catapult を再起動してください
+
+## Analyzer
+
+analyzer-mode-on = アナライザーモード: 切り替えとアンプは無効です
+analyzer-mode-off = アナライザーモード オフ
+start = 開始
+running = 実行中
+analyzer-statistics = 統計
+reset = リセット
+copy = コピー
+analyzer-statistics-copied = アナライザーの統計をクリップボードにコピーしました
+analyzer-frames = フレーム
+analyzer-bytes = バイト
+analyzer-unknown = 不明
+analyzer-running = { $logger } <-> { $radio } を解析中
+
+## Problem report
+
+bug-report-title = 問題を報告
+bug-report-description = 何が起きましたか。そのとき何をしていましたか。
+bug-report-include = 含める内容:
+bug-report-traffic = 通信
+bug-report-diagnostics = 診断
+bug-report-settings = 設定
+bug-report-ports = シリアルポート
+bug-report-debug = これを開いている間は診断を Debug レベルで記録する
+bug-report-debug-hover = 今問題を再現してから「更新」を押すと、詳しい情報がレポートに入ります
+bug-report-privacy = 設定内の秘密情報は伏せ字にし、USB シリアル番号は含めず、ホームフォルダーは ~ と表示します
+bug-report-bytes = { $bytes } バイト
+bug-report-refresh = 更新
+bug-report-truncated = { $total } KiB のうち最初の { $shown } KiB を表示しています
+bug-report-consent = これら { $files } 個のファイル ({ $kib } KiB) を確認し、保存します
+bug-report-save = レポートを保存...
+bug-report-save-hover = GitHub の Issue に添付する zip を書き出します
+bug-report-saved = 問題レポートを { $path } に保存しました
+
+## Safe mode
+
+safe-mode-banner = セーフモード
+safe-mode-recovery = 復旧...
+safe-mode-title = セーフモード
+safe-mode-failed-starts = Catapult は直近 { $count } 回、起動を完了できませんでした。
+safe-mode-explanation = 無線機に接続せず、既定の設定で起動しました。セーフモード中の変更は保存されません。
+safe-mode-suspect = 停止したとき { $radio } に接続中でした。
+safe-mode-disable-radio = 無線機を無効にして通常どおり起動
+safe-mode-disable-radio-hover = { $radio } の設定は残し、接続はしません
+safe-mode-start-normally = 通常どおり起動
+safe-mode-stay = セーフモードのままにする
+safe-mode-unreadable-title = 設定を読み込めませんでした
+safe-mode-unreadable-explanation = Catapult は無線機に接続せず、既定の設定で起動しました。今行う変更は保存されません。
+safe-mode-backup-loads = { $time } のバックアップは読み込めます。
+safe-mode-no-backup = 読み込めるバックアップもありません。
+safe-mode-restore-backup = バックアップを復元
+safe-mode-start-defaults = 既定の設定で起動
+safe-mode-start-defaults-hover = 読めないファイルは settings.json.broken として新しいファイルの隣に残します
+safe-mode-decide-later = 後で決める
+safe-mode-disabled = { $radio } を無効にしました
+safe-mode-left = セーフモードを終了しました
+
+## Amplifier
+
+amp-com-port = COM ポート
+amp-simulated = シミュレーション
+amp-auto-info = オート情報
+amp-polling = ポーリング
+select-port-menu = ポートを選択...
+amp-connection = 接続:
+baud-rate = ボーレート:
+amp-simulation-mode = シミュレーションモード:
+amp-flow-software = ソフトウェア (XON/XOFF)
+amp-flow-hardware = ハードウェア (RTS/CTS)
+disconnected = 切断中
+amp-respond-as = 応答形式:
+amp-respond-as-hover = ある形式で送信し、別の形式の応答を期待するアンプ用
+amp-same-protocol = プロトコルと同じ
+amp-translation = 変換:
+amp-translation-hover = 「寛容」では、このアンプが無線機と同じ系統のプロトコルを話す場合、Catapult が認識しないフレームも転送します。すぐに適用されます。
+amp-translation-global = 全体設定 ({ $strictness })
+amp-flow-control = フロー制御:
+civ-address = CI-V アドレス:
+amp-auto-info-hover = 無線機から送られる状態更新を受け取ります
+amp-polling-hover = 無線機に周波数を能動的に問い合わせます
+amp-frequency = 周波数:
+amp-dry-run = ドライラン
+amp-dry-run-hover = アンプ向けにすべて変換して通信モニターに表示しますが、ポートには何も書き込みません
+amp-dry-run-active = アンプには何も送信されません
+amp-transition = 切り替えを保留している間:
+amp-transition-hover = 切り替えロックアウト中に無線機がアンプを要求した、またはアクティブな無線機のバンドで送信した場合です。「ビジー応答」はアンプの周波数・モード問い合わせに ?; (CI-V: NG) を返します
+amp-gapless-handover = 切り替え時は送信前にアンプを同期する
+amp-gapless-handover-hover = 新しい無線機の周波数とモードをアンプに送り、書き込まれるまで送信を保留します (最大 500 ms)
+amp-personality = アンプ:
+amp-bypass = 範囲外ではバイパスする
+amp-bypass-hover = アクティブな無線機が範囲外に出たらアンプをスタンバイにします
+amp-bypass-range = 範囲 (kHz):
+amp-forward-meters = 無線機の S メーターをアンプに表示する
+amp-forward-meters-hover = アンプのメーター問い合わせに、アクティブな無線機の最新値で応答します
+amp-ptt-latency = PTT 遅延: { $last } ms (平均 { $mean }、最大 { $max })
+amp-ptt-latency-hover = 無線機の送信開始からアンプ通知まで ({ $edges } 回)。{ $budget } ms の予算超過 { $over } 回
+amp-queries = アンプの問い合わせ: { $queries }
+amp-no-port = アンプのポートが選択されていません
+amp-connecting = { $port } ({ $baud } baud) のアンプに接続中
+amp-virtual-connected = 仮想アンプに接続しました (プロトコル: { $protocol })
+amp-disconnected = アンプを切断しました
+shadow-amp-heading = A/B シャドウアンプ
+shadow-amp-description = アンプへのデータを 2 台目のアンプにも送り、違いを報告します
+shadow-amp-needs-primary = 先にメインのアンプを接続してください
+shadow-amp-comparing = 比較中
+shadow-amp-stats = { $windows } 区間を比較、不一致 { $mismatches } 件
+shadow-amp-save-report = レポートを保存...
+shadow-amp-report-saved = シャドウレポートを { $path } に保存しました
+shadow-amp-connecting = アンプを { $port } のシャドウと比較中
+shadow-amp-simulated = アンプをシミュレーションのシャドウと比較中
+backup-amp-heading = 予備アンプ
+backup-amp-description = 使用中のアンプが故障したら、待機中のアンプに出力を移すかバイパスします
+backup-amp-failover = 自動で切り替える
+backup-amp-failover-hover = アンプのポートがエラーになるかアンプが故障を報告したら予備アンプに切り替え、予備がなければすべてのアンプをバイパスします
+backup-amp-standing-by = 待機中
+backup-amp-failed-over = 出力先: { $amp } ({ $reason })
+backup-amp-acknowledge = 確認
+backup-amp-acknowledge-hover = メインのアンプを復帰させます (ポートが故障した場合は先に再接続してください)
+backup-amp-connecting = { $port } の予備アンプが待機中
+backup-amp-simulated = シミュレーションの予備アンプが待機中
+
+## Radio dialog
+
+radio-dialog-needs-flrig-address = flrig のアドレスを host:port で入力してください
+radio-dialog-needs-server-address = シリアルサーバーのアドレスを host:port で入力してください
+radio-dialog-needs-port = ポートを選択してください
+radio-dialog-port-in-use = { $port } は既に使用中です
+radio-dialog-not-host-port = { $address } は host:port 形式のアドレスではありません
+radio-dialog-civ-reserved = CI-V アドレス { $address } は予約済みです (01〜DF を使用してください)
+radio-dialog-civ-not-hex = CI-V アドレスは 16 進数で入力してください (例: 94)
+radio-dialog-needs-8-bits = { $protocol } はバイナリフレームを送るため 8 データビットが必要です
+radio-dialog-rts-with-hardware-flow = ハードウェアフロー制御では RTS を設定できません
+radio-dialog-flrig-needs-polling = flrig 経由の無線機はポーリングが必要です
+radio-dialog-poll-too-fast = ポーリング間隔は { $ms } ms 以上にしてください (0 でポーリングなし)
+radio-dialog-settle-too-long = 待機時間は最大 { $ms } ms です
+radio-dialog-gap-too-long = コマンド間隔は最大 { $ms } ms です
+radio-dialog-calibration-too-large = 校正値は上下とも最大 { $hz } Hz です
+radio-dialog-transverter-not-mhz = トランスバーターのオフセットは MHz で入力してください (例: 116)
+radio-dialog-save = 保存
+radio-dialog-add = 無線機を追加
+radio-dialog-virtual-port-missing = 仮想ポート「{ $port }」が見つかりません
+radio-dialog-updated = 無線機を更新しました: { $port }
+radio-dialog-connection = 接続
+radio-dialog-transport = 接続方式:
+radio-dialog-serial-port = シリアルポート
+radio-dialog-tcp-hover = ser2net などのシリアルサーバー、またはリモート局
+radio-dialog-flrig-hover = flrig が既に制御している無線機を、その XML-RPC サーバー経由で追従します
+host-port-hint = ホスト:ポート
+radio-dialog-flrig-push = 変更を flrig に送る
+radio-dialog-flrig-push-hover = mux の要求に応じて flrig 経由で同調、モード変更、送信を行います。オフでは追従のみです。
+radio-dialog-bluetooth-pair = Bluetooth 接続: 無線機のペアリングが必要です
+radio-dialog-bluetooth-baud = Bluetooth では使用しません
+radio-dialog-format = 形式:
+radio-dialog-parity-odd = 奇数
+radio-dialog-parity-even = 偶数
+radio-dialog-stop-bits = ストップ { $bits }
+radio-dialog-flow-control = フロー制御:
+radio-dialog-flow-software = ソフトウェア (XON/XOFF)
+radio-dialog-flow-hardware = ハードウェア (RTS/CTS)
+radio-dialog-dtr-hover = 一部のインターフェースは DTR で無線機に給電したり送信させたりします
+radio-dialog-rts-hover = 一部のインターフェースは RTS で無線機を送信させます
+radio-dialog-settle = 待機時間:
+radio-dialog-settle-hover = ポートを開いてから無線機と通信を始めるまでの待ち時間です。一部の USB アダプター (CH340) は数百 ms の間、書き込みを捨てます。
+radio-dialog-settle-custom = カスタム
+radio-dialog-settle-default = 既定
+radio-dialog-radio = 無線機
+radio-dialog-virtual = シミュレーションの無線機です。プロトコルは仮想ポートのものを使います
+radio-dialog-model = 機種:
+radio-dialog-detect = 検出
+radio-dialog-detect-hover = 選択したプロトコルで無線機に機種を問い合わせます
+radio-dialog-alias = 別名:
+radio-dialog-alias-hint = 例: Run、Mult
+radio-dialog-polling = ポーリングと送信間隔
+radio-dialog-poll-idle = アイドル時のポーリング:
+radio-dialog-poll-idle-hover = 通信がこの時間ないと周波数を問い合わせます。0 ではポーリングしません。
+radio-dialog-command-gap = コマンド間隔:
+radio-dialog-command-gap-hover = 連続して送られたコマンドを取りこぼす無線機のための、コマンド間の最小時間
+radio-dialog-frequency = 周波数
+radio-dialog-calibration = 校正:
+radio-dialog-calibration-hover = 基準のずれを補正するため無線機の周波数に加算します
+radio-dialog-transverter = トランスバーター LO:
+radio-dialog-transverter-hover = 無線機の表示周波数に加算し、アンプとバンドデコーダーが実際の周波数を受け取れるようにします (例: 28 MHz IF で 2 m なら 116)
+radio-dialog-transverter-hint = なし
+radio-dialog-kat500 = このラインにKAT500チューナー
+radio-dialog-kat500-hover = 無線機のバンドが変わるたびにチューナーを同じバンドに切り替えます (~BN)
+
+## Radio panel
+
+radio-peer-block = アンプはロック中: { $peer } がこのバンドを使用中
+radio-dx-spot = DX スポット上: { $spot }
+radio-disabled-tag = 無効
+radio-disabled-hover = 有効にするまで接続しません
+radio-rx-only = 受信のみ
+radio-rx-only-following-hover = モニターチャンネル: アンプを制御せず、アクティブな無線機の周波数に追従します
+radio-rx-only-hover = モニターチャンネル: アンプを制御しません
+radio-profiled-tag = プロファイル済み
+radio-no-data-for = { $seconds } 秒間データなし
+radio-no-data = データを受信していません
+radio-idle = アイドル
+radio-idle-hover = { $ago } - 自動切り替えでは対象外
+radio-same-band = 同じバンド ({ $band })
+radio-same-band-hover = アクティブな無線機と同じバンドに同調しています
+radio-duty-hover = デューティ比の警告しきい値を超えて送信しています
+radio-syncing = 同期中 { $step }/{ $total }
+radio-syncing-hover = 無線機から初期状態を読み込んでいます
+radio-select = 選択
+radio-less = 閉じる
+radio-more = 詳細
+radio-power-hover = 出力電力の設定
+sim-band = バンド:
+sim-tune = 同調:
+sim-split = スプリット
+sim-split-hover = もう一方の VFO で送信します
+sim-equalize-hover = 選択中の VFO をもう一方にコピーします
+sim-rules = ルール ({ $count })
+sim-faults = 障害
+sim-mode = モード:
+radio-tune-to = 同調先:
+radio-tune-to-hint = 14.074, 7074 kHz
+radio-tune-to-hover = MHz、kHz、Hz で入力。無線機の範囲と同調ステップ、送信時はアマチュアバンドと照合します
+radio-tune = 同調
+radio-protocol-hover = 再接続せずにプロトコルを切り替えます。最近の通信は新しいプロトコルで再解析されます
+radio-input-throttle = 入力間引き (ms):
+radio-input-throttle-hover = 周波数・モード・メーターの報告の連続 (例: トランシーブモードの IC-7610 を同調中) を期間ごとに 1 回の更新にまとめます。0 で無効。
+radio-receive-only = 受信のみ
+radio-receive-only-hover = SDR などのモニターチャンネル: ここに表示されますがアクティブな無線機にはならないため、アンプは追従しません
+radio-follow-active = アクティブな無線機に追従
+radio-follow-active-hover = アクティブな無線機の周波数が変わるたびに、この無線機を同調させます
+radio-usb-serial = USB シリアル: { $serial }
+radio-split-frames = 分割フレーム: { $percent }% (最大 { $reads } 回の読み取り、{ $ms } ms)
+radio-split-frames-hover = 複数回の読み取りに分かれて届いたフレームです。多くは USB アダプターのレイテンシタイマーで遅れたものです
+radio-latency-timer = レイテンシタイマー: { $ms } ms
+radio-latency-timer-unknown = レイテンシタイマー: 不明
+radio-latency-timer-fix = { $ms } ms に設定
+radio-profile = プロファイル: { $summary }
+radio-reprofile = 再プロファイル
+radio-reprofile-hover = この無線機について学習した内容を忘れて再度問い合わせます (再接続します)
+radio-civ-echo-back = CI-V USB エコーバックがオンです (エコーは除外)
+radio-edit = 編集...
+radio-edit-hover = ポート、回線設定、ポーリング、オフセット
+radio-enable = 有効にする
+radio-tune-transmit = 送信
+radio-tune-transmit-hover = 送信用: 設定のバンドプランのアマチュアバンド外では拒否されます
+
+## Switching
+
+switching-mode = モード:
+switching-confirm-tx = 送信中の無線機から切り替えるときは確認する
+switching-confirm-tx-hover = 送信中に別の無線機を選ぶと、確認するまで待ちます
+switching-carry-keyer-speed = キーヤー速度を新しいアクティブな無線機に引き継ぐ
+switching-carry-keyer-speed-hover = 切り替えのたびに、新しい無線機の CW 速度を直前に送っていた速度に合わせます
+switching-band-mode-memory = バンドごとに前回のモードに戻す
+switching-band-mode-memory-hover = アクティブな無線機がバンドを変えたら、バンドスタッキングレジスターのように、そのバンドで前回使ったモードに切り替えます
+switching-suppress-repeats = 無線機の状態を繰り返すだけの報告を無視する
+switching-suppress-repeats-hover = アクティブでない無線機へのポーリングに同じ周波数・モード・出力が返った場合、通信モニターには表示しますが再処理はしません。アクティブな無線機の報告は常にアンプに届き、PTT は無視されません。
+switching-same-band = 同じバンド:
+switching-same-band-hover = 2 台目の無線機がアクティブな無線機と同じバンドに同調したときの動作
+switching-per-mode = CW、電話、デジタルを別々に数える
+switching-per-mode-hover = フィールドデー: バンドとモードごとに送信機 1 台
+
+## Simulation
+
+sim-clock = シミュレーション:
+sim-pause-hover = 仮想デバイスの定期報告とポーリングを止めます
+sim-step = ステップ
+sim-step-hover = 一時停止中の時計を { $seconds } 秒進めます
+add-radio-description = シリアルポート、TCP シリアルサーバー、または仮想ポート
+add-radio = 無線機を追加...
+add-radio-hover = 無線機を設定して接続します
+menu-item = メニュー:
+menu-read = 読み取り
+menu-value = 値: { $value }
+menu-write = 書き込み
+sim-go-silent = 無応答にする
+sim-go-silent-hover = 電源の切れた無線機のように、すべてのコマンドを無視して何も送りません
+sim-go-silent-for = 期間
+sim-malformed = 不正なフレーム
+sim-malformed-hover = デコードできない周波数報告、または途中で切れたバイナリフレーム
+sim-report-frequency = { $frequency } を報告
+sim-report-frequency-hover = 同調せずにこの周波数を報告します
+sim-wrong-mode = 誤ったモード
+sim-wrong-mode-hover = モードを変えずに { $mode } を報告します
+sim-remove-rule = ルールを削除
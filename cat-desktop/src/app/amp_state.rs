@@ -0,0 +1,110 @@
+//! What the amplifier has been told, kept across restarts
+//!
+//! The mux reports its [`AmplifierEmulatedState`] whenever it changes. The
+//! last one is shown in the amplifier panel and saved next to the settings,
+//! so after a restart the panel still shows what the amp was last told
+//! until the mux has told it something new. The saved copy is only shown:
+//! the mux starts empty, because the amp may have been power-cycled since.
+
+use std::time::{Duration, Instant, SystemTime};
+
+use cat_mux::AmplifierEmulatedState;
+use egui::{Color32, RichText, Ui};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::tr;
+use crate::settings::Settings;
+use crate::traffic_monitor::TrafficMonitor;
+
+use super::CatapultApp;
+
+/// Shortest gap between saves while the amp's view keeps changing
+const SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// The amp's view as last reported by the mux
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct KnownAmpState {
+    pub state: AmplifierEmulatedState,
+    /// When the mux reported it
+    pub at: SystemTime,
+    /// Loaded from the last session rather than reported in this one
+    #[serde(skip)]
+    pub restored: bool,
+}
+
+impl KnownAmpState {
+    /// The state saved by the last session, if any
+    pub(super) fn load() -> Option<Self> {
+        let path = Settings::amp_state_path()?;
+        let json = std::fs::read_to_string(path).ok()?;
+        let mut known: Self = serde_json::from_str(&json).ok()?;
+        known.restored = true;
+        Some(known)
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = Settings::amp_state_path() else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, json).map_err(|e| e.to_string())
+    }
+}
+
+impl CatapultApp {
+    /// Note a new amp view from the mux (saved on the next save interval)
+    pub(super) fn on_amp_state_changed(&mut self, state: AmplifierEmulatedState) {
+        self.amp_known_state = Some(KnownAmpState {
+            state,
+            at: SystemTime::now(),
+            restored: false,
+        });
+        self.amp_state_unsaved = true;
+    }
+
+    /// Save the amp's view if it changed and the last save was a while ago
+    pub(super) fn maybe_save_amp_state(&mut self) {
+        if self.amp_state_unsaved && self.last_amp_state_save.elapsed() >= SAVE_INTERVAL {
+            self.save_amp_state();
+        }
+    }
+
+    /// Save the amp's view now (also called on exit)
+    pub(super) fn save_amp_state(&mut self) {
+        self.last_amp_state_save = Instant::now();
+        if !std::mem::take(&mut self.amp_state_unsaved) || self.settings.read_only {
+            return;
+        }
+        if let Some(known) = &self.amp_known_state {
+            if let Err(e) = known.save() {
+                self.report_warning(
+                    "Amplifier",
+                    tr!("status-amp-state-save-failed", error = e.to_string()),
+                );
+            }
+        }
+    }
+
+    /// Show what the amp has been told
+    pub(super) fn draw_amp_known_state(&self, ui: &mut Ui) {
+        let Some(known) = &self.amp_known_state else {
+            return;
+        };
+        let mut text = format!("Amp knows: {}", known.state.summary());
+        let mut hover = format!(
+            "What the mux has told the amplifier, as of {}",
+            TrafficMonitor::format_timestamp(&known.at)
+        );
+        if known.restored {
+            text.push_str(" (last session)");
+            hover.push_str(
+                ". Saved by the last session; the mux tells the amp again once a radio reports.",
+            );
+        }
+        ui.label(RichText::new(text).small().color(Color32::GRAY))
+            .on_hover_text(hover);
+    }
+}
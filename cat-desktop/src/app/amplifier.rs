@@ -2,15 +2,20 @@
 
 use std::time::Duration;
 
+use cat_detect::describe_open_error;
 use cat_mux::{
-    AmplifierChannel, AmplifierChannelMeta, AsyncAmpConnection, MuxActorCommand, MuxEvent,
+    AmpPersonality, AmplifierChannel, AmplifierChannelMeta, AsyncAmpConnection, MuxActorCommand,
+    MuxEvent, TransitionPolicy, TranslationStrictness, AGGRESSIVE_POLL_HZ, PTT_LATENCY_BUDGET,
 };
-use cat_protocol::Protocol;
+use cat_protocol::display::{format_decimal, format_frequency};
+use cat_protocol::{Frequency, Protocol};
 use cat_sim::{run_virtual_amp_task, VirtualAmpCommand, VirtualAmpMode, VirtualAmplifier};
 use egui::{Color32, RichText, Ui};
 use tokio::sync::{broadcast, mpsc as tokio_mpsc, oneshot};
 use tokio_serial::SerialPortBuilderExt;
 
+use crate::i18n::tr;
+
 use super::{AmplifierConnectionType, CatapultApp};
 
 impl CatapultApp {
@@ -19,6 +24,8 @@ impl CatapultApp {
         // Capture previous state for change detection
         let prev_connection_type = self.amp_connection_type;
         let prev_protocol = self.amp_protocol;
+        let prev_response_protocol = self.amp_response_protocol;
+        let prev_strictness = self.amp_strictness;
         let prev_port = self.amp_port.clone();
         let prev_baud = self.amp_baud;
         let prev_civ = self.amp_civ_address;
@@ -29,18 +36,18 @@ impl CatapultApp {
             .spacing([10.0, 4.0])
             .show(ui, |ui| {
                 // Connection type selector
-                ui.label("Connection:");
+                ui.label(tr!("amp-connection"));
                 egui::ComboBox::from_id_salt("amp_connection_type")
                     .selected_text(match self.amp_connection_type {
-                        AmplifierConnectionType::ComPort => "COM Port",
-                        AmplifierConnectionType::Simulated => "Simulated",
+                        AmplifierConnectionType::ComPort => tr!("amp-com-port"),
+                        AmplifierConnectionType::Simulated => tr!("amp-simulated"),
                     })
                     .show_ui(ui, |ui| {
                         if ui
                             .selectable_value(
                                 &mut self.amp_connection_type,
                                 AmplifierConnectionType::ComPort,
-                                "COM Port",
+                                tr!("amp-com-port"),
                             )
                             .changed()
                         {
@@ -52,12 +59,12 @@ impl CatapultApp {
                         ui.selectable_value(
                             &mut self.amp_connection_type,
                             AmplifierConnectionType::Simulated,
-                            "Simulated",
+                            tr!("amp-simulated"),
                         );
                     });
                 ui.end_row();
 
-                ui.label("Protocol:");
+                ui.label(tr!("protocol"));
                 egui::ComboBox::from_id_salt("amp_protocol")
                     .selected_text(self.amp_protocol.name())
                     .show_ui(ui, |ui| {
@@ -89,9 +96,65 @@ impl CatapultApp {
                     });
                 ui.end_row();
 
+                ui.label(tr!("amp-respond-as"))
+                    .on_hover_text(tr!("amp-respond-as-hover"));
+                egui::ComboBox::from_id_salt("amp_response_protocol")
+                    .selected_text(
+                        self.amp_response_protocol
+                            .map_or_else(|| tr!("amp-same-protocol"), |p| p.name().to_string()),
+                    )
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.amp_response_protocol,
+                            None,
+                            tr!("amp-same-protocol"),
+                        );
+                        for protocol in [
+                            Protocol::Kenwood,
+                            Protocol::IcomCIV,
+                            Protocol::Yaesu,
+                            Protocol::YaesuAscii,
+                            Protocol::Elecraft,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.amp_response_protocol,
+                                Some(protocol),
+                                protocol.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("amp-translation"))
+                    .on_hover_text(tr!("amp-translation-hover"));
+                let global = self.settings.translation_strictness;
+                egui::ComboBox::from_id_salt("amp_strictness")
+                    .selected_text(match self.amp_strictness {
+                        Some(strictness) => strictness.name().to_string(),
+                        None => tr!("amp-translation-global", strictness = global.name()),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.amp_strictness,
+                            None,
+                            tr!("amp-translation-global", strictness = global.name()),
+                        );
+                        for strictness in [
+                            TranslationStrictness::Strict,
+                            TranslationStrictness::Permissive,
+                        ] {
+                            ui.selectable_value(
+                                &mut self.amp_strictness,
+                                Some(strictness),
+                                strictness.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
                 // Only show port/baud for COM port mode
                 if self.amp_connection_type == AmplifierConnectionType::ComPort {
-                    ui.label("Port:");
+                    ui.label(tr!("port"));
                     // Get available ports (excludes ports used by radios)
                     // Collect into owned data to avoid borrow conflicts
                     let available_amp_ports: Vec<(String, String)> = self
@@ -102,7 +165,7 @@ impl CatapultApp {
 
                     // Find the selected port's hint for display
                     let selected_label = if self.amp_port.is_empty() {
-                        "Select port...".to_string()
+                        tr!("select-port-menu")
                     } else {
                         available_amp_ports
                             .iter()
@@ -119,7 +182,7 @@ impl CatapultApp {
                         });
                     ui.end_row();
 
-                    ui.label("Baud Rate:");
+                    ui.label(tr!("baud-rate"));
                     egui::ComboBox::from_id_salt("amp_baud")
                         .selected_text(format!("{}", self.amp_baud))
                         .show_ui(ui, |ui| {
@@ -130,35 +193,41 @@ impl CatapultApp {
                         });
                     ui.end_row();
 
-                    ui.label("Flow Control:");
+                    ui.label(tr!("amp-flow-control"));
                     egui::ComboBox::from_id_salt("amp_flow_control")
                         .selected_text(match self.amp_flow_control {
-                            crate::settings::SerialFlowControl::None => "None",
-                            crate::settings::SerialFlowControl::Software => "Software",
-                            crate::settings::SerialFlowControl::Hardware => "Hardware",
+                            crate::settings::SerialFlowControl::None => tr!("none"),
+                            crate::settings::SerialFlowControl::Software => {
+                                tr!("amp-flow-software")
+                            }
+                            crate::settings::SerialFlowControl::Hardware => {
+                                tr!("amp-flow-hardware")
+                            }
                         })
                         .show_ui(ui, |ui| {
                             ui.selectable_value(
                                 &mut self.amp_flow_control,
                                 crate::settings::SerialFlowControl::None,
-                                "None",
+                                tr!("none"),
                             );
                             ui.selectable_value(
                                 &mut self.amp_flow_control,
                                 crate::settings::SerialFlowControl::Software,
-                                "Software",
+                                tr!("amp-flow-software"),
                             );
                             ui.selectable_value(
                                 &mut self.amp_flow_control,
                                 crate::settings::SerialFlowControl::Hardware,
-                                "Hardware",
+                                tr!("amp-flow-hardware"),
                             );
                         });
                     ui.end_row();
 
                     // Show CI-V address for Icom protocol
-                    if self.amp_protocol == Protocol::IcomCIV {
-                        ui.label("CI-V Address:");
+                    if self.amp_protocol == Protocol::IcomCIV
+                        || self.amp_response_protocol == Some(Protocol::IcomCIV)
+                    {
+                        ui.label(tr!("civ-address"));
                         let mut addr_str = format!("{:02X}", self.amp_civ_address);
                         if ui.text_edit_singleline(&mut addr_str).changed() {
                             if let Ok(addr) =
@@ -180,23 +249,23 @@ impl CatapultApp {
                     let can_connect = !self.amp_port.is_empty() && !is_connected;
 
                     if ui
-                        .add_enabled(can_connect, egui::Button::new("Connect"))
+                        .add_enabled(can_connect, egui::Button::new(tr!("connect")))
                         .clicked()
                     {
                         self.connect_amplifier();
                     }
 
                     if ui
-                        .add_enabled(is_connected, egui::Button::new("Disconnect"))
+                        .add_enabled(is_connected, egui::Button::new(tr!("disconnect")))
                         .clicked()
                     {
                         self.disconnect_amplifier();
                     }
 
                     if is_connected {
-                        ui.label(RichText::new("Connected").color(Color32::GREEN));
+                        ui.label(RichText::new(tr!("connected")).color(Color32::GREEN));
                     } else if !self.amp_port.is_empty() {
-                        ui.label(RichText::new("Disconnected").color(Color32::GRAY));
+                        ui.label(RichText::new(tr!("disconnected")).color(Color32::GRAY));
                     }
                 });
             }
@@ -204,25 +273,25 @@ impl CatapultApp {
                 // Simulation mode selector (only when not connected)
                 if self.amp_data_tx.is_none() {
                     ui.horizontal(|ui| {
-                        ui.label("Simulation Mode:");
+                        ui.label(tr!("amp-simulation-mode"));
                         egui::ComboBox::from_id_salt("virtual_amp_mode")
                             .selected_text(match self.virtual_amp_mode {
-                                VirtualAmpMode::AutoInfo => "Auto-Info",
-                                VirtualAmpMode::Polling => "Polling",
+                                VirtualAmpMode::AutoInfo => tr!("amp-auto-info"),
+                                VirtualAmpMode::Polling => tr!("amp-polling"),
                             })
                             .show_ui(ui, |ui| {
                                 ui.selectable_value(
                                     &mut self.virtual_amp_mode,
                                     VirtualAmpMode::AutoInfo,
-                                    "Auto-Info",
+                                    tr!("amp-auto-info"),
                                 )
-                                .on_hover_text("Receive state updates pushed from radio");
+                                .on_hover_text(tr!("amp-auto-info-hover"));
                                 ui.selectable_value(
                                     &mut self.virtual_amp_mode,
                                     VirtualAmpMode::Polling,
-                                    "Polling",
+                                    tr!("amp-polling"),
                                 )
-                                .on_hover_text("Actively poll radio for frequency");
+                                .on_hover_text(tr!("amp-polling-hover"));
                             });
                     });
                 }
@@ -232,23 +301,23 @@ impl CatapultApp {
                     let is_connected = self.amp_data_tx.is_some();
 
                     if ui
-                        .add_enabled(!is_connected, egui::Button::new("Connect"))
+                        .add_enabled(!is_connected, egui::Button::new(tr!("connect")))
                         .clicked()
                     {
                         self.connect_amplifier();
                     }
 
                     if ui
-                        .add_enabled(is_connected, egui::Button::new("Disconnect"))
+                        .add_enabled(is_connected, egui::Button::new(tr!("disconnect")))
                         .clicked()
                     {
                         self.disconnect_amplifier();
                     }
 
                     if is_connected {
-                        ui.label(RichText::new("Connected").color(Color32::GREEN));
+                        ui.label(RichText::new(tr!("connected")).color(Color32::GREEN));
                     } else {
-                        ui.label(RichText::new("Disconnected").color(Color32::GRAY));
+                        ui.label(RichText::new(tr!("disconnected")).color(Color32::GRAY));
                     }
                 });
 
@@ -259,12 +328,9 @@ impl CatapultApp {
 
                     // Frequency display
                     ui.horizontal(|ui| {
-                        ui.label("Freq:");
+                        ui.label(tr!("amp-frequency"));
                         let freq_str = match self.virtual_amp_state.as_ref() {
-                            Some(state) => {
-                                let mhz = state.frequency_hz as f64 / 1_000_000.0;
-                                format!("{:.3} MHz", mhz)
-                            }
+                            Some(state) => format_frequency(Frequency::from_hz(state.frequency_hz)),
                             None => "---".to_string(),
                         };
                         ui.label(RichText::new(freq_str).monospace());
@@ -273,9 +339,29 @@ impl CatapultApp {
             }
         }
 
+        self.draw_amp_dry_run(ui);
+        self.draw_amp_bypass_settings(ui);
+        self.draw_amp_transition_settings(ui);
+        self.draw_ptt_latency(ui);
+        self.draw_amp_known_state(ui);
+        self.draw_amp_query_profile(ui);
+        self.draw_shadow_amp_section(ui);
+        self.draw_backup_amp_section(ui);
+
+        if self.amp_strictness != prev_strictness {
+            self.send_mux_command(
+                MuxActorCommand::SetAmpTranslationStrictness {
+                    strictness: self.amp_strictness,
+                },
+                "SetAmpTranslationStrictness",
+            );
+        }
+
         // Save if any amplifier settings changed
         if self.amp_connection_type != prev_connection_type
             || self.amp_protocol != prev_protocol
+            || self.amp_response_protocol != prev_response_protocol
+            || self.amp_strictness != prev_strictness
             || self.amp_port != prev_port
             || self.amp_baud != prev_baud
             || self.amp_civ_address != prev_civ
@@ -285,9 +371,226 @@ impl CatapultApp {
         }
     }
 
+    /// Draw the dry-run toggle
+    fn draw_amp_dry_run(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(&mut self.settings.amp_dry_run, tr!("amp-dry-run"))
+                .on_hover_text(tr!("amp-dry-run-hover"))
+                .changed()
+            {
+                self.send_mux_command(
+                    MuxActorCommand::SetAmpDryRun {
+                        enabled: self.settings.amp_dry_run,
+                    },
+                    "SetAmpDryRun",
+                );
+                if let Err(e) = self.settings.save() {
+                    self.handle_save_error(e);
+                }
+            }
+            if self.settings.amp_dry_run {
+                ui.label(RichText::new(tr!("amp-dry-run-active")).color(Color32::YELLOW));
+            }
+        });
+    }
+
+    /// Draw the switch-hold policy controls
+    fn draw_amp_transition_settings(&mut self, ui: &mut Ui) {
+        let prev = self.settings.amp_transition;
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("amp-transition"))
+                .on_hover_text(tr!("amp-transition-hover"));
+            egui::ComboBox::from_id_salt("amp_transition")
+                .selected_text(self.settings.amp_transition.name())
+                .show_ui(ui, |ui| {
+                    for policy in [
+                        TransitionPolicy::Off,
+                        TransitionPolicy::HoldUpdates,
+                        TransitionPolicy::Busy,
+                    ] {
+                        ui.selectable_value(
+                            &mut self.settings.amp_transition,
+                            policy,
+                            policy.name(),
+                        );
+                    }
+                });
+        });
+
+        if self.settings.amp_transition != prev {
+            self.send_mux_command(
+                MuxActorCommand::SetTransitionPolicy {
+                    policy: self.settings.amp_transition,
+                },
+                "SetTransitionPolicy",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        if ui
+            .checkbox(
+                &mut self.settings.gapless_handover,
+                tr!("amp-gapless-handover"),
+            )
+            .on_hover_text(tr!("amp-gapless-handover-hover"))
+            .changed()
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetGaplessHandover {
+                    enabled: self.settings.gapless_handover,
+                },
+                "SetGaplessHandover",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+    }
+
+    /// Draw the amp's personality, out-of-range bypass and meter forwarding
+    fn draw_amp_bypass_settings(&mut self, ui: &mut Ui) {
+        let prev = self.settings.amp_bypass;
+        let bypass = &mut self.settings.amp_bypass;
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(tr!("amp-personality"));
+            egui::ComboBox::from_id_salt("amp_personality")
+                .selected_text(bypass.personality.name())
+                .show_ui(ui, |ui| {
+                    for personality in AmpPersonality::all() {
+                        ui.selectable_value(
+                            &mut bypass.personality,
+                            personality,
+                            personality.name(),
+                        );
+                    }
+                });
+        });
+        ui.checkbox(&mut bypass.enabled, tr!("amp-bypass"))
+            .on_hover_text(tr!("amp-bypass-hover"));
+
+        if bypass.enabled {
+            egui::Grid::new("amp_bypass")
+                .num_columns(2)
+                .spacing([10.0, 4.0])
+                .show(ui, |ui| {
+                    let mut min_khz = bypass.min_hz.whole_khz();
+                    let mut max_khz = bypass.max_hz.whole_khz();
+                    ui.label(tr!("amp-bypass-range"));
+                    ui.horizontal(|ui| {
+                        ui.add(egui::DragValue::new(&mut min_khz).range(0..=max_khz));
+                        ui.label("-");
+                        ui.add(egui::DragValue::new(&mut max_khz).range(min_khz..=500_000));
+                    });
+                    ui.end_row();
+                    bypass.min_hz = Frequency::from_khz(min_khz);
+                    bypass.max_hz = Frequency::from_khz(max_khz);
+                });
+        }
+
+        if bypass.personality.displays_meters() {
+            ui.checkbox(&mut bypass.forward_meters, tr!("amp-forward-meters"))
+                .on_hover_text(tr!("amp-forward-meters-hover"));
+        }
+
+        if self.settings.amp_bypass != prev {
+            self.send_mux_command(
+                MuxActorCommand::SetAmpBypassConfig {
+                    config: self.settings.amp_bypass,
+                },
+                "SetAmpBypassConfig",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+    }
+
+    /// Show how quickly radio PTT edges reach the amplifier
+    fn draw_ptt_latency(&mut self, ui: &mut Ui) {
+        let stats = self.ptt_latency;
+        if self.amp_data_tx.is_none() || stats.samples == 0 {
+            return;
+        }
+
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let color = if stats.max > PTT_LATENCY_BUDGET {
+            Color32::from_rgb(255, 180, 0)
+        } else {
+            Color32::GRAY
+        };
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(tr!(
+                    "amp-ptt-latency",
+                    last = format_decimal(ms(stats.last), 1),
+                    mean = format_decimal(ms(stats.mean()), 1),
+                    max = format_decimal(ms(stats.max), 1)
+                ))
+                .small()
+                .color(color),
+            )
+            .on_hover_text(tr!(
+                "amp-ptt-latency-hover",
+                edges = stats.samples,
+                over = stats.over_budget,
+                budget = PTT_LATENCY_BUDGET.as_millis() as u64
+            ));
+            if ui.small_button(tr!("reset")).clicked() {
+                self.ptt_latency.reset();
+            }
+        });
+    }
+
+    /// Show which queries the amplifier polls and how often
+    fn draw_amp_query_profile(&mut self, ui: &mut Ui) {
+        let profile = &self.amp_query_profile;
+        if self.amp_data_tx.is_none() || profile.queries.is_empty() {
+            return;
+        }
+
+        let color = if profile.is_aggressive() {
+            Color32::from_rgb(255, 180, 0)
+        } else {
+            Color32::GRAY
+        };
+        let top: Vec<String> = profile
+            .queries
+            .iter()
+            .take(3)
+            .map(|q| format!("{} {:.1}/s", q.kind.name(), q.rate_hz))
+            .collect();
+        let details: Vec<String> = profile
+            .queries
+            .iter()
+            .map(|q| format!("{}: {:.1}/s ({} total)", q.kind.name(), q.rate_hz, q.count))
+            .collect();
+        let mut hover = details.join("\n");
+        if profile.is_aggressive() {
+            hover.push_str(&format!(
+                "\n\nPolling at {:.0}/s or more: unsolicited updates of polled values are \
+                 rate-limited ({} skipped)",
+                AGGRESSIVE_POLL_HZ, profile.limited_updates
+            ));
+        }
+        ui.label(
+            RichText::new(tr!("amp-queries", queries = top.join(", ")))
+                .small()
+                .color(color),
+        )
+        .on_hover_text(hover);
+    }
+
     /// Connect to the amplifier (handles both COM and virtual based on connection type)
     pub(super) fn connect_amplifier(&mut self) {
-        let civ_address = if self.amp_protocol == Protocol::IcomCIV {
+        let civ_address = if self.amp_protocol == Protocol::IcomCIV
+            || self.amp_response_protocol == Some(Protocol::IcomCIV)
+        {
             Some(self.amp_civ_address)
         } else {
             None
@@ -297,7 +600,7 @@ impl CatapultApp {
         let (port_name, baud_rate, amp_meta) = match self.amp_connection_type {
             AmplifierConnectionType::ComPort => {
                 if self.amp_port.is_empty() {
-                    self.set_status("No amplifier port selected".into());
+                    self.set_status(tr!("amp-no-port"));
                     return;
                 }
                 (
@@ -308,7 +611,8 @@ impl CatapultApp {
                         self.amp_protocol,
                         self.amp_baud,
                         civ_address,
-                    ),
+                    )
+                    .with_flow_control(self.amp_flow_control.into()),
                 )
             }
             AmplifierConnectionType::Simulated => (
@@ -325,17 +629,22 @@ impl CatapultApp {
                 protocol: self.amp_protocol,
                 baud_rate,
                 civ_address,
+                response_protocol: self.amp_response_protocol,
+                strictness: self.amp_strictness,
             },
             "SetAmplifierConfig",
         );
 
         // Create channels
         let (amp_data_tx, amp_data_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
+        let (urgent_tx, urgent_rx) = tokio_mpsc::channel::<Vec<u8>>(8);
         let (shutdown_tx, shutdown_rx) = oneshot::channel();
         let (_response_tx, response_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
 
         // Create AmplifierChannel and tell mux actor
-        let amp_channel = AmplifierChannel::new(amp_meta, amp_data_tx.clone(), response_rx);
+        let amp_channel = AmplifierChannel::new(amp_meta, amp_data_tx.clone(), response_rx)
+            .with_urgent(urgent_tx);
+        let backlog = amp_channel.backlog.clone();
         self.send_mux_command(
             MuxActorCommand::ConnectAmplifier {
                 channel: amp_channel,
@@ -348,8 +657,7 @@ impl CatapultApp {
         self.amp_shutdown_tx = Some(shutdown_tx);
 
         // Spawn the async amp connection based on connection type
-        let mux_tx = self.mux_cmd_tx.clone();
-        let event_tx = self.mux_event_tx.clone();
+        let mux = self.mux.clone();
 
         match self.amp_connection_type {
             AmplifierConnectionType::ComPort => {
@@ -376,25 +684,28 @@ impl CatapultApp {
                         Ok(s) => s,
                         Err(e) => {
                             tracing::error!(port = %port, error = %e, "Failed to open amplifier port");
-                            let _ = event_tx
-                                .send(MuxEvent::Error {
+                            let _ = mux
+                                .emit(MuxEvent::Error {
                                     source: format!("Amplifier {}", port),
-                                    message: format!("Failed to open port: {}", e),
+                                    message: format!("Failed to open port: {}", describe_open_error(&port, &e)),
                                 })
                                 .await;
-                            let _ = mux_tx.send(MuxActorCommand::DisconnectAmplifier).await;
+                            let _ = mux.send(MuxActorCommand::DisconnectAmplifier).await;
                             return;
                         }
                     };
 
                     tracing::debug!(port = %port, "Amplifier serial port opened successfully");
-                    let conn = AsyncAmpConnection::new(stream, mux_tx, event_tx);
+                    let conn = AsyncAmpConnection::new(stream, &mux)
+                        .with_backlog(backlog)
+                        .with_urgent(urgent_rx);
                     conn.run(shutdown_rx, amp_data_rx).await;
                 });
 
-                self.set_status(format!(
-                    "Connecting to amplifier on {} @ {} baud",
-                    self.amp_port, self.amp_baud
+                self.set_status(tr!(
+                    "amp-connecting",
+                    port = self.amp_port.clone(),
+                    baud = self.amp_baud
                 ));
             }
             AmplifierConnectionType::Simulated => {
@@ -403,7 +714,8 @@ impl CatapultApp {
 
                 // Create virtual amplifier
                 let virtual_amp =
-                    VirtualAmplifier::new("virtual-amp", self.amp_protocol, civ_address);
+                    VirtualAmplifier::new("virtual-amp", self.amp_protocol, civ_address)
+                        .with_clock(self.simulation_panel.clock());
 
                 // Create channels for virtual amp actor
                 let (vamp_cmd_tx, vamp_cmd_rx) = tokio_mpsc::channel::<VirtualAmpCommand>(32);
@@ -432,13 +744,15 @@ impl CatapultApp {
 
                 // Spawn the AsyncAmpConnection with the mux side of the duplex
                 self.rt_handle.spawn(async move {
-                    let conn = AsyncAmpConnection::new(mux_stream, mux_tx, event_tx);
+                    let conn = AsyncAmpConnection::new(mux_stream, &mux)
+                        .with_backlog(backlog)
+                        .with_urgent(urgent_rx);
                     conn.run(shutdown_rx, amp_data_rx).await;
                 });
 
-                self.set_status(format!(
-                    "Connected to virtual amplifier (protocol: {})",
-                    self.amp_protocol.name()
+                self.set_status(tr!(
+                    "amp-virtual-connected",
+                    protocol = self.amp_protocol.name()
                 ));
             }
         }
@@ -446,6 +760,15 @@ impl CatapultApp {
 
     /// Disconnect from the amplifier
     pub(super) fn disconnect_amplifier(&mut self) {
+        // The shadow only mirrors the primary; it goes too
+        if self.shadow_amp.is_connected() {
+            self.disconnect_shadow_amplifier();
+        }
+        // So does the backup, before the primary so the failover is cleared
+        if self.backup_amp.is_connected() {
+            self.disconnect_backup_amplifier();
+        }
+
         // Tell mux actor to stop sending to amp
         self.send_mux_command(MuxActorCommand::DisconnectAmplifier, "DisconnectAmplifier");
 
@@ -462,6 +785,6 @@ impl CatapultApp {
         self.amp_data_tx = None;
         self.virtual_amp_state_rx = None;
         self.virtual_amp_state = None;
-        self.set_status("Amplifier disconnected".into());
+        self.set_status(tr!("amp-disconnected"));
     }
 }
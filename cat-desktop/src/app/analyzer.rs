@@ -0,0 +1,311 @@
+//! Analyzer (monitor-only) mode: logger <-> radio passthrough with decoding
+
+use std::time::Duration;
+
+use cat_detect::describe_open_error;
+use cat_mux::{AnalyzerDirection, AnalyzerPassthrough, MuxEvent};
+use cat_protocol::Protocol;
+use egui::{Color32, RichText, Ui};
+use tokio::sync::oneshot;
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::i18n::tr;
+
+use super::CatapultApp;
+
+impl CatapultApp {
+    /// Whether the analyzer passthrough task is running
+    pub(super) fn analyzer_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits (port error, unplugged)
+        self.analyzer_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Enter or leave analyzer mode
+    ///
+    /// Entering disconnects the radios, amplifier and band decoder so the
+    /// passthrough has the ports to itself. Leaving stops the passthrough and
+    /// restores the configured radios.
+    pub(super) fn set_analyzer_mode(&mut self, enabled: bool) {
+        if self.settings.analyzer.enabled == enabled {
+            return;
+        }
+        self.settings.analyzer.enabled = enabled;
+        if let Err(e) = self.settings.save() {
+            self.handle_save_error(e);
+        }
+
+        if enabled {
+            self.close_radios();
+            if self.amp_data_tx.is_some() {
+                self.disconnect_amplifier();
+            }
+            self.disconnect_band_decoder();
+            self.set_status(tr!("analyzer-mode-on"));
+        } else {
+            self.stop_analyzer();
+            if self.radio_panels.is_empty() {
+                self.restore_radios();
+            }
+            self.set_status(tr!("analyzer-mode-off"));
+        }
+    }
+
+    /// Draw the analyzer panel (replaces the radio list in analyzer mode)
+    pub(super) fn draw_analyzer_panel(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("analyzer-heading"));
+        ui.label(
+            RichText::new(tr!("analyzer-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+        ui.add_space(8.0);
+
+        let running = self.analyzer_running();
+        let prev = self.settings.analyzer.clone();
+        let ports: Vec<(String, String)> = self
+            .available_amp_ports()
+            .into_iter()
+            .map(|p| (p.port.clone(), Self::format_port_label(p)))
+            .collect();
+
+        ui.add_enabled_ui(!running, |ui| {
+            let analyzer = &mut self.settings.analyzer;
+            egui::Grid::new("analyzer_grid")
+                .num_columns(2)
+                .spacing([10.0, 8.0])
+                .show(ui, |ui| {
+                    for (label, salt, selected) in [
+                        (
+                            "Logger port:",
+                            "analyzer_logger_port",
+                            &mut analyzer.logger_port,
+                        ),
+                        (
+                            "Radio port:",
+                            "analyzer_radio_port",
+                            &mut analyzer.radio_port,
+                        ),
+                    ] {
+                        ui.label(label);
+                        let text = if selected.is_empty() {
+                            tr!("select-port-menu")
+                        } else {
+                            selected.clone()
+                        };
+                        egui::ComboBox::from_id_salt(salt)
+                            .selected_text(text)
+                            .width(150.0)
+                            .show_ui(ui, |ui| {
+                                for (port, label) in &ports {
+                                    ui.selectable_value(selected, port.clone(), label);
+                                }
+                            });
+                        ui.end_row();
+                    }
+
+                    ui.label(tr!("protocol"));
+                    egui::ComboBox::from_id_salt("analyzer_protocol")
+                        .selected_text(analyzer.protocol.name())
+                        .width(150.0)
+                        .show_ui(ui, |ui| {
+                            for proto in [
+                                Protocol::Kenwood,
+                                Protocol::IcomCIV,
+                                Protocol::Yaesu,
+                                Protocol::YaesuAscii,
+                                Protocol::Elecraft,
+                                Protocol::FlexRadio,
+                            ] {
+                                ui.selectable_value(&mut analyzer.protocol, proto, proto.name());
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(tr!("baud"));
+                    egui::ComboBox::from_id_salt("analyzer_baud")
+                        .selected_text(format!("{}", analyzer.baud_rate))
+                        .width(150.0)
+                        .show_ui(ui, |ui| {
+                            for &baud in &[4800u32, 9600, 19200, 38400, 57600, 115200] {
+                                ui.selectable_value(
+                                    &mut analyzer.baud_rate,
+                                    baud,
+                                    format!("{}", baud),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                });
+        });
+
+        if self.settings.analyzer != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            let analyzer = &self.settings.analyzer;
+            let can_start = !running
+                && !analyzer.logger_port.is_empty()
+                && !analyzer.radio_port.is_empty()
+                && analyzer.logger_port != analyzer.radio_port;
+            if ui
+                .add_enabled(can_start, egui::Button::new(tr!("start")))
+                .clicked()
+            {
+                self.start_analyzer();
+            }
+            if ui
+                .add_enabled(running, egui::Button::new(tr!("stop")))
+                .clicked()
+            {
+                self.stop_analyzer();
+            }
+            if running {
+                ui.label(RichText::new(tr!("running")).color(Color32::GREEN));
+            }
+        });
+
+        ui.add_space(12.0);
+        self.draw_analyzer_stats(ui);
+    }
+
+    /// Draw frame counters and the per-command breakdown
+    fn draw_analyzer_stats(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.strong(tr!("analyzer-statistics"));
+            if ui.small_button(tr!("reset")).clicked() {
+                self.analyzer_stats.reset();
+            }
+            if ui.small_button(tr!("copy")).clicked() {
+                ui.ctx().copy_text(self.format_analyzer_stats());
+                self.set_status(tr!("analyzer-statistics-copied"));
+            }
+        });
+
+        let stats = &self.analyzer_stats;
+        egui::Grid::new("analyzer_totals")
+            .num_columns(4)
+            .spacing([16.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("");
+                ui.strong(tr!("analyzer-frames"));
+                ui.strong(tr!("analyzer-bytes"));
+                ui.strong(tr!("analyzer-unknown"));
+                ui.end_row();
+                for (label, totals) in [
+                    ("Logger → Radio", &stats.logger_to_radio),
+                    ("Radio → Logger", &stats.radio_to_logger),
+                ] {
+                    ui.label(label);
+                    ui.monospace(totals.frames.to_string());
+                    ui.monospace(totals.bytes.to_string());
+                    ui.monospace(totals.unknown.to_string());
+                    ui.end_row();
+                }
+            });
+
+        if stats.commands.is_empty() {
+            return;
+        }
+        ui.add_space(8.0);
+        egui::Grid::new("analyzer_commands")
+            .num_columns(3)
+            .spacing([16.0, 2.0])
+            .striped(true)
+            .show(ui, |ui| {
+                for ((direction, command), count) in &stats.commands {
+                    ui.label(direction_arrow(*direction));
+                    ui.monospace(command);
+                    ui.monospace(count.to_string());
+                    ui.end_row();
+                }
+            });
+    }
+
+    /// Plain-text statistics for the clipboard
+    fn format_analyzer_stats(&self) -> String {
+        let stats = &self.analyzer_stats;
+        let mut out = format!(
+            "Analyzer statistics ({})\n",
+            self.settings.analyzer.protocol.name()
+        );
+        for (label, totals) in [
+            ("Logger -> Radio", &stats.logger_to_radio),
+            ("Radio -> Logger", &stats.radio_to_logger),
+        ] {
+            out.push_str(&format!(
+                "{}: {} frames, {} bytes, {} unknown\n",
+                label, totals.frames, totals.bytes, totals.unknown
+            ));
+        }
+        for ((direction, command), count) in &stats.commands {
+            out.push_str(&format!(
+                "{} {} {}\n",
+                direction_arrow(*direction),
+                command,
+                count
+            ));
+        }
+        out
+    }
+
+    /// Open both ports and start relaying
+    pub(super) fn start_analyzer(&mut self) {
+        let analyzer = self.settings.analyzer.clone();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.analyzer_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+
+        self.rt_handle.spawn(async move {
+            let open = |port: &str| {
+                tokio_serial::new(port, analyzer.baud_rate)
+                    .timeout(Duration::from_millis(100))
+                    .open_native_async()
+                    .map_err(|e| describe_open_error(port, &e))
+            };
+            let (logger, radio) = match (open(&analyzer.logger_port), open(&analyzer.radio_port)) {
+                (Ok(logger), Ok(radio)) => (logger, radio),
+                (Err(e), _) | (_, Err(e)) => {
+                    tracing::error!(error = %e, "Failed to open analyzer ports");
+                    let _ = mux
+                        .emit(MuxEvent::Error {
+                            source: "Analyzer".to_string(),
+                            message: format!("Failed to open port: {}", e),
+                        })
+                        .await;
+                    return;
+                }
+            };
+
+            AnalyzerPassthrough::new(logger, radio, analyzer.protocol, &mux)
+                .run(shutdown_rx)
+                .await;
+        });
+
+        self.set_status(tr!(
+            "analyzer-running",
+            logger = self.settings.analyzer.logger_port.clone(),
+            radio = self.settings.analyzer.radio_port.clone()
+        ));
+    }
+
+    /// Stop the passthrough (the ports are closed when the task exits)
+    pub(super) fn stop_analyzer(&mut self) {
+        if let Some(tx) = self.analyzer_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
+
+fn direction_arrow(direction: AnalyzerDirection) -> &'static str {
+    match direction {
+        AnalyzerDirection::LoggerToRadio => "Logger → Radio",
+        AnalyzerDirection::RadioToLogger => "Radio → Logger",
+    }
+}
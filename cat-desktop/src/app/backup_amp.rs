@@ -0,0 +1,353 @@
+//! Backup amplifier and failover UI
+//!
+//! A second amplifier stands by next to the primary. When failover is on and
+//! the amp in service fails, the mux moves output to the backup (or to
+//! bypass) and keeps it there until the operator acknowledges.
+
+use std::time::Duration;
+
+use cat_detect::describe_open_error;
+use cat_mux::{
+    AmpRole, AmplifierChannel, AmplifierChannelMeta, AsyncAmpConnection, MuxActorCommand, MuxEvent,
+};
+use cat_sim::{run_virtual_amp_task, VirtualAmpCommand, VirtualAmpMode, VirtualAmplifier};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::{broadcast, mpsc as tokio_mpsc, oneshot};
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::i18n::tr;
+
+use super::{AmplifierConnectionType, CatapultApp};
+
+/// Backup amplifier selection, connection and failover state
+pub(crate) struct BackupAmpState {
+    /// COM port or simulated backup
+    pub connection_type: AmplifierConnectionType,
+    /// Serial port of a real backup amp
+    pub port: String,
+    /// Baud rate of a real backup amp
+    pub baud: u32,
+    /// Behavior of a simulated backup amp
+    pub virtual_mode: VirtualAmpMode,
+    /// Connection task shutdown (Some while connected)
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Simulated backup amp command sender (for shutdown)
+    virtual_cmd_tx: Option<tokio_mpsc::Sender<VirtualAmpCommand>>,
+    /// Where output went and why, while it is off the primary
+    pub failed_over: Option<(AmpRole, String)>,
+}
+
+impl Default for BackupAmpState {
+    fn default() -> Self {
+        Self {
+            connection_type: AmplifierConnectionType::Simulated,
+            port: String::new(),
+            baud: 9600,
+            virtual_mode: VirtualAmpMode::Polling,
+            shutdown_tx: None,
+            virtual_cmd_tx: None,
+            failed_over: None,
+        }
+    }
+}
+
+impl BackupAmpState {
+    /// Whether a backup amp is connected
+    pub fn is_connected(&self) -> bool {
+        self.shutdown_tx.is_some()
+    }
+
+    /// The amp in service, when output has left the primary
+    pub fn failed_over_to(&self) -> Option<AmpRole> {
+        self.failed_over.as_ref().map(|(role, _)| *role)
+    }
+}
+
+impl CatapultApp {
+    /// Draw the backup amplifier controls (below the amplifier settings)
+    pub(super) fn draw_backup_amp_section(&mut self, ui: &mut Ui) {
+        let section = egui::CollapsingHeader::new(tr!("backup-amp-heading"))
+            .id_salt("backup_amp")
+            .default_open(self.settings.layout.section_open("backup_amp"))
+            .show(ui, |ui| {
+                ui.label(
+                    RichText::new(tr!("backup-amp-description"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                if ui
+                    .checkbox(&mut self.settings.amp_failover, tr!("backup-amp-failover"))
+                    .on_hover_text(tr!("backup-amp-failover-hover"))
+                    .changed()
+                {
+                    self.send_mux_command(
+                        MuxActorCommand::SetAmpFailover {
+                            enabled: self.settings.amp_failover,
+                        },
+                        "SetAmpFailover",
+                    );
+                    if let Err(e) = self.settings.save() {
+                        self.handle_save_error(e);
+                    }
+                }
+
+                let connected = self.backup_amp.is_connected();
+                ui.add_enabled_ui(!connected, |ui| self.draw_backup_amp_config(ui));
+
+                ui.horizontal(|ui| {
+                    let can_connect = !connected
+                        && (self.backup_amp.connection_type == AmplifierConnectionType::Simulated
+                            || !self.backup_amp.port.is_empty());
+                    if ui
+                        .add_enabled(can_connect, egui::Button::new(tr!("connect")))
+                        .clicked()
+                    {
+                        self.connect_backup_amplifier();
+                    }
+                    if ui
+                        .add_enabled(connected, egui::Button::new(tr!("disconnect")))
+                        .clicked()
+                    {
+                        self.disconnect_backup_amplifier();
+                    }
+                    if connected && self.backup_amp.failed_over_to() != Some(AmpRole::Backup) {
+                        ui.label(
+                            RichText::new(tr!("backup-amp-standing-by")).color(Color32::GREEN),
+                        );
+                    }
+                });
+
+                let Some((role, reason)) = self.backup_amp.failed_over.clone() else {
+                    return;
+                };
+                ui.label(
+                    RichText::new(tr!(
+                        "backup-amp-failed-over",
+                        amp = role.name(),
+                        reason = reason.clone()
+                    ))
+                    .color(Color32::from_rgb(255, 120, 0)),
+                );
+                if ui
+                    .button(tr!("backup-amp-acknowledge"))
+                    .on_hover_text(tr!("backup-amp-acknowledge-hover"))
+                    .clicked()
+                {
+                    self.send_mux_command(
+                        MuxActorCommand::AcknowledgeAmpFailover,
+                        "AcknowledgeAmpFailover",
+                    );
+                }
+            });
+        self.settings
+            .layout
+            .remember_section("backup_amp", &section);
+    }
+
+    /// Connection type, port and simulation mode for the backup amp
+    fn draw_backup_amp_config(&mut self, ui: &mut Ui) {
+        let available_ports: Vec<(String, String)> = self
+            .available_amp_ports()
+            .into_iter()
+            .filter(|p| p.port != self.amp_port && p.port != self.shadow_amp.port)
+            .map(|p| (p.port.clone(), Self::format_port_label(p)))
+            .collect();
+        let backup = &mut self.backup_amp;
+
+        egui::Grid::new("backup_amp_config")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label(tr!("amp-connection"));
+                egui::ComboBox::from_id_salt("backup_amp_connection_type")
+                    .selected_text(match backup.connection_type {
+                        AmplifierConnectionType::ComPort => tr!("amp-com-port"),
+                        AmplifierConnectionType::Simulated => tr!("amp-simulated"),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut backup.connection_type,
+                            AmplifierConnectionType::ComPort,
+                            tr!("amp-com-port"),
+                        );
+                        ui.selectable_value(
+                            &mut backup.connection_type,
+                            AmplifierConnectionType::Simulated,
+                            tr!("amp-simulated"),
+                        );
+                    });
+                ui.end_row();
+
+                match backup.connection_type {
+                    AmplifierConnectionType::ComPort => {
+                        ui.label(tr!("port"));
+                        let selected = if backup.port.is_empty() {
+                            tr!("select-port-menu")
+                        } else {
+                            backup.port.clone()
+                        };
+                        egui::ComboBox::from_id_salt("backup_amp_port")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (port, label) in &available_ports {
+                                    ui.selectable_value(&mut backup.port, port.clone(), label);
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr!("baud-rate"));
+                        egui::ComboBox::from_id_salt("backup_amp_baud")
+                            .selected_text(format!("{}", backup.baud))
+                            .show_ui(ui, |ui| {
+                                for &baud in &[4800u32, 9600, 19200, 38400, 57600, 115200, 230400] {
+                                    ui.selectable_value(
+                                        &mut backup.baud,
+                                        baud,
+                                        format!("{}", baud),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                    }
+                    AmplifierConnectionType::Simulated => {
+                        ui.label(tr!("amp-simulation-mode"));
+                        egui::ComboBox::from_id_salt("backup_amp_mode")
+                            .selected_text(match backup.virtual_mode {
+                                VirtualAmpMode::AutoInfo => tr!("amp-auto-info"),
+                                VirtualAmpMode::Polling => tr!("amp-polling"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut backup.virtual_mode,
+                                    VirtualAmpMode::AutoInfo,
+                                    tr!("amp-auto-info"),
+                                );
+                                ui.selectable_value(
+                                    &mut backup.virtual_mode,
+                                    VirtualAmpMode::Polling,
+                                    tr!("amp-polling"),
+                                );
+                            });
+                        ui.end_row();
+                    }
+                }
+            });
+    }
+
+    /// Connect the backup amplifier (speaks the primary amp's protocol)
+    fn connect_backup_amplifier(&mut self) {
+        let civ_address =
+            (self.amp_protocol == cat_protocol::Protocol::IcomCIV).then_some(self.amp_civ_address);
+        let meta = match self.backup_amp.connection_type {
+            AmplifierConnectionType::ComPort => AmplifierChannelMeta::new_real(
+                self.backup_amp.port.clone(),
+                self.amp_protocol,
+                self.backup_amp.baud,
+                civ_address,
+            ),
+            AmplifierConnectionType::Simulated => {
+                AmplifierChannelMeta::new_virtual(self.amp_protocol, civ_address)
+            }
+        };
+
+        let (data_tx, data_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
+        let (urgent_tx, urgent_rx) = tokio_mpsc::channel::<Vec<u8>>(8);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (_response_tx, response_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
+        let channel = AmplifierChannel::new(meta, data_tx, response_rx).with_urgent(urgent_tx);
+        let backlog = channel.backlog.clone();
+        self.send_mux_command(
+            MuxActorCommand::ConnectBackupAmplifier { channel },
+            "ConnectBackupAmplifier",
+        );
+        self.backup_amp.shutdown_tx = Some(shutdown_tx);
+
+        let mux = self.mux.clone();
+
+        match self.backup_amp.connection_type {
+            AmplifierConnectionType::ComPort => {
+                let port = self.backup_amp.port.clone();
+                let baud = self.backup_amp.baud;
+                self.rt_handle.spawn(async move {
+                    let stream = match tokio_serial::new(&port, baud)
+                        .timeout(Duration::from_millis(100))
+                        .open_native_async()
+                    {
+                        Ok(s) => s,
+                        Err(e) => {
+                            let _ = mux
+                                .emit(MuxEvent::Error {
+                                    source: format!("Backup amplifier {}", port),
+                                    message: format!(
+                                        "Failed to open port: {}",
+                                        describe_open_error(&port, &e)
+                                    ),
+                                })
+                                .await;
+                            let _ = mux.send(MuxActorCommand::DisconnectBackupAmplifier).await;
+                            return;
+                        }
+                    };
+                    AsyncAmpConnection::new(stream, &mux)
+                        .as_backup()
+                        .with_backlog(backlog)
+                        .with_urgent(urgent_rx)
+                        .run(shutdown_rx, data_rx)
+                        .await;
+                });
+                self.set_status(tr!(
+                    "backup-amp-connecting",
+                    port = self.backup_amp.port.clone()
+                ));
+            }
+            AmplifierConnectionType::Simulated => {
+                let (mux_stream, amp_stream) = tokio::io::duplex(4096);
+                let virtual_amp =
+                    VirtualAmplifier::new("backup-amp", self.amp_protocol, civ_address)
+                        .with_clock(self.simulation_panel.clock());
+                let (cmd_tx, cmd_rx) = tokio_mpsc::channel::<VirtualAmpCommand>(32);
+                // The backup's state is not shown; nobody subscribes
+                let (state_tx, _) = broadcast::channel::<cat_sim::VirtualAmpStateEvent>(32);
+                self.backup_amp.virtual_cmd_tx = Some(cmd_tx);
+
+                let mode = self.backup_amp.virtual_mode;
+                self.rt_handle.spawn(async move {
+                    if let Err(e) =
+                        run_virtual_amp_task(amp_stream, virtual_amp, cmd_rx, state_tx, mode).await
+                    {
+                        tracing::error!("Backup virtual amplifier task error: {}", e);
+                    }
+                });
+                self.rt_handle.spawn(async move {
+                    AsyncAmpConnection::new(mux_stream, &mux)
+                        .as_backup()
+                        .with_backlog(backlog)
+                        .with_urgent(urgent_rx)
+                        .run(shutdown_rx, data_rx)
+                        .await;
+                });
+                self.set_status(tr!("backup-amp-simulated"));
+            }
+        }
+    }
+
+    /// Disconnect the backup amplifier (output on it moves to bypass)
+    pub(super) fn disconnect_backup_amplifier(&mut self) {
+        self.send_mux_command(
+            MuxActorCommand::DisconnectBackupAmplifier,
+            "DisconnectBackupAmplifier",
+        );
+        self.backup_amp_stopped();
+    }
+
+    /// Stop the backup connection tasks
+    pub(super) fn backup_amp_stopped(&mut self) {
+        if let Some(tx) = self.backup_amp.virtual_cmd_tx.take() {
+            let _ = tx.try_send(VirtualAmpCommand::Shutdown);
+        }
+        if let Some(tx) = self.backup_amp.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
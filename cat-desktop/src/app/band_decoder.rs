@@ -0,0 +1,156 @@
+//! Band decoder (OTRSP / microHAM) connection and management
+
+use std::time::Duration;
+
+use cat_detect::describe_open_error;
+use cat_mux::band_decoder::OTRSP_DEFAULT_BAUD;
+use cat_mux::{BandDecoderConnection, MuxActorCommand, MuxEvent};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::i18n::tr;
+
+use super::CatapultApp;
+
+impl CatapultApp {
+    /// Draw the band decoder configuration panel
+    pub(super) fn draw_band_decoder_panel(&mut self, ui: &mut Ui) {
+        let prev_port = self.settings.band_decoder_port.clone();
+        // The task drops its shutdown receiver when it exits (port error, unplugged)
+        let is_connected = self
+            .band_decoder_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed());
+
+        ui.label(
+            RichText::new(tr!("band-decoder-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("port"));
+            let ports: Vec<(String, String)> = self
+                .available_amp_ports()
+                .into_iter()
+                .filter(|p| p.port != self.amp_port)
+                .map(|p| (p.port.clone(), Self::format_port_label(p)))
+                .collect();
+
+            let selected_text = if self.settings.band_decoder_port.is_empty() {
+                "None".to_string()
+            } else {
+                self.settings.band_decoder_port.clone()
+            };
+            ui.add_enabled_ui(!is_connected, |ui| {
+                egui::ComboBox::from_id_salt("band_decoder_port")
+                    .selected_text(selected_text)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.settings.band_decoder_port,
+                            String::new(),
+                            tr!("none"),
+                        );
+                        for (port, label) in &ports {
+                            ui.selectable_value(
+                                &mut self.settings.band_decoder_port,
+                                port.clone(),
+                                label,
+                            );
+                        }
+                    });
+            });
+        });
+
+        ui.horizontal(|ui| {
+            let can_connect = !self.settings.band_decoder_port.is_empty() && !is_connected;
+
+            if ui
+                .add_enabled(can_connect, egui::Button::new(tr!("connect")))
+                .clicked()
+            {
+                self.connect_band_decoder();
+            }
+
+            if ui
+                .add_enabled(is_connected, egui::Button::new(tr!("disconnect")))
+                .clicked()
+            {
+                self.disconnect_band_decoder();
+            }
+
+            if is_connected {
+                ui.label(RichText::new(tr!("connected")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.band_decoder_port != prev_port {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+    }
+
+    /// Open the band decoder port and hand its data channel to the mux actor
+    pub(super) fn connect_band_decoder(&mut self) {
+        let port = self.settings.band_decoder_port.clone();
+        if port.is_empty() {
+            self.set_status(tr!("band-decoder-no-port"));
+            return;
+        }
+
+        let (data_tx, data_rx) = tokio_mpsc::channel::<Vec<u8>>(32);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.band_decoder_shutdown_tx = Some(shutdown_tx);
+
+        let mux = self.mux.clone();
+
+        self.rt_handle.spawn(async move {
+            let stream = match tokio_serial::new(&port, OTRSP_DEFAULT_BAUD)
+                .timeout(Duration::from_millis(100))
+                .open_native_async()
+            {
+                Ok(s) => s,
+                Err(e) => {
+                    tracing::error!(port = %port, error = %e, "Failed to open band decoder port");
+                    let _ = mux
+                        .emit(MuxEvent::Error {
+                            source: format!("Band Decoder {}", port),
+                            message: format!(
+                                "Failed to open port: {}",
+                                describe_open_error(&port, &e)
+                            ),
+                        })
+                        .await;
+                    return;
+                }
+            };
+
+            let _ = mux
+                .send(MuxActorCommand::ConnectBandDecoder { tx: data_tx })
+                .await;
+
+            let conn = BandDecoderConnection::new(stream, &mux);
+            conn.run(shutdown_rx, data_rx).await;
+
+            // Covers both user disconnect and I/O failure
+
+            let _ = mux.send(MuxActorCommand::DisconnectBandDecoder).await;
+        });
+
+        self.set_status(tr!(
+            "band-decoder-connecting",
+            port = self.settings.band_decoder_port.clone()
+        ));
+    }
+
+    /// Disconnect the band decoder
+    ///
+    /// The connection task detaches itself from the mux actor on its way out.
+    pub(super) fn disconnect_band_decoder(&mut self) {
+        if let Some(tx) = self.band_decoder_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
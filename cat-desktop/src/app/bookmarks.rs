@@ -0,0 +1,197 @@
+//! Hands-free bookmarks (settings section, pin watcher and bookmark log)
+
+use std::io::Write as _;
+use std::time::SystemTime;
+
+use cat_mux::{run_pin_trigger, MuxActorCommand, SerialPin};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::oneshot;
+
+use crate::i18n::{civil_from_days, tr};
+use crate::settings::Settings;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the bookmark pin watcher is running
+    pub(super) fn bookmark_pin_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits (port error)
+        self.bookmark_pin_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the bookmark trigger controls (settings panel)
+    pub(super) fn draw_bookmark_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("bookmarks-heading"));
+        ui.label(
+            RichText::new(tr!("bookmarks-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.bookmark_pin_running();
+        let prev = self.settings.bookmarks.clone();
+        let ports: Vec<String> = self
+            .available_ports
+            .iter()
+            .map(|p| p.port.clone())
+            .collect();
+
+        let bookmarks = &mut self.settings.bookmarks;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut bookmarks.ptt_double_tap, tr!("bookmarks-double-tap"));
+            ui.add_enabled(
+                bookmarks.ptt_double_tap,
+                egui::DragValue::new(&mut bookmarks.double_tap_ms)
+                    .range(100..=2000)
+                    .suffix(" ms"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut bookmarks.pin_enabled, tr!("pin-switch-on"));
+            ui.add_enabled_ui(!running, |ui| {
+                egui::ComboBox::from_id_salt("bookmark_pin")
+                    .selected_text(bookmarks.pin.name())
+                    .show_ui(ui, |ui| {
+                        for pin in SerialPin::ALL {
+                            ui.selectable_value(&mut bookmarks.pin, pin, pin.name());
+                        }
+                    });
+                ui.label(tr!("pin-of"));
+                egui::ComboBox::from_id_salt("bookmark_pin_port")
+                    .selected_text(if bookmarks.pin_port.is_empty() {
+                        tr!("select-port")
+                    } else {
+                        bookmarks.pin_port.clone()
+                    })
+                    .show_ui(ui, |ui| {
+                        for port in &ports {
+                            ui.selectable_value(&mut bookmarks.pin_port, port.clone(), port);
+                        }
+                    });
+            });
+            if running {
+                ui.label(RichText::new(tr!("pin-watching")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.bookmarks != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            let bookmarks = &self.settings.bookmarks;
+            if bookmarks.double_tap_window_ms() != prev.double_tap_window_ms() {
+                self.send_mux_command(
+                    MuxActorCommand::SetBookmarkDoubleTap {
+                        ms: bookmarks.double_tap_window_ms(),
+                    },
+                    "SetBookmarkDoubleTap",
+                );
+            }
+            if bookmarks.pin_enabled != prev.pin_enabled {
+                if bookmarks.pin_enabled {
+                    self.start_bookmark_pin();
+                } else {
+                    self.stop_bookmark_pin();
+                }
+            }
+        }
+    }
+
+    /// Start watching the configured pin for bookmark presses
+    pub(super) fn start_bookmark_pin(&mut self) {
+        let port = self.settings.bookmarks.pin_port.trim().to_string();
+        let pin = self.settings.bookmarks.pin;
+        if port.is_empty() {
+            self.report_err("Bookmarks", tr!("bookmarks-select-port-first"));
+            return;
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.bookmark_pin_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = tokio::select! {
+                _ = shutdown_rx => return,
+                result = run_pin_trigger(&port, pin, mux) => result,
+            };
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::BookmarkTriggerError {
+                    message: format!("{}: {}", port, e),
+                });
+            }
+        });
+    }
+
+    /// Stop watching the bookmark pin
+    fn stop_bookmark_pin(&mut self) {
+        if let Some(tx) = self.bookmark_pin_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Note a bookmark from the mux in the status bar and the bookmark log
+    pub(super) fn record_bookmark(&mut self, source: &str, note: &str, timestamp: SystemTime) {
+        let line = format!("{} [{}] {}", format_utc(timestamp), source, note);
+        if let Err(e) = append_bookmark_line(&line) {
+            self.report_warning(
+                "Bookmarks",
+                tr!("bookmarks-log-failed", error = e.to_string()),
+            );
+        }
+        self.set_status(tr!("bookmarks-status", note = note));
+    }
+}
+
+/// Append a line to the bookmark log next to the settings file
+fn append_bookmark_line(line: &str) -> std::io::Result<()> {
+    let Some(path) = Settings::bookmarks_path() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// `YYYY-MM-DD HH:MM:SS.mmmZ`
+pub(super) fn format_utc(timestamp: SystemTime) -> String {
+    let d = timestamp
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let secs = d.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let tod = secs % 86_400;
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        tod / 3600,
+        (tod % 3600) / 60,
+        tod % 60,
+        d.subsec_millis()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(
+            format_utc(SystemTime::UNIX_EPOCH),
+            "1970-01-01 00:00:00.000Z"
+        );
+        let t = SystemTime::UNIX_EPOCH + Duration::from_millis(1_709_251_199_250);
+        assert_eq!(format_utc(t), "2024-02-29 23:59:59.250Z");
+    }
+}
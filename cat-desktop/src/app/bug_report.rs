@@ -0,0 +1,241 @@
+//! "Report a problem" dialog
+
+use std::collections::BTreeMap;
+
+use egui::{Color32, RichText};
+use tracing::Level;
+
+use crate::bug_report::{
+    BugReport, ReportInput, ReportSections, DEFAULT_DIAGNOSTIC_ENTRIES, DEFAULT_TRAFFIC_ENTRIES,
+};
+use crate::i18n::tr;
+use crate::traffic_monitor::TrafficEntry;
+
+use super::CatapultApp;
+
+/// Largest file shown in full in the preview
+const PREVIEW_LIMIT: usize = 64 * 1024;
+
+/// State of the open dialog
+#[derive(Default)]
+pub(crate) struct BugReportDialog {
+    description: String,
+    sections: ReportSections,
+    /// Diagnostics are being captured at Debug while the dialog is open
+    capturing_debug: bool,
+    /// The report as it would be saved
+    report: Option<BugReport>,
+    /// Index of the file shown in the preview
+    previewing: usize,
+    /// The user agreed to what is in the report
+    consent: bool,
+}
+
+impl CatapultApp {
+    /// Open the dialog
+    pub(super) fn open_bug_report(&mut self) {
+        if self.bug_report.is_none() {
+            self.bug_report = Some(BugReportDialog::default());
+            self.refresh_bug_report();
+        }
+    }
+
+    /// Rebuild the preview from the current state
+    fn refresh_bug_report(&mut self) {
+        let Some(dialog) = &self.bug_report else {
+            return;
+        };
+        let traffic = self
+            .traffic_monitor
+            .format_recent(DEFAULT_TRAFFIC_ENTRIES, |e| {
+                !matches!(e, TrafficEntry::Diagnostic { .. })
+            });
+        let diagnostics = self
+            .traffic_monitor
+            .format_recent(DEFAULT_DIAGNOSTIC_ENTRIES, |e| {
+                matches!(e, TrafficEntry::Diagnostic { .. })
+            });
+        let input = ReportInput {
+            description: &dialog.description,
+            settings: &self.settings,
+            traffic,
+            diagnostics,
+            ports: &self.available_ports,
+        };
+        let result = BugReport::new(&input, dialog.sections);
+        match result {
+            Ok(report) => {
+                if let Some(dialog) = &mut self.bug_report {
+                    dialog.previewing = dialog.previewing.min(report.files.len() - 1);
+                    dialog.report = Some(report);
+                    // What the user agreed to has changed
+                    dialog.consent = false;
+                }
+            }
+            Err(e) => self.report_err("Report", e),
+        }
+    }
+
+    /// Capture every project crate at Debug, or go back to the configured levels
+    fn set_debug_capture(&mut self, on: bool) {
+        if on {
+            self.diagnostic_level_state.set_level(Some(Level::DEBUG));
+            self.diagnostic_level_state
+                .set_crate_levels(&BTreeMap::new());
+        } else {
+            self.diagnostic_level_state
+                .set_level(self.traffic_monitor.diagnostic_level());
+            self.diagnostic_level_state
+                .set_crate_levels(&self.settings.diagnostic_crate_levels);
+        }
+    }
+
+    fn close_bug_report(&mut self) {
+        if let Some(dialog) = self.bug_report.take() {
+            if dialog.capturing_debug {
+                self.set_debug_capture(false);
+            }
+        }
+    }
+
+    /// Draw the dialog while it is open
+    pub(super) fn draw_bug_report_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &mut self.bug_report else {
+            return;
+        };
+
+        let prev_sections = dialog.sections;
+        let prev_capture = dialog.capturing_debug;
+        let mut open = true;
+        let mut refresh = false;
+        let mut save = false;
+
+        egui::Window::new(tr!("bug-report-title"))
+            .open(&mut open)
+            .collapsible(false)
+            .default_width(560.0)
+            .show(ctx, |ui| {
+                ui.label(tr!("bug-report-description"));
+                if ui
+                    .add(
+                        egui::TextEdit::multiline(&mut dialog.description)
+                            .desired_rows(3)
+                            .desired_width(f32::INFINITY),
+                    )
+                    .lost_focus()
+                {
+                    refresh = true;
+                }
+
+                ui.horizontal(|ui| {
+                    ui.label(tr!("bug-report-include"));
+                    ui.checkbox(&mut dialog.sections.traffic, tr!("bug-report-traffic"));
+                    ui.checkbox(
+                        &mut dialog.sections.diagnostics,
+                        tr!("bug-report-diagnostics"),
+                    );
+                    ui.checkbox(&mut dialog.sections.settings, tr!("bug-report-settings"));
+                    ui.checkbox(&mut dialog.sections.ports, tr!("bug-report-ports"));
+                });
+                ui.checkbox(&mut dialog.capturing_debug, tr!("bug-report-debug"))
+                    .on_hover_text(tr!("bug-report-debug-hover"));
+                ui.label(
+                    RichText::new(tr!("bug-report-privacy"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.separator();
+                let Some(report) = &dialog.report else {
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    for (i, file) in report.files.iter().enumerate() {
+                        ui.selectable_value(&mut dialog.previewing, i, file.name)
+                            .on_hover_text(tr!("bug-report-bytes", bytes = file.contents.len()));
+                    }
+                    if ui.button(tr!("bug-report-refresh")).clicked() {
+                        refresh = true;
+                    }
+                });
+                if let Some(file) = report.files.get(dialog.previewing) {
+                    let mut text = file.contents.as_str();
+                    if text.len() > PREVIEW_LIMIT {
+                        let mut end = PREVIEW_LIMIT;
+                        while !text.is_char_boundary(end) {
+                            end -= 1;
+                        }
+                        text = &text[..end];
+                    }
+                    egui::ScrollArea::vertical()
+                        .max_height(240.0)
+                        .show(ui, |ui| {
+                            ui.add(
+                                egui::TextEdit::multiline(&mut text)
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY),
+                            );
+                        });
+                    if file.contents.len() > PREVIEW_LIMIT {
+                        ui.label(
+                            RichText::new(tr!(
+                                "bug-report-truncated",
+                                shown = PREVIEW_LIMIT / 1024,
+                                total = file.contents.len() / 1024
+                            ))
+                            .small()
+                            .color(Color32::GRAY),
+                        );
+                    }
+                }
+
+                ui.separator();
+                ui.checkbox(
+                    &mut dialog.consent,
+                    tr!(
+                        "bug-report-consent",
+                        files = report.files.len(),
+                        kib = report.size().div_ceil(1024)
+                    ),
+                );
+                ui.horizontal(|ui| {
+                    save = ui
+                        .add_enabled(dialog.consent, egui::Button::new(tr!("bug-report-save")))
+                        .on_hover_text(tr!("bug-report-save-hover"))
+                        .clicked();
+                });
+            });
+
+        let capture = dialog.capturing_debug;
+        if dialog.sections != prev_sections {
+            refresh = true;
+        }
+        if capture != prev_capture {
+            self.set_debug_capture(capture);
+        }
+        if !open {
+            self.close_bug_report();
+            return;
+        }
+        if refresh {
+            self.refresh_bug_report();
+        }
+        if save {
+            self.save_bug_report();
+        }
+    }
+
+    fn save_bug_report(&mut self) {
+        let Some(report) = self.bug_report.as_ref().and_then(|d| d.report.as_ref()) else {
+            return;
+        };
+        match report.save_with_dialog() {
+            Ok(Some(path)) => {
+                self.set_status(tr!("bug-report-saved", path = path.display().to_string()));
+                self.close_bug_report();
+            }
+            Ok(None) => {}
+            Err(e) => self.report_err("Report", e),
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Configuration bundle export/import UI
+
+use egui::{Color32, RichText, Ui};
+
+use crate::config_bundle::{ConfigBundle, ImportMode};
+use crate::i18n::tr;
+use crate::import::ImportedRadios;
+
+use super::CatapultApp;
+
+impl CatapultApp {
+    /// Draw the export/import controls (settings panel)
+    pub(super) fn draw_bundle_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("bundle-heading"));
+        ui.label(
+            RichText::new(tr!("bundle-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        ui.horizontal(|ui| {
+            if ui.button(tr!("bundle-export")).clicked() {
+                self.export_bundle();
+            }
+            ui.checkbox(&mut self.bundle_include_logs, tr!("bundle-include-logs"));
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button(tr!("bundle-import")).clicked() {
+                self.choose_bundle_import();
+            }
+            egui::ComboBox::from_id_salt("bundle_import_mode")
+                .selected_text(self.bundle_import_mode.name())
+                .show_ui(ui, |ui| {
+                    for mode in ImportMode::ALL {
+                        ui.selectable_value(&mut self.bundle_import_mode, mode, mode.name());
+                    }
+                });
+        });
+        if ui.button(tr!("bundle-import-radios")).clicked() {
+            self.choose_radio_import();
+        }
+
+        self.draw_pending_import(ui);
+    }
+
+    /// Write the current settings (and optionally the log) to a bundle file
+    fn export_bundle(&mut self) {
+        let logs = self
+            .bundle_include_logs
+            .then(|| self.traffic_monitor.format_filtered_log());
+        let result = ConfigBundle::new(&self.settings, logs).and_then(|b| b.save_with_dialog());
+        match result {
+            Ok(Some(path)) => {
+                self.set_status(tr!("bundle-exported", path = path.display().to_string()))
+            }
+            Ok(None) => {}
+            Err(e) => self.report_err("Export", e),
+        }
+    }
+
+    /// Pick a bundle file and work out what importing it would change
+    fn choose_bundle_import(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Catapult bundle", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        let preview = ConfigBundle::load(&path)
+            .and_then(|b| b.preview(&self.settings, self.bundle_import_mode));
+        match preview {
+            Ok(preview) => self.pending_import = Some((path, preview)),
+            Err(e) => self.report_err("Import", e),
+        }
+    }
+
+    /// Pick an OmniRig or Ham Radio Deluxe file and preview adding its radios
+    fn choose_radio_import(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("OmniRig / HRD", &["ini", "xml"])
+            .pick_file()
+        else {
+            return;
+        };
+        match ImportedRadios::load(&path) {
+            Ok((source, imported)) => {
+                for skipped in &imported.skipped {
+                    self.report_warning(
+                        source.name(),
+                        tr!("bundle-not-imported", radio = skipped.to_string()),
+                    );
+                }
+                let preview = imported.preview(&self.settings, self.bundle_import_mode);
+                self.pending_import = Some((path, preview));
+            }
+            Err(e) => self.report_err("Import", e),
+        }
+    }
+
+    /// Summary of a pending import with Apply/Cancel
+    fn draw_pending_import(&mut self, ui: &mut Ui) {
+        let Some((path, preview)) = &self.pending_import else {
+            return;
+        };
+
+        let mut apply = false;
+        let mut cancel = false;
+        ui.group(|ui| {
+            ui.label(tr!(
+                "bundle-import-file",
+                file = path.file_name().unwrap_or_default().to_string_lossy()
+            ));
+            ui.label(
+                RichText::new(tr!(
+                    "bundle-import-counts",
+                    radios = preview.settings.configured_radios.len(),
+                    virtual_radios = preview.settings.virtual_radios.len(),
+                    virtual_ports = preview.settings.virtual_ports.len()
+                ))
+                .small(),
+            );
+            for added in &preview.added {
+                ui.label(
+                    RichText::new(format!("+ {}", added))
+                        .small()
+                        .color(Color32::GREEN),
+                );
+            }
+            for conflict in &preview.conflicts {
+                ui.label(
+                    RichText::new(format!("! {}", conflict))
+                        .small()
+                        .color(Color32::from_rgb(255, 180, 0)),
+                );
+            }
+            if preview.redacted > 0 {
+                ui.label(
+                    RichText::new(tr!("bundle-redacted", count = preview.redacted))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+            }
+            ui.horizontal(|ui| {
+                apply = ui.button(tr!("apply")).clicked();
+                cancel = ui.button(tr!("cancel")).clicked();
+            });
+        });
+
+        if cancel {
+            self.pending_import = None;
+        } else if apply {
+            self.apply_pending_import();
+        }
+    }
+
+    /// Save the previewed settings and push what can change live
+    fn apply_pending_import(&mut self) {
+        let Some((_, preview)) = self.pending_import.take() else {
+            return;
+        };
+        self.settings = preview.settings;
+        if let Err(e) = self.settings.save() {
+            self.handle_save_error(e);
+            return;
+        }
+
+        self.push_mux_settings();
+        self.diagnostic_level_state
+            .set_crate_levels(&self.settings.diagnostic_crate_levels);
+        self.apply_memory_budget();
+        self.set_status(tr!("bundle-imported"));
+    }
+}
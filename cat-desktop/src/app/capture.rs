@@ -0,0 +1,148 @@
+//! Capture folder watching UI (offline decoding into the traffic monitor)
+
+use cat_protocol::Protocol;
+use egui::{Color32, RichText, Ui};
+use tokio::sync::oneshot;
+
+use crate::capture_watch::{CaptureWatcher, POLL_INTERVAL};
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the capture folder watcher is running
+    pub(super) fn capture_watch_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits (folder error)
+        self.capture_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the capture folder controls (settings panel)
+    pub(super) fn draw_capture_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("capture-heading"));
+        ui.label(
+            RichText::new(tr!("capture-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.capture_watch_running();
+        let prev = self.settings.capture_watch.clone();
+
+        ui.add_enabled_ui(!running, |ui| {
+            let capture = &mut self.settings.capture_watch;
+            ui.horizontal(|ui| {
+                ui.label(tr!("folder"));
+                ui.add(egui::TextEdit::singleline(&mut capture.folder).desired_width(220.0));
+                if ui.button(tr!("browse")).clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        capture.folder = folder.display().to_string();
+                    }
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr!("protocol"));
+                egui::ComboBox::from_id_salt("capture_protocol")
+                    .selected_text(capture.protocol.name())
+                    .show_ui(ui, |ui| {
+                        for proto in [
+                            Protocol::Kenwood,
+                            Protocol::IcomCIV,
+                            Protocol::Yaesu,
+                            Protocol::YaesuAscii,
+                            Protocol::Elecraft,
+                            Protocol::FlexRadio,
+                        ] {
+                            ui.selectable_value(&mut capture.protocol, proto, proto.name());
+                        }
+                    });
+            });
+        });
+
+        if self.settings.capture_watch != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            let can_start = !running && !self.settings.capture_watch.folder.trim().is_empty();
+            if ui
+                .add_enabled(can_start, egui::Button::new(tr!("capture-watch")))
+                .clicked()
+            {
+                self.start_capture_watch();
+            }
+            if ui
+                .add_enabled(running, egui::Button::new(tr!("stop")))
+                .clicked()
+            {
+                self.stop_capture_watch();
+            }
+            if running {
+                ui.label(RichText::new(tr!("capture-watching")).color(Color32::GREEN));
+            }
+        });
+    }
+
+    /// Start polling the capture folder
+    fn start_capture_watch(&mut self) {
+        let capture = self.settings.capture_watch.clone();
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        self.capture_shutdown_tx = Some(shutdown_tx);
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let mut watcher = Some(CaptureWatcher::new(capture.folder.trim(), capture.protocol));
+            let mut interval = tokio::time::interval(POLL_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = &mut shutdown_rx => break,
+                    _ = interval.tick() => {}
+                }
+
+                // Reading files blocks; keep it off the runtime's worker threads
+                let Some(mut w) = watcher.take() else { break };
+                let Ok((w, result)) = tokio::task::spawn_blocking(move || {
+                    let result = w.poll();
+                    (w, result)
+                })
+                .await
+                else {
+                    break;
+                };
+                watcher = Some(w);
+
+                match result {
+                    Ok(batches) => {
+                        for batch in batches {
+                            let _ = bg_tx.send(BackgroundMessage::CaptureFrames {
+                                file: batch.file,
+                                frames: batch.frames,
+                                protocol: capture.protocol,
+                            });
+                        }
+                    }
+                    Err(message) => {
+                        let _ = bg_tx.send(BackgroundMessage::CaptureError { message });
+                        break;
+                    }
+                }
+            }
+        });
+
+        self.set_status(tr!(
+            "capture-watching-folder",
+            folder = self.settings.capture_watch.folder.trim().to_string(),
+            protocol = self.settings.capture_watch.protocol.name()
+        ));
+    }
+
+    /// Stop polling the capture folder
+    fn stop_capture_watch(&mut self) {
+        if let Some(tx) = self.capture_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! Sweeping a port's CI-V bus for Icom radios (from the radio dialog)
+
+use cat_detect::{
+    all_addresses, sweep_civ_port, CivDevice, CivSweepConfig, CivSweepEvent, ScanCancel,
+};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::mpsc as tokio_mpsc;
+
+use crate::i18n::tr;
+
+use super::radio_dialog::RadioDialog;
+use super::{BackgroundMessage, CatapultApp};
+
+/// A sweep in progress, or its results until the dialog closes
+pub(crate) struct CivSweep {
+    /// Port being swept
+    port: String,
+    /// Cancels the sweep (Some while it runs)
+    cancel: Option<ScanCancel>,
+    /// Baud rate and address being asked
+    asking: Option<(u32, u8)>,
+    /// Devices found so far
+    found: Vec<CivDevice>,
+}
+
+/// What the operator asked for in the sweep section
+pub(super) enum CivSweepAction {
+    Start,
+    Cancel,
+    /// Fill the dialog in from the found device at this index
+    Use(usize),
+}
+
+impl CivSweep {
+    pub(super) fn running(&self) -> bool {
+        self.cancel.is_some()
+    }
+}
+
+impl CatapultApp {
+    /// Whether a CI-V sweep is running
+    pub(super) fn civ_sweep_running(&self) -> bool {
+        self.civ_sweep.as_ref().is_some_and(CivSweep::running)
+    }
+
+    /// Sweep the dialog's port for CI-V devices
+    pub(super) fn start_civ_sweep(&mut self) {
+        let Some(dialog) = &self.radio_dialog else {
+            return;
+        };
+        if dialog.port.is_empty() || dialog.is_network() || dialog.is_virtual() {
+            return;
+        }
+        if self.civ_sweep_running() || self.probing {
+            return;
+        }
+        let port = dialog.port.clone();
+        let mut config = CivSweepConfig {
+            settle: Some(dialog.settle_delay(&self.available_ports)),
+            ..CivSweepConfig::default()
+        };
+        if dialog.civ_sweep_all {
+            config.addresses = all_addresses();
+        }
+        let cancel = ScanCancel::new();
+        self.civ_sweep = Some(CivSweep {
+            port: port.clone(),
+            cancel: Some(cancel.clone()),
+            asking: None,
+            found: Vec::new(),
+        });
+        self.set_status(tr!("civ-sweep-sweeping", port = port.clone()));
+
+        let tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            let (event_tx, mut event_rx) = tokio_mpsc::unbounded_channel();
+            let forward = {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    while let Some(event) = event_rx.recv().await {
+                        let _ = tx.send(BackgroundMessage::CivSweep(event));
+                    }
+                })
+            };
+            let result = sweep_civ_port(&port, &config, event_tx, cancel.clone()).await;
+            // Everything the sweep sent arrives before the finish
+            let _ = forward.await;
+            let _ = tx.send(BackgroundMessage::CivSweepFinished {
+                result: result.map(|found| found.len()).map_err(|e| e.to_string()),
+                cancelled: cancel.is_cancelled(),
+            });
+        });
+    }
+
+    /// Stop the running sweep, keeping what it found
+    pub(super) fn cancel_civ_sweep(&mut self) {
+        if let Some(cancel) = self.civ_sweep.as_ref().and_then(|s| s.cancel.as_ref()) {
+            cancel.cancel();
+        }
+    }
+
+    /// Apply a progress event from the running sweep
+    pub(super) fn handle_civ_sweep_event(&mut self, event: CivSweepEvent) {
+        let Some(sweep) = self.civ_sweep.as_mut() else {
+            return;
+        };
+        match event {
+            CivSweepEvent::Baud { .. } => {}
+            CivSweepEvent::Asking { baud_rate, address } => {
+                sweep.asking = Some((baud_rate, address));
+            }
+            CivSweepEvent::Found(device) => {
+                sweep.found.push(device);
+                sweep.found.sort_by_key(|d| d.address);
+            }
+        }
+    }
+
+    /// The sweep task has returned
+    pub(super) fn finish_civ_sweep(&mut self, result: Result<usize, String>, cancelled: bool) {
+        let Some(sweep) = self.civ_sweep.as_mut() else {
+            return;
+        };
+        sweep.cancel = None;
+        sweep.asking = None;
+        let port = sweep.port.clone();
+        match (result, cancelled) {
+            (Err(e), _) => self.report_err("CI-V sweep", format!("{}: {}", port, e)),
+            (Ok(found), true) => self.set_status(tr!("civ-sweep-cancelled", count = found)),
+            (Ok(0), false) => self.set_status(tr!("civ-sweep-none", port = port)),
+            (Ok(found), false) => {
+                self.set_status(tr!("civ-sweep-found", count = found, port = port))
+            }
+        }
+    }
+
+    /// Cancel and forget the sweep (the dialog closed)
+    pub(super) fn drop_civ_sweep(&mut self) {
+        self.cancel_civ_sweep();
+        self.civ_sweep = None;
+    }
+}
+
+/// Fill the dialog in from a found device
+pub(super) fn use_civ_device(dialog: &mut RadioDialog, device: &CivDevice) {
+    dialog.civ_address = format!("{:02X}", device.address);
+    dialog.baud_rate = device.baud_rate;
+    if let Some(model) = &device.model {
+        dialog.model_name = format!("{} {}", model.manufacturer, model.model);
+    }
+}
+
+/// Scan button, progress and the devices found (radio dialog, CI-V only)
+pub(super) fn draw_civ_sweep_section(
+    ui: &mut Ui,
+    dialog: &mut RadioDialog,
+    sweep: Option<&CivSweep>,
+    busy: bool,
+    action: &mut Option<CivSweepAction>,
+) {
+    let sweep = sweep.filter(|s| s.port == dialog.port);
+    let running = sweep.is_some_and(CivSweep::running);
+    ui.horizontal(|ui| {
+        if running {
+            ui.spinner();
+            if let Some((baud_rate, address)) = sweep.and_then(|s| s.asking) {
+                ui.label(
+                    RichText::new(tr!(
+                        "civ-sweep-asking",
+                        address = format!("{:02X}h", address),
+                        baud = baud_rate
+                    ))
+                    .small()
+                    .color(Color32::GRAY),
+                );
+            }
+            if ui.small_button(tr!("cancel")).clicked() {
+                *action = Some(CivSweepAction::Cancel);
+            }
+        } else {
+            let can_sweep = !dialog.port.is_empty() && !dialog.is_network() && !busy;
+            if ui
+                .add_enabled(can_sweep, egui::Button::new(tr!("civ-sweep-start")))
+                .on_hover_text(tr!("civ-sweep-start-hover"))
+                .clicked()
+            {
+                *action = Some(CivSweepAction::Start);
+            }
+            ui.checkbox(&mut dialog.civ_sweep_all, tr!("civ-sweep-all"))
+                .on_hover_text(tr!("civ-sweep-all-hover"));
+        }
+    });
+
+    let Some(sweep) = sweep else {
+        return;
+    };
+    if !running && sweep.found.is_empty() {
+        ui.label(
+            RichText::new(tr!("civ-sweep-no-devices"))
+                .small()
+                .color(Color32::GRAY),
+        );
+    }
+    for (idx, device) in sweep.found.iter().enumerate() {
+        ui.horizontal(|ui| {
+            let model = device
+                .model
+                .as_ref()
+                .map(|m| format!("{} {}", m.manufacturer, m.model))
+                .unwrap_or_else(|| tr!("civ-sweep-unknown-radio"));
+            ui.label(format!(
+                "{} at {:02X}h @ {}",
+                model, device.address, device.baud_rate
+            ));
+            if device.address_changed() {
+                ui.label(
+                    RichText::new(tr!(
+                        "civ-sweep-default-address",
+                        address = format!("{:02X}h", device.id.unwrap_or_default())
+                    ))
+                    .small()
+                    .color(Color32::GRAY),
+                );
+            }
+            if ui.small_button(tr!("civ-sweep-use")).clicked() {
+                *action = Some(CivSweepAction::Use(idx));
+            }
+        });
+    }
+}
+
+impl CatapultApp {
+    /// Carry out what the sweep section asked for
+    pub(super) fn apply_civ_sweep_action(&mut self, action: CivSweepAction) {
+        match action {
+            CivSweepAction::Start => self.start_civ_sweep(),
+            CivSweepAction::Cancel => self.cancel_civ_sweep(),
+            CivSweepAction::Use(idx) => {
+                let device = self.civ_sweep.as_ref().and_then(|s| s.found.get(idx));
+                if let (Some(dialog), Some(device)) = (self.radio_dialog.as_mut(), device) {
+                    use_civ_device(dialog, device);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Frequency log on PTT release (settings section and CSV writer)
+
+use std::io::Write as _;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+use cat_mux::MuxActorCommand;
+use cat_protocol::display::format_mode;
+use cat_protocol::OperatingMode;
+use egui::{Color32, RichText, Ui};
+
+use super::bookmarks::format_utc;
+use super::CatapultApp;
+use crate::i18n::tr;
+use crate::settings::Settings;
+
+/// First line of a new log
+const CSV_HEADER: &str = "utc,frequency_hz,mode,radio";
+
+impl CatapultApp {
+    /// Draw the frequency log controls (settings panel)
+    pub(super) fn draw_contact_log_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("contact-log-heading"));
+        ui.label(
+            RichText::new(tr!("contact-log-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let prev = self.settings.contact_log.clone();
+        ui.horizontal(|ui| {
+            ui.label(tr!("contact-log-file"));
+            let hint = Settings::contact_log_path()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default();
+            ui.add(
+                egui::TextEdit::singleline(&mut self.settings.contact_log.path)
+                    .hint_text(hint)
+                    .desired_width(220.0),
+            );
+            if ui.button(tr!("browse")).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter("CSV", &["csv"])
+                    .set_file_name("contacts.csv")
+                    .save_file()
+                {
+                    self.settings.contact_log.path = path.display().to_string();
+                }
+            }
+        });
+        ui.checkbox(
+            &mut self.settings.contact_log.enabled,
+            tr!("contact-log-enabled"),
+        );
+
+        if self.settings.contact_log != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.contact_log.enabled != prev.enabled {
+                self.send_mux_command(
+                    MuxActorCommand::SetContactSnapshots {
+                        enabled: self.settings.contact_log.enabled,
+                    },
+                    "SetContactSnapshots",
+                );
+            }
+        }
+    }
+
+    /// Append a snapshot from the mux to the frequency log
+    pub(super) fn record_contact_snapshot(
+        &mut self,
+        radio: &str,
+        frequency_hz: Option<u64>,
+        mode: Option<OperatingMode>,
+        timestamp: SystemTime,
+    ) {
+        if !self.settings.contact_log.enabled {
+            return;
+        }
+        let Some(path) = self.contact_log_path() else {
+            return;
+        };
+        let row = csv_row(radio, frequency_hz, mode, timestamp);
+        if let Err(e) = append_row(path, &row) {
+            self.report_warning(
+                "Frequency log",
+                tr!("contact-log-write-failed", error = e.to_string()),
+            );
+        }
+    }
+
+    /// File snapshots are appended to
+    fn contact_log_path(&self) -> Option<PathBuf> {
+        match self.settings.contact_log.path.trim() {
+            "" => Settings::contact_log_path(),
+            path => Some(PathBuf::from(path)),
+        }
+    }
+}
+
+/// One line of the log (no line ending)
+fn csv_row(
+    radio: &str,
+    frequency_hz: Option<u64>,
+    mode: Option<OperatingMode>,
+    timestamp: SystemTime,
+) -> String {
+    format!(
+        "{},{},{},{}",
+        format_utc(timestamp),
+        frequency_hz.map(|hz| hz.to_string()).unwrap_or_default(),
+        mode.map(format_mode).unwrap_or_default(),
+        csv_field(radio)
+    )
+}
+
+/// Quote a field if it holds a comma, quote or line break
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Append a row, starting the file with the header if it is new or empty
+fn append_row(path: PathBuf, row: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?;
+    if file.metadata()?.len() == 0 {
+        writeln!(file, "{}", CSV_HEADER)?;
+    }
+    writeln!(file, "{}", row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_csv_row() {
+        let t = SystemTime::UNIX_EPOCH + Duration::from_millis(1_709_251_199_250);
+        assert_eq!(
+            csv_row("IC-7300", Some(14_074_000), Some(OperatingMode::DataU), t),
+            "2024-02-29 23:59:59.250Z,14074000,DATA-U,IC-7300"
+        );
+        assert_eq!(
+            csv_row("Run, \"left\"", None, None, t),
+            "2024-02-29 23:59:59.250Z,,,\"Run, \"\"left\"\"\""
+        );
+    }
+}
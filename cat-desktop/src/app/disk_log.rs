@@ -0,0 +1,311 @@
+//! Always-on traffic logging to disk and the log viewer (settings section)
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError};
+use std::time::{Duration, Instant, SystemTime};
+
+use cat_mux::traffic_log::LOG_EXTENSION;
+use cat_mux::{MuxEvent, TrafficLogReader, TrafficLogWriter, TrafficRecord};
+use egui::{Color32, RichText, Ui};
+
+use super::{BackgroundMessage, CatapultApp};
+use crate::i18n::{civil_from_days, format_date_time, tr};
+use crate::settings::Settings;
+
+/// Records buffered before frames are dropped
+const DISK_LOG_CHANNEL_CAPACITY: usize = 4096;
+
+/// Longest a record waits in memory before it is written out, however busy
+/// the traffic (a full block is written sooner)
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A log opened in the viewer
+pub(crate) struct LogViewer {
+    path: PathBuf,
+    /// Time span covered by the log
+    range: (SystemTime, SystemTime),
+    records: u64,
+    /// Where to start loading, in seconds from the start of the log
+    offset_secs: f64,
+    /// A load is running
+    pub(super) loading: bool,
+}
+
+impl CatapultApp {
+    /// Whether traffic is being logged to disk
+    pub(super) fn disk_log_running(&self) -> bool {
+        self.disk_log_tx.is_some()
+    }
+
+    /// Queue a traffic event for the disk log, if it is recording
+    ///
+    /// Frames are dropped rather than stalling the UI when the disk falls behind.
+    pub(super) fn log_traffic(&self, event: &MuxEvent) {
+        let Some(tx) = &self.disk_log_tx else {
+            return;
+        };
+        let radio_name = |handle| {
+            self.radio_panels
+                .iter()
+                .find(|p| p.handle == Some(handle))
+                .map(|p| p.name.clone())
+                .unwrap_or_else(|| format!("Radio {}", handle.0))
+        };
+        if let Some(record) = TrafficRecord::from_event(event, radio_name) {
+            let _ = tx.try_send(record);
+        }
+    }
+
+    /// Draw the disk log controls (settings panel)
+    pub(super) fn draw_disk_log_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("disk-log-heading"));
+        ui.label(
+            RichText::new(tr!("disk-log-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.disk_log_running();
+        let prev = self.settings.disk_log.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("disk-log-folder"));
+            ui.add_enabled_ui(!running, |ui| {
+                let hint = Settings::traffic_log_dir()
+                    .map(|d| d.display().to_string())
+                    .unwrap_or_default();
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.settings.disk_log.folder)
+                        .hint_text(hint)
+                        .desired_width(220.0),
+                );
+                if ui.button(tr!("browse")).clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        self.settings.disk_log.folder = folder.display().to_string();
+                    }
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.settings.disk_log.enabled, tr!("disk-log-record"));
+            if running {
+                ui.label(RichText::new(tr!("disk-log-recording")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.disk_log != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.disk_log.enabled != prev.enabled {
+                if self.settings.disk_log.enabled {
+                    self.start_disk_log();
+                } else {
+                    self.stop_disk_log();
+                }
+            }
+        }
+
+        self.draw_log_viewer(ui);
+    }
+
+    /// Open a log and pick where in it to load from
+    fn draw_log_viewer(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(tr!("disk-log-open")).clicked() {
+                let mut dialog = rfd::FileDialog::new().add_filter("Traffic log", &[LOG_EXTENSION]);
+                if let Some(dir) = self.disk_log_folder() {
+                    dialog = dialog.set_directory(dir);
+                }
+                if let Some(path) = dialog.pick_file() {
+                    self.open_log(path);
+                }
+            }
+            if let Some(viewer) = &self.log_viewer {
+                let name = viewer
+                    .path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                ui.label(tr!("disk-log-file", name = name, frames = viewer.records));
+            }
+        });
+
+        let Some(viewer) = &mut self.log_viewer else {
+            return;
+        };
+        let (first, last) = viewer.range;
+        let span = last.duration_since(first).unwrap_or_default().as_secs_f64();
+        let start = first + Duration::from_secs_f64(viewer.offset_secs);
+        ui.horizontal(|ui| {
+            ui.label(tr!("disk-log-from"));
+            ui.add(
+                egui::Slider::new(&mut viewer.offset_secs, 0.0..=span)
+                    .show_value(false)
+                    .step_by(1.0),
+            );
+            ui.label(format_date_time(start));
+        });
+
+        let mut load = false;
+        ui.horizontal(|ui| {
+            load = ui
+                .add_enabled(!viewer.loading, egui::Button::new(tr!("disk-log-load")))
+                .clicked();
+            if viewer.loading {
+                ui.spinner();
+            }
+        });
+        if load {
+            self.load_log(start);
+        }
+    }
+
+    /// Read a log's index for the viewer
+    fn open_log(&mut self, path: PathBuf) {
+        let reader = match TrafficLogReader::open(&path) {
+            Ok(reader) => reader,
+            Err(e) => {
+                self.report_err("Disk log", format!("{}: {}", path.display(), e));
+                return;
+            }
+        };
+        let Some(range) = reader.time_range() else {
+            self.report_err(
+                "Disk log",
+                tr!("disk-log-empty", path = path.display().to_string()),
+            );
+            return;
+        };
+        self.log_viewer = Some(LogViewer {
+            path,
+            range,
+            records: reader.record_count(),
+            offset_secs: 0.0,
+            loading: false,
+        });
+    }
+
+    /// Load frames from `start` on into the traffic monitor
+    fn load_log(&mut self, start: SystemTime) {
+        let Some(viewer) = &mut self.log_viewer else {
+            return;
+        };
+        viewer.loading = true;
+        let path = viewer.path.clone();
+        let limit = self.settings.traffic_history_limit();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn_blocking(move || {
+            let file = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let message = match TrafficLogReader::open(&path)
+                .and_then(|mut reader| reader.read_from(start, limit))
+            {
+                Ok(records) => BackgroundMessage::LogRecords { file, records },
+                Err(e) => BackgroundMessage::LogLoadError {
+                    message: format!("{}: {}", path.display(), e),
+                },
+            };
+            let _ = bg_tx.send(message);
+        });
+    }
+
+    /// Folder logs are written to
+    fn disk_log_folder(&self) -> Option<PathBuf> {
+        match self.settings.disk_log.folder.trim() {
+            "" => Settings::traffic_log_dir(),
+            folder => Some(PathBuf::from(folder)),
+        }
+    }
+
+    /// Start recording traffic to the configured folder
+    pub(super) fn start_disk_log(&mut self) {
+        let Some(folder) = self.disk_log_folder() else {
+            self.report_err("Disk log", tr!("disk-log-no-folder"));
+            return;
+        };
+        let (tx, rx) = sync_channel(DISK_LOG_CHANNEL_CAPACITY);
+        self.disk_log_tx = Some(tx);
+        let bg_tx = self.bg_tx.clone();
+
+        let thread_folder = folder.clone();
+        let spawned = std::thread::Builder::new()
+            .name("disk-log".to_string())
+            .spawn(move || {
+                if let Err(e) = write_disk_log(&thread_folder, rx) {
+                    let _ = bg_tx.send(BackgroundMessage::DiskLogError {
+                        message: format!("{}: {}", thread_folder.display(), e),
+                    });
+                }
+            });
+        if let Err(e) = spawned {
+            self.disk_log_tx = None;
+            self.report_err("Disk log", e.to_string());
+            return;
+        }
+
+        self.set_status(tr!(
+            "disk-log-logging-to",
+            folder = folder.display().to_string()
+        ));
+    }
+
+    /// Stop recording; buffered frames are still written
+    pub(super) fn stop_disk_log(&mut self) {
+        // Closing the channel lets the writer flush and exit
+        self.disk_log_tx = None;
+    }
+}
+
+/// Daily log file name (UTC date)
+fn log_file_name(timestamp: SystemTime) -> String {
+    let days = timestamp
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86_400;
+    let (year, month, day) = civil_from_days(days as i64);
+    format!(
+        "traffic-{:04}-{:02}-{:02}.{}",
+        year, month, day, LOG_EXTENSION
+    )
+}
+
+/// Write records to the day's log until the channel closes
+///
+/// The writer writes a block once enough records build up; anything less is
+/// flushed [`FLUSH_INTERVAL`] after the oldest of it arrived, so a crash
+/// under steady traffic loses at most that much.
+fn write_disk_log(folder: &Path, rx: Receiver<TrafficRecord>) -> std::io::Result<()> {
+    std::fs::create_dir_all(folder)?;
+    let mut writer: Option<TrafficLogWriter> = None;
+    let mut flush_due: Option<Instant> = None;
+    loop {
+        let wait = flush_due.map_or(FLUSH_INTERVAL, |due| {
+            due.saturating_duration_since(Instant::now())
+        });
+        match rx.recv_timeout(wait) {
+            Ok(record) => {
+                let path = folder.join(log_file_name(record.timestamp));
+                let writer = match &mut writer {
+                    Some(w) if w.path() == path => w,
+                    // Replacing the writer flushes the previous day's log
+                    _ => writer.insert(TrafficLogWriter::open(path)?),
+                };
+                writer.append(&record)?;
+                flush_due.get_or_insert_with(|| Instant::now() + FLUSH_INTERVAL);
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+        }
+        if flush_due.is_some_and(|due| Instant::now() >= due) {
+            if let Some(w) = &mut writer {
+                w.flush()?;
+            }
+            flush_due = None;
+        }
+    }
+}
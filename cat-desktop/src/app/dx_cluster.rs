@@ -0,0 +1,102 @@
+//! DX cluster spot feed (settings section and client task)
+
+use cat_mux::run_dx_cluster_client;
+use egui::{Color32, RichText, Ui};
+use tokio::sync::oneshot;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the DX cluster client is connected (or connecting)
+    pub(super) fn dx_cluster_running(&self) -> bool {
+        // The task drops its shutdown receiver when the connection ends
+        self.dx_cluster_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the DX cluster controls (settings panel)
+    pub(super) fn draw_dx_cluster_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("dx-cluster-heading"));
+        ui.label(
+            RichText::new(tr!("dx-cluster-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.dx_cluster_running();
+        let prev = self.settings.dx_cluster.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("address"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.dx_cluster.address)
+                    .hint_text(tr!("dx-cluster-address-hint"))
+                    .desired_width(160.0),
+            );
+            ui.label(tr!("dx-cluster-callsign"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.dx_cluster.callsign)
+                    .desired_width(80.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.settings.dx_cluster.enabled, tr!("connect"));
+            if running {
+                ui.label(RichText::new(tr!("connected")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.dx_cluster != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.dx_cluster.enabled != prev.enabled {
+                if self.settings.dx_cluster.enabled {
+                    self.start_dx_cluster();
+                } else {
+                    self.stop_dx_cluster();
+                }
+            }
+        }
+    }
+
+    /// Connect to the configured DX cluster
+    pub(super) fn start_dx_cluster(&mut self) {
+        let address = self.settings.dx_cluster.address.trim().to_string();
+        let callsign = self.settings.dx_cluster.callsign.trim().to_string();
+        if address.is_empty() || callsign.is_empty() {
+            self.report_err("DX Cluster", tr!("dx-cluster-needs-address"));
+            return;
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.dx_cluster_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = tokio::select! {
+                _ = shutdown_rx => return,
+                result = run_dx_cluster_client(&address, &callsign, mux) => result,
+            };
+            let message = match result {
+                Ok(()) => format!("{} closed the connection", address),
+                Err(e) => format!("{}: {}", address, e),
+            };
+            let _ = bg_tx.send(BackgroundMessage::DxClusterError { message });
+        });
+
+        self.set_status(tr!("dx-cluster-connecting"));
+    }
+
+    /// Disconnect from the DX cluster
+    fn stop_dx_cluster(&mut self) {
+        if let Some(tx) = self.dx_cluster_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
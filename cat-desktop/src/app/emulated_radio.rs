@@ -0,0 +1,157 @@
+//! Emulated radio for single-radio software (settings section)
+
+use std::net::SocketAddr;
+
+use cat_mux::{run_emulated_radio_serial, run_emulated_radio_server, EmulatedPersonality};
+use egui::{Color32, RichText, Ui};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// CI-V address offered when switching to the Icom personality (IC-7300)
+const DEFAULT_CIV_ADDRESS: u8 = 0x94;
+
+impl CatapultApp {
+    /// Whether the emulated radio is serving
+    pub(super) fn emulated_radio_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits
+        self.emulated_radio_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the emulated radio controls (settings panel)
+    pub(super) fn draw_emulated_radio_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("emulated-radio-heading"));
+        ui.label(
+            RichText::new(tr!("emulated-radio-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.emulated_radio_running();
+        let prev = self.settings.emulated_radio.clone();
+        let emulated = &mut self.settings.emulated_radio;
+
+        ui.add_enabled_ui(!running, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr!("emulated-radio-radio"));
+                let is_civ = matches!(emulated.personality, EmulatedPersonality::IcomCiv { .. });
+                egui::ComboBox::from_id_salt("emulated_radio_personality")
+                    .selected_text(emulated.personality.name())
+                    .show_ui(ui, |ui| {
+                        if ui
+                            .selectable_label(!is_civ, EmulatedPersonality::Kenwood.name())
+                            .clicked()
+                        {
+                            emulated.personality = EmulatedPersonality::Kenwood;
+                        }
+                        let civ = EmulatedPersonality::IcomCiv {
+                            address: DEFAULT_CIV_ADDRESS,
+                        };
+                        if ui.selectable_label(is_civ, civ.name()).clicked() && !is_civ {
+                            emulated.personality = civ;
+                        }
+                    });
+                if let EmulatedPersonality::IcomCiv { address } = &mut emulated.personality {
+                    ui.label(tr!("address"));
+                    ui.add(
+                        egui::DragValue::new(address)
+                            .range(0x01..=0xDF)
+                            .hexadecimal(2, false, true),
+                    );
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr!("emulated-radio-endpoint"));
+                ui.add(egui::TextEdit::singleline(&mut emulated.endpoint).desired_width(160.0))
+                    .on_hover_text(tr!("emulated-radio-endpoint-hover"));
+                if emulated.endpoint.trim().parse::<SocketAddr>().is_err() {
+                    ui.label(tr!("baud"));
+                    ui.add(egui::DragValue::new(&mut emulated.baud_rate).range(1200..=115_200));
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut emulated.enabled, tr!("serve"));
+            if running {
+                ui.label(RichText::new(tr!("serving")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.emulated_radio != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.emulated_radio.enabled != prev.enabled {
+                if self.settings.emulated_radio.enabled {
+                    self.start_emulated_radio();
+                } else {
+                    self.stop_emulated_radio();
+                }
+            }
+        }
+    }
+
+    /// Start emulating the radio on the configured endpoint
+    pub(super) fn start_emulated_radio(&mut self) {
+        let settings = self.settings.emulated_radio.clone();
+        let endpoint = settings.endpoint.trim().to_string();
+        if endpoint.is_empty() {
+            self.report_err("Emulated radio", tr!("emulated-radio-no-endpoint"));
+            return;
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.emulated_radio_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+        let personality = settings.personality;
+        let task_endpoint = endpoint.clone();
+
+        self.rt_handle.spawn(async move {
+            let serve = async {
+                match task_endpoint.parse::<SocketAddr>() {
+                    Ok(addr) => {
+                        let listener = TcpListener::bind(addr).await?;
+                        run_emulated_radio_server(listener, personality, mux).await
+                    }
+                    Err(_) => {
+                        run_emulated_radio_serial(
+                            &task_endpoint,
+                            settings.baud_rate,
+                            personality,
+                            mux,
+                        )
+                        .await
+                    }
+                }
+            };
+            // Dropping the server disconnects its clients
+            let result = tokio::select! {
+                _ = shutdown_rx => return,
+                result = serve => result,
+            };
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::EmulatedRadioError {
+                    message: format!("{}: {}", task_endpoint, e),
+                });
+            }
+        });
+
+        self.set_status(tr!(
+            "emulated-radio-emulating",
+            radio = personality.name(),
+            endpoint = endpoint.to_string()
+        ));
+    }
+
+    /// Stop emulating the radio and disconnect its clients
+    fn stop_emulated_radio(&mut self) {
+        if let Some(tx) = self.emulated_radio_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
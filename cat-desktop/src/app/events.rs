@@ -2,10 +2,16 @@
 
 use std::time::Instant;
 
-use cat_mux::{MuxActorCommand, MuxEvent, RadioChannelMeta, RadioHandle};
-use tokio::sync::oneshot;
+use cat_detect::read_latency_timer;
+use cat_mux::{
+    AmpQueryProfile, MetricsInput, MuxActorCommand, MuxEvent, RadioChannelMeta, RadioHandle,
+    RadioProfile,
+};
+use cat_protocol::display::format_frequency;
+use cat_protocol::{Frequency, RadioResponse};
 use tracing::Level;
 
+use crate::i18n::tr;
 use crate::radio_panel::ConnectionState;
 use crate::traffic_monitor::DiagnosticSeverity;
 
@@ -39,46 +45,96 @@ impl CatapultApp {
                     result,
                 } => {
                     self.probing = false;
-                    if port == self.add_radio_port {
-                        if let Some(probe_result) = result {
-                            // Update UI fields with probe result
-                            // Note: protocol is NOT updated - user already selected it
-                            if baud_rate > 0 {
-                                self.add_radio_baud = baud_rate;
-                            }
-                            if let Some(addr) = probe_result.address {
-                                self.add_radio_civ_address = addr;
-                            }
-                            // Set model name from detected model
-                            self.add_radio_model = probe_result
-                                .model
-                                .map(|m| format!("{} {}", m.manufacturer, m.model))
-                                .unwrap_or_else(|| {
-                                    format!("{} radio", self.add_radio_protocol.name())
-                                });
-                            self.set_status(format!(
-                                "Identified {} on {}",
-                                self.add_radio_model, port
-                            ));
-                        } else {
-                            self.set_status(format!(
-                                "No response on {} using {} at {} baud. Check protocol, baud rate, and connection.",
-                                port,
-                                self.add_radio_protocol.name(),
-                                self.add_radio_baud
-                            ));
+                    let Some(dialog) = self.radio_dialog.as_mut().filter(|d| d.port == port) else {
+                        continue;
+                    };
+                    if let Some(probe_result) = result {
+                        // Update dialog fields with probe result
+                        // Note: protocol is NOT updated - user already selected it
+                        if baud_rate > 0 {
+                            dialog.baud_rate = baud_rate;
+                        }
+                        if let Some(addr) = probe_result.address {
+                            dialog.civ_address = format!("{:02X}", addr);
                         }
+                        // Set model name from detected model
+                        dialog.model_name = probe_result
+                            .model
+                            .map(|m| format!("{} {}", m.manufacturer, m.model))
+                            .unwrap_or_else(|| format!("{} radio", dialog.protocol.name()));
+                        let message = format!("Identified {} on {}", dialog.model_name, port);
+                        self.set_status(message);
+                    } else {
+                        let message = format!(
+                            "No response on {} using {} at {} baud. Check protocol, baud rate, and connection.",
+                            port,
+                            dialog.protocol.name(),
+                            dialog.baud_rate
+                        );
+                        self.set_status(message);
                     }
                 }
+                BackgroundMessage::PortScan(event) => self.handle_port_scan_event(event),
+                BackgroundMessage::PortScanFinished { found, cancelled } => {
+                    self.finish_port_scan(found, cancelled);
+                }
+                BackgroundMessage::CivSweep(event) => self.handle_civ_sweep_event(event),
+                BackgroundMessage::CivSweepFinished { result, cancelled } => {
+                    self.finish_civ_sweep(result, cancelled);
+                }
+                BackgroundMessage::TxStats(stats) => {
+                    self.tx_stats.update(stats);
+                }
+                BackgroundMessage::FrequencyEntered { radio, hz, result } => match result {
+                    Ok(()) => self.set_status(tr!(
+                        "radio-tuning",
+                        radio = radio,
+                        frequency = format_frequency(hz)
+                    )),
+                    Err(e) => self.report_err("Frequency", format!("{}: {}", radio, e)),
+                },
                 BackgroundMessage::RadioRegistered {
                     correlation_id,
                     handle,
                 } => {
+                    // Its radio was closed while the registration was in flight
+                    if !self.pending_radio_configs.contains_key(&correlation_id) {
+                        self.send_mux_command(
+                            MuxActorCommand::UnregisterRadio { handle },
+                            "UnregisterRadio",
+                        );
+                        continue;
+                    }
+
                     // Look up panel index from pending_registrations
                     if let Some(panel_idx) = self.pending_registrations.remove(&correlation_id) {
                         if let Some(panel) = self.radio_panels.get_mut(panel_idx) {
                             panel.handle = Some(handle);
                             tracing::info!("Radio registered: handle={:?}", handle);
+                            let throttle_ms = panel.input_throttle_ms;
+
+                            // Registration used the bare model name; give the mux the
+                            // disambiguated one
+                            if panel.name != panel.model_name {
+                                let name = panel.name.clone();
+                                self.send_mux_command(
+                                    MuxActorCommand::UpdateRadioMeta {
+                                        handle,
+                                        name: Some(name),
+                                    },
+                                    "UpdateRadioMeta",
+                                );
+                            }
+
+                            if throttle_ms > 0 {
+                                self.send_mux_command(
+                                    MuxActorCommand::SetRadioInputThrottle {
+                                        handle,
+                                        ms: throttle_ms as u64,
+                                    },
+                                    "SetRadioInputThrottle",
+                                );
+                            }
                         }
                     }
 
@@ -103,21 +159,47 @@ impl CatapultApp {
                         .iter_mut()
                         .find(|p| p.handle == Some(handle))
                     {
-                        panel.name = model.clone();
+                        panel.model_name = model.clone();
                         // Reset connection state on successful connection
                         panel.connection_state = ConnectionState::Connected;
                         panel.last_response = Some(Instant::now());
                         panel.last_reconnect_attempt = None;
-                        self.send_mux_command(
-                            MuxActorCommand::UpdateRadioMeta {
-                                handle,
-                                name: Some(model.clone()),
-                            },
-                            "UpdateRadioMeta",
-                        );
+                        // Renames (including suffixes on other radios) go to the mux actor
+                        self.refresh_radio_names();
                     }
 
-                    self.report_info("Radio", format!("Connected {} on {}", model, port));
+                    self.report_info(
+                        "Radio",
+                        tr!(
+                            "radio-connected",
+                            model = model.to_string(),
+                            port = port.to_string()
+                        ),
+                    );
+                }
+                BackgroundMessage::RadioProfiled {
+                    handle,
+                    profile,
+                    learned,
+                } => {
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        panel.profile = Some(profile.clone());
+                    }
+                    if learned {
+                        self.report_info(
+                            "Radio",
+                            tr!(
+                                "radio-profiled",
+                                radio = profile.identity.to_string(),
+                                summary = profile.summary()
+                            ),
+                        );
+                        self.store_radio_profile(profile);
+                    }
                 }
                 BackgroundMessage::RadioStateSync { handle, state } => {
                     // Update RadioPanel from authoritative mux actor state
@@ -127,8 +209,8 @@ impl CatapultApp {
                         .find(|p| p.handle == Some(handle))
                     {
                         // Only update if different (avoid unnecessary changes)
-                        if panel.frequency_hz != state.frequency_hz {
-                            panel.frequency_hz = state.frequency_hz;
+                        if panel.frequency_hz != state.frequency_hz.map(Frequency::hz) {
+                            panel.frequency_hz = state.frequency_hz.map(Frequency::hz);
                         }
                         if panel.mode != state.mode {
                             panel.mode = state.mode;
@@ -136,7 +218,78 @@ impl CatapultApp {
                         if panel.ptt != state.ptt {
                             panel.ptt = state.ptt;
                         }
+                        if panel.power_watts != state.power_watts {
+                            panel.power_watts = state.power_watts;
+                        }
+                        panel.last_seen = state.last_seen;
+                    }
+                }
+                BackgroundMessage::CaptureFrames {
+                    file,
+                    frames,
+                    protocol,
+                } => {
+                    self.traffic_monitor
+                        .add_capture_frames(&file, frames, protocol);
+                }
+                BackgroundMessage::CaptureError { message } => {
+                    self.report_err("Capture folder", message);
+                }
+                BackgroundMessage::MetricsError { message } => {
+                    self.report_err("Metrics", message);
+                }
+                BackgroundMessage::StatusPageError { message } => {
+                    self.report_err("Status page", message);
+                }
+                BackgroundMessage::StatusPushError { message } => {
+                    self.report_err("Push alert", message);
+                }
+                BackgroundMessage::RigctlError { message } => {
+                    self.report_err("rigctl", message);
+                }
+                BackgroundMessage::GrpcError { message } => {
+                    self.report_err("gRPC", message);
+                }
+                BackgroundMessage::EmulatedRadioError { message } => {
+                    self.report_err("Emulated radio", message);
+                }
+                BackgroundMessage::DiskLogError { message } => {
+                    self.disk_log_tx = None;
+                    self.report_err("Disk log", message);
+                }
+                BackgroundMessage::LogRecords { file, records } => {
+                    if let Some(viewer) = &mut self.log_viewer {
+                        viewer.loading = false;
                     }
+                    let count = records.len();
+                    self.traffic_monitor.add_log_records(&file, records);
+                    self.show_traffic_monitor = true;
+                    self.set_status(tr!(
+                        "traffic-loaded",
+                        count = count,
+                        file = file.to_string()
+                    ));
+                }
+                BackgroundMessage::LogLoadError { message } => {
+                    if let Some(viewer) = &mut self.log_viewer {
+                        viewer.loading = false;
+                    }
+                    self.report_err("Disk log", message);
+                }
+                BackgroundMessage::DxClusterError { message } => {
+                    self.report_err("DX Cluster", message);
+                }
+                BackgroundMessage::PeerSyncError { message } => {
+                    self.report_err("Peer sync", message);
+                }
+                BackgroundMessage::BookmarkTriggerError { message } => {
+                    self.report_err("Bookmarks", message);
+                }
+                BackgroundMessage::PttSwitchError { message } => {
+                    self.report_err("PTT switch", message);
+                }
+                BackgroundMessage::WatchWebhookError { message } => {
+                    self.report_err("Watch webhook", message);
                 }
             }
         }
@@ -144,13 +297,24 @@ impl CatapultApp {
 
     /// Process events from the mux actor and update local state
     pub(super) fn process_mux_events(&mut self) {
+        self.forward_metrics(MetricsInput::EventQueueDepth(self.mux_event_rx.len()));
         while let Ok(event) = self.mux_event_rx.try_recv() {
+            if self.metrics_tx.is_some() {
+                self.forward_metrics(MetricsInput::Event(Box::new(event.clone())));
+            }
+            self.forward_grpc(&event);
+            self.observe_status(&event);
+            self.log_traffic(&event);
+            self.watch_engine
+                .observe(&event, tokio::time::Instant::now());
+            self.state_inspector.record(&event);
             match event {
                 MuxEvent::RadioStateChanged {
                     handle,
                     freq,
                     mode,
                     ptt,
+                    power_watts,
                 } => {
                     // Update the RadioPanel's local state
                     if let Some(panel) = self
@@ -159,34 +323,227 @@ impl CatapultApp {
                         .find(|p| p.handle == Some(handle))
                     {
                         if let Some(f) = freq {
-                            panel.frequency_hz = Some(f);
+                            panel.frequency_hz = Some(f.hz());
                         }
+                        let mut learned = None;
                         if let Some(m) = mode {
                             panel.mode = Some(m);
+                            if let Some(profile) = panel.profile.as_mut() {
+                                if profile.observe(&RadioResponse::Mode { mode: m }) {
+                                    learned = Some(profile.clone());
+                                }
+                            }
                         }
                         if let Some(p) = ptt {
                             panel.ptt = p;
                         }
+                        if let Some(w) = power_watts {
+                            panel.power_watts = Some(w);
+                        }
 
                         // Also update SimulationPanel for virtual radios
-                        if let Some(sim_id) = panel.sim_id() {
-                            self.simulation_panel
-                                .update_radio_state(sim_id, freq, mode, ptt);
+                        if panel.is_virtual() {
+                            self.simulation_panel.update_radio_state(
+                                &panel.channel_id,
+                                freq.map(Frequency::hz),
+                                mode,
+                                ptt,
+                            );
+                        }
+                        if let Some(profile) = learned {
+                            self.store_radio_profile(profile);
                         }
                     }
                 }
                 MuxEvent::ActiveRadioChanged { from: _, to } => {
                     self.active_radio = Some(to);
                 }
+                MuxEvent::SwitchPendingConfirmation {
+                    token,
+                    requested,
+                    current,
+                    timeout_ms,
+                } => {
+                    self.show_switch_confirmation(token, requested, current, timeout_ms);
+                }
+                MuxEvent::SwitchConfirmationClosed { token, outcome } => {
+                    tracing::debug!("Switch confirmation {} closed: {:?}", token, outcome);
+                    if self.pending_switch.is_some_and(|p| p.token == token) {
+                        self.pending_switch = None;
+                    }
+                }
+                MuxEvent::SwitchAudited { record } => {
+                    self.switch_audit.push(record);
+                }
                 MuxEvent::SwitchingModeChanged { mode } => {
                     self.switching_mode = mode;
                 }
+                MuxEvent::RadioStaleChanged {
+                    handle,
+                    stale,
+                    last_seen,
+                } => {
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        panel.stale = stale;
+                        panel.last_seen = last_seen;
+                    }
+                }
+                MuxEvent::RadioSyncProgress {
+                    handle,
+                    step,
+                    total,
+                    ..
+                } => {
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        // A failed step doesn't end the plan
+                        panel.sync_progress = (step < total).then_some((step, total));
+                    }
+                    // Steps are also logged to the traffic monitor
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::RadioMeter {
+                    handle,
+                    meter,
+                    level,
+                } => {
+                    let ballistics = self.settings.meter_ballistics;
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        panel
+                            .meters
+                            .record(meter, level, Instant::now(), &ballistics);
+                    }
+                }
+                MuxEvent::PttLatency { latency, .. } => {
+                    self.ptt_latency.record(latency);
+                    // Logged to the traffic monitor (as a warning when over budget)
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::RadioCustomEvent { .. } => {
+                    // Logged to the traffic monitor
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::Bookmark {
+                    ref source,
+                    ref note,
+                    timestamp,
+                } => {
+                    self.record_bookmark(source, note, timestamp);
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::ContactSnapshot {
+                    ref radio,
+                    frequency_hz,
+                    mode,
+                    timestamp,
+                    ..
+                } => {
+                    self.record_contact_snapshot(
+                        radio,
+                        frequency_hz.map(Frequency::hz),
+                        mode,
+                        timestamp,
+                    );
+                }
+                MuxEvent::MuxRestarted {
+                    ref reason,
+                    restarts,
+                } => {
+                    self.report_err(
+                        "Mux",
+                        tr!(
+                            "mux-restarted",
+                            reason = reason.to_string(),
+                            restarts = restarts
+                        ),
+                    );
+                    // The new actor has the channels back but not the settings
+                    self.push_mux_settings();
+                    self.apply_memory_budget();
+                }
+                MuxEvent::SpotMatched { handle, ref spot } => {
+                    self.dx_spot_match = spot.clone().map(|spot| (handle, spot));
+                    // Tags the traffic monitor at the point the radio landed on it
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::BandConflict { handle, band, .. } => {
+                    match band {
+                        Some(band) => self.band_conflicts.insert(handle, band),
+                        None => self.band_conflicts.remove(&handle),
+                    };
+                    // Logged to the traffic monitor as a warning
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::DutyCycleAlert {
+                    handle,
+                    duty_cycle,
+                    over,
+                } => {
+                    self.handle_duty_cycle_alert(handle, duty_cycle, over);
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::BandLockoutChanged { .. } => {
+                    // Logged to the traffic monitor as a warning
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::PeersChanged { ref peers } => {
+                    self.peers = peers.clone();
+                }
+                MuxEvent::PeerInterlockChanged { ref blocked_by } => {
+                    self.peer_block = blocked_by.clone();
+                    // Logged to the traffic monitor as a warning
+                    self.forward_traffic_event(event);
+                }
                 MuxEvent::RadioConnected { handle, meta } => {
                     tracing::debug!(
                         "MuxEvent::RadioConnected: handle={}, name={}",
                         handle.0,
                         meta.display_name
                     );
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle) && !p.is_virtual())
+                    {
+                        panel.reassembly = None;
+                        panel.civ_echo_back = None;
+                        panel.latency_timer_ms = read_latency_timer(&panel.port);
+                    }
+                }
+                MuxEvent::RadioReassembly { handle, stats } => {
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        panel.reassembly = Some(stats);
+                    }
+                }
+                MuxEvent::RadioEchoBack { handle, echo_back } => {
+                    if let Some(panel) = self
+                        .radio_panels
+                        .iter_mut()
+                        .find(|p| p.handle == Some(handle))
+                    {
+                        panel.civ_echo_back = Some(echo_back);
+                    }
+                    // The advice also goes in the traffic monitor
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::PartialFrameDiscarded { .. } => {
+                    // Shown as a warning in the traffic monitor
+                    self.forward_traffic_event(event);
                 }
                 MuxEvent::RadioDisconnected { handle } => {
                     // Remove the task sender
@@ -201,6 +558,31 @@ impl CatapultApp {
                     }
                     tracing::debug!("MuxEvent::RadioDisconnected: handle={}", handle.0);
                 }
+                MuxEvent::RadioProtocolChanged { handle, protocol } => {
+                    // Earlier traffic from the radio has already been forwarded,
+                    // so everything buffered was framed by the old codec
+                    let frames = self.traffic_monitor.reparse_radio(handle, protocol);
+                    let name = self
+                        .radio_panels
+                        .iter()
+                        .find(|p| p.handle == Some(handle))
+                        .map(|p| p.name.clone())
+                        .unwrap_or_else(|| format!("Radio {}", handle.0));
+                    self.report_info(
+                        "Radio",
+                        tr!(
+                            "radio-protocol-reparsed",
+                            radio = name.to_string(),
+                            protocol = protocol.name(),
+                            frames = frames
+                        ),
+                    );
+                    self.set_status(tr!(
+                        "radio-protocol-changed",
+                        radio = name.to_string(),
+                        protocol = protocol.name()
+                    ));
+                }
                 MuxEvent::Error { source, message } => {
                     self.report_err(&source, message);
                 }
@@ -210,10 +592,94 @@ impl CatapultApp {
                 MuxEvent::AmpDisconnected => {
                     tracing::debug!("MuxEvent::AmpDisconnected");
                     // Reset virtual amp state when disconnected
+                    self.amp_query_profile = AmpQueryProfile::default();
                     self.virtual_amp_state = None;
                     self.virtual_amp_cmd_tx = None;
                     self.virtual_amp_state_rx = None;
                 }
+                MuxEvent::AmpStateChanged { state } => {
+                    self.on_amp_state_changed(state);
+                }
+                MuxEvent::AmpQueryProfileUpdated { profile } => {
+                    self.amp_query_profile = profile;
+                }
+                MuxEvent::AmpWritePreempted { queued } => {
+                    tracing::debug!("Amplifier safety frame sent ahead of {} queued", queued);
+                }
+                MuxEvent::AmpBacklogChanged {
+                    backed_up,
+                    pending_bytes,
+                    flow_control,
+                } => {
+                    if backed_up {
+                        // Name the handshake that may be what's holding the port
+                        let message = match flow_control {
+                            cat_mux::FlowControl::Hardware => {
+                                tr!("amp-output-backing-up-cts", bytes = pending_bytes)
+                            }
+                            cat_mux::FlowControl::Software => {
+                                tr!("amp-output-backing-up-xoff", bytes = pending_bytes)
+                            }
+                            cat_mux::FlowControl::None => {
+                                tr!("amp-output-backing-up", bytes = pending_bytes)
+                            }
+                        };
+                        self.report_warning("Amplifier", message);
+                    } else {
+                        self.report_info("Amplifier", tr!("amp-output-caught-up"));
+                    }
+                }
+                MuxEvent::ShadowAmpConnected { meta: _ } => {
+                    tracing::debug!("MuxEvent::ShadowAmpConnected");
+                }
+                MuxEvent::ShadowAmpDisconnected => {
+                    tracing::debug!("MuxEvent::ShadowAmpDisconnected");
+                    // Also reached when the shadow's port failed to open
+                    self.shadow_amp_stopped();
+                }
+                MuxEvent::BackupAmpConnected { meta: _ } => {
+                    tracing::debug!("MuxEvent::BackupAmpConnected");
+                }
+                MuxEvent::BackupAmpDisconnected => {
+                    tracing::debug!("MuxEvent::BackupAmpDisconnected");
+                    // Also reached when the backup's port failed to open
+                    self.backup_amp_stopped();
+                }
+                MuxEvent::AmpFailedOver {
+                    from,
+                    to,
+                    ref reason,
+                } => {
+                    self.report_err(
+                        "Amplifier",
+                        tr!(
+                            "amp-failed-over",
+                            from = from.name(),
+                            reason = reason.to_string(),
+                            to = to.name()
+                        ),
+                    );
+                    // Keep the first reason, as the mux does
+                    let reason = self
+                        .backup_amp
+                        .failed_over
+                        .take()
+                        .map_or_else(|| reason.clone(), |(_, first)| first);
+                    self.backup_amp.failed_over = Some((to, reason));
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::AmpFailoverAcknowledged => {
+                    self.backup_amp.failed_over = None;
+                    self.set_status(tr!("amp-primary-restored"));
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::ShadowAmpCompared {
+                    stats, ref diff, ..
+                } => {
+                    self.shadow_amp.record(stats, diff.clone());
+                    // Mismatches are also logged to the traffic monitor
+                    self.forward_traffic_event(event);
+                }
                 MuxEvent::SwitchingBlocked {
                     requested,
                     current,
@@ -227,7 +693,9 @@ impl CatapultApp {
                     );
                 }
                 // Traffic events - forward to traffic monitor
-                MuxEvent::RadioDataIn { handle, .. } => {
+                MuxEvent::RadioDataIn {
+                    handle, ref data, ..
+                } => {
                     // Update last response time for connection health tracking
                     if let Some(panel) = self
                         .radio_panels
@@ -236,16 +704,29 @@ impl CatapultApp {
                     {
                         panel.last_response = Some(Instant::now());
                         panel.connection_state = ConnectionState::Connected;
+                        panel.record_menu_values(data);
                     }
                     self.forward_traffic_event(event);
                 }
                 MuxEvent::RadioDataOut { .. }
                 | MuxEvent::AmpDataOut { .. }
-                | MuxEvent::AmpDataIn { .. } => {
+                | MuxEvent::AmpDataIn { .. }
+                | MuxEvent::ShadowAmpDataOut { .. }
+                | MuxEvent::ShadowAmpDataIn { .. } => {
+                    self.forward_traffic_event(event);
+                }
+                MuxEvent::AnalyzerData {
+                    direction,
+                    ref data,
+                    ref command,
+                    ..
+                } => {
+                    self.analyzer_stats.record(direction, data.len(), command);
                     self.forward_traffic_event(event);
                 }
             }
         }
+        self.check_watches();
     }
 
     /// Process events from virtual amplifier actor (if connected)
@@ -299,20 +780,18 @@ impl CatapultApp {
                 continue;
             };
 
-            let (resp_tx, resp_rx) = oneshot::channel();
-
-            self.send_mux_command(
-                MuxActorCommand::QueryRadioState {
-                    handle,
-                    response: resp_tx,
-                },
-                "QueryRadioState",
-            );
+            let reply = match self.mux.radio_state(handle) {
+                Ok(reply) => reply,
+                Err(e) => {
+                    Self::log_mux_send_error("QueryRadioState", e);
+                    continue;
+                }
+            };
 
             // Spawn task to handle the response
             let bg_tx = self.bg_tx.clone();
             self.rt_handle.spawn(async move {
-                if let Ok(Some(summary)) = resp_rx.await {
+                if let Ok(Some(summary)) = reply.await {
                     let _ = bg_tx.send(BackgroundMessage::RadioStateSync {
                         handle,
                         state: summary,
@@ -330,10 +809,10 @@ impl CatapultApp {
                 .iter()
                 .find(|p| p.handle == Some(handle))
                 .map(|p| {
-                    if p.is_virtual() {
+                    if let Some(sim_id) = p.sim_id() {
                         RadioChannelMeta::new_virtual(
                             p.name.clone(),
-                            p.sim_id().unwrap_or_default().to_string(),
+                            sim_id.to_string(),
                             p.protocol,
                         )
                     } else {
@@ -343,6 +822,7 @@ impl CatapultApp {
                             p.protocol,
                             p.civ_address,
                         )
+                        .with_channel_id(p.channel_id.clone())
                     }
                 })
         };
@@ -350,4 +830,13 @@ impl CatapultApp {
         self.traffic_monitor
             .process_event_with_amp_port(event, &radio_metas, &amp_port);
     }
+
+    /// Remember a radio's profile for its next connection
+    fn store_radio_profile(&mut self, profile: RadioProfile) {
+        let key = RadioProfile::key(profile.protocol, &profile.identity);
+        self.settings.radio_profiles.insert(key, profile);
+        if let Err(e) = self.settings.save() {
+            self.handle_save_error(e);
+        }
+    }
 }
@@ -0,0 +1,138 @@
+//! gRPC control API (settings section and event forwarding)
+//!
+//! The server is only built with the `grpc` feature; without it the section
+//! says so and an enabled setting is reported instead of served.
+
+use std::sync::atomic::Ordering;
+
+use cat_mux::MuxEvent;
+use egui::{Color32, RichText, Ui};
+use tokio::sync::mpsc::error::TrySendError;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// Events buffered for the server before they are dropped
+#[cfg(feature = "grpc")]
+const GRPC_CHANNEL_CAPACITY: usize = 1024;
+
+impl CatapultApp {
+    /// Whether the gRPC server is serving
+    pub(super) fn grpc_running(&self) -> bool {
+        // The server drops its receiver when it exits
+        self.grpc_tx.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Pass a mux event on to the gRPC server, if it is serving
+    ///
+    /// Events are dropped rather than stalling the UI when the server falls
+    /// behind; the server tells its clients how many.
+    pub(super) fn forward_grpc(&self, event: &MuxEvent) {
+        if let Some(tx) = &self.grpc_tx {
+            if let Err(TrySendError::Full(_)) = tx.try_send(event.clone()) {
+                self.grpc_dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Draw the gRPC server controls (settings panel)
+    pub(super) fn draw_grpc_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("grpc-heading"));
+        ui.label(
+            RichText::new(tr!("grpc-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+        if !cfg!(feature = "grpc") {
+            ui.label(RichText::new(tr!("grpc-not-built")).color(Color32::GRAY));
+            return;
+        }
+
+        let running = self.grpc_running();
+        let prev = self.settings.grpc.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("address"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.grpc.address).desired_width(160.0),
+            );
+            ui.checkbox(&mut self.settings.grpc.enabled, tr!("serve"));
+            if running {
+                ui.label(RichText::new(tr!("serving")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.grpc != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.grpc.enabled != prev.enabled {
+                if self.settings.grpc.enabled {
+                    self.start_grpc_server();
+                } else {
+                    self.stop_grpc_server();
+                }
+            }
+        }
+    }
+
+    /// Start serving the gRPC API on the configured address
+    #[cfg(feature = "grpc")]
+    pub(super) fn start_grpc_server(&mut self) {
+        use std::net::SocketAddr;
+
+        use tokio::net::TcpListener;
+        use tokio::sync::mpsc as tokio_mpsc;
+
+        let address = self.settings.grpc.address.trim().to_string();
+        let addr: SocketAddr = match address.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                self.report_err(
+                    "gRPC",
+                    tr!("invalid-address", address = address.to_string()),
+                );
+                return;
+            }
+        };
+        let (tx, rx) = tokio_mpsc::channel(GRPC_CHANNEL_CAPACITY);
+        self.grpc_tx = Some(tx);
+        self.grpc_dropped.store(0, Ordering::Relaxed);
+        let dropped = self.grpc_dropped.clone();
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let message = match TcpListener::bind(addr).await {
+                Ok(listener) => cat_mux::run_grpc_server(listener, mux, rx, dropped)
+                    .await
+                    .err()
+                    .map(|e| e.to_string()),
+                Err(e) => Some(e.to_string()),
+            };
+            if let Some(message) = message {
+                let _ = bg_tx.send(BackgroundMessage::GrpcError {
+                    message: format!("{}: {}", addr, message),
+                });
+            }
+        });
+
+        self.set_status(tr!("grpc-serving-on", address = addr.to_string()));
+    }
+
+    /// Report that this build can't serve gRPC
+    #[cfg(not(feature = "grpc"))]
+    pub(super) fn start_grpc_server(&mut self) {
+        let _ = self.bg_tx.send(BackgroundMessage::GrpcError {
+            message: "This build doesn't include the gRPC server".to_string(),
+        });
+    }
+
+    /// Stop serving gRPC
+    fn stop_grpc_server(&mut self) {
+        // Closing the event channel stops the server
+        self.grpc_tx = None;
+    }
+}
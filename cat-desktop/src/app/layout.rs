@@ -0,0 +1,144 @@
+//! Remembering the window and panel layout between sessions
+
+use egui::containers::panel::PanelState;
+use egui::{Id, ViewportCommand};
+
+use crate::i18n::tr;
+use crate::settings::LayoutSettings;
+
+use super::CatapultApp;
+
+/// Inner window size on first run and after a layout reset
+pub const DEFAULT_WINDOW_SIZE: [f32; 2] = [1024.0, 768.0];
+
+/// Side panels whose widths are remembered
+pub(super) const SETTINGS_PANEL: &str = "settings";
+pub(super) const TRAFFIC_PANEL: &str = "console";
+
+/// Default widths of those panels
+pub(super) const SETTINGS_PANEL_WIDTH: f32 = 300.0;
+pub(super) const TRAFFIC_PANEL_WIDTH: f32 = 400.0;
+
+/// Where to put a window saved as `layout` on a monitor of `monitor` size
+///
+/// `None` keeps the saved placement. When the window was saved on a monitor
+/// of another size it may be on one that is no longer connected, so it is
+/// centered instead, shrunk to fit if need be. The size is the new inner size.
+fn replacement(layout: &LayoutSettings, monitor: [f32; 2]) -> Option<[f32; 2]> {
+    // Only a saved position can be off-screen
+    let saved = layout.window_position.and(layout.monitor_size)?;
+    let same_monitor = (saved[0] - monitor[0]).abs() < 1.0 && (saved[1] - monitor[1]).abs() < 1.0;
+    if same_monitor {
+        return None;
+    }
+    let size = layout.window_size.unwrap_or(DEFAULT_WINDOW_SIZE);
+    Some([size[0].min(monitor[0]), size[1].min(monitor[1])])
+}
+
+impl CatapultApp {
+    /// Note the window geometry and open panels, and bring back a window
+    /// restored off-screen (once the first monitor size arrives)
+    pub(super) fn track_layout(&mut self, ctx: &egui::Context) {
+        let (monitor, inner, outer, minimized, maximized) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.monitor_size,
+                viewport.inner_rect,
+                viewport.outer_rect,
+                viewport.minimized.unwrap_or(false),
+                viewport.maximized.unwrap_or(false),
+            )
+        });
+        let layout = &mut self.settings.layout;
+
+        if let Some(monitor) = monitor {
+            let monitor = [monitor.x, monitor.y];
+            if !self.layout_placed {
+                self.layout_placed = true;
+                if let Some(size) = replacement(layout, monitor) {
+                    tracing::info!("Monitor changed since the last session, centering the window");
+                    ctx.send_viewport_cmd(ViewportCommand::InnerSize(size.into()));
+                    if let Some(center) = ViewportCommand::center_on_screen(ctx) {
+                        ctx.send_viewport_cmd(center);
+                    }
+                    return;
+                }
+            }
+            if !minimized {
+                layout.monitor_size = Some(monitor);
+            }
+        }
+
+        if !minimized {
+            layout.maximized = maximized;
+            // Keep the size and position to go back to when unmaximized
+            if !maximized {
+                if let Some(inner) = inner {
+                    layout.window_size = Some([inner.width(), inner.height()]);
+                }
+                if let Some(outer) = outer {
+                    layout.window_position = Some([outer.min.x, outer.min.y]);
+                }
+            }
+        }
+        layout.show_settings = self.show_settings;
+        layout.show_traffic_monitor = self.show_traffic_monitor;
+    }
+
+    /// Put the window and panels back the way a fresh install has them
+    pub(super) fn reset_layout(&mut self, ctx: &egui::Context) {
+        self.settings.layout = LayoutSettings::default();
+        self.show_settings = self.settings.layout.show_settings;
+        self.show_traffic_monitor = self.settings.layout.show_traffic_monitor;
+        ctx.data_mut(|d| {
+            d.remove::<PanelState>(Id::new(SETTINGS_PANEL));
+            d.remove::<PanelState>(Id::new(TRAFFIC_PANEL));
+        });
+        ctx.send_viewport_cmd(ViewportCommand::Maximized(false));
+        ctx.send_viewport_cmd(ViewportCommand::InnerSize(DEFAULT_WINDOW_SIZE.into()));
+        if let Some(center) = ViewportCommand::center_on_screen(ctx) {
+            ctx.send_viewport_cmd(center);
+        }
+        if let Err(e) = self.settings.save() {
+            self.handle_save_error(e);
+        }
+        self.set_status(tr!("status-layout-reset"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved_on(monitor: [f32; 2]) -> LayoutSettings {
+        LayoutSettings {
+            window_size: Some([1600.0, 1000.0]),
+            window_position: Some([2200.0, 100.0]),
+            monitor_size: Some(monitor),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_same_monitor_keeps_placement() {
+        assert_eq!(
+            replacement(&saved_on([2560.0, 1440.0]), [2560.0, 1440.0]),
+            None
+        );
+        // Nothing saved yet
+        assert_eq!(
+            replacement(&LayoutSettings::default(), [1920.0, 1080.0]),
+            None
+        );
+    }
+
+    #[test]
+    fn test_other_monitor_recenters_and_fits() {
+        let layout = saved_on([2560.0, 1440.0]);
+        assert_eq!(replacement(&layout, [1366.0, 768.0]), Some([1366.0, 768.0]));
+        assert_eq!(
+            replacement(&layout, [3840.0, 2160.0]),
+            Some([1600.0, 1000.0])
+        );
+    }
+}
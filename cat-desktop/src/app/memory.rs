@@ -0,0 +1,79 @@
+//! Memory budget: sizing the long-lived buffers and reporting what they hold
+
+use std::time::Instant;
+
+use cat_mux::memory::DX_SPOT_BYTES;
+use cat_mux::{MemoryUsage, MuxActorCommand, AUDIT_LOG_CAPACITY, MAX_SPOTS};
+
+use crate::traffic_monitor::ANNOTATION_CACHE_MAX_SIZE;
+
+use super::CatapultApp;
+
+/// How often usage is reported while a budget is set
+const REPORT_INTERVAL_SECS: u64 = 60;
+
+impl CatapultApp {
+    /// Size every buffer from the memory budget, or put back the defaults
+    pub(super) fn apply_memory_budget(&mut self) {
+        self.traffic_monitor
+            .set_max_entries(self.settings.traffic_history_limit());
+        let (decodes, switch_audit_records, dx_spots) = match self.settings.memory_budget() {
+            Some(budget) => {
+                let limits = budget.limits();
+                (
+                    limits.annotation_cache_entries,
+                    limits.switch_audit_records,
+                    limits.dx_spots,
+                )
+            }
+            None => (ANNOTATION_CACHE_MAX_SIZE, AUDIT_LOG_CAPACITY, MAX_SPOTS),
+        };
+        self.traffic_monitor.set_decode_cache_capacity(decodes);
+        self.switch_audit.set_capacity(switch_audit_records);
+        self.send_mux_command(
+            MuxActorCommand::SetMemoryLimits {
+                switch_audit_records,
+                dx_spots,
+            },
+            "SetMemoryLimits",
+        );
+    }
+
+    /// Report estimated usage and trim traffic history past its share
+    /// (every minute, while a budget is set)
+    pub(super) fn maybe_report_memory(&mut self) {
+        let Some(budget) = self.settings.memory_budget() else {
+            return;
+        };
+        if self.last_memory_report.elapsed().as_secs() < REPORT_INTERVAL_SECS {
+            return;
+        }
+        self.last_memory_report = Instant::now();
+
+        let limits = budget.limits();
+        let trimmed = self.traffic_monitor.trim_to_bytes(limits.traffic_bytes);
+        let ((entries, traffic_bytes), (decodes, decode_bytes)) =
+            self.traffic_monitor.memory_usage();
+        let (records, record_bytes) = self.switch_audit.memory_usage();
+        let (changes, change_bytes) = self.state_inspector.memory_usage();
+
+        let mut usage = MemoryUsage::default();
+        usage.add("traffic", entries, traffic_bytes);
+        usage.add("decodes", decodes, decode_bytes);
+        usage.add("switch history", records, record_bytes);
+        usage.add("state history", changes, change_bytes);
+        // The mux holds its spots; count them as full
+        usage.add("dx spots", limits.dx_spots, limits.dx_spots * DX_SPOT_BYTES);
+
+        if trimmed > 0 {
+            tracing::warn!(
+                source = "Memory",
+                "Trimmed {} traffic entries to stay in budget; {}",
+                trimmed,
+                usage.summary(budget)
+            );
+        } else {
+            tracing::info!(source = "Memory", "{}", usage.summary(budget));
+        }
+    }
+}
@@ -0,0 +1,108 @@
+//! Prometheus metrics endpoint (settings section and event forwarding)
+
+use std::net::SocketAddr;
+
+use cat_mux::{run_metrics_server, MetricsInput};
+use egui::{Color32, RichText, Ui};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc as tokio_mpsc;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// Metrics input buffered before events are dropped
+const METRICS_CHANNEL_CAPACITY: usize = 1024;
+
+impl CatapultApp {
+    /// Whether the metrics endpoint is serving
+    pub(super) fn metrics_running(&self) -> bool {
+        // The server drops its receiver when it exits (bind or accept error)
+        self.metrics_tx.as_ref().is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Pass mux activity on to the metrics endpoint, if it is serving
+    ///
+    /// Input is dropped rather than stalling the UI when the server falls behind.
+    pub(super) fn forward_metrics(&self, input: MetricsInput) {
+        if let Some(tx) = &self.metrics_tx {
+            let _ = tx.try_send(input);
+        }
+    }
+
+    /// Draw the metrics endpoint controls (settings panel)
+    pub(super) fn draw_metrics_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("metrics-heading"));
+        ui.label(
+            RichText::new(tr!("metrics-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.metrics_running();
+        let prev = self.settings.metrics.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("address"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.metrics.address).desired_width(160.0),
+            );
+            ui.checkbox(&mut self.settings.metrics.enabled, tr!("serve"));
+            if running {
+                ui.label(RichText::new(tr!("serving")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.metrics != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.metrics.enabled != prev.enabled {
+                if self.settings.metrics.enabled {
+                    self.start_metrics_server();
+                } else {
+                    self.stop_metrics_server();
+                }
+            }
+        }
+    }
+
+    /// Start serving metrics on the configured address
+    pub(super) fn start_metrics_server(&mut self) {
+        let address = self.settings.metrics.address.trim().to_string();
+        let addr: SocketAddr = match address.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                self.report_err(
+                    "Metrics",
+                    tr!("invalid-address", address = address.to_string()),
+                );
+                return;
+            }
+        };
+        let (tx, rx) = tokio_mpsc::channel(METRICS_CHANNEL_CAPACITY);
+        self.metrics_tx = Some(tx);
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = match TcpListener::bind(addr).await {
+                Ok(listener) => run_metrics_server(listener, rx).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::MetricsError {
+                    message: format!("{}: {}", addr, e),
+                });
+            }
+        });
+
+        self.set_status(format!("Serving metrics on http://{}/metrics", addr));
+    }
+
+    /// Stop serving metrics
+    fn stop_metrics_server(&mut self) {
+        // Closing the input channel stops the server
+        self.metrics_tx = None;
+    }
+}
@@ -6,26 +6,65 @@
 //! - `radio`: Radio management (COM and virtual)
 //! - `events`: Event processing from mux actor and background tasks
 //! - `amplifier`: Amplifier connection and management
+//! - `band_decoder`: OTRSP band decoder connection
+//! - `bundle`: Configuration bundle export/import
+//! - `safe_mode`: Safe-mode startup and recovery dialog
 //! - `ui_panels`: UI panel drawing methods
 
+mod amp_state;
 mod amplifier;
+mod analyzer;
+mod backup_amp;
+mod band_decoder;
+mod bookmarks;
+mod bug_report;
+mod bundle;
+mod capture;
+mod civ_sweep;
+mod contact_log;
+mod disk_log;
+mod dx_cluster;
+mod emulated_radio;
 mod events;
+mod grpc;
+mod layout;
+mod memory;
+mod metrics;
+mod peer_sync;
 mod ports;
+mod ptt_switch;
 mod radio;
+mod radio_dialog;
+mod rigctl;
+mod safe_mode;
+mod scan;
+mod shadow;
+mod state_inspector;
 mod status;
+mod status_page;
+mod switch_audit;
+mod switch_confirm;
+mod tx_stats;
 mod ui_panels;
+mod watches;
 
 use std::collections::HashMap;
-use std::sync::mpsc::{Receiver, Sender};
+use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::mpsc::{Receiver, Sender, SyncSender};
 use std::sync::Arc;
 use std::time::Instant;
 
-use cat_detect::{PortScanner, ProbeResult, SerialPortInfo};
+use cat_detect::{CivSweepEvent, PortScanner, ProbeResult, ScanEvent, SerialPortInfo};
 use cat_mux::{
-    run_mux_actor, MuxActorCommand, MuxEvent, RadioHandle, RadioStateSummary, RadioTaskCommand,
-    SwitchingMode,
+    sanitize_instance, AmpQueryProfile, AnalyzerStats, ChannelId, ClientError, DxSpot,
+    FrequencyEntryError, MetricsInput, MuxActorCommand, MuxClient, MuxEvent, PeerState, PeerStatus,
+    PttLatencyStats, RadioHandle, RadioProfile, RadioStateSummary, RadioTaskCommand,
+    SharedStatusBoard, SwitchingMode, TrafficRecord, TranslationStrictness, TxBandStats,
+    WatchEngine,
 };
-use cat_protocol::{OperatingMode, Protocol};
+use cat_protocol::display::set_frequency_format;
+use cat_protocol::{Frequency, OperatingMode, Protocol};
 use cat_sim::{VirtualAmpCommand, VirtualAmpMode, VirtualAmpStateEvent};
 use eframe::CreationContext;
 use tokio::sync::{mpsc as tokio_mpsc, oneshot};
@@ -33,12 +72,18 @@ use tracing::Level;
 
 use cat_sim::VirtualRadioCommand;
 
+use crate::config_bundle::{ImportMode, ImportPreview};
 use crate::diagnostics_layer::{DiagnosticEvent, DiagnosticLevelState};
+use crate::i18n::{self, tr};
 use crate::radio_panel::{ConnectionState, RadioPanel};
 use crate::settings::Settings;
+use crate::settings_store::Loaded;
 use crate::simulation_panel::SimulationPanel;
+use crate::startup_guard::StartupGuard;
 use crate::traffic_monitor::TrafficMonitor;
 
+pub use layout::DEFAULT_WINDOW_SIZE;
+
 /// Threshold for marking a radio as unresponsive (no data received)
 const UNRESPONSIVE_THRESHOLD: std::time::Duration = std::time::Duration::from_secs(2);
 
@@ -90,6 +135,25 @@ pub enum BackgroundMessage {
         baud_rate: u32,
         result: Option<ProbeResult>,
     },
+    /// Progress from a port scan
+    PortScan(ScanEvent),
+    /// Port scan returned (finished or cancelled)
+    PortScanFinished { found: usize, cancelled: bool },
+    /// Progress from a CI-V bus sweep
+    CivSweep(CivSweepEvent),
+    /// CI-V sweep returned: devices found, or why the port couldn't be opened
+    CivSweepFinished {
+        result: Result<usize, String>,
+        cancelled: bool,
+    },
+    /// Transmit statistics answered by the mux
+    TxStats(Vec<TxBandStats>),
+    /// A typed frequency was sent to a radio, or refused
+    FrequencyEntered {
+        radio: String,
+        hz: Frequency,
+        result: Result<(), FrequencyEntryError>,
+    },
     /// Radio registered with mux actor (handle assigned)
     RadioRegistered {
         correlation_id: u64,
@@ -101,21 +165,69 @@ pub enum BackgroundMessage {
         model: String,
         port: String,
     },
+    /// Radio's capability profile, loaded from settings or `learned` just now
+    RadioProfiled {
+        handle: RadioHandle,
+        profile: RadioProfile,
+        learned: bool,
+    },
     /// Radio state sync response from mux actor
     RadioStateSync {
         handle: RadioHandle,
         state: RadioStateSummary,
     },
+    /// Frames decoded from a watched capture file
+    CaptureFrames {
+        file: String,
+        frames: Vec<Vec<u8>>,
+        protocol: Protocol,
+    },
+    /// The capture folder could not be read
+    CaptureError { message: String },
+    /// The metrics endpoint could not listen or stopped with an error
+    MetricsError { message: String },
+    /// The status page could not listen or stopped with an error
+    StatusPageError { message: String },
+    /// A critical alert could not be pushed
+    StatusPushError { message: String },
+    /// The rigctl server could not listen or stopped with an error
+    RigctlError { message: String },
+    /// The gRPC server could not start or stopped with an error
+    GrpcError { message: String },
+    /// The emulated radio could not open its endpoint or stopped with an error
+    EmulatedRadioError { message: String },
+    /// The disk log could not be written
+    DiskLogError { message: String },
+    /// Frames loaded from a traffic log for the monitor
+    LogRecords {
+        file: String,
+        records: Vec<TrafficRecord>,
+    },
+    /// A traffic log could not be read
+    LogLoadError { message: String },
+    /// The DX cluster connection failed or dropped
+    DxClusterError { message: String },
+    /// Peer sync could not start or stopped with an error
+    PeerSyncError { message: String },
+    /// The bookmark trigger port could not be opened or read
+    BookmarkTriggerError { message: String },
+    /// The PTT switch port could not be opened or read
+    PttSwitchError { message: String },
+    /// A watch webhook could not be delivered
+    WatchWebhookError { message: String },
 }
 
 /// Configuration for connecting a COM port radio
 pub(super) struct ComRadioConfig {
+    pub channel_id: ChannelId,
     pub port: String,
     pub protocol: Protocol,
     pub baud_rate: u32,
     pub civ_address: Option<u8>,
     pub model_name: String,
     pub flow_control: cat_mux::FlowControl,
+    pub monitor: Option<cat_mux::MonitorConfig>,
+    pub link: crate::settings::RadioLinkSettings,
 }
 
 /// Main application state
@@ -138,6 +250,8 @@ pub struct CatapultApp {
     pub(super) show_settings: bool,
     /// Show traffic monitor/log console
     pub(super) show_traffic_monitor: bool,
+    /// The saved window placement has been checked against the monitor
+    pub(super) layout_placed: bool,
     /// Simulation panel for virtual radio state management
     pub(super) simulation_panel: SimulationPanel,
     /// Background message receiver
@@ -150,6 +264,10 @@ pub struct CatapultApp {
     pub(super) amp_port: String,
     /// Selected amplifier protocol
     pub(super) amp_protocol: Protocol,
+    /// Protocol to answer the amplifier in (None = same as amp_protocol)
+    pub(super) amp_response_protocol: Option<Protocol>,
+    /// Translation strictness for the amplifier (None = the global setting)
+    pub(super) amp_strictness: Option<TranslationStrictness>,
     /// Selected amplifier baud rate
     pub(super) amp_baud: u32,
     /// CI-V address for Icom amplifiers (0x00-0xFF)
@@ -170,28 +288,99 @@ pub struct CatapultApp {
     pub(super) virtual_amp_state: Option<VirtualAmpStateEvent>,
     /// Virtual amplifier behavior mode (selected before connecting)
     pub(super) virtual_amp_mode: VirtualAmpMode,
-    /// Selected port for adding a new COM radio
-    pub(super) add_radio_port: String,
-    /// Selected protocol for adding a new COM radio
-    pub(super) add_radio_protocol: Protocol,
-    /// Selected baud rate for adding a new COM radio
-    pub(super) add_radio_baud: u32,
-    /// CI-V address for new Icom COM radio
-    pub(super) add_radio_civ_address: u8,
-    /// Model name for new radio (from probe or manual entry)
-    pub(super) add_radio_model: String,
-    /// Flow control for new COM radio
-    pub(super) add_radio_flow_control: crate::settings::SerialFlowControl,
+    /// Band decoder shutdown sender (Some while a band decoder task is running)
+    pub(super) band_decoder_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Analyzer passthrough shutdown sender (Some while the analyzer is running)
+    pub(super) analyzer_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Capture folder watcher shutdown sender (Some while watching)
+    pub(super) capture_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Input to the metrics endpoint (Some while it is serving)
+    pub(super) metrics_tx: Option<tokio_mpsc::Sender<MetricsInput>>,
+    /// Status page shutdown sender (Some while it is serving)
+    pub(super) status_page_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Station state for the status page, and critical alerts raised for pushing
+    pub(super) status_board: SharedStatusBoard,
+    /// rigctl server shutdown sender (Some while serving)
+    pub(super) rigctl_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Events for the gRPC server (Some while it is serving)
+    pub(super) grpc_tx: Option<tokio_mpsc::Sender<MuxEvent>>,
+    /// Events dropped because the gRPC server's channel was full
+    pub(super) grpc_dropped: Arc<AtomicU64>,
+    /// Emulated radio shutdown sender (Some while serving)
+    pub(super) emulated_radio_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Input to the disk log writer (Some while recording)
+    pub(super) disk_log_tx: Option<SyncSender<TrafficRecord>>,
+    /// Traffic log open in the viewer
+    pub(super) log_viewer: Option<disk_log::LogViewer>,
+    /// Bookmark pin watcher shutdown sender (Some while watching)
+    pub(super) bookmark_pin_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// PTT switch watcher shutdown sender (Some while watching)
+    pub(super) ptt_switch_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// DX cluster client shutdown sender (Some while connected)
+    pub(super) dx_cluster_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// DX spot the active radio is on, if any
+    pub(super) dx_spot_match: Option<(RadioHandle, DxSpot)>,
+    /// Peer sync task shutdown sender (Some while syncing)
+    pub(super) peer_sync_shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Where the mux sends local state for peers (Some while syncing, and
+    /// handed again to a restarted mux)
+    pub(super) peer_sync_outgoing: Option<tokio_mpsc::Sender<PeerState>>,
+    /// Peer instances being heard
+    pub(super) peers: Vec<PeerStatus>,
+    /// Peer holding the band while the amp is locked out of transmit
+    pub(super) peer_block: Option<PeerStatus>,
+    /// Radios tuned to the active radio's band, with the band
+    pub(super) band_conflicts: HashMap<RadioHandle, &'static str>,
+    /// Evaluates the configured watch expressions
+    pub(super) watch_engine: WatchEngine,
+    /// Watches alerting right now (name, detail)
+    pub(super) watch_alerts: Vec<(String, String)>,
+    /// Frame statistics for the analyzer session
+    pub(super) analyzer_stats: AnalyzerStats,
+    /// Radio PTT edge to amplifier notify latency
+    pub(super) ptt_latency: PttLatencyStats,
+    /// Queries the amplifier issues and how often
+    pub(super) amp_query_profile: AmpQueryProfile,
+    /// What the amp has been told (this session, or saved by the last one)
+    pub(super) amp_known_state: Option<amp_state::KnownAmpState>,
+    /// `amp_known_state` changed since it was last saved
+    pub(super) amp_state_unsaved: bool,
+    /// Last time `amp_known_state` was saved
+    pub(super) last_amp_state_save: Instant,
+    /// Shadow amplifier for A/B comparison
+    pub(super) shadow_amp: shadow::ShadowAmpState,
+    /// Standby amplifier and failover state
+    pub(super) backup_amp: backup_amp::BackupAmpState,
+    /// Include the traffic log when exporting a configuration bundle
+    pub(super) bundle_include_logs: bool,
+    /// How an imported bundle is combined with the current settings
+    pub(super) bundle_import_mode: ImportMode,
+    /// Imported bundle awaiting confirmation (source file and result)
+    pub(super) pending_import: Option<(PathBuf, ImportPreview)>,
+    /// Open "Report a problem" dialog
+    pub(super) bug_report: Option<bug_report::BugReportDialog>,
+    /// Switch audit records and the "Why did it switch?" window
+    pub(super) switch_audit: switch_audit::SwitchAuditView,
+    /// Mux state history for inspecting past moments
+    pub(super) state_inspector: state_inspector::StateInspector,
+    /// Transmit statistics window and radios over the duty-cycle alert
+    pub(super) tx_stats: tx_stats::TxStatsView,
+    /// Manual switch during TX waiting for the operator to confirm
+    pub(super) pending_switch: Option<cat_mux::PendingSwitch>,
+    /// Open radio configuration dialog (adding or editing a radio)
+    pub(super) radio_dialog: Option<radio_dialog::RadioDialog>,
     /// Is probing in progress
     pub(super) probing: bool,
+    /// Port scan in progress, or its results until cleared
+    pub(super) port_scan: Option<scan::PortScan>,
+    /// CI-V sweep from the radio dialog, or its results until the dialog closes
+    pub(super) civ_sweep: Option<civ_sweep::CivSweep>,
     /// Diagnostic event receiver (from tracing layer)
     pub(super) diag_rx: Receiver<DiagnosticEvent>,
     /// Next correlation_id to assign for pending registrations
     pub(super) next_correlation_id: u64,
-    /// Mux command sender (for sending commands to mux actor)
-    pub(super) mux_cmd_tx: tokio_mpsc::Sender<MuxActorCommand>,
-    /// Mux event sender (for async connection tasks to send events)
-    pub(super) mux_event_tx: tokio_mpsc::Sender<MuxEvent>,
+    /// Client for the mux actor (commands, queries, and the event sender for connection tasks)
+    pub(super) mux: MuxClient,
     /// Mux event receiver (for receiving events from mux actor)
     pub(super) mux_event_rx: tokio_mpsc::Receiver<MuxEvent>,
     /// Pending registrations: correlation_id -> panel index
@@ -206,25 +395,66 @@ pub struct CatapultApp {
     pub(super) next_sim_id: u32,
     /// Last time we synced radio states with mux actor
     pub(super) last_state_sync: Instant,
+    /// Last time memory usage was reported
+    pub(super) last_memory_report: Instant,
     /// Tokio runtime (must be kept alive for async tasks)
     _runtime: Option<tokio::runtime::Runtime>,
     /// Shared state for dynamic diagnostics level filtering
     pub(super) diagnostic_level_state: Arc<DiagnosticLevelState>,
     /// Previous diagnostic level (for detecting changes)
     pub(super) prev_diagnostic_level: Option<Level>,
+    /// Crash-loop detection for this startup
+    pub(super) startup_guard: StartupGuard,
+    /// Set while running in safe mode after repeated failed startups
+    pub(super) safe_mode: Option<safe_mode::SafeMode>,
 }
 
 impl CatapultApp {
     /// Create a new application
     pub fn new(
         _cc: &CreationContext<'_>,
+        loaded: Loaded<Settings>,
         diag_rx: Receiver<DiagnosticEvent>,
         runtime: tokio::runtime::Runtime,
         diagnostic_level_state: Arc<DiagnosticLevelState>,
     ) -> Self {
         let rt_handle = runtime.handle().clone();
         let (bg_tx, bg_rx) = std::sync::mpsc::channel();
-        let settings = Settings::load();
+        let startup_guard = StartupGuard::begin(Settings::startup_sentinel_path());
+
+        // After repeated failed startups, or with a settings file that
+        // won't load, come up with defaults and connect nothing
+        let safe_settings = || Settings {
+            read_only: true,
+            ..Default::default()
+        };
+        let (settings, safe_mode) = match loaded {
+            Loaded::Unreadable { error, backup } => {
+                tracing::warn!("{}, starting in safe mode", error);
+                let safe_mode = safe_mode::SafeMode::unreadable_settings(error, backup);
+                (safe_settings(), Some(safe_mode))
+            }
+            loaded => {
+                let saved = match loaded {
+                    Loaded::Ok(settings) => settings,
+                    _ => Settings::default(),
+                };
+                if startup_guard.safe_mode() {
+                    tracing::warn!(
+                        "Last {} startups did not finish, starting in safe mode",
+                        startup_guard.failed_starts()
+                    );
+                    let safe_mode = safe_mode::SafeMode::new(
+                        startup_guard.failed_starts(),
+                        startup_guard.suspect(),
+                        saved,
+                    );
+                    (safe_settings(), Some(safe_mode))
+                } else {
+                    (saved, None)
+                }
+            }
+        };
 
         // Restore amplifier settings
         let amp_connection_type = if settings.amplifier.connection_type == "com" {
@@ -233,25 +463,15 @@ impl CatapultApp {
             AmplifierConnectionType::Simulated
         };
 
-        // Create channels for mux actor
-        let (mux_cmd_tx, mux_cmd_rx) = tokio_mpsc::channel::<MuxActorCommand>(256);
-        let (mux_event_tx, mux_event_rx) = tokio_mpsc::channel::<MuxEvent>(256);
-
-        // Clone mux_event_tx for async connection tasks (they'll send events on the same channel)
-        let mux_event_tx_for_actor = mux_event_tx.clone();
-
         // Spawn the mux actor (from cat-mux crate)
-        rt_handle.spawn(async move {
-            run_mux_actor(mux_cmd_rx, mux_event_tx_for_actor).await;
-            tracing::error!("Mux actor exited unexpectedly");
-        });
+        let (mux, mux_event_rx) = MuxClient::start(&rt_handle, 256);
 
         // Track initial diagnostic level for change detection
         let initial_diagnostic_level = settings.diagnostic_level;
 
         let mut app = Self {
             traffic_monitor: TrafficMonitor::new(
-                settings.traffic_history_size,
+                settings.traffic_history_limit(),
                 settings.diagnostic_level,
             ),
             scanner: PortScanner::new(),
@@ -259,14 +479,17 @@ impl CatapultApp {
             radio_panels: Vec::new(),
             radio_task_senders: HashMap::new(),
             status_message: None,
-            show_settings: false,
-            show_traffic_monitor: true,
+            show_settings: settings.layout.show_settings,
+            show_traffic_monitor: settings.layout.show_traffic_monitor,
+            layout_placed: false,
             simulation_panel: SimulationPanel::new(),
             bg_rx,
             bg_tx,
             rt_handle,
             amp_port: settings.amplifier.port.clone(),
             amp_protocol: settings.amplifier.protocol,
+            amp_response_protocol: settings.amplifier.response_protocol,
+            amp_strictness: settings.amplifier.strictness,
             amp_baud: settings.amplifier.baud_rate,
             amp_civ_address: settings.amplifier.civ_address,
             amp_flow_control: settings.amplifier.flow_control,
@@ -277,18 +500,53 @@ impl CatapultApp {
             virtual_amp_state_rx: None,
             virtual_amp_state: None,
             virtual_amp_mode: VirtualAmpMode::default(),
-            add_radio_port: String::new(),
-            add_radio_protocol: Protocol::Kenwood,
-            add_radio_baud: 9600,
-            add_radio_civ_address: 0x00,
-            add_radio_model: String::new(),
-            add_radio_flow_control: crate::settings::SerialFlowControl::default(),
+            band_decoder_shutdown_tx: None,
+            analyzer_shutdown_tx: None,
+            capture_shutdown_tx: None,
+            metrics_tx: None,
+            status_page_shutdown_tx: None,
+            status_board: SharedStatusBoard::default(),
+            rigctl_shutdown_tx: None,
+            grpc_tx: None,
+            grpc_dropped: Arc::new(AtomicU64::new(0)),
+            emulated_radio_shutdown_tx: None,
+            disk_log_tx: None,
+            log_viewer: None,
+            bookmark_pin_shutdown_tx: None,
+            ptt_switch_shutdown_tx: None,
+            dx_cluster_shutdown_tx: None,
+            dx_spot_match: None,
+            peer_sync_shutdown_tx: None,
+            peer_sync_outgoing: None,
+            peers: Vec::new(),
+            peer_block: None,
+            band_conflicts: HashMap::new(),
+            watch_engine: WatchEngine::new(),
+            watch_alerts: Vec::new(),
+            analyzer_stats: AnalyzerStats::default(),
+            ptt_latency: PttLatencyStats::default(),
+            amp_query_profile: AmpQueryProfile::default(),
+            amp_known_state: amp_state::KnownAmpState::load(),
+            amp_state_unsaved: false,
+            last_amp_state_save: Instant::now(),
+            shadow_amp: shadow::ShadowAmpState::default(),
+            backup_amp: backup_amp::BackupAmpState::default(),
+            bundle_include_logs: false,
+            bundle_import_mode: ImportMode::default(),
+            pending_import: None,
+            bug_report: None,
+            switch_audit: Default::default(),
+            state_inspector: Default::default(),
+            tx_stats: Default::default(),
+            pending_switch: None,
+            radio_dialog: None,
             probing: false,
+            port_scan: None,
+            civ_sweep: None,
             diag_rx,
             settings,
             next_correlation_id: 1,
-            mux_cmd_tx,
-            mux_event_tx,
+            mux,
             mux_event_rx,
             pending_registrations: HashMap::new(),
             active_radio: None,
@@ -296,37 +554,225 @@ impl CatapultApp {
             pending_radio_configs: HashMap::new(),
             next_sim_id: 1,
             last_state_sync: Instant::now(),
+            last_memory_report: Instant::now(),
             _runtime: Some(runtime),
             diagnostic_level_state,
             prev_diagnostic_level: initial_diagnostic_level,
+            startup_guard,
+            safe_mode,
         };
 
+        // Push the staleness threshold etc. to the mux before any radios register
+        app.push_mux_settings();
+        app.apply_memory_budget();
+
         // Initial port enumeration
         app.refresh_ports();
 
-        // Restore virtual radios from settings
-        for config in app.settings.virtual_radios.clone() {
-            app.add_virtual_radio_from_config(config);
+        // Analyzer mode leaves the radio ports free for the passthrough
+        if app.safe_mode.is_none() && !app.settings.analyzer.enabled {
+            app.restore_radios();
         }
-
-        // Restore configured COM radios from settings
-        app.restore_configured_radios();
+        if app.safe_mode.is_none() && app.settings.metrics.enabled {
+            app.start_metrics_server();
+        }
+        if app.safe_mode.is_none() && app.settings.status_page.enabled {
+            app.start_status_page();
+        }
+        if app.safe_mode.is_none() && app.settings.rigctl.enabled {
+            app.start_rigctl_server();
+        }
+        if app.safe_mode.is_none() && app.settings.grpc.enabled {
+            app.start_grpc_server();
+        }
+        if app.safe_mode.is_none() && app.settings.emulated_radio.enabled {
+            app.start_emulated_radio();
+        }
+        if app.safe_mode.is_none() && app.settings.disk_log.enabled {
+            app.start_disk_log();
+        }
+        if app.safe_mode.is_none() && app.settings.dx_cluster.enabled {
+            app.start_dx_cluster();
+        }
+        if app.safe_mode.is_none() && app.settings.peer_sync.enabled {
+            app.start_peer_sync();
+        }
+        if app.safe_mode.is_none() && app.settings.bookmarks.pin_enabled {
+            app.start_bookmark_pin();
+        }
+        if app.safe_mode.is_none() && app.settings.ptt_switch.enabled {
+            app.start_ptt_switch();
+        }
+        app.rebuild_watches();
 
         app
     }
 
+    /// Restore virtual and COM radios from settings
+    pub(super) fn restore_radios(&mut self) {
+        for config in self.settings.virtual_radios.clone() {
+            self.add_virtual_radio_from_config(config);
+        }
+        self.restore_configured_radios();
+    }
+
+    /// Send the mux the settings it keeps its own copy of
+    pub(super) fn push_mux_settings(&self) {
+        // Process-wide, so watch alerts and the mux's displays follow it too
+        set_frequency_format(self.settings.frequency_format);
+        i18n::set_language(self.settings.language());
+        self.send_mux_command(
+            MuxActorCommand::SetStaleThreshold {
+                secs: self.settings.stale_threshold_secs(),
+            },
+            "SetStaleThreshold",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetSequencerConfig {
+                config: self.settings.sequencer_config(),
+            },
+            "SetSequencerConfig",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetAmpBypassConfig {
+                config: self.settings.amp_bypass,
+            },
+            "SetAmpBypassConfig",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetTransitionPolicy {
+                policy: self.settings.amp_transition,
+            },
+            "SetTransitionPolicy",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetGaplessHandover {
+                enabled: self.settings.gapless_handover,
+            },
+            "SetGaplessHandover",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetBandGuardConfig {
+                config: self.settings.band_guard,
+            },
+            "SetBandGuardConfig",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetSwitchConfirmation {
+                enabled: self.settings.confirm_tx_switch,
+            },
+            "SetSwitchConfirmation",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetCarryKeyerSpeed {
+                enabled: self.settings.carry_keyer_speed,
+            },
+            "SetCarryKeyerSpeed",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetBandModeMemory {
+                enabled: self.settings.band_mode_memory,
+            },
+            "SetBandModeMemory",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetSuppressRepeats {
+                enabled: self.settings.suppress_repeats,
+            },
+            "SetSuppressRepeats",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetAmpCoalesceWindow {
+                ms: self.settings.amp_coalesce_ms,
+            },
+            "SetAmpCoalesceWindow",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetAmpDryRun {
+                enabled: self.settings.amp_dry_run,
+            },
+            "SetAmpDryRun",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetAmpFailover {
+                enabled: self.settings.amp_failover,
+            },
+            "SetAmpFailover",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetFrequencySmoothing {
+                config: self.settings.frequency_smoothing,
+            },
+            "SetFrequencySmoothing",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetTranslationStrictness {
+                strictness: self.settings.translation_strictness,
+            },
+            "SetTranslationStrictness",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetDutyCycleAlert {
+                config: self.settings.duty_cycle_alert,
+            },
+            "SetDutyCycleAlert",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetBookmarkDoubleTap {
+                ms: self.settings.bookmarks.double_tap_window_ms(),
+            },
+            "SetBookmarkDoubleTap",
+        );
+        self.send_mux_command(
+            MuxActorCommand::SetContactSnapshots {
+                enabled: self.settings.contact_log.enabled,
+            },
+            "SetContactSnapshots",
+        );
+        for panel in &self.radio_panels {
+            if let (Some(handle), Some(monitor)) = (panel.handle, panel.monitor) {
+                self.send_mux_command(
+                    MuxActorCommand::SetRadioMonitor {
+                        handle,
+                        monitor: Some(monitor),
+                    },
+                    "SetRadioMonitor",
+                );
+            }
+        }
+        if let Some(outgoing) = self
+            .peer_sync_outgoing
+            .as_ref()
+            .filter(|_| self.peer_sync_running())
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetPeerSync {
+                    instance: sanitize_instance(&self.settings.peer_sync.instance),
+                    outgoing: Some(outgoing.clone()),
+                },
+                "SetPeerSync",
+            );
+        }
+    }
+
     /// Send a command to the mux actor, logging a warning if the channel is full
     pub(super) fn send_mux_command(&self, cmd: MuxActorCommand, context: &str) {
-        match self.mux_cmd_tx.try_send(cmd) {
-            Ok(()) => {}
-            Err(tokio_mpsc::error::TrySendError::Full(_)) => {
+        if let Err(e) = self.mux.try_send(cmd) {
+            Self::log_mux_send_error(context, e);
+        }
+    }
+
+    /// Log a command or query that didn't reach the mux actor
+    pub(super) fn log_mux_send_error(context: &str, error: ClientError) {
+        match error {
+            ClientError::Full => {
                 tracing::warn!(
                     source = "MuxChannel",
                     "Failed to send {} command: channel full",
                     context,
                 );
             }
-            Err(tokio_mpsc::error::TrySendError::Closed(_)) => {
+            ClientError::Closed | ClientError::NoReply => {
                 tracing::error!(
                     source = "MuxChannel",
                     "Failed to send {} command: channel CLOSED (mux actor not running!)",
@@ -356,6 +802,11 @@ impl CatapultApp {
 impl eframe::App for CatapultApp {
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
         tracing::info!("Application closing, shutting down background tasks...");
+        self.startup_guard.finish();
+        self.save_amp_state();
+        if let Err(e) = self.settings.save() {
+            tracing::warn!("Failed to save the window layout: {}", e);
+        }
 
         // Send shutdown to all radio tasks
         for (handle, sender) in self.radio_task_senders.drain() {
@@ -363,13 +814,14 @@ impl eframe::App for CatapultApp {
             let _ = sender.task_cmd_tx.blocking_send(RadioTaskCommand::Shutdown);
         }
 
+        self.shadow_amp_stopped();
+        self.backup_amp_stopped();
+
         // Disconnect amplifier (sends shutdown to amp tasks)
         if self.amp_data_tx.is_some() {
             tracing::debug!("Disconnecting amplifier");
             // Inline the disconnect logic to avoid borrow issues, using blocking_send
-            let _ = self
-                .mux_cmd_tx
-                .blocking_send(MuxActorCommand::DisconnectAmplifier);
+            let _ = self.mux.blocking_send(MuxActorCommand::DisconnectAmplifier);
 
             if let Some(tx) = self.virtual_amp_cmd_tx.take() {
                 let _ = tx.blocking_send(VirtualAmpCommand::Shutdown);
@@ -382,9 +834,11 @@ impl eframe::App for CatapultApp {
             self.amp_data_tx = None;
         }
 
+        self.stop_analyzer();
+
         // Send shutdown to mux actor
         tracing::debug!("Sending shutdown to mux actor");
-        let _ = self.mux_cmd_tx.blocking_send(MuxActorCommand::Shutdown);
+        let _ = self.mux.blocking_send(MuxActorCommand::Shutdown);
 
         // Give tasks a moment to clean up before runtime is dropped
         std::thread::sleep(std::time::Duration::from_millis(100));
@@ -396,11 +850,16 @@ impl eframe::App for CatapultApp {
         // Process background messages and events (non-blocking)
         // All I/O data now comes through channels from async tasks
         // Command processing happens in mux actor - UI receives events
+        i18n::ensure_fonts(ctx);
         self.process_diagnostic_events();
         self.process_messages();
         self.process_mux_events();
         self.process_virtual_amp_events();
         self.maybe_sync_radio_states();
+        self.maybe_report_memory();
+        self.maybe_save_amp_state();
+        self.startup_guard.poll(Instant::now());
+        self.track_layout(ctx);
 
         // Check for unresponsive radios (no data received within threshold)
         for panel in &mut self.radio_panels {
@@ -428,56 +887,201 @@ impl eframe::App for CatapultApp {
             self.draw_toolbar(ui);
         });
 
+        self.draw_safe_mode_dialog(ctx);
+        self.draw_bug_report_dialog(ctx);
+        self.draw_radio_dialog(ctx);
+        self.draw_switch_audit_window(ctx);
+        self.draw_tx_stats_window(ctx);
+        self.draw_switch_confirm_dialog(ctx);
+
         // Settings panel (side panel)
         if self.show_settings {
-            egui::SidePanel::right("settings")
-                .default_width(300.0)
+            let panel = egui::SidePanel::right(layout::SETTINGS_PANEL)
+                .default_width(
+                    self.settings
+                        .layout
+                        .settings_width
+                        .unwrap_or(layout::SETTINGS_PANEL_WIDTH),
+                )
                 .show(ctx, |ui| {
                     egui::ScrollArea::vertical().show(ui, |ui| {
-                        ui.heading("Amplifier");
-                        ui.separator();
-                        self.draw_amplifier_panel(ui);
+                        // Switching and the amplifier are disabled in analyzer mode
+                        if !self.settings.analyzer.enabled {
+                            ui.heading(tr!("amplifier-heading"));
+                            ui.separator();
+                            self.draw_amplifier_panel(ui);
 
-                        ui.add_space(16.0);
-                        ui.heading("Switching");
-                        ui.separator();
-                        self.draw_switching_panel(ui);
+                            ui.add_space(16.0);
+                            ui.heading(tr!("band-decoder-heading"));
+                            ui.separator();
+                            self.draw_band_decoder_panel(ui);
 
-                        ui.add_space(16.0);
-                        ui.heading("Add Radio");
-                        ui.separator();
-                        self.draw_add_radio_section(ui);
+                            ui.add_space(16.0);
+                            ui.heading(tr!("switching-heading"));
+                            ui.separator();
+                            self.draw_switching_panel(ui);
 
-                        ui.add_space(16.0);
-                        ui.heading("Settings");
+                            ui.add_space(16.0);
+                            ui.heading(tr!("add-radio-heading"));
+                            ui.separator();
+                            self.draw_add_radio_section(ui);
+
+                            ui.add_space(16.0);
+                        }
+                        ui.heading(tr!("settings-heading"));
                         ui.separator();
+                        let prev_stale_secs = self.settings.stale_threshold_secs();
+                        let prev_sequencer = self.settings.sequencer_config();
+                        let prev_coalesce_ms = self.settings.amp_coalesce_ms;
+                        let prev_smoothing = self.settings.frequency_smoothing;
+                        let prev_strictness = self.settings.translation_strictness;
+                        let prev_crate_levels = self.settings.diagnostic_crate_levels.clone();
+                        let prev_frequency_format = self.settings.frequency_format;
+                        let prev_memory_budget = self.settings.memory_budget();
                         if let Some(error) = self.settings.draw(ui) {
                             self.handle_save_error(error);
                         }
+                        self.traffic_monitor
+                            .set_max_entries(self.settings.traffic_history_limit());
+                        let stale_secs = self.settings.stale_threshold_secs();
+                        if stale_secs != prev_stale_secs {
+                            self.send_mux_command(
+                                MuxActorCommand::SetStaleThreshold { secs: stale_secs },
+                                "SetStaleThreshold",
+                            );
+                        }
+                        let sequencer = self.settings.sequencer_config();
+                        if sequencer != prev_sequencer {
+                            self.send_mux_command(
+                                MuxActorCommand::SetSequencerConfig { config: sequencer },
+                                "SetSequencerConfig",
+                            );
+                        }
+                        if self.settings.diagnostic_crate_levels != prev_crate_levels {
+                            self.diagnostic_level_state
+                                .set_crate_levels(&self.settings.diagnostic_crate_levels);
+                        }
+                        if self.settings.memory_budget() != prev_memory_budget {
+                            self.apply_memory_budget();
+                        }
+                        if self.settings.frequency_format != prev_frequency_format {
+                            set_frequency_format(self.settings.frequency_format);
+                            self.traffic_monitor.redecode();
+                        }
+                        if self.settings.amp_coalesce_ms != prev_coalesce_ms {
+                            self.send_mux_command(
+                                MuxActorCommand::SetAmpCoalesceWindow {
+                                    ms: self.settings.amp_coalesce_ms,
+                                },
+                                "SetAmpCoalesceWindow",
+                            );
+                        }
+                        if self.settings.frequency_smoothing != prev_smoothing {
+                            self.send_mux_command(
+                                MuxActorCommand::SetFrequencySmoothing {
+                                    config: self.settings.frequency_smoothing,
+                                },
+                                "SetFrequencySmoothing",
+                            );
+                        }
+                        if self.settings.translation_strictness != prev_strictness {
+                            self.send_mux_command(
+                                MuxActorCommand::SetTranslationStrictness {
+                                    strictness: self.settings.translation_strictness,
+                                },
+                                "SetTranslationStrictness",
+                            );
+                        }
+
+                        ui.add_space(16.0);
+                        self.draw_capture_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_metrics_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_status_page_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_rigctl_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_grpc_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_emulated_radio_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_disk_log_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_dx_cluster_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_peer_sync_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_bookmark_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_ptt_switch_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_contact_log_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_watch_section(ui);
+
+                        ui.add_space(16.0);
+                        self.draw_bundle_section(ui);
 
                         ui.add_space(16.0);
                         ui.separator();
-                        if ui.button("Close").clicked() {
-                            self.show_settings = false;
-                        }
+                        ui.horizontal(|ui| {
+                            if ui.button(tr!("close")).clicked() {
+                                self.show_settings = false;
+                            }
+                            if ui
+                                .button(tr!("settings-reset-layout"))
+                                .on_hover_text(tr!("settings-reset-layout-hover"))
+                                .clicked()
+                            {
+                                self.reset_layout(ctx);
+                            }
+                        });
                     });
                 });
+            if self.show_settings {
+                self.settings.layout.settings_width = Some(panel.response.rect.width());
+            }
         }
 
         // Console panel - pops out from right when shown
         if self.show_traffic_monitor {
-            egui::SidePanel::right("console")
-                .default_width(400.0)
+            let panel = egui::SidePanel::right(layout::TRAFFIC_PANEL)
+                .default_width(
+                    self.settings
+                        .layout
+                        .traffic_monitor_width
+                        .unwrap_or(layout::TRAFFIC_PANEL_WIDTH),
+                )
                 .min_width(300.0)
                 .show(ctx, |ui| {
                     self.draw_traffic_panel(ui);
                 });
+            self.settings.layout.traffic_monitor_width = Some(panel.response.rect.width());
         }
+        self.draw_state_inspector_panel(ctx);
 
         // Central panel - radio list (takes full space when console is closed)
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
-                self.draw_radio_panel(ui);
+                self.draw_watch_alerts(ui);
+                if self.settings.analyzer.enabled {
+                    self.draw_analyzer_panel(ui);
+                } else {
+                    self.draw_radio_panel(ui);
+                }
             });
         });
 
@@ -486,7 +1090,25 @@ impl eframe::App for CatapultApp {
         let has_com_radios = !self.radio_task_senders.is_empty();
         let has_amplifier = self.amp_data_tx.is_some();
 
-        if has_virtual_radios || has_com_radios || has_amplifier {
+        if has_virtual_radios
+            || has_com_radios
+            || has_amplifier
+            || self.analyzer_running()
+            || self.port_scan_running()
+            || self.civ_sweep_running()
+            || self.capture_watch_running()
+            || self.metrics_running()
+            || self.status_page_running()
+            || self.rigctl_running()
+            || self.grpc_running()
+            || self.emulated_radio_running()
+            || self.log_viewer.as_ref().is_some_and(|v| v.loading)
+            || self.dx_cluster_running()
+            || self.peer_sync_running()
+            || self.bookmark_pin_running()
+            || self.ptt_switch_running()
+            || self.watch_engine.has_pending_timer()
+        {
             ctx.request_repaint();
         }
     }
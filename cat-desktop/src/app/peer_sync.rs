@@ -0,0 +1,160 @@
+//! Multi-op peer sync (settings section and sync task)
+
+use cat_mux::{run_peer_sync, sanitize_instance, MuxActorCommand};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// Local state changes buffered for the sync task
+const PEER_CHANNEL_CAPACITY: usize = 16;
+
+impl CatapultApp {
+    /// Whether the peer sync task is running
+    pub(super) fn peer_sync_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits
+        self.peer_sync_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the peer sync controls (settings panel)
+    pub(super) fn draw_peer_sync_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("peer-sync-heading"));
+        ui.label(
+            RichText::new(tr!("peer-sync-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.peer_sync_running();
+        let prev = self.settings.peer_sync.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("peer-sync-station"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.peer_sync.instance)
+                    .hint_text(tr!("peer-sync-station-hint"))
+                    .desired_width(80.0),
+            );
+            ui.label(tr!("peer-sync-udp-port"));
+            ui.add_enabled(
+                !running,
+                egui::DragValue::new(&mut self.settings.peer_sync.port).range(1..=65535),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label(tr!("peer-sync-peers"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.peer_sync.peers)
+                    .hint_text(tr!("peer-sync-peers-hint"))
+                    .desired_width(240.0),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut self.settings.peer_sync.enabled, tr!("peer-sync-sync"));
+            if running {
+                ui.label(RichText::new(tr!("peer-sync-syncing")).color(Color32::GREEN));
+            }
+        });
+        if running {
+            if self.peers.is_empty() {
+                ui.label(
+                    RichText::new(tr!("peer-sync-no-peers"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+            }
+            for peer in &self.peers {
+                ui.label(RichText::new(peer.summary()).small());
+            }
+        }
+
+        if self.settings.peer_sync != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.peer_sync.enabled != prev.enabled {
+                if self.settings.peer_sync.enabled {
+                    self.start_peer_sync();
+                } else {
+                    self.stop_peer_sync();
+                }
+            }
+        }
+    }
+
+    /// Start sharing state with the configured peers
+    pub(super) fn start_peer_sync(&mut self) {
+        let settings = &self.settings.peer_sync;
+        let instance = sanitize_instance(&settings.instance);
+        let bind = format!("0.0.0.0:{}", settings.port);
+        let peers: Vec<String> = settings
+            .peers
+            .split(',')
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(str::to_string)
+            .collect();
+        if peers.is_empty() {
+            self.report_err("Peer sync", tr!("peer-sync-needs-peer"));
+            return;
+        }
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.peer_sync_shutdown_tx = Some(shutdown_tx);
+        let (local_tx, local_rx) = tokio_mpsc::channel(PEER_CHANNEL_CAPACITY);
+        self.peer_sync_outgoing = Some(local_tx.clone());
+        self.send_mux_command(
+            MuxActorCommand::SetPeerSync {
+                instance: instance.clone(),
+                outgoing: Some(local_tx),
+            },
+            "SetPeerSync",
+        );
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = tokio::select! {
+                _ = shutdown_rx => return,
+                result = run_peer_sync(&bind, &peers, &instance, mux.clone(), local_rx) => result,
+            };
+            // Release the interlock rather than hold it on stale peer state
+            let _ = mux
+                .send(MuxActorCommand::SetPeerSync {
+                    instance,
+                    outgoing: None,
+                })
+                .await;
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::PeerSyncError {
+                    message: format!("{}: {}", bind, e),
+                });
+            }
+        });
+
+        self.set_status(tr!("peer-sync-started"));
+    }
+
+    /// Stop sharing state with peers
+    fn stop_peer_sync(&mut self) {
+        if let Some(tx) = self.peer_sync_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+        self.peer_sync_outgoing = None;
+        self.send_mux_command(
+            MuxActorCommand::SetPeerSync {
+                instance: sanitize_instance(&self.settings.peer_sync.instance),
+                outgoing: None,
+            },
+            "SetPeerSync",
+        );
+        self.peers.clear();
+        self.peer_block = None;
+    }
+}
@@ -4,6 +4,7 @@ use std::collections::HashSet;
 
 use cat_detect::SerialPortInfo;
 
+use crate::i18n::tr;
 use crate::port_info::PortInfo;
 
 use super::CatapultApp;
@@ -17,24 +18,16 @@ impl CatapultApp {
                 self.validate_port_selections();
             }
             Err(e) => {
-                self.report_warning("System", format!("Failed to enumerate ports: {}", e));
+                self.report_warning(
+                    "System",
+                    tr!("status-ports-enumerate-failed", error = e.to_string()),
+                );
             }
         }
     }
 
     /// Validate port selections after port list changes
     pub(super) fn validate_port_selections(&mut self) {
-        // Clear add_radio_port if it's no longer available
-        if !self.add_radio_port.is_empty() {
-            let port_exists = self
-                .available_ports
-                .iter()
-                .any(|p| p.port == self.add_radio_port);
-            if !port_exists {
-                self.add_radio_port.clear();
-            }
-        }
-
         // Clear amp_port if it's no longer available or is now used by a radio
         if !self.amp_port.is_empty() {
             let port_exists = self.available_ports.iter().any(|p| p.port == self.amp_port);
@@ -43,7 +36,7 @@ impl CatapultApp {
                 self.amp_port.clear();
                 if self.amp_data_tx.is_some() {
                     self.disconnect_amplifier();
-                    self.set_status("Amplifier disconnected: port no longer available".into());
+                    self.set_status(tr!("status-amp-port-gone"));
                 }
                 self.save_amplifier_settings();
             }
@@ -110,6 +103,7 @@ impl CatapultApp {
     pub(super) fn format_port_label(port: &SerialPortInfo) -> String {
         match &port.product {
             Some(product) => format!("{} ({})", port.port, product),
+            None if port.bluetooth => format!("{} (Bluetooth)", port.port),
             None => port.port.clone(),
         }
     }
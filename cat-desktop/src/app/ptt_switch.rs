@@ -0,0 +1,135 @@
+//! Foot switch PTT (settings section and pin watcher)
+
+use cat_mux::{ptt_switch_source, run_ptt_switch, MuxActorCommand, SerialPin};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::oneshot;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the PTT switch watcher is running
+    pub(super) fn ptt_switch_running(&self) -> bool {
+        // The task drops its shutdown receiver when it exits (port error)
+        self.ptt_switch_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the PTT switch controls (settings panel)
+    pub(super) fn draw_ptt_switch_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("ptt-switch-heading"));
+        ui.label(
+            RichText::new(tr!("ptt-switch-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.ptt_switch_running();
+        let prev = self.settings.ptt_switch.clone();
+        let ports: Vec<String> = self
+            .available_ports
+            .iter()
+            .map(|p| p.port.clone())
+            .collect();
+
+        let ptt_switch = &mut self.settings.ptt_switch;
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut ptt_switch.enabled, tr!("pin-switch-on"));
+            ui.add_enabled_ui(!running, |ui| {
+                egui::ComboBox::from_id_salt("ptt_switch_pin")
+                    .selected_text(ptt_switch.pin.name())
+                    .show_ui(ui, |ui| {
+                        for pin in SerialPin::ALL {
+                            ui.selectable_value(&mut ptt_switch.pin, pin, pin.name());
+                        }
+                    });
+                ui.label(tr!("pin-of"));
+                egui::ComboBox::from_id_salt("ptt_switch_port")
+                    .selected_text(if ptt_switch.port.is_empty() {
+                        tr!("select-port")
+                    } else {
+                        ptt_switch.port.clone()
+                    })
+                    .show_ui(ui, |ui| {
+                        for port in &ports {
+                            ui.selectable_value(&mut ptt_switch.port, port.clone(), port);
+                        }
+                    });
+            });
+            ui.add_enabled_ui(!running, |ui| {
+                ui.checkbox(&mut ptt_switch.inverted, tr!("ptt-switch-inverted"))
+                    .on_hover_text(tr!("ptt-switch-inverted-hint"));
+            });
+            if running {
+                ui.label(RichText::new(tr!("pin-watching")).color(Color32::GREEN));
+            }
+        });
+        if ptt_switch.enabled
+            && self.settings.bookmarks.pin_enabled
+            && ptt_switch.port == self.settings.bookmarks.pin_port
+            && ptt_switch.pin == self.settings.bookmarks.pin
+        {
+            ui.label(
+                RichText::new(tr!("ptt-switch-shares-bookmark-pin"))
+                    .small()
+                    .color(Color32::YELLOW),
+            );
+        }
+
+        if self.settings.ptt_switch != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.ptt_switch.enabled != prev.enabled {
+                if self.settings.ptt_switch.enabled {
+                    self.start_ptt_switch();
+                } else {
+                    self.stop_ptt_switch();
+                }
+            }
+        }
+    }
+
+    /// Start watching the configured pin as a PTT switch
+    pub(super) fn start_ptt_switch(&mut self) {
+        let port = self.settings.ptt_switch.port.trim().to_string();
+        let pin = self.settings.ptt_switch.pin;
+        let inverted = self.settings.ptt_switch.inverted;
+        if port.is_empty() {
+            self.report_err("PTT switch", tr!("ptt-switch-select-port-first"));
+            return;
+        }
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.ptt_switch_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = tokio::select! {
+                _ = shutdown_rx => Ok(()),
+                result = run_ptt_switch(&port, pin, inverted, mux.clone()) => result,
+            };
+            // Never leave the radio keyed by a switch nobody is watching
+            let _ = mux
+                .send(MuxActorCommand::SwitchPtt {
+                    source: ptt_switch_source(&port, pin),
+                    active: false,
+                })
+                .await;
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::PttSwitchError {
+                    message: format!("{}: {}", port, e),
+                });
+            }
+        });
+    }
+
+    /// Stop watching the PTT switch (releasing it if pressed)
+    fn stop_ptt_switch(&mut self) {
+        if let Some(tx) = self.ptt_switch_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
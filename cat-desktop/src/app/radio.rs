@@ -1,17 +1,27 @@
 //! Radio management - COM and virtual radio handling
 
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
-use cat_detect::{probe_port_with_protocol, ProbeResult, RadioProber};
+use cat_detect::latency_timer::{
+    latency_timer_hint, read_latency_timer, set_latency_timer, RECOMMENDED_LATENCY_MS,
+};
+use cat_detect::{
+    describe_open_error, probe_port_with_protocol, ProbeResult, RadioProber, SerialPortInfo,
+    DEFAULT_SETTLE,
+};
 use cat_mux::{
-    AsyncRadioConnection, MuxActorCommand, MuxEvent, RadioChannelMeta, RadioHandle,
-    RadioTaskCommand,
+    parse_frequency, run_flrig_client, AsyncRadioConnection, ChannelId, FlrigClient,
+    MuxActorCommand, MuxEvent, RadioChannelMeta, RadioHandle, RadioProfile, RadioTaskCommand,
+    SyncPlan,
 };
-use cat_protocol::Protocol;
+use cat_protocol::{Protocol, RadioDatabase};
 use cat_sim::{run_virtual_radio_task, VirtualRadio};
-use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+use tokio::sync::mpsc as tokio_mpsc;
 
-use crate::radio_panel::{ConnectionState, RadioPanel};
+use crate::i18n::tr;
+use crate::radio_panel::{disambiguate_names, ConnectionState, RadioPanel};
+use crate::settings::{ConfiguredRadio, RadioLinkSettings};
 
 use super::{
     BackgroundMessage, CatapultApp, ComRadioConfig, VirtualRadioCommand, RECONNECT_INTERVAL,
@@ -66,15 +76,19 @@ async fn probe_virtual_port(protocol: Protocol) -> Option<ProbeResult> {
 
 /// Run the post-connection setup and read loop for any radio connection
 ///
-/// This function handles CI-V address configuration, initial settle delay,
-/// model ID query, initial state query, auto-info enablement, and the read loop.
+/// This function handles CI-V address configuration, the port's settle delay,
+/// the protocol's initial sync plan (ID, state, auto-info), capability profiling
+/// (from `profiles` when the radio has been seen before), and the read loop.
 /// It's used by both COM and virtual radio connections to ensure consistent behavior.
+#[allow(clippy::too_many_arguments)]
 async fn run_radio_connection<T>(
     mut conn: AsyncRadioConnection<T>,
     handle: RadioHandle,
     port_display: String,
     model_name: String,
     civ_address: Option<u8>,
+    settle: Duration,
+    profiles: BTreeMap<String, RadioProfile>,
     bg_tx: std::sync::mpsc::Sender<BackgroundMessage>,
     cmd_rx: tokio_mpsc::Receiver<RadioTaskCommand>,
 ) where
@@ -85,33 +99,48 @@ async fn run_radio_connection<T>(
         conn.set_civ_address(civ_addr);
     }
 
-    // Small delay to let the radio settle
-    tokio::time::sleep(Duration::from_millis(100)).await;
-
-    // Query radio ID to get actual model name
-    let actual_model_name = conn.query_id().await.unwrap_or(model_name);
+    // Some adapters drop what is written right after opening
+    tokio::time::sleep(settle).await;
 
-    // Query initial state
-    if let Err(e) = conn.query_initial_state().await {
-        tracing::warn!("Failed to query initial state on {}: {}", port_display, e);
-    }
-
-    // Enable auto-info mode
-    if let Err(e) = conn.enable_auto_info().await {
-        tracing::warn!("Failed to enable auto-info on {}: {}", port_display, e);
-    }
+    // Identify the radio, read its state and enable auto-info
+    let plan = SyncPlan::for_protocol(conn.protocol());
+    let actual_model_name = conn.run_sync_plan(plan).await.unwrap_or(model_name);
 
     // Notify UI of successful connection
     let _ = bg_tx.send(BackgroundMessage::RadioConnected {
         handle,
-        model: actual_model_name,
+        model: actual_model_name.clone(),
         port: port_display,
     });
 
+    // Use what we learned last time, or ask the radio what it supports
+    let key = RadioProfile::key(conn.protocol(), &actual_model_name);
+    let (profile, learned) = match profiles.get(&key) {
+        Some(profile) if !profile.is_expired(std::time::SystemTime::now()) => {
+            (profile.clone(), false)
+        }
+        _ => (conn.discover_profile(&actual_model_name).await, true),
+    };
+    let _ = bg_tx.send(BackgroundMessage::RadioProfiled {
+        handle,
+        profile: profile.clone(),
+        learned,
+    });
+    conn.set_profile(profile);
+
     // Start read loop (runs until error or shutdown)
     conn.run_read_loop(cmd_rx).await;
 }
 
+/// Apply a configured radio's polling and pacing to its connection
+fn pace_connection<T>(conn: &mut AsyncRadioConnection<T>, link: &RadioLinkSettings)
+where
+    T: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send,
+{
+    conn.set_poll_interval(link.poll_interval());
+    conn.set_command_gap(link.command_gap());
+}
+
 impl CatapultApp {
     /// Allocate a new correlation_id for pending registrations
     pub(super) fn allocate_correlation_id(&mut self) -> u64 {
@@ -120,6 +149,34 @@ impl CatapultApp {
         id
     }
 
+    /// Ask the mux actor to register a radio
+    ///
+    /// The handle comes back as `BackgroundMessage::RadioRegistered` with `correlation_id`.
+    fn request_radio_handle(
+        &self,
+        meta: RadioChannelMeta,
+        cmd_tx: tokio_mpsc::Sender<RadioTaskCommand>,
+        correlation_id: u64,
+        context: &str,
+    ) {
+        let reply = match self.mux.register_radio(meta, Some(cmd_tx)) {
+            Ok(reply) => reply,
+            Err(e) => {
+                Self::log_mux_send_error(context, e);
+                return;
+            }
+        };
+        let bg_tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            if let Ok(handle) = reply.await {
+                let _ = bg_tx.send(BackgroundMessage::RadioRegistered {
+                    correlation_id,
+                    handle,
+                });
+            }
+        });
+    }
+
     /// Register a COM port radio with the mux actor
     /// Returns correlation_id - the RadioHandle will arrive via BackgroundMessage::RadioRegistered
     /// The async radio task is spawned when the handle is received
@@ -129,40 +186,31 @@ impl CatapultApp {
         let correlation_id = self.allocate_correlation_id();
 
         // Create metadata for the radio channel
-        let meta = RadioChannelMeta::new_real(
+        let mut meta = RadioChannelMeta::new_real(
             config.model_name.clone(),
             config.port.clone(),
             config.protocol,
             config.civ_address,
-        );
+        )
+        .with_channel_id(config.channel_id.clone())
+        .with_frequency_offset(config.link.frequency_offset_hz());
+        // Model quirks (e.g. TS-990 dual TX) change how the mux parses its traffic
+        if let Some(model) = RadioDatabase::by_model_name(config.protocol, &config.model_name) {
+            meta.set_model(model);
+        }
+        if let Some(monitor) = config.monitor {
+            meta = meta.with_monitor(monitor);
+        }
+        if config.link.kat500 && config.protocol == Protocol::Elecraft {
+            meta = meta.with_kat500();
+        }
 
         // Create command channel for the radio task (for AI2 heartbeat and shutdown)
         let (cmd_tx, cmd_rx) = tokio_mpsc::channel::<RadioTaskCommand>(32);
 
-        // Create oneshot for receiving the handle
-        let (resp_tx, resp_rx) = oneshot::channel();
-
-        // Send RegisterRadio to mux actor with a clone of the command channel sender
+        // Register with a clone of the command channel sender
         // (mux actor uses it for AI2 heartbeat, app uses the original for shutdown)
-        self.send_mux_command(
-            MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: Some(cmd_tx.clone()),
-            },
-            "RegisterRadio",
-        );
-
-        // Spawn a task to await the handle and send it back via BackgroundMessage
-        let bg_tx = self.bg_tx.clone();
-        self.rt_handle.spawn(async move {
-            if let Ok(handle) = resp_rx.await {
-                let _ = bg_tx.send(BackgroundMessage::RadioRegistered {
-                    correlation_id,
-                    handle,
-                });
-            }
-        });
+        self.request_radio_handle(meta, cmd_tx.clone(), correlation_id, "RegisterRadio");
 
         // Store the config so we can spawn the task when the handle arrives
         self.pending_radio_configs.insert(
@@ -218,16 +266,19 @@ impl CatapultApp {
         cmd_rx: tokio_mpsc::Receiver<RadioTaskCommand>,
     ) {
         let bg_tx = self.bg_tx.clone();
-        let mux_tx = self.mux_cmd_tx.clone();
-        let event_tx = self.mux_event_tx.clone();
+        let mux = self.mux.clone();
         let rt = self.rt_handle.clone();
 
         let port = config.port;
-        let baud_rate = config.baud_rate;
-        let flow_control = config.flow_control;
+        let line = config
+            .link
+            .serial_line(config.baud_rate, config.flow_control);
+        let link = config.link;
         let protocol = config.protocol;
         let civ_address = config.civ_address;
         let model_name = config.model_name;
+        let profiles = self.settings.radio_profiles.clone();
+        let settle = link.settle_delay(self.available_ports.iter().find(|p| p.port == port));
 
         // Store the sender so we can send shutdown commands to this radio
         // (mux actor has a clone for AI2 heartbeat)
@@ -240,37 +291,67 @@ impl CatapultApp {
 
         // Spawn the async connection task
         rt.spawn(async move {
-            match AsyncRadioConnection::connect(
-                handle,
-                &port,
-                baud_rate,
-                flow_control,
-                protocol,
-                event_tx.clone(),
-                mux_tx,
-            ) {
-                Ok(conn) => {
-                    run_radio_connection(
-                        conn,
-                        handle,
-                        port,
-                        model_name,
-                        civ_address,
-                        bg_tx,
-                        cmd_rx,
-                    )
-                    .await;
+            let error = if link.is_flrig() {
+                match FlrigClient::connect(&port).await {
+                    Ok((client, xcvr)) => {
+                        let _ = bg_tx.send(BackgroundMessage::RadioConnected {
+                            handle,
+                            model: format!("{} (flrig)", xcvr),
+                            port,
+                        });
+                        run_flrig_client(handle, client, link.flrig_options(), mux, cmd_rx).await;
+                        return;
+                    }
+                    Err(e) => e.to_string(),
                 }
-                Err(e) => {
-                    let _ = event_tx
-                        .send(MuxEvent::Error {
-                            source: format!("Radio {}", port),
-                            message: format!("Connection failed: {}", e),
-                        })
+            } else if link.is_network() {
+                match AsyncRadioConnection::connect_tcp(handle, &port, protocol, &mux).await {
+                    Ok(mut conn) => {
+                        pace_connection(&mut conn, &link);
+                        run_radio_connection(
+                            conn,
+                            handle,
+                            port,
+                            model_name,
+                            civ_address,
+                            settle,
+                            profiles,
+                            bg_tx,
+                            cmd_rx,
+                        )
                         .await;
-                    let _ = event_tx.send(MuxEvent::RadioDisconnected { handle }).await;
+                        return;
+                    }
+                    Err(e) => e.to_string(),
                 }
-            }
+            } else {
+                match AsyncRadioConnection::connect(handle, &port, &line, protocol, &mux) {
+                    Ok(mut conn) => {
+                        pace_connection(&mut conn, &link);
+                        run_radio_connection(
+                            conn,
+                            handle,
+                            port,
+                            model_name,
+                            civ_address,
+                            settle,
+                            profiles,
+                            bg_tx,
+                            cmd_rx,
+                        )
+                        .await;
+                        return;
+                    }
+                    Err(e) => describe_open_error(&port, &e),
+                }
+            };
+            let _ = mux
+                .emit(MuxEvent::Error {
+                    source: format!("Radio {}", port),
+                    message: format!("Connection failed: {}", error),
+                })
+                .await;
+            let _ = mux.emit(MuxEvent::RadioDisconnected { handle }).await;
         });
     }
 
@@ -286,9 +367,10 @@ impl CatapultApp {
         let name = config.id.clone();
         let protocol = config.protocol;
         let civ_address = config.civ_address;
+        let rules = config.rules.clone();
 
         // Create the VirtualRadio from config
-        let radio = VirtualRadio::from_config(config);
+        let radio = VirtualRadio::from_config(config).with_clock(self.simulation_panel.clock());
         let model_name = radio.model_name().to_string();
 
         // Create duplex stream pair for communication
@@ -307,8 +389,13 @@ impl CatapultApp {
         );
 
         // Register with SimulationPanel for UI display and commands
-        self.simulation_panel
-            .register_radio(sim_id.clone(), name.clone(), protocol, ui_cmd_tx);
+        self.simulation_panel.register_radio(
+            ChannelId::virtual_radio(&sim_id),
+            name.clone(),
+            protocol,
+            rules,
+            ui_cmd_tx,
+        );
 
         // Spawn the virtual radio actor task
         self.rt_handle.spawn(async move {
@@ -319,24 +406,19 @@ impl CatapultApp {
 
         // Spawn the AsyncRadioConnection task
         let bg_tx = self.bg_tx.clone();
-        let mux_tx = self.mux_cmd_tx.clone();
-        let event_tx = self.mux_event_tx.clone();
+        let mux = self.mux.clone();
         let port_display = format!("Virtual ({})", sim_id);
+        let profiles = self.settings.radio_profiles.clone();
         self.rt_handle.spawn(async move {
-            let conn = AsyncRadioConnection::new(
-                handle,
-                sim_id,
-                connection_stream,
-                protocol,
-                event_tx,
-                mux_tx,
-            );
+            let conn = AsyncRadioConnection::new(handle, sim_id, connection_stream, protocol, &mux);
             run_radio_connection(
                 conn,
                 handle,
                 port_display,
                 model_name,
                 civ_address,
+                DEFAULT_SETTLE,
+                profiles,
                 bg_tx,
                 cmd_rx,
             )
@@ -352,25 +434,91 @@ impl CatapultApp {
             .map(|p| p.port.clone())
             .collect();
 
-        for config in self.settings.configured_radios.clone() {
-            let port_available = available_ports.contains(&config.port);
+        let mut restored_keys = std::collections::HashSet::new();
+
+        for mut config in self.settings.configured_radios.clone() {
+            // Skip duplicate entries for the same physical radio
+            let channel_id = config.channel_id();
+            if !restored_keys.insert(channel_id.clone()) {
+                tracing::warn!("Skipping duplicate radio config {}", channel_id);
+                continue;
+            }
+
+            // Port paths can change between sessions (COM renumbering, ttyUSB order);
+            // follow the adapter's USB serial number (and interface) when we have one
+            if let Some(serial) = &config.usb_serial {
+                let matches = |p: &SerialPortInfo| {
+                    p.serial_number.as_ref() == Some(serial)
+                        && (config.usb_interface.is_none() || p.interface == config.usb_interface)
+                };
+                let still_there = self
+                    .available_ports
+                    .iter()
+                    .any(|p| p.port == config.port && matches(p));
+                // Ports another configured radio was saved on belong to it
+                let candidates: Vec<_> =
+                    self.available_ports
+                        .iter()
+                        .filter(|p| matches(p))
+                        .filter(|p| {
+                            !self.settings.configured_radios.iter().any(|other| {
+                                other.port == p.port && other.channel_id() != channel_id
+                            })
+                        })
+                        .collect();
+                if !still_there {
+                    match candidates.as_slice() {
+                        [found] => {
+                            tracing::info!(
+                                "Radio with USB serial {} moved from {} to {}",
+                                serial,
+                                config.port,
+                                found.port
+                            );
+                            config.port = found.port.clone();
+                        }
+                        [] => {}
+                        _ => tracing::warn!(
+                            "Radio with USB serial {} could be on any of {} ports; leaving it on {}",
+                            serial,
+                            candidates.len(),
+                            config.port
+                        ),
+                    }
+                }
+            }
+
+            // Network radios are only found by trying to connect
+            let port_available = config.link.is_network() || available_ports.contains(&config.port);
 
             // Create ComRadioConfig
             let com_config = ComRadioConfig {
+                channel_id,
                 port: config.port.clone(),
                 protocol: config.protocol,
                 baud_rate: config.baud_rate,
                 civ_address: config.civ_address,
                 model_name: config.model_name.clone(),
                 flow_control: config.flow_control.into(),
+                monitor: config.monitor,
+                link: config.link.clone(),
             };
 
-            if port_available {
+            if config.disabled {
+                // Shown but left alone until the user enables it
+                let mut panel = RadioPanel::new_from_config(None, &config);
+                panel.connection_state = ConnectionState::Disconnected;
+                self.radio_panels.push(panel);
+                self.report_info("Radio", tr!("radio-disabled", port = config.port.clone()));
+            } else if port_available {
                 // Create RadioPanel with no handle (will be updated when handle arrives)
                 let panel = RadioPanel::new_from_config(None, &config);
                 self.radio_panels.push(panel);
                 let panel_index = self.radio_panels.len() - 1;
 
+                // Recorded so a crash while connecting can be traced to this radio
+                self.startup_guard.note_channel(&com_config.channel_id);
+
                 // Register with mux actor (handle will arrive via RadioRegistered message)
                 let _correlation_id = self.register_com_radio(com_config, panel_index);
             } else {
@@ -378,9 +526,16 @@ impl CatapultApp {
                 let mut panel = RadioPanel::new_from_config(None, &config);
                 panel.unavailable = true;
                 self.radio_panels.push(panel);
-                self.report_warning("Radio", format!("{} not available", config.port));
+                self.report_warning(
+                    "Radio",
+                    tr!("radio-port-unavailable", port = config.port.clone()),
+                );
             }
         }
+
+        self.refresh_radio_names();
+        // Persist any ports that were re-resolved by USB serial
+        self.save_configured_radios();
     }
 
     /// Add a new virtual radio - creates duplex stream, spawns actor, registers with mux
@@ -419,6 +574,7 @@ impl CatapultApp {
             initial_frequency_hz: radio.frequency_hz(),
             initial_mode: radio.mode(),
             civ_address: radio.civ_address(),
+            rules: radio.rules().to_vec(),
         };
 
         // Allocate a correlation_id for tracking the registration
@@ -430,17 +586,12 @@ impl CatapultApp {
         // Create command channel for the radio task (for AI2 heartbeat and shutdown)
         let (cmd_tx, cmd_rx) = tokio_mpsc::channel::<RadioTaskCommand>(32);
 
-        // Create oneshot for receiving the handle
-        let (resp_tx, resp_rx) = oneshot::channel();
-
-        // Send RegisterRadio to mux actor with a clone of the command channel sender
+        // Register with a clone of the command channel sender
         // (mux actor uses it for AI2 heartbeat, app uses the original for shutdown)
-        self.send_mux_command(
-            MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: Some(cmd_tx.clone()),
-            },
+        self.request_radio_handle(
+            meta,
+            cmd_tx.clone(),
+            correlation_id,
             "RegisterRadio (virtual)",
         );
 
@@ -463,22 +614,12 @@ impl CatapultApp {
             sim_id.clone(),
         ));
         let panel_idx = self.radio_panels.len() - 1;
+        self.refresh_radio_names();
 
         // Store the pending registration
         self.pending_registrations.insert(correlation_id, panel_idx);
 
-        // Spawn a task to await the handle and notify via BackgroundMessage
-        let bg_tx = self.bg_tx.clone();
-        self.rt_handle.spawn(async move {
-            if let Ok(handle) = resp_rx.await {
-                let _ = bg_tx.send(BackgroundMessage::RadioRegistered {
-                    correlation_id,
-                    handle,
-                });
-            }
-        });
-
-        self.set_status(format!("Virtual radio added: {}", sim_id));
+        self.set_status(tr!("radio-virtual-added", id = sim_id.to_string()));
 
         sim_id
     }
@@ -493,9 +634,9 @@ impl CatapultApp {
             .radio_panels
             .iter()
             .find(|p| p.handle == Some(handle))
-            .map(|p| (p.is_virtual(), p.sim_id().map(String::from)));
+            .map(|p| (p.channel_id.clone(), p.sim_id().map(String::from)));
 
-        let Some((is_virtual, sim_id)) = panel_info else {
+        let Some((channel_id, sim_id)) = panel_info else {
             tracing::warn!("remove_radio: no panel found for handle {:?}", handle);
             return;
         };
@@ -516,44 +657,177 @@ impl CatapultApp {
         );
 
         // Handle type-specific cleanup
-        if is_virtual {
+        if channel_id.is_virtual() {
             // Virtual radio: unregister from SimulationPanel
-            if let Some(ref sim_id) = sim_id {
-                self.simulation_panel.unregister_radio(sim_id);
-            }
+            self.simulation_panel.unregister_radio(&channel_id);
             // Remove from radio_panels
             self.radio_panels.retain(|p| p.handle != Some(handle));
             // Save virtual radios config
             self.save_virtual_radios();
-            self.set_status(format!(
-                "Virtual radio removed: {}",
-                sim_id.unwrap_or_default()
+            self.set_status(tr!(
+                "radio-virtual-removed",
+                id = sim_id.unwrap_or_default()
             ));
         } else {
             // COM radio: remove from radio_panels
             self.radio_panels.retain(|p| p.handle != Some(handle));
             // Save configured radios
             self.save_configured_radios();
-            self.set_status("Radio removed".to_string());
+            self.set_status(tr!("radio-removed"));
+        }
+
+        // A former duplicate may no longer need its suffix
+        self.refresh_radio_names();
+    }
+
+    /// Shut down and unregister every radio without forgetting its settings
+    ///
+    /// [`Self::restore_radios`] brings them back.
+    pub(super) fn close_radios(&mut self) {
+        for (handle, sender) in std::mem::take(&mut self.radio_task_senders) {
+            Self::send_radio_task_command(
+                &sender.task_cmd_tx,
+                RadioTaskCommand::Shutdown,
+                "Shutdown",
+            );
+            self.send_mux_command(
+                MuxActorCommand::UnregisterRadio { handle },
+                "UnregisterRadio",
+            );
+        }
+        for panel in std::mem::take(&mut self.radio_panels) {
+            if panel.channel_id.is_virtual() {
+                self.simulation_panel.unregister_radio(&panel.channel_id);
+            }
+            // Registered but its task not spawned yet
+            if let Some(handle) = panel.handle {
+                self.send_mux_command(
+                    MuxActorCommand::UnregisterRadio { handle },
+                    "UnregisterRadio",
+                );
+            }
         }
+        // Registrations still in flight are unregistered when they land
+        self.pending_registrations.clear();
+        self.pending_radio_configs.clear();
+    }
+
+    /// Recompute display names so radios sharing a model/alias stay distinguishable
+    ///
+    /// Renamed radios that are already registered are pushed to the mux actor so
+    /// events and traffic exports carry the unique name.
+    pub(super) fn refresh_radio_names(&mut self) {
+        for idx in disambiguate_names(&mut self.radio_panels) {
+            let panel = &self.radio_panels[idx];
+            if let Some(handle) = panel.handle {
+                self.send_mux_command(
+                    MuxActorCommand::UpdateRadioMeta {
+                        handle,
+                        name: Some(panel.name.clone()),
+                    },
+                    "UpdateRadioMeta",
+                );
+            }
+        }
+    }
+
+    /// Lower a radio's USB adapter latency timer so frames arrive in one piece
+    pub(super) fn set_radio_latency_timer(&mut self, idx: usize) {
+        let Some(panel) = self.radio_panels.get_mut(idx) else {
+            return;
+        };
+        let port = panel.port.clone();
+        match set_latency_timer(&port, RECOMMENDED_LATENCY_MS) {
+            Ok(()) => {
+                panel.latency_timer_ms = read_latency_timer(&port);
+                self.report_info(
+                    "Radio",
+                    tr!(
+                        "radio-latency-timer-set",
+                        port = port.clone(),
+                        ms = RECOMMENDED_LATENCY_MS
+                    ),
+                );
+            }
+            Err(e) => {
+                let hint = self
+                    .available_ports
+                    .iter()
+                    .find(|p| p.port == port)
+                    .and_then(latency_timer_hint);
+                let message = match hint {
+                    Some(hint) => format!("{}: {}. To set it by hand: {}", port, e, hint),
+                    None => format!("{}: {}", port, e),
+                };
+                self.report_err("Latency Timer", message);
+            }
+        }
+    }
+
+    /// Tune a radio to a typed frequency, once it parses and the mux has
+    /// checked it against the radio (answered with a background message)
+    ///
+    /// Only a frequency the operator marked for transmit is checked against
+    /// the band plan; monitor channels never transmit.
+    pub(super) fn enter_frequency(&mut self, idx: usize, text: &str, transmit: bool) {
+        let Some(panel) = self.radio_panels.get(idx) else {
+            return;
+        };
+        let Some(handle) = panel.handle else {
+            self.report_err(
+                "Frequency",
+                tr!("radio-not-connected", radio = panel.name.clone()),
+            );
+            return;
+        };
+        let radio = panel.name.clone();
+        let transmit = transmit && panel.monitor.is_none();
+        let hz = match parse_frequency(text) {
+            Ok(hz) => hz,
+            Err(e) => {
+                self.report_err("Frequency", e.to_string());
+                return;
+            }
+        };
+        let reply =
+            match self
+                .mux
+                .enter_frequency(handle, hz, transmit, self.settings.band_plan_region)
+            {
+                Ok(reply) => reply,
+                Err(e) => {
+                    Self::log_mux_send_error("EnterFrequency", e);
+                    return;
+                }
+            };
+        let bg_tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            if let Ok(result) = reply.await {
+                let _ = bg_tx.send(BackgroundMessage::FrequencyEntered { radio, hz, result });
+            }
+        });
     }
 
-    /// Probe the selected port for radio model detection using the user-selected protocol
+    /// Probe the radio dialog's port for model detection using its protocol
     pub(super) fn probe_selected_port(&mut self) {
-        if self.add_radio_port.is_empty() || self.probing {
+        let Some(dialog) = &self.radio_dialog else {
+            return;
+        };
+        if dialog.port.is_empty() || dialog.is_network() || self.probing {
             return;
         }
 
+        let port = dialog.port.clone();
+        let baud_rate = dialog.baud_rate;
+        let protocol = dialog.protocol;
+        let settle = dialog.settle_delay(&self.available_ports);
         self.probing = true;
-        self.set_status(format!(
-            "Detecting model on {} using {} protocol...",
-            self.add_radio_port,
-            self.add_radio_protocol.name()
+        self.set_status(tr!(
+            "radio-detecting-model",
+            port = port.clone(),
+            protocol = protocol.name()
         ));
 
-        let port = self.add_radio_port.clone();
-        let baud_rate = self.add_radio_baud;
-        let protocol = self.add_radio_protocol;
         let tx = self.bg_tx.clone();
         let rt_handle = self.rt_handle.clone();
 
@@ -571,8 +845,9 @@ impl CatapultApp {
         } else {
             // Real COM port - use the user-selected protocol
             std::thread::spawn(move || {
-                let result = rt_handle
-                    .block_on(async { probe_port_with_protocol(&port, baud_rate, protocol).await });
+                let result = rt_handle.block_on(async {
+                    probe_port_with_protocol(&port, baud_rate, protocol, settle).await
+                });
                 let _ = tx.send(BackgroundMessage::ProbeComplete {
                     port,
                     baud_rate,
@@ -582,51 +857,31 @@ impl CatapultApp {
         }
     }
 
-    /// Add a new COM radio with the current add_radio_* settings
-    pub(super) fn add_com_radio(&mut self) {
-        if self.add_radio_port.is_empty() {
-            return;
-        }
-
-        let civ_address = if self.add_radio_protocol == Protocol::IcomCIV {
-            Some(self.add_radio_civ_address)
-        } else {
-            None
-        };
-        // Use detected model name if available, otherwise generate from protocol
-        let model_name = if self.add_radio_model.is_empty() {
-            format!("{} Radio", self.add_radio_protocol.name())
-        } else {
-            self.add_radio_model.clone()
-        };
-
-        let config = ComRadioConfig {
-            port: self.add_radio_port.clone(),
-            protocol: self.add_radio_protocol,
-            baud_rate: self.add_radio_baud,
-            civ_address,
-            model_name: model_name.clone(),
-            flow_control: self.add_radio_flow_control.into(),
+    /// Add a new COM (or network) radio from the radio dialog's configuration
+    pub(super) fn add_com_radio(&mut self, config: ConfiguredRadio) {
+        let com_config = ComRadioConfig {
+            channel_id: config.channel_id(),
+            port: config.port.clone(),
+            protocol: config.protocol,
+            baud_rate: config.baud_rate,
+            civ_address: config.civ_address,
+            model_name: config.model_name.clone(),
+            flow_control: config.flow_control.into(),
+            monitor: config.monitor,
+            link: config.link.clone(),
         };
 
         // Create RadioPanel with no handle (will be updated when handle arrives)
-        let panel = RadioPanel::new_com(
-            None,
-            model_name,
-            self.add_radio_port.clone(),
-            self.add_radio_protocol,
-            self.add_radio_baud,
-            self.add_radio_flow_control.into(),
-            civ_address,
-        );
-        self.radio_panels.push(panel);
+        self.radio_panels
+            .push(RadioPanel::new_from_config(None, &config));
         let panel_index = self.radio_panels.len() - 1;
+        self.refresh_radio_names();
 
         // Register with mux actor (handle will arrive via RadioRegistered)
-        let _correlation_id = self.register_com_radio(config, panel_index);
+        let _correlation_id = self.register_com_radio(com_config, panel_index);
 
         // If this port was selected as amp port, clear it
-        if self.amp_port == self.add_radio_port {
+        if self.amp_port == config.port {
             self.amp_port.clear();
             if self.amp_data_tx.is_some() {
                 self.disconnect_amplifier();
@@ -636,41 +891,6 @@ impl CatapultApp {
 
         // Save to config
         self.save_configured_radios();
-
-        // Clear the add_radio_port for next addition
-        self.add_radio_port.clear();
-    }
-
-    /// Add a radio from the selected port (handles both real and virtual ports)
-    ///
-    /// Checks if the selected port is virtual (starts with "VSIM:") and either
-    /// calls add_virtual_radio() or add_com_radio() accordingly.
-    pub(super) fn add_radio_from_port(&mut self) {
-        if self.add_radio_port.is_empty() {
-            return;
-        }
-
-        // Check if this is a virtual port (VSIM:name format)
-        if let Some(name) = self.add_radio_port.strip_prefix("VSIM:") {
-            // Find the virtual port config by name
-            if let Some(vport) = self
-                .settings
-                .virtual_ports
-                .iter()
-                .find(|v| v.name == name)
-                .cloned()
-            {
-                // Add virtual radio with the configured protocol
-                self.add_virtual_radio(&vport.name, vport.protocol);
-                // Clear selection for next addition
-                self.add_radio_port.clear();
-            } else {
-                self.report_warning("Radio", format!("Virtual port '{}' not found", name));
-            }
-        } else {
-            // Real COM port - use existing add_com_radio logic
-            self.add_com_radio();
-        }
     }
 
     /// Attempt to reconnect disconnected COM radios
@@ -692,13 +912,16 @@ impl CatapultApp {
             .iter()
             .enumerate()
             .filter(|(_, panel)| {
-                // Only reconnect disconnected COM radios (not virtual)
-                if panel.is_virtual() || panel.connection_state != ConnectionState::Disconnected {
+                // Only reconnect disconnected COM radios (not virtual or disabled)
+                if panel.is_virtual()
+                    || panel.disabled
+                    || panel.connection_state != ConnectionState::Disconnected
+                {
                     return false;
                 }
 
-                // Check if port is available
-                if !available_ports.contains(panel.port.as_str()) {
+                // Check if port is available (network radios just get retried)
+                if !panel.link.is_network() && !available_ports.contains(panel.port.as_str()) {
                     return false;
                 }
 
@@ -717,20 +940,41 @@ impl CatapultApp {
         }
     }
 
+    /// Forget what was learned about a radio and reconnect it, so it is
+    /// profiled again
+    pub(super) fn reprofile_radio(&mut self, panel_idx: usize) {
+        let Some(panel) = self.radio_panels.get_mut(panel_idx) else {
+            return;
+        };
+        let Some(profile) = panel.profile.take() else {
+            return;
+        };
+        let key = RadioProfile::key(profile.protocol, &profile.identity);
+        self.settings.radio_profiles.remove(&key);
+        if let Err(e) = self.settings.save() {
+            self.handle_save_error(e);
+        }
+        tracing::info!("Re-profiling {}", profile.identity);
+        self.reconnect_radio(panel_idx);
+    }
+
     /// Attempt to reconnect a specific radio panel
-    fn reconnect_radio(&mut self, panel_idx: usize) {
+    pub(super) fn reconnect_radio(&mut self, panel_idx: usize) {
         let panel = &mut self.radio_panels[panel_idx];
 
         // Update last reconnect attempt time
         panel.last_reconnect_attempt = Some(Instant::now());
 
         // Extract the info we need for reconnection
+        let channel_id = panel.channel_id.clone();
         let port = panel.port.clone();
         let protocol = panel.protocol;
         let baud_rate = panel.baud_rate;
         let flow_control = panel.flow_control;
         let civ_address = panel.civ_address;
-        let model_name = panel.name.clone();
+        let model_name = panel.model_name.clone();
+        let monitor = panel.monitor;
+        let link = panel.link.clone();
         let old_handle = panel.handle;
 
         tracing::info!("Attempting to reconnect radio on {}", port);
@@ -748,12 +992,15 @@ impl CatapultApp {
 
         // Create new ComRadioConfig
         let config = ComRadioConfig {
+            channel_id,
             port,
             protocol,
             baud_rate,
             civ_address,
             model_name,
             flow_control,
+            monitor,
+            link,
         };
 
         // Register with mux actor (handle will arrive via RadioRegistered)
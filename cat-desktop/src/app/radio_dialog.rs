@@ -0,0 +1,1022 @@
+//! Radio configuration dialog: add a radio, or edit a configured one
+
+use cat_detect::{adapter_for, pairing_hint, ScanHit, SerialPortInfo, UsbAdapter, DEFAULT_SETTLE};
+use cat_mux::{is_virtual_port, ChannelId, DEFAULT_FLRIG_ADDR};
+use cat_protocol::Protocol;
+use egui::{Color32, Id, RichText, Ui};
+
+use crate::i18n::tr;
+use crate::settings::{
+    ConfiguredRadio, LineLevel, RadioLinkSettings, RadioTransportKind, SerialDataBits,
+    SerialFlowControl, SerialParity, SerialStopBits,
+};
+
+use super::{civ_sweep, CatapultApp};
+
+/// Protocols offered in the dialog
+const PROTOCOLS: [Protocol; 6] = [
+    Protocol::Kenwood,
+    Protocol::IcomCIV,
+    Protocol::Yaesu,
+    Protocol::YaesuAscii,
+    Protocol::Elecraft,
+    Protocol::FlexRadio,
+];
+
+/// Baud rates offered in the dialog
+const BAUD_RATES: [u32; 6] = [4800, 9600, 19200, 38400, 57600, 115200];
+
+/// Largest reference correction accepted, in Hz
+const MAX_CALIBRATION_HZ: i32 = 10_000;
+
+/// Largest gap between commands accepted, in ms
+const MAX_COMMAND_GAP_MS: u32 = 1000;
+
+/// Shortest idle poll interval accepted, in ms (0 turns polling off)
+const MIN_POLL_INTERVAL_MS: u32 = 50;
+
+/// Longest settle delay accepted, in ms
+const MAX_SETTLE_MS: u32 = 5000;
+
+/// State of the open dialog
+pub(crate) struct RadioDialog {
+    /// Radio being edited (None when adding one)
+    editing: Option<ChannelId>,
+    /// Serial port, virtual port or "host:port"
+    pub(super) port: String,
+    pub(super) protocol: Protocol,
+    pub(super) baud_rate: u32,
+    flow_control: SerialFlowControl,
+    /// CI-V address as typed (hex)
+    pub(super) civ_address: String,
+    /// Detected or configured model (empty = generic for the protocol)
+    pub(super) model_name: String,
+    alias: String,
+    link: RadioLinkSettings,
+    /// Transverter local oscillator as typed, in MHz (empty = none)
+    transverter_mhz: String,
+    /// Sweep every CI-V address, not just the models' defaults
+    pub(super) civ_sweep_all: bool,
+}
+
+impl Default for RadioDialog {
+    fn default() -> Self {
+        Self {
+            editing: None,
+            port: String::new(),
+            protocol: Protocol::Kenwood,
+            baud_rate: 9600,
+            flow_control: SerialFlowControl::default(),
+            civ_address: "94".to_string(),
+            model_name: String::new(),
+            alias: String::new(),
+            link: RadioLinkSettings::default(),
+            transverter_mhz: String::new(),
+            civ_sweep_all: false,
+        }
+    }
+}
+
+impl RadioDialog {
+    /// Dialog for adding a radio found by a port scan
+    pub(super) fn from_scan(hit: &ScanHit) -> Self {
+        let mut dialog = Self {
+            port: hit.port.port.clone(),
+            protocol: hit.result.protocol,
+            baud_rate: hit.baud_rate,
+            model_name: hit
+                .result
+                .model
+                .as_ref()
+                .map(|m| format!("{} {}", m.manufacturer, m.model))
+                .unwrap_or_default(),
+            ..Self::default()
+        };
+        if let Some(addr) = hit.result.address {
+            dialog.civ_address = format!("{:02X}", addr);
+        }
+        dialog
+    }
+
+    /// Dialog for editing a configured radio
+    pub(super) fn edit(config: &ConfiguredRadio) -> Self {
+        let offset = config.link.transverter_offset_hz;
+        Self {
+            editing: Some(config.channel_id()),
+            port: config.port.clone(),
+            protocol: config.protocol,
+            baud_rate: config.baud_rate,
+            flow_control: config.flow_control,
+            civ_address: format!("{:02X}", config.civ_address.unwrap_or(0x94)),
+            model_name: config.model_name.clone(),
+            alias: config.alias.clone().unwrap_or_default(),
+            link: config.link.clone(),
+            transverter_mhz: if offset == 0 {
+                String::new()
+            } else {
+                format_mhz(offset)
+            },
+            civ_sweep_all: false,
+        }
+    }
+
+    /// Radio being edited, if any
+    pub(super) fn editing(&self) -> Option<&ChannelId> {
+        self.editing.as_ref()
+    }
+
+    /// Whether the chosen port is a simulated radio's
+    pub(super) fn is_virtual(&self) -> bool {
+        is_virtual_port(&self.port)
+    }
+
+    /// Whether the radio is reached over TCP (a serial server or flrig)
+    pub(super) fn is_network(&self) -> bool {
+        self.link.is_network()
+    }
+
+    /// Wait after opening the port, from the override or the adapter on it
+    pub(super) fn settle_delay(&self, ports: &[SerialPortInfo]) -> std::time::Duration {
+        self.link
+            .settle_delay(ports.iter().find(|p| p.port == self.port))
+    }
+
+    /// CI-V address, if the protocol uses one and it parses
+    fn parsed_civ_address(&self) -> Option<u8> {
+        let text = self.civ_address.trim();
+        let text = text
+            .strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"))
+            .unwrap_or(text);
+        u8::from_str_radix(text, 16).ok()
+    }
+
+    /// Everything wrong with the current values; the dialog can't be saved until empty
+    ///
+    /// `ports_in_use` holds the ports of other radios and the amplifier.
+    pub(super) fn problems(&self, ports_in_use: &[String]) -> Vec<String> {
+        let mut problems = Vec::new();
+        let port = self.port.trim();
+
+        if port.is_empty() {
+            problems.push(if self.link.is_flrig() {
+                tr!("radio-dialog-needs-flrig-address")
+            } else if self.is_network() {
+                tr!("radio-dialog-needs-server-address")
+            } else {
+                tr!("radio-dialog-needs-port")
+            });
+        } else if ports_in_use.iter().any(|p| p == port) {
+            problems.push(tr!("radio-dialog-port-in-use", port = port));
+        }
+        if self.is_virtual() {
+            return problems;
+        }
+
+        if self.is_network() && !port.is_empty() && !is_host_port(port) {
+            problems.push(tr!("radio-dialog-not-host-port", address = port));
+        }
+
+        if self.protocol == Protocol::IcomCIV {
+            match self.parsed_civ_address() {
+                Some(0x01..=0xDF) => {}
+                Some(addr) => problems.push(tr!(
+                    "radio-dialog-civ-reserved",
+                    address = format!("{:02X}", addr)
+                )),
+                None => problems.push(tr!("radio-dialog-civ-not-hex")),
+            }
+        }
+
+        if !self.is_network() {
+            if self.link.data_bits == SerialDataBits::Seven
+                && matches!(self.protocol, Protocol::IcomCIV | Protocol::Yaesu)
+            {
+                problems.push(tr!(
+                    "radio-dialog-needs-8-bits",
+                    protocol = self.protocol.name()
+                ));
+            }
+            if self.flow_control == SerialFlowControl::Hardware
+                && self.link.rts != LineLevel::Unchanged
+            {
+                problems.push(tr!("radio-dialog-rts-with-hardware-flow"));
+            }
+        }
+
+        if self.link.is_flrig() && self.link.poll_interval_ms == 0 {
+            problems.push(tr!("radio-dialog-flrig-needs-polling"));
+        } else if self.link.poll_interval_ms != 0
+            && self.link.poll_interval_ms < MIN_POLL_INTERVAL_MS
+        {
+            problems.push(tr!("radio-dialog-poll-too-fast", ms = MIN_POLL_INTERVAL_MS));
+        }
+        if self.link.settle_ms.is_some_and(|ms| ms > MAX_SETTLE_MS) {
+            problems.push(tr!("radio-dialog-settle-too-long", ms = MAX_SETTLE_MS));
+        }
+        if self.link.command_gap_ms > MAX_COMMAND_GAP_MS {
+            problems.push(tr!("radio-dialog-gap-too-long", ms = MAX_COMMAND_GAP_MS));
+        }
+        if self.link.calibration_hz.abs() > MAX_CALIBRATION_HZ {
+            problems.push(tr!(
+                "radio-dialog-calibration-too-large",
+                hz = MAX_CALIBRATION_HZ
+            ));
+        }
+        if parse_mhz(&self.transverter_mhz).is_none() {
+            problems.push(tr!("radio-dialog-transverter-not-mhz"));
+        }
+
+        problems
+    }
+
+    /// Model name saved when none is entered (kept in English, like detected names)
+    fn default_model_name(&self) -> String {
+        format!("{} Radio", self.protocol.name())
+    }
+
+    /// The configuration to save; only meaningful once [`Self::problems`] is empty
+    pub(super) fn to_config(&self, id: ChannelId, usb: AdapterPort) -> ConfiguredRadio {
+        let model_name = if self.model_name.trim().is_empty() {
+            self.default_model_name()
+        } else {
+            self.model_name.trim().to_string()
+        };
+        let alias = self.alias.trim();
+        let mut link = self.link.clone();
+        link.transverter_offset_hz = parse_mhz(&self.transverter_mhz).unwrap_or(0);
+        ConfiguredRadio {
+            id: Some(id),
+            port: self.port.trim().to_string(),
+            protocol: self.protocol,
+            model_name,
+            baud_rate: self.baud_rate,
+            civ_address: (self.protocol == Protocol::IcomCIV)
+                .then(|| self.parsed_civ_address())
+                .flatten(),
+            flow_control: self.flow_control,
+            usb_serial: usb.serial,
+            usb_interface: usb.interface,
+            alias: (!alias.is_empty()).then(|| alias.to_string()),
+            input_throttle_ms: 0,
+            disabled: false,
+            monitor: None,
+            link,
+        }
+    }
+}
+
+/// Where a serial port sits on its USB adapter, as far as it is known
+#[derive(Debug, Clone, Default)]
+pub(super) struct AdapterPort {
+    pub(super) serial: Option<String>,
+    pub(super) interface: Option<u8>,
+}
+
+/// Whether `address` looks like "host:port"
+fn is_host_port(address: &str) -> bool {
+    address
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok_and(|p| p > 0))
+}
+
+/// Parse a frequency typed in MHz into Hz (empty = 0)
+fn parse_mhz(text: &str) -> Option<i64> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Some(0);
+    }
+    let mhz: f64 = text.parse().ok()?;
+    mhz.is_finite().then(|| (mhz * 1_000_000.0).round() as i64)
+}
+
+/// Format Hz as MHz without trailing zeros
+fn format_mhz(hz: i64) -> String {
+    let text = format!("{:.6}", hz as f64 / 1_000_000.0);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+impl CatapultApp {
+    /// Open the dialog to add a radio
+    pub(super) fn open_add_radio_dialog(&mut self) {
+        self.radio_dialog = Some(RadioDialog::default());
+    }
+
+    /// Open the dialog to edit a configured COM radio
+    pub(super) fn open_edit_radio_dialog(&mut self, idx: usize) {
+        let Some(config) =
+            self.settings.configured_radios.iter().find(|c| {
+                Some(&c.channel_id()) == self.radio_panels.get(idx).map(|p| &p.channel_id)
+            })
+        else {
+            return;
+        };
+        self.radio_dialog = Some(RadioDialog::edit(config));
+    }
+
+    /// Ports taken by radios other than the one being edited, and by the amplifier
+    fn ports_in_use_except(&self, editing: Option<&ChannelId>) -> Vec<String> {
+        let mut ports: Vec<String> = self
+            .radio_panels
+            .iter()
+            .filter(|p| Some(&p.channel_id) != editing)
+            .map(|p| p.port.clone())
+            .collect();
+        if !self.amp_port.is_empty() {
+            ports.push(self.amp_port.clone());
+        }
+        ports
+    }
+
+    /// Draw the dialog while it is open
+    pub(super) fn draw_radio_dialog(&mut self, ctx: &egui::Context) {
+        let Some(dialog) = &self.radio_dialog else {
+            return;
+        };
+
+        // Ports to offer: free ones, plus the edited radio's own
+        let mut ports: Vec<(String, String)> = self
+            .available_radio_ports()
+            .into_iter()
+            .filter(|p| dialog.editing.is_none() || !p.is_virtual())
+            .map(|p| (p.port_name(), p.display_label()))
+            .collect();
+        if !dialog.port.is_empty()
+            && !dialog.is_network()
+            && !ports.iter().any(|(name, _)| *name == dialog.port)
+        {
+            ports.insert(0, (dialog.port.clone(), dialog.port.clone()));
+        }
+        let problems = dialog.problems(&self.ports_in_use_except(dialog.editing()));
+        let probing = self.probing;
+        let busy = probing || self.civ_sweep_running();
+        let bluetooth = self
+            .available_ports
+            .iter()
+            .any(|p| p.port == dialog.port && p.bluetooth);
+        let adapter = self
+            .available_ports
+            .iter()
+            .find(|p| p.port == dialog.port)
+            .and_then(adapter_for);
+
+        let Some(dialog) = &mut self.radio_dialog else {
+            return;
+        };
+        let sweep = self.civ_sweep.as_ref();
+        let mut probe = false;
+        let mut sweep_action = None;
+        let mut save = false;
+        let mut cancel = false;
+
+        let title = if dialog.editing.is_some() {
+            "Edit Radio"
+        } else {
+            "Add Radio"
+        };
+        let modal = egui::Modal::new(Id::new("radio_dialog")).show(ctx, |ui| {
+            ui.set_width(420.0);
+            ui.heading(title);
+            ui.add_space(4.0);
+
+            egui::ScrollArea::vertical()
+                .max_height(ctx.content_rect().height() * 0.7)
+                .show(ui, |ui| {
+                    draw_connection_section(ui, dialog, &ports, bluetooth, adapter);
+                    ui.add_space(8.0);
+                    draw_radio_section(ui, dialog, busy, &mut probe);
+                    if dialog.protocol == Protocol::IcomCIV && !dialog.is_virtual() {
+                        civ_sweep::draw_civ_sweep_section(
+                            ui,
+                            dialog,
+                            sweep,
+                            busy,
+                            &mut sweep_action,
+                        );
+                    }
+                    if !dialog.is_virtual() {
+                        ui.add_space(8.0);
+                        draw_polling_section(ui, dialog);
+                        ui.add_space(8.0);
+                        draw_frequency_section(ui, dialog);
+                    }
+                });
+
+            ui.add_space(8.0);
+            for problem in &problems {
+                ui.label(RichText::new(problem).color(Color32::from_rgb(255, 100, 100)));
+            }
+            ui.separator();
+            ui.horizontal(|ui| {
+                let label = if dialog.editing.is_some() {
+                    tr!("radio-dialog-save")
+                } else {
+                    tr!("radio-dialog-add")
+                };
+                save = ui
+                    .add_enabled(problems.is_empty() && !busy, egui::Button::new(label))
+                    .clicked();
+                cancel = ui.button(tr!("cancel")).clicked();
+            });
+        });
+
+        if probe {
+            self.probe_selected_port();
+        }
+        if let Some(action) = sweep_action {
+            self.apply_civ_sweep_action(action);
+        }
+        if save {
+            if let Some(dialog) = self.radio_dialog.take() {
+                self.drop_civ_sweep();
+                self.save_radio_dialog(dialog);
+            }
+        } else if cancel || modal.should_close() {
+            self.drop_civ_sweep();
+            self.radio_dialog = None;
+        }
+    }
+
+    /// Add the dialog's radio, or apply its changes to the edited one
+    fn save_radio_dialog(&mut self, dialog: RadioDialog) {
+        if let Some(channel_id) = dialog.editing.clone() {
+            self.apply_radio_edit(&channel_id, &dialog);
+            return;
+        }
+        if let Some(name) = dialog.port.strip_prefix("VSIM:") {
+            match self
+                .settings
+                .virtual_ports
+                .iter()
+                .find(|v| v.name == name)
+                .cloned()
+            {
+                Some(vport) => {
+                    self.add_virtual_radio(&vport.name, vport.protocol);
+                }
+                None => {
+                    self.report_warning(
+                        "Radio",
+                        tr!("radio-dialog-virtual-port-missing", port = name),
+                    );
+                }
+            }
+            return;
+        }
+
+        let usb = self.adapter_port_for(&dialog);
+        let id = self.new_channel_id(dialog.port.trim(), &usb);
+        let config = dialog.to_config(id, usb);
+        self.add_com_radio(config);
+    }
+
+    /// USB serial number and interface of the adapter on the dialog's port
+    fn adapter_port_for(&self, dialog: &RadioDialog) -> AdapterPort {
+        if dialog.is_network() {
+            return AdapterPort::default();
+        }
+        self.available_ports
+            .iter()
+            .find(|p| p.port == dialog.port.trim())
+            .map(|p| AdapterPort {
+                serial: p.serial_number.clone(),
+                interface: p.interface,
+            })
+            .unwrap_or_default()
+    }
+
+    /// ID for a radio newly configured on `port`
+    ///
+    /// An adapter that doesn't report which of its ports this is would give
+    /// a second radio on it the first one's ID; that one goes by its port.
+    fn new_channel_id(&self, port: &str, usb: &AdapterPort) -> ChannelId {
+        let id = ChannelId::for_serial_port(port, usb.serial.as_deref(), usb.interface);
+        if self.radio_panels.iter().any(|p| p.channel_id == id) {
+            ChannelId::port(port)
+        } else {
+            id
+        }
+    }
+
+    /// Apply an edited configuration, reconnecting the radio with it
+    fn apply_radio_edit(&mut self, channel_id: &ChannelId, dialog: &RadioDialog) {
+        let Some(idx) = self
+            .radio_panels
+            .iter()
+            .position(|p| &p.channel_id == channel_id)
+        else {
+            return;
+        };
+
+        let panel = &self.radio_panels[idx];
+        // A new port is a new channel unless the same adapter just moved
+        let (id, usb) = if dialog.port.trim() == panel.port {
+            let usb = AdapterPort {
+                serial: panel.usb_serial.clone(),
+                interface: panel.usb_interface,
+            };
+            (panel.channel_id.clone(), usb)
+        } else {
+            let usb = self.adapter_port_for(dialog);
+            (self.new_channel_id(dialog.port.trim(), &usb), usb)
+        };
+        let config = dialog.to_config(id, usb);
+
+        let panel = &mut self.radio_panels[idx];
+        panel.channel_id = config.channel_id();
+        panel.port = config.port.clone();
+        panel.usb_serial = config.usb_serial;
+        panel.usb_interface = config.usb_interface;
+        panel.protocol = config.protocol;
+        panel.model_name = config.model_name;
+        panel.alias = config.alias;
+        panel.baud_rate = config.baud_rate;
+        panel.flow_control = config.flow_control.into();
+        panel.civ_address = config.civ_address;
+        panel.link = config.link;
+        panel.unavailable = false;
+        let handle = panel.handle;
+        let disabled = panel.disabled;
+
+        // Line settings and offsets only take effect on a new connection
+        if let Some(handle) = handle {
+            if let Some(sender) = self.radio_task_senders.remove(&handle) {
+                Self::send_radio_task_command(
+                    &sender.task_cmd_tx,
+                    cat_mux::RadioTaskCommand::Shutdown,
+                    "Shutdown (edit)",
+                );
+            }
+        }
+        if !disabled {
+            self.reconnect_radio(idx);
+        }
+
+        self.refresh_radio_names();
+        self.save_configured_radios();
+        self.set_status(tr!("radio-dialog-updated", port = config.port.clone()));
+    }
+}
+
+/// Transport, port and serial line settings
+///
+/// `bluetooth` is set when the selected port is a Bluetooth serial link, and
+/// `adapter` when it is on a USB adapter with a known settle time.
+fn draw_connection_section(
+    ui: &mut Ui,
+    dialog: &mut RadioDialog,
+    ports: &[(String, String)],
+    bluetooth: bool,
+    adapter: Option<&UsbAdapter>,
+) {
+    ui.label(RichText::new(tr!("radio-dialog-connection")).strong());
+    egui::Grid::new("radio_dialog_connection")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(tr!("radio-dialog-transport"));
+            ui.horizontal(|ui| {
+                let prev = dialog.link.transport;
+                ui.selectable_value(
+                    &mut dialog.link.transport,
+                    RadioTransportKind::Serial,
+                    tr!("radio-dialog-serial-port"),
+                );
+                ui.selectable_value(&mut dialog.link.transport, RadioTransportKind::Tcp, "TCP")
+                    .on_hover_text(tr!("radio-dialog-tcp-hover"));
+                ui.selectable_value(
+                    &mut dialog.link.transport,
+                    RadioTransportKind::Flrig,
+                    "flrig",
+                )
+                .on_hover_text(tr!("radio-dialog-flrig-hover"));
+                if dialog.link.transport != prev {
+                    dialog.port.clear();
+                    dialog.model_name.clear();
+                    if dialog.link.is_flrig() {
+                        dialog.port = DEFAULT_FLRIG_ADDR.to_string();
+                    }
+                }
+            });
+            ui.end_row();
+
+            if dialog.is_network() {
+                ui.label(tr!("address"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut dialog.port)
+                        .hint_text(tr!("host-port-hint"))
+                        .desired_width(200.0),
+                );
+                ui.end_row();
+                if dialog.link.is_flrig() {
+                    ui.label("");
+                    ui.checkbox(&mut dialog.link.flrig_push, tr!("radio-dialog-flrig-push"))
+                        .on_hover_text(tr!("radio-dialog-flrig-push-hover"));
+                    ui.end_row();
+                }
+                return;
+            }
+
+            ui.label(tr!("port"));
+            let selected = ports
+                .iter()
+                .find(|(name, _)| *name == dialog.port)
+                .map(|(_, label)| label.clone())
+                .unwrap_or_else(|| tr!("select-port-menu"));
+            let prev_port = dialog.port.clone();
+            egui::ComboBox::from_id_salt("radio_dialog_port")
+                .selected_text(selected)
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for (name, label) in ports {
+                        ui.selectable_value(&mut dialog.port, name.clone(), label);
+                    }
+                });
+            if dialog.port != prev_port {
+                dialog.model_name.clear();
+            }
+            ui.end_row();
+
+            if bluetooth {
+                ui.label("");
+                ui.label(
+                    RichText::new(tr!("radio-dialog-bluetooth-pair"))
+                        .small()
+                        .color(Color32::GRAY),
+                )
+                .on_hover_text(pairing_hint());
+                ui.end_row();
+            }
+
+            if dialog.is_virtual() {
+                return;
+            }
+
+            ui.label(tr!("baud"));
+            egui::ComboBox::from_id_salt("radio_dialog_baud")
+                .selected_text(dialog.baud_rate.to_string())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for baud in BAUD_RATES {
+                        ui.selectable_value(&mut dialog.baud_rate, baud, baud.to_string());
+                    }
+                });
+            ui.end_row();
+            if bluetooth {
+                ui.label("");
+                ui.label(
+                    RichText::new(tr!("radio-dialog-bluetooth-baud"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+                ui.end_row();
+            }
+
+            ui.label(tr!("radio-dialog-format"));
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("radio_dialog_data_bits")
+                    .selected_text(match dialog.link.data_bits {
+                        SerialDataBits::Seven => "7",
+                        SerialDataBits::Eight => "8",
+                    })
+                    .width(40.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut dialog.link.data_bits, SerialDataBits::Eight, "8");
+                        ui.selectable_value(&mut dialog.link.data_bits, SerialDataBits::Seven, "7");
+                    });
+                egui::ComboBox::from_id_salt("radio_dialog_parity")
+                    .selected_text(match dialog.link.parity {
+                        SerialParity::None => tr!("none"),
+                        SerialParity::Odd => tr!("radio-dialog-parity-odd"),
+                        SerialParity::Even => tr!("radio-dialog-parity-even"),
+                    })
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut dialog.link.parity,
+                            SerialParity::None,
+                            tr!("none"),
+                        );
+                        ui.selectable_value(
+                            &mut dialog.link.parity,
+                            SerialParity::Odd,
+                            tr!("radio-dialog-parity-odd"),
+                        );
+                        ui.selectable_value(
+                            &mut dialog.link.parity,
+                            SerialParity::Even,
+                            tr!("radio-dialog-parity-even"),
+                        );
+                    });
+                egui::ComboBox::from_id_salt("radio_dialog_stop_bits")
+                    .selected_text(match dialog.link.stop_bits {
+                        SerialStopBits::One => tr!("radio-dialog-stop-bits", bits = 1),
+                        SerialStopBits::Two => tr!("radio-dialog-stop-bits", bits = 2),
+                    })
+                    .width(60.0)
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut dialog.link.stop_bits,
+                            SerialStopBits::One,
+                            tr!("radio-dialog-stop-bits", bits = 1),
+                        );
+                        ui.selectable_value(
+                            &mut dialog.link.stop_bits,
+                            SerialStopBits::Two,
+                            tr!("radio-dialog-stop-bits", bits = 2),
+                        );
+                    });
+            });
+            ui.end_row();
+
+            ui.label(tr!("radio-dialog-flow-control"));
+            egui::ComboBox::from_id_salt("radio_dialog_flow_control")
+                .selected_text(flow_control_name(dialog.flow_control))
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for fc in [
+                        SerialFlowControl::None,
+                        SerialFlowControl::Software,
+                        SerialFlowControl::Hardware,
+                    ] {
+                        ui.selectable_value(&mut dialog.flow_control, fc, flow_control_name(fc));
+                    }
+                });
+            ui.end_row();
+
+            ui.label("DTR:");
+            line_level_combo(ui, "radio_dialog_dtr", &mut dialog.link.dtr)
+                .on_hover_text(tr!("radio-dialog-dtr-hover"));
+            ui.end_row();
+            ui.label("RTS:");
+            line_level_combo(ui, "radio_dialog_rts", &mut dialog.link.rts)
+                .on_hover_text(tr!("radio-dialog-rts-hover"));
+            ui.end_row();
+
+            ui.label(tr!("radio-dialog-settle"))
+                .on_hover_text(tr!("radio-dialog-settle-hover"));
+            ui.horizontal(|ui| {
+                let default = adapter.map_or(DEFAULT_SETTLE, |a| a.settle);
+                let mut custom = dialog.link.settle_ms.is_some();
+                if ui
+                    .checkbox(&mut custom, tr!("radio-dialog-settle-custom"))
+                    .changed()
+                {
+                    dialog.link.settle_ms = custom.then_some(default.as_millis() as u32);
+                }
+                match &mut dialog.link.settle_ms {
+                    Some(ms) => {
+                        ui.add(
+                            egui::DragValue::new(ms)
+                                .range(0..=MAX_SETTLE_MS)
+                                .suffix(" ms"),
+                        );
+                    }
+                    None => {
+                        let source = adapter.map_or_else(
+                            || tr!("radio-dialog-settle-default"),
+                            |a| a.chip.to_string(),
+                        );
+                        ui.label(
+                            RichText::new(format!("{} ms ({})", default.as_millis(), source))
+                                .small()
+                                .color(Color32::GRAY),
+                        );
+                    }
+                }
+            });
+            ui.end_row();
+        });
+}
+
+/// Protocol, CI-V address, model detection and alias
+fn draw_radio_section(ui: &mut Ui, dialog: &mut RadioDialog, probing: bool, probe: &mut bool) {
+    ui.label(RichText::new(tr!("radio-dialog-radio")).strong());
+    if dialog.is_virtual() {
+        ui.label(
+            RichText::new(tr!("radio-dialog-virtual"))
+                .small()
+                .color(Color32::GRAY),
+        );
+        return;
+    }
+    egui::Grid::new("radio_dialog_radio")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(tr!("protocol"));
+            egui::ComboBox::from_id_salt("radio_dialog_protocol")
+                .selected_text(dialog.protocol.name())
+                .width(200.0)
+                .show_ui(ui, |ui| {
+                    for proto in PROTOCOLS {
+                        ui.selectable_value(&mut dialog.protocol, proto, proto.name());
+                    }
+                });
+            ui.end_row();
+
+            if dialog.protocol == Protocol::IcomCIV {
+                ui.label(tr!("civ-address"));
+                ui.add(egui::TextEdit::singleline(&mut dialog.civ_address).desired_width(50.0));
+                ui.end_row();
+            }
+            if dialog.protocol == Protocol::Elecraft {
+                ui.label("");
+                ui.checkbox(&mut dialog.link.kat500, tr!("radio-dialog-kat500"))
+                    .on_hover_text(tr!("radio-dialog-kat500-hover"));
+                ui.end_row();
+            }
+
+            ui.label(tr!("radio-dialog-model"));
+            let default_model = dialog.default_model_name();
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut dialog.model_name)
+                        .hint_text(default_model)
+                        .desired_width(140.0),
+                );
+                if probing {
+                    ui.spinner();
+                } else {
+                    let can_probe = !dialog.port.is_empty() && !dialog.is_network();
+                    *probe = ui
+                        .add_enabled(can_probe, egui::Button::new(tr!("radio-dialog-detect")))
+                        .on_hover_text(tr!("radio-dialog-detect-hover"))
+                        .clicked();
+                }
+            });
+            ui.end_row();
+
+            ui.label(tr!("radio-dialog-alias"));
+            ui.add(
+                egui::TextEdit::singleline(&mut dialog.alias)
+                    .hint_text(tr!("radio-dialog-alias-hint"))
+                    .desired_width(140.0),
+            );
+            ui.end_row();
+        });
+}
+
+/// Idle polling and command pacing
+fn draw_polling_section(ui: &mut Ui, dialog: &mut RadioDialog) {
+    ui.label(RichText::new(tr!("radio-dialog-polling")).strong());
+    egui::Grid::new("radio_dialog_polling")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(tr!("radio-dialog-poll-idle"))
+                .on_hover_text(tr!("radio-dialog-poll-idle-hover"));
+            ui.add(
+                egui::DragValue::new(&mut dialog.link.poll_interval_ms)
+                    .range(0..=10_000)
+                    .suffix(" ms"),
+            );
+            ui.end_row();
+
+            ui.label(tr!("radio-dialog-command-gap"))
+                .on_hover_text(tr!("radio-dialog-command-gap-hover"));
+            ui.add(
+                egui::DragValue::new(&mut dialog.link.command_gap_ms)
+                    .range(0..=MAX_COMMAND_GAP_MS)
+                    .suffix(" ms"),
+            );
+            ui.end_row();
+        });
+}
+
+/// Calibration and transverter offsets
+fn draw_frequency_section(ui: &mut Ui, dialog: &mut RadioDialog) {
+    ui.label(RichText::new(tr!("radio-dialog-frequency")).strong());
+    egui::Grid::new("radio_dialog_frequency")
+        .num_columns(2)
+        .spacing([10.0, 6.0])
+        .show(ui, |ui| {
+            ui.label(tr!("radio-dialog-calibration"))
+                .on_hover_text(tr!("radio-dialog-calibration-hover"));
+            ui.add(
+                egui::DragValue::new(&mut dialog.link.calibration_hz)
+                    .range(-MAX_CALIBRATION_HZ..=MAX_CALIBRATION_HZ)
+                    .suffix(" Hz"),
+            );
+            ui.end_row();
+
+            ui.label(tr!("radio-dialog-transverter"))
+                .on_hover_text(tr!("radio-dialog-transverter-hover"));
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::TextEdit::singleline(&mut dialog.transverter_mhz)
+                        .hint_text(tr!("radio-dialog-transverter-hint"))
+                        .desired_width(80.0),
+                );
+                ui.label("MHz");
+            });
+            ui.end_row();
+        });
+}
+
+fn flow_control_name(fc: SerialFlowControl) -> String {
+    match fc {
+        SerialFlowControl::None => tr!("none"),
+        SerialFlowControl::Software => tr!("radio-dialog-flow-software"),
+        SerialFlowControl::Hardware => tr!("radio-dialog-flow-hardware"),
+    }
+}
+
+fn line_level_combo(ui: &mut Ui, id: &str, level: &mut LineLevel) -> egui::Response {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(level.name())
+        .width(200.0)
+        .show_ui(ui, |ui| {
+            for l in [LineLevel::Unchanged, LineLevel::Low, LineLevel::High] {
+                ui.selectable_value(level, l, l.name());
+            }
+        })
+        .response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serial_dialog() -> RadioDialog {
+        RadioDialog {
+            port: "/dev/ttyUSB0".to_string(),
+            ..RadioDialog::default()
+        }
+    }
+
+    #[test]
+    fn test_valid_serial_radio() {
+        let mut dialog = serial_dialog();
+        dialog.transverter_mhz = "116".to_string();
+        assert!(dialog.problems(&[]).is_empty());
+
+        let config = dialog.to_config(ChannelId::port("/dev/ttyUSB0"), AdapterPort::default());
+        assert_eq!(config.model_name, "Kenwood Radio");
+        assert_eq!(config.civ_address, None);
+        assert_eq!(config.link.transverter_offset_hz, 116_000_000);
+        assert_eq!(RadioDialog::edit(&config).transverter_mhz, "116");
+    }
+
+    #[test]
+    fn test_problems() {
+        let mut dialog = serial_dialog();
+        assert_eq!(
+            dialog.problems(&["/dev/ttyUSB0".to_string()]),
+            vec!["/dev/ttyUSB0 is already in use"]
+        );
+
+        dialog.protocol = Protocol::IcomCIV;
+        dialog.civ_address = "E0".to_string();
+        dialog.link.data_bits = SerialDataBits::Seven;
+        dialog.link.poll_interval_ms = 10;
+        assert_eq!(dialog.problems(&[]).len(), 3);
+
+        let mut dialog = RadioDialog::default();
+        dialog.link.transport = RadioTransportKind::Tcp;
+        dialog.port = "radio.local".to_string();
+        assert_eq!(
+            dialog.problems(&[]),
+            vec!["radio.local is not a host:port address"]
+        );
+        dialog.port = "radio.local:4532".to_string();
+        assert!(dialog.problems(&[]).is_empty());
+
+        dialog.link.transport = RadioTransportKind::Flrig;
+        dialog.link.poll_interval_ms = 0;
+        assert_eq!(
+            dialog.problems(&[]),
+            vec!["Radios behind flrig must be polled"]
+        );
+    }
+
+    #[test]
+    fn test_settle_delay_override() {
+        use std::time::Duration;
+
+        let ch340 = SerialPortInfo {
+            port: "/dev/ttyUSB0".to_string(),
+            vid: Some(0x1A86),
+            pid: Some(0x7523),
+            serial_number: None,
+            interface: None,
+            manufacturer: None,
+            product: None,
+            bluetooth: false,
+        };
+        let mut dialog = serial_dialog();
+        assert_eq!(
+            dialog.settle_delay(std::slice::from_ref(&ch340)),
+            Duration::from_millis(500)
+        );
+        assert_eq!(dialog.settle_delay(&[]), DEFAULT_SETTLE);
+
+        dialog.link.settle_ms = Some(800);
+        assert_eq!(dialog.settle_delay(&[ch340]), Duration::from_millis(800));
+        dialog.link.settle_ms = Some(MAX_SETTLE_MS + 1);
+        assert_eq!(
+            dialog.problems(&[]),
+            vec!["Settle time can be at most 5000 ms"]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Hamlib NET rigctl server (settings section)
+
+use std::net::SocketAddr;
+
+use cat_mux::run_rigctl_server;
+use egui::{Color32, RichText, Ui};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the rigctl server is listening
+    pub(super) fn rigctl_running(&self) -> bool {
+        // The server task drops its shutdown receiver when it exits
+        self.rigctl_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Draw the rigctl server controls (settings panel)
+    pub(super) fn draw_rigctl_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("rigctl-heading"));
+        ui.label(
+            RichText::new(tr!("rigctl-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.rigctl_running();
+        let prev = self.settings.rigctl.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("address"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.rigctl.address).desired_width(160.0),
+            );
+            ui.checkbox(&mut self.settings.rigctl.enabled, tr!("serve"));
+            if running {
+                ui.label(RichText::new(tr!("listening")).color(Color32::GREEN));
+            }
+        });
+
+        if self.settings.rigctl != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.rigctl.enabled != prev.enabled {
+                if self.settings.rigctl.enabled {
+                    self.start_rigctl_server();
+                } else {
+                    self.stop_rigctl_server();
+                }
+            }
+        }
+    }
+
+    /// Start accepting rigctl clients on the configured address
+    pub(super) fn start_rigctl_server(&mut self) {
+        let address = self.settings.rigctl.address.trim().to_string();
+        let addr: SocketAddr = match address.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                self.report_err(
+                    "rigctl",
+                    tr!("invalid-address", address = address.to_string()),
+                );
+                return;
+            }
+        };
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.rigctl_shutdown_tx = Some(shutdown_tx);
+        let mux = self.mux.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let serve = async {
+                let listener = TcpListener::bind(addr).await?;
+                run_rigctl_server(listener, mux).await
+            };
+            // Dropping the server disconnects its clients
+            let result = tokio::select! {
+                _ = shutdown_rx => return,
+                result = serve => result,
+            };
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::RigctlError {
+                    message: format!("{}: {}", addr, e),
+                });
+            }
+        });
+
+        self.set_status(tr!("rigctl-serving-on", address = addr.to_string()));
+    }
+
+    /// Stop the rigctl server and disconnect its clients
+    fn stop_rigctl_server(&mut self) {
+        if let Some(tx) = self.rigctl_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
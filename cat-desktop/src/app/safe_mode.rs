@@ -0,0 +1,297 @@
+//! Safe-mode startup and the recovery dialog
+
+use cat_mux::ChannelId;
+use egui::{Color32, RichText};
+
+use crate::settings::Settings;
+use crate::settings_store::Backup;
+
+use super::{AmplifierConnectionType, CatapultApp};
+use crate::i18n::{format_date_time, tr};
+
+/// State of a safe-mode session
+pub(crate) struct SafeMode {
+    /// Unfinished startups that led to safe mode
+    failed_starts: u32,
+    /// Radio the last startup was connecting, with a display label
+    suspect: Option<(ChannelId, String)>,
+    /// Settings read from disk at startup, put into effect on leaving
+    saved: Settings,
+    /// Why the settings file couldn't be used, if that's what led here
+    unreadable: Option<UnreadableSettings>,
+    /// Whether the recovery dialog is open
+    show_dialog: bool,
+}
+
+/// A settings file that failed to load
+struct UnreadableSettings {
+    error: String,
+    /// Newest backup that loads, with its contents
+    backup: Option<(Backup, Settings)>,
+}
+
+impl SafeMode {
+    /// Describe the safe-mode session, looking the suspect up in the saved settings
+    pub(crate) fn new(failed_starts: u32, suspect: Option<&ChannelId>, saved: Settings) -> Self {
+        let suspect = suspect.map(|channel| {
+            let label = saved
+                .configured_radios
+                .iter()
+                .find(|r| r.channel_id() == *channel)
+                .map(|r| {
+                    let name = r.alias.as_deref().unwrap_or(&r.model_name);
+                    format!("{} on {}", name, r.port)
+                })
+                .unwrap_or_else(|| channel.to_string());
+            (channel.clone(), label)
+        });
+        Self {
+            failed_starts,
+            suspect,
+            saved,
+            unreadable: None,
+            show_dialog: true,
+        }
+    }
+
+    /// Describe a session started because the settings file couldn't be loaded
+    pub(crate) fn unreadable_settings(error: String, backup: Option<(Backup, Settings)>) -> Self {
+        Self {
+            failed_starts: 0,
+            suspect: None,
+            saved: Settings::default(),
+            unreadable: Some(UnreadableSettings { error, backup }),
+            show_dialog: true,
+        }
+    }
+}
+
+impl CatapultApp {
+    /// Draw the safe-mode indicator (toolbar)
+    pub(super) fn draw_safe_mode_indicator(&mut self, ui: &mut egui::Ui) {
+        let Some(safe_mode) = &mut self.safe_mode else {
+            return;
+        };
+        ui.label(
+            RichText::new(tr!("safe-mode-banner"))
+                .color(Color32::from_rgb(255, 170, 60))
+                .strong(),
+        );
+        if ui.button(tr!("safe-mode-recovery")).clicked() {
+            safe_mode.show_dialog = true;
+        }
+        ui.separator();
+    }
+
+    /// Draw the recovery dialog while it is open
+    pub(super) fn draw_safe_mode_dialog(&mut self, ctx: &egui::Context) {
+        let Some(safe_mode) = &self.safe_mode else {
+            return;
+        };
+        if !safe_mode.show_dialog {
+            return;
+        }
+
+        if safe_mode.unreadable.is_some() {
+            self.draw_unreadable_settings_dialog(ctx);
+            return;
+        }
+
+        let failed_starts = safe_mode.failed_starts;
+        let suspect = safe_mode.suspect.clone();
+        let mut disable = false;
+        let mut resume = false;
+        let mut close = false;
+
+        egui::Window::new(tr!("safe-mode-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(tr!("safe-mode-failed-starts", count = failed_starts));
+                ui.label(tr!("safe-mode-explanation"));
+                if let Some((_, label)) = &suspect {
+                    ui.add_space(8.0);
+                    ui.label(tr!("safe-mode-suspect", radio = label.clone()));
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if let Some((_, label)) = &suspect {
+                        if ui
+                            .button(tr!("safe-mode-disable-radio"))
+                            .on_hover_text(tr!(
+                                "safe-mode-disable-radio-hover",
+                                radio = label.clone()
+                            ))
+                            .clicked()
+                        {
+                            disable = true;
+                        }
+                    }
+                    if ui.button(tr!("safe-mode-start-normally")).clicked() {
+                        resume = true;
+                    }
+                    if ui.button(tr!("safe-mode-stay")).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if disable || resume {
+            let Some(safe_mode) = self.safe_mode.take() else {
+                return;
+            };
+            let mut saved = safe_mode.saved;
+            if disable {
+                if let Some((channel, label)) = suspect {
+                    self.disable_saved_radio(&mut saved, &channel, &label);
+                }
+            }
+            self.leave_safe_mode(saved);
+        } else if close {
+            if let Some(safe_mode) = &mut self.safe_mode {
+                safe_mode.show_dialog = false;
+            }
+        }
+    }
+
+    /// Offer the newest backup of a settings file that failed to load
+    fn draw_unreadable_settings_dialog(&mut self, ctx: &egui::Context) {
+        let Some(unreadable) = self.safe_mode.as_ref().and_then(|s| s.unreadable.as_ref()) else {
+            return;
+        };
+        let error = unreadable.error.clone();
+        let backup = unreadable.backup.as_ref().map(|(backup, _)| backup.clone());
+        let mut restore = false;
+        let mut defaults = false;
+        let mut close = false;
+
+        egui::Window::new(tr!("safe-mode-unreadable-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(RichText::new(&error).color(Color32::from_rgb(255, 170, 60)));
+                ui.label(tr!("safe-mode-unreadable-explanation"));
+                ui.add_space(8.0);
+                match &backup {
+                    Some(backup) => {
+                        ui.label(tr!(
+                            "safe-mode-backup-loads",
+                            time = format_date_time(backup.taken)
+                        ));
+                    }
+                    None => {
+                        ui.label(tr!("safe-mode-no-backup"));
+                    }
+                }
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if backup.is_some() && ui.button(tr!("safe-mode-restore-backup")).clicked() {
+                        restore = true;
+                    }
+                    if ui
+                        .button(tr!("safe-mode-start-defaults"))
+                        .on_hover_text(tr!("safe-mode-start-defaults-hover"))
+                        .clicked()
+                    {
+                        defaults = true;
+                    }
+                    if ui.button(tr!("safe-mode-decide-later")).clicked() {
+                        close = true;
+                    }
+                });
+            });
+
+        if restore {
+            if let Some(backup) = &backup {
+                match Settings::restore_backup(backup) {
+                    Ok(()) => {
+                        let restored = self
+                            .safe_mode
+                            .take()
+                            .and_then(|s| s.unreadable)
+                            .and_then(|u| u.backup)
+                            .map(|(_, settings)| settings)
+                            .unwrap_or_default();
+                        self.leave_safe_mode(restored);
+                    }
+                    Err(e) => self.handle_save_error(e),
+                }
+            }
+        } else if defaults {
+            match Settings::set_aside_unreadable() {
+                Ok(_) => self.leave_safe_mode(Settings::default()),
+                Err(e) => self.handle_save_error(e),
+            }
+        } else if close {
+            if let Some(safe_mode) = &mut self.safe_mode {
+                safe_mode.show_dialog = false;
+            }
+        }
+    }
+
+    /// Mark a radio disabled in the saved settings
+    fn disable_saved_radio(&mut self, saved: &mut Settings, channel: &ChannelId, label: &str) {
+        for radio in &mut saved.configured_radios {
+            if radio.channel_id() == *channel {
+                radio.disabled = true;
+            }
+        }
+        match saved.save() {
+            Ok(()) => self.report_info("Safe Mode", tr!("safe-mode-disabled", radio = label)),
+            Err(e) => self.handle_save_error(e),
+        }
+    }
+
+    /// Put `settings` into effect and connect everything as a normal startup would
+    fn leave_safe_mode(&mut self, settings: Settings) {
+        self.safe_mode = None;
+        self.settings = settings;
+        self.apply_memory_budget();
+        self.diagnostic_level_state
+            .set_crate_levels(&self.settings.diagnostic_crate_levels);
+        let amp = &self.settings.amplifier;
+        self.amp_connection_type = if amp.connection_type == "com" {
+            AmplifierConnectionType::ComPort
+        } else {
+            AmplifierConnectionType::Simulated
+        };
+        self.amp_port = amp.port.clone();
+        self.amp_protocol = amp.protocol;
+        self.amp_response_protocol = amp.response_protocol;
+        self.amp_strictness = amp.strictness;
+        self.amp_baud = amp.baud_rate;
+        self.amp_civ_address = amp.civ_address;
+        self.amp_flow_control = amp.flow_control;
+        self.push_mux_settings();
+        if !self.settings.analyzer.enabled {
+            self.restore_radios();
+        }
+        if self.settings.metrics.enabled {
+            self.start_metrics_server();
+        }
+        if self.settings.status_page.enabled {
+            self.start_status_page();
+        }
+        if self.settings.rigctl.enabled {
+            self.start_rigctl_server();
+        }
+        if self.settings.emulated_radio.enabled {
+            self.start_emulated_radio();
+        }
+        if self.settings.disk_log.enabled {
+            self.start_disk_log();
+        }
+        if self.settings.dx_cluster.enabled {
+            self.start_dx_cluster();
+        }
+        if self.settings.peer_sync.enabled {
+            self.start_peer_sync();
+        }
+        if self.settings.bookmarks.pin_enabled {
+            self.start_bookmark_pin();
+        }
+        self.set_status(tr!("safe-mode-left"));
+    }
+}
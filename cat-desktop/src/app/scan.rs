@@ -0,0 +1,208 @@
+//! Scanning every free serial port for radios
+
+use std::collections::BTreeSet;
+
+use cat_detect::{PortScanner, ScanCancel, ScanEvent, ScanHit};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::mpsc as tokio_mpsc;
+
+use crate::i18n::tr;
+
+use super::radio_dialog::RadioDialog;
+use super::{BackgroundMessage, CatapultApp};
+
+/// A scan in progress, or its results until dismissed
+pub(crate) struct PortScan {
+    /// Cancels the scan (Some while it runs)
+    cancel: Option<ScanCancel>,
+    /// Ports being probed right now
+    probing: BTreeSet<String>,
+    /// Ports finished, with or without a radio
+    done: usize,
+    /// Ports in the scan
+    total: usize,
+    /// Radios found so far
+    found: Vec<ScanHit>,
+}
+
+impl PortScan {
+    fn running(&self) -> bool {
+        self.cancel.is_some()
+    }
+}
+
+impl CatapultApp {
+    /// Whether a port scan is running
+    pub(super) fn port_scan_running(&self) -> bool {
+        self.port_scan.as_ref().is_some_and(PortScan::running)
+    }
+
+    /// Probe every serial port not used by a radio or the amplifier
+    pub(super) fn start_port_scan(&mut self) {
+        if self.port_scan_running() {
+            return;
+        }
+        self.refresh_ports();
+        let in_use = self.radio_ports_in_use();
+        let ports: Vec<_> = self
+            .available_ports
+            .iter()
+            .filter(|p| !in_use.contains(&p.port) && p.port != self.amp_port)
+            .cloned()
+            .collect();
+        if ports.is_empty() {
+            self.set_status(tr!("scan-no-free-ports"));
+            return;
+        }
+
+        let cancel = ScanCancel::new();
+        self.port_scan = Some(PortScan {
+            cancel: Some(cancel.clone()),
+            probing: BTreeSet::new(),
+            done: 0,
+            total: ports.len(),
+            found: Vec::new(),
+        });
+        self.set_status(tr!("scan-scanning", count = ports.len()));
+
+        let tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            let (event_tx, mut event_rx) = tokio_mpsc::unbounded_channel();
+            let forward = {
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    while let Some(event) = event_rx.recv().await {
+                        let _ = tx.send(BackgroundMessage::PortScan(event));
+                    }
+                })
+            };
+            let hits = PortScanner::new()
+                .scan_ports(ports, event_tx, cancel.clone())
+                .await;
+            // Everything the scan sent arrives before the finish
+            let _ = forward.await;
+            let _ = tx.send(BackgroundMessage::PortScanFinished {
+                found: hits.len(),
+                cancelled: cancel.is_cancelled(),
+            });
+        });
+    }
+
+    /// Stop the running scan, keeping what it found
+    pub(super) fn cancel_port_scan(&mut self) {
+        if let Some(cancel) = self.port_scan.as_ref().and_then(|s| s.cancel.as_ref()) {
+            cancel.cancel();
+        }
+    }
+
+    /// Apply a progress event from the running scan
+    pub(super) fn handle_port_scan_event(&mut self, event: ScanEvent) {
+        let Some(scan) = self.port_scan.as_mut() else {
+            return;
+        };
+        match event {
+            ScanEvent::Probing { port } => {
+                scan.probing.insert(port);
+            }
+            ScanEvent::Found(hit) => {
+                scan.probing.remove(&hit.port.port);
+                scan.done += 1;
+                scan.found.push(*hit);
+                scan.found.sort_by(|a, b| a.port.port.cmp(&b.port.port));
+            }
+            ScanEvent::NoRadio { port, .. } => {
+                scan.probing.remove(&port);
+                scan.done += 1;
+            }
+        }
+    }
+
+    /// The scan task has returned
+    pub(super) fn finish_port_scan(&mut self, found: usize, cancelled: bool) {
+        if let Some(scan) = self.port_scan.as_mut() {
+            scan.cancel = None;
+            scan.probing.clear();
+        }
+        let message = match (cancelled, found) {
+            (true, _) => format!("Scan cancelled: {} radio(s) found", found),
+            (false, 0) => "Scan finished: no radios found".to_string(),
+            (false, _) => format!("Scan finished: {} radio(s) found", found),
+        };
+        self.set_status(message);
+    }
+
+    /// Scan button, progress, and the radios found (in the Add Radio section)
+    pub(super) fn draw_port_scan(&mut self, ui: &mut Ui) {
+        let running = self.port_scan_running();
+        ui.horizontal(|ui| {
+            if running {
+                if ui.button(tr!("scan-cancel")).clicked() {
+                    self.cancel_port_scan();
+                }
+            } else if ui
+                .button(tr!("scan-start"))
+                .on_hover_text(tr!("scan-start-hover"))
+                .clicked()
+            {
+                self.start_port_scan();
+            }
+            if !running && self.port_scan.is_some() && ui.small_button(tr!("clear")).clicked() {
+                self.port_scan = None;
+            }
+        });
+
+        let Some(scan) = &self.port_scan else {
+            return;
+        };
+        if running {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(
+                    RichText::new(tr!("scan-progress", done = scan.done, total = scan.total))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+            });
+            if !scan.probing.is_empty() {
+                let probing: Vec<_> = scan.probing.iter().map(String::as_str).collect();
+                ui.label(
+                    RichText::new(tr!("scan-probing", ports = probing.join(", ")))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+            }
+        }
+
+        let in_use = self.radio_ports_in_use();
+        let mut add = None;
+        for (idx, hit) in scan.found.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let model = hit
+                    .result
+                    .model
+                    .as_ref()
+                    .map(|m| format!("{} {}", m.manufacturer, m.model))
+                    .unwrap_or_else(|| format!("{} radio", hit.result.protocol.name()));
+                ui.label(format!(
+                    "{} on {} @ {}",
+                    model, hit.port.port, hit.baud_rate
+                ));
+                if in_use.contains(&hit.port.port) {
+                    ui.label(
+                        RichText::new(tr!("scan-added"))
+                            .small()
+                            .color(Color32::GRAY),
+                    );
+                } else if ui.small_button(tr!("scan-add")).clicked() {
+                    add = Some(idx);
+                }
+            });
+        }
+
+        if let Some(idx) = add {
+            let hit = &scan.found[idx];
+            let dialog = RadioDialog::from_scan(hit);
+            self.radio_dialog = Some(dialog);
+        }
+    }
+}
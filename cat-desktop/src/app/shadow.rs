@@ -0,0 +1,429 @@
+//! Shadow amplifier A/B comparison UI
+//!
+//! A second amplifier receives everything the primary amp gets so a new amp
+//! personality can be checked against the one in service before switching.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use cat_detect::describe_open_error;
+use cat_mux::{
+    frame_text, AmplifierChannel, AmplifierChannelMeta, AsyncAmpConnection, MuxActorCommand,
+    MuxEvent, ShadowDiff, ShadowStats,
+};
+use cat_sim::{run_virtual_amp_task, VirtualAmpCommand, VirtualAmpMode, VirtualAmplifier};
+use egui::{Color32, RichText, Ui};
+use tokio::sync::{broadcast, mpsc as tokio_mpsc, oneshot};
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::i18n::tr;
+use crate::traffic_monitor::TrafficMonitor;
+
+use super::{AmplifierConnectionType, CatapultApp};
+
+/// Most mismatches kept for the report
+const MAX_SHADOW_DIFFS: usize = 500;
+
+/// Shadow amplifier selection, connection and comparison results
+pub(crate) struct ShadowAmpState {
+    /// COM port or simulated shadow
+    pub connection_type: AmplifierConnectionType,
+    /// Serial port of a real shadow amp
+    pub port: String,
+    /// Baud rate of a real shadow amp
+    pub baud: u32,
+    /// Behavior of a simulated shadow amp
+    pub virtual_mode: VirtualAmpMode,
+    /// Connection task shutdown (Some while connected)
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    /// Simulated shadow amp command sender (for shutdown)
+    virtual_cmd_tx: Option<tokio_mpsc::Sender<VirtualAmpCommand>>,
+    /// Totals for the current session
+    pub stats: ShadowStats,
+    /// Mismatching windows, oldest first
+    pub diffs: VecDeque<ShadowDiff>,
+}
+
+impl Default for ShadowAmpState {
+    fn default() -> Self {
+        Self {
+            connection_type: AmplifierConnectionType::Simulated,
+            port: String::new(),
+            baud: 9600,
+            virtual_mode: VirtualAmpMode::Polling,
+            shutdown_tx: None,
+            virtual_cmd_tx: None,
+            stats: ShadowStats::default(),
+            diffs: VecDeque::new(),
+        }
+    }
+}
+
+impl ShadowAmpState {
+    /// Whether a shadow amp is connected
+    pub fn is_connected(&self) -> bool {
+        self.shutdown_tx.is_some()
+    }
+
+    /// Record a closed comparison window
+    pub fn record(&mut self, stats: ShadowStats, diff: Option<ShadowDiff>) {
+        self.stats = stats;
+        if let Some(diff) = diff {
+            if self.diffs.len() >= MAX_SHADOW_DIFFS {
+                self.diffs.pop_front();
+            }
+            self.diffs.push_back(diff);
+        }
+    }
+
+    /// Plain-text diff report
+    pub fn report(&self) -> String {
+        let mut output = String::new();
+        output.push_str("# Catapult Shadow Amplifier Report\n");
+        output.push_str(&format!(
+            "# Windows compared: {}, mismatches: {}\n\n",
+            self.stats.windows, self.stats.mismatches
+        ));
+        for diff in &self.diffs {
+            let list = |frames: &[Vec<u8>]| {
+                frames
+                    .iter()
+                    .map(|f| frame_text(f))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            };
+            output.push_str(&format!(
+                "{}\n  sent:         {}\n  primary only: {}\n  shadow only:  {}\n",
+                TrafficMonitor::format_timestamp(&diff.timestamp),
+                if diff.stimulus.is_empty() {
+                    "(idle)".to_string()
+                } else {
+                    list(&diff.stimulus)
+                },
+                list(&diff.primary_only),
+                list(&diff.shadow_only)
+            ));
+        }
+        output
+    }
+}
+
+impl CatapultApp {
+    /// Draw the shadow amplifier controls (below the amplifier settings)
+    pub(super) fn draw_shadow_amp_section(&mut self, ui: &mut Ui) {
+        let section = egui::CollapsingHeader::new(tr!("shadow-amp-heading"))
+            .id_salt("shadow_amp")
+            .default_open(self.settings.layout.section_open("shadow_amp"))
+            .show(ui, |ui| {
+                ui.label(
+                    RichText::new(tr!("shadow-amp-description"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                let connected = self.shadow_amp.is_connected();
+                ui.add_enabled_ui(!connected, |ui| self.draw_shadow_amp_config(ui));
+
+                ui.horizontal(|ui| {
+                    let can_connect = !connected
+                        && self.amp_data_tx.is_some()
+                        && (self.shadow_amp.connection_type == AmplifierConnectionType::Simulated
+                            || !self.shadow_amp.port.is_empty());
+                    if ui
+                        .add_enabled(can_connect, egui::Button::new(tr!("connect")))
+                        .on_disabled_hover_text(tr!("shadow-amp-needs-primary"))
+                        .clicked()
+                    {
+                        self.connect_shadow_amplifier();
+                    }
+                    if ui
+                        .add_enabled(connected, egui::Button::new(tr!("disconnect")))
+                        .clicked()
+                    {
+                        self.disconnect_shadow_amplifier();
+                    }
+                    if connected {
+                        ui.label(RichText::new(tr!("shadow-amp-comparing")).color(Color32::GREEN));
+                    }
+                });
+
+                let stats = self.shadow_amp.stats;
+                if stats.windows == 0 {
+                    return;
+                }
+                let color = if stats.mismatches == 0 {
+                    Color32::GREEN
+                } else {
+                    Color32::from_rgb(255, 180, 0)
+                };
+                ui.label(
+                    RichText::new(tr!(
+                        "shadow-amp-stats",
+                        windows = stats.windows,
+                        mismatches = stats.mismatches
+                    ))
+                    .color(color),
+                );
+                egui::ScrollArea::vertical()
+                    .id_salt("shadow_amp_diffs")
+                    .max_height(120.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        for diff in &self.shadow_amp.diffs {
+                            ui.label(
+                                RichText::new(format!(
+                                    "{} {}",
+                                    TrafficMonitor::format_timestamp(&diff.timestamp),
+                                    diff.summary()
+                                ))
+                                .small()
+                                .monospace(),
+                            );
+                        }
+                    });
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("shadow-amp-save-report")).clicked() {
+                        match self.save_shadow_report() {
+                            Ok(Some(path)) => self.set_status(tr!(
+                                "shadow-amp-report-saved",
+                                path = path.display().to_string()
+                            )),
+                            Ok(None) => {}
+                            Err(e) => self.report_err("Shadow report", e),
+                        }
+                    }
+                    if ui.button(tr!("clear")).clicked() {
+                        self.shadow_amp.diffs.clear();
+                    }
+                });
+            });
+        self.settings
+            .layout
+            .remember_section("shadow_amp", &section);
+    }
+
+    /// Connection type, port and simulation mode for the shadow amp
+    fn draw_shadow_amp_config(&mut self, ui: &mut Ui) {
+        let available_ports: Vec<(String, String)> = self
+            .available_amp_ports()
+            .into_iter()
+            .filter(|p| p.port != self.amp_port)
+            .map(|p| (p.port.clone(), Self::format_port_label(p)))
+            .collect();
+        let shadow = &mut self.shadow_amp;
+
+        egui::Grid::new("shadow_amp_config")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label(tr!("amp-connection"));
+                egui::ComboBox::from_id_salt("shadow_amp_connection_type")
+                    .selected_text(match shadow.connection_type {
+                        AmplifierConnectionType::ComPort => tr!("amp-com-port"),
+                        AmplifierConnectionType::Simulated => tr!("amp-simulated"),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut shadow.connection_type,
+                            AmplifierConnectionType::ComPort,
+                            tr!("amp-com-port"),
+                        );
+                        ui.selectable_value(
+                            &mut shadow.connection_type,
+                            AmplifierConnectionType::Simulated,
+                            tr!("amp-simulated"),
+                        );
+                    });
+                ui.end_row();
+
+                match shadow.connection_type {
+                    AmplifierConnectionType::ComPort => {
+                        ui.label(tr!("port"));
+                        let selected = if shadow.port.is_empty() {
+                            tr!("select-port-menu")
+                        } else {
+                            shadow.port.clone()
+                        };
+                        egui::ComboBox::from_id_salt("shadow_amp_port")
+                            .selected_text(selected)
+                            .show_ui(ui, |ui| {
+                                for (port, label) in &available_ports {
+                                    ui.selectable_value(&mut shadow.port, port.clone(), label);
+                                }
+                            });
+                        ui.end_row();
+
+                        ui.label(tr!("baud-rate"));
+                        egui::ComboBox::from_id_salt("shadow_amp_baud")
+                            .selected_text(format!("{}", shadow.baud))
+                            .show_ui(ui, |ui| {
+                                for &baud in &[4800u32, 9600, 19200, 38400, 57600, 115200, 230400] {
+                                    ui.selectable_value(
+                                        &mut shadow.baud,
+                                        baud,
+                                        format!("{}", baud),
+                                    );
+                                }
+                            });
+                        ui.end_row();
+                    }
+                    AmplifierConnectionType::Simulated => {
+                        ui.label(tr!("amp-simulation-mode"));
+                        egui::ComboBox::from_id_salt("shadow_amp_mode")
+                            .selected_text(match shadow.virtual_mode {
+                                VirtualAmpMode::AutoInfo => tr!("amp-auto-info"),
+                                VirtualAmpMode::Polling => tr!("amp-polling"),
+                            })
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(
+                                    &mut shadow.virtual_mode,
+                                    VirtualAmpMode::AutoInfo,
+                                    tr!("amp-auto-info"),
+                                );
+                                ui.selectable_value(
+                                    &mut shadow.virtual_mode,
+                                    VirtualAmpMode::Polling,
+                                    tr!("amp-polling"),
+                                );
+                            });
+                        ui.end_row();
+                    }
+                }
+            });
+    }
+
+    /// Connect the shadow amplifier (speaks the primary amp's protocol)
+    fn connect_shadow_amplifier(&mut self) {
+        let civ_address =
+            (self.amp_protocol == cat_protocol::Protocol::IcomCIV).then_some(self.amp_civ_address);
+        let meta = match self.shadow_amp.connection_type {
+            AmplifierConnectionType::ComPort => AmplifierChannelMeta::new_real(
+                self.shadow_amp.port.clone(),
+                self.amp_protocol,
+                self.shadow_amp.baud,
+                civ_address,
+            ),
+            AmplifierConnectionType::Simulated => {
+                AmplifierChannelMeta::new_virtual(self.amp_protocol, civ_address)
+            }
+        };
+
+        let (data_tx, data_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (_response_tx, response_rx) = tokio_mpsc::channel::<Vec<u8>>(64);
+        self.send_mux_command(
+            MuxActorCommand::ConnectShadowAmplifier {
+                channel: AmplifierChannel::new(meta, data_tx, response_rx),
+            },
+            "ConnectShadowAmplifier",
+        );
+        self.shadow_amp.shutdown_tx = Some(shutdown_tx);
+        self.shadow_amp.stats = ShadowStats::default();
+        self.shadow_amp.diffs.clear();
+
+        let mux = self.mux.clone();
+
+        match self.shadow_amp.connection_type {
+            AmplifierConnectionType::ComPort => {
+                let port = self.shadow_amp.port.clone();
+                let baud = self.shadow_amp.baud;
+                self.rt_handle.spawn(async move {
+                    let stream = match tokio_serial::new(&port, baud)
+                        .timeout(Duration::from_millis(100))
+                        .open_native_async()
+                    {
+                        Ok(s) => s,
+                        Err(e) => {
+                            let _ = mux
+                                .emit(MuxEvent::Error {
+                                    source: format!("Shadow amplifier {}", port),
+                                    message: format!(
+                                        "Failed to open port: {}",
+                                        describe_open_error(&port, &e)
+                                    ),
+                                })
+                                .await;
+                            let _ = mux.send(MuxActorCommand::DisconnectShadowAmplifier).await;
+                            return;
+                        }
+                    };
+                    AsyncAmpConnection::new(stream, &mux)
+                        .as_shadow()
+                        .run(shutdown_rx, data_rx)
+                        .await;
+                });
+                self.set_status(tr!(
+                    "shadow-amp-connecting",
+                    port = self.shadow_amp.port.clone()
+                ));
+            }
+            AmplifierConnectionType::Simulated => {
+                let (mux_stream, amp_stream) = tokio::io::duplex(4096);
+                let virtual_amp =
+                    VirtualAmplifier::new("shadow-amp", self.amp_protocol, civ_address)
+                        .with_clock(self.simulation_panel.clock());
+                let (cmd_tx, cmd_rx) = tokio_mpsc::channel::<VirtualAmpCommand>(32);
+                // The shadow's state is not shown; nobody subscribes
+                let (state_tx, _) = broadcast::channel::<cat_sim::VirtualAmpStateEvent>(32);
+                self.shadow_amp.virtual_cmd_tx = Some(cmd_tx);
+
+                let mode = self.shadow_amp.virtual_mode;
+                self.rt_handle.spawn(async move {
+                    if let Err(e) =
+                        run_virtual_amp_task(amp_stream, virtual_amp, cmd_rx, state_tx, mode).await
+                    {
+                        tracing::error!("Shadow virtual amplifier task error: {}", e);
+                    }
+                });
+                self.rt_handle.spawn(async move {
+                    AsyncAmpConnection::new(mux_stream, &mux)
+                        .as_shadow()
+                        .run(shutdown_rx, data_rx)
+                        .await;
+                });
+                self.set_status(tr!("shadow-amp-simulated"));
+            }
+        }
+    }
+
+    /// Disconnect the shadow amplifier (results are kept for the report)
+    pub(super) fn disconnect_shadow_amplifier(&mut self) {
+        self.send_mux_command(
+            MuxActorCommand::DisconnectShadowAmplifier,
+            "DisconnectShadowAmplifier",
+        );
+        self.shadow_amp_stopped();
+    }
+
+    /// Stop the shadow connection tasks
+    pub(super) fn shadow_amp_stopped(&mut self) {
+        if let Some(tx) = self.shadow_amp.virtual_cmd_tx.take() {
+            let _ = tx.try_send(VirtualAmpCommand::Shutdown);
+        }
+        if let Some(tx) = self.shadow_amp.shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+
+    /// Save the diff report to a user-selected file
+    fn save_shadow_report(&self) -> Result<Option<PathBuf>, String> {
+        let default_name = format!(
+            "shadow-amp-{}.txt",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+        );
+        let Some(path) = rfd::FileDialog::new()
+            .set_file_name(&default_name)
+            .add_filter("Text files", &["txt"])
+            .save_file()
+        else {
+            return Ok(None);
+        };
+        std::fs::write(&path, self.shadow_amp.report())
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        Ok(Some(path))
+    }
+}
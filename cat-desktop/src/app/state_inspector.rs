@@ -0,0 +1,155 @@
+//! Mux state at a moment picked in the traffic monitor
+
+use std::time::SystemTime;
+
+use cat_mux::{MuxEvent, StateHistory, SwitchingMode};
+use cat_protocol::display::format_frequency;
+use egui::{Color32, RichText};
+
+use crate::i18n::tr;
+use crate::traffic_monitor::TrafficMonitor;
+
+use super::{mode_name, CatapultApp};
+
+/// State changes seen this session, and the moment being inspected
+pub(crate) struct StateInspector {
+    history: StateHistory,
+    /// Time of the traffic entry whose timestamp was clicked
+    at: Option<SystemTime>,
+}
+
+impl Default for StateInspector {
+    fn default() -> Self {
+        Self {
+            history: StateHistory::new(SwitchingMode::default()),
+            at: None,
+        }
+    }
+}
+
+impl StateInspector {
+    /// Note an event from the mux as it arrives
+    pub(super) fn record(&mut self, event: &MuxEvent) {
+        self.history.record(SystemTime::now(), event);
+    }
+
+    /// Changes held and their estimated size
+    pub(super) fn memory_usage(&self) -> (usize, usize) {
+        let n = self.history.len();
+        (n, n * cat_mux::memory::STATE_CHANGE_BYTES)
+    }
+}
+
+impl CatapultApp {
+    /// Draw the state at the clicked moment in a side panel while one is picked
+    pub(super) fn draw_state_inspector_panel(&mut self, ctx: &egui::Context) {
+        if let Some(at) = self.traffic_monitor.take_inspect_request() {
+            self.state_inspector.at = Some(at);
+        }
+        let Some(at) = self.state_inspector.at else {
+            return;
+        };
+        let mut close = false;
+
+        egui::SidePanel::right("state_inspector")
+            .default_width(280.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading(tr!(
+                        "state-inspector-title",
+                        time = TrafficMonitor::format_timestamp(&at)
+                    ));
+                    close = ui.small_button("✕").on_hover_text(tr!("close")).clicked();
+                });
+                ui.label(
+                    RichText::new(tr!("state-inspector-description"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+                ui.separator();
+
+                let Some(state) = self.state_inspector.history.at(at) else {
+                    ui.label(tr!("state-inspector-too-old"));
+                    return;
+                };
+
+                ui.label(tr!(
+                    "state-inspector-switching-mode",
+                    mode = state.switching_mode.name()
+                ));
+                let active = state
+                    .active_radio
+                    .map(|h| self.radio_label(h))
+                    .unwrap_or_else(|| tr!("none"));
+                ui.label(tr!("state-inspector-active-radio", radio = active));
+
+                let mut radios: Vec<_> = state.radios.iter().collect();
+                radios.sort_by_key(|(handle, _)| handle.0);
+                if radios.is_empty() {
+                    ui.label(RichText::new(tr!("state-inspector-no-radios")).color(Color32::GRAY));
+                } else {
+                    ui.add_space(4.0);
+                    egui::Grid::new("state_inspector_radios")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (&handle, radio) in radios {
+                                let mut name = RichText::new(self.radio_label(handle));
+                                if state.active_radio == Some(handle) {
+                                    name = name.strong().color(Color32::GREEN);
+                                } else if !radio.connected {
+                                    name = name.color(Color32::GRAY);
+                                }
+                                ui.label(name);
+                                ui.label(
+                                    radio
+                                        .frequency_hz
+                                        .map(format_frequency)
+                                        .unwrap_or_else(|| "---".to_string()),
+                                );
+                                ui.label(radio.mode.map(mode_name).unwrap_or("---"));
+                                if radio.ptt {
+                                    ui.label(RichText::new("TX").color(Color32::RED).strong());
+                                } else if !radio.connected {
+                                    ui.label(RichText::new(tr!("offline")).color(Color32::GRAY));
+                                } else {
+                                    ui.label("RX");
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+
+                if let Some(as_of) = state.as_of {
+                    let ago = at.duration_since(as_of).unwrap_or_default();
+                    ui.label(
+                        RichText::new(tr!(
+                            "state-inspector-last-change",
+                            time = TrafficMonitor::format_timestamp(&as_of),
+                            ms = ago.as_millis() as u64
+                        ))
+                        .small()
+                        .color(Color32::GRAY),
+                    );
+                }
+
+                if let Some(record) = &state.last_switch {
+                    ui.add_space(8.0);
+                    ui.label(
+                        RichText::new(tr!(
+                            "state-inspector-last-switch",
+                            id = record.id,
+                            time = TrafficMonitor::format_timestamp(&record.timestamp)
+                        ))
+                        .strong(),
+                    );
+                    for line in record.chain(|h| self.radio_label(h)) {
+                        ui.label(RichText::new(line).monospace().small());
+                    }
+                }
+            });
+
+        if close {
+            self.state_inspector.at = None;
+        }
+    }
+}
@@ -64,6 +64,8 @@ impl CatapultApp {
                 AmplifierConnectionType::Simulated => "simulated".to_string(),
             },
             protocol: self.amp_protocol,
+            response_protocol: self.amp_response_protocol,
+            strictness: self.amp_strictness,
             port: self.amp_port.clone(),
             baud_rate: self.amp_baud,
             civ_address: self.amp_civ_address,
@@ -85,12 +87,20 @@ impl CatapultApp {
             .iter()
             .filter(|p| !p.is_virtual())
             .map(|p| ConfiguredRadio {
+                id: Some(p.channel_id.clone()),
                 port: p.port.clone(),
                 protocol: p.protocol,
-                model_name: p.name.clone(),
+                model_name: p.model_name.clone(),
                 baud_rate: p.baud_rate,
                 civ_address: p.civ_address,
                 flow_control: p.flow_control.into(),
+                usb_serial: p.usb_serial.clone(),
+                usb_interface: p.usb_interface,
+                alias: p.alias.clone(),
+                input_throttle_ms: p.input_throttle_ms,
+                disabled: p.disabled,
+                monitor: p.monitor,
+                link: p.link.clone(),
             })
             .collect();
 
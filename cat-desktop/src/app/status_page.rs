@@ -0,0 +1,146 @@
+//! Remote status page and push alerts (settings section and event forwarding)
+
+use std::net::SocketAddr;
+
+use cat_mux::{observe_status, run_status_server, MuxEvent, StatusAlert};
+use egui::{Color32, RichText, Ui};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+impl CatapultApp {
+    /// Whether the status page is serving
+    pub(super) fn status_page_running(&self) -> bool {
+        // The server drops its receiver when it exits (bind error)
+        self.status_page_shutdown_tx
+            .as_ref()
+            .is_some_and(|tx| !tx.is_closed())
+    }
+
+    /// Fold a mux event into the status board the page serves, and push any
+    /// critical alert it raises
+    pub(super) fn observe_status(&mut self, event: &MuxEvent) {
+        if let Some(alert) = observe_status(&self.status_board, event) {
+            self.push_status_alert(alert);
+        }
+    }
+
+    /// Send an alert to the configured ntfy topic and webhook
+    fn push_status_alert(&self, alert: StatusAlert) {
+        let targets = self.settings.status_page.alert_targets();
+        if targets.is_empty() {
+            return;
+        }
+        let bg_tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            for message in targets.push(&alert).await {
+                let _ = bg_tx.send(BackgroundMessage::StatusPushError { message });
+            }
+        });
+    }
+
+    /// Draw the status page and push alert controls (settings panel)
+    pub(super) fn draw_status_page_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("status-page-heading"));
+        ui.label(
+            RichText::new(tr!("status-page-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let running = self.status_page_running();
+        let prev = self.settings.status_page.clone();
+
+        ui.horizontal(|ui| {
+            ui.label(tr!("status-page-address"));
+            ui.add_enabled(
+                !running,
+                egui::TextEdit::singleline(&mut self.settings.status_page.address)
+                    .desired_width(160.0),
+            )
+            .on_hover_text(tr!("status-page-address-hover"));
+            ui.checkbox(
+                &mut self.settings.status_page.enabled,
+                tr!("status-page-serve"),
+            );
+            if running {
+                ui.label(RichText::new(tr!("status-page-serving")).color(Color32::GREEN));
+            }
+        });
+        egui::Grid::new("status_push")
+            .num_columns(2)
+            .spacing([10.0, 4.0])
+            .show(ui, |ui| {
+                ui.label(tr!("status-page-ntfy"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.settings.status_page.ntfy_url)
+                        .hint_text("http://ntfy.sh/my-shack")
+                        .desired_width(260.0),
+                );
+                ui.end_row();
+                ui.label(tr!("status-page-webhook"));
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.settings.status_page.webhook_url)
+                        .hint_text("http://host/path")
+                        .desired_width(260.0),
+                );
+                ui.end_row();
+            });
+
+        if self.settings.status_page != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            if self.settings.status_page.enabled != prev.enabled {
+                if self.settings.status_page.enabled {
+                    self.start_status_page();
+                } else {
+                    self.stop_status_page();
+                }
+            }
+        }
+    }
+
+    /// Start serving the status page on the configured address
+    pub(super) fn start_status_page(&mut self) {
+        let address = self.settings.status_page.address.trim().to_string();
+        let addr: SocketAddr = match address.parse() {
+            Ok(addr) => addr,
+            Err(_) => {
+                self.report_err(
+                    "Status page",
+                    tr!("invalid-address", address = address.to_string()),
+                );
+                return;
+            }
+        };
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        self.status_page_shutdown_tx = Some(shutdown_tx);
+        let board = self.status_board.clone();
+        let bg_tx = self.bg_tx.clone();
+
+        self.rt_handle.spawn(async move {
+            let result = match TcpListener::bind(addr).await {
+                Ok(listener) => run_status_server(listener, board, shutdown_rx).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                let _ = bg_tx.send(BackgroundMessage::StatusPageError {
+                    message: format!("{}: {}", addr, e),
+                });
+            }
+        });
+
+        self.set_status(tr!("status-page-serving-on", address = addr.to_string()));
+    }
+
+    /// Stop serving the status page
+    fn stop_status_page(&mut self) {
+        if let Some(tx) = self.status_page_shutdown_tx.take() {
+            let _ = tx.send(());
+        }
+    }
+}
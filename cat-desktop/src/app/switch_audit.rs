@@ -0,0 +1,123 @@
+//! "Why did it switch?" view of the mux's switch audit log
+
+use std::collections::VecDeque;
+
+use cat_mux::{RadioHandle, SwitchRecord, AUDIT_LOG_CAPACITY};
+use egui::{Color32, RichText};
+
+use crate::i18n::tr;
+use crate::traffic_monitor::TrafficMonitor;
+
+use super::CatapultApp;
+
+/// Switch records received from the mux, newest last
+pub(crate) struct SwitchAuditView {
+    records: VecDeque<SwitchRecord>,
+    /// Most records kept
+    capacity: usize,
+    /// The window is showing
+    pub(super) open: bool,
+    /// Record whose chain is expanded
+    selected: Option<u64>,
+}
+
+impl Default for SwitchAuditView {
+    fn default() -> Self {
+        Self {
+            records: VecDeque::new(),
+            capacity: AUDIT_LOG_CAPACITY,
+            open: false,
+            selected: None,
+        }
+    }
+}
+
+impl SwitchAuditView {
+    pub(super) fn push(&mut self, record: SwitchRecord) {
+        if self.records.len() >= self.capacity {
+            self.records.pop_front();
+        }
+        // The latest switch is the one the operator is asking about
+        self.selected = Some(record.id);
+        self.records.push_back(record);
+    }
+
+    /// Keep at most `capacity` records, dropping the oldest
+    pub(super) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let excess = self.records.len().saturating_sub(self.capacity);
+        self.records.drain(..excess);
+    }
+
+    /// Records held and their estimated size
+    pub(super) fn memory_usage(&self) -> (usize, usize) {
+        let n = self.records.len();
+        (n, n * cat_mux::memory::SWITCH_RECORD_BYTES)
+    }
+}
+
+impl CatapultApp {
+    /// Name a radio the way the radio list does
+    pub(super) fn radio_label(&self, handle: RadioHandle) -> String {
+        self.radio_panels
+            .iter()
+            .find(|p| p.handle == Some(handle))
+            .map(|p| p.alias.clone().unwrap_or_else(|| p.name.clone()))
+            .unwrap_or_else(|| format!("Radio {}", handle.0))
+    }
+
+    /// Draw the switch history window while it is open
+    pub(super) fn draw_switch_audit_window(&mut self, ctx: &egui::Context) {
+        if !self.switch_audit.open {
+            return;
+        }
+        let mut open = true;
+        let mut selected = self.switch_audit.selected;
+
+        egui::Window::new(tr!("switch-audit-title"))
+            .open(&mut open)
+            .default_width(520.0)
+            .show(ctx, |ui| {
+                if self.switch_audit.records.is_empty() {
+                    ui.label(tr!("switch-audit-none"));
+                    return;
+                }
+                ui.label(
+                    RichText::new(tr!("switch-audit-hint"))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for record in self.switch_audit.records.iter().rev() {
+                            let from = record
+                                .from
+                                .map(|h| self.radio_label(h))
+                                .unwrap_or_else(|| "none".to_string());
+                            let heading = format!(
+                                "#{}  {}  {} -> {}",
+                                record.id,
+                                TrafficMonitor::format_timestamp(&record.timestamp),
+                                from,
+                                self.radio_label(record.to)
+                            );
+                            let is_selected = selected == Some(record.id);
+                            if ui.selectable_label(is_selected, heading).clicked() {
+                                selected = if is_selected { None } else { Some(record.id) };
+                            }
+                            if is_selected {
+                                ui.indent(("switch_chain", record.id), |ui| {
+                                    for line in record.chain(|h| self.radio_label(h)) {
+                                        ui.label(RichText::new(line).monospace());
+                                    }
+                                });
+                            }
+                        }
+                    });
+            });
+
+        self.switch_audit.selected = selected;
+        self.switch_audit.open = open;
+    }
+}
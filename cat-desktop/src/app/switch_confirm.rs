@@ -0,0 +1,84 @@
+//! Prompt for confirming a manual switch away from a transmitting radio
+
+use std::time::Duration;
+
+use cat_mux::{MuxActorCommand, PendingSwitch, RadioHandle};
+use egui::{Color32, RichText};
+use tokio::time::Instant;
+
+use crate::i18n::tr;
+
+use super::CatapultApp;
+
+impl CatapultApp {
+    /// Hold the prompt for a switch the mux is waiting to have confirmed
+    pub(super) fn show_switch_confirmation(
+        &mut self,
+        token: u64,
+        requested: RadioHandle,
+        current: RadioHandle,
+        timeout_ms: u64,
+    ) {
+        self.pending_switch = Some(PendingSwitch {
+            token,
+            requested,
+            current,
+            expires: Instant::now() + Duration::from_millis(timeout_ms),
+        });
+    }
+
+    /// Draw the confirmation prompt while a switch is pending
+    pub(super) fn draw_switch_confirm_dialog(&mut self, ctx: &egui::Context) {
+        let Some(pending) = self.pending_switch else {
+            return;
+        };
+        let remaining = pending.expires.saturating_duration_since(Instant::now());
+        let mut answer = None;
+
+        egui::Window::new(tr!("switch-confirm-title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.label(tr!(
+                    "switch-confirm-question",
+                    current = self.radio_label(pending.current),
+                    requested = self.radio_label(pending.requested)
+                ));
+                ui.label(
+                    RichText::new(tr!(
+                        "switch-confirm-countdown",
+                        radio = self.radio_label(pending.current),
+                        seconds = remaining.as_secs() + 1
+                    ))
+                    .small()
+                    .color(Color32::GRAY),
+                );
+                ui.add_space(8.0);
+                ui.horizontal(|ui| {
+                    if ui.button(tr!("switch-confirm-switch")).clicked() {
+                        answer = Some(true);
+                    }
+                    if ui.button(tr!("switch-confirm-stay")).clicked() {
+                        answer = Some(false);
+                    }
+                });
+            });
+
+        if let Some(confirm) = answer {
+            // The mux closes the request either way; don't wait for it to
+            // take the prompt down
+            self.pending_switch = None;
+            self.send_mux_command(
+                MuxActorCommand::ConfirmSwitch {
+                    token: pending.token,
+                    confirm,
+                },
+                "ConfirmSwitch",
+            );
+        } else {
+            // Keep the countdown moving
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Transmit statistics window: time on the air and duty cycle per radio and band
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use cat_mux::{MuxActorCommand, RadioHandle, TxBandStats};
+use cat_protocol::display::format_decimal;
+use egui::{Color32, RichText};
+
+use crate::i18n::tr;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// How often the open window asks the mux for fresh numbers
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Transmit statistics received from the mux
+#[derive(Default)]
+pub(crate) struct TxStatsView {
+    /// The window is showing
+    pub(super) open: bool,
+    stats: Vec<TxBandStats>,
+    last_query: Option<Instant>,
+    /// Radios over the duty-cycle alert threshold, with their duty cycle
+    pub(super) alerts: HashMap<RadioHandle, f32>,
+}
+
+impl TxStatsView {
+    pub(super) fn update(&mut self, stats: Vec<TxBandStats>) {
+        self.stats = stats;
+    }
+}
+
+/// "1h 02m", "4m 05s", "12.3s"
+fn format_tx_time(d: Duration) -> String {
+    let secs = d.as_secs();
+    if secs >= 3600 {
+        format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60)
+    } else if secs >= 60 {
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", format_decimal(d.as_secs_f64(), 1))
+    }
+}
+
+impl CatapultApp {
+    /// A radio's duty cycle went over (or back under) the alert threshold
+    pub(super) fn handle_duty_cycle_alert(
+        &mut self,
+        handle: RadioHandle,
+        duty_cycle: f32,
+        over: bool,
+    ) {
+        if over {
+            self.tx_stats.alerts.insert(handle, duty_cycle);
+            let window_mins = self.settings.duty_cycle_alert.window_secs / 60;
+            self.report_warning(
+                "Duty Cycle",
+                tr!(
+                    "tx-stats-duty-alert",
+                    radio = self.radio_label(handle),
+                    percent = format_decimal(f64::from(duty_cycle) * 100.0, 0),
+                    minutes = window_mins
+                ),
+            );
+        } else {
+            self.tx_stats.alerts.remove(&handle);
+        }
+    }
+
+    /// Ask the mux for transmit statistics (answered with a background message)
+    fn query_tx_stats(&mut self) {
+        self.tx_stats.last_query = Some(Instant::now());
+        let reply = match self.mux.tx_stats() {
+            Ok(reply) => reply,
+            Err(e) => {
+                Self::log_mux_send_error("QueryTxStats", e);
+                return;
+            }
+        };
+        let bg_tx = self.bg_tx.clone();
+        self.rt_handle.spawn(async move {
+            if let Ok(stats) = reply.await {
+                let _ = bg_tx.send(BackgroundMessage::TxStats(stats));
+            }
+        });
+    }
+
+    /// Draw the transmit statistics window while it is open
+    pub(super) fn draw_tx_stats_window(&mut self, ctx: &egui::Context) {
+        if !self.tx_stats.open {
+            return;
+        }
+        if self
+            .tx_stats
+            .last_query
+            .is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL)
+        {
+            self.query_tx_stats();
+        }
+        ctx.request_repaint_after(REFRESH_INTERVAL);
+
+        let mut open = true;
+        let mut reset = false;
+        let prev_alert = self.settings.duty_cycle_alert;
+
+        egui::Window::new(tr!("tx-stats-title"))
+            .open(&mut open)
+            .default_width(480.0)
+            .show(ctx, |ui| {
+                let window_secs = self.settings.duty_cycle_alert.window_secs;
+                if self.tx_stats.stats.is_empty() {
+                    ui.label(tr!("tx-stats-none"));
+                } else {
+                    egui::Grid::new("tx_stats_grid")
+                        .num_columns(6)
+                        .striped(true)
+                        .spacing([12.0, 4.0])
+                        .show(ui, |ui| {
+                            for heading in [
+                                tr!("tx-stats-radio"),
+                                tr!("tx-stats-band"),
+                                tr!("tx-stats-tx"),
+                                tr!("tx-stats-total"),
+                                tr!("tx-stats-longest"),
+                                tr!("tx-stats-duty"),
+                            ] {
+                                ui.label(RichText::new(heading).strong());
+                            }
+                            ui.end_row();
+
+                            for stats in &self.tx_stats.stats {
+                                ui.label(self.radio_label(stats.handle));
+                                ui.label(stats.band.unwrap_or("-"));
+                                ui.label(stats.transmissions.to_string());
+                                ui.label(format_tx_time(stats.total));
+                                ui.label(format_tx_time(stats.longest));
+                                let duty = format!(
+                                    "{}%",
+                                    format_decimal(f64::from(stats.duty_cycle) * 100.0, 0)
+                                );
+                                if self.tx_stats.alerts.contains_key(&stats.handle) {
+                                    ui.label(
+                                        RichText::new(duty).color(Color32::from_rgb(255, 165, 0)),
+                                    );
+                                } else {
+                                    ui.label(duty);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                }
+                ui.label(
+                    RichText::new(tr!("tx-stats-duty-note", minutes = window_secs / 60))
+                        .small()
+                        .color(Color32::GRAY),
+                );
+
+                ui.separator();
+                let alert = &mut self.settings.duty_cycle_alert;
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut alert.enabled, tr!("tx-stats-alert-above"))
+                        .on_hover_text(tr!("tx-stats-alert-hover"));
+                    ui.add_enabled(
+                        alert.enabled,
+                        egui::DragValue::new(&mut alert.threshold_pct)
+                            .range(5..=100)
+                            .suffix("%"),
+                    );
+                    ui.label(tr!("tx-stats-over"));
+                    let mut minutes = alert.window_secs / 60;
+                    if ui
+                        .add(
+                            egui::DragValue::new(&mut minutes)
+                                .range(1..=120)
+                                .suffix(" min"),
+                        )
+                        .changed()
+                    {
+                        alert.window_secs = minutes * 60;
+                    }
+                });
+                if ui
+                    .button(tr!("tx-stats-reset"))
+                    .on_hover_text(tr!("tx-stats-reset-hover"))
+                    .clicked()
+                {
+                    reset = true;
+                }
+            });
+
+        self.tx_stats.open = open;
+        if reset {
+            self.send_mux_command(MuxActorCommand::ResetTxStats, "ResetTxStats");
+            self.query_tx_stats();
+        }
+        if self.settings.duty_cycle_alert != prev_alert {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            self.send_mux_command(
+                MuxActorCommand::SetDutyCycleAlert {
+                    config: self.settings.duty_cycle_alert,
+                },
+                "SetDutyCycleAlert",
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_tx_time() {
+        assert_eq!(format_tx_time(Duration::from_millis(12_340)), "12.3s");
+        assert_eq!(format_tx_time(Duration::from_secs(245)), "4m 05s");
+        assert_eq!(format_tx_time(Duration::from_secs(3720)), "1h 02m");
+    }
+}
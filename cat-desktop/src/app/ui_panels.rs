@@ -1,12 +1,25 @@
 //! UI panel drawing methods
 
-use cat_mux::{MuxActorCommand, RadioHandle, SwitchingMode};
-use cat_protocol::{OperatingMode, Protocol};
-use cat_sim::VirtualRadioCommand;
-use egui::{Color32, RichText, Ui};
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
 
+use cat_detect::latency_timer::{latency_timer_hint, RECOMMENDED_LATENCY_MS};
+use cat_mux::civ_echo::echo_back_advice;
+use cat_mux::{
+    AmpRole, BandGuardStrictness, ChannelId, MonitorConfig, MuxActorCommand, RadioHandle,
+    RadioProfile, RadioTaskCommand, SwitchingMode,
+};
+use cat_protocol::display::{format_decimal, format_frequency};
+use cat_protocol::yaesu_ascii::YaesuAsciiCommand;
+use cat_protocol::yaesu_menu::MenuTable;
+use cat_protocol::{EncodeCommand, Frequency, OperatingMode, Protocol, Vfo};
+use cat_sim::{ResponderRule, VirtualRadioCommand};
+use egui::{Color32, Id, RichText, Ui};
+
+use crate::i18n::tr;
+use crate::meters::{s_unit_label, MeterBallistics, RadioMeters};
 use crate::radio_panel::ConnectionState;
-use crate::settings::SerialFlowControl;
+use crate::simulation_panel::{CLOCK_RATES, CLOCK_STEP};
 use crate::traffic_monitor::ExportAction;
 
 use super::{mode_name, AmplifierConnectionType, CatapultApp};
@@ -17,44 +30,86 @@ impl CatapultApp {
         ui.horizontal(|ui| {
             // Console toggle button
             if self.show_traffic_monitor {
-                if ui.button("Hide Console").clicked() {
+                if ui.button(tr!("toolbar-hide-console")).clicked() {
                     self.show_traffic_monitor = false;
                 }
-            } else if ui.button("Show Console").clicked() {
+            } else if ui.button(tr!("toolbar-show-console")).clicked() {
                 self.show_traffic_monitor = true;
             }
 
             ui.separator();
 
-            if ui.button("Settings").clicked() {
+            self.draw_safe_mode_indicator(ui);
+
+            if ui.button(tr!("toolbar-settings")).clicked() {
                 self.show_settings = !self.show_settings;
             }
 
+            let mut analyzer = self.settings.analyzer.enabled;
+            if ui
+                .toggle_value(&mut analyzer, tr!("toolbar-analyzer"))
+                .on_hover_text(tr!("toolbar-analyzer-hover"))
+                .changed()
+            {
+                self.set_analyzer_mode(analyzer);
+            }
+
+            ui.toggle_value(&mut self.switch_audit.open, tr!("toolbar-switch-history"))
+                .on_hover_text(tr!("toolbar-switch-history-hover"));
+
+            ui.toggle_value(&mut self.tx_stats.open, tr!("toolbar-tx-stats"))
+                .on_hover_text(tr!("toolbar-tx-stats-hover"));
+
+            if ui
+                .button(tr!("toolbar-report-problem"))
+                .on_hover_text(tr!("toolbar-report-problem-hover"))
+                .clicked()
+            {
+                self.open_bug_report();
+            }
+
             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                 // Active radio indicator
                 let has_active = self.active_radio.is_some();
                 if has_active {
                     ui.label(RichText::new("*").color(Color32::GREEN).size(16.0));
-                    ui.label("Active");
+                    ui.label(tr!("toolbar-active"));
                 } else {
                     ui.label(RichText::new("o").color(Color32::GRAY).size(16.0));
-                    ui.label("No radio");
+                    ui.label(tr!("toolbar-no-radio"));
                 }
 
                 ui.separator();
 
                 // Amplifier status
+                match self.backup_amp.failed_over_to() {
+                    Some(AmpRole::Backup) => {
+                        ui.label(
+                            RichText::new(tr!("toolbar-amp-backup"))
+                                .color(Color32::from_rgb(255, 120, 0)),
+                        );
+                    }
+                    Some(_) => {
+                        ui.label(RichText::new(tr!("toolbar-amp-bypassed")).color(Color32::RED));
+                    }
+                    None => {}
+                }
                 match self.amp_connection_type {
                     AmplifierConnectionType::ComPort => {
                         if self.amp_data_tx.is_some() {
-                            ui.label(RichText::new("Amp: Connected").color(Color32::GREEN));
+                            ui.label(
+                                RichText::new(tr!("toolbar-amp-connected")).color(Color32::GREEN),
+                            );
                         } else {
-                            ui.label(RichText::new("Amp: Disconnected").color(Color32::GRAY));
+                            ui.label(
+                                RichText::new(tr!("toolbar-amp-disconnected")).color(Color32::GRAY),
+                            );
                         }
                     }
                     AmplifierConnectionType::Simulated => {
                         ui.label(
-                            RichText::new("Amp: Simulated").color(Color32::from_rgb(100, 180, 255)),
+                            RichText::new(tr!("toolbar-amp-simulated"))
+                                .color(Color32::from_rgb(100, 180, 255)),
                         );
                     }
                 }
@@ -71,17 +126,43 @@ impl CatapultApp {
 
     /// Draw the radio list panel (unified COM and Virtual radios)
     pub(super) fn draw_radio_panel(&mut self, ui: &mut Ui) {
-        ui.heading("Radios");
+        ui.heading(tr!("radios-heading"));
+
+        if self.radio_panels.iter().any(|p| p.is_virtual())
+            || self.amp_connection_type == AmplifierConnectionType::Simulated
+        {
+            self.draw_sim_clock_controls(ui);
+        }
 
         if self.radio_panels.is_empty() {
-            ui.label("No radios. Open Settings to add a radio.");
+            ui.label(tr!("radios-none"));
             return;
         }
 
         // Get active radio handle for comparison
         let active_handle = self.active_radio;
 
+        if let Some(peer) = &self.peer_block {
+            ui.label(
+                RichText::new(tr!("radio-peer-block", peer = peer.summary()))
+                    .color(Color32::from_rgb(255, 165, 0)),
+            );
+        }
+
+        if let Some((_, spot)) = self
+            .dx_spot_match
+            .as_ref()
+            .filter(|(h, _)| Some(*h) == active_handle)
+        {
+            ui.label(
+                RichText::new(tr!("radio-dx-spot", spot = spot.summary()))
+                    .color(Color32::LIGHT_BLUE),
+            )
+            .on_hover_text(&spot.comment);
+        }
+
         // Collect radio info from local RadioPanel state
+        let expanded_radios = &self.settings.layout.expanded_radios;
         let radio_info: Vec<_> = self
             .radio_panels
             .iter()
@@ -91,7 +172,7 @@ impl CatapultApp {
                 let freq = panel.frequency_hz.unwrap_or(0);
                 let mode = panel.mode.unwrap_or(OperatingMode::Usb);
                 let freq_display = if freq > 0 {
-                    format!("{:.3} MHz", freq as f64 / 1_000_000.0)
+                    format_frequency(Frequency::from_hz(freq))
                 } else {
                     "---.--- MHz".to_string()
                 };
@@ -103,15 +184,39 @@ impl CatapultApp {
                     panel.name.clone(),
                     panel.port.clone(),
                     panel.is_virtual(),
-                    panel.sim_id().map(String::from),
-                    panel.expanded,
+                    panel.is_virtual().then(|| panel.channel_id.clone()),
+                    expanded_radios.contains(&panel.channel_id),
                     panel.protocol,
                     freq_display,
                     mode_display,
                     panel.ptt,
+                    panel.power_watts,
                     freq,
                     mode,
                     panel.connection_state,
+                    panel.stale,
+                    panel.last_seen,
+                    panel.sync_progress,
+                    panel.alias.clone().unwrap_or_default(),
+                    panel.usb_serial.clone(),
+                    panel.menu_table(),
+                    panel.menu_values.clone(),
+                    panel.meters,
+                    panel.input_throttle_ms,
+                    panel.reassembly,
+                    panel.latency_timer_ms,
+                    self.available_ports
+                        .iter()
+                        .find(|p| p.port == panel.port)
+                        .and_then(latency_timer_hint),
+                    panel.civ_echo_back,
+                    self.simulation_panel
+                        .radio_state(&panel.channel_id)
+                        .filter(|_| panel.is_virtual())
+                        .cloned(),
+                    panel.disabled,
+                    panel.monitor,
+                    panel.profile.as_ref().map(RadioProfile::summary),
                 )
             })
             .collect::<Vec<_>>();
@@ -119,9 +224,26 @@ impl CatapultApp {
         let mut selected_handle: Option<RadioHandle> = None;
         let mut toggle_expanded_idx = None;
         let mut remove_radio_idx = None;
-        let mut freq_change: Option<(String, u64)> = None;
-        let mut mode_change: Option<(String, OperatingMode)> = None;
-        let mut ptt_change: Option<(String, bool)> = None;
+        let mut freq_change: Option<(ChannelId, u64)> = None;
+        let mut mode_change: Option<(ChannelId, OperatingMode)> = None;
+        let mut ptt_change: Option<(Option<RadioHandle>, ChannelId, bool)> = None;
+        let mut alias_change: Option<(usize, String)> = None;
+        let mut throttle_change: Option<(usize, u32)> = None;
+        let mut monitor_change: Option<(usize, Option<MonitorConfig>)> = None;
+        let mut protocol_change: Option<(usize, Protocol)> = None;
+        let mut sim_command: Option<(ChannelId, VirtualRadioCommand)> = None;
+        let mut enable_idx: Option<usize> = None;
+        let mut edit_idx: Option<usize> = None;
+        let mut menu_send: Option<(RadioHandle, YaesuAsciiCommand)> = None;
+        let mut latency_fix_idx: Option<usize> = None;
+        let mut reprofile_idx: Option<usize> = None;
+        let mut freq_entry: Option<(usize, String, bool)> = None;
+        let mut sections: Vec<(String, egui::CollapsingResponse<()>)> = Vec::new();
+        let layout = &self.settings.layout;
+        let ballistics = self.settings.meter_ballistics;
+        let band_conflicts = self.band_conflicts.clone();
+        let duty_alerts = self.tx_stats.alerts.clone();
+        let now = Instant::now();
 
         for (
             idx,
@@ -129,15 +251,33 @@ impl CatapultApp {
             name,
             port,
             is_virtual,
-            sim_id,
+            sim_channel,
             expanded,
             protocol,
             freq_display,
             mode_display,
             ptt,
+            power_watts,
             freq_hz,
             mode,
             connection_state,
+            stale,
+            last_seen,
+            sync_progress,
+            alias,
+            usb_serial,
+            menu_table,
+            menu_values,
+            meters,
+            input_throttle_ms,
+            reassembly,
+            latency_timer_ms,
+            latency_hint,
+            civ_echo_back,
+            sim_state,
+            disabled,
+            monitor,
+            profile_summary,
         ) in &radio_info
         {
             let is_active = handle.is_some() && active_handle == *handle;
@@ -167,6 +307,11 @@ impl CatapultApp {
                 .inner_margin(8.0)
                 .outer_margin(4.0)
                 .show(ui, |ui| {
+                    // Dim radios that have gone quiet (likely powered off)
+                    if *stale {
+                        ui.multiply_opacity(0.5);
+                    }
+
                     // Top row: Connection indicator, TX indicator, and Select/Expand button
                     ui.horizontal(|ui| {
                         // Connection state indicator (skip for virtual radios - always connected)
@@ -186,6 +331,80 @@ impl CatapultApp {
                                 .on_hover_text(tooltip);
                         }
 
+                        if *disabled {
+                            ui.label(
+                                RichText::new(tr!("radio-disabled-tag"))
+                                    .color(Color32::GRAY)
+                                    .small(),
+                            )
+                            .on_hover_text(tr!("radio-disabled-hover"));
+                        }
+
+                        if let Some(monitor) = monitor {
+                            ui.label(
+                                RichText::new(tr!("radio-rx-only"))
+                                    .color(Color32::LIGHT_BLUE)
+                                    .small(),
+                            )
+                            .on_hover_text(if monitor.follow_active {
+                                tr!("radio-rx-only-following-hover")
+                            } else {
+                                tr!("radio-rx-only-hover")
+                            });
+                        }
+
+                        if let Some(summary) = profile_summary {
+                            ui.label(
+                                RichText::new(tr!("radio-profiled-tag"))
+                                    .color(Color32::GRAY)
+                                    .small(),
+                            )
+                            .on_hover_text(summary);
+                        }
+
+                        if *stale {
+                            let ago = last_seen
+                                .and_then(|t| t.elapsed().ok())
+                                .map(|d| tr!("radio-no-data-for", seconds = d.as_secs()))
+                                .unwrap_or_else(|| tr!("radio-no-data"));
+                            ui.label(
+                                RichText::new(tr!("radio-idle"))
+                                    .color(Color32::GRAY)
+                                    .small(),
+                            )
+                            .on_hover_text(tr!("radio-idle-hover", ago = ago));
+                        }
+
+                        if let Some(band) = handle.and_then(|h| band_conflicts.get(&h)) {
+                            ui.label(
+                                RichText::new(tr!("radio-same-band", band = band.to_string()))
+                                    .color(Color32::from_rgb(255, 165, 0))
+                                    .small(),
+                            )
+                            .on_hover_text(tr!("radio-same-band-hover"));
+                        }
+
+                        if let Some(duty) = handle.and_then(|h| duty_alerts.get(&h)) {
+                            ui.label(
+                                RichText::new(tr!(
+                                    "radio-duty",
+                                    percent = format_decimal(f64::from(*duty) * 100.0, 0)
+                                ))
+                                .color(Color32::from_rgb(255, 165, 0))
+                                .small(),
+                            )
+                            .on_hover_text(tr!("radio-duty-hover"));
+                        }
+
+                        if let Some((step, total)) = sync_progress {
+                            ui.label(
+                                RichText::new(tr!("radio-syncing", step = step, total = total))
+                                    .color(Color32::GRAY)
+                                    .small(),
+                            )
+                            .on_hover_text(tr!("radio-syncing-hover"));
+                        }
+
                         if *ptt {
                             ui.label(
                                 RichText::new("* TX")
@@ -196,11 +415,19 @@ impl CatapultApp {
                         }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if !is_active && ui.button("Select").clicked() {
+                            if !is_active
+                                && monitor.is_none()
+                                && ui.button(tr!("radio-select")).clicked()
+                            {
                                 selected_handle = *handle;
                             }
                             // Expand/collapse toggle
-                            if ui.button(if *expanded { "Less" } else { "More" }).clicked() {
+                            let toggle = if *expanded {
+                                tr!("radio-less")
+                            } else {
+                                tr!("radio-more")
+                            };
+                            if ui.button(toggle).clicked() {
                                 toggle_expanded_idx = Some(*idx);
                             }
                         });
@@ -214,12 +441,27 @@ impl CatapultApp {
                             .color(Color32::WHITE),
                     );
 
-                    // Mode - prominent
-                    ui.label(
-                        RichText::new(mode_display)
-                            .size(16.0)
-                            .color(Color32::from_rgb(180, 180, 255)),
-                    );
+                    // Mode - prominent, with output power alongside once reported
+                    ui.horizontal(|ui| {
+                        ui.label(
+                            RichText::new(mode_display)
+                                .size(16.0)
+                                .color(Color32::from_rgb(180, 180, 255)),
+                        );
+                        if let Some(watts) = power_watts {
+                            ui.label(
+                                RichText::new(format!("{} W", watts))
+                                    .size(14.0)
+                                    .color(Color32::from_rgb(255, 200, 120)),
+                            )
+                            .on_hover_text(tr!("radio-power-hover"));
+                        }
+                    });
+
+                    draw_meters(ui, meters, *ptt, now, &ballistics);
+                    if meters.is_animating(now, &ballistics) {
+                        ui.ctx().request_repaint();
+                    }
 
                     ui.add_space(4.0);
 
@@ -242,14 +484,14 @@ impl CatapultApp {
 
                     // Expanded controls for virtual radios
                     if *is_virtual && *expanded {
-                        if let Some(sim_id) = sim_id {
+                        if let Some(sim_channel) = sim_channel {
                             ui.add_space(8.0);
                             ui.separator();
                             ui.add_space(4.0);
 
                             // Band presets
                             ui.horizontal_wrapped(|ui| {
-                                ui.label(RichText::new("Band:").small());
+                                ui.label(RichText::new(tr!("sim-band")).small());
                                 for (band_name, band_freq) in &[
                                     ("160m", 1_900_000u64),
                                     ("80m", 3_750_000),
@@ -261,14 +503,14 @@ impl CatapultApp {
                                     ("2m", 146_520_000),
                                 ] {
                                     if ui.small_button(*band_name).clicked() {
-                                        freq_change = Some((sim_id.clone(), *band_freq));
+                                        freq_change = Some((sim_channel.clone(), *band_freq));
                                     }
                                 }
                             });
 
                             // Tune buttons
                             ui.horizontal(|ui| {
-                                ui.label(RichText::new("Tune:").small());
+                                ui.label(RichText::new(tr!("sim-tune")).small());
                                 for (label, delta) in [
                                     ("-10k", -10_000i64),
                                     ("-1k", -1_000),
@@ -277,14 +519,124 @@ impl CatapultApp {
                                 ] {
                                     if ui.small_button(label).clicked() {
                                         let new_freq = (*freq_hz as i64 + delta).max(0) as u64;
-                                        freq_change = Some((sim_id.clone(), new_freq));
+                                        freq_change = Some((sim_channel.clone(), new_freq));
                                     }
                                 }
                             });
 
+                            // VFO selection, split and the unselected VFO
+                            if let Some(sim) = sim_state {
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new("VFO:").small());
+                                    for (label, vfo) in [("A", Vfo::A), ("B", Vfo::B)] {
+                                        let selected = sim.rx_vfo == vfo;
+                                        let button =
+                                            egui::Button::new(label).small().fill(if selected {
+                                                Color32::from_rgb(60, 80, 60)
+                                            } else {
+                                                Color32::from_rgb(40, 40, 40)
+                                            });
+                                        if ui.add(button).clicked() && !selected {
+                                            sim_command = Some((
+                                                sim_channel.clone(),
+                                                VirtualRadioCommand::SelectVfo(vfo),
+                                            ));
+                                        }
+                                    }
+                                    let split_button = egui::Button::new(tr!("sim-split"))
+                                        .small()
+                                        .fill(if sim.split {
+                                            Color32::from_rgb(150, 100, 40)
+                                        } else {
+                                            Color32::from_rgb(40, 40, 40)
+                                        });
+                                    if ui
+                                        .add(split_button)
+                                        .on_hover_text(tr!("sim-split-hover"))
+                                        .clicked()
+                                    {
+                                        sim_command = Some((
+                                            sim_channel.clone(),
+                                            VirtualRadioCommand::SetSplit(!sim.split),
+                                        ));
+                                    }
+                                    if ui
+                                        .small_button("A=B")
+                                        .on_hover_text(tr!("sim-equalize-hover"))
+                                        .clicked()
+                                    {
+                                        sim_command = Some((
+                                            sim_channel.clone(),
+                                            VirtualRadioCommand::EqualizeVfos,
+                                        ));
+                                    }
+                                });
+
+                                let other = sim.other_vfo();
+                                ui.horizontal(|ui| {
+                                    ui.label(
+                                        RichText::new(format!(
+                                            "VFO {:?}{}: {}",
+                                            other,
+                                            if sim.split { " (TX)" } else { "" },
+                                            format_frequency(Frequency::from_hz(
+                                                sim.other_frequency_hz
+                                            ))
+                                        ))
+                                        .small(),
+                                    );
+                                    for (label, delta) in [("-1k", -1_000i64), ("+1k", 1_000)] {
+                                        if ui.small_button(label).clicked() {
+                                            let new_freq = (sim.other_frequency_hz as i64 + delta)
+                                                .max(0)
+                                                as u64;
+                                            sim_command = Some((
+                                                sim_channel.clone(),
+                                                VirtualRadioCommand::SetVfoFrequency(
+                                                    other, new_freq,
+                                                ),
+                                            ));
+                                        }
+                                    }
+                                });
+
+                                // Auto-responder rules
+                                let rules_section = format!("sim_rules:{}", sim_channel);
+                                let rules = egui::CollapsingHeader::new(
+                                    RichText::new(tr!("sim-rules", count = sim.rules.len()))
+                                        .small(),
+                                )
+                                .id_salt(Id::new("sim_rules").with(*idx))
+                                .default_open(layout.section_open(&rules_section))
+                                .show(ui, |ui| {
+                                    if let Some(rules) = draw_sim_rules(ui, *protocol, &sim.rules) {
+                                        sim_command = Some((
+                                            sim_channel.clone(),
+                                            VirtualRadioCommand::SetRules(rules),
+                                        ));
+                                    }
+                                });
+                                sections.push((rules_section, rules));
+
+                                // Fault injection
+                                let faults_section = format!("sim_faults:{}", sim_channel);
+                                let faults_id = Id::new("sim_faults").with(*idx);
+                                let faults = egui::CollapsingHeader::new(
+                                    RichText::new(tr!("sim-faults")).small(),
+                                )
+                                .id_salt(faults_id)
+                                .default_open(layout.section_open(&faults_section))
+                                .show(ui, |ui| {
+                                    if let Some(cmd) = draw_sim_faults(ui, faults_id, *mode) {
+                                        sim_command = Some((sim_channel.clone(), cmd));
+                                    }
+                                });
+                                sections.push((faults_section, faults));
+                            }
+
                             // Mode buttons
                             ui.horizontal_wrapped(|ui| {
-                                ui.label(RichText::new("Mode:").small());
+                                ui.label(RichText::new(tr!("sim-mode")).small());
                                 for m in [
                                     OperatingMode::Lsb,
                                     OperatingMode::Usb,
@@ -302,7 +654,7 @@ impl CatapultApp {
                                         },
                                     );
                                     if ui.add(button).clicked() {
-                                        mode_change = Some((sim_id.clone(), m));
+                                        mode_change = Some((sim_channel.clone(), m));
                                     }
                                 }
                             });
@@ -316,7 +668,7 @@ impl CatapultApp {
                                     Color32::from_rgb(50, 50, 50)
                                 });
                                 if ui.add(ptt_button).clicked() {
-                                    ptt_change = Some((sim_id.clone(), !*ptt));
+                                    ptt_change = Some((*handle, sim_channel.clone(), !*ptt));
                                 }
 
                                 ui.with_layout(
@@ -324,7 +676,7 @@ impl CatapultApp {
                                     |ui| {
                                         if ui
                                             .button(
-                                                RichText::new("Remove")
+                                                RichText::new(tr!("remove"))
                                                     .color(Color32::from_rgb(255, 100, 100)),
                                             )
                                             .clicked()
@@ -343,13 +695,222 @@ impl CatapultApp {
                         ui.separator();
                         ui.add_space(4.0);
 
+                        // Alias (edited in egui temp memory, committed on focus loss)
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(tr!("radio-dialog-alias")).small());
+                            let edit_id = Id::new("radio_alias_edit").with(*idx);
+                            let mut text = ui
+                                .memory(|m| m.data.get_temp::<String>(edit_id))
+                                .unwrap_or_else(|| alias.clone());
+                            let response = ui.add(
+                                egui::TextEdit::singleline(&mut text)
+                                    .hint_text(tr!("radio-dialog-alias-hint"))
+                                    .desired_width(120.0),
+                            );
+                            if response.lost_focus() {
+                                ui.memory_mut(|m| m.data.remove::<String>(edit_id));
+                                if text != *alias {
+                                    alias_change = Some((*idx, text));
+                                }
+                            } else if response.has_focus() {
+                                ui.memory_mut(|m| m.data.insert_temp(edit_id, text));
+                            }
+                        });
+                        // Frequency entry (checked by the mux before it is sent)
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(tr!("radio-tune-to")).small());
+                            let edit_id = Id::new("radio_freq_entry").with(*idx);
+                            let mut text = ui
+                                .memory(|m| m.data.get_temp::<String>(edit_id))
+                                .unwrap_or_default();
+                            let response = ui
+                                .add_enabled(
+                                    handle.is_some(),
+                                    egui::TextEdit::singleline(&mut text)
+                                        .hint_text(tr!("radio-tune-to-hint"))
+                                        .desired_width(120.0),
+                                )
+                                .on_hover_text(tr!("radio-tune-to-hover"));
+                            let entered = response.lost_focus()
+                                && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                            // Only a frequency to transmit on is held to the band plan
+                            let transmit_id = Id::new("radio_freq_entry_tx").with(*idx);
+                            let mut transmit = ui
+                                .memory(|m| m.data.get_temp::<bool>(transmit_id))
+                                .unwrap_or_default();
+                            if monitor.is_none() {
+                                ui.checkbox(&mut transmit, tr!("radio-tune-transmit"))
+                                    .on_hover_text(tr!("radio-tune-transmit-hover"));
+                            }
+                            let tune = ui
+                                .add_enabled(
+                                    handle.is_some() && !text.trim().is_empty(),
+                                    egui::Button::new(tr!("radio-tune")).small(),
+                                )
+                                .clicked();
+                            if (entered || tune) && !text.trim().is_empty() {
+                                freq_entry = Some((*idx, text.clone(), transmit));
+                            }
+                            ui.memory_mut(|m| {
+                                m.data.insert_temp(edit_id, text);
+                                m.data.insert_temp(transmit_id, transmit);
+                            });
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label(RichText::new(tr!("protocol")).small());
+                            let mut selected = *protocol;
+                            egui::ComboBox::from_id_salt(Id::new("radio_protocol").with(*idx))
+                                .selected_text(protocol.name())
+                                .show_ui(ui, |ui| {
+                                    for proto in [
+                                        Protocol::Kenwood,
+                                        Protocol::IcomCIV,
+                                        Protocol::Yaesu,
+                                        Protocol::YaesuAscii,
+                                        Protocol::Elecraft,
+                                        Protocol::FlexRadio,
+                                    ] {
+                                        ui.selectable_value(&mut selected, proto, proto.name());
+                                    }
+                                })
+                                .response
+                                .on_hover_text(tr!("radio-protocol-hover"));
+                            if selected != *protocol {
+                                protocol_change = Some((*idx, selected));
+                            }
+                        });
                         ui.horizontal(|ui| {
+                            ui.label(RichText::new(tr!("radio-input-throttle")).small())
+                                .on_hover_text(tr!("radio-input-throttle-hover"));
+                            let mut ms = *input_throttle_ms;
+                            if ui
+                                .add(egui::DragValue::new(&mut ms).range(0..=500))
+                                .changed()
+                            {
+                                throttle_change = Some((*idx, ms));
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            let mut rx_only = monitor.is_some();
+                            let mut follow = monitor.is_some_and(|m| m.follow_active);
+                            let mut changed = ui
+                                .checkbox(
+                                    &mut rx_only,
+                                    RichText::new(tr!("radio-receive-only")).small(),
+                                )
+                                .on_hover_text(tr!("radio-receive-only-hover"))
+                                .changed();
+                            if rx_only {
+                                changed |= ui
+                                    .checkbox(
+                                        &mut follow,
+                                        RichText::new(tr!("radio-follow-active")).small(),
+                                    )
+                                    .on_hover_text(tr!("radio-follow-active-hover"))
+                                    .changed();
+                            }
+                            if changed {
+                                let monitor = rx_only.then_some(MonitorConfig {
+                                    follow_active: follow,
+                                });
+                                monitor_change = Some((*idx, monitor));
+                            }
+                        });
+                        if let Some(serial) = usb_serial {
+                            ui.label(
+                                RichText::new(tr!("radio-usb-serial", serial = serial.clone()))
+                                    .color(Color32::GRAY)
+                                    .small(),
+                            );
+                        }
+                        if let Some(stats) = reassembly.filter(|s| s.frames > 0) {
+                            ui.label(
+                                RichText::new(tr!(
+                                    "radio-split-frames",
+                                    percent = format_decimal(stats.split_ratio() * 100.0, 0),
+                                    reads = stats.max_reads_per_frame.max(1),
+                                    ms = format_decimal(
+                                        stats.max_frame_span.as_secs_f64() * 1000.0,
+                                        0
+                                    )
+                                ))
+                                .color(Color32::GRAY)
+                                .small(),
+                            )
+                            .on_hover_text(tr!("radio-split-frames-hover"));
+                        }
+                        if latency_timer_ms.is_some() || latency_hint.is_some() {
+                            ui.horizontal(|ui| {
+                                let text = match latency_timer_ms {
+                                    Some(ms) => tr!("radio-latency-timer", ms = ms),
+                                    None => tr!("radio-latency-timer-unknown"),
+                                };
+                                let label =
+                                    ui.label(RichText::new(text).color(Color32::GRAY).small());
+                                if let Some(hint) = latency_hint {
+                                    label.on_hover_text(hint);
+                                }
+                                if latency_timer_ms.is_some_and(|ms| ms > RECOMMENDED_LATENCY_MS)
+                                    && ui
+                                        .small_button(tr!(
+                                            "radio-latency-timer-fix",
+                                            ms = RECOMMENDED_LATENCY_MS
+                                        ))
+                                        .clicked()
+                                {
+                                    latency_fix_idx = Some(*idx);
+                                }
+                            });
+                        }
+                        if let (Some(summary), Some(_)) = (profile_summary, handle) {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    RichText::new(tr!("radio-profile", summary = summary.clone()))
+                                        .color(Color32::GRAY)
+                                        .small(),
+                                );
+                                if ui
+                                    .small_button(tr!("radio-reprofile"))
+                                    .on_hover_text(tr!("radio-reprofile-hover"))
+                                    .clicked()
+                                {
+                                    reprofile_idx = Some(*idx);
+                                }
+                            });
+                        }
+                        if *civ_echo_back == Some(true) {
+                            ui.label(
+                                RichText::new(tr!("radio-civ-echo-back"))
+                                    .color(Color32::from_rgb(255, 165, 0))
+                                    .small(),
+                            )
+                            .on_hover_text(echo_back_advice(name));
+                        }
+
+                        if let (Some(table), Some(handle)) = (menu_table, handle) {
+                            ui.add_space(4.0);
+                            if let Some(cmd) = draw_menu_controls(ui, *idx, table, menu_values) {
+                                menu_send = Some((*handle, cmd));
+                            }
+                        }
+
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button(tr!("radio-edit"))
+                                .on_hover_text(tr!("radio-edit-hover"))
+                                .clicked()
+                            {
+                                edit_idx = Some(*idx);
+                            }
+                            if *disabled && ui.button(tr!("radio-enable")).clicked() {
+                                enable_idx = Some(*idx);
+                            }
                             ui.with_layout(
                                 egui::Layout::right_to_left(egui::Align::Center),
                                 |ui| {
                                     if ui
                                         .button(
-                                            RichText::new("Remove")
+                                            RichText::new(tr!("remove"))
                                                 .color(Color32::from_rgb(255, 100, 100)),
                                         )
                                         .clicked()
@@ -368,20 +929,116 @@ impl CatapultApp {
             // Send SetActiveRadio to mux actor
             self.send_mux_command(MuxActorCommand::SetActiveRadio { handle }, "SetActiveRadio");
         }
+        for (id, response) in &sections {
+            self.settings.layout.remember_section(id, response);
+        }
         if let Some(idx) = toggle_expanded_idx {
-            self.radio_panels[idx].expanded = !self.radio_panels[idx].expanded;
+            let channel_id = &self.radio_panels[idx].channel_id;
+            let expanded = &mut self.settings.layout.expanded_radios;
+            if !expanded.remove(channel_id) {
+                expanded.insert(channel_id.clone());
+            }
         }
-        if let Some((sim_id, freq)) = freq_change {
+        if let Some((sim_channel, freq)) = freq_change {
             self.simulation_panel
-                .send_command(&sim_id, VirtualRadioCommand::SetFrequency(freq));
+                .send_command(&sim_channel, VirtualRadioCommand::SetFrequency(freq));
         }
-        if let Some((sim_id, m)) = mode_change {
+        if let Some((sim_channel, m)) = mode_change {
             self.simulation_panel
-                .send_command(&sim_id, VirtualRadioCommand::SetMode(m));
+                .send_command(&sim_channel, VirtualRadioCommand::SetMode(m));
         }
-        if let Some((sim_id, active)) = ptt_change {
-            self.simulation_panel
-                .send_command(&sim_id, VirtualRadioCommand::SetPtt(active));
+        if let Some((sim_channel, cmd)) = sim_command {
+            self.simulation_panel.send_command(&sim_channel, cmd);
+        }
+        if let Some((handle, sim_channel, active)) = ptt_change {
+            // Key over CAT through the mux so the PTT sequencer can order it with the amp
+            match handle {
+                Some(handle) => self
+                    .send_mux_command(MuxActorCommand::RequestPtt { handle, active }, "RequestPtt"),
+                None => self
+                    .simulation_panel
+                    .send_command(&sim_channel, VirtualRadioCommand::SetPtt(active)),
+            }
+        }
+        if let Some((idx, alias)) = alias_change {
+            if let Some(panel) = self.radio_panels.get_mut(idx) {
+                let alias = alias.trim().to_string();
+                panel.alias = (!alias.is_empty()).then_some(alias);
+                self.refresh_radio_names();
+                self.save_configured_radios();
+            }
+        }
+        if let Some(idx) = enable_idx {
+            if let Some(panel) = self.radio_panels.get_mut(idx) {
+                // Left disconnected, so the reconnect loop picks it up
+                panel.disabled = false;
+                self.save_configured_radios();
+            }
+        }
+        if let Some(idx) = edit_idx {
+            self.open_edit_radio_dialog(idx);
+        }
+        if let Some(idx) = latency_fix_idx {
+            self.set_radio_latency_timer(idx);
+        }
+        if let Some(idx) = reprofile_idx {
+            self.reprofile_radio(idx);
+        }
+        if let Some((idx, text, transmit)) = freq_entry {
+            self.enter_frequency(idx, &text, transmit);
+        }
+        if let Some((idx, ms)) = throttle_change {
+            if let Some(panel) = self.radio_panels.get_mut(idx) {
+                panel.input_throttle_ms = ms;
+                if let Some(handle) = panel.handle {
+                    self.send_mux_command(
+                        MuxActorCommand::SetRadioInputThrottle {
+                            handle,
+                            ms: ms as u64,
+                        },
+                        "SetRadioInputThrottle",
+                    );
+                }
+                self.save_configured_radios();
+            }
+        }
+        if let Some((idx, monitor)) = monitor_change {
+            if let Some(panel) = self.radio_panels.get_mut(idx) {
+                panel.monitor = monitor;
+                if let Some(handle) = panel.handle {
+                    // The mux announces the radio that takes over, if any
+                    if monitor.is_some() && self.active_radio == Some(handle) {
+                        self.active_radio = None;
+                    }
+                    self.send_mux_command(
+                        MuxActorCommand::SetRadioMonitor { handle, monitor },
+                        "SetRadioMonitor",
+                    );
+                }
+                self.save_configured_radios();
+            }
+        }
+        if let Some((idx, protocol)) = protocol_change {
+            if let Some(panel) = self.radio_panels.get_mut(idx) {
+                panel.protocol = protocol;
+                // Unconnected radios just use it on the next connection
+                if let Some(handle) = panel.handle {
+                    self.send_mux_command(
+                        MuxActorCommand::SetRadioProtocol { handle, protocol },
+                        "SetRadioProtocol",
+                    );
+                }
+                self.save_configured_radios();
+            }
+        }
+        if let Some((handle, cmd)) = menu_send {
+            if let Some(sender) = self.radio_task_senders.get(&handle) {
+                Self::send_radio_task_command(
+                    &sender.task_cmd_tx,
+                    RadioTaskCommand::SendData { data: cmd.encode() },
+                    "SendData",
+                );
+            }
         }
         if let Some(idx) = remove_radio_idx {
             // Get the handle from the panel
@@ -401,7 +1058,7 @@ impl CatapultApp {
             .num_columns(2)
             .spacing([10.0, 4.0])
             .show(ui, |ui| {
-                ui.label("Mode:");
+                ui.label(tr!("switching-mode"));
                 egui::ComboBox::from_id_salt("switch_mode")
                     .selected_text(mode.name())
                     .show_ui(ui, |ui| {
@@ -428,11 +1085,129 @@ impl CatapultApp {
                 .color(Color32::GRAY)
                 .size(11.0),
         );
+
+        self.draw_band_guard_settings(ui);
+
+        if ui
+            .checkbox(
+                &mut self.settings.confirm_tx_switch,
+                tr!("switching-confirm-tx"),
+            )
+            .on_hover_text(tr!("switching-confirm-tx-hover"))
+            .changed()
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetSwitchConfirmation {
+                    enabled: self.settings.confirm_tx_switch,
+                },
+                "SetSwitchConfirmation",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        if ui
+            .checkbox(
+                &mut self.settings.carry_keyer_speed,
+                tr!("switching-carry-keyer-speed"),
+            )
+            .on_hover_text(tr!("switching-carry-keyer-speed-hover"))
+            .changed()
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetCarryKeyerSpeed {
+                    enabled: self.settings.carry_keyer_speed,
+                },
+                "SetCarryKeyerSpeed",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        if ui
+            .checkbox(
+                &mut self.settings.band_mode_memory,
+                tr!("switching-band-mode-memory"),
+            )
+            .on_hover_text(tr!("switching-band-mode-memory-hover"))
+            .changed()
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetBandModeMemory {
+                    enabled: self.settings.band_mode_memory,
+                },
+                "SetBandModeMemory",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+
+        if ui
+            .checkbox(
+                &mut self.settings.suppress_repeats,
+                tr!("switching-suppress-repeats"),
+            )
+            .on_hover_text(tr!("switching-suppress-repeats-hover"))
+            .changed()
+        {
+            self.send_mux_command(
+                MuxActorCommand::SetSuppressRepeats {
+                    enabled: self.settings.suppress_repeats,
+                },
+                "SetSuppressRepeats",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
+    }
+
+    /// Draw the same-band guard controls
+    fn draw_band_guard_settings(&mut self, ui: &mut Ui) {
+        let prev = self.settings.band_guard;
+        let guard = &mut self.settings.band_guard;
+
+        ui.add_space(4.0);
+        ui.horizontal(|ui| {
+            ui.label(tr!("switching-same-band"));
+            egui::ComboBox::from_id_salt("band_guard")
+                .selected_text(guard.strictness.name())
+                .show_ui(ui, |ui| {
+                    for strictness in [
+                        BandGuardStrictness::Off,
+                        BandGuardStrictness::Warn,
+                        BandGuardStrictness::BlockPtt,
+                    ] {
+                        ui.selectable_value(&mut guard.strictness, strictness, strictness.name());
+                    }
+                })
+                .response
+                .on_hover_text(tr!("switching-same-band-hover"));
+        });
+        if guard.strictness != BandGuardStrictness::Off {
+            ui.checkbox(&mut guard.per_mode, tr!("switching-per-mode"))
+                .on_hover_text(tr!("switching-per-mode-hover"));
+        }
+
+        if self.settings.band_guard != prev {
+            self.send_mux_command(
+                MuxActorCommand::SetBandGuardConfig {
+                    config: self.settings.band_guard,
+                },
+                "SetBandGuardConfig",
+            );
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+        }
     }
 
     /// Draw the traffic monitor panel
     pub(super) fn draw_traffic_panel(&mut self, ui: &mut Ui) {
-        ui.heading("Traffic Monitor");
+        ui.heading(tr!("traffic-heading"));
 
         // Draw and handle export actions
         if let Some(action) =
@@ -442,10 +1217,10 @@ impl CatapultApp {
             match action {
                 ExportAction::CopyToClipboard(content) => {
                     ui.ctx().copy_text(content);
-                    self.set_status("Log copied to clipboard".to_string());
+                    self.set_status(tr!("traffic-log-copied"));
                 }
                 ExportAction::SavedToFile(path) => {
-                    self.set_status(format!("Log saved to {}", path.display()));
+                    self.set_status(tr!("traffic-log-saved", path = path.display().to_string()));
                 }
                 ExportAction::Cancelled => {
                     // User cancelled, do nothing
@@ -473,181 +1248,326 @@ impl CatapultApp {
         }
     }
 
+    /// Pause, step and speed controls for the simulation clock
+    fn draw_sim_clock_controls(&mut self, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(tr!("sim-clock")).color(Color32::from_rgb(100, 180, 255)));
+            let paused = self.simulation_panel.is_paused();
+            if ui
+                .button(if paused {
+                    tr!("traffic-resume")
+                } else {
+                    tr!("traffic-pause")
+                })
+                .on_hover_text(tr!("sim-pause-hover"))
+                .clicked()
+            {
+                self.simulation_panel.set_paused(!paused);
+            }
+            if ui
+                .add_enabled(paused, egui::Button::new(tr!("sim-step")))
+                .on_hover_text(tr!("sim-step-hover", seconds = CLOCK_STEP.as_secs()))
+                .clicked()
+            {
+                self.simulation_panel.step();
+            }
+            let rate = self.simulation_panel.rate();
+            egui::ComboBox::from_id_salt("sim_clock_rate")
+                .selected_text(format!("{}x", rate))
+                .width(60.0)
+                .show_ui(ui, |ui| {
+                    for &r in CLOCK_RATES {
+                        if ui.selectable_label(rate == r, format!("{}x", r)).clicked() {
+                            self.simulation_panel.set_rate(r);
+                        }
+                    }
+                });
+        });
+    }
+
     /// Draw the add radio section for the settings panel
     pub(super) fn draw_add_radio_section(&mut self, ui: &mut Ui) {
-        // Collect available ports into owned data to avoid borrow conflicts
-        // PortInfo includes both real serial ports and virtual ports from settings
-        let available_ports: Vec<(String, String, bool, Option<Protocol>)> = self
-            .available_radio_ports()
-            .into_iter()
-            .map(|p| {
-                (
-                    p.port_name(),
-                    p.display_label(),
-                    p.is_virtual(),
-                    p.virtual_protocol(),
-                )
-            })
-            .collect();
-
-        if available_ports.is_empty() {
-            ui.label(
-                RichText::new("No ports available")
-                    .color(Color32::GRAY)
-                    .small(),
-            );
-            ui.label(
-                RichText::new("Configure virtual ports below, or connect a serial device")
-                    .color(Color32::GRAY)
-                    .small(),
-            );
-            return;
+        ui.label(
+            RichText::new(tr!("add-radio-description"))
+                .color(Color32::GRAY)
+                .small(),
+        );
+        if ui
+            .button(tr!("add-radio"))
+            .on_hover_text(tr!("add-radio-hover"))
+            .clicked()
+        {
+            self.open_add_radio_dialog();
         }
+        self.draw_port_scan(ui);
+    }
+}
 
-        egui::Grid::new("add_radio_grid")
-            .num_columns(2)
-            .spacing([10.0, 8.0])
-            .show(ui, |ui| {
-                // Port dropdown
-                ui.label("Port:");
-                let selected_label = if self.add_radio_port.is_empty() {
-                    "Select port...".to_string()
-                } else {
-                    // Find the label for the selected port
-                    available_ports
-                        .iter()
-                        .find(|(port, _, _, _)| *port == self.add_radio_port)
-                        .map(|(_, label, _, _)| label.clone())
-                        .unwrap_or_else(|| self.add_radio_port.clone())
-                };
+/// Mini meters: S-meter while receiving, power and SWR while transmitting
+fn draw_meters(
+    ui: &mut Ui,
+    meters: &RadioMeters,
+    ptt: bool,
+    now: Instant,
+    ballistics: &MeterBallistics,
+) {
+    let rows: Vec<_> = if ptt {
+        vec![("PWR", meters.power), ("SWR", meters.swr)]
+    } else {
+        vec![("S", meters.signal)]
+    };
 
-                let prev_port = self.add_radio_port.clone();
-                egui::ComboBox::from_id_salt("add_radio_port")
-                    .selected_text(&selected_label)
-                    .width(150.0)
-                    .show_ui(ui, |ui| {
-                        for (port_name, label, _, _) in &available_ports {
-                            ui.selectable_value(&mut self.add_radio_port, port_name.clone(), label);
-                        }
-                    });
-                ui.end_row();
+    for (label, meter) in rows {
+        let Some(meter) = meter else { continue };
+        let value = meter.value(now, ballistics);
+        let readout = match label {
+            "S" => s_unit_label(value),
+            "PWR" => format!("{:.0}%", value * 100.0),
+            _ => String::new(),
+        };
 
-                // Clear model when port changes
-                if self.add_radio_port != prev_port {
-                    self.add_radio_model.clear();
-                }
+        ui.horizontal(|ui| {
+            ui.add_sized(
+                [28.0, 10.0],
+                egui::Label::new(RichText::new(label).small().color(Color32::GRAY)),
+            );
+            let (rect, _) = ui.allocate_exact_size(egui::vec2(120.0, 8.0), egui::Sense::hover());
+            let painter = ui.painter();
+            painter.rect_filled(rect, 2.0, Color32::from_gray(45));
 
-                // Protocol dropdown
-                ui.label("Protocol:");
-                egui::ComboBox::from_id_salt("add_radio_protocol")
-                    .selected_text(self.add_radio_protocol.name())
-                    .width(150.0)
-                    .show_ui(ui, |ui| {
-                        for proto in [
-                            Protocol::Kenwood,
-                            Protocol::IcomCIV,
-                            Protocol::Yaesu,
-                            Protocol::YaesuAscii,
-                            Protocol::Elecraft,
-                            Protocol::FlexRadio,
-                        ] {
-                            ui.selectable_value(&mut self.add_radio_protocol, proto, proto.name());
-                        }
-                    });
-                ui.end_row();
+            let color = if ptt {
+                Color32::from_rgb(255, 140, 60)
+            } else {
+                Color32::from_rgb(90, 200, 90)
+            };
+            let mut fill = rect;
+            fill.set_width(rect.width() * value.clamp(0.0, 1.0));
+            painter.rect_filled(fill, 2.0, color);
 
-                // Baud rate dropdown
-                ui.label("Baud:");
-                egui::ComboBox::from_id_salt("add_radio_baud")
-                    .selected_text(format!("{}", self.add_radio_baud))
-                    .width(150.0)
-                    .show_ui(ui, |ui| {
-                        for &baud in &[4800u32, 9600, 19200, 38400, 57600, 115200] {
-                            ui.selectable_value(
-                                &mut self.add_radio_baud,
-                                baud,
-                                format!("{}", baud),
-                            );
-                        }
-                    });
-                ui.end_row();
+            if let Some(peak) = meter.peak(now, ballistics) {
+                let x = rect.left() + rect.width() * peak.clamp(0.0, 1.0);
+                painter.vline(x, rect.y_range(), egui::Stroke::new(1.5, Color32::WHITE));
+            }
 
-                // Flow control dropdown
-                ui.label("Flow Ctrl:");
-                egui::ComboBox::from_id_salt("add_radio_flow_control")
-                    .selected_text(match self.add_radio_flow_control {
-                        SerialFlowControl::None => "None",
-                        SerialFlowControl::Software => "Software (XON/XOFF)",
-                        SerialFlowControl::Hardware => "Hardware (RTS/CTS)",
-                    })
-                    .width(150.0)
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut self.add_radio_flow_control,
-                            SerialFlowControl::None,
-                            "None",
-                        );
-                        ui.selectable_value(
-                            &mut self.add_radio_flow_control,
-                            SerialFlowControl::Software,
-                            "Software (XON/XOFF)",
-                        );
-                        ui.selectable_value(
-                            &mut self.add_radio_flow_control,
-                            SerialFlowControl::Hardware,
-                            "Hardware (RTS/CTS)",
-                        );
-                    });
-                ui.end_row();
+            ui.label(RichText::new(readout).small().color(Color32::GRAY));
+        });
+    }
+}
 
-                // CI-V address for Icom protocol
-                if self.add_radio_protocol == Protocol::IcomCIV {
-                    ui.label("CI-V Address:");
-                    let mut addr_str = format!("{:02X}", self.add_radio_civ_address);
-                    let response =
-                        ui.add(egui::TextEdit::singleline(&mut addr_str).desired_width(50.0));
-                    if response.changed() {
-                        if let Ok(addr) = u8::from_str_radix(addr_str.trim_start_matches("0x"), 16)
-                        {
-                            self.add_radio_civ_address = addr;
-                        }
-                    }
-                    ui.end_row();
+/// Yaesu `EX` menu editor: pick a parameter, read it, or write a new value
+///
+/// Returns the command to send, if a button was pressed.
+fn draw_menu_controls(
+    ui: &mut Ui,
+    idx: usize,
+    table: &MenuTable,
+    values: &BTreeMap<u32, String>,
+) -> Option<YaesuAsciiCommand> {
+    let first = table.params.first()?;
+    let item_id = Id::new("radio_menu_item").with(idx);
+    let value_id = Id::new("radio_menu_value").with(idx);
+    let selected = ui
+        .memory(|m| m.data.get_temp::<u32>(item_id))
+        .unwrap_or(first.number);
+    let param = table.param(selected).unwrap_or(first);
+    let mut number = param.number;
+    let mut send = None;
+
+    ui.horizontal(|ui| {
+        ui.label(RichText::new(tr!("menu-item")).small());
+        egui::ComboBox::from_id_salt(item_id)
+            .selected_text(format!(
+                "{:0width$} {}",
+                param.number,
+                param.name,
+                width = table.digits
+            ))
+            .width(170.0)
+            .show_ui(ui, |ui| {
+                for p in table.params {
+                    ui.selectable_value(
+                        &mut number,
+                        p.number,
+                        format!("{:0width$} {}", p.number, p.name, width = table.digits),
+                    );
                 }
             });
+        if ui.small_button(tr!("menu-read")).clicked() {
+            // Show the answer rather than a stale edit
+            ui.memory_mut(|m| m.data.remove::<String>(value_id));
+            send = Some(YaesuAsciiCommand::Menu {
+                item: number,
+                digits: table.digits,
+                value: None,
+            });
+        }
+    });
+    if number != param.number {
+        ui.memory_mut(|m| m.data.remove::<String>(value_id));
+    }
+    ui.memory_mut(|m| m.data.insert_temp(item_id, number));
 
-        ui.add_space(8.0);
+    ui.horizontal(|ui| {
+        let current = values.get(&number);
+        let current_text = match current {
+            Some(v) => match param.value_label(v) {
+                Some(label) => format!("{} ({})", v, label),
+                None => v.clone(),
+            },
+            None => "---".to_string(),
+        };
+        ui.label(RichText::new(tr!("menu-value", value = current_text)).small());
 
-        // Detect Model button and detected model display
-        ui.horizontal(|ui| {
-            let can_probe = !self.add_radio_port.is_empty() && !self.probing;
-            if self.probing {
-                ui.spinner();
-                ui.label("Detecting...");
-            } else if ui
-                .add_enabled(can_probe, egui::Button::new("Detect Model"))
-                .on_hover_text("Query radio for model identification using selected protocol")
+        let mut text = ui
+            .memory(|m| m.data.get_temp::<String>(value_id))
+            .unwrap_or_else(|| current.cloned().unwrap_or_default());
+        ui.add(egui::TextEdit::singleline(&mut text).desired_width(60.0));
+        let valid = !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+        if ui
+            .add_enabled(valid, egui::Button::new(tr!("menu-write")).small())
+            .clicked()
+        {
+            send = Some(YaesuAsciiCommand::Menu {
+                item: number,
+                digits: table.digits,
+                value: Some(text.clone()),
+            });
+        }
+        // Follow the radio's value until the user edits it
+        if current.is_some_and(|v| *v == text) {
+            ui.memory_mut(|m| m.data.remove::<String>(value_id));
+        } else {
+            ui.memory_mut(|m| m.data.insert_temp(value_id, text));
+        }
+    });
+
+    if !param.values.is_empty() {
+        let hint: Vec<String> = param
+            .values
+            .iter()
+            .enumerate()
+            .map(|(i, label)| format!("{}={}", i, label))
+            .collect();
+        ui.label(RichText::new(hint.join("  ")).color(Color32::GRAY).small());
+    }
+
+    send
+}
+
+/// Fault injection buttons for a virtual radio
+///
+/// `mode` is the radio's current mode, so a wrong one can be reported.
+/// Returns the fault asked for, if any.
+fn draw_sim_faults(ui: &mut Ui, id: Id, mode: OperatingMode) -> Option<VirtualRadioCommand> {
+    let mut fault = None;
+    let silence_id = id.with("silence_secs");
+    let mut secs = ui.data_mut(|d| *d.get_temp_mut_or(silence_id, 10u32));
+
+    ui.horizontal(|ui| {
+        if ui
+            .small_button(tr!("sim-go-silent"))
+            .on_hover_text(tr!("sim-go-silent-hover"))
+            .clicked()
+        {
+            fault = Some(VirtualRadioCommand::GoSilent(Duration::from_secs(
+                secs as u64,
+            )));
+        }
+        ui.label(RichText::new(tr!("sim-go-silent-for")).small());
+        ui.add(egui::DragValue::new(&mut secs).range(1..=600).suffix(" s"));
+    });
+    ui.data_mut(|d| d.insert_temp(silence_id, secs));
+
+    ui.horizontal_wrapped(|ui| {
+        if ui
+            .small_button(tr!("sim-malformed"))
+            .on_hover_text(tr!("sim-malformed-hover"))
+            .clicked()
+        {
+            fault = Some(VirtualRadioCommand::SendMalformedFrame);
+        }
+        for hz in [0, 999_990_000] {
+            let label = tr!(
+                "sim-report-frequency",
+                frequency = format_frequency(Frequency::from_hz(hz))
+            );
+            if ui
+                .small_button(label)
+                .on_hover_text(tr!("sim-report-frequency-hover"))
                 .clicked()
             {
-                self.probe_selected_port();
-            }
-            if !self.add_radio_model.is_empty() {
-                ui.label(RichText::new(&self.add_radio_model).color(Color32::GREEN));
+                fault = Some(VirtualRadioCommand::ReportFrequency(hz));
             }
-        });
-
-        ui.add_space(4.0);
-
-        // Add Radio button
-        let can_add = !self.add_radio_port.is_empty() && !self.probing;
+        }
+        let wrong = [OperatingMode::Fm, OperatingMode::Cw]
+            .into_iter()
+            .find(|&m| m != mode)
+            .unwrap_or(OperatingMode::Am);
         if ui
-            .add_enabled(can_add, egui::Button::new("Add Radio"))
-            .on_hover_text("Add radio with current settings")
+            .small_button(tr!("sim-wrong-mode"))
+            .on_hover_text(tr!("sim-wrong-mode-hover", mode = mode_name(wrong)))
             .clicked()
         {
-            self.add_radio_from_port();
-            // Reset the model field after adding
-            self.add_radio_model.clear();
+            fault = Some(VirtualRadioCommand::ReportMode(wrong));
         }
+    });
+
+    fault
+}
+
+/// Auto-responder rule editor for a virtual radio
+///
+/// Lists the rules with their tunable parameter and offers the protocol's
+/// presets. Returns the edited rule set, if anything changed.
+fn draw_sim_rules(
+    ui: &mut Ui,
+    protocol: Protocol,
+    rules: &[ResponderRule],
+) -> Option<Vec<ResponderRule>> {
+    let mut edited = rules.to_vec();
+    let mut changed = false;
+    let mut remove = None;
+
+    for (i, rule) in edited.iter_mut().enumerate() {
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(rule.describe()).small());
+            let param = match rule {
+                ResponderRule::MeterRamp { step, .. } => {
+                    ui.add(egui::DragValue::new(step).range(1..=255).prefix("step "))
+                }
+                ResponderRule::DropEvery { every, .. } => {
+                    ui.add(egui::DragValue::new(every).range(2..=100).prefix("every "))
+                }
+                ResponderRule::Periodic { interval_ms, .. } => ui.add(
+                    egui::DragValue::new(interval_ms)
+                        .range(50..=10_000)
+                        .suffix(" ms"),
+                ),
+            };
+            changed |= param.changed();
+            if ui
+                .small_button("x")
+                .on_hover_text(tr!("sim-remove-rule"))
+                .clicked()
+            {
+                remove = Some(i);
+            }
+        });
     }
+    if let Some(i) = remove {
+        edited.remove(i);
+        changed = true;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.label(RichText::new(tr!("virtual-ports-add")).small());
+        for (label, rule) in ResponderRule::presets(protocol) {
+            if ui.small_button(label).clicked() {
+                edited.push(rule);
+                changed = true;
+            }
+        }
+    });
+
+    changed.then_some(edited)
 }
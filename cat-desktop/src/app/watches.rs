@@ -0,0 +1,214 @@
+//! Watch expressions (settings section, evaluation and alert actions)
+
+use cat_mux::watch::webhook_body;
+use cat_mux::{post_webhook, WatchExpr, WatchTransition};
+use egui::{Color32, RichText, Ui};
+use tokio::time::Instant;
+
+use crate::i18n::tr;
+use crate::settings::WatchRule;
+
+use super::{BackgroundMessage, CatapultApp};
+
+/// Ready-made watches offered in the settings section
+const PRESETS: &[(&str, &str)] = &[
+    (
+        "Off-mode in 20m phone",
+        "mode != USB && freq in 14.150..14.350",
+    ),
+    ("Amp missed band change", "amp_lag > 1000"),
+    ("Transmitting off the active radio", "ptt && !active"),
+];
+
+impl CatapultApp {
+    /// Draw the watch list (settings panel)
+    pub(super) fn draw_watch_section(&mut self, ui: &mut Ui) {
+        ui.heading(tr!("watches-heading"));
+        ui.label(
+            RichText::new(tr!("watches-description"))
+                .small()
+                .color(Color32::GRAY),
+        );
+
+        let prev = self.settings.watches.clone();
+        let mut remove = None;
+        for (i, rule) in self.settings.watches.rules.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut rule.enabled, "");
+                    ui.add(egui::TextEdit::singleline(&mut rule.name).desired_width(140.0));
+                    if self.watch_engine.is_firing(&rule.name) {
+                        ui.label(
+                            RichText::new(tr!("watch-firing"))
+                                .color(Color32::from_rgb(255, 165, 0)),
+                        );
+                    }
+                    if ui.small_button(tr!("remove")).clicked() {
+                        remove = Some(i);
+                    }
+                });
+                ui.add(
+                    egui::TextEdit::singleline(&mut rule.expression)
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+                if let Err(e) = WatchExpr::parse(&rule.expression) {
+                    ui.label(RichText::new(e.to_string()).small().color(Color32::RED));
+                }
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut rule.alert, tr!("watch-alert"));
+                    ui.checkbox(&mut rule.sound, tr!("watch-sound"));
+                    ui.label(tr!("watch-webhook"));
+                    ui.add(
+                        egui::TextEdit::singleline(&mut rule.webhook)
+                            .hint_text("http://host:port/path")
+                            .desired_width(f32::INFINITY),
+                    );
+                });
+                ui.add_space(4.0);
+            });
+        }
+        if let Some(i) = remove {
+            self.settings.watches.rules.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button(tr!("watch-add")).clicked() {
+                let n = self.settings.watches.rules.len() + 1;
+                self.settings
+                    .watches
+                    .rules
+                    .push(WatchRule::new(&format!("Watch {}", n), ""));
+            }
+            ui.menu_button(tr!("watch-add-preset"), |ui| {
+                for (name, expression) in PRESETS {
+                    if ui.button(*name).on_hover_text(*expression).clicked() {
+                        self.settings
+                            .watches
+                            .rules
+                            .push(WatchRule::new(name, expression));
+                        ui.close();
+                    }
+                }
+            });
+        });
+
+        if self.settings.watches != prev {
+            if let Err(e) = self.settings.save() {
+                self.handle_save_error(e);
+            }
+            self.rebuild_watches();
+        }
+    }
+
+    /// Give the watch engine the enabled rules that parse
+    pub(super) fn rebuild_watches(&mut self) {
+        let watches = self
+            .settings
+            .watches
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| {
+                let expr = WatchExpr::parse(&rule.expression).ok()?;
+                Some((rule.name.clone(), expr))
+            })
+            .collect();
+        self.watch_engine.set_watches(watches);
+        self.watch_alerts
+            .retain(|(name, _)| self.watch_engine.is_firing(name));
+    }
+
+    /// Evaluate watches and run the actions of any that changed
+    pub(super) fn check_watches(&mut self) {
+        if self.watch_engine.is_empty() {
+            return;
+        }
+        for transition in self.watch_engine.evaluate(Instant::now()) {
+            let Some(rule) = self
+                .settings
+                .watches
+                .rules
+                .iter()
+                .find(|rule| rule.enabled && rule.name == transition.name)
+                .cloned()
+            else {
+                continue;
+            };
+            self.run_watch_actions(&rule, &transition);
+        }
+    }
+
+    fn run_watch_actions(&mut self, rule: &WatchRule, transition: &WatchTransition) {
+        self.watch_alerts.retain(|(name, _)| *name != rule.name);
+        if transition.firing {
+            if rule.alert {
+                self.watch_alerts
+                    .push((rule.name.clone(), transition.detail.clone()));
+                self.report_warning("Watch", format!("{}: {}", rule.name, transition.detail));
+            } else {
+                self.report_info("Watch", format!("{}: {}", rule.name, transition.detail));
+            }
+            if rule.sound {
+                beep();
+            }
+        } else {
+            self.report_info("Watch", tr!("watch-cleared", watch = rule.name.clone()));
+        }
+
+        let url = rule.webhook.trim();
+        if !url.is_empty() {
+            let url = url.to_string();
+            let body = webhook_body(transition);
+            let name = rule.name.clone();
+            let bg_tx = self.bg_tx.clone();
+            self.rt_handle.spawn(async move {
+                if let Err(e) = post_webhook(&url, &body).await {
+                    let _ = bg_tx.send(BackgroundMessage::WatchWebhookError {
+                        message: format!("{}: {}", name, e),
+                    });
+                }
+            });
+        }
+    }
+
+    /// Draw a banner for each alerting watch (above the radio list)
+    pub(super) fn draw_watch_alerts(&mut self, ui: &mut Ui) {
+        let mut dismissed = None;
+        for (i, (name, detail)) in self.watch_alerts.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(
+                    RichText::new(format!("⚠ {}: {}", name, detail))
+                        .color(Color32::from_rgb(255, 165, 0))
+                        .strong(),
+                );
+                if ui.small_button(tr!("dismiss")).clicked() {
+                    dismissed = Some(i);
+                }
+            });
+        }
+        if let Some(i) = dismissed {
+            self.watch_alerts.remove(i);
+        }
+    }
+}
+
+/// Audible alert
+#[cfg(windows)]
+fn beep() {
+    use windows_sys::Win32::System::Diagnostics::Debug::MessageBeep;
+    use windows_sys::Win32::UI::WindowsAndMessaging::MB_ICONWARNING;
+    // SAFETY: MessageBeep takes no pointers
+    unsafe {
+        MessageBeep(MB_ICONWARNING);
+    }
+}
+
+/// Audible alert (terminal bell)
+#[cfg(not(windows))]
+fn beep() {
+    use std::io::Write as _;
+    let mut out = std::io::stdout();
+    let _ = out.write_all(b"\x07");
+    let _ = out.flush();
+}
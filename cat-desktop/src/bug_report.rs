@@ -0,0 +1,344 @@
+//! Bug report bundles
+//!
+//! "Report a problem" collects what a maintainer needs to make sense of an
+//! issue into one zip: a summary of the environment, the recent traffic
+//! (raw bytes with their decode), recent diagnostics, the settings and the
+//! serial ports this machine sees, with any other program holding one.
+//! Nothing is written until the user has previewed the files and agreed to
+//! save them.
+//!
+//! Settings go through the same secret redaction as configuration bundles,
+//! USB serial numbers are left out of the port list and replaced wherever
+//! else they appear (such as in a radio's channel ID), and the user's home
+//! directory is replaced with `~` in every file.
+
+use std::io::{Seek, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use cat_detect::{find_port_holder, SerialPortInfo};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::config_bundle::{redact, REDACTED};
+use crate::settings::Settings;
+
+/// Traffic entries included by default
+pub const DEFAULT_TRAFFIC_ENTRIES: usize = 2000;
+
+/// Diagnostic entries included by default
+pub const DEFAULT_DIAGNOSTIC_ENTRIES: usize = 1000;
+
+/// Which parts of the report to include
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportSections {
+    pub traffic: bool,
+    pub diagnostics: bool,
+    pub settings: bool,
+    pub ports: bool,
+}
+
+impl Default for ReportSections {
+    fn default() -> Self {
+        Self {
+            traffic: true,
+            diagnostics: true,
+            settings: true,
+            ports: true,
+        }
+    }
+}
+
+/// What the report is built from
+pub struct ReportInput<'a> {
+    /// The user's description of the problem
+    pub description: &'a str,
+    pub settings: &'a Settings,
+    /// Formatted recent traffic
+    pub traffic: String,
+    /// Formatted recent diagnostics
+    pub diagnostics: String,
+    pub ports: &'a [SerialPortInfo],
+}
+
+/// One file in the report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportFile {
+    pub name: &'static str,
+    pub contents: String,
+}
+
+/// An assembled, redacted report ready to preview and save
+#[derive(Debug, Clone)]
+pub struct BugReport {
+    /// When the report was assembled (seconds since the Unix epoch)
+    pub created_unix: u64,
+    pub files: Vec<ReportFile>,
+}
+
+impl BugReport {
+    /// Assemble the sections the user chose
+    pub fn new(input: &ReportInput<'_>, sections: ReportSections) -> Result<Self, String> {
+        let created_unix = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+
+        let mut files = vec![ReportFile {
+            name: "README.txt",
+            contents: summary(input.description, created_unix, sections),
+        }];
+        if sections.traffic {
+            files.push(ReportFile {
+                name: "traffic.log",
+                contents: input.traffic.clone(),
+            });
+        }
+        if sections.diagnostics {
+            files.push(ReportFile {
+                name: "diagnostics.log",
+                contents: input.diagnostics.clone(),
+            });
+        }
+        if sections.settings {
+            let mut value = serde_json::to_value(input.settings)
+                .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+            redact(&mut value);
+            files.push(ReportFile {
+                name: "settings.json",
+                contents: serde_json::to_string_pretty(&value)
+                    .map_err(|e| format!("Failed to serialize settings: {}", e))?,
+            });
+        }
+        if sections.ports {
+            files.push(ReportFile {
+                name: "ports.txt",
+                contents: format_ports(input.ports),
+            });
+        }
+
+        let serials: Vec<&str> = input
+            .settings
+            .configured_radios
+            .iter()
+            .filter_map(|radio| radio.usb_serial.as_deref())
+            .chain(
+                input
+                    .ports
+                    .iter()
+                    .filter_map(|p| p.serial_number.as_deref()),
+            )
+            .filter(|serial| !serial.is_empty())
+            .collect();
+        let home = dirs::home_dir();
+        for file in &mut files {
+            file.contents = redact_serials(&file.contents, &serials);
+            if let Some(home) = &home {
+                file.contents = redact_home(&file.contents, home);
+            }
+        }
+        Ok(Self {
+            created_unix,
+            files,
+        })
+    }
+
+    /// Total size of the files before compression
+    pub fn size(&self) -> usize {
+        self.files.iter().map(|f| f.contents.len()).sum()
+    }
+
+    /// Write the files as a zip archive
+    pub fn write_zip<W: Write + Seek>(&self, writer: W) -> Result<W, String> {
+        let mut zip = ZipWriter::new(writer);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        for file in &self.files {
+            zip.start_file(file.name, options)
+                .map_err(|e| format!("Failed to write {}: {}", file.name, e))?;
+            zip.write_all(file.contents.as_bytes())
+                .map_err(|e| format!("Failed to write {}: {}", file.name, e))?;
+        }
+        zip.finish()
+            .map_err(|e| format!("Failed to finish archive: {}", e))
+    }
+
+    /// Write the report to a user-selected file
+    /// Returns Ok(Some(path)) on success, Ok(None) if cancelled
+    pub fn save_with_dialog(&self) -> Result<Option<PathBuf>, String> {
+        let path = rfd::FileDialog::new()
+            .set_file_name(format!("catapult-report-{}.zip", self.created_unix))
+            .add_filter("Zip archive", &["zip"])
+            .save_file();
+        let Some(path) = path else {
+            return Ok(None);
+        };
+        let file =
+            std::fs::File::create(&path).map_err(|e| format!("Failed to create file: {}", e))?;
+        self.write_zip(file)?;
+        Ok(Some(path))
+    }
+}
+
+/// README: the user's description plus what the report was made on
+fn summary(description: &str, created_unix: u64, sections: ReportSections) -> String {
+    let mut out = String::new();
+    out.push_str("Catapult problem report\n\n");
+    let description = description.trim();
+    if !description.is_empty() {
+        out.push_str(description);
+        out.push_str("\n\n");
+    }
+    out.push_str(&format!("catapult:   {}\n", env!("CARGO_PKG_VERSION")));
+    let crates = [
+        ("cat-protocol", cat_protocol::VERSION),
+        ("cat-detect", cat_detect::VERSION),
+        ("cat-mux", cat_mux::VERSION),
+        ("cat-sim", cat_sim::VERSION),
+    ];
+    let crates: Vec<String> = crates
+        .iter()
+        .map(|(name, version)| format!("{} {}", name, version))
+        .collect();
+    out.push_str(&format!("crates:     {}\n", crates.join(", ")));
+    out.push_str(&format!(
+        "os:         {} ({}, {})\n",
+        std::env::consts::OS,
+        std::env::consts::FAMILY,
+        std::env::consts::ARCH
+    ));
+    out.push_str(&format!("created:    {} (unix)\n", created_unix));
+    let included: Vec<&str> = [
+        (sections.traffic, "traffic"),
+        (sections.diagnostics, "diagnostics"),
+        (sections.settings, "settings"),
+        (sections.ports, "ports"),
+    ]
+    .into_iter()
+    .filter_map(|(on, name)| on.then_some(name))
+    .collect();
+    out.push_str(&format!("includes:   {}\n", included.join(", ")));
+    out
+}
+
+/// One line per serial port with its USB identity (no serial numbers)
+pub fn format_ports(ports: &[SerialPortInfo]) -> String {
+    if ports.is_empty() {
+        return "No serial ports found\n".to_string();
+    }
+    ports
+        .iter()
+        .map(|p| {
+            let line = match (p.vid, p.pid) {
+                (Some(vid), Some(pid)) => format!(
+                    "{}  {:04X}:{:04X}  {} {}",
+                    p.port,
+                    vid,
+                    pid,
+                    p.manufacturer.as_deref().unwrap_or("-"),
+                    p.product.as_deref().unwrap_or("-")
+                ),
+                _ => format!("{}  (not USB)", p.port),
+            };
+            // Another program holding a port is a common cause of failed connects
+            match find_port_holder(&p.port) {
+                Some(holder) => format!("{}  in use by {}\n", line, holder),
+                None => format!("{}\n", line),
+            }
+        })
+        .collect()
+}
+
+/// Replace USB serial numbers wherever they appear, such as in channel IDs
+fn redact_serials(text: &str, serials: &[&str]) -> String {
+    serials.iter().fold(text.to_string(), |text, serial| {
+        text.replace(serial, REDACTED)
+    })
+}
+
+/// Replace the home directory with `~` so the user name doesn't leak
+fn redact_home(text: &str, home: &Path) -> String {
+    let home = home.to_string_lossy();
+    if home.len() < 2 {
+        return text.to_string();
+    }
+    let mut out = text.replace(home.as_ref(), "~");
+    // Paths inside JSON have their backslashes escaped
+    if home.contains('\\') {
+        out = out.replace(&home.replace('\\', "\\\\"), "~");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn test_report_redacts_and_zips() {
+        let ports = vec![SerialPortInfo {
+            port: "/dev/ttyUSB0".into(),
+            vid: Some(0x0403),
+            pid: Some(0x6001),
+            serial_number: Some("A12345".into()),
+            interface: None,
+            manufacturer: Some("FTDI".into()),
+            product: Some("FT232R".into()),
+            bluetooth: false,
+        }];
+        let mut settings = serde_json::to_value(Settings::default()).unwrap();
+        settings["configured_radios"] = serde_json::json!([{
+            "id": "usb:A12345",
+            "port": "/dev/ttyUSB0",
+            "protocol": "Kenwood",
+            "model_name": "TS-590",
+            "baud_rate": 9600,
+            "usb_serial": "A12345",
+        }]);
+        let mut settings: Settings = serde_json::from_value(settings).unwrap();
+        settings.status_page.ntfy_url = "http://ntfy.sh/shack-8f3k2".into();
+        settings.status_page.webhook_url = "http://hooks.example.org/T0K3N".into();
+        let input = ReportInput {
+            description: "Amp stopped following",
+            settings: &settings,
+            traffic: "12:00:00.000 IN  Radio(COM3) 46 41 3B\n".into(),
+            diagnostics: String::new(),
+            ports: &ports,
+        };
+        let sections = ReportSections {
+            diagnostics: false,
+            ..Default::default()
+        };
+        let report = BugReport::new(&input, sections).unwrap();
+        let names: Vec<_> = report.files.iter().map(|f| f.name).collect();
+        assert_eq!(
+            names,
+            vec!["README.txt", "traffic.log", "settings.json", "ports.txt"]
+        );
+        assert!(report.files[0].contents.contains("Amp stopped following"));
+        let port_list = &report.files[3].contents;
+        assert!(port_list.contains("0403:6001"));
+        assert!(!port_list.contains("A12345"));
+        // Nor in the radio's settings, including its channel ID
+        assert!(!report.files[2].contents.contains("A12345"));
+        // Nor the alert destinations, which anyone could post to
+        assert!(!report.files[2].contents.contains("shack-8f3k2"));
+        assert!(!report.files[2].contents.contains("T0K3N"));
+        assert!(report.files[0].contents.contains("cat-mux "));
+
+        let zip = report.write_zip(Cursor::new(Vec::new())).unwrap();
+        let archive = zip::ZipArchive::new(Cursor::new(zip.into_inner())).unwrap();
+        assert_eq!(archive.len(), 4);
+    }
+
+    #[test]
+    fn test_redact_home() {
+        let home = Path::new("/home/op");
+        assert_eq!(
+            redact_home("capture: /home/op/captures", home),
+            "capture: ~/captures"
+        );
+        assert_eq!(redact_home("/tmp/x", home), "/tmp/x");
+    }
+}
@@ -0,0 +1,228 @@
+//! Watch-folder ingestion of capture files
+//!
+//! Turns catapult into an offline CAT log viewer: every file in a watched
+//! folder is read as a raw byte capture (or, for `.hex` files, a text hex
+//! dump), split into frames with the selected protocol's codec and shown in
+//! the traffic monitor. Files that grow are picked up from where the last poll
+//! stopped, so a capture still being written by another tool streams in; only
+//! the new tail of a file is read.
+
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use cat_protocol::display::parse_hex_dump;
+use cat_protocol::{Protocol, RadioSideCodec};
+
+/// How often the folder is rescanned
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Frames decoded from one capture file since the previous poll
+#[derive(Debug)]
+pub struct CaptureBatch {
+    /// File name (without the folder)
+    pub file: String,
+    /// Complete frames, in file order
+    pub frames: Vec<Vec<u8>>,
+}
+
+struct WatchedFile {
+    /// File bytes already consumed (for `.hex` files, text bytes)
+    offset: u64,
+    /// File length at the previous poll
+    len: u64,
+    codec: RadioSideCodec,
+}
+
+/// Polls a folder for new and growing capture files
+pub struct CaptureWatcher {
+    folder: PathBuf,
+    protocol: Protocol,
+    files: HashMap<PathBuf, WatchedFile>,
+}
+
+impl CaptureWatcher {
+    pub fn new(folder: impl Into<PathBuf>, protocol: Protocol) -> Self {
+        Self {
+            folder: folder.into(),
+            protocol,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Scan the folder and decode whatever is new
+    ///
+    /// Hidden files and subfolders are skipped. A file that shrank is assumed
+    /// to have been replaced and is read again from the start. A `.hex` file
+    /// is decoded a whole line at a time; a last line without a newline is
+    /// taken once the file has stopped growing.
+    pub fn poll(&mut self) -> Result<Vec<CaptureBatch>, String> {
+        let entries = std::fs::read_dir(&self.folder)
+            .map_err(|e| format!("Failed to read {}: {}", self.folder.display(), e))?;
+
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file() && !is_hidden(path))
+            .collect();
+        paths.sort();
+
+        let mut batches = Vec::new();
+        for path in paths {
+            let len = match std::fs::metadata(&path) {
+                Ok(meta) => meta.len(),
+                Err(e) => {
+                    tracing::warn!("Skipping capture {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+
+            let protocol = self.protocol;
+            let watched = self
+                .files
+                .entry(path.clone())
+                .or_insert_with(|| WatchedFile {
+                    offset: 0,
+                    len: 0,
+                    codec: RadioSideCodec::new(protocol),
+                });
+            if len < watched.offset {
+                watched.offset = 0;
+                watched.codec.clear();
+            }
+            if len == watched.offset {
+                watched.len = len;
+                continue;
+            }
+            let growing = len != watched.len;
+            watched.len = len;
+
+            let tail = match read_tail(&path, watched.offset, len) {
+                Ok(tail) => tail,
+                Err(e) => {
+                    tracing::warn!("Skipping capture {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            if is_hex(&path) {
+                // Leave a line that may still be being written for later
+                let end = if growing {
+                    tail.iter().rposition(|&b| b == b'\n').map_or(0, |i| i + 1)
+                } else {
+                    tail.len()
+                };
+                if end == 0 {
+                    continue;
+                }
+                watched.offset += end as u64;
+                let parsed = std::str::from_utf8(&tail[..end])
+                    .map_err(|e| e.to_string())
+                    .and_then(parse_hex_dump);
+                match parsed {
+                    Ok(bytes) => watched.codec.push_bytes(&bytes),
+                    Err(e) => {
+                        tracing::warn!("Skipping part of capture {}: {}", path.display(), e);
+                        continue;
+                    }
+                }
+            } else {
+                watched.offset = len;
+                watched.codec.push_bytes(&tail);
+            }
+
+            let mut frames = Vec::new();
+            while let Some((_, frame)) = watched.codec.next_response_with_bytes() {
+                frames.push(frame);
+            }
+            if !frames.is_empty() {
+                batches.push(CaptureBatch {
+                    file: file_name(&path),
+                    frames,
+                });
+            }
+        }
+
+        Ok(batches)
+    }
+}
+
+/// Read a file's bytes from `offset` up to `len`
+fn read_tail(path: &Path, offset: u64, len: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    file.seek(SeekFrom::Start(offset))?;
+    let mut tail = Vec::new();
+    file.take(len - offset).read_to_end(&mut tail)?;
+    Ok(tail)
+}
+
+/// Whether a capture is a text hex dump rather than raw bytes
+fn is_hex(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("hex"))
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_watcher_picks_up_growing_files() {
+        let dir = temp_dir("capture");
+        let path = dir.join("kenwood.cap");
+        std::fs::write(&path, b"FA00014250000;MD").unwrap();
+
+        let mut watcher = CaptureWatcher::new(&dir, Protocol::Kenwood);
+        let batches = watcher.poll().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].file, "kenwood.cap");
+        assert_eq!(batches[0].frames, vec![b"FA00014250000;".to_vec()]);
+
+        // Nothing new: nothing reported
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // The partial frame completes once the file grows
+        std::fs::write(&path, b"FA00014250000;MD2;").unwrap();
+        let batches = watcher.poll().unwrap();
+        assert_eq!(batches[0].frames, vec![b"MD2;".to_vec()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_watcher_waits_for_whole_hex_lines() {
+        let dir = temp_dir("capture-hex");
+        let path = dir.join("icom.hex");
+        // The second line is still being written, split inside a byte
+        std::fs::write(&path, "# IC-7300\nFE FE E0 94 03\n00 00 25 1").unwrap();
+
+        let mut watcher = CaptureWatcher::new(&dir, Protocol::IcomCIV);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        std::fs::write(&path, "# IC-7300\nFE FE E0 94 03\n00 00 25 14 00 FD").unwrap();
+        // Still growing: the unterminated line waits
+        assert!(watcher.poll().unwrap().is_empty());
+        // Stopped growing: taken as it is
+        let batches = watcher.poll().unwrap();
+        assert_eq!(
+            batches[0].frames,
+            vec![vec![
+                0xFE, 0xFE, 0xE0, 0x94, 0x03, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD
+            ]]
+        );
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,478 @@
+//! Configuration bundle import/export
+//!
+//! A bundle is a single JSON file holding the settings (radios, virtual
+//! ports, amplifier, analyzer and so on) and optionally the recent traffic
+//! log. It is meant for attaching to support requests and for moving a setup
+//! to another machine.
+//!
+//! Anything that looks like a credential (a field named like a token, secret,
+//! password, API key, webhook or ntfy topic, whose URLs work as one) or identifies a device (a USB serial number) is
+//! replaced with [`REDACTED`] on export. On import a redacted field keeps the
+//! value this machine already has.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::settings::Settings;
+
+/// Identifies a catapult configuration bundle
+pub const BUNDLE_FORMAT: &str = "catapult-config-bundle";
+
+/// Newest bundle version this build reads and the one it writes
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Placeholder written in place of secrets
+pub const REDACTED: &str = "<redacted>";
+
+/// Field name fragments that mark a value as a secret
+const SECRET_KEY_PARTS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passphrase",
+    "api_key",
+    "apikey",
+    "webhook",
+    "ntfy",
+    "usb_serial",
+    "serial_number",
+];
+
+/// Exported configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigBundle {
+    /// Always [`BUNDLE_FORMAT`]
+    pub format: String,
+    /// Bundle layout version
+    pub version: u32,
+    /// Catapult version that wrote the bundle
+    pub app_version: String,
+    /// When the bundle was written (seconds since the Unix epoch)
+    pub created_unix: u64,
+    /// Settings with secrets redacted
+    pub settings: Value,
+    /// Recent traffic log, if it was included
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logs: Option<String>,
+}
+
+/// How an imported bundle is combined with the current settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ImportMode {
+    /// Use the bundle as is
+    #[default]
+    Replace,
+    /// Add radios and ports the bundle has; on a clash keep the current entry
+    MergeKeepCurrent,
+    /// Add radios and ports the bundle has; on a clash take the bundle's entry
+    MergePreferImported,
+}
+
+impl ImportMode {
+    /// All modes, for UI selection
+    pub const ALL: [ImportMode; 3] = [
+        ImportMode::Replace,
+        ImportMode::MergeKeepCurrent,
+        ImportMode::MergePreferImported,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportMode::Replace => "Replace",
+            ImportMode::MergeKeepCurrent => "Merge (keep current)",
+            ImportMode::MergePreferImported => "Merge (prefer imported)",
+        }
+    }
+}
+
+/// Result of applying a bundle, for review before it is saved
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportPreview {
+    /// Settings that would be saved
+    pub settings: Settings,
+    /// Entries the bundle adds
+    pub added: Vec<String>,
+    /// Entries present in both; says which side won
+    pub conflicts: Vec<String>,
+    /// Fields that were redacted in the bundle and keep their current value
+    pub redacted: usize,
+}
+
+impl ConfigBundle {
+    /// Bundle the given settings, redacting secrets
+    pub fn new(settings: &Settings, logs: Option<String>) -> Result<Self, String> {
+        let mut value = serde_json::to_value(settings)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        redact(&mut value);
+        Ok(Self {
+            format: BUNDLE_FORMAT.to_string(),
+            version: BUNDLE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_unix: SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0),
+            settings: value,
+            logs,
+        })
+    }
+
+    /// Parse and validate a bundle
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let bundle: Self =
+            serde_json::from_str(json).map_err(|e| format!("Not a configuration bundle: {}", e))?;
+        if bundle.format != BUNDLE_FORMAT {
+            return Err(format!("Unknown bundle format '{}'", bundle.format));
+        }
+        if bundle.version > BUNDLE_VERSION {
+            return Err(format!(
+                "Bundle version {} is newer than this catapult supports ({})",
+                bundle.version, BUNDLE_VERSION
+            ));
+        }
+        serde_json::from_value::<Settings>(bundle.settings.clone())
+            .map_err(|e| format!("Invalid settings in bundle: {}", e))?;
+        Ok(bundle)
+    }
+
+    /// Pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize bundle: {}", e))
+    }
+
+    /// Work out what importing this bundle over `current` would produce
+    pub fn preview(&self, current: &Settings, mode: ImportMode) -> Result<ImportPreview, String> {
+        let current_value = serde_json::to_value(current)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        let mut value = self.settings.clone();
+        let redacted = restore_redacted(&mut value, &current_value);
+        let imported = serde_json::from_value::<Settings>(value)
+            .map_err(|e| format!("Invalid settings in bundle: {}", e))?
+            .migrate();
+
+        let mut preview = ImportPreview {
+            settings: imported.clone(),
+            added: Vec::new(),
+            conflicts: Vec::new(),
+            redacted,
+        };
+        let prefer_imported = match mode {
+            ImportMode::Replace => return Ok(preview),
+            ImportMode::MergeKeepCurrent => false,
+            ImportMode::MergePreferImported => true,
+        };
+
+        // Everything but the lists comes from whichever side wins clashes
+        let mut merged = if prefer_imported {
+            imported.clone()
+        } else {
+            current.clone()
+        };
+        merged.configured_radios = merge_by_key(
+            &current.configured_radios,
+            &imported.configured_radios,
+            |r| r.channel_id().to_string(),
+            prefer_imported,
+            &mut preview,
+        );
+        merged.virtual_radios = merge_by_key(
+            &current.virtual_radios,
+            &imported.virtual_radios,
+            |r| format!("virtual radio {}", r.id),
+            prefer_imported,
+            &mut preview,
+        );
+        merged.virtual_ports = merge_by_key(
+            &current.virtual_ports,
+            &imported.virtual_ports,
+            |p| format!("virtual port {}", p.name),
+            prefer_imported,
+            &mut preview,
+        );
+        preview.settings = merged;
+        Ok(preview)
+    }
+
+    /// Write the bundle to a user-selected file
+    /// Returns Ok(Some(path)) on success, Ok(None) if cancelled
+    pub fn save_with_dialog(&self) -> Result<Option<PathBuf>, String> {
+        let path = rfd::FileDialog::new()
+            .set_file_name(format!("catapult-config-{}.json", self.created_unix))
+            .add_filter("Catapult bundle", &["json"])
+            .save_file();
+        let Some(path) = path else {
+            return Ok(None);
+        };
+        std::fs::write(&path, self.to_json()?)
+            .map_err(|e| format!("Failed to write file: {}", e))?;
+        Ok(Some(path))
+    }
+
+    /// Read and validate a bundle from a file
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let json =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        Self::parse(&json)
+    }
+}
+
+/// Union two lists by key, reporting additions and clashes
+pub(crate) fn merge_by_key<T: Clone>(
+    current: &[T],
+    imported: &[T],
+    key: impl Fn(&T) -> String,
+    prefer_imported: bool,
+    preview: &mut ImportPreview,
+) -> Vec<T> {
+    let imported_keys: HashSet<String> = imported.iter().map(&key).collect();
+    let mut merged = Vec::with_capacity(current.len() + imported.len());
+
+    for item in current {
+        let k = key(item);
+        if !imported_keys.contains(&k) {
+            merged.push(item.clone());
+        } else if prefer_imported {
+            let theirs = imported.iter().find(|i| key(i) == k).unwrap_or(item);
+            merged.push(theirs.clone());
+            preview.conflicts.push(format!("{}: using imported", k));
+        } else {
+            merged.push(item.clone());
+            preview.conflicts.push(format!("{}: keeping current", k));
+        }
+    }
+
+    let current_keys: HashSet<String> = current.iter().map(&key).collect();
+    for item in imported {
+        let k = key(item);
+        if !current_keys.contains(&k) {
+            preview.added.push(k);
+            merged.push(item.clone());
+        }
+    }
+    merged
+}
+
+/// Whether a field name looks like it holds a credential
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+/// Replace secret-looking string fields with [`REDACTED`]
+pub(crate) fn redact(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                if is_secret_key(key) && field.is_string() {
+                    *field = Value::String(REDACTED.to_string());
+                } else {
+                    redact(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact),
+        _ => {}
+    }
+}
+
+/// Put current values back into redacted fields; returns how many there were
+///
+/// Redacted fields with no current counterpart become empty strings.
+fn restore_redacted(value: &mut Value, current: &Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, field)| {
+                let theirs = current.get(key).unwrap_or(&Value::Null);
+                if field.as_str() == Some(REDACTED) {
+                    *field = match theirs {
+                        Value::String(_) => theirs.clone(),
+                        _ => Value::String(String::new()),
+                    };
+                    1
+                } else {
+                    restore_redacted(field, theirs)
+                }
+            })
+            .sum(),
+        Value::Array(items) => items
+            .iter_mut()
+            .enumerate()
+            .map(|(i, item)| restore_redacted(item, current.get(i).unwrap_or(&Value::Null)))
+            .sum(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::{VirtualPortConfig, WatchRule};
+    use cat_mux::ChannelId;
+    use cat_protocol::Protocol;
+    use serde_json::json;
+
+    fn port(name: &str, protocol: Protocol) -> VirtualPortConfig {
+        VirtualPortConfig {
+            name: name.to_string(),
+            protocol,
+        }
+    }
+
+    #[test]
+    fn test_redacts_secret_fields() {
+        let mut value = json!({
+            "cluster": { "host": "dx.example.org", "password": "hunter2" },
+            "services": [{ "api_key": "abc", "name": "qrz" }],
+            "token_count": 3,
+            "radio": { "usb_serial": "FT1234", "usb_interface": 1 },
+        });
+        redact(&mut value);
+        assert_eq!(value["cluster"]["password"], REDACTED);
+        assert_eq!(value["radio"]["usb_serial"], REDACTED);
+        assert_eq!(value["cluster"]["host"], "dx.example.org");
+        assert_eq!(value["services"][0]["api_key"], REDACTED);
+        // Only strings are secrets
+        assert_eq!(value["token_count"], 3);
+
+        let current = json!({
+            "cluster": { "password": "local" },
+            "services": [{ "api_key": "mine" }],
+        });
+        assert_eq!(restore_redacted(&mut value, &current), 3);
+        assert_eq!(value["cluster"]["password"], "local");
+        assert_eq!(value["services"][0]["api_key"], "mine");
+        // Another machine's adapter binds by port instead
+        assert_eq!(value["radio"]["usb_serial"], "");
+    }
+
+    #[test]
+    fn test_watch_webhook_redacted() {
+        let mut settings = Settings::default();
+        let mut rule = WatchRule::new("Hot amp", "amp.temp > 60");
+        rule.webhook = "http://hooks.example.org/T0K3N".to_string();
+        settings.watches.rules.push(rule);
+
+        let bundle = ConfigBundle::new(&settings, None).unwrap();
+        assert_eq!(bundle.settings["watches"]["rules"][0]["webhook"], REDACTED);
+        assert!(!bundle.to_json().unwrap().contains("T0K3N"));
+    }
+
+    #[test]
+    fn test_alert_destinations_redacted() {
+        let mut settings = Settings::default();
+        settings.status_page.ntfy_url = "http://ntfy.sh/shack-8f3k2".to_string();
+        settings.status_page.webhook_url = "http://hooks.example.org/T0K3N".to_string();
+
+        let bundle = ConfigBundle::new(&settings, None).unwrap();
+        assert_eq!(bundle.settings["status_page"]["ntfy_url"], REDACTED);
+        assert_eq!(bundle.settings["status_page"]["webhook_url"], REDACTED);
+
+        // Importing the bundle back keeps this machine's topic
+        let preview = bundle.preview(&settings, ImportMode::Replace).unwrap();
+        assert_eq!(preview.settings.status_page, settings.status_page);
+    }
+
+    #[test]
+    fn test_round_trip_and_validation() {
+        let settings = Settings {
+            lockout_ms: 750,
+            ..Default::default()
+        };
+        let json = ConfigBundle::new(&settings, Some("log".into()))
+            .unwrap()
+            .to_json()
+            .unwrap();
+
+        let bundle = ConfigBundle::parse(&json).unwrap();
+        assert_eq!(bundle.logs.as_deref(), Some("log"));
+        let preview = bundle
+            .preview(&Settings::default(), ImportMode::Replace)
+            .unwrap();
+        assert_eq!(preview.settings.lockout_ms, 750);
+
+        assert!(ConfigBundle::parse("{}").is_err());
+        let future = json.replace(
+            &format!("\"version\": {}", BUNDLE_VERSION),
+            &format!("\"version\": {}", BUNDLE_VERSION + 1),
+        );
+        assert!(ConfigBundle::parse(&future).is_err());
+    }
+
+    #[test]
+    fn test_merge_resolves_conflicts() {
+        let current = Settings {
+            lockout_ms: 100,
+            virtual_ports: vec![port("a", Protocol::Kenwood), port("b", Protocol::Kenwood)],
+            ..Default::default()
+        };
+        let theirs = Settings {
+            lockout_ms: 900,
+            virtual_ports: vec![port("b", Protocol::IcomCIV), port("c", Protocol::Elecraft)],
+            ..Default::default()
+        };
+        let bundle = ConfigBundle::new(&theirs, None).unwrap();
+
+        let keep = bundle
+            .preview(&current, ImportMode::MergeKeepCurrent)
+            .unwrap();
+        assert_eq!(keep.settings.lockout_ms, 100);
+        assert_eq!(
+            keep.settings.virtual_ports,
+            vec![
+                port("a", Protocol::Kenwood),
+                port("b", Protocol::Kenwood),
+                port("c", Protocol::Elecraft)
+            ]
+        );
+        assert_eq!(keep.added, vec!["virtual port c"]);
+        assert_eq!(keep.conflicts, vec!["virtual port b: keeping current"]);
+
+        let prefer = bundle
+            .preview(&current, ImportMode::MergePreferImported)
+            .unwrap();
+        assert_eq!(prefer.settings.lockout_ms, 900);
+        assert_eq!(
+            prefer.settings.virtual_ports[1],
+            port("b", Protocol::IcomCIV)
+        );
+    }
+
+    #[test]
+    fn test_migrate_splits_radios_sharing_an_adapter_id() {
+        // Saved before adapter interfaces were told apart: both ports of one
+        // adapter got the ID derived from its serial number
+        let radio = |port: &str| {
+            json!({
+                "id": "usb:FT1234",
+                "port": port,
+                "protocol": Protocol::Kenwood,
+                "model_name": "TS-590",
+                "baud_rate": 9600,
+                "usb_serial": "FT1234",
+            })
+        };
+        let mut value = serde_json::to_value(Settings::default()).unwrap();
+        value["configured_radios"] = json!([
+            radio("/dev/ttyUSB0"),
+            radio("/dev/ttyUSB1"),
+            radio("/dev/ttyUSB0"),
+        ]);
+        let settings = serde_json::from_value::<Settings>(value).unwrap().migrate();
+
+        let ids: Vec<_> = settings
+            .configured_radios
+            .iter()
+            .map(|r| r.channel_id())
+            .collect();
+        assert_eq!(ids[0], ChannelId::usb("FT1234", None));
+        assert_eq!(ids[1], ChannelId::port("/dev/ttyUSB1"));
+        // A true duplicate keeps its ID, to be skipped on restore
+        assert_eq!(ids[2], ids[0]);
+    }
+}
@@ -1,8 +1,11 @@
 //! Custom tracing layer for sending log events to the diagnostics portal
 
+use std::collections::BTreeMap;
 use std::sync::atomic::{AtomicU8, Ordering};
 use std::sync::mpsc::Sender;
 use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
 use tracing::field::{Field, Visit};
 use tracing::subscriber::Interest;
 use tracing::{Event, Level, Metadata, Subscriber};
@@ -10,7 +13,7 @@ use tracing_subscriber::layer::Context;
 use tracing_subscriber::Layer;
 
 /// Crates that belong to this project (for filtering)
-const PROJECT_CRATES: &[&str] = &[
+pub const PROJECT_CRATES: &[&str] = &[
     "catapult",
     "cat_protocol",
     "cat_detect",
@@ -18,12 +21,75 @@ const PROJECT_CRATES: &[&str] = &[
     "cat_sim",
 ];
 
+/// Per-crate diagnostic level, overriding the global level
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CrateLevel {
+    /// Use the global level
+    #[default]
+    Inherit,
+    /// Capture nothing from this crate
+    Off,
+    /// Errors only
+    Error,
+    /// Warnings and above
+    Warn,
+    /// Info and above
+    Info,
+    /// Everything
+    Debug,
+}
+
+impl CrateLevel {
+    /// All choices, in menu order
+    pub const ALL: [CrateLevel; 6] = [
+        CrateLevel::Inherit,
+        CrateLevel::Off,
+        CrateLevel::Error,
+        CrateLevel::Warn,
+        CrateLevel::Info,
+        CrateLevel::Debug,
+    ];
+
+    /// Label for the settings UI
+    pub fn name(&self) -> &'static str {
+        match self {
+            CrateLevel::Inherit => "Global",
+            CrateLevel::Off => "Off",
+            CrateLevel::Error => "Error",
+            CrateLevel::Warn => "Warning",
+            CrateLevel::Info => "Info",
+            CrateLevel::Debug => "Debug",
+        }
+    }
+
+    /// The level this override stands for (None = inherit)
+    fn level(&self) -> Option<Option<Level>> {
+        match self {
+            CrateLevel::Inherit => None,
+            CrateLevel::Off => Some(None),
+            CrateLevel::Error => Some(Some(Level::ERROR)),
+            CrateLevel::Warn => Some(Some(Level::WARN)),
+            CrateLevel::Info => Some(Some(Level::INFO)),
+            CrateLevel::Debug => Some(Some(Level::DEBUG)),
+        }
+    }
+}
+
+/// Encoded value for a crate without an override
+const INHERIT: u8 = u8::MAX;
+
 /// Shared state for dynamic level filtering
 ///
 /// Uses atomic operations for lock-free level changes.
 /// Level encoding: 0=off, 1=error, 2=warn, 3=info, 4=debug, 5=trace
+///
+/// Each project crate may override the global level. Overrides only apply
+/// while the global level is on; turning diagnostics off silences every crate.
 pub struct DiagnosticLevelState {
     level: AtomicU8,
+    /// Per-crate overrides, indexed like [`PROJECT_CRATES`] ([`INHERIT`] = none)
+    overrides: [AtomicU8; PROJECT_CRATES.len()],
 }
 
 impl DiagnosticLevelState {
@@ -31,9 +97,32 @@ impl DiagnosticLevelState {
     pub fn new(level: Option<Level>) -> Self {
         Self {
             level: AtomicU8::new(Self::level_to_u8(level)),
+            overrides: std::array::from_fn(|_| AtomicU8::new(INHERIT)),
         }
     }
 
+    /// Replace all per-crate overrides (crates not in the map inherit)
+    pub fn set_crate_levels(&self, levels: &BTreeMap<String, CrateLevel>) {
+        for (name, slot) in PROJECT_CRATES.iter().zip(&self.overrides) {
+            let value = levels
+                .get(*name)
+                .and_then(|l| l.level())
+                .map_or(INHERIT, Self::level_to_u8);
+            slot.store(value, Ordering::Relaxed);
+        }
+    }
+
+    /// Effective level for an event target
+    pub fn level_for(&self, target: &str) -> Option<Level> {
+        let global = self.get_level()?;
+        PROJECT_CRATES
+            .iter()
+            .position(|name| target.starts_with(name))
+            .map(|idx| self.overrides[idx].load(Ordering::Relaxed))
+            .filter(|value| *value != INHERIT)
+            .map_or(Some(global), Self::u8_to_level)
+    }
+
     /// Update the filter level (atomic store)
     pub fn set_level(&self, level: Option<Level>) {
         self.level
@@ -92,8 +181,8 @@ impl<S> tracing_subscriber::layer::Filter<S> for ProjectCrateFilter {
             return false;
         }
 
-        // Compare event level against current filter level
-        match self.state.get_level() {
+        // Compare event level against the crate's effective filter level
+        match self.state.level_for(target) {
             None => false, // Filter is off
             Some(filter_level) => *meta.level() <= filter_level,
         }
@@ -209,6 +298,28 @@ fn simplify_target(target: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_crate_level_overrides() {
+        let state = DiagnosticLevelState::new(Some(Level::INFO));
+        let mut levels = BTreeMap::new();
+        levels.insert("cat_mux".to_string(), CrateLevel::Debug);
+        levels.insert("cat_detect".to_string(), CrateLevel::Off);
+        state.set_crate_levels(&levels);
+
+        assert_eq!(state.level_for("cat_mux::actor"), Some(Level::DEBUG));
+        assert_eq!(state.level_for("cat_detect::probe"), None);
+        assert_eq!(state.level_for("cat_protocol::icom"), Some(Level::INFO));
+
+        // Global off silences overrides too
+        state.set_level(None);
+        assert_eq!(state.level_for("cat_mux::actor"), None);
+
+        levels.clear();
+        state.set_level(Some(Level::WARN));
+        state.set_crate_levels(&levels);
+        assert_eq!(state.level_for("cat_mux::actor"), Some(Level::WARN));
+    }
+
     #[test]
     fn test_simplify_target() {
         assert_eq!(simplify_target("catapult::app"), "App");
@@ -0,0 +1,580 @@
+//! Translations of the user interface
+//!
+//! Text shown to the user is looked up by message id with [`tr!`], in the
+//! language chosen in Settings (or the system's, when none is chosen). The
+//! messages live in Fluent files under `locales/`, one per language, compiled
+//! into the binary. A message missing from a translation falls back to
+//! English, so a translation can be filled in a piece at a time.
+//!
+//! Numbers other than frequencies use the frequency format's decimal
+//! separator ([`cat_protocol::display::format_decimal`]); choosing a language
+//! switches the separator to the one its speakers expect.
+//!
+//! Japanese needs a font with CJK glyphs, which egui doesn't ship. One is
+//! loaded from the system the first time a frame is drawn in Japanese
+//! ([`ensure_fonts`]).
+
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::SystemTime;
+
+use cat_protocol::display::DecimalSeparator;
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use serde::{Deserialize, Serialize};
+
+/// A language the interface is translated into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+    Japanese,
+}
+
+impl Language {
+    pub const ALL: [Language; 3] = [Language::English, Language::German, Language::Japanese];
+
+    /// BCP 47 language tag
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::Japanese => "ja",
+        }
+    }
+
+    /// Name of the language in itself, for the language list
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+            Language::Japanese => "日本語",
+        }
+    }
+
+    /// Decimal separator its speakers write numbers with
+    pub fn decimal_separator(&self) -> DecimalSeparator {
+        match self {
+            Language::German => DecimalSeparator::Comma,
+            Language::English | Language::Japanese => DecimalSeparator::Point,
+        }
+    }
+
+    /// The language a locale name such as `de_DE.UTF-8` is in, if translated
+    pub fn from_locale(locale: &str) -> Option<Self> {
+        let code = locale.split(['_', '-', '.', '@']).next()?;
+        Self::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(code))
+    }
+
+    /// Messages in this language (Fluent source)
+    fn source(&self) -> &'static str {
+        match self {
+            Language::English => include_str!("../locales/en.ftl"),
+            Language::German => include_str!("../locales/de.ftl"),
+            Language::Japanese => include_str!("../locales/ja.ftl"),
+        }
+    }
+
+    fn from_index(index: u8) -> Self {
+        Self::ALL
+            .get(usize::from(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    fn index(self) -> u8 {
+        Self::ALL.iter().position(|l| *l == self).unwrap_or(0) as u8
+    }
+}
+
+/// The system's language, from the locale environment (English if it isn't
+/// one the interface is translated into)
+pub fn system_language() -> Language {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|locale| Language::from_locale(&locale))
+        .unwrap_or_default()
+}
+
+/// Language every message is looked up in, held for the whole process like
+/// the frequency format
+static LANGUAGE: AtomicU8 = AtomicU8::new(0);
+
+/// Show the interface in `language` from the next frame on
+pub fn set_language(language: Language) {
+    LANGUAGE.store(language.index(), Ordering::Relaxed);
+}
+
+/// The language the interface is shown in
+pub fn language() -> Language {
+    Language::from_index(LANGUAGE.load(Ordering::Relaxed))
+}
+
+/// Every language's messages, parsed once
+struct Catalog {
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl Catalog {
+    fn load() -> Self {
+        let bundles = Language::ALL
+            .iter()
+            .map(|language| {
+                let id = language
+                    .code()
+                    .parse()
+                    .expect("language codes are valid tags");
+                let mut bundle = FluentBundle::new_concurrent(vec![id]);
+                // egui draws the bidi isolation marks Fluent puts around
+                // arguments as boxes
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(language.source().to_string())
+                    .unwrap_or_else(|(resource, errors)| {
+                        tracing::warn!(
+                            "{} translation has {} syntax errors",
+                            language.code(),
+                            errors.len()
+                        );
+                        resource
+                    });
+                if let Err(errors) = bundle.add_resource(resource) {
+                    tracing::warn!(
+                        "{} translation defines {} messages twice",
+                        language.code(),
+                        errors.len()
+                    );
+                }
+                bundle
+            })
+            .collect();
+        Self { bundles }
+    }
+
+    fn format(&self, language: Language, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+        let bundle = &self.bundles[usize::from(language.index())];
+        let pattern = bundle.get_message(id)?.value()?;
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args, &mut errors);
+        Some(text.into_owned())
+    }
+}
+
+fn catalog() -> &'static Catalog {
+    static CATALOG: OnceLock<Catalog> = OnceLock::new();
+    CATALOG.get_or_init(Catalog::load)
+}
+
+/// Message `id` in the current language, falling back to English and then
+/// to the id itself
+pub fn message(id: &str, args: Option<&FluentArgs>) -> String {
+    message_in(language(), id, args)
+}
+
+fn message_in(language: Language, id: &str, args: Option<&FluentArgs>) -> String {
+    let catalog = catalog();
+    catalog
+        .format(language, id, args)
+        .or_else(|| catalog.format(Language::English, id, args))
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// Look up a message in the current language, with optional arguments:
+/// `tr!("search-matches", count = found)`
+macro_rules! tr {
+    ($id:literal) => {
+        $crate::i18n::message($id, None)
+    };
+    ($id:literal, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::message($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+/// A time as a UTC date and time of day, written the way the interface's
+/// language writes dates
+pub fn format_date_time(timestamp: SystemTime) -> String {
+    format_date_time_in(language(), timestamp)
+}
+
+fn format_date_time_in(language: Language, timestamp: SystemTime) -> String {
+    let secs = timestamp
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let tod = secs % 86_400;
+    let time = format!(
+        "{:02}:{:02}:{:02} UTC",
+        tod / 3600,
+        (tod % 3600) / 60,
+        tod % 60
+    );
+    match language {
+        Language::English => format!("{:04}-{:02}-{:02} {}", year, month, day, time),
+        Language::German => format!("{:02}.{:02}.{:04} {}", day, month, year, time),
+        Language::Japanese => format!("{}年{}月{}日 {}", year, month, day, time),
+    }
+}
+
+/// Days since 1970-01-01 to a (year, month, day) date (proleptic Gregorian)
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// System fonts with Japanese glyphs, most likely first
+const CJK_FONTS: &[&str] = &[
+    // Linux (Noto CJK, as packaged by Debian, Arch and Fedora)
+    "/usr/share/fonts/opentype/noto/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/google-noto-cjk/NotoSansCJK-Regular.ttc",
+    "/usr/share/fonts/truetype/fonts-japanese-gothic.ttf",
+    // macOS
+    "/System/Library/Fonts/ヒラギノ角ゴシック W3.ttc",
+    "/System/Library/Fonts/Hiragino Sans GB.ttc",
+    // Windows
+    "C:\\Windows\\Fonts\\YuGothM.ttc",
+    "C:\\Windows\\Fonts\\meiryo.ttc",
+    "C:\\Windows\\Fonts\\msgothic.ttc",
+];
+
+/// Add a system CJK font as a fallback, the first time the interface is
+/// shown in a language that needs one
+pub fn ensure_fonts(ctx: &egui::Context) {
+    static LOADED: AtomicBool = AtomicBool::new(false);
+    if language() != Language::Japanese || LOADED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let Some((path, data)) = CJK_FONTS
+        .iter()
+        .find_map(|path| std::fs::read(path).ok().map(|data| (path, data)))
+    else {
+        tracing::warn!("No Japanese font found; install Noto Sans CJK to show Japanese text");
+        return;
+    };
+    tracing::info!("Using {} for Japanese text", path);
+    let mut fonts = egui::FontDefinitions::default();
+    fonts.font_data.insert(
+        "cjk".to_string(),
+        Arc::new(egui::FontData::from_owned(data)),
+    );
+    for family in [egui::FontFamily::Proportional, egui::FontFamily::Monospace] {
+        fonts
+            .families
+            .entry(family)
+            .or_default()
+            .push("cjk".to_string());
+    }
+    ctx.set_fonts(fonts);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::Path;
+
+    use super::*;
+
+    /// Message ids defined in a language's file
+    fn ids(language: Language) -> BTreeSet<String> {
+        if let Err((_, errors)) = FluentResource::try_new(language.source().to_string()) {
+            panic!("{}: {:?}", language.code(), errors);
+        }
+        // Messages start a line with their id; continuations are indented
+        language
+            .source()
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_alphabetic()))
+            .filter_map(|line| line.split_once('='))
+            .map(|(id, _)| id.trim().to_string())
+            .collect()
+    }
+
+    /// Message ids `tr!` is called with anywhere in the source
+    fn used_ids(dir: &Path, found: &mut BTreeSet<String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                used_ids(&path, found);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let source = std::fs::read_to_string(&path).unwrap();
+                for (at, call) in source.match_indices("tr!(\"") {
+                    // Not the tail of another macro (include_str!)
+                    let in_other_macro =
+                        source[..at].ends_with(|c: char| c.is_alphanumeric() || c == '_');
+                    let rest = &source[at + call.len()..];
+                    if let (false, Some((id, _))) = (in_other_macro, rest.split_once('"')) {
+                        found.insert(id.to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Calls whose string arguments are shown to the user
+    const UI_CALLS: &[&str] = &[
+        ".label(",
+        ".button(",
+        ".small_button(",
+        ".heading(",
+        ".small(",
+        ".strong(",
+        ".weak(",
+        ".colored_label(",
+        ".selectable_label(",
+        ".selectable_value(",
+        ".radio_value(",
+        ".checkbox(",
+        ".collapsing(",
+        ".menu_button(",
+        ".selected_text(",
+        ".hint_text(",
+        ".on_hover_text(",
+        ".on_disabled_hover_text(",
+        "RichText::new(",
+        "Button::new(",
+        "Window::new(",
+        "CollapsingHeader::new(",
+        "ComboBox::from_label(",
+        ".set_status(",
+        ".report_info(",
+        ".report_warning(",
+        ".report_err(",
+    ];
+
+    /// Literals that are names rather than words, and read the same in
+    /// every language
+    const NAMES: &[&str] = &["rigctl", "ntfy", "Hamlib", "flrig"];
+
+    /// Arguments of the call whose opening parenthesis ends at `start`
+    fn call_args(source: &str, start: usize) -> &str {
+        let mut depth = 1;
+        let mut chars = source[start..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    while let Some((_, c)) = chars.next() {
+                        match c {
+                            '\\' => {
+                                chars.next();
+                            }
+                            '"' => break,
+                            _ => {}
+                        }
+                    }
+                }
+                '(' => depth += 1,
+                ')' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return &source[start..start + i];
+                    }
+                }
+                _ => {}
+            }
+        }
+        &source[start..]
+    }
+
+    /// String literals in `args`, with the text just before each
+    fn literals(args: &str) -> Vec<(&str, &str)> {
+        let mut found = Vec::new();
+        let mut chars = args.char_indices();
+        while let Some((open, c)) = chars.next() {
+            if c != '"' {
+                continue;
+            }
+            while let Some((close, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        chars.next();
+                    }
+                    '"' => {
+                        found.push((&args[..open], &args[open + 1..close]));
+                        break;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        found
+    }
+
+    /// Whether a literal reads as words (a capital-or-not letter followed
+    /// by at least two lowercase ones) rather than a unit or placeholder;
+    /// `format!` placeholders such as `{:0width$}` don't count
+    fn has_words(text: &str) -> bool {
+        let mut depth = 0;
+        let chars: Vec<char> = text
+            .chars()
+            .filter(|&c| {
+                match c {
+                    '{' => depth += 1,
+                    '}' => depth -= 1,
+                    _ => return depth == 0,
+                }
+                false
+            })
+            .collect();
+        chars
+            .windows(3)
+            .any(|w| w[0].is_alphabetic() && w[1].is_lowercase() && w[2].is_lowercase())
+    }
+
+    /// User-facing literals in the source under `dir` that bypass `tr!`,
+    /// by file and position (a literal inside nested calls is seen once)
+    fn untranslated(dir: &Path, found: &mut BTreeMap<(String, usize), String>) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                untranslated(&path, found);
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "rs") || path.ends_with("i18n.rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            let source = match source.find("#[cfg(test)]\nmod tests") {
+                Some(end) => &source[..end],
+                None => &source[..],
+            };
+            for call in UI_CALLS {
+                for (at, _) in source.match_indices(call) {
+                    let start = at + call.len();
+                    let args = call_args(source, start);
+                    for (index, (before, text)) in literals(args).into_iter().enumerate() {
+                        let translated = before
+                            .trim_end()
+                            .strip_suffix('(')
+                            .is_some_and(|b| b.ends_with("tr!"));
+                        // The source of a report is a log category, not a message
+                        let category =
+                            index == 0 && call.starts_with(".report_") && before.trim().is_empty();
+                        // Example addresses in hint text
+                        let url = text.contains("://");
+                        // Source tags such as [→Shadow] read as in exported logs
+                        let tag = text.starts_with('[') && text.ends_with(']');
+                        // Widget ids inside a closure passed to the call
+                        let id_salt = text.contains('_')
+                            && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                        if !translated
+                            && !category
+                            && !url
+                            && !tag
+                            && !id_salt
+                            && has_words(text)
+                            && !NAMES.contains(&text)
+                        {
+                            let key = (path.display().to_string(), start + before.len());
+                            found.insert(key, text.to_string());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_no_untranslated_text() {
+        let mut found = BTreeMap::new();
+        untranslated(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut found,
+        );
+        let listed: Vec<String> = found
+            .iter()
+            .map(|((path, _), text)| format!("{}: {:?}", path, text))
+            .collect();
+        assert!(
+            listed.is_empty(),
+            "{} literals not looked up with tr!:\n{}",
+            listed.len(),
+            listed.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_translations_cover_english() {
+        let english = ids(Language::English);
+        for language in [Language::German, Language::Japanese] {
+            let translated = ids(language);
+            let extra: Vec<_> = translated.difference(&english).collect();
+            assert!(extra.is_empty(), "{} only: {:?}", language.code(), extra);
+            let missing: Vec<_> = english.difference(&translated).collect();
+            assert!(
+                missing.is_empty(),
+                "{} missing: {:?}",
+                language.code(),
+                missing
+            );
+        }
+
+        let mut used = BTreeSet::new();
+        used_ids(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+            &mut used,
+        );
+        let undefined: Vec<_> = used.difference(&english).collect();
+        assert!(undefined.is_empty(), "not in en.ftl: {:?}", undefined);
+    }
+
+    #[test]
+    fn test_messages_and_fallback() {
+        assert_eq!(
+            message_in(Language::German, "toolbar-settings", None),
+            "Einstellungen"
+        );
+        let mut args = FluentArgs::new();
+        args.set("found", 3);
+        assert_eq!(
+            message_in(Language::English, "search-searching", Some(&args)),
+            "Searching... 3 found"
+        );
+        // Unknown ids come back as they are
+        assert_eq!(
+            message_in(Language::Japanese, "no-such-message", None),
+            "no-such-message"
+        );
+    }
+
+    #[test]
+    fn test_format_date_time() {
+        let time = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1_792_245_785);
+        assert_eq!(
+            format_date_time_in(Language::German, time),
+            "17.10.2026 14:03:05 UTC"
+        );
+        assert_eq!(
+            format_date_time_in(Language::Japanese, time),
+            "2026年10月17日 14:03:05 UTC"
+        );
+        assert_eq!(
+            format_date_time_in(Language::English, time),
+            "2026-10-17 14:03:05 UTC"
+        );
+    }
+
+    #[test]
+    fn test_language_from_locale() {
+        assert_eq!(Language::from_locale("de_DE.UTF-8"), Some(Language::German));
+        assert_eq!(Language::from_locale("ja"), Some(Language::Japanese));
+        assert_eq!(Language::from_locale("en-GB"), Some(Language::English));
+        assert_eq!(Language::from_locale("fr_FR"), None);
+        assert_eq!(Language::from_locale("C"), None);
+    }
+}
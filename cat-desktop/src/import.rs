@@ -0,0 +1,482 @@
+//! Radio import from OmniRig and Ham Radio Deluxe
+//!
+//! Users moving from another CAT program usually have their radios set up
+//! there already. This reads those setups into [`ConfiguredRadio`]s:
+//!
+//! - OmniRig's `OmniRig.ini` has a `[RIG1]`, `[RIG2]`... section per radio
+//!   with `RigType`, `Port` (the COM number), `BaudRate`, `DataBits`,
+//!   `Parity`, `StopBits`, `RtsMode`, `DtrMode` and `PollMs`. It holds no
+//!   CI-V address; that lives in the rig's command file, so Icom radios get
+//!   their model's default address.
+//! - Ham Radio Deluxe keeps its radios as XML elements with `Company`,
+//!   `Radio`, `COM`, `Speed` and, for Icom, `CIV` attributes.
+//!
+//! The protocol is worked out from the model name. Radios whose model isn't
+//! in the radio database are skipped and listed, as are entries without a
+//! port. The result is previewed and merged like a configuration bundle.
+
+use std::path::Path;
+
+use cat_mux::ChannelId;
+use cat_protocol::{Protocol, ProtocolId, RadioDatabase, RadioModel};
+
+use crate::config_bundle::{merge_by_key, ImportMode, ImportPreview};
+use crate::settings::{
+    ConfiguredRadio, LineLevel, RadioLinkSettings, SerialDataBits, SerialFlowControl, SerialParity,
+    SerialStopBits, Settings,
+};
+
+/// Baud rate for entries that don't give one
+const FALLBACK_BAUD: u32 = 9600;
+
+/// Protocols searched for a model name, in order
+const PROTOCOLS: [Protocol; 6] = [
+    Protocol::IcomCIV,
+    Protocol::Kenwood,
+    Protocol::Elecraft,
+    Protocol::YaesuAscii,
+    Protocol::Yaesu,
+    Protocol::FlexRadio,
+];
+
+/// Program a radio setup is imported from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportSource {
+    OmniRig,
+    HamRadioDeluxe,
+}
+
+impl ImportSource {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            ImportSource::OmniRig => "OmniRig",
+            ImportSource::HamRadioDeluxe => "Ham Radio Deluxe",
+        }
+    }
+}
+
+/// Radios read from another program's configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImportedRadios {
+    /// Radios that could be converted
+    pub radios: Vec<ConfiguredRadio>,
+    /// Entries that couldn't, and why
+    pub skipped: Vec<String>,
+}
+
+impl ImportedRadios {
+    /// Read radios from a file, telling the format from its contents
+    pub fn load(path: &Path) -> Result<(ImportSource, Self), String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("Failed to read file: {}", e))?;
+        Self::parse(&text)
+    }
+
+    /// Read radios from OmniRig or HRD configuration text
+    pub fn parse(text: &str) -> Result<(ImportSource, Self), String> {
+        let source = if text.trim_start().starts_with('<') {
+            ImportSource::HamRadioDeluxe
+        } else {
+            ImportSource::OmniRig
+        };
+        let imported = match source {
+            ImportSource::OmniRig => parse_omnirig(text),
+            ImportSource::HamRadioDeluxe => parse_hrd(text),
+        };
+        if imported.radios.is_empty() && imported.skipped.is_empty() {
+            return Err(format!("No {} radios found in file", source.name()));
+        }
+        Ok((source, imported))
+    }
+
+    /// Work out what adding these radios to `current` would produce
+    ///
+    /// Only the radio list changes; [`ImportMode::Replace`] swaps it for the
+    /// imported radios.
+    pub fn preview(&self, current: &Settings, mode: ImportMode) -> ImportPreview {
+        let mut preview = ImportPreview {
+            settings: current.clone(),
+            added: Vec::new(),
+            conflicts: Vec::new(),
+            redacted: 0,
+        };
+        let prefer_imported = match mode {
+            ImportMode::Replace => {
+                preview.settings.configured_radios = self.radios.clone();
+                preview.added = self.radios.iter().map(describe).collect();
+                return preview;
+            }
+            ImportMode::MergeKeepCurrent => false,
+            ImportMode::MergePreferImported => true,
+        };
+        preview.settings.configured_radios = merge_by_key(
+            &current.configured_radios,
+            &self.radios,
+            |r| r.channel_id().to_string(),
+            prefer_imported,
+            &mut preview,
+        );
+        preview
+    }
+}
+
+/// Radios in an `OmniRig.ini`
+pub fn parse_omnirig(text: &str) -> ImportedRadios {
+    let mut imported = ImportedRadios::default();
+    for (section, keys) in ini_sections(text) {
+        if !section.to_ascii_uppercase().starts_with("RIG") {
+            continue;
+        }
+        let get = |key: &str| {
+            keys.iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        };
+        let number = |key: &str| get(key).and_then(|v| v.parse::<u32>().ok());
+
+        let Some(rig_type) = get("RigType").filter(|t| !t.is_empty() && *t != "NONE") else {
+            continue;
+        };
+        let Some(port) = number("Port").filter(|&p| p > 0) else {
+            imported
+                .skipped
+                .push(format!("{} ({}): no COM port set", section, rig_type));
+            continue;
+        };
+        let Some(model) = find_model(rig_type) else {
+            imported
+                .skipped
+                .push(format!("{} ({}): model not recognized", section, rig_type));
+            continue;
+        };
+
+        let rts = number("RtsMode");
+        let mut link = RadioLinkSettings {
+            data_bits: match number("DataBits") {
+                Some(7) => SerialDataBits::Seven,
+                _ => SerialDataBits::Eight,
+            },
+            // OmniRig stores the index into None, Odd, Even, Mark, Space
+            parity: match number("Parity") {
+                Some(1) => SerialParity::Odd,
+                Some(2) => SerialParity::Even,
+                _ => SerialParity::None,
+            },
+            // Index into 1, 1.5, 2
+            stop_bits: match number("StopBits") {
+                Some(2) => SerialStopBits::Two,
+                _ => SerialStopBits::One,
+            },
+            dtr: omnirig_line(number("DtrMode")),
+            rts: omnirig_line(rts),
+            ..RadioLinkSettings::default()
+        };
+        if let Some(poll_ms) = number("PollMs") {
+            link.poll_interval_ms = poll_ms;
+        }
+        imported.radios.push(configured_radio(
+            format!("COM{}", port),
+            &model,
+            number("BaudRate"),
+            None,
+            // RTS in handshake mode is hardware flow control
+            if rts == Some(2) {
+                SerialFlowControl::Hardware
+            } else {
+                SerialFlowControl::None
+            },
+            link,
+        ));
+    }
+    imported
+}
+
+/// Radios in a Ham Radio Deluxe radio definition file
+pub fn parse_hrd(text: &str) -> ImportedRadios {
+    let mut imported = ImportedRadios::default();
+    for attrs in xml_elements(text) {
+        let get = |key: &str| {
+            attrs
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        };
+        let Some(radio) = get("Radio").filter(|r| !r.is_empty()) else {
+            continue;
+        };
+        let name = match get("Company") {
+            Some(company) => format!("{} {}", company, radio),
+            None => radio.to_string(),
+        };
+        let Some(port) = get("COM").or_else(|| get("Port")).filter(|p| !p.is_empty()) else {
+            imported.skipped.push(format!("{}: no COM port set", name));
+            continue;
+        };
+        let Some(model) = find_model(radio) else {
+            imported
+                .skipped
+                .push(format!("{}: model not recognized", name));
+            continue;
+        };
+        // HRD writes the port either as "COM4" or just "4"
+        let port = if port.bytes().all(|b| b.is_ascii_digit()) {
+            format!("COM{}", port)
+        } else {
+            port.to_string()
+        };
+        let civ_address = get("CIV")
+            .map(|a| a.trim_start_matches("0x").trim_end_matches(['h', 'H']))
+            .and_then(|a| u8::from_str_radix(a, 16).ok());
+        imported.radios.push(configured_radio(
+            port,
+            &model,
+            get("Speed").and_then(|s| s.parse().ok()),
+            civ_address,
+            SerialFlowControl::None,
+            RadioLinkSettings::default(),
+        ));
+    }
+    imported
+}
+
+/// Build a configured radio for a recognized model
+fn configured_radio(
+    port: String,
+    model: &RadioModel,
+    baud_rate: Option<u32>,
+    civ_address: Option<u8>,
+    flow_control: SerialFlowControl,
+    link: RadioLinkSettings,
+) -> ConfiguredRadio {
+    let civ_address = match model.protocol_id {
+        ProtocolId::CivAddress(default) => Some(civ_address.unwrap_or(default)),
+        _ => None,
+    };
+    ConfiguredRadio {
+        id: Some(ChannelId::for_serial_port(&port, None, None)),
+        port,
+        protocol: model.protocol,
+        model_name: model.model.clone(),
+        baud_rate: baud_rate.filter(|&b| b > 0).unwrap_or(FALLBACK_BAUD),
+        civ_address,
+        flow_control,
+        usb_serial: None,
+        usb_interface: None,
+        alias: None,
+        input_throttle_ms: 0,
+        disabled: false,
+        monitor: None,
+        link,
+    }
+}
+
+/// One-line description of an imported radio, e.g. "IC-7300 on COM4"
+fn describe(radio: &ConfiguredRadio) -> String {
+    format!("{} on {}", radio.model_name, radio.port)
+}
+
+/// OmniRig's RTS/DTR mode: 0 = low, 1 = high, 2 = handshake
+fn omnirig_line(mode: Option<u32>) -> LineLevel {
+    match mode {
+        Some(0) => LineLevel::Low,
+        Some(1) => LineLevel::High,
+        _ => LineLevel::Unchanged,
+    }
+}
+
+/// Radio database entry for a model name as another program spells it
+///
+/// Case, spaces and dashes are ignored, so "FTDX-101D" finds "FTDX101D".
+fn find_model(name: &str) -> Option<RadioModel> {
+    let key = |s: &str| {
+        s.chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_uppercase()
+    };
+    let wanted = key(name);
+    PROTOCOLS
+        .iter()
+        .flat_map(|&p| RadioDatabase::radios_for_protocol(p))
+        .find(|m| key(&m.model) == wanted)
+}
+
+/// `[section]` headers and their `key=value` lines
+fn ini_sections(text: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let mut sections: Vec<(String, Vec<(String, String)>)> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push((name.trim().to_string(), Vec::new()));
+        } else if let (Some((key, value)), Some((_, keys))) =
+            (line.split_once('='), sections.last_mut())
+        {
+            keys.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+/// Attributes of each element in an XML document
+///
+/// Enough for the flat attribute lists HRD writes; text content, comments
+/// and entities other than the common five are not handled.
+fn xml_elements(text: &str) -> Vec<Vec<(String, String)>> {
+    let mut elements = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if tag.starts_with(['/', '?', '!']) {
+            continue;
+        }
+        let mut attrs = Vec::new();
+        // Skip the element name
+        let mut body = tag.split_once(char::is_whitespace).map_or("", |(_, b)| b);
+        while let Some((name, after)) = body.split_once('=') {
+            let after = after.trim_start();
+            let Some(quote) = after.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+                break;
+            };
+            let Some((value, tail)) = after[1..].split_once(quote) else {
+                break;
+            };
+            attrs.push((name.trim().to_string(), unescape_xml(value)));
+            body = tail;
+        }
+        elements.push(attrs);
+    }
+    elements
+}
+
+fn unescape_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OMNIRIG_INI: &str = "\
+[RIG1]
+RigType=IC-7300
+Port=4
+BaudRate=115200
+DataBits=8
+Parity=0
+StopBits=0
+RtsMode=1
+DtrMode=1
+PollMs=500
+TimeoutMs=4000
+
+[RIG2]
+RigType=FTDX-101D
+Port=7
+BaudRate=38400
+DataBits=8
+Parity=0
+StopBits=2
+RtsMode=2
+DtrMode=0
+PollMs=250
+
+[RIG3]
+RigType=NONE
+Port=0
+";
+
+    const HRD_XML: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Radios>
+  <Radio Company="Icom" Radio="IC-705" COM="COM9" Speed="19200" CIV="A4"/>
+  <Radio Company="Kenwood" Radio="TS-590SG" COM="5" Speed="57600"/>
+  <Radio Company="Acme" Radio="Rig 9000" COM="COM2" Speed="4800"/>
+</Radios>
+"#;
+
+    #[test]
+    fn test_omnirig_ini() {
+        let (source, imported) = ImportedRadios::parse(OMNIRIG_INI).unwrap();
+        assert_eq!(source, ImportSource::OmniRig);
+        assert!(imported.skipped.is_empty());
+        assert_eq!(imported.radios.len(), 2);
+
+        let icom = &imported.radios[0];
+        assert_eq!(icom.port, "COM4");
+        assert_eq!(icom.protocol, Protocol::IcomCIV);
+        assert_eq!(icom.model_name, "IC-7300");
+        assert_eq!(icom.baud_rate, 115200);
+        // The model's default address
+        assert_eq!(icom.civ_address, Some(0x94));
+        assert_eq!(icom.flow_control, SerialFlowControl::None);
+        assert_eq!(icom.link.rts, LineLevel::High);
+        assert_eq!(icom.link.poll_interval_ms, 500);
+
+        let yaesu = &imported.radios[1];
+        assert_eq!(yaesu.port, "COM7");
+        assert_eq!(yaesu.protocol, Protocol::YaesuAscii);
+        assert_eq!(yaesu.model_name, "FTDX101D");
+        assert_eq!(yaesu.civ_address, None);
+        assert_eq!(yaesu.flow_control, SerialFlowControl::Hardware);
+        assert_eq!(yaesu.link.stop_bits, SerialStopBits::Two);
+        assert_eq!(yaesu.link.dtr, LineLevel::Low);
+    }
+
+    #[test]
+    fn test_hrd_xml() {
+        let (source, imported) = ImportedRadios::parse(HRD_XML).unwrap();
+        assert_eq!(source, ImportSource::HamRadioDeluxe);
+        assert_eq!(imported.radios.len(), 2);
+        assert_eq!(
+            imported.skipped,
+            vec!["Acme Rig 9000: model not recognized".to_string()]
+        );
+
+        let icom = &imported.radios[0];
+        assert_eq!(icom.port, "COM9");
+        assert_eq!(icom.model_name, "IC-705");
+        assert_eq!(icom.baud_rate, 19200);
+        assert_eq!(icom.civ_address, Some(0xA4));
+
+        let kenwood = &imported.radios[1];
+        assert_eq!(kenwood.port, "COM5");
+        assert_eq!(kenwood.protocol, Protocol::Kenwood);
+        assert_eq!(kenwood.baud_rate, 57600);
+    }
+
+    #[test]
+    fn test_empty_file_is_an_error() {
+        assert!(ImportedRadios::parse("[Settings]\nFoo=1\n").is_err());
+        assert!(ImportedRadios::parse("<Radios></Radios>").is_err());
+    }
+
+    #[test]
+    fn test_preview_merges_radios() {
+        let (_, imported) = ImportedRadios::parse(OMNIRIG_INI).unwrap();
+        let mut current = Settings::default();
+        let mut existing = imported.radios[0].clone();
+        existing.alias = Some("Shack".to_string());
+        current.configured_radios.push(existing);
+
+        let preview = imported.preview(&current, ImportMode::MergeKeepCurrent);
+        assert_eq!(preview.settings.configured_radios.len(), 2);
+        assert_eq!(
+            preview.settings.configured_radios[0].alias.as_deref(),
+            Some("Shack")
+        );
+        assert_eq!(preview.added.len(), 1);
+        assert_eq!(preview.conflicts.len(), 1);
+
+        let preview = imported.preview(&current, ImportMode::Replace);
+        assert_eq!(preview.settings.configured_radios, imported.radios);
+    }
+}
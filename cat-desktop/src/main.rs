@@ -7,11 +7,21 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod bug_report;
+mod capture_watch;
+mod config_bundle;
 mod diagnostics_layer;
+mod i18n;
+mod import;
+mod meters;
 mod port_info;
 mod radio_panel;
 mod settings;
+mod settings_store;
 mod simulation_panel;
+mod startup_guard;
+#[cfg(test)]
+mod test_util;
 mod traffic_monitor;
 
 use std::sync::mpsc;
@@ -93,6 +103,7 @@ use diagnostics_layer::{
 };
 use eframe::NativeOptions;
 use settings::Settings;
+use settings_store::Loaded;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::Layer;
@@ -101,14 +112,21 @@ fn main() -> eframe::Result<()> {
     // Install panic handler first, before anything else can panic
     install_panic_handler();
 
-    // Load settings to get initial diagnostic level
-    let settings = Settings::load();
+    // Read settings once: the initial diagnostic level and window come from
+    // them here, and the app decides whether they are safe to act on
+    let loaded = Settings::read();
+    let defaults = Settings::default();
+    let settings = match &loaded {
+        Loaded::Ok(settings) => settings,
+        Loaded::Missing | Loaded::Unreadable { .. } => &defaults,
+    };
 
     // Create channel for diagnostic events (before tracing init so we can capture all logs)
     let (diag_tx, diag_rx) = mpsc::channel::<DiagnosticEvent>();
 
     // Create shared state for dynamic level filtering (atomic, no parsing overhead on changes)
     let diagnostic_level_state = Arc::new(DiagnosticLevelState::new(settings.diagnostic_level));
+    diagnostic_level_state.set_crate_levels(&settings.diagnostic_crate_levels);
     let diagnostics_filter = ProjectCrateFilter::new(Arc::clone(&diagnostic_level_state));
 
     // Initialize logging with two separate filter chains:
@@ -129,11 +147,18 @@ fn main() -> eframe::Result<()> {
     // Create global tokio runtime for async serial I/O
     let rt = tokio::runtime::Runtime::new().expect("Failed to create tokio runtime");
 
+    // Reopen the window where it was left
+    let layout = &settings.layout;
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(layout.window_size.unwrap_or(app::DEFAULT_WINDOW_SIZE))
+        .with_min_inner_size([800.0, 600.0])
+        .with_maximized(layout.maximized)
+        .with_title("Catapult - CAT Multiplexer");
+    if let Some(position) = layout.window_position {
+        viewport = viewport.with_position(position);
+    }
     let options = NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1024.0, 768.0])
-            .with_min_inner_size([800.0, 600.0])
-            .with_title("Catapult - CAT Multiplexer"),
+        viewport,
         ..Default::default()
     };
 
@@ -144,6 +169,7 @@ fn main() -> eframe::Result<()> {
         Box::new(move |cc| {
             Ok(Box::new(CatapultApp::new(
                 cc,
+                loaded,
                 diag_rx,
                 rt,
                 diagnostic_level_state,
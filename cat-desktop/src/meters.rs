@@ -0,0 +1,199 @@
+//! Radio panel meters
+//!
+//! Meter readings arrive as throttled `MuxEvent::RadioMeter` events (a few per
+//! second at most). The needle position between readings is computed from the
+//! time since the last one, so drawing needs no per-frame state updates.
+
+use std::time::{Duration, Instant};
+
+use cat_protocol::MeterKind;
+use serde::{Deserialize, Serialize};
+
+/// Meter needle ballistics
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct MeterBallistics {
+    /// Rise time constant in milliseconds
+    #[serde(default = "default_attack_ms")]
+    pub attack_ms: u32,
+    /// Time for the needle to fall from full scale to zero, in milliseconds
+    #[serde(default = "default_decay_ms")]
+    pub decay_ms: u32,
+    /// How long the peak marker holds, in milliseconds (0 disables)
+    #[serde(default = "default_peak_hold_ms")]
+    pub peak_hold_ms: u32,
+}
+
+fn default_attack_ms() -> u32 {
+    50
+}
+
+fn default_decay_ms() -> u32 {
+    800
+}
+
+fn default_peak_hold_ms() -> u32 {
+    1500
+}
+
+impl Default for MeterBallistics {
+    fn default() -> Self {
+        Self {
+            attack_ms: default_attack_ms(),
+            decay_ms: default_decay_ms(),
+            peak_hold_ms: default_peak_hold_ms(),
+        }
+    }
+}
+
+/// One meter's last reading and needle state
+#[derive(Debug, Clone, Copy)]
+pub struct MeterDisplay {
+    /// Needle position when the last reading arrived (0.0-1.0)
+    from: f32,
+    /// Last reading (0.0-1.0)
+    target: f32,
+    /// When the last reading arrived
+    at: Instant,
+    /// Held peak (0.0-1.0)
+    peak: f32,
+    /// When the peak was set
+    peak_at: Instant,
+}
+
+impl MeterDisplay {
+    fn new(level: f32, now: Instant) -> Self {
+        Self {
+            from: 0.0,
+            target: level,
+            at: now,
+            peak: level,
+            peak_at: now,
+        }
+    }
+
+    /// Needle position at `now` (0.0-1.0)
+    pub fn value(&self, now: Instant, ballistics: &MeterBallistics) -> f32 {
+        let dt = now.saturating_duration_since(self.at).as_secs_f32();
+        if self.target >= self.from {
+            let tau = (ballistics.attack_ms as f32 / 1000.0).max(f32::EPSILON);
+            self.target - (self.target - self.from) * (-dt / tau).exp()
+        } else {
+            let fall = dt * 1000.0 / (ballistics.decay_ms.max(1) as f32);
+            (self.from - fall).max(self.target)
+        }
+    }
+
+    /// Peak marker position at `now`, if peak hold is on
+    pub fn peak(&self, now: Instant, ballistics: &MeterBallistics) -> Option<f32> {
+        let hold = Duration::from_millis(ballistics.peak_hold_ms as u64);
+        (!hold.is_zero() && now.saturating_duration_since(self.peak_at) < hold).then_some(self.peak)
+    }
+
+    /// Whether the needle is still moving or a peak is still held
+    pub fn is_animating(&self, now: Instant, ballistics: &MeterBallistics) -> bool {
+        (self.value(now, ballistics) - self.target).abs() > 0.002
+            || self.peak(now, ballistics).is_some()
+    }
+
+    fn update(&mut self, level: f32, now: Instant, ballistics: &MeterBallistics) {
+        if self.peak(now, ballistics).is_none_or(|p| level >= p) {
+            self.peak = level;
+            self.peak_at = now;
+        }
+        self.from = self.value(now, ballistics);
+        self.target = level;
+        self.at = now;
+    }
+}
+
+/// Meters shown on a radio panel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RadioMeters {
+    pub signal: Option<MeterDisplay>,
+    pub power: Option<MeterDisplay>,
+    pub swr: Option<MeterDisplay>,
+}
+
+impl RadioMeters {
+    /// Record a reading (0-255 of full scale)
+    pub fn record(
+        &mut self,
+        meter: MeterKind,
+        level: u8,
+        now: Instant,
+        ballistics: &MeterBallistics,
+    ) {
+        let level = level as f32 / 255.0;
+        let slot = match meter {
+            MeterKind::Signal => &mut self.signal,
+            MeterKind::Power => &mut self.power,
+            MeterKind::Swr => &mut self.swr,
+        };
+        match slot {
+            Some(display) => display.update(level, now, ballistics),
+            None => *slot = Some(MeterDisplay::new(level, now)),
+        }
+    }
+
+    /// Whether any meter needs another frame
+    pub fn is_animating(&self, now: Instant, ballistics: &MeterBallistics) -> bool {
+        [self.signal, self.power, self.swr]
+            .iter()
+            .flatten()
+            .any(|m| m.is_animating(now, ballistics))
+    }
+}
+
+/// S-meter label for a needle position (S9 at 120/255, 10 dB steps above)
+pub fn s_unit_label(value: f32) -> String {
+    let level = value * 255.0;
+    if level <= 120.0 {
+        format!("S{}", (level / 120.0 * 9.0).round() as u32)
+    } else {
+        let over = ((level - 120.0) / (255.0 - 120.0) * 60.0 / 10.0).round() as u32 * 10;
+        if over == 0 {
+            "S9".to_string()
+        } else {
+            format!("S9+{}", over)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ballistics_and_peak_hold() {
+        let ballistics = MeterBallistics {
+            attack_ms: 10,
+            decay_ms: 1000,
+            peak_hold_ms: 500,
+        };
+        let t0 = Instant::now();
+        let mut meters = RadioMeters::default();
+        meters.record(MeterKind::Signal, 255, t0, &ballistics);
+        let signal = meters.signal.unwrap();
+        // Fast attack: essentially at full scale after 100 ms
+        assert!(signal.value(t0 + Duration::from_millis(100), &ballistics) > 0.99);
+
+        let t1 = t0 + Duration::from_millis(200);
+        meters.record(MeterKind::Signal, 0, t1, &ballistics);
+        let signal = meters.signal.unwrap();
+        // Slow decay: a quarter of full scale per 250 ms
+        let v = signal.value(t1 + Duration::from_millis(250), &ballistics);
+        assert!((v - 0.75).abs() < 0.01, "decayed to {v}");
+        assert_eq!(signal.peak(t1, &ballistics), Some(1.0));
+        assert_eq!(
+            signal.peak(t0 + Duration::from_millis(600), &ballistics),
+            None
+        );
+    }
+
+    #[test]
+    fn test_s_unit_label() {
+        assert_eq!(s_unit_label(0.0), "S0");
+        assert_eq!(s_unit_label(120.0 / 255.0), "S9");
+        assert_eq!(s_unit_label(1.0), "S9+60");
+    }
+}
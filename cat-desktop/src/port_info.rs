@@ -1,7 +1,6 @@
 //! Unified port information abstraction for real and virtual ports
 
 use cat_detect::SerialPortInfo;
-use cat_protocol::Protocol;
 
 use crate::settings::VirtualPortConfig;
 
@@ -28,12 +27,13 @@ impl PortInfo {
 
     /// Get a display label for the port dropdown
     ///
-    /// - Real ports: "ttyUSB0 (Product Name)" or just the port name
+    /// - Real ports: "ttyUSB0 (Product Name)", "rfcomm0 (Bluetooth)" or just the port name
     /// - Virtual ports: `<name> [SIM - Protocol]`
     pub fn display_label(&self) -> String {
         match self {
             PortInfo::Real(info) => match &info.product {
                 Some(product) => format!("{} ({})", info.port, product),
+                None if info.bluetooth => format!("{} (Bluetooth)", info.port),
                 None => info.port.clone(),
             },
             PortInfo::Virtual(config) => {
@@ -46,12 +46,4 @@ impl PortInfo {
     pub fn is_virtual(&self) -> bool {
         matches!(self, PortInfo::Virtual(_))
     }
-
-    /// Get the protocol for virtual ports (None for real ports)
-    pub fn virtual_protocol(&self) -> Option<Protocol> {
-        match self {
-            PortInfo::Real(_) => None,
-            PortInfo::Virtual(config) => Some(config.protocol),
-        }
-    }
 }
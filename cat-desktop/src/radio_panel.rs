@@ -1,11 +1,18 @@
 //! Radio panel UI component
 
-use std::time::Instant;
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Instant, SystemTime};
 
-use cat_mux::{is_virtual_port, sim_id_from_port, virtual_port_name, FlowControl, RadioHandle};
-use cat_protocol::{OperatingMode, Protocol};
+use cat_mux::{
+    virtual_port_name, ChannelId, FlowControl, MonitorConfig, RadioHandle, RadioProfile,
+    ReassemblyStats,
+};
+use cat_protocol::yaesu_ascii::{YaesuAsciiCodec, YaesuAsciiCommand};
+use cat_protocol::yaesu_menu::{menu_table_for_model, MenuTable};
+use cat_protocol::{OperatingMode, Protocol, ProtocolCodec};
 
-use crate::settings::ConfiguredRadio;
+use crate::meters::RadioMeters;
+use crate::settings::{ConfiguredRadio, RadioLinkSettings};
 
 /// Connection health state for a radio
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -21,10 +28,20 @@ pub enum ConnectionState {
 
 /// UI panel for a single radio
 pub struct RadioPanel {
+    /// Persistent channel identifier
+    pub channel_id: ChannelId,
     /// Radio handle in the local multiplexer (None if pending connection)
     pub handle: Option<RadioHandle>,
-    /// Display name
+    /// Display name (alias or model, with a suffix if another radio shares it)
     pub name: String,
+    /// Model name reported by (or configured for) the radio
+    pub model_name: String,
+    /// User-assigned alias (overrides the model name for display)
+    pub alias: Option<String>,
+    /// USB serial number of the radio's serial adapter, if known
+    pub usb_serial: Option<String>,
+    /// USB interface of the adapter's port, if known
+    pub usb_interface: Option<u8>,
     /// Serial port (or "VSIM:..." for virtual radios)
     pub port: String,
     /// Protocol (for future use in protocol-specific UI)
@@ -35,72 +52,87 @@ pub struct RadioPanel {
     pub flow_control: FlowControl,
     /// CI-V address for Icom radios
     pub civ_address: Option<u8>,
-    /// Is expanded in UI (for collapsible virtual radio controls)
-    pub expanded: bool,
     /// Whether the port is unavailable (for restored radios)
     pub unavailable: bool,
+    /// Not connected until the user enables it again
+    pub disabled: bool,
     /// Current frequency in Hz (local state updated from MuxEvent)
     pub frequency_hz: Option<u64>,
     /// Current operating mode (local state updated from MuxEvent)
     pub mode: Option<OperatingMode>,
     /// Current PTT state (local state updated from MuxEvent)
     pub ptt: bool,
+    /// Output power setting in watts (local state updated from MuxEvent)
+    pub power_watts: Option<u16>,
     /// Last time we received data from this radio (for connection health tracking)
     pub last_response: Option<Instant>,
     /// Connection health state for UI display
     pub connection_state: ConnectionState,
     /// Last time we attempted to reconnect (for backoff)
     pub last_reconnect_attempt: Option<Instant>,
+    /// Whether the mux considers this radio's data stale (likely powered off)
+    pub stale: bool,
+    /// When the radio last sent a frame
+    pub last_seen: Option<SystemTime>,
+    /// Initial sync progress (finished steps, total) while it is running
+    pub sync_progress: Option<(usize, usize)>,
+    /// Last `EX` menu values read from the radio (Yaesu ASCII), by item number
+    pub menu_values: BTreeMap<u32, String>,
+    /// S-meter and TX meters (updated from throttled meter events)
+    pub meters: RadioMeters,
+    /// Window for collapsing bursts of reports (transceive storms), 0 = off
+    pub input_throttle_ms: u32,
+    /// How the radio's frames are split across reads (from the mux)
+    pub reassembly: Option<ReassemblyStats>,
+    /// USB adapter latency timer in ms, where the OS exposes it (read on connect)
+    pub latency_timer_ms: Option<u8>,
+    /// Whether the radio echoes CI-V commands back (Icom, once the mux knows)
+    pub civ_echo_back: Option<bool>,
+    /// Receive-only monitor channel (never the active radio)
+    pub monitor: Option<MonitorConfig>,
+    /// Capabilities learned from the radio (once connected)
+    pub profile: Option<RadioProfile>,
+    /// Transport, line settings, pacing, polling and frequency offsets (COM radios)
+    pub link: RadioLinkSettings,
 }
 
 impl RadioPanel {
     /// Create a new radio panel from a saved configuration
     pub fn new_from_config(handle: Option<RadioHandle>, config: &ConfiguredRadio) -> Self {
         Self {
+            channel_id: config.channel_id(),
             handle,
             name: config.model_name.clone(),
+            model_name: config.model_name.clone(),
+            alias: config.alias.clone(),
+            usb_serial: config.usb_serial.clone(),
+            usb_interface: config.usb_interface,
             port: config.port.clone(),
             protocol: config.protocol,
             baud_rate: config.baud_rate,
             flow_control: config.flow_control.into(),
             civ_address: config.civ_address,
-            expanded: false,
-            unavailable: false,
-            frequency_hz: None,
-            mode: None,
-            ptt: false,
-            last_response: None,
-            connection_state: ConnectionState::default(),
-            last_reconnect_attempt: None,
-        }
-    }
-
-    /// Create a new COM port radio panel with explicit parameters
-    pub fn new_com(
-        handle: Option<RadioHandle>,
-        name: String,
-        port: String,
-        protocol: Protocol,
-        baud_rate: u32,
-        flow_control: FlowControl,
-        civ_address: Option<u8>,
-    ) -> Self {
-        Self {
-            handle,
-            name,
-            port,
-            protocol,
-            baud_rate,
-            flow_control,
-            civ_address,
-            expanded: false,
             unavailable: false,
+            disabled: config.disabled,
             frequency_hz: None,
             mode: None,
             ptt: false,
+            power_watts: None,
             last_response: None,
             connection_state: ConnectionState::default(),
             last_reconnect_attempt: None,
+            stale: false,
+            sync_progress: None,
+            last_seen: None,
+            menu_values: BTreeMap::new(),
+            meters: RadioMeters::default(),
+            input_throttle_ms: config.input_throttle_ms,
+            reassembly: None,
+            latency_timer_ms: None,
+            civ_echo_back: None,
+            monitor: config.monitor,
+            profile: None,
+            link: config.link.clone(),
         }
     }
 
@@ -112,31 +144,169 @@ impl RadioPanel {
         sim_id: String,
     ) -> Self {
         Self {
+            channel_id: ChannelId::virtual_radio(&sim_id),
             handle,
+            model_name: name.clone(),
             name,
+            alias: None,
+            usb_serial: None,
+            usb_interface: None,
             port: virtual_port_name(&sim_id),
             protocol,
             baud_rate: 0,
             flow_control: FlowControl::None,
             civ_address: None,
-            expanded: false,
             unavailable: false,
+            disabled: false,
             frequency_hz: None,
             mode: None,
             ptt: false,
+            power_watts: None,
             last_response: None,
             connection_state: ConnectionState::default(),
             last_reconnect_attempt: None,
+            stale: false,
+            sync_progress: None,
+            last_seen: None,
+            menu_values: BTreeMap::new(),
+            meters: RadioMeters::default(),
+            input_throttle_ms: 0,
+            reassembly: None,
+            latency_timer_ms: None,
+            civ_echo_back: None,
+            monitor: None,
+            profile: None,
+            link: RadioLinkSettings::default(),
         }
     }
 
-    /// Check if this is a virtual radio based on port name
+    /// Name to display before disambiguation (alias if set, else model)
+    pub fn base_name(&self) -> &str {
+        self.alias
+            .as_deref()
+            .filter(|a| !a.is_empty())
+            .unwrap_or(&self.model_name)
+    }
+
+    /// Check if this is a virtual radio
     pub fn is_virtual(&self) -> bool {
-        is_virtual_port(&self.port)
+        self.channel_id.is_virtual()
     }
 
-    /// Get the simulation ID for virtual radios (derived from port name)
+    /// Get the simulation ID for virtual radios
     pub fn sim_id(&self) -> Option<&str> {
-        sim_id_from_port(&self.port)
+        self.channel_id.sim_id()
+    }
+
+    /// `EX` menu table for this radio, if it is a Yaesu ASCII model we know
+    pub fn menu_table(&self) -> Option<&'static MenuTable> {
+        if self.protocol != Protocol::YaesuAscii {
+            return None;
+        }
+        menu_table_for_model(&self.model_name)
+    }
+
+    /// Remember any `EX` menu values in data received from the radio
+    pub fn record_menu_values(&mut self, data: &[u8]) {
+        let Some(table) = self.menu_table() else {
+            return;
+        };
+        let mut codec = YaesuAsciiCodec::with_menu_digits(table.digits);
+        codec.push_bytes(data);
+        while let Some(cmd) = codec.next_command() {
+            if let YaesuAsciiCommand::Menu {
+                item,
+                value: Some(value),
+                ..
+            } = cmd
+            {
+                self.menu_values.insert(item, value);
+            }
+        }
+    }
+}
+
+/// Assign unique display names, suffixing radios that share a base name
+///
+/// Radios sharing a name are numbered in list order ("IC-7300 #1",
+/// "IC-7300 #2"). Returns the indices of panels whose name changed.
+pub fn disambiguate_names(panels: &mut [RadioPanel]) -> Vec<usize> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for panel in panels.iter() {
+        *counts.entry(panel.base_name().to_string()).or_default() += 1;
+    }
+
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut changed = Vec::new();
+    for (idx, panel) in panels.iter_mut().enumerate() {
+        let base = panel.base_name().to_string();
+        let name = if counts[&base] > 1 {
+            let n = seen.entry(base.clone()).or_default();
+            *n += 1;
+            format!("{} #{}", base, n)
+        } else {
+            base
+        };
+        if panel.name != name {
+            panel.name = name;
+            changed.push(idx);
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::SerialFlowControl;
+
+    fn com_panel(model: &str, port: &str) -> RadioPanel {
+        let config = ConfiguredRadio {
+            id: None,
+            port: port.to_string(),
+            protocol: Protocol::IcomCIV,
+            model_name: model.to_string(),
+            baud_rate: 115200,
+            civ_address: Some(0x94),
+            flow_control: SerialFlowControl::None,
+            usb_serial: None,
+            usb_interface: None,
+            alias: None,
+            input_throttle_ms: 0,
+            disabled: false,
+            monitor: None,
+            link: RadioLinkSettings::default(),
+        };
+        RadioPanel::new_from_config(None, &config)
+    }
+
+    #[test]
+    fn test_duplicate_models_get_suffixes() {
+        let mut panels = vec![
+            com_panel("IC-7300", "/dev/ttyUSB0"),
+            com_panel("IC-7610", "/dev/ttyUSB1"),
+            com_panel("IC-7300", "/dev/ttyUSB2"),
+        ];
+        let changed = disambiguate_names(&mut panels);
+
+        assert_eq!(changed, vec![0, 2]);
+        assert_eq!(panels[0].name, "IC-7300 #1");
+        assert_eq!(panels[1].name, "IC-7610");
+        assert_eq!(panels[2].name, "IC-7300 #2");
+    }
+
+    #[test]
+    fn test_alias_resolves_collision() {
+        let mut panels = vec![
+            com_panel("IC-7300", "/dev/ttyUSB0"),
+            com_panel("IC-7300", "/dev/ttyUSB1"),
+        ];
+        disambiguate_names(&mut panels);
+        panels[1].alias = Some("Run".to_string());
+
+        let changed = disambiguate_names(&mut panels);
+        assert_eq!(changed, vec![0, 1]);
+        assert_eq!(panels[0].name, "IC-7300");
+        assert_eq!(panels[1].name, "Run");
     }
 }
@@ -1,13 +1,30 @@
 //! Application settings
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
 
+use cat_detect::{settle_delay, SerialPortInfo, DEFAULT_SETTLE};
+use cat_mux::async_radio::DEFAULT_POLL_INTERVAL;
+use cat_mux::{
+    AmpBypassConfig, BandGuardConfig, BandPlanRegion, ChannelId, DutyCycleAlertConfig,
+    EmulatedPersonality, FrequencySmoothing, MemoryBudget, MonitorConfig, OutputSink, RadioProfile,
+    SequencerConfig, SerialLine, SerialPin, SmoothingPolicy, TransitionPolicy,
+    TranslationStrictness, MIN_BUDGET_MB,
+};
+use cat_protocol::display::{DecimalSeparator, FrequencyFormat, FrequencyStyle};
 use cat_protocol::Protocol;
 use cat_sim::VirtualRadioConfig;
 use egui::Ui;
 use serde::{Deserialize, Serialize};
 use tracing::Level;
 
+use crate::diagnostics_layer::{CrateLevel, PROJECT_CRATES};
+use crate::i18n::{set_language, system_language, tr, Language};
+use crate::meters::MeterBallistics;
+use crate::settings_store::{self, Backup, Loaded};
+use crate::traffic_monitor::MAX_HISTORY_ENTRIES;
+
 /// Virtual port configuration (for simulated radios configured in Settings)
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VirtualPortConfig {
@@ -52,6 +69,9 @@ impl From<cat_mux::FlowControl> for SerialFlowControl {
 /// Saved COM port radio configuration
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ConfiguredRadio {
+    /// Persistent channel identifier (filled in on load for older configs)
+    #[serde(default)]
+    pub id: Option<ChannelId>,
     /// Serial port path
     pub port: String,
     /// Protocol used
@@ -66,6 +86,240 @@ pub struct ConfiguredRadio {
     /// Flow control setting
     #[serde(default)]
     pub flow_control: SerialFlowControl,
+    /// USB serial number of the port's adapter (used to find the radio if the port path changes)
+    #[serde(default)]
+    pub usb_serial: Option<String>,
+    /// USB interface of the port, for adapters with several ports
+    #[serde(default)]
+    pub usb_interface: Option<u8>,
+    /// User-assigned alias (display name override)
+    #[serde(default)]
+    pub alias: Option<String>,
+    /// Window for collapsing bursts of reports from the radio (0 = off)
+    #[serde(default)]
+    pub input_throttle_ms: u32,
+    /// Not connected at startup (e.g. disabled from safe mode)
+    #[serde(default)]
+    pub disabled: bool,
+    /// Receive-only monitor channel (e.g. an SDR), never the active radio
+    #[serde(default)]
+    pub monitor: Option<MonitorConfig>,
+    /// Transport, line settings, pacing, polling and frequency offsets
+    #[serde(default)]
+    pub link: RadioLinkSettings,
+}
+
+impl ConfiguredRadio {
+    /// Persistent identity for this radio: the saved ID, else one derived
+    /// from the USB serial (and interface) or port path
+    pub fn channel_id(&self) -> ChannelId {
+        self.id.clone().unwrap_or_else(|| {
+            ChannelId::for_serial_port(&self.port, self.usb_serial.as_deref(), self.usb_interface)
+        })
+    }
+}
+
+/// How a configured radio is reached
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum RadioTransportKind {
+    /// Local serial port
+    #[default]
+    Serial,
+    /// TCP serial server; the radio's port holds "host:port"
+    Tcp,
+    /// Through flrig's XML-RPC server, which keeps the serial port; the
+    /// radio's port holds flrig's "host:port"
+    Flrig,
+}
+
+/// Serial data bits (mirrors tokio_serial::DataBits)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SerialDataBits {
+    Seven,
+    #[default]
+    Eight,
+}
+
+impl From<SerialDataBits> for cat_mux::DataBits {
+    fn from(bits: SerialDataBits) -> Self {
+        match bits {
+            SerialDataBits::Seven => cat_mux::DataBits::Seven,
+            SerialDataBits::Eight => cat_mux::DataBits::Eight,
+        }
+    }
+}
+
+/// Serial parity (mirrors tokio_serial::Parity)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SerialParity {
+    #[default]
+    None,
+    Odd,
+    Even,
+}
+
+impl From<SerialParity> for cat_mux::Parity {
+    fn from(parity: SerialParity) -> Self {
+        match parity {
+            SerialParity::None => cat_mux::Parity::None,
+            SerialParity::Odd => cat_mux::Parity::Odd,
+            SerialParity::Even => cat_mux::Parity::Even,
+        }
+    }
+}
+
+/// Serial stop bits (mirrors tokio_serial::StopBits)
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum SerialStopBits {
+    #[default]
+    One,
+    Two,
+}
+
+impl From<SerialStopBits> for cat_mux::StopBits {
+    fn from(bits: SerialStopBits) -> Self {
+        match bits {
+            SerialStopBits::One => cat_mux::StopBits::One,
+            SerialStopBits::Two => cat_mux::StopBits::Two,
+        }
+    }
+}
+
+/// Level a control line (DTR, RTS) is held at once the port is open
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub enum LineLevel {
+    /// Whatever the OS leaves it at
+    #[default]
+    Unchanged,
+    Low,
+    High,
+}
+
+impl LineLevel {
+    /// Level to set, if any
+    pub fn level(self) -> Option<bool> {
+        match self {
+            Self::Unchanged => None,
+            Self::Low => Some(false),
+            Self::High => Some(true),
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Unchanged => "Unchanged",
+            Self::Low => "Low",
+            Self::High => "High",
+        }
+    }
+}
+
+/// How a configured radio is reached and paced, and how its dial maps to the air
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RadioLinkSettings {
+    pub transport: RadioTransportKind,
+    pub data_bits: SerialDataBits,
+    pub parity: SerialParity,
+    pub stop_bits: SerialStopBits,
+    pub dtr: LineLevel,
+    pub rts: LineLevel,
+    /// Minimum time between commands sent to the radio (0 = none)
+    pub command_gap_ms: u32,
+    /// Poll the frequency after this long without traffic (0 = never)
+    pub poll_interval_ms: u32,
+    /// Wait after opening the port before talking to the radio (None = the
+    /// adapter's default from [`cat_detect::usb_quirks`])
+    pub settle_ms: Option<u32>,
+    /// Correction for the radio's frequency reference, added to its dial
+    pub calibration_hz: i32,
+    /// Transverter local oscillator, added to the dial (0 = no transverter)
+    pub transverter_offset_hz: i64,
+    /// Send the mux's frequency, mode and PTT changes on to flrig (flrig
+    /// transport only; otherwise the radio is only followed)
+    pub flrig_push: bool,
+    /// A KAT500 tuner shares the radio's line (Elecraft only); it is
+    /// band-switched along with the radio
+    pub kat500: bool,
+}
+
+impl Default for RadioLinkSettings {
+    fn default() -> Self {
+        Self {
+            transport: RadioTransportKind::Serial,
+            data_bits: SerialDataBits::default(),
+            parity: SerialParity::default(),
+            stop_bits: SerialStopBits::default(),
+            dtr: LineLevel::default(),
+            rts: LineLevel::default(),
+            command_gap_ms: 0,
+            poll_interval_ms: DEFAULT_POLL_INTERVAL.as_millis() as u32,
+            settle_ms: None,
+            calibration_hz: 0,
+            transverter_offset_hz: 0,
+            flrig_push: false,
+            kat500: false,
+        }
+    }
+}
+
+impl RadioLinkSettings {
+    /// Whether the radio is reached over the network rather than a local port
+    pub fn is_network(&self) -> bool {
+        matches!(
+            self.transport,
+            RadioTransportKind::Tcp | RadioTransportKind::Flrig
+        )
+    }
+
+    /// Whether the radio is reached through flrig
+    pub fn is_flrig(&self) -> bool {
+        self.transport == RadioTransportKind::Flrig
+    }
+
+    /// How the flrig client polls and drives the radio
+    pub fn flrig_options(&self) -> cat_mux::FlrigOptions {
+        cat_mux::FlrigOptions {
+            poll_interval: self.poll_interval().unwrap_or(DEFAULT_POLL_INTERVAL),
+            push: self.flrig_push,
+        }
+    }
+
+    /// Serial line settings for the port
+    pub fn serial_line(&self, baud_rate: u32, flow_control: cat_mux::FlowControl) -> SerialLine {
+        SerialLine {
+            data_bits: self.data_bits.into(),
+            parity: self.parity.into(),
+            stop_bits: self.stop_bits.into(),
+            dtr: self.dtr.level(),
+            rts: self.rts.level(),
+            ..SerialLine::new(baud_rate, flow_control)
+        }
+    }
+
+    /// Added to the radio's dial to get the on-air frequency
+    pub fn frequency_offset_hz(&self) -> i64 {
+        self.transverter_offset_hz + self.calibration_hz as i64
+    }
+
+    /// Idle poll interval (None = don't poll)
+    pub fn poll_interval(&self) -> Option<Duration> {
+        (self.poll_interval_ms > 0).then(|| Duration::from_millis(self.poll_interval_ms as u64))
+    }
+
+    /// Minimum gap between commands
+    pub fn command_gap(&self) -> Duration {
+        Duration::from_millis(self.command_gap_ms as u64)
+    }
+
+    /// Wait after opening the port, given the adapter it is on (if known)
+    pub fn settle_delay(&self, adapter: Option<&SerialPortInfo>) -> Duration {
+        match self.settle_ms {
+            Some(ms) => Duration::from_millis(ms as u64),
+            None => adapter.map_or(DEFAULT_SETTLE, settle_delay),
+        }
+    }
 }
 
 /// Saved amplifier configuration
@@ -87,6 +341,12 @@ pub struct AmplifierSettings {
     /// Flow control setting
     #[serde(default)]
     pub flow_control: SerialFlowControl,
+    /// Protocol to answer the amp in, if not the one it sends (hybrid setups)
+    #[serde(default)]
+    pub response_protocol: Option<Protocol>,
+    /// Translation strictness for this amp (None = the global setting)
+    #[serde(default)]
+    pub strictness: Option<TranslationStrictness>,
 }
 
 fn default_amp_baud() -> u32 {
@@ -102,10 +362,511 @@ impl Default for AmplifierSettings {
             baud_rate: 9600,
             civ_address: 0x00,
             flow_control: SerialFlowControl::default(),
+            response_protocol: None,
+            strictness: None,
+        }
+    }
+}
+
+/// Analyzer (monitor-only) mode configuration
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AnalyzerSettings {
+    /// Start in analyzer mode (switching and amplifier disabled)
+    #[serde(default)]
+    pub enabled: bool,
+    /// Port the logger is connected to
+    #[serde(default)]
+    pub logger_port: String,
+    /// Port the radio is connected to
+    #[serde(default)]
+    pub radio_port: String,
+    /// Protocol used to decode the traffic
+    pub protocol: Protocol,
+    /// Baud rate for both ports
+    #[serde(default = "default_analyzer_baud")]
+    pub baud_rate: u32,
+}
+
+fn default_analyzer_baud() -> u32 {
+    9600
+}
+
+impl Default for AnalyzerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            logger_port: String::new(),
+            radio_port: String::new(),
+            protocol: Protocol::Kenwood,
+            baud_rate: default_analyzer_baud(),
+        }
+    }
+}
+
+/// Capture folder watching (offline log viewing)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CaptureWatchSettings {
+    /// Folder to watch for capture files
+    #[serde(default)]
+    pub folder: String,
+    /// Protocol used to split and decode the captures
+    pub protocol: Protocol,
+}
+
+impl Default for CaptureWatchSettings {
+    fn default() -> Self {
+        Self {
+            folder: String::new(),
+            protocol: Protocol::Kenwood,
+        }
+    }
+}
+
+/// Prometheus metrics endpoint
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MetricsSettings {
+    /// Serve `/metrics` while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on
+    #[serde(default = "default_metrics_address")]
+    pub address: String,
+}
+
+fn default_metrics_address() -> String {
+    cat_mux::DEFAULT_METRICS_ADDR.to_string()
+}
+
+impl Default for MetricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_metrics_address(),
+        }
+    }
+}
+
+/// Remote status page and push alerts
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StatusPageSettings {
+    /// Serve the status page while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on
+    #[serde(default = "default_status_address")]
+    pub address: String,
+    /// ntfy topic URL critical alerts are published to (empty = off)
+    #[serde(default)]
+    pub ntfy_url: String,
+    /// URL critical alerts are POSTed to as JSON (empty = off)
+    #[serde(default)]
+    pub webhook_url: String,
+}
+
+fn default_status_address() -> String {
+    cat_mux::DEFAULT_STATUS_ADDR.to_string()
+}
+
+impl StatusPageSettings {
+    /// Where critical alerts are pushed
+    pub fn alert_targets(&self) -> cat_mux::AlertTargets {
+        cat_mux::AlertTargets {
+            ntfy_url: self.ntfy_url.clone(),
+            webhook_url: self.webhook_url.clone(),
+        }
+    }
+}
+
+impl Default for StatusPageSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_status_address(),
+            ntfy_url: String::new(),
+            webhook_url: String::new(),
+        }
+    }
+}
+
+/// Hamlib NET rigctl server
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RigctlSettings {
+    /// Accept rigctl clients while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on
+    #[serde(default = "default_rigctl_address")]
+    pub address: String,
+}
+
+fn default_rigctl_address() -> String {
+    cat_mux::DEFAULT_RIGCTL_ADDR.to_string()
+}
+
+impl Default for RigctlSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_rigctl_address(),
+        }
+    }
+}
+
+/// gRPC control API (builds with the `grpc` feature only)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GrpcSettings {
+    /// Serve the API while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address to listen on
+    #[serde(default = "default_grpc_address")]
+    pub address: String,
+}
+
+/// `cat_mux::DEFAULT_GRPC_ADDR`, which only exists with the `grpc` feature
+fn default_grpc_address() -> String {
+    "127.0.0.1:50051".to_string()
+}
+
+impl Default for GrpcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            address: default_grpc_address(),
+        }
+    }
+}
+
+/// Window geometry and panel visibility, restored at startup
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LayoutSettings {
+    /// Inner window size in points (None = default)
+    #[serde(default)]
+    pub window_size: Option<[f32; 2]>,
+    /// Outer window position in points (None = where the OS puts it)
+    #[serde(default)]
+    pub window_position: Option<[f32; 2]>,
+    /// Size of the monitor the window was on, to notice when it is gone
+    #[serde(default)]
+    pub monitor_size: Option<[f32; 2]>,
+    /// Window was maximized (size and position are from before)
+    #[serde(default)]
+    pub maximized: bool,
+    /// Settings side panel open
+    #[serde(default)]
+    pub show_settings: bool,
+    /// Traffic monitor (console) side panel open
+    #[serde(default = "default_true")]
+    pub show_traffic_monitor: bool,
+    /// Settings panel width in points (None = default)
+    #[serde(default)]
+    pub settings_width: Option<f32>,
+    /// Traffic monitor width in points (None = default)
+    #[serde(default)]
+    pub traffic_monitor_width: Option<f32>,
+    /// Radios whose panels show their extra controls
+    #[serde(default)]
+    pub expanded_radios: BTreeSet<ChannelId>,
+    /// Collapsible sections left open, by id
+    #[serde(default)]
+    pub open_sections: BTreeSet<String>,
+}
+
+impl Default for LayoutSettings {
+    fn default() -> Self {
+        Self {
+            window_size: None,
+            window_position: None,
+            monitor_size: None,
+            maximized: false,
+            show_settings: false,
+            show_traffic_monitor: true,
+            settings_width: None,
+            traffic_monitor_width: None,
+            expanded_radios: BTreeSet::new(),
+            open_sections: BTreeSet::new(),
+        }
+    }
+}
+
+impl LayoutSettings {
+    /// Whether a collapsible section was left open
+    pub fn section_open(&self, id: &str) -> bool {
+        self.open_sections.contains(id)
+    }
+
+    /// Record a collapsible section being opened or closed
+    pub fn remember_section<R>(&mut self, id: &str, response: &egui::CollapsingResponse<R>) {
+        if response.fully_open() {
+            if !self.open_sections.contains(id) {
+                self.open_sections.insert(id.to_string());
+            }
+        } else if response.fully_closed() {
+            self.open_sections.remove(id);
+        }
+    }
+}
+
+/// Emulated radio for single-radio software
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct EmulatedRadioSettings {
+    /// Emulate the radio while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Which radio clients see
+    #[serde(default)]
+    pub personality: EmulatedPersonality,
+    /// Address to listen on ("host:port") or serial port to open
+    #[serde(default = "default_emulated_radio_endpoint")]
+    pub endpoint: String,
+    /// Baud rate, for a serial port
+    #[serde(default = "default_emulated_radio_baud")]
+    pub baud_rate: u32,
+}
+
+fn default_emulated_radio_endpoint() -> String {
+    cat_mux::DEFAULT_EMULATED_RADIO_ADDR.to_string()
+}
+
+fn default_emulated_radio_baud() -> u32 {
+    38400
+}
+
+impl Default for EmulatedRadioSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            personality: EmulatedPersonality::default(),
+            endpoint: default_emulated_radio_endpoint(),
+            baud_rate: default_emulated_radio_baud(),
         }
     }
 }
 
+/// Always-on traffic logging to disk
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DiskLogSettings {
+    /// Record traffic while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Folder for the daily logs (empty for the default in the config directory)
+    #[serde(default)]
+    pub folder: String,
+}
+
+/// DX cluster spot feed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DxClusterSettings {
+    /// Connect to the cluster while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// Cluster address (host:port)
+    #[serde(default)]
+    pub address: String,
+    /// Callsign to log in with
+    #[serde(default)]
+    pub callsign: String,
+}
+
+/// Multi-op peer sync
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PeerSyncSettings {
+    /// Share state with peers while the app runs
+    #[serde(default)]
+    pub enabled: bool,
+    /// This station's name as peers see it, e.g. "run"
+    #[serde(default)]
+    pub instance: String,
+    /// UDP port to listen on
+    #[serde(default = "default_peer_port")]
+    pub port: u16,
+    /// Peer addresses (host:port), separated by commas
+    #[serde(default)]
+    pub peers: String,
+}
+
+fn default_peer_port() -> u16 {
+    cat_mux::DEFAULT_PEER_PORT
+}
+
+impl Default for PeerSyncSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            instance: String::new(),
+            port: default_peer_port(),
+            peers: String::new(),
+        }
+    }
+}
+
+/// Hands-free bookmark triggers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BookmarkSettings {
+    /// Bookmark on two PTT key-downs in quick succession
+    #[serde(default)]
+    pub ptt_double_tap: bool,
+    /// Longest gap between the two key-downs
+    #[serde(default = "default_double_tap_ms")]
+    pub double_tap_ms: u64,
+    /// Bookmark when a pin on a spare serial port is asserted
+    #[serde(default)]
+    pub pin_enabled: bool,
+    /// Spare serial port with the footswitch or button
+    #[serde(default)]
+    pub pin_port: String,
+    /// Modem-status line the switch is wired to
+    #[serde(default)]
+    pub pin: SerialPin,
+}
+
+fn default_double_tap_ms() -> u64 {
+    cat_mux::DEFAULT_DOUBLE_TAP_MS
+}
+
+impl Default for BookmarkSettings {
+    fn default() -> Self {
+        Self {
+            ptt_double_tap: false,
+            double_tap_ms: default_double_tap_ms(),
+            pin_enabled: false,
+            pin_port: String::new(),
+            pin: SerialPin::default(),
+        }
+    }
+}
+
+impl BookmarkSettings {
+    /// Double-tap window to give the mux (None when off)
+    pub fn double_tap_window_ms(&self) -> Option<u64> {
+        self.ptt_double_tap.then_some(self.double_tap_ms)
+    }
+}
+
+/// Foot switch that keys the active radio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PttSwitchSettings {
+    /// Key the active radio while the switch is pressed
+    #[serde(default)]
+    pub enabled: bool,
+    /// Spare serial port the switch is wired to
+    #[serde(default)]
+    pub port: String,
+    /// Modem-status line the switch is wired to
+    #[serde(default)]
+    pub pin: SerialPin,
+    /// Normally-closed switch: pressed while the pin is deasserted
+    #[serde(default)]
+    pub inverted: bool,
+}
+
+/// Frequency log written on each PTT release of the active radio
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ContactLogSettings {
+    /// Append a row each time the active radio unkeys
+    #[serde(default)]
+    pub enabled: bool,
+    /// CSV file to append to (empty for the default in the config directory)
+    #[serde(default)]
+    pub path: String,
+}
+
+/// What a watch does when its expression starts holding
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WatchRule {
+    /// Shown in alerts
+    #[serde(default)]
+    pub name: String,
+    /// Watch expression (see `cat_mux::watch`)
+    #[serde(default)]
+    pub expression: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Show a banner over the radio list while it holds
+    #[serde(default = "default_true")]
+    pub alert: bool,
+    /// Beep when it starts holding
+    #[serde(default)]
+    pub sound: bool,
+    /// `http://` URL to POST to when it starts and stops holding
+    #[serde(default)]
+    pub webhook: String,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl WatchRule {
+    pub fn new(name: &str, expression: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            enabled: true,
+            alert: true,
+            sound: false,
+            webhook: String::new(),
+        }
+    }
+}
+
+/// User-defined watch expressions
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct WatchSettings {
+    #[serde(default)]
+    pub rules: Vec<WatchRule>,
+}
+
+/// Strict/permissive selector
+fn strictness_ui(ui: &mut Ui, id: &str, strictness: &mut TranslationStrictness) {
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(strictness.name())
+        .show_ui(ui, |ui| {
+            for option in [
+                TranslationStrictness::Strict,
+                TranslationStrictness::Permissive,
+            ] {
+                ui.selectable_value(strictness, option, option.name());
+            }
+        });
+}
+
+/// Pick a smoothing policy and its window
+fn smoothing_policy_ui(ui: &mut Ui, id: &str, sink: OutputSink, policy: &mut SmoothingPolicy) {
+    ui.horizontal(|ui| {
+        let ms = policy
+            .window()
+            .or(sink.suggested_policy().window())
+            .map_or(100, |w| w.as_millis() as u64);
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(match policy {
+                SmoothingPolicy::Immediate => tr!("smoothing-immediate"),
+                SmoothingPolicy::Trailing { .. } => tr!("smoothing-trailing"),
+                SmoothingPolicy::Sample { .. } => tr!("smoothing-sample"),
+            })
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    policy,
+                    SmoothingPolicy::Immediate,
+                    tr!("smoothing-immediate"),
+                );
+                ui.selectable_value(
+                    policy,
+                    SmoothingPolicy::Trailing { ms },
+                    tr!("smoothing-trailing"),
+                );
+                ui.selectable_value(
+                    policy,
+                    SmoothingPolicy::Sample { ms },
+                    tr!("smoothing-sample"),
+                );
+            });
+        if let SmoothingPolicy::Trailing { ms } | SmoothingPolicy::Sample { ms } = policy {
+            ui.add(egui::DragValue::new(ms).range(10..=2000).suffix(" ms"));
+        }
+    });
+}
+
 /// Helper for serializing tracing::Level as a string
 mod level_serde {
     use serde::{Deserialize, Deserializer, Serializer};
@@ -146,14 +907,29 @@ pub struct Settings {
     pub lockout_ms: u64,
     /// Traffic monitor history size
     pub traffic_history_size: usize,
+    /// Ceiling for everything kept in memory, in MiB (0 = no budget)
+    #[serde(default)]
+    pub memory_budget_mb: u32,
     /// Show hex in traffic monitor
     pub show_hex: bool,
     /// Show decoded in traffic monitor
     pub show_decoded: bool,
+    /// How frequencies are written everywhere in the app and its exports
+    #[serde(default)]
+    pub frequency_format: FrequencyFormat,
+    /// Language of the interface (None = the system's)
+    #[serde(default)]
+    pub language: Option<Language>,
+    /// ITU region whose amateur bands a typed transmit frequency must be in
+    #[serde(default)]
+    pub band_plan_region: BandPlanRegion,
     /// Minimum diagnostic level to capture (None = off, Some(Level::DEBUG) = all)
     /// When set, events at this level and above are captured (e.g., INFO captures INFO, WARN, ERROR)
     #[serde(default = "default_diagnostic_level", with = "level_serde")]
     pub diagnostic_level: Option<Level>,
+    /// Per-crate overrides of `diagnostic_level` (crates not listed inherit it)
+    #[serde(default)]
+    pub diagnostic_crate_levels: BTreeMap<String, CrateLevel>,
     /// Virtual radios to restore on startup
     #[serde(default)]
     pub virtual_radios: Vec<VirtualRadioConfig>,
@@ -166,6 +942,114 @@ pub struct Settings {
     /// Amplifier configuration
     #[serde(default)]
     pub amplifier: AmplifierSettings,
+    /// Serial port of the OTRSP band decoder (empty = none)
+    #[serde(default)]
+    pub band_decoder_port: String,
+    /// Seconds without data before a radio is dimmed and skipped by auto-switching (0 = off)
+    #[serde(default)]
+    pub stale_radio_secs: u64,
+    /// PTT sequencer: ms between keying the amp and keying the radio (0 = none)
+    #[serde(default)]
+    pub ptt_lead_ms: u64,
+    /// PTT sequencer: ms between unkeying the radio and unkeying the amp (0 = none)
+    #[serde(default)]
+    pub ptt_tail_ms: u64,
+    /// Put the amplifier in bypass when the active radio leaves its range
+    #[serde(default)]
+    pub amp_bypass: AmpBypassConfig,
+    /// What the amp is told while a radio switch is held off
+    #[serde(default)]
+    pub amp_transition: TransitionPolicy,
+    /// Sync the amp to the new radio before letting it key on a switch
+    #[serde(default)]
+    pub gapless_handover: bool,
+    /// Same-band guard across radios
+    #[serde(default)]
+    pub band_guard: BandGuardConfig,
+    /// Ask before switching away from a transmitting radio
+    #[serde(default)]
+    pub confirm_tx_switch: bool,
+    /// Set the newly active radio's keyer speed to the outgoing radio's
+    #[serde(default)]
+    pub carry_keyer_speed: bool,
+    /// Put the active radio back in the mode it last used on each band
+    #[serde(default)]
+    pub band_mode_memory: bool,
+    /// Only log reports that repeat an inactive radio's known state, not act on them
+    #[serde(default)]
+    pub suppress_repeats: bool,
+    /// Translate for the amplifier without writing to its port
+    #[serde(default)]
+    pub amp_dry_run: bool,
+    /// Move output off a failing amplifier to the backup (or bypass)
+    #[serde(default)]
+    pub amp_failover: bool,
+    /// Window for merging a radio's burst of reports into one amp write (0 = off)
+    #[serde(default)]
+    pub amp_coalesce_ms: u64,
+    /// What happens to radio frames with no translation for the amp
+    #[serde(default)]
+    pub translation_strictness: TranslationStrictness,
+    /// Warn when a radio transmits too much of the time
+    #[serde(default)]
+    pub duty_cycle_alert: DutyCycleAlertConfig,
+    /// How often frequency changes reach the amp and band decoder
+    #[serde(default)]
+    pub frequency_smoothing: FrequencySmoothing,
+    /// Analyzer (monitor-only) mode
+    #[serde(default)]
+    pub analyzer: AnalyzerSettings,
+    /// Radio panel meter ballistics
+    #[serde(default)]
+    pub meter_ballistics: MeterBallistics,
+    /// Capture folder watching
+    #[serde(default)]
+    pub capture_watch: CaptureWatchSettings,
+    /// Prometheus metrics endpoint
+    #[serde(default)]
+    pub metrics: MetricsSettings,
+    /// Remote status page and push alerts
+    #[serde(default)]
+    pub status_page: StatusPageSettings,
+    /// Hamlib NET rigctl server
+    #[serde(default)]
+    pub rigctl: RigctlSettings,
+    /// gRPC control API
+    #[serde(default)]
+    pub grpc: GrpcSettings,
+    /// Emulated radio for single-radio software
+    #[serde(default)]
+    pub emulated_radio: EmulatedRadioSettings,
+    /// Always-on traffic logging to disk
+    #[serde(default)]
+    pub disk_log: DiskLogSettings,
+    /// DX cluster spot feed
+    #[serde(default)]
+    pub dx_cluster: DxClusterSettings,
+    /// Multi-op peer sync
+    #[serde(default)]
+    pub peer_sync: PeerSyncSettings,
+    /// Hands-free bookmark triggers
+    #[serde(default)]
+    pub bookmarks: BookmarkSettings,
+    /// Foot switch PTT
+    #[serde(default)]
+    pub ptt_switch: PttSwitchSettings,
+    /// Frequency log on PTT release
+    #[serde(default)]
+    pub contact_log: ContactLogSettings,
+    /// Watch expressions with alert actions
+    #[serde(default)]
+    pub watches: WatchSettings,
+    /// Window and panel layout
+    #[serde(default)]
+    pub layout: LayoutSettings,
+    /// Capabilities learned from each radio on its first connection, by [`RadioProfile::key`]
+    #[serde(default)]
+    pub radio_profiles: BTreeMap<String, RadioProfile>,
+    /// Session-only settings that are never written to disk (safe mode)
+    #[serde(skip)]
+    pub read_only: bool,
 }
 
 fn default_diagnostic_level() -> Option<Level> {
@@ -177,18 +1061,92 @@ impl Default for Settings {
         Self {
             lockout_ms: 500,
             traffic_history_size: 1000,
+            memory_budget_mb: 0,
             show_hex: true,
             show_decoded: true,
+            frequency_format: FrequencyFormat::default(),
+            language: None,
+            band_plan_region: BandPlanRegion::default(),
             diagnostic_level: Some(Level::INFO),
+            diagnostic_crate_levels: BTreeMap::new(),
             virtual_radios: Vec::new(),
             configured_radios: Vec::new(),
             virtual_ports: Vec::new(),
             amplifier: AmplifierSettings::default(),
+            band_decoder_port: String::new(),
+            stale_radio_secs: 0,
+            ptt_lead_ms: 0,
+            ptt_tail_ms: 0,
+            amp_bypass: AmpBypassConfig::default(),
+            amp_transition: TransitionPolicy::default(),
+            gapless_handover: false,
+            band_guard: BandGuardConfig::default(),
+            confirm_tx_switch: false,
+            carry_keyer_speed: false,
+            band_mode_memory: false,
+            suppress_repeats: false,
+            amp_dry_run: false,
+            amp_failover: false,
+            amp_coalesce_ms: 0,
+            translation_strictness: TranslationStrictness::default(),
+            duty_cycle_alert: DutyCycleAlertConfig::default(),
+            frequency_smoothing: FrequencySmoothing::default(),
+            analyzer: AnalyzerSettings::default(),
+            meter_ballistics: MeterBallistics::default(),
+            capture_watch: CaptureWatchSettings::default(),
+            metrics: MetricsSettings::default(),
+            status_page: StatusPageSettings::default(),
+            rigctl: RigctlSettings::default(),
+            grpc: GrpcSettings::default(),
+            emulated_radio: EmulatedRadioSettings::default(),
+            disk_log: DiskLogSettings::default(),
+            dx_cluster: DxClusterSettings::default(),
+            peer_sync: PeerSyncSettings::default(),
+            bookmarks: BookmarkSettings::default(),
+            ptt_switch: PttSwitchSettings::default(),
+            contact_log: ContactLogSettings::default(),
+            watches: WatchSettings::default(),
+            layout: LayoutSettings::default(),
+            radio_profiles: BTreeMap::new(),
+            read_only: false,
         }
     }
 }
 
 impl Settings {
+    /// Staleness threshold to hand to the mux (None when disabled)
+    pub fn stale_threshold_secs(&self) -> Option<u64> {
+        (self.stale_radio_secs > 0).then_some(self.stale_radio_secs)
+    }
+
+    /// The language to show the interface in
+    pub fn language(&self) -> Language {
+        self.language.unwrap_or_else(system_language)
+    }
+
+    /// The memory budget, if one is set
+    pub fn memory_budget(&self) -> Option<MemoryBudget> {
+        (self.memory_budget_mb > 0).then(|| MemoryBudget::new(self.memory_budget_mb))
+    }
+
+    /// Traffic history size, capped by the memory budget
+    pub fn traffic_history_limit(&self) -> usize {
+        match self.memory_budget() {
+            Some(budget) => self
+                .traffic_history_size
+                .min(budget.limits().traffic_entries),
+            None => self.traffic_history_size,
+        }
+    }
+
+    /// PTT sequencer timing to hand to the mux
+    pub fn sequencer_config(&self) -> SequencerConfig {
+        SequencerConfig {
+            lead_ms: self.ptt_lead_ms,
+            tail_ms: self.ptt_tail_ms,
+        }
+    }
+
     /// Get the XDG config directory for catapult
     /// Uses $XDG_CONFIG_HOME/catapult on Linux/macOS, falls back to ~/.config/catapult
     fn config_dir() -> Option<PathBuf> {
@@ -209,16 +1167,89 @@ impl Settings {
         Self::config_dir().map(|p| p.join("settings.json"))
     }
 
-    /// Load settings from disk
-    pub fn load() -> Self {
-        Self::settings_path()
-            .and_then(|path| std::fs::read_to_string(path).ok())
-            .and_then(|s| serde_json::from_str(&s).ok())
-            .unwrap_or_default()
+    /// Get the startup sentinel path (see [`crate::startup_guard`])
+    pub fn startup_sentinel_path() -> Option<PathBuf> {
+        Self::config_dir().map(|p| p.join("startup.json"))
+    }
+
+    /// Get the bookmark log path (appended to as bookmarks are dropped)
+    pub fn bookmarks_path() -> Option<PathBuf> {
+        Self::config_dir().map(|p| p.join("bookmarks.log"))
+    }
+
+    /// Get the default frequency log path (appended to on each PTT release)
+    pub fn contact_log_path() -> Option<PathBuf> {
+        Self::config_dir().map(|p| p.join("contacts.csv"))
+    }
+
+    /// Get the default folder for disk traffic logs
+    pub fn traffic_log_dir() -> Option<PathBuf> {
+        Self::config_dir().map(|p| p.join("logs"))
+    }
+
+    /// Where the amplifier's last known state is kept between sessions
+    pub fn amp_state_path() -> Option<PathBuf> {
+        Self::config_dir().map(|p| p.join("amp_state.json"))
+    }
+
+    /// Load settings from disk, with a backup to offer if the file is unreadable
+    pub fn read() -> Loaded<Self> {
+        let Some(path) = Self::settings_path() else {
+            return Loaded::Missing;
+        };
+        match settings_store::load::<Self>(&path) {
+            Loaded::Ok(settings) => Loaded::Ok(settings.migrate()),
+            Loaded::Missing => Loaded::Missing,
+            Loaded::Unreadable { error, backup } => Loaded::Unreadable {
+                error,
+                backup: backup.map(|(backup, settings)| (backup, settings.migrate())),
+            },
+        }
+    }
+
+    /// Put a backup back as the settings file, keeping the unreadable one aside
+    pub fn restore_backup(backup: &Backup) -> Result<(), String> {
+        let path =
+            Self::settings_path().ok_or_else(|| "Could not determine settings path".to_string())?;
+        settings_store::restore(&path, backup)
+            .map_err(|e| format!("Failed to restore {}: {}", backup.path.display(), e))
+    }
+
+    /// Move an unreadable settings file aside so defaults can be saved
+    pub fn set_aside_unreadable() -> Result<PathBuf, String> {
+        let path =
+            Self::settings_path().ok_or_else(|| "Could not determine settings path".to_string())?;
+        settings_store::set_aside(&path)
+            .map_err(|e| format!("Failed to move {} aside: {}", path.display(), e))
     }
 
-    /// Save settings to disk
+    /// Bring settings saved by older versions up to date
+    pub(crate) fn migrate(mut self) -> Self {
+        let mut seen: Vec<(ChannelId, String)> = Vec::new();
+        for radio in &mut self.configured_radios {
+            if radio.id.is_none() {
+                radio.id = Some(radio.channel_id());
+            }
+            // Radios on two ports of one adapter used to share an ID derived
+            // from its serial number; the later one goes by its port instead
+            let id = radio.channel_id();
+            if seen.iter().any(|(i, port)| *i == id && *port != radio.port) {
+                radio.id = Some(ChannelId::port(&radio.port));
+            }
+            seen.push((radio.channel_id(), radio.port.clone()));
+        }
+        // The old default clashed with rotctld
+        if self.emulated_radio.endpoint == "127.0.0.1:4533" {
+            self.emulated_radio.endpoint = default_emulated_radio_endpoint();
+        }
+        self
+    }
+
+    /// Save settings to disk (does nothing for read-only settings)
     pub fn save(&self) -> Result<(), String> {
+        if self.read_only {
+            return Ok(());
+        }
         let path =
             Self::settings_path().ok_or_else(|| "Could not determine settings path".to_string())?;
 
@@ -230,7 +1261,12 @@ impl Settings {
         let json = serde_json::to_string_pretty(self)
             .map_err(|e| format!("Failed to serialize settings: {}", e))?;
 
-        std::fs::write(&path, json).map_err(|e| format!("Failed to write settings: {}", e))?;
+        // A failed backup shouldn't stop the save
+        if let Err(e) = settings_store::back_up(&path, SystemTime::now()) {
+            tracing::warn!("Failed to back up settings: {}", e);
+        }
+        settings_store::write_atomic(&path, json.as_bytes())
+            .map_err(|e| format!("Failed to write settings: {}", e))?;
 
         Ok(())
     }
@@ -255,33 +1291,251 @@ impl Settings {
             .num_columns(2)
             .spacing([10.0, 8.0])
             .show(ui, |ui| {
+                // Language
+                ui.label(tr!("settings-language"));
+                let system = system_language();
+                egui::ComboBox::from_id_salt("language")
+                    .selected_text(match self.language {
+                        Some(language) => language.native_name().to_string(),
+                        None => tr!("settings-language-system", language = system.native_name()),
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(
+                            &mut self.language,
+                            None,
+                            tr!("settings-language-system", language = system.native_name()),
+                        );
+                        for language in Language::ALL {
+                            ui.selectable_value(
+                                &mut self.language,
+                                Some(language),
+                                language.native_name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+                if self.language != previous.language {
+                    // Numbers follow the language; the separator can still
+                    // be changed back below
+                    let language = self.language();
+                    set_language(language);
+                    self.frequency_format.decimal = language.decimal_separator();
+                }
+
                 // Lockout time
-                ui.label("Lockout time (ms):");
+                ui.label(tr!("settings-lockout"));
                 ui.add(egui::DragValue::new(&mut self.lockout_ms).range(0..=5000));
                 ui.end_row();
 
+                // Stale radio threshold
+                ui.label(tr!("settings-stale"))
+                    .on_hover_text(tr!("settings-stale-hover"));
+                ui.add(egui::DragValue::new(&mut self.stale_radio_secs).range(0..=600));
+                ui.end_row();
+
+                // PTT sequencer
+                ui.label(tr!("settings-ptt-lead"))
+                    .on_hover_text(tr!("settings-ptt-lead-hover"));
+                ui.add(egui::DragValue::new(&mut self.ptt_lead_ms).range(0..=1000));
+                ui.end_row();
+
+                ui.label(tr!("settings-ptt-tail"))
+                    .on_hover_text(tr!("settings-ptt-tail-hover"));
+                ui.add(egui::DragValue::new(&mut self.ptt_tail_ms).range(0..=1000));
+                ui.end_row();
+
+                // Amp burst coalescing
+                ui.label(tr!("settings-amp-coalescing"))
+                    .on_hover_text(tr!("settings-amp-coalescing-hover"));
+                ui.add(egui::DragValue::new(&mut self.amp_coalesce_ms).range(0..=100));
+                ui.end_row();
+
+                // Translation strictness
+                ui.label(tr!("settings-translation"))
+                    .on_hover_text(tr!("settings-translation-hover"));
+                strictness_ui(
+                    ui,
+                    "translation_strictness",
+                    &mut self.translation_strictness,
+                );
+                ui.end_row();
+
+                // Frequency smoothing
+                ui.label(tr!("settings-amp-frequency"))
+                    .on_hover_text(tr!("settings-amp-frequency-hover"));
+                smoothing_policy_ui(
+                    ui,
+                    "amp_smoothing",
+                    OutputSink::Amplifier,
+                    &mut self.frequency_smoothing.amplifier,
+                );
+                ui.end_row();
+
+                ui.label(tr!("settings-band-decoder-frequency"))
+                    .on_hover_text(tr!("settings-band-decoder-frequency-hover"));
+                smoothing_policy_ui(
+                    ui,
+                    "band_decoder_smoothing",
+                    OutputSink::BandDecoder,
+                    &mut self.frequency_smoothing.band_decoder,
+                );
+                ui.end_row();
+
+                // Meter ballistics
+                ui.label(tr!("settings-meter-attack"))
+                    .on_hover_text(tr!("settings-meter-attack-hover"));
+                ui.add(egui::DragValue::new(&mut self.meter_ballistics.attack_ms).range(0..=1000));
+                ui.end_row();
+
+                ui.label(tr!("settings-meter-decay"))
+                    .on_hover_text(tr!("settings-meter-decay-hover"));
+                ui.add(egui::DragValue::new(&mut self.meter_ballistics.decay_ms).range(0..=5000));
+                ui.end_row();
+
+                ui.label(tr!("settings-meter-peak-hold"))
+                    .on_hover_text(tr!("settings-meter-peak-hold-hover"));
+                ui.add(
+                    egui::DragValue::new(&mut self.meter_ballistics.peak_hold_ms).range(0..=10000),
+                );
+                ui.end_row();
+
                 // Traffic history
-                ui.label("Traffic history:");
-                ui.add(egui::DragValue::new(&mut self.traffic_history_size).range(100..=10000));
+                ui.label(tr!("settings-traffic-history"));
+                ui.add(
+                    egui::DragValue::new(&mut self.traffic_history_size)
+                        .range(100..=MAX_HISTORY_ENTRIES)
+                        .speed(100),
+                );
+                ui.end_row();
+
+                // Memory budget
+                ui.label(tr!("settings-memory-budget"))
+                    .on_hover_text(tr!("settings-memory-budget-hover"));
+                ui.horizontal(|ui| {
+                    let mut enabled = self.memory_budget_mb > 0;
+                    if ui.checkbox(&mut enabled, "").changed() {
+                        self.memory_budget_mb = if enabled { 64 } else { 0 };
+                    }
+                    if enabled {
+                        ui.add(
+                            egui::DragValue::new(&mut self.memory_budget_mb)
+                                .range(MIN_BUDGET_MB..=4096)
+                                .speed(8),
+                        );
+                    }
+                    if self.traffic_history_limit() < self.traffic_history_size {
+                        ui.label(
+                            egui::RichText::new(tr!(
+                                "settings-history-capped",
+                                limit = self.traffic_history_limit()
+                            ))
+                            .small()
+                            .color(egui::Color32::GRAY),
+                        );
+                    }
+                });
                 ui.end_row();
 
                 // Show hex
-                ui.label("Show hex:");
+                ui.label(tr!("settings-show-hex"));
                 ui.checkbox(&mut self.show_hex, "");
                 ui.end_row();
 
                 // Show decoded
-                ui.label("Show decoded:");
+                ui.label(tr!("settings-show-decoded"));
                 ui.checkbox(&mut self.show_decoded, "");
                 ui.end_row();
+
+                // Frequency format
+                ui.label(tr!("settings-frequency-format"))
+                    .on_hover_text(tr!("settings-frequency-format-hover"));
+                egui::ComboBox::from_id_salt("frequency_style")
+                    .selected_text(self.frequency_format.style.name())
+                    .show_ui(ui, |ui| {
+                        for style in FrequencyStyle::ALL {
+                            ui.selectable_value(
+                                &mut self.frequency_format.style,
+                                style,
+                                style.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("settings-decimal-separator"));
+                egui::ComboBox::from_id_salt("frequency_decimal")
+                    .selected_text(self.frequency_format.decimal.name())
+                    .show_ui(ui, |ui| {
+                        for decimal in DecimalSeparator::ALL {
+                            ui.selectable_value(
+                                &mut self.frequency_format.decimal,
+                                decimal,
+                                decimal.name(),
+                            );
+                        }
+                    });
+                ui.end_row();
+
+                ui.label(tr!("settings-band-plan"))
+                    .on_hover_text(tr!("settings-band-plan-hover"));
+                egui::ComboBox::from_id_salt("band_plan_region")
+                    .selected_text(self.band_plan_region.name())
+                    .show_ui(ui, |ui| {
+                        for region in BandPlanRegion::ALL {
+                            ui.selectable_value(&mut self.band_plan_region, region, region.name());
+                        }
+                    });
+                ui.end_row();
+            });
+
+        ui.add_space(16.0);
+
+        // Per-crate diagnostic levels
+        let crate_levels = egui::CollapsingHeader::new(tr!("settings-crate-levels"))
+            .id_salt("crate_levels")
+            .default_open(self.layout.section_open("crate_levels"))
+            .show(ui, |ui| {
+                ui.label(
+                    egui::RichText::new(tr!("settings-crate-levels-description"))
+                        .small()
+                        .color(egui::Color32::GRAY),
+                );
+                egui::Grid::new("crate_levels_grid")
+                    .num_columns(2)
+                    .spacing([10.0, 4.0])
+                    .show(ui, |ui| {
+                        for name in PROJECT_CRATES {
+                            let mut level = self
+                                .diagnostic_crate_levels
+                                .get(*name)
+                                .copied()
+                                .unwrap_or_default();
+                            ui.label(*name);
+                            egui::ComboBox::from_id_salt(("crate_level", *name))
+                                .selected_text(level.name())
+                                .show_ui(ui, |ui| {
+                                    for choice in CrateLevel::ALL {
+                                        ui.selectable_value(&mut level, choice, choice.name());
+                                    }
+                                });
+                            ui.end_row();
+
+                            if level == CrateLevel::Inherit {
+                                self.diagnostic_crate_levels.remove(*name);
+                            } else {
+                                self.diagnostic_crate_levels.insert(name.to_string(), level);
+                            }
+                        }
+                    });
             });
+        self.layout.remember_section("crate_levels", &crate_levels);
 
         ui.add_space(16.0);
 
         // Virtual Ports section
-        ui.heading("Virtual Ports");
+        ui.heading(tr!("virtual-ports-heading"));
         ui.label(
-            egui::RichText::new("Configure simulated radios that appear in the port dropdown")
+            egui::RichText::new(tr!("virtual-ports-description"))
                 .small()
                 .color(egui::Color32::GRAY),
         );
@@ -297,7 +1551,8 @@ impl Settings {
                 );
                 if ui
                     .button(
-                        egui::RichText::new("Remove").color(egui::Color32::from_rgb(255, 100, 100)),
+                        egui::RichText::new(tr!("remove"))
+                            .color(egui::Color32::from_rgb(255, 100, 100)),
                     )
                     .clicked()
                 {
@@ -312,7 +1567,7 @@ impl Settings {
         // Add new virtual port form
         ui.separator();
         ui.horizontal(|ui| {
-            ui.label("Add:");
+            ui.label(tr!("virtual-ports-add"));
 
             // Use egui's temp memory for the form state
             let id = ui.id().with("new_vport_name");
@@ -321,7 +1576,7 @@ impl Settings {
                 .unwrap_or_default();
             let name_response = ui.add(
                 egui::TextEdit::singleline(&mut name)
-                    .hint_text("Name")
+                    .hint_text(tr!("virtual-ports-name"))
                     .desired_width(100.0),
             );
             ui.memory_mut(|m| m.data.insert_temp(id, name.clone()));
@@ -359,7 +1614,7 @@ impl Settings {
             // Show error if name already exists
             if !name.is_empty() && self.virtual_ports.iter().any(|v| v.name == name) {
                 ui.label(
-                    egui::RichText::new("Name already exists")
+                    egui::RichText::new(tr!("virtual-ports-name-taken"))
                         .small()
                         .color(egui::Color32::RED),
                 );
@@ -383,9 +1638,12 @@ impl Settings {
         // Show config file location
         if let Some(path) = Self::settings_path() {
             ui.label(
-                egui::RichText::new(format!("Config: {}", path.display()))
-                    .small()
-                    .color(egui::Color32::GRAY),
+                egui::RichText::new(tr!(
+                    "settings-config-path",
+                    path = path.display().to_string()
+                ))
+                .small()
+                .color(egui::Color32::GRAY),
             );
         }
 
@@ -0,0 +1,255 @@
+//! Crash-safe storage for the settings file
+//!
+//! Saves go to a temporary file beside the settings file, which is then
+//! renamed over it, so a crash mid-save leaves either the old file or the
+//! new one, never half of one. Before a save replaces a readable file, a
+//! copy goes into `backups/` (at most one per [`BACKUP_INTERVAL`], keeping
+//! the newest [`BACKUPS_KEPT`]). When the settings file can't be parsed,
+//! [`load`] finds the newest backup that can, for the app to offer.
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::de::DeserializeOwned;
+
+/// Backups kept before the oldest is removed
+pub const BACKUPS_KEPT: usize = 5;
+
+/// Minimum time between backups, so a dragged slider doesn't churn them
+pub const BACKUP_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// A backup copy of the settings file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    pub path: PathBuf,
+    /// When the copy was taken
+    pub taken: SystemTime,
+}
+
+/// What [`load`] found
+#[derive(Debug)]
+pub enum Loaded<T> {
+    /// No settings file yet
+    Missing,
+    /// The settings file parsed
+    Ok(T),
+    /// The settings file exists but couldn't be read or parsed
+    Unreadable {
+        error: String,
+        /// Newest backup that parses, with its contents
+        backup: Option<(Backup, T)>,
+    },
+}
+
+/// Read and parse the settings file at `path`, looking for a backup if it fails
+pub fn load<T: DeserializeOwned>(path: &Path) -> Loaded<T> {
+    let error = match std::fs::read_to_string(path) {
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(value) => return Loaded::Ok(value),
+            Err(e) => format!("{} is not valid: {}", path.display(), e),
+        },
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Loaded::Missing,
+        Err(e) => format!("Could not read {}: {}", path.display(), e),
+    };
+    let backup = backups(path).into_iter().find_map(|backup| {
+        let text = std::fs::read_to_string(&backup.path).ok()?;
+        let value = serde_json::from_str(&text).ok()?;
+        Some((backup, value))
+    });
+    Loaded::Unreadable { error, backup }
+}
+
+/// Replace the file at `path` with `contents` in one step
+pub fn write_atomic(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let tmp = sibling(path, "tmp");
+    let result = std::fs::File::create(&tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = result.and_then(|()| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// Copy the current settings file into the backups if the last copy is old enough
+///
+/// Only a file that parses as JSON is copied, so a damaged file never
+/// displaces a good backup. Returns the new backup, if one was taken.
+pub fn back_up(path: &Path, now: SystemTime) -> io::Result<Option<Backup>> {
+    let existing = backups(path);
+    let recent = existing.first().is_some_and(|newest| {
+        now.duration_since(newest.taken)
+            .is_ok_and(|age| age < BACKUP_INTERVAL)
+    });
+    if recent {
+        return Ok(None);
+    }
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if serde_json::from_str::<serde_json::Value>(&text).is_err() {
+        return Ok(None);
+    }
+
+    let dir = backup_dir(path);
+    std::fs::create_dir_all(&dir)?;
+    let secs = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let backup = Backup {
+        path: dir.join(format!("{}-{}.json", file_stem(path), secs)),
+        taken: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+    };
+    write_atomic(&backup.path, text.as_bytes())?;
+
+    for old in existing.iter().skip(BACKUPS_KEPT - 1) {
+        if let Err(e) = std::fs::remove_file(&old.path) {
+            tracing::warn!("Failed to remove {}: {}", old.path.display(), e);
+        }
+    }
+    Ok(Some(backup))
+}
+
+/// Backups of the file at `path`, newest first
+pub fn backups(path: &Path) -> Vec<Backup> {
+    let prefix = format!("{}-", file_stem(path));
+    let Ok(entries) = std::fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<Backup> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let secs = path
+                .file_name()?
+                .to_str()?
+                .strip_prefix(&prefix)?
+                .strip_suffix(".json")?
+                .parse()
+                .ok()?;
+            Some(Backup {
+                path,
+                taken: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+            })
+        })
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.taken));
+    backups
+}
+
+/// Move an unreadable settings file out of the way, keeping it for inspection
+///
+/// Returns where it went.
+pub fn set_aside(path: &Path) -> io::Result<PathBuf> {
+    let aside = sibling(path, "broken");
+    std::fs::rename(path, &aside)?;
+    Ok(aside)
+}
+
+/// Put `backup` back as the settings file, setting the unreadable one aside
+pub fn restore(path: &Path, backup: &Backup) -> io::Result<()> {
+    let contents = std::fs::read(&backup.path)?;
+    if path.exists() {
+        set_aside(path)?;
+    }
+    write_atomic(path, &contents)
+}
+
+fn backup_dir(path: &Path) -> PathBuf {
+    path.with_file_name("backups")
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// `settings.json` -> `settings.json.<ext>`
+fn sibling(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ext);
+    path.with_file_name(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_backups_rotate() {
+        let dir = temp_dir("settings-rotate");
+        let path = dir.join("settings.json");
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        write_atomic(&path, b"{\"n\": 0}").unwrap();
+        assert!(back_up(&path, t0).unwrap().is_some());
+        // Too soon for another
+        assert!(back_up(&path, t0 + Duration::from_secs(60))
+            .unwrap()
+            .is_none());
+
+        for i in 1..=BACKUPS_KEPT as u32 + 2 {
+            back_up(&path, t0 + BACKUP_INTERVAL * i).unwrap();
+        }
+        let kept = backups(&path);
+        assert_eq!(kept.len(), BACKUPS_KEPT);
+        assert_eq!(
+            kept[0].taken,
+            t0 + BACKUP_INTERVAL * (BACKUPS_KEPT as u32 + 2)
+        );
+        assert!(!dir.join("settings.json.tmp").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_unreadable_file_offers_newest_valid_backup() {
+        let dir = temp_dir("settings-recover");
+        let path = dir.join("settings.json");
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+
+        write_atomic(&path, b"[1]").unwrap();
+        back_up(&path, t0).unwrap();
+        write_atomic(&path, b"[2]").unwrap();
+        back_up(&path, t0 + BACKUP_INTERVAL).unwrap();
+        // Damaged after the last backup: a damaged file is never backed up
+        write_atomic(&path, b"[3").unwrap();
+        assert!(back_up(&path, t0 + BACKUP_INTERVAL * 2).unwrap().is_none());
+
+        let Loaded::Unreadable {
+            backup: Some((backup, value)),
+            ..
+        } = load::<Vec<u8>>(&path)
+        else {
+            panic!("expected a backup to be offered");
+        };
+        assert_eq!(value, vec![2]);
+
+        restore(&path, &backup).unwrap();
+        assert!(matches!(load::<Vec<u8>>(&path), Loaded::Ok(v) if v == vec![2]));
+        assert_eq!(
+            std::fs::read(dir.join("settings.json.broken")).unwrap(),
+            b"[3"
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_missing_file() {
+        let dir = temp_dir("settings-missing");
+        assert!(matches!(
+            load::<Vec<u8>>(&dir.join("settings.json")),
+            Loaded::Missing
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
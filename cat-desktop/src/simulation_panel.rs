@@ -5,11 +5,14 @@
 //! controls are integrated in the radio panel (ui_panels.rs).
 
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
 
-use cat_protocol::{OperatingMode, Protocol, ProtocolId, RadioDatabase, RadioModel};
+use cat_mux::ChannelId;
+use cat_protocol::{OperatingMode, Protocol, ProtocolId, RadioDatabase, RadioModel, Vfo};
 use tokio::sync::mpsc;
 
-use cat_sim::VirtualRadioCommand;
+use cat_sim::{Clock, ResponderRule, SimClock, VirtualRadioCommand};
 
 /// State of a virtual radio for display purposes
 #[derive(Debug, Clone)]
@@ -20,36 +23,86 @@ pub struct VirtualRadioDisplayState {
     pub protocol: Protocol,
     /// Radio model (if known)
     pub model: Option<RadioModel>,
-    /// Current frequency in Hz
+    /// Current frequency in Hz (selected VFO)
     pub frequency_hz: u64,
     /// Current operating mode
     pub mode: OperatingMode,
+    /// Frequency of the unselected VFO in Hz
+    pub other_frequency_hz: u64,
+    /// Selected (receive) VFO
+    pub rx_vfo: Vfo,
+    /// Split on (transmit on the unselected VFO)
+    pub split: bool,
     /// PTT active state
     pub ptt: bool,
+    /// Auto-responder rules
+    pub rules: Vec<ResponderRule>,
 }
 
 impl VirtualRadioDisplayState {
     /// Create a new display state with default values
-    pub fn new(name: String, protocol: Protocol) -> Self {
+    pub fn new(name: String, protocol: Protocol, rules: Vec<ResponderRule>) -> Self {
         Self {
             name,
             protocol,
             model: RadioDatabase::default_for_protocol(protocol),
             frequency_hz: 14_250_000, // 20m default
             mode: OperatingMode::Usb,
+            other_frequency_hz: 14_250_000,
+            rx_vfo: Vfo::A,
+            split: false,
             ptt: false,
+            rules,
+        }
+    }
+
+    /// The VFO that is not selected
+    pub fn other_vfo(&self) -> Vfo {
+        if self.rx_vfo == Vfo::B {
+            Vfo::A
+        } else {
+            Vfo::B
+        }
+    }
+
+    /// Track the VFO changes a UI command makes on the radio
+    ///
+    /// The virtual radio does not report its unselected VFO through the mux,
+    /// so the panel mirrors what it asked for.
+    fn apply(&mut self, cmd: &VirtualRadioCommand) {
+        match cmd {
+            VirtualRadioCommand::SetVfoFrequency(vfo, hz) if *vfo == self.rx_vfo => {
+                self.frequency_hz = *hz;
+            }
+            VirtualRadioCommand::SetVfoFrequency(_, hz) => self.other_frequency_hz = *hz,
+            VirtualRadioCommand::SelectVfo(vfo @ (Vfo::A | Vfo::B)) if *vfo != self.rx_vfo => {
+                self.rx_vfo = *vfo;
+                std::mem::swap(&mut self.frequency_hz, &mut self.other_frequency_hz);
+            }
+            VirtualRadioCommand::SetSplit(on) => self.split = *on,
+            VirtualRadioCommand::EqualizeVfos => self.other_frequency_hz = self.frequency_hz,
+            VirtualRadioCommand::SetRules(rules) => self.rules = rules.clone(),
+            _ => {}
         }
     }
 }
 
 /// Simulation panel state - manages command channels to virtual radios
 pub struct SimulationPanel {
-    /// Display state for each virtual radio (keyed by sim_id)
-    radio_states: HashMap<String, VirtualRadioDisplayState>,
-    /// Command senders for each virtual radio (keyed by sim_id)
-    radio_commands: HashMap<String, mpsc::Sender<VirtualRadioCommand>>,
+    /// Display state for each virtual radio
+    radio_states: HashMap<ChannelId, VirtualRadioDisplayState>,
+    /// Command senders for each virtual radio
+    radio_commands: HashMap<ChannelId, mpsc::Sender<VirtualRadioCommand>>,
+    /// Time source shared by every virtual radio and amplifier
+    clock: SimClock,
 }
 
+/// Speeds offered for the simulation clock
+pub const CLOCK_RATES: &[f64] = &[1.0, 10.0, 60.0];
+
+/// How far one press of Step moves a paused clock
+pub const CLOCK_STEP: Duration = Duration::from_secs(1);
+
 impl Default for SimulationPanel {
     fn default() -> Self {
         Self::new()
@@ -62,43 +115,79 @@ impl SimulationPanel {
         Self {
             radio_states: HashMap::new(),
             radio_commands: HashMap::new(),
+            clock: SimClock::new(),
         }
     }
 
+    /// Clock to give a new virtual device
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::new(self.clock.clone())
+    }
+
+    /// Whether the simulation clock is stopped
+    pub fn is_paused(&self) -> bool {
+        self.clock.is_paused()
+    }
+
+    /// Stop or restart time for every virtual device
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused {
+            self.clock.pause();
+        } else {
+            self.clock.resume();
+        }
+    }
+
+    /// Move a paused clock forward by [`CLOCK_STEP`]
+    pub fn step(&mut self) {
+        self.clock.advance(CLOCK_STEP);
+    }
+
+    /// Current clock speed relative to real time
+    pub fn rate(&self) -> f64 {
+        self.clock.rate()
+    }
+
+    /// Run virtual devices at `rate` times real time
+    pub fn set_rate(&mut self, rate: f64) {
+        self.clock.set_rate(rate);
+    }
+
     /// Register a virtual radio after it has been added by App
     ///
     /// Called by App::add_virtual_radio() after spawning the actor.
     pub fn register_radio(
         &mut self,
-        sim_id: String,
+        channel_id: ChannelId,
         name: String,
         protocol: Protocol,
+        rules: Vec<ResponderRule>,
         cmd_tx: mpsc::Sender<VirtualRadioCommand>,
     ) {
         self.radio_states.insert(
-            sim_id.clone(),
-            VirtualRadioDisplayState::new(name, protocol),
+            channel_id.clone(),
+            VirtualRadioDisplayState::new(name, protocol, rules),
         );
-        self.radio_commands.insert(sim_id, cmd_tx);
+        self.radio_commands.insert(channel_id, cmd_tx);
     }
 
     /// Unregister a virtual radio
     ///
     /// Called by App::remove_virtual_radio().
-    pub fn unregister_radio(&mut self, sim_id: &str) {
-        self.radio_states.remove(sim_id);
-        self.radio_commands.remove(sim_id);
+    pub fn unregister_radio(&mut self, channel_id: &ChannelId) {
+        self.radio_states.remove(channel_id);
+        self.radio_commands.remove(channel_id);
     }
 
     /// Update a radio's display state from mux events
     pub fn update_radio_state(
         &mut self,
-        sim_id: &str,
+        channel_id: &ChannelId,
         frequency_hz: Option<u64>,
         mode: Option<OperatingMode>,
         ptt: Option<bool>,
     ) {
-        if let Some(state) = self.radio_states.get_mut(sim_id) {
+        if let Some(state) = self.radio_states.get_mut(channel_id) {
             if let Some(hz) = frequency_hz {
                 state.frequency_hz = hz;
             }
@@ -111,6 +200,11 @@ impl SimulationPanel {
         }
     }
 
+    /// Get a virtual radio's display state
+    pub fn radio_state(&self, channel_id: &ChannelId) -> Option<&VirtualRadioDisplayState> {
+        self.radio_states.get(channel_id)
+    }
+
     /// Get radio configurations for saving to settings
     ///
     /// Returns an iterator of VirtualRadioConfig from the current display state.
@@ -130,14 +224,18 @@ impl SimulationPanel {
                         None
                     }
                 }),
+                rules: state.rules.clone(),
             })
     }
 
     /// Send a command to a virtual radio
     ///
     /// This can be called from app.rs for the radio panel UI controls.
-    pub fn send_command(&self, sim_id: &str, cmd: VirtualRadioCommand) {
-        if let Some(tx) = self.radio_commands.get(sim_id) {
+    pub fn send_command(&mut self, channel_id: &ChannelId, cmd: VirtualRadioCommand) {
+        if let Some(state) = self.radio_states.get_mut(channel_id) {
+            state.apply(&cmd);
+        }
+        if let Some(tx) = self.radio_commands.get(channel_id) {
             let _ = tx.try_send(cmd);
         }
     }
@@ -0,0 +1,167 @@
+//! Crash-loop detection for safe-mode startup
+//!
+//! A sentinel file is written when the app starts and removed once it has
+//! run for [`STARTUP_GRACE`] or exits cleanly. Finding the sentinel on the
+//! next start means that startup never finished; after
+//! [`SAFE_MODE_THRESHOLD`] such starts in a row the app comes up in safe mode.
+//! While starting, the sentinel also records the last radio channel the app
+//! began connecting, which is the most likely culprit.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use cat_mux::ChannelId;
+use serde::{Deserialize, Serialize};
+
+/// Consecutive unfinished startups before safe mode kicks in
+pub const SAFE_MODE_THRESHOLD: u32 = 2;
+
+/// How long the app must run before a startup counts as finished
+pub const STARTUP_GRACE: Duration = Duration::from_secs(30);
+
+/// Sentinel file contents
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Sentinel {
+    /// Unfinished startups before this one
+    failed_starts: u32,
+    /// Last radio channel the app began connecting
+    #[serde(default)]
+    last_channel: Option<ChannelId>,
+}
+
+/// Tracks whether the current startup has finished
+pub struct StartupGuard {
+    path: Option<PathBuf>,
+    sentinel: Sentinel,
+    /// Channel recorded by the previous (unfinished) startup
+    suspect: Option<ChannelId>,
+    started: Instant,
+    finished: bool,
+}
+
+impl StartupGuard {
+    /// Read the previous sentinel (if any) and write this startup's
+    ///
+    /// With no path (no config directory) crashes are never detected.
+    pub fn begin(path: Option<PathBuf>) -> Self {
+        let previous = path
+            .as_ref()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .map(|s| serde_json::from_str::<Sentinel>(&s).unwrap_or_default());
+
+        let sentinel = Sentinel {
+            failed_starts: previous.as_ref().map_or(0, |s| s.failed_starts + 1),
+            last_channel: None,
+        };
+        let guard = Self {
+            path,
+            sentinel,
+            suspect: previous.and_then(|s| s.last_channel),
+            started: Instant::now(),
+            finished: false,
+        };
+        guard.write();
+        guard
+    }
+
+    /// Whether the last startups failed often enough to start in safe mode
+    pub fn safe_mode(&self) -> bool {
+        self.sentinel.failed_starts >= SAFE_MODE_THRESHOLD
+    }
+
+    /// Consecutive unfinished startups before this one
+    pub fn failed_starts(&self) -> u32 {
+        self.sentinel.failed_starts
+    }
+
+    /// Channel the previous startup was connecting when it stopped
+    pub fn suspect(&self) -> Option<&ChannelId> {
+        self.suspect.as_ref()
+    }
+
+    /// Record the channel about to be connected (ignored once finished)
+    pub fn note_channel(&mut self, channel: &ChannelId) {
+        if !self.finished {
+            self.sentinel.last_channel = Some(channel.clone());
+            self.write();
+        }
+    }
+
+    /// Finish the startup once the grace period has passed
+    pub fn poll(&mut self, now: Instant) {
+        if !self.finished && now.duration_since(self.started) >= STARTUP_GRACE {
+            self.finish();
+        }
+    }
+
+    /// Mark the startup as finished (removes the sentinel)
+    pub fn finish(&mut self) {
+        if self.finished {
+            return;
+        }
+        self.finished = true;
+        if let Some(path) = &self.path {
+            if let Err(e) = std::fs::remove_file(path) {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to remove {}: {}", path.display(), e);
+                }
+            }
+        }
+    }
+
+    fn write(&self) {
+        let Some(path) = &self.path else { return };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        let result = serde_json::to_string(&self.sentinel)
+            .map_err(|e| e.to_string())
+            .and_then(|json| std::fs::write(path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to write {}: {}", path.display(), e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    #[test]
+    fn test_repeated_unfinished_startups_enter_safe_mode() {
+        let dir = temp_dir("startup");
+        let path = dir.join("startup.json");
+        let radio = ChannelId::port("/dev/ttyUSB0");
+
+        // First start, crashes while connecting a radio
+        let mut guard = StartupGuard::begin(Some(path.clone()));
+        assert_eq!(guard.failed_starts(), 0);
+        guard.note_channel(&radio);
+        drop(guard);
+
+        // Second start crashes at the same place
+        let mut guard = StartupGuard::begin(Some(path.clone()));
+        assert_eq!(guard.failed_starts(), 1);
+        assert!(!guard.safe_mode());
+        assert_eq!(guard.suspect(), Some(&radio));
+        guard.note_channel(&radio);
+        drop(guard);
+
+        // Third start comes up in safe mode
+        let mut guard = StartupGuard::begin(Some(path.clone()));
+        assert!(guard.safe_mode());
+        assert_eq!(guard.suspect(), Some(&radio));
+        guard.finish();
+        // Ignored after the startup has finished
+        guard.note_channel(&radio);
+        assert!(!path.exists());
+
+        // A finished startup resets the count
+        let mut guard = StartupGuard::begin(Some(path.clone()));
+        assert_eq!(guard.failed_starts(), 0);
+        guard.finish();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers shared by the unit tests
+
+use std::path::PathBuf;
+
+/// An empty scratch folder under the system temp dir, unique to this test
+/// process and `name`
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("catapult-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -1,19 +1,22 @@
 //! Annotation caching for traffic monitor
+//!
+//! Traffic entries only store raw bytes; frames are decoded when a row
+//! becomes visible and the result is kept here so scrolling doesn't
+//! re-parse the same frames every repaint.
 
 use std::collections::{HashMap, VecDeque};
 
-use cat_protocol::display::{decode_and_annotate_with_hint, AnnotatedFrame};
-use cat_protocol::Protocol;
+use cat_protocol::display::{decode_and_annotate_for_model, AnnotatedFrame};
+use cat_protocol::yaesu_menu::menu_table_for_model;
+use cat_protocol::{Protocol, RadioModel};
 
-use super::TrafficMonitor;
-
-/// Maximum number of entries in the annotation cache
-pub(super) const ANNOTATION_CACHE_MAX_SIZE: usize = 1000;
+/// Maximum number of entries in the annotation cache (without a memory budget)
+pub(crate) const ANNOTATION_CACHE_MAX_SIZE: usize = 1000;
 
 /// Cache key for AnnotatedFrame results
 ///
-/// Combines a hash of the raw bytes with the protocol hint to create
-/// a unique key for caching decoded frames.
+/// Combines a hash of the raw bytes with the protocol hint and the source
+/// model's menu width to create a unique key for caching decoded frames.
 #[derive(Clone, Eq, PartialEq, Hash)]
 pub(super) struct AnnotationCacheKey {
     /// Hash of the raw bytes (using FxHash-style computation for speed)
@@ -22,11 +25,17 @@ pub(super) struct AnnotationCacheKey {
     bytes_len: usize,
     /// Protocol hint used for decoding
     protocol: Option<Protocol>,
+    /// Menu item width of the source radio's model, if it has a menu table
+    menu_digits: Option<usize>,
 }
 
 impl AnnotationCacheKey {
-    /// Create a new cache key from raw bytes and protocol hint
-    pub(super) fn new(bytes: &[u8], protocol: Option<Protocol>) -> Self {
+    /// Create a new cache key from raw bytes, protocol hint and source model
+    pub(super) fn new(
+        bytes: &[u8],
+        protocol: Option<Protocol>,
+        model: Option<&RadioModel>,
+    ) -> Self {
         // Fast hash computation (FxHash-style)
         let mut hash: u64 = 0;
         for &byte in bytes {
@@ -38,44 +47,140 @@ impl AnnotationCacheKey {
             bytes_hash: hash,
             bytes_len: bytes.len(),
             protocol,
+            menu_digits: model
+                .and_then(|m| menu_table_for_model(&m.model))
+                .map(|t| t.digits),
         }
     }
 }
 
-/// Type alias for the annotation cache
-pub(super) type AnnotationCache = HashMap<AnnotationCacheKey, Option<AnnotatedFrame>>;
+/// LRU-style cache of decoded frames, evicting in insertion order
+pub(super) struct AnnotationCache {
+    entries: HashMap<AnnotationCacheKey, Option<AnnotatedFrame>>,
+    /// Most entries kept
+    capacity: usize,
+    /// Keys in insertion order for eviction
+    order: VecDeque<AnnotationCacheKey>,
+}
 
-/// Type alias for the cache order queue
-pub(super) type CacheOrder = VecDeque<AnnotationCacheKey>;
+impl AnnotationCache {
+    pub(super) fn new() -> Self {
+        Self {
+            entries: HashMap::with_capacity(ANNOTATION_CACHE_MAX_SIZE),
+            capacity: ANNOTATION_CACHE_MAX_SIZE,
+            order: VecDeque::with_capacity(ANNOTATION_CACHE_MAX_SIZE),
+        }
+    }
 
-impl TrafficMonitor {
-    /// Get cached annotation or compute and cache it
-    pub(super) fn get_cached_annotation(
+    /// Get cached annotation or decode and cache it
+    ///
+    /// `model` is the identified model of the radio the frame came from or
+    /// went to, when known.
+    pub(super) fn get_or_decode(
         &mut self,
         data: &[u8],
         protocol: Option<Protocol>,
+        model: Option<&RadioModel>,
     ) -> Option<AnnotatedFrame> {
-        let key = AnnotationCacheKey::new(data, protocol);
+        let key = AnnotationCacheKey::new(data, protocol, model);
 
         // Check cache first
-        if let Some(cached) = self.annotation_cache.get(&key) {
+        if let Some(cached) = self.entries.get(&key) {
             return cached.clone();
         }
 
         // Decode and cache
-        let result = decode_and_annotate_with_hint(data, protocol);
+        let result = decode_and_annotate_for_model(data, protocol, model);
 
         // Evict oldest entry if cache is full
-        if self.annotation_cache.len() >= ANNOTATION_CACHE_MAX_SIZE {
-            if let Some(old_key) = self.cache_order.pop_front() {
-                self.annotation_cache.remove(&old_key);
+        if self.entries.len() >= self.capacity {
+            if let Some(old_key) = self.order.pop_front() {
+                self.entries.remove(&old_key);
             }
         }
 
         // Insert into cache
-        self.annotation_cache.insert(key.clone(), result.clone());
-        self.cache_order.push_back(key);
+        self.entries.insert(key.clone(), result.clone());
+        self.order.push_back(key);
 
         result
     }
+
+    pub(super) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Keep at most `capacity` decodes, dropping the oldest
+    pub(super) fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.entries.len() > self.capacity {
+            let Some(old_key) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&old_key);
+        }
+    }
+
+    pub(super) fn clear(&mut self) {
+        self.entries.clear();
+        self.order.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_is_bounded() {
+        let mut cache = AnnotationCache::new();
+        for i in 0..(ANNOTATION_CACHE_MAX_SIZE as u64 + 50) {
+            let frame = format!("FA{:011};", 14_000_000 + i);
+            cache.get_or_decode(frame.as_bytes(), Some(Protocol::Kenwood), None);
+        }
+        assert_eq!(cache.len(), ANNOTATION_CACHE_MAX_SIZE);
+    }
+
+    #[test]
+    fn test_cache_shrinks_to_capacity() {
+        let mut cache = AnnotationCache::new();
+        for i in 0..100u64 {
+            let frame = format!("FA{:011};", 14_000_000 + i);
+            cache.get_or_decode(frame.as_bytes(), Some(Protocol::Kenwood), None);
+        }
+        cache.set_capacity(10);
+        assert_eq!(cache.len(), 10);
+        cache.get_or_decode(b"FA00007074000;", Some(Protocol::Kenwood), None);
+        assert_eq!(cache.len(), 10);
+    }
+
+    #[test]
+    fn test_cache_hit_matches_decode() {
+        let mut cache = AnnotationCache::new();
+        let first = cache.get_or_decode(b"FA00014074000;", Some(Protocol::Kenwood), None);
+        let second = cache.get_or_decode(b"FA00014074000;", Some(Protocol::Kenwood), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(first.map(|f| f.protocol), second.map(|f| f.protocol));
+    }
+
+    #[test]
+    fn test_cache_keeps_model_widths_apart() {
+        let ftdx10 = cat_protocol::RadioDatabase::by_yaesu_ascii_id("0690").unwrap();
+        let item = |frame: Option<AnnotatedFrame>| {
+            let frame = frame.unwrap();
+            let item = frame.segments.iter().find(|s| s.label == "item").unwrap();
+            item.value.clone()
+        };
+        let mut cache = AnnotationCache::new();
+        let protocol = Some(Protocol::YaesuAscii);
+        assert_eq!(
+            item(cache.get_or_decode(b"EX030101;", protocol, None)),
+            "030"
+        );
+        assert_eq!(
+            item(cache.get_or_decode(b"EX030101;", protocol, Some(&ftdx10))),
+            "030101"
+        );
+        assert_eq!(cache.len(), 2);
+    }
 }
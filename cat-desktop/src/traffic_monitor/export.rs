@@ -3,6 +3,9 @@
 use std::path::PathBuf;
 use std::time::SystemTime;
 
+use cat_mux::AnalyzerDirection;
+use cat_protocol::display::{decode_and_annotate_for_model, format_hex};
+
 use super::models::{DiagnosticSeverity, TrafficDirection, TrafficEntry, TrafficSource};
 use super::TrafficMonitor;
 
@@ -16,6 +19,9 @@ impl TrafficMonitor {
             // Otherwise, all diagnostics that arrive have already passed the tracing filter
             return self.diagnostic_level.is_some();
         }
+        if let TrafficEntry::Bookmark { .. } = entry {
+            return true;
+        }
 
         // Direction filter for data entries
         entry
@@ -25,7 +31,7 @@ impl TrafficMonitor {
     }
 
     /// Format a timestamp for export
-    fn format_timestamp(timestamp: &SystemTime) -> String {
+    pub(crate) fn format_timestamp(timestamp: &SystemTime) -> String {
         timestamp
             .duration_since(SystemTime::UNIX_EPOCH)
             .map(|d| {
@@ -39,40 +45,70 @@ impl TrafficMonitor {
             .unwrap_or_else(|_| "??:??:??.???".to_string())
     }
 
+    /// Name a frame's source the way the export writes it
+    pub(super) fn format_source(source: &TrafficSource) -> String {
+        match source {
+            TrafficSource::RealRadio { port, .. } => format!("Radio({})", port),
+            TrafficSource::ToRealRadio { port, .. } => format!("->Radio({})", port),
+            TrafficSource::RealAmplifier { port } => format!("->Amp({})", port),
+            TrafficSource::SuppressedAmplifier { port } => {
+                format!("->Amp({}, dry run)", port)
+            }
+            TrafficSource::FromRealAmplifier { port } => format!("Amp({})", port),
+            TrafficSource::Analyzer {
+                direction: AnalyzerDirection::LoggerToRadio,
+            } => "Logger->Radio".to_string(),
+            TrafficSource::Analyzer {
+                direction: AnalyzerDirection::RadioToLogger,
+            } => "Radio->Logger".to_string(),
+            TrafficSource::ShadowAmplifier => "->Shadow".to_string(),
+            TrafficSource::FromShadowAmplifier => "Shadow".to_string(),
+            TrafficSource::CaptureFile { file } => format!("File({})", file),
+            TrafficSource::Logged {
+                channel,
+                outgoing: true,
+            } => format!("->Log({})", channel),
+            TrafficSource::Logged {
+                channel,
+                outgoing: false,
+            } => format!("Log({})", channel),
+        }
+    }
+
     /// Format an entry as a text line for export
-    fn format_entry_for_export(entry: &TrafficEntry) -> String {
+    pub(super) fn format_entry_for_export(&self, entry: &TrafficEntry) -> String {
         match entry {
             TrafficEntry::Data {
                 timestamp,
                 direction,
                 source,
                 data,
-                decoded,
+                protocol,
+                link,
             } => {
                 let time = Self::format_timestamp(timestamp);
                 let dir = match direction {
                     TrafficDirection::Incoming => "IN ",
                     TrafficDirection::Outgoing => "OUT",
                 };
-                let src = match source {
-                    TrafficSource::RealRadio { port, .. } => format!("Radio({})", port),
-                    TrafficSource::ToRealRadio { port, .. } => format!("->Radio({})", port),
-                    TrafficSource::RealAmplifier { port } => format!("->Amp({})", port),
-                    TrafficSource::FromRealAmplifier { port } => format!("Amp({})", port),
-                };
-                let hex: String = data
-                    .iter()
-                    .map(|b| format!("{:02X}", b))
-                    .collect::<Vec<_>>()
-                    .join(" ");
-                let decoded_str = decoded
-                    .as_ref()
-                    .map(|d| {
-                        let summary: String = d.summary.iter().map(|p| p.text.as_str()).collect();
-                        format!(" [{}] {}", d.protocol, summary)
-                    })
+                let src = Self::format_source(source);
+                let hex = format_hex(data);
+                // Decode directly rather than through the display cache so a
+                // full export doesn't evict the rows currently on screen
+                let model = source.radio().and_then(|h| self.radio_models.get(&h));
+                let decoded_str = decode_and_annotate_for_model(data, *protocol, model)
+                    .map(|d| format!(" [{}] {}", d.protocol, d.summary_text()))
                     .unwrap_or_default();
-                format!("{} {} {:12} {}{}", time, dir, src, hex, decoded_str)
+                // Received frames are numbered; writes name the frame behind them
+                let link_str = match (link.id, link.cause) {
+                    (Some(id), _) => format!(" id={}", id.0),
+                    (None, Some(cause)) => format!(" cause={}", cause.0),
+                    (None, None) => String::new(),
+                };
+                format!(
+                    "{} {} {:12} {}{}{}",
+                    time, dir, src, hex, decoded_str, link_str
+                )
             }
             TrafficEntry::Diagnostic {
                 timestamp,
@@ -89,6 +125,15 @@ impl TrafficMonitor {
                 };
                 format!("{} {} [{}] {}", time, sev, source, message)
             }
+            TrafficEntry::Bookmark {
+                timestamp,
+                source,
+                note,
+                ..
+            } => {
+                let time = Self::format_timestamp(timestamp);
+                format!("{} MARK  [{}] {}", time, source, note)
+            }
         }
     }
 
@@ -105,13 +150,34 @@ impl TrafficMonitor {
         output.push_str(&format!("# Entries: {}\n\n", filtered.len()));
 
         for entry in filtered {
-            output.push_str(&Self::format_entry_for_export(entry));
+            output.push_str(&self.format_entry_for_export(entry));
             output.push('\n');
         }
 
         output
     }
 
+    /// Format the last `limit` entries matching `keep`, ignoring the view filters
+    ///
+    /// Used for bug reports, which want what happened regardless of what the
+    /// console is currently showing.
+    pub fn format_recent(&self, limit: usize, keep: impl Fn(&TrafficEntry) -> bool) -> String {
+        let recent: Vec<_> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|e| keep(e))
+            .take(limit)
+            .collect();
+
+        let mut output = String::new();
+        for entry in recent.into_iter().rev() {
+            output.push_str(&self.format_entry_for_export(entry));
+            output.push('\n');
+        }
+        output
+    }
+
     /// Save the filtered log to a user-selected file
     /// Returns Ok(Some(path)) on success, Ok(None) if cancelled, Err on failure
     pub fn save_filtered_log_with_dialog(&self) -> Result<Option<PathBuf>, String> {
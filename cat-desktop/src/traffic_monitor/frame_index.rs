@@ -0,0 +1,393 @@
+//! Search index over the frames in the history
+//!
+//! A session repeats the same few frames thousands of times: every `FA;`
+//! poll, every frequency report while the radio sits on one spot. The index
+//! gives each distinct frame (bytes and protocol hint) an id, decodes and
+//! classifies it once, and records which frame every data entry holds.
+//! Searching then tests each distinct frame's text rather than decoding
+//! every entry again for every query.
+//!
+//! Like the row index it follows the entries by sequence number: new
+//! entries are indexed as they arrive ([`INDEX_CHUNK`] per frame), trimmed
+//! ones are dropped from the front, and a frame no entry holds any more is
+//! forgotten.
+
+use std::collections::{HashMap, VecDeque};
+
+use cat_protocol::display::decode_and_annotate_with_hint;
+use cat_protocol::icom::PREAMBLE;
+use cat_protocol::{ControllerSideCodec, Protocol, RadioRequest, RadioResponse, RadioSideCodec};
+
+use super::models::TrafficEntry;
+
+/// Entries indexed per frame
+pub(super) const INDEX_CHUNK: usize = 20_000;
+
+/// What a frame is about, whichever way it travels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum CommandClass {
+    Frequency,
+    Mode,
+    Ptt,
+    Vfo,
+    Status,
+    Power,
+    Meter,
+    Keyer,
+    Rit,
+    Id,
+    AutoInfo,
+}
+
+impl CommandClass {
+    pub(super) const ALL: [CommandClass; 11] = [
+        CommandClass::Frequency,
+        CommandClass::Mode,
+        CommandClass::Ptt,
+        CommandClass::Vfo,
+        CommandClass::Status,
+        CommandClass::Power,
+        CommandClass::Meter,
+        CommandClass::Keyer,
+        CommandClass::Rit,
+        CommandClass::Id,
+        CommandClass::AutoInfo,
+    ];
+
+    /// Name typed after `cmd:` in the search box
+    pub(super) fn name(&self) -> &'static str {
+        match self {
+            Self::Frequency => "freq",
+            Self::Mode => "mode",
+            Self::Ptt => "ptt",
+            Self::Vfo => "vfo",
+            Self::Status => "status",
+            Self::Power => "power",
+            Self::Meter => "meter",
+            Self::Keyer => "keyer",
+            Self::Rit => "rit",
+            Self::Id => "id",
+            Self::AutoInfo => "ai",
+        }
+    }
+
+    /// Class named `name` (case-insensitive)
+    pub(super) fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|class| class.name() == name)
+    }
+
+    fn of_response(response: &RadioResponse) -> Option<Self> {
+        Some(match response {
+            RadioResponse::Frequency { .. } => Self::Frequency,
+            RadioResponse::Mode { .. } => Self::Mode,
+            RadioResponse::Ptt { .. } => Self::Ptt,
+            RadioResponse::Vfo { .. }
+            | RadioResponse::VfoState { .. }
+            | RadioResponse::ControlBand { .. }
+            | RadioResponse::TransmitBand { .. } => Self::Vfo,
+            RadioResponse::Status { .. } => Self::Status,
+            RadioResponse::TxPower { .. } => Self::Power,
+            RadioResponse::Meter { .. } => Self::Meter,
+            RadioResponse::KeyerSpeed { .. }
+            | RadioResponse::BreakIn { .. }
+            | RadioResponse::BreakInDelay { .. }
+            | RadioResponse::SidetonePitch { .. } => Self::Keyer,
+            RadioResponse::Rit { .. } | RadioResponse::RitOffset { .. } => Self::Rit,
+            RadioResponse::Id { .. } => Self::Id,
+            RadioResponse::AutoInfo { .. } => Self::AutoInfo,
+            RadioResponse::Unknown { .. } => return None,
+        })
+    }
+
+    fn of_request(request: &RadioRequest) -> Option<Self> {
+        Some(match request {
+            RadioRequest::SetFrequency { .. }
+            | RadioRequest::SetFrequencyMode { .. }
+            | RadioRequest::GetFrequency => Self::Frequency,
+            RadioRequest::SetMode { .. } | RadioRequest::GetMode => Self::Mode,
+            RadioRequest::SetPtt { .. } | RadioRequest::GetPtt => Self::Ptt,
+            RadioRequest::SetVfoState { .. }
+            | RadioRequest::SetVfo { .. }
+            | RadioRequest::GetVfo
+            | RadioRequest::GetControlBand
+            | RadioRequest::GetTransmitBand => Self::Vfo,
+            RadioRequest::GetStatus => Self::Status,
+            RadioRequest::SetPower { .. }
+            | RadioRequest::SetTxPower { .. }
+            | RadioRequest::GetTxPower => Self::Power,
+            RadioRequest::GetMeter { .. } => Self::Meter,
+            RadioRequest::SetKeyerSpeed { .. }
+            | RadioRequest::GetKeyerSpeed
+            | RadioRequest::SetBreakIn { .. }
+            | RadioRequest::GetBreakIn
+            | RadioRequest::SetBreakInDelay { .. }
+            | RadioRequest::GetBreakInDelay
+            | RadioRequest::SetSidetonePitch { .. }
+            | RadioRequest::GetSidetonePitch => Self::Keyer,
+            RadioRequest::SetRit { .. }
+            | RadioRequest::AdjustRit { .. }
+            | RadioRequest::ClearRit
+            | RadioRequest::GetRit
+            | RadioRequest::GetRitOffset => Self::Rit,
+            RadioRequest::GetId => Self::Id,
+            RadioRequest::SetAutoInfo { .. } | RadioRequest::GetAutoInfo => Self::AutoInfo,
+            RadioRequest::Unknown { .. } => return None,
+        })
+    }
+
+    /// Class of a frame: what it reports, or failing that what it asks
+    pub(super) fn of_frame(data: &[u8], protocol: Option<Protocol>) -> Option<Self> {
+        let protocol = protocol.or_else(|| guess_protocol(data))?;
+        let mut radio = RadioSideCodec::new(protocol);
+        radio.push_bytes(data);
+        if let Some(class) = radio.next_response().as_ref().and_then(Self::of_response) {
+            return Some(class);
+        }
+        let mut controller = ControllerSideCodec::new(protocol);
+        controller.push_bytes(data);
+        controller
+            .next_request()
+            .as_ref()
+            .and_then(Self::of_request)
+    }
+}
+
+/// Protocol of a frame logged without a hint, as the decoder guesses it
+fn guess_protocol(data: &[u8]) -> Option<Protocol> {
+    if data.starts_with(&[PREAMBLE, PREAMBLE]) {
+        Some(Protocol::IcomCIV)
+    } else if data.last() == Some(&b';') {
+        Some(Protocol::Kenwood)
+    } else if data.len() == 5 {
+        Some(Protocol::Yaesu)
+    } else {
+        None
+    }
+}
+
+/// One distinct frame and what it is searched by
+#[derive(Debug)]
+pub(super) struct IndexedFrame {
+    pub data: Vec<u8>,
+    protocol: Option<Protocol>,
+    /// Decoded summary, then the frame itself if it is text, lower-cased
+    pub text: String,
+    pub class: Option<CommandClass>,
+    /// Entries holding it
+    uses: usize,
+}
+
+impl IndexedFrame {
+    fn new(data: &[u8], protocol: Option<Protocol>) -> Self {
+        let mut text = decode_and_annotate_with_hint(data, protocol)
+            .map(|decoded| decoded.summary_text())
+            .unwrap_or_default();
+        if data.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+            text.push('\n');
+            text.push_str(&String::from_utf8_lossy(data));
+        }
+        Self {
+            data: data.to_vec(),
+            protocol,
+            text: text.to_lowercase(),
+            class: CommandClass::of_frame(data, protocol),
+            uses: 0,
+        }
+    }
+}
+
+/// Which distinct frame each entry holds
+#[derive(Debug, Default)]
+pub(super) struct FrameIndex {
+    /// Frame id of each indexed entry (None for diagnostics and bookmarks)
+    ids: VecDeque<Option<usize>>,
+    /// Sequence number of the first of `ids`
+    first: u64,
+    /// Frames by id; None for a free id
+    frames: Vec<Option<IndexedFrame>>,
+    by_key: HashMap<(Vec<u8>, Option<Protocol>), usize>,
+    free: Vec<usize>,
+}
+
+impl FrameIndex {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Entries before this sequence number have been indexed
+    pub(super) fn end(&self) -> u64 {
+        self.first + self.ids.len() as u64
+    }
+
+    /// Whether nothing is indexed
+    pub(super) fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Number of distinct frames
+    #[cfg(test)]
+    pub(super) fn distinct(&self) -> usize {
+        self.by_key.len()
+    }
+
+    /// The frame the entry with sequence number `seq` holds, if indexed
+    pub(super) fn frame(&self, seq: u64) -> Option<&IndexedFrame> {
+        let id = (*self
+            .ids
+            .get(usize::try_from(seq.checked_sub(self.first)?).ok()?)?)?;
+        self.frames[id].as_ref()
+    }
+
+    /// Index up to [`INDEX_CHUNK`] more of `entries`, whose oldest has
+    /// sequence number `first_seq`
+    pub(super) fn update(&mut self, entries: &VecDeque<TrafficEntry>, first_seq: u64) {
+        while self.first < first_seq {
+            let Some(id) = self.ids.pop_front() else {
+                self.first = first_seq;
+                break;
+            };
+            self.release(id);
+            self.first += 1;
+        }
+        let end_seq = first_seq + entries.len() as u64;
+        let start = self.end();
+        for seq in start..end_seq.min(start + INDEX_CHUNK as u64) {
+            let id = match &entries[(seq - first_seq) as usize] {
+                TrafficEntry::Data { data, protocol, .. } => Some(self.intern(data, *protocol)),
+                TrafficEntry::Diagnostic { .. } | TrafficEntry::Bookmark { .. } => None,
+            };
+            self.ids.push_back(id);
+        }
+    }
+
+    /// Forget entries from sequence number `seq` on (they were replaced)
+    pub(super) fn truncate(&mut self, seq: u64) {
+        while self.end() > seq.max(self.first) {
+            if let Some(id) = self.ids.pop_back() {
+                self.release(id);
+            }
+        }
+    }
+
+    /// Forget everything, to decode every frame again
+    pub(super) fn reset(&mut self) {
+        *self = Self {
+            first: self.first,
+            ..Self::default()
+        };
+    }
+
+    fn intern(&mut self, data: &[u8], protocol: Option<Protocol>) -> usize {
+        let id = match self.by_key.get(&(data.to_vec(), protocol)) {
+            Some(&id) => id,
+            None => {
+                let frame = Some(IndexedFrame::new(data, protocol));
+                let id = match self.free.pop() {
+                    Some(id) => {
+                        self.frames[id] = frame;
+                        id
+                    }
+                    None => {
+                        self.frames.push(frame);
+                        self.frames.len() - 1
+                    }
+                };
+                self.by_key.insert((data.to_vec(), protocol), id);
+                id
+            }
+        };
+        if let Some(frame) = self.frames[id].as_mut() {
+            frame.uses += 1;
+        }
+        id
+    }
+
+    fn release(&mut self, id: Option<usize>) {
+        let Some(id) = id else {
+            return;
+        };
+        let Some(frame) = self.frames[id].as_mut() else {
+            return;
+        };
+        frame.uses -= 1;
+        if frame.uses == 0 {
+            if let Some(frame) = self.frames[id].take() {
+                self.by_key.remove(&(frame.data, frame.protocol));
+            }
+            self.free.push(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::traffic_monitor::models::{FrameLink, TrafficDirection, TrafficSource};
+
+    fn frame(data: &[u8]) -> TrafficEntry {
+        TrafficEntry::Data {
+            timestamp: SystemTime::UNIX_EPOCH,
+            direction: TrafficDirection::Incoming,
+            source: TrafficSource::CaptureFile {
+                file: String::new(),
+            },
+            data: data.to_vec(),
+            protocol: Some(Protocol::Kenwood),
+            link: FrameLink::default(),
+        }
+    }
+
+    #[test]
+    fn test_repeated_frames_share_an_entry() {
+        let mut entries: VecDeque<_> = ["FA;", "FA00014074000;", "FA;", "MD2;"]
+            .into_iter()
+            .map(|text| frame(text.as_bytes()))
+            .collect();
+        let mut index = FrameIndex::new();
+        index.update(&entries, 10);
+        assert_eq!(index.end(), 14);
+        assert_eq!(index.distinct(), 3);
+        assert!(std::ptr::eq(
+            index.frame(10).unwrap(),
+            index.frame(12).unwrap()
+        ));
+        assert_eq!(
+            index.frame(11).unwrap().class,
+            Some(CommandClass::Frequency)
+        );
+        assert_eq!(
+            index.frame(10).unwrap().class,
+            Some(CommandClass::Frequency)
+        );
+        assert_eq!(index.frame(13).unwrap().class, Some(CommandClass::Mode));
+        assert!(index.frame(13).unwrap().text.contains("md2;"));
+
+        // Frames go when the last entry holding them does
+        entries.pop_front();
+        entries.pop_front();
+        index.update(&entries, 12);
+        assert_eq!(index.distinct(), 2);
+        assert!(index.frame(11).is_none());
+        entries.pop_back();
+        index.truncate(13);
+        assert_eq!(index.distinct(), 1);
+        assert_eq!(index.end(), 13);
+    }
+
+    #[test]
+    fn test_command_class_names() {
+        for class in CommandClass::ALL {
+            assert_eq!(CommandClass::parse(class.name()), Some(class));
+        }
+        assert_eq!(CommandClass::parse(" PTT"), Some(CommandClass::Ptt));
+        assert_eq!(CommandClass::parse("frequency"), None);
+        // CI-V without a protocol hint, asked of the radio
+        assert_eq!(
+            CommandClass::of_frame(&[0xFE, 0xFE, 0x94, 0xE0, 0x1C, 0x00, 0xFD], None),
+            Some(CommandClass::Ptt)
+        );
+    }
+}
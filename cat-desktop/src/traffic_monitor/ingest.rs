@@ -2,12 +2,20 @@
 
 use std::time::SystemTime;
 
-use cat_mux::{MuxEvent, RadioChannelMeta, RadioHandle};
-use cat_protocol::Protocol;
+use cat_mux::civ_echo::echo_back_advice;
+use cat_mux::{
+    AnalyzerDirection, FrameSource, MuxEvent, RadioChannelMeta, RadioHandle, SyncStepStatus,
+    TrafficRecord, PTT_LATENCY_BUDGET,
+};
+use cat_protocol::display::format_hex;
+use cat_protocol::{Protocol, RadioSideCodec};
 
-use super::models::{DiagnosticSeverity, TrafficDirection, TrafficEntry, TrafficSource};
+use super::models::{DiagnosticSeverity, FrameLink, TrafficDirection, TrafficEntry, TrafficSource};
 use super::TrafficMonitor;
 
+/// Most recent entries from a radio that are re-parsed after a protocol change
+const REPARSE_MAX_ENTRIES: usize = 5_000;
+
 impl TrafficMonitor {
     /// Add an incoming traffic entry from a real radio
     #[allow(dead_code)] // Direct API, may be used for testing
@@ -28,7 +36,6 @@ impl TrafficMonitor {
             return;
         }
 
-        let decoded = self.get_cached_annotation(data, protocol);
         self.add_entry(TrafficEntry::Data {
             timestamp: SystemTime::now(),
             direction: TrafficDirection::Incoming,
@@ -37,7 +44,8 @@ impl TrafficMonitor {
                 port,
             },
             data: data.to_vec(),
-            decoded,
+            protocol,
+            link: FrameLink::default(),
         });
     }
 
@@ -59,13 +67,13 @@ impl TrafficMonitor {
             return;
         }
 
-        let decoded = self.get_cached_annotation(data, protocol);
         self.add_entry(TrafficEntry::Data {
             timestamp: SystemTime::now(),
             direction: TrafficDirection::Outgoing,
             source: TrafficSource::RealAmplifier { port },
             data: data.to_vec(),
-            decoded,
+            protocol,
+            link: FrameLink::default(),
         });
     }
 
@@ -82,13 +90,13 @@ impl TrafficMonitor {
             return;
         }
 
-        let decoded = self.get_cached_annotation(data, protocol);
         self.add_entry(TrafficEntry::Data {
             timestamp: SystemTime::now(),
             direction: TrafficDirection::Outgoing,
             source: TrafficSource::ToRealRadio { handle, port },
             data: data.to_vec(),
-            decoded,
+            protocol,
+            link: FrameLink::default(),
         });
     }
 
@@ -99,16 +107,80 @@ impl TrafficMonitor {
             return;
         }
 
-        let decoded = self.get_cached_annotation(data, protocol);
         self.add_entry(TrafficEntry::Data {
             timestamp: SystemTime::now(),
             direction: TrafficDirection::Incoming,
             source: TrafficSource::FromRealAmplifier { port },
             data: data.to_vec(),
-            decoded,
+            protocol,
+            link: FrameLink::default(),
         });
     }
 
+    /// Add frames decoded from a capture file
+    ///
+    /// While paused the frames are held (up to the history size) and added
+    /// on resume.
+    pub fn add_capture_frames(&mut self, file: &str, frames: Vec<Vec<u8>>, protocol: Protocol) {
+        let timestamp = SystemTime::now();
+        for data in frames {
+            let entry = TrafficEntry::Data {
+                timestamp,
+                direction: TrafficDirection::Incoming,
+                source: TrafficSource::CaptureFile {
+                    file: file.to_string(),
+                },
+                data,
+                protocol: Some(protocol),
+                link: FrameLink::default(),
+            };
+            if self.paused {
+                if self.held_captures.len() >= self.max_entries {
+                    self.held_captures.pop_front();
+                }
+                self.held_captures.push_back(entry);
+            } else {
+                self.add_entry(entry);
+            }
+        }
+    }
+
+    /// Add frames loaded from a disk traffic log
+    ///
+    /// A header entry names the log so the loaded frames stand apart from
+    /// live traffic.
+    pub fn add_log_records(&mut self, file: &str, records: Vec<TrafficRecord>) {
+        if self.paused {
+            return;
+        }
+
+        self.add_entry(TrafficEntry::Diagnostic {
+            timestamp: records
+                .first()
+                .map_or_else(SystemTime::now, |r| r.timestamp),
+            source: "Disk log".to_string(),
+            severity: DiagnosticSeverity::Info,
+            message: format!("{} frames from {}", records.len(), file),
+        });
+        for record in records {
+            self.add_entry(TrafficEntry::Data {
+                timestamp: record.timestamp,
+                direction: if record.outgoing {
+                    TrafficDirection::Outgoing
+                } else {
+                    TrafficDirection::Incoming
+                },
+                source: TrafficSource::Logged {
+                    channel: record.channel,
+                    outgoing: record.outgoing,
+                },
+                data: record.data,
+                protocol: record.protocol,
+                link: FrameLink::default(),
+            });
+        }
+    }
+
     /// Add a diagnostic entry (error or warning)
     pub fn add_diagnostic(
         &mut self,
@@ -128,14 +200,116 @@ impl TrafficMonitor {
         });
     }
 
+    /// Re-frame a radio's recent incoming traffic with a different protocol
+    ///
+    /// Incoming entries were split into frames by the radio's previous codec,
+    /// so under the wrong protocol they are cut in the wrong places. The bytes
+    /// of the last [`REPARSE_MAX_ENTRIES`] entries from the radio are run
+    /// through a codec for `protocol` in order; each new frame takes the place
+    /// and timestamp of the entry its last byte arrived in. Bytes the old codec
+    /// discarded are gone. Returns the number of frames produced.
+    pub fn reparse_radio(&mut self, handle: RadioHandle, protocol: Protocol) -> usize {
+        let from_radio = |entry: &TrafficEntry| {
+            matches!(
+                entry,
+                TrafficEntry::Data {
+                    source: TrafficSource::RealRadio { handle: h, .. },
+                    ..
+                } if *h == handle
+            )
+        };
+        let positions: Vec<usize> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| from_radio(entry))
+            .map(|(i, _)| i)
+            .collect();
+        let Some(&first) = positions.get(positions.len().saturating_sub(REPARSE_MAX_ENTRIES))
+        else {
+            return 0;
+        };
+
+        let mut codec = RadioSideCodec::new(protocol);
+        let mut frames = 0;
+        let tail: Vec<TrafficEntry> = self.entries.drain(first..).collect();
+        let replaced = self.first_seq + first as u64;
+        self.row_index.truncate(replaced);
+        self.frame_index.truncate(replaced);
+        self.search.truncate(replaced);
+        for entry in tail {
+            if !from_radio(&entry) {
+                self.entries.push_back(entry);
+                continue;
+            }
+            let TrafficEntry::Data {
+                timestamp,
+                direction,
+                source,
+                data,
+                ..
+            } = entry
+            else {
+                continue;
+            };
+            codec.push_bytes(&data);
+            while let Some((_, bytes)) = codec.next_response_with_bytes() {
+                frames += 1;
+                self.entries.push_back(TrafficEntry::Data {
+                    timestamp,
+                    direction,
+                    source: source.clone(),
+                    data: bytes,
+                    protocol: Some(protocol),
+                    link: FrameLink::default(),
+                });
+            }
+        }
+
+        self.trim_to_max_entries();
+        frames
+    }
+
+    /// Remember a radio's identified model, returning its port name
+    fn note_radio(
+        &mut self,
+        handle: RadioHandle,
+        radio_metas: &dyn Fn(RadioHandle) -> Option<RadioChannelMeta>,
+    ) -> String {
+        let Some(meta) = radio_metas(handle) else {
+            return String::new();
+        };
+        match meta.model_info {
+            Some(model) => {
+                self.radio_models.insert(handle, model);
+            }
+            None => {
+                self.radio_models.remove(&handle);
+            }
+        }
+        meta.port_name.unwrap_or_default()
+    }
+
     /// Add an entry
     pub(super) fn add_entry(&mut self, entry: TrafficEntry) {
         if self.entries.len() >= self.max_entries {
-            self.entries.pop_front();
+            self.pop_oldest();
         }
         self.entries.push_back(entry);
     }
 
+    /// Add a bookmark entry
+    pub fn add_bookmark(&mut self, source: String, note: String, timestamp: SystemTime) {
+        let id = self.next_bookmark_id;
+        self.next_bookmark_id += 1;
+        self.add_entry(TrafficEntry::Bookmark {
+            id,
+            timestamp,
+            source,
+            note,
+        });
+    }
+
     /// Process a MuxEvent and add appropriate traffic entries
     ///
     /// This is the unified event processing method that handles all traffic
@@ -146,6 +320,17 @@ impl TrafficMonitor {
         event: MuxEvent,
         radio_metas: &dyn Fn(RadioHandle) -> Option<RadioChannelMeta>,
     ) {
+        // Bookmarks are kept even while paused: pausing to look at something
+        // is exactly when one is likely
+        if let MuxEvent::Bookmark {
+            source,
+            note,
+            timestamp,
+        } = event
+        {
+            self.add_bookmark(source, note, timestamp);
+            return;
+        }
         if self.paused {
             return;
         }
@@ -153,21 +338,20 @@ impl TrafficMonitor {
         match event {
             MuxEvent::RadioDataIn {
                 handle,
+                id,
                 data,
                 protocol,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
-                let port = radio_metas(handle)
-                    .and_then(|m| m.port_name)
-                    .unwrap_or_default();
+                let port = self.note_radio(handle, radio_metas);
 
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Incoming,
                     source: TrafficSource::RealRadio { handle, port },
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::received(id),
                 });
             }
 
@@ -177,43 +361,41 @@ impl TrafficMonitor {
                 protocol,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
-                let port = radio_metas(handle)
-                    .and_then(|m| m.port_name)
-                    .unwrap_or_default();
+                let port = self.note_radio(handle, radio_metas);
 
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Outgoing,
                     source: TrafficSource::ToRealRadio { handle, port },
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::default(),
                 });
             }
 
             MuxEvent::AmpDataOut {
                 data,
+                cause,
                 protocol,
+                suppressed,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Outgoing,
-                    source: TrafficSource::RealAmplifier {
-                        port: String::new(),
-                    },
+                    source: amp_out_source(String::new(), suppressed),
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::caused_by(cause),
                 });
             }
 
             MuxEvent::AmpDataIn {
                 data,
+                id,
                 protocol,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Incoming,
@@ -221,7 +403,95 @@ impl TrafficMonitor {
                         port: String::new(),
                     },
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::received(id),
+                });
+            }
+
+            MuxEvent::ShadowAmpDataOut {
+                data,
+                cause,
+                protocol,
+                timestamp,
+            } => {
+                self.add_entry(TrafficEntry::Data {
+                    timestamp,
+                    direction: TrafficDirection::Outgoing,
+                    source: TrafficSource::ShadowAmplifier,
+                    data,
+                    protocol: Some(protocol),
+                    link: FrameLink::caused_by(cause),
+                });
+            }
+
+            MuxEvent::ShadowAmpDataIn {
+                data,
+                id,
+                protocol,
+                timestamp,
+            } => {
+                self.add_entry(TrafficEntry::Data {
+                    timestamp,
+                    direction: TrafficDirection::Incoming,
+                    source: TrafficSource::FromShadowAmplifier,
+                    data,
+                    protocol: Some(protocol),
+                    link: FrameLink::received(id),
+                });
+            }
+
+            MuxEvent::ShadowAmpCompared {
+                diff: Some(diff), ..
+            } => {
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: "Shadow amp".to_string(),
+                    severity: DiagnosticSeverity::Warning,
+                    message: format!("Mismatch {}", diff.summary()),
+                });
+            }
+
+            MuxEvent::AmpFailedOver { from, to, reason } => {
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: "Amp failover".to_string(),
+                    severity: DiagnosticSeverity::Error,
+                    message: format!(
+                        "{} failed ({}); output moved to {}",
+                        from.name(),
+                        reason,
+                        to.name()
+                    ),
+                });
+            }
+
+            MuxEvent::AmpFailoverAcknowledged => {
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: "Amp failover".to_string(),
+                    severity: DiagnosticSeverity::Info,
+                    message: "Acknowledged; primary amplifier back in service".to_string(),
+                });
+            }
+
+            MuxEvent::AnalyzerData {
+                direction,
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => {
+                let traffic_direction = match direction {
+                    AnalyzerDirection::LoggerToRadio => TrafficDirection::Outgoing,
+                    AnalyzerDirection::RadioToLogger => TrafficDirection::Incoming,
+                };
+                self.add_entry(TrafficEntry::Data {
+                    timestamp,
+                    direction: traffic_direction,
+                    source: TrafficSource::Analyzer { direction },
+                    data,
+                    protocol: Some(protocol),
+                    link: FrameLink::default(),
                 });
             }
 
@@ -234,15 +504,261 @@ impl TrafficMonitor {
                 });
             }
 
+            MuxEvent::RadioSyncProgress {
+                handle,
+                step,
+                total,
+                label,
+                status,
+            } => {
+                let name = radio_metas(handle)
+                    .map(|m| m.display_name)
+                    .unwrap_or_else(|| format!("Radio {}", handle.0));
+                let severity = match status {
+                    SyncStepStatus::Failed => DiagnosticSeverity::Warning,
+                    SyncStepStatus::Done | SyncStepStatus::Skipped => DiagnosticSeverity::Debug,
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity,
+                    message: format!("Initial sync {}/{}: {} {:?}", step, total, label, status),
+                });
+            }
+
+            MuxEvent::PttLatency {
+                handle,
+                active,
+                latency,
+            } => {
+                let name = radio_metas(handle)
+                    .map(|m| m.display_name)
+                    .unwrap_or_else(|| format!("Radio {}", handle.0));
+                let severity = if latency > PTT_LATENCY_BUDGET {
+                    DiagnosticSeverity::Warning
+                } else {
+                    DiagnosticSeverity::Debug
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity,
+                    message: format!(
+                        "PTT {} to amp in {:.1} ms",
+                        if active { "on" } else { "off" },
+                        latency.as_secs_f64() * 1000.0
+                    ),
+                });
+            }
+
+            MuxEvent::RadioCustomEvent { handle, event } => {
+                let name = radio_metas(handle)
+                    .map(|m| m.display_name)
+                    .unwrap_or_else(|| format!("Radio {}", handle.0));
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity: DiagnosticSeverity::Info,
+                    message: format!("{}: {}", event.name, format_hex(&event.data)),
+                });
+            }
+
+            MuxEvent::SpotMatched { handle, spot } => {
+                let name = radio_metas(handle)
+                    .map(|m| m.display_name)
+                    .unwrap_or_else(|| format!("Radio {}", handle.0));
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity: DiagnosticSeverity::Info,
+                    message: match spot {
+                        Some(spot) => format!("On DX spot: {}", spot.summary()),
+                        None => "Left DX spot".to_string(),
+                    },
+                });
+            }
+
+            MuxEvent::BandConflict {
+                handle,
+                active,
+                band,
+            } => {
+                let name = |h: RadioHandle| {
+                    radio_metas(h)
+                        .map(|m| m.display_name)
+                        .unwrap_or_else(|| format!("Radio {}", h.0))
+                };
+                let (severity, message) = match band {
+                    Some(band) => (
+                        DiagnosticSeverity::Warning,
+                        format!("On {}, the same band as {}", band, name(active)),
+                    ),
+                    None => (
+                        DiagnosticSeverity::Info,
+                        format!("Left {}'s band", name(active)),
+                    ),
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name(handle),
+                    severity,
+                    message,
+                });
+            }
+
+            MuxEvent::DutyCycleAlert {
+                handle,
+                duty_cycle,
+                over,
+            } => {
+                let (severity, message) = if over {
+                    (
+                        DiagnosticSeverity::Warning,
+                        format!(
+                            "Duty cycle {:.0}%, above the alert threshold",
+                            duty_cycle * 100.0
+                        ),
+                    )
+                } else {
+                    (
+                        DiagnosticSeverity::Info,
+                        format!("Duty cycle back to {:.0}%", duty_cycle * 100.0),
+                    )
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: radio_metas(handle)
+                        .map(|m| m.display_name)
+                        .unwrap_or_else(|| format!("Radio {}", handle.0)),
+                    severity,
+                    message,
+                });
+            }
+
+            MuxEvent::RadioEchoBack { handle, echo_back } => {
+                let name = radio_metas(handle)
+                    .map(|m| m.display_name)
+                    .unwrap_or_else(|| format!("Radio {}", handle.0));
+                let (severity, message) = if echo_back {
+                    (DiagnosticSeverity::Warning, echo_back_advice(&name))
+                } else {
+                    (
+                        DiagnosticSeverity::Info,
+                        "CI-V USB Echo Back is off".to_string(),
+                    )
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity,
+                    message,
+                });
+            }
+
+            MuxEvent::BandLockoutChanged { handle, holder } => {
+                let name = |h: RadioHandle| {
+                    radio_metas(h)
+                        .map(|m| m.display_name)
+                        .unwrap_or_else(|| format!("Radio {}", h.0))
+                };
+                let (severity, message) = match holder {
+                    Some(holder) => (
+                        DiagnosticSeverity::Warning,
+                        format!(
+                            "Amplifier not keyed: {} is already transmitting on this band",
+                            name(holder)
+                        ),
+                    ),
+                    None => (
+                        DiagnosticSeverity::Info,
+                        "Band interlock released".to_string(),
+                    ),
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name(handle),
+                    severity,
+                    message,
+                });
+            }
+
+            MuxEvent::PeerInterlockChanged { blocked_by } => {
+                let (severity, message) = match blocked_by {
+                    Some(peer) => (
+                        DiagnosticSeverity::Warning,
+                        format!(
+                            "Amplifier locked out of transmit, peer {} holds the band",
+                            peer.summary()
+                        ),
+                    ),
+                    None => (
+                        DiagnosticSeverity::Info,
+                        "Peer interlock released".to_string(),
+                    ),
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: "Peer sync".to_string(),
+                    severity,
+                    message,
+                });
+            }
+
+            MuxEvent::PartialFrameDiscarded {
+                source,
+                data,
+                protocol,
+                stalled,
+            } => {
+                let name = match source {
+                    FrameSource::Radio(handle) => radio_metas(handle)
+                        .map(|m| m.display_name)
+                        .unwrap_or_else(|| format!("Radio {}", handle.0)),
+                    FrameSource::Amplifier => "Amplifier".to_string(),
+                    FrameSource::ShadowAmplifier => "Shadow amp".to_string(),
+                };
+                self.add_entry(TrafficEntry::Diagnostic {
+                    timestamp: SystemTime::now(),
+                    source: name,
+                    severity: DiagnosticSeverity::Warning,
+                    message: format!(
+                        "Discarded partial {} frame after {} ms without data: {}",
+                        protocol.name(),
+                        stalled.as_millis(),
+                        format_hex(&data)
+                    ),
+                });
+            }
+
             // Non-traffic events are ignored by the traffic monitor
             MuxEvent::RadioConnected { .. }
             | MuxEvent::RadioDisconnected { .. }
+            | MuxEvent::RadioProtocolChanged { .. }
             | MuxEvent::RadioStateChanged { .. }
             | MuxEvent::ActiveRadioChanged { .. }
+            | MuxEvent::SwitchAudited { .. }
+            | MuxEvent::RadioStaleChanged { .. }
+            | MuxEvent::RadioMeter { .. }
+            | MuxEvent::RadioReassembly { .. }
+            | MuxEvent::Bookmark { .. }
+            | MuxEvent::ContactSnapshot { .. }
+            | MuxEvent::MuxRestarted { .. }
+            | MuxEvent::PeersChanged { .. }
             | MuxEvent::AmpConnected { .. }
             | MuxEvent::AmpDisconnected
+            | MuxEvent::AmpBacklogChanged { .. }
+            | MuxEvent::AmpWritePreempted { .. }
+            | MuxEvent::AmpQueryProfileUpdated { .. }
+            | MuxEvent::AmpStateChanged { .. }
+            | MuxEvent::ShadowAmpConnected { .. }
+            | MuxEvent::ShadowAmpDisconnected
+            | MuxEvent::BackupAmpConnected { .. }
+            | MuxEvent::BackupAmpDisconnected
+            | MuxEvent::ShadowAmpCompared { diff: None, .. }
             | MuxEvent::SwitchingModeChanged { .. }
-            | MuxEvent::SwitchingBlocked { .. } => {}
+            | MuxEvent::SwitchingBlocked { .. }
+            | MuxEvent::SwitchPendingConfirmation { .. }
+            | MuxEvent::SwitchConfirmationClosed { .. } => {}
         }
     }
 
@@ -263,27 +779,27 @@ impl TrafficMonitor {
         match event {
             MuxEvent::AmpDataOut {
                 data,
+                cause,
                 protocol,
+                suppressed,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Outgoing,
-                    source: TrafficSource::RealAmplifier {
-                        port: amp_port.to_string(),
-                    },
+                    source: amp_out_source(amp_port.to_string(), suppressed),
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::caused_by(cause),
                 });
             }
 
             MuxEvent::AmpDataIn {
                 data,
+                id,
                 protocol,
                 timestamp,
             } => {
-                let decoded = self.get_cached_annotation(&data, Some(protocol));
                 self.add_entry(TrafficEntry::Data {
                     timestamp,
                     direction: TrafficDirection::Incoming,
@@ -291,7 +807,8 @@ impl TrafficMonitor {
                         port: amp_port.to_string(),
                     },
                     data,
-                    decoded,
+                    protocol: Some(protocol),
+                    link: FrameLink::received(id),
                 });
             }
 
@@ -300,3 +817,153 @@ impl TrafficMonitor {
         }
     }
 }
+
+/// Where a frame sent to the amplifier is shown, dry run or not
+fn amp_out_source(port: String, suppressed: bool) -> TrafficSource {
+    if suppressed {
+        TrafficSource::SuppressedAmplifier { port }
+    } else {
+        TrafficSource::RealAmplifier { port }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_mux::FrameId;
+
+    fn frames(monitor: &TrafficMonitor) -> Vec<(Vec<u8>, Option<Protocol>)> {
+        monitor
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                TrafficEntry::Data { data, protocol, .. } => Some((data.clone(), *protocol)),
+                TrafficEntry::Diagnostic { .. } | TrafficEntry::Bookmark { .. } => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_reparse_radio_reframes_traffic() {
+        let radio = RadioHandle(1);
+        let mut monitor = TrafficMonitor::new(100, None);
+        // A CI-V report split in the wrong place by the Kenwood codec, with
+        // another radio's frame in between
+        let civ = [
+            0xFE, 0xFE, 0xE0, 0x94, 0x00, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ];
+        monitor.add_incoming(radio, &civ[..4], Some(Protocol::Kenwood));
+        monitor.add_incoming(RadioHandle(2), b"FA00007074000;", Some(Protocol::Kenwood));
+        monitor.add_incoming(radio, &civ[4..], Some(Protocol::Kenwood));
+
+        assert_eq!(monitor.reparse_radio(radio, Protocol::IcomCIV), 1);
+        assert_eq!(
+            frames(&monitor),
+            vec![
+                (b"FA00007074000;".to_vec(), Some(Protocol::Kenwood)),
+                (civ.to_vec(), Some(Protocol::IcomCIV)),
+            ]
+        );
+        assert_eq!(monitor.reparse_radio(RadioHandle(3), Protocol::Yaesu), 0);
+    }
+
+    #[test]
+    fn test_capture_frames_are_held_while_paused() {
+        let mut monitor = TrafficMonitor::new(100, None);
+        monitor.set_paused(true);
+        monitor.add_capture_frames("a.cap", vec![b"FA00014250000;".to_vec()], Protocol::Kenwood);
+        monitor.add_incoming(RadioHandle(1), b"MD2;", Some(Protocol::Kenwood));
+        assert!(frames(&monitor).is_empty());
+
+        // Live traffic seen while paused is gone; the capture frame is not
+        monitor.set_paused(false);
+        assert_eq!(
+            frames(&monitor),
+            vec![(b"FA00014250000;".to_vec(), Some(Protocol::Kenwood))]
+        );
+    }
+
+    #[test]
+    fn test_amp_write_links_to_its_cause() {
+        let mut monitor = TrafficMonitor::new(100, None);
+        let no_meta = |_: RadioHandle| None;
+        monitor.process_event(
+            MuxEvent::RadioDataIn {
+                handle: RadioHandle(1),
+                id: FrameId(7),
+                data: b"FA00014074000;".to_vec(),
+                protocol: Protocol::Kenwood,
+                timestamp: SystemTime::UNIX_EPOCH,
+            },
+            &no_meta,
+        );
+        monitor.process_event(
+            MuxEvent::AmpDataOut {
+                data: b"FA00014074000;".to_vec(),
+                cause: Some(FrameId(7)),
+                protocol: Protocol::Kenwood,
+                suppressed: false,
+                timestamp: SystemTime::UNIX_EPOCH,
+            },
+            &no_meta,
+        );
+
+        let links: Vec<_> = monitor
+            .entries
+            .iter()
+            .filter_map(|entry| match entry {
+                TrafficEntry::Data { link, .. } => Some(*link),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                FrameLink::received(FrameId(7)),
+                FrameLink::caused_by(Some(FrameId(7)))
+            ]
+        );
+
+        let export = monitor.format_filtered_log();
+        let lines: Vec<_> = export.lines().skip(3).collect();
+        assert!(lines[0].ends_with(" id=7"), "{}", lines[0]);
+        assert!(lines[1].ends_with(" cause=7"), "{}", lines[1]);
+    }
+
+    #[test]
+    fn test_menu_items_decoded_at_model_width() {
+        let mut monitor = TrafficMonitor::new(100, None);
+        let mut meta = RadioChannelMeta::new_real(
+            "FTDX10".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::YaesuAscii,
+            None,
+        );
+        meta.set_model(cat_protocol::RadioDatabase::by_yaesu_ascii_id("0690").unwrap());
+        let metas = |_: RadioHandle| Some(meta.clone());
+        monitor.process_event(
+            MuxEvent::RadioDataOut {
+                handle: RadioHandle(1),
+                data: b"EX030101;".to_vec(),
+                protocol: Protocol::YaesuAscii,
+                timestamp: SystemTime::UNIX_EPOCH,
+            },
+            &metas,
+        );
+        monitor.process_event(
+            MuxEvent::RadioDataIn {
+                handle: RadioHandle(1),
+                id: FrameId(1),
+                data: b"EX0301010;".to_vec(),
+                protocol: Protocol::YaesuAscii,
+                timestamp: SystemTime::UNIX_EPOCH,
+            },
+            &metas,
+        );
+
+        let export = monitor.format_filtered_log();
+        let lines: Vec<_> = export.lines().skip(3).collect();
+        assert!(lines[0].contains("Read Menu 030101"), "{}", lines[0]);
+        assert!(lines[1].contains("Menu 030101 = 0"), "{}", lines[1]);
+    }
+}
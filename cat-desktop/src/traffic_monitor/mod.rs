@@ -4,27 +4,43 @@
 //! traffic between radios and amplifiers, with support for filtering,
 //! export, and diagnostic messages.
 
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
+
+use cat_mux::RadioHandle;
+use cat_protocol::RadioModel;
 
 use tracing::Level;
 
 mod cache;
 mod export;
+mod frame_index;
 mod ingest;
 mod models;
+mod navigate;
+mod rows;
 mod ui;
 
 // Re-export public types (used by TrafficEntry fields and for pattern matching)
 #[allow(unused_imports)]
 pub use models::{DiagnosticSeverity, ExportAction, TrafficDirection, TrafficEntry, TrafficSource};
 
-use cache::{AnnotationCache, CacheOrder, ANNOTATION_CACHE_MAX_SIZE};
+use cache::AnnotationCache;
+pub(crate) use cache::ANNOTATION_CACHE_MAX_SIZE;
+use frame_index::FrameIndex;
 use models::TrafficDirection as Direction;
+use navigate::TrafficSearch;
+use rows::RowIndex;
 
 /// Traffic monitor state
 pub struct TrafficMonitor {
     /// Traffic entries
     entries: VecDeque<TrafficEntry>,
+    /// Sequence number of the oldest entry (counts every entry ever dropped)
+    first_seq: u64,
+    /// Which entry line each visual row shows
+    row_index: RowIndex,
+    /// Vertical scroll position after the last draw
+    scroll_offset: f32,
     /// Maximum entries to keep
     max_entries: usize,
     /// Auto-scroll to bottom
@@ -33,15 +49,40 @@ pub struct TrafficMonitor {
     filter_direction: Option<Direction>,
     /// Pause monitoring
     paused: bool,
+    /// Capture file frames that arrived while paused, added on resume (a
+    /// capture is only read once, so they cannot be dropped like live traffic)
+    held_captures: VecDeque<TrafficEntry>,
     /// Minimum diagnostic level to show (None = off, Some(Level::DEBUG) = all)
     /// Events at this level and above are shown (filtering happens at tracing layer)
     diagnostic_level: Option<Level>,
-    /// Cache for AnnotatedFrame results to avoid redundant parsing
+    /// Decoded frames for recently displayed rows (entries store raw bytes only)
     annotation_cache: AnnotationCache,
-    /// Keys in insertion order for LRU-style eviction
-    cache_order: CacheOrder,
+    /// Identified model of each radio seen, for decoding model-specific frames
+    radio_models: HashMap<RadioHandle, RadioModel>,
+    /// Id for the next bookmark entry
+    next_bookmark_id: u64,
+    /// Bookmark to scroll to on the next draw
+    jump_to_bookmark: Option<u64>,
+    /// Received frame highlighted with the writes it caused
+    highlight_frame: Option<cat_mux::FrameId>,
+    /// Received frame to scroll to on the next draw
+    jump_to_frame: Option<cat_mux::FrameId>,
+    /// Entry (by sequence number) to scroll to on the next draw
+    jump_to_entry: Option<u64>,
+    /// Distinct frames in the history, decoded for search
+    frame_index: FrameIndex,
+    /// Search through the whole history
+    search: TrafficSearch,
+    /// Time typed into the go-to box
+    goto_time: String,
+    /// Time of an entry whose timestamp was clicked, for the host to show
+    /// the mux state at
+    inspect_at: Option<std::time::SystemTime>,
 }
 
+/// Upper bound for the configurable traffic history size
+pub const MAX_HISTORY_ENTRIES: usize = 1_000_000;
+
 impl TrafficMonitor {
     /// Create a new traffic monitor
     ///
@@ -53,14 +94,62 @@ impl TrafficMonitor {
     /// - `Some(Level::DEBUG)` = all diagnostics
     pub fn new(max_entries: usize, diagnostic_level: Option<Level>) -> Self {
         Self {
-            entries: VecDeque::with_capacity(max_entries),
-            max_entries,
+            entries: VecDeque::new(),
+            first_seq: 0,
+            row_index: RowIndex::new(),
+            scroll_offset: 0.0,
+            max_entries: max_entries.min(MAX_HISTORY_ENTRIES),
             auto_scroll: true,
             filter_direction: None,
             paused: false,
+            held_captures: VecDeque::new(),
             diagnostic_level,
-            annotation_cache: AnnotationCache::with_capacity(ANNOTATION_CACHE_MAX_SIZE),
-            cache_order: CacheOrder::with_capacity(ANNOTATION_CACHE_MAX_SIZE),
+            annotation_cache: AnnotationCache::new(),
+            radio_models: HashMap::new(),
+            next_bookmark_id: 0,
+            jump_to_bookmark: None,
+            highlight_frame: None,
+            jump_to_frame: None,
+            jump_to_entry: None,
+            frame_index: FrameIndex::new(),
+            search: TrafficSearch::new(),
+            goto_time: String::new(),
+            inspect_at: None,
+        }
+    }
+
+    /// Change the history size, dropping the oldest entries if it shrank
+    pub fn set_max_entries(&mut self, max_entries: usize) {
+        self.max_entries = max_entries.min(MAX_HISTORY_ENTRIES);
+        self.trim_to_max_entries();
+    }
+
+    /// Drop the oldest entry
+    fn pop_oldest(&mut self) -> Option<TrafficEntry> {
+        let entry = self.entries.pop_front()?;
+        self.first_seq += 1;
+        Some(entry)
+    }
+
+    /// Drop the oldest entries beyond the history size
+    fn trim_to_max_entries(&mut self) {
+        while self.entries.len() > self.max_entries {
+            self.pop_oldest();
+        }
+    }
+
+    /// Take the time of the entry the operator asked to inspect, if any
+    pub fn take_inspect_request(&mut self) -> Option<std::time::SystemTime> {
+        self.inspect_at.take()
+    }
+
+    /// Pause or resume monitoring; resuming adds the held capture frames
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+        if !paused {
+            while let Some(entry) = self.held_captures.pop_front() {
+                self.add_entry(entry);
+            }
         }
     }
 
@@ -69,10 +158,50 @@ impl TrafficMonitor {
         self.diagnostic_level
     }
 
-    /// Clear all entries and the annotation cache
+    /// Estimated memory held: (entries, bytes) for the history and (decodes,
+    /// bytes) for the decode cache
+    pub fn memory_usage(&self) -> ((usize, usize), (usize, usize)) {
+        let history = self.entries.iter().map(TrafficEntry::estimated_bytes).sum();
+        let decodes = self.annotation_cache.len();
+        (
+            (self.entries.len(), history),
+            (decodes, decodes * cat_mux::memory::ANNOTATION_BYTES),
+        )
+    }
+
+    /// Drop the oldest entries until the history is estimated at no more
+    /// than `max_bytes`; returns how many were dropped
+    pub fn trim_to_bytes(&mut self, max_bytes: usize) -> usize {
+        let mut total: usize = self.entries.iter().map(TrafficEntry::estimated_bytes).sum();
+        let mut dropped = 0;
+        while total > max_bytes {
+            let Some(entry) = self.pop_oldest() else {
+                break;
+            };
+            total -= entry.estimated_bytes();
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// Keep at most `capacity` decoded frames
+    pub fn set_decode_cache_capacity(&mut self, capacity: usize) {
+        self.annotation_cache.set_capacity(capacity);
+    }
+
+    /// Decode frames again the next time they are drawn (after the frequency
+    /// format changes)
+    pub fn redecode(&mut self) {
+        self.annotation_cache.clear();
+        self.frame_index.reset();
+        self.search.rescan();
+    }
+
+    /// Clear all entries, held capture frames and the annotation cache
     pub fn clear(&mut self) {
+        self.first_seq += self.entries.len() as u64;
         self.entries.clear();
+        self.held_captures.clear();
         self.annotation_cache.clear();
-        self.cache_order.clear();
     }
 }
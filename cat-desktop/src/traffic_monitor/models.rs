@@ -3,8 +3,10 @@
 use std::path::PathBuf;
 use std::time::SystemTime;
 
-use cat_mux::RadioHandle;
-use cat_protocol::display::{AnnotatedFrame, SegmentType};
+use cat_mux::memory::TRAFFIC_ENTRY_BYTES;
+use cat_mux::{AnalyzerDirection, FrameId, RadioHandle};
+use cat_protocol::display::SegmentType;
+use cat_protocol::Protocol;
 use egui::Color32;
 
 /// Map SegmentType to UI color
@@ -30,8 +32,42 @@ pub enum TrafficSource {
     ToRealRadio { handle: RadioHandle, port: String },
     /// Amplifier on a serial port (outgoing to amp)
     RealAmplifier { port: String },
+    /// Frame the amplifier would have been sent, held back by dry run
+    SuppressedAmplifier { port: String },
     /// Amplifier on a serial port (incoming from amp)
     FromRealAmplifier { port: String },
+    /// Frame relayed by the analyzer passthrough
+    Analyzer { direction: AnalyzerDirection },
+    /// Shadow amplifier in an A/B comparison (outgoing to amp)
+    ShadowAmplifier,
+    /// Shadow amplifier in an A/B comparison (incoming from amp)
+    FromShadowAmplifier,
+    /// Frame read from a capture file in the watched folder
+    CaptureFile { file: String },
+    /// Frame loaded from a disk traffic log
+    Logged { channel: String, outgoing: bool },
+}
+
+/// Where a frame sits in the mux's cause and effect
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FrameLink {
+    /// Received frame's id (what writes it caused refer to)
+    pub id: Option<FrameId>,
+    /// Received frame a write was sent because of
+    pub cause: Option<FrameId>,
+}
+
+impl FrameLink {
+    pub fn received(id: FrameId) -> Self {
+        Self {
+            id: Some(id),
+            cause: None,
+        }
+    }
+
+    pub fn caused_by(cause: Option<FrameId>) -> Self {
+        Self { id: None, cause }
+    }
 }
 
 /// Severity level for diagnostic entries
@@ -60,8 +96,10 @@ pub enum TrafficEntry {
         source: TrafficSource,
         /// Raw data
         data: Vec<u8>,
-        /// Decoded representation (from cache or computed on add)
-        decoded: Option<AnnotatedFrame>,
+        /// Protocol hint for decoding (annotation happens lazily when displayed)
+        protocol: Option<Protocol>,
+        /// Links to the frame that caused it, or that it caused (live traffic only)
+        link: FrameLink,
     },
     /// Diagnostic entry (error or warning)
     Diagnostic {
@@ -74,14 +112,72 @@ pub enum TrafficEntry {
         /// Message
         message: String,
     },
+    /// Operator bookmark ("that just happened")
+    Bookmark {
+        /// Sequence number, for jumping back to it
+        id: u64,
+        /// Timestamp
+        timestamp: SystemTime,
+        /// What triggered it
+        source: String,
+        /// What the active radio was doing
+        note: String,
+    },
+}
+
+impl TrafficSource {
+    /// Radio the frame was exchanged with, for live radio traffic
+    pub fn radio(&self) -> Option<RadioHandle> {
+        match self {
+            TrafficSource::RealRadio { handle, .. } | TrafficSource::ToRealRadio { handle, .. } => {
+                Some(*handle)
+            }
+            _ => None,
+        }
+    }
 }
 
 impl TrafficEntry {
-    /// Get the direction (None for diagnostics)
+    /// Rough memory held by the entry (see [`cat_mux::memory`])
+    pub fn estimated_bytes(&self) -> usize {
+        let owned = match self {
+            TrafficEntry::Data { source, data, .. } => {
+                let port = match source {
+                    TrafficSource::RealRadio { port, .. }
+                    | TrafficSource::ToRealRadio { port, .. }
+                    | TrafficSource::RealAmplifier { port }
+                    | TrafficSource::SuppressedAmplifier { port }
+                    | TrafficSource::FromRealAmplifier { port } => port.len(),
+                    TrafficSource::CaptureFile { file } => file.len(),
+                    TrafficSource::Logged { channel, .. } => channel.len(),
+                    TrafficSource::Analyzer { .. }
+                    | TrafficSource::ShadowAmplifier
+                    | TrafficSource::FromShadowAmplifier => 0,
+                };
+                port + data.capacity()
+            }
+            TrafficEntry::Diagnostic {
+                source, message, ..
+            } => source.capacity() + message.capacity(),
+            TrafficEntry::Bookmark { source, note, .. } => source.capacity() + note.capacity(),
+        };
+        TRAFFIC_ENTRY_BYTES + owned
+    }
+
+    /// When the entry was recorded
+    pub fn timestamp(&self) -> SystemTime {
+        match self {
+            TrafficEntry::Data { timestamp, .. }
+            | TrafficEntry::Diagnostic { timestamp, .. }
+            | TrafficEntry::Bookmark { timestamp, .. } => *timestamp,
+        }
+    }
+
+    /// Get the direction (None for diagnostics and bookmarks)
     pub fn direction(&self) -> Option<TrafficDirection> {
         match self {
             TrafficEntry::Data { direction, .. } => Some(*direction),
-            TrafficEntry::Diagnostic { .. } => None,
+            TrafficEntry::Diagnostic { .. } | TrafficEntry::Bookmark { .. } => None,
         }
     }
 }
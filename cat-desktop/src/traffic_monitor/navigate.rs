@@ -0,0 +1,698 @@
+//! Finding entries in the history: search and jump to time
+//!
+//! Search looks at every entry, not just the rows that have been drawn. A
+//! query is one of:
+//!
+//! - text, matched against what an export writes for each entry: its time,
+//!   source, hex bytes and decoded summary (and the frame itself when it is
+//!   text), and against diagnostics and bookmark notes
+//! - `hex:FE FE 94`, a byte sequence anywhere in a frame
+//! - `cmd:freq`, frames of one [`CommandClass`], whichever way they travel
+//!
+//! Frames are matched through the [`FrameIndex`], which decodes each distinct
+//! frame once, so a search tests each entry's indexed frame rather than
+//! decoding it again. The index is only kept while a search is active. A long history is scanned [`SEARCH_CHUNK`] entries per
+//! frame so the window stays responsive, and entries that arrive afterwards
+//! are checked as they come in. In follow mode the view jumps to each new
+//! match as it arrives.
+
+use std::collections::VecDeque;
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
+use cat_protocol::display::{format_hex, AnnotatedFrame, SegmentType};
+
+use super::frame_index::{CommandClass, FrameIndex, IndexedFrame};
+use super::models::TrafficEntry;
+use super::TrafficMonitor;
+
+/// Entries searched per frame
+pub(super) const SEARCH_CHUNK: usize = 20_000;
+
+const SECS_PER_DAY: u64 = 86_400;
+
+/// What a search looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum SearchQuery {
+    /// Lower-cased text
+    Text(String),
+    /// A byte sequence
+    Hex(Vec<u8>),
+    /// Frames of one class
+    Class(CommandClass),
+}
+
+impl SearchQuery {
+    /// Parse the search box's text; `Ok(None)` if it's empty
+    pub(super) fn parse(text: &str) -> Result<Option<Self>, String> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Ok(None);
+        }
+        if let Some(hex) = strip_prefix_ignore_case(text, "hex:") {
+            let digits: Vec<u8> = hex.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+            if digits.is_empty() || !digits.len().is_multiple_of(2) {
+                return Err("hex: takes whole bytes, e.g. hex:FE FE 94".to_string());
+            }
+            return digits
+                .chunks(2)
+                .map(|pair| {
+                    std::str::from_utf8(pair)
+                        .ok()
+                        .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                })
+                .collect::<Option<Vec<u8>>>()
+                .map(|bytes| Some(Self::Hex(bytes)))
+                .ok_or_else(|| format!("'{}' isn't hex", hex.trim()));
+        }
+        if let Some(name) = strip_prefix_ignore_case(text, "cmd:") {
+            return CommandClass::parse(name)
+                .map(|class| Some(Self::Class(class)))
+                .ok_or_else(|| {
+                    let names: Vec<_> = CommandClass::ALL.iter().map(|c| c.name()).collect();
+                    format!("cmd: takes one of {}", names.join(", "))
+                });
+        }
+        Ok(Some(Self::Text(text.to_lowercase())))
+    }
+
+    /// Whether an entry matches, given the frame a data entry holds
+    fn matches(&self, entry: &TrafficEntry, frame: Option<&IndexedFrame>) -> bool {
+        match (entry, self) {
+            (
+                TrafficEntry::Data {
+                    timestamp,
+                    source,
+                    data,
+                    ..
+                },
+                Self::Text(needle),
+            ) => {
+                frame.is_some_and(|frame| self.matches_frame(frame))
+                    || TrafficMonitor::format_source(source)
+                        .to_lowercase()
+                        .contains(needle.as_str())
+                    || TrafficMonitor::format_timestamp(timestamp).contains(needle.as_str())
+                    || format_hex(data).to_lowercase().contains(needle.as_str())
+            }
+            (TrafficEntry::Data { .. }, _) => frame.is_some_and(|frame| self.matches_frame(frame)),
+            (
+                TrafficEntry::Diagnostic {
+                    timestamp,
+                    source,
+                    message,
+                    ..
+                },
+                Self::Text(needle),
+            )
+            | (
+                TrafficEntry::Bookmark {
+                    timestamp,
+                    source,
+                    note: message,
+                    ..
+                },
+                Self::Text(needle),
+            ) => {
+                message.to_lowercase().contains(needle.as_str())
+                    || source.to_lowercase().contains(needle.as_str())
+                    || TrafficMonitor::format_timestamp(timestamp).contains(needle.as_str())
+            }
+            (TrafficEntry::Diagnostic { .. } | TrafficEntry::Bookmark { .. }, _) => false,
+        }
+    }
+
+    fn matches_frame(&self, frame: &IndexedFrame) -> bool {
+        match self {
+            Self::Text(needle) => frame.text.contains(needle.as_str()),
+            Self::Hex(bytes) => find_bytes(&frame.data, bytes).is_some(),
+            Self::Class(class) => frame.class == Some(*class),
+        }
+    }
+
+    /// What to mark in a matching frame's row
+    pub(super) fn highlight(
+        &self,
+        data: &[u8],
+        decoded: Option<&AnnotatedFrame>,
+    ) -> SearchHighlight {
+        let summary = decoded.map_or(&[][..], |decoded| &decoded.summary[..]);
+        let mut highlight = SearchHighlight::default();
+        match self {
+            Self::Text(needle) => {
+                // Parts the first occurrence in the summary runs through
+                let mut text = String::new();
+                let mut spans = Vec::with_capacity(summary.len());
+                for part in summary {
+                    let start = text.len();
+                    text.push_str(&part.text.to_lowercase());
+                    spans.push(start..text.len());
+                }
+                if let Some(start) = text.find(needle.as_str()) {
+                    let found = start..start + needle.len();
+                    highlight.parts = spans
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, span)| overlaps(span, &found))
+                        .map(|(i, _)| i)
+                        .collect();
+                }
+                // The text in the frame itself, or else the bytes behind the
+                // matching parts
+                highlight.bytes = find_bytes(&data.to_ascii_lowercase(), needle.as_bytes())
+                    .or_else(|| {
+                        highlight
+                            .parts
+                            .iter()
+                            .filter_map(|&i| summary[i].range.clone())
+                            .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end))
+                    });
+            }
+            Self::Hex(bytes) => {
+                highlight.bytes = find_bytes(data, bytes);
+                if let Some(found) = &highlight.bytes {
+                    highlight.parts = summary
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, part)| part.range.as_ref().is_some_and(|r| overlaps(r, found)))
+                        .map(|(i, _)| i)
+                        .collect();
+                }
+            }
+            Self::Class(_) => {
+                highlight.parts = summary
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, part)| part.part_type == SegmentType::Command)
+                    .map(|(i, _)| i)
+                    .collect();
+                highlight.bytes = decoded
+                    .into_iter()
+                    .flat_map(|decoded| &decoded.segments)
+                    .filter(|segment| segment.segment_type == SegmentType::Command)
+                    .map(|segment| segment.range.clone())
+                    .reduce(|a, b| a.start.min(b.start)..a.end.max(b.end));
+            }
+        }
+        highlight
+    }
+}
+
+/// What to mark in a matching frame's row
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(super) struct SearchHighlight {
+    /// Indices of the summary parts that match
+    pub parts: Vec<usize>,
+    /// Bytes of the frame that match
+    pub bytes: Option<Range<usize>>,
+}
+
+impl SearchHighlight {
+    /// Whether any of the bytes in `range` match
+    pub(super) fn covers(&self, range: &Range<usize>) -> bool {
+        self.bytes
+            .as_ref()
+            .is_some_and(|bytes| overlaps(bytes, range))
+    }
+}
+
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end
+}
+
+/// Where `needle` first occurs in `data`
+fn find_bytes(data: &[u8], needle: &[u8]) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    data.windows(needle.len())
+        .position(|window| window == needle)
+        .map(|start| start..start + needle.len())
+}
+
+fn strip_prefix_ignore_case<'a>(text: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = text.get(..prefix.len())?;
+    head.eq_ignore_ascii_case(prefix)
+        .then(|| &text[prefix.len()..])
+}
+
+/// A search through the history
+#[derive(Debug, Default)]
+pub(super) struct TrafficSearch {
+    /// Text in the search box
+    pub query: String,
+    /// Jump to each new match as it arrives
+    pub follow: bool,
+    /// What the matches are for (None = no search)
+    search: Option<SearchQuery>,
+    /// Why the query couldn't be searched for
+    error: Option<String>,
+    /// Sequence numbers of matching entries, oldest first
+    matches: VecDeque<u64>,
+    /// Entries before this sequence number have been searched
+    searched_to: u64,
+    /// Match last jumped to
+    current: Option<u64>,
+    /// Direction of a jump waiting for the scan to find a match
+    pending: Option<bool>,
+    /// Newest match follow mode has seen
+    followed: Option<u64>,
+}
+
+impl TrafficSearch {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a search is running or has results
+    pub(super) fn is_active(&self) -> bool {
+        self.search.is_some()
+    }
+
+    /// What the search is for, while one is active
+    pub(super) fn search(&self) -> Option<&SearchQuery> {
+        self.search.as_ref()
+    }
+
+    /// Why the query in the box isn't being searched for
+    pub(super) fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    /// Whether the whole history has been searched
+    pub(super) fn is_complete(&self, end_seq: u64) -> bool {
+        self.searched_to >= end_seq
+    }
+
+    /// Number of matches found so far
+    pub(super) fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    /// Match last jumped to
+    pub(super) fn current(&self) -> Option<u64> {
+        self.current
+    }
+
+    /// Whether the entry with sequence number `seq` matches
+    pub(super) fn is_match(&self, seq: u64) -> bool {
+        self.matches.binary_search(&seq).is_ok()
+    }
+
+    /// 1-based position of the current match
+    pub(super) fn position(&self) -> Option<usize> {
+        let current = self.current?;
+        self.matches.binary_search(&current).ok().map(|i| i + 1)
+    }
+
+    /// Go to the next (or previous) match, searching for the query again
+    /// first if it changed
+    pub(super) fn find(&mut self, forward: bool) {
+        match SearchQuery::parse(&self.query) {
+            Ok(search) => {
+                self.error = None;
+                if search != self.search {
+                    self.search = search;
+                    self.rescan();
+                }
+            }
+            Err(error) => {
+                self.error = Some(error);
+                self.search = None;
+                self.rescan();
+            }
+        }
+        self.pending = self.is_active().then_some(forward);
+    }
+
+    /// Stop searching
+    pub(super) fn clear(&mut self) {
+        self.query.clear();
+        self.search = None;
+        self.error = None;
+        self.rescan();
+    }
+
+    /// Search the whole history again (frames decode differently now)
+    pub(super) fn rescan(&mut self) {
+        self.matches.clear();
+        self.searched_to = 0;
+        self.current = None;
+        self.pending = None;
+        self.followed = None;
+    }
+
+    /// Search up to [`SEARCH_CHUNK`] more entries of `entries` (the oldest
+    /// has sequence number `first_seq`), as far as `frames` has indexed them
+    pub(super) fn update(
+        &mut self,
+        entries: &VecDeque<TrafficEntry>,
+        first_seq: u64,
+        frames: &FrameIndex,
+    ) {
+        while self.matches.front().is_some_and(|&seq| seq < first_seq) {
+            self.matches.pop_front();
+        }
+        let Some(search) = &self.search else {
+            self.searched_to = first_seq + entries.len() as u64;
+            return;
+        };
+        let start = self.searched_to.max(first_seq);
+        let end = (first_seq + entries.len() as u64)
+            .min(frames.end())
+            .min(start + SEARCH_CHUNK as u64)
+            .max(start);
+        for seq in start..end {
+            if search.matches(&entries[(seq - first_seq) as usize], frames.frame(seq)) {
+                self.matches.push_back(seq);
+            }
+        }
+        self.searched_to = end;
+    }
+
+    /// Forget entries from sequence number `seq` on (they were replaced)
+    pub(super) fn truncate(&mut self, seq: u64) {
+        while self.matches.back().is_some_and(|&m| m >= seq) {
+            self.matches.pop_back();
+        }
+        self.searched_to = self.searched_to.min(seq);
+        self.followed = self.followed.filter(|&m| m < seq);
+    }
+
+    /// The match a pending jump lands on, once it's known
+    ///
+    /// `shown` says whether an entry passes the view filters; matches that
+    /// don't are skipped. Wraps around at either end.
+    pub(super) fn take_jump(&mut self, end_seq: u64, shown: impl Fn(u64) -> bool) -> Option<u64> {
+        let forward = self.pending?;
+        let ahead = |m: u64| {
+            self.current
+                .is_none_or(|c| if forward { m > c } else { m < c })
+        };
+        let found = if forward {
+            let matches = self.matches.iter().copied();
+            matches
+                .clone()
+                .filter(|&m| ahead(m))
+                .chain(matches)
+                .find(|&m| shown(m))
+        } else {
+            let matches = self.matches.iter().rev().copied();
+            matches
+                .clone()
+                .filter(|&m| ahead(m))
+                .chain(matches)
+                .find(|&m| shown(m))
+        };
+        // Matches are found oldest first, so only the next one forward can be
+        // known before the scan is done
+        let nearest = forward && found.is_some_and(ahead);
+        if !nearest && !self.is_complete(end_seq) {
+            return None;
+        }
+        self.pending = None;
+        if found.is_some() {
+            self.current = found;
+        }
+        found
+    }
+
+    /// In follow mode, the newest match to jump to if one has arrived since
+    /// the last call
+    ///
+    /// Waits for the scan to reach the end of the history, so the view lands
+    /// on the live edge rather than on each older match as it's found.
+    pub(super) fn take_follow(&mut self, end_seq: u64, shown: impl Fn(u64) -> bool) -> Option<u64> {
+        if !self.follow || !self.is_complete(end_seq) {
+            return None;
+        }
+        let newest = *self.matches.back()?;
+        if self.followed.is_some_and(|m| m >= newest) {
+            return None;
+        }
+        let followed = self.followed.replace(newest);
+        let found = self
+            .matches
+            .iter()
+            .rev()
+            .copied()
+            .take_while(|&m| followed.is_none_or(|f| m > f))
+            .find(|&m| shown(m))?;
+        self.current = Some(found);
+        Some(found)
+    }
+}
+
+/// Parse a time of day typed as `hh:mm`, `hh:mm:ss` or `hh:mm:ss.mmm` (UTC,
+/// as the list shows it)
+pub(super) fn parse_time_of_day(text: &str) -> Option<Duration> {
+    let (hms, millis) = match text.trim().split_once('.') {
+        Some((hms, millis)) if (1..=3).contains(&millis.len()) => {
+            let scale = 10u64.pow(3 - millis.len() as u32);
+            (hms, millis.parse::<u64>().ok()? * scale)
+        }
+        Some(_) => return None,
+        None => (text.trim(), 0),
+    };
+    let mut parts = hms.split(':');
+    let mut field = |max: u64| {
+        parts
+            .next()
+            .map(|p| p.parse::<u64>().ok().filter(|&v| v < max))
+    };
+    let hours = field(24)??;
+    let mins = field(60)??;
+    let secs = field(60).unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(Duration::from_millis(
+        (hours * 3600 + mins * 60 + secs) * 1000 + millis,
+    ))
+}
+
+/// The most recent moment at or before `newest` with the time of day `time`
+pub(super) fn time_target(newest: SystemTime, time: Duration) -> SystemTime {
+    let since_epoch = newest
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+    let day_start = Duration::from_secs(since_epoch.as_secs() / SECS_PER_DAY * SECS_PER_DAY);
+    let mut target = day_start + time;
+    if target > since_epoch {
+        target = target.saturating_sub(Duration::from_secs(SECS_PER_DAY));
+    }
+    SystemTime::UNIX_EPOCH + target
+}
+
+#[cfg(test)]
+mod tests {
+    use cat_protocol::display::decode_and_annotate_with_hint;
+
+    use super::*;
+
+    fn diagnostic(message: &str) -> TrafficEntry {
+        TrafficEntry::Diagnostic {
+            timestamp: SystemTime::UNIX_EPOCH,
+            source: "test".to_string(),
+            severity: super::super::DiagnosticSeverity::Info,
+            message: message.to_string(),
+        }
+    }
+
+    fn kenwood(text: &str) -> TrafficEntry {
+        TrafficEntry::Data {
+            timestamp: SystemTime::UNIX_EPOCH,
+            direction: super::super::TrafficDirection::Incoming,
+            source: super::super::TrafficSource::CaptureFile {
+                file: "test".to_string(),
+            },
+            data: text.as_bytes().to_vec(),
+            protocol: Some(cat_protocol::Protocol::Kenwood),
+            link: Default::default(),
+        }
+    }
+
+    /// Index then search `entries`
+    fn update(
+        search: &mut TrafficSearch,
+        frames: &mut FrameIndex,
+        entries: &VecDeque<TrafficEntry>,
+        first_seq: u64,
+    ) {
+        frames.update(entries, first_seq);
+        search.update(entries, first_seq, frames);
+    }
+
+    #[test]
+    fn test_search_steps_through_matches() {
+        let entries: VecDeque<_> = ["FA 14074", "MD 2", "fa 7074", "PS"]
+            .into_iter()
+            .map(diagnostic)
+            .collect();
+        let mut search = TrafficSearch::new();
+        search.query = "FA".to_string();
+        search.find(true);
+        update(&mut search, &mut FrameIndex::new(), &entries, 100);
+        assert_eq!(search.len(), 2);
+
+        assert_eq!(search.take_jump(104, |_| true), Some(100));
+        assert_eq!(search.position(), Some(1));
+        // Nothing pending until asked again
+        assert_eq!(search.take_jump(104, |_| true), None);
+        search.find(true);
+        assert_eq!(search.take_jump(104, |_| true), Some(102));
+        search.find(true);
+        assert_eq!(search.take_jump(104, |_| true), Some(100));
+        search.find(false);
+        assert_eq!(search.take_jump(104, |_| true), Some(102));
+        // Hidden matches are skipped
+        search.find(true);
+        assert_eq!(search.take_jump(104, |seq| seq != 100), Some(102));
+    }
+
+    #[test]
+    fn test_search_spans_frames_and_follows_new_entries() {
+        let mut entries: VecDeque<_> = (0..SEARCH_CHUNK + 10)
+            .map(|i| diagnostic(if i % 2 == 0 { "even" } else { "odd" }))
+            .collect();
+        let mut search = TrafficSearch::new();
+        search.query = "odd".to_string();
+        search.find(false);
+
+        let end = entries.len() as u64;
+        let mut frames = FrameIndex::new();
+        update(&mut search, &mut frames, &entries, 0);
+        assert!(!search.is_complete(end));
+        // The last match can't be known until the scan is done
+        assert_eq!(search.take_jump(end, |_| true), None);
+        update(&mut search, &mut frames, &entries, 0);
+        assert!(search.is_complete(end));
+        assert_eq!(search.take_jump(end, |_| true), Some(end - 1));
+        assert_eq!(search.len(), (SEARCH_CHUNK + 10) / 2);
+
+        // Old matches go with trimmed entries; new entries are searched
+        entries.pop_front();
+        entries.pop_front();
+        entries.push_back(diagnostic("odd one out"));
+        update(&mut search, &mut frames, &entries, 2);
+        assert_eq!(search.len(), (SEARCH_CHUNK + 10) / 2);
+    }
+
+    #[test]
+    fn test_query_kinds() {
+        let entries: VecDeque<_> = ["FA00014074000;", "MD2;", "TX;", "FA;"]
+            .into_iter()
+            .map(kenwood)
+            .chain([diagnostic("FA timed out")])
+            .collect();
+        let mut frames = FrameIndex::new();
+        let mut found = |query: &str| {
+            let mut search = TrafficSearch::new();
+            search.query = query.to_string();
+            search.find(true);
+            update(&mut search, &mut frames, &entries, 0);
+            (0..5)
+                .filter(|&seq| search.is_match(seq))
+                .collect::<Vec<_>>()
+        };
+
+        // Text matches the frame itself, its summary and diagnostics, and
+        // each frame's time, source and hex
+        assert_eq!(found("fa"), [0, 3, 4]);
+        assert_eq!(found("file(test)"), [0, 1, 2, 3]);
+        assert_eq!(found("00:00:00"), [0, 1, 2, 3, 4]);
+        assert_eq!(found("4d 44 32"), [1]);
+        assert_eq!(found("14.074"), [0]);
+        assert_eq!(found("hex:3b"), [0, 1, 2, 3]);
+        assert_eq!(found("HEX: 4d 44"), [1]);
+        assert_eq!(found("cmd:freq"), [0, 3]);
+        assert_eq!(found("cmd:PTT"), [2]);
+
+        let mut search = TrafficSearch::new();
+        search.query = "hex:4D4".to_string();
+        search.find(true);
+        assert!(!search.is_active());
+        assert!(search.error().is_some());
+        search.query = "cmd:nope".to_string();
+        search.find(true);
+        assert!(search.error().unwrap().contains("freq, mode"));
+    }
+
+    #[test]
+    fn test_highlight() {
+        let data = b"FA00014074000;";
+        let decoded = decode_and_annotate_with_hint(data, Some(cat_protocol::Protocol::Kenwood));
+        let hex = SearchQuery::parse("hex:31 34").unwrap().unwrap();
+        let highlight = hex.highlight(data, decoded.as_ref());
+        assert_eq!(highlight.bytes, Some(5..7));
+        assert!(highlight.covers(&(6..8)));
+        assert!(!highlight.covers(&(7..8)));
+
+        let text = SearchQuery::parse("fa000").unwrap().unwrap();
+        assert_eq!(text.highlight(data, decoded.as_ref()).bytes, Some(0..5));
+
+        // A command class marks the command
+        let class = SearchQuery::parse("cmd:freq").unwrap().unwrap();
+        let highlight = class.highlight(data, decoded.as_ref());
+        assert!(highlight.covers(&(0..2)));
+        assert!(!highlight.covers(&(2..13)));
+        assert!(!highlight.parts.is_empty());
+    }
+
+    #[test]
+    fn test_follow_jumps_to_new_matches() {
+        let mut entries: VecDeque<_> = ["TX;", "FA;", "TX;"].into_iter().map(kenwood).collect();
+        let mut frames = FrameIndex::new();
+        let mut search = TrafficSearch::new();
+        search.query = "cmd:ptt".to_string();
+        search.find(true);
+        search.take_jump(0, |_| true);
+        update(&mut search, &mut frames, &entries, 0);
+        assert_eq!(search.take_follow(3, |_| true), None);
+
+        search.follow = true;
+        assert_eq!(search.take_follow(3, |_| true), Some(2));
+        assert_eq!(search.take_follow(3, |_| true), None);
+
+        // New matches are followed, unless the view hides them
+        entries.extend(["RX;", "FA;"].map(kenwood));
+        update(&mut search, &mut frames, &entries, 0);
+        assert_eq!(search.take_follow(5, |_| true), Some(3));
+        entries.push_back(kenwood("TX;"));
+        update(&mut search, &mut frames, &entries, 0);
+        assert_eq!(search.take_follow(6, |seq| seq != 5), None);
+        assert_eq!(search.current(), Some(3));
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(
+            parse_time_of_day("14:05"),
+            Some(Duration::from_secs(14 * 3600 + 5 * 60))
+        );
+        assert_eq!(
+            parse_time_of_day(" 00:00:07.5 "),
+            Some(Duration::from_millis(7500))
+        );
+        assert_eq!(parse_time_of_day("24:00"), None);
+        assert_eq!(parse_time_of_day("12"), None);
+        assert_eq!(parse_time_of_day("12:00:00:00"), None);
+        assert_eq!(parse_time_of_day("12:00.1234"), None);
+    }
+
+    #[test]
+    fn test_time_target_is_latest_occurrence() {
+        let day = 20_000 * SECS_PER_DAY;
+        let newest = SystemTime::UNIX_EPOCH + Duration::from_secs(day + 3600);
+        // Earlier today
+        assert_eq!(
+            time_target(newest, Duration::from_secs(1800)),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(day + 1800)
+        );
+        // Not yet today: yesterday
+        assert_eq!(
+            time_target(newest, Duration::from_secs(23 * 3600)),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(day - 3600)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Visual row index for the traffic list
+//!
+//! Only the rows in view are laid out, but the scroll area still needs to
+//! know how many rows there are and which entry line each one shows. With
+//! hex wrapping an entry can span several rows, so the mapping is kept here
+//! and brought up to date incrementally: new entries are appended, trimmed
+//! ones dropped from the front, and the whole index is rebuilt only when the
+//! layout (wrap width, filters) changes.
+//!
+//! Entries are identified by sequence number: the oldest entry in the
+//! history has the monitor's `first_seq`, and each later one the next number.
+
+use std::collections::VecDeque;
+
+use super::models::{TrafficDirection, TrafficEntry};
+
+/// What decides which entries are shown and how many rows each takes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct RowLayout {
+    pub show_hex: bool,
+    pub bytes_per_line: usize,
+    pub filter_direction: Option<TrafficDirection>,
+    pub show_diagnostics: bool,
+}
+
+/// Map from visual rows to entry lines
+#[derive(Debug, Default)]
+pub(super) struct RowIndex {
+    layout: Option<RowLayout>,
+    /// (entry sequence number, absolute first row) of each shown entry
+    starts: VecDeque<(u64, u64)>,
+    /// Entries before this sequence number have been indexed
+    indexed_to: u64,
+    /// Absolute number of row 0 (rows of trimmed entries included)
+    base_row: u64,
+    /// Absolute number of the row after the last
+    end_row: u64,
+}
+
+impl RowIndex {
+    pub(super) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the index up to date with `entries`, whose oldest has sequence
+    /// number `first_seq`
+    ///
+    /// `rows_for` gives the rows an entry takes under `layout` (0 if it's
+    /// filtered out). Returns how many rows were dropped from the top since
+    /// the last update, for keeping the view still.
+    pub(super) fn update(
+        &mut self,
+        entries: &VecDeque<TrafficEntry>,
+        first_seq: u64,
+        layout: RowLayout,
+        rows_for: impl Fn(&TrafficEntry) -> usize,
+    ) -> usize {
+        if self.layout != Some(layout) {
+            *self = Self {
+                layout: Some(layout),
+                indexed_to: first_seq,
+                ..Self::default()
+            };
+        }
+
+        while self.starts.front().is_some_and(|&(seq, _)| seq < first_seq) {
+            self.starts.pop_front();
+        }
+        let base_row = self.starts.front().map_or(self.end_row, |&(_, row)| row);
+        let dropped = (base_row - self.base_row) as usize;
+        self.base_row = base_row;
+
+        let end_seq = first_seq + entries.len() as u64;
+        for seq in self.indexed_to.max(first_seq)..end_seq {
+            let rows = rows_for(&entries[(seq - first_seq) as usize]);
+            if rows > 0 {
+                self.starts.push_back((seq, self.end_row));
+                self.end_row += rows as u64;
+            }
+        }
+        self.indexed_to = end_seq;
+        dropped
+    }
+
+    /// Forget entries from sequence number `seq` on (they were replaced)
+    pub(super) fn truncate(&mut self, seq: u64) {
+        while let Some(&(last, row)) = self.starts.back() {
+            if last < seq {
+                break;
+            }
+            self.starts.pop_back();
+            self.end_row = row;
+        }
+        self.indexed_to = self.indexed_to.min(seq);
+    }
+
+    /// Number of rows
+    pub(super) fn len(&self) -> usize {
+        (self.end_row - self.base_row) as usize
+    }
+
+    /// Entry sequence number and line within the entry shown on `row`
+    pub(super) fn row(&self, row: usize) -> Option<(u64, usize)> {
+        let abs = self.base_row + row as u64;
+        if abs >= self.end_row {
+            return None;
+        }
+        let idx = self.starts.partition_point(|&(_, start)| start <= abs);
+        let (seq, start) = self.starts[idx.checked_sub(1)?];
+        Some((seq, (abs - start) as usize))
+    }
+
+    /// First row of the first shown entry at or after sequence number `seq`
+    pub(super) fn row_of(&self, seq: u64) -> Option<usize> {
+        let idx = self.starts.partition_point(|&(s, _)| s < seq);
+        self.starts
+            .get(idx)
+            .map(|&(_, start)| (start - self.base_row) as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::SystemTime;
+
+    use super::*;
+    use crate::traffic_monitor::models::{FrameLink, TrafficSource};
+
+    const LAYOUT: RowLayout = RowLayout {
+        show_hex: true,
+        bytes_per_line: 4,
+        filter_direction: None,
+        show_diagnostics: true,
+    };
+
+    fn entry(len: usize, direction: TrafficDirection) -> TrafficEntry {
+        TrafficEntry::Data {
+            timestamp: SystemTime::UNIX_EPOCH,
+            direction,
+            source: TrafficSource::CaptureFile {
+                file: String::new(),
+            },
+            data: vec![0; len],
+            protocol: None,
+            link: FrameLink::default(),
+        }
+    }
+
+    fn rows_for(layout: RowLayout) -> impl Fn(&TrafficEntry) -> usize {
+        move |entry| {
+            let TrafficEntry::Data {
+                data, direction, ..
+            } = entry
+            else {
+                return 1;
+            };
+            if layout.filter_direction.is_some_and(|d| d != *direction) {
+                0
+            } else {
+                data.len().div_ceil(layout.bytes_per_line)
+            }
+        }
+    }
+
+    #[test]
+    fn test_rows_map_to_entry_lines() {
+        let entries: VecDeque<_> = [
+            entry(4, TrafficDirection::Incoming),
+            entry(9, TrafficDirection::Outgoing),
+            entry(2, TrafficDirection::Incoming),
+        ]
+        .into();
+        let mut index = RowIndex::new();
+        index.update(&entries, 10, LAYOUT, rows_for(LAYOUT));
+
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.row(0), Some((10, 0)));
+        assert_eq!(index.row(3), Some((11, 2)));
+        assert_eq!(index.row(4), Some((12, 0)));
+        assert_eq!(index.row(5), None);
+        assert_eq!(index.row_of(12), Some(4));
+
+        // A filter change rebuilds it
+        let incoming = RowLayout {
+            filter_direction: Some(TrafficDirection::Incoming),
+            ..LAYOUT
+        };
+        index.update(&entries, 10, incoming, rows_for(incoming));
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.row(1), Some((12, 0)));
+        // A hidden entry resolves to the next shown one
+        assert_eq!(index.row_of(11), Some(1));
+    }
+
+    #[test]
+    fn test_trimming_reports_dropped_rows() {
+        let mut entries: VecDeque<_> = (0..3)
+            .map(|_| entry(8, TrafficDirection::Incoming))
+            .collect();
+        let mut index = RowIndex::new();
+        assert_eq!(index.update(&entries, 0, LAYOUT, rows_for(LAYOUT)), 0);
+        assert_eq!(index.len(), 6);
+
+        // History full: the oldest goes as a new one arrives
+        entries.pop_front();
+        entries.push_back(entry(4, TrafficDirection::Incoming));
+        assert_eq!(index.update(&entries, 1, LAYOUT, rows_for(LAYOUT)), 2);
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.row(0), Some((1, 0)));
+        assert_eq!(index.row(4), Some((3, 0)));
+
+        // Everything cleared
+        entries.clear();
+        assert_eq!(index.update(&entries, 4, LAYOUT, rows_for(LAYOUT)), 5);
+        assert_eq!(index.len(), 0);
+    }
+
+    #[test]
+    fn test_truncate_reindexes_replaced_entries() {
+        let mut entries: VecDeque<_> = (0..3)
+            .map(|_| entry(4, TrafficDirection::Incoming))
+            .collect();
+        let mut index = RowIndex::new();
+        index.update(&entries, 0, LAYOUT, rows_for(LAYOUT));
+
+        entries.truncate(1);
+        entries.push_back(entry(12, TrafficDirection::Incoming));
+        index.truncate(1);
+        index.update(&entries, 0, LAYOUT, rows_for(LAYOUT));
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.row(3), Some((1, 2)));
+    }
+}
@@ -3,22 +3,41 @@
 use std::ops::Range;
 use std::time::SystemTime;
 
+use cat_mux::{AnalyzerDirection, FrameId};
 use cat_protocol::display::{AnnotatedFrame, FrameSegment};
-use egui::{Color32, Id, RichText, Ui};
+use egui::{Color32, Id, RichText, Sense, Ui};
 use tracing::Level;
 
+use crate::i18n::tr;
+
+use super::frame_index::FrameIndex;
 use super::models::{
-    segment_color, DiagnosticSeverity, ExportAction, TrafficDirection, TrafficEntry, TrafficSource,
+    segment_color, DiagnosticSeverity, ExportAction, FrameLink, TrafficDirection, TrafficEntry,
+    TrafficSource,
 };
+use super::navigate::{parse_time_of_day, time_target, SearchHighlight};
+use super::rows::RowLayout;
 use super::TrafficMonitor;
 
 /// Minimum bytes per line (don't wrap smaller than this)
 const MIN_BYTES_PER_LINE: usize = 4;
 
-/// A visual row in the traffic monitor, mapping to a specific line of an entry
-struct VisualRow {
-    entry_idx: usize,
-    line_offset: usize, // 0 = first line with metadata, 1+ = continuation lines
+/// Frame linked to the one the operator clicked
+const LINK_HIGHLIGHT: Color32 = Color32::from_rgb(255, 215, 0);
+
+/// Current search match
+const SEARCH_HIGHLIGHT: Color32 = Color32::from_rgba_premultiplied(60, 60, 0, 60);
+
+/// What matched the search within a matching row
+const SEARCH_MATCH: Color32 = Color32::from_rgb(110, 90, 0);
+
+/// Mark the byte at `pos` if it matched the search
+fn mark_match(text: RichText, matched: Option<&SearchHighlight>, pos: usize) -> RichText {
+    if matched.is_some_and(|m| m.covers(&(pos..pos + 1))) {
+        text.background_color(SEARCH_MATCH)
+    } else {
+        text
+    }
 }
 
 /// Calculate the number of visual lines needed to display an entry
@@ -53,6 +72,11 @@ fn calculate_bytes_per_line(ui: &Ui, available_width: f32) -> usize {
     (bytes_that_fit as usize).max(MIN_BYTES_PER_LINE)
 }
 
+/// Where a clicked timestamp waits until the list has been drawn
+fn inspect_id() -> Id {
+    Id::new("traffic_inspect_at")
+}
+
 impl TrafficMonitor {
     /// Draw the traffic monitor UI with display settings
     /// Returns Some(ExportAction) if an export action was requested
@@ -65,27 +89,31 @@ impl TrafficMonitor {
         let mut export_action = None;
         // Toolbar - use horizontal_wrapped to allow narrower panels
         ui.horizontal_wrapped(|ui| {
-            ui.checkbox(&mut self.auto_scroll, "Auto-scroll");
+            ui.checkbox(&mut self.auto_scroll, tr!("traffic-auto-scroll"));
             ui.separator();
 
             if ui
-                .button(if self.paused { "Resume" } else { "Pause" })
+                .button(if self.paused {
+                    tr!("traffic-resume")
+                } else {
+                    tr!("traffic-pause")
+                })
                 .clicked()
             {
-                self.paused = !self.paused;
+                self.set_paused(!self.paused);
             }
 
-            if ui.button("Clear").clicked() {
+            if ui.button(tr!("clear")).clicked() {
                 self.clear();
             }
 
             // Export dropdown menu
-            ui.menu_button("Export", |ui| {
-                if ui.button("Copy to Clipboard").clicked() {
+            ui.menu_button(tr!("traffic-export"), |ui| {
+                if ui.button(tr!("traffic-copy")).clicked() {
                     export_action = Some(ExportAction::CopyToClipboard(self.format_filtered_log()));
                     ui.close();
                 }
-                if ui.button("Save to File...").clicked() {
+                if ui.button(tr!("traffic-save")).clicked() {
                     export_action = Some(match self.save_filtered_log_with_dialog() {
                         Ok(Some(path)) => ExportAction::SavedToFile(path),
                         Ok(None) => ExportAction::Cancelled,
@@ -95,12 +123,41 @@ impl TrafficMonitor {
                 }
             });
 
+            // Bookmark navigation
+            let bookmarks: Vec<(u64, String)> = self
+                .entries
+                .iter()
+                .filter_map(|entry| match entry {
+                    TrafficEntry::Bookmark {
+                        id,
+                        timestamp,
+                        note,
+                        ..
+                    } => Some((
+                        *id,
+                        format!("{} {}", Self::format_timestamp(timestamp), note),
+                    )),
+                    _ => None,
+                })
+                .collect();
+            if !bookmarks.is_empty() {
+                ui.menu_button(tr!("traffic-bookmarks", count = bookmarks.len()), |ui| {
+                    for (id, label) in bookmarks.iter().rev() {
+                        if ui.button(label).clicked() {
+                            self.jump_to_bookmark = Some(*id);
+                            self.auto_scroll = false;
+                            ui.close();
+                        }
+                    }
+                });
+            }
+
             ui.separator();
 
             // Direction filter
-            ui.label("Filter:");
+            ui.label(tr!("traffic-filter"));
             if ui
-                .selectable_label(self.filter_direction.is_none(), "All")
+                .selectable_label(self.filter_direction.is_none(), tr!("traffic-filter-all"))
                 .clicked()
             {
                 self.filter_direction = None;
@@ -108,7 +165,7 @@ impl TrafficMonitor {
             if ui
                 .selectable_label(
                     self.filter_direction == Some(TrafficDirection::Incoming),
-                    "In",
+                    tr!("traffic-filter-in"),
                 )
                 .clicked()
             {
@@ -117,7 +174,7 @@ impl TrafficMonitor {
             if ui
                 .selectable_label(
                     self.filter_direction == Some(TrafficDirection::Outgoing),
-                    "Out",
+                    tr!("traffic-filter-out"),
                 )
                 .clicked()
             {
@@ -127,13 +184,14 @@ impl TrafficMonitor {
             ui.separator();
 
             // Diagnostic level selector with dropdown menu
-            let level_label = match self.diagnostic_level {
-                Some(Level::DEBUG) | Some(Level::TRACE) => "Logs: Debug",
-                Some(Level::INFO) => "Logs: Info",
-                Some(Level::WARN) => "Logs: Warn",
-                Some(Level::ERROR) => "Logs: Error",
-                None => "Logs: Off",
+            let level = match self.diagnostic_level {
+                Some(Level::DEBUG) | Some(Level::TRACE) => tr!("log-level-debug"),
+                Some(Level::INFO) => tr!("log-level-info"),
+                Some(Level::WARN) => tr!("log-level-warning"),
+                Some(Level::ERROR) => tr!("log-level-error"),
+                None => tr!("log-level-off"),
             };
+            let level_label = tr!("traffic-logs", level = level);
             ui.menu_button(
                 RichText::new(format!("{} ▾", level_label)).color(
                     if self.diagnostic_level.is_some() {
@@ -144,28 +202,37 @@ impl TrafficMonitor {
                 ),
                 |ui| {
                     if ui
-                        .selectable_label(self.diagnostic_level.is_none(), "Off")
+                        .selectable_label(self.diagnostic_level.is_none(), tr!("log-level-off"))
                         .clicked()
                     {
                         self.diagnostic_level = None;
                         ui.close();
                     }
                     if ui
-                        .selectable_label(self.diagnostic_level == Some(Level::ERROR), "Error")
+                        .selectable_label(
+                            self.diagnostic_level == Some(Level::ERROR),
+                            tr!("log-level-error"),
+                        )
                         .clicked()
                     {
                         self.diagnostic_level = Some(Level::ERROR);
                         ui.close();
                     }
                     if ui
-                        .selectable_label(self.diagnostic_level == Some(Level::WARN), "Warning")
+                        .selectable_label(
+                            self.diagnostic_level == Some(Level::WARN),
+                            tr!("log-level-warning"),
+                        )
                         .clicked()
                     {
                         self.diagnostic_level = Some(Level::WARN);
                         ui.close();
                     }
                     if ui
-                        .selectable_label(self.diagnostic_level == Some(Level::INFO), "Info")
+                        .selectable_label(
+                            self.diagnostic_level == Some(Level::INFO),
+                            tr!("log-level-info"),
+                        )
                         .clicked()
                     {
                         self.diagnostic_level = Some(Level::INFO);
@@ -177,7 +244,7 @@ impl TrafficMonitor {
                                 self.diagnostic_level,
                                 Some(Level::DEBUG) | Some(Level::TRACE)
                             ),
-                            "Debug",
+                            tr!("log-level-debug"),
                         )
                         .clicked()
                     {
@@ -186,6 +253,9 @@ impl TrafficMonitor {
                     }
                 },
             );
+
+            ui.separator();
+            self.draw_find_controls(ui);
         });
 
         ui.separator();
@@ -194,83 +264,315 @@ impl TrafficMonitor {
         let available_width = ui.available_width();
         let bytes_per_line = calculate_bytes_per_line(ui, available_width);
 
-        // Traffic list - build visual rows for proper virtual scrolling with line wrapping
-        let visual_rows: Vec<VisualRow> = self
-            .entries
-            .iter()
-            .enumerate()
-            .filter(|(_, entry)| self.entry_passes_filter(entry))
-            .flat_map(|(entry_idx, entry)| {
-                let num_lines = lines_for_entry(entry, show_hex, bytes_per_line);
-                (0..num_lines).map(move |line_offset| VisualRow {
-                    entry_idx,
-                    line_offset,
-                })
-            })
-            .collect();
+        // Only the rows in view are built; the index maps them to entry lines
+        let layout = RowLayout {
+            show_hex,
+            bytes_per_line,
+            filter_direction: self.filter_direction,
+            show_diagnostics: self.diagnostic_level.is_some(),
+        };
+        // The index and search are taken out while they look at the entries
+        let mut row_index = std::mem::take(&mut self.row_index);
+        let dropped_rows = row_index.update(&self.entries, self.first_seq, layout, |entry| {
+            if self.entry_passes_filter(entry) {
+                lines_for_entry(entry, show_hex, bytes_per_line)
+            } else {
+                0
+            }
+        });
+        self.row_index = row_index;
+        let mut search = std::mem::take(&mut self.search);
+        if search.is_active() {
+            self.frame_index.update(&self.entries, self.first_seq);
+        } else if !self.frame_index.is_empty() {
+            // Only searches use the index; give its memory back
+            self.frame_index = FrameIndex::new();
+        }
+        search.update(&self.entries, self.first_seq, &self.frame_index);
+        let end_seq = self.first_seq + self.entries.len() as u64;
+        if !search.is_complete(end_seq) {
+            ui.ctx().request_repaint();
+        }
+        let shown = |seq| {
+            self.entry(seq)
+                .is_some_and(|entry| self.entry_passes_filter(entry))
+        };
+        let search_jump = search
+            .take_jump(end_seq, shown)
+            .or_else(|| search.take_follow(end_seq, shown));
+        self.search = search;
 
         let text_style = egui::TextStyle::Monospace;
         let row_height = ui.text_style_height(&text_style);
+        let row_pitch = row_height + ui.spacing().item_spacing.y;
 
-        egui::ScrollArea::both()
+        let mut scroll_area = egui::ScrollArea::both()
             .auto_shrink([false, false])
-            .stick_to_bottom(self.auto_scroll)
-            .show_rows(ui, row_height, visual_rows.len(), |ui, row_range| {
+            .stick_to_bottom(self.auto_scroll);
+        let jump_seq = if let Some(id) = self.jump_to_bookmark.take() {
+            self.position_of(
+                |entry| matches!(entry, TrafficEntry::Bookmark { id: b, .. } if *b == id),
+            )
+        } else if let Some(id) = self.jump_to_frame.take() {
+            self.position_of(
+                |entry| matches!(entry, TrafficEntry::Data { link, .. } if link.id == Some(id)),
+            )
+        } else if let Some(seq) = search_jump {
+            self.auto_scroll = false;
+            Some(seq)
+        } else {
+            self.jump_to_entry.take()
+        };
+        if let Some(row) = jump_seq.and_then(|seq| self.row_index.row_of(seq)) {
+            scroll_area = scroll_area.vertical_scroll_offset(row as f32 * row_pitch);
+        } else if dropped_rows > 0 && !self.auto_scroll {
+            // Entries trimmed from the top would pull the view down; keep it
+            // on the rows it was showing
+            let offset = (self.scroll_offset - dropped_rows as f32 * row_pitch).max(0.0);
+            scroll_area = scroll_area.vertical_scroll_offset(offset);
+        }
+        let search_hit = self.search.current();
+        let search = self.search.search().cloned();
+        let mut clicked_link = None;
+        let output =
+            scroll_area.show_rows(ui, row_height, self.row_index.len(), |ui, row_range| {
                 for i in row_range {
-                    if let Some(visual_row) = visual_rows.get(i) {
-                        if let Some(entry) = self.entries.get(visual_row.entry_idx) {
-                            self.draw_entry(
-                                ui,
-                                entry,
-                                visual_row.entry_idx,
-                                visual_row.line_offset,
-                                bytes_per_line,
-                                show_hex,
-                                show_decoded,
-                            );
+                    let Some((seq, line_offset)) = self.row_index.row(i) else {
+                        continue;
+                    };
+                    let Some(entry) = self.entries.get((seq - self.first_seq) as usize) else {
+                        continue;
+                    };
+                    let highlight = (search_hit == Some(seq)).then(|| {
+                        let rect = egui::Rect::from_min_size(
+                            ui.cursor().min,
+                            egui::vec2(ui.available_width(), row_height),
+                        );
+                        (ui.painter().add(egui::Shape::Noop), rect)
+                    });
+                    // Annotate lazily, only for rows that are actually visible
+                    let decoded = match entry {
+                        TrafficEntry::Data {
+                            data,
+                            protocol,
+                            source,
+                            ..
+                        } => {
+                            let model = source.radio().and_then(|h| self.radio_models.get(&h));
+                            self.annotation_cache.get_or_decode(data, *protocol, model)
                         }
+                        TrafficEntry::Diagnostic { .. } | TrafficEntry::Bookmark { .. } => None,
+                    };
+                    let matched = match (&search, entry) {
+                        (Some(search), TrafficEntry::Data { data, .. })
+                            if self.search.is_match(seq) =>
+                        {
+                            Some(search.highlight(data, decoded.as_ref()))
+                        }
+                        _ => None,
+                    };
+                    let clicked = self.draw_entry(
+                        ui,
+                        entry,
+                        decoded.as_ref(),
+                        matched.as_ref(),
+                        seq,
+                        line_offset,
+                        bytes_per_line,
+                        show_hex,
+                        show_decoded,
+                    );
+                    clicked_link = clicked_link.or(clicked);
+                    if let Some((shape, rect)) = highlight {
+                        ui.painter()
+                            .set(shape, egui::Shape::rect_filled(rect, 2.0, SEARCH_HIGHLIGHT));
                     }
                 }
                 // Bottom margin to prevent scroll jitter during autoscroll
                 ui.add_space(row_height);
             });
+        self.scroll_offset = output.state.offset.y;
+        let clicked_time = ui.memory_mut(|mem| {
+            let timestamp = mem.data.get_temp::<SystemTime>(inspect_id());
+            mem.data.remove::<SystemTime>(inspect_id());
+            timestamp
+        });
+        if clicked_time.is_some() {
+            self.inspect_at = clicked_time;
+        }
+
+        // Clicking a cause highlights (and scrolls to) the frame it names;
+        // clicking it again clears the highlight
+        if let Some(id) = clicked_link {
+            if self.highlight_frame == Some(id) {
+                self.highlight_frame = None;
+            } else {
+                self.highlight_frame = Some(id);
+                self.jump_to_frame = Some(id);
+                self.auto_scroll = false;
+            }
+        }
 
         export_action
     }
 
+    /// Draw the search box, match navigation and go-to-time box
+    fn draw_find_controls(&mut self, ui: &mut Ui) {
+        let find = ui.add(
+            egui::TextEdit::singleline(&mut self.search.query)
+                .hint_text(tr!("search-hint"))
+                .desired_width(120.0),
+        );
+        if find.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            let backwards = ui.input(|i| i.modifiers.shift);
+            self.search.find(!backwards);
+            find.request_focus();
+        }
+        find.on_hover_text(tr!("search-hover"));
+        if let Some(error) = self.search.error() {
+            ui.label(
+                RichText::new(error)
+                    .color(Color32::from_rgb(255, 120, 80))
+                    .small(),
+            );
+        }
+
+        if self.search.is_active() {
+            if ui
+                .small_button("◀")
+                .on_hover_text(tr!("search-previous"))
+                .clicked()
+            {
+                self.search.find(false);
+            }
+            if ui
+                .small_button("▶")
+                .on_hover_text(tr!("search-next"))
+                .clicked()
+            {
+                self.search.find(true);
+            }
+            ui.checkbox(&mut self.search.follow, tr!("search-follow"))
+                .on_hover_text(tr!("search-follow-hover"));
+            let found = self.search.len();
+            let status = if !self
+                .search
+                .is_complete(self.first_seq + self.entries.len() as u64)
+            {
+                tr!("search-searching", found = found)
+            } else if found == 0 {
+                tr!("search-no-matches")
+            } else if let Some(position) = self.search.position() {
+                format!("{}/{}", position, found)
+            } else {
+                tr!("search-matches", count = found)
+            };
+            ui.label(RichText::new(status).color(Color32::GRAY).small());
+            if ui
+                .small_button("✕")
+                .on_hover_text(tr!("search-clear"))
+                .clicked()
+            {
+                self.search.clear();
+            }
+        }
+
+        ui.separator();
+
+        let goto = ui
+            .add(
+                egui::TextEdit::singleline(&mut self.goto_time)
+                    .hint_text("hh:mm:ss")
+                    .desired_width(70.0),
+            )
+            .on_hover_text(tr!("goto-time-hover"));
+        if goto.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+            if let Some(time) = parse_time_of_day(&self.goto_time) {
+                self.jump_to_entry = self.entry_at_time(time);
+                self.auto_scroll = false;
+            }
+        }
+    }
+
+    /// The entry with sequence number `seq`, if it's still in the history
+    fn entry(&self, seq: u64) -> Option<&TrafficEntry> {
+        let idx = seq.checked_sub(self.first_seq)?;
+        self.entries.get(usize::try_from(idx).ok()?)
+    }
+
+    /// Sequence number of the first entry matching `found`
+    fn position_of(&self, found: impl Fn(&TrafficEntry) -> bool) -> Option<u64> {
+        self.entries
+            .iter()
+            .position(found)
+            .map(|idx| self.first_seq + idx as u64)
+    }
+
+    /// Sequence number of the first entry at or after the most recent
+    /// occurrence of the time of day `time`
+    fn entry_at_time(&self, time: std::time::Duration) -> Option<u64> {
+        let newest = self.entries.back()?.timestamp();
+        let target = time_target(newest, time);
+        let idx = self
+            .entries
+            .partition_point(|entry| entry.timestamp() < target);
+        Some(self.first_seq + idx as u64)
+    }
+
+    /// Draw an entry's time; clicking it asks to inspect the mux state then
+    fn draw_timestamp(ui: &mut Ui, timestamp: &SystemTime) {
+        let response = ui
+            .add(
+                egui::Label::new(
+                    RichText::new(Self::format_timestamp(timestamp))
+                        .color(Color32::GRAY)
+                        .monospace(),
+                )
+                .sense(Sense::click()),
+            )
+            .on_hover_text(tr!("timestamp-inspect-hover"));
+        if response.clicked() {
+            ui.memory_mut(|mem| mem.data.insert_temp(inspect_id(), *timestamp));
+        }
+    }
+
     /// Draw a single line of a traffic entry
+    ///
+    /// Returns the frame named by a cause link the operator clicked.
     #[allow(clippy::too_many_arguments)]
     fn draw_entry(
         &self,
         ui: &mut Ui,
         entry: &TrafficEntry,
-        entry_idx: usize,
+        decoded: Option<&AnnotatedFrame>,
+        matched: Option<&SearchHighlight>,
+        seq: u64,
         line_offset: usize,
         bytes_per_line: usize,
         show_hex: bool,
         show_decoded: bool,
-    ) {
+    ) -> Option<FrameId> {
         match entry {
             TrafficEntry::Data {
                 timestamp,
                 source,
                 data,
-                decoded,
+                link,
                 ..
-            } => {
-                self.draw_data_entry(
-                    ui,
-                    entry_idx,
-                    line_offset,
-                    bytes_per_line,
-                    timestamp,
-                    source,
-                    data,
-                    decoded.as_ref(),
-                    show_hex,
-                    show_decoded,
-                );
-            }
+            } => self.draw_data_entry(
+                ui,
+                seq,
+                line_offset,
+                bytes_per_line,
+                timestamp,
+                source,
+                data,
+                link,
+                decoded,
+                matched,
+                show_hex,
+                show_decoded,
+            ),
             TrafficEntry::Diagnostic {
                 timestamp,
                 source,
@@ -281,6 +583,27 @@ impl TrafficMonitor {
                 if line_offset == 0 {
                     self.draw_diagnostic_entry(ui, timestamp, source, severity, message);
                 }
+                None
+            }
+            TrafficEntry::Bookmark {
+                timestamp,
+                source,
+                note,
+                ..
+            } => {
+                let color = Color32::from_rgb(255, 215, 0);
+                ui.horizontal(|ui| {
+                    Self::draw_timestamp(ui, timestamp);
+                    ui.label(RichText::new("★").color(color).monospace());
+                    ui.label(
+                        RichText::new(format!("[{}]", source))
+                            .color(color)
+                            .strong()
+                            .monospace(),
+                    );
+                    ui.label(RichText::new(note).color(color).monospace());
+                });
+                None
             }
         }
     }
@@ -290,28 +613,31 @@ impl TrafficMonitor {
     fn draw_data_entry(
         &self,
         ui: &mut Ui,
-        entry_idx: usize,
+        seq: u64,
         line_offset: usize,
         bytes_per_line: usize,
         timestamp: &SystemTime,
         source: &TrafficSource,
         data: &[u8],
+        link: &FrameLink,
         decoded: Option<&AnnotatedFrame>,
+        matched: Option<&SearchHighlight>,
         show_hex: bool,
         show_decoded: bool,
-    ) {
+    ) -> Option<FrameId> {
         // Calculate byte range for this line
         let start_byte = line_offset * bytes_per_line;
         let end_byte = (start_byte + bytes_per_line).min(data.len());
 
         // Safety check: don't render if start is past data length
         if start_byte >= data.len() && !data.is_empty() {
-            return;
+            return None;
         }
 
+        let mut clicked_link = None;
         ui.horizontal(|ui| {
             // Create a unique ID for this entry's hover state (shared across all lines)
-            let hover_id = Id::new("traffic_hover").with(entry_idx);
+            let hover_id = Id::new("traffic_hover").with(seq);
 
             // Get the currently hovered byte range from previous frame
             let hovered_range: Option<Range<usize>> = ui.memory(|mem| mem.data.get_temp(hover_id));
@@ -322,20 +648,7 @@ impl TrafficMonitor {
             if line_offset == 0 {
                 // First line: show metadata (timestamp, direction, protocol, decoded summary)
 
-                // Timestamp
-                let time = timestamp
-                    .duration_since(SystemTime::UNIX_EPOCH)
-                    .map(|d| {
-                        let secs = d.as_secs() % 86400;
-                        let hours = secs / 3600;
-                        let mins = (secs % 3600) / 60;
-                        let secs = secs % 60;
-                        let millis = d.subsec_millis();
-                        format!("{:02}:{:02}:{:02}.{:03}", hours, mins, secs, millis)
-                    })
-                    .unwrap_or_default();
-
-                ui.label(RichText::new(time).color(Color32::GRAY).monospace());
+                Self::draw_timestamp(ui, timestamp);
 
                 // Direction indicator with source info
                 match source {
@@ -367,6 +680,15 @@ impl TrafficMonitor {
                         };
                         ui.label(RichText::new(label).color(Color32::LIGHT_GREEN).monospace());
                     }
+                    TrafficSource::SuppressedAmplifier { port } => {
+                        let label = if port.is_empty() {
+                            "[→Amp dry run]".to_string()
+                        } else {
+                            format!("[→{} dry run]", port)
+                        };
+                        ui.label(RichText::new(label).color(Color32::GRAY).monospace())
+                            .on_hover_text(tr!("traffic-dry-run-hover"));
+                    }
                     TrafficSource::FromRealAmplifier { port } => {
                         let label = if port.is_empty() {
                             "[Amp→]".to_string()
@@ -375,6 +697,81 @@ impl TrafficMonitor {
                         };
                         ui.label(RichText::new(label).color(Color32::LIGHT_GREEN).monospace());
                     }
+                    TrafficSource::Analyzer { direction } => {
+                        let (label, color) = match direction {
+                            AnalyzerDirection::LoggerToRadio => {
+                                ("[Logger→Radio]", Color32::from_rgb(180, 100, 255))
+                            }
+                            AnalyzerDirection::RadioToLogger => {
+                                ("[Radio→Logger]", Color32::LIGHT_BLUE)
+                            }
+                        };
+                        ui.label(RichText::new(label).color(color).monospace());
+                    }
+                    TrafficSource::ShadowAmplifier => {
+                        ui.label(
+                            RichText::new("[→Shadow]")
+                                .color(Color32::from_rgb(120, 200, 160))
+                                .monospace(),
+                        );
+                    }
+                    TrafficSource::FromShadowAmplifier => {
+                        ui.label(
+                            RichText::new("[Shadow→]")
+                                .color(Color32::from_rgb(120, 200, 160))
+                                .monospace(),
+                        );
+                    }
+                    TrafficSource::CaptureFile { file } => {
+                        ui.label(
+                            RichText::new(format!("[{}]", file))
+                                .color(Color32::from_rgb(200, 200, 140))
+                                .monospace(),
+                        );
+                    }
+                    TrafficSource::Logged { channel, outgoing } => {
+                        let label = if *outgoing {
+                            format!("[→{}]", channel)
+                        } else {
+                            format!("[{}→]", channel)
+                        };
+                        ui.label(
+                            RichText::new(label)
+                                .color(Color32::from_rgb(200, 200, 140))
+                                .monospace(),
+                        );
+                    }
+                }
+
+                // Cause and effect: a received frame's id, a write's cause
+                if let Some(id) = link.id {
+                    let text = RichText::new(id.to_string()).monospace();
+                    let text = if self.highlight_frame == Some(id) {
+                        text.color(Color32::BLACK).background_color(LINK_HIGHLIGHT)
+                    } else {
+                        text.color(Color32::DARK_GRAY)
+                    };
+                    ui.label(text);
+                }
+                if let Some(cause) = link.cause {
+                    let color = if self.highlight_frame == Some(cause) {
+                        LINK_HIGHLIGHT
+                    } else {
+                        Color32::GRAY
+                    };
+                    let response = ui
+                        .add(
+                            egui::Label::new(
+                                RichText::new(format!("←{}", cause))
+                                    .color(color)
+                                    .monospace(),
+                            )
+                            .sense(Sense::click()),
+                        )
+                        .on_hover_text(tr!("traffic-cause-hover", frame = cause.to_string()));
+                    if response.clicked() {
+                        clicked_link = Some(cause);
+                    }
                 }
 
                 // Protocol badge
@@ -402,7 +799,7 @@ impl TrafficMonitor {
                         let prev_spacing = ui.spacing().item_spacing.x;
                         ui.spacing_mut().item_spacing.x = 0.0;
 
-                        for part in &decoded.summary {
+                        for (i, part) in decoded.summary.iter().enumerate() {
                             let color = segment_color(part.part_type);
 
                             // Check if this part should be highlighted
@@ -422,6 +819,11 @@ impl TrafficMonitor {
                                     .color(Color32::WHITE)
                                     .background_color(Color32::from_rgb(60, 60, 80))
                                     .monospace()
+                            } else if matched.is_some_and(|m| m.parts.contains(&i)) {
+                                RichText::new(&part.text)
+                                    .color(color)
+                                    .background_color(SEARCH_MATCH)
+                                    .monospace()
                             } else {
                                 RichText::new(&part.text).color(color).monospace()
                             };
@@ -454,12 +856,14 @@ impl TrafficMonitor {
             if show_hex && !data.is_empty() {
                 ui.add_space(8.0);
                 let line_data = &data[start_byte..end_byte];
-                if let Some(decoded) = decoded {
+                // Undecoded frames still show what matched
+                if decoded.is_some() || matched.is_some() {
                     self.draw_ascii_with_segments(
                         ui,
                         line_data,
                         start_byte,
-                        &decoded.segments,
+                        decoded.map_or(&[][..], |decoded| &decoded.segments),
+                        matched,
                         hovered_range.as_ref(),
                         &mut new_hovered_range,
                     );
@@ -482,12 +886,13 @@ impl TrafficMonitor {
             if show_hex && !data.is_empty() {
                 ui.add_space(8.0);
                 let line_data = &data[start_byte..end_byte];
-                if let Some(decoded) = decoded {
+                if decoded.is_some() || matched.is_some() {
                     self.draw_colored_hex(
                         ui,
                         line_data,
                         start_byte,
-                        &decoded.segments,
+                        decoded.map_or(&[][..], |decoded| &decoded.segments),
+                        matched,
                         hovered_range.as_ref(),
                         &mut new_hovered_range,
                     );
@@ -510,6 +915,7 @@ impl TrafficMonitor {
                 }
             });
         });
+        clicked_link
     }
 
     /// Draw a diagnostic entry (error or warning)
@@ -522,20 +928,7 @@ impl TrafficMonitor {
         message: &str,
     ) {
         ui.horizontal(|ui| {
-            // Timestamp
-            let time = timestamp
-                .duration_since(SystemTime::UNIX_EPOCH)
-                .map(|d| {
-                    let secs = d.as_secs() % 86400;
-                    let hours = secs / 3600;
-                    let mins = (secs % 3600) / 60;
-                    let secs = secs % 60;
-                    let millis = d.subsec_millis();
-                    format!("{:02}:{:02}:{:02}.{:03}", hours, mins, secs, millis)
-                })
-                .unwrap_or_default();
-
-            ui.label(RichText::new(time).color(Color32::GRAY).monospace());
+            Self::draw_timestamp(ui, timestamp);
 
             // Severity badge and color
             let (badge, color) = match severity {
@@ -559,12 +952,14 @@ impl TrafficMonitor {
     /// Draw ASCII representation with segment-based highlighting
     /// `data` contains only the bytes for this line
     /// `byte_offset` is the global byte offset where this line's data starts
+    #[allow(clippy::too_many_arguments)]
     fn draw_ascii_with_segments(
         &self,
         ui: &mut Ui,
         data: &[u8],
         byte_offset: usize,
         segments: &[FrameSegment],
+        matched: Option<&SearchHighlight>,
         hovered_range: Option<&Range<usize>>,
         new_hovered_range: &mut Option<Range<usize>>,
     ) {
@@ -593,7 +988,11 @@ impl TrafficMonitor {
                 } else {
                     '.'
                 };
-                ui.label(RichText::new(ch).color(Color32::DARK_GRAY).monospace());
+                ui.label(mark_match(
+                    RichText::new(ch).color(Color32::DARK_GRAY).monospace(),
+                    matched,
+                    byte_offset + pos,
+                ));
                 pos += 1;
             }
 
@@ -626,6 +1025,11 @@ impl TrafficMonitor {
                         .color(color)
                         .background_color(Color32::from_rgb(60, 60, 80))
                         .monospace()
+                } else if matched.is_some_and(|m| m.covers(&seg.range)) {
+                    RichText::new(&ascii)
+                        .color(color)
+                        .background_color(SEARCH_MATCH)
+                        .monospace()
                 } else {
                     RichText::new(&ascii).color(color).monospace()
                 };
@@ -653,7 +1057,11 @@ impl TrafficMonitor {
             } else {
                 '.'
             };
-            ui.label(RichText::new(ch).color(Color32::DARK_GRAY).monospace());
+            ui.label(mark_match(
+                RichText::new(ch).color(Color32::DARK_GRAY).monospace(),
+                matched,
+                byte_offset + pos,
+            ));
             pos += 1;
         }
 
@@ -663,12 +1071,14 @@ impl TrafficMonitor {
     /// Draw hex bytes with colors based on frame segments
     /// `data` contains only the bytes for this line
     /// `byte_offset` is the global byte offset where this line's data starts
+    #[allow(clippy::too_many_arguments)]
     fn draw_colored_hex(
         &self,
         ui: &mut Ui,
         data: &[u8],
         byte_offset: usize,
         segments: &[FrameSegment],
+        matched: Option<&SearchHighlight>,
         hovered_range: Option<&Range<usize>>,
         new_hovered_range: &mut Option<Range<usize>>,
     ) {
@@ -694,7 +1104,11 @@ impl TrafficMonitor {
             // Handle any gap before this segment
             while pos < seg_local_start && pos < data.len() {
                 let hex = format!("{:02X} ", data[pos]);
-                ui.label(RichText::new(hex).color(Color32::WHITE).monospace());
+                ui.label(mark_match(
+                    RichText::new(hex).color(Color32::WHITE).monospace(),
+                    matched,
+                    byte_offset + pos,
+                ));
                 pos += 1;
             }
 
@@ -729,6 +1143,11 @@ impl TrafficMonitor {
                         .color(color)
                         .background_color(Color32::from_rgb(60, 60, 80))
                         .monospace()
+                } else if matched.is_some_and(|m| m.covers(&seg.range)) {
+                    RichText::new(&hex_display)
+                        .color(color)
+                        .background_color(SEARCH_MATCH)
+                        .monospace()
                 } else {
                     RichText::new(&hex_display).color(color).monospace()
                 };
@@ -756,7 +1175,11 @@ impl TrafficMonitor {
             } else {
                 format!("{:02X}", data[pos])
             };
-            ui.label(RichText::new(hex).color(Color32::WHITE).monospace());
+            ui.label(mark_match(
+                RichText::new(hex).color(Color32::WHITE).monospace(),
+                matched,
+                byte_offset + pos,
+            ));
             pos += 1;
         }
 
@@ -0,0 +1,423 @@
+//! The daemon's configuration file
+//!
+//! TOML, read at startup and again on every reload:
+//!
+//! ```toml
+//! switching_mode = "FrequencyTriggered"
+//! control_socket = "/run/catapult/control.sock"
+//! metrics = "0.0.0.0:9464"
+//! status_page = "0.0.0.0:8073"
+//! ntfy_url = "http://ntfy.sh/my-shack"
+//!
+//! [[radios]]
+//! name = "K3"
+//! port = "/dev/ttyUSB0"
+//! protocol = "Elecraft"
+//! baud_rate = 38400
+//!
+//! [[radios]]
+//! name = "IC-7300"
+//! port = "192.168.1.20:4532"
+//! network = true
+//! protocol = "IcomCIV"
+//! civ_address = 148
+//!
+//! [amplifier]
+//! port = "/dev/ttyUSB2"
+//! protocol = "Kenwood"
+//! baud_rate = 9600
+//! ```
+//!
+//! Radios are identified by their port: a radio whose port is unchanged
+//! between two versions of the file is the same radio.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use cat_detect::port_settle_delay;
+use cat_mux::{AlertTargets, FlowControl, SerialLine, SwitchingMode};
+use cat_protocol::Protocol;
+use serde::{Deserialize, Serialize};
+
+/// Baud rate for radios and amplifiers that don't give one
+pub const DEFAULT_BAUD_RATE: u32 = 9600;
+
+fn default_baud_rate() -> u32 {
+    DEFAULT_BAUD_RATE
+}
+
+/// Why a configuration file wasn't accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The file couldn't be read
+    Read(String),
+    /// The file isn't valid TOML, or doesn't match the expected fields
+    Parse(String),
+    /// The file parses but describes an impossible setup
+    Invalid(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(e) => write!(f, "can't read config: {}", e),
+            ConfigError::Parse(e) => write!(f, "can't parse config: {}", e),
+            ConfigError::Invalid(e) => write!(f, "invalid config: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Serial port flow control (mirrors `cat_mux::FlowControl`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SerialFlowControl {
+    /// No flow control
+    None,
+    /// Software flow control (XON/XOFF)
+    Software,
+    /// Hardware flow control (RTS/CTS)
+    #[default]
+    Hardware,
+}
+
+impl From<SerialFlowControl> for FlowControl {
+    fn from(fc: SerialFlowControl) -> Self {
+        match fc {
+            SerialFlowControl::None => FlowControl::None,
+            SerialFlowControl::Software => FlowControl::Software,
+            SerialFlowControl::Hardware => FlowControl::Hardware,
+        }
+    }
+}
+
+/// A radio to connect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RadioConfig {
+    /// Display name
+    pub name: String,
+    /// Serial port path, or "host:port" for a network radio
+    pub port: String,
+    /// Protocol the radio speaks
+    pub protocol: Protocol,
+    /// Baud rate (serial radios only)
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    /// Flow control (serial radios only)
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+    /// CI-V address for Icom radios
+    #[serde(default)]
+    pub civ_address: Option<u8>,
+    /// Reach the radio over TCP at `port` (a serial server such as ser2net)
+    #[serde(default)]
+    pub network: bool,
+    /// Wait after opening the port before talking to the radio (serial radios
+    /// only; the adapter's default from [`cat_detect::usb_quirks`] if omitted)
+    #[serde(default)]
+    pub settle_ms: Option<u32>,
+}
+
+impl RadioConfig {
+    /// Serial line settings for the radio's port
+    pub fn serial_line(&self) -> SerialLine {
+        SerialLine::new(self.baud_rate, self.flow_control.into())
+    }
+
+    /// Wait after opening the port, before the radio is identified
+    ///
+    /// Network radios are written to as soon as they connect.
+    pub fn settle_delay(&self) -> Duration {
+        match (self.network, self.settle_ms) {
+            (true, _) => Duration::ZERO,
+            (false, Some(ms)) => Duration::from_millis(ms as u64),
+            (false, None) => port_settle_delay(&self.port),
+        }
+    }
+
+    /// Whether going from `self` to `other` needs the connection reopened
+    ///
+    /// Name and protocol changes are applied to the open connection.
+    pub fn needs_reopen(&self, other: &RadioConfig) -> bool {
+        self.port != other.port
+            || self.network != other.network
+            || self.baud_rate != other.baud_rate
+            || self.flow_control != other.flow_control
+            || self.civ_address != other.civ_address
+            || self.settle_ms != other.settle_ms
+    }
+}
+
+/// The amplifier to drive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmplifierConfig {
+    /// Serial port path
+    pub port: String,
+    /// Protocol the amplifier speaks
+    pub protocol: Protocol,
+    /// Baud rate
+    #[serde(default = "default_baud_rate")]
+    pub baud_rate: u32,
+    /// Flow control
+    #[serde(default)]
+    pub flow_control: SerialFlowControl,
+    /// CI-V address for Icom amplifiers
+    #[serde(default)]
+    pub civ_address: Option<u8>,
+}
+
+/// Everything the daemon runs
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaemonConfig {
+    /// How the active radio is chosen
+    #[serde(default)]
+    pub switching_mode: SwitchingMode,
+    /// Unix socket to accept `reload` and `status` requests on (none if omitted)
+    #[serde(default)]
+    pub control_socket: Option<PathBuf>,
+    /// Address to serve Prometheus metrics on (not served if omitted)
+    #[serde(default)]
+    pub metrics: Option<SocketAddr>,
+    /// Address to serve the status page on (not served if omitted)
+    #[serde(default)]
+    pub status_page: Option<SocketAddr>,
+    /// ntfy topic URL critical alerts are published to (none if omitted)
+    #[serde(default)]
+    pub ntfy_url: Option<String>,
+    /// URL critical alerts are POSTed to as JSON (none if omitted)
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Radios, connected in order
+    #[serde(default)]
+    pub radios: Vec<RadioConfig>,
+    /// Amplifier (none if omitted)
+    #[serde(default)]
+    pub amplifier: Option<AmplifierConfig>,
+}
+
+impl DaemonConfig {
+    /// Where critical alerts are pushed
+    pub fn alert_targets(&self) -> AlertTargets {
+        AlertTargets {
+            ntfy_url: self.ntfy_url.clone().unwrap_or_default(),
+            webhook_url: self.webhook_url.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Read and check a configuration file
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|e| ConfigError::Read(e.to_string()))?;
+        Self::parse(&text)
+    }
+
+    /// Parse and check a configuration
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(text).map_err(|e| ConfigError::Parse(e.to_string()))?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Check that radios can be told apart and don't share a port, and that
+    /// alerts have somewhere they can be pushed
+    fn validate(&self) -> Result<(), ConfigError> {
+        for (key, url) in [
+            ("ntfy_url", &self.ntfy_url),
+            ("webhook_url", &self.webhook_url),
+        ] {
+            if url.as_ref().is_some_and(|url| !url.starts_with("http://")) {
+                return Err(ConfigError::Invalid(format!(
+                    "{} must start with http://",
+                    key
+                )));
+            }
+        }
+        let mut names = HashSet::new();
+        let mut ports = HashSet::new();
+        for radio in &self.radios {
+            if radio.name.trim().is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "radio on {} has no name",
+                    radio.port
+                )));
+            }
+            if radio.port.trim().is_empty() {
+                return Err(ConfigError::Invalid(format!(
+                    "radio {} has no port",
+                    radio.name
+                )));
+            }
+            if !names.insert(radio.name.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "two radios are named {}",
+                    radio.name
+                )));
+            }
+            if !ports.insert(radio.port.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "two radios are on {}",
+                    radio.port
+                )));
+            }
+        }
+        if let Some(amp) = &self.amplifier {
+            if ports.contains(amp.port.as_str()) {
+                return Err(ConfigError::Invalid(format!(
+                    "the amplifier and a radio are both on {}",
+                    amp.port
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"
+switching_mode = "Manual"
+control_socket = "/tmp/catapult.sock"
+metrics = "127.0.0.1:9464"
+status_page = "0.0.0.0:8073"
+ntfy_url = "http://ntfy.sh/my-shack"
+
+[[radios]]
+name = "K3"
+port = "/dev/ttyUSB0"
+protocol = "Elecraft"
+baud_rate = 38400
+
+[[radios]]
+name = "IC-7300"
+port = "192.168.1.20:4532"
+network = true
+protocol = "IcomCIV"
+civ_address = 148
+
+[amplifier]
+port = "/dev/ttyUSB2"
+protocol = "Kenwood"
+"#;
+
+    #[test]
+    fn test_parse_example() {
+        let config = DaemonConfig::parse(EXAMPLE).unwrap();
+        assert_eq!(config.switching_mode, SwitchingMode::Manual);
+        assert_eq!(config.metrics, Some("127.0.0.1:9464".parse().unwrap()));
+        assert_eq!(config.status_page, Some("0.0.0.0:8073".parse().unwrap()));
+        let targets = config.alert_targets();
+        assert_eq!(targets.ntfy_url, "http://ntfy.sh/my-shack");
+        assert!(targets.webhook_url.is_empty());
+        assert_eq!(config.radios.len(), 2);
+        assert_eq!(config.radios[0].baud_rate, 38400);
+        assert_eq!(config.radios[0].flow_control, SerialFlowControl::Hardware);
+        assert_eq!(config.radios[0].settle_ms, None);
+        assert!(config.radios[1].network);
+        assert_eq!(config.radios[1].civ_address, Some(148));
+        let amp = config.amplifier.unwrap();
+        assert_eq!(amp.baud_rate, DEFAULT_BAUD_RATE);
+    }
+
+    #[test]
+    fn test_empty_file_is_an_empty_config() {
+        assert_eq!(DaemonConfig::parse("").unwrap(), DaemonConfig::default());
+    }
+
+    #[test]
+    fn test_rejects_shared_ports_and_names() {
+        let shared_port = r#"
+[[radios]]
+name = "A"
+port = "/dev/ttyUSB0"
+protocol = "Kenwood"
+
+[[radios]]
+name = "B"
+port = "/dev/ttyUSB0"
+protocol = "Kenwood"
+"#;
+        assert!(matches!(
+            DaemonConfig::parse(shared_port),
+            Err(ConfigError::Invalid(_))
+        ));
+
+        let shared_name = shared_port
+            .replacen("ttyUSB0", "ttyUSB1", 1)
+            .replace("\"B\"", "\"A\"");
+        assert!(matches!(
+            DaemonConfig::parse(&shared_name),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_unknown_protocol_is_a_parse_error() {
+        let text = "[[radios]]\nname = \"A\"\nport = \"COM3\"\nprotocol = \"Morse\"\n";
+        assert!(matches!(
+            DaemonConfig::parse(text),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_bad_metrics_address_is_a_parse_error() {
+        assert!(matches!(
+            DaemonConfig::parse("metrics = \"localhost\"\n"),
+            Err(ConfigError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_alert_urls_must_be_http() {
+        assert!(matches!(
+            DaemonConfig::parse("webhook_url = \"https://example.com/hook\"\n"),
+            Err(ConfigError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_only_line_changes_need_a_reopen() {
+        let radio = DaemonConfig::parse(EXAMPLE).unwrap().radios[0].clone();
+        let renamed = RadioConfig {
+            name: "K3 (run)".into(),
+            protocol: Protocol::Kenwood,
+            ..radio.clone()
+        };
+        assert!(!radio.needs_reopen(&renamed));
+        let faster = RadioConfig {
+            baud_rate: 57600,
+            ..radio.clone()
+        };
+        assert!(radio.needs_reopen(&faster));
+        let slower_adapter = RadioConfig {
+            settle_ms: Some(500),
+            ..radio.clone()
+        };
+        assert!(radio.needs_reopen(&slower_adapter));
+    }
+
+    #[test]
+    fn test_settle_delay() {
+        let config = DaemonConfig::parse(EXAMPLE).unwrap();
+        let serial = RadioConfig {
+            settle_ms: Some(300),
+            ..config.radios[0].clone()
+        };
+        assert_eq!(serial.settle_delay(), Duration::from_millis(300));
+        // Not a connected port, so the default
+        let unknown = RadioConfig {
+            port: "/dev/catapult-no-such-port".into(),
+            ..config.radios[0].clone()
+        };
+        assert_eq!(unknown.settle_delay(), cat_detect::DEFAULT_SETTLE);
+        let network = RadioConfig {
+            settle_ms: Some(300),
+            ..config.radios[1].clone()
+        };
+        assert_eq!(network.settle_delay(), Duration::ZERO);
+    }
+}
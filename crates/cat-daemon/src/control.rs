@@ -0,0 +1,234 @@
+//! What asks the daemon to do something
+//!
+//! A reload can come from SIGHUP, from the configuration file changing on
+//! disk, or from a `reload` line on the control socket. Each source runs as
+//! its own task and sends a [`Request`]; the daemon handles them one at a
+//! time, so two reloads never overlap.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::{mpsc, oneshot};
+
+/// How often the configuration file is checked for changes
+pub const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Something for the daemon to do
+#[derive(Debug)]
+pub enum Request {
+    /// Read the configuration file again and apply what changed
+    Reload {
+        /// What asked for it, for the log
+        reason: &'static str,
+        /// Where to send the report, if anyone is waiting for it
+        reply: Option<oneshot::Sender<String>>,
+    },
+    /// Describe what is running
+    Status { reply: oneshot::Sender<String> },
+    /// Close everything and exit
+    Shutdown,
+}
+
+/// Reload on SIGHUP; shut down on Ctrl-C or SIGTERM
+pub fn spawn_signals(tx: mpsc::Sender<Request>) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut hangup = signal(SignalKind::hangup())?;
+        let reload_tx = tx.clone();
+        tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                let request = Request::Reload {
+                    reason: "SIGHUP",
+                    reply: None,
+                };
+                if reload_tx.send(request).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        let terminate_tx = tx.clone();
+        tokio::spawn(async move {
+            terminate.recv().await;
+            let _ = terminate_tx.send(Request::Shutdown).await;
+        });
+    }
+
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        let _ = tx.send(Request::Shutdown).await;
+    });
+    Ok(())
+}
+
+/// When the file was last written, and how long it is
+fn file_stamp(path: &Path) -> Option<(SystemTime, u64)> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok()?, meta.len()))
+}
+
+/// Reload whenever the configuration file is rewritten
+///
+/// Polls instead of subscribing to file system events, so it works the same
+/// on every platform and when an editor replaces the file rather than
+/// writing to it. A half-written file fails to parse and changes nothing;
+/// the write that finishes it triggers another reload.
+pub fn spawn_file_watch(path: PathBuf, interval: Duration, tx: mpsc::Sender<Request>) {
+    tokio::spawn(async move {
+        let mut last = file_stamp(&path);
+        let mut ticks = tokio::time::interval(interval);
+        ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+        loop {
+            ticks.tick().await;
+            let stamp = file_stamp(&path);
+            if stamp == last || stamp.is_none() {
+                continue;
+            }
+            last = stamp;
+            let request = Request::Reload {
+                reason: "file changed",
+                reply: None,
+            };
+            if tx.send(request).await.is_err() {
+                break;
+            }
+        }
+    });
+}
+
+/// Answer `reload` and `status` requests on a Unix socket
+///
+/// Each connection sends one request line and gets the answer back before
+/// the socket is closed, e.g. `echo reload | nc -U /run/catapult/control.sock`.
+/// A socket left behind by an earlier run is replaced.
+#[cfg(unix)]
+pub fn spawn_control_socket(path: &Path, tx: mpsc::Sender<Request>) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::UnixListener;
+
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        if meta.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    let listener = UnixListener::bind(path)?;
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let (read, mut write) = stream.into_split();
+                let mut line = String::new();
+                if BufReader::new(read).read_line(&mut line).await.is_err() {
+                    return;
+                }
+                let (reply, answer) = oneshot::channel();
+                let request = match line.trim() {
+                    "reload" => Request::Reload {
+                        reason: "control socket",
+                        reply: Some(reply),
+                    },
+                    "status" => Request::Status { reply },
+                    other => {
+                        let message =
+                            format!("unknown request '{}'; try reload or status\n", other);
+                        let _ = write.write_all(message.as_bytes()).await;
+                        return;
+                    }
+                };
+                if tx.send(request).await.is_err() {
+                    return;
+                }
+                if let Ok(text) = answer.await {
+                    let _ = write.write_all(text.as_bytes()).await;
+                }
+            });
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("catapultd-{}-{}", name, std::process::id()))
+    }
+
+    #[tokio::test]
+    async fn test_file_change_requests_a_reload() {
+        let path = temp_path("watch.toml");
+        std::fs::write(&path, "").unwrap();
+        let (tx, mut rx) = mpsc::channel(4);
+        spawn_file_watch(path.clone(), Duration::from_millis(10), tx);
+
+        // Longer than before, so the change shows even on coarse timestamps
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        std::fs::write(&path, "switching_mode = \"Manual\"\n").unwrap();
+
+        let request = tokio::time::timeout(Duration::from_secs(2), rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(matches!(
+            request,
+            Request::Reload {
+                reason: "file changed",
+                ..
+            }
+        ));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_control_socket_answers_requests() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::UnixStream;
+
+        let path = temp_path("control.sock");
+        let (tx, mut rx) = mpsc::channel(4);
+        spawn_control_socket(&path, tx).unwrap();
+
+        // Stands in for the daemon's request loop
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                match request {
+                    Request::Reload {
+                        reply: Some(reply), ..
+                    } => {
+                        let _ = reply.send("no changes\n".into());
+                    }
+                    Request::Status { reply } => {
+                        let _ = reply.send("switching mode: Manual\n".into());
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        for (request, expected) in [
+            ("reload\n", "no changes\n"),
+            ("status\n", "switching mode: Manual\n"),
+        ] {
+            let mut stream = UnixStream::connect(&path).await.unwrap();
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut answer = String::new();
+            stream.read_to_string(&mut answer).await.unwrap();
+            assert_eq!(answer, expected);
+        }
+
+        let mut stream = UnixStream::connect(&path).await.unwrap();
+        stream.write_all(b"restart\n").await.unwrap();
+        let mut answer = String::new();
+        stream.read_to_string(&mut answer).await.unwrap();
+        assert!(answer.starts_with("unknown request 'restart'"));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
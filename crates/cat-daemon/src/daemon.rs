@@ -0,0 +1,508 @@
+//! The running radios and amplifier, and applying reloads to them
+//!
+//! What is running is the source of truth: [`Daemon::running_config`] is
+//! rebuilt from the open connections, so a change that was rejected is
+//! simply tried again on the next reload.
+
+use std::time::Duration;
+
+use cat_detect::describe_open_error;
+use cat_mux::{
+    AmplifierChannel, AmplifierChannelMeta, AsyncAmpConnection, AsyncRadioConnection,
+    MuxActorCommand, MuxClient, RadioChannelMeta, RadioHandle, RadioTaskCommand, SwitchingMode,
+    SyncPlan,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio_serial::SerialPortBuilderExt;
+
+use crate::config::{AmplifierConfig, DaemonConfig, RadioConfig};
+use crate::reload::{diff, Change, ReloadReport};
+
+/// How long a closing connection gets to let go of its port before it is
+/// stopped outright
+const CLOSE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// An open radio connection
+struct RunningRadio {
+    config: RadioConfig,
+    handle: RadioHandle,
+    task_tx: mpsc::Sender<RadioTaskCommand>,
+    task: JoinHandle<()>,
+}
+
+/// The open amplifier connection
+struct RunningAmp {
+    config: AmplifierConfig,
+    shutdown_tx: oneshot::Sender<()>,
+    task: JoinHandle<()>,
+}
+
+/// Radios and amplifier connected to a mux actor
+pub struct Daemon {
+    client: MuxClient,
+    switching_mode: SwitchingMode,
+    /// Settings only read at startup
+    startup: DaemonConfig,
+    radios: Vec<RunningRadio>,
+    amplifier: Option<RunningAmp>,
+}
+
+impl Daemon {
+    /// A daemon with nothing connected yet
+    ///
+    /// The control socket, metrics and status page addresses and the alert
+    /// URLs are taken from `startup`; they can't change on a reload.
+    pub fn new(client: MuxClient, startup: &DaemonConfig) -> Self {
+        Self {
+            client,
+            switching_mode: SwitchingMode::default(),
+            startup: DaemonConfig {
+                radios: Vec::new(),
+                amplifier: None,
+                ..startup.clone()
+            },
+            radios: Vec::new(),
+            amplifier: None,
+        }
+    }
+
+    /// The configuration that is actually running
+    pub fn running_config(&self) -> DaemonConfig {
+        DaemonConfig {
+            switching_mode: self.switching_mode,
+            radios: self.radios.iter().map(|r| r.config.clone()).collect(),
+            amplifier: self.amplifier.as_ref().map(|a| a.config.clone()),
+            ..self.startup.clone()
+        }
+    }
+
+    /// Apply the differences between what is running and `new`
+    pub async fn reload(&mut self, new: &DaemonConfig) -> ReloadReport {
+        let mut report = ReloadReport::default();
+        for change in diff(&self.running_config(), new) {
+            let description = change.to_string();
+            match self.apply(change).await {
+                Ok(()) => report.applied.push(description),
+                Err(e) => report.rejected.push(format!("{}: {}", description, e)),
+            }
+        }
+
+        // Keep the file's order, so the next diff lines up
+        self.radios.sort_by_key(|r| {
+            new.radios
+                .iter()
+                .position(|c| c.port == r.config.port)
+                .unwrap_or(usize::MAX)
+        });
+        report
+    }
+
+    /// One line per setting, radio and amplifier, with the active radio marked
+    pub async fn status(&self) -> String {
+        let active = match self.client.active_radio() {
+            Ok(reply) => reply.await.ok().flatten().map(|(handle, _)| handle),
+            Err(_) => None,
+        };
+        let mut lines = vec![format!("switching mode: {:?}", self.switching_mode)];
+        for radio in &self.radios {
+            lines.push(format!(
+                "radio {} on {} ({:?}){}",
+                radio.config.name,
+                radio.config.port,
+                radio.config.protocol,
+                if active == Some(radio.handle) {
+                    ", active"
+                } else {
+                    ""
+                }
+            ));
+        }
+        match &self.amplifier {
+            Some(amp) => lines.push(format!(
+                "amplifier on {} ({:?})",
+                amp.config.port, amp.config.protocol
+            )),
+            None => lines.push("no amplifier".to_string()),
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Close every connection and stop the mux
+    pub async fn shutdown(mut self) {
+        for radio in std::mem::take(&mut self.radios) {
+            self.close_radio(radio).await;
+        }
+        if let Some(amp) = self.amplifier.take() {
+            self.close_amplifier(amp).await;
+        }
+        let _ = self.client.shutdown();
+    }
+
+    async fn apply(&mut self, change: Change) -> Result<(), String> {
+        match change {
+            Change::RemoveRadio(radio) => {
+                let running = self.take_radio(&radio.port)?;
+                self.close_radio(running).await;
+                Ok(())
+            }
+            Change::ReopenRadio { old, new } => {
+                let running = self.take_radio(&old.port)?;
+                self.close_radio(running).await;
+                match self.open_radio(&new).await {
+                    Ok(running) => {
+                        self.radios.push(running);
+                        Ok(())
+                    }
+                    // Put it back the way it was
+                    Err(e) => match self.open_radio(&old).await {
+                        Ok(running) => {
+                            self.radios.push(running);
+                            Err(e)
+                        }
+                        Err(again) => Err(format!(
+                            "{}, and it couldn't be reopened as before: {}",
+                            e, again
+                        )),
+                    },
+                }
+            }
+            Change::RenameRadio { port, name } => {
+                let radio = self.radio_mut(&port)?;
+                radio.config.name = name.clone();
+                let handle = radio.handle;
+                self.client
+                    .try_send(MuxActorCommand::UpdateRadioMeta {
+                        handle,
+                        name: Some(name),
+                    })
+                    .map_err(|e| e.to_string())
+            }
+            Change::SetRadioProtocol { port, protocol } => {
+                let radio = self.radio_mut(&port)?;
+                radio.config.protocol = protocol;
+                let handle = radio.handle;
+                // The connection encodes requests, the mux parses replies
+                let _ = radio
+                    .task_tx
+                    .try_send(RadioTaskCommand::SetProtocol { protocol });
+                self.client
+                    .try_send(MuxActorCommand::SetRadioProtocol { handle, protocol })
+                    .map_err(|e| e.to_string())
+            }
+            Change::AddRadio(radio) => {
+                let running = self.open_radio(&radio).await?;
+                self.radios.push(running);
+                Ok(())
+            }
+            Change::SetSwitchingMode(mode) => {
+                self.client
+                    .try_send(MuxActorCommand::SetSwitchingMode { mode })
+                    .map_err(|e| e.to_string())?;
+                self.switching_mode = mode;
+                Ok(())
+            }
+            Change::ReplaceAmplifier(new) => {
+                let old = self.amplifier.take();
+                let old_config = old.as_ref().map(|a| a.config.clone());
+                if let Some(amp) = old {
+                    self.close_amplifier(amp).await;
+                }
+                let Some(new) = new else {
+                    return Ok(());
+                };
+                match self.open_amplifier(&new) {
+                    Ok(amp) => {
+                        self.amplifier = Some(amp);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        // Put the old one back, if there was one
+                        self.amplifier = old_config.and_then(|c| self.open_amplifier(&c).ok());
+                        Err(e)
+                    }
+                }
+            }
+            Change::NeedsRestart(_) => Err("needs a restart".to_string()),
+        }
+    }
+
+    fn take_radio(&mut self, port: &str) -> Result<RunningRadio, String> {
+        let index = self
+            .radios
+            .iter()
+            .position(|r| r.config.port == port)
+            .ok_or("not running")?;
+        Ok(self.radios.remove(index))
+    }
+
+    fn radio_mut(&mut self, port: &str) -> Result<&mut RunningRadio, String> {
+        self.radios
+            .iter_mut()
+            .find(|r| r.config.port == port)
+            .ok_or_else(|| "not running".to_string())
+    }
+
+    /// Register a radio with the mux and open its port
+    ///
+    /// A port that fails to open unregisters the radio again.
+    async fn open_radio(&self, config: &RadioConfig) -> Result<RunningRadio, String> {
+        let meta = RadioChannelMeta::new_real(
+            config.name.clone(),
+            config.port.clone(),
+            config.protocol,
+            config.civ_address,
+        );
+        let (task_tx, task_rx) = mpsc::channel(32);
+        let handle = self
+            .client
+            .register_radio(meta, Some(task_tx.clone()))
+            .map_err(|e| e.to_string())?
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let settle = config.settle_delay();
+        let task = if config.network {
+            AsyncRadioConnection::connect_tcp(handle, &config.port, config.protocol, &self.client)
+                .await
+                .map(|conn| spawn_radio(conn, config.civ_address, settle, task_rx))
+                .map_err(|e| e.to_string())
+        } else {
+            AsyncRadioConnection::connect(
+                handle,
+                &config.port,
+                &config.serial_line(),
+                config.protocol,
+                &self.client,
+            )
+            .map(|conn| spawn_radio(conn, config.civ_address, settle, task_rx))
+            .map_err(|e| describe_open_error(&config.port, &e))
+        };
+
+        match task {
+            Ok(task) => {
+                tracing::info!("Connected {} on {}", config.name, config.port);
+                Ok(RunningRadio {
+                    config: config.clone(),
+                    handle,
+                    task_tx,
+                    task,
+                })
+            }
+            Err(e) => {
+                let _ = self
+                    .client
+                    .try_send(MuxActorCommand::UnregisterRadio { handle });
+                Err(e)
+            }
+        }
+    }
+
+    async fn close_radio(&self, mut radio: RunningRadio) {
+        let _ = radio.task_tx.try_send(RadioTaskCommand::Shutdown);
+        // Still identifying the radio: it isn't reading commands yet
+        if tokio::time::timeout(CLOSE_TIMEOUT, &mut radio.task)
+            .await
+            .is_err()
+        {
+            radio.task.abort();
+            let _ = radio.task.await;
+        }
+        let _ = self.client.try_send(MuxActorCommand::UnregisterRadio {
+            handle: radio.handle,
+        });
+        tracing::info!("Closed {} on {}", radio.config.name, radio.config.port);
+    }
+
+    /// Open the amplifier's port and hand it to the mux
+    fn open_amplifier(&self, config: &AmplifierConfig) -> Result<RunningAmp, String> {
+        let stream = tokio_serial::new(&config.port, config.baud_rate)
+            .flow_control(config.flow_control.into())
+            .timeout(Duration::from_millis(100))
+            .open_native_async()
+            .map_err(|e| describe_open_error(&config.port, &e))?;
+
+        self.client
+            .try_send(MuxActorCommand::SetAmplifierConfig {
+                port: config.port.clone(),
+                protocol: config.protocol,
+                baud_rate: config.baud_rate,
+                civ_address: config.civ_address,
+                response_protocol: None,
+                strictness: None,
+            })
+            .map_err(|e| e.to_string())?;
+
+        let (amp_data_tx, amp_data_rx) = mpsc::channel::<Vec<u8>>(64);
+        let (urgent_tx, urgent_rx) = mpsc::channel::<Vec<u8>>(8);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (_response_tx, response_rx) = mpsc::channel::<Vec<u8>>(64);
+        let meta = AmplifierChannelMeta::new_real(
+            config.port.clone(),
+            config.protocol,
+            config.baud_rate,
+            config.civ_address,
+        )
+        .with_flow_control(config.flow_control.into());
+        let channel = AmplifierChannel::new(meta, amp_data_tx, response_rx).with_urgent(urgent_tx);
+        let backlog = channel.backlog.clone();
+        self.client
+            .try_send(MuxActorCommand::ConnectAmplifier { channel })
+            .map_err(|e| e.to_string())?;
+
+        let conn = AsyncAmpConnection::new(stream, &self.client)
+            .with_backlog(backlog)
+            .with_urgent(urgent_rx);
+        let task = tokio::spawn(conn.run(shutdown_rx, amp_data_rx));
+        tracing::info!("Connected amplifier on {}", config.port);
+
+        Ok(RunningAmp {
+            config: config.clone(),
+            shutdown_tx,
+            task,
+        })
+    }
+
+    async fn close_amplifier(&self, mut amp: RunningAmp) {
+        let _ = amp.shutdown_tx.send(());
+        if tokio::time::timeout(CLOSE_TIMEOUT, &mut amp.task)
+            .await
+            .is_err()
+        {
+            amp.task.abort();
+            let _ = amp.task.await;
+        }
+        let _ = self.client.try_send(MuxActorCommand::DisconnectAmplifier);
+        tracing::info!("Closed amplifier on {}", amp.config.port);
+    }
+}
+
+/// Identify the radio, turn on auto-info, then relay its traffic
+fn spawn_radio<T>(
+    mut conn: AsyncRadioConnection<T>,
+    civ_address: Option<u8>,
+    settle: Duration,
+    task_rx: mpsc::Receiver<RadioTaskCommand>,
+) -> JoinHandle<()>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        if let Some(addr) = civ_address {
+            conn.set_civ_address(addr);
+        }
+        // Some adapters drop what is written right after opening
+        tokio::time::sleep(settle).await;
+        let plan = SyncPlan::for_protocol(conn.protocol());
+        conn.run_sync_plan(plan).await;
+        conn.run_read_loop(task_rx).await;
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SerialFlowControl;
+    use cat_protocol::Protocol;
+    use tokio::net::TcpListener;
+    use tokio::runtime::Handle;
+
+    fn network_radio(name: &str, port: &str) -> RadioConfig {
+        RadioConfig {
+            name: name.into(),
+            port: port.into(),
+            protocol: Protocol::Kenwood,
+            baud_rate: 9600,
+            flow_control: SerialFlowControl::None,
+            civ_address: None,
+            network: true,
+            settle_ms: None,
+        }
+    }
+
+    /// Counts the connections a fake serial server accepts
+    async fn serial_server() -> (String, mpsc::UnboundedReceiver<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (accepted_tx, accepted_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut open = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                open.push(stream);
+                let _ = accepted_tx.send(());
+            }
+        });
+        (address, accepted_rx)
+    }
+
+    #[tokio::test]
+    async fn test_reload_keeps_unchanged_connections() {
+        let (client, _events) = MuxClient::start(&Handle::current(), 256);
+        let mut daemon = Daemon::new(client, &DaemonConfig::default());
+        let (first, mut first_accepts) = serial_server().await;
+        let (second, mut second_accepts) = serial_server().await;
+
+        let config = DaemonConfig {
+            radios: vec![network_radio("A", &first)],
+            ..Default::default()
+        };
+        let report = daemon.reload(&config).await;
+        assert_eq!(report.applied, vec![format!("add radio A on {}", first)]);
+        first_accepts.recv().await.unwrap();
+
+        let new = DaemonConfig {
+            switching_mode: SwitchingMode::Manual,
+            radios: vec![network_radio("Run", &first), network_radio("B", &second)],
+            ..Default::default()
+        };
+        let report = daemon.reload(&new).await;
+        assert_eq!(
+            report.applied,
+            vec![
+                format!("rename radio on {} to Run", first),
+                format!("add radio B on {}", second),
+                "set switching mode to Manual".to_string(),
+            ]
+        );
+        assert!(report.rejected.is_empty());
+        second_accepts.recv().await.unwrap();
+        // The first radio's connection was left open
+        assert!(first_accepts.try_recv().is_err());
+        assert_eq!(daemon.running_config(), new);
+
+        daemon.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn test_rejected_radio_leaves_the_rest_running() {
+        let (client, _events) = MuxClient::start(&Handle::current(), 256);
+        let mut daemon = Daemon::new(client, &DaemonConfig::default());
+        let (good, _accepts) = serial_server().await;
+        // Nothing listens here once the listener is gone
+        let closed = {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            listener.local_addr().unwrap().to_string()
+        };
+
+        let config = DaemonConfig {
+            radios: vec![network_radio("A", &good), network_radio("B", &closed)],
+            ..Default::default()
+        };
+        let report = daemon.reload(&config).await;
+        assert_eq!(report.applied, vec![format!("add radio A on {}", good)]);
+        assert_eq!(report.rejected.len(), 1);
+        assert!(report.rejected[0].starts_with(&format!("add radio B on {}: ", closed)));
+        assert_eq!(
+            daemon.running_config().radios,
+            vec![network_radio("A", &good)]
+        );
+
+        // Tried again on the next reload
+        let report = daemon.reload(&config).await;
+        assert_eq!(report.rejected.len(), 1);
+        assert!(report.applied.is_empty());
+
+        daemon.shutdown().await;
+    }
+}
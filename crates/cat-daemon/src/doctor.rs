@@ -0,0 +1,77 @@
+//! `catapultd --doctor`: check the configured ports without connecting
+//!
+//! Each serial port in the file is opened and closed again, and any that
+//! can't be opened are named with the reason, including the program holding
+//! the port when that is the problem.
+
+use std::fmt::Write;
+
+use cat_detect::describe_open_error;
+
+use crate::config::DaemonConfig;
+
+/// Whether `port` can be opened at `baud_rate`, or why not
+fn check_port(port: &str, baud_rate: u32) -> String {
+    match tokio_serial::new(port, baud_rate).open() {
+        Ok(_) => "ok".to_string(),
+        Err(e) => describe_open_error(port, &e),
+    }
+}
+
+/// One line per configured port
+pub fn report(config: &DaemonConfig) -> String {
+    let mut out = String::new();
+    for radio in &config.radios {
+        let result = if radio.network {
+            "network radio, not checked".to_string()
+        } else {
+            check_port(&radio.port, radio.baud_rate)
+        };
+        let _ = writeln!(out, "{} on {}: {}", radio.name, radio.port, result);
+    }
+    if let Some(amp) = &config.amplifier {
+        let result = check_port(&amp.port, amp.baud_rate);
+        let _ = writeln!(out, "amplifier on {}: {}", amp.port, result);
+    }
+    if out.is_empty() {
+        out.push_str("no ports configured\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_names_each_port() {
+        let config = DaemonConfig::parse(
+            r#"
+[[radios]]
+name = "Missing"
+port = "/dev/catapultd-no-such-port"
+protocol = "Kenwood"
+
+[[radios]]
+name = "Remote"
+port = "192.168.1.20:4532"
+network = true
+protocol = "Kenwood"
+"#,
+        )
+        .unwrap();
+        let report = report(&config);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("Missing on /dev/catapultd-no-such-port: "));
+        assert!(!lines[0].ends_with(": ok"));
+        assert_eq!(
+            lines[1],
+            "Remote on 192.168.1.20:4532: network radio, not checked"
+        );
+        assert_eq!(
+            super::report(&DaemonConfig::default()),
+            "no ports configured\n"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! catapultd: run the multiplexer without the app
+//!
+//! Reads a TOML file naming the radios, the amplifier and the switching
+//! mode (see [`config`]) and keeps them connected. The file is read again on
+//! SIGHUP, when it changes on disk, or on a `reload` request to the control
+//! socket, and only what changed is applied: radios whose port settings are
+//! the same keep their connections (see [`reload`]). Prometheus metrics, a
+//! status page and pushed alerts can be turned on as well (see [`monitor`]).
+
+mod config;
+mod control;
+mod daemon;
+mod doctor;
+mod monitor;
+mod reload;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use cat_mux::MuxClient;
+use tokio::runtime::Handle;
+use tokio::sync::mpsc;
+
+use config::DaemonConfig;
+use control::{Request, WATCH_INTERVAL};
+use daemon::Daemon;
+use monitor::Monitor;
+use reload::ReloadReport;
+
+const USAGE: &str = "\
+Usage: catapultd [OPTIONS] CONFIG
+
+Connect the radios and amplifier in CONFIG (a TOML file) to the
+multiplexer and keep them connected.
+
+The file is read again on SIGHUP, whenever it changes, and on a
+`reload` request to the control socket. Only what changed is applied;
+radios whose port settings didn't change stay connected.
+
+Options:
+      --no-watch  Don't reload when the file changes
+      --doctor    Check that each configured port can be opened, then exit
+  -h, --help      Show this help
+";
+
+struct Options {
+    config: PathBuf,
+    watch: bool,
+    doctor: bool,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut config = None;
+    let mut watch = true;
+    let mut doctor = false;
+
+    for arg in args {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--no-watch" => watch = false,
+            "--doctor" => doctor = true,
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if config.is_some() => return Err("only one config file can be given".into()),
+            _ => config = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let config = config.ok_or("no config file given")?;
+    Ok(Some(Options {
+        config,
+        watch,
+        doctor,
+    }))
+}
+
+/// Log a reload's outcome; rejected changes are warnings
+fn log_report(reason: &str, report: &ReloadReport) {
+    if report.applied.is_empty() && report.rejected.is_empty() {
+        tracing::info!("Reload ({}): no changes", reason);
+    }
+    for change in &report.applied {
+        tracing::info!("Reload ({}): {}", reason, change);
+    }
+    for change in &report.rejected {
+        tracing::warn!("Reload ({}) rejected: {}", reason, change);
+    }
+}
+
+async fn run(options: &Options) -> Result<(), String> {
+    let source = options.config.display();
+    let config = DaemonConfig::load(&options.config).map_err(|e| format!("{}: {}", source, e))?;
+
+    let monitor = Monitor::start(&config).await?;
+    let (client, events) = MuxClient::start(&Handle::current(), 256);
+    tokio::spawn(monitor.run(events));
+
+    let (request_tx, mut requests) = mpsc::channel(8);
+    control::spawn_signals(request_tx.clone()).map_err(|e| e.to_string())?;
+    if options.watch {
+        control::spawn_file_watch(options.config.clone(), WATCH_INTERVAL, request_tx.clone());
+    }
+    #[cfg(unix)]
+    if let Some(path) = &config.control_socket {
+        control::spawn_control_socket(path, request_tx.clone())
+            .map_err(|e| format!("{}: {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    if config.control_socket.is_some() {
+        tracing::warn!("The control socket is only available on Unix; ignoring it");
+    }
+    drop(request_tx);
+
+    let mut daemon = Daemon::new(client, &config);
+    log_report("startup", &daemon.reload(&config).await);
+
+    while let Some(request) = requests.recv().await {
+        match request {
+            Request::Reload { reason, reply } => {
+                // A file that fails to parse changes nothing
+                let report = match DaemonConfig::load(&options.config) {
+                    Ok(new) => daemon.reload(&new).await,
+                    Err(e) => ReloadReport::failed(e),
+                };
+                log_report(reason, &report);
+                if let Some(reply) = reply {
+                    let _ = reply.send(report.to_string());
+                }
+            }
+            Request::Status { reply } => {
+                let _ = reply.send(daemon.status().await);
+            }
+            Request::Shutdown => break,
+        }
+    }
+
+    tracing::info!("Shutting down");
+    daemon.shutdown().await;
+    if let Some(path) = &config.control_socket {
+        let _ = std::fs::remove_file(path);
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("catapultd: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    if options.doctor {
+        return match DaemonConfig::load(&options.config) {
+            Ok(config) => {
+                print!("{}", doctor::report(&config));
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("catapultd: {}: {}", options.config.display(), e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| "cat_daemon=info,cat_mux=info,cat_detect=info".into()),
+        )
+        .init();
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("catapultd: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    match runtime.block_on(run(&options)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("catapultd: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,195 @@
+//! Watching the station from elsewhere: Prometheus metrics, the status page
+//! and pushed alerts
+//!
+//! All three are fed from the mux's event stream by [`Monitor::run`], which
+//! also logs the mux's errors. The servers are started before the mux, so an
+//! address that is already in use stops the daemon at startup rather than
+//! leaving it running without them.
+
+use std::net::SocketAddr;
+
+use cat_mux::{
+    observe_status, run_metrics_server, run_status_server, AlertTargets, MetricsInput, MuxEvent,
+    SharedStatusBoard,
+};
+use tokio::net::TcpListener;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::config::DaemonConfig;
+
+/// Metrics input buffered before events are dropped
+const METRICS_CHANNEL_CAPACITY: usize = 1024;
+
+/// The servers and alert targets the mux's events are passed on to
+#[derive(Default)]
+pub struct Monitor {
+    metrics: Option<mpsc::Sender<MetricsInput>>,
+    board: SharedStatusBoard,
+    alerts: AlertTargets,
+    /// Stops the status page when dropped
+    status_shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl Monitor {
+    /// Start the metrics server and status page `config` asks for
+    pub async fn start(config: &DaemonConfig) -> Result<Self, String> {
+        let mut monitor = Monitor {
+            alerts: config.alert_targets(),
+            ..Default::default()
+        };
+        if let Some(addr) = config.metrics {
+            let listener = bind(addr, "metrics").await?;
+            let (tx, rx) = mpsc::channel(METRICS_CHANNEL_CAPACITY);
+            tokio::spawn(async move {
+                if let Err(e) = run_metrics_server(listener, rx).await {
+                    tracing::warn!("Metrics server on {} stopped: {}", addr, e);
+                }
+            });
+            monitor.metrics = Some(tx);
+        }
+        if let Some(addr) = config.status_page {
+            let listener = bind(addr, "status page").await?;
+            let (shutdown_tx, shutdown_rx) = oneshot::channel();
+            let board = monitor.board.clone();
+            tokio::spawn(async move {
+                if let Err(e) = run_status_server(listener, board, shutdown_rx).await {
+                    tracing::warn!("Status page on {} stopped: {}", addr, e);
+                }
+            });
+            monitor.status_shutdown = Some(shutdown_tx);
+        }
+        Ok(monitor)
+    }
+
+    /// Pass every mux event on until the mux stops
+    ///
+    /// Metrics input is dropped rather than holding up the mux when the
+    /// server falls behind, and alerts are pushed in the background.
+    pub async fn run(self, mut events: mpsc::Receiver<MuxEvent>) {
+        while let Some(event) = events.recv().await {
+            if let Some(tx) = &self.metrics {
+                let _ = tx.try_send(MetricsInput::EventQueueDepth(events.len()));
+                let _ = tx.try_send(MetricsInput::Event(Box::new(event.clone())));
+            }
+            if let Some(alert) = observe_status(&self.board, &event) {
+                tracing::warn!("{}: {}", alert.kind.title(), alert.message);
+                if !self.alerts.is_empty() {
+                    let targets = self.alerts.clone();
+                    tokio::spawn(async move {
+                        for failure in targets.push(&alert).await {
+                            tracing::warn!("Couldn't push alert to {}", failure);
+                        }
+                    });
+                }
+            }
+            if let MuxEvent::Error { source, message } = event {
+                tracing::warn!("{}: {}", source, message);
+            }
+        }
+    }
+}
+
+async fn bind(addr: SocketAddr, what: &str) -> Result<TcpListener, String> {
+    TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("{} on {}: {}", what, addr, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_mux::{RadioChannelMeta, RadioHandle};
+    use cat_protocol::Protocol;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    fn free_addr() -> SocketAddr {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    /// GET `path` until the response contains `expected`
+    async fn wait_for(addr: SocketAddr, path: &str, expected: &str) {
+        for _ in 0..50 {
+            if get(addr, path).await.contains(expected) {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+        panic!("{} never showed {}", path, expected);
+    }
+
+    async fn get(addr: SocketAddr, path: &str) -> String {
+        let mut socket = TcpStream::connect(addr).await.unwrap();
+        socket
+            .write_all(format!("GET {} HTTP/1.1\r\nHost: x\r\n\r\n", path).as_bytes())
+            .await
+            .unwrap();
+        let mut response = String::new();
+        socket.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_the_page_and_metrics() {
+        let config = DaemonConfig {
+            metrics: Some(free_addr()),
+            status_page: Some(free_addr()),
+            ..Default::default()
+        };
+        let monitor = Monitor::start(&config).await.unwrap();
+        let (events_tx, events_rx) = mpsc::channel(8);
+        let task = tokio::spawn(monitor.run(events_rx));
+
+        events_tx
+            .send(MuxEvent::RadioConnected {
+                handle: RadioHandle(1),
+                meta: RadioChannelMeta::new_real(
+                    "K3".to_string(),
+                    "/dev/ttyUSB0".to_string(),
+                    Protocol::Elecraft,
+                    None,
+                ),
+            })
+            .await
+            .unwrap();
+        events_tx
+            .send(MuxEvent::RadioDisconnected {
+                handle: RadioHandle(1),
+            })
+            .await
+            .unwrap();
+
+        let status_page = config.status_page.unwrap();
+        wait_for(status_page, "/status.json", "\"name\":\"K3\"").await;
+        wait_for(
+            status_page,
+            "/status.json",
+            "\"kind\":\"radio_disconnected\"",
+        )
+        .await;
+        wait_for(
+            config.metrics.unwrap(),
+            "/metrics",
+            "catapult_radio_connected{radio=\"K3\",handle=\"1\"} 0",
+        )
+        .await;
+
+        // The mux stopping stops the servers with it
+        drop(events_tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_address_in_use_stops_startup() {
+        let taken = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = DaemonConfig {
+            status_page: Some(taken.local_addr().unwrap()),
+            ..Default::default()
+        };
+        let error = Monitor::start(&config).await.err().unwrap();
+        assert!(error.starts_with("status page on 127.0.0.1:"));
+    }
+}
@@ -0,0 +1,287 @@
+//! Working out what a reload changes
+//!
+//! [`diff`] compares the running configuration with a new one and lists the
+//! smallest set of [`Change`]s that gets from one to the other. Radios are
+//! matched by port; a radio whose serial line settings are unchanged keeps
+//! its connection, and only its name or protocol is updated.
+
+use std::fmt;
+
+use cat_mux::SwitchingMode;
+use cat_protocol::Protocol;
+
+use crate::config::{AmplifierConfig, DaemonConfig, RadioConfig};
+
+/// One step of a reload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Close a radio that is no longer configured
+    RemoveRadio(RadioConfig),
+    /// Close a radio and open it again with new line settings
+    ReopenRadio { old: RadioConfig, new: RadioConfig },
+    /// Give a connected radio a new name
+    RenameRadio { port: String, name: String },
+    /// Parse and encode a connected radio's traffic in another protocol
+    SetRadioProtocol { port: String, protocol: Protocol },
+    /// Open a newly configured radio
+    AddRadio(RadioConfig),
+    /// Choose the active radio a different way
+    SetSwitchingMode(SwitchingMode),
+    /// Close the amplifier, and open it with the new settings if there are any
+    ReplaceAmplifier(Option<AmplifierConfig>),
+    /// A setting that is only read at startup changed
+    NeedsRestart(&'static str),
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Change::RemoveRadio(radio) => {
+                write!(f, "remove radio {} on {}", radio.name, radio.port)
+            }
+            Change::ReopenRadio { new, .. } => {
+                write!(f, "reopen radio {} on {}", new.name, new.port)
+            }
+            Change::RenameRadio { port, name } => {
+                write!(f, "rename radio on {} to {}", port, name)
+            }
+            Change::SetRadioProtocol { port, protocol } => {
+                write!(f, "switch radio on {} to {:?}", port, protocol)
+            }
+            Change::AddRadio(radio) => write!(f, "add radio {} on {}", radio.name, radio.port),
+            Change::SetSwitchingMode(mode) => write!(f, "set switching mode to {:?}", mode),
+            Change::ReplaceAmplifier(Some(amp)) => {
+                write!(f, "connect {:?} amplifier on {}", amp.protocol, amp.port)
+            }
+            Change::ReplaceAmplifier(None) => write!(f, "remove amplifier"),
+            Change::NeedsRestart(setting) => write!(f, "change {}", setting),
+        }
+    }
+}
+
+/// The changes that take `running` to `new`
+///
+/// Removals come first so a port moved from one radio to another is free
+/// by the time it is opened again.
+pub fn diff(running: &DaemonConfig, new: &DaemonConfig) -> Vec<Change> {
+    let find = |radios: &[RadioConfig], port: &str| -> Option<RadioConfig> {
+        radios.iter().find(|r| r.port == port).cloned()
+    };
+
+    let mut changes = Vec::new();
+
+    for old in &running.radios {
+        if find(&new.radios, &old.port).is_none() {
+            changes.push(Change::RemoveRadio(old.clone()));
+        }
+    }
+
+    for radio in &new.radios {
+        match find(&running.radios, &radio.port) {
+            None => {}
+            Some(old) if old.needs_reopen(radio) => changes.push(Change::ReopenRadio {
+                old,
+                new: radio.clone(),
+            }),
+            Some(old) => {
+                if old.name != radio.name {
+                    changes.push(Change::RenameRadio {
+                        port: radio.port.clone(),
+                        name: radio.name.clone(),
+                    });
+                }
+                if old.protocol != radio.protocol {
+                    changes.push(Change::SetRadioProtocol {
+                        port: radio.port.clone(),
+                        protocol: radio.protocol,
+                    });
+                }
+            }
+        }
+    }
+
+    for radio in &new.radios {
+        if find(&running.radios, &radio.port).is_none() {
+            changes.push(Change::AddRadio(radio.clone()));
+        }
+    }
+
+    if running.switching_mode != new.switching_mode {
+        changes.push(Change::SetSwitchingMode(new.switching_mode));
+    }
+
+    if running.amplifier != new.amplifier {
+        changes.push(Change::ReplaceAmplifier(new.amplifier.clone()));
+    }
+
+    if running.control_socket != new.control_socket {
+        changes.push(Change::NeedsRestart("control_socket"));
+    }
+
+    if running.metrics != new.metrics {
+        changes.push(Change::NeedsRestart("metrics"));
+    }
+
+    if running.status_page != new.status_page {
+        changes.push(Change::NeedsRestart("status_page"));
+    }
+
+    if running.ntfy_url != new.ntfy_url || running.webhook_url != new.webhook_url {
+        changes.push(Change::NeedsRestart("alert URLs"));
+    }
+
+    changes
+}
+
+/// What a reload did
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// Changes now in effect
+    pub applied: Vec<String>,
+    /// Changes that weren't made, and why
+    pub rejected: Vec<String>,
+}
+
+impl ReloadReport {
+    /// A reload that changed nothing because the file was unusable
+    pub fn failed(reason: impl fmt::Display) -> Self {
+        Self {
+            applied: Vec::new(),
+            rejected: vec![reason.to_string()],
+        }
+    }
+}
+
+impl fmt::Display for ReloadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.applied.is_empty() && self.rejected.is_empty() {
+            return writeln!(f, "no changes");
+        }
+        for change in &self.applied {
+            writeln!(f, "applied: {}", change)?;
+        }
+        for change in &self.rejected {
+            writeln!(f, "rejected: {}", change)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SerialFlowControl;
+
+    fn radio(name: &str, port: &str) -> RadioConfig {
+        RadioConfig {
+            name: name.into(),
+            port: port.into(),
+            protocol: Protocol::Kenwood,
+            baud_rate: 9600,
+            flow_control: SerialFlowControl::None,
+            civ_address: None,
+            network: false,
+            settle_ms: None,
+        }
+    }
+
+    fn config(radios: Vec<RadioConfig>) -> DaemonConfig {
+        DaemonConfig {
+            radios,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_unchanged_config_has_no_changes() {
+        let running = config(vec![radio("A", "COM3"), radio("B", "COM4")]);
+        assert!(diff(&running, &running.clone()).is_empty());
+    }
+
+    #[test]
+    fn test_radios_added_and_removed() {
+        let running = config(vec![radio("A", "COM3"), radio("B", "COM4")]);
+        let new = config(vec![radio("A", "COM3"), radio("C", "COM5")]);
+        assert_eq!(
+            diff(&running, &new),
+            vec![
+                Change::RemoveRadio(radio("B", "COM4")),
+                Change::AddRadio(radio("C", "COM5")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_name_and_protocol_change_keep_the_connection() {
+        let running = config(vec![radio("A", "COM3")]);
+        let mut renamed = radio("Run", "COM3");
+        renamed.protocol = Protocol::Elecraft;
+        assert_eq!(
+            diff(&running, &config(vec![renamed])),
+            vec![
+                Change::RenameRadio {
+                    port: "COM3".into(),
+                    name: "Run".into()
+                },
+                Change::SetRadioProtocol {
+                    port: "COM3".into(),
+                    protocol: Protocol::Elecraft
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_baud_change_reopens() {
+        let running = config(vec![radio("A", "COM3")]);
+        let mut faster = radio("A", "COM3");
+        faster.baud_rate = 38400;
+        assert_eq!(
+            diff(&running, &config(vec![faster.clone()])),
+            vec![Change::ReopenRadio {
+                old: radio("A", "COM3"),
+                new: faster
+            }]
+        );
+    }
+
+    #[test]
+    fn test_mode_amp_and_socket_changes() {
+        let running = DaemonConfig::default();
+        let new = DaemonConfig {
+            switching_mode: SwitchingMode::Manual,
+            control_socket: Some("/tmp/c.sock".into()),
+            metrics: Some("127.0.0.1:9464".parse().unwrap()),
+            status_page: Some("127.0.0.1:8073".parse().unwrap()),
+            webhook_url: Some("http://host/hook".into()),
+            amplifier: Some(AmplifierConfig {
+                port: "COM9".into(),
+                protocol: Protocol::Kenwood,
+                baud_rate: 9600,
+                flow_control: SerialFlowControl::None,
+                civ_address: None,
+            }),
+            ..Default::default()
+        };
+        let changes = diff(&running, &new);
+        assert_eq!(changes[0], Change::SetSwitchingMode(SwitchingMode::Manual));
+        assert!(matches!(changes[1], Change::ReplaceAmplifier(Some(_))));
+        assert_eq!(changes[2], Change::NeedsRestart("control_socket"));
+        assert_eq!(changes[3], Change::NeedsRestart("metrics"));
+        assert_eq!(changes[4], Change::NeedsRestart("status_page"));
+        assert_eq!(changes[5], Change::NeedsRestart("alert URLs"));
+    }
+
+    #[test]
+    fn test_report_text() {
+        assert_eq!(ReloadReport::default().to_string(), "no changes\n");
+        let report = ReloadReport {
+            applied: vec![Change::AddRadio(radio("A", "COM3")).to_string()],
+            rejected: vec!["add radio B on COM4: port is busy".into()],
+        };
+        assert_eq!(
+            report.to_string(),
+            "applied: add radio A on COM3\nrejected: add radio B on COM4: port is busy\n"
+        );
+    }
+}
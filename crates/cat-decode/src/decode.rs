@@ -0,0 +1,279 @@
+//! Splitting captures into frames and rendering them
+
+use cat_protocol::display::{
+    decode_and_annotate_with_hint, format_frame, format_frame_detailed, format_hex, parse_hex_dump,
+};
+use cat_protocol::{ControllerSideCodec, Protocol, RadioRequest, RadioResponse, RadioSideCodec};
+use serde_json::{json, Value};
+
+/// Protocol names accepted by `--protocol`
+pub const PROTOCOL_NAMES: &str = "auto, yaesu, yaesu-ascii, icom, kenwood, elecraft, flex";
+
+/// Parse a protocol name; `None` for `auto` or an unknown name
+pub fn parse_protocol(s: &str) -> Option<Protocol> {
+    match s.to_ascii_lowercase().as_str() {
+        "yaesu" => Some(Protocol::Yaesu),
+        "yaesu-ascii" => Some(Protocol::YaesuAscii),
+        "icom" | "civ" | "ci-v" => Some(Protocol::IcomCIV),
+        "kenwood" => Some(Protocol::Kenwood),
+        "elecraft" => Some(Protocol::Elecraft),
+        "flex" | "flexradio" => Some(Protocol::FlexRadio),
+        _ => None,
+    }
+}
+
+/// How input bytes are interpreted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputFormat {
+    /// Hex dump if it parses as one, text if it is printable, raw otherwise
+    Auto,
+    /// Text hex dump such as `FE FE 94 E0 03 FD`
+    Hex,
+    /// ASCII commands as pasted from a log; line breaks are ignored
+    Text,
+    /// Bytes exactly as captured from the serial port
+    Raw,
+}
+
+impl InputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "auto" => Some(Self::Auto),
+            "hex" => Some(Self::Hex),
+            "text" => Some(Self::Text),
+            "raw" => Some(Self::Raw),
+            _ => None,
+        }
+    }
+}
+
+/// Which side of the link the bytes were recorded on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent by the radio (parsed as responses)
+    FromRadio,
+    /// Sent to the radio by a logger or amplifier (parsed as requests)
+    ToRadio,
+}
+
+/// Turn input into the bytes that were on the wire
+pub fn read_input(input: &[u8], format: InputFormat) -> Result<Vec<u8>, String> {
+    let text = || std::str::from_utf8(input).map_err(|_| "input is not text".to_string());
+    match format {
+        InputFormat::Raw => Ok(input.to_vec()),
+        InputFormat::Hex => parse_hex_dump(text()?),
+        InputFormat::Text => Ok(strip_line_breaks(text()?)),
+        InputFormat::Auto => {
+            let Ok(text) = std::str::from_utf8(input) else {
+                return Ok(input.to_vec());
+            };
+            if let Ok(bytes) = parse_hex_dump(text) {
+                if !bytes.is_empty() {
+                    return Ok(bytes);
+                }
+            }
+            if text
+                .chars()
+                .all(|c| c.is_ascii_graphic() || c.is_ascii_whitespace())
+            {
+                Ok(strip_line_breaks(text))
+            } else {
+                Ok(input.to_vec())
+            }
+        }
+    }
+}
+
+fn strip_line_breaks(text: &str) -> Vec<u8> {
+    text.lines().flat_map(|line| line.trim().bytes()).collect()
+}
+
+/// One frame split off the input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub bytes: Vec<u8>,
+    /// Decoded to something other than `Unknown`
+    pub known: bool,
+}
+
+/// Input split into frames with one protocol's codec
+#[derive(Debug)]
+pub struct Decoded {
+    pub protocol: Protocol,
+    pub frames: Vec<Frame>,
+    /// Bytes not claimed by any frame (skipped garbage or a trailing partial frame)
+    pub unclaimed: usize,
+}
+
+impl Decoded {
+    fn known(&self) -> usize {
+        self.frames.iter().filter(|f| f.known).count()
+    }
+}
+
+/// Split `data` into frames with the codec for `protocol`
+pub fn split_frames(data: &[u8], protocol: Protocol, direction: Direction) -> Decoded {
+    let frames: Vec<Frame> = match direction {
+        Direction::FromRadio => {
+            let mut codec = RadioSideCodec::new(protocol);
+            codec.push_bytes(data);
+            std::iter::from_fn(|| codec.next_response_with_bytes())
+                .map(|(response, bytes)| Frame {
+                    bytes,
+                    known: !matches!(response, RadioResponse::Unknown { .. }),
+                })
+                .collect()
+        }
+        Direction::ToRadio => {
+            let mut codec = ControllerSideCodec::new(protocol);
+            codec.push_bytes(data);
+            std::iter::from_fn(|| codec.next_request_with_bytes())
+                .map(|(request, bytes)| Frame {
+                    bytes,
+                    known: !matches!(request, RadioRequest::Unknown { .. }),
+                })
+                .collect()
+        }
+    };
+
+    let claimed: usize = frames.iter().map(|f| f.bytes.len()).sum();
+    Decoded {
+        protocol,
+        frames,
+        unclaimed: data.len().saturating_sub(claimed),
+    }
+}
+
+/// Split `data` with whichever codec decodes most of it
+///
+/// Elecraft and FlexRadio share the Kenwood codec: a Kenwood-framed capture
+/// with `ZZ` commands is taken to be FlexRadio. Elecraft has to be named.
+pub fn split_frames_auto(data: &[u8], direction: Direction) -> Decoded {
+    let mut best: Option<Decoded> = None;
+    for protocol in [
+        Protocol::IcomCIV,
+        Protocol::Kenwood,
+        Protocol::YaesuAscii,
+        Protocol::Yaesu,
+    ] {
+        let decoded = split_frames(data, protocol, direction);
+        let better = best.as_ref().is_none_or(|b| {
+            (decoded.known(), std::cmp::Reverse(decoded.unclaimed))
+                > (b.known(), std::cmp::Reverse(b.unclaimed))
+        });
+        if better {
+            best = Some(decoded);
+        }
+    }
+
+    let mut best = best.expect("at least one candidate protocol");
+    if best.protocol == Protocol::Kenwood && best.frames.iter().any(|f| f.bytes.starts_with(b"ZZ"))
+    {
+        best.protocol = Protocol::FlexRadio;
+    }
+    best
+}
+
+/// Render one frame as text
+pub fn frame_text(frame: &Frame, protocol: Protocol, detailed: bool) -> String {
+    if detailed {
+        format_frame_detailed(&frame.bytes, protocol)
+    } else {
+        format_frame(&frame.bytes, protocol)
+    }
+}
+
+/// Render one frame as a JSON object
+///
+/// `fields` lists the annotated segments in byte order, as shown by
+/// `--detailed`.
+pub fn frame_json(frame: &Frame, protocol: Protocol, source: Option<&str>) -> Value {
+    let annotated = decode_and_annotate_with_hint(&frame.bytes, Some(protocol));
+    let (summary, fields) = match &annotated {
+        Some(a) => {
+            let mut segments: Vec<_> = a
+                .segments
+                .iter()
+                .filter(|s| s.range.start < s.range.end && s.range.end <= frame.bytes.len())
+                .collect();
+            segments.sort_by_key(|s| s.range.start);
+            let fields: Vec<Value> = segments
+                .iter()
+                .map(|s| {
+                    json!({
+                        "label": s.label,
+                        "hex": format_hex(&frame.bytes[s.range.clone()]),
+                        "value": s.value,
+                    })
+                })
+                .collect();
+            (Value::from(a.summary_text()), fields)
+        }
+        None => (Value::Null, Vec::new()),
+    };
+
+    let mut object = json!({
+        "protocol": protocol.name(),
+        "hex": format_hex(&frame.bytes),
+        "known": frame.known,
+        "summary": summary,
+        "fields": fields,
+    });
+    if let Some(source) = source {
+        object["source"] = Value::from(source);
+    }
+    object
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_input_formats() {
+        assert_eq!(
+            read_input(b"FE FE 94 E0 03 FD\n", InputFormat::Auto).unwrap(),
+            [0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD]
+        );
+        assert_eq!(
+            read_input(b"FA00014250000;\r\nMD2;\n", InputFormat::Auto).unwrap(),
+            b"FA00014250000;MD2;"
+        );
+        // Binary stays as captured, line feeds included
+        assert_eq!(
+            read_input(&[0x00, 0x0A, 0xFF], InputFormat::Auto).unwrap(),
+            [0x00, 0x0A, 0xFF]
+        );
+        assert!(read_input(b"FA;", InputFormat::Hex).is_err());
+    }
+
+    #[test]
+    fn test_auto_detects_protocol() {
+        let civ = [
+            0xFE, 0xFE, 0xE0, 0x94, 0x03, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ];
+        let decoded = split_frames_auto(&civ, Direction::FromRadio);
+        assert_eq!(decoded.protocol, Protocol::IcomCIV);
+        assert_eq!(decoded.frames.len(), 1);
+        assert_eq!(decoded.unclaimed, 0);
+
+        let kenwood = split_frames_auto(b"FA00014250000;MD2;", Direction::FromRadio);
+        assert_eq!(kenwood.protocol, Protocol::Kenwood);
+        assert_eq!(kenwood.frames.len(), 2);
+        assert!(kenwood.frames.iter().all(|f| f.known));
+
+        let flex = split_frames_auto(b"ZZFA00014250000;", Direction::FromRadio);
+        assert_eq!(flex.protocol, Protocol::FlexRadio);
+    }
+
+    #[test]
+    fn test_frame_json() {
+        let decoded = split_frames(b"FA00014250000;", Protocol::Kenwood, Direction::FromRadio);
+        let value = frame_json(&decoded.frames[0], Protocol::Kenwood, Some("log.txt"));
+        assert_eq!(value["summary"], "VFO A 14.250 MHz");
+        assert_eq!(value["known"], true);
+        assert_eq!(value["source"], "log.txt");
+        assert_eq!(value["fields"][0]["label"], "cmd");
+        assert_eq!(value["fields"][0]["hex"], "46 41");
+    }
+}
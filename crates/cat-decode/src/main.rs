@@ -0,0 +1,192 @@
+//! catapult-decode: decode CAT captures and pasted logs offline
+//!
+//! Reads each file named on the command line (or stdin), splits it into
+//! frames and prints one decoded line per frame, the same text the traffic
+//! monitor exports. Handy for logs other operators paste into issues and
+//! emails.
+
+mod decode;
+
+use std::io::{Read, Write};
+use std::process::ExitCode;
+
+use decode::{
+    frame_json, frame_text, parse_protocol, read_input, split_frames, split_frames_auto, Direction,
+    InputFormat, PROTOCOL_NAMES,
+};
+
+const USAGE: &str = "\
+Usage: catapult-decode [OPTIONS] [FILE]...
+
+Decode CAT protocol frames from captures or pasted logs. With no FILE, or
+when FILE is -, read stdin.
+
+Options:
+  -p, --protocol <NAME>  Protocol to decode as (default: auto)
+                         One of: auto, yaesu, yaesu-ascii, icom, kenwood, elecraft, flex
+  -i, --input <FORMAT>   hex, text, raw or auto (default: auto)
+                         hex: a hex dump such as `FE FE 94 E0 03 FD`
+                         text: ASCII commands; line breaks are ignored
+                         raw: bytes exactly as captured
+      --to-radio         Input was sent to the radio (decode as commands
+                         rather than radio reports)
+  -d, --detailed         Show every field of each frame
+      --json             Print one JSON object per frame
+  -h, --help             Show this help
+";
+
+struct Options {
+    protocol: Option<cat_protocol::Protocol>,
+    input: InputFormat,
+    direction: Direction,
+    detailed: bool,
+    json: bool,
+    files: Vec<String>,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        protocol: None,
+        input: InputFormat::Auto,
+        direction: Direction::FromRadio,
+        detailed: false,
+        json: false,
+        files: Vec::new(),
+    };
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        // Accept `--option=value` as well as `--option value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = |name: &str| {
+            inline
+                .map(str::to_string)
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", name))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-p" | "--protocol" => {
+                let name = value("--protocol")?;
+                options.protocol = match parse_protocol(&name) {
+                    Some(protocol) => Some(protocol),
+                    None if name.eq_ignore_ascii_case("auto") => None,
+                    None => {
+                        return Err(format!(
+                            "unknown protocol '{}' (expected one of: {})",
+                            name, PROTOCOL_NAMES
+                        ))
+                    }
+                };
+            }
+            "-i" | "--input" => {
+                let name = value("--input")?;
+                options.input = InputFormat::parse(&name).ok_or_else(|| {
+                    format!(
+                        "unknown input format '{}' (expected hex, text, raw or auto)",
+                        name
+                    )
+                })?;
+            }
+            "--to-radio" => options.direction = Direction::ToRadio,
+            "-d" | "--detailed" => options.detailed = true,
+            "--json" => options.json = true,
+            "-" => options.files.push(arg),
+            _ if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ => options.files.push(arg),
+        }
+    }
+
+    if options.files.is_empty() {
+        options.files.push("-".to_string());
+    }
+    Ok(Some(options))
+}
+
+fn read_source(name: &str) -> std::io::Result<Vec<u8>> {
+    if name == "-" {
+        let mut buf = Vec::new();
+        std::io::stdin().read_to_end(&mut buf)?;
+        Ok(buf)
+    } else {
+        std::fs::read(name)
+    }
+}
+
+fn run(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let many = options.files.len() > 1;
+
+    for (i, name) in options.files.iter().enumerate() {
+        let source = if name == "-" { "stdin" } else { name.as_str() };
+        let input = read_source(name).map_err(|e| format!("{}: {}", source, e))?;
+        let data = read_input(&input, options.input).map_err(|e| format!("{}: {}", source, e))?;
+
+        let decoded = match options.protocol {
+            Some(protocol) => split_frames(&data, protocol, options.direction),
+            None => split_frames_auto(&data, options.direction),
+        };
+
+        let write_err = |e: std::io::Error| e.to_string();
+        if many && !options.json {
+            if i > 0 {
+                writeln!(out).map_err(write_err)?;
+            }
+            writeln!(out, "== {} ({}) ==", source, decoded.protocol.name()).map_err(write_err)?;
+        }
+        for frame in &decoded.frames {
+            if options.json {
+                let value = frame_json(frame, decoded.protocol, many.then_some(source));
+                writeln!(out, "{}", value).map_err(write_err)?;
+            } else {
+                writeln!(
+                    out,
+                    "{}",
+                    frame_text(frame, decoded.protocol, options.detailed)
+                )
+                .map_err(write_err)?;
+            }
+        }
+
+        if decoded.frames.is_empty() {
+            match options.protocol {
+                Some(protocol) => eprintln!("{}: no {} frames found", source, protocol.name()),
+                None => eprintln!("{}: no frames found", source),
+            }
+        }
+        if decoded.unclaimed > 0 {
+            eprintln!(
+                "{}: {} bytes not part of any frame",
+                source, decoded.unclaimed
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("catapult-decode: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let stdout = std::io::stdout();
+    match run(&options, &mut stdout.lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("catapult-decode: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
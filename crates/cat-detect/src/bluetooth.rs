@@ -0,0 +1,132 @@
+//! Bluetooth serial (SPP) ports
+//!
+//! Radios such as the Icom IC-705 carry CI-V over the Bluetooth Serial Port
+//! Profile. Once paired, the operating system surfaces the link as an
+//! ordinary serial port: `/dev/rfcommN` on Linux (after `rfcomm bind`),
+//! `/dev/cu.<name>` on macOS, and an outgoing COM port on Windows. The mux
+//! then talks to it like any other port. The baud rate is not used on the
+//! air, so any setting works.
+//!
+//! A radio that hasn't been paired (or, on Linux, bound to an RFCOMM device)
+//! has no port to list, so [`pairing_hint`] explains how to get one.
+
+use crate::SerialPortInfo;
+
+/// Whether a port name is a Bluetooth serial device by its naming alone
+///
+/// Covers ports enumerated without their transport: Linux RFCOMM devices and
+/// macOS's `Bluetooth-Incoming-Port`.
+pub fn is_bluetooth_port_name(port: &str) -> bool {
+    let name = port.rsplit('/').next().unwrap_or(port);
+    name.starts_with("rfcomm") || name.contains("Bluetooth")
+}
+
+/// Drop macOS `/dev/tty.*` Bluetooth ports that have a `/dev/cu.*` twin
+///
+/// Opening the `tty.` side waits for carrier detect, which an SPP link
+/// never raises, so only the `cu.` side is usable.
+pub(crate) fn drop_dialin_twins(ports: &mut Vec<SerialPortInfo>) {
+    let callout: Vec<String> = ports
+        .iter()
+        .filter_map(|p| p.port.strip_prefix("/dev/cu."))
+        .map(str::to_string)
+        .collect();
+    ports.retain(|p| {
+        !(p.bluetooth
+            && p.port
+                .strip_prefix("/dev/tty.")
+                .is_some_and(|name| callout.iter().any(|c| c == name)))
+    });
+}
+
+/// How to pair a Bluetooth radio and get a serial port for it, on this platform
+pub fn pairing_hint() -> String {
+    let os = if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "macos") {
+        "macos"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        "other"
+    };
+    hint_for(os)
+}
+
+fn hint_for(os: &str) -> String {
+    let radio = "On the IC-705: MENU > SET > Bluetooth Set > Bluetooth ON, \
+                 Data Device Set > Serialport Function = CI-V (Echo Back OFF), \
+                 then <<Pairing Reception>>.";
+    let host = match os {
+        "linux" => {
+            "Pair and trust it with bluetoothctl (scan on, pair <MAC>, trust <MAC>), \
+             then bind it to a serial device: sudo rfcomm bind 0 <MAC> 1. \
+             /dev/rfcomm0 appears after Refresh; the binding is lost on reboot, \
+             and your user needs the dialout group to open it."
+        }
+        "macos" => {
+            "Pair it in System Settings > Bluetooth, then Refresh. \
+             Use the /dev/cu.<name> port, not /dev/tty.<name>."
+        }
+        "windows" => {
+            "Pair it in Settings > Bluetooth & devices, then add an outgoing COM \
+             port for it under More Bluetooth settings > COM Ports and Refresh."
+        }
+        _ => "Pair it with the system's Bluetooth settings and bind it to a serial port.",
+    };
+    format!("{} {}", radio, host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(name: &str, bluetooth: bool) -> SerialPortInfo {
+        SerialPortInfo {
+            port: name.to_string(),
+            vid: None,
+            pid: None,
+            serial_number: None,
+            interface: None,
+            manufacturer: None,
+            product: None,
+            bluetooth,
+        }
+    }
+
+    #[test]
+    fn test_port_names() {
+        assert!(is_bluetooth_port_name("/dev/rfcomm0"));
+        assert!(is_bluetooth_port_name("/dev/cu.Bluetooth-Incoming-Port"));
+        assert!(!is_bluetooth_port_name("/dev/ttyUSB0"));
+        assert!(!is_bluetooth_port_name("COM3"));
+    }
+
+    #[test]
+    fn test_drop_dialin_twins() {
+        let mut ports = vec![
+            port("/dev/cu.IC-705", true),
+            port("/dev/tty.IC-705", true),
+            port("/dev/tty.usbserial-1410", false),
+            port("/dev/cu.usbserial-1410", false),
+        ];
+        drop_dialin_twins(&mut ports);
+        let names: Vec<_> = ports.iter().map(|p| p.port.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "/dev/cu.IC-705",
+                "/dev/tty.usbserial-1410",
+                "/dev/cu.usbserial-1410"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pairing_hints() {
+        assert!(hint_for("linux").contains("rfcomm bind"));
+        assert!(hint_for("macos").contains("/dev/cu."));
+        assert!(hint_for("windows").contains("COM Ports"));
+        assert!(hint_for("other").starts_with("On the IC-705"));
+    }
+}
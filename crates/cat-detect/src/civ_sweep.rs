@@ -0,0 +1,328 @@
+//! CI-V bus discovery
+//!
+//! Icom radios only answer commands sent to their CI-V address, at the baud
+//! rate they are set to, so a radio whose menu settings aren't known can be
+//! hard to find. [`sweep_civ_port`] works through the combinations on one
+//! port: at each baud rate it sends a read-ID command (`0x19 0x00`) to every
+//! address in [`CivSweepConfig::addresses`] and reports each device that
+//! answers. The ID a radio returns is its model's default address, so the
+//! model is identified even when its address has been changed. By default
+//! only the models' default addresses are asked; [`all_addresses`] covers a
+//! radio moved to any other one, at the cost of a much longer sweep.
+//!
+//! All devices on a bus share one baud rate, so the sweep stops after the
+//! first rate anything answered at. Radios set to "Auto" answer at any rate.
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use cat_protocol::icom::{self, IdReply};
+use cat_protocol::models::{ProtocolId, RadioDatabase};
+use cat_protocol::RadioModel;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::time::{timeout_at, Instant};
+use tracing::{debug, info};
+
+use crate::error::DetectError;
+use crate::port_conflict::{describe_open_error, is_port_busy};
+use crate::scanner::ScanCancel;
+use crate::usb_quirks::port_settle_delay;
+
+/// What to sweep
+#[derive(Debug, Clone)]
+pub struct CivSweepConfig {
+    /// Baud rates to try, in order
+    pub baud_rates: Vec<u32>,
+    /// CI-V addresses to ask at each rate
+    pub addresses: Vec<u8>,
+    /// How long each address gets to answer
+    pub reply_timeout: Duration,
+    /// Wait after opening the port at each rate (None = the adapter's own,
+    /// from [`crate::usb_quirks`])
+    pub settle: Option<Duration>,
+}
+
+impl Default for CivSweepConfig {
+    fn default() -> Self {
+        Self {
+            baud_rates: vec![19200, 9600, 115200, 38400, 4800],
+            addresses: known_addresses(),
+            reply_timeout: Duration::from_millis(150),
+            settle: None,
+        }
+    }
+}
+
+/// Default CI-V addresses of the radios in the model database
+pub fn known_addresses() -> Vec<u8> {
+    let addresses: BTreeSet<u8> = RadioDatabase::icom_radios()
+        .filter_map(|m| match m.protocol_id {
+            ProtocolId::CivAddress(address) => Some(address),
+            _ => None,
+        })
+        .collect();
+    addresses.into_iter().collect()
+}
+
+/// Every address a radio can have, the models' defaults first
+///
+/// 0x00 is the broadcast address and 0xE0 up are controllers, so neither is
+/// asked.
+pub fn all_addresses() -> Vec<u8> {
+    let mut addresses = known_addresses();
+    let others: Vec<u8> = (0x01..0xE0).filter(|a| !addresses.contains(a)).collect();
+    addresses.extend(others);
+    addresses
+}
+
+/// A device that answered on the CI-V bus
+#[derive(Debug, Clone)]
+pub struct CivDevice {
+    /// Baud rate it answered at
+    pub baud_rate: u32,
+    /// Address it answered from
+    pub address: u8,
+    /// ID it reported (its model's default address); `None` if it answered
+    /// NG to read ID
+    pub id: Option<u8>,
+    /// Model, from the ID (or the address when there is no ID)
+    pub model: Option<RadioModel>,
+}
+
+impl CivDevice {
+    fn from_reply(reply: IdReply, baud_rate: u32) -> Self {
+        Self {
+            baud_rate,
+            address: reply.address,
+            id: reply.id,
+            model: RadioDatabase::by_civ_address(reply.id.unwrap_or(reply.address)),
+        }
+    }
+
+    /// Whether the address differs from the model's default
+    pub fn address_changed(&self) -> bool {
+        self.id.is_some_and(|id| id != self.address)
+    }
+}
+
+/// Progress of a sweep, sent as it happens
+#[derive(Debug, Clone)]
+pub enum CivSweepEvent {
+    /// Started on a baud rate
+    Baud { baud_rate: u32 },
+    /// Asking one address
+    Asking { baud_rate: u32, address: u8 },
+    /// A device answered
+    Found(CivDevice),
+}
+
+/// Sweep `port` for CI-V devices
+///
+/// Progress goes to `events`; the devices found are also returned, in
+/// address order, once the sweep is done or `cancel` fires. Fails only if
+/// the port can't be opened.
+pub async fn sweep_civ_port(
+    port: &str,
+    config: &CivSweepConfig,
+    events: mpsc::UnboundedSender<CivSweepEvent>,
+    cancel: ScanCancel,
+) -> Result<Vec<CivDevice>, DetectError> {
+    use tokio_serial::SerialPortBuilderExt;
+
+    info!(
+        "Sweeping {} for CI-V devices: {} address(es) at up to {} baud rate(s)",
+        port,
+        config.addresses.len(),
+        config.baud_rates.len()
+    );
+    let settle = config.settle.unwrap_or_else(|| port_settle_delay(port));
+    let mut devices = Vec::new();
+    for &baud_rate in &config.baud_rates {
+        if cancel.is_cancelled() {
+            break;
+        }
+        let _ = events.send(CivSweepEvent::Baud { baud_rate });
+        let mut stream = tokio_serial::new(port, baud_rate)
+            .timeout(Duration::from_millis(100))
+            .open_native_async()
+            .map_err(|e| match is_port_busy(&e) {
+                true => DetectError::PortInUse(describe_open_error(port, &e)),
+                false => e.into(),
+            })?;
+        // Some adapters drop what is written right after opening
+        tokio::time::sleep(settle).await;
+
+        tokio::select! {
+            found = sweep_stream(&mut stream, baud_rate, config, &events) => devices = found,
+            _ = cancel.cancelled() => break,
+        }
+        if !devices.is_empty() {
+            break;
+        }
+    }
+    info!("CI-V sweep of {} found {} device(s)", port, devices.len());
+    Ok(devices)
+}
+
+/// Ask every configured address for its ID over `stream`, at `baud_rate`
+async fn sweep_stream<S>(
+    stream: &mut S,
+    baud_rate: u32,
+    config: &CivSweepConfig,
+    events: &mpsc::UnboundedSender<CivSweepEvent>,
+) -> Vec<CivDevice>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut devices: Vec<CivDevice> = Vec::new();
+    for &address in &config.addresses {
+        // A device found earlier may already have answered for this address
+        if devices.iter().any(|d| d.address == address) {
+            continue;
+        }
+        let _ = events.send(CivSweepEvent::Asking { baud_rate, address });
+        if let Err(e) = stream.write_all(&icom::read_id_command(address)).await {
+            debug!("CI-V sweep write failed at {} baud: {}", baud_rate, e);
+            break;
+        }
+        for reply in read_replies(stream, address, config.reply_timeout).await {
+            if devices.iter().any(|d| d.address == reply.address) {
+                continue;
+            }
+            let device = CivDevice::from_reply(reply, baud_rate);
+            info!(
+                "CI-V device at 0x{:02X} ({} baud): {}",
+                device.address,
+                baud_rate,
+                device
+                    .model
+                    .as_ref()
+                    .map(|m| m.model.as_str())
+                    .unwrap_or("unknown model")
+            );
+            let _ = events.send(CivSweepEvent::Found(device.clone()));
+            devices.push(device);
+        }
+    }
+    devices.sort_by_key(|d| d.address);
+    devices
+}
+
+/// Read until `address` answers or `reply_timeout` passes
+async fn read_replies<S>(stream: &mut S, address: u8, reply_timeout: Duration) -> Vec<IdReply>
+where
+    S: AsyncRead + Unpin,
+{
+    let deadline = Instant::now() + reply_timeout;
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 64];
+    loop {
+        match timeout_at(deadline, stream.read(&mut chunk)).await {
+            Ok(Ok(n)) if n > 0 => {
+                buf.extend_from_slice(&chunk[..n]);
+                let replies = icom::parse_id_replies(&buf);
+                if replies.iter().any(|r| r.address == address) {
+                    return replies;
+                }
+            }
+            _ => return icom::parse_id_replies(&buf),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{duplex, DuplexStream};
+
+    /// A CI-V bus with radios at `(address, id)`, echoing what is sent
+    fn bus(radios: Vec<(u8, Option<u8>)>) -> DuplexStream {
+        let (ours, mut theirs) = duplex(1024);
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok(n) = theirs.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                let frame = buf[..n].to_vec();
+                let mut out = frame.clone();
+                if let Some(&(address, id)) = radios.iter().find(|(a, _)| *a == frame[2]) {
+                    out.extend(match id {
+                        Some(id) => vec![0xFE, 0xFE, 0xE0, address, 0x19, 0x00, id, 0xFD],
+                        None => vec![0xFE, 0xFE, 0xE0, address, 0xFA, 0xFD],
+                    });
+                }
+                if theirs.write_all(&out).await.is_err() {
+                    break;
+                }
+            }
+        });
+        ours
+    }
+
+    fn config(addresses: Vec<u8>) -> CivSweepConfig {
+        CivSweepConfig {
+            baud_rates: vec![19200],
+            addresses,
+            reply_timeout: Duration::from_millis(50),
+            settle: Some(Duration::ZERO),
+        }
+    }
+
+    #[test]
+    fn test_known_addresses_include_common_radios() {
+        let addresses = known_addresses();
+        assert!(addresses.contains(&0x94)); // IC-7300
+        assert!(addresses.contains(&0xA4)); // IC-705
+        assert!(addresses.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_all_addresses_cover_the_range_once() {
+        let addresses = all_addresses();
+        assert_eq!(addresses.len(), 0xDF);
+        assert_eq!(addresses[..known_addresses().len()], known_addresses()[..]);
+        assert!(addresses.contains(&0x01) && addresses.contains(&0xDF));
+        assert!(!addresses.contains(&0x00) && !addresses.contains(&0xE0));
+        let unique: BTreeSet<u8> = addresses.iter().copied().collect();
+        assert_eq!(unique.len(), addresses.len());
+    }
+
+    #[tokio::test]
+    async fn test_sweep_finds_every_device_on_the_bus() {
+        // An IC-7300 moved to 0x70, and an old radio that answers NG
+        let mut stream = bus(vec![(0x70, Some(0x94)), (0x58, None)]);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let devices = sweep_stream(&mut stream, 19200, &config(vec![0x58, 0x70, 0x94]), &tx).await;
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!((devices[0].address, devices[0].id), (0x58, None));
+        assert!(!devices[0].address_changed());
+        assert_eq!(devices[1].address, 0x70);
+        assert!(devices[1].address_changed());
+        assert_eq!(
+            devices[1].model.as_ref().map(|m| m.model.as_str()),
+            Some("IC-7300")
+        );
+
+        let mut found = 0;
+        let mut asked = 0;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                CivSweepEvent::Found(_) => found += 1,
+                CivSweepEvent::Asking { .. } => asked += 1,
+                CivSweepEvent::Baud { .. } => {}
+            }
+        }
+        assert_eq!((found, asked), (2, 3));
+    }
+
+    #[tokio::test]
+    async fn test_silent_bus_finds_nothing() {
+        let mut stream = bus(Vec::new());
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let devices = sweep_stream(&mut stream, 9600, &config(vec![0x94, 0xA4]), &tx).await;
+        assert!(devices.is_empty());
+    }
+}
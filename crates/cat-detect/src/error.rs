@@ -12,4 +12,8 @@ pub enum DetectError {
     /// Serial port error
     #[error("serial port error: {0}")]
     SerialPort(#[from] serialport::Error),
+
+    /// Another program has the port open (names it where possible)
+    #[error("{0}")]
+    PortInUse(String),
 }
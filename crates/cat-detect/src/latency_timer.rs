@@ -0,0 +1,158 @@
+//! FTDI latency timer inspection and adjustment
+//!
+//! FTDI USB serial chips buffer received bytes until 62 have arrived or the
+//! latency timer expires, 16 ms by default. At CAT baud rates that means
+//! most frames reach the host in pieces, up to 16 ms late. Setting the timer
+//! to 1 ms delivers bytes almost as they arrive.
+//!
+//! Linux exposes the timer in sysfs, where it can be read and (with write
+//! access to the attribute) changed. On Windows it lives in the driver's
+//! registry settings and is changed through Device Manager, so only a hint is
+//! offered there.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::SerialPortInfo;
+
+/// FTDI's USB vendor ID
+pub const FTDI_VID: u16 = 0x0403;
+
+/// Latency timer FTDI drivers default to, in milliseconds
+pub const FTDI_DEFAULT_LATENCY_MS: u8 = 16;
+
+/// Latency timer worth setting for CAT traffic, in milliseconds
+pub const RECOMMENDED_LATENCY_MS: u8 = 1;
+
+/// Whether a port is an FTDI adapter
+pub fn is_ftdi(info: &SerialPortInfo) -> bool {
+    info.vid == Some(FTDI_VID)
+}
+
+/// Sysfs attribute holding a port's latency timer (Linux)
+///
+/// Follows symlinks such as `/dev/serial/by-id/...` to the tty they name.
+pub fn sysfs_latency_path(port: &str) -> Option<PathBuf> {
+    let resolved = std::fs::canonicalize(port).unwrap_or_else(|_| PathBuf::from(port));
+    let tty = resolved.file_name()?.to_str()?;
+    Some(
+        Path::new("/sys/bus/usb-serial/devices")
+            .join(tty)
+            .join("latency_timer"),
+    )
+}
+
+/// Read a port's latency timer in milliseconds
+///
+/// `None` when the platform or driver doesn't expose it.
+pub fn read_latency_timer(port: &str) -> Option<u8> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let text = std::fs::read_to_string(sysfs_latency_path(port)?).ok()?;
+    text.trim().parse().ok()
+}
+
+/// Set a port's latency timer in milliseconds
+///
+/// Only Linux supports this; writing the attribute usually needs root or a
+/// udev rule (see [`latency_timer_hint`]).
+pub fn set_latency_timer(port: &str, ms: u8) -> io::Result<()> {
+    if !cfg!(target_os = "linux") {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "the latency timer can only be set from here on Linux",
+        ));
+    }
+    let path = sysfs_latency_path(port)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "not a device path"))?;
+    std::fs::write(path, format!("{}\n", ms))
+}
+
+/// How to lower an FTDI port's latency timer by hand, for this platform
+///
+/// `None` for ports that aren't FTDI adapters.
+pub fn latency_timer_hint(info: &SerialPortInfo) -> Option<String> {
+    let os = if cfg!(target_os = "windows") {
+        "windows"
+    } else if cfg!(target_os = "linux") {
+        "linux"
+    } else {
+        "other"
+    };
+    hint_for(os, info)
+}
+
+fn hint_for(os: &str, info: &SerialPortInfo) -> Option<String> {
+    if !is_ftdi(info) {
+        return None;
+    }
+    let ms = RECOMMENDED_LATENCY_MS;
+    Some(match os {
+        "windows" => {
+            let device = match (info.pid, &info.serial_number) {
+                (Some(pid), Some(serial)) => format!("VID_0403+PID_{:04X}+{}A", pid, serial),
+                _ => "<device>".to_string(),
+            };
+            format!(
+                "Device Manager > Ports > {} > Port Settings > Advanced > Latency Timer = {} ms \
+                 (stored as LatencyTimer under \
+                 HKLM\\SYSTEM\\CurrentControlSet\\Enum\\FTDIBUS\\{}\\0000\\Device Parameters)",
+                info.port, ms, device
+            )
+        }
+        "linux" => {
+            let path = sysfs_latency_path(&info.port)?;
+            format!(
+                "echo {} | sudo tee {}, or permanently with a udev rule: \
+                 ACTION==\"add\", SUBSYSTEM==\"usb-serial\", DRIVERS==\"ftdi_sio\", \
+                 ATTR{{latency_timer}}=\"{}\"",
+                ms,
+                path.display(),
+                ms
+            )
+        }
+        _ => format!("Set the FTDI driver's latency timer to {} ms", ms),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(name: &str, vid: u16) -> SerialPortInfo {
+        SerialPortInfo {
+            port: name.to_string(),
+            vid: Some(vid),
+            pid: Some(0x6001),
+            serial_number: Some("A50285BI".to_string()),
+            interface: None,
+            manufacturer: None,
+            product: None,
+            bluetooth: false,
+        }
+    }
+
+    #[test]
+    fn test_sysfs_path_uses_tty_name() {
+        assert_eq!(
+            sysfs_latency_path("/dev/ttyUSB3"),
+            Some(PathBuf::from(
+                "/sys/bus/usb-serial/devices/ttyUSB3/latency_timer"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_hints_only_for_ftdi() {
+        assert!(hint_for("linux", &port("/dev/ttyACM0", 0x10C4)).is_none());
+
+        let linux = hint_for("linux", &port("/dev/ttyUSB0", FTDI_VID)).unwrap();
+        assert!(linux.contains("/sys/bus/usb-serial/devices/ttyUSB0/latency_timer"));
+        assert!(linux.contains("ATTR{latency_timer}=\"1\""));
+
+        let windows = hint_for("windows", &port("COM5", FTDI_VID)).unwrap();
+        assert!(windows.contains("COM5"));
+        assert!(windows.contains("FTDIBUS\\VID_0403+PID_6001+A50285BIA\\0000"));
+    }
+}
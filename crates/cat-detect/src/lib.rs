@@ -1,6 +1,8 @@
 //! CAT Serial Port Detection Library
 //!
-//! This crate provides serial port enumeration and manual probing for
+//! This crate provides serial port enumeration (USB and Bluetooth serial),
+//! manual probing, concurrent scanning, CI-V bus discovery, diagnosis of
+//! ports held by another program, and per-adapter settle delays for
 //! CAT-capable amateur radio transceivers.
 //!
 //! # Example
@@ -16,10 +18,25 @@
 //! }
 //! ```
 
+pub mod bluetooth;
+pub mod civ_sweep;
 pub mod error;
+pub mod latency_timer;
+pub mod port_conflict;
 pub mod probe;
 pub mod scanner;
+pub mod usb_quirks;
 
+pub use bluetooth::pairing_hint;
+pub use civ_sweep::{
+    all_addresses, known_addresses, sweep_civ_port, CivDevice, CivSweepConfig, CivSweepEvent,
+};
 pub use error::DetectError;
+pub use latency_timer::{latency_timer_hint, read_latency_timer, set_latency_timer};
+pub use port_conflict::{describe_open_error, find_port_holder, is_port_busy, PortHolder};
 pub use probe::{probe_port, probe_port_with_protocol, ProbeResult, RadioProber};
-pub use scanner::{PortScanner, SerialPortInfo};
+pub use scanner::{PortScanner, ScanCancel, ScanEvent, ScanHit, ScannerConfig, SerialPortInfo};
+pub use usb_quirks::{adapter_for, port_settle_delay, settle_delay, UsbAdapter, DEFAULT_SETTLE};
+
+/// This crate's version, for reports
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
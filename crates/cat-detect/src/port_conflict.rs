@@ -0,0 +1,342 @@
+//! Ports held open by another program
+//!
+//! Opening a port that a logger or digital-mode program already has fails
+//! with an error that says little: "Device or resource busy" or "Unable to
+//! acquire exclusive lock" on Linux and macOS, "Access is denied." on
+//! Windows. [`is_port_busy`] picks these out from other open failures, and
+//! [`find_port_holder`] names the program holding the port where the
+//! platform allows: on Linux by looking through `/proc/<pid>/fd`, on macOS by
+//! asking `lsof`, and on Windows by asking the Restart Manager which
+//! processes have the port's device open.
+//!
+//! [`describe_open_error`] puts the two together into a message such as
+//! "COM5 is in use by wsjtx.exe (pid 4242)".
+
+use std::fmt;
+
+use serialport::ErrorKind;
+
+/// A program with a port open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PortHolder {
+    /// Process ID
+    pub pid: u32,
+    /// Process name, e.g. "wsjtx"
+    pub name: String,
+}
+
+impl fmt::Display for PortHolder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (pid {})", self.name, self.pid)
+    }
+}
+
+/// Whether an open failed because another program has the port
+pub fn is_port_busy(err: &serialport::Error) -> bool {
+    // Each platform also reports a missing port as NoDevice
+    if err.kind() != ErrorKind::NoDevice {
+        return false;
+    }
+    let text = err.description.to_lowercase();
+    ["busy", "lock", "access is denied"]
+        .iter()
+        .any(|s| text.contains(s))
+}
+
+/// The program (other than this one) holding `port` open, if it can be found
+pub fn find_port_holder(port: &str) -> Option<PortHolder> {
+    #[cfg(target_os = "linux")]
+    {
+        let target = std::fs::canonicalize(port).ok()?;
+        holder_in_proc(std::path::Path::new("/proc"), &target, std::process::id())
+    }
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        let output = std::process::Command::new("lsof")
+            .args(["-F", "pc", "--", port])
+            .output()
+            .ok()?;
+        parse_lsof(&String::from_utf8_lossy(&output.stdout), std::process::id())
+    }
+    #[cfg(windows)]
+    {
+        windows::holder(&device_path(port), std::process::id())
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = port;
+        None
+    }
+}
+
+/// Why `port` couldn't be opened, naming the program holding it if that's
+/// the reason
+pub fn describe_open_error(port: &str, err: &serialport::Error) -> String {
+    if !is_port_busy(err) {
+        return err.to_string();
+    }
+    match find_port_holder(port) {
+        Some(holder) => format!("{} is in use by {}", port, holder),
+        None => format!("{} is in use by another program", port),
+    }
+}
+
+/// Look through each process's open files under `proc` for `target`
+///
+/// Processes of other users can't be looked into without root and are
+/// skipped.
+#[cfg(target_os = "linux")]
+fn holder_in_proc(
+    proc: &std::path::Path,
+    target: &std::path::Path,
+    own_pid: u32,
+) -> Option<PortHolder> {
+    for entry in std::fs::read_dir(proc).ok()?.flatten() {
+        let Some(pid) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+            continue;
+        };
+        if pid == own_pid {
+            continue;
+        }
+        let Ok(fds) = std::fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let holds = fds
+            .flatten()
+            .any(|fd| std::fs::read_link(fd.path()).is_ok_and(|link| link == target));
+        if holds {
+            let name = std::fs::read_to_string(entry.path().join("comm"))
+                .map(|comm| comm.trim().to_string())
+                .unwrap_or_else(|_| "unknown".to_string());
+            return Some(PortHolder { pid, name });
+        }
+    }
+    None
+}
+
+/// First process other than `own_pid` in `lsof -F pc` output
+///
+/// The output has a `p<pid>` line for each process followed by a
+/// `c<command>` line.
+#[cfg_attr(not(all(unix, not(target_os = "linux"))), allow(dead_code))]
+fn parse_lsof(output: &str, own_pid: u32) -> Option<PortHolder> {
+    let mut pid = None;
+    for line in output.lines() {
+        if let Some(p) = line.strip_prefix('p') {
+            pid = p.parse().ok().filter(|&p| p != own_pid);
+        } else if let (Some(name), Some(pid)) = (line.strip_prefix('c'), pid) {
+            return Some(PortHolder {
+                pid,
+                name: name.to_string(),
+            });
+        }
+    }
+    None
+}
+
+/// Device path for a Windows port name: `COM5` becomes `\\.\COM5`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn device_path(port: &str) -> String {
+    if port.starts_with(r"\\") {
+        port.to_string()
+    } else {
+        format!(r"\\.\{}", port)
+    }
+}
+
+/// File name of an executable path, e.g. `wsjtx.exe`
+#[cfg_attr(not(windows), allow(dead_code))]
+fn exe_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+#[cfg(windows)]
+mod windows {
+    use windows_sys::Win32::Foundation::{CloseHandle, ERROR_MORE_DATA, ERROR_SUCCESS};
+    use windows_sys::Win32::System::RestartManager::{
+        RmEndSession, RmGetList, RmRegisterResources, RmStartSession, CCH_RM_SESSION_KEY,
+        RM_PROCESS_INFO,
+    };
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, QueryFullProcessImageNameW, PROCESS_NAME_WIN32,
+        PROCESS_QUERY_LIMITED_INFORMATION,
+    };
+
+    use super::{exe_name, PortHolder};
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn from_wide(s: &[u16]) -> String {
+        let end = s.iter().position(|&c| c == 0).unwrap_or(s.len());
+        String::from_utf16_lossy(&s[..end])
+    }
+
+    /// Executable name of a running process, e.g. `wsjtx.exe`
+    fn process_exe(pid: u32) -> Option<String> {
+        // SAFETY: the handle is checked before use and closed after
+        unsafe {
+            let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid);
+            if process.is_null() {
+                return None;
+            }
+            let mut buf = [0u16; 1024];
+            let mut len = buf.len() as u32;
+            let ok =
+                QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buf.as_mut_ptr(), &mut len);
+            CloseHandle(process);
+            (ok != 0).then(|| exe_name(&from_wide(&buf[..len as usize])).to_string())
+        }
+    }
+
+    /// First process other than `own_pid` with `device` open
+    pub(super) fn holder(device: &str, own_pid: u32) -> Option<PortHolder> {
+        let mut session = 0u32;
+        let mut key = [0u16; CCH_RM_SESSION_KEY as usize + 1];
+        let device = wide(device);
+        let files = [device.as_ptr()];
+
+        // SAFETY: every pointer is to a live local buffer of the size given,
+        // and the session is ended on every path after it is started
+        unsafe {
+            if RmStartSession(&mut session, 0, key.as_mut_ptr()) != ERROR_SUCCESS {
+                return None;
+            }
+            let mut processes = Vec::new();
+            let mut listed = 0;
+            if RmRegisterResources(
+                session,
+                1,
+                files.as_ptr(),
+                0,
+                std::ptr::null(),
+                0,
+                std::ptr::null(),
+            ) == ERROR_SUCCESS
+            {
+                // Ask for the count first; processes can come and go in
+                // between, so try once more if it grew
+                for _ in 0..2 {
+                    let mut needed = 0u32;
+                    let mut count = processes.len() as u32;
+                    let mut reasons = 0u32;
+                    let result = RmGetList(
+                        session,
+                        &mut needed,
+                        &mut count,
+                        processes.as_mut_ptr(),
+                        &mut reasons,
+                    );
+                    if result == ERROR_MORE_DATA {
+                        processes = vec![std::mem::zeroed::<RM_PROCESS_INFO>(); needed as usize];
+                        continue;
+                    }
+                    if result == ERROR_SUCCESS {
+                        listed = count as usize;
+                    }
+                    break;
+                }
+            }
+            RmEndSession(session);
+
+            processes[..listed.min(processes.len())]
+                .iter()
+                .map(|info| (info.Process.dwProcessId, info))
+                .find(|(pid, _)| *pid != own_pid)
+                .map(|(pid, info)| PortHolder {
+                    pid,
+                    name: process_exe(pid).unwrap_or_else(|| from_wide(&info.strAppName)),
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(kind: ErrorKind, description: &str) -> serialport::Error {
+        serialport::Error::new(kind, description)
+    }
+
+    #[test]
+    fn test_busy_errors() {
+        assert!(is_port_busy(&error(
+            ErrorKind::NoDevice,
+            "Device or resource busy"
+        )));
+        assert!(is_port_busy(&error(
+            ErrorKind::NoDevice,
+            "Unable to acquire exclusive lock on serial port"
+        )));
+        assert!(is_port_busy(&error(
+            ErrorKind::NoDevice,
+            "Access is denied."
+        )));
+        assert!(!is_port_busy(&error(
+            ErrorKind::NoDevice,
+            "The system cannot find the file specified."
+        )));
+        assert!(!is_port_busy(&error(
+            ErrorKind::Io(std::io::ErrorKind::PermissionDenied),
+            "Permission denied"
+        )));
+    }
+
+    #[test]
+    fn test_describe_other_errors() {
+        let err = error(
+            ErrorKind::Io(std::io::ErrorKind::NotFound),
+            "No such file or directory",
+        );
+        assert_eq!(
+            describe_open_error("/dev/ttyUSB9", &err),
+            "No such file or directory"
+        );
+    }
+
+    #[test]
+    fn test_parse_lsof() {
+        let output = "p100\ncCatapult\np4242\ncwsjtx\n";
+        assert_eq!(
+            parse_lsof(output, 100),
+            Some(PortHolder {
+                pid: 4242,
+                name: "wsjtx".to_string()
+            })
+        );
+        assert_eq!(parse_lsof("p100\ncCatapult\n", 100), None);
+        assert_eq!(parse_lsof("", 100), None);
+    }
+
+    #[test]
+    fn test_windows_names() {
+        assert_eq!(device_path("COM5"), r"\\.\COM5");
+        assert_eq!(device_path(r"\\.\COM12"), r"\\.\COM12");
+        assert_eq!(
+            exe_name(r"C:\Program Files\wsjtx\bin\wsjtx.exe"),
+            "wsjtx.exe"
+        );
+        assert_eq!(exe_name("wsjtx.exe"), "wsjtx.exe");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_holder_in_proc() {
+        let path = std::env::temp_dir().join(format!("port-conflict-{}", std::process::id()));
+        let file = std::fs::File::create(&path).unwrap();
+        let target = std::fs::canonicalize(&path).unwrap();
+
+        // This process has it open, so looks like the holder when not skipped
+        let holder = holder_in_proc(std::path::Path::new("/proc"), &target, 0).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(
+            holder_in_proc(std::path::Path::new("/proc"), &target, std::process::id()),
+            None
+        );
+
+        drop(file);
+        std::fs::remove_file(path).unwrap();
+    }
+}
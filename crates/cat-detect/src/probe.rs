@@ -13,6 +13,8 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::time::timeout;
 use tracing::{debug, info, trace, warn};
 
+use crate::port_conflict::describe_open_error;
+
 /// Result of probing a serial port
 #[derive(Debug, Clone)]
 pub struct ProbeResult {
@@ -522,9 +524,9 @@ impl Default for RadioProber {
 /// Probe a specific port at a given baud rate
 ///
 /// This is a convenience function for manual probing from the UI.
+/// Waits `settle` after opening the port (see [`crate::usb_quirks`]).
 /// Returns the probe result if a radio is detected.
-pub async fn probe_port(port_name: &str, baud_rate: u32) -> Option<ProbeResult> {
-    use std::time::Duration;
+pub async fn probe_port(port_name: &str, baud_rate: u32, settle: Duration) -> Option<ProbeResult> {
     use tokio_serial::SerialPortBuilderExt;
 
     debug!("Probing {} at {} baud", port_name, baud_rate);
@@ -535,13 +537,17 @@ pub async fn probe_port(port_name: &str, baud_rate: u32) -> Option<ProbeResult>
     {
         Ok(s) => s,
         Err(e) => {
-            warn!("Failed to open {}: {}", port_name, e);
+            warn!(
+                "Failed to open {}: {}",
+                port_name,
+                describe_open_error(port_name, &e)
+            );
             return None;
         }
     };
 
-    // Give the port a moment to settle
-    tokio::time::sleep(Duration::from_millis(50)).await;
+    // Some adapters drop what is written right after opening
+    tokio::time::sleep(settle).await;
 
     let prober = RadioProber::new();
     prober.probe(&mut stream).await
@@ -551,13 +557,14 @@ pub async fn probe_port(port_name: &str, baud_rate: u32) -> Option<ProbeResult>
 ///
 /// This is a convenience function for probing when the user has already
 /// selected which protocol their radio uses. Only the specified protocol
-/// is tried, rather than auto-detecting across all protocols.
+/// is tried, rather than auto-detecting across all protocols. Waits `settle`
+/// after opening the port.
 pub async fn probe_port_with_protocol(
     port_name: &str,
     baud_rate: u32,
     protocol: Protocol,
+    settle: Duration,
 ) -> Option<ProbeResult> {
-    use std::time::Duration;
     use tokio_serial::SerialPortBuilderExt;
 
     debug!(
@@ -571,13 +578,17 @@ pub async fn probe_port_with_protocol(
     {
         Ok(s) => s,
         Err(e) => {
-            warn!("Failed to open {}: {}", port_name, e);
+            warn!(
+                "Failed to open {}: {}",
+                port_name,
+                describe_open_error(port_name, &e)
+            );
             return None;
         }
     };
 
-    // Give the port a moment to settle
-    tokio::time::sleep(Duration::from_millis(50)).await;
+    // Some adapters drop what is written right after opening
+    tokio::time::sleep(settle).await;
 
     let prober = RadioProber::new();
     prober.probe_protocol(&mut stream, protocol).await
@@ -1,11 +1,25 @@
 //! Serial port scanner
 //!
-//! This module provides serial port enumeration.
+//! This module provides serial port enumeration, and scanning: probing every
+//! port for a radio. A scan probes several ports at once (a hub full of
+//! adapters would take minutes one port at a time), reports each radio as soon
+//! as it answers, and can be cancelled part way through.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
 
 use serialport::{available_ports, SerialPortType};
-use tracing::info;
+use tokio::sync::{mpsc, watch, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+use tracing::{debug, info};
 
+use crate::bluetooth::{drop_dialin_twins, is_bluetooth_port_name};
 use crate::error::DetectError;
+use crate::probe::{probe_port, ProbeResult};
+use crate::usb_quirks::{settle_delay, DEFAULT_SETTLE};
 
 /// Information about a serial port
 #[derive(Debug, Clone)]
@@ -18,29 +32,39 @@ pub struct SerialPortInfo {
     pub pid: Option<u16>,
     /// USB serial number (if available)
     pub serial_number: Option<String>,
+    /// USB interface of the port, telling apart the ports of a multi-port
+    /// adapter that share a serial number
+    pub interface: Option<u8>,
     /// USB manufacturer string
     pub manufacturer: Option<String>,
     /// USB product string
     pub product: Option<String>,
+    /// Bluetooth serial (SPP) link rather than a cable
+    pub bluetooth: bool,
 }
 
 impl SerialPortInfo {
     /// Create from serialport crate's port info
-    fn from_serialport(name: String, port_type: &SerialPortType) -> Self {
+    pub(crate) fn from_serialport(name: String, port_type: &SerialPortType) -> Self {
         match port_type {
             SerialPortType::UsbPort(usb) => Self {
                 port: name,
                 vid: Some(usb.vid),
                 pid: Some(usb.pid),
                 serial_number: usb.serial_number.clone(),
+                interface: usb.interface,
                 manufacturer: usb.manufacturer.clone(),
                 product: usb.product.clone(),
+                bluetooth: false,
             },
             _ => Self {
+                bluetooth: matches!(port_type, SerialPortType::BluetoothPort)
+                    || is_bluetooth_port_name(&name),
                 port: name,
                 vid: None,
                 pid: None,
                 serial_number: None,
+                interface: None,
                 manufacturer: None,
                 product: None,
             },
@@ -49,10 +73,93 @@ impl SerialPortInfo {
 }
 
 /// Serial port scanner configuration
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub struct ScannerConfig {
     /// Skip ports matching these patterns
     pub skip_patterns: Vec<String>,
+    /// Baud rates to try on each port while scanning, in order
+    pub baud_rates: Vec<u32>,
+    /// How many ports to probe at once while scanning
+    pub concurrency: usize,
+    /// Give up on a port after this long (across all of its baud rates)
+    pub port_timeout: Duration,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            skip_patterns: Vec::new(),
+            baud_rates: vec![38400, 19200, 9600, 4800, 115200],
+            concurrency: 4,
+            port_timeout: Duration::from_secs(20),
+        }
+    }
+}
+
+/// A radio found by a scan
+#[derive(Debug, Clone)]
+pub struct ScanHit {
+    /// Port the radio answered on
+    pub port: SerialPortInfo,
+    /// Baud rate it answered at
+    pub baud_rate: u32,
+    /// What it identified as
+    pub result: ProbeResult,
+}
+
+/// Progress of a scan, sent as it happens
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// Started probing a port
+    Probing { port: String },
+    /// A radio answered
+    Found(Box<ScanHit>),
+    /// Nothing answered on a port (or it couldn't be opened)
+    NoRadio {
+        port: String,
+        /// Gave up at the per-port timeout rather than running out of baud rates
+        timed_out: bool,
+    },
+}
+
+/// Cancels a scan in progress
+///
+/// Clones share the same state. Cancelling stops ports from being started,
+/// abandons probes in flight (closing their ports), and makes the scan return
+/// with whatever it had found.
+#[derive(Debug, Clone)]
+pub struct ScanCancel {
+    tx: Arc<watch::Sender<bool>>,
+}
+
+impl ScanCancel {
+    pub fn new() -> Self {
+        Self {
+            tx: Arc::new(watch::Sender::new(false)),
+        }
+    }
+
+    /// Cancel the scan
+    pub fn cancel(&self) {
+        self.tx.send_replace(true);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        *self.tx.borrow()
+    }
+
+    /// Resolves once the scan is cancelled
+    pub async fn cancelled(&self) {
+        let mut rx = self.tx.subscribe();
+        // The sender lives as long as `self`, so this only returns on cancel
+        let _ = rx.wait_for(|cancelled| *cancelled).await;
+    }
+}
+
+impl Default for ScanCancel {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// Serial port scanner
@@ -71,6 +178,7 @@ impl PortScanner {
                     // Debug/logging ports
                     "debug".to_string(),
                 ],
+                ..ScannerConfig::default()
             },
         }
     }
@@ -85,18 +193,23 @@ impl PortScanner {
         info!("Enumerating serial ports...");
         let ports = available_ports().map_err(|e| DetectError::EnumerationFailed(e.to_string()))?;
 
-        let result: Vec<_> = ports
+        let mut result: Vec<_> = ports
             .into_iter()
             .map(|p| SerialPortInfo::from_serialport(p.port_name, &p.port_type))
             .filter(|p| !self.should_skip_port(p))
             .collect();
+        drop_dialin_twins(&mut result);
 
         if result.is_empty() {
             info!("No serial ports found");
         } else {
             info!("Found {} serial port(s)", result.len());
             for port in &result {
-                let desc = port.product.as_deref().unwrap_or("Unknown");
+                let desc = match (&port.product, port.bluetooth) {
+                    (Some(product), _) => product.as_str(),
+                    (None, true) => "Bluetooth",
+                    (None, false) => "Unknown",
+                };
                 info!("  {} - {}", port.port, desc);
             }
         }
@@ -104,6 +217,52 @@ impl PortScanner {
         Ok(result)
     }
 
+    /// Enumerate ports and probe them all for radios
+    ///
+    /// Progress goes to `events` as it happens; the radios found are also
+    /// returned, in port order, once every port is done or `cancel` fires.
+    pub async fn scan(
+        &self,
+        events: mpsc::UnboundedSender<ScanEvent>,
+        cancel: ScanCancel,
+    ) -> Result<Vec<ScanHit>, DetectError> {
+        let ports = self.enumerate_ports()?;
+        Ok(self.scan_ports(ports, events, cancel).await)
+    }
+
+    /// Probe the given ports for radios (see [`PortScanner::scan`])
+    pub async fn scan_ports(
+        &self,
+        ports: Vec<SerialPortInfo>,
+        events: mpsc::UnboundedSender<ScanEvent>,
+        cancel: ScanCancel,
+    ) -> Vec<ScanHit> {
+        info!(
+            "Scanning {} port(s), {} at a time",
+            ports.len(),
+            self.config.concurrency.max(1)
+        );
+        let settle: Arc<HashMap<String, Duration>> = Arc::new(
+            ports
+                .iter()
+                .map(|p| (p.port.clone(), settle_delay(p)))
+                .collect(),
+        );
+        let hits = run_scan(
+            ports,
+            &self.config,
+            move |port: String, baud_rate| {
+                let settle = settle.get(&port).copied().unwrap_or(DEFAULT_SETTLE);
+                async move { probe_port(&port, baud_rate, settle).await }
+            },
+            events,
+            cancel,
+        )
+        .await;
+        info!("Scan finished: {} radio(s) found", hits.len());
+        hits
+    }
+
     /// Check if a port should be skipped
     fn should_skip_port(&self, port: &SerialPortInfo) -> bool {
         for pattern in &self.config.skip_patterns {
@@ -121,10 +280,129 @@ impl Default for PortScanner {
     }
 }
 
+/// Probe `ports` with `probe`, at most `config.concurrency` at a time
+async fn run_scan<P, F>(
+    ports: Vec<SerialPortInfo>,
+    config: &ScannerConfig,
+    probe: P,
+    events: mpsc::UnboundedSender<ScanEvent>,
+    cancel: ScanCancel,
+) -> Vec<ScanHit>
+where
+    P: Fn(String, u32) -> F + Clone + Send + 'static,
+    F: Future<Output = Option<ProbeResult>> + Send,
+{
+    let limit = Arc::new(Semaphore::new(config.concurrency.max(1)));
+    let baud_rates: Arc<[u32]> = config.baud_rates.clone().into();
+    let port_timeout = config.port_timeout;
+
+    let mut tasks = JoinSet::new();
+    for port in ports {
+        let limit = limit.clone();
+        let baud_rates = baud_rates.clone();
+        let probe = probe.clone();
+        let events = events.clone();
+        let cancel = cancel.clone();
+        tasks.spawn(async move {
+            let _permit = tokio::select! {
+                biased;
+                _ = cancel.cancelled() => return None,
+                permit = limit.acquire_owned() => permit.ok()?,
+            };
+            // A permit freed by a cancelled probe can beat the cancel here
+            if cancel.is_cancelled() {
+                return None;
+            }
+            let _ = events.send(ScanEvent::Probing {
+                port: port.port.clone(),
+            });
+
+            let name = port.port.clone();
+            // The baud rate means nothing over Bluetooth: one try is enough
+            let tries = if port.bluetooth { 1 } else { baud_rates.len() };
+            let attempt = async move {
+                for &baud_rate in baud_rates.iter().take(tries) {
+                    if let Some(result) = probe(name.clone(), baud_rate).await {
+                        return Some((baud_rate, result));
+                    }
+                }
+                None
+            };
+            let outcome = tokio::select! {
+                outcome = timeout(port_timeout, attempt) => outcome,
+                _ = cancel.cancelled() => {
+                    debug!("Scan of {} cancelled", port.port);
+                    return None;
+                }
+            };
+
+            match outcome {
+                Ok(Some((baud_rate, result))) => {
+                    let hit = ScanHit {
+                        port,
+                        baud_rate,
+                        result,
+                    };
+                    let _ = events.send(ScanEvent::Found(Box::new(hit.clone())));
+                    Some(hit)
+                }
+                Ok(None) => {
+                    let _ = events.send(ScanEvent::NoRadio {
+                        port: port.port,
+                        timed_out: false,
+                    });
+                    None
+                }
+                Err(_) => {
+                    debug!("Gave up on {} after {:?}", port.port, port_timeout);
+                    let _ = events.send(ScanEvent::NoRadio {
+                        port: port.port,
+                        timed_out: true,
+                    });
+                    None
+                }
+            }
+        });
+    }
+
+    let mut hits = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok(Some(hit)) = joined {
+            hits.push(hit);
+        }
+    }
+    hits.sort_by(|a, b| a.port.port.cmp(&b.port.port));
+    hits
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cat_protocol::Protocol;
     use serialport::UsbPortInfo;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn plain_port(name: &str) -> SerialPortInfo {
+        SerialPortInfo::from_serialport(name.to_string(), &SerialPortType::Unknown)
+    }
+
+    fn kenwood() -> ProbeResult {
+        ProbeResult {
+            protocol: Protocol::Kenwood,
+            model: None,
+            id_data: b"ID019;".to_vec(),
+            address: None,
+        }
+    }
+
+    fn scan_config(concurrency: usize, port_timeout: Duration) -> ScannerConfig {
+        ScannerConfig {
+            baud_rates: vec![38400, 9600],
+            concurrency,
+            port_timeout,
+            ..ScannerConfig::default()
+        }
+    }
 
     #[test]
     fn test_serial_port_info_from_usb() {
@@ -132,6 +410,7 @@ mod tests {
             vid: 0x0403,
             pid: 0x6001,
             serial_number: Some("12345".to_string()),
+            interface: None,
             manufacturer: Some("FTDI".to_string()),
             product: Some("FT232R".to_string()),
         });
@@ -142,4 +421,177 @@ mod tests {
         assert_eq!(info.pid, Some(0x6001));
         assert_eq!(info.product.as_deref(), Some("FT232R"));
     }
+
+    #[tokio::test]
+    async fn test_scan_limits_concurrency_and_reports_hits() {
+        let ports: Vec<_> = (0..6)
+            .map(|i| plain_port(&format!("/dev/ttyUSB{}", i)))
+            .collect();
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let probe = {
+            let (active, peak) = (active.clone(), peak.clone());
+            move |port: String, baud_rate: u32| {
+                let (active, peak) = (active.clone(), peak.clone());
+                async move {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    // Radios on the odd ports, answering at 9600
+                    let odd = port.ends_with(['1', '3', '5']);
+                    (odd && baud_rate == 9600).then(kenwood)
+                }
+            }
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let hits = run_scan(
+            ports,
+            &scan_config(2, Duration::from_secs(5)),
+            probe,
+            tx,
+            ScanCancel::new(),
+        )
+        .await;
+
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        let found: Vec<_> = hits
+            .iter()
+            .map(|h| (h.port.port.as_str(), h.baud_rate))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("/dev/ttyUSB1", 9600),
+                ("/dev/ttyUSB3", 9600),
+                ("/dev/ttyUSB5", 9600)
+            ]
+        );
+
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        let probing = events
+            .iter()
+            .filter(|e| matches!(e, ScanEvent::Probing { .. }))
+            .count();
+        let found = events
+            .iter()
+            .filter(|e| matches!(e, ScanEvent::Found(_)))
+            .count();
+        assert_eq!((probing, found, events.len()), (6, 3, 12));
+    }
+
+    #[tokio::test]
+    async fn test_scan_gives_up_on_slow_port() {
+        let probe = |port: String, _baud_rate: u32| async move {
+            if port == "/dev/ttyS0" {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            Some(kenwood())
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let hits = run_scan(
+            vec![plain_port("/dev/ttyS0"), plain_port("/dev/ttyUSB0")],
+            &scan_config(2, Duration::from_millis(50)),
+            probe,
+            tx,
+            ScanCancel::new(),
+        )
+        .await;
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].port.port, "/dev/ttyUSB0");
+        let mut timed_out = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let ScanEvent::NoRadio {
+                port,
+                timed_out: true,
+            } = event
+            {
+                timed_out.push(port);
+            }
+        }
+        assert_eq!(timed_out, vec!["/dev/ttyS0".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_bluetooth_port_probed_at_one_baud_rate() {
+        let bluetooth = SerialPortInfo::from_serialport(
+            "/dev/rfcomm0".to_string(),
+            &SerialPortType::BluetoothPort,
+        );
+        assert!(bluetooth.bluetooth);
+        let tries = Arc::new(AtomicUsize::new(0));
+        let probe = {
+            let tries = tries.clone();
+            move |_port: String, _baud_rate: u32| {
+                let tries = tries.clone();
+                async move {
+                    tries.fetch_add(1, Ordering::SeqCst);
+                    None
+                }
+            }
+        };
+
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let hits = run_scan(
+            vec![bluetooth],
+            &scan_config(1, Duration::from_secs(1)),
+            probe,
+            tx,
+            ScanCancel::new(),
+        )
+        .await;
+
+        assert!(hits.is_empty());
+        assert_eq!(tries.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_stops_scan() {
+        let started = Arc::new(AtomicUsize::new(0));
+        let probe = {
+            let started = started.clone();
+            move |_port: String, _baud_rate: u32| {
+                started.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    tokio::time::sleep(Duration::from_secs(60)).await;
+                    None
+                }
+            }
+        };
+
+        let cancel = ScanCancel::new();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let ports: Vec<_> = (0..4).map(|i| plain_port(&format!("COM{}", i))).collect();
+        let scan = tokio::spawn({
+            let cancel = cancel.clone();
+            async move {
+                run_scan(
+                    ports,
+                    &scan_config(1, Duration::from_secs(60)),
+                    probe,
+                    tx,
+                    cancel,
+                )
+                .await
+            }
+        });
+
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        cancel.cancel();
+        let hits = tokio::time::timeout(Duration::from_secs(1), scan)
+            .await
+            .expect("scan should stop promptly")
+            .unwrap();
+
+        assert!(hits.is_empty());
+        assert!(cancel.is_cancelled());
+        // Only the first port got going before the cancel
+        assert_eq!(started.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,129 @@
+//! Known USB serial adapters and how long their ports take to settle
+//!
+//! Not every adapter is usable the moment its port opens. Cheap CH340
+//! adapters drop or garble what is written for several hundred milliseconds
+//! while the chip comes up, so the first command (often the ID query a probe
+//! depends on) goes nowhere. Probing, the CI-V sweep and radio connections
+//! wait [`settle_delay`] after opening a port before writing to it. A radio's
+//! configuration can override the delay for adapters not listed here.
+
+use std::time::Duration;
+
+use crate::scanner::SerialPortInfo;
+
+/// Wait after opening a port whose adapter isn't in [`USB_ADAPTERS`]
+pub const DEFAULT_SETTLE: Duration = Duration::from_millis(100);
+
+/// A USB serial chip
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UsbAdapter {
+    /// USB vendor ID
+    pub vid: u16,
+    /// USB product ID (None = every product from the vendor)
+    pub pid: Option<u16>,
+    /// Chip name, for display
+    pub chip: &'static str,
+    /// How long to wait after opening before writing
+    pub settle: Duration,
+}
+
+const fn adapter(vid: u16, pid: Option<u16>, chip: &'static str, settle_ms: u64) -> UsbAdapter {
+    UsbAdapter {
+        vid,
+        pid,
+        chip,
+        settle: Duration::from_millis(settle_ms),
+    }
+}
+
+/// Adapters with known settle times; a product entry wins over its vendor's
+pub const USB_ADAPTERS: &[UsbAdapter] = &[
+    adapter(0x1A86, Some(0x7523), "CH340", 500),
+    adapter(0x1A86, Some(0x5523), "CH341", 500),
+    adapter(0x1A86, Some(0x55D4), "CH9102", 300),
+    adapter(0x1A86, None, "WCH", 500),
+    adapter(0x067B, None, "PL2303", 250),
+    // Built into most recent Icom and Kenwood radios
+    adapter(0x10C4, None, "CP210x", 100),
+    adapter(0x0403, None, "FTDI", 50),
+];
+
+/// The table entry for a USB device, if any
+pub fn find_adapter(vid: u16, pid: u16) -> Option<&'static UsbAdapter> {
+    USB_ADAPTERS
+        .iter()
+        .find(|a| a.vid == vid && a.pid == Some(pid))
+        .or_else(|| {
+            USB_ADAPTERS
+                .iter()
+                .find(|a| a.vid == vid && a.pid.is_none())
+        })
+}
+
+/// The table entry for a port's adapter, if it is a known USB adapter
+pub fn adapter_for(info: &SerialPortInfo) -> Option<&'static UsbAdapter> {
+    find_adapter(info.vid?, info.pid?)
+}
+
+/// How long to wait after opening `info`'s port before writing to it
+pub fn settle_delay(info: &SerialPortInfo) -> Duration {
+    adapter_for(info).map_or(DEFAULT_SETTLE, |a| a.settle)
+}
+
+/// [`settle_delay`] for a port known only by name, looked up among the
+/// connected ports ([`DEFAULT_SETTLE`] if it isn't one of them)
+pub fn port_settle_delay(port: &str) -> Duration {
+    serialport::available_ports()
+        .unwrap_or_default()
+        .into_iter()
+        .find(|p| p.port_name == port)
+        .map_or(DEFAULT_SETTLE, |p| {
+            settle_delay(&SerialPortInfo::from_serialport(p.port_name, &p.port_type))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn port(vid: u16, pid: u16) -> SerialPortInfo {
+        SerialPortInfo {
+            port: "/dev/ttyUSB0".to_string(),
+            vid: Some(vid),
+            pid: Some(pid),
+            serial_number: None,
+            interface: None,
+            manufacturer: None,
+            product: None,
+            bluetooth: false,
+        }
+    }
+
+    #[test]
+    fn test_settle_delay_by_adapter() {
+        assert_eq!(
+            settle_delay(&port(0x1A86, 0x7523)),
+            Duration::from_millis(500)
+        );
+        // A product entry wins over its vendor's
+        assert_eq!(
+            settle_delay(&port(0x1A86, 0x55D4)),
+            Duration::from_millis(300)
+        );
+        assert_eq!(adapter_for(&port(0x1A86, 0x1234)).unwrap().chip, "WCH");
+        assert_eq!(
+            settle_delay(&port(0x0403, 0x6001)),
+            Duration::from_millis(50)
+        );
+
+        // Unknown adapters, and ports that aren't USB, get the default
+        assert_eq!(settle_delay(&port(0x2341, 0x0043)), DEFAULT_SETTLE);
+        let bluetooth = SerialPortInfo {
+            vid: None,
+            pid: None,
+            bluetooth: true,
+            ..port(0, 0)
+        };
+        assert_eq!(settle_delay(&bluetooth), DEFAULT_SETTLE);
+    }
+}
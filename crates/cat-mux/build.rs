@@ -0,0 +1,21 @@
+//! Generates the gRPC service from `proto/` when the `grpc` feature is on
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    compile_protos();
+}
+
+#[cfg(feature = "grpc")]
+fn compile_protos() {
+    use std::path::PathBuf;
+
+    println!("cargo:rerun-if-changed=proto");
+    // A bundled protoc, so building doesn't need one installed
+    let protoc = protoc_bin_vendored::protoc_bin_path().expect("bundled protoc");
+    std::env::set_var("PROTOC", protoc);
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").expect("OUT_DIR"));
+    tonic_build::configure()
+        .file_descriptor_set_path(out_dir.join("catapult_descriptor.bin"))
+        .compile_protos(&["proto/catapult/v1/mux.proto"], &["proto"])
+        .expect("compile protos");
+}
@@ -14,6 +14,25 @@
 //! - Send control commands (add/remove radios, change settings)
 //! - Receive all events (traffic, state changes, errors) through a unified stream
 //!
+//! # PTT priority
+//!
+//! Radio data shares the command queue with everything else, so a radio
+//! streaming scope or meter data can leave a PTT edge waiting behind a backlog
+//! of bulk frames. The actor therefore takes whatever is queued as one batch,
+//! parses the raw radio data up front, and handles PTT-off responses before
+//! the rest of the batch. PTT-on keeps its place: the amp must hear a retune
+//! queued ahead of it before it keys. Each edge of the active radio is timed
+//! from the radio task's read until the amp has followed it (which may be
+//! later, e.g. behind the sequencer) and reported as [`MuxEvent::PttLatency`].
+//!
+//! # Shadow amplifier
+//!
+//! A second amplifier can be connected as a shadow to validate a new amp
+//! personality. It receives every write the primary amp gets, its own queries
+//! are answered from the same cached state, and the frames both amps send are
+//! compared by a [`ShadowComparator`] and reported as
+//! [`MuxEvent::ShadowAmpCompared`]. The shadow never affects the mux state.
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -30,24 +49,59 @@
 //! // Send commands and receive events
 //! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::time::SystemTime;
 
+use cat_protocol::elecraft::{self, ElecraftAccessory, ElecraftCommand};
 use cat_protocol::{
-    create_radio_codec, OperatingMode, Protocol, RadioCodec, RadioRequest, RadioResponse, Vfo,
+    icom, CommandHooks, ControllerSideCodec, CustomEvent, EncodeCommand, Frequency, OperatingMode,
+    Protocol, RadioModel, RadioRequest, RadioResponse, RadioSideCodec, Receiver, Vfo, VfoState,
 };
 use tokio::sync::{mpsc, oneshot};
-use tokio::time::{interval, Duration, MissedTickBehavior};
+use tokio::time::{interval, Duration, Instant, MissedTickBehavior};
+use tokio_serial::FlowControl;
 use tracing::{debug, info, warn};
 
+use crate::amp_bypass::{AmpBypass, AmpBypassConfig, AmpPersonality};
+use crate::amp_failover::{
+    kpa_fault, AmpFailover, AmpRole, KPA_FAULT_POLL_INTERVAL, KPA_FAULT_QUERY,
+};
+use crate::amp_queries::{AmpQueryKind, AmpQueryProfile, AmpQueryTracker};
 use crate::amplifier::AmplifierChannel;
 use crate::async_radio::RadioTaskCommand;
+use crate::band_decoder::BandDecoderSink;
+use crate::band_guard::{BandGuardConfig, BandGuardStrictness};
+use crate::band_plan::band_name;
+use crate::band_plan::BandPlanRegion;
+use crate::bookmark::PttDoubleTap;
 use crate::channel::RadioChannelMeta;
+use crate::civ_echo::EchoDetector;
+use crate::dx_cluster::{DxSpot, SpotCache};
 use crate::engine::Multiplexer;
 use crate::error::MuxError;
-use crate::events::MuxEvent;
-use crate::state::{AmplifierConfig, RadioHandle, SwitchingMode};
-use crate::translation::translate_response;
+use crate::events::{FrameId, MuxEvent};
+use crate::freq_entry::{check_frequency, FrequencyEntryError};
+use crate::handover::Handover;
+use crate::input_throttle::InputThrottle;
+use crate::link::{request_to_dial, response_to_air};
+use crate::monitor::{MonitorConfig, MonitorFollowers};
+use crate::peer_sync::{unix_ms, PeerState, PeerStatus, PeerTable};
+use crate::reassembly::{partial_frame_timeout, FrameSource, PartialFrameTimer, ReassemblyTracker};
+use crate::sequencer::{PttSequencer, SequencerAction, SequencerConfig};
+use crate::shadow::{ShadowComparator, ShadowDiff, ShadowSide, ShadowStats};
+use crate::smoothing::{FrequencySmoother, FrequencySmoothing};
+use crate::state::{AmplifierConfig, AmplifierEmulatedState, RadioHandle, SwitchingMode};
+use crate::supervisor::{ChannelRegistry, Heartbeat, SavedAmp, SavedRadio, WatchdogConfig};
+use crate::switch_audit::{SwitchAuditLog, SwitchRecord, SwitchTrigger};
+use crate::switch_confirm::{SwitchConfirmOutcome, SwitchConfirmation, SWITCH_CONFIRM_TIMEOUT};
+use crate::telemetry::{MeterCache, MeterThrottle};
+use crate::transition::{Transition, TransitionPolicy, TransitionReason};
+use crate::translation::{
+    same_protocol_family, translate_frequency_mode, translate_response, BurstCoalescer,
+    TranslationStrictness,
+};
+use crate::tx_stats::{DutyCycleAlertConfig, TxBandStats, TxStats};
+use crate::write_backlog::BacklogMonitor;
 
 /// Summary of a radio's state for sync purposes
 ///
@@ -55,11 +109,15 @@ use crate::translation::translate_response;
 #[derive(Debug, Clone)]
 pub struct RadioStateSummary {
     /// Current frequency in Hz
-    pub frequency_hz: Option<u64>,
+    pub frequency_hz: Option<Frequency>,
     /// Current operating mode
     pub mode: Option<OperatingMode>,
     /// PTT active
     pub ptt: bool,
+    /// Output power setting in watts
+    pub power_watts: Option<u16>,
+    /// When the radio last sent a frame
+    pub last_seen: Option<SystemTime>,
 }
 
 impl RadioStateSummary {
@@ -69,6 +127,8 @@ impl RadioStateSummary {
             frequency_hz: state.frequency_hz,
             mode: state.mode,
             ptt: state.ptt,
+            power_watts: state.power_watts,
+            last_seen: state.last_seen,
         }
     }
 }
@@ -101,11 +161,26 @@ pub enum MuxActorCommand {
     },
 
     /// Raw data received from a radio (emits RadioDataIn event, then parses)
+    ///
+    /// PTT responses are pulled out and handled ahead of anything else
+    /// already queued (see [`run_mux_actor`]).
     RadioRawData {
         /// Handle of the source radio
         handle: RadioHandle,
         /// Raw bytes received
         data: Vec<u8>,
+        /// When the radio task read the bytes (start of the PTT latency metric)
+        received: Instant,
+    },
+
+    /// Responses from a radio reached through another program (e.g. flrig),
+    /// already decoded, each with the bytes to show as its traffic
+    ///
+    /// An empty list still counts as hearing from the radio.
+    RadioResponses {
+        /// Handle of the source radio
+        handle: RadioHandle,
+        frames: Vec<(RadioResponse, Vec<u8>)>,
     },
 
     /// Raw data sent to a radio (emits RadioDataOut event)
@@ -122,12 +197,95 @@ pub enum MuxActorCommand {
         data: Vec<u8>,
     },
 
+    /// Raw data received from the shadow amplifier (emits ShadowAmpDataIn event)
+    ShadowAmpRawData {
+        /// Raw bytes received
+        data: Vec<u8>,
+    },
+
     /// Set the active radio
+    ///
+    /// With switch confirmation on, selecting another radio while the active
+    /// one transmits waits for [`MuxActorCommand::ConfirmSwitch`] instead.
     SetActiveRadio {
         /// Handle of the radio to make active
         handle: RadioHandle,
     },
 
+    /// Answer a switch pending confirmation (see [`crate::switch_confirm`])
+    ConfirmSwitch {
+        /// Token from [`MuxEvent::SwitchPendingConfirmation`]
+        token: u64,
+        /// Go ahead with the switch (true) or stay on the transmitting radio
+        confirm: bool,
+    },
+
+    /// Require confirmation for manual switches away from a transmitting radio
+    SetSwitchConfirmation { enabled: bool },
+
+    /// Set the newly active radio's keyer speed to the outgoing radio's on switch
+    SetCarryKeyerSpeed { enabled: bool },
+
+    /// When the active radio changes band, put it back in the mode it last
+    /// used there (band stacking for radios without it)
+    SetBandModeMemory { enabled: bool },
+
+    /// Drop reports from radios other than the active one that repeat their
+    /// known state, once they are logged as traffic (off by default; see
+    /// [`RadioState::repeats`])
+    ///
+    /// [`RadioState::repeats`]: crate::RadioState::repeats
+    SetSuppressRepeats { enabled: bool },
+
+    /// Bound the actor's long-lived buffers (see [`crate::memory`])
+    SetMemoryLimits {
+        /// Switch audit records kept
+        switch_audit_records: usize,
+        /// DX spots kept
+        dx_spots: usize,
+    },
+
+    /// Fetch the most recent switch records, oldest first
+    QuerySwitchAudit {
+        /// At most this many
+        limit: usize,
+        response: oneshot::Sender<Vec<SwitchRecord>>,
+    },
+
+    /// Make a radio a receive-only monitor channel (Some) or a normal one (None)
+    SetRadioMonitor {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Monitor settings
+        monitor: Option<MonitorConfig>,
+    },
+
+    /// Offer a radio's unrecognized frames to user command handlers
+    ///
+    /// Replaces any handlers set before; empty hooks remove them.
+    SetRadioHooks {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Handlers, tried in order
+        hooks: CommandHooks,
+    },
+
+    /// Fetch transmit statistics for every radio and band
+    QueryTxStats {
+        response: oneshot::Sender<Vec<TxBandStats>>,
+    },
+
+    /// Start transmit statistics again from zero
+    ResetTxStats,
+
+    /// Configure the transmit duty-cycle alert
+    SetDutyCycleAlert { config: DutyCycleAlertConfig },
+
+    /// Fetch what the amp has been told
+    QueryAmpState {
+        response: oneshot::Sender<AmplifierEmulatedState>,
+    },
+
     /// Query the state of a specific radio
     QueryRadioState {
         /// Handle of the radio to query
@@ -136,6 +294,37 @@ pub enum MuxActorCommand {
         response: oneshot::Sender<Option<RadioStateSummary>>,
     },
 
+    /// Query the active radio and its state (None if no radio is active)
+    QueryActiveRadio {
+        response: oneshot::Sender<Option<(RadioHandle, RadioStateSummary)>>,
+    },
+
+    /// Send a request to whichever radio is active
+    ///
+    /// PTT goes through the sequencer, as with [`MuxActorCommand::RequestPtt`].
+    ControlActiveRadio {
+        /// What to ask of the radio
+        request: RadioRequest,
+        /// Whether there was an active radio with a command channel to
+        /// send it to
+        response: oneshot::Sender<bool>,
+    },
+
+    /// Tune a radio to a typed frequency, checked against its capabilities
+    /// and, for `transmit`, the amateur bands (see [`crate::freq_entry`])
+    EnterFrequency {
+        /// Radio to tune
+        handle: RadioHandle,
+        /// On-air frequency
+        hz: Frequency,
+        /// The frequency is to transmit on
+        transmit: bool,
+        /// Band plan a transmit frequency is checked against
+        region: BandPlanRegion,
+        /// Sent, or why not
+        response: oneshot::Sender<Result<(), FrequencyEntryError>>,
+    },
+
     /// Update a radio's metadata
     UpdateRadioMeta {
         /// Handle of the radio to update
@@ -144,6 +333,28 @@ pub enum MuxActorCommand {
         name: Option<String>,
     },
 
+    /// Parse and encode a radio's traffic with a different protocol
+    ///
+    /// The radio's codec is replaced, dropping any partial frame buffered
+    /// under the old protocol. Emits [`MuxEvent::RadioProtocolChanged`].
+    SetRadioProtocol {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Protocol to use from now on
+        protocol: Protocol,
+    },
+
+    /// Parse a radio's traffic as the model it identified itself as
+    ///
+    /// The radio's codec is rebuilt with the model's mode codes (an Elecraft
+    /// on a Kenwood link reads MD6 as DATA, not RTTY).
+    SetRadioModel {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Model the radio identified as
+        model: RadioModel,
+    },
+
     /// Connect an amplifier
     ConnectAmplifier {
         /// The amplifier channel
@@ -153,6 +364,49 @@ pub enum MuxActorCommand {
     /// Disconnect the amplifier
     DisconnectAmplifier,
 
+    /// Connect a shadow amplifier that mirrors the primary for A/B comparison
+    ConnectShadowAmplifier {
+        /// The shadow amplifier channel
+        channel: AmplifierChannel,
+    },
+
+    /// Disconnect the shadow amplifier
+    DisconnectShadowAmplifier,
+
+    /// Connect a backup amplifier that stands by for failover
+    ConnectBackupAmplifier {
+        /// The backup amplifier channel
+        channel: AmplifierChannel,
+    },
+
+    /// Disconnect the backup amplifier
+    DisconnectBackupAmplifier,
+
+    /// Raw data received from the backup amplifier (handled like
+    /// [`MuxActorCommand::AmpRawData`] while it is in service)
+    BackupAmpRawData {
+        /// Raw bytes received
+        data: Vec<u8>,
+    },
+
+    /// An amplifier's connection failed (port error); fails over if it was
+    /// in service and failover is on
+    AmpFailed {
+        /// Which amplifier failed
+        role: AmpRole,
+        /// What went wrong
+        reason: String,
+    },
+
+    /// Turn amplifier failover on or off
+    SetAmpFailover {
+        /// Move output off a failing amp
+        enabled: bool,
+    },
+
+    /// Put the primary amplifier back in service after a failover
+    AcknowledgeAmpFailover,
+
     /// Set the amplifier configuration (protocol, port, etc.)
     SetAmplifierConfig {
         /// Port name
@@ -163,6 +417,10 @@ pub enum MuxActorCommand {
         baud_rate: u32,
         /// CI-V address for Icom
         civ_address: Option<u8>,
+        /// Protocol to reply in, if the amp expects a different one than it sends
+        response_protocol: Option<Protocol>,
+        /// Translation strictness for this amp (`None` = the mux-wide setting)
+        strictness: Option<TranslationStrictness>,
     },
 
     /// Set the switching mode
@@ -171,6 +429,143 @@ pub enum MuxActorCommand {
         mode: SwitchingMode,
     },
 
+    /// Connect an OTRSP band decoder (band data and TX/RX routing)
+    ConnectBandDecoder {
+        /// Sender for bytes to write to the band decoder
+        tx: mpsc::Sender<Vec<u8>>,
+    },
+
+    /// Disconnect the band decoder
+    DisconnectBandDecoder,
+
+    /// Collapse bursts of same-kind reports from a radio (0 = off)
+    SetRadioInputThrottle {
+        /// Radio to throttle
+        handle: RadioHandle,
+        /// Window length in milliseconds
+        ms: u64,
+    },
+
+    /// Set the staleness threshold for auto-switching (None disables it)
+    SetStaleThreshold {
+        /// Seconds without data before a radio is considered stale
+        secs: Option<u64>,
+    },
+
+    /// Set PTT sequencer lead/tail delays (all zero disables sequencing)
+    SetSequencerConfig {
+        /// New sequencer timing
+        config: SequencerConfig,
+    },
+
+    /// Translate for the amplifier but don't write to it
+    ///
+    /// Writes still appear as [`MuxEvent::AmpDataOut`], marked suppressed.
+    SetAmpDryRun { enabled: bool },
+
+    /// Set how long amp-bound changes are collected before one write (0 = off)
+    SetAmpCoalesceWindow {
+        /// Window length in milliseconds
+        ms: u64,
+    },
+
+    /// Set the mux-wide translation strictness (an amp's own setting wins)
+    SetTranslationStrictness {
+        /// Strict drops untranslatable frames; permissive passes them through
+        strictness: TranslationStrictness,
+    },
+
+    /// Set the amplifier's own translation strictness without reconnecting it
+    SetAmpTranslationStrictness {
+        /// `None` follows the mux-wide setting
+        strictness: Option<TranslationStrictness>,
+    },
+
+    /// Set how often frequency changes are passed on to each output
+    SetFrequencySmoothing {
+        /// Policy per output
+        config: FrequencySmoothing,
+    },
+
+    /// Configure frequency-dependent amplifier bypass
+    SetAmpBypassConfig {
+        /// Amp range, personality and hysteresis
+        config: AmpBypassConfig,
+    },
+
+    /// Set what the amp is told while a switch is held off
+    SetTransitionPolicy {
+        /// Hold updates, answer busy, or nothing
+        policy: TransitionPolicy,
+    },
+
+    /// Bring the amp up to date with the new radio before letting it key
+    /// on a switch (see [`crate::handover`])
+    SetGaplessHandover {
+        /// Hold keying until the amp's port has written the update
+        enabled: bool,
+    },
+
+    /// Configure the same-band guard across radios
+    SetBandGuardConfig {
+        /// Strictness and whether mode classes count
+        config: BandGuardConfig,
+    },
+
+    /// Key or unkey a radio, sequenced with the amplifier when enabled
+    RequestPtt {
+        /// Radio to key
+        handle: RadioHandle,
+        /// True to transmit
+        active: bool,
+    },
+
+    /// A PTT switch was pressed or released (see [`crate::ptt_switch`])
+    ///
+    /// Pressing keys the active radio; releasing unkeys the radio the press
+    /// keyed. The amplifier is keyed with it.
+    SwitchPtt {
+        /// What the switch is, for the log
+        source: String,
+        /// True while the switch is pressed
+        active: bool,
+    },
+
+    /// Drop a bookmark (from a hardware trigger or the UI)
+    AddBookmark {
+        /// What triggered it
+        source: String,
+    },
+
+    /// Set the PTT double-tap bookmark window (None disables it)
+    SetBookmarkDoubleTap {
+        /// Longest gap between the two key-downs, in milliseconds
+        ms: Option<u64>,
+    },
+
+    /// Emit a [`MuxEvent::ContactSnapshot`] each time the active radio unkeys
+    SetContactSnapshots { enabled: bool },
+
+    /// A spot arrived from the DX cluster
+    AddDxSpot {
+        /// The spot
+        spot: DxSpot,
+    },
+
+    /// Start or stop sharing state with peer instances (see [`crate::peer_sync`])
+    SetPeerSync {
+        /// This instance's name, used to break ties between peers
+        instance: String,
+        /// Where local state changes go, `None` to stop and forget all peers
+        outgoing: Option<mpsc::Sender<PeerState>>,
+    },
+
+    /// A status arrived from a peer instance
+    PeerStatus {
+        /// The status
+        status: PeerStatus,
+    },
+
     /// Report an error from an async task (emits MuxEvent::Error)
     ReportError {
         /// Source of the error (e.g., "Radio", "Amplifier")
@@ -181,6 +576,10 @@ pub enum MuxActorCommand {
 
     /// Shutdown the actor
     Shutdown,
+
+    /// Make the actor panic, or hang until aborted (watchdog tests)
+    #[cfg(test)]
+    InjectFault { hang: bool },
 }
 
 /// Internal state for the mux actor
@@ -190,49 +589,234 @@ struct MuxActorState {
     /// Registered radio channels (keyed by handle)
     radio_channels: HashMap<RadioHandle, RadioChannelMeta>,
     /// Protocol codecs for parsing raw data (keyed by handle)
-    codecs: HashMap<RadioHandle, Box<dyn RadioCodec>>,
+    codecs: HashMap<RadioHandle, RadioSideCodec>,
     /// Command senders for radios (for AI2 heartbeat)
     radio_cmd_tx: HashMap<RadioHandle, mpsc::Sender<RadioTaskCommand>>,
     /// Amplifier data sender (for sending translated commands)
     amp_tx: Option<mpsc::Sender<Vec<u8>>>,
+    /// Bytes queued to the amplifier but not yet written
+    amp_backlog: Option<BacklogMonitor>,
+    /// Amplifier priority sender for safety frames (None = use `amp_tx`)
+    amp_urgent_tx: Option<mpsc::Sender<Vec<u8>>>,
+    /// Amplifier writes are shown but not sent
+    amp_dry_run: bool,
     /// Amplifier metadata
     amp_meta: Option<crate::amplifier::AmplifierChannelMeta>,
     /// Codec for parsing amplifier data
-    amp_codec: Option<Box<dyn RadioCodec>>,
+    amp_codec: Option<ControllerSideCodec>,
     /// Whether auto-info mode is enabled (amp requested updates via AI2)
     auto_info_enabled: bool,
-    /// Cached state for responding to amplifier queries
-    cached_frequency_hz: Option<u64>,
-    cached_mode: Option<OperatingMode>,
-    cached_ptt: bool,
-    /// Cached control band (0=Main/A, 1=Sub/B) - which VFO has front panel control
-    cached_control_band: Option<u8>,
-    /// Cached transmit band (0=Main/A, 1=Sub/B) - which VFO is selected for TX
-    cached_tx_band: Option<u8>,
+    /// Queries the amplifier has issued, for profiling and update limiting
+    amp_queries: AmpQueryTracker,
+    /// Query profile last reported to observers
+    amp_query_profile: AmpQueryProfile,
+    /// What the amp has been told, for answering its queries
+    amp_state: AmplifierEmulatedState,
+    /// `amp_state` as last reported to observers
+    reported_amp_state: AmplifierEmulatedState,
     /// Cached RX VFO (0=A, 1=B) - for inferring CB/TB from VFO commands
     cached_rx_vfo: Option<u8>,
     /// Cached split state - for inferring TB from split commands
     cached_split: bool,
+    /// Radios currently reported as stale
+    stale_radios: HashSet<RadioHandle>,
+    /// Band decoder data sender (None when no band decoder is connected)
+    band_decoder_tx: Option<mpsc::Sender<Vec<u8>>>,
+    /// Band decoder port assignments and last-sent state
+    band_decoder: BandDecoderSink,
+    /// Amp/radio PTT ordering
+    sequencer: PttSequencer,
+    /// Amp-bound changes held back until the current burst ends
+    coalescer: BurstCoalescer,
+    /// Mux-wide translation strictness, for amps without their own
+    translation_strictness: TranslationStrictness,
+    /// Transmit time and duty cycle per radio and band
+    tx_stats: TxStats,
+    /// Smoothing policies for the outputs
+    smoothing: FrequencySmoothing,
+    /// The active radio's frequency changes, as passed on to the amp
+    amp_smoother: FrequencySmoother,
+    /// Each radio's frequency changes, as passed on to the band decoder
+    band_smoothers: HashMap<RadioHandle, FrequencySmoother>,
+    /// Out-of-range amp bypass
+    amp_bypass: AmpBypass,
+    /// Switch held off, while the amp is told to wait
+    transition: Transition,
+    /// Two-phase switches: keying held off until the amp has the new radio
+    handover: Handover,
+    /// Rate limiter for meter events
+    meter_throttle: MeterThrottle,
+    /// Latest meter readings, for amps that display them
+    meter_cache: MeterCache,
+    /// How each radio's frames are split across reads
+    reassembly: HashMap<RadioHandle, ReassemblyTracker>,
+    /// Whether each Icom radio echoes what it's sent
+    civ_echo: HashMap<RadioHandle, EchoDetector>,
+    /// Why each recent switch happened
+    switch_audit: SwitchAuditLog,
+    /// Manual switch during TX waiting for confirmation
+    switch_confirm: SwitchConfirmation,
+    /// Push the outgoing radio's keyer speed to the newly active radio
+    carry_keyer_speed: bool,
+    /// Restore the active radio's last mode on each band it moves to
+    band_mode_memory: bool,
+    /// Drop reports that repeat an inactive radio's known state
+    suppress_repeats: bool,
+    /// Each radio's most recent frame, for the switch audit
+    last_frames: HashMap<RadioHandle, Vec<u8>>,
+    /// Band each radio's KAT500 was last switched to (Elecraft band number)
+    tuner_bands: HashMap<RadioHandle, u8>,
+    /// Id for the next frame received from a radio or amp
+    next_frame_id: u64,
+    /// Each radio's most recent frame id, for reports its throttle releases later
+    last_frame_ids: HashMap<RadioHandle, FrameId>,
+    /// Frame being handled, recorded as the cause of amp writes it leads to
+    cause: Option<FrameId>,
+    /// Latest frame behind an amp update held back (burst, backlog or smoothing)
+    held_cause: Option<FrameId>,
+    /// Codecs holding the start of a frame, and when it stops being worth waiting for
+    partial_frames: PartialFrameTimer,
+    /// Per-radio collapsing of report bursts (transceive storms)
+    input_throttle: InputThrottle,
+    /// PTT edges read from a radio that the amp hasn't followed yet, and when
+    /// they were read
+    ptt_edges: HashMap<RadioHandle, (bool, Instant)>,
+    /// Shadow amplifier data sender (None when no shadow is connected)
+    shadow_amp_tx: Option<mpsc::Sender<Vec<u8>>>,
+    /// Codec for parsing shadow amplifier data
+    shadow_amp_codec: Option<ControllerSideCodec>,
+    /// Primary vs shadow amplifier comparison
+    shadow: ShadowComparator,
+    /// Which amplifier is in service
+    amp_failover: AmpFailover,
+    /// Backup amplifier while standing by (in service it is the amp)
+    backup_amp: Option<SavedAmp>,
+    /// Primary amplifier while failed over from
+    standby_primary: Option<SavedAmp>,
+    /// PTT double-tap bookmark trigger
+    double_tap: PttDoubleTap,
+    /// Radio a PTT switch is holding keyed
+    switch_ptt: Option<RadioHandle>,
+    /// Snapshot the active radio on PTT release
+    contact_snapshots: bool,
+    /// Recent DX cluster spots
+    dx_spots: SpotCache,
+    /// Radio and spot last reported as matched (station, frequency)
+    spot_match: Option<(RadioHandle, String, Frequency)>,
+    /// Monitor channels retuned to follow the active radio
+    monitor_followers: MonitorFollowers,
+    /// User command handlers, by radio (see [`cat_protocol::hooks`])
+    radio_hooks: HashMap<RadioHandle, CommandHooks>,
+    /// Events from command handlers, not yet emitted
+    custom_events: Vec<(RadioHandle, CustomEvent)>,
+    /// Instance name and outgoing channel while peer sync is on
+    peer_sync: Option<(String, mpsc::Sender<PeerState>)>,
+    /// Latest status of each peer instance
+    peers: PeerTable,
+    /// Local state last sent to peers
+    peer_state_sent: Option<PeerState>,
+    /// When the active radio went into transmit (ms since the Unix epoch)
+    local_tx_since: Option<u64>,
+    /// Peer holding the band, while the amp is locked out of transmit
+    peer_block: Option<String>,
+    /// Local radio holding the band, while the amp is locked out of transmit
+    band_block: Option<RadioHandle>,
+    /// Channels to restore if the actor is restarted (see [`crate::supervisor`])
+    registry: ChannelRegistry,
 }
 
 impl MuxActorState {
-    fn new() -> Self {
+    /// Number a received frame
+    fn frame_id(&mut self) -> FrameId {
+        self.next_frame_id += 1;
+        FrameId(self.next_frame_id)
+    }
+
+    fn new(registry: ChannelRegistry) -> Self {
         Self {
             multiplexer: Multiplexer::new(),
             radio_channels: HashMap::new(),
             codecs: HashMap::new(),
             radio_cmd_tx: HashMap::new(),
             amp_tx: None,
+            amp_backlog: None,
+            amp_urgent_tx: None,
+            amp_dry_run: false,
             amp_meta: None,
             amp_codec: None,
             auto_info_enabled: false,
-            cached_frequency_hz: None,
-            cached_mode: None,
-            cached_ptt: false,
-            cached_control_band: None,
-            cached_tx_band: None,
+            amp_queries: AmpQueryTracker::new(),
+            amp_query_profile: AmpQueryProfile::default(),
+            amp_state: AmplifierEmulatedState::default(),
+            reported_amp_state: AmplifierEmulatedState::default(),
             cached_rx_vfo: None,
             cached_split: false,
+            stale_radios: HashSet::new(),
+            band_decoder_tx: None,
+            band_decoder: BandDecoderSink::new(),
+            sequencer: PttSequencer::new(),
+            coalescer: BurstCoalescer::default(),
+            translation_strictness: TranslationStrictness::default(),
+            tx_stats: TxStats::new(),
+            smoothing: FrequencySmoothing::default(),
+            amp_smoother: FrequencySmoother::new(FrequencySmoothing::default().amplifier),
+            band_smoothers: HashMap::new(),
+            amp_bypass: AmpBypass::new(),
+            transition: Transition::new(),
+            handover: Handover::new(),
+            meter_throttle: MeterThrottle::new(),
+            meter_cache: MeterCache::new(),
+            reassembly: HashMap::new(),
+            civ_echo: HashMap::new(),
+            switch_audit: SwitchAuditLog::default(),
+            switch_confirm: SwitchConfirmation::new(),
+            carry_keyer_speed: false,
+            band_mode_memory: false,
+            suppress_repeats: false,
+            last_frames: HashMap::new(),
+            tuner_bands: HashMap::new(),
+            next_frame_id: 0,
+            last_frame_ids: HashMap::new(),
+            cause: None,
+            held_cause: None,
+            partial_frames: PartialFrameTimer::new(),
+            input_throttle: InputThrottle::new(),
+            ptt_edges: HashMap::new(),
+            shadow_amp_tx: None,
+            shadow_amp_codec: None,
+            shadow: ShadowComparator::new(),
+            amp_failover: AmpFailover::new(),
+            backup_amp: None,
+            standby_primary: None,
+            double_tap: PttDoubleTap::new(),
+            switch_ptt: None,
+            contact_snapshots: false,
+            dx_spots: SpotCache::new(),
+            spot_match: None,
+            monitor_followers: MonitorFollowers::new(),
+            radio_hooks: HashMap::new(),
+            custom_events: Vec::new(),
+            peer_sync: None,
+            peers: PeerTable::new(),
+            peer_state_sent: None,
+            local_tx_since: None,
+            peer_block: None,
+            band_block: None,
+            registry,
+        }
+    }
+
+    /// A codec for a radio, wrapped with its command handlers if it has any
+    fn radio_codec(
+        &self,
+        handle: RadioHandle,
+        protocol: Protocol,
+        model: Option<&RadioModel>,
+    ) -> RadioSideCodec {
+        let codec = RadioSideCodec::for_model(protocol, model);
+        match self.radio_hooks.get(&handle) {
+            Some(hooks) => codec.with_hooks(hooks.clone()),
+            None => codec,
         }
     }
 
@@ -258,105 +842,137 @@ async fn process_radio_response(
         );
         return;
     };
+    // Everything past here sees on-air frequencies
+    let response = response_to_air(response, meta.frequency_offset_hz);
 
     debug!(
         "Processing response from radio {} (handle {}): {:?}",
         meta.display_name, handle.0, response
     );
 
+    // A poll answered with what's already known changes nothing: it's in
+    // the traffic log, and that's all the work it needs. The active radio's
+    // reports always go on to the amp.
+    if state.suppress_repeats && state.multiplexer.active_radio() != Some(handle) {
+        if let Some(radio) = state.multiplexer.get_radio_mut(handle) {
+            if radio.repeats(&response) {
+                radio.touch();
+                return;
+            }
+        }
+    }
+
+    if let Some((meter, level)) = response.meter() {
+        state
+            .meter_cache
+            .record(handle, meter, level, Instant::now());
+        if let Some(level) = state
+            .meter_throttle
+            .offer(handle, meter, level, Instant::now())
+        {
+            let _ = event_tx
+                .send(MuxEvent::RadioMeter {
+                    handle,
+                    meter,
+                    level,
+                })
+                .await;
+        }
+    }
+
     // Update cached CB/TB state from radio reports (only from active radio)
     if state.multiplexer.active_radio() == Some(handle) {
         match &response {
             RadioResponse::ControlBand { band } => {
-                state.cached_control_band = Some(*band);
+                state.amp_state.control_band = Some(*band);
                 debug!("Updated cached control band to {}", band);
             }
             RadioResponse::TransmitBand { band } => {
-                state.cached_tx_band = Some(*band);
+                state.amp_state.tx_band = Some(*band);
                 debug!("Updated cached transmit band to {}", band);
             }
+            // Dual-TX radios (TS-990) key up on a chosen receiver
+            RadioResponse::Status {
+                ptt: Some(true),
+                vfo: Some(vfo @ (Vfo::A | Vfo::B)),
+                ..
+            } => {
+                let band = if *vfo == Vfo::A { 0 } else { 1 };
+                state.amp_state.tx_band = Some(band);
+                debug!("Transmitting on receiver {}: TB={}", band, band);
+            }
             // Infer CB/TB from VFO responses (for radios that don't report CB/TB directly)
-            RadioResponse::Vfo { vfo } => match vfo {
-                Vfo::A => {
-                    // VFO A selected - RX on A, control on A
-                    state.cached_rx_vfo = Some(0);
-                    state.cached_control_band = Some(0);
-                    // Selecting VFO A/B clears split mode
-                    state.cached_split = false;
-                    state.cached_tx_band = Some(0);
-                    debug!("VFO A selected: CB=0, TB=0, split=false");
+            RadioResponse::Vfo { .. } | RadioResponse::VfoState { .. } => {
+                if let Some(vfo_state) = response.vfo_state() {
+                    apply_vfo_state(state, vfo_state);
                 }
-                Vfo::B => {
-                    // VFO B selected - RX on B, control on B
-                    state.cached_rx_vfo = Some(1);
-                    state.cached_control_band = Some(1);
-                    // Selecting VFO A/B clears split mode
-                    state.cached_split = false;
-                    state.cached_tx_band = Some(1);
-                    debug!("VFO B selected: CB=1, TB=1, split=false");
-                }
-                Vfo::Split => {
-                    // Split enabled - TX on opposite of current RX VFO
-                    state.cached_split = true;
-                    let rx = state.cached_rx_vfo.unwrap_or(0);
-                    state.cached_tx_band = Some(1 - rx); // Opposite of RX
-                                                         // CB stays as current RX VFO
-                    debug!(
-                        "Split enabled: CB={}, TB={} (RX on {}, TX on opposite)",
-                        state.cached_control_band.unwrap_or(0),
-                        state.cached_tx_band.unwrap_or(1),
-                        rx
-                    );
-                }
-                Vfo::Memory => {
-                    // Memory mode - treat as VFO A, no split
-                    state.cached_rx_vfo = Some(0);
-                    state.cached_control_band = Some(0);
-                    state.cached_tx_band = Some(0);
-                    state.cached_split = false;
-                    debug!("Memory mode: CB=0, TB=0, split=false");
-                }
-            },
+            }
             _ => {}
         }
     }
 
     // Capture old state with a single lookup
-    let (old_freq, old_mode, old_ptt) = state
+    let (old_freq, old_mode, old_ptt, old_power) = state
         .multiplexer
         .get_radio(handle)
-        .map(|r| (r.frequency_hz, r.mode, Some(r.ptt)))
-        .unwrap_or((None, None, None));
+        .map(|r| (r.frequency_hz, r.mode, Some(r.ptt), r.power_watts))
+        .unwrap_or((None, None, None, None));
     let old_active = state.multiplexer.active_radio();
 
     // Process through multiplexer
     let amp_data = state.multiplexer.process_radio_response(handle, &response);
+    update_transition(state, event_tx).await;
 
     // Capture new state with a single lookup
-    let (new_freq, new_mode, new_ptt) = state
+    let (new_freq, new_mode, new_ptt, new_power) = state
         .multiplexer
         .get_radio(handle)
-        .map(|r| (r.frequency_hz, r.mode, Some(r.ptt)))
-        .unwrap_or((None, None, None));
+        .map(|r| (r.frequency_hz, r.mode, Some(r.ptt), r.power_watts))
+        .unwrap_or((None, None, None, None));
     let new_active = state.multiplexer.active_radio();
 
     // Emit state change event if anything changed
     let freq_changed = old_freq != new_freq;
     let mode_changed = old_mode != new_mode;
     let ptt_changed = old_ptt != new_ptt;
+    let power_changed = old_power != new_power;
 
-    if freq_changed || mode_changed || ptt_changed {
+    if freq_changed || mode_changed || ptt_changed || power_changed {
         let _ = event_tx
             .send(MuxEvent::RadioStateChanged {
                 handle,
                 freq: if freq_changed { new_freq } else { None },
                 mode: if mode_changed { new_mode } else { None },
                 ptt: if ptt_changed { new_ptt } else { None },
+                power_watts: if power_changed { new_power } else { None },
             })
             .await;
     }
 
+    if freq_changed {
+        follow_tuner_band(state, handle, new_freq).await;
+    }
+
+    if let (true, Some(active)) = (ptt_changed, new_ptt) {
+        let band = new_freq.and_then(band_name);
+        state.tx_stats.on_ptt(handle, active, band, Instant::now());
+        if state.double_tap.on_ptt(handle, active, Instant::now()) {
+            let name = state
+                .get_radio_meta(handle)
+                .map(|m| m.display_name.clone())
+                .unwrap_or_else(|| format!("Radio {}", handle.0));
+            add_bookmark(state, event_tx, format!("PTT double-tap on {}", name)).await;
+        }
+        if !active && state.contact_snapshots && new_active == Some(handle) {
+            snapshot_contact(state, event_tx, handle).await;
+        }
+        if !active {
+            refresh_keyer_speed(state, handle).await;
+        }
+    }
+
     // Emit active radio change event if needed
+    let mut presynced = false;
     if old_active != new_active {
         if let Some(to) = new_active {
             let _ = event_tx
@@ -365,606 +981,7047 @@ async fn process_radio_response(
                     to,
                 })
                 .await;
+            audit_switch(state, event_tx).await;
+            carry_keyer_speed(state, old_active, to).await;
+            presynced = begin_handover(state, event_tx, to).await;
         }
     }
 
+    if freq_changed || mode_changed {
+        update_band_mode(state, handle, old_freq, new_active == Some(handle)).await;
+    }
+
+    // Antennas must be on the radio's band before it transmits
+    if ptt_changed && new_ptt == Some(true) {
+        flush_band_smoother(state, handle).await;
+    }
+    if freq_changed || old_active != new_active {
+        sync_band_decoder(state).await;
+        update_amp_bypass(state, event_tx).await;
+        update_spot_match(state, event_tx).await;
+        sync_monitor_followers(state).await;
+    }
+    if freq_changed || ptt_changed || old_active != new_active {
+        update_peer_sync(state, event_tx).await;
+    }
+    if freq_changed || mode_changed || ptt_changed || old_active != new_active {
+        let keyed_up = new_active == Some(handle) && ptt_changed && new_ptt == Some(true);
+        update_band_guard(state, event_tx, keyed_up).await;
+    }
+
     // Check if this radio is now the active radio (for auto-info updates)
     let is_active = new_active == Some(handle);
 
-    // Send to amplifier if there's data and auto-info is enabled
-    if let Some(data) = amp_data {
-        // Only send if auto-info is enabled (amp requested updates via AI2)
-        if state.auto_info_enabled {
-            let amp_protocol = state.multiplexer.amplifier_config().protocol;
+    // With the sequencer on, amp keying follows the sequencer rather than the radio
+    let sequenced = state.sequencer.is_enabled();
+    let amp_data = amp_data.filter(|_| !(sequenced && response.ptt().is_some()));
+    let amp_data = amp_data.filter(|_| !(amp_ptt_inhibited(state) && response.ptt() == Some(true)));
+    // The handover already gave the amp this radio's frequency and mode
+    let amp_data = amp_data.filter(|_| !(presynced && response.ptt().is_none()));
 
-            // Emit traffic event for data going to amplifier
-            let _ = event_tx
-                .send(MuxEvent::AmpDataOut {
-                    data: data.clone(),
-                    protocol: amp_protocol,
-                    timestamp: SystemTime::now(),
-                })
-                .await;
+    // A burst from the previous radio must not reach the amp after a switch
+    if old_active != new_active {
+        state.coalescer.clear();
+        state.amp_smoother.clear();
+    }
 
-            // Send to amplifier if connected
-            if let Some(ref tx) = state.amp_tx {
-                if let Err(e) = tx.send(data).await {
-                    warn!("Failed to send to amplifier: {}", e);
-                    let _ = event_tx
-                        .send(MuxEvent::Error {
-                            source: "Amplifier".to_string(),
-                            message: format!("Send failed: {}", e),
-                        })
-                        .await;
+    // When coalescing, the active radio's changes go out when the burst window closes
+    let coalesce = is_active && state.coalescer.is_enabled();
+    if coalesce {
+        state
+            .coalescer
+            .record(&response, !sequenced, Instant::now());
+        state.held_cause = state.cause;
+    }
+    let amp_data = amp_data.filter(|_| !coalesce);
+
+    // While the amp's output is backed up or a switch is held off, frequency
+    // and mode changes are held like a burst and only the latest goes out
+    // once it drains or the hold ends; PTT is never held
+    let hold = is_active
+        && !coalesce
+        && response.ptt().is_none()
+        && (state.transition.holds_updates() || amp_backed_up(state, event_tx).await);
+    if hold {
+        state.coalescer.record(&response, false, Instant::now());
+        state.held_cause = state.cause;
+    }
+    let amp_data = amp_data.filter(|_| !hold);
+
+    // A frequency-only change (a VFO spin) goes through the amp's smoothing
+    // policy; one that comes with a mode or PTT change, or a radio switch,
+    // goes out at once and after any frequency still held
+    let mut smoothed = false;
+    if is_active && !coalesce && !hold {
+        let now = Instant::now();
+        let changed_freq = new_freq.filter(|_| freq_changed);
+        if mode_changed || ptt_changed || old_active != new_active {
+            match changed_freq {
+                Some(hz) => state.amp_smoother.force(hz, now),
+                None => {
+                    if let Some(hz) = state.amp_smoother.flush(now) {
+                        release_amp_frequency(state, event_tx, hz).await;
+                    }
                 }
             }
+        } else if let Some(hz) = changed_freq {
+            smoothed = !state.amp_smoother.offer(hz, now);
+            if smoothed {
+                state.held_cause = state.cause;
+            }
+        }
+    }
+    let amp_data = amp_data.filter(|_| !smoothed);
+
+    // A key-up takes anything held for the amp with it
+    let released = is_active
+        && !coalesce
+        && amp_data.is_some()
+        && key_up_with_held(state, event_tx, &response).await;
+    let amp_data = amp_data.filter(|_| !released);
+
+    // Coalesce a simultaneous frequency + mode change into a single amp write
+    // so the amp never sees the new frequency with the old mode
+    let amp_data = match (amp_data, new_freq, new_mode) {
+        (Some(data), Some(hz), Some(mode)) if freq_changed && mode_changed => {
+            let amp_protocol = state.multiplexer.amplifier_config().encode_protocol();
+            translate_frequency_mode(hz, mode, amp_protocol)
+                .ok()
+                .or(Some(data))
+        }
+        (amp_data, _, _) => amp_data,
+    };
+
+    // Send to amplifier if there's data and auto-info is enabled
+    if let Some(data) = amp_data {
+        // Only send if auto-info is enabled (amp requested updates via AI2)
+        if state.auto_info_enabled && state.amp_queries.allow_update(&response, Instant::now()) {
+            if response.ptt() == Some(true) {
+                release_withheld_updates(state, event_tx).await;
+            }
+            let amp_protocol = state.multiplexer.amplifier_config().encode_protocol();
+            let priority = AmpPriority::for_response(&response);
+            send_amp_data(state, event_tx, data, amp_protocol, priority).await;
         }
 
         // Always update cached state so we can respond to amp queries
         if let Some(hz) = new_freq {
-            state.cached_frequency_hz = Some(hz);
+            state.amp_state.frequency_hz = Some(hz);
         }
         if let Some(mode) = new_mode {
-            state.cached_mode = Some(mode);
+            state.amp_state.mode = Some(mode);
         }
-        if let Some(ptt) = new_ptt {
-            state.cached_ptt = ptt;
+        // Only a plain PTT report is forwarded as PTT; a status report's PTT
+        // (e.g. a TS-990 keying up on the sub receiver) goes out below, after
+        // its frequency
+        if let (RadioResponse::Ptt { active }, false) = (&response, sequenced) {
+            state.amp_state.keyed = *active;
         }
     }
 
     // Send auto-info updates if enabled and this is the active radio
-    if is_active && state.auto_info_enabled && state.amp_tx.is_some() {
+    if is_active
+        && !coalesce
+        && !hold
+        && !smoothed
+        && state.auto_info_enabled
+        && state.amp_tx.is_some()
+    {
         // Send unsolicited updates for changed state
         if freq_changed {
             if let Some(hz) = new_freq {
                 // Only send if different from what amp already knows
-                if state.cached_frequency_hz != Some(hz) {
-                    state.cached_frequency_hz = Some(hz);
-                    send_to_amp(state, event_tx, RadioResponse::Frequency { hz }).await;
+                if state.amp_state.frequency_hz != Some(hz) {
+                    state.amp_state.frequency_hz = Some(hz);
+                    let update = RadioResponse::Frequency { hz };
+                    if state.amp_queries.allow_update(&update, Instant::now()) {
+                        send_to_amp(state, event_tx, update).await;
+                    }
                 }
             }
         }
         if mode_changed {
             if let Some(mode) = new_mode {
-                if state.cached_mode != Some(mode) {
-                    state.cached_mode = Some(mode);
-                    send_to_amp(state, event_tx, RadioResponse::Mode { mode }).await;
+                if state.amp_state.mode != Some(mode) {
+                    state.amp_state.mode = Some(mode);
+                    let update = RadioResponse::Mode { mode };
+                    if state.amp_queries.allow_update(&update, Instant::now()) {
+                        send_to_amp(state, event_tx, update).await;
+                    }
                 }
             }
         }
-        if ptt_changed {
+        if ptt_changed && !sequenced {
             if let Some(ptt) = new_ptt {
-                if state.cached_ptt != ptt {
-                    state.cached_ptt = ptt;
-                    send_to_amp(state, event_tx, RadioResponse::Ptt { active: ptt }).await;
+                if state.amp_state.keyed != ptt {
+                    send_ptt_to_amp(state, event_tx, ptt).await;
                 }
             }
         }
     }
+
+    if sequenced && is_active && ptt_changed {
+        if let Some(ptt) = new_ptt {
+            let actions = state.sequencer.on_radio_ptt(handle, ptt, Instant::now());
+            apply_sequencer_actions(state, event_tx, actions).await;
+        }
+    }
 }
 
-/// Handle a query from the amplifier using cached state
+/// Infer control and transmit band from a radio's VFO report
 ///
-/// Returns `Some(RadioResponse)` with the response if we can answer,
-/// or `None` if we don't have the state to answer (amp should retry later).
-fn handle_amp_query(state: &MuxActorState, query: &RadioRequest) -> Option<RadioResponse> {
-    match query {
-        RadioRequest::GetFrequency => state
-            .cached_frequency_hz
-            .map(|hz| RadioResponse::Frequency { hz }),
-
-        RadioRequest::GetMode => state.cached_mode.map(|mode| RadioResponse::Mode { mode }),
+/// Band 0 is VFO A (or MAIN), band 1 is VFO B (or SUB). A report that names
+/// the transmit VFO or the split state sets the transmit band; one that only
+/// names the receive VFO leaves it following the receive VFO, or opposite it
+/// while split is on.
+fn apply_vfo_state(state: &mut MuxActorState, vfo_state: VfoState) {
+    let band = |vfo: Vfo| match vfo {
+        Vfo::B => Some(1),
+        Vfo::A | Vfo::Memory => Some(0),
+        Vfo::Split => None,
+    };
+    let rx = vfo_state
+        .rx_vfo
+        .and_then(band)
+        .or(vfo_state.active_receiver.map(|receiver| match receiver {
+            Receiver::Main => 0,
+            Receiver::Sub => 1,
+        }));
+    if let Some(rx) = rx {
+        state.cached_rx_vfo = Some(rx);
+        state.amp_state.control_band = Some(rx);
+    }
+    let rx = state.cached_rx_vfo.unwrap_or(0);
+
+    if let Some(tx) = vfo_state.tx_vfo.and_then(band) {
+        state.cached_split = tx != rx;
+        state.amp_state.tx_band = Some(tx);
+    } else if let Some(split) = vfo_state.is_split() {
+        state.cached_split = split;
+        state.amp_state.tx_band = Some(if split { 1 - rx } else { rx });
+    } else if vfo_state.rx_vfo == Some(Vfo::Split) {
+        state.cached_split = true;
+        state.amp_state.tx_band = Some(1 - rx);
+    } else if vfo_state.rx_vfo.is_some() || vfo_state.active_receiver.is_some() {
+        state.amp_state.tx_band = Some(if state.cached_split { 1 - rx } else { rx });
+    }
+    debug!(
+        "VFO report {:?}: CB={}, TB={}, split={}",
+        vfo_state,
+        state.amp_state.control_band.unwrap_or(0),
+        state.amp_state.tx_band.unwrap_or(0),
+        state.cached_split
+    );
+}
 
-        RadioRequest::GetPtt => Some(RadioResponse::Ptt {
-            active: state.cached_ptt,
-        }),
+/// Carry out amp and radio keying decided by the PTT sequencer
+async fn apply_sequencer_actions(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    actions: Vec<SequencerAction>,
+) {
+    for action in actions {
+        match action {
+            SequencerAction::KeyAmp(active) => {
+                debug!("Sequencer keying amplifier: {}", active);
+                if active {
+                    if let Some(handle) = state.sequencer.keying() {
+                        flush_band_smoother(state, handle).await;
+                    }
+                    if let Some(hz) = state.amp_smoother.flush(Instant::now()) {
+                        release_amp_frequency(state, event_tx, hz).await;
+                    }
+                }
+                let response = RadioResponse::Ptt { active };
+                if !key_up_with_held(state, event_tx, &response).await {
+                    state.amp_state.keyed = active;
+                    send_to_amp(state, event_tx, response).await;
+                }
+            }
+            SequencerAction::KeyRadio { handle, active } => {
+                debug!("Sequencer keying radio {}: {}", handle.0, active);
+                key_radio(state, handle, active).await;
+            }
+        }
+    }
+}
 
-        RadioRequest::GetAutoInfo => Some(RadioResponse::AutoInfo {
-            enabled: state.auto_info_enabled,
-        }),
+/// Key or unkey a radio, through the sequencer when it is enabled
+async fn request_ptt(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+    active: bool,
+) {
+    if state.sequencer.is_enabled() {
+        let actions = state.sequencer.request(handle, active, Instant::now());
+        apply_sequencer_actions(state, event_tx, actions).await;
+    } else {
+        key_radio(state, handle, active).await;
+    }
+}
 
-        // Always identify as TS-990S (ID022) to amplifiers
-        RadioRequest::GetId => Some(RadioResponse::Id {
-            id: "022".to_string(), // TS-990S
-        }),
+/// Key the active radio from a PTT switch, or unkey the radio it keyed
+async fn switch_ptt(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    source: &str,
+    active: bool,
+) {
+    if active {
+        if state.switch_ptt.is_some() {
+            return;
+        }
+        let Some(handle) = state.multiplexer.active_radio() else {
+            warn!("PTT switch {} pressed with no active radio", source);
+            return;
+        };
+        info!("PTT switch {} keying radio {}", source, handle.0);
+        state.switch_ptt = Some(handle);
+        request_ptt(state, event_tx, handle, true).await;
+        confirm_ptt(state, handle).await;
+    } else if let Some(handle) = state.switch_ptt.take() {
+        info!("PTT switch {} releasing radio {}", source, handle.0);
+        request_ptt(state, event_tx, handle, false).await;
+        confirm_ptt(state, handle).await;
+        release_amp(state, event_tx).await;
+    }
+}
 
-        // Control band query - return cached or default to main (0)
-        RadioRequest::GetControlBand => Some(RadioResponse::ControlBand {
-            band: state.cached_control_band.unwrap_or(0),
-        }),
+/// Ask a radio keyed from the mux to report its PTT
+///
+/// The amp only follows the radio's own report, which not every radio sends
+/// unasked. The sequencer keys the amp itself, so it doesn't need one.
+async fn confirm_ptt(state: &MuxActorState, handle: RadioHandle) {
+    if !state.sequencer.is_enabled() {
+        send_radio_request(state, handle, RadioRequest::GetPtt).await;
+    }
+}
 
-        // Transmit band query - return cached or default to main (0)
-        RadioRequest::GetTransmitBand => Some(RadioResponse::TransmitBand {
-            band: state.cached_tx_band.unwrap_or(0),
-        }),
+/// Unkey the amp for a switch release without waiting for the radio's report
+///
+/// The report may never reach the amp: the radio is gone, or is no longer
+/// the one the amp follows. The sequencer unkeys the amp itself.
+async fn release_amp(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if !state.sequencer.is_enabled() && state.amp_state.keyed {
+        unkey_amp(state, event_tx).await;
+    }
+}
 
-        _ => None,
+/// Ask a radio's task to change PTT
+async fn key_radio(state: &MuxActorState, handle: RadioHandle, active: bool) {
+    if !send_radio_request(state, handle, RadioRequest::SetPtt { active }).await {
+        warn!("No command channel for radio {}, cannot key it", handle.0);
     }
 }
 
-/// Send a RadioResponse to the amplifier
+/// Send a request to a radio's task, moved from on-air frequencies to its dial
 ///
-/// Translates the response to the amplifier's protocol and sends it.
-async fn send_to_amp(
+/// Returns false if the radio has no command channel.
+async fn send_radio_request(
     state: &MuxActorState,
-    event_tx: &mpsc::Sender<MuxEvent>,
-    response: RadioResponse,
-) {
-    let Some(ref tx) = state.amp_tx else {
-        return;
+    handle: RadioHandle,
+    request: RadioRequest,
+) -> bool {
+    let Some(tx) = state.radio_cmd_tx.get(&handle) else {
+        return false;
     };
+    let offset_hz = state
+        .get_radio_meta(handle)
+        .map_or(0, |m| m.frequency_offset_hz);
+    let request = request_to_dial(request, offset_hz);
+    let _ = tx.send(RadioTaskCommand::SendRequest { request }).await;
+    true
+}
 
-    let protocol = state.multiplexer.amplifier_config().protocol;
-
-    let data = match translate_response(&response, protocol) {
-        Ok(d) => d,
-        Err(e) => {
-            debug!("Cannot translate {:?} to {:?}: {}", response, protocol, e);
-            return;
-        }
+/// Band-switch a KAT500 sharing an Elecraft radio's line when the radio
+/// changes band
+///
+/// The tuner sits at the radio's antenna jack, so it follows the dial rather
+/// than the on-air frequency. Frequencies outside the Elecraft bands leave it
+/// where it is.
+async fn follow_tuner_band(state: &mut MuxActorState, handle: RadioHandle, hz: Option<Frequency>) {
+    let Some(meta) = state.get_radio_meta(handle).filter(|m| m.kat500) else {
+        return;
     };
-
-    // Emit traffic event
-    let _ = event_tx
-        .send(MuxEvent::AmpDataOut {
-            data: data.clone(),
-            protocol,
-            timestamp: SystemTime::now(),
-        })
-        .await;
-
-    // Send to amplifier
-    if let Err(e) = tx.send(data).await {
-        warn!("Failed to send to amplifier: {}", e);
-        let _ = event_tx
-            .send(MuxEvent::Error {
-                source: "Amplifier".to_string(),
-                message: format!("Send failed: {}", e),
-            })
-            .await;
+    let Some(band) = hz
+        .and_then(|hz| hz.checked_offset(meta.frequency_offset_hz.saturating_neg()))
+        .and_then(elecraft::band_number)
+    else {
+        return;
+    };
+    if state.tuner_bands.insert(handle, band) == Some(band) {
+        return;
     }
+    let Some(tx) = state.radio_cmd_tx.get(&handle) else {
+        return;
+    };
+    let data = ElecraftCommand::accessory_band(ElecraftAccessory::Kat500, band).encode();
+    debug!("Switching radio {}'s KAT500 to band {}", handle.0, band);
+    let _ = tx.send(RadioTaskCommand::SendData { data }).await;
 }
 
-/// Run the multiplexer actor
-///
-/// This async function processes all radio commands through the multiplexer
-/// and emits events through the event channel.
-///
-/// # Arguments
+/// Send the coalesced burst to the amplifier as one write
 ///
-/// * `cmd_rx` - Receiver for commands sent to the actor
-/// * `event_tx` - Sender for events emitted by the actor
-pub async fn run_mux_actor(
-    mut cmd_rx: mpsc::Receiver<MuxActorCommand>,
-    event_tx: mpsc::Sender<MuxEvent>,
-) {
-    let mut state = MuxActorState::new();
-    info!("Multiplexer actor started");
-
-    // AI2 heartbeat timer - sends AI2; to all Kenwood/Elecraft radios every second
-    let mut ai2_timer = interval(Duration::from_secs(1));
-    ai2_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
-
-    loop {
-        tokio::select! {
-            cmd = cmd_rx.recv() => {
-                let Some(cmd) = cmd else { break; };
-                match cmd {
-            MuxActorCommand::RegisterRadio {
-                meta,
-                response,
-                cmd_tx,
-            } => {
-                let name = meta.display_name.clone();
-                let port = meta
-                    .port_name
-                    .clone()
-                    .unwrap_or_else(|| "[VRT]".to_string());
-                let protocol = meta.protocol;
-
-                // Add to multiplexer
-                let handle = state
-                    .multiplexer
-                    .add_radio(name.clone(), port.clone(), protocol);
-
-                // Store the channel metadata
-                state.radio_channels.insert(handle, meta.clone());
-
-                // Create codec for parsing raw data
-                state.codecs.insert(handle, create_radio_codec(protocol));
-
-                // Store the command channel for AI2 heartbeat
-                if let Some(tx) = cmd_tx {
-                    state.radio_cmd_tx.insert(handle, tx);
-                }
+/// Fields the amplifier already has are dropped, so a burst that only
+/// repeats the current frequency and mode sends nothing.
+async fn flush_amp_burst(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    // Keep holding while the amp is backed up or a switch is held off,
+    // unless the burst keys it
+    if state.coalescer.pending().ptt.is_none()
+        && (state.transition.holds_updates() || amp_backed_up(state, event_tx).await)
+    {
+        state
+            .coalescer
+            .postpone(Instant::now() + BACKLOG_POLL_INTERVAL);
+        return;
+    }
 
-                // Send back the handle
-                let _ = response.send(handle);
+    let mut update = state.coalescer.take().without_known(
+        state.amp_state.frequency_hz,
+        state.amp_state.mode,
+        state.amp_state.keyed,
+    );
+    if amp_ptt_inhibited(state) && update.ptt == Some(true) {
+        update.ptt = None;
+    }
 
-                // Emit event
-                let _ = event_tx
-                    .send(MuxEvent::RadioConnected { handle, meta })
-                    .await;
+    // The burst's frequency is smoothed like a single report's
+    let now = Instant::now();
+    match update.frequency_hz {
+        Some(hz) if update.mode.is_some() || update.ptt.is_some() => {
+            state.amp_smoother.force(hz, now);
+        }
+        Some(hz) => {
+            let release = state.amp_smoother.offer(hz, now);
+            update.frequency_hz = update.frequency_hz.filter(|_| release);
+        }
+        None if update.mode.is_some() || update.ptt.is_some() => {
+            update.frequency_hz = state
+                .amp_smoother
+                .flush(now)
+                .filter(|hz| state.amp_state.frequency_hz != Some(*hz));
+        }
+        None => {}
+    }
+    if update.is_empty() {
+        return;
+    }
 
-                info!("Registered radio: {} (handle {})", name, handle.0);
+    if state.auto_info_enabled {
+        if update.ptt == Some(true) {
+            release_withheld_updates(state, event_tx).await;
+        }
+        let protocol = state.multiplexer.amplifier_config().encode_protocol();
+        match update.encode(protocol) {
+            Ok(data) => {
+                let current = state.cause;
+                state.cause = state.held_cause.take().or(current);
+                send_amp_data(state, event_tx, data, protocol, AmpPriority::Normal).await;
+                state.cause = current;
             }
+            Err(e) => debug!("Cannot translate {:?} to {:?}: {}", update, protocol, e),
+        }
+    }
 
-            MuxActorCommand::UnregisterRadio { handle } => {
-                if let Some(meta) = state.radio_channels.remove(&handle) {
-                    state.multiplexer.remove_radio(handle);
-                    state.codecs.remove(&handle);
-                    state.radio_cmd_tx.remove(&handle);
-
-                    // Emit event
-                    let _ = event_tx.send(MuxEvent::RadioDisconnected { handle }).await;
+    if let Some(hz) = update.frequency_hz {
+        state.amp_state.frequency_hz = Some(hz);
+    }
+    if let Some(mode) = update.mode {
+        state.amp_state.mode = Some(mode);
+    }
+    if let Some(ptt) = update.ptt {
+        state.amp_state.keyed = ptt;
+    }
+}
 
-                    info!(
-                        "Unregistered radio: {} (handle {})",
-                        meta.display_name, handle.0
-                    );
-                }
-            }
+/// Key the amp in one write with the frequency and mode held for it
+///
+/// Updates held during a switch hold or while the amp is backed up would
+/// otherwise reach it after it keyed on the band it is leaving. Returns
+/// whether the key-up went out this way.
+async fn key_up_with_held(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    response: &RadioResponse,
+) -> bool {
+    if response.ptt() != Some(true) || state.coalescer.pending().is_empty() {
+        return false;
+    }
+    state.coalescer.record(response, true, Instant::now());
+    flush_amp_burst(state, event_tx).await;
+    true
+}
 
-            MuxActorCommand::RadioResponse { handle, response } => {
-                // Direct response injection - useful for testing and virtual radios
-                process_radio_response(&mut state, &event_tx, handle, response).await;
-            }
+/// Send the amp a frequency its smoothing policy was holding
+async fn release_amp_frequency(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    hz: Frequency,
+) {
+    if state.amp_state.frequency_hz == Some(hz) {
+        return;
+    }
+    state.amp_state.frequency_hz = Some(hz);
+    let update = RadioResponse::Frequency { hz };
+    if state.auto_info_enabled && state.amp_queries.allow_update(&update, Instant::now()) {
+        let current = state.cause;
+        state.cause = state.held_cause.take().or(current);
+        send_to_amp(state, event_tx, update).await;
+        state.cause = current;
+    }
+}
 
-            MuxActorCommand::SetActiveRadio { handle } => {
-                let old_active = state.multiplexer.active_radio();
+/// Re-check the amp's write backlog, reporting when it backs up or drains
+async fn amp_backed_up(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) -> bool {
+    let flow_control = state
+        .amp_meta
+        .as_ref()
+        .map_or(FlowControl::None, |meta| meta.flow_control);
+    let Some(monitor) = &mut state.amp_backlog else {
+        return false;
+    };
+    if let Some(backed_up) = monitor.check() {
+        let pending_bytes = monitor.pending();
+        if backed_up {
+            warn!(
+                "Amplifier output backing up ({} bytes queued), holding frequency/mode updates",
+                pending_bytes
+            );
+        } else {
+            info!("Amplifier output drained ({} bytes queued)", pending_bytes);
+        }
+        let _ = event_tx
+            .send(MuxEvent::AmpBacklogChanged {
+                backed_up,
+                pending_bytes,
+                flow_control,
+            })
+            .await;
+    }
+    monitor.is_backed_up()
+}
 
-                match state.multiplexer.select_radio(handle) {
-                    Ok(()) => {
-                        if old_active != Some(handle) {
-                            let _ = event_tx
-                                .send(MuxEvent::ActiveRadioChanged {
-                                    from: old_active,
-                                    to: handle,
-                                })
-                                .await;
+/// Re-check amp bypass against the active radio's frequency
+async fn update_amp_bypass(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let Some(hz) = state
+        .multiplexer
+        .active_radio()
+        .and_then(|h| state.multiplexer.get_radio(h))
+        .and_then(|r| r.frequency_hz)
+    else {
+        return;
+    };
 
-                            // If auto-info is enabled, send new radio's state to amplifier
-                            if state.auto_info_enabled && state.amp_tx.is_some() {
-                                if let Some(radio) = state.multiplexer.get_radio(handle) {
-                                    // Update and send frequency
-                                    if let Some(hz) = radio.frequency_hz {
-                                        state.cached_frequency_hz = Some(hz);
-                                        send_to_amp(
-                                            &state,
-                                            &event_tx,
-                                            RadioResponse::Frequency { hz },
-                                        )
-                                        .await;
-                                    }
-                                    // Update and send mode
-                                    if let Some(mode) = radio.mode {
-                                        state.cached_mode = Some(mode);
-                                        send_to_amp(&state, &event_tx, RadioResponse::Mode { mode })
-                                            .await;
-                                    }
-                                    // Update and send PTT
-                                    state.cached_ptt = radio.ptt;
-                                    send_to_amp(
-                                        &state,
-                                        &event_tx,
-                                        RadioResponse::Ptt { active: radio.ptt },
-                                    )
-                                    .await;
-                                }
-                            }
-                        }
-                    }
-                    Err(MuxError::SwitchingLocked {
-                        requested,
-                        current,
-                        remaining_ms,
-                    }) => {
-                        let _ = event_tx
-                            .send(MuxEvent::SwitchingBlocked {
-                                requested,
-                                current,
-                                remaining_ms,
-                            })
-                            .await;
-                    }
-                    Err(e) => {
-                        warn!("Failed to select radio {}: {}", handle.0, e);
-                        let _ = event_tx
-                            .send(MuxEvent::Error {
-                                source: "Multiplexer".to_string(),
-                                message: format!("Select failed: {}", e),
-                            })
-                            .await;
-                    }
-                }
-            }
+    if let Some(bypassed) = state.amp_bypass.on_frequency(hz, Instant::now()) {
+        set_amp_bypass(state, event_tx, bypassed).await;
+    }
+}
 
-            MuxActorCommand::QueryRadioState { handle, response } => {
-                let summary = state
-                    .multiplexer
-                    .get_radio(handle)
-                    .map(RadioStateSummary::from_state);
-                let _ = response.send(summary);
-            }
+/// Put the amplifier in or out of bypass using its personality
+async fn set_amp_bypass(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    bypassed: bool,
+) {
+    info!(
+        "Amplifier {}",
+        if bypassed {
+            "bypassed (out of range)"
+        } else {
+            "back in operate"
+        }
+    );
 
-            MuxActorCommand::UpdateRadioMeta { handle, name } => {
-                if let Some(new_name) = name {
-                    state.multiplexer.rename_radio(handle, new_name.clone());
+    let personality = state.amp_bypass.config().personality;
+    if let Some(data) = personality.operate_command(!bypassed) {
+        let protocol = state.multiplexer.amplifier_config().encode_protocol();
+        // Going to bypass protects the amp, so it can't wait behind updates
+        let priority = if bypassed {
+            AmpPriority::Urgent
+        } else {
+            AmpPriority::Normal
+        };
+        send_amp_data(state, event_tx, data, protocol, priority).await;
+    } else if bypassed && state.amp_state.keyed {
+        // PTT inhibit: drop the amp out of transmit if it was keyed
+        unkey_amp(state, event_tx).await;
+    }
+}
 
-                    if let Some(meta) = state.radio_channels.get_mut(&handle) {
-                        meta.display_name = new_name.clone();
-                    }
+/// Enter or leave the amp's transitional state as switches are held off and released
+async fn update_transition(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if let Some((handle, reason)) = state.multiplexer.take_held_switch() {
+        if state.transition.begin(handle, reason) {
+            info!(
+                "Switch to radio {} held off ({}), amplifier told to wait",
+                handle.0,
+                reason.describe()
+            );
+        }
+    }
 
-                    info!("Updated radio {} name to: {}", handle.0, new_name);
+    if let Some((handle, reason)) = state.transition.held() {
+        let mux = &state.multiplexer;
+        let still_held = mux.active_radio() != Some(handle)
+            && match reason {
+                TransitionReason::Lockout => mux.is_locked(),
+                TransitionReason::Transmitting => {
+                    mux.get_radio(handle).is_some_and(|r| r.ptt)
+                        && mux.band_conflict(handle).is_some()
                 }
+            };
+        if !still_held {
+            state.transition.end();
+            info!("Switch hold ended, amplifier following again");
+            // Whatever was held goes out now rather than at the next backlog poll
+            if !state.coalescer.pending().is_empty() {
+                flush_amp_burst(state, event_tx).await;
             }
+        }
+    }
 
-            MuxActorCommand::ConnectAmplifier { channel } => {
-                state.amp_tx = Some(channel.command_tx);
-                state.amp_meta = Some(channel.meta.clone());
-                // Reset codec and cached state for new connection
-                state.amp_codec = None;
-                state.auto_info_enabled = false;
-                state.cached_frequency_hz = None;
-                state.cached_mode = None;
-                state.cached_ptt = false;
-                state.cached_control_band = None;
-                state.cached_tx_band = None;
-                state.cached_rx_vfo = None;
-                state.cached_split = false;
-
-                let _ = event_tx
-                    .send(MuxEvent::AmpConnected { meta: channel.meta })
-                    .await;
-
-                info!("Amplifier connected");
-            }
-
-            MuxActorCommand::DisconnectAmplifier => {
-                state.amp_tx = None;
-                state.amp_meta = None;
-                state.amp_codec = None;
-                state.auto_info_enabled = false;
-                state.cached_frequency_hz = None;
-                state.cached_mode = None;
-                state.cached_ptt = false;
-                state.cached_control_band = None;
-                state.cached_tx_band = None;
-                state.cached_rx_vfo = None;
-                state.cached_split = false;
-
-                let _ = event_tx.send(MuxEvent::AmpDisconnected).await;
-
-                info!("Amplifier disconnected");
-            }
-
-            MuxActorCommand::SetAmplifierConfig {
-                port,
-                protocol,
-                baud_rate,
-                civ_address,
-            } => {
-                let config = AmplifierConfig {
-                    port,
-                    protocol,
-                    baud_rate,
-                    civ_address,
-                };
-                state.multiplexer.set_amplifier_config(config);
-                info!("Updated amplifier config");
-            }
-
-            MuxActorCommand::SetSwitchingMode { mode } => {
-                state.multiplexer.set_switching_mode(mode);
-
-                let _ = event_tx.send(MuxEvent::SwitchingModeChanged { mode }).await;
-
-                info!("Set switching mode to {:?}", mode);
-            }
-
-            MuxActorCommand::RadioRawData { handle, data } => {
-                // Log raw bytes at DEBUG level for diagnostics
-                let port_name = state
-                    .get_radio_meta(handle)
-                    .and_then(|m| m.port_name.clone())
-                    .unwrap_or_else(|| format!("handle={}", handle.0));
-                debug!(
-                    "IN  <-Radio({}) {:02X?}",
-                    port_name,
-                    &data[..data.len().min(64)]
-                );
-
-                // Look up protocol for this radio
-                let protocol = state
-                    .get_radio_meta(handle)
-                    .map(|m| m.protocol)
-                    .unwrap_or(cat_protocol::Protocol::Kenwood);
-
-                // Parse responses from raw data using the codec
-                // Emit traffic event for EACH response with its specific bytes
-                let responses_with_bytes: Vec<_> =
-                    if let Some(codec) = state.codecs.get_mut(&handle) {
-                        codec.push_bytes(&data);
-                        std::iter::from_fn(|| codec.next_response_with_bytes()).collect()
-                    } else {
-                        debug!(
-                            "No codec found for radio {} (handle {}), skipping parse",
-                            handle.0, handle.0
-                        );
-                        Vec::new()
-                    };
+    state.amp_state.transitional = state.transition.is_active();
+}
 
-                // Process each complete response and emit its traffic event
-                for (response, raw_bytes) in responses_with_bytes {
-                    // Emit traffic event with just this response's bytes
-                    let _ = event_tx
-                        .send(MuxEvent::RadioDataIn {
-                            handle,
-                            data: raw_bytes,
-                            protocol,
-                            timestamp: SystemTime::now(),
-                        })
-                        .await;
+/// When a lockout hold ends, if one is in progress
+fn transition_deadline(state: &MuxActorState) -> Option<Instant> {
+    match state.transition.held()? {
+        (_, TransitionReason::Lockout) => Some(
+            state
+                .multiplexer
+                .lockout_expires()
+                .unwrap_or_else(Instant::now),
+        ),
+        (_, TransitionReason::Transmitting) => None,
+    }
+}
 
-                    process_radio_response(&mut state, &event_tx, handle, response).await;
-                }
-            }
+/// Wait until a pending deadline expires (forever if there is none)
+async fn wait_for_deadline(deadline: Option<Instant>) {
+    match deadline {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
 
-            MuxActorCommand::RadioRawDataOut { handle, data } => {
-                // Look up protocol for this radio
-                let protocol = state
-                    .get_radio_meta(handle)
-                    .map(|m| m.protocol)
-                    .unwrap_or(cat_protocol::Protocol::Kenwood);
+/// Make `handle` the active radio, as the operator selected it
+async fn select_active_radio(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+) {
+    let old_active = state.multiplexer.active_radio();
 
-                // Emit traffic event
+    match state.multiplexer.select_radio(handle) {
+        Ok(()) => {
+            if old_active != Some(handle) {
                 let _ = event_tx
-                    .send(MuxEvent::RadioDataOut {
-                        handle,
-                        data,
-                        protocol,
-                        timestamp: SystemTime::now(),
+                    .send(MuxEvent::ActiveRadioChanged {
+                        from: old_active,
+                        to: handle,
                     })
                     .await;
-            }
-
-            MuxActorCommand::AmpRawData { data } => {
-                // Get amplifier protocol
-                let protocol = state.multiplexer.amplifier_config().protocol;
-
-                // Create codec if not exists
-                if state.amp_codec.is_none() {
-                    state.amp_codec = Some(create_radio_codec(protocol));
-                }
-
-                // Parse requests from amplifier data
-                // Emit traffic event for EACH request with its specific bytes
-                let requests_with_bytes: Vec<_> = if let Some(codec) = state.amp_codec.as_mut() {
-                    codec.push_bytes(&data);
-                    std::iter::from_fn(|| codec.next_request_with_bytes()).collect()
-                } else {
-                    Vec::new()
-                };
-
-                // Process each request from the amplifier
-                for (req, raw_bytes) in requests_with_bytes {
-                    // Emit traffic event with just this request's bytes
-                    let _ = event_tx
-                        .send(MuxEvent::AmpDataIn {
-                            data: raw_bytes,
-                            protocol,
-                            timestamp: SystemTime::now(),
-                        })
-                        .await;
-
-                    debug!("Amp sent request: {:?}", req);
-
-                    // Handle based on request type - queries get responses, sets are actions
-                    if req.is_query() {
-                        // Respond to queries from cached state
-                        if let Some(response) = handle_amp_query(&state, &req) {
-                            debug!("Responding to amp query {:?} with {:?}", req, response);
-                            send_to_amp(&state, &event_tx, response).await;
-                        } else {
-                            debug!("No cached state to respond to amp query {:?}", req);
-                        }
-                    } else if let RadioRequest::SetAutoInfo { enabled } = req {
-                        // Handle auto-info enable/disable
-                        state.auto_info_enabled = enabled;
-                        debug!("Amp auto-info mode set to {}", enabled);
-
-                        // If auto-info just enabled, send current state
-                        if enabled {
-                            if let Some(hz) = state.cached_frequency_hz {
-                                send_to_amp(&state, &event_tx, RadioResponse::Frequency { hz })
-                                    .await;
-                            }
-                            if let Some(mode) = state.cached_mode {
-                                send_to_amp(&state, &event_tx, RadioResponse::Mode { mode }).await;
-                            }
+                audit_switch(state, event_tx).await;
+                carry_keyer_speed(state, old_active, handle).await;
+
+                sync_band_decoder(state).await;
+                state.amp_smoother.clear();
+                let presynced = begin_handover(state, event_tx, handle).await;
+
+                // If auto-info is enabled, send new radio's state to amplifier
+                if state.auto_info_enabled && state.amp_tx.is_some() {
+                    if let Some(radio) = state.multiplexer.get_radio(handle).cloned() {
+                        // Update and send frequency
+                        if let Some(hz) = radio.frequency_hz.filter(|_| !presynced) {
+                            state.amp_smoother.force(hz, Instant::now());
+                            state.amp_state.frequency_hz = Some(hz);
+                            send_to_amp(state, event_tx, RadioResponse::Frequency { hz }).await;
+                        }
+                        // Update and send mode
+                        if let Some(mode) = radio.mode.filter(|_| !presynced) {
+                            state.amp_state.mode = Some(mode);
+                            send_to_amp(state, event_tx, RadioResponse::Mode { mode }).await;
+                        }
+                        // Update and send PTT (a key-up waits for the handover;
+                        // one withheld from a bypassed or locked-out amp isn't
+                        // recorded)
+                        let holds = state.handover.holds_ptt();
+                        let response = RadioResponse::Ptt { active: radio.ptt };
+                        if send_to_amp(state, event_tx, response).await || !radio.ptt || holds {
+                            state.amp_state.keyed = radio.ptt && !holds;
                         }
                     }
                 }
+                update_spot_match(state, event_tx).await;
+                update_peer_sync(state, event_tx).await;
+                update_band_guard(state, event_tx, false).await;
+                sync_monitor_followers(state).await;
             }
-
-            MuxActorCommand::Shutdown => {
-                info!("Multiplexer actor shutting down");
-                break;
-            }
-
-            MuxActorCommand::ReportError { source, message } => {
-                let _ = event_tx.send(MuxEvent::Error { source, message }).await;
-            }
-                }
-            }
-            _ = ai2_timer.tick() => {
-                send_ai2_heartbeat(&mut state).await;
-            }
+        }
+        Err(MuxError::SwitchingLocked {
+            requested,
+            current,
+            remaining_ms,
+        }) => {
+            let _ = event_tx
+                .send(MuxEvent::SwitchingBlocked {
+                    requested,
+                    current,
+                    remaining_ms,
+                })
+                .await;
+        }
+        Err(e) => {
+            warn!("Failed to select radio {}: {}", handle.0, e);
+            let _ = event_tx
+                .send(MuxEvent::Error {
+                    source: "Multiplexer".to_string(),
+                    message: format!("Select failed: {}", e),
+                })
+                .await;
         }
     }
-
-    info!("Multiplexer actor stopped");
 }
 
-/// Send AI2; heartbeat to all connected Kenwood/Elecraft radios
+/// First phase of a gapless handover: give the amp the new active radio's
+/// frequency and mode, and hold off keying it until they are written
 ///
-/// This ensures auto-info mode stays enabled even if a radio restarts.
-async fn send_ai2_heartbeat(state: &mut MuxActorState) {
-    let ai2_bytes = b"AI2;".to_vec();
+/// Returns false (and does nothing) with the handover off or no amp.
+async fn begin_handover(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+) -> bool {
+    if state.amp_tx.is_none() || !state.handover.begin(handle, Instant::now()) {
+        return false;
+    }
+    let Some(radio) = state.multiplexer.get_radio(handle).cloned() else {
+        return false;
+    };
+    debug!("Handing the amplifier over to radio {}", handle.0);
 
-    for (handle, tx) in &state.radio_cmd_tx {
-        // Only send to Kenwood-compatible protocols
-        if let Some(meta) = state.radio_channels.get(handle) {
-            if matches!(meta.protocol, Protocol::Kenwood | Protocol::Elecraft) {
-                let _ = tx
-                    .send(RadioTaskCommand::SendData {
-                        data: ai2_bytes.clone(),
-                    })
-                    .await;
+    // Queries are answered from the cache; an auto-info amp is told now
+    if let Some(hz) = radio.frequency_hz {
+        state.amp_smoother.force(hz, Instant::now());
+        state.amp_state.frequency_hz = Some(hz);
+    }
+    if let Some(mode) = radio.mode {
+        state.amp_state.mode = Some(mode);
+    }
+    if !state.auto_info_enabled {
+        return true;
+    }
+    let protocol = state.multiplexer.amplifier_config().encode_protocol();
+    match (radio.frequency_hz, radio.mode) {
+        // One write, so the amp never has the new frequency with the old mode
+        (Some(hz), Some(mode)) => match translate_frequency_mode(hz, mode, protocol) {
+            Ok(data) => send_amp_data(state, event_tx, data, protocol, AmpPriority::Normal).await,
+            Err(_) => {
+                send_to_amp(state, event_tx, RadioResponse::Frequency { hz }).await;
+                send_to_amp(state, event_tx, RadioResponse::Mode { mode }).await;
             }
+        },
+        (Some(hz), None) => {
+            send_to_amp(state, event_tx, RadioResponse::Frequency { hz }).await;
         }
+        (None, Some(mode)) => {
+            send_to_amp(state, event_tx, RadioResponse::Mode { mode }).await;
+        }
+        (None, None) => {}
     }
+    true
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::amplifier::{AmplifierChannel, AmplifierChannelMeta};
-    use crate::channel::RadioChannelMeta;
-
-    /// Create a channel pair for a virtual amplifier (test helper)
-    fn create_virtual_amp_channel(
-        protocol: Protocol,
-        civ_address: Option<u8>,
-        buffer_size: usize,
-    ) -> (
-        AmplifierChannel,
-        mpsc::Sender<Vec<u8>>,
-        mpsc::Receiver<Vec<u8>>,
-    ) {
-        let (cmd_tx, cmd_rx) = mpsc::channel(buffer_size);
-        let (resp_tx, resp_rx) = mpsc::channel(buffer_size);
-        let meta = AmplifierChannelMeta::new_virtual(protocol, civ_address);
-        let channel = AmplifierChannel::new(meta, cmd_tx, resp_rx);
-        (channel, resp_tx, cmd_rx)
+/// Second phase of a gapless handover: once the amp has written the new
+/// radio's update (or the wait times out), let it key, and key it now if
+/// the radio is already transmitting
+async fn poll_handover(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let pending_bytes = state
+        .amp_backlog
+        .as_ref()
+        .map_or(0, BacklogMonitor::pending);
+    let Some(done) = state.handover.poll(pending_bytes, Instant::now()) else {
+        return;
+    };
+    if done.timed_out {
+        warn!(
+            "Amplifier still writing after {} ms, letting radio {} key it",
+            done.elapsed.as_millis(),
+            done.radio.0
+        );
+    } else {
+        debug!(
+            "Amplifier handed over to radio {} in {} ms",
+            done.radio.0,
+            done.elapsed.as_millis()
+        );
     }
 
-    #[tokio::test]
-    async fn test_register_radio() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+    // The sequencer decides amp keying itself and recorded it while held
+    let sequenced = state.sequencer.is_enabled();
+    let keyed = if sequenced {
+        state.amp_state.keyed
+    } else {
+        state.multiplexer.active_radio() == Some(done.radio)
+            && state
+                .multiplexer
+                .get_radio(done.radio)
+                .is_some_and(|r| r.ptt)
+    };
+    if keyed && !amp_ptt_inhibited(state) && (sequenced || state.auto_info_enabled) {
+        send_ptt_to_amp(state, event_tx, true).await;
+    }
+}
+
+/// Hold a manual switch away from a transmitting radio until it is confirmed
+async fn request_switch_confirmation(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    requested: RadioHandle,
+    current: RadioHandle,
+) {
+    let (pending, replaced) = state
+        .switch_confirm
+        .request(requested, current, Instant::now());
+    if let Some(replaced) = replaced {
+        let _ = event_tx
+            .send(MuxEvent::SwitchConfirmationClosed {
+                token: replaced.token,
+                outcome: SwitchConfirmOutcome::Superseded,
+            })
+            .await;
+    }
+    info!(
+        "Radio {} is transmitting, switch to radio {} awaits confirmation",
+        current.0, requested.0
+    );
+    let _ = event_tx
+        .send(MuxEvent::SwitchPendingConfirmation {
+            token: pending.token,
+            requested,
+            current,
+            timeout_ms: SWITCH_CONFIRM_TIMEOUT.as_millis() as u64,
+        })
+        .await;
+}
+
+/// Handle a query from the amplifier using cached state
+///
+/// Returns `Some(RadioResponse)` with the response if we can answer,
+/// or `None` if we don't have the state to answer (amp should retry later).
+fn handle_amp_query(state: &MuxActorState, query: &RadioRequest) -> Option<RadioResponse> {
+    match query {
+        RadioRequest::GetFrequency => state
+            .amp_state
+            .frequency_hz
+            .map(|hz| RadioResponse::Frequency { hz }),
+
+        RadioRequest::GetMode => state
+            .amp_state
+            .mode
+            .map(|mode| RadioResponse::Mode { mode }),
+
+        RadioRequest::GetPtt => Some(RadioResponse::Ptt {
+            active: state.amp_state.keyed,
+        }),
+
+        // Only CI-V's split query (0x0F) reads as a VFO query and is
+        // answered: split is on when the amp transmits on the other band
+        RadioRequest::GetVfo
+            if state.multiplexer.amplifier_config().parse_protocol() == Protocol::IcomCIV =>
+        {
+            let rx = state.amp_state.control_band.unwrap_or(0);
+            let split = state.amp_state.tx_band.is_some_and(|tx| tx != rx);
+            Some(RadioResponse::VfoState {
+                state: VfoState::with_split(split),
+            })
+        }
+
+        RadioRequest::GetAutoInfo => Some(RadioResponse::AutoInfo {
+            enabled: state.auto_info_enabled,
+        }),
+
+        // Always identify as TS-990S (ID022) to amplifiers
+        RadioRequest::GetId => Some(RadioResponse::Id {
+            id: "022".to_string(), // TS-990S
+        }),
+
+        // Control band query - return cached or default to main (0)
+        RadioRequest::GetControlBand => Some(RadioResponse::ControlBand {
+            band: state.amp_state.control_band.unwrap_or(0),
+        }),
+
+        // Transmit band query - return cached or default to main (0)
+        RadioRequest::GetTransmitBand => Some(RadioResponse::TransmitBand {
+            band: state.amp_state.tx_band.unwrap_or(0),
+        }),
+
+        // Amps that display the exciter's meters poll them; answer with
+        // the active radio's latest reading, in the amp's own scale
+        RadioRequest::GetMeter { meter } if state.amp_bypass.config().forwards_meters() => {
+            let handle = state.multiplexer.active_radio()?;
+            state
+                .meter_cache
+                .latest(handle, *meter, Instant::now())
+                .map(|level| RadioResponse::Meter {
+                    meter: *meter,
+                    level,
+                })
+        }
+
+        _ => None,
+    }
+}
+
+/// Whether PTT to the amp is withheld (generic bypass, peer or band interlock)
+fn amp_ptt_inhibited(state: &MuxActorState) -> bool {
+    state.amp_bypass.inhibits_ptt()
+        || state.peer_block.is_some()
+        || state.band_block.is_some()
+        || state.handover.holds_ptt()
+}
+
+/// Translate a response to the amplifier's protocol
+///
+/// Returns `None` for a PTT-on while the amp is bypassed or locked out, or when the
+/// response has no equivalent in the amp's protocol.
+fn encode_for_amp(state: &MuxActorState, response: &RadioResponse) -> Option<(Vec<u8>, Protocol)> {
+    if amp_ptt_inhibited(state) && response.ptt() == Some(true) {
+        debug!("Amplifier bypassed, locked out or mid-handover, not keying it");
+        return None;
+    }
+
+    let protocol = state.multiplexer.amplifier_config().encode_protocol();
+    match translate_response(response, protocol) {
+        Ok(data) => Some((data, protocol)),
+        Err(e) => {
+            debug!("Cannot translate {:?} to {:?}: {}", response, protocol, e);
+            None
+        }
+    }
+}
+
+/// Which amplifier queue a write goes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AmpPriority {
+    /// Behind everything already queued
+    Normal,
+    /// Ahead of queued writes, for safety frames (PTT-off, bypass)
+    Urgent,
+}
+
+impl AmpPriority {
+    /// PTT-off is a safety frame wherever it comes from (radio, sequencer,
+    /// PTT switch); everything else waits its turn
+    fn for_response(response: &RadioResponse) -> Self {
+        if response.ptt() == Some(false) {
+            Self::Urgent
+        } else {
+            Self::Normal
+        }
+    }
+}
+
+/// Send a RadioResponse to the amplifier
+///
+/// Translates the response to the amplifier's protocol and sends it. Returns
+/// whether it was queued for the amp; a key-up held back by
+/// [`encode_for_amp`] is not, and the amp doesn't know about it.
+async fn send_to_amp(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    response: RadioResponse,
+) -> bool {
+    if state.amp_tx.is_none() {
+        return false;
+    }
+    if response.ptt() == Some(true) {
+        release_withheld_updates(state, event_tx).await;
+    }
+    let Some((data, protocol)) = encode_for_amp(state, &response) else {
+        return false;
+    };
+    let priority = AmpPriority::for_response(&response);
+    send_amp_data(state, event_tx, data, protocol, priority).await;
+    true
+}
+
+/// Send the amp a PTT change, and record it as keyed only once a key-up has
+/// actually gone out
+///
+/// An unkey is recorded either way: the amp must never be thought keyed
+/// after it was told to stand down.
+async fn send_ptt_to_amp(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    active: bool,
+) {
+    let sent = send_to_amp(state, event_tx, RadioResponse::Ptt { active }).await;
+    if sent || !active {
+        state.amp_state.keyed = active;
+    }
+}
+
+/// Send the frequency and mode rate-limiting held back, ahead of a key-up
+///
+/// The amp would otherwise transmit on a value it was left to poll for.
+async fn release_withheld_updates(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let update = state.amp_queries.take_withheld();
+    if update.is_empty() || !state.auto_info_enabled {
+        return;
+    }
+    let protocol = state.multiplexer.amplifier_config().encode_protocol();
+    match update.encode(protocol) {
+        Ok(data) => send_amp_data(state, event_tx, data, protocol, AmpPriority::Normal).await,
+        Err(e) => debug!("Cannot translate {:?} to {:?}: {}", update, protocol, e),
+    }
+}
+
+/// Take the amp out of transmit for an interlock, ahead of queued updates
+async fn unkey_amp(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    state.amp_state.keyed = false;
+    if state.amp_tx.is_none() {
+        return;
+    }
+    let response = RadioResponse::Ptt { active: false };
+    if let Some((data, protocol)) = encode_for_amp(state, &response) {
+        send_amp_data(state, event_tx, data, protocol, AmpPriority::Urgent).await;
+    }
+}
+
+/// Answer a query from one amplifier without mirroring it to the other
+async fn reply_to_amp(
+    state: &MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    side: ShadowSide,
+    response: RadioResponse,
+) {
+    if let Some((data, protocol)) = encode_for_amp(state, &response) {
+        write_amp(state, event_tx, side, data, protocol, AmpPriority::Normal).await;
+    }
+}
+
+/// Answer a query from one amplifier, addressed back to it if it speaks CI-V
+async fn answer_amp_query(
+    state: &MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    side: ShadowSide,
+    response: RadioResponse,
+    query: &[u8],
+) {
+    if let Some((mut data, protocol)) = encode_for_amp(state, &response) {
+        if protocol == Protocol::IcomCIV {
+            icom::address_reply(&mut data, query);
+        }
+        write_amp(state, event_tx, side, data, protocol, AmpPriority::Normal).await;
+    }
+}
+
+/// Send the cached frequency and mode to one amplifier (auto-info just enabled)
+async fn send_amp_snapshot(
+    state: &MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    side: ShadowSide,
+) {
+    if let Some(hz) = state.amp_state.frequency_hz {
+        reply_to_amp(state, event_tx, side, RadioResponse::Frequency { hz }).await;
+    }
+    if let Some(mode) = state.amp_state.mode {
+        reply_to_amp(state, event_tx, side, RadioResponse::Mode { mode }).await;
+    }
+}
+
+/// Send already-encoded bytes to the amplifier (and the shadow amp, if any)
+async fn send_amp_data(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    data: Vec<u8>,
+    protocol: Protocol,
+    priority: AmpPriority,
+) {
+    if state.amp_tx.is_none() {
+        return;
+    }
+
+    write_amp(
+        state,
+        event_tx,
+        ShadowSide::Primary,
+        data.clone(),
+        protocol,
+        priority,
+    )
+    .await;
+
+    if state.shadow_amp_tx.is_some() {
+        if let Some(result) = state.shadow.on_stimulus(data.clone(), Instant::now()) {
+            emit_shadow_result(event_tx, result).await;
+        }
+        write_amp(
+            state,
+            event_tx,
+            ShadowSide::Shadow,
+            data,
+            protocol,
+            priority,
+        )
+        .await;
+    }
+}
+
+/// Write bytes to one amplifier and emit the matching traffic event
+///
+/// Urgent writes go through the primary amp's priority queue when it has
+/// one; the shadow amp only has the regular queue. An urgent write that
+/// overtakes queued frames is queued again behind them, so a key-up still
+/// waiting in the queue can't undo an unkey.
+async fn write_amp(
+    state: &MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    side: ShadowSide,
+    data: Vec<u8>,
+    protocol: Protocol,
+    priority: AmpPriority,
+) {
+    let (tx, source) = match side {
+        ShadowSide::Primary if priority == AmpPriority::Urgent && state.amp_tx.is_some() => (
+            state.amp_urgent_tx.as_ref().or(state.amp_tx.as_ref()),
+            "Amplifier",
+        ),
+        ShadowSide::Primary => (state.amp_tx.as_ref(), "Amplifier"),
+        ShadowSide::Shadow => (state.shadow_amp_tx.as_ref(), "Shadow amplifier"),
+    };
+    let Some(tx) = tx else {
+        return;
+    };
+    let requeue = match (side, priority, &state.amp_urgent_tx, &state.amp_tx) {
+        (ShadowSide::Primary, AmpPriority::Urgent, Some(_), Some(queue))
+            if queue.capacity() < queue.max_capacity() =>
+        {
+            Some(queue)
+        }
+        _ => None,
+    };
+
+    // Emit traffic event
+    let timestamp = SystemTime::now();
+    let suppressed = side == ShadowSide::Primary && state.amp_dry_run;
+    let event = match side {
+        ShadowSide::Primary => MuxEvent::AmpDataOut {
+            data: data.clone(),
+            cause: state.cause,
+            protocol,
+            suppressed,
+            timestamp,
+        },
+        ShadowSide::Shadow => MuxEvent::ShadowAmpDataOut {
+            data: data.clone(),
+            cause: state.cause,
+            protocol,
+            timestamp,
+        },
+    };
+    let _ = event_tx.send(event).await;
+    if suppressed {
+        return;
+    }
+
+    // Send to amplifier
+    let backlog = match side {
+        ShadowSide::Primary => state.amp_backlog.as_ref().map(BacklogMonitor::backlog),
+        ShadowSide::Shadow => None,
+    };
+    let len = data.len();
+    if let Some(queue) = requeue {
+        debug!("Urgent amp write overtook queued frames, repeating it behind them");
+        if let Some(backlog) = backlog {
+            backlog.queued(len);
+        }
+        if queue.send(data.clone()).await.is_err() {
+            if let Some(backlog) = backlog {
+                backlog.written(len);
+            }
+        }
+    }
+    if let Some(backlog) = backlog {
+        backlog.queued(len);
+    }
+    if let Err(e) = tx.send(data).await {
+        if let Some(backlog) = backlog {
+            backlog.written(len);
+        }
+        warn!("Failed to send to {}: {}", source.to_lowercase(), e);
+        let _ = event_tx
+            .send(MuxEvent::Error {
+                source: source.to_string(),
+                message: format!("Send failed: {}", e),
+            })
+            .await;
+    }
+}
+
+/// Report a closed shadow comparison window
+async fn emit_shadow_result(
+    event_tx: &mpsc::Sender<MuxEvent>,
+    (stats, diff): (ShadowStats, Option<ShadowDiff>),
+) {
+    if diff.is_some() {
+        debug!(
+            "Shadow amplifier mismatch ({} of {} windows)",
+            stats.mismatches, stats.windows
+        );
+    }
+    let _ = event_tx
+        .send(MuxEvent::ShadowAmpCompared { stats, diff })
+        .await;
+}
+
+/// How often held amp updates re-check a backed-up write backlog
+const BACKLOG_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Most queued commands taken into one batch for PTT prioritization
+const MAX_BATCH: usize = 64;
+
+/// Parsed radio frames: (response, the bytes it was decoded from)
+type RadioFrames = Vec<(RadioResponse, Vec<u8>)>;
+
+/// A unit of actor work once a batch has been through [`prioritize_ptt`]
+#[allow(clippy::large_enum_variant)]
+enum Work {
+    /// A command to handle as usual
+    Command(MuxActorCommand),
+    /// Radio data already parsed, waiting behind the PTT-off frames
+    Frames {
+        handle: RadioHandle,
+        frames: RadioFrames,
+        received: Instant,
+    },
+}
+
+/// Handle every PTT-off response in a batch, returning the rest in order
+///
+/// Raw radio data is parsed here so PTT responses can be found; frames from
+/// the same radio keep their order, only PTT-off frames jump the queue. A
+/// PTT-off behind a queued PTT-on for the same radio keeps its place, or the
+/// amp would be left keyed. Data for a radio without a codec (registered
+/// later in the same batch), or arriving after the radio's protocol is
+/// changed in the same batch, is left as a command.
+async fn prioritize_ptt(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    batch: Vec<MuxActorCommand>,
+) -> Vec<Work> {
+    let mut rest = Vec::with_capacity(batch.len());
+    // Radios whose protocol changes partway through the batch
+    let mut switched = HashSet::new();
+    // Radios with data in `rest` that may key them up
+    let mut keying = HashSet::new();
+
+    for cmd in batch {
+        if let MuxActorCommand::SetRadioProtocol { handle, .. }
+        | MuxActorCommand::SetRadioModel { handle, .. }
+        | MuxActorCommand::SetRadioHooks { handle, .. } = &cmd
+        {
+            switched.insert(*handle);
+        }
+        if let MuxActorCommand::RadioResponses { handle, .. } = &cmd {
+            keying.insert(*handle);
+        }
+        // Writes are noted as they come, so their echoes are recognised
+        // when the reads behind them are parsed below
+        if let MuxActorCommand::RadioRawDataOut { handle, data } = &cmd {
+            note_civ_write(state, *handle, data);
+        }
+        let MuxActorCommand::RadioRawData {
+            handle,
+            data,
+            received,
+        } = cmd
+        else {
+            rest.push(Work::Command(cmd));
+            continue;
+        };
+        if !state.codecs.contains_key(&handle) || switched.contains(&handle) {
+            keying.insert(handle);
+            rest.push(Work::Command(MuxActorCommand::RadioRawData {
+                handle,
+                data,
+                received,
+            }));
+            continue;
+        }
+
+        let mut unkey = Vec::new();
+        let mut bulk = Vec::new();
+        for frame in parse_radio_frames(state, handle, &data, received) {
+            match frame.0 {
+                RadioResponse::Ptt { active: false } if !keying.contains(&handle) => {
+                    unkey.push(frame);
+                }
+                RadioResponse::Ptt { active: true } => {
+                    keying.insert(handle);
+                    bulk.push(frame);
+                }
+                _ => bulk.push(frame),
+            }
+        }
+
+        if !unkey.is_empty() {
+            process_radio_frames(state, event_tx, handle, unkey, Some(received)).await;
+            report_ptt_latency(state, event_tx).await;
+        }
+        rest.push(Work::Frames {
+            handle,
+            frames: bulk,
+            received,
+        });
+    }
+
+    rest
+}
+
+/// Report the PTT edges the amp has now followed
+///
+/// Only the active radio's edges reach the amp; any other radio's are
+/// dropped. An edge still waiting (on the sequencer, a handover or held
+/// updates) stays pending until the amp's keying matches it.
+async fn report_ptt_latency(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if state.ptt_edges.is_empty() {
+        return;
+    }
+    let active_radio = state.multiplexer.active_radio();
+    let keyed = state.amp_state.keyed;
+    let mut followed = Vec::new();
+    state.ptt_edges.retain(|&handle, &mut (active, received)| {
+        if Some(handle) != active_radio {
+            return false;
+        }
+        if keyed != active {
+            return true;
+        }
+        followed.push((handle, active, received.elapsed()));
+        false
+    });
+
+    for (handle, active, latency) in followed {
+        debug!(
+            "PTT {} latency for radio {}: {:?}",
+            active, handle.0, latency
+        );
+        let _ = event_tx
+            .send(MuxEvent::PttLatency {
+                handle,
+                active,
+                latency,
+            })
+            .await;
+    }
+}
+
+/// Run raw radio bytes through the radio's codec
+fn parse_radio_frames(
+    state: &mut MuxActorState,
+    handle: RadioHandle,
+    data: &[u8],
+    received: Instant,
+) -> RadioFrames {
+    // Log raw bytes at DEBUG level for diagnostics
+    let port_name = state
+        .get_radio_meta(handle)
+        .and_then(|m| m.port_name.clone())
+        .unwrap_or_else(|| format!("handle={}", handle.0));
+    debug!(
+        "IN  <-Radio({}) {:02X?}",
+        port_name,
+        &data[..data.len().min(64)]
+    );
+
+    let protocol = state
+        .get_radio_meta(handle)
+        .map(|m| m.protocol)
+        .unwrap_or(Protocol::Kenwood);
+    if let Some(codec) = state.codecs.get_mut(&handle) {
+        codec.push_bytes(data);
+        let frames: RadioFrames = std::iter::from_fn(|| codec.next_response_with_bytes()).collect();
+        state.custom_events.extend(
+            codec
+                .take_custom_events()
+                .into_iter()
+                .map(|event| (handle, event)),
+        );
+        state.partial_frames.note(
+            FrameSource::Radio(handle),
+            codec.buffered().len(),
+            partial_frame_timeout(protocol),
+            received,
+        );
+        state.reassembly.entry(handle).or_default().record(
+            data,
+            frames.iter().map(|(_, bytes)| bytes.as_slice()),
+            received,
+        );
+        if protocol != Protocol::IcomCIV {
+            return frames;
+        }
+        // Our own frames repeated back aren't the radio's reports
+        let echo = state.civ_echo.entry(handle).or_default();
+        frames
+            .into_iter()
+            .filter(|(_, bytes)| !echo.received(bytes, received))
+            .collect()
+    } else {
+        debug!(
+            "No codec found for radio {} (handle {}), skipping parse",
+            handle.0, handle.0
+        );
+        Vec::new()
+    }
+}
+
+/// Emit traffic for and process each parsed radio frame
+async fn process_radio_frames(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+    frames: RadioFrames,
+    received: Option<Instant>,
+) {
+    // Look up protocol for this radio
+    let protocol = state
+        .get_radio_meta(handle)
+        .map(|m| m.protocol)
+        .unwrap_or(cat_protocol::Protocol::Kenwood);
+
+    // Emit traffic event for EACH response with its specific bytes
+    for (response, raw_bytes) in frames {
+        state.last_frames.insert(handle, raw_bytes.clone());
+        let id = state.frame_id();
+        state.last_frame_ids.insert(handle, id);
+        let _ = event_tx
+            .send(MuxEvent::RadioDataIn {
+                handle,
+                id,
+                data: raw_bytes.clone(),
+                protocol,
+                timestamp: SystemTime::now(),
+            })
+            .await;
+
+        let unknown = matches!(response, RadioResponse::Unknown { .. });
+        if let (Some(received), RadioResponse::Ptt { active }) = (received, &response) {
+            if state
+                .multiplexer
+                .get_radio(handle)
+                .is_some_and(|r| r.ptt != *active)
+            {
+                state.ptt_edges.insert(handle, (*active, received));
+            }
+        }
+        state.cause = Some(id);
+        // Every frame is logged; bursts are collapsed before processing
+        for response in state.input_throttle.offer(handle, response, Instant::now()) {
+            process_radio_response(state, event_tx, handle, response).await;
+        }
+        if unknown {
+            pass_through_to_amp(state, event_tx, handle, protocol, raw_bytes).await;
+        }
+        state.cause = None;
+    }
+
+    // Any bytes at all (even partial frames) mean the radio is alive
+    if let Some(radio) = state.multiplexer.get_radio_mut(handle) {
+        radio.touch();
+    }
+    update_stale_radios(state, event_tx).await;
+}
+
+/// Forward a frame the radio's codec didn't recognise to the amp as-is
+///
+/// Only with permissive translation, from the active radio, and when the amp
+/// speaks the radio's protocol family; like any other update it waits for the
+/// amp to turn on auto-info. Strict translation drops the frame.
+async fn pass_through_to_amp(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+    radio_protocol: Protocol,
+    data: Vec<u8>,
+) {
+    if state.amp_tx.is_none()
+        || !state.auto_info_enabled
+        || state.multiplexer.active_radio() != Some(handle)
+    {
+        return;
+    }
+    let config = state.multiplexer.amplifier_config();
+    let strictness = config.strictness.unwrap_or(state.translation_strictness);
+    let amp_protocol = config.encode_protocol();
+    if strictness == TranslationStrictness::Permissive
+        && same_protocol_family(radio_protocol, amp_protocol)
+    {
+        send_amp_data(state, event_tx, data, amp_protocol, AmpPriority::Normal).await;
+    }
+}
+
+/// Run the multiplexer actor
+///
+/// This async function processes all radio commands through the multiplexer
+/// and emits events through the event channel.
+///
+/// # Arguments
+///
+/// * `cmd_rx` - Receiver for commands sent to the actor
+/// * `event_tx` - Sender for events emitted by the actor
+///
+/// Nothing restarts it if it fails; see [`crate::supervisor`] for that.
+pub async fn run_mux_actor(
+    cmd_rx: mpsc::Receiver<MuxActorCommand>,
+    event_tx: mpsc::Sender<MuxEvent>,
+) {
+    let heartbeat = Heartbeat::new(WatchdogConfig::default().heartbeat_interval);
+    run_actor(cmd_rx, event_tx, ChannelRegistry::new(), heartbeat).await;
+}
+
+/// Run the actor, starting with the channels in `registry` and beating
+/// `heartbeat` while it runs
+pub(crate) async fn run_actor(
+    mut cmd_rx: mpsc::Receiver<MuxActorCommand>,
+    event_tx: mpsc::Sender<MuxEvent>,
+    registry: ChannelRegistry,
+    heartbeat: Heartbeat,
+) {
+    let mut state = MuxActorState::new(registry);
+    info!("Multiplexer actor started");
+    restore_channels(&mut state, &event_tx).await;
+
+    // AI2 heartbeat timer - sends AI2; to all Kenwood/Elecraft radios every second
+    let mut ai2_timer = interval(Duration::from_secs(1));
+    ai2_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Fault polling for a KPA in service (see [`crate::amp_failover`])
+    let mut fault_timer = interval(KPA_FAULT_POLL_INTERVAL);
+    fault_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    // Liveness for the supervisor; also beaten after every pass of the loop
+    let mut heartbeat_timer = interval(heartbeat.interval());
+    heartbeat_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    'run: loop {
+        // Edges held back (sequencer, handover, held updates) are followed
+        // by whatever the last iteration handled
+        report_ptt_latency(&mut state, &event_tx).await;
+        tokio::select! {
+            cmd = cmd_rx.recv() => {
+                let Some(cmd) = cmd else { break; };
+                let mut batch = vec![cmd];
+                while batch.len() < MAX_BATCH {
+                    match cmd_rx.try_recv() {
+                        Ok(cmd) => batch.push(cmd),
+                        Err(_) => break,
+                    }
+                }
+                for work in prioritize_ptt(&mut state, &event_tx, batch).await {
+                    let cmd = match work {
+                        Work::Command(cmd) => cmd,
+                        Work::Frames {
+                            handle,
+                            frames,
+                            received,
+                        } => {
+                            process_radio_frames(
+                                &mut state,
+                                &event_tx,
+                                handle,
+                                frames,
+                                Some(received),
+                            )
+                            .await;
+                            report_ptt_latency(&mut state, &event_tx).await;
+                            continue;
+                        }
+                    };
+                    match cmd {
+                        MuxActorCommand::RegisterRadio {
+                            meta,
+                            response,
+                            cmd_tx,
+                        } => {
+                            let name = meta.display_name.clone();
+                            let handle = register_radio(&mut state, None, meta.clone(), cmd_tx);
+
+                            // Send back the handle
+                            let _ = response.send(handle);
+
+                            // Emit event
+                            let _ = event_tx
+                                .send(MuxEvent::RadioConnected { handle, meta })
+                                .await;
+
+                            info!("Registered radio: {} (handle {})", name, handle.0);
+                        }
+
+                        MuxActorCommand::UnregisterRadio { handle } => {
+                            state.registry.lock().radios.remove(&handle);
+                            if let Some(meta) = state.radio_channels.remove(&handle) {
+                                let keyed_active = state.multiplexer.active_radio() == Some(handle)
+                                    && state.multiplexer.get_radio(handle).is_some_and(|r| r.ptt);
+                                state.multiplexer.remove_radio(handle);
+                                state.codecs.remove(&handle);
+                                state.radio_hooks.remove(&handle);
+                                state.radio_cmd_tx.remove(&handle);
+                                state.stale_radios.remove(&handle);
+                                state.meter_throttle.remove_radio(handle);
+                                state.meter_cache.remove_radio(handle);
+                                state.tx_stats.remove_radio(handle);
+                                state.reassembly.remove(&handle);
+                                state.civ_echo.remove(&handle);
+                                state.last_frames.remove(&handle);
+                                state.tuner_bands.remove(&handle);
+                                state.partial_frames.forget(FrameSource::Radio(handle));
+                                state.input_throttle.remove_radio(handle);
+                                state.band_decoder.release_radio(handle);
+                                state.band_smoothers.remove(&handle);
+                                state.monitor_followers.remove(handle);
+                                // The radio is gone; don't leave the amp keyed for it
+                                if state.sequencer.keying() == Some(handle) {
+                                    state.sequencer.reset();
+                                    unkey_amp(&mut state, &event_tx).await;
+                                } else if keyed_active || state.switch_ptt == Some(handle) {
+                                    release_amp(&mut state, &event_tx).await;
+                                }
+                                if state.switch_ptt == Some(handle) {
+                                    state.switch_ptt = None;
+                                }
+
+                                // Emit event
+                                let _ = event_tx.send(MuxEvent::RadioDisconnected { handle }).await;
+                                update_spot_match(&mut state, &event_tx).await;
+                                update_peer_sync(&mut state, &event_tx).await;
+                                update_band_guard(&mut state, &event_tx, false).await;
+
+                                info!(
+                                    "Unregistered radio: {} (handle {})",
+                                    meta.display_name, handle.0
+                                );
+                            }
+                        }
+
+                        MuxActorCommand::RadioResponse { handle, response } => {
+                            // Direct response injection - useful for testing and virtual radios
+                            process_radio_response(&mut state, &event_tx, handle, response).await;
+                        }
+
+                        MuxActorCommand::SetActiveRadio { handle } => {
+                            let transmitting = state
+                                .multiplexer
+                                .active_radio()
+                                .filter(|&current| current != handle)
+                                .filter(|&current| {
+                                    state.multiplexer.get_radio(current).is_some_and(|r| r.ptt)
+                                });
+                            match transmitting {
+                                Some(current) if state.switch_confirm.is_enabled() => {
+                                    request_switch_confirmation(
+                                        &mut state, &event_tx, handle, current,
+                                    )
+                                    .await;
+                                }
+                                _ => select_active_radio(&mut state, &event_tx, handle).await,
+                            }
+                        }
+
+                        MuxActorCommand::ConfirmSwitch { token, confirm } => {
+                            if let Some(pending) =
+                                state.switch_confirm.answer(token, Instant::now())
+                            {
+                                let outcome = if confirm {
+                                    info!("Switch to radio {} confirmed", pending.requested.0);
+                                    select_active_radio(&mut state, &event_tx, pending.requested)
+                                        .await;
+                                    SwitchConfirmOutcome::Confirmed
+                                } else {
+                                    info!("Switch to radio {} declined", pending.requested.0);
+                                    SwitchConfirmOutcome::Declined
+                                };
+                                let _ = event_tx
+                                    .send(MuxEvent::SwitchConfirmationClosed { token, outcome })
+                                    .await;
+                            } else {
+                                debug!("Switch confirmation {} no longer pending", token);
+                            }
+                        }
+
+                        MuxActorCommand::SetSwitchConfirmation { enabled } => {
+                            if let Some(dropped) = state.switch_confirm.set_enabled(enabled) {
+                                let _ = event_tx
+                                    .send(MuxEvent::SwitchConfirmationClosed {
+                                        token: dropped.token,
+                                        outcome: SwitchConfirmOutcome::Superseded,
+                                    })
+                                    .await;
+                            }
+                        }
+
+                        MuxActorCommand::SetCarryKeyerSpeed { enabled } => {
+                            state.carry_keyer_speed = enabled;
+                        }
+
+                        MuxActorCommand::SetBandModeMemory { enabled } => {
+                            state.band_mode_memory = enabled;
+                            info!("Band mode memory: {}", enabled);
+                        }
+
+                        MuxActorCommand::SetSuppressRepeats { enabled } => {
+                            state.suppress_repeats = enabled;
+                            info!("Suppress repeated radio reports: {}", enabled);
+                        }
+
+                        MuxActorCommand::SetMemoryLimits {
+                            switch_audit_records,
+                            dx_spots,
+                        } => {
+                            state.switch_audit.set_capacity(switch_audit_records);
+                            state.dx_spots.set_max_spots(dx_spots);
+                            info!(
+                                "Memory limits: {} switch records, {} DX spots",
+                                switch_audit_records, dx_spots
+                            );
+                        }
+
+                        MuxActorCommand::SetRadioMonitor { handle, monitor } => {
+                            if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                                saved.meta.monitor = monitor;
+                            }
+                            if let Some(meta) = state.radio_channels.get_mut(&handle) {
+                                meta.monitor = monitor;
+                                let old_active = state.multiplexer.active_radio();
+                                state.multiplexer.set_rx_only(handle, monitor.is_some());
+                                state
+                                    .monitor_followers
+                                    .set(handle, monitor.is_some_and(|m| m.follow_active));
+
+                                let new_active = state.multiplexer.active_radio();
+                                if old_active != new_active {
+                                    if let Some(to) = new_active {
+                                        let _ = event_tx
+                                            .send(MuxEvent::ActiveRadioChanged {
+                                                from: old_active,
+                                                to,
+                                            })
+                                            .await;
+                                    }
+                                    // Nothing from the old active radio may reach the amp now
+                                    state.coalescer.clear();
+                                    state.amp_smoother.clear();
+                                    if let Some(to) = new_active {
+                                        begin_handover(&mut state, &event_tx, to).await;
+                                    }
+                                    sync_band_decoder(&mut state).await;
+                                    update_spot_match(&mut state, &event_tx).await;
+                                    update_peer_sync(&mut state, &event_tx).await;
+                                }
+                                update_band_guard(&mut state, &event_tx, false).await;
+                                sync_monitor_followers(&mut state).await;
+
+                                info!(
+                                    "Radio {} is {}",
+                                    handle.0,
+                                    match monitor {
+                                        Some(m) if m.follow_active => {
+                                            "receive-only, following the active radio"
+                                        }
+                                        Some(_) => "receive-only",
+                                        None => "a normal radio",
+                                    }
+                                );
+                            }
+                        }
+
+                        MuxActorCommand::SetRadioHooks { handle, hooks } => {
+                            if set_radio_hooks(&mut state, handle, hooks) {
+                                info!("Updated command handlers for radio {}", handle.0);
+                            }
+                        }
+
+                        MuxActorCommand::SetRadioModel { handle, model } => {
+                            if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                                saved.meta.model_info = Some(model.clone());
+                            }
+                            if let Some(meta) = state.radio_channels.get_mut(&handle) {
+                                meta.model_info = Some(model.clone());
+                                let protocol = meta.protocol;
+                                let codec = state.radio_codec(handle, protocol, Some(&model));
+                                state.codecs.insert(handle, codec);
+                                state.partial_frames.forget(FrameSource::Radio(handle));
+                                debug!("Radio {} parsed as {}", handle.0, model.model);
+                            }
+                        }
+
+                        MuxActorCommand::QueryAmpState { response } => {
+                            let _ = response.send(state.amp_state);
+                        }
+
+                        MuxActorCommand::QueryTxStats { response } => {
+                            let _ = response.send(state.tx_stats.snapshot(Instant::now()));
+                        }
+
+                        MuxActorCommand::ResetTxStats => {
+                            state.tx_stats.reset(Instant::now());
+                            info!("Reset transmit statistics");
+                        }
+
+                        MuxActorCommand::SetDutyCycleAlert { config } => {
+                            state.tx_stats.set_config(config);
+                            report_duty_cycle(&mut state, &event_tx).await;
+                            info!(
+                                "Duty-cycle alert {} ({}% over {}s)",
+                                if config.enabled { "on" } else { "off" },
+                                config.threshold_pct,
+                                config.window_secs
+                            );
+                        }
+
+                        MuxActorCommand::QuerySwitchAudit { limit, response } => {
+                            let _ = response.send(state.switch_audit.recent(limit));
+                        }
+
+                        MuxActorCommand::QueryRadioState { handle, response } => {
+                            let summary = state
+                                .multiplexer
+                                .get_radio(handle)
+                                .map(RadioStateSummary::from_state);
+                            let _ = response.send(summary);
+                        }
+
+                        MuxActorCommand::QueryActiveRadio { response } => {
+                            let active = state
+                                .multiplexer
+                                .active_radio_state()
+                                .map(|r| (r.handle, RadioStateSummary::from_state(r)));
+                            let _ = response.send(active);
+                        }
+
+                        MuxActorCommand::ControlActiveRadio { request, response } => {
+                            let Some(handle) = state.multiplexer.active_radio() else {
+                                let _ = response.send(false);
+                                continue;
+                            };
+                            let sent = match request {
+                                RadioRequest::SetPtt { active } => {
+                                    let reachable = state.radio_cmd_tx.contains_key(&handle);
+                                    request_ptt(&mut state, &event_tx, handle, active).await;
+                                    reachable
+                                }
+                                request => send_radio_request(&state, handle, request).await,
+                            };
+                            if !sent {
+                                warn!("No command channel for radio {}", handle.0);
+                            }
+                            let _ = response.send(sent);
+                        }
+
+                        MuxActorCommand::EnterFrequency {
+                            handle,
+                            hz,
+                            transmit,
+                            region,
+                            response,
+                        } => {
+                            let result = match state.get_radio_meta(handle) {
+                                None => Err(FrequencyEntryError::UnknownRadio),
+                                Some(meta) => check_frequency(
+                                    hz,
+                                    meta.frequency_offset_hz,
+                                    meta.model_info.as_ref().map(|m| &m.capabilities),
+                                    transmit,
+                                    region,
+                                ),
+                            };
+                            let result = match result {
+                                Ok(()) => {
+                                    if send_radio_request(
+                                        &state,
+                                        handle,
+                                        RadioRequest::SetFrequency { hz },
+                                    )
+                                    .await
+                                    {
+                                        info!("Tuning radio {} to {} Hz", handle.0, hz.hz());
+                                        Ok(())
+                                    } else {
+                                        Err(FrequencyEntryError::NotConnected)
+                                    }
+                                }
+                                Err(e) => {
+                                    debug!("Not tuning radio {}: {}", handle.0, e);
+                                    Err(e)
+                                }
+                            };
+                            let _ = response.send(result);
+                        }
+
+                        MuxActorCommand::UpdateRadioMeta { handle, name } => {
+                            if let Some(new_name) = name {
+                                if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                                    saved.meta.display_name = new_name.clone();
+                                }
+                                state.multiplexer.rename_radio(handle, new_name.clone());
+
+                                if let Some(meta) = state.radio_channels.get_mut(&handle) {
+                                    meta.display_name = new_name.clone();
+                                }
+
+                                info!("Updated radio {} name to: {}", handle.0, new_name);
+                            }
+                        }
+
+                        MuxActorCommand::SetRadioProtocol { handle, protocol } => {
+                            if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                                saved.meta.protocol = protocol;
+                            }
+                            if let Some(meta) = state.radio_channels.get_mut(&handle) {
+                                let previous = std::mem::replace(&mut meta.protocol, protocol);
+                                state.multiplexer.set_radio_protocol(handle, protocol);
+                                let model = meta.model_info.clone();
+                                let codec = state.radio_codec(handle, protocol, model.as_ref());
+                                state.codecs.insert(handle, codec);
+                                state.partial_frames.forget(FrameSource::Radio(handle));
+                                state.civ_echo.remove(&handle);
+                                // Held reports were parsed with the old protocol
+                                state.input_throttle.reset_radio(handle);
+                                state.tuner_bands.remove(&handle);
+                                if let Some(tx) = state.radio_cmd_tx.get(&handle) {
+                                    let _ =
+                                        tx.send(RadioTaskCommand::SetProtocol { protocol }).await;
+                                }
+
+                                let _ = event_tx
+                                    .send(MuxEvent::RadioProtocolChanged { handle, protocol })
+                                    .await;
+
+                                info!(
+                                    "Radio {} protocol changed from {:?} to {:?}",
+                                    handle.0, previous, protocol
+                                );
+                            }
+                        }
+
+                        MuxActorCommand::ConnectAmplifier { channel } => {
+                            let amp = SavedAmp {
+                                meta: channel.meta,
+                                command_tx: channel.command_tx,
+                                backlog: channel.backlog,
+                                urgent_tx: channel.urgent_tx,
+                            };
+                            if state.amp_failover.role() == AmpRole::Primary {
+                                connect_amplifier(&mut state, &event_tx, amp).await;
+                            } else {
+                                // A reconnected primary waits for the failover to be acknowledged
+                                info!(
+                                    "Primary amplifier reconnected; standing by until the \
+                                     failover is acknowledged"
+                                );
+                                state.standby_primary = Some(amp);
+                                save_amp_failover(&state);
+                            }
+                        }
+
+                        MuxActorCommand::DisconnectAmplifier => {
+                            state.standby_primary = None;
+                            let backup = state.registry.lock().backup_amp.is_some();
+                            if state.amp_failover.role() == AmpRole::Primary || !backup {
+                                take_amp(&mut state);
+                                state.amp_failover.reset();
+                            }
+                            // Otherwise output stays off the primary until acknowledged
+                            save_amp_failover(&state);
+
+                            let _ = event_tx.send(MuxEvent::AmpDisconnected).await;
+
+                            info!("Amplifier disconnected");
+                        }
+
+                        MuxActorCommand::ConnectShadowAmplifier { channel } => {
+                            let amp = SavedAmp {
+                                meta: channel.meta,
+                                command_tx: channel.command_tx,
+                                backlog: channel.backlog,
+                                urgent_tx: channel.urgent_tx,
+                            };
+                            connect_shadow_amplifier(&mut state, &event_tx, amp).await;
+                        }
+
+                        MuxActorCommand::DisconnectShadowAmplifier => {
+                            state.registry.lock().shadow_amp = None;
+                            state.shadow_amp_tx = None;
+                            state.shadow_amp_codec = None;
+                            state.shadow.reset();
+
+                            let _ = event_tx.send(MuxEvent::ShadowAmpDisconnected).await;
+
+                            info!("Shadow amplifier disconnected");
+                        }
+
+                        MuxActorCommand::ConnectBackupAmplifier { channel } => {
+                            let amp = SavedAmp {
+                                meta: channel.meta,
+                                command_tx: channel.command_tx,
+                                backlog: channel.backlog,
+                                urgent_tx: channel.urgent_tx,
+                            };
+                            connect_backup_amplifier(&mut state, &event_tx, amp).await;
+                        }
+
+                        MuxActorCommand::DisconnectBackupAmplifier => {
+                            // In service, the station is left without an amp
+                            fail_over(&mut state, &event_tx, AmpRole::Backup, "Disconnected").await;
+                            state.registry.lock().backup_amp = None;
+                            state.backup_amp = None;
+
+                            let _ = event_tx.send(MuxEvent::BackupAmpDisconnected).await;
+
+                            info!("Backup amplifier disconnected");
+                        }
+
+                        MuxActorCommand::BackupAmpRawData { data } => {
+                            if state.amp_failover.role() == AmpRole::Backup {
+                                handle_amp_data(&mut state, &event_tx, data).await;
+                            }
+                        }
+
+                        MuxActorCommand::AmpFailed { role, reason } => {
+                            fail_over(&mut state, &event_tx, role, &reason).await;
+                        }
+
+                        MuxActorCommand::SetAmpFailover { enabled } => {
+                            state.amp_failover.set_enabled(enabled);
+                            save_amp_failover(&state);
+                        }
+
+                        MuxActorCommand::AcknowledgeAmpFailover => {
+                            acknowledge_amp_failover(&mut state, &event_tx).await;
+                        }
+
+                        MuxActorCommand::SetAmplifierConfig {
+                            port,
+                            protocol,
+                            baud_rate,
+                            civ_address,
+                            response_protocol,
+                            strictness,
+                        } => {
+                            let config = AmplifierConfig {
+                                port,
+                                protocol,
+                                baud_rate,
+                                civ_address,
+                                response_protocol,
+                                strictness,
+                            };
+                            state.registry.lock().amp_config = Some(config.clone());
+                            if state.multiplexer.amplifier_config().parse_protocol() != protocol {
+                                // Buffered bytes were framed for the old protocol
+                                state.amp_codec = None;
+                                state.shadow_amp_codec = None;
+                            }
+                            state.multiplexer.set_amplifier_config(config);
+                            match response_protocol.filter(|p| *p != protocol) {
+                                Some(reply) => info!(
+                                    "Updated amplifier config (parse as {:?}, respond as {:?})",
+                                    protocol, reply
+                                ),
+                                None => info!("Updated amplifier config"),
+                            }
+                        }
+
+                        MuxActorCommand::SetSwitchingMode { mode } => {
+                            state.registry.lock().switching_mode = Some(mode);
+                            state.multiplexer.set_switching_mode(mode);
+
+                            let _ = event_tx.send(MuxEvent::SwitchingModeChanged { mode }).await;
+
+                            info!("Set switching mode to {:?}", mode);
+                        }
+
+                        MuxActorCommand::RadioRawData {
+                            handle,
+                            data,
+                            received,
+                        } => {
+                            // Only reached when the radio has no codec yet
+                            let frames = parse_radio_frames(&mut state, handle, &data, received);
+                            process_radio_frames(
+                                &mut state,
+                                &event_tx,
+                                handle,
+                                frames,
+                                Some(received),
+                            )
+                            .await;
+                        }
+
+                        MuxActorCommand::RadioResponses { handle, frames } => {
+                            process_radio_frames(&mut state, &event_tx, handle, frames, None).await;
+                        }
+
+                        MuxActorCommand::RadioRawDataOut { handle, data } => {
+                            // Look up protocol for this radio
+                            let protocol = state
+                                .get_radio_meta(handle)
+                                .map(|m| m.protocol)
+                                .unwrap_or(cat_protocol::Protocol::Kenwood);
+
+                            // Emit traffic event
+                            let _ = event_tx
+                                .send(MuxEvent::RadioDataOut {
+                                    handle,
+                                    data,
+                                    protocol,
+                                    timestamp: SystemTime::now(),
+                                })
+                                .await;
+                        }
+
+                        MuxActorCommand::AmpRawData { data } => {
+                            if state.amp_failover.role() == AmpRole::Primary {
+                                handle_amp_data(&mut state, &event_tx, data).await;
+                            }
+                        }
+
+                        MuxActorCommand::ShadowAmpRawData { data } => {
+                            if state.shadow_amp_tx.is_none() {
+                                continue;
+                            }
+                            // The shadow speaks the primary amp's protocol
+                            let protocol = state.multiplexer.amplifier_config().parse_protocol();
+                            let codec = state
+                                .shadow_amp_codec
+                                .get_or_insert_with(|| ControllerSideCodec::new(protocol));
+                            codec.push_bytes(&data);
+                            let requests_with_bytes: Vec<_> =
+                                std::iter::from_fn(|| codec.next_request_with_bytes()).collect();
+                            state.partial_frames.note(
+                                FrameSource::ShadowAmplifier,
+                                codec.buffered().len(),
+                                partial_frame_timeout(protocol),
+                                Instant::now(),
+                            );
+
+                            for (req, raw_bytes) in requests_with_bytes {
+                                let id = state.frame_id();
+                                state.cause = Some(id);
+                                let _ = event_tx
+                                    .send(MuxEvent::ShadowAmpDataIn {
+                                        data: raw_bytes.clone(),
+                                        id,
+                                        protocol,
+                                        timestamp: SystemTime::now(),
+                                    })
+                                    .await;
+                                state.shadow.on_frame(
+                                    ShadowSide::Shadow,
+                                    raw_bytes.clone(),
+                                    Instant::now(),
+                                );
+
+                                debug!("Shadow amp sent request: {:?}", req);
+
+                                // Answered like the primary, but never changes mux state:
+                                // auto-info stays under the primary amp's control
+                                if req.is_query() {
+                                    let encode =
+                                        state.multiplexer.amplifier_config().encode_protocol();
+                                    if let Some(reply) =
+                                        state.transition.busy_reply_to(&req, encode)
+                                    {
+                                        write_amp(
+                                            &state,
+                                            &event_tx,
+                                            ShadowSide::Shadow,
+                                            reply,
+                                            encode,
+                                            AmpPriority::Normal,
+                                        )
+                                        .await;
+                                    } else if let Some(response) = handle_amp_query(&state, &req) {
+                                        answer_amp_query(
+                                            &state,
+                                            &event_tx,
+                                            ShadowSide::Shadow,
+                                            response,
+                                            &raw_bytes,
+                                        )
+                                        .await;
+                                    }
+                                } else if let RadioRequest::SetAutoInfo { enabled: true } = req {
+                                    send_amp_snapshot(&state, &event_tx, ShadowSide::Shadow).await;
+                                }
+                                state.cause = None;
+                            }
+                        }
+
+                        MuxActorCommand::Shutdown => {
+                            info!("Multiplexer actor shutting down");
+                            break 'run;
+                        }
+
+                        #[cfg(test)]
+                        MuxActorCommand::InjectFault { hang } => {
+                            if hang {
+                                std::future::pending::<()>().await;
+                            }
+                            panic!("injected fault");
+                        }
+
+                        MuxActorCommand::ConnectBandDecoder { tx } => {
+                            state.registry.lock().band_decoder = Some(tx.clone());
+                            state.band_decoder_tx = Some(tx);
+                            // Send the full picture to the newly connected device
+                            state.band_decoder.reset_sent();
+                            sync_band_decoder(&mut state).await;
+
+                            info!("Band decoder connected");
+                        }
+
+                        MuxActorCommand::DisconnectBandDecoder => {
+                            state.registry.lock().band_decoder = None;
+                            state.band_decoder_tx = None;
+
+                            info!("Band decoder disconnected");
+                        }
+
+                        MuxActorCommand::SetRadioInputThrottle { handle, ms } => {
+                            if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                                saved.input_throttle_ms = Some(ms);
+                            }
+                            state
+                                .input_throttle
+                                .set_window(handle, Duration::from_millis(ms));
+
+                            info!("Set radio {} input throttle to {}ms", handle.0, ms);
+                        }
+
+                        MuxActorCommand::SetStaleThreshold { secs } => {
+                            state.multiplexer.set_stale_after_secs(secs);
+                            update_stale_radios(&mut state, &event_tx).await;
+
+                            info!("Set stale radio threshold to {:?}s", secs);
+                        }
+
+                        MuxActorCommand::SetSequencerConfig { config } => {
+                            let was_keyed = state.sequencer.amp_keyed();
+                            state.sequencer.set_config(config);
+                            if !config.is_enabled() {
+                                state.sequencer.reset();
+                                // Hand amp keying back to the active radio's own PTT
+                                let ptt = state
+                                    .multiplexer
+                                    .active_radio()
+                                    .and_then(|h| state.multiplexer.get_radio(h))
+                                    .is_some_and(|r| r.ptt);
+                                if was_keyed && !ptt {
+                                    state.amp_state.keyed = false;
+                                    send_to_amp(
+                                        &mut state,
+                                        &event_tx,
+                                        RadioResponse::Ptt { active: false },
+                                    )
+                                    .await;
+                                }
+                            }
+
+                            info!(
+                                "Set PTT sequencer lead {}ms, tail {}ms",
+                                config.lead_ms, config.tail_ms
+                            );
+                        }
+
+                        MuxActorCommand::SetAmpCoalesceWindow { ms } => {
+                            state.coalescer.set_window(Duration::from_millis(ms));
+                            if !state.coalescer.is_enabled() {
+                                flush_amp_burst(&mut state, &event_tx).await;
+                            }
+
+                            info!("Set amp coalescing window to {}ms", ms);
+                        }
+
+                        MuxActorCommand::SetAmpDryRun { enabled } => {
+                            state.amp_dry_run = enabled;
+                            info!(
+                                "Amplifier dry run {}",
+                                if enabled { "enabled" } else { "disabled" }
+                            );
+                        }
+
+                        MuxActorCommand::SetTranslationStrictness { strictness } => {
+                            state.translation_strictness = strictness;
+                            info!("Set translation strictness to {}", strictness.name());
+                        }
+
+                        MuxActorCommand::SetAmpTranslationStrictness { strictness } => {
+                            let mut config = state.multiplexer.amplifier_config().clone();
+                            config.strictness = strictness;
+                            if let Some(saved) = &mut state.registry.lock().amp_config {
+                                saved.strictness = strictness;
+                            }
+                            state.multiplexer.set_amplifier_config(config);
+                            info!(
+                                "Set amplifier translation strictness to {}",
+                                strictness.map_or("global", |s| s.name())
+                            );
+                        }
+
+                        MuxActorCommand::SetFrequencySmoothing { config } => {
+                            let now = Instant::now();
+                            state.smoothing = config;
+                            if let Some(hz) = state.amp_smoother.set_policy(config.amplifier, now) {
+                                release_amp_frequency(&mut state, &event_tx, hz).await;
+                            }
+                            let mut released = false;
+                            for smoother in state.band_smoothers.values_mut() {
+                                released |= smoother.set_policy(config.band_decoder, now).is_some();
+                            }
+                            if released {
+                                sync_band_decoder(&mut state).await;
+                            }
+
+                            info!(
+                                "Set frequency smoothing: amp {}, band decoder {}",
+                                config.amplifier, config.band_decoder
+                            );
+                        }
+
+                        MuxActorCommand::SetBandGuardConfig { config } => {
+                            state.multiplexer.set_band_guard_config(config);
+                            update_band_guard(&mut state, &event_tx, false).await;
+                            info!(
+                                "Set band guard {:?}, per mode: {}",
+                                config.strictness, config.per_mode
+                            );
+                        }
+
+                        MuxActorCommand::SetTransitionPolicy { policy } => {
+                            state.transition.set_policy(policy);
+                            info!("Set switch hold policy {:?}", policy);
+                        }
+
+                        MuxActorCommand::SetGaplessHandover { enabled } => {
+                            state.handover.set_enabled(enabled);
+                            info!("Gapless handover {}", if enabled { "on" } else { "off" });
+                        }
+
+                        MuxActorCommand::SetAmpBypassConfig { config } => {
+                            if let Some(bypassed) = state.amp_bypass.set_config(config) {
+                                set_amp_bypass(&mut state, &event_tx, bypassed).await;
+                            }
+                            update_amp_bypass(&mut state, &event_tx).await;
+
+                            info!(
+                                "Set amp bypass enabled={}, {:?} {}-{} Hz",
+                                config.enabled,
+                                config.personality,
+                                config.min_hz.hz(),
+                                config.max_hz.hz()
+                            );
+                        }
+
+                        MuxActorCommand::RequestPtt { handle, active } => {
+                            request_ptt(&mut state, &event_tx, handle, active).await;
+                        }
+
+                        MuxActorCommand::SwitchPtt { source, active } => {
+                            switch_ptt(&mut state, &event_tx, &source, active).await;
+                        }
+
+                        MuxActorCommand::AddBookmark { source } => {
+                            add_bookmark(&state, &event_tx, source).await;
+                        }
+
+                        MuxActorCommand::SetBookmarkDoubleTap { ms } => {
+                            state.double_tap.set_window(ms.map(Duration::from_millis));
+                            info!("Set PTT double-tap bookmark window to {:?}ms", ms);
+                        }
+
+                        MuxActorCommand::SetContactSnapshots { enabled } => {
+                            state.contact_snapshots = enabled;
+                            info!(
+                                "Contact snapshots on PTT release {}",
+                                if enabled { "on" } else { "off" }
+                            );
+                        }
+
+                        MuxActorCommand::AddDxSpot { spot } => {
+                            state.dx_spots.insert(spot);
+                            update_spot_match(&mut state, &event_tx).await;
+                        }
+
+                        MuxActorCommand::SetPeerSync { instance, outgoing } => {
+                            state.peer_state_sent = None;
+                            if let Some(outgoing) = outgoing {
+                                info!("Peer sync on as {}", instance);
+                                state.peer_sync = Some((instance, outgoing));
+                            } else {
+                                info!("Peer sync off");
+                                state.peer_sync = None;
+                                if !state.peers.is_empty() {
+                                    state.peers.clear();
+                                    let _ = event_tx
+                                        .send(MuxEvent::PeersChanged { peers: Vec::new() })
+                                        .await;
+                                }
+                            }
+                            update_peer_sync(&mut state, &event_tx).await;
+                        }
+
+                        MuxActorCommand::PeerStatus { status } => {
+                            if state.peer_sync.is_some()
+                                && state.peers.update(status, Instant::now())
+                            {
+                                let peers = state.peers.peers();
+                                let _ = event_tx.send(MuxEvent::PeersChanged { peers }).await;
+                                update_peer_sync(&mut state, &event_tx).await;
+                            }
+                        }
+
+                        MuxActorCommand::ReportError { source, message } => {
+                            let _ = event_tx.send(MuxEvent::Error { source, message }).await;
+                        }
+                    }
+                }
+            }
+            _ = wait_for_deadline(state.sequencer.next_deadline()) => {
+                let actions = state.sequencer.poll(Instant::now());
+                apply_sequencer_actions(&mut state, &event_tx, actions).await;
+            }
+            _ = wait_for_deadline(state.coalescer.next_deadline()) => {
+                flush_amp_burst(&mut state, &event_tx).await;
+            }
+            _ = wait_for_deadline(state.amp_bypass.next_deadline()) => {
+                if let Some(bypassed) = state.amp_bypass.poll(Instant::now()) {
+                    set_amp_bypass(&mut state, &event_tx, bypassed).await;
+                }
+            }
+            _ = wait_for_deadline(state.amp_smoother.next_deadline()) => {
+                if let Some(hz) = state.amp_smoother.poll(Instant::now()) {
+                    release_amp_frequency(&mut state, &event_tx, hz).await;
+                }
+            }
+            _ = wait_for_deadline(band_smoothing_deadline(&state)) => {
+                let now = Instant::now();
+                let mut released = false;
+                for smoother in state.band_smoothers.values_mut() {
+                    released |= smoother.poll(now).is_some();
+                }
+                if released {
+                    sync_band_decoder(&mut state).await;
+                }
+            }
+            _ = wait_for_deadline(state.input_throttle.next_deadline()) => {
+                for (handle, response) in state.input_throttle.poll(Instant::now()) {
+                    state.cause = state.last_frame_ids.get(&handle).copied();
+                    process_radio_response(&mut state, &event_tx, handle, response).await;
+                    state.cause = None;
+                }
+            }
+            _ = wait_for_deadline(state.partial_frames.next_deadline()) => {
+                discard_stalled_frames(&mut state, &event_tx).await;
+            }
+            _ = wait_for_deadline(transition_deadline(&state)) => {}
+            _ = wait_for_deadline(state.handover.next_deadline()) => {
+                poll_handover(&mut state, &event_tx).await;
+            }
+            _ = wait_for_deadline(state.switch_confirm.next_deadline()) => {
+                if let Some(expired) = state.switch_confirm.poll(Instant::now()) {
+                    info!("Switch to radio {} not confirmed in time", expired.requested.0);
+                    let _ = event_tx
+                        .send(MuxEvent::SwitchConfirmationClosed {
+                            token: expired.token,
+                            outcome: SwitchConfirmOutcome::Expired,
+                        })
+                        .await;
+                }
+            }
+            _ = wait_for_deadline(state.shadow.next_deadline()) => {
+                if let Some(result) = state.shadow.poll(Instant::now()) {
+                    emit_shadow_result(&event_tx, result).await;
+                }
+            }
+            _ = heartbeat_timer.tick() => {}
+            _ = fault_timer.tick() => {
+                poll_amp_fault(&state, &event_tx).await;
+            }
+            _ = ai2_timer.tick() => {
+                send_ai2_heartbeat(&mut state).await;
+                update_stale_radios(&mut state, &event_tx).await;
+                report_amp_query_profile(&mut state, &event_tx).await;
+                report_reassembly(&mut state, &event_tx).await;
+                report_civ_echo(&mut state, &event_tx).await;
+                report_duty_cycle(&mut state, &event_tx).await;
+                if !state.dx_spots.is_empty() {
+                    state.dx_spots.prune(Instant::now());
+                    update_spot_match(&mut state, &event_tx).await;
+                }
+                if state.peers.expire(Instant::now()) {
+                    let peers = state.peers.peers();
+                    let _ = event_tx.send(MuxEvent::PeersChanged { peers }).await;
+                    update_peer_sync(&mut state, &event_tx).await;
+                }
+            }
+        }
+        update_transition(&mut state, &event_tx).await;
+        report_amp_state(&mut state, &event_tx).await;
+        report_custom_events(&mut state, &event_tx).await;
+        {
+            let mut saved = state.registry.lock();
+            saved.active = state.multiplexer.active_radio();
+            saved.amp_auto_info = state.auto_info_enabled;
+            saved.amp_state = state.amp_state;
+        }
+        heartbeat.beat();
+    }
+
+    info!("Multiplexer actor stopped");
+}
+
+/// Add a radio's channel (under `handle` when restoring one)
+///
+/// Emits nothing; the caller reports the connection.
+fn register_radio(
+    state: &mut MuxActorState,
+    handle: Option<RadioHandle>,
+    meta: RadioChannelMeta,
+    cmd_tx: Option<mpsc::Sender<RadioTaskCommand>>,
+) -> RadioHandle {
+    let name = meta.display_name.clone();
+    let port = meta
+        .port_name
+        .clone()
+        .unwrap_or_else(|| "[VRT]".to_string());
+    let protocol = meta.protocol;
+
+    // Add to multiplexer
+    let handle = match handle {
+        Some(handle) => {
+            state
+                .multiplexer
+                .restore_radio(handle, name, port, protocol);
+            handle
+        }
+        None => state.multiplexer.add_radio(name, port, protocol),
+    };
+
+    // Store the channel metadata
+    state.radio_channels.insert(handle, meta.clone());
+
+    // Create codec for parsing raw data
+    let codec = state.radio_codec(handle, protocol, meta.model_info.as_ref());
+    state.codecs.insert(handle, codec);
+
+    // Store the command channel for AI2 heartbeat
+    if let Some(tx) = &cmd_tx {
+        state.radio_cmd_tx.insert(handle, tx.clone());
+    }
+
+    if let Some(monitor) = meta.monitor {
+        state.multiplexer.set_rx_only(handle, true);
+        state.monitor_followers.set(handle, monitor.follow_active);
+    }
+
+    if let Some(port) = state.band_decoder.assign_radio(handle) {
+        debug!("Radio {} drives band decoder port {}", handle.0, port);
+    }
+
+    state.registry.lock().radios.insert(
+        handle,
+        SavedRadio {
+            meta,
+            cmd_tx,
+            hooks: None,
+            input_throttle_ms: None,
+        },
+    );
+    handle
+}
+
+/// Replace a radio's command handlers; false if it isn't registered
+fn set_radio_hooks(state: &mut MuxActorState, handle: RadioHandle, hooks: CommandHooks) -> bool {
+    let Some(meta) = state.radio_channels.get(&handle) else {
+        return false;
+    };
+    let (protocol, model) = (meta.protocol, meta.model_info.clone());
+    if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+        saved.hooks = (!hooks.is_empty()).then(|| hooks.clone());
+    }
+    if hooks.is_empty() {
+        state.radio_hooks.remove(&handle);
+    } else {
+        state.radio_hooks.insert(handle, hooks);
+    }
+    let codec = state.radio_codec(handle, protocol, model.as_ref());
+    state.codecs.insert(handle, codec);
+    state.partial_frames.forget(FrameSource::Radio(handle));
+    true
+}
+
+async fn connect_amplifier(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    amp: SavedAmp,
+) {
+    state.registry.lock().amp = Some(amp.clone());
+    state.amp_tx = Some(amp.command_tx);
+    state.amp_urgent_tx = amp.urgent_tx;
+    state.amp_backlog = Some(BacklogMonitor::new(amp.backlog, amp.meta.baud_rate));
+    state.amp_meta = Some(amp.meta.clone());
+    // Reset codec and cached state for new connection
+    state.amp_codec = None;
+    state.auto_info_enabled = false;
+    state.amp_queries.reset();
+    state.amp_query_profile = AmpQueryProfile::default();
+    state.amp_state = AmplifierEmulatedState::default();
+    state.cached_rx_vfo = None;
+    state.cached_split = false;
+    state.shadow_amp_codec = None;
+    state.shadow.reset();
+
+    let _ = event_tx
+        .send(MuxEvent::AmpConnected { meta: amp.meta })
+        .await;
+
+    // A freshly connected amp needs to hear it is out of range
+    if state.amp_bypass.is_bypassed() {
+        set_amp_bypass(state, event_tx, true).await;
+    }
+
+    info!("Amplifier connected");
+}
+
+async fn connect_shadow_amplifier(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    amp: SavedAmp,
+) {
+    state.registry.lock().shadow_amp = Some(amp.clone());
+    state.shadow_amp_tx = Some(amp.command_tx);
+    state.shadow_amp_codec = None;
+    state.shadow.reset();
+
+    let _ = event_tx
+        .send(MuxEvent::ShadowAmpConnected { meta: amp.meta })
+        .await;
+
+    info!("Shadow amplifier connected");
+}
+
+/// Handle data from the amp in service: answer its queries from cached state
+async fn handle_amp_data(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    data: Vec<u8>,
+) {
+    let kpa = amp_is_kpa(state);
+
+    // Requests are parsed as the amp's protocol (replies may differ)
+    let protocol = state.multiplexer.amplifier_config().parse_protocol();
+
+    // Create codec if not exists
+    if state.amp_codec.is_none() {
+        state.amp_codec = Some(ControllerSideCodec::new(protocol));
+    }
+
+    // Parse requests from amplifier data
+    // Emit traffic event for EACH request with its specific bytes
+    let requests_with_bytes: Vec<_> = if let Some(codec) = state.amp_codec.as_mut() {
+        codec.push_bytes(&data);
+        let requests = std::iter::from_fn(|| codec.next_request_with_bytes()).collect();
+        state.partial_frames.note(
+            FrameSource::Amplifier,
+            codec.buffered().len(),
+            partial_frame_timeout(protocol),
+            Instant::now(),
+        );
+        requests
+    } else {
+        Vec::new()
+    };
+
+    // Process each request from the amplifier
+    for (req, raw_bytes) in requests_with_bytes {
+        // A KPA reporting a fault has taken itself out of operate
+        if let Some(code) = kpa.then(|| kpa_fault(&raw_bytes)).flatten() {
+            let role = state.amp_failover.role();
+            warn!("{} reports fault {}", role.name(), code);
+            fail_over(state, event_tx, role, &format!("Fault code {}", code)).await;
+            return;
+        }
+
+        // Emit traffic event with just this request's bytes
+        let id = state.frame_id();
+        state.cause = Some(id);
+        let _ = event_tx
+            .send(MuxEvent::AmpDataIn {
+                data: raw_bytes.clone(),
+                id,
+                protocol,
+                timestamp: SystemTime::now(),
+            })
+            .await;
+
+        debug!("Amp sent request: {:?}", req);
+        if state.shadow_amp_tx.is_some() {
+            state
+                .shadow
+                .on_frame(ShadowSide::Primary, raw_bytes.clone(), Instant::now());
+        }
+
+        if let Some(kind) = AmpQueryKind::of(&req) {
+            state.amp_queries.record(kind, Instant::now());
+        }
+
+        // Handle based on request type - queries get responses, sets are actions
+        if req.is_query() {
+            let encode = state.multiplexer.amplifier_config().encode_protocol();
+            if let Some(reply) = state.transition.busy_reply_to(&req, encode) {
+                // Switch held off: have the amp ask again
+                debug!("Answering amp query {:?} busy", req);
+                write_amp(
+                    state,
+                    event_tx,
+                    ShadowSide::Primary,
+                    reply,
+                    encode,
+                    AmpPriority::Normal,
+                )
+                .await;
+            } else if let Some(response) = handle_amp_query(state, &req) {
+                // Respond to queries from cached state
+                debug!("Responding to amp query {:?} with {:?}", req, response);
+                answer_amp_query(state, event_tx, ShadowSide::Primary, response, &raw_bytes).await;
+            } else {
+                debug!("No cached state to respond to amp query {:?}", req);
+            }
+        } else if let RadioRequest::SetAutoInfo { enabled } = req {
+            // Handle auto-info enable/disable
+            state.auto_info_enabled = enabled;
+            debug!("Amp auto-info mode set to {}", enabled);
+
+            // If auto-info just enabled, send current state
+            if enabled {
+                send_amp_snapshot(state, event_tx, ShadowSide::Primary).await;
+            }
+        }
+        state.cause = None;
+    }
+}
+
+/// Record which amp is in service where a restart can find it
+fn save_amp_failover(state: &MuxActorState) {
+    let mut saved = state.registry.lock();
+    saved.amp_failover = state.amp_failover.clone();
+    saved.standby_primary = state.standby_primary.clone();
+}
+
+/// Whether the amp is an Elecraft KPA (which reports faults when asked)
+fn amp_is_kpa(state: &MuxActorState) -> bool {
+    matches!(
+        state.amp_bypass.config().personality,
+        AmpPersonality::Kpa500 | AmpPersonality::Kpa1500
+    )
+}
+
+/// Ask a KPA in service for its fault code while failover is on
+async fn poll_amp_fault(state: &MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if !state.amp_failover.is_enabled() || state.amp_tx.is_none() || !amp_is_kpa(state) {
+        return;
+    }
+    let protocol = state.multiplexer.amplifier_config().encode_protocol();
+    write_amp(
+        state,
+        event_tx,
+        ShadowSide::Primary,
+        KPA_FAULT_QUERY.to_vec(),
+        protocol,
+        AmpPriority::Normal,
+    )
+    .await;
+}
+
+/// Unkey the amp in service and put it in standby before it leaves service
+///
+/// Sent urgently while its port is still open: once its channels are taken
+/// nothing can reach it, and it would stay keyed or in operate.
+async fn stand_down_amp(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if state.amp_tx.as_ref().is_none_or(|tx| tx.is_closed()) {
+        return;
+    }
+    unkey_amp(state, event_tx).await;
+    if let Some(data) = state.amp_bypass.config().personality.operate_command(false) {
+        let protocol = state.multiplexer.amplifier_config().encode_protocol();
+        send_amp_data(state, event_tx, data, protocol, AmpPriority::Urgent).await;
+    }
+}
+
+/// Take the amp in service out of it, returning its channels
+fn take_amp(state: &mut MuxActorState) -> Option<SavedAmp> {
+    state.amp_tx = None;
+    state.amp_urgent_tx = None;
+    state.amp_backlog = None;
+    state.amp_meta = None;
+    state.amp_codec = None;
+    state.auto_info_enabled = false;
+    state.amp_queries.reset();
+    state.amp_query_profile = AmpQueryProfile::default();
+    state.amp_state = AmplifierEmulatedState::default();
+    state.cached_rx_vfo = None;
+    state.cached_split = false;
+    state.shadow.reset();
+    state.registry.lock().amp.take()
+}
+
+async fn connect_backup_amplifier(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    amp: SavedAmp,
+) {
+    state.registry.lock().backup_amp = Some(amp.clone());
+    let meta = amp.meta.clone();
+    if state.amp_failover.role() == AmpRole::Backup {
+        // Replacing the backup in service: the new one takes over
+        take_amp(state);
+        connect_amplifier(state, event_tx, amp).await;
+    } else {
+        state.backup_amp = Some(amp);
+    }
+
+    let _ = event_tx.send(MuxEvent::BackupAmpConnected { meta }).await;
+
+    info!("Backup amplifier connected");
+}
+
+/// The amp in role `failed` failed: move output off it if it is in service
+async fn fail_over(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    failed: AmpRole,
+    reason: &str,
+) {
+    let backup_ready = state
+        .backup_amp
+        .as_ref()
+        .is_some_and(|amp| !amp.command_tx.is_closed());
+    let Some(to) = state.amp_failover.fail(failed, reason, backup_ready) else {
+        return;
+    };
+
+    // Keep the failed amp's channels for when the failover is acknowledged
+    stand_down_amp(state, event_tx).await;
+    let out = take_amp(state);
+    match failed {
+        AmpRole::Primary => state.standby_primary = out,
+        AmpRole::Backup => state.backup_amp = out,
+        AmpRole::Bypass => {}
+    }
+    if to == AmpRole::Backup {
+        if let Some(backup) = state.backup_amp.take() {
+            connect_amplifier(state, event_tx, backup).await;
+        }
+    }
+    save_amp_failover(state);
+
+    warn!(
+        "{} failed ({}); output moved to {}",
+        failed.name(),
+        reason,
+        to.name()
+    );
+    let _ = event_tx
+        .send(MuxEvent::AmpFailedOver {
+            from: failed,
+            to,
+            reason: reason.to_string(),
+        })
+        .await;
+}
+
+/// Put the primary amp back in service
+async fn acknowledge_amp_failover(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let role = state.amp_failover.role();
+    if role == AmpRole::Primary {
+        return;
+    }
+    let primary_ready = state
+        .standby_primary
+        .as_ref()
+        .is_some_and(|amp| !amp.command_tx.is_closed());
+    if !primary_ready {
+        let _ = event_tx
+            .send(MuxEvent::Error {
+                source: "Amplifier".to_string(),
+                message: "Reconnect the primary amplifier before acknowledging the failover"
+                    .to_string(),
+            })
+            .await;
+        return;
+    }
+
+    // The backup goes back to standing by
+    stand_down_amp(state, event_tx).await;
+    let out = take_amp(state);
+    if role == AmpRole::Backup {
+        state.backup_amp = out;
+    }
+    state.amp_failover.acknowledge();
+    if let Some(primary) = state.standby_primary.take() {
+        connect_amplifier(state, event_tx, primary).await;
+    }
+    save_amp_failover(state);
+
+    info!("Amplifier failover acknowledged; primary back in service");
+    let _ = event_tx.send(MuxEvent::AmpFailoverAcknowledged).await;
+}
+
+/// Put back the channels a previous actor had (after a restart)
+async fn restore_channels(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let saved = state.registry.snapshot();
+    if let Some(config) = saved.amp_config {
+        state.multiplexer.set_amplifier_config(config);
+    }
+    if let Some(mode) = saved.switching_mode {
+        state.multiplexer.set_switching_mode(mode);
+        let _ = event_tx.send(MuxEvent::SwitchingModeChanged { mode }).await;
+    }
+    for (handle, radio) in saved.radios {
+        register_radio(state, Some(handle), radio.meta.clone(), radio.cmd_tx);
+        if let Some(hooks) = radio.hooks {
+            set_radio_hooks(state, handle, hooks);
+        }
+        if let Some(ms) = radio.input_throttle_ms {
+            state
+                .input_throttle
+                .set_window(handle, Duration::from_millis(ms));
+            if let Some(saved) = state.registry.lock().radios.get_mut(&handle) {
+                saved.input_throttle_ms = Some(ms);
+            }
+        }
+        info!(
+            "Restored radio: {} (handle {})",
+            radio.meta.display_name, handle.0
+        );
+        let _ = event_tx
+            .send(MuxEvent::RadioConnected {
+                handle,
+                meta: radio.meta,
+            })
+            .await;
+    }
+    if let Some(tx) = saved.band_decoder {
+        state.registry.lock().band_decoder = Some(tx.clone());
+        state.band_decoder_tx = Some(tx);
+    }
+    // Output stays off a failed amp until the operator acknowledges it
+    state.amp_failover = saved.amp_failover;
+    state.standby_primary = saved.standby_primary;
+    let in_service_backup = match (&saved.amp, &saved.backup_amp) {
+        (Some(amp), Some(backup)) => amp.command_tx.same_channel(&backup.command_tx),
+        _ => false,
+    };
+    if let Some(amp) = saved.amp {
+        connect_amplifier(state, event_tx, amp).await;
+        // Whatever the old actor was doing when it died, the amp must not be
+        // left keyed; it is then brought back up to date
+        state.auto_info_enabled = saved.amp_auto_info;
+        state.amp_state = AmplifierEmulatedState {
+            transitional: false,
+            ..saved.amp_state
+        };
+        unkey_amp(state, event_tx).await;
+        if state.auto_info_enabled {
+            send_amp_snapshot(state, event_tx, ShadowSide::Primary).await;
+        }
+    }
+    if let Some(amp) = saved.shadow_amp {
+        connect_shadow_amplifier(state, event_tx, amp).await;
+    }
+    // A backup that was in service came back as the amp
+    if let Some(amp) = saved.backup_amp.filter(|_| !in_service_backup) {
+        connect_backup_amplifier(state, event_tx, amp).await;
+    }
+    if let Some(active) = saved.active {
+        select_active_radio(state, event_tx, active).await;
+    }
+    // The new actor knows nothing of the radios' state; their answers reach
+    // the amp as any other report would
+    let handles: Vec<RadioHandle> = state.radio_channels.keys().copied().collect();
+    for handle in handles {
+        for request in [
+            RadioRequest::GetFrequency,
+            RadioRequest::GetMode,
+            RadioRequest::GetPtt,
+        ] {
+            send_radio_request(state, handle, request).await;
+        }
+    }
+}
+
+/// Report radios whose duty cycle crossed the alert threshold
+async fn report_duty_cycle(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    for alert in state.tx_stats.check(Instant::now()) {
+        if alert.over {
+            warn!(
+                "Radio {} duty cycle {:.0}% over the last {}s",
+                alert.handle.0,
+                alert.duty_cycle * 100.0,
+                state.tx_stats.config().window_secs
+            );
+        }
+        let _ = event_tx
+            .send(MuxEvent::DutyCycleAlert {
+                handle: alert.handle,
+                duty_cycle: alert.duty_cycle,
+                over: alert.over,
+            })
+            .await;
+    }
+}
+
+/// Emit a bookmark, noting what the active radio was doing
+async fn add_bookmark(state: &MuxActorState, event_tx: &mpsc::Sender<MuxEvent>, source: String) {
+    let note = state
+        .multiplexer
+        .active_radio()
+        .and_then(|h| state.multiplexer.get_radio(h))
+        .map(|r| {
+            let mut note = r.name.clone();
+            if let Some(hz) = r.frequency_hz {
+                note.push_str(&format!(" on {:.6} MHz", hz.mhz()));
+            }
+            if let Some(mode) = r.mode {
+                note.push_str(&format!(" {:?}", mode));
+            }
+            if r.ptt {
+                note.push_str(" (TX)");
+            }
+            note
+        })
+        .unwrap_or_default();
+    info!("Bookmark ({}): {}", source, note);
+    let _ = event_tx
+        .send(MuxEvent::Bookmark {
+            source,
+            note,
+            timestamp: SystemTime::now(),
+        })
+        .await;
+}
+
+/// Note where the active radio was when it unkeyed
+async fn snapshot_contact(
+    state: &MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    handle: RadioHandle,
+) {
+    let Some(radio) = state.multiplexer.get_radio(handle) else {
+        return;
+    };
+    debug!(
+        "Contact snapshot: {} on {:?} Hz {:?}",
+        radio.name, radio.frequency_hz, radio.mode
+    );
+    let _ = event_tx
+        .send(MuxEvent::ContactSnapshot {
+            handle,
+            radio: radio.name.clone(),
+            frequency_hz: radio.frequency_hz,
+            mode: radio.mode,
+            timestamp: SystemTime::now(),
+        })
+        .await;
+}
+
+/// Share the active radio's state with peers and apply the same-band TX interlock
+///
+/// The amp is unkeyed when a peer that keyed first holds the band. Once the
+/// peer lets go, the amp is keyed again only on the radio's next key-down, so
+/// its relays never close with RF present.
+async fn update_peer_sync(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let Some((instance, outgoing)) = &state.peer_sync else {
+        if state.peer_block.take().is_some() {
+            let _ = event_tx
+                .send(MuxEvent::PeerInterlockChanged { blocked_by: None })
+                .await;
+        }
+        return;
+    };
+
+    // The station transmits while any of its radios is keyed, not just the
+    // active one: switching away from a keyed radio, or a late key-down on
+    // the radio switched from, is not an edge
+    let active = state
+        .multiplexer
+        .active_radio()
+        .and_then(|h| state.multiplexer.get_radio(h));
+    let radio = active
+        .filter(|r| r.ptt)
+        .or_else(|| state.multiplexer.radios().find(|r| r.ptt && !r.rx_only))
+        .or(active);
+    let ptt = radio.is_some_and(|r| r.ptt);
+    state.local_tx_since = match (ptt, state.local_tx_since) {
+        (false, _) => None,
+        (true, Some(since)) => Some(since),
+        (true, None) => Some(unix_ms(SystemTime::now())),
+    };
+    let local = PeerState {
+        frequency_hz: radio.and_then(|r| r.frequency_hz),
+        tx_since_ms: state.local_tx_since,
+    };
+    if state.peer_state_sent != Some(local) {
+        if outgoing.try_send(local).is_err() {
+            debug!("Peer sync not keeping up, dropping a local state update");
+        }
+        state.peer_state_sent = Some(local);
+    }
+
+    let blocked_by = state.peers.blocking(&local, instance).cloned();
+    let block = blocked_by.as_ref().map(|p| p.instance.clone());
+    // Stays locked out until the radio unkeys, even if the peer lets go first
+    let block = match (block, &state.peer_block) {
+        (None, Some(peer)) if ptt => Some(peer.clone()),
+        (block, _) => block,
+    };
+    if block == state.peer_block {
+        return;
+    }
+    match &blocked_by {
+        Some(peer) => warn!(
+            "Peer {} holds the band, amplifier locked out of transmit",
+            peer.summary()
+        ),
+        None => info!("Peer interlock released"),
+    }
+    state.peer_block = block;
+    if state.peer_block.is_some() && state.amp_state.keyed {
+        unkey_amp(state, event_tx).await;
+    }
+    let _ = event_tx
+        .send(MuxEvent::PeerInterlockChanged { blocked_by })
+        .await;
+}
+
+/// Report radios entering or leaving the active radio's band, and apply the
+/// same-band interlock
+///
+/// With the guard blocking PTT, an active radio that keys up (`keyed_up`)
+/// while another radio on its band is already transmitting doesn't key the
+/// amp, until it unkeys.
+async fn update_band_guard(
+    state: &mut MuxActorState,
+    event_tx: &mpsc::Sender<MuxEvent>,
+    keyed_up: bool,
+) {
+    for conflict in state.multiplexer.update_band_conflicts() {
+        match conflict.band {
+            Some(band) => warn!(
+                "Radio {} is on {}, the active radio's band",
+                conflict.handle.0, band
+            ),
+            None => debug!("Radio {} left the active radio's band", conflict.handle.0),
+        }
+        let _ = event_tx
+            .send(MuxEvent::BandConflict {
+                handle: conflict.handle,
+                active: conflict.active,
+                band: conflict.band,
+            })
+            .await;
+    }
+
+    let active = state.multiplexer.active_radio_state();
+    let keyed = active.is_some_and(|r| r.ptt);
+    let block = match (keyed, state.band_block) {
+        (false, _) => None,
+        (true, Some(holder)) => Some(holder),
+        (true, None)
+            if keyed_up
+                && state.multiplexer.band_guard_config().strictness
+                    == BandGuardStrictness::BlockPtt =>
+        {
+            state
+                .multiplexer
+                .radios()
+                .find(|r| r.ptt && state.multiplexer.band_conflict(r.handle).is_some())
+                .map(|r| r.handle)
+        }
+        (true, None) => None,
+    };
+    if block == state.band_block {
+        return;
+    }
+    let Some(handle) = state.multiplexer.active_radio() else {
+        return;
+    };
+    match block {
+        Some(holder) => warn!(
+            "Radio {} transmitting on the same band, not keying the amp for radio {}",
+            holder.0, handle.0
+        ),
+        None => info!("Band interlock released"),
+    }
+    state.band_block = block;
+    if block.is_some() && state.amp_state.keyed {
+        unkey_amp(state, event_tx).await;
+    }
+    let _ = event_tx
+        .send(MuxEvent::BandLockoutChanged {
+            handle,
+            holder: block,
+        })
+        .await;
+}
+
+/// Report when the active radio lands on or leaves a spotted frequency
+async fn update_spot_match(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let active = state.multiplexer.active_radio();
+    let spot = active
+        .and_then(|h| state.multiplexer.get_radio(h))
+        .and_then(|r| r.frequency_hz)
+        .and_then(|hz| state.dx_spots.find(hz, Instant::now()))
+        .cloned();
+    let matched = active
+        .zip(spot.as_ref())
+        .map(|(h, s)| (h, s.dx_call.clone(), s.frequency_hz));
+    if matched == state.spot_match {
+        return;
+    }
+
+    // Leaving a spot is reported against the radio that was on it
+    let handle = match (&matched, &state.spot_match) {
+        (Some((h, ..)), _) | (None, Some((h, ..))) => *h,
+        (None, None) => return,
+    };
+    if let Some(spot) = &spot {
+        info!("Radio {} is on DX spot {}", handle.0, spot.summary());
+    }
+    state.spot_match = matched;
+    let _ = event_tx.send(MuxEvent::SpotMatched { handle, spot }).await;
+}
+
+/// Report the amp's query profile when it has changed
+async fn report_amp_query_profile(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if state.amp_tx.is_none() || state.amp_queries.is_empty() {
+        return;
+    }
+    let profile = state.amp_queries.profile(Instant::now());
+    if profile == state.amp_query_profile {
+        return;
+    }
+    if profile.is_aggressive() && !state.amp_query_profile.is_aggressive() {
+        info!(
+            "Amplifier is polling aggressively ({:.1} queries/s), limiting unsolicited updates",
+            profile.total_rate_hz()
+        );
+    }
+    state.amp_query_profile = profile.clone();
+    let _ = event_tx
+        .send(MuxEvent::AmpQueryProfileUpdated { profile })
+        .await;
+}
+
+/// Emit AmpStateChanged if what the amp has been told changed
+async fn report_amp_state(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    if state.amp_state == state.reported_amp_state {
+        return;
+    }
+    state.reported_amp_state = state.amp_state;
+    let _ = event_tx
+        .send(MuxEvent::AmpStateChanged {
+            state: state.amp_state,
+        })
+        .await;
+}
+
+/// Emit events reported by user command handlers
+async fn report_custom_events(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    for (handle, event) in std::mem::take(&mut state.custom_events) {
+        debug!("Radio {} custom event {}", handle.0, event.name);
+        let _ = event_tx
+            .send(MuxEvent::RadioCustomEvent { handle, event })
+            .await;
+    }
+}
+
+/// Log why the active radio just changed
+async fn audit_switch(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let Some(mut record) = state.multiplexer.take_switch_record() else {
+        return;
+    };
+    if let SwitchTrigger::Report { frame, .. } = &mut record.trigger {
+        *frame = state.last_frames.get(&record.to).cloned();
+    }
+    let record = state.switch_audit.push(record).clone();
+    debug!(
+        "Switch #{} to radio {}: {:?}",
+        record.id, record.to.0, record.rule
+    );
+    let _ = event_tx.send(MuxEvent::SwitchAudited { record }).await;
+}
+
+/// Give the newly active radio the keyer speed the operator was using
+///
+/// Only when carrying is on and the outgoing radio's speed is known and
+/// differs from the incoming one's.
+async fn carry_keyer_speed(state: &mut MuxActorState, from: Option<RadioHandle>, to: RadioHandle) {
+    if !state.carry_keyer_speed {
+        return;
+    }
+    let Some(wpm) = from
+        .and_then(|from| state.multiplexer.get_radio(from))
+        .and_then(|radio| radio.keyer_wpm)
+    else {
+        return;
+    };
+    if state.multiplexer.get_radio(to).and_then(|r| r.keyer_wpm) == Some(wpm) {
+        return;
+    }
+    debug!("Carrying keyer speed {} WPM to radio {}", wpm, to.0);
+    send_radio_request(state, to, RadioRequest::SetKeyerSpeed { wpm }).await;
+    // Radios don't confirm a set, and the speed may be carried on again
+    // before this one is next read
+    if let Some(radio) = state.multiplexer.get_radio_mut(to) {
+        radio.set_keyer_speed(wpm);
+    }
+}
+
+/// Read a radio's keyer speed again after it unkeys, when speeds are carried
+///
+/// Radios don't report a speed change on their own, so without this a
+/// change made on the radio after connecting would never be carried.
+async fn refresh_keyer_speed(state: &MuxActorState, handle: RadioHandle) {
+    let has_keyer = state
+        .multiplexer
+        .get_radio(handle)
+        .is_some_and(|radio| radio.keyer_wpm.is_some());
+    if state.carry_keyer_speed && has_keyer {
+        send_radio_request(state, handle, RadioRequest::GetKeyerSpeed).await;
+    }
+}
+
+/// Note the mode a radio uses on its band, or, for the active radio just
+/// moved to another band, put back the mode it last used there
+async fn update_band_mode(
+    state: &mut MuxActorState,
+    handle: RadioHandle,
+    old_freq: Option<Frequency>,
+    is_active: bool,
+) {
+    let Some(radio) = state.multiplexer.get_radio(handle) else {
+        return;
+    };
+    let old_band = old_freq.and_then(band_name);
+    let band = radio.band();
+    // A first frequency report isn't a band change
+    if state.band_mode_memory && is_active && old_band.is_some() && band != old_band {
+        let remembered = band.and_then(|band| radio.band_mode(band));
+        if let Some(mode) = remembered.filter(|mode| radio.mode != Some(*mode)) {
+            // The mode it arrived in belongs to the old band; the radio's
+            // report of the restored mode is remembered instead
+            debug!(
+                "Restoring {:?} on {} for radio {}",
+                mode,
+                band.unwrap_or_default(),
+                handle.0
+            );
+            send_radio_request(state, handle, RadioRequest::SetMode { mode }).await;
+            return;
+        }
+    }
+    if let Some(radio) = state.multiplexer.get_radio_mut(handle) {
+        radio.remember_band_mode();
+    }
+}
+
+/// Report radios whose reads have changed how frames arrive
+async fn report_reassembly(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let updates: Vec<_> = state
+        .reassembly
+        .iter_mut()
+        .filter_map(|(handle, tracker)| tracker.take_update().map(|stats| (*handle, stats)))
+        .collect();
+    for (handle, stats) in updates {
+        let _ = event_tx
+            .send(MuxEvent::RadioReassembly { handle, stats })
+            .await;
+    }
+}
+
+/// Remember a write to an Icom radio, to recognise its echo
+fn note_civ_write(state: &mut MuxActorState, handle: RadioHandle, data: &[u8]) {
+    if state.get_radio_meta(handle).map(|m| m.protocol) == Some(Protocol::IcomCIV) {
+        state
+            .civ_echo
+            .entry(handle)
+            .or_default()
+            .sent(data, Instant::now());
+    }
+}
+
+/// Report Icom radios found to echo (or not echo) our CI-V frames
+async fn report_civ_echo(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let updates: Vec<_> = state
+        .civ_echo
+        .iter_mut()
+        .filter_map(|(handle, echo)| echo.take_update().map(|on| (*handle, on)))
+        .collect();
+    for (handle, echo_back) in updates {
+        if echo_back {
+            warn!(
+                "Radio {} echoes CI-V commands back; filtering the echoes",
+                handle.0
+            );
+        } else {
+            debug!("Radio {} doesn't echo CI-V commands", handle.0);
+        }
+        let _ = event_tx
+            .send(MuxEvent::RadioEchoBack { handle, echo_back })
+            .await;
+    }
+}
+
+/// Throw away partial frames that stopped arriving, so the bytes that
+/// remain can't be glued onto the front of the next frame
+async fn discard_stalled_frames(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let amp_protocol = state.multiplexer.amplifier_config().parse_protocol();
+    for (source, stalled) in state.partial_frames.expired(Instant::now()) {
+        // The codecs read different directions, so each is emptied where found
+        let (data, protocol, name) = match source {
+            FrameSource::Radio(handle) => {
+                let meta = state.radio_channels.get(&handle);
+                (
+                    state.codecs.get_mut(&handle).map(|codec| {
+                        let data = codec.buffered().to_vec();
+                        codec.clear();
+                        data
+                    }),
+                    meta.map(|m| m.protocol).unwrap_or(Protocol::Kenwood),
+                    meta.map(|m| m.display_name.clone())
+                        .unwrap_or_else(|| format!("radio {}", handle.0)),
+                )
+            }
+            FrameSource::Amplifier => (
+                state.amp_codec.as_mut().map(|codec| {
+                    let data = codec.buffered().to_vec();
+                    codec.clear();
+                    data
+                }),
+                amp_protocol,
+                "amplifier".to_string(),
+            ),
+            FrameSource::ShadowAmplifier => (
+                state.shadow_amp_codec.as_mut().map(|codec| {
+                    let data = codec.buffered().to_vec();
+                    codec.clear();
+                    data
+                }),
+                amp_protocol,
+                "shadow amplifier".to_string(),
+            ),
+        };
+        let Some(data) = data.filter(|data| !data.is_empty()) else {
+            continue;
+        };
+        warn!(
+            "Discarded {} bytes of a partial frame from {} after {:?} without more data: {:02X?}",
+            data.len(),
+            name,
+            stalled,
+            data
+        );
+        let _ = event_tx
+            .send(MuxEvent::PartialFrameDiscarded {
+                source,
+                data,
+                protocol,
+                stalled,
+            })
+            .await;
+    }
+}
+
+/// When the next frequency held back from the band decoder is due
+fn band_smoothing_deadline(state: &MuxActorState) -> Option<Instant> {
+    state
+        .band_smoothers
+        .values()
+        .filter_map(FrequencySmoother::next_deadline)
+        .min()
+}
+
+/// Hand the band decoder a frequency still held back for a radio keying up
+async fn flush_band_smoother(state: &mut MuxActorState, handle: RadioHandle) {
+    let flushed = state
+        .band_smoothers
+        .get_mut(&handle)
+        .and_then(|smoother| smoother.flush(Instant::now()));
+    if flushed.is_some() {
+        sync_band_decoder(state).await;
+    }
+}
+
+/// Push band data and TX/RX routing changes to the band decoder
+async fn sync_band_decoder(state: &mut MuxActorState) {
+    let Some(tx) = &state.band_decoder_tx else {
+        return;
+    };
+
+    let now = Instant::now();
+    let policy = state.smoothing.band_decoder;
+    let mut data = Vec::new();
+    for radio in state.multiplexer.radios() {
+        let Some(hz) = radio.frequency_hz else {
+            continue;
+        };
+        let smoother = state
+            .band_smoothers
+            .entry(radio.handle)
+            .or_insert_with(|| FrequencySmoother::new(policy));
+        smoother.offer(hz, now);
+        if let Some(hz) = smoother.released() {
+            if let Some(bytes) = state.band_decoder.on_frequency(radio.handle, hz) {
+                data.extend(bytes);
+            }
+        }
+    }
+    if let Some(active) = state.multiplexer.active_radio() {
+        if let Some(bytes) = state.band_decoder.on_active_radio(active) {
+            data.extend(bytes);
+        }
+    }
+
+    if !data.is_empty() {
+        if let Err(e) = tx.send(data).await {
+            warn!("Failed to send to band decoder: {}", e);
+        }
+    }
+}
+
+/// Tune monitor channels that follow the active radio to its frequency
+async fn sync_monitor_followers(state: &mut MuxActorState) {
+    let Some(hz) = state
+        .multiplexer
+        .active_radio_state()
+        .and_then(|r| r.frequency_hz)
+    else {
+        return;
+    };
+    let multiplexer = &state.multiplexer;
+    let due = state.monitor_followers.retune(hz, |h| {
+        multiplexer.get_radio(h).and_then(|r| r.frequency_hz)
+    });
+    for handle in due {
+        debug!("Monitor radio {} following to {} Hz", handle.0, hz.hz());
+        send_radio_request(state, handle, RadioRequest::SetFrequency { hz }).await;
+    }
+}
+
+/// Emit RadioStaleChanged for any radio whose staleness has flipped
+async fn update_stale_radios(state: &mut MuxActorState, event_tx: &mpsc::Sender<MuxEvent>) {
+    let handles: Vec<RadioHandle> = state.radio_channels.keys().copied().collect();
+
+    for handle in handles {
+        let stale = state.multiplexer.is_radio_stale(handle);
+        let changed = if stale {
+            state.stale_radios.insert(handle)
+        } else {
+            state.stale_radios.remove(&handle)
+        };
+        if !changed {
+            continue;
+        }
+
+        let last_seen = state
+            .multiplexer
+            .get_radio(handle)
+            .and_then(|r| r.last_seen);
+        if stale {
+            info!("Radio {} has gone stale", handle.0);
+        } else {
+            info!("Radio {} is active again", handle.0);
+        }
+        let _ = event_tx
+            .send(MuxEvent::RadioStaleChanged {
+                handle,
+                stale,
+                last_seen,
+            })
+            .await;
+    }
+}
+
+/// Send AI2; heartbeat to all connected Kenwood/Elecraft radios
+///
+/// This ensures auto-info mode stays enabled even if a radio restarts.
+async fn send_ai2_heartbeat(state: &mut MuxActorState) {
+    let ai2_bytes = b"AI2;".to_vec();
+
+    for (handle, tx) in &state.radio_cmd_tx {
+        // Only send to Kenwood-compatible protocols
+        if let Some(meta) = state.radio_channels.get(handle) {
+            if matches!(meta.protocol, Protocol::Kenwood | Protocol::Elecraft) {
+                let _ = tx
+                    .send(RadioTaskCommand::SendData {
+                        data: ai2_bytes.clone(),
+                    })
+                    .await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amplifier::{AmplifierChannel, AmplifierChannelMeta};
+    use crate::channel::RadioChannelMeta;
+    use crate::smoothing::SmoothingPolicy;
+    use crate::switch_audit::{LockoutState, SwitchRule};
+    use cat_protocol::MeterKind;
+
+    /// Create a channel pair for a virtual amplifier (test helper)
+    fn create_virtual_amp_channel(
+        protocol: Protocol,
+        civ_address: Option<u8>,
+        buffer_size: usize,
+    ) -> (
+        AmplifierChannel,
+        mpsc::Sender<Vec<u8>>,
+        mpsc::Receiver<Vec<u8>>,
+    ) {
+        let (cmd_tx, cmd_rx) = mpsc::channel(buffer_size);
+        let (resp_tx, resp_rx) = mpsc::channel(buffer_size);
+        let meta = AmplifierChannelMeta::new_virtual(protocol, civ_address);
+        let channel = AmplifierChannel::new(meta, cmd_tx, resp_rx);
+        (channel, resp_tx, cmd_rx)
+    }
+
+    #[tokio::test]
+    async fn test_register_radio() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        // Start actor
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Create radio metadata
+        let meta = RadioChannelMeta::new_virtual(
+            "Test Radio".to_string(),
+            "sim-001".to_string(),
+            Protocol::Kenwood,
+        );
+
+        // Register the radio
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+
+        // Get the handle
+        let handle = resp_rx.await.unwrap();
+        assert_eq!(handle.0, 1);
+
+        // Check for event
+        let event = event_rx.recv().await.unwrap();
+        match event {
+            MuxEvent::RadioConnected { handle: h, meta } => {
+                assert_eq!(h.0, 1);
+                assert_eq!(meta.display_name, "Test Radio");
+            }
+            _ => panic!("Expected RadioConnected event"),
+        }
+
+        // Shutdown
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_radio_state_changes() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
 
         // Start actor
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Create radio metadata
-        let meta = RadioChannelMeta::new_virtual(
-            "Test Radio".to_string(),
-            "sim-001".to_string(),
-            Protocol::Kenwood,
-        );
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // Drain the connected event
+        let _ = event_rx.recv().await;
+
+        // Send a frequency response
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        // Should get a state change event
+        let event = event_rx.recv().await.unwrap();
+        match event {
+            MuxEvent::RadioStateChanged {
+                handle: h, freq, ..
+            } => {
+                assert_eq!(h, handle);
+                assert_eq!(freq, Some(Frequency::from_hz(14_250_000)));
+            }
+            _ => panic!("Expected RadioStateChanged event"),
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_decoded_responses_are_logged_and_applied() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_real(
+            "FT-991A".to_string(),
+            "127.0.0.1:12345".to_string(),
+            Protocol::Kenwood,
+            None,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        cmd_tx
+            .send(MuxActorCommand::RadioResponses {
+                handle,
+                frames: vec![(
+                    RadioResponse::Frequency {
+                        hz: Frequency::from_hz(7_074_000),
+                    },
+                    b"rig.get_vfo 7074000".to_vec(),
+                )],
+            })
+            .await
+            .unwrap();
+
+        match event_rx.recv().await.unwrap() {
+            MuxEvent::RadioDataIn { data, .. } => assert_eq!(data, b"rig.get_vfo 7074000"),
+            other => panic!("Expected RadioDataIn, got {:?}", other),
+        }
+        match event_rx.recv().await.unwrap() {
+            MuxEvent::RadioStateChanged { freq, .. } => {
+                assert_eq!(freq, Some(Frequency::from_hz(7_074_000)))
+            }
+            other => panic!("Expected RadioStateChanged, got {:?}", other),
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_radio_protocol_swaps_codec() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Configured as Kenwood, but it is really an Icom
+        let meta = RadioChannelMeta::new_real(
+            "IC-7300".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::Kenwood,
+            None,
+        );
+        let (task_tx, mut task_rx) = mpsc::channel(4);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(task_tx),
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Half a Kenwood frame is left in the old codec
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"FA0001".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetRadioProtocol {
+                handle,
+                protocol: Protocol::IcomCIV,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: vec![
+                    0xFE, 0xFE, 0xE0, 0x94, 0x00, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+                ],
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+
+        match event_rx.recv().await.unwrap() {
+            MuxEvent::RadioProtocolChanged {
+                handle: h,
+                protocol,
+            } => {
+                assert_eq!(h, handle);
+                assert_eq!(protocol, Protocol::IcomCIV);
+            }
+            other => panic!("Expected RadioProtocolChanged, got {:?}", other),
+        }
+        assert!(matches!(
+            task_rx.recv().await,
+            Some(RadioTaskCommand::SetProtocol {
+                protocol: Protocol::IcomCIV
+            })
+        ));
+
+        // The CI-V frame is parsed whole, without the stale Kenwood bytes
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::RadioDataIn { data, protocol, .. } => {
+                    assert_eq!(protocol, Protocol::IcomCIV);
+                    assert_eq!(data[0], 0xFE);
+                }
+                MuxEvent::RadioStateChanged { freq, .. } => {
+                    assert_eq!(freq, Some(Frequency::from_hz(14_250_000)));
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_set_radio_model_reads_its_mode_codes() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Configured as Kenwood; identifies as a K3
+        let meta = RadioChannelMeta::new_real(
+            "K3".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::Kenwood,
+            None,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        cmd_tx
+            .send(MuxActorCommand::SetRadioModel {
+                handle,
+                model: cat_protocol::RadioDatabase::by_elecraft_id("K3").unwrap(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"MD6;".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            if let MuxEvent::RadioStateChanged { mode, .. } = event_rx.recv().await.unwrap() {
+                assert_eq!(mode, Some(OperatingMode::Data));
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_civ_echo_is_not_read_as_a_report() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_real(
+            "IC-7300".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::IcomCIV,
+            Some(0x94),
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // We ask for 7.074 MHz; the radio echoes the command, refuses it
+        // and reports where it really is
+        let set_freq = vec![
+            0xFE, 0xFE, 0x94, 0xE0, 0x05, 0x00, 0x40, 0x07, 0x07, 0x00, 0xFD,
+        ];
+        let mut reply = set_freq.clone();
+        reply.extend([0xFE, 0xFE, 0xE0, 0x94, 0xFA, 0xFD]);
+        reply.extend([
+            0xFE, 0xFE, 0xE0, 0x94, 0x00, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ]);
+        cmd_tx
+            .send(MuxActorCommand::RadioRawDataOut {
+                handle,
+                data: set_freq.clone(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: reply,
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::RadioDataIn { data, .. } => assert_ne!(data, set_freq),
+                MuxEvent::RadioStateChanged { freq, .. } => {
+                    assert_eq!(freq, Some(Frequency::from_hz(14_250_000)));
+                    break;
+                }
+                _ => {}
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_state_is_reported_and_queryable() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta: RadioChannelMeta::new_real(
+                    "Rig".to_string(),
+                    "/dev/ttyUSB0".to_string(),
+                    Protocol::Kenwood,
+                    None,
+                ),
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_074_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        loop {
+            if let MuxEvent::AmpStateChanged { state } = event_rx.recv().await.unwrap() {
+                assert_eq!(state.frequency_hz, Some(Frequency::from_hz(14_074_000)));
+                assert_eq!(state.band(), Some("20m"));
+                break;
+            }
+        }
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QueryAmpState { response: resp_tx })
+            .await
+            .unwrap();
+        let state = resp_rx.await.unwrap();
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(14_074_000)));
+        assert!(!state.keyed);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_monitor_radio_follows_active_radio() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // The SDR registers first but must not become active
+        let sdr_meta = RadioChannelMeta::new_real(
+            "SDR".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::Kenwood,
+            None,
+        )
+        .with_monitor(MonitorConfig {
+            follow_active: true,
+        });
+        let (task_tx, mut task_rx) = mpsc::channel(4);
+        let mut handles = Vec::new();
+        for (meta, task_tx) in [
+            (sdr_meta, Some(task_tx)),
+            (
+                RadioChannelMeta::new_real(
+                    "Rig".to_string(),
+                    "/dev/ttyUSB1".to_string(),
+                    Protocol::Kenwood,
+                    None,
+                ),
+                None,
+            ),
+        ] {
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: task_tx,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (sdr, rig) = (handles[0], handles[1]);
+
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle: rig,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_074_000),
+                },
+            })
+            .await
+            .unwrap();
+        assert!(matches!(
+            task_rx.recv().await,
+            Some(RadioTaskCommand::SendRequest {
+                request: RadioRequest::SetFrequency { hz }
+            }) if hz == Frequency::from_khz(14_074)
+        ));
+
+        // Selecting it is refused
+        cmd_tx
+            .send(MuxActorCommand::SetActiveRadio { handle: sdr })
+            .await
+            .unwrap();
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::Error { message, .. } => {
+                    assert!(message.contains("receive-only"), "{}", message);
+                    break;
+                }
+                MuxEvent::ActiveRadioChanged { to, .. } => assert_ne!(to, sdr),
+                _ => {}
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_radio_hooks_handle_unknown_frames() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta: RadioChannelMeta::new_real(
+                    "Rig".to_string(),
+                    "/dev/ttyUSB0".to_string(),
+                    Protocol::Kenwood,
+                    None,
+                ),
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // A homebrew band switch reports XBnn; and a tuner reports its own frequency
+        let mut hooks = CommandHooks::new();
+        hooks
+            .register(|frame: &[u8]| {
+                let band = frame.strip_prefix(b"XB")?.strip_suffix(b";")?;
+                Some(cat_protocol::HookOutput::event("band", band.to_vec()))
+            })
+            .register(|frame: &[u8]| {
+                (frame == b"XT7074;").then_some(cat_protocol::HookOutput::Response(
+                    RadioResponse::Frequency {
+                        hz: Frequency::from_hz(7_074_000),
+                    },
+                ))
+            });
+        cmd_tx
+            .send(MuxActorCommand::SetRadioHooks { handle, hooks })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"XB40;XT7074;".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+
+        let mut custom = None;
+        let mut tuned = false;
+        while custom.is_none() || !tuned {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::RadioCustomEvent { handle: h, event } => {
+                    assert_eq!(h, handle);
+                    custom = Some(event);
+                }
+                MuxEvent::RadioStateChanged { freq: Some(hz), .. }
+                    if hz == Frequency::from_khz(7_074) =>
+                {
+                    tuned = true
+                }
+                _ => {}
+            }
+        }
+        let custom = custom.unwrap();
+        assert_eq!(
+            (custom.name.as_str(), custom.data.as_slice()),
+            ("band", &b"40"[..])
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_switch_is_audited_with_its_frame() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let mut handles = Vec::new();
+        for (name, port) in [("TS-590", "/dev/ttyUSB0"), ("TS-890", "/dev/ttyUSB1")] {
+            let meta = RadioChannelMeta::new_real(
+                name.to_string(),
+                port.to_string(),
+                Protocol::Kenwood,
+                None,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let second = handles[1];
+
+        // The first report only sets the frequency; the second is a change
+        for frame in [&b"FA00014074000;"[..], &b"FA00014080000;"[..]] {
+            cmd_tx
+                .send(MuxActorCommand::RadioRawData {
+                    handle: second,
+                    data: frame.to_vec(),
+                    received: Instant::now(),
+                })
+                .await
+                .unwrap();
+        }
+        let record = loop {
+            if let MuxEvent::SwitchAudited { record } = event_rx.recv().await.unwrap() {
+                break record;
+            }
+        };
+        assert_eq!(record.id, 1);
+        assert_eq!((record.from, record.to), (Some(handles[0]), second));
+        assert_eq!(
+            record.rule,
+            SwitchRule::FrequencyChanged {
+                from_hz: Frequency::from_hz(14_074_000),
+                to_hz: Frequency::from_hz(14_080_000)
+            }
+        );
+        assert_eq!(record.lockout, LockoutState::NeverArmed);
+        let SwitchTrigger::Report { frame, .. } = &record.trigger else {
+            panic!("Expected a report trigger, got {:?}", record.trigger);
+        };
+        assert_eq!(frame.as_deref(), Some(&b"FA00014080000;"[..]));
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QuerySwitchAudit {
+                limit: 10,
+                response: resp_tx,
+            })
+            .await
+            .unwrap();
+        assert_eq!(resp_rx.await.unwrap(), vec![record]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_stalled_partial_frame_is_discarded() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_real(
+            "TS-590".to_string(),
+            "/dev/ttyUSB0".to_string(),
+            Protocol::Kenwood,
+            None,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // The cable is pulled halfway through a frame
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"FA000140".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+
+        let discarded = tokio::time::timeout(Duration::from_secs(3), async {
+            loop {
+                if let MuxEvent::PartialFrameDiscarded { source, data, .. } =
+                    event_rx.recv().await.unwrap()
+                {
+                    return (source, data);
+                }
+            }
+        })
+        .await
+        .expect("partial frame should be discarded");
+        assert_eq!(
+            discarded,
+            (FrameSource::Radio(handle), b"FA000140".to_vec())
+        );
+
+        // The next frame is parsed on its own
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"FA00007074000;".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+        loop {
+            if let MuxEvent::RadioStateChanged { freq, .. } = event_rx.recv().await.unwrap() {
+                assert_eq!(freq, Some(Frequency::from_hz(7_074_000)));
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_query_responds_with_cached_frequency() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // Drain the connected event
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier using the helper
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+
+        // Drain the amp connected event
+        let _ = event_rx.recv().await;
+
+        // Set frequency on the radio (this updates the emulated state for queries)
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        // Wait for state change event (no AmpDataOut without auto-info enabled)
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if matches!(event, MuxEvent::RadioStateChanged { .. }) {
+                break;
+            }
+        }
+
+        // Now send a frequency query from the amp (FA;)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"FA;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should get AmpDataIn followed by AmpDataOut with response
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                // Should be a frequency report
+                let s = String::from_utf8_lossy(&data);
+                assert!(
+                    s.starts_with("FA") && s.contains("14250000"),
+                    "Expected frequency response, got: {}",
+                    s
+                );
+                break;
+            }
+        }
+
+        // Verify amp received the response
+        let amp_data = amp_rx.recv().await.unwrap();
+        let s = String::from_utf8_lossy(&amp_data);
+        assert!(s.contains("14250000"));
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_parses_one_protocol_and_replies_in_another() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Amp sends Kenwood queries but wants CI-V answers
+        cmd_tx
+            .send(MuxActorCommand::SetAmplifierConfig {
+                port: "[VIRTUAL]".to_string(),
+                protocol: Protocol::Kenwood,
+                baud_rate: 0,
+                civ_address: Some(0x94),
+                response_protocol: Some(Protocol::IcomCIV),
+                strictness: None,
+            })
+            .await
+            .unwrap();
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"FA;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::AmpDataIn { protocol, .. } => assert_eq!(protocol, Protocol::Kenwood),
+                MuxEvent::AmpDataOut { protocol, .. } => {
+                    assert_eq!(protocol, Protocol::IcomCIV);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        let amp_data = amp_rx.recv().await.unwrap();
+        assert_eq!(&amp_data[..2], &[0xFE, 0xFE]);
+        assert_eq!(amp_data.last(), Some(&0xFD));
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_civ_amp_queries_answered_from_cache() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        cmd_tx
+            .send(MuxActorCommand::SetAmplifierConfig {
+                port: "[VIRTUAL]".to_string(),
+                protocol: Protocol::IcomCIV,
+                baud_rate: 0,
+                civ_address: Some(0x94),
+                response_protocol: None,
+                strictness: None,
+            })
+            .await
+            .unwrap();
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::IcomCIV, Some(0x94), 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+
+        for response in [
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+            RadioResponse::Mode {
+                mode: OperatingMode::Cw,
+            },
+            RadioResponse::VfoState {
+                state: VfoState::with_split(true),
+            },
+        ] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        while amp_rx.try_recv().is_ok() {}
+
+        // The amp (0x54) asks the radio (0x94); each answer comes back from
+        // the radio's address
+        let cases: [(&[u8], &[u8]); 4] = [
+            (&[0x03], &[0x03, 0x00, 0x00, 0x25, 0x14, 0x00]),
+            (&[0x04], &[0x04, 0x03, 0x01]),
+            (&[0x0F], &[0x0F, 0x01]),
+            (&[0x1C, 0x00], &[0x1C, 0x00, 0x00]),
+        ];
+        for (query, answer) in cases {
+            let frame = [&[0xFE, 0xFE, 0x94, 0x54][..], query, &[0xFD]].concat();
+            cmd_tx
+                .send(MuxActorCommand::AmpRawData { data: frame })
+                .await
+                .unwrap();
+            let reply = amp_rx.recv().await.unwrap();
+            let expected = [&[0xFE, 0xFE, 0x54, 0x94][..], answer, &[0xFD]].concat();
+            assert_eq!(reply, expected, "answer to {:02X?}", query);
+        }
+        while event_rx.try_recv().is_ok() {}
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_query_no_response_when_no_state() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio but don't set any state
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let _ = resp_rx.await.unwrap();
+
+        // Drain the connected event
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier using the helper
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+
+        // Drain the amp connected event
+        let _ = event_rx.recv().await;
+
+        // Send a frequency query when no frequency is cached
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"FA;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should get AmpDataIn but no AmpDataOut (no cached state)
+        let event = event_rx.recv().await.unwrap();
+        assert!(matches!(event, MuxEvent::AmpDataIn { .. }));
+
+        // Amp should not receive any data (use try_recv to check without blocking)
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert!(amp_rx.try_recv().is_err());
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dual_tx_keys_amp_on_sub_receiver_frequency() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let mut meta = RadioChannelMeta::new_virtual(
+            "TS-990S".to_string(),
+            "sim".to_string(),
+            Protocol::Kenwood,
+        );
+        meta.set_model(
+            cat_protocol::RadioDatabase::by_model_name(Protocol::Kenwood, "TS-990S").unwrap(),
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        let raw = |data: &[u8]| MuxActorCommand::RadioRawData {
+            handle,
+            data: data.to_vec(),
+            received: Instant::now(),
+        };
+        cmd_tx
+            .send(raw(b"FB00021300000;FA00014250000;"))
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        while amp_rx.try_recv().is_ok() {}
+
+        // Keying up on the sub receiver moves the amp to its frequency first
+        cmd_tx.send(raw(b"TX1;")).await.unwrap();
+        let mut sent = Vec::new();
+        while sent.len() < 2 {
+            let data = tokio::time::timeout(tokio::time::Duration::from_millis(500), amp_rx.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            sent.push(String::from_utf8(data).unwrap());
+        }
+        assert_eq!(sent, vec!["FA00021300000;", "TX1;"]);
+
+        // TB; is answered with the transmitting receiver
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+        let data = tokio::time::timeout(tokio::time::Duration::from_millis(500), amp_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(data, b"TB1;");
+        while event_rx.try_recv().is_ok() {}
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_writes_reference_the_frame_that_caused_them() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio".to_string(),
+            "sim".to_string(),
+            Protocol::Kenwood,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, _amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"FA00014250000;".to_vec(),
+                received: Instant::now(),
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"FA;".to_vec(),
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        // (frame id, Some(cause) for amp writes)
+        let mut radio_in = Vec::new();
+        let mut amp_in = Vec::new();
+        let mut amp_out = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            match event {
+                MuxEvent::RadioDataIn { id, .. } => radio_in.push(id),
+                MuxEvent::AmpDataIn { id, .. } => amp_in.push(id),
+                MuxEvent::AmpDataOut { data, cause, .. } => {
+                    amp_out.push((String::from_utf8(data).unwrap(), cause))
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(radio_in.len(), 1);
+        assert_eq!(amp_in.len(), 2);
+        assert!(amp_in[0] < radio_in[0] && radio_in[0] < amp_in[1]);
+        // The radio's report is passed on, then the amp's query answered
+        assert_eq!(
+            amp_out,
+            vec![
+                ("FA00014250000;".to_string(), Some(radio_in[0])),
+                ("FA00014250000;".to_string(), Some(amp_in[1])),
+            ]
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_permissive_translation_passes_unknown_frames_through() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Elecraft);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        let raw = |data: &[u8]| MuxActorCommand::RadioRawData {
+            handle,
+            data: data.to_vec(),
+            received: Instant::now(),
+        };
+        cmd_tx.send(raw(b"FA00014250000;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        while amp_rx.try_recv().is_ok() {}
+
+        let amp_gets = |amp_rx: &mut mpsc::Receiver<Vec<u8>>| {
+            let mut sent = Vec::new();
+            while let Ok(data) = amp_rx.try_recv() {
+                sent.push(String::from_utf8(data).unwrap());
+            }
+            sent
+        };
+
+        // Strict (the default) drops what it can't translate
+        cmd_tx.send(raw(b"XY123;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        assert!(amp_gets(&mut amp_rx).is_empty());
+
+        // Permissive forwards it, Elecraft and Kenwood being one family
+        cmd_tx
+            .send(MuxActorCommand::SetTranslationStrictness {
+                strictness: TranslationStrictness::Permissive,
+            })
+            .await
+            .unwrap();
+        cmd_tx.send(raw(b"XY123;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        assert_eq!(amp_gets(&mut amp_rx), vec!["XY123;"]);
+
+        // The amp's own setting wins over the mux-wide one, and takes effect
+        // without reconnecting
+        cmd_tx
+            .send(MuxActorCommand::SetAmpTranslationStrictness {
+                strictness: Some(TranslationStrictness::Strict),
+            })
+            .await
+            .unwrap();
+        cmd_tx.send(raw(b"XY123;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        assert!(amp_gets(&mut amp_rx).is_empty());
+
+        // A CI-V amp never sees Kenwood-family frames verbatim
+        cmd_tx
+            .send(MuxActorCommand::SetAmplifierConfig {
+                port: "[VIRTUAL]".to_string(),
+                protocol: Protocol::Kenwood,
+                baud_rate: 0,
+                civ_address: Some(0x94),
+                response_protocol: Some(Protocol::IcomCIV),
+                strictness: Some(TranslationStrictness::Permissive),
+            })
+            .await
+            .unwrap();
+        cmd_tx.send(raw(b"XY123;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        assert!(amp_gets(&mut amp_rx).is_empty());
+        while event_rx.try_recv().is_ok() {}
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tx_stats_follow_radio_ptt() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let raw = |data: &[u8]| MuxActorCommand::RadioRawData {
+            handle,
+            data: data.to_vec(),
+            received: Instant::now(),
+        };
+        cmd_tx.send(raw(b"FA00014074000;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        cmd_tx.send(raw(b"TX1;")).await.unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(30)).await;
+        cmd_tx.send(raw(b"RX;")).await.unwrap();
+
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QueryTxStats { response: resp_tx })
+            .await
+            .unwrap();
+        let stats = resp_rx.await.unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].handle, handle);
+        assert_eq!(stats[0].band, Some("20m"));
+        assert_eq!(stats[0].transmissions, 1);
+        assert!(stats[0].longest >= Duration::from_millis(30));
+
+        cmd_tx.send(MuxActorCommand::ResetTxStats).await.unwrap();
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QueryTxStats { response: resp_tx })
+            .await
+            .unwrap();
+        assert!(resp_rx.await.unwrap().is_empty());
+        while event_rx.try_recv().is_ok() {}
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_backed_up_amp_holds_frequency_until_drained() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // A 4800-baud amp whose port is not draining
+        let (amp_cmd_tx, mut amp_rx) = mpsc::channel(64);
+        let (_amp_resp_tx, amp_resp_rx) = mpsc::channel(16);
+        let meta =
+            AmplifierChannelMeta::new_real("COM9".to_string(), Protocol::Kenwood, 4800, None)
+                .with_flow_control(FlowControl::Hardware);
+        let channel = AmplifierChannel::new(meta, amp_cmd_tx, amp_resp_rx);
+        let backlog = channel.backlog.clone();
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier { channel })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        backlog.queued(300);
+
+        for hz in [7_074_000, 7_075_000] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Frequency {
+                        hz: Frequency::from_hz(hz),
+                    },
+                })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        assert!(amp_rx.try_recv().is_err(), "Nothing sent while backed up");
+
+        // Once the port drains, only the latest frequency goes out
+        backlog.written(300);
+        let data = tokio::time::timeout(tokio::time::Duration::from_millis(500), amp_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&data), "FA00007075000;");
+
+        let mut changes = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let MuxEvent::AmpBacklogChanged {
+                backed_up,
+                flow_control,
+                ..
+            } = event
+            {
+                assert_eq!(flow_control, FlowControl::Hardware);
+                changes.push(backed_up);
+            }
+        }
+        assert_eq!(changes, vec![true, false]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_gapless_handover_syncs_amp_before_keying() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(256);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let mut handles = Vec::new();
+        for name in ["Radio A", "Radio B"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (radio_a, radio_b) = (handles[0], handles[1]);
+
+        // An amp whose port writes only when the test says so
+        let (amp_cmd_tx, mut amp_rx) = mpsc::channel(64);
+        let (_amp_resp_tx, amp_resp_rx) = mpsc::channel(16);
+        let meta =
+            AmplifierChannelMeta::new_real("COM9".to_string(), Protocol::Kenwood, 4800, None);
+        let channel = AmplifierChannel::new(meta, amp_cmd_tx, amp_resp_rx);
+        let backlog = channel.backlog.clone();
+        for cmd in [
+            MuxActorCommand::SetSwitchingMode {
+                mode: SwitchingMode::Manual,
+            },
+            MuxActorCommand::ConnectAmplifier { channel },
+            MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            },
+            MuxActorCommand::SetActiveRadio { handle: radio_a },
+            MuxActorCommand::RadioResponse {
+                handle: radio_b,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(21_074_000),
+                },
+            },
+            MuxActorCommand::RadioResponse {
+                handle: radio_b,
+                response: RadioResponse::Mode {
+                    mode: OperatingMode::Usb,
+                },
+            },
+            MuxActorCommand::SetGaplessHandover { enabled: true },
+            MuxActorCommand::SetSwitchingMode {
+                mode: SwitchingMode::Automatic,
+            },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        backlog.written(usize::MAX);
+        while amp_rx.try_recv().is_ok() {}
+
+        // Radio B keys up and takes over: the amp hears its frequency and
+        // mode in one write, and is not keyed while that is unwritten
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle: radio_b,
+                response: RadioResponse::Ptt { active: true },
+            })
+            .await
+            .unwrap();
+        let data = tokio::time::timeout(tokio::time::Duration::from_millis(500), amp_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&data), "FA00021074000;MD2;");
+        tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+        assert!(amp_rx.try_recv().is_err(), "Amp keyed before the sync");
+
+        // Once the port has written it, the amp is keyed
+        backlog.written(data.len());
+        let data = tokio::time::timeout(tokio::time::Duration::from_millis(500), amp_rx.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(String::from_utf8_lossy(&data), "TX1;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_dry_run_shows_but_does_not_write() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        for cmd in [
+            MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            },
+            MuxActorCommand::SetAmpDryRun { enabled: true },
+            MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            },
+            MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_074_000),
+                },
+            },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+
+        let data = loop {
+            if let MuxEvent::AmpDataOut {
+                data, suppressed, ..
+            } = event_rx.recv().await.unwrap()
+            {
+                assert!(suppressed);
+                break data;
+            }
+        };
+        assert_eq!(String::from_utf8_lossy(&data), "FA00007074000;");
+        assert!(amp_rx.try_recv().is_err(), "Nothing written in dry run");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_auto_info_sends_updates_on_state_change() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await; // Drain connected event
+
+        // Connect an amplifier using the helper
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await; // Drain amp connected event
+
+        // Enable auto-info mode (AI2; in Kenwood)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await; // Drain AmpDataIn
+
+        // Give it time to process
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Now change frequency - should trigger an unsolicited update
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_074_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        // Should get multiple events: state change, amp data out (for forwarding),
+        // and amp data out (for auto-info)
+        let mut found_auto_info_update = false;
+        for _ in 0..10 {
+            if let Ok(event) =
+                tokio::time::timeout(tokio::time::Duration::from_millis(100), event_rx.recv()).await
+            {
+                if let Some(MuxEvent::AmpDataOut { data, .. }) = event {
+                    let s = String::from_utf8_lossy(&data);
+                    if s.contains("7074000") {
+                        found_auto_info_update = true;
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+
+        assert!(
+            found_auto_info_update,
+            "Expected auto-info frequency update"
+        );
+
+        // Verify amp received the update
+        let mut found_in_amp = false;
+        while let Ok(data) = amp_rx.try_recv() {
+            let s = String::from_utf8_lossy(&data);
+            if s.contains("7074000") {
+                found_in_amp = true;
+            }
+        }
+        assert!(found_in_amp, "Amp should have received frequency update");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_coalesces_burst_into_single_amp_write() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_virtual(
+            "FT-991A".to_string(),
+            "sim".to_string(),
+            Protocol::YaesuAscii,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetAmpCoalesceWindow { ms: 20 })
+            .await
+            .unwrap();
+
+        // IF + FA + MD burst, as a Yaesu sends after a band change
+        for response in [
+            RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(7_074_000)),
+                mode: Some(OperatingMode::DataU),
+                ptt: Some(false),
+                vfo: None,
+            },
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+            RadioResponse::Mode {
+                mode: OperatingMode::DataU,
+            },
+        ] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(60)).await;
+
+        let mut writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            writes.push(data);
+        }
+        assert_eq!(writes, vec![b"FA00007074000;MD10;".to_vec()]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_repeated_reports_still_reach_amp() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        let report = MuxActorCommand::RadioResponse {
+            handle,
+            response: RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_074_000),
+            },
+        };
+        let repeat = || MuxActorCommand::RadioResponse {
+            handle,
+            response: RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_074_000),
+            },
+        };
+        // Suppression leaves the active radio's reports alone
+        cmd_tx
+            .send(MuxActorCommand::SetSuppressRepeats { enabled: true })
+            .await
+            .unwrap();
+        cmd_tx.send(report).await.unwrap();
+        cmd_tx.send(repeat()).await.unwrap();
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(60)).await;
+
+        let mut writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            writes.push(data);
+        }
+        assert_eq!(
+            writes,
+            vec![b"FA00014074000;".to_vec(), b"FA00014074000;".to_vec()]
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_key_up_sends_rate_limited_frequency_first() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "sim".to_string(),
+            Protocol::Kenwood,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 64);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        let report = |response| MuxActorCommand::RadioResponse { handle, response };
+        let tune = |hz| RadioResponse::Frequency {
+            hz: Frequency::from_hz(hz),
+        };
+        cmd_tx.send(report(tune(14_074_000))).await.unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"FA00014074000;");
+
+        // The amp polls its frequency, so updates between polls are limited
+        for _ in 0..15 {
+            cmd_tx
+                .send(MuxActorCommand::AmpRawData {
+                    data: b"FA;".to_vec(),
+                })
+                .await
+                .unwrap();
+        }
+        for _ in 0..15 {
+            assert_eq!(amp_rx.recv().await.unwrap(), b"FA00014074000;");
+        }
+        cmd_tx.send(report(tune(14_075_000))).await.unwrap();
+        cmd_tx.send(report(tune(14_076_000))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(amp_rx.try_recv().is_err());
+
+        // A key-up doesn't wait for the next poll
+        cmd_tx
+            .send(report(RadioResponse::Ptt { active: true }))
+            .await
+            .unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"FA00014076000;");
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX1;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vfo_spin_is_sampled_for_amp() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "sim".to_string(),
+            Protocol::Kenwood,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetFrequencySmoothing {
+                config: FrequencySmoothing {
+                    amplifier: SmoothingPolicy::Sample { ms: 50 },
+                    ..FrequencySmoothing::OFF
+                },
+            })
+            .await
+            .unwrap();
+
+        for hz in [14_000_000, 14_000_500, 14_001_000, 14_001_500] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Frequency {
+                        hz: Frequency::from_hz(hz),
+                    },
+                })
+                .await
+                .unwrap();
+        }
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+
+        // The first step goes straight out, then the latest once the period ends
+        let mut writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            writes.push(data);
+        }
+        assert_eq!(
+            writes,
+            vec![b"FA00014000000;".to_vec(), b"FA00014001500;".to_vec()]
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_bypassed_out_of_range() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Elecraft);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Elecraft, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetAmpBypassConfig {
+                config: AmpBypassConfig {
+                    enabled: true,
+                    personality: crate::AmpPersonality::Kpa500,
+                    ..AmpBypassConfig::default()
+                },
+            })
+            .await
+            .unwrap();
+
+        let mut amp_writes = Vec::new();
+        for hz in [14_074_000, 475_000, 1_805_000, 1_840_000] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Frequency {
+                        hz: Frequency::from_hz(hz),
+                    },
+                })
+                .await
+                .unwrap();
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+            while let Ok(data) = amp_rx.try_recv() {
+                amp_writes.push(data);
+            }
+        }
+
+        // Standby on leaving the range, operate only once clear of the edge
+        assert_eq!(amp_writes, vec![b"^OS0;".to_vec(), b"^OS1;".to_vec()]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_bypass_uses_urgent_queue() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Elecraft);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Elecraft, None, 16);
+        let (urgent_tx, mut urgent_rx) = mpsc::channel(4);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel.with_urgent(urgent_tx),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetAmpBypassConfig {
+                config: AmpBypassConfig {
+                    enabled: true,
+                    personality: crate::AmpPersonality::Kpa500,
+                    ..AmpBypassConfig::default()
+                },
+            })
+            .await
+            .unwrap();
+
+        for hz in [475_000, 1_840_000] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Frequency {
+                        hz: Frequency::from_hz(hz),
+                    },
+                })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        // Standby protects the amp and jumps the queue; operate waits its turn
+        assert_eq!(urgent_rx.try_recv().unwrap(), b"^OS0;".to_vec());
+        assert!(urgent_rx.try_recv().is_err());
+        let mut regular = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            regular.push(data);
+        }
+        assert_eq!(regular, vec![b"^OS1;".to_vec()]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_bypassed_amp_not_recorded_as_keyed() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("TS".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetAmpBypassConfig {
+                config: AmpBypassConfig {
+                    enabled: true,
+                    personality: crate::AmpPersonality::Generic,
+                    ..AmpBypassConfig::default()
+                },
+            })
+            .await
+            .unwrap();
+
+        // Below the amp's range, so it is bypassed, then key up
+        for response in [
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(475_000),
+            },
+            RadioResponse::Ptt { active: true },
+        ] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        let mut writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            writes.push(String::from_utf8_lossy(&data).into_owned());
+        }
+        assert!(!writes.iter().any(|w| w.starts_with("TX")), "{:?}", writes);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QueryAmpState { response: resp_tx })
+            .await
+            .unwrap();
+        assert!(!resp_rx.await.unwrap().keyed);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_urgent_unkey_repeated_behind_queued_key_up() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("TS".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        // Nothing drains the regular queue, as with a port still writing
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        let (urgent_tx, mut urgent_rx) = mpsc::channel(4);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel.with_urgent(urgent_tx),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        for active in [true, false] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Ptt { active },
+                })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+
+        // The unkey jumps the queue, and is repeated after the queued key-up
+        assert_eq!(urgent_rx.try_recv().unwrap(), b"TX0;".to_vec());
+        let mut regular = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            regular.push(String::from_utf8_lossy(&data).into_owned());
+        }
+        assert_eq!(regular[regular.len() - 2..], ["TX1;", "TX0;"]);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_meter_polls_answered_for_kpa1500() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Elecraft);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Meter {
+                    meter: MeterKind::Signal,
+                    level: 120,
+                },
+            })
+            .await
+            .unwrap();
+
+        let poll = |cmd_tx: mpsc::Sender<MuxActorCommand>| async move {
+            cmd_tx
+                .send(MuxActorCommand::AmpRawData {
+                    data: b"SM0;".to_vec(),
+                })
+                .await
+                .unwrap();
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        };
+
+        // Not forwarded unless the personality displays meters
+        for personality in [
+            crate::AmpPersonality::Kpa500,
+            crate::AmpPersonality::Kpa1500,
+        ] {
+            cmd_tx
+                .send(MuxActorCommand::SetAmpBypassConfig {
+                    config: AmpBypassConfig {
+                        personality,
+                        forward_meters: personality == crate::AmpPersonality::Kpa500,
+                        ..AmpBypassConfig::default()
+                    },
+                })
+                .await
+                .unwrap();
+            poll(cmd_tx.clone()).await;
+            assert!(amp_rx.try_recv().is_err());
+        }
+
+        cmd_tx
+            .send(MuxActorCommand::SetAmpBypassConfig {
+                config: AmpBypassConfig {
+                    personality: crate::AmpPersonality::Kpa1500,
+                    forward_meters: true,
+                    ..AmpBypassConfig::default()
+                },
+            })
+            .await
+            .unwrap();
+        poll(cmd_tx.clone()).await;
+        // S9 (120 of 255) in Kenwood's 0-30 scale
+        assert_eq!(amp_rx.try_recv().unwrap(), b"SM00014;".to_vec());
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_peers_hear_tx_until_every_radio_unkeys() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let mut handles = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (run, mult) = (handles[0], handles[1]);
+
+        let (peer_tx, mut peer_rx) = mpsc::channel(16);
+        cmd_tx
+            .send(MuxActorCommand::SetPeerSync {
+                instance: "run".to_string(),
+                outgoing: Some(peer_tx),
+            })
+            .await
+            .unwrap();
+
+        let send = |handle, response| MuxActorCommand::RadioResponse { handle, response };
+        for cmd in [
+            MuxActorCommand::SetActiveRadio { handle: run },
+            send(
+                run,
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_025_000),
+                },
+            ),
+            send(run, RadioResponse::Ptt { active: true }),
+            // Switched away while still transmitting
+            MuxActorCommand::SetActiveRadio { handle: mult },
+            send(
+                mult,
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_025_000),
+                },
+            ),
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let mut sent = Vec::new();
+        while let Ok(state) = peer_rx.try_recv() {
+            sent.push(state);
+        }
+        let keyed: Vec<_> = sent.iter().skip_while(|s| !s.transmitting()).collect();
+        assert!(!keyed.is_empty());
+        // One key-down, kept through the switch, on the band actually in use
+        assert!(keyed.iter().all(|s| s.tx_since_ms == keyed[0].tx_since_ms));
+        assert!(keyed
+            .iter()
+            .all(|s| s.frequency_hz == Some(Frequency::from_hz(14_025_000))));
+
+        // The real key-up
+        cmd_tx
+            .send(send(run, RadioResponse::Ptt { active: false }))
+            .await
+            .unwrap();
+        let last = loop {
+            let state = peer_rx.recv().await.unwrap();
+            if !state.transmitting() {
+                break state;
+            }
+        };
+        assert_eq!(last.frequency_hz, Some(Frequency::from_hz(7_025_000)));
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_peer_on_same_band_locks_out_amp() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Run".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        let (peer_tx, mut peer_rx) = mpsc::channel(16);
+        cmd_tx
+            .send(MuxActorCommand::SetPeerSync {
+                instance: "run".to_string(),
+                outgoing: Some(peer_tx),
+            })
+            .await
+            .unwrap();
+
+        // The mult station has been transmitting on 20m for a second
+        let now_ms = unix_ms(SystemTime::now());
+        cmd_tx
+            .send(MuxActorCommand::PeerStatus {
+                status: PeerStatus {
+                    instance: "mult".to_string(),
+                    seq: 1,
+                    sent_ms: now_ms,
+                    state: PeerState {
+                        frequency_hz: Some(Frequency::from_hz(14_200_000)),
+                        tx_since_ms: Some(now_ms - 1_000),
+                    },
+                },
+            })
+            .await
+            .unwrap();
+
+        let send = |response| MuxActorCommand::RadioResponse { handle, response };
+        cmd_tx
+            .send(send(RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_025_000),
+            }))
+            .await
+            .unwrap();
+        cmd_tx
+            .send(send(RadioResponse::Ptt { active: true }))
+            .await
+            .unwrap();
+
+        let blocked_by = loop {
+            if let MuxEvent::PeerInterlockChanged { blocked_by } = event_rx.recv().await.unwrap() {
+                break blocked_by;
+            }
+        };
+        assert_eq!(blocked_by.unwrap().instance, "mult");
+
+        // Peers heard our frequency and key-down
+        let mut sent = Vec::new();
+        while let Ok(state) = peer_rx.try_recv() {
+            sent.push(state);
+        }
+        let last = sent.last().unwrap();
+        assert_eq!(last.frequency_hz, Some(Frequency::from_hz(14_025_000)));
+        assert!(last.transmitting());
+
+        // The amp heard the frequency but was never keyed
+        let mut amp_writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            amp_writes.push(String::from_utf8_lossy(&data).into_owned());
+        }
+        assert!(amp_writes.iter().any(|w| w.contains("FA00014025000;")));
+        assert!(!amp_writes.iter().any(|w| w.contains("TX")));
+
+        // Released once the radio unkeys
+        cmd_tx
+            .send(send(RadioResponse::Ptt { active: false }))
+            .await
+            .unwrap();
+        let blocked_by = loop {
+            if let MuxEvent::PeerInterlockChanged { blocked_by } = event_rx.recv().await.unwrap() {
+                break blocked_by;
+            }
+        };
+        assert!(blocked_by.is_none());
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_band_guard_locks_out_second_radio_on_band() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let mut handles = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (run, mult) = (handles[0], handles[1]);
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::SetBandGuardConfig {
+                config: BandGuardConfig {
+                    strictness: BandGuardStrictness::BlockPtt,
+                    per_mode: false,
+                },
+            })
+            .await
+            .unwrap();
+
+        let send = |handle, response| MuxActorCommand::RadioResponse { handle, response };
+        for cmd in [
+            send(
+                run,
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_025_000),
+                },
+            ),
+            send(
+                mult,
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            ),
+            send(mult, RadioResponse::Ptt { active: true }),
+            send(run, RadioResponse::Ptt { active: true }),
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+
+        let mut conflict = None;
+        let lockout = loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::BandConflict {
+                    handle,
+                    active,
+                    band,
+                } => {
+                    conflict = Some((handle, active, band));
+                }
+                MuxEvent::BandLockoutChanged { handle, holder } => break (handle, holder),
+                _ => {}
+            }
+        };
+        assert_eq!(conflict, Some((mult, run, Some("20m"))));
+        assert_eq!(lockout, (run, Some(mult)));
+
+        // The run radio keyed second on 20m, so the amp wasn't keyed
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        let mut amp_writes = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            amp_writes.push(String::from_utf8_lossy(&data).into_owned());
+        }
+        assert!(!amp_writes.iter().any(|w| w.contains("TX")));
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_unsolicited_updates_without_auto_info() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await; // Drain connected event
+
+        // Connect an amplifier - but do NOT send AI2; to enable auto-info
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await; // Drain amp connected event
+
+        // Set frequency - without auto-info, nothing should be sent to amp
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        // Wait for processing
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Count messages received by amp - should be 0 without auto-info
+        let mut amp_messages = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            amp_messages.push(data);
+        }
+
+        // Should have no messages - amp must send AI2 to enable auto-info first
+        assert_eq!(
+            amp_messages.len(),
+            0,
+            "Without auto-info enabled, amp should receive no messages. Got {} messages",
+            amp_messages.len()
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_resets_emulated_state() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier using the helper
+        let (amp_channel, _resp_tx, _amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to cache some state
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+
+        // Drain events
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Disconnect amplifier
+        cmd_tx
+            .send(MuxActorCommand::DisconnectAmplifier)
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await; // AmpDisconnected
+
+        // Reconnect with a new channel
+        let (amp_channel2, _resp_tx2, mut amp_rx2) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel2,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Query frequency - should have no cached state after disconnect/reconnect
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"FA;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should not get a response (state was reset)
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert!(
+            amp_rx2.try_recv().is_err(),
+            "Should not have cached state after reconnect"
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_id_query_responds_with_ts990s() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Connect an amplifier using the helper (no radio needed for ID query)
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+
+        // Drain the amp connected event
+        let _ = event_rx.recv().await;
+
+        // Send an ID query from the amp (ID;)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"ID;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should get AmpDataIn followed by AmpDataOut with ID022 response
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                // Should be ID022; (TS-990S)
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "ID022;", "Expected ID022; response, got: {}", s);
+                break;
+            }
+        }
+
+        // Verify amp received the response
+        let amp_data = amp_rx.recv().await.unwrap();
+        let s = String::from_utf8_lossy(&amp_data);
+        assert_eq!(s, "ID022;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_cb_query_defaults_to_main() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Send a CB query from the amp (CB;)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should get CB0; response (default to main)
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB0;", "Expected CB0; response, got: {}", s);
+                break;
+            }
+        }
+
+        let amp_data = amp_rx.recv().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&amp_data), "CB0;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_amp_tb_query_defaults_to_main() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Send a TB query from the amp (TB;)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        // Should get TB0; response (default to main)
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB0;", "Expected TB0; response, got: {}", s);
+                break;
+            }
+        }
+
+        let amp_data = amp_rx.recv().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&amp_data), "TB0;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_cb_tb_cached_from_active_radio() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await; // Drain connected event
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio reports CB1 (Sub band selected)
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::ControlBand { band: 1 },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Radio reports TB1 (Sub band for TX)
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::TransmitBand { band: 1 },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Now amp queries CB; - should get CB1;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB1;", "Expected CB1; response, got: {}", s);
+                break;
+            }
+        }
+
+        // Verify amp received CB1
+        let amp_data = amp_rx.recv().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&amp_data), "CB1;");
+
+        // Amp queries TB; - should get TB1;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB1;", "Expected TB1; response, got: {}", s);
+                break;
+            }
+        }
+
+        let amp_data = amp_rx.recv().await.unwrap();
+        assert_eq!(String::from_utf8_lossy(&amp_data), "TB1;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vfo_a_infers_cb0_tb0() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio reports VFO A selection
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::A,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Amp queries CB; - should get CB0;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB0;", "Expected CB0; for VFO A, got: {}", s);
+                break;
+            }
+        }
+        let _ = amp_rx.recv().await.unwrap();
+
+        // Amp queries TB; - should get TB0;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB0;", "Expected TB0; for VFO A, got: {}", s);
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vfo_b_infers_cb1_tb1() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio reports VFO B selection
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::B,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Amp queries CB; - should get CB1;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB1;", "Expected CB1; for VFO B, got: {}", s);
+                break;
+            }
+        }
+        let _ = amp_rx.recv().await.unwrap();
+
+        // Amp queries TB; - should get TB1;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB1;", "Expected TB1; for VFO B, got: {}", s);
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vfo_a_then_split_infers_cb0_tb1() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio reports VFO A selection first
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::A,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Then radio enables split mode
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::Split,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Amp queries CB; - should get CB0; (RX on A)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB0;", "Expected CB0; for VFO A + Split, got: {}", s);
+                break;
+            }
+        }
+        let _ = amp_rx.recv().await.unwrap();
+
+        // Amp queries TB; - should get TB1; (TX on B, opposite of A)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB1;", "Expected TB1; for VFO A + Split, got: {}", s);
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_vfo_b_then_split_infers_cb1_tb0() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
+
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
 
-        // Register the radio
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio reports VFO B selection first
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::B,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Then radio enables split mode
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::Split,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Amp queries CB; - should get CB1; (RX on B)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB1;", "Expected CB1; for VFO B + Split, got: {}", s);
+                break;
+            }
+        }
+        let _ = amp_rx.recv().await.unwrap();
+
+        // Amp queries TB; - should get TB0; (TX on A, opposite of B)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB0;", "Expected TB0; for VFO B + Split, got: {}", s);
+                break;
+            }
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_transmit_vfo_report_infers_split() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
@@ -974,87 +8031,256 @@ mod tests {
             })
             .await
             .unwrap();
+        let handle = resp_rx.await.unwrap();
+        let _ = event_rx.recv().await;
 
-        // Get the handle
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // FR1; then FT0;: receive on B, transmit on A
+        for state in [VfoState::select(Vfo::B), VfoState::transmit_on(Vfo::A)] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::VfoState { state },
+                })
+                .await
+                .unwrap();
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        for (query, expected) in [("CB;", "CB1;"), ("TB;", "TB0;")] {
+            cmd_tx
+                .send(MuxActorCommand::AmpRawData {
+                    data: query.as_bytes().to_vec(),
+                })
+                .await
+                .unwrap();
+            loop {
+                let event = event_rx.recv().await.unwrap();
+                if let MuxEvent::AmpDataOut { data, .. } = event {
+                    assert_eq!(String::from_utf8_lossy(&data), expected);
+                    break;
+                }
+            }
+            let _ = amp_rx.recv().await.unwrap();
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_split_then_vfo_a_clears_split() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(16);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        // Register a radio
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
         let handle = resp_rx.await.unwrap();
-        assert_eq!(handle.0, 1);
+        let _ = event_rx.recv().await;
 
-        // Check for event
-        let event = event_rx.recv().await.unwrap();
-        match event {
-            MuxEvent::RadioConnected { handle: h, meta } => {
-                assert_eq!(h.0, 1);
-                assert_eq!(meta.display_name, "Test Radio");
+        // Connect an amplifier
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            })
+            .await
+            .unwrap();
+        let _ = event_rx.recv().await;
+
+        // Set frequency to make the radio active
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        while event_rx.try_recv().is_ok() {}
+
+        // Radio selects VFO A, then enables split
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::A,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::Split,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Now radio selects VFO A again (exits split mode)
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Vfo {
+                    vfo: cat_protocol::Vfo::A,
+                },
+            })
+            .await
+            .unwrap();
+        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+
+        // Amp queries CB; - should get CB0;
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"CB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "CB0;", "Expected CB0; after exiting split, got: {}", s);
+                break;
+            }
+        }
+        let _ = amp_rx.recv().await.unwrap();
+
+        // Amp queries TB; - should get TB0; (split cleared, TX back to A)
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData {
+                data: b"TB;".to_vec(),
+            })
+            .await
+            .unwrap();
+
+        loop {
+            let event = event_rx.recv().await.unwrap();
+            if let MuxEvent::AmpDataOut { data, .. } = event {
+                let s = String::from_utf8_lossy(&data);
+                assert_eq!(s, "TB0;", "Expected TB0; after exiting split, got: {}", s);
+                break;
             }
-            _ => panic!("Expected RadioConnected event"),
         }
 
-        // Shutdown
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_radio_state_changes() {
+    async fn test_band_decoder_follows_band_and_active_radio() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
-        // Start actor
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let mut handles = Vec::new();
+        for name in ["Radio 1", "Radio 2"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                name.to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+            let _ = event_rx.recv().await;
+        }
 
-        let (resp_tx, resp_rx) = oneshot::channel();
+        let (bd_tx, mut bd_rx) = mpsc::channel(16);
         cmd_tx
-            .send(MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: None,
-            })
+            .send(MuxActorCommand::ConnectBandDecoder { tx: bd_tx })
             .await
             .unwrap();
-        let handle = resp_rx.await.unwrap();
 
-        // Drain the connected event
-        let _ = event_rx.recv().await;
+        // Radio 1 is active on connect, but nothing is known about its band yet
+        assert_eq!(bd_rx.recv().await.unwrap(), b"TX1\rRX1\r");
 
-        // Send a frequency response
+        // Second radio moves to 40m: band data goes out on port 2
         cmd_tx
             .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+                handle: handles[1],
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_074_000),
+                },
             })
             .await
             .unwrap();
+        assert_eq!(bd_rx.recv().await.unwrap(), b"AUX203\r");
 
-        // Should get a state change event
-        let event = event_rx.recv().await.unwrap();
-        match event {
-            MuxEvent::RadioStateChanged {
-                handle: h, freq, ..
-            } => {
-                assert_eq!(h, handle);
-                assert_eq!(freq, Some(14_250_000));
-            }
-            _ => panic!("Expected RadioStateChanged event"),
-        }
+        // Selecting it routes TX/RX to port 2
+        cmd_tx
+            .send(MuxActorCommand::SetActiveRadio { handle: handles[1] })
+            .await
+            .unwrap();
+        assert_eq!(bd_rx.recv().await.unwrap(), b"TX2\rRX2\r");
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_amp_query_responds_with_cached_frequency() {
+    async fn test_key_up_releases_held_band_data() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        );
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
@@ -1066,81 +8292,59 @@ mod tests {
             .unwrap();
         let handle = resp_rx.await.unwrap();
 
-        // Drain the connected event
-        let _ = event_rx.recv().await;
-
-        // Connect an amplifier using the helper
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        let (bd_tx, mut bd_rx) = mpsc::channel(16);
         cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
-            })
+            .send(MuxActorCommand::ConnectBandDecoder { tx: bd_tx })
             .await
             .unwrap();
-
-        // Drain the amp connected event
-        let _ = event_rx.recv().await;
-
-        // Set frequency on the radio (this updates the emulated state for queries)
+        assert_eq!(bd_rx.recv().await.unwrap(), b"TX1\rRX1\r");
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::SetFrequencySmoothing {
+                config: FrequencySmoothing {
+                    band_decoder: SmoothingPolicy::Trailing { ms: 500 },
+                    ..FrequencySmoothing::OFF
+                },
             })
             .await
             .unwrap();
 
-        // Wait for state change event (no AmpDataOut without auto-info enabled)
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if matches!(event, MuxEvent::RadioStateChanged { .. }) {
-                break;
-            }
-        }
+        let report = |response| MuxActorCommand::RadioResponse { handle, response };
+        let tune = |hz| RadioResponse::Frequency {
+            hz: Frequency::from_hz(hz),
+        };
+        cmd_tx.send(report(tune(14_074_000))).await.unwrap();
+        assert_eq!(bd_rx.recv().await.unwrap(), b"AUX105\r");
 
-        // Now send a frequency query from the amp (FA;)
+        // The move to 40m is held for the window...
+        cmd_tx.send(report(tune(7_074_000))).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(bd_rx.try_recv().is_err());
+
+        // ...until the radio keys up
+        let keyed_at = Instant::now();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"FA;".to_vec(),
-            })
+            .send(report(RadioResponse::Ptt { active: true }))
             .await
             .unwrap();
-
-        // Should get AmpDataIn followed by AmpDataOut with response
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                // Should be a frequency report
-                let s = String::from_utf8_lossy(&data);
-                assert!(
-                    s.starts_with("FA") && s.contains("14250000"),
-                    "Expected frequency response, got: {}",
-                    s
-                );
-                break;
-            }
-        }
-
-        // Verify amp received the response
-        let amp_data = amp_rx.recv().await.unwrap();
-        let s = String::from_utf8_lossy(&amp_data);
-        assert!(s.contains("14250000"));
+        assert_eq!(bd_rx.recv().await.unwrap(), b"AUX103\r");
+        assert!(keyed_at.elapsed() < Duration::from_millis(250));
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_amp_query_no_response_when_no_state() {
+    async fn test_spot_match_follows_active_frequency() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio but don't set any state
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        );
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
@@ -1150,54 +8354,65 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = resp_rx.await.unwrap();
+        let handle = resp_rx.await.unwrap();
 
-        // Drain the connected event
-        let _ = event_rx.recv().await;
+        async fn next_spot_match(
+            rx: &mut mpsc::Receiver<MuxEvent>,
+        ) -> (RadioHandle, Option<DxSpot>) {
+            loop {
+                if let MuxEvent::SpotMatched { handle, spot } = rx.recv().await.unwrap() {
+                    return (handle, spot);
+                }
+            }
+        }
 
-        // Connect an amplifier using the helper
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_025_300),
+                },
             })
             .await
             .unwrap();
+        let spot =
+            DxSpot::parse("DX de W3LPL:  14025.0  JA1ABC  CW  1234Z", Instant::now()).unwrap();
+        cmd_tx
+            .send(MuxActorCommand::AddDxSpot { spot })
+            .await
+            .unwrap();
+        let (matched, spot) = next_spot_match(&mut event_rx).await;
+        assert_eq!(matched, handle);
+        assert_eq!(spot.unwrap().dx_call, "JA1ABC");
 
-        // Drain the amp connected event
-        let _ = event_rx.recv().await;
-
-        // Send a frequency query when no frequency is cached
+        // Tuning away reports leaving the spot
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"FA;".to_vec(),
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_030_000),
+                },
             })
             .await
             .unwrap();
-
-        // Should get AmpDataIn but no AmpDataOut (no cached state)
-        let event = event_rx.recv().await.unwrap();
-        assert!(matches!(event, MuxEvent::AmpDataIn { .. }));
-
-        // Amp should not receive any data (use try_recv to check without blocking)
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        assert!(amp_rx.try_recv().is_err());
+        assert_eq!(next_spot_match(&mut event_rx).await, (handle, None));
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_auto_info_sends_updates_on_state_change() {
+    async fn test_ptt_double_tap_drops_bookmark() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        );
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
@@ -1208,87 +8423,49 @@ mod tests {
             .await
             .unwrap();
         let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await; // Drain connected event
-
-        // Connect an amplifier using the helper
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
-        cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
-            })
-            .await
-            .unwrap();
-        let _ = event_rx.recv().await; // Drain amp connected event
-
-        // Enable auto-info mode (AI2; in Kenwood)
-        cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"AI2;".to_vec(),
-            })
-            .await
-            .unwrap();
-        let _ = event_rx.recv().await; // Drain AmpDataIn
 
-        // Give it time to process
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Now change frequency - should trigger an unsolicited update
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 7_074_000 },
-            })
+            .send(MuxActorCommand::SetBookmarkDoubleTap { ms: Some(500) })
             .await
             .unwrap();
-
-        // Should get multiple events: state change, amp data out (for forwarding),
-        // and amp data out (for auto-info)
-        let mut found_auto_info_update = false;
-        for _ in 0..10 {
-            if let Ok(event) =
-                tokio::time::timeout(tokio::time::Duration::from_millis(100), event_rx.recv()).await
-            {
-                if let Some(MuxEvent::AmpDataOut { data, .. }) = event {
-                    let s = String::from_utf8_lossy(&data);
-                    if s.contains("7074000") {
-                        found_auto_info_update = true;
-                    }
-                }
-            } else {
-                break;
-            }
+        for response in [
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_074_000),
+            },
+            RadioResponse::Ptt { active: true },
+            RadioResponse::Ptt { active: false },
+            RadioResponse::Ptt { active: true },
+        ] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
         }
 
-        assert!(
-            found_auto_info_update,
-            "Expected auto-info frequency update"
-        );
-
-        // Verify amp received the update
-        let mut found_in_amp = false;
-        while let Ok(data) = amp_rx.try_recv() {
-            let s = String::from_utf8_lossy(&data);
-            if s.contains("7074000") {
-                found_in_amp = true;
+        let (source, note) = loop {
+            if let MuxEvent::Bookmark { source, note, .. } = event_rx.recv().await.unwrap() {
+                break (source, note);
             }
-        }
-        assert!(found_in_amp, "Amp should have received frequency update");
+        };
+        assert_eq!(source, "PTT double-tap on Radio 1");
+        assert_eq!(note, "Radio 1 on 14.074000 MHz (TX)");
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_no_unsolicited_updates_without_auto_info() {
+    async fn test_ptt_release_snapshots_active_radio() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        );
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
@@ -1299,188 +8476,329 @@ mod tests {
             .await
             .unwrap();
         let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await; // Drain connected event
-
-        // Connect an amplifier - but do NOT send AI2; to enable auto-info
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
-        cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
-            })
-            .await
-            .unwrap();
-        let _ = event_rx.recv().await; // Drain amp connected event
 
-        // Set frequency - without auto-info, nothing should be sent to amp
+        // Off by default: this key-down and release leave no snapshot
+        let cycle = [
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_030_000),
+            },
+            RadioResponse::Mode {
+                mode: OperatingMode::Cw,
+            },
+            RadioResponse::Ptt { active: true },
+            RadioResponse::Ptt { active: false },
+        ];
+        for response in cycle.clone() {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
+        }
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
-            })
+            .send(MuxActorCommand::SetContactSnapshots { enabled: true })
             .await
             .unwrap();
-
-        // Wait for processing
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Count messages received by amp - should be 0 without auto-info
-        let mut amp_messages = Vec::new();
-        while let Ok(data) = amp_rx.try_recv() {
-            amp_messages.push(data);
+        for response in cycle {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse { handle, response })
+                .await
+                .unwrap();
         }
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
 
-        // Should have no messages - amp must send AI2 to enable auto-info first
-        assert_eq!(
-            amp_messages.len(),
-            0,
-            "Without auto-info enabled, amp should receive no messages. Got {} messages",
-            amp_messages.len()
+        let mut snapshots = Vec::new();
+        while let Ok(event) = event_rx.try_recv() {
+            if let MuxEvent::ContactSnapshot {
+                handle: h,
+                radio,
+                frequency_hz,
+                mode,
+                ..
+            } = event
+            {
+                snapshots.push((h, radio, frequency_hz, mode));
+            }
+        }
+        assert_eq!(
+            snapshots,
+            vec![(
+                handle,
+                "Radio 1".to_string(),
+                Some(Frequency::from_hz(7_030_000)),
+                Some(OperatingMode::Cw)
+            )]
         );
-
-        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
-        actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_disconnect_resets_emulated_state() {
+    async fn test_transverter_offset_moves_frequencies() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        // 2 m transverter on a 28 MHz IF
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        )
+        .with_frequency_offset(116_000_000);
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
         let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
             .send(MuxActorCommand::RegisterRadio {
                 meta,
                 response: resp_tx,
-                cmd_tx: None,
+                cmd_tx: Some(radio_tx),
             })
             .await
             .unwrap();
         let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
 
-        // Connect an amplifier using the helper
-        let (amp_channel, _resp_tx, _amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
+            .send(MuxActorCommand::RadioResponse {
+                handle,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(28_174_000),
+                },
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Set frequency to cache some state
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
+            .send(MuxActorCommand::QueryRadioState {
                 handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+                response: resp_tx,
             })
             .await
             .unwrap();
+        let summary = resp_rx.await.unwrap().unwrap();
+        assert_eq!(summary.frequency_hz, Some(Frequency::from_hz(144_174_000)));
 
-        // Drain events
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
-
-        // Disconnect amplifier
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::DisconnectAmplifier)
+            .send(MuxActorCommand::ControlActiveRadio {
+                request: RadioRequest::SetFrequency {
+                    hz: Frequency::from_hz(144_300_000),
+                },
+                response: resp_tx,
+            })
             .await
             .unwrap();
-        let _ = event_rx.recv().await; // AmpDisconnected
+        assert!(resp_rx.await.unwrap());
+        assert_eq!(
+            next_radio_request(&mut radio_rx).await,
+            RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(28_300_000)
+            }
+        );
 
-        // Reconnect with a new channel
-        let (amp_channel2, _resp_tx2, mut amp_rx2) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_control_active_radio_without_command_channel() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, _event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta = RadioChannelMeta::new_virtual(
+            "Radio 1".to_string(),
+            "Radio 1".to_string(),
+            Protocol::Kenwood,
+        );
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel2,
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
+        resp_rx.await.unwrap();
 
-        // Query frequency - should have no cached state after disconnect/reconnect
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"FA;".to_vec(),
+            .send(MuxActorCommand::ControlActiveRadio {
+                request: RadioRequest::SetFrequency {
+                    hz: Frequency::from_hz(14_074_000),
+                },
+                response: resp_tx,
             })
             .await
             .unwrap();
-
-        // Should not get a response (state was reset)
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        assert!(
-            amp_rx2.try_recv().is_err(),
-            "Should not have cached state after reconnect"
-        );
+        assert!(!resp_rx.await.unwrap());
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_amp_id_query_responds_with_ts990s() {
+    async fn test_entered_frequency_checked_against_radio() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
 
-        // Connect an amplifier using the helper (no radio needed for ID query)
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        let mut meta = RadioChannelMeta::new_virtual(
+            "TS-990S".to_string(),
+            "sim".to_string(),
+            Protocol::Kenwood,
+        );
+        meta.set_model(
+            cat_protocol::RadioDatabase::by_model_name(Protocol::Kenwood, "TS-990S").unwrap(),
+        );
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(radio_tx),
             })
             .await
             .unwrap();
+        let handle = resp_rx.await.unwrap();
 
-        // Drain the amp connected event
-        let _ = event_rx.recv().await;
+        let enter = |handle, hz, transmit| {
+            let cmd_tx = cmd_tx.clone();
+            async move {
+                let (response, rx) = oneshot::channel();
+                cmd_tx
+                    .send(MuxActorCommand::EnterFrequency {
+                        handle,
+                        hz: Frequency::from_hz(hz),
+                        transmit,
+                        region: BandPlanRegion::Region2,
+                        response,
+                    })
+                    .await
+                    .unwrap();
+                rx.await.unwrap()
+            }
+        };
+
+        // Above the TS-990S's 60 MHz limit: refused, not clamped
+        assert!(matches!(
+            enter(handle, 144_300_000, false).await,
+            Err(FrequencyEntryError::OutOfRange { max_hz, .. })
+                if max_hz == Frequency::from_mhz(60)
+        ));
+        // Shortwave broadcast can be listened to but not transmitted on
+        assert_eq!(
+            enter(handle, 9_500_000, true).await,
+            Err(FrequencyEntryError::TxNotPermitted {
+                hz: Frequency::from_hz(9_500_000),
+                region: BandPlanRegion::Region2
+            })
+        );
+        assert_eq!(
+            enter(RadioHandle(99), 14_074_000, true).await,
+            Err(FrequencyEntryError::UnknownRadio)
+        );
+        assert!(radio_rx.try_recv().is_err(), "Nothing sent for a refusal");
 
-        // Send an ID query from the amp (ID;)
+        assert_eq!(enter(handle, 9_500_000, false).await, Ok(()));
+        assert_eq!(
+            next_radio_request(&mut radio_rx).await,
+            RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(9_500_000)
+            }
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_kat500_follows_radio_band() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let meta =
+            RadioChannelMeta::new_virtual("K3".to_string(), "sim".to_string(), Protocol::Elecraft)
+                .with_kat500();
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"ID;".to_vec(),
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(radio_tx),
             })
             .await
             .unwrap();
+        let handle = resp_rx.await.unwrap();
 
-        // Should get AmpDataIn followed by AmpDataOut with ID022 response
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                // Should be ID022; (TS-990S)
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "ID022;", "Expected ID022; response, got: {}", s);
-                break;
+        // Tuner frames on the radio's line, skipping the AI2 heartbeat
+        async fn next_tuner_frame(rx: &mut mpsc::Receiver<RadioTaskCommand>) -> Vec<u8> {
+            loop {
+                if let RadioTaskCommand::SendData { data } = rx.recv().await.unwrap() {
+                    if data.starts_with(b"~") {
+                        return data;
+                    }
+                }
             }
         }
-
-        // Verify amp received the response
-        let amp_data = amp_rx.recv().await.unwrap();
-        let s = String::from_utf8_lossy(&amp_data);
-        assert_eq!(s, "ID022;");
+        for khz in [14_074, 14_250, 7_074] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle,
+                    response: RadioResponse::Frequency {
+                        hz: Frequency::from_khz(khz),
+                    },
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(next_tuner_frame(&mut radio_rx).await, b"~BN05;");
+        // Retuning within 20m didn't switch it again
+        assert_eq!(next_tuner_frame(&mut radio_rx).await, b"~BN03;");
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
+    /// Next request sent to a radio task, skipping raw writes like the AI2 heartbeat
+    async fn next_radio_request(rx: &mut mpsc::Receiver<RadioTaskCommand>) -> RadioRequest {
+        loop {
+            if let RadioTaskCommand::SendRequest { request } = rx.recv().await.unwrap() {
+                return request;
+            }
+        }
+    }
+
     #[tokio::test]
-    async fn test_amp_cb_query_defaults_to_main() {
+    async fn test_sequencer_keys_amp_before_radio() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(radio_tx),
+            })
+            .await
+            .unwrap();
+        let handle = resp_rx.await.unwrap();
 
-        // Connect an amplifier
         let (amp_channel, _resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
@@ -1489,41 +8807,63 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
+        cmd_tx
+            .send(MuxActorCommand::SetSequencerConfig {
+                config: SequencerConfig {
+                    lead_ms: 30,
+                    tail_ms: 30,
+                },
+            })
+            .await
+            .unwrap();
 
-        // Send a CB query from the amp (CB;)
+        let keyed_at = Instant::now();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+            .send(MuxActorCommand::RequestPtt {
+                handle,
+                active: true,
             })
             .await
             .unwrap();
 
-        // Should get CB0; response (default to main)
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB0;", "Expected CB0; response, got: {}", s);
-                break;
-            }
-        }
+        // Amp first...
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX1;");
+        // ...then the radio once the lead time has passed
+        assert_eq!(
+            next_radio_request(&mut radio_rx).await,
+            RadioRequest::SetPtt { active: true }
+        );
+        assert!(keyed_at.elapsed() >= Duration::from_millis(30));
 
-        let amp_data = amp_rx.recv().await.unwrap();
-        assert_eq!(String::from_utf8_lossy(&amp_data), "CB0;");
+        let unkeyed_at = Instant::now();
+        cmd_tx
+            .send(MuxActorCommand::RequestPtt {
+                handle,
+                active: false,
+            })
+            .await
+            .unwrap();
+
+        // Radio drops straight away, amp follows after the tail
+        assert_eq!(
+            next_radio_request(&mut radio_rx).await,
+            RadioRequest::SetPtt { active: false }
+        );
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
+        assert!(unkeyed_at.elapsed() >= Duration::from_millis(30));
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_amp_tb_query_defaults_to_main() {
+    async fn test_removing_keyed_active_radio_unkeys_amp() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
 
-        // Connect an amplifier
         let (amp_channel, _resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
@@ -1532,56 +8872,117 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Send a TB query from the amp (TB;)
         cmd_tx
             .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
+                data: b"AI2;".to_vec(),
             })
             .await
             .unwrap();
 
-        // Should get TB0; response (default to main)
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB0;", "Expected TB0; response, got: {}", s);
-                break;
+        for sequenced in [false, true] {
+            let meta = RadioChannelMeta::new_virtual(
+                "Test".to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (radio_tx, mut radio_rx) = mpsc::channel(16);
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: Some(radio_tx),
+                })
+                .await
+                .unwrap();
+            let handle = resp_rx.await.unwrap();
+            cmd_tx
+                .send(MuxActorCommand::SetActiveRadio { handle })
+                .await
+                .unwrap();
+
+            if sequenced {
+                // Removed during the lead time, before the radio is keyed
+                cmd_tx
+                    .send(MuxActorCommand::SetSequencerConfig {
+                        config: SequencerConfig {
+                            lead_ms: 50,
+                            tail_ms: 50,
+                        },
+                    })
+                    .await
+                    .unwrap();
+                cmd_tx
+                    .send(MuxActorCommand::RequestPtt {
+                        handle,
+                        active: true,
+                    })
+                    .await
+                    .unwrap();
+            } else {
+                cmd_tx
+                    .send(MuxActorCommand::RadioRawData {
+                        handle,
+                        data: b"TX1;".to_vec(),
+                        received: Instant::now(),
+                    })
+                    .await
+                    .unwrap();
+            }
+            assert_eq!(amp_rx.recv().await.unwrap(), b"TX1;");
+
+            cmd_tx
+                .send(MuxActorCommand::UnregisterRadio { handle })
+                .await
+                .unwrap();
+            assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
+
+            // The sequencer doesn't go on to key the radio that's gone
+            tokio::time::sleep(Duration::from_millis(80)).await;
+            while let Ok(command) = radio_rx.try_recv() {
+                assert!(!matches!(
+                    command,
+                    RadioTaskCommand::SendRequest {
+                        request: RadioRequest::SetPtt { active: true }
+                    }
+                ));
             }
+            assert!(amp_rx.try_recv().is_err());
         }
 
-        let amp_data = amp_rx.recv().await.unwrap();
-        assert_eq!(String::from_utf8_lossy(&amp_data), "TB0;");
-
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_cb_tb_cached_from_active_radio() {
+    async fn test_ptt_switch_keys_active_radio_and_amp_follows() {
         let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let mut radios = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                name.to_string(),
+                Protocol::Kenwood,
+            );
+            let (radio_tx, radio_rx) = mpsc::channel(16);
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: Some(radio_tx),
+                })
+                .await
+                .unwrap();
+            radios.push((resp_rx.await.unwrap(), radio_rx));
+        }
+        let [(run, mut run_rx), (mult, mut mult_rx)] = <[_; 2]>::try_from(radios).unwrap();
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
-        let (resp_tx, resp_rx) = oneshot::channel();
-        cmd_tx
-            .send(MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: None,
-            })
-            .await
-            .unwrap();
-        let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await; // Drain connected event
-
-        // Connect an amplifier
         let (amp_channel, _resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
@@ -1590,92 +8991,155 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Set frequency to make the radio active
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
+        cmd_tx
+            .send(MuxActorCommand::SetActiveRadio { handle: run })
+            .await
+            .unwrap();
+
+        let press = |active| MuxActorCommand::SwitchPtt {
+            source: "CTS on /dev/ttyUSB3".to_string(),
+            active,
+        };
+        let report = |handle, data: &[u8]| MuxActorCommand::RadioRawData {
+            handle,
+            data: data.to_vec(),
+            received: Instant::now(),
+        };
+
+        // The radio is keyed and asked to confirm; the amp waits for it
+        cmd_tx.send(press(true)).await.unwrap();
+        assert_eq!(
+            next_radio_request(&mut run_rx).await,
+            RadioRequest::SetPtt { active: true }
+        );
+        assert_eq!(next_radio_request(&mut run_rx).await, RadioRequest::GetPtt);
+        tokio::time::sleep(tokio::time::Duration::from_millis(20)).await;
+        assert!(amp_rx.try_recv().is_err());
+        cmd_tx.send(report(run, b"TX1;")).await.unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX1;");
+
+        // Release unkeys the radio that was keyed, even after a switch
+        cmd_tx
+            .send(MuxActorCommand::SetActiveRadio { handle: mult })
+            .await
+            .unwrap();
+        cmd_tx.send(press(false)).await.unwrap();
+        assert_eq!(
+            next_radio_request(&mut run_rx).await,
+            RadioRequest::SetPtt { active: false }
+        );
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
 
-        // Radio reports CB1 (Sub band selected)
+        // A radio that disconnects while keyed unkeys the amp
+        cmd_tx.send(press(true)).await.unwrap();
+        assert_eq!(
+            next_radio_request(&mut mult_rx).await,
+            RadioRequest::SetPtt { active: true }
+        );
+        cmd_tx.send(report(mult, b"TX1;")).await.unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX1;");
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::ControlBand { band: 1 },
-            })
+            .send(MuxActorCommand::UnregisterRadio { handle: mult })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
 
-        // Radio reports TB1 (Sub band for TX)
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ptt_off_jumps_queued_bulk_data() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(256);
+        let (event_tx, mut event_rx) = mpsc::channel(1024);
+
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let meta =
+            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::TransmitBand { band: 1 },
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Now amp queries CB; - should get CB1;
+        let handle = resp_rx.await.unwrap();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"TX1;".to_vec(),
+                received: Instant::now(),
             })
             .await
             .unwrap();
-
         loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB1;", "Expected CB1; response, got: {}", s);
+            if let MuxEvent::PttLatency { active, .. } = event_rx.recv().await.unwrap() {
+                assert!(active);
                 break;
             }
         }
 
-        // Verify amp received CB1
-        let amp_data = amp_rx.recv().await.unwrap();
-        assert_eq!(String::from_utf8_lossy(&amp_data), "CB1;");
-
-        // Amp queries TB; - should get TB1;
+        // A backlog of bulk frames queued ahead of the unkey
+        for i in 0..40u64 {
+            let data = format!("FA{:011};", 14_000_000 + i).into_bytes();
+            cmd_tx
+                .send(MuxActorCommand::RadioRawData {
+                    handle,
+                    data,
+                    received: Instant::now(),
+                })
+                .await
+                .unwrap();
+        }
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
+            .send(MuxActorCommand::RadioRawData {
+                handle,
+                data: b"TX0;".to_vec(),
+                received: Instant::now(),
             })
             .await
             .unwrap();
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB1;", "Expected TB1; response, got: {}", s);
-                break;
+        let mut first_data = None;
+        let mut latency = None;
+        while latency.is_none() {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::RadioDataIn { data, .. } if first_data.is_none() => {
+                    first_data = Some(data);
+                }
+                MuxEvent::PttLatency {
+                    active, latency: l, ..
+                } => {
+                    assert!(!active);
+                    latency = Some(l);
+                }
+                _ => {}
             }
         }
 
-        let amp_data = amp_rx.recv().await.unwrap();
-        assert_eq!(String::from_utf8_lossy(&amp_data), "TB1;");
+        assert_eq!(first_data.unwrap(), b"TX0;");
+        assert!(latency.unwrap() < crate::latency::PTT_LATENCY_BUDGET);
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_vfo_a_infers_cb0_tb0() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
+    async fn test_ptt_on_waits_for_queued_retune() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
 
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
         let meta =
             RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
         let (resp_tx, resp_rx) = oneshot::channel();
@@ -1688,9 +9152,7 @@ mod tests {
             .await
             .unwrap();
         let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
 
-        // Connect an amplifier
         let (amp_channel, _resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
@@ -1699,78 +9161,52 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Set frequency to make the radio active
-        cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
-            })
-            .await
-            .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
-
-        // Radio reports VFO A selection
-        cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::A,
-                },
-            })
-            .await
-            .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Amp queries CB; - should get CB0;
         cmd_tx
             .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+                data: b"AI2;".to_vec(),
             })
             .await
             .unwrap();
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB0;", "Expected CB0; for VFO A, got: {}", s);
-                break;
-            }
+        // Queued back to back, so both land in one batch: a retune, then a
+        // key-up, and an unkey behind the key-up
+        for data in [&b"FA00021074000;"[..], b"TX1;", b"TX0;"] {
+            cmd_tx
+                .send(MuxActorCommand::RadioRawData {
+                    handle,
+                    data: data.to_vec(),
+                    received: Instant::now(),
+                })
+                .await
+                .unwrap();
         }
-        let _ = amp_rx.recv().await.unwrap();
-
-        // Amp queries TB; - should get TB0;
-        cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
-            })
-            .await
-            .unwrap();
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB0;", "Expected TB0; for VFO A, got: {}", s);
-                break;
+        let mut edges = Vec::new();
+        while edges.len() < 2 {
+            if let MuxEvent::PttLatency { active, .. } = event_rx.recv().await.unwrap() {
+                edges.push(active);
             }
         }
+        assert_eq!(edges, [true, false]);
+
+        let mut sent = Vec::new();
+        while let Ok(data) = amp_rx.try_recv() {
+            sent.push(String::from_utf8_lossy(&data).into_owned());
+        }
+        let position = |frame: &str| sent.iter().position(|s| s == frame).unwrap();
+        assert!(position("FA00021074000;") < position("TX1;"));
+        assert!(position("TX1;") < position("TX0;"));
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_vfo_b_infers_cb1_tb1() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
-
+    async fn test_shadow_amp_mirrors_writes_and_reports_diff() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
         let meta =
             RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
         let (resp_tx, resp_rx) = oneshot::channel();
@@ -1783,10 +9219,10 @@ mod tests {
             .await
             .unwrap();
         let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
 
-        // Connect an amplifier
-        let (amp_channel, _resp_tx, mut amp_rx) =
+        let (amp_channel, _amp_resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        let (shadow_channel, _shadow_resp_tx, mut shadow_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
             .send(MuxActorCommand::ConnectAmplifier {
@@ -1794,200 +9230,257 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Set frequency to make the radio active
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::ConnectShadowAmplifier {
+                channel: shadow_channel,
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
-
-        // Radio reports VFO B selection
+        // Both amps enable auto-info: a matching window
+        for cmd in [
+            MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            },
+            MuxActorCommand::ShadowAmpRawData {
+                data: b"AI2;".to_vec(),
+            },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
         cmd_tx
             .send(MuxActorCommand::RadioResponse {
                 handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::B,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_074_000),
                 },
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
 
-        // Amp queries CB; - should get CB1;
+        // The shadow gets exactly what the primary gets
+        let to_amp = amp_rx.recv().await.unwrap();
+        assert_eq!(shadow_rx.recv().await.unwrap(), to_amp);
+
+        // The two amps react differently
         cmd_tx
             .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+                data: b"FA;".to_vec(),
             })
             .await
             .unwrap();
-
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB1;", "Expected CB1; for VFO B, got: {}", s);
-                break;
-            }
-        }
-        let _ = amp_rx.recv().await.unwrap();
-
-        // Amp queries TB; - should get TB1;
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
+            .send(MuxActorCommand::ShadowAmpRawData {
+                data: b"PS;".to_vec(),
             })
             .await
             .unwrap();
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB1;", "Expected TB1; for VFO B, got: {}", s);
-                break;
+        // Each query is answered only to the amp that asked
+        assert_eq!(amp_rx.recv().await.unwrap(), b"FA00007074000;");
+        assert!(shadow_rx.try_recv().is_err());
+
+        let (stats, diff) = loop {
+            let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+                .await
+                .expect("comparison should close after settling")
+                .unwrap();
+            if let MuxEvent::ShadowAmpCompared { stats, diff } = event {
+                if diff.is_some() {
+                    break (stats, diff);
+                }
             }
-        }
+        };
+        assert_eq!(
+            stats,
+            ShadowStats {
+                windows: 2,
+                mismatches: 1
+            }
+        );
+        let diff = diff.unwrap();
+        assert_eq!(diff.stimulus, vec![to_amp]);
+        assert_eq!(diff.primary_only, vec![b"FA;".to_vec()]);
+        assert_eq!(diff.shadow_only, vec![b"PS;".to_vec()]);
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_vfo_a_then_split_infers_cb0_tb1() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
-
+    async fn test_amp_failover_holds_until_acknowledged() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
-        let (resp_tx, resp_rx) = oneshot::channel();
-        cmd_tx
-            .send(MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: None,
-            })
-            .await
-            .unwrap();
-        let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
-
-        // Connect an amplifier
-        let (amp_channel, _resp_tx, mut amp_rx) =
+        let (amp_channel, _amp_resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
-        cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
+        let (backup_channel, _backup_resp_tx, mut backup_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        for cmd in [
+            MuxActorCommand::ConnectAmplifier {
                 channel: amp_channel,
-            })
-            .await
-            .unwrap();
-        let _ = event_rx.recv().await;
+            },
+            MuxActorCommand::ConnectBackupAmplifier {
+                channel: backup_channel,
+            },
+            MuxActorCommand::SetAmpFailover { enabled: true },
+            MuxActorCommand::AmpFailed {
+                role: AmpRole::Primary,
+                reason: "Read error".to_string(),
+            },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
 
-        // Set frequency to make the radio active
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+                .await
+                .expect("failover should be reported")
+                .unwrap();
+            if let MuxEvent::AmpFailedOver { from, to, reason } = event {
+                assert_eq!((from, to), (AmpRole::Primary, AmpRole::Backup));
+                assert_eq!(reason, "Read error");
+                break;
+            }
+        }
+        // The failed primary was unkeyed on its way out
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
+
+        // The backup answers; the failed primary is no longer listened to
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::AmpRawData {
+                data: b"ID;".to_vec(),
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
-
-        // Radio reports VFO A selection first
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::A,
-                },
+            .send(MuxActorCommand::BackupAmpRawData {
+                data: b"ID;".to_vec(),
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert_eq!(backup_rx.recv().await.unwrap(), b"ID022;");
+        assert!(amp_rx.try_recv().is_err());
 
-        // Then radio enables split mode
+        // Only an acknowledgment puts the primary back
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::Split,
-                },
-            })
+            .send(MuxActorCommand::AcknowledgeAmpFailover)
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Amp queries CB; - should get CB0; (RX on A)
+        loop {
+            let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+                .await
+                .expect("acknowledgment should be reported")
+                .unwrap();
+            if matches!(event, MuxEvent::AmpFailoverAcknowledged) {
+                break;
+            }
+        }
         cmd_tx
             .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+                data: b"ID;".to_vec(),
             })
             .await
             .unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"ID022;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_kpa_fault_polled_and_failed_amp_stood_down() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+
+        let (amp_channel, _amp_resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        let (backup_channel, _backup_resp_tx, _backup_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        for cmd in [
+            MuxActorCommand::SetAmpBypassConfig {
+                config: AmpBypassConfig {
+                    personality: AmpPersonality::Kpa500,
+                    ..AmpBypassConfig::default()
+                },
+            },
+            MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            },
+            MuxActorCommand::ConnectBackupAmplifier {
+                channel: backup_channel,
+            },
+            MuxActorCommand::SetAmpFailover { enabled: true },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
 
         loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB0;", "Expected CB0; for VFO A + Split, got: {}", s);
+            let data = tokio::time::timeout(Duration::from_secs(2), amp_rx.recv())
+                .await
+                .expect("the KPA should be asked for faults")
+                .unwrap();
+            if data == KPA_FAULT_QUERY {
                 break;
             }
         }
-        let _ = amp_rx.recv().await.unwrap();
-
-        // Amp queries TB; - should get TB1; (TX on B, opposite of A)
-        cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
-            })
-            .await
-            .unwrap();
 
+        // The reply arrives split across reads
+        for data in [&b"^FL"[..], b"04;"] {
+            cmd_tx
+                .send(MuxActorCommand::AmpRawData {
+                    data: data.to_vec(),
+                })
+                .await
+                .unwrap();
+        }
         loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB1;", "Expected TB1; for VFO A + Split, got: {}", s);
+            let event = tokio::time::timeout(Duration::from_secs(2), event_rx.recv())
+                .await
+                .expect("failover should be reported")
+                .unwrap();
+            if let MuxEvent::AmpFailedOver { from, to, reason } = event {
+                assert_eq!((from, to), (AmpRole::Primary, AmpRole::Backup));
+                assert_eq!(reason, "Fault code 4");
                 break;
             }
         }
 
+        // Unkeyed and put in standby while its port was still open
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
+        assert_eq!(amp_rx.recv().await.unwrap(), b"^OS0;");
+
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_vfo_b_then_split_infers_cb1_tb0() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
-
+    async fn test_amp_told_busy_while_switch_held_off() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
-        let (resp_tx, resp_rx) = oneshot::channel();
-        cmd_tx
-            .send(MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: None,
-            })
-            .await
-            .unwrap();
-        let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
+        let mut handles = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (run, mult) = (handles[0], handles[1]);
 
-        // Connect an amplifier
         let (amp_channel, _resp_tx, mut amp_rx) =
             create_virtual_amp_channel(Protocol::Kenwood, None, 16);
         cmd_tx
@@ -1996,195 +9489,368 @@ mod tests {
             })
             .await
             .unwrap();
-        let _ = event_rx.recv().await;
-
-        // Set frequency to make the radio active
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::SetTransitionPolicy {
+                policy: TransitionPolicy::Busy,
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
 
-        // Radio reports VFO B selection first
-        cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::B,
-                },
-            })
-            .await
-            .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        // Switching to the mult radio arms the lockout; switching straight
+        // back is held off
+        for handle in [mult, run] {
+            cmd_tx
+                .send(MuxActorCommand::SetActiveRadio { handle })
+                .await
+                .unwrap();
+        }
+        loop {
+            if let MuxEvent::SwitchingBlocked { requested, .. } = event_rx.recv().await.unwrap() {
+                assert_eq!(requested, run);
+                break;
+            }
+        }
 
-        // Then radio enables split mode
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::Split,
-                },
-            })
+            .send(MuxActorCommand::QueryAmpState { response: resp_tx })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        assert!(resp_rx.await.unwrap().transitional);
 
-        // Amp queries CB; - should get CB1; (RX on B)
         cmd_tx
             .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
+                data: b"FA;".to_vec(),
             })
             .await
             .unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"?;");
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_key_up_during_hold_sends_held_frequency_first() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
+        let mut handles = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (run, mult) = (handles[0], handles[1]);
+
+        let (amp_channel, _resp_tx, mut amp_rx) =
+            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
+        for cmd in [
+            MuxActorCommand::ConnectAmplifier {
+                channel: amp_channel,
+            },
+            MuxActorCommand::AmpRawData {
+                data: b"AI2;".to_vec(),
+            },
+            MuxActorCommand::SetTransitionPolicy {
+                policy: TransitionPolicy::HoldUpdates,
+            },
+            MuxActorCommand::SetActiveRadio { handle: mult },
+            MuxActorCommand::SetActiveRadio { handle: run },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
         loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB1;", "Expected CB1; for VFO B + Split, got: {}", s);
+            if let MuxEvent::SwitchingBlocked { .. } = event_rx.recv().await.unwrap() {
                 break;
             }
         }
-        let _ = amp_rx.recv().await.unwrap();
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        while amp_rx.try_recv().is_ok() {}
 
-        // Amp queries TB; - should get TB0; (TX on A, opposite of B)
+        // The mult radio's retune is held while the switch back is held off
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
+            .send(MuxActorCommand::RadioResponse {
+                handle: mult,
+                response: RadioResponse::Frequency {
+                    hz: Frequency::from_hz(7_074_000),
+                },
             })
             .await
             .unwrap();
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(amp_rx.try_recv().is_err());
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB0;", "Expected TB0; for VFO B + Split, got: {}", s);
-                break;
-            }
-        }
+        // Keying up doesn't leave it behind
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse {
+                handle: mult,
+                response: RadioResponse::Ptt { active: true },
+            })
+            .await
+            .unwrap();
+        assert_eq!(amp_rx.recv().await.unwrap(), b"FA00007074000;TX1;");
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
     }
 
     #[tokio::test]
-    async fn test_split_then_vfo_a_clears_split() {
-        let (cmd_tx, cmd_rx) = mpsc::channel(16);
-        let (event_tx, mut event_rx) = mpsc::channel(16);
-
+    async fn test_switch_during_tx_waits_for_confirmation() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
         let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
 
-        // Register a radio
-        let meta =
-            RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood);
-        let (resp_tx, resp_rx) = oneshot::channel();
-        cmd_tx
-            .send(MuxActorCommand::RegisterRadio {
-                meta,
-                response: resp_tx,
-                cmd_tx: None,
-            })
-            .await
-            .unwrap();
-        let handle = resp_rx.await.unwrap();
-        let _ = event_rx.recv().await;
+        let mut handles = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: None,
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+        }
+        let (run, mult) = (handles[0], handles[1]);
+
+        for cmd in [
+            MuxActorCommand::SetActiveRadio { handle: run },
+            MuxActorCommand::SetSwitchConfirmation { enabled: true },
+            MuxActorCommand::RadioResponse {
+                handle: run,
+                response: RadioResponse::Ptt { active: true },
+            },
+            MuxActorCommand::SetActiveRadio { handle: mult },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
 
-        // Connect an amplifier
-        let (amp_channel, _resp_tx, mut amp_rx) =
-            create_virtual_amp_channel(Protocol::Kenwood, None, 16);
-        cmd_tx
-            .send(MuxActorCommand::ConnectAmplifier {
-                channel: amp_channel,
-            })
-            .await
-            .unwrap();
-        let _ = event_rx.recv().await;
+        async fn next_pending(event_rx: &mut mpsc::Receiver<MuxEvent>) -> (u64, RadioHandle) {
+            loop {
+                if let MuxEvent::SwitchPendingConfirmation {
+                    token, requested, ..
+                } = event_rx.recv().await.unwrap()
+                {
+                    return (token, requested);
+                }
+            }
+        }
 
-        // Set frequency to make the radio active
+        // Declining keeps the transmitting radio active
+        let (token, requested) = next_pending(&mut event_rx).await;
+        assert_eq!(requested, mult);
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Frequency { hz: 14_250_000 },
+            .send(MuxActorCommand::ConfirmSwitch {
+                token,
+                confirm: false,
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-        while event_rx.try_recv().is_ok() {}
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::ActiveRadioChanged { to, .. } => {
+                    panic!("switched to radio {} without confirmation", to.0)
+                }
+                MuxEvent::SwitchConfirmationClosed { outcome, .. } => {
+                    assert_eq!(outcome, SwitchConfirmOutcome::Declined);
+                    break;
+                }
+                _ => {}
+            }
+        }
 
-        // Radio selects VFO A, then enables split
+        // Confirming goes ahead with the switch
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::A,
-                },
-            })
+            .send(MuxActorCommand::SetActiveRadio { handle: mult })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
+        let (token, _) = next_pending(&mut event_rx).await;
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::Split,
-                },
+            .send(MuxActorCommand::ConfirmSwitch {
+                token,
+                confirm: true,
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        let mut changed_to = None;
+        loop {
+            match event_rx.recv().await.unwrap() {
+                MuxEvent::ActiveRadioChanged { to, .. } => changed_to = Some(to),
+                MuxEvent::SwitchConfirmationClosed { outcome, .. } => {
+                    assert_eq!(outcome, SwitchConfirmOutcome::Confirmed);
+                    break;
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(changed_to, Some(mult));
 
-        // Now radio selects VFO A again (exits split mode)
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_keyer_speed_carried_to_new_radio() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let mut handles = Vec::new();
+        let mut radio_rxs = Vec::new();
+        for name in ["Run", "Mult"] {
+            let meta = RadioChannelMeta::new_virtual(
+                name.to_string(),
+                "sim".to_string(),
+                Protocol::Kenwood,
+            );
+            let (radio_tx, radio_rx) = mpsc::channel(16);
+            let (resp_tx, resp_rx) = oneshot::channel();
+            cmd_tx
+                .send(MuxActorCommand::RegisterRadio {
+                    meta,
+                    response: resp_tx,
+                    cmd_tx: Some(radio_tx),
+                })
+                .await
+                .unwrap();
+            handles.push(resp_rx.await.unwrap());
+            radio_rxs.push(radio_rx);
+        }
+        let (run, mult) = (handles[0], handles[1]);
+
+        for cmd in [
+            MuxActorCommand::SetActiveRadio { handle: run },
+            MuxActorCommand::SetCarryKeyerSpeed { enabled: true },
+            MuxActorCommand::RadioResponse {
+                handle: run,
+                response: RadioResponse::KeyerSpeed { wpm: 28 },
+            },
+            MuxActorCommand::SetActiveRadio { handle: mult },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+
+        assert_eq!(
+            next_radio_request(&mut radio_rxs[1]).await,
+            RadioRequest::SetKeyerSpeed { wpm: 28 }
+        );
+
+        // After an over the speed is read again, and a change made on the
+        // radio is carried back
+        for active in [true, false] {
+            cmd_tx
+                .send(MuxActorCommand::RadioResponse {
+                    handle: mult,
+                    response: RadioResponse::Ptt { active },
+                })
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            next_radio_request(&mut radio_rxs[1]).await,
+            RadioRequest::GetKeyerSpeed
+        );
         cmd_tx
-            .send(MuxActorCommand::RadioResponse {
-                handle,
-                response: RadioResponse::Vfo {
-                    vfo: cat_protocol::Vfo::A,
-                },
+            .send(MuxActorCommand::RadioRawData {
+                handle: mult,
+                data: b"KS032;".to_vec(),
+                received: Instant::now(),
             })
             .await
             .unwrap();
-        tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
-
-        // Amp queries CB; - should get CB0;
+        // Past the lockout the last switch armed
+        tokio::time::sleep(Duration::from_millis(600)).await;
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"CB;".to_vec(),
-            })
+            .send(MuxActorCommand::SetActiveRadio { handle: run })
             .await
             .unwrap();
+        assert_eq!(
+            next_radio_request(&mut radio_rxs[0]).await,
+            RadioRequest::SetKeyerSpeed { wpm: 32 }
+        );
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "CB0;", "Expected CB0; after exiting split, got: {}", s);
-                break;
-            }
-        }
-        let _ = amp_rx.recv().await.unwrap();
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        actor_handle.await.unwrap();
+    }
 
-        // Amp queries TB; - should get TB0; (split cleared, TX back to A)
+    #[tokio::test]
+    async fn test_band_mode_restored_on_band_change() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(64);
+        let (event_tx, mut event_rx) = mpsc::channel(256);
+        let actor_handle = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+        tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+        let meta =
+            RadioChannelMeta::new_virtual("Run".to_string(), "sim".to_string(), Protocol::Kenwood);
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
+        let (resp_tx, resp_rx) = oneshot::channel();
         cmd_tx
-            .send(MuxActorCommand::AmpRawData {
-                data: b"TB;".to_vec(),
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(radio_tx),
             })
             .await
             .unwrap();
+        let run = resp_rx.await.unwrap();
+
+        let report = |response| MuxActorCommand::RadioResponse {
+            handle: run,
+            response,
+        };
+        for cmd in [
+            MuxActorCommand::SetActiveRadio { handle: run },
+            MuxActorCommand::SetBandModeMemory { enabled: true },
+            // CW on 40m, then USB on 20m
+            report(RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_025_000),
+            }),
+            report(RadioResponse::Mode {
+                mode: OperatingMode::Cw,
+            }),
+            report(RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            }),
+            report(RadioResponse::Mode {
+                mode: OperatingMode::Usb,
+            }),
+            // Back to 40m, still in USB
+            report(RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_030_000),
+            }),
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
 
-        loop {
-            let event = event_rx.recv().await.unwrap();
-            if let MuxEvent::AmpDataOut { data, .. } = event {
-                let s = String::from_utf8_lossy(&data);
-                assert_eq!(s, "TB0;", "Expected TB0; after exiting split, got: {}", s);
-                break;
+        assert_eq!(
+            next_radio_request(&mut radio_rx).await,
+            RadioRequest::SetMode {
+                mode: OperatingMode::Cw
             }
-        }
+        );
 
         cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
         actor_handle.await.unwrap();
@@ -0,0 +1,327 @@
+//! Frequency-dependent amplifier bypass
+//!
+//! Puts the amplifier in standby when the active radio tunes outside the
+//! amp's coverage (below 160m, above 6m, or whatever range is configured) and
+//! back to operate once it returns. How that is done depends on the amp's
+//! personality:
+//!
+//! - **KPA500**: the native `^OS0;` / `^OS1;` standby/operate commands
+//! - **Generic**: the mux stops forwarding PTT to the amp while out of range,
+//!   so it is never keyed and RF passes through the bypass relay
+//!
+//! To avoid chattering when parked on a band edge, the amp only returns to
+//! operate once the frequency is `hysteresis_hz` inside the range. The
+//! hysteresis is bounded: it never covers more than a quarter of the range,
+//! and a radio that stays in range but within it for [`HYSTERESIS_HOLD`]
+//! puts the amp back in operate anyway.
+//!
+//! Some amps can also show the exciter's S-meter if they get answers to their
+//! meter polls. With `forward_meters` on and a personality that displays
+//! meters, those polls are answered from the active radio's latest readings.
+
+use std::time::Duration;
+
+use cat_protocol::Frequency;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// Longest the amp stays bypassed while the radio sits in range but within
+/// the hysteresis band
+pub const HYSTERESIS_HOLD: Duration = Duration::from_secs(2);
+
+/// How the amplifier is told to get out of the way
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AmpPersonality {
+    /// No native bypass command; inhibit PTT to the amp instead
+    #[default]
+    Generic,
+    /// Elecraft KPA500 (`^OS` operate/standby)
+    Kpa500,
+    /// Elecraft KPA1500 (`^OS` operate/standby, shows the exciter's S-meter)
+    Kpa1500,
+}
+
+impl AmpPersonality {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            AmpPersonality::Generic => "Generic (PTT inhibit)",
+            AmpPersonality::Kpa500 => "Elecraft KPA500",
+            AmpPersonality::Kpa1500 => "Elecraft KPA1500",
+        }
+    }
+
+    /// Every personality, for pickers
+    pub fn all() -> [AmpPersonality; 3] {
+        [
+            AmpPersonality::Generic,
+            AmpPersonality::Kpa500,
+            AmpPersonality::Kpa1500,
+        ]
+    }
+
+    /// Whether the amp shows the radio's meters when its meter polls are answered
+    pub fn displays_meters(&self) -> bool {
+        matches!(self, AmpPersonality::Kpa1500)
+    }
+
+    /// Native command for operate (`true`) or standby (`false`), if the amp has one
+    pub fn operate_command(&self, operate: bool) -> Option<Vec<u8>> {
+        match self {
+            AmpPersonality::Generic => None,
+            AmpPersonality::Kpa500 | AmpPersonality::Kpa1500 => {
+                Some(format!("^OS{};", if operate { 1 } else { 0 }).into_bytes())
+            }
+        }
+    }
+}
+
+/// Bypass configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmpBypassConfig {
+    /// Whether out-of-range bypass is active at all
+    pub enabled: bool,
+    /// How to put the amp in bypass
+    pub personality: AmpPersonality,
+    /// Lowest frequency the amp covers
+    pub min_hz: Frequency,
+    /// Highest frequency the amp covers
+    pub max_hz: Frequency,
+    /// How far inside the range the radio must be before the amp operates again (Hz)
+    pub hysteresis_hz: u64,
+    /// Answer the amp's meter polls with the active radio's readings
+    #[serde(default)]
+    pub forward_meters: bool,
+}
+
+impl Default for AmpBypassConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            personality: AmpPersonality::Generic,
+            min_hz: Frequency::from_hz(1_800_000),
+            max_hz: Frequency::from_hz(54_000_000),
+            hysteresis_hz: 10_000,
+            forward_meters: false,
+        }
+    }
+}
+
+impl AmpBypassConfig {
+    /// Whether meter polls from the amp get answered
+    pub fn forwards_meters(&self) -> bool {
+        self.forward_meters && self.personality.displays_meters()
+    }
+
+    /// Hysteresis actually applied, at most a quarter of the range
+    ///
+    /// A wider one could leave no frequency that ends bypass.
+    pub fn effective_hysteresis_hz(&self) -> u64 {
+        let span = self.max_hz.hz().saturating_sub(self.min_hz.hz());
+        self.hysteresis_hz.min(span / 4)
+    }
+
+    fn in_range(&self, hz: Frequency) -> bool {
+        hz >= self.min_hz && hz <= self.max_hz
+    }
+}
+
+/// Tracks whether the amp should be in bypass for the active radio's frequency
+#[derive(Debug, Default)]
+pub struct AmpBypass {
+    config: AmpBypassConfig,
+    bypassed: bool,
+    /// When a bypass held only by the hysteresis ends
+    release_at: Option<Instant>,
+}
+
+impl AmpBypass {
+    /// Create a disabled controller
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> AmpBypassConfig {
+        self.config
+    }
+
+    /// Change configuration
+    ///
+    /// Returns `Some(false)` if the amp was bypassed and bypass is now
+    /// disabled, so the caller can put it back in operate.
+    pub fn set_config(&mut self, config: AmpBypassConfig) -> Option<bool> {
+        self.config = config;
+        if !config.enabled && self.bypassed {
+            self.bypassed = false;
+            self.release_at = None;
+            return Some(false);
+        }
+        None
+    }
+
+    /// Whether the amp is currently held in bypass
+    pub fn is_bypassed(&self) -> bool {
+        self.bypassed
+    }
+
+    /// Whether PTT to the amp must be withheld
+    pub fn inhibits_ptt(&self) -> bool {
+        self.bypassed && self.config.personality == AmpPersonality::Generic
+    }
+
+    /// Feed the active radio's frequency; returns the new bypass state on a change
+    pub fn on_frequency(&mut self, hz: Frequency, now: Instant) -> Option<bool> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let c = &self.config;
+        let hysteresis = i64::try_from(c.effective_hysteresis_hz()).unwrap_or(i64::MAX);
+        let bypass = if self.bypassed {
+            // Stay bypassed until comfortably back inside the range
+            hz < c.min_hz.saturating_offset(hysteresis)
+                || hz > c.max_hz.saturating_offset(-hysteresis)
+        } else {
+            !c.in_range(hz)
+        };
+
+        // In range but inside the hysteresis band: only for so long
+        if bypass && c.in_range(hz) {
+            self.release_at.get_or_insert(now + HYSTERESIS_HOLD);
+        } else {
+            self.release_at = None;
+        }
+
+        if bypass == self.bypassed {
+            return None;
+        }
+        self.bypassed = bypass;
+        Some(bypass)
+    }
+
+    /// When [`Self::poll`] should next be called
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.release_at
+    }
+
+    /// End a bypass the hysteresis has held for [`HYSTERESIS_HOLD`];
+    /// returns `Some(false)` when it does
+    pub fn poll(&mut self, now: Instant) -> Option<bool> {
+        if self.release_at.is_none_or(|at| now < at) {
+            return None;
+        }
+        self.release_at = None;
+        self.bypassed = false;
+        Some(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bypass(personality: AmpPersonality) -> AmpBypass {
+        let mut b = AmpBypass::new();
+        b.set_config(AmpBypassConfig {
+            enabled: true,
+            personality,
+            ..AmpBypassConfig::default()
+        });
+        b
+    }
+
+    #[test]
+    fn test_bypass_with_hysteresis() {
+        let t0 = Instant::now();
+        let mut b = bypass(AmpPersonality::Kpa500);
+
+        assert_eq!(b.on_frequency(Frequency::from_hz(14_074_000), t0), None);
+        assert_eq!(
+            b.on_frequency(Frequency::from_hz(1_799_000), t0),
+            Some(true)
+        );
+        // Back in range but within the hysteresis band
+        assert_eq!(b.on_frequency(Frequency::from_hz(1_805_000), t0), None);
+        assert!(b.is_bypassed());
+        assert_eq!(
+            b.on_frequency(Frequency::from_hz(1_815_000), t0),
+            Some(false)
+        );
+
+        assert_eq!(
+            b.on_frequency(Frequency::from_hz(144_200_000), t0),
+            Some(true)
+        );
+        assert!(!b.inhibits_ptt());
+    }
+
+    #[test]
+    fn test_hysteresis_is_bounded() {
+        let t0 = Instant::now();
+        let mut b = bypass(AmpPersonality::Kpa500);
+        b.on_frequency(Frequency::from_hz(1_799_000), t0);
+
+        // Parked just inside the edge: operate again after the hold
+        assert_eq!(b.on_frequency(Frequency::from_hz(1_805_000), t0), None);
+        assert_eq!(b.next_deadline(), Some(t0 + HYSTERESIS_HOLD));
+        assert_eq!(b.poll(t0 + HYSTERESIS_HOLD / 2), None);
+        // Going back out of range cancels the hold
+        b.on_frequency(Frequency::from_hz(1_799_500), t0 + HYSTERESIS_HOLD / 2);
+        assert_eq!(b.next_deadline(), None);
+        b.on_frequency(Frequency::from_hz(1_805_000), t0 + HYSTERESIS_HOLD);
+        assert_eq!(b.poll(t0 + HYSTERESIS_HOLD * 2), Some(false));
+        assert!(!b.is_bypassed());
+
+        // A hysteresis wider than the range is cut to a quarter of it
+        let config = AmpBypassConfig {
+            enabled: true,
+            min_hz: Frequency::from_hz(1_800_000),
+            max_hz: Frequency::from_hz(2_000_000),
+            hysteresis_hz: 1_000_000,
+            ..AmpBypassConfig::default()
+        };
+        assert_eq!(config.effective_hysteresis_hz(), 50_000);
+        let mut b = AmpBypass::new();
+        b.set_config(config);
+        b.on_frequency(Frequency::from_hz(1_700_000), t0);
+        assert_eq!(
+            b.on_frequency(Frequency::from_hz(1_900_000), t0),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn test_generic_inhibits_ptt_and_disable_restores() {
+        let t0 = Instant::now();
+        let mut b = bypass(AmpPersonality::Generic);
+        b.on_frequency(Frequency::from_hz(475_000), t0);
+        assert!(b.inhibits_ptt());
+
+        assert_eq!(b.set_config(AmpBypassConfig::default()), Some(false));
+        assert!(!b.inhibits_ptt());
+        assert_eq!(b.on_frequency(Frequency::from_hz(475_000), t0), None);
+    }
+
+    #[test]
+    fn test_kpa500_commands() {
+        assert_eq!(
+            AmpPersonality::Kpa500.operate_command(false),
+            Some(b"^OS0;".to_vec())
+        );
+        assert_eq!(AmpPersonality::Generic.operate_command(true), None);
+    }
+
+    #[test]
+    fn test_meter_forwarding_gated_by_personality() {
+        let config = |personality, forward_meters| AmpBypassConfig {
+            personality,
+            forward_meters,
+            ..AmpBypassConfig::default()
+        };
+        assert!(config(AmpPersonality::Kpa1500, true).forwards_meters());
+        assert!(!config(AmpPersonality::Kpa1500, false).forwards_meters());
+        assert!(!config(AmpPersonality::Kpa500, true).forwards_meters());
+        assert!(!config(AmpPersonality::Generic, true).forwards_meters());
+    }
+}
@@ -0,0 +1,183 @@
+//! Redundant amplifier failover
+//!
+//! A backup amplifier can stand by next to the primary. With failover on,
+//! translation output moves to the backup when the amp in service fails:
+//! its port errors out, or it reports a fault (an Elecraft KPA answering
+//! `^FL` with a non-zero code). With no backup ready, or when the backup
+//! fails too, output goes to bypass: nothing is sent to any amp, so it is
+//! never keyed and RF passes through its bypass relay.
+//!
+//! A KPA only reports a fault when asked, so while failover is on the amp in
+//! service is polled with [`KPA_FAULT_QUERY`] every
+//! [`KPA_FAULT_POLL_INTERVAL`]. An amp leaving service is first told to
+//! unkey and, if its personality can, to go to standby, while its port is
+//! still open.
+//!
+//! Switching back is never automatic. A primary that faults, recovers and
+//! faults again would otherwise flap the station between amps; the operator
+//! acknowledges the failover once the primary has been looked at.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Asks an Elecraft KPA for its fault code
+pub const KPA_FAULT_QUERY: &[u8] = b"^FL;";
+
+/// How often the amp in service is asked for its fault code
+pub const KPA_FAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Which amplifier gets translation output
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AmpRole {
+    /// The amplifier normally in service
+    #[default]
+    Primary,
+    /// The standby amplifier
+    Backup,
+    /// Neither: every amp is bypassed
+    Bypass,
+}
+
+impl AmpRole {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            AmpRole::Primary => "primary amplifier",
+            AmpRole::Backup => "backup amplifier",
+            AmpRole::Bypass => "bypass",
+        }
+    }
+}
+
+/// Where translation output goes, and why it left the primary
+#[derive(Debug, Clone, Default)]
+pub struct AmpFailover {
+    enabled: bool,
+    role: AmpRole,
+    reason: Option<String>,
+}
+
+impl AmpFailover {
+    /// Create with failover off
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether failing amps are switched away from
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn failover on or off (an amp already failed over from stays out of
+    /// service until acknowledged)
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// The amp in service
+    pub fn role(&self) -> AmpRole {
+        self.role
+    }
+
+    /// Why output left the primary, while it has
+    pub fn reason(&self) -> Option<&str> {
+        self.reason.as_deref()
+    }
+
+    /// The amp in role `failed` failed; returns where output goes now, if
+    /// it moves
+    ///
+    /// Only the amp in service moves output: a standby backup failing is
+    /// just reported. Once output has left the primary, a failing backup
+    /// always goes to bypass, even if failover has since been turned off.
+    pub fn fail(&mut self, failed: AmpRole, reason: &str, backup_ready: bool) -> Option<AmpRole> {
+        if failed != self.role {
+            return None;
+        }
+        let next = match self.role {
+            AmpRole::Primary if !self.enabled => return None,
+            AmpRole::Primary if backup_ready => AmpRole::Backup,
+            AmpRole::Primary | AmpRole::Backup => AmpRole::Bypass,
+            AmpRole::Bypass => return None,
+        };
+        self.role = next;
+        // The first failure is the one the operator needs to see
+        self.reason.get_or_insert_with(|| reason.to_string());
+        Some(next)
+    }
+
+    /// The operator acknowledged the failover; returns the role output left,
+    /// or None if it is already on the primary
+    pub fn acknowledge(&mut self) -> Option<AmpRole> {
+        if self.role == AmpRole::Primary {
+            return None;
+        }
+        self.reason = None;
+        Some(std::mem::replace(&mut self.role, AmpRole::Primary))
+    }
+
+    /// Back on the primary without an acknowledgment (amps disconnected)
+    pub fn reset(&mut self) {
+        self.role = AmpRole::Primary;
+        self.reason = None;
+    }
+}
+
+/// The fault code in an Elecraft KPA `^FLnn;` frame, if it reports one
+/// (`^FL00;` means no fault)
+pub fn kpa_fault(data: &[u8]) -> Option<u8> {
+    let start = data.windows(3).position(|w| w == b"^FL")? + 3;
+    let rest = &data[start..];
+    let end = rest.iter().position(|&b| b == b';')?;
+    let code: u8 = std::str::from_utf8(&rest[..end]).ok()?.parse().ok()?;
+    (code != 0).then_some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failover_needs_acknowledgment() {
+        let mut failover = AmpFailover::new();
+        // Off: failures are only reported
+        assert_eq!(failover.fail(AmpRole::Primary, "port gone", true), None);
+
+        failover.set_enabled(true);
+        // A standby backup failing doesn't move output
+        assert_eq!(failover.fail(AmpRole::Backup, "port gone", true), None);
+        assert_eq!(
+            failover.fail(AmpRole::Primary, "fault 4", true),
+            Some(AmpRole::Backup)
+        );
+        // The primary failing again changes nothing
+        assert_eq!(failover.fail(AmpRole::Primary, "fault 4", true), None);
+        assert_eq!(
+            failover.fail(AmpRole::Backup, "read error", true),
+            Some(AmpRole::Bypass)
+        );
+        assert_eq!(failover.fail(AmpRole::Bypass, "read error", true), None);
+        assert_eq!(failover.reason(), Some("fault 4"));
+
+        assert_eq!(failover.acknowledge(), Some(AmpRole::Bypass));
+        assert_eq!(failover.role(), AmpRole::Primary);
+        assert_eq!(failover.reason(), None);
+        assert_eq!(failover.acknowledge(), None);
+
+        // No backup ready: straight to bypass
+        assert_eq!(
+            failover.fail(AmpRole::Primary, "port gone", false),
+            Some(AmpRole::Bypass)
+        );
+    }
+
+    #[test]
+    fn test_kpa_fault() {
+        assert_eq!(kpa_fault(b"^FL04;"), Some(4));
+        assert_eq!(kpa_fault(b"FA00014074000;^FL12;"), Some(12));
+        assert_eq!(kpa_fault(b"^FL00;"), None);
+        assert_eq!(kpa_fault(b"^FL"), None);
+        assert_eq!(kpa_fault(b"FA00014074000;"), None);
+    }
+}
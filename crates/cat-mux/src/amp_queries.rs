@@ -0,0 +1,343 @@
+//! Amplifier query profiling
+//!
+//! Amplifiers differ a lot in how they follow the radio: some enable
+//! auto-info and wait, others poll `FA;` several times a second on top of
+//! auto-info. An [`AmpQueryTracker`] learns which queries the connected amp
+//! issues and how often. Queries are always answered straight from the
+//! mux's cached state; when the amp polls a value aggressively it will pick
+//! up the latest one on its next poll anyway, so unsolicited updates of that
+//! value are rate-limited to keep slow amp links from overflowing. A value
+//! held back that way and not yet polled goes out ahead of the next key-up
+//! (see [`AmpQueryTracker::take_withheld`]).
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use cat_protocol::{RadioRequest, RadioResponse};
+use tokio::time::Instant;
+
+use crate::translation::AmpUpdate;
+
+/// Window over which query rates are measured
+pub const QUERY_RATE_WINDOW: Duration = Duration::from_secs(5);
+
+/// Poll rate at which the amp counts as polling a value aggressively
+pub const AGGRESSIVE_POLL_HZ: f32 = 2.0;
+
+/// Shortest gap between unsolicited updates of an aggressively polled value
+pub const POLLED_UPDATE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Most queries kept for rate measurement
+const MAX_RECENT: usize = 1024;
+
+/// Kind of query an amplifier can send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AmpQueryKind {
+    Frequency,
+    Mode,
+    Ptt,
+    Vfo,
+    Id,
+    Status,
+    TxPower,
+    AutoInfo,
+    ControlBand,
+    TransmitBand,
+    Meter,
+}
+
+impl AmpQueryKind {
+    /// Kind of a query request (None for set commands)
+    pub fn of(request: &RadioRequest) -> Option<Self> {
+        match request {
+            RadioRequest::GetFrequency => Some(Self::Frequency),
+            RadioRequest::GetMode => Some(Self::Mode),
+            RadioRequest::GetPtt => Some(Self::Ptt),
+            RadioRequest::GetVfo => Some(Self::Vfo),
+            RadioRequest::GetId => Some(Self::Id),
+            RadioRequest::GetStatus => Some(Self::Status),
+            RadioRequest::GetTxPower => Some(Self::TxPower),
+            RadioRequest::GetAutoInfo => Some(Self::AutoInfo),
+            RadioRequest::GetControlBand => Some(Self::ControlBand),
+            RadioRequest::GetTransmitBand => Some(Self::TransmitBand),
+            RadioRequest::GetMeter { .. } => Some(Self::Meter),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Frequency => "Frequency",
+            Self::Mode => "Mode",
+            Self::Ptt => "PTT",
+            Self::Vfo => "VFO",
+            Self::Id => "ID",
+            Self::Status => "Status",
+            Self::TxPower => "TX power",
+            Self::AutoInfo => "Auto-info",
+            Self::ControlBand => "Control band",
+            Self::TransmitBand => "Transmit band",
+            Self::Meter => "Meter",
+        }
+    }
+}
+
+/// How often the amp has issued one kind of query
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmpQueryRate {
+    pub kind: AmpQueryKind,
+    /// Queries of this kind since the amp connected
+    pub count: u64,
+    /// Recent rate in queries per second
+    pub rate_hz: f32,
+}
+
+/// Snapshot of the learned query profile
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AmpQueryProfile {
+    /// Query kinds seen, most frequent first
+    pub queries: Vec<AmpQueryRate>,
+    /// Unsolicited updates skipped because the amp polls the value itself
+    pub limited_updates: u64,
+}
+
+impl AmpQueryProfile {
+    /// Whether the amp polls any value aggressively
+    pub fn is_aggressive(&self) -> bool {
+        self.queries.iter().any(|q| q.rate_hz >= AGGRESSIVE_POLL_HZ)
+    }
+
+    /// Recent rate of all queries together
+    pub fn total_rate_hz(&self) -> f32 {
+        self.queries.iter().map(|q| q.rate_hz).sum()
+    }
+}
+
+/// Learns the connected amplifier's query pattern
+#[derive(Debug, Default)]
+pub struct AmpQueryTracker {
+    counts: HashMap<AmpQueryKind, u64>,
+    recent: VecDeque<(Instant, AmpQueryKind)>,
+    last_update: HashMap<AmpQueryKind, Instant>,
+    limited_updates: u64,
+    /// Latest values held back and not polled since
+    withheld: AmpUpdate,
+}
+
+impl AmpQueryTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a query from the amp
+    pub fn record(&mut self, kind: AmpQueryKind, now: Instant) {
+        // The reply carries the latest value
+        match kind {
+            AmpQueryKind::Frequency => self.withheld.frequency_hz = None,
+            AmpQueryKind::Mode => self.withheld.mode = None,
+            AmpQueryKind::Status => {
+                self.withheld.frequency_hz = None;
+                self.withheld.mode = None;
+            }
+            _ => {}
+        }
+        *self.counts.entry(kind).or_default() += 1;
+        self.recent.push_back((now, kind));
+        if self.recent.len() > MAX_RECENT {
+            self.recent.pop_front();
+        }
+        self.prune(now);
+    }
+
+    /// Recent rate of one kind of query, in queries per second
+    pub fn rate_hz(&self, kind: AmpQueryKind, now: Instant) -> f32 {
+        let n = self
+            .recent
+            .iter()
+            .filter(|(t, k)| *k == kind && now.duration_since(*t) < QUERY_RATE_WINDOW)
+            .count();
+        n as f32 / QUERY_RATE_WINDOW.as_secs_f32()
+    }
+
+    /// Whether the amp polls a value (directly or via status) aggressively
+    pub fn polls(&self, kind: AmpQueryKind, now: Instant) -> bool {
+        self.rate_hz(kind, now) + self.rate_hz(AmpQueryKind::Status, now) >= AGGRESSIVE_POLL_HZ
+    }
+
+    /// Decide whether an unsolicited update should be sent now
+    ///
+    /// PTT always goes out. Frequency and mode updates are limited to one per
+    /// [`POLLED_UPDATE_INTERVAL`] while the amp polls them aggressively.
+    pub fn allow_update(&mut self, response: &RadioResponse, now: Instant) -> bool {
+        if response.ptt().is_some() {
+            return true;
+        }
+        let kinds: Vec<_> = [
+            response.frequency().map(|_| AmpQueryKind::Frequency),
+            response.mode().map(|_| AmpQueryKind::Mode),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let limited = !kinds.is_empty()
+            && kinds.iter().all(|&kind| {
+                self.polls(kind, now)
+                    && self
+                        .last_update
+                        .get(&kind)
+                        .is_some_and(|t| now.duration_since(*t) < POLLED_UPDATE_INTERVAL)
+            });
+        if limited {
+            self.limited_updates += 1;
+            self.withheld.frequency_hz = response.frequency().or(self.withheld.frequency_hz);
+            self.withheld.mode = response.mode().or(self.withheld.mode);
+            return false;
+        }
+        for kind in kinds {
+            self.last_update.insert(kind, now);
+        }
+        if response.frequency().is_some() {
+            self.withheld.frequency_hz = None;
+        }
+        if response.mode().is_some() {
+            self.withheld.mode = None;
+        }
+        true
+    }
+
+    /// Take the frequency and mode held back and not polled since
+    ///
+    /// Sent ahead of a key-up, so the amp never keys on a value it was
+    /// expected to pick up on its next poll.
+    pub fn take_withheld(&mut self) -> AmpUpdate {
+        std::mem::take(&mut self.withheld)
+    }
+
+    /// Snapshot of the profile learned so far
+    pub fn profile(&self, now: Instant) -> AmpQueryProfile {
+        let mut queries: Vec<_> = self
+            .counts
+            .iter()
+            .map(|(&kind, &count)| AmpQueryRate {
+                kind,
+                count,
+                rate_hz: self.rate_hz(kind, now),
+            })
+            .collect();
+        queries.sort_by(|a, b| {
+            b.rate_hz
+                .total_cmp(&a.rate_hz)
+                .then(b.count.cmp(&a.count))
+                .then(a.kind.cmp(&b.kind))
+        });
+        AmpQueryProfile {
+            queries,
+            limited_updates: self.limited_updates,
+        }
+    }
+
+    /// Whether no query has been seen yet
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Forget everything (e.g. a different amp connected)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn prune(&mut self, now: Instant) {
+        while let Some(&(t, _)) = self.recent.front() {
+            if now.duration_since(t) < QUERY_RATE_WINDOW {
+                break;
+            }
+            self.recent.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::Frequency;
+
+    #[test]
+    fn test_profile_orders_by_rate() {
+        let mut tracker = AmpQueryTracker::new();
+        let t0 = Instant::now();
+        for i in 0..20 {
+            tracker.record(AmpQueryKind::Frequency, t0 + Duration::from_millis(i * 200));
+        }
+        tracker.record(AmpQueryKind::Id, t0);
+
+        let now = t0 + Duration::from_secs(4);
+        let profile = tracker.profile(now);
+        assert_eq!(profile.queries[0].kind, AmpQueryKind::Frequency);
+        assert_eq!(profile.queries[0].count, 20);
+        assert_eq!(profile.queries[0].rate_hz, 4.0);
+        assert_eq!(profile.queries[1].kind, AmpQueryKind::Id);
+        assert!(profile.is_aggressive());
+
+        // Rates decay once the amp stops polling
+        let later = t0 + Duration::from_secs(20);
+        assert!(!tracker.profile(later).is_aggressive());
+        assert_eq!(tracker.profile(later).queries[0].count, 20);
+    }
+
+    #[test]
+    fn test_updates_limited_only_for_polled_values() {
+        let mut tracker = AmpQueryTracker::new();
+        let t0 = Instant::now();
+        let freq = RadioResponse::Frequency {
+            hz: Frequency::from_hz(14_074_000),
+        };
+        let mode = RadioResponse::Mode {
+            mode: cat_protocol::OperatingMode::Usb,
+        };
+
+        // Not polled: everything goes out
+        assert!(tracker.allow_update(&freq, t0));
+        assert!(tracker.allow_update(&freq, t0));
+
+        for i in 0..15 {
+            tracker.record(AmpQueryKind::Frequency, t0 + Duration::from_millis(i * 100));
+        }
+        let t1 = t0 + Duration::from_millis(1500);
+        assert!(tracker.allow_update(&freq, t1));
+        assert!(!tracker.allow_update(&freq, t1 + Duration::from_millis(100)));
+        assert!(tracker.allow_update(&freq, t1 + POLLED_UPDATE_INTERVAL));
+        // Mode isn't polled, PTT is never limited
+        assert!(tracker.allow_update(&mode, t1));
+        assert!(tracker.allow_update(&mode, t1));
+        assert!(tracker.allow_update(&RadioResponse::Ptt { active: true }, t1));
+        assert_eq!(tracker.profile(t1).limited_updates, 1);
+    }
+
+    #[test]
+    fn test_withheld_update_kept_until_polled_or_taken() {
+        let mut tracker = AmpQueryTracker::new();
+        let t0 = Instant::now();
+        let tune = |hz| RadioResponse::Frequency {
+            hz: Frequency::from_hz(hz),
+        };
+        for i in 0..15 {
+            tracker.record(AmpQueryKind::Frequency, t0 + Duration::from_millis(i * 100));
+        }
+        let t1 = t0 + Duration::from_millis(1500);
+        assert!(tracker.allow_update(&tune(14_074_000), t1));
+        assert!(!tracker.allow_update(&tune(14_075_000), t1));
+        assert!(!tracker.allow_update(&tune(14_076_000), t1));
+        assert_eq!(
+            tracker.take_withheld().frequency_hz,
+            Some(Frequency::from_hz(14_076_000))
+        );
+        assert!(tracker.take_withheld().is_empty());
+
+        // The amp's own poll picks up the latest value
+        assert!(!tracker.allow_update(&tune(14_077_000), t1));
+        tracker.record(AmpQueryKind::Frequency, t1);
+        assert!(tracker.take_withheld().is_empty());
+    }
+}
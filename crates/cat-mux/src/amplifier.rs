@@ -7,6 +7,9 @@
 use cat_protocol::Protocol;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
+use tokio_serial::FlowControl;
+
+use crate::write_backlog::WriteBacklog;
 
 /// Type of amplifier connection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -30,6 +33,8 @@ pub struct AmplifierChannelMeta {
     pub civ_address: Option<u8>,
     /// Baud rate for serial communication
     pub baud_rate: u32,
+    /// Serial flow control the port was opened with
+    pub flow_control: FlowControl,
 }
 
 impl AmplifierChannelMeta {
@@ -46,9 +51,16 @@ impl AmplifierChannelMeta {
             port_name: Some(port_name),
             civ_address,
             baud_rate,
+            flow_control: FlowControl::None,
         }
     }
 
+    /// Record the flow control the port was opened with
+    pub fn with_flow_control(mut self, flow_control: FlowControl) -> Self {
+        self.flow_control = flow_control;
+        self
+    }
+
     /// Create metadata for a virtual amplifier
     pub fn new_virtual(protocol: Protocol, civ_address: Option<u8>) -> Self {
         Self {
@@ -57,6 +69,7 @@ impl AmplifierChannelMeta {
             port_name: None,
             civ_address,
             baud_rate: 0, // Not used for virtual
+            flow_control: FlowControl::None,
         }
     }
 
@@ -69,7 +82,9 @@ impl AmplifierChannelMeta {
 /// Bidirectional amplifier channel
 ///
 /// The multiplexer sends translated commands to the amplifier through `command_tx`,
-/// and receives responses through `response_rx`.
+/// and receives responses through `response_rx`. Safety frames (PTT-off from an
+/// interlock) go through `urgent_tx` when the connection has one, so they are
+/// written ahead of frequency updates still queued in `command_tx`.
 pub struct AmplifierChannel {
     /// Metadata about this amplifier
     pub meta: AmplifierChannelMeta,
@@ -77,6 +92,10 @@ pub struct AmplifierChannel {
     pub command_tx: mpsc::Sender<Vec<u8>>,
     /// Receiver for responses from the amplifier (amp -> mux)
     pub response_rx: mpsc::Receiver<Vec<u8>>,
+    /// Bytes sent through `command_tx` that the connection has not yet written
+    pub backlog: WriteBacklog,
+    /// Sender for safety frames the connection writes first (mux -> amp)
+    pub urgent_tx: Option<mpsc::Sender<Vec<u8>>>,
 }
 
 impl std::fmt::Debug for AmplifierChannel {
@@ -85,12 +104,17 @@ impl std::fmt::Debug for AmplifierChannel {
             .field("meta", &self.meta)
             .field("command_tx", &"<sender>")
             .field("response_rx", &"<receiver>")
+            .field("backlog", &self.backlog.pending())
+            .field("urgent_tx", &self.urgent_tx.as_ref().map(|_| "<sender>"))
             .finish()
     }
 }
 
 impl AmplifierChannel {
     /// Create a new amplifier channel
+    ///
+    /// Its backlog starts untracked; hand [`Self::backlog`] to the connection
+    /// (see [`crate::AsyncAmpConnection::with_backlog`]) to have writes counted out.
     pub fn new(
         meta: AmplifierChannelMeta,
         command_tx: mpsc::Sender<Vec<u8>>,
@@ -100,8 +124,20 @@ impl AmplifierChannel {
             meta,
             command_tx,
             response_rx,
+            backlog: WriteBacklog::new(),
+            urgent_tx: None,
         }
     }
+
+    /// Send safety frames through a priority queue
+    ///
+    /// The receiving end goes to the connection (see
+    /// [`crate::AsyncAmpConnection::with_urgent`]); without one, safety frames
+    /// queue behind everything else in `command_tx`.
+    pub fn with_urgent(mut self, urgent_tx: mpsc::Sender<Vec<u8>>) -> Self {
+        self.urgent_tx = Some(urgent_tx);
+        self
+    }
 }
 
 #[cfg(test)]
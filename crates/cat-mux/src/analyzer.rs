@@ -0,0 +1,307 @@
+//! Analyzer (monitor-only) mode
+//!
+//! Uses catapult purely as a CAT sniffer: a logger and a radio are connected
+//! through a pair of ports and every byte is relayed unchanged in both
+//! directions. Nothing is switched and no amplifier is driven; the traffic is
+//! only split into frames, decoded and counted.
+//!
+//! ```text
+//! logger <-> [logger port] catapult [radio port] <-> radio
+//! ```
+//!
+//! Bytes are forwarded as soon as they are read, before framing, so the
+//! analyzer never adds more latency than one read.
+
+use std::collections::BTreeMap;
+use std::fmt::Debug;
+use std::time::{Duration, SystemTime};
+
+use cat_protocol::{ControllerSideCodec, Protocol, RadioSideCodec};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+use tracing::{debug, info};
+
+use crate::client::MuxClient;
+use crate::MuxEvent;
+
+/// Which way a frame travelled through the analyzer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum AnalyzerDirection {
+    /// Sent by the logger, relayed to the radio
+    LoggerToRadio,
+    /// Sent by the radio, relayed to the logger
+    RadioToLogger,
+}
+
+/// Frame and byte counters for one direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DirectionStats {
+    /// Complete frames seen
+    pub frames: u64,
+    /// Bytes in those frames
+    pub bytes: u64,
+    /// Frames the protocol codec could not decode
+    pub unknown: u64,
+}
+
+/// Running statistics for an analyzer session
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnalyzerStats {
+    /// Logger -> radio counters
+    pub logger_to_radio: DirectionStats,
+    /// Radio -> logger counters
+    pub radio_to_logger: DirectionStats,
+    /// Frames seen per direction and decoded command name
+    pub commands: BTreeMap<(AnalyzerDirection, String), u64>,
+}
+
+impl AnalyzerStats {
+    /// Count one frame
+    pub fn record(&mut self, direction: AnalyzerDirection, len: usize, command: &str) {
+        let totals = match direction {
+            AnalyzerDirection::LoggerToRadio => &mut self.logger_to_radio,
+            AnalyzerDirection::RadioToLogger => &mut self.radio_to_logger,
+        };
+        totals.frames += 1;
+        totals.bytes += len as u64;
+        if command == UNKNOWN_COMMAND {
+            totals.unknown += 1;
+        }
+        *self
+            .commands
+            .entry((direction, command.to_string()))
+            .or_default() += 1;
+    }
+
+    /// Forget everything counted so far
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Command name recorded for frames the codec could not decode
+pub const UNKNOWN_COMMAND: &str = "Unknown";
+
+/// Name of a decoded request or response (its enum variant)
+fn command_name(decoded: &impl Debug) -> String {
+    let text = format!("{:?}", decoded);
+    text.chars().take_while(|c| c.is_alphanumeric()).collect()
+}
+
+/// Relays traffic between a logger and a radio and reports each frame
+pub struct AnalyzerPassthrough<L, R> {
+    logger: L,
+    radio: R,
+    protocol: Protocol,
+    event_tx: tokio_mpsc::Sender<MuxEvent>,
+}
+
+impl<L, R> AnalyzerPassthrough<L, R>
+where
+    L: AsyncRead + AsyncWrite + Unpin + Send,
+    R: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Create a passthrough for the given protocol
+    pub fn new(logger: L, radio: R, protocol: Protocol, mux: &MuxClient) -> Self {
+        Self {
+            logger,
+            radio,
+            protocol,
+            event_tx: mux.event_sender(),
+        }
+    }
+
+    /// Relay traffic until shutdown or either side fails
+    pub async fn run(mut self, mut shutdown_rx: oneshot::Receiver<()>) {
+        info!("Analyzer passthrough starting ({})", self.protocol.name());
+
+        let mut from_logger = ControllerSideCodec::new(self.protocol);
+        let mut from_radio = RadioSideCodec::new(self.protocol);
+        let mut logger_buf = vec![0u8; 1024];
+        let mut radio_buf = vec![0u8; 1024];
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => break,
+
+                result = read_with_timeout(&mut self.logger, &mut logger_buf) => {
+                    match result {
+                        Ok(0) => {}
+                        Ok(n) => {
+                            let data = &logger_buf[..n];
+                            if let Err(e) = relay(&mut self.radio, data).await {
+                                self.report_error("Radio", e).await;
+                                break;
+                            }
+                            from_logger.push_bytes(data);
+                            self.emit_requests(&mut from_logger).await;
+                        }
+                        Err(e) => {
+                            self.report_error("Logger", e).await;
+                            break;
+                        }
+                    }
+                }
+
+                result = read_with_timeout(&mut self.radio, &mut radio_buf) => {
+                    match result {
+                        Ok(0) => {}
+                        Ok(n) => {
+                            let data = &radio_buf[..n];
+                            if let Err(e) = relay(&mut self.logger, data).await {
+                                self.report_error("Logger", e).await;
+                                break;
+                            }
+                            from_radio.push_bytes(data);
+                            self.emit_responses(&mut from_radio).await;
+                        }
+                        Err(e) => {
+                            self.report_error("Radio", e).await;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        info!("Analyzer passthrough shutting down");
+    }
+
+    /// Report every complete logger frame
+    async fn emit_requests(&self, codec: &mut ControllerSideCodec) {
+        while let Some((request, data)) = codec.next_request_with_bytes() {
+            self.emit(
+                AnalyzerDirection::LoggerToRadio,
+                data,
+                command_name(&request),
+            )
+            .await;
+        }
+    }
+
+    /// Report every complete radio frame
+    async fn emit_responses(&self, codec: &mut RadioSideCodec) {
+        while let Some((response, data)) = codec.next_response_with_bytes() {
+            self.emit(
+                AnalyzerDirection::RadioToLogger,
+                data,
+                command_name(&response),
+            )
+            .await;
+        }
+    }
+
+    async fn emit(&self, direction: AnalyzerDirection, data: Vec<u8>, command: String) {
+        debug!("Analyzer {:?}: {} ({:02X?})", direction, command, data);
+        let _ = self
+            .event_tx
+            .send(MuxEvent::AnalyzerData {
+                direction,
+                data,
+                protocol: self.protocol,
+                command,
+                timestamp: SystemTime::now(),
+            })
+            .await;
+    }
+
+    async fn report_error(&self, side: &str, error: std::io::Error) {
+        let _ = self
+            .event_tx
+            .send(MuxEvent::Error {
+                source: format!("Analyzer {}", side),
+                message: format!("I/O error: {}", error),
+            })
+            .await;
+    }
+}
+
+/// Read with a short timeout so shutdown is noticed on idle ports
+///
+/// Timeouts and `WouldBlock` count as zero bytes read; a closed stream is an error.
+async fn read_with_timeout<T: AsyncRead + Unpin>(
+    io: &mut T,
+    buf: &mut [u8],
+) -> std::io::Result<usize> {
+    match tokio::time::timeout(Duration::from_millis(100), io.read(buf)).await {
+        Ok(Ok(0)) => Err(std::io::ErrorKind::UnexpectedEof.into()),
+        Ok(Ok(n)) => Ok(n),
+        Ok(Err(e))
+            if e.kind() == std::io::ErrorKind::WouldBlock
+                || e.kind() == std::io::ErrorKind::TimedOut =>
+        {
+            Ok(0)
+        }
+        Ok(Err(e)) => Err(e),
+        Err(_) => Ok(0),
+    }
+}
+
+async fn relay<T: AsyncWrite + Unpin>(io: &mut T, data: &[u8]) -> std::io::Result<()> {
+    io.write_all(data).await?;
+    io.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_count_per_direction_and_command() {
+        let mut stats = AnalyzerStats::default();
+        stats.record(AnalyzerDirection::LoggerToRadio, 3, "GetFrequency");
+        stats.record(AnalyzerDirection::LoggerToRadio, 3, "GetFrequency");
+        stats.record(AnalyzerDirection::RadioToLogger, 14, "Frequency");
+        stats.record(AnalyzerDirection::RadioToLogger, 4, UNKNOWN_COMMAND);
+
+        assert_eq!(stats.logger_to_radio.frames, 2);
+        assert_eq!(stats.logger_to_radio.bytes, 6);
+        assert_eq!(stats.radio_to_logger.unknown, 1);
+        assert_eq!(
+            stats.commands[&(AnalyzerDirection::LoggerToRadio, "GetFrequency".into())],
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_passthrough_relays_and_reports_frames() {
+        let (logger_side, mut logger) = tokio::io::duplex(256);
+        let (radio_side, mut radio) = tokio::io::duplex(256);
+        let (mux, _mux_rx, mut event_rx) = MuxClient::channel(16);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        let task = tokio::spawn(
+            AnalyzerPassthrough::new(logger_side, radio_side, Protocol::Kenwood, &mux)
+                .run(shutdown_rx),
+        );
+
+        logger.write_all(b"FA;").await.unwrap();
+        let mut buf = [0u8; 32];
+        let n = radio.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"FA;");
+
+        radio.write_all(b"FA00014074000;").await.unwrap();
+        let n = logger.read(&mut buf).await.unwrap();
+        assert_eq!(&buf[..n], b"FA00014074000;");
+
+        let mut seen = Vec::new();
+        while seen.len() < 2 {
+            if let Some(MuxEvent::AnalyzerData {
+                direction, command, ..
+            }) = event_rx.recv().await
+            {
+                seen.push((direction, command));
+            }
+        }
+        assert_eq!(
+            seen,
+            vec![
+                (AnalyzerDirection::LoggerToRadio, "GetFrequency".to_string()),
+                (AnalyzerDirection::RadioToLogger, "Frequency".to_string()),
+            ]
+        );
+
+        let _ = shutdown_tx.send(());
+        task.await.unwrap();
+    }
+}
@@ -10,6 +10,9 @@ use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{mpsc as tokio_mpsc, oneshot};
 use tracing::{debug, info};
 
+use crate::amp_failover::AmpRole;
+use crate::client::MuxClient;
+use crate::write_backlog::WriteBacklog;
 use crate::{MuxActorCommand, MuxEvent};
 
 /// Async amplifier connection that runs in a spawned task
@@ -20,6 +23,14 @@ pub struct AsyncAmpConnection<T> {
     io: T,
     mux_tx: tokio_mpsc::Sender<MuxActorCommand>,
     event_tx: tokio_mpsc::Sender<MuxEvent>,
+    /// Report reads as shadow amplifier data
+    shadow: bool,
+    /// Report reads and failures as the backup amplifier's
+    backup: bool,
+    /// Count of queued bytes, decremented as they are written
+    backlog: Option<WriteBacklog>,
+    /// Safety frames, written ahead of the regular queue
+    urgent_rx: Option<tokio_mpsc::Receiver<Vec<u8>>>,
 }
 
 impl<T> AsyncAmpConnection<T>
@@ -31,20 +42,79 @@ where
     /// # Arguments
     ///
     /// * `io` - Any type implementing AsyncRead + AsyncWrite (SerialStream, VirtualAmplifier, etc.)
-    /// * `mux_tx` - Channel sender for communicating with the mux actor
-    /// * `event_tx` - Channel sender for emitting MuxEvents (errors)
-    pub fn new(
-        io: T,
-        mux_tx: tokio_mpsc::Sender<MuxActorCommand>,
-        event_tx: tokio_mpsc::Sender<MuxEvent>,
-    ) -> Self {
+    /// * `mux` - Client for the mux actor, which gets the amp's replies and errors
+    pub fn new(io: T, mux: &MuxClient) -> Self {
         Self {
             io,
-            mux_tx,
-            event_tx,
+            mux_tx: mux.command_sender(),
+            event_tx: mux.event_sender(),
+            shadow: false,
+            backup: false,
+            backlog: None,
+            urgent_rx: None,
         }
     }
 
+    /// Run this connection as the shadow amplifier in an A/B comparison
+    pub fn as_shadow(mut self) -> Self {
+        self.shadow = true;
+        self
+    }
+
+    /// Run this connection as the standby amplifier for failover
+    pub fn as_backup(mut self) -> Self {
+        self.backup = true;
+        self
+    }
+
+    /// Count written bytes out of the backlog the mux actor counts them into
+    pub fn with_backlog(mut self, backlog: WriteBacklog) -> Self {
+        self.backlog = Some(backlog);
+        self
+    }
+
+    /// Write frames from a priority queue before any regular ones still waiting
+    pub fn with_urgent(mut self, urgent_rx: tokio_mpsc::Receiver<Vec<u8>>) -> Self {
+        self.urgent_rx = Some(urgent_rx);
+        self
+    }
+
+    fn source(&self) -> &'static str {
+        if self.shadow {
+            "Shadow amplifier"
+        } else if self.backup {
+            "Backup amplifier"
+        } else {
+            "Amplifier"
+        }
+    }
+
+    /// Report an error, and tell the mux the amp failed so it can fail over
+    /// (the shadow only mirrors the primary; it never carries output)
+    async fn report_failure(&self, message: String) {
+        if !self.shadow {
+            let role = if self.backup {
+                AmpRole::Backup
+            } else {
+                AmpRole::Primary
+            };
+            let _ = self
+                .mux_tx
+                .send(MuxActorCommand::AmpFailed {
+                    role,
+                    reason: message.clone(),
+                })
+                .await;
+        }
+        let _ = self
+            .event_tx
+            .send(MuxEvent::Error {
+                source: self.source().to_string(),
+                message,
+            })
+            .await;
+    }
+
     /// Run the amplifier I/O loop
     ///
     /// This handles all async I/O with the amplifier. Returns when shutdown is
@@ -62,25 +132,31 @@ where
         info!("Amplifier connection starting");
 
         let mut buffer = vec![0u8; 256];
+        let mut urgent_rx = self.urgent_rx.take();
 
         loop {
             tokio::select! {
+                // Arms are polled in order, so urgent frames go before queued ones
+                biased;
+
                 // Check for shutdown signal
                 _ = &mut shutdown_rx => {
                     break;
                 }
 
+                // Safety frames (from mux actor)
+                Some(data) = recv_urgent(&mut urgent_rx) => {
+                    let queued = data_rx.len();
+                    if queued > 0 {
+                        debug!("Urgent amp write ahead of {} queued frames", queued);
+                        let _ = self.event_tx.send(MuxEvent::AmpWritePreempted { queued }).await;
+                    }
+                    self.write(&data).await;
+                }
+
                 // Check for data to write (from mux actor)
                 Some(data) = data_rx.recv() => {
-                    debug!("Amp connection writing {} bytes", data.len());
-                    if let Err(e) = self.io.write_all(&data).await {
-                        let _ = self.event_tx.send(MuxEvent::Error {
-                            source: "Amplifier".to_string(),
-                            message: format!("Write error: {}", e),
-                        }).await;
-                    } else {
-                        let _ = self.io.flush().await;
-                    }
+                    self.write(&data).await;
                 }
 
                 // Read from amplifier with timeout
@@ -93,7 +169,14 @@ where
                             let data = buffer[..n].to_vec();
                             debug!("Amp connection received {} bytes", n);
                             // Send raw amp data to mux actor for traffic monitoring
-                            let _ = self.mux_tx.send(MuxActorCommand::AmpRawData { data }).await;
+                            let cmd = if self.shadow {
+                                MuxActorCommand::ShadowAmpRawData { data }
+                            } else if self.backup {
+                                MuxActorCommand::BackupAmpRawData { data }
+                            } else {
+                                MuxActorCommand::AmpRawData { data }
+                            };
+                            let _ = self.mux_tx.send(cmd).await;
                         }
                         Ok(Ok(_)) => {} // 0 bytes
                         Ok(Err(e)) => {
@@ -101,10 +184,7 @@ where
                             if e.kind() != std::io::ErrorKind::WouldBlock
                                 && e.kind() != std::io::ErrorKind::TimedOut
                             {
-                                let _ = self.event_tx.send(MuxEvent::Error {
-                                    source: "Amplifier".to_string(),
-                                    message: format!("Read error: {}", e),
-                                }).await;
+                                self.report_failure(format!("Read error: {}", e)).await;
                                 break;
                             }
                         }
@@ -116,4 +196,65 @@ where
 
         info!("Amplifier connection shutting down");
     }
+
+    /// Write one frame, counting it out of the backlog and reporting failures
+    async fn write(&mut self, data: &[u8]) {
+        debug!("Amp connection writing {} bytes", data.len());
+        let result = self.io.write_all(data).await;
+        if result.is_ok() {
+            let _ = self.io.flush().await;
+        }
+        if let Some(backlog) = &self.backlog {
+            backlog.written(data.len());
+        }
+        if let Err(e) = result {
+            self.report_failure(format!("Write error: {}", e)).await;
+        }
+    }
+}
+
+/// Next urgent frame, or never if there is no urgent queue
+async fn recv_urgent(urgent_rx: &mut Option<tokio_mpsc::Receiver<Vec<u8>>>) -> Option<Vec<u8>> {
+    match urgent_rx {
+        Some(rx) => rx.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_urgent_frames_jump_the_queue() {
+        let (conn_io, mut amp_io) = tokio::io::duplex(256);
+        let (mux, _mux_rx, mut event_rx) = MuxClient::channel(16);
+        let (data_tx, data_rx) = tokio_mpsc::channel(16);
+        let (urgent_tx, urgent_rx) = tokio_mpsc::channel(4);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+        // Queued before the connection starts writing
+        for frame in [b"FA00014074000;", b"FA00014075000;"] {
+            data_tx.send(frame.to_vec()).await.unwrap();
+        }
+        urgent_tx.send(b"TX0;".to_vec()).await.unwrap();
+
+        let conn = AsyncAmpConnection::new(conn_io, &mux).with_urgent(urgent_rx);
+        let task = tokio::spawn(conn.run(shutdown_rx, data_rx));
+
+        let mut written = vec![0u8; 32];
+        let mut got = Vec::new();
+        while got.len() < 4 + 2 * 14 {
+            let n = amp_io.read(&mut written).await.unwrap();
+            got.extend_from_slice(&written[..n]);
+        }
+        assert_eq!(got, b"TX0;FA00014074000;FA00014075000;");
+        match event_rx.recv().await.unwrap() {
+            MuxEvent::AmpWritePreempted { queued } => assert_eq!(queued, 2),
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        shutdown_tx.send(()).unwrap();
+        task.await.unwrap();
+    }
 }
@@ -1,10 +1,12 @@
 //! Async serial I/O handling for radio connections
 //!
-//! This module provides non-blocking async serial communication using tokio_serial.
+//! This module provides non-blocking async serial communication using tokio_serial,
+//! or a raw TCP stream for radios behind a serial server.
 //! Each radio connection runs in its own spawned task, communicating with the
 //! multiplexer via channels.
 //!
-//! Radio commands are sent directly to the multiplexer actor through mux_tx,
+//! Radio commands are sent directly to the multiplexer actor through its
+//! [`MuxClient`],
 //! ensuring that both real and virtual radios use the same code path.
 //!
 //! ## Virtual Radio Support
@@ -16,15 +18,22 @@ use std::io::ErrorKind;
 use std::time::Duration;
 
 use cat_protocol::{
-    elecraft::ElecraftCommand, flex::FlexCommand, icom::CivCommand, kenwood::KenwoodCommand,
-    yaesu::YaesuCommand, yaesu_ascii::YaesuAsciiCommand, EncodeCommand, FromRadioRequest, Protocol,
-    RadioDatabase, RadioRequest,
+    elecraft::ElecraftCommand, encode_model_request_sequence, encode_request_sequence,
+    flex::FlexCommand, icom::CivCommand, kenwood::KenwoodCommand, yaesu::YaesuCommand,
+    yaesu_ascii::YaesuAsciiCommand, EncodeCommand, FromRadioRequest, ModeCode, ModeMap, Protocol,
+    RadioDatabase, RadioModel, RadioRequest, RadioSideCodec,
 };
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
 use tokio::sync::mpsc as tokio_mpsc;
-use tokio_serial::{FlowControl, SerialPortBuilderExt, SerialStream};
+use tokio::time::Instant;
+use tokio_serial::{FlowControl, SerialPort, SerialPortBuilderExt, SerialStream};
 use tracing::{debug, info, warn};
 
+use crate::client::MuxClient;
+use crate::link::SerialLine;
+use crate::radio_profile::{auto_info_level, ProfileQuery, RadioProfile, PROFILE_ATTEMPTS};
+use crate::sync_plan::{Expect, SyncPlan, SyncStep, SyncStepStatus, DEFAULT_STEP_TIMEOUT};
 use crate::{MuxActorCommand, MuxEvent, RadioHandle};
 
 /// Commands that can be sent to an async radio connection task
@@ -34,8 +43,15 @@ pub enum RadioTaskCommand {
     Shutdown,
     /// Send raw data to the radio
     SendData { data: Vec<u8> },
+    /// Encode a request in the radio's protocol and send it in a single write
+    SendRequest { request: RadioRequest },
+    /// Encode later requests in a different protocol (manual override)
+    SetProtocol { protocol: Protocol },
 }
 
+/// How often an idle radio is polled unless configured otherwise
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Async radio connection that runs in a spawned task
 ///
 /// Generic over the I/O type to support both real serial ports and virtual radios.
@@ -49,6 +65,16 @@ pub struct AsyncRadioConnection<T> {
     mux_tx: tokio_mpsc::Sender<MuxActorCommand>,
     buffer: Vec<u8>,
     civ_address: Option<u8>,
+    /// Mode codes for the identified model (protocol defaults until then)
+    mode_map: ModeMap,
+    /// Minimum time between writes, for radios that drop commands sent back to back
+    command_gap: Duration,
+    /// When the last write finished
+    last_write: Option<Instant>,
+    /// Poll the frequency after this long without traffic (None = never)
+    poll_interval: Option<Duration>,
+    /// What the radio was found to support, once known
+    profile: Option<RadioProfile>,
 }
 
 impl AsyncRadioConnection<SerialStream> {
@@ -56,19 +82,23 @@ impl AsyncRadioConnection<SerialStream> {
     pub fn connect(
         handle: RadioHandle,
         port_name: &str,
-        baud_rate: u32,
-        flow_control: FlowControl,
+        line: &SerialLine,
         protocol: Protocol,
-        event_tx: tokio_mpsc::Sender<MuxEvent>,
-        mux_tx: tokio_mpsc::Sender<MuxActorCommand>,
+        mux: &MuxClient,
     ) -> Result<Self, tokio_serial::Error> {
         debug!(
-            "Opening serial port {} at {} baud, flow_control={:?}, protocol={:?}",
-            port_name, baud_rate, flow_control, protocol
+            "Opening serial port {} at {}, flow_control={:?}, protocol={:?}",
+            port_name,
+            line.describe(),
+            line.flow_control,
+            protocol
         );
 
-        let stream = match tokio_serial::new(port_name, baud_rate)
-            .flow_control(flow_control)
+        let mut stream = match tokio_serial::new(port_name, line.baud_rate)
+            .flow_control(line.flow_control)
+            .data_bits(line.data_bits)
+            .parity(line.parity)
+            .stop_bits(line.stop_bits)
             .timeout(Duration::from_millis(100))
             .open_native_async()
         {
@@ -82,16 +112,49 @@ impl AsyncRadioConnection<SerialStream> {
             }
         };
 
-        Ok(Self {
+        // Some interfaces key or power the radio from DTR/RTS
+        if let Some(level) = line.dtr {
+            stream.write_data_terminal_ready(level)?;
+        }
+        if let Some(level) = line
+            .rts
+            .filter(|_| line.flow_control != FlowControl::Hardware)
+        {
+            stream.write_request_to_send(level)?;
+        }
+
+        Ok(Self::new(
             handle,
-            port_name: port_name.to_string(),
-            io: stream,
+            port_name.to_string(),
+            stream,
             protocol,
-            event_tx,
-            mux_tx,
-            buffer: vec![0u8; 1024],
-            civ_address: None,
-        })
+            mux,
+        ))
+    }
+}
+
+impl AsyncRadioConnection<TcpStream> {
+    /// Create a new async radio connection to a TCP serial server ("host:port")
+    pub async fn connect_tcp(
+        handle: RadioHandle,
+        address: &str,
+        protocol: Protocol,
+        mux: &MuxClient,
+    ) -> Result<Self, std::io::Error> {
+        debug!(
+            "Connecting to {} over TCP, protocol={:?}",
+            address, protocol
+        );
+        let stream = TcpStream::connect(address).await?;
+        // CAT commands are tiny; don't let Nagle hold them back
+        stream.set_nodelay(true)?;
+        Ok(Self::new(
+            handle,
+            address.to_string(),
+            stream,
+            protocol,
+            mux,
+        ))
     }
 }
 
@@ -107,18 +170,22 @@ where
         name: String,
         io: T,
         protocol: Protocol,
-        event_tx: tokio_mpsc::Sender<MuxEvent>,
-        mux_tx: tokio_mpsc::Sender<MuxActorCommand>,
+        mux: &MuxClient,
     ) -> Self {
         Self {
             handle,
             port_name: name,
             io,
             protocol,
-            event_tx,
-            mux_tx,
+            event_tx: mux.event_sender(),
+            mux_tx: mux.command_sender(),
             buffer: vec![0u8; 1024],
             civ_address: None,
+            mode_map: ModeMap::for_protocol(protocol),
+            command_gap: Duration::ZERO,
+            last_write: None,
+            poll_interval: Some(DEFAULT_POLL_INTERVAL),
+            profile: None,
         }
     }
 
@@ -127,63 +194,97 @@ where
         self.civ_address = Some(addr);
     }
 
-    /// Encode a request for the ID query
-    fn encode_id_request(&self) -> Option<Vec<u8>> {
-        let id_req = RadioRequest::GetId;
-        match self.protocol {
-            Protocol::Kenwood => KenwoodCommand::from_radio_request(&id_req).map(|c| c.encode()),
-            Protocol::Elecraft => ElecraftCommand::from_radio_request(&id_req).map(|c| c.encode()),
-            Protocol::FlexRadio => FlexCommand::from_radio_request(&id_req).map(|c| c.encode()),
-            Protocol::YaesuAscii => {
-                YaesuAsciiCommand::from_radio_request(&id_req).map(|c| c.encode())
-            }
-            Protocol::IcomCIV | Protocol::Yaesu => {
-                // Icom and legacy Yaesu don't use ASCII ID command
-                None
-            }
+    /// Leave at least `gap` between writes to the radio
+    pub fn set_command_gap(&mut self, gap: Duration) {
+        self.command_gap = gap;
+    }
+
+    /// Poll an idle radio's frequency this often (None turns polling off)
+    pub fn set_poll_interval(&mut self, interval: Option<Duration>) {
+        self.poll_interval = interval.filter(|i| !i.is_zero());
+    }
+
+    /// Follow a learned profile (see [`crate::radio_profile`])
+    ///
+    /// Queries the radio ignores are no longer sent, and a radio whose
+    /// auto-information doesn't stay on is polled even if polling was off.
+    pub fn set_profile(&mut self, profile: RadioProfile) {
+        if profile.needs_polling() && self.poll_interval.is_none() {
+            info!(
+                "Radio {:?} doesn't keep auto-info on; polling it",
+                self.handle
+            );
+            self.poll_interval = Some(DEFAULT_POLL_INTERVAL);
         }
+        self.profile = Some(profile);
+    }
+
+    /// Whether the profile (if any) says `request` is worth sending
+    fn should_send(&self, request: &RadioRequest) -> bool {
+        self.profile.as_ref().is_none_or(|p| p.should_send(request))
+    }
+
+    /// Protocol this connection speaks
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
     }
 
     /// Encode a RadioRequest to protocol-specific bytes
+    ///
+    /// Compound requests are encoded as a frame sequence that is written in one go.
     fn encode_radio_request(&self, req: &RadioRequest) -> Option<Vec<u8>> {
+        let map = &self.mode_map;
         match self.protocol {
-            Protocol::Kenwood => KenwoodCommand::from_radio_request(req).map(|c| c.encode()),
-            Protocol::Elecraft => ElecraftCommand::from_radio_request(req).map(|c| c.encode()),
-            Protocol::FlexRadio => FlexCommand::from_radio_request(req).map(|c| c.encode()),
+            Protocol::Kenwood => encode_model_request_sequence::<KenwoodCommand>(req, map),
+            Protocol::Elecraft => encode_model_request_sequence::<ElecraftCommand>(req, map),
+            Protocol::FlexRadio => encode_request_sequence::<FlexCommand>(req),
             Protocol::IcomCIV => {
                 let addr = self.civ_address.unwrap_or(0x94);
-                CivCommand::from_radio_request(req).map(|c| {
-                    CivCommand::new(cat_protocol::icom::CONTROLLER_ADDR, addr, c.command).encode()
+                if let RadioRequest::SetFrequencyMode { hz, mode } = req {
+                    return Some(cat_protocol::icom::encode_frequency_mode(
+                        addr,
+                        hz.hz(),
+                        *mode,
+                    ));
+                }
+                CivCommand::from_radio_request(req).map(|mut c| {
+                    if let Some(mode) = req.mode() {
+                        c.set_mode_code(map.code(mode));
+                    }
+                    CivCommand::to_radio(addr, c.command).encode()
                 })
             }
-            Protocol::Yaesu => YaesuCommand::from_radio_request(req).map(|c| c.encode()),
-            Protocol::YaesuAscii => YaesuAsciiCommand::from_radio_request(req).map(|c| c.encode()),
+            Protocol::Yaesu => encode_model_request_sequence::<YaesuCommand>(req, map),
+            Protocol::YaesuAscii => encode_model_request_sequence::<YaesuAsciiCommand>(req, map),
         }
     }
 
-    /// Try to parse an ID response and look up the model name
-    fn try_parse_id_response(&self, data: &[u8]) -> Option<String> {
+    /// Try to parse an ID response into a display name and database model
+    #[allow(clippy::collapsible_match)]
+    fn try_parse_id_response(&self, data: &[u8]) -> Option<(String, Option<RadioModel>)> {
         if !data.contains(&b';') {
             return None;
         }
 
+        let known = |model: RadioModel| (model.model.clone(), Some(model));
+
         match self.protocol {
             Protocol::Kenwood => {
                 if cat_protocol::kenwood::is_valid_id_response(data) {
                     let id_str =
                         String::from_utf8_lossy(&data[2..data.iter().position(|&b| b == b';')?]);
                     if let Some(model) = RadioDatabase::by_kenwood_id(&id_str) {
-                        return Some(model.model);
+                        return Some(known(model));
                     }
-                    return Some(format!("Kenwood (ID{})", id_str));
+                    return Some((format!("Kenwood (ID{})", id_str), None));
                 }
             }
             Protocol::Elecraft => {
                 if let Some(model_name) = cat_protocol::elecraft::is_elecraft_response(data) {
                     if let Some(model) = RadioDatabase::by_elecraft_id(model_name) {
-                        return Some(model.model);
+                        return Some(known(model));
                     }
-                    return Some(model_name.to_string());
+                    return Some((model_name.to_string(), None));
                 }
             }
             Protocol::FlexRadio => {
@@ -191,9 +292,9 @@ where
                     let id_str =
                         String::from_utf8_lossy(&data[2..data.iter().position(|&b| b == b';')?]);
                     if let Some(model) = RadioDatabase::by_flex_id(&id_str) {
-                        return Some(model.model);
+                        return Some(known(model));
                     }
-                    return Some(format!("FlexRadio (ID{})", id_str));
+                    return Some((format!("FlexRadio (ID{})", id_str), None));
                 }
             }
             Protocol::YaesuAscii => {
@@ -201,9 +302,9 @@ where
                     let id_str =
                         String::from_utf8_lossy(&data[2..data.iter().position(|&b| b == b';')?]);
                     if let Some(model) = RadioDatabase::by_yaesu_ascii_id(&id_str) {
-                        return Some(model.model);
+                        return Some(known(model));
                     }
-                    return Some(format!("Yaesu (ID{})", id_str));
+                    return Some((format!("Yaesu (ID{})", id_str), None));
                 }
             }
             _ => {}
@@ -212,87 +313,224 @@ where
         None
     }
 
-    /// Query the radio's ID and return the model name if identified
-    pub async fn query_id(&mut self) -> Option<String> {
-        let id_cmd = self.encode_id_request()?;
-
-        debug!(
-            "Querying ID on radio {:?} with protocol {:?}",
-            self.handle, self.protocol
-        );
-
-        if self.write(&id_cmd).await.is_err() {
-            return None;
-        }
-
-        let timeout = Duration::from_millis(500);
-        let mut response = Vec::new();
-
-        loop {
-            match tokio::time::timeout(timeout, self.io.read(&mut self.buffer)).await {
-                Ok(Ok(n)) if n > 0 => {
-                    let data = &self.buffer[..n];
-                    // Send raw data to mux actor for traffic monitoring
+    /// Run an initial sync plan, returning the radio's model name if it identified itself
+    ///
+    /// Emits a `RadioSyncProgress` event after every step. A required step that
+    /// goes unanswered is reported as failed and the plan carries on, so that
+    /// auto-info still gets switched on; the read loop and the mux's own polling
+    /// fill in whatever the plan missed.
+    pub async fn run_sync_plan(&mut self, mut plan: SyncPlan) -> Option<String> {
+        let mut codec = RadioSideCodec::new(self.protocol);
+        let mut model_name = None;
+
+        let mut index = 0;
+        while index < plan.steps.len() {
+            let step = plan.steps[index].clone();
+            let (status, identified) = self
+                .run_sync_step(&step, plan.step_timeout, &mut codec)
+                .await;
+
+            if let Some((name, model)) = identified {
+                info!("Identified radio as {}", name);
+                if let Some(model) = model {
+                    plan.add_model_steps(&model);
+                    self.mode_map = model.mode_map();
+                    // Replies are read with the model's mode codes from here on
+                    codec = RadioSideCodec::for_model(self.protocol, Some(&model));
                     let _ = self
                         .mux_tx
-                        .send(MuxActorCommand::RadioRawData {
+                        .send(MuxActorCommand::SetRadioModel {
                             handle: self.handle,
-                            data: data.to_vec(),
+                            model,
                         })
                         .await;
-                    response.extend_from_slice(data);
-                    if let Some(model) = self.try_parse_id_response(&response) {
-                        info!("Identified radio as {}", model);
-                        return Some(model);
-                    }
                 }
-                Ok(Ok(_)) => {}
-                Ok(Err(_)) | Err(_) => break,
+                model_name = Some(name);
+            }
+
+            let _ = self
+                .event_tx
+                .send(MuxEvent::RadioSyncProgress {
+                    handle: self.handle,
+                    step: index + 1,
+                    total: plan.len(),
+                    label: step.label.to_string(),
+                    status,
+                })
+                .await;
+
+            if status == SyncStepStatus::Failed {
+                warn!(
+                    "Initial sync of radio {:?}: no {} response, carrying on",
+                    self.handle, step.label
+                );
             }
+            index += 1;
         }
-        None
+
+        model_name
     }
 
-    /// Query the radio's current frequency and mode
-    pub async fn query_initial_state(&mut self) -> Result<(), std::io::Error> {
-        // Query frequency
-        if let Some(data) = self.encode_radio_request(&RadioRequest::GetFrequency) {
-            debug!(
-                "Querying frequency on radio {:?} with protocol {:?}",
-                self.handle, self.protocol
-            );
-            self.write(&data).await?;
+    /// Send one sync step and wait for its response
+    async fn run_sync_step(
+        &mut self,
+        step: &SyncStep,
+        timeout: Duration,
+        codec: &mut RadioSideCodec,
+    ) -> (SyncStepStatus, Option<(String, Option<RadioModel>)>) {
+        let Some(data) = self.encode_radio_request(&step.request) else {
+            debug!("Skipping sync step {} for {:?}", step.label, self.protocol);
+            return (SyncStepStatus::Skipped, None);
+        };
+
+        debug!("Sync step {} on radio {:?}", step.label, self.handle);
+        if let Err(e) = self.write(&data).await {
+            warn!("Failed to send {} to {:?}: {}", step.label, self.handle, e);
+            return (SyncStepStatus::Failed, None);
         }
 
-        // Query mode
-        if let Some(data) = self.encode_radio_request(&RadioRequest::GetMode) {
-            debug!(
-                "Querying mode on radio {:?} with protocol {:?}",
-                self.handle, self.protocol
-            );
-            self.write(&data).await?;
+        let Some(expect) = step.expect else {
+            return (SyncStepStatus::Done, None);
+        };
+
+        let unanswered = if step.optional {
+            SyncStepStatus::Skipped
+        } else {
+            SyncStepStatus::Failed
+        };
+
+        // An earlier step may already have pulled in the answer
+        if expect != Expect::Id && Self::codec_has(codec, expect) {
+            return (SyncStepStatus::Done, None);
         }
 
-        Ok(())
+        let deadline = Instant::now() + timeout;
+        let mut received = Vec::new();
+
+        loop {
+            let n = match tokio::time::timeout_at(deadline, self.io.read(&mut self.buffer)).await {
+                Ok(Ok(n)) if n > 0 => n,
+                _ => return (unanswered, None),
+            };
+
+            let data = self.buffer[..n].to_vec();
+            let _ = self
+                .mux_tx
+                .send(MuxActorCommand::RadioRawData {
+                    handle: self.handle,
+                    data: data.clone(),
+                    received: Instant::now(),
+                })
+                .await;
+
+            codec.push_bytes(&data);
+            if expect == Expect::Id {
+                received.extend_from_slice(&data);
+                if let Some(identified) = self.try_parse_id_response(&received) {
+                    return (SyncStepStatus::Done, Some(identified));
+                }
+            } else if Self::codec_has(codec, expect) {
+                return (SyncStepStatus::Done, None);
+            }
+        }
     }
 
-    /// Enable auto-information mode on the radio
-    pub async fn enable_auto_info(&mut self) -> Result<(), std::io::Error> {
-        let req = RadioRequest::SetAutoInfo { enabled: true };
-        if let Some(data) = self.encode_radio_request(&req) {
+    /// Ask the radio each [`ProfileQuery`] and record what it answers
+    ///
+    /// Meant for a radio's first connection, after the initial sync plan
+    /// has switched auto-information on. Queries the protocol can't encode
+    /// are left out of the profile.
+    pub async fn discover_profile(&mut self, identity: &str) -> RadioProfile {
+        let mut profile = RadioProfile::new(identity, self.protocol);
+        let mut codec = RadioSideCodec::new(self.protocol);
+
+        'queries: for query in ProfileQuery::ALL {
+            let Some(data) = self.encode_radio_request(&query.request()) else {
+                continue;
+            };
+            // A radio busy with something else may miss one query
+            let mut answered = false;
+            for _ in 0..PROFILE_ATTEMPTS {
+                if let Err(e) = self.write(&data).await {
+                    warn!("Profiling radio {:?} stopped: {}", self.handle, e);
+                    break 'queries;
+                }
+                answered = self.await_answer(query, &mut codec, &mut profile).await;
+                if answered {
+                    break;
+                }
+            }
             debug!(
-                "Enabling auto-info on radio {:?} with protocol {:?}",
-                self.handle, self.protocol
+                "Radio {:?} {} {} query",
+                self.handle,
+                if answered { "answered" } else { "ignored" },
+                query.name()
             );
-            self.write(&data).await?;
+            profile.record(query, answered);
+        }
+
+        let profile = profile.learned(std::time::SystemTime::now());
+        info!("Profiled radio {:?}: {}", self.handle, profile.summary());
+        profile
+    }
+
+    /// Read until a response answers `query`, noting everything seen on the way
+    async fn await_answer(
+        &mut self,
+        query: ProfileQuery,
+        codec: &mut RadioSideCodec,
+        profile: &mut RadioProfile,
+    ) -> bool {
+        let deadline = Instant::now() + DEFAULT_STEP_TIMEOUT;
+        loop {
+            while let Some(response) = codec.next_response() {
+                profile.observe(&response);
+                if query.answered_by(&response) {
+                    return true;
+                }
+            }
+
+            let n = match tokio::time::timeout_at(deadline, self.io.read(&mut self.buffer)).await {
+                Ok(Ok(n)) if n > 0 => n,
+                _ => return false,
+            };
+            let data = self.buffer[..n].to_vec();
+            let _ = self
+                .mux_tx
+                .send(MuxActorCommand::RadioRawData {
+                    handle: self.handle,
+                    data: data.clone(),
+                    received: Instant::now(),
+                })
+                .await;
+            // The codec only says on or off; ASCII replies carry the level
+            if query == ProfileQuery::AutoInfo && self.protocol != Protocol::IcomCIV {
+                if let Some(level) = auto_info_level(&data) {
+                    profile.auto_info_level = Some(level);
+                }
+            }
+            codec.push_bytes(&data);
         }
-        Ok(())
     }
 
-    /// Write data to the radio
+    /// Drain parsed responses until one satisfies `expect`
+    fn codec_has(codec: &mut RadioSideCodec, expect: Expect) -> bool {
+        while let Some(response) = codec.next_response() {
+            if expect.matches(&response) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Write data to the radio, waiting out the command gap first
     pub async fn write(&mut self, data: &[u8]) -> Result<(), std::io::Error> {
+        if let Some(last) = self.last_write.filter(|_| !self.command_gap.is_zero()) {
+            tokio::time::sleep_until(last + self.command_gap).await;
+        }
         self.io.write_all(data).await?;
         self.io.flush().await?;
+        self.last_write = Some(Instant::now());
 
         // Send traffic notification to mux actor
         let _ = self
@@ -308,8 +546,9 @@ where
 
     /// Main read loop - runs until connection fails, shutdown is requested, or channel closed
     ///
-    /// Includes idle polling: when no data is received for 500ms, polls the radio's
-    /// frequency every 500ms to ensure UI stays in sync during rapid VFO changes.
+    /// Includes idle polling: when no data is received for the poll interval (500ms
+    /// by default), polls the radio's frequency to ensure UI stays in sync during
+    /// rapid VFO changes.
     pub async fn run_read_loop(mut self, mut cmd_rx: tokio_mpsc::Receiver<RadioTaskCommand>) {
         use tokio::time::{interval, Instant, MissedTickBehavior};
 
@@ -319,11 +558,11 @@ where
         );
 
         // Idle polling configuration
-        const IDLE_THRESHOLD: Duration = Duration::from_millis(500);
-        const POLL_INTERVAL: Duration = Duration::from_millis(500);
+        let polling = self.poll_interval.is_some();
+        let idle_threshold = self.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL);
 
         let mut last_activity = Instant::now();
-        let mut poll_timer = interval(POLL_INTERVAL);
+        let mut poll_timer = interval(idle_threshold);
         poll_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
         loop {
@@ -341,6 +580,27 @@ where
                                 warn!("Failed to send data to radio {:?}: {}", self.handle, e);
                             }
                         }
+                        Some(RadioTaskCommand::SendRequest { request }) => {
+                            if !self.should_send(&request) {
+                                debug!("Radio {:?} ignores {:?}; not sent", self.handle, request);
+                                continue;
+                            }
+                            let Some(data) = self.encode_radio_request(&request) else {
+                                debug!("Cannot encode {:?} for {:?}", request, self.protocol);
+                                continue;
+                            };
+                            if let Err(e) = self.write(&data).await {
+                                warn!("Failed to send request to radio {:?}: {}", self.handle, e);
+                            }
+                        }
+                        Some(RadioTaskCommand::SetProtocol { protocol }) => {
+                            info!(
+                                "Radio {:?} protocol changed from {:?} to {:?}",
+                                self.handle, self.protocol, protocol
+                            );
+                            self.protocol = protocol;
+                            self.mode_map = ModeMap::for_protocol(protocol);
+                        }
                     }
                 }
 
@@ -361,6 +621,7 @@ where
                             let _ = self.mux_tx.send(MuxActorCommand::RadioRawData {
                                 handle: self.handle,
                                 data: data.to_vec(),
+                                received: last_activity,
                             }).await;
                         }
                         Ok(Ok(_)) => {} // 0 bytes
@@ -386,9 +647,11 @@ where
                 }
 
                 // Idle polling timer
-                _ = poll_timer.tick() => {
+                _ = poll_timer.tick(), if polling => {
                     // Only poll if we've been idle for the threshold duration
-                    if last_activity.elapsed() >= IDLE_THRESHOLD {
+                    if last_activity.elapsed() >= idle_threshold
+                        && self.should_send(&RadioRequest::GetFrequency)
+                    {
                         // Send frequency query to poll the radio
                         if let Some(data) = self.encode_radio_request(&RadioRequest::GetFrequency) {
                             debug!("Idle polling frequency for radio {:?}", self.handle);
@@ -0,0 +1,282 @@
+//! Band decoder output (OTRSP)
+//!
+//! Drives external band decoder / antenna switching hardware that speaks the
+//! Open Two Radio Switching Protocol, the ASCII control subset implemented by
+//! microHAM microKeyer/MK2R/Station Master boxes and most SO2R controllers.
+//!
+//! Only the commands needed for station automation are emitted:
+//!
+//! - `TXn` / `RXn` route PTT/keying and receive audio to radio `n` (1 or 2)
+//! - `AUXnvv` sets the band data output for radio `n` to value `vv`
+//!
+//! Band data uses the common Yaesu-style BCD band code understood by band
+//! decoders (1 = 160m ... 10 = 6m).
+
+use cat_protocol::Frequency;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::{mpsc as tokio_mpsc, oneshot};
+use tracing::{debug, info};
+
+use crate::client::MuxClient;
+use crate::state::RadioHandle;
+use crate::MuxEvent;
+
+/// Number of radio ports an OTRSP device exposes
+pub const OTRSP_RADIO_PORTS: u8 = 2;
+
+/// Default baud rate for OTRSP devices
+pub const OTRSP_DEFAULT_BAUD: u32 = 9600;
+
+/// Map a frequency to the standard band decoder BCD code
+///
+/// Returns 0 for frequencies outside the HF/6m amateur bands, which band
+/// decoders treat as "no band" (all outputs off).
+pub fn band_data_code(frequency: Frequency) -> u8 {
+    match frequency.hz() {
+        1_800_000..=2_000_000 => 1,
+        3_500_000..=4_000_000 => 2,
+        7_000_000..=7_300_000 => 3,
+        10_100_000..=10_150_000 => 4,
+        14_000_000..=14_350_000 => 5,
+        18_068_000..=18_168_000 => 6,
+        21_000_000..=21_450_000 => 7,
+        24_890_000..=24_990_000 => 8,
+        28_000_000..=29_700_000 => 9,
+        50_000_000..=54_000_000 => 10,
+        _ => 0,
+    }
+}
+
+/// Encode an OTRSP `TXn` command (route PTT/keying to radio `port`)
+pub fn encode_tx(port: u8) -> Vec<u8> {
+    format!("TX{}\r", port).into_bytes()
+}
+
+/// Encode an OTRSP `RXn` command (route receive audio to radio `port`)
+pub fn encode_rx(port: u8) -> Vec<u8> {
+    format!("RX{}\r", port).into_bytes()
+}
+
+/// Encode an OTRSP `AUXnvv` command (band data output for radio `port`)
+pub fn encode_aux(port: u8, value: u8) -> Vec<u8> {
+    format!("AUX{}{:02}\r", port, value).into_bytes()
+}
+
+/// Tracks what has been sent to the band decoder and emits only changes
+///
+/// Radios are assigned to OTRSP ports 1 and 2 in registration order; further
+/// radios are ignored until a port frees up.
+#[derive(Debug, Default)]
+pub struct BandDecoderSink {
+    /// OTRSP port assigned to each radio
+    ports: HashMap<RadioHandle, u8>,
+    /// Last band code sent per port (index 0 = port 1)
+    last_aux: [Option<u8>; OTRSP_RADIO_PORTS as usize],
+    /// Last port selected for TX/RX
+    last_tx: Option<u8>,
+}
+
+impl BandDecoderSink {
+    /// Create an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assign a radio to the first free OTRSP port
+    pub fn assign_radio(&mut self, handle: RadioHandle) -> Option<u8> {
+        if let Some(port) = self.ports.get(&handle) {
+            return Some(*port);
+        }
+        let port = (1..=OTRSP_RADIO_PORTS).find(|p| !self.ports.values().any(|used| used == p))?;
+        self.ports.insert(handle, port);
+        Some(port)
+    }
+
+    /// Release a radio's port
+    pub fn release_radio(&mut self, handle: RadioHandle) {
+        if let Some(port) = self.ports.remove(&handle) {
+            self.last_aux[(port - 1) as usize] = None;
+            if self.last_tx == Some(port) {
+                self.last_tx = None;
+            }
+        }
+    }
+
+    /// Port assigned to a radio, if any
+    pub fn port_for(&self, handle: RadioHandle) -> Option<u8> {
+        self.ports.get(&handle).copied()
+    }
+
+    /// Forget everything sent so the next updates are emitted in full
+    ///
+    /// Call when a (re)connected device needs the complete state.
+    pub fn reset_sent(&mut self) {
+        self.last_aux = [None; OTRSP_RADIO_PORTS as usize];
+        self.last_tx = None;
+    }
+
+    /// Band data for a radio's new frequency (None if unchanged or unassigned)
+    pub fn on_frequency(&mut self, handle: RadioHandle, hz: Frequency) -> Option<Vec<u8>> {
+        let port = self.port_for(handle)?;
+        let code = band_data_code(hz);
+        let slot = &mut self.last_aux[(port - 1) as usize];
+        if *slot == Some(code) {
+            return None;
+        }
+        *slot = Some(code);
+        Some(encode_aux(port, code))
+    }
+
+    /// TX/RX routing for a newly active radio (None if unchanged or unassigned)
+    pub fn on_active_radio(&mut self, handle: RadioHandle) -> Option<Vec<u8>> {
+        let port = self.port_for(handle)?;
+        if self.last_tx == Some(port) {
+            return None;
+        }
+        self.last_tx = Some(port);
+
+        let mut data = encode_tx(port);
+        data.extend(encode_rx(port));
+        Some(data)
+    }
+}
+
+/// Async band decoder connection that runs in a spawned task
+///
+/// Write-only from the mux's perspective: anything the device sends back
+/// (e.g. replies to `?NAME`) is read and discarded so buffers don't fill.
+pub struct BandDecoderConnection<T> {
+    io: T,
+    event_tx: tokio_mpsc::Sender<MuxEvent>,
+}
+
+impl<T> BandDecoderConnection<T>
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send,
+{
+    /// Create a new band decoder connection
+    pub fn new(io: T, mux: &MuxClient) -> Self {
+        Self {
+            io,
+            event_tx: mux.event_sender(),
+        }
+    }
+
+    /// Run the band decoder I/O loop until shutdown or the data channel closes
+    pub async fn run(
+        mut self,
+        mut shutdown_rx: oneshot::Receiver<()>,
+        mut data_rx: tokio_mpsc::Receiver<Vec<u8>>,
+    ) {
+        info!("Band decoder connection starting");
+
+        let mut buffer = vec![0u8; 64];
+
+        loop {
+            tokio::select! {
+                _ = &mut shutdown_rx => {
+                    break;
+                }
+
+                data = data_rx.recv() => {
+                    let Some(data) = data else { break; };
+                    debug!("Band decoder writing {:?}", String::from_utf8_lossy(&data));
+                    if let Err(e) = self.io.write_all(&data).await {
+                        let _ = self.event_tx.send(MuxEvent::Error {
+                            source: "Band Decoder".to_string(),
+                            message: format!("Write error: {}", e),
+                        }).await;
+                    } else {
+                        let _ = self.io.flush().await;
+                    }
+                }
+
+                result = tokio::time::timeout(
+                    Duration::from_millis(100),
+                    self.io.read(&mut buffer)
+                ) => {
+                    match result {
+                        Ok(Ok(n)) if n > 0 => {
+                            debug!("Band decoder sent {:02X?}", &buffer[..n]);
+                        }
+                        Ok(Ok(_)) => {}
+                        Ok(Err(e)) => {
+                            if e.kind() != std::io::ErrorKind::WouldBlock
+                                && e.kind() != std::io::ErrorKind::TimedOut
+                            {
+                                let _ = self.event_tx.send(MuxEvent::Error {
+                                    source: "Band Decoder".to_string(),
+                                    message: format!("Read error: {}", e),
+                                }).await;
+                                break;
+                            }
+                        }
+                        Err(_) => {}
+                    }
+                }
+            }
+        }
+
+        info!("Band decoder connection shutting down");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_band_data_codes() {
+        assert_eq!(band_data_code(Frequency::from_hz(1_830_000)), 1);
+        assert_eq!(band_data_code(Frequency::from_hz(7_074_000)), 3);
+        assert_eq!(band_data_code(Frequency::from_hz(14_074_000)), 5);
+        assert_eq!(band_data_code(Frequency::from_hz(50_313_000)), 10);
+        assert_eq!(band_data_code(Frequency::from_hz(144_174_000)), 0);
+    }
+
+    #[test]
+    fn test_encoding() {
+        assert_eq!(encode_tx(2), b"TX2\r");
+        assert_eq!(encode_rx(1), b"RX1\r");
+        assert_eq!(encode_aux(1, 5), b"AUX105\r");
+        assert_eq!(encode_aux(2, 10), b"AUX210\r");
+    }
+
+    #[test]
+    fn test_sink_assigns_two_ports() {
+        let mut sink = BandDecoderSink::new();
+        assert_eq!(sink.assign_radio(RadioHandle(1)), Some(1));
+        assert_eq!(sink.assign_radio(RadioHandle(2)), Some(2));
+        assert_eq!(sink.assign_radio(RadioHandle(3)), None);
+
+        sink.release_radio(RadioHandle(1));
+        assert_eq!(sink.assign_radio(RadioHandle(3)), Some(1));
+    }
+
+    #[test]
+    fn test_sink_only_emits_changes() {
+        let mut sink = BandDecoderSink::new();
+        let h = RadioHandle(1);
+        sink.assign_radio(h);
+
+        assert_eq!(
+            sink.on_frequency(h, Frequency::from_hz(14_074_000)),
+            Some(b"AUX105\r".to_vec())
+        );
+        // Same band, no output
+        assert_eq!(sink.on_frequency(h, Frequency::from_hz(14_200_000)), None);
+        assert_eq!(
+            sink.on_frequency(h, Frequency::from_hz(7_074_000)),
+            Some(b"AUX103\r".to_vec())
+        );
+
+        assert_eq!(sink.on_active_radio(h), Some(b"TX1\rRX1\r".to_vec()));
+        assert_eq!(sink.on_active_radio(h), None);
+
+        sink.reset_sent();
+        assert_eq!(sink.on_active_radio(h), Some(b"TX1\rRX1\r".to_vec()));
+    }
+}
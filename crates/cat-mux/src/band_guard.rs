@@ -0,0 +1,258 @@
+//! Same-band guard across radios
+//!
+//! Contest rules allow one signal per band (multi-single, multi-two), and
+//! Field Day one transmitter per band and mode. [`BandGuard`] notices when
+//! another radio is tuned to the active radio's band and reports it as
+//! [`crate::MuxEvent::BandConflict`]. At [`BandGuardStrictness::BlockPtt`]
+//! the mux also refuses to honor its PTT: keying it does not make it the
+//! active radio, and the amp is not keyed for the active radio while a
+//! conflicting radio is already transmitting.
+
+use std::collections::HashMap;
+
+use cat_protocol::OperatingMode;
+use serde::{Deserialize, Serialize};
+
+use crate::band_plan::band_name;
+use crate::state::{RadioHandle, RadioState};
+
+/// What the guard does about radios sharing a band
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BandGuardStrictness {
+    /// No checks
+    #[default]
+    Off,
+    /// Warn when a radio enters the active radio's band
+    Warn,
+    /// Warn, and don't honor PTT from a radio in the active radio's band
+    BlockPtt,
+}
+
+impl BandGuardStrictness {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::Warn => "Warn",
+            Self::BlockPtt => "Warn and block PTT",
+        }
+    }
+}
+
+/// Same-band guard configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BandGuardConfig {
+    /// What to do about a conflict
+    pub strictness: BandGuardStrictness,
+    /// Only radios in the same mode class (CW, phone, digital) conflict, as
+    /// at Field Day
+    pub per_mode: bool,
+}
+
+/// Mode classes counted separately at Field Day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeClass {
+    Cw,
+    Phone,
+    Digital,
+}
+
+impl ModeClass {
+    /// Class of an operating mode
+    pub fn of(mode: OperatingMode) -> Self {
+        match mode {
+            OperatingMode::Cw | OperatingMode::CwR => Self::Cw,
+            m if m.is_voice() => Self::Phone,
+            _ => Self::Digital,
+        }
+    }
+}
+
+/// A radio entering or leaving the active radio's band
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BandConflict {
+    /// The radio sharing the band
+    pub handle: RadioHandle,
+    /// The active radio
+    pub active: RadioHandle,
+    /// The shared band, or `None` when the conflict ended
+    pub band: Option<&'static str>,
+}
+
+/// Tracks which radios share the active radio's band
+#[derive(Debug, Default)]
+pub struct BandGuard {
+    config: BandGuardConfig,
+    /// Conflicts last reported: radio -> (active radio, band)
+    reported: HashMap<RadioHandle, (RadioHandle, &'static str)>,
+}
+
+impl BandGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current configuration
+    pub fn config(&self) -> BandGuardConfig {
+        self.config
+    }
+
+    /// Change configuration
+    pub fn set_config(&mut self, config: BandGuardConfig) {
+        self.config = config;
+    }
+
+    /// Whether PTT from a conflicting radio is refused
+    pub fn blocks_ptt(&self) -> bool {
+        self.config.strictness == BandGuardStrictness::BlockPtt
+    }
+
+    /// The band `radio` shares with `active`, if that counts as a conflict
+    ///
+    /// With `per_mode`, a radio whose mode isn't known yet is assumed to be
+    /// in the same mode class.
+    pub fn conflict(&self, radio: &RadioState, active: &RadioState) -> Option<&'static str> {
+        if self.config.strictness == BandGuardStrictness::Off
+            || radio.handle == active.handle
+            || radio.rx_only
+            || active.rx_only
+        {
+            return None;
+        }
+        let band = radio.frequency_hz.and_then(band_name)?;
+        if active.frequency_hz.and_then(band_name) != Some(band) {
+            return None;
+        }
+        let other_class = match (radio.mode, active.mode) {
+            (Some(a), Some(b)) => ModeClass::of(a) != ModeClass::of(b),
+            _ => false,
+        };
+        if self.config.per_mode && other_class {
+            return None;
+        }
+        Some(band)
+    }
+
+    /// Re-check every radio against the active one; returns what changed
+    pub fn update<'a>(
+        &mut self,
+        active: Option<&RadioState>,
+        radios: impl Iterator<Item = &'a RadioState>,
+    ) -> Vec<BandConflict> {
+        let now: HashMap<_, _> = match active {
+            Some(active) => radios
+                .filter_map(|r| Some((r.handle, (active.handle, self.conflict(r, active)?))))
+                .collect(),
+            None => HashMap::new(),
+        };
+
+        let mut changes: Vec<_> = self
+            .reported
+            .iter()
+            .filter(|(handle, (active, _))| {
+                now.get(handle)
+                    .is_none_or(|(now_active, _)| now_active != active)
+            })
+            .map(|(&handle, &(active, _))| BandConflict {
+                handle,
+                active,
+                band: None,
+            })
+            .collect();
+        changes.extend(
+            now.iter()
+                .filter(|(handle, seen)| self.reported.get(handle) != Some(seen))
+                .map(|(&handle, &(active, band))| BandConflict {
+                    handle,
+                    active,
+                    band: Some(band),
+                }),
+        );
+        changes.sort_by_key(|c| (c.band.is_some(), c.handle.0));
+        self.reported = now;
+        changes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::Frequency;
+    use cat_protocol::Protocol;
+
+    fn radio(handle: u32, hz: u64, mode: OperatingMode) -> RadioState {
+        let mut r = RadioState::new(
+            RadioHandle(handle),
+            format!("Radio {}", handle),
+            String::new(),
+            Protocol::Kenwood,
+        );
+        r.set_frequency(Frequency::from_hz(hz));
+        r.set_mode(mode);
+        r
+    }
+
+    fn guard(strictness: BandGuardStrictness, per_mode: bool) -> BandGuard {
+        let mut g = BandGuard::new();
+        g.set_config(BandGuardConfig {
+            strictness,
+            per_mode,
+        });
+        g
+    }
+
+    #[test]
+    fn test_conflict_by_band_and_mode_class() {
+        let run = radio(1, 14_025_000, OperatingMode::Cw);
+        let mult = radio(2, 14_250_000, OperatingMode::Usb);
+        let other_band = radio(3, 7_025_000, OperatingMode::Cw);
+
+        // Contest: one signal per band
+        let contest = guard(BandGuardStrictness::BlockPtt, false);
+        assert_eq!(contest.conflict(&mult, &run), Some("20m"));
+        assert_eq!(contest.conflict(&other_band, &run), None);
+        assert_eq!(contest.conflict(&run, &run), None);
+
+        // Field Day: CW and phone on the same band are separate
+        let field_day = guard(BandGuardStrictness::Warn, true);
+        assert_eq!(field_day.conflict(&mult, &run), None);
+        let cw = radio(2, 14_040_000, OperatingMode::CwR);
+        assert_eq!(field_day.conflict(&cw, &run), Some("20m"));
+
+        assert_eq!(
+            guard(BandGuardStrictness::Off, false).conflict(&mult, &run),
+            None
+        );
+    }
+
+    #[test]
+    fn test_update_reports_changes_only() {
+        let mut g = guard(BandGuardStrictness::Warn, false);
+        let run = radio(1, 14_025_000, OperatingMode::Cw);
+        let mut mult = radio(2, 7_050_000, OperatingMode::Cw);
+
+        assert!(g.update(Some(&run), [&run, &mult].into_iter()).is_empty());
+
+        mult.set_frequency(Frequency::from_hz(14_100_000));
+        let changes = g.update(Some(&run), [&run, &mult].into_iter());
+        assert_eq!(
+            changes,
+            vec![BandConflict {
+                handle: RadioHandle(2),
+                active: RadioHandle(1),
+                band: Some("20m"),
+            }]
+        );
+        assert!(g.update(Some(&run), [&run, &mult].into_iter()).is_empty());
+
+        // The other radio becoming active moves the conflict onto the first
+        let changes = g.update(Some(&mult), [&run, &mult].into_iter());
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].band, None);
+        assert_eq!(changes[1].handle, RadioHandle(1));
+
+        mult.set_frequency(Frequency::from_hz(21_100_000));
+        let changes = g.update(Some(&mult), [&run, &mult].into_iter());
+        assert_eq!(changes[0].band, None);
+    }
+}
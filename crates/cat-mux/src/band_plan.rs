@@ -0,0 +1,224 @@
+//! Amateur band plan
+//!
+//! The amateur allocations by ITU region, from 2200m to 1.2cm. Frequency
+//! entry uses it to refuse a transmit frequency outside the bands; watch
+//! expressions use its band names. Edges follow the ITU Radio Regulations
+//! (with the common 60m channel block in Region 2); a country's own plan
+//! may be narrower.
+
+use cat_protocol::Frequency;
+use serde::{Deserialize, Serialize};
+
+/// ITU region whose allocations apply
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BandPlanRegion {
+    /// Europe, Africa, the Middle East and northern Asia
+    Region1,
+    /// The Americas
+    #[default]
+    Region2,
+    /// The rest of Asia and Oceania
+    Region3,
+}
+
+impl BandPlanRegion {
+    /// Every region, for pickers
+    pub const ALL: [BandPlanRegion; 3] = [
+        BandPlanRegion::Region1,
+        BandPlanRegion::Region2,
+        BandPlanRegion::Region3,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            BandPlanRegion::Region1 => "ITU Region 1",
+            BandPlanRegion::Region2 => "ITU Region 2",
+            BandPlanRegion::Region3 => "ITU Region 3",
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            BandPlanRegion::Region1 => 0,
+            BandPlanRegion::Region2 => 1,
+            BandPlanRegion::Region3 => 2,
+        }
+    }
+}
+
+/// One amateur band: its name and edges in Hz for each region (None = not
+/// allocated there)
+struct Band {
+    name: &'static str,
+    edges: [Option<(u64, u64)>; 3],
+}
+
+const fn everywhere(name: &'static str, lo: u64, hi: u64) -> Band {
+    Band {
+        name,
+        edges: [Some((lo, hi)); 3],
+    }
+}
+
+const BAND_PLAN: &[Band] = &[
+    everywhere("2200m", 135_700, 137_800),
+    everywhere("630m", 472_000, 479_000),
+    Band {
+        name: "160m",
+        edges: [
+            Some((1_810_000, 2_000_000)),
+            Some((1_800_000, 2_000_000)),
+            Some((1_800_000, 2_000_000)),
+        ],
+    },
+    Band {
+        name: "80m",
+        edges: [
+            Some((3_500_000, 3_800_000)),
+            Some((3_500_000, 4_000_000)),
+            Some((3_500_000, 3_900_000)),
+        ],
+    },
+    Band {
+        name: "60m",
+        edges: [
+            Some((5_351_500, 5_366_500)),
+            Some((5_330_500, 5_406_500)),
+            Some((5_351_500, 5_366_500)),
+        ],
+    },
+    Band {
+        name: "40m",
+        edges: [
+            Some((7_000_000, 7_200_000)),
+            Some((7_000_000, 7_300_000)),
+            Some((7_000_000, 7_200_000)),
+        ],
+    },
+    everywhere("30m", 10_100_000, 10_150_000),
+    everywhere("20m", 14_000_000, 14_350_000),
+    everywhere("17m", 18_068_000, 18_168_000),
+    everywhere("15m", 21_000_000, 21_450_000),
+    everywhere("12m", 24_890_000, 24_990_000),
+    everywhere("10m", 28_000_000, 29_700_000),
+    Band {
+        name: "6m",
+        edges: [
+            Some((50_000_000, 52_000_000)),
+            Some((50_000_000, 54_000_000)),
+            Some((50_000_000, 54_000_000)),
+        ],
+    },
+    Band {
+        name: "4m",
+        edges: [Some((70_000_000, 70_500_000)), None, None],
+    },
+    Band {
+        name: "2m",
+        edges: [
+            Some((144_000_000, 146_000_000)),
+            Some((144_000_000, 148_000_000)),
+            Some((144_000_000, 148_000_000)),
+        ],
+    },
+    Band {
+        name: "1.25m",
+        edges: [None, Some((220_000_000, 225_000_000)), None],
+    },
+    Band {
+        name: "70cm",
+        edges: [
+            Some((430_000_000, 440_000_000)),
+            Some((420_000_000, 450_000_000)),
+            Some((430_000_000, 440_000_000)),
+        ],
+    },
+    Band {
+        name: "33cm",
+        edges: [None, Some((902_000_000, 928_000_000)), None],
+    },
+    everywhere("23cm", 1_240_000_000, 1_300_000_000),
+    everywhere("13cm", 2_300_000_000, 2_450_000_000),
+    Band {
+        name: "9cm",
+        edges: [
+            Some((3_400_000_000, 3_475_000_000)),
+            Some((3_300_000_000, 3_500_000_000)),
+            Some((3_300_000_000, 3_500_000_000)),
+        ],
+    },
+    everywhere("6cm", 5_650_000_000, 5_925_000_000),
+    everywhere("3cm", 10_000_000_000, 10_500_000_000),
+    everywhere("1.2cm", 24_000_000_000, 24_250_000_000),
+];
+
+/// Name of the amateur band a frequency is in, in `region`
+pub fn amateur_band(hz: Frequency, region: BandPlanRegion) -> Option<&'static str> {
+    BAND_PLAN
+        .iter()
+        .find(|band| {
+            band.edges[region.index()].is_some_and(|(lo, hi)| (lo..=hi).contains(&hz.hz()))
+        })
+        .map(|band| band.name)
+}
+
+/// Name of the band a frequency is in, in any region
+pub fn band_name(hz: Frequency) -> Option<&'static str> {
+    BAND_PLAN
+        .iter()
+        .find(|band| {
+            band.edges
+                .iter()
+                .flatten()
+                .any(|(lo, hi)| (*lo..=*hi).contains(&hz.hz()))
+        })
+        .map(|band| band.name)
+}
+
+/// Every band name, lowest first
+pub fn band_names() -> impl Iterator<Item = &'static str> {
+    BAND_PLAN.iter().map(|band| band.name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_regional_edges() {
+        let khz = Frequency::from_khz;
+        // 3.9 MHz is phone band in the Americas only
+        assert_eq!(
+            amateur_band(khz(3_900), BandPlanRegion::Region2),
+            Some("80m")
+        );
+        assert_eq!(amateur_band(khz(3_900), BandPlanRegion::Region1), None);
+        assert_eq!(amateur_band(khz(7_250), BandPlanRegion::Region3), None);
+        assert_eq!(amateur_band(khz(1_805), BandPlanRegion::Region1), None);
+        assert_eq!(
+            amateur_band(khz(70_200), BandPlanRegion::Region1),
+            Some("4m")
+        );
+        assert_eq!(amateur_band(khz(70_200), BandPlanRegion::Region2), None);
+        assert_eq!(
+            amateur_band(khz(223_500), BandPlanRegion::Region2),
+            Some("1.25m")
+        );
+
+        for region in BandPlanRegion::ALL {
+            assert_eq!(amateur_band(khz(137), region), Some("2200m"));
+            assert_eq!(amateur_band(khz(475), region), Some("630m"));
+            assert_eq!(amateur_band(khz(1_296_200), region), Some("23cm"));
+            assert_eq!(amateur_band(khz(9_500), region), None);
+        }
+    }
+
+    #[test]
+    fn test_band_name_in_any_region() {
+        assert_eq!(band_name(Frequency::from_khz(70_200)), Some("4m"));
+        assert_eq!(band_name(Frequency::from_khz(3_900)), Some("80m"));
+        assert_eq!(band_name(Frequency::from_khz(9_500)), None);
+        assert!(band_names().any(|name| name == "33cm"));
+    }
+}
@@ -0,0 +1,235 @@
+//! Hands-free bookmarks
+//!
+//! When something odd happens mid-contest the operator's hands are busy, so a
+//! bookmark ("that just happened") can be dropped without touching the
+//! keyboard:
+//!
+//! - **PTT double-tap**: two key-downs on any radio within a short window
+//!   ([`PttDoubleTap`], detected by the mux actor)
+//! - **Serial pin**: a footswitch or button wired to a modem-status line
+//!   (CTS, DSR, DCD or RI) of a spare serial port ([`run_pin_trigger`])
+//!
+//! Either way the actor emits [`crate::MuxEvent::Bookmark`].
+
+use std::collections::HashMap;
+use std::io;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+use tokio_serial::{SerialPort, SerialPortBuilderExt};
+use tracing::info;
+
+use crate::actor::MuxActorCommand;
+use crate::client::MuxClient;
+use crate::state::RadioHandle;
+
+/// Default window for a PTT double-tap
+pub const DEFAULT_DOUBLE_TAP_MS: u64 = 500;
+
+/// How often a trigger pin is sampled
+pub(crate) const PIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Samples a pin must hold a new level before it counts (contact bounce)
+const PIN_DEBOUNCE_SAMPLES: u8 = 3;
+
+/// Detects two PTT key-downs in quick succession
+#[derive(Debug, Default)]
+pub struct PttDoubleTap {
+    window: Option<Duration>,
+    last_press: HashMap<RadioHandle, Instant>,
+}
+
+impl PttDoubleTap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the double-tap window (None disables detection)
+    pub fn set_window(&mut self, window: Option<Duration>) {
+        self.window = window;
+        self.last_press.clear();
+    }
+
+    /// Record a PTT edge; true when it completes a double-tap
+    pub fn on_ptt(&mut self, handle: RadioHandle, active: bool, now: Instant) -> bool {
+        let Some(window) = self.window else {
+            return false;
+        };
+        if !active {
+            return false;
+        }
+        match self.last_press.remove(&handle) {
+            Some(prev) if now.duration_since(prev) <= window => true,
+            _ => {
+                self.last_press.insert(handle, now);
+                false
+            }
+        }
+    }
+}
+
+/// Modem-status line a footswitch can be wired to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SerialPin {
+    /// Clear To Send
+    #[default]
+    Cts,
+    /// Data Set Ready
+    Dsr,
+    /// Data Carrier Detect
+    Dcd,
+    /// Ring Indicator
+    Ri,
+}
+
+impl SerialPin {
+    /// All pins, for selection lists
+    pub const ALL: [SerialPin; 4] = [Self::Cts, Self::Dsr, Self::Dcd, Self::Ri];
+
+    /// Short display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Cts => "CTS",
+            Self::Dsr => "DSR",
+            Self::Dcd => "DCD",
+            Self::Ri => "RI",
+        }
+    }
+
+    pub(crate) fn read(&self, port: &mut dyn SerialPort) -> tokio_serial::Result<bool> {
+        match self {
+            Self::Cts => port.read_clear_to_send(),
+            Self::Dsr => port.read_data_set_ready(),
+            Self::Dcd => port.read_carrier_detect(),
+            Self::Ri => port.read_ring_indicator(),
+        }
+    }
+}
+
+/// Debounced edge detector for a sampled pin
+#[derive(Debug, Default)]
+pub(crate) struct PinEdge {
+    level: Option<bool>,
+    pending: Option<(bool, u8)>,
+}
+
+impl PinEdge {
+    /// Feed a sample; true when the pin has settled high after being low
+    fn sample(&mut self, high: bool) -> bool {
+        self.change(high) == Some(true)
+    }
+
+    /// Feed a sample; the new level when the pin has settled at one
+    pub(crate) fn change(&mut self, high: bool) -> Option<bool> {
+        let Some(level) = self.level else {
+            // The first sample sets the idle level without triggering
+            self.level = Some(high);
+            return None;
+        };
+        if high == level {
+            self.pending = None;
+            return None;
+        }
+        let count = match self.pending {
+            Some((to, n)) if to == high => n + 1,
+            _ => 1,
+        };
+        if count < PIN_DEBOUNCE_SAMPLES {
+            self.pending = Some((high, count));
+            return None;
+        }
+        self.pending = None;
+        self.level = Some(high);
+        Some(high)
+    }
+}
+
+/// Watch a pin on a spare serial port and bookmark each time it is asserted
+///
+/// Runs until the port fails or the actor goes away.
+pub async fn run_pin_trigger(port_name: &str, pin: SerialPin, mux: MuxClient) -> io::Result<()> {
+    let mut port = tokio_serial::new(port_name, 9600).open_native_async()?;
+    info!(
+        "Watching {} on {} for bookmark triggers",
+        pin.name(),
+        port_name
+    );
+
+    let mut edge = PinEdge::default();
+    let mut interval = tokio::time::interval(PIN_POLL_INTERVAL);
+    loop {
+        interval.tick().await;
+        if edge.sample(pin.read(&mut port)?) {
+            let source = format!("{} on {}", pin.name(), port_name);
+            if mux
+                .send(MuxActorCommand::AddBookmark { source })
+                .await
+                .is_err()
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_double_tap_within_window() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let radio = RadioHandle(1);
+        let mut tap = PttDoubleTap::new();
+
+        // Disabled by default
+        assert!(!tap.on_ptt(radio, true, t0));
+        assert!(!tap.on_ptt(radio, true, t0 + ms(100)));
+
+        tap.set_window(Some(ms(500)));
+        assert!(!tap.on_ptt(radio, true, t0));
+        assert!(!tap.on_ptt(radio, false, t0 + ms(100)));
+        assert!(tap.on_ptt(radio, true, t0 + ms(400)));
+
+        // A third press starts a new pair
+        assert!(!tap.on_ptt(radio, true, t0 + ms(600)));
+
+        // Too slow
+        assert!(!tap.on_ptt(radio, true, t0 + ms(1200)));
+
+        // Presses on different radios don't pair up
+        let other = RadioHandle(2);
+        assert!(!tap.on_ptt(other, true, t0 + ms(1300)));
+        assert!(tap.on_ptt(radio, true, t0 + ms(1400)));
+    }
+
+    #[test]
+    fn test_pin_edge_is_debounced() {
+        let mut edge = PinEdge::default();
+        // Idle high doesn't trigger
+        assert!(!edge.sample(true));
+        let mut edge = PinEdge::default();
+        assert!(!edge.sample(false));
+
+        // Bounce shorter than the debounce doesn't count
+        assert!(!edge.sample(true));
+        assert!(!edge.sample(false));
+
+        let fired: Vec<bool> = [true, true, true, true, false, false, false]
+            .into_iter()
+            .map(|s| edge.sample(s))
+            .collect();
+        assert_eq!(fired.iter().filter(|f| **f).count(), 1);
+        assert!(fired[2]);
+
+        // Releases are reported as changes too
+        let mut edge = PinEdge::default();
+        let changes: Vec<_> = [false, true, true, true, false, true, false, false, false]
+            .into_iter()
+            .filter_map(|s| edge.change(s))
+            .collect();
+        assert_eq!(changes, [true, false]);
+    }
+}
@@ -4,7 +4,12 @@
 //! radios to the multiplexer. Both real (COM port) and virtual radios use
 //! these types.
 
+use std::fmt;
+
 use cat_protocol::{Protocol, RadioModel};
+use serde::{Deserialize, Serialize};
+
+use crate::monitor::MonitorConfig;
 
 /// Prefix for virtual/simulated radio port names
 pub const VIRTUAL_PORT_PREFIX: &str = "VSIM:";
@@ -24,9 +29,92 @@ pub fn sim_id_from_port(port_name: &str) -> Option<&str> {
     port_name.strip_prefix(VIRTUAL_PORT_PREFIX)
 }
 
+/// Prefix for channels identified by their USB adapter's serial number
+const USB_PREFIX: &str = "usb:";
+
+/// Prefix for channels identified by serial port path
+const PORT_PREFIX: &str = "port:";
+
+/// Persistent identifier for a radio channel
+///
+/// Unlike [`RadioHandle`](crate::RadioHandle), which is assigned per session,
+/// a channel ID survives restarts and is what settings, events and UI maps
+/// key radios by. It serializes as a plain string:
+///
+/// - `VSIM:<sim_id>` for virtual radios (the same as their port name)
+/// - `usb:<serial>` for radios behind a USB adapter with a serial number,
+///   `usb:<serial>:<interface>` when the adapter has several ports
+/// - `port:<path>` for anything else
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ChannelId(String);
+
+impl ChannelId {
+    /// ID for a virtual radio
+    pub fn virtual_radio(sim_id: &str) -> Self {
+        Self(virtual_port_name(sim_id))
+    }
+
+    /// ID for a radio behind a USB adapter with a serial number
+    ///
+    /// The ports of a multi-port adapter share its serial number, so the
+    /// interface is part of the ID when it is known.
+    pub fn usb(serial: &str, interface: Option<u8>) -> Self {
+        match interface {
+            Some(interface) => Self(format!("{}{}:{}", USB_PREFIX, serial, interface)),
+            None => Self(format!("{}{}", USB_PREFIX, serial)),
+        }
+    }
+
+    /// ID for a radio known only by its serial port path
+    pub fn port(path: &str) -> Self {
+        Self(format!("{}{}", PORT_PREFIX, path))
+    }
+
+    /// ID for a serial port radio, preferring the adapter serial over the path
+    ///
+    /// Virtual port names map to virtual radio IDs.
+    pub fn for_serial_port(
+        port: &str,
+        usb_serial: Option<&str>,
+        usb_interface: Option<u8>,
+    ) -> Self {
+        if let Some(sim_id) = sim_id_from_port(port) {
+            return Self::virtual_radio(sim_id);
+        }
+        match usb_serial.filter(|s| !s.is_empty()) {
+            Some(serial) => Self::usb(serial, usb_interface),
+            None => Self::port(port),
+        }
+    }
+
+    /// The serialized form
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Whether this identifies a virtual radio
+    pub fn is_virtual(&self) -> bool {
+        is_virtual_port(&self.0)
+    }
+
+    /// Simulation ID for virtual radios
+    pub fn sim_id(&self) -> Option<&str> {
+        sim_id_from_port(&self.0)
+    }
+}
+
+impl fmt::Display for ChannelId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 /// Metadata for a connected radio channel
 #[derive(Debug, Clone)]
 pub struct RadioChannelMeta {
+    /// Persistent channel identifier
+    pub channel_id: ChannelId,
     /// Protocol used by this radio
     pub protocol: Protocol,
     /// Identified radio model (if known)
@@ -37,6 +125,13 @@ pub struct RadioChannelMeta {
     pub display_name: String,
     /// CI-V address (for Icom radios)
     pub civ_address: Option<u8>,
+    /// Receive-only monitor channel, never the active radio (see [`crate::monitor`])
+    pub monitor: Option<MonitorConfig>,
+    /// Added to the radio's dial to get the on-air frequency (see [`crate::link`])
+    pub frequency_offset_hz: i64,
+    /// An Elecraft KAT500 tuner shares the radio's line and is band-switched
+    /// with it (`~BN`)
+    pub kat500: bool,
 }
 
 impl RadioChannelMeta {
@@ -48,25 +143,57 @@ impl RadioChannelMeta {
         civ_address: Option<u8>,
     ) -> Self {
         Self {
+            channel_id: ChannelId::for_serial_port(&port_name, None, None),
             protocol,
             model_info: None,
             port_name: Some(port_name),
             display_name,
             civ_address,
+            monitor: None,
+            frequency_offset_hz: 0,
+            kat500: false,
         }
     }
 
     /// Create metadata for a virtual radio
     pub fn new_virtual(display_name: String, sim_id: String, protocol: Protocol) -> Self {
         Self {
+            channel_id: ChannelId::virtual_radio(&sim_id),
             protocol,
             model_info: None,
             port_name: Some(virtual_port_name(&sim_id)),
             display_name,
             civ_address: None,
+            monitor: None,
+            frequency_offset_hz: 0,
+            kat500: false,
         }
     }
 
+    /// Override the channel ID (e.g. one keyed by USB serial)
+    pub fn with_channel_id(mut self, channel_id: ChannelId) -> Self {
+        self.channel_id = channel_id;
+        self
+    }
+
+    /// Register as a receive-only monitor channel
+    pub fn with_monitor(mut self, monitor: MonitorConfig) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
+    /// Offset the radio's dial, e.g. for a transverter or calibration
+    pub fn with_frequency_offset(mut self, offset_hz: i64) -> Self {
+        self.frequency_offset_hz = offset_hz;
+        self
+    }
+
+    /// Band-switch a KAT500 tuner on the radio's line along with the radio
+    pub fn with_kat500(mut self) -> Self {
+        self.kat500 = true;
+        self
+    }
+
     /// Check if this is a virtual/simulated radio
     pub fn is_simulated(&self) -> bool {
         self.port_name
@@ -121,6 +248,37 @@ mod tests {
         assert!(meta.is_simulated());
         assert_eq!(meta.port_name, Some("VSIM:sim-001".to_string()));
         assert_eq!(meta.sim_id(), Some("sim-001"));
+        assert_eq!(meta.channel_id, ChannelId::virtual_radio("sim-001"));
+    }
+
+    #[test]
+    fn test_channel_id_forms() {
+        assert_eq!(
+            ChannelId::for_serial_port("/dev/ttyUSB0", Some("A10KXYZ"), None).as_str(),
+            "usb:A10KXYZ"
+        );
+        assert_eq!(
+            ChannelId::for_serial_port("COM3", Some(""), Some(0)).as_str(),
+            "port:COM3"
+        );
+        // The two ports of a dual adapter
+        assert_ne!(
+            ChannelId::for_serial_port("/dev/ttyUSB0", Some("FT4232"), Some(0)),
+            ChannelId::for_serial_port("/dev/ttyUSB1", Some("FT4232"), Some(1))
+        );
+        assert_eq!(
+            ChannelId::for_serial_port("/dev/ttyUSB1", Some("FT4232"), Some(1)).as_str(),
+            "usb:FT4232:1"
+        );
+
+        let id = ChannelId::for_serial_port("VSIM:sim-002", None, None);
+        assert!(id.is_virtual());
+        assert_eq!(id.sim_id(), Some("sim-002"));
+
+        // Serializes as a bare string so it can key settings maps
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, "\"VSIM:sim-002\"");
+        assert_eq!(serde_json::from_str::<ChannelId>(&json).unwrap(), id);
     }
 
     #[test]
@@ -0,0 +1,198 @@
+//! CI-V "USB Echo Back" detection
+//!
+//! Icom radios can repeat every CI-V frame they receive over USB back to the
+//! sender ("CI-V USB Echo Back" in the radio's menu), the way a single-wire
+//! CI-V bus does by nature. Read as the radio's own reports, those echoes
+//! cause subtle trouble: the echo of a set-frequency command reads like the
+//! radio reporting the frequency we asked for, even if it refused it.
+//!
+//! [`EchoDetector`] watches a radio's reads for the frames the mux wrote to
+//! it. A frame that comes back is dropped before it is parsed. After a few
+//! writes in a row have (or haven't) come back, the detector settles on
+//! whether echo back is on; while it is, any frame from the controller
+//! address is treated as an echo, including ones the detector lost track of.
+//! The desktop app turns an "on" verdict into a recommendation to switch the
+//! setting off ([`echo_back_advice`]).
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use cat_protocol::icom::{CONTROLLER_ADDR, PREAMBLE, TERMINATOR};
+use tokio::time::Instant;
+
+/// Writes in a row that must agree before the verdict changes
+pub const ECHO_SAMPLES: u32 = 3;
+
+/// A write the radio has answered something else this long after, without
+/// echoing it, wasn't echoed (the radio echoes as it receives)
+const ECHO_GRACE: Duration = Duration::from_millis(200);
+
+/// Writes older than this are forgotten unjudged (the radio may be off)
+const SENT_EXPIRY: Duration = Duration::from_secs(2);
+
+/// Most writes waited on at once
+const MAX_PENDING: usize = 16;
+
+/// Works out whether one Icom radio echoes the mux's CI-V frames
+#[derive(Debug, Default)]
+pub struct EchoDetector {
+    /// Frames written and not yet seen again, with when
+    pending: VecDeque<(Vec<u8>, Instant)>,
+    /// Whether the last writes came back, and how many in a row
+    streak: Option<(bool, u32)>,
+    /// Whether echo back is on, once known
+    echo_back: Option<bool>,
+    /// Verdict as of the last [`Self::take_update`]
+    reported: Option<bool>,
+}
+
+impl EchoDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the radio echoes what it's sent, once known
+    pub fn echo_back(&self) -> Option<bool> {
+        self.echo_back
+    }
+
+    /// The verdict, if it changed since the last call
+    pub fn take_update(&mut self) -> Option<bool> {
+        if self.echo_back == self.reported {
+            return None;
+        }
+        self.reported = self.echo_back;
+        self.echo_back
+    }
+
+    /// Note data the mux wrote to the radio
+    pub fn sent(&mut self, data: &[u8], now: Instant) {
+        self.expire(now);
+        for frame in civ_frames(data).filter(|frame| from_controller(frame)) {
+            if self.pending.len() == MAX_PENDING {
+                self.pending.pop_front();
+            }
+            self.pending.push_back((frame.to_vec(), now));
+        }
+    }
+
+    /// Check a frame read from the radio; `true` if it is an echo to drop
+    pub fn received(&mut self, frame: &[u8], now: Instant) -> bool {
+        self.expire(now);
+        if let Some(pos) = self.pending.iter().position(|(sent, _)| sent == frame) {
+            self.pending.remove(pos);
+            self.observe(true);
+            return true;
+        }
+        if from_controller(frame) {
+            // Not one we're waiting on: another controller, or an echo we lost
+            return self.echo_back == Some(true);
+        }
+
+        // The radio is talking, so writes from well before this should
+        // already have come back
+        while let Some((_, at)) = self.pending.front() {
+            if now.saturating_duration_since(*at) < ECHO_GRACE {
+                break;
+            }
+            self.pending.pop_front();
+            self.observe(false);
+        }
+        false
+    }
+
+    fn observe(&mut self, echoed: bool) {
+        let count = match self.streak {
+            Some((last, count)) if last == echoed => count + 1,
+            _ => 1,
+        };
+        self.streak = Some((echoed, count));
+        if count >= ECHO_SAMPLES {
+            self.echo_back = Some(echoed);
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((_, at)) = self.pending.front() {
+            if now.saturating_duration_since(*at) < SENT_EXPIRY {
+                break;
+            }
+            self.pending.pop_front();
+        }
+    }
+}
+
+/// What to change on the radio when it echoes
+pub fn echo_back_advice(model: &str) -> String {
+    format!(
+        "The {} is echoing CI-V commands back over USB. Turn off MENU > SET > Connectors > \
+         CI-V > CI-V USB Echo Back; until then the echoes are filtered out.",
+        model
+    )
+}
+
+/// Whole CI-V frames in `data`, skipping anything between them
+fn civ_frames(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    data.split_inclusive(|&b| b == TERMINATOR)
+        .filter_map(|chunk| {
+            let start = chunk.windows(2).position(|w| w == [PREAMBLE, PREAMBLE])?;
+            let frame = &chunk[start..];
+            (frame.last() == Some(&TERMINATOR)).then_some(frame)
+        })
+}
+
+/// Whether a frame's source is the controller address
+fn from_controller(frame: &[u8]) -> bool {
+    frame.get(3) == Some(&CONTROLLER_ADDR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SET_FREQ: [u8; 11] = [
+        0xFE, 0xFE, 0x94, 0xE0, 0x05, 0x00, 0x40, 0x07, 0x14, 0x00, 0xFD,
+    ];
+    const READ_FREQ: [u8; 6] = [0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD];
+    const OK: [u8; 6] = [0xFE, 0xFE, 0xE0, 0x94, 0xFB, 0xFD];
+
+    #[test]
+    fn test_echoes_are_dropped_and_detected() {
+        let mut detector = EchoDetector::new();
+        let start = Instant::now();
+
+        for i in 0..ECHO_SAMPLES {
+            let at = start + Duration::from_millis(500 * i as u64);
+            detector.sent(&SET_FREQ, at);
+            assert!(detector.received(&SET_FREQ, at + Duration::from_millis(5)));
+            assert!(!detector.received(&OK, at + Duration::from_millis(10)));
+        }
+        assert_eq!(detector.echo_back(), Some(true));
+        assert_eq!(detector.take_update(), Some(true));
+        assert_eq!(detector.take_update(), None);
+
+        // Once known, a controller frame nobody is waiting on is dropped too
+        assert!(detector.received(&READ_FREQ, start + Duration::from_secs(2)));
+    }
+
+    #[test]
+    fn test_unechoed_writes_turn_the_verdict_off() {
+        let mut detector = EchoDetector::new();
+        let start = Instant::now();
+
+        // Answers arriving right after a write say nothing yet
+        detector.sent(&[READ_FREQ, READ_FREQ].concat(), start);
+        assert!(!detector.received(&OK, start + Duration::from_millis(20)));
+        assert_eq!(detector.echo_back(), None);
+
+        for i in 1..=ECHO_SAMPLES as u64 {
+            let at = start + Duration::from_millis(500 * i);
+            assert!(!detector.received(&OK, at));
+            detector.sent(&READ_FREQ, at);
+        }
+        assert_eq!(detector.echo_back(), Some(false));
+        assert_eq!(detector.take_update(), Some(false));
+        // A controller frame we didn't write isn't dropped without echo back
+        assert!(!detector.received(&SET_FREQ, start + Duration::from_secs(3)));
+    }
+}
@@ -0,0 +1,377 @@
+//! Typed client for the multiplexer actor
+//!
+//! [`MuxClient`] is how everything outside the actor talks to it: the
+//! desktop app, the rigctl server, and tests. It owns the command and
+//! event channels, and turns the actor's oneshot replies into [`Reply`]
+//! futures, so callers never build a `response` channel by hand.
+//!
+//! Commands are queued without waiting (`try_send`), so a query sent after
+//! a command is always handled after it, even from synchronous UI code. A
+//! full or closed channel is reported as a [`ClientError`] instead of
+//! leaving the caller waiting on a reply that will never come. Tasks that
+//! can wait use [`MuxClient::send`], and servers answering a client wait
+//! for room up to [`SERVER_SEND_TIMEOUT`].
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use cat_protocol::{Frequency, RadioRequest};
+use thiserror::Error;
+use tokio::runtime::Handle;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::actor::{MuxActorCommand, RadioStateSummary};
+use crate::async_radio::RadioTaskCommand;
+use crate::band_plan::BandPlanRegion;
+use crate::channel::RadioChannelMeta;
+use crate::events::MuxEvent;
+use crate::freq_entry::FrequencyEntryError;
+use crate::state::{AmplifierEmulatedState, RadioHandle};
+use crate::supervisor::{run_supervised_mux, WatchdogConfig};
+use crate::switch_audit::SwitchRecord;
+use crate::tx_stats::TxBandStats;
+
+/// How long a server waits for room in a full command queue before giving
+/// its client an error
+const SERVER_SEND_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Why a command didn't reach the actor, or its reply never came back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ClientError {
+    /// The command queue is full; the command was dropped
+    #[error("mux command queue is full")]
+    Full,
+
+    /// The actor has stopped
+    #[error("mux actor is not running")]
+    Closed,
+
+    /// The actor dropped the command without replying
+    #[error("mux actor did not reply")]
+    NoReply,
+}
+
+impl<T> From<mpsc::error::TrySendError<T>> for ClientError {
+    fn from(e: mpsc::error::TrySendError<T>) -> Self {
+        match e {
+            mpsc::error::TrySendError::Full(_) => ClientError::Full,
+            mpsc::error::TrySendError::Closed(_) => ClientError::Closed,
+        }
+    }
+}
+
+impl<T> From<mpsc::error::SendTimeoutError<T>> for ClientError {
+    fn from(e: mpsc::error::SendTimeoutError<T>) -> Self {
+        match e {
+            mpsc::error::SendTimeoutError::Timeout(_) => ClientError::Full,
+            mpsc::error::SendTimeoutError::Closed(_) => ClientError::Closed,
+        }
+    }
+}
+
+/// The actor's answer to a query, once it arrives
+#[derive(Debug)]
+#[must_use = "a reply does nothing unless awaited"]
+pub struct Reply<T> {
+    rx: oneshot::Receiver<T>,
+}
+
+impl<T> Future for Reply<T> {
+    type Output = Result<T, ClientError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx)
+            .poll(cx)
+            .map(|r| r.map_err(|_| ClientError::NoReply))
+    }
+}
+
+/// Cloneable handle to a running multiplexer actor
+#[derive(Debug, Clone)]
+pub struct MuxClient {
+    cmd_tx: mpsc::Sender<MuxActorCommand>,
+    event_tx: mpsc::Sender<MuxEvent>,
+}
+
+impl MuxClient {
+    /// Create a client and the receiving ends of its channels
+    ///
+    /// Whoever holds the command receiver plays the actor; tests use this
+    /// to stand in for it.
+    pub fn channel(
+        capacity: usize,
+    ) -> (
+        Self,
+        mpsc::Receiver<MuxActorCommand>,
+        mpsc::Receiver<MuxEvent>,
+    ) {
+        let (cmd_tx, cmd_rx) = mpsc::channel(capacity);
+        let (event_tx, event_rx) = mpsc::channel(capacity);
+        (Self { cmd_tx, event_tx }, cmd_rx, event_rx)
+    }
+
+    /// Spawn the multiplexer actor on `runtime`, under a watchdog that
+    /// restarts it if it fails
+    ///
+    /// Returns a client for it and the stream of events it emits.
+    pub fn start(runtime: &Handle, capacity: usize) -> (Self, mpsc::Receiver<MuxEvent>) {
+        let (client, cmd_rx, event_rx) = Self::channel(capacity);
+        let event_tx = client.event_tx.clone();
+        let supervisor = runtime.spawn(run_supervised_mux(
+            cmd_rx,
+            event_tx,
+            WatchdogConfig::default(),
+        ));
+        runtime.spawn(async move {
+            // The supervisor only returns once asked to shut down
+            match supervisor.await {
+                Ok(()) => tracing::debug!("Mux actor shut down"),
+                Err(e) => tracing::error!("Mux actor exited unexpectedly: {}", e),
+            }
+        });
+        (client, event_rx)
+    }
+
+    /// Sender for connection tasks that feed the actor commands directly
+    pub(crate) fn command_sender(&self) -> mpsc::Sender<MuxActorCommand> {
+        self.cmd_tx.clone()
+    }
+
+    /// Sender for connection tasks that emit events alongside the actor
+    pub(crate) fn event_sender(&self) -> mpsc::Sender<MuxEvent> {
+        self.event_tx.clone()
+    }
+
+    /// Queue a command without waiting
+    pub fn try_send(&self, cmd: MuxActorCommand) -> Result<(), ClientError> {
+        self.cmd_tx.try_send(cmd).map_err(ClientError::from)
+    }
+
+    /// Queue a command, waiting for room
+    ///
+    /// For background tasks; fails only once the actor has stopped.
+    pub async fn send(&self, cmd: MuxActorCommand) -> Result<(), ClientError> {
+        self.cmd_tx.send(cmd).await.map_err(|_| ClientError::Closed)
+    }
+
+    /// Queue a command, waiting up to `timeout` for room
+    pub async fn send_timeout(
+        &self,
+        cmd: MuxActorCommand,
+        timeout: Duration,
+    ) -> Result<(), ClientError> {
+        self.cmd_tx
+            .send_timeout(cmd, timeout)
+            .await
+            .map_err(ClientError::from)
+    }
+
+    /// Emit an event alongside the actor's (e.g. a connection error),
+    /// waiting for room
+    pub async fn emit(&self, event: MuxEvent) -> Result<(), ClientError> {
+        self.event_tx
+            .send(event)
+            .await
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Queue a command, blocking the thread until there is room
+    ///
+    /// For shutdown paths outside the runtime; panics inside it.
+    pub fn blocking_send(&self, cmd: MuxActorCommand) -> Result<(), ClientError> {
+        self.cmd_tx
+            .blocking_send(cmd)
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Queue a command carrying a oneshot `response`, and await its reply
+    fn query<T>(
+        &self,
+        cmd: impl FnOnce(oneshot::Sender<T>) -> MuxActorCommand,
+    ) -> Result<Reply<T>, ClientError> {
+        let (response, rx) = oneshot::channel();
+        self.try_send(cmd(response))?;
+        Ok(Reply { rx })
+    }
+
+    /// Register a radio; the reply is its handle
+    pub fn register_radio(
+        &self,
+        meta: RadioChannelMeta,
+        cmd_tx: Option<mpsc::Sender<RadioTaskCommand>>,
+    ) -> Result<Reply<RadioHandle>, ClientError> {
+        self.query(|response| MuxActorCommand::RegisterRadio {
+            meta,
+            response,
+            cmd_tx,
+        })
+    }
+
+    /// A radio's state, or None if it isn't registered
+    pub fn radio_state(
+        &self,
+        handle: RadioHandle,
+    ) -> Result<Reply<Option<RadioStateSummary>>, ClientError> {
+        self.query(|response| MuxActorCommand::QueryRadioState { handle, response })
+    }
+
+    /// The active radio and its state, or None if no radio is active
+    pub fn active_radio(
+        &self,
+    ) -> Result<Reply<Option<(RadioHandle, RadioStateSummary)>>, ClientError> {
+        self.query(|response| MuxActorCommand::QueryActiveRadio { response })
+    }
+
+    /// Send a request to the active radio; the reply says whether there was one
+    pub fn control_active_radio(&self, request: RadioRequest) -> Result<Reply<bool>, ClientError> {
+        self.query(|response| MuxActorCommand::ControlActiveRadio { request, response })
+    }
+
+    /// Tune a radio to a typed frequency; the reply says it was sent, or
+    /// why it wasn't
+    ///
+    /// With `transmit`, the frequency must also be in an amateur band in
+    /// `region`.
+    pub fn enter_frequency(
+        &self,
+        handle: RadioHandle,
+        hz: Frequency,
+        transmit: bool,
+        region: BandPlanRegion,
+    ) -> Result<Reply<Result<(), FrequencyEntryError>>, ClientError> {
+        self.query(|response| MuxActorCommand::EnterFrequency {
+            handle,
+            hz,
+            transmit,
+            region,
+            response,
+        })
+    }
+
+    /// What the amp has been told
+    pub fn amp_state(&self) -> Result<Reply<AmplifierEmulatedState>, ClientError> {
+        self.query(|response| MuxActorCommand::QueryAmpState { response })
+    }
+
+    /// The most recent `limit` switch records, oldest first
+    pub fn switch_audit(&self, limit: usize) -> Result<Reply<Vec<SwitchRecord>>, ClientError> {
+        self.query(|response| MuxActorCommand::QuerySwitchAudit { limit, response })
+    }
+
+    /// Transmit statistics for every radio and band
+    pub fn tx_stats(&self) -> Result<Reply<Vec<TxBandStats>>, ClientError> {
+        self.query(|response| MuxActorCommand::QueryTxStats { response })
+    }
+
+    /// Ask the actor to stop
+    pub fn shutdown(&self) -> Result<(), ClientError> {
+        self.try_send(MuxActorCommand::Shutdown)
+    }
+}
+
+/// Where servers get the active radio's state and send it requests
+///
+/// A trait so their protocols can be tested without a running mux.
+pub(crate) trait ActiveRadio {
+    async fn state(&self) -> Option<(RadioHandle, RadioStateSummary)>;
+    async fn control(&self, request: RadioRequest) -> bool;
+}
+
+impl MuxClient {
+    /// Like [`Self::query`], but waits up to [`SERVER_SEND_TIMEOUT`] for room
+    async fn server_query<T>(
+        &self,
+        cmd: impl FnOnce(oneshot::Sender<T>) -> MuxActorCommand,
+    ) -> Result<T, ClientError> {
+        let (response, rx) = oneshot::channel();
+        self.send_timeout(cmd(response), SERVER_SEND_TIMEOUT)
+            .await?;
+        Reply { rx }.await
+    }
+}
+
+impl ActiveRadio for MuxClient {
+    async fn state(&self) -> Option<(RadioHandle, RadioStateSummary)> {
+        self.server_query(|response| MuxActorCommand::QueryActiveRadio { response })
+            .await
+            .ok()
+            .flatten()
+    }
+
+    async fn control(&self, request: RadioRequest) -> bool {
+        self.server_query(|response| MuxActorCommand::ControlActiveRadio { request, response })
+            .await
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::Protocol;
+
+    fn meta() -> RadioChannelMeta {
+        RadioChannelMeta::new_virtual("Test".to_string(), "sim".to_string(), Protocol::Kenwood)
+    }
+
+    #[tokio::test]
+    async fn test_register_and_query() {
+        let (client, _events) = MuxClient::start(&Handle::current(), 16);
+        let handle = client.register_radio(meta(), None).unwrap().await.unwrap();
+        let summary = client.radio_state(handle).unwrap().await.unwrap();
+        assert!(summary.is_some());
+        assert!(client.tx_stats().unwrap().await.unwrap().is_empty());
+        client.shutdown().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_is_reported() {
+        let (client, _cmd_rx, _events) = MuxClient::channel(1);
+        let _first = client.tx_stats().unwrap();
+        assert_eq!(client.tx_stats().unwrap_err(), ClientError::Full);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_server_waits_for_room() {
+        let (client, mut cmd_rx, _events) = MuxClient::channel(1);
+        let _queued = client.tx_stats().unwrap();
+
+        // A busy actor frees the queue in time
+        let server = tokio::spawn({
+            let client = client.clone();
+            async move { client.control(RadioRequest::GetFrequency).await }
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!server.is_finished());
+        drop(cmd_rx.recv().await);
+        match cmd_rx.recv().await {
+            Some(MuxActorCommand::ControlActiveRadio { response, .. }) => {
+                response.send(true).unwrap()
+            }
+            _ => panic!("expected the control request"),
+        }
+        assert!(server.await.unwrap());
+
+        // A stuck one fails it after the timeout
+        let _queued = client.tx_stats().unwrap();
+        assert!(!client.control(RadioRequest::GetFrequency).await);
+    }
+
+    #[tokio::test]
+    async fn test_stopped_actor_is_reported() {
+        let (client, cmd_rx, _events) = MuxClient::channel(4);
+        drop(cmd_rx);
+        assert_eq!(client.amp_state().unwrap_err(), ClientError::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_dropped_reply_is_reported() {
+        let (client, mut cmd_rx, _events) = MuxClient::channel(4);
+        let reply = client.active_radio().unwrap();
+        // Stands in for an actor that drops the command unanswered
+        drop(cmd_rx.recv().await);
+        assert_eq!(reply.await.unwrap_err(), ClientError::NoReply);
+    }
+}
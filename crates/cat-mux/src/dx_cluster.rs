@@ -0,0 +1,384 @@
+//! DX cluster client and spot matching
+//!
+//! A DX cluster is a telnet service that streams spots: "W3LPL heard JA1ABC
+//! on 14025.0". [`run_dx_cluster_client`] logs in with the operator's call,
+//! parses the spot lines and hands each [`DxSpot`] to the mux actor, which
+//! keeps recent ones in a [`SpotCache`] and reports
+//! [`crate::MuxEvent::SpotMatched`] when the active radio lands on (or
+//! leaves) a spotted frequency.
+
+use cat_protocol::Frequency;
+use std::io;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::actor::MuxActorCommand;
+use crate::client::MuxClient;
+
+/// How long a spot stays matchable
+pub const SPOT_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+/// Distance from a spot's frequency that still counts as on it
+pub const SPOT_TOLERANCE_HZ: u64 = 1_000;
+
+/// Most spots kept, unless a memory budget says otherwise
+pub const MAX_SPOTS: usize = 500;
+
+/// Longest line kept while waiting for its newline
+const MAX_LINE_BYTES: usize = 1024;
+
+/// A spot received from a DX cluster
+#[derive(Debug, Clone, PartialEq)]
+pub struct DxSpot {
+    /// Station that was heard
+    pub dx_call: String,
+    /// Spotted frequency in Hz
+    pub frequency_hz: Frequency,
+    /// Station that reported it
+    pub spotter: String,
+    /// Free-text comment (mode, report, ...)
+    pub comment: String,
+    /// When the spot arrived
+    pub received: Instant,
+}
+
+impl DxSpot {
+    /// Parse a cluster spot line
+    ///
+    /// `DX de W3LPL:     14025.0  JA1ABC       CW 599            1234Z`
+    pub fn parse(line: &str, received: Instant) -> Option<Self> {
+        let rest = line.trim().strip_prefix("DX de ")?;
+        let (spotter, rest) = rest.split_once(':')?;
+        let mut fields = rest.split_whitespace();
+        let khz: f64 = fields.next()?.parse().ok()?;
+        let dx_call = fields.next()?.to_string();
+        let mut comment: Vec<&str> = fields.collect();
+        // Drop the trailing UTC time (and a locator some clusters append)
+        if let Some(pos) = comment.iter().rposition(|f| is_spot_time(f)) {
+            comment.truncate(pos);
+        }
+        if !khz.is_finite() || khz <= 0.0 {
+            return None;
+        }
+        Some(Self {
+            dx_call,
+            frequency_hz: Frequency::from_khz_f64(khz)?,
+            spotter: spotter.trim().to_string(),
+            comment: comment.join(" "),
+            received,
+        })
+    }
+
+    /// One-line description for display
+    pub fn summary(&self) -> String {
+        format!(
+            "{} on {:.1} kHz (de {})",
+            self.dx_call,
+            self.frequency_hz.khz(),
+            self.spotter
+        )
+    }
+}
+
+fn is_spot_time(field: &str) -> bool {
+    field.len() == 5 && field.ends_with('Z') && field[..4].bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Recent spots, matched against radio frequencies
+#[derive(Debug)]
+pub struct SpotCache {
+    spots: Vec<DxSpot>,
+    max_spots: usize,
+    max_age: Duration,
+    tolerance_hz: u64,
+}
+
+impl Default for SpotCache {
+    fn default() -> Self {
+        Self {
+            spots: Vec::new(),
+            max_spots: MAX_SPOTS,
+            max_age: SPOT_MAX_AGE,
+            tolerance_hz: SPOT_TOLERANCE_HZ,
+        }
+    }
+}
+
+impl SpotCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a spot, replacing an older spot of the same station near it
+    pub fn insert(&mut self, spot: DxSpot) {
+        let tolerance = self.tolerance_hz;
+        self.spots.retain(|s| {
+            s.dx_call != spot.dx_call || s.frequency_hz.abs_diff(spot.frequency_hz) > tolerance
+        });
+        self.spots.push(spot);
+        if self.spots.len() > self.max_spots {
+            self.spots.remove(0);
+        }
+    }
+
+    /// Keep at most `max_spots`, dropping the oldest
+    pub fn set_max_spots(&mut self, max_spots: usize) {
+        self.max_spots = max_spots.max(1);
+        let excess = self.spots.len().saturating_sub(self.max_spots);
+        self.spots.drain(..excess);
+    }
+
+    /// The freshest spot within tolerance of a frequency, closest first
+    pub fn find(&self, frequency: Frequency, now: Instant) -> Option<&DxSpot> {
+        self.spots
+            .iter()
+            .filter(|s| now.duration_since(s.received) < self.max_age)
+            .filter(|s| s.frequency_hz.abs_diff(frequency) <= self.tolerance_hz)
+            .min_by_key(|s| {
+                (
+                    s.frequency_hz.abs_diff(frequency),
+                    now.duration_since(s.received),
+                )
+            })
+    }
+
+    /// Drop spots past their age
+    pub fn prune(&mut self, now: Instant) {
+        let max_age = self.max_age;
+        self.spots
+            .retain(|s| now.duration_since(s.received) < max_age);
+    }
+
+    /// Number of spots held
+    pub fn len(&self) -> usize {
+        self.spots.len()
+    }
+
+    /// Whether no spots are held
+    pub fn is_empty(&self) -> bool {
+        self.spots.is_empty()
+    }
+}
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WONT: u8 = 252;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum TelnetState {
+    #[default]
+    Data,
+    Iac,
+    Option(u8),
+    Sub,
+    SubIac,
+}
+
+/// Strips telnet negotiation from a byte stream, refusing every option
+#[derive(Debug, Default)]
+struct TelnetDecoder {
+    state: TelnetState,
+}
+
+impl TelnetDecoder {
+    /// Feed received bytes; returns the text and any replies to send
+    fn feed(&mut self, data: &[u8]) -> (Vec<u8>, Vec<u8>) {
+        let mut text = Vec::with_capacity(data.len());
+        let mut replies = Vec::new();
+        for &b in data {
+            self.state = match (self.state, b) {
+                (TelnetState::Data, IAC) => TelnetState::Iac,
+                (TelnetState::Data, _) => {
+                    text.push(b);
+                    TelnetState::Data
+                }
+                (TelnetState::Iac, IAC) => {
+                    text.push(IAC);
+                    TelnetState::Data
+                }
+                (TelnetState::Iac, WILL | WONT | DO | DONT) => TelnetState::Option(b),
+                (TelnetState::Iac, SB) => TelnetState::Sub,
+                (TelnetState::Iac, _) => TelnetState::Data,
+                (TelnetState::Option(cmd), option) => {
+                    match cmd {
+                        WILL => replies.extend_from_slice(&[IAC, DONT, option]),
+                        DO => replies.extend_from_slice(&[IAC, WONT, option]),
+                        _ => {}
+                    }
+                    TelnetState::Data
+                }
+                (TelnetState::Sub, IAC) => TelnetState::SubIac,
+                (TelnetState::Sub, _) => TelnetState::Sub,
+                (TelnetState::SubIac, SE) => TelnetState::Data,
+                (TelnetState::SubIac, _) => TelnetState::Sub,
+            };
+        }
+        (text, replies)
+    }
+}
+
+/// Whether a partial line is the cluster asking for a callsign
+fn is_login_prompt(line: &str) -> bool {
+    let line = line.trim_end().to_ascii_lowercase();
+    line.ends_with("login:") || line.ends_with("call:") || line.ends_with("callsign:")
+}
+
+/// Connect to a DX cluster and pass its spots to the mux actor
+///
+/// Logs in with `callsign` when prompted and runs until the cluster closes
+/// the connection, the actor goes away, or an I/O error occurs.
+pub async fn run_dx_cluster_client(
+    address: &str,
+    callsign: &str,
+    mux: MuxClient,
+) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address).await?;
+    info!("Connected to DX cluster {}", address);
+
+    let mut telnet = TelnetDecoder::default();
+    let mut line = Vec::new();
+    let mut logged_in = false;
+    let mut buf = [0u8; 2048];
+
+    loop {
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            info!("DX cluster {} closed the connection", address);
+            return Ok(());
+        }
+        let (text, replies) = telnet.feed(&buf[..n]);
+        if !replies.is_empty() {
+            stream.write_all(&replies).await?;
+        }
+
+        for b in text {
+            if b != b'\n' {
+                if b != b'\r' && line.len() < MAX_LINE_BYTES {
+                    line.push(b);
+                }
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).into_owned();
+            line.clear();
+            if let Some(spot) = DxSpot::parse(&text, Instant::now()) {
+                debug!("DX spot: {}", spot.summary());
+                if mux.send(MuxActorCommand::AddDxSpot { spot }).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+
+        // Prompts come without a newline
+        if !logged_in && is_login_prompt(&String::from_utf8_lossy(&line)) {
+            stream
+                .write_all(format!("{}\r\n", callsign.trim()).as_bytes())
+                .await?;
+            line.clear();
+            logged_in = true;
+            info!("Logged in to DX cluster as {}", callsign.trim());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spot_line() {
+        let now = Instant::now();
+        let spot = DxSpot::parse(
+            "DX de W3LPL:     14025.0  JA1ABC       CW 599 up 1                1234Z",
+            now,
+        )
+        .unwrap();
+        assert_eq!(spot.dx_call, "JA1ABC");
+        assert_eq!(spot.frequency_hz, Frequency::from_hz(14_025_000));
+        assert_eq!(spot.spotter, "W3LPL");
+        assert_eq!(spot.comment, "CW 599 up 1");
+        assert_eq!(spot.summary(), "JA1ABC on 14025.0 kHz (de W3LPL)");
+
+        let skimmer = DxSpot::parse(
+            "DX de DK9IP-#:    7003.2  OH2BH        CW    22 dB  25 WPM  CQ    0959Z JO31",
+            now,
+        )
+        .unwrap();
+        assert_eq!(skimmer.frequency_hz, Frequency::from_hz(7_003_200));
+        assert_eq!(skimmer.comment, "CW 22 dB 25 WPM CQ");
+
+        assert!(DxSpot::parse("WWV de W0MU <18>:   SFI=150, A=5", now).is_none());
+        assert!(DxSpot::parse("DX de N0CALL: abc JA1ABC", now).is_none());
+    }
+
+    #[test]
+    fn test_cache_matches_nearby_fresh_spots() {
+        let t0 = Instant::now();
+        let spot = |call: &str, hz, at| DxSpot {
+            dx_call: call.to_string(),
+            frequency_hz: hz,
+            spotter: "W3LPL".to_string(),
+            comment: String::new(),
+            received: at,
+        };
+        let mut cache = SpotCache::new();
+        cache.insert(spot("JA1ABC", Frequency::from_hz(14_025_000), t0));
+        cache.insert(spot("VK2XYZ", Frequency::from_hz(14_025_800), t0));
+
+        assert_eq!(
+            cache
+                .find(Frequency::from_hz(14_025_300), t0)
+                .unwrap()
+                .dx_call,
+            "JA1ABC"
+        );
+        assert_eq!(
+            cache
+                .find(Frequency::from_hz(14_025_700), t0)
+                .unwrap()
+                .dx_call,
+            "VK2XYZ"
+        );
+        assert!(cache.find(Frequency::from_hz(14_030_000), t0).is_none());
+
+        // A re-spot of the same station near the old one replaces it
+        cache.insert(spot(
+            "JA1ABC",
+            Frequency::from_hz(14_025_100),
+            t0 + Duration::from_secs(60),
+        ));
+        assert_eq!(cache.len(), 2);
+
+        // Old spots stop matching
+        let later = t0 + SPOT_MAX_AGE;
+        assert!(cache.find(Frequency::from_hz(14_026_500), later).is_none());
+        assert!(cache.find(Frequency::from_hz(14_025_100), later).is_some());
+        cache.prune(later);
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_telnet_negotiation_is_refused_and_stripped() {
+        let mut telnet = TelnetDecoder::default();
+        let (text, replies) = telnet.feed(&[IAC, DO, 24, b'h', b'i', IAC, WILL, 1]);
+        assert_eq!(text, b"hi");
+        assert_eq!(replies, vec![IAC, WONT, 24, IAC, DONT, 1]);
+
+        // Subnegotiation split across reads
+        let (text, _) = telnet.feed(&[IAC, SB, 31, 0]);
+        assert!(text.is_empty());
+        let (text, _) = telnet.feed(&[80, IAC, SE, b'!']);
+        assert_eq!(text, b"!");
+
+        assert!(is_login_prompt("Please enter your call: "));
+        assert!(is_login_prompt("login:"));
+        assert!(!is_login_prompt("Welcome"));
+    }
+}
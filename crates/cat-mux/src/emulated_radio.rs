@@ -0,0 +1,495 @@
+//! Emulated radio for single-radio software ("reverse mode")
+//!
+//! Loggers and contest programs that can only talk to one radio can connect
+//! to an emulated Kenwood TS-2000 or Icom CI-V rig instead, on a TCP port or
+//! one end of a virtual serial pair. It answers from whichever radio is
+//! active, much as the amplifier is answered, and passes frequency, mode and
+//! PTT changes on to that radio. The program follows the active radio
+//! without knowing there is more than one.
+//!
+//! The emulated rig has a single VFO and no auto-information: clients poll.
+
+use std::io;
+
+use cat_protocol::icom::{
+    operating_mode_to_civ_data, CivCodec, CivCommand, CivCommandType, BROADCAST_ADDR,
+};
+use cat_protocol::kenwood::{KenwoodCodec, KenwoodCommand};
+use cat_protocol::{EncodeCommand, Frequency, ModeMap, ProtocolCodec, ToRadioRequest};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinSet;
+use tokio_serial::SerialPortBuilderExt;
+use tracing::{debug, info};
+
+use crate::actor::RadioStateSummary;
+use crate::client::{ActiveRadio, MuxClient};
+
+/// Default listen address for TCP clients (clear of rigctld's 4532 and rotctld's 4533)
+pub const DEFAULT_EMULATED_RADIO_ADDR: &str = "127.0.0.1:4550";
+
+/// Kenwood ID reported to clients (TS-2000, which nearly all software supports)
+const KENWOOD_ID: &str = "019";
+
+/// Kenwood's reply to a command it can't carry out
+const KENWOOD_REJECTED: &[u8] = b"?;";
+
+/// Which radio clients see
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EmulatedPersonality {
+    /// A Kenwood TS-2000
+    #[default]
+    Kenwood,
+    /// An Icom radio at this CI-V address (0x94 for an IC-7300)
+    IcomCiv { address: u8 },
+}
+
+impl EmulatedPersonality {
+    /// Human-readable name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Kenwood => "Kenwood TS-2000",
+            Self::IcomCiv { .. } => "Icom CI-V",
+        }
+    }
+}
+
+/// One client's parser, and how to answer it
+enum Emulator {
+    Kenwood(KenwoodCodec),
+    Civ { codec: CivCodec, address: u8 },
+}
+
+impl Emulator {
+    fn new(personality: EmulatedPersonality) -> Self {
+        match personality {
+            EmulatedPersonality::Kenwood => Self::Kenwood(KenwoodCodec::new()),
+            EmulatedPersonality::IcomCiv { address } => Self::Civ {
+                codec: CivCodec::new(),
+                address,
+            },
+        }
+    }
+
+    /// Take bytes from the client; returns what to send back
+    async fn feed(&mut self, data: &[u8], radio: &impl ActiveRadio) -> Vec<u8> {
+        let mut out = Vec::new();
+        match self {
+            Self::Kenwood(codec) => {
+                codec.push_bytes(data);
+                while let Some(cmd) = codec.next_command() {
+                    out.extend(kenwood_reply(&cmd, radio).await);
+                }
+            }
+            Self::Civ { codec, address } => {
+                codec.push_bytes(data);
+                while let Some((cmd, raw)) = codec.next_command_with_bytes() {
+                    // Frames for other radios on the bus, and our own echo
+                    if cmd.to_addr != *address && cmd.to_addr != BROADCAST_ADDR {
+                        continue;
+                    }
+                    let reply = civ_reply(&cmd, &raw, radio).await;
+                    out.extend(CivCommand::new(cmd.from_addr, *address, reply).encode());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// The active radio's state, if there is one
+async fn active_state(radio: &impl ActiveRadio) -> Option<RadioStateSummary> {
+    radio.state().await.map(|(_, state)| state)
+}
+
+/// A TS-2000 `IF` report of the active radio's state
+///
+/// 38 bytes: frequency, step, RIT offset, RIT/XIT, memory channel, TX,
+/// mode, VFO, scan, split, tone, tone number and shift.
+fn kenwood_info(hz: Frequency, state: &RadioStateSummary) -> Vec<u8> {
+    let mode = state.mode.map_or(2, |m| ModeMap::KENWOOD.code(m));
+    format!(
+        "IF{:011}     +000000000{}{}0000000;",
+        hz.hz(),
+        u8::from(state.ptt),
+        char::from_digit(u32::from(mode), 36)
+            .unwrap_or('2')
+            .to_ascii_uppercase(),
+    )
+    .into_bytes()
+}
+
+/// Answer one Kenwood command
+///
+/// Queries get a report; sets that reach the active radio get nothing, as
+/// on a real rig. Anything that can't be done is answered `?;`.
+async fn kenwood_reply(cmd: &KenwoodCommand, radio: &impl ActiveRadio) -> Vec<u8> {
+    let reply = match cmd {
+        KenwoodCommand::Id(None) => KenwoodCommand::Id(Some(KENWOOD_ID.to_string())),
+        KenwoodCommand::Info(None) => {
+            return match active_state(radio).await {
+                Some(state) => match state.frequency_hz {
+                    Some(hz) => kenwood_info(hz, &state),
+                    None => KENWOOD_REJECTED.to_vec(),
+                },
+                None => KENWOOD_REJECTED.to_vec(),
+            };
+        }
+        // One VFO: B reads the same as A
+        KenwoodCommand::FrequencyA(None) | KenwoodCommand::FrequencyB(None) => {
+            let Some(hz) = active_state(radio).await.and_then(|s| s.frequency_hz) else {
+                return KENWOOD_REJECTED.to_vec();
+            };
+            match cmd {
+                KenwoodCommand::FrequencyB(_) => KenwoodCommand::FrequencyB(Some(hz.hz())),
+                _ => KenwoodCommand::FrequencyA(Some(hz.hz())),
+            }
+        }
+        KenwoodCommand::Mode(None) => {
+            let Some(mode) = active_state(radio).await.and_then(|s| s.mode) else {
+                return KENWOOD_REJECTED.to_vec();
+            };
+            KenwoodCommand::Mode(Some(ModeMap::KENWOOD.code(mode)))
+        }
+        KenwoodCommand::PowerControl(None) => {
+            let Some(watts) = active_state(radio).await.and_then(|s| s.power_watts) else {
+                return KENWOOD_REJECTED.to_vec();
+            };
+            KenwoodCommand::PowerControl(Some(watts))
+        }
+        KenwoodCommand::VfoSelect(None) => KenwoodCommand::VfoSelect(Some(0)),
+        KenwoodCommand::Split(None) => KenwoodCommand::Split(Some(false)),
+        KenwoodCommand::Power(None) => KenwoodCommand::Power(Some(true)),
+        KenwoodCommand::AutoInfo(None) => KenwoodCommand::AutoInfo(Some(false)),
+        KenwoodCommand::ControlBand(None) => KenwoodCommand::ControlBand(Some(0)),
+        KenwoodCommand::TransmitBand(None) => KenwoodCommand::TransmitBand(Some(0)),
+
+        KenwoodCommand::FrequencyA(Some(_))
+        | KenwoodCommand::Mode(Some(_))
+        | KenwoodCommand::Transmit(Some(_))
+        | KenwoodCommand::Receive => {
+            return if radio.control(cmd.to_radio_request()).await {
+                Vec::new()
+            } else {
+                KENWOOD_REJECTED.to_vec()
+            };
+        }
+        // Already so: VFO A, no split, powered, no auto-information
+        KenwoodCommand::VfoSelect(Some(0))
+        | KenwoodCommand::Split(Some(false))
+        | KenwoodCommand::Power(Some(true))
+        | KenwoodCommand::AutoInfo(Some(_)) => return Vec::new(),
+        _ => return KENWOOD_REJECTED.to_vec(),
+    };
+    reply.encode()
+}
+
+/// Answer one CI-V command addressed to us
+///
+/// Queries get a report; sets get OK when they reach the active radio and
+/// NG otherwise, as does anything not understood.
+async fn civ_reply(cmd: &CivCommand, raw: &[u8], radio: &impl ActiveRadio) -> CivCommandType {
+    let data = raw.get(5..raw.len().saturating_sub(1)).unwrap_or_default();
+    let set = |ok: bool| {
+        if ok {
+            CivCommandType::Ok
+        } else {
+            CivCommandType::Ng
+        }
+    };
+
+    match (&cmd.command, data) {
+        (CivCommandType::GetFrequency, _) => {
+            match active_state(radio).await.and_then(|s| s.frequency_hz) {
+                Some(hz) => CivCommandType::FrequencyReport { hz: hz.hz() },
+                None => CivCommandType::Ng,
+            }
+        }
+        (CivCommandType::GetMode, _) => match active_state(radio).await.and_then(|s| s.mode) {
+            Some(mode) => CivCommandType::ModeReport {
+                mode: ModeMap::CIV.code(mode),
+                filter: 1,
+            },
+            None => CivCommandType::Ng,
+        },
+        (CivCommandType::GetPtt, _) => match active_state(radio).await {
+            Some(s) => CivCommandType::PttReport { on: s.ptt },
+            None => CivCommandType::Ng,
+        },
+        // 0x25 0x00 / 0x26 0x00: the selected VFO's frequency and mode
+        (CivCommandType::Unknown { cmd: 0x25, .. }, [0x00]) => {
+            match active_state(radio).await.and_then(|s| s.frequency_hz) {
+                Some(hz) => CivCommandType::SelectedFrequency { hz: hz.hz() },
+                None => CivCommandType::Ng,
+            }
+        }
+        (CivCommandType::Unknown { cmd: 0x26, .. }, [0x00]) => {
+            match active_state(radio).await.and_then(|s| s.mode) {
+                Some(mode) => {
+                    let (mode, data) = operating_mode_to_civ_data(mode);
+                    CivCommandType::SelectedMode {
+                        mode,
+                        data,
+                        filter: 1,
+                    }
+                }
+                None => CivCommandType::Ng,
+            }
+        }
+        // 0x19 0x00: transceiver ID
+        (CivCommandType::Unknown { cmd: 0x19, .. }, [0x00]) => CivCommandType::Unknown {
+            cmd: 0x19,
+            subcmd: Some(0x00),
+            data: vec![cmd.to_addr],
+        },
+        (
+            CivCommandType::SetFrequency { .. }
+            | CivCommandType::SelectedFrequency { .. }
+            | CivCommandType::SetMode { .. }
+            | CivCommandType::SelectedMode { .. }
+            | CivCommandType::SetPtt { .. },
+            _,
+        ) => set(radio.control(cmd.to_radio_request()).await),
+        (CivCommandType::GetSplit, _) => CivCommandType::Split { on: false },
+        // Already so: VFO A, no split, no transceive
+        (CivCommandType::VfoSelect { vfo: 0x00 }, _)
+        | (CivCommandType::Split { on: false }, _)
+        | (CivCommandType::Transceive { .. }, _) => CivCommandType::Ok,
+        _ => CivCommandType::Ng,
+    }
+}
+
+/// Emulate a radio to one client over `io` until it disconnects
+///
+/// Use this for a serial port (e.g. one end of a com0com or socat pair).
+pub async fn serve_emulated_radio<T>(
+    mut io: T,
+    personality: EmulatedPersonality,
+    mux: MuxClient,
+) -> io::Result<()>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let mut emulator = Emulator::new(personality);
+    let mut buf = [0u8; 256];
+    loop {
+        let n = io.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(());
+        }
+        let reply = emulator.feed(&buf[..n], &mux).await;
+        if !reply.is_empty() {
+            io.write_all(&reply).await?;
+        }
+    }
+}
+
+/// Emulate a radio on a serial port until it fails
+pub async fn run_emulated_radio_serial(
+    port_name: &str,
+    baud_rate: u32,
+    personality: EmulatedPersonality,
+    mux: MuxClient,
+) -> io::Result<()> {
+    let port = tokio_serial::new(port_name, baud_rate).open_native_async()?;
+    info!("Emulating a {} on {}", personality.name(), port_name);
+    serve_emulated_radio(port, personality, mux).await
+}
+
+/// Emulate a radio to TCP clients on `listener` until an accept fails
+///
+/// Each client gets its own emulated rig; dropping the returned future
+/// disconnects them all.
+pub async fn run_emulated_radio_server(
+    listener: TcpListener,
+    personality: EmulatedPersonality,
+    mux: MuxClient,
+) -> io::Result<()> {
+    info!(
+        "Emulating a {} on {}",
+        personality.name(),
+        listener.local_addr()?
+    );
+    let mut clients = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (socket, peer) = accepted?;
+                socket.set_nodelay(true)?;
+                info!("Emulated radio client {} connected", peer);
+                let mux = mux.clone();
+                clients.spawn(async move {
+                    match serve_emulated_radio(socket, personality, mux).await {
+                        Ok(()) => info!("Emulated radio client {} disconnected", peer),
+                        Err(e) => debug!("Emulated radio client {} dropped: {}", peer, e),
+                    }
+                });
+            }
+            // Reap finished clients so the set doesn't grow
+            Some(_) = clients.join_next(), if !clients.is_empty() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::MuxActorCommand;
+    use crate::state::RadioHandle;
+    use cat_protocol::{OperatingMode, RadioRequest};
+    use std::sync::Mutex;
+    use tokio::net::TcpStream;
+
+    /// Active radio stand-in that records what it was asked to do
+    struct FakeRadio {
+        state: Option<RadioStateSummary>,
+        sent: Mutex<Vec<RadioRequest>>,
+    }
+
+    impl FakeRadio {
+        fn new(state: Option<RadioStateSummary>) -> Self {
+            Self {
+                state,
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl ActiveRadio for FakeRadio {
+        async fn state(&self) -> Option<(RadioHandle, RadioStateSummary)> {
+            self.state.clone().map(|s| (RadioHandle(1), s))
+        }
+
+        async fn control(&self, request: RadioRequest) -> bool {
+            self.sent.lock().unwrap().push(request);
+            self.state.is_some()
+        }
+    }
+
+    fn on_20m() -> RadioStateSummary {
+        RadioStateSummary {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: Some(OperatingMode::Usb),
+            ptt: false,
+            power_watts: Some(100),
+            last_seen: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_kenwood_replies() {
+        let radio = FakeRadio::new(Some(on_20m()));
+        let mut kenwood = Emulator::new(EmulatedPersonality::Kenwood);
+        assert_eq!(kenwood.feed(b"ID;", &radio).await, b"ID019;");
+        assert_eq!(
+            kenwood.feed(b"FA;MD;FB", &radio).await,
+            b"FA00014074000;MD2;"
+        );
+        assert_eq!(kenwood.feed(b";", &radio).await, b"FB00014074000;");
+        let info = kenwood.feed(b"IF;", &radio).await;
+        assert_eq!(info, b"IF00014074000     +000000000020000000;");
+        assert_eq!(info.len(), 38);
+
+        // Sets are silent when they reach the radio
+        assert!(kenwood
+            .feed(b"FA00007074000;TX1;AI2;", &radio)
+            .await
+            .is_empty());
+        assert_eq!(
+            *radio.sent.lock().unwrap(),
+            vec![
+                RadioRequest::SetFrequency {
+                    hz: Frequency::from_hz(7_074_000)
+                },
+                RadioRequest::SetPtt { active: true },
+            ]
+        );
+        assert_eq!(kenwood.feed(b"FB00007074000;", &radio).await, b"?;");
+    }
+
+    #[tokio::test]
+    async fn test_kenwood_without_active_radio() {
+        let radio = FakeRadio::new(None);
+        let mut kenwood = Emulator::new(EmulatedPersonality::Kenwood);
+        assert_eq!(kenwood.feed(b"ID;", &radio).await, b"ID019;");
+        assert_eq!(kenwood.feed(b"FA;IF;", &radio).await, b"?;?;");
+        assert_eq!(kenwood.feed(b"FA00007074000;", &radio).await, b"?;");
+    }
+
+    #[tokio::test]
+    async fn test_civ_replies() {
+        let radio = FakeRadio::new(Some(on_20m()));
+        let mut civ = Emulator::new(EmulatedPersonality::IcomCiv { address: 0x94 });
+        // Frequency query, answered to the controller that asked
+        assert_eq!(
+            civ.feed(&[0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD], &radio)
+                .await,
+            [0xFE, 0xFE, 0xE0, 0x94, 0x03, 0x00, 0x40, 0x07, 0x14, 0x00, 0xFD]
+        );
+        // PTT read doesn't unkey
+        assert_eq!(
+            civ.feed(&[0xFE, 0xFE, 0x94, 0xE0, 0x1C, 0x00, 0xFD], &radio)
+                .await,
+            [0xFE, 0xFE, 0xE0, 0x94, 0x1C, 0x00, 0x00, 0xFD]
+        );
+        // Another radio's traffic is ignored
+        assert!(civ
+            .feed(&[0xFE, 0xFE, 0xA4, 0xE0, 0x03, 0xFD], &radio)
+            .await
+            .is_empty());
+        // A set is passed on and acknowledged
+        assert_eq!(
+            civ.feed(
+                &[0xFE, 0xFE, 0x94, 0xE0, 0x05, 0x00, 0x40, 0x07, 0x07, 0x00, 0xFD],
+                &radio
+            )
+            .await,
+            [0xFE, 0xFE, 0xE0, 0x94, 0xFB, 0xFD]
+        );
+        assert_eq!(
+            *radio.sent.lock().unwrap(),
+            vec![RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(7_074_000)
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_civ_without_active_radio() {
+        let radio = FakeRadio::new(None);
+        let mut civ = Emulator::new(EmulatedPersonality::IcomCiv { address: 0x94 });
+        assert_eq!(
+            civ.feed(&[0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD], &radio)
+                .await,
+            [0xFE, 0xFE, 0xE0, 0x94, 0xFA, 0xFD]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_answers_over_tcp() {
+        let (mux, mut cmd_rx, _events) = MuxClient::channel(8);
+        // Stands in for the mux actor
+        tokio::spawn(async move {
+            while let Some(cmd) = cmd_rx.recv().await {
+                if let MuxActorCommand::QueryActiveRadio { response } = cmd {
+                    let _ = response.send(Some((RadioHandle(1), on_20m())));
+                }
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(run_emulated_radio_server(
+            listener,
+            EmulatedPersonality::Kenwood,
+            mux,
+        ));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"FA;").await.unwrap();
+        let mut reply = [0u8; 14];
+        client.read_exact(&mut reply).await.unwrap();
+        assert_eq!(&reply, b"FA00014074000;");
+    }
+}
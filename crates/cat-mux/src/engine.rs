@@ -4,14 +4,18 @@
 //! state tracking, and command routing.
 
 use std::collections::HashMap;
-use std::time::{Duration, Instant};
+use std::time::{Duration, SystemTime};
 
-use cat_protocol::{Protocol, RadioResponse};
+use cat_protocol::{Frequency, Protocol, RadioResponse};
 use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
 use tracing::{debug, error, info};
 
+use crate::band_guard::{BandConflict, BandGuard, BandGuardConfig};
 use crate::error::MuxError;
 use crate::state::{AmplifierConfig, RadioHandle, RadioState, SwitchingMode};
+use crate::switch_audit::{LockoutState, OutgoingRadio, SwitchRecord, SwitchRule, SwitchTrigger};
+use crate::transition::TransitionReason;
 use crate::translation::{filter_response_for_amplifier, translate_response, TranslationConfig};
 
 /// Multiplexer configuration
@@ -27,6 +31,11 @@ pub struct MultiplexerConfig {
     pub translation: TranslationConfig,
     /// Radio priority order (handles)
     pub priority_order: Vec<u32>,
+    /// Refuse to auto-switch to a radio that has been silent this long (seconds)
+    ///
+    /// `None` disables the check.
+    #[serde(default)]
+    pub stale_after_secs: Option<u64>,
 }
 
 impl Default for MultiplexerConfig {
@@ -37,6 +46,7 @@ impl Default for MultiplexerConfig {
             amplifier: AmplifierConfig::default(),
             translation: TranslationConfig::default(),
             priority_order: Vec::new(),
+            stale_after_secs: None,
         }
     }
 }
@@ -48,6 +58,12 @@ pub struct Multiplexer {
     next_handle: u32,
     active_radio: Option<RadioHandle>,
     lockout_until: Option<Instant>,
+    /// Why the active radio last changed, until the actor takes it
+    last_switch: Option<SwitchRecord>,
+    /// Radios sharing the active radio's band
+    band_guard: BandGuard,
+    /// A switch held off since the actor last looked (see [`crate::transition`])
+    held_switch: Option<(RadioHandle, TransitionReason)>,
 }
 
 impl Multiplexer {
@@ -64,6 +80,9 @@ impl Multiplexer {
             next_handle: 1,
             active_radio: None,
             lockout_until: None,
+            last_switch: None,
+            band_guard: BandGuard::new(),
+            held_switch: None,
         }
     }
 
@@ -87,10 +106,55 @@ impl Multiplexer {
         self.config.switching_mode
     }
 
+    /// Set the staleness threshold (None disables it)
+    pub fn set_stale_after_secs(&mut self, secs: Option<u64>) {
+        self.config.stale_after_secs = secs;
+    }
+
+    /// Check whether a radio has been silent longer than the staleness threshold
+    ///
+    /// Always false when no threshold is configured.
+    pub fn is_radio_stale(&self, handle: RadioHandle) -> bool {
+        let Some(max_age) = self.stale_threshold() else {
+            return false;
+        };
+        self.radios
+            .get(&handle)
+            .is_some_and(|r| r.is_stale(max_age))
+    }
+
+    fn stale_threshold(&self) -> Option<Duration> {
+        self.config.stale_after_secs.map(Duration::from_secs)
+    }
+
     /// Add a radio to the multiplexer
     pub fn add_radio(&mut self, name: String, port: String, protocol: Protocol) -> RadioHandle {
         let handle = RadioHandle(self.next_handle);
-        self.next_handle += 1;
+        self.insert_radio(handle, name, port, protocol);
+        handle
+    }
+
+    /// Add a radio back under the handle it had (the actor restarting)
+    ///
+    /// Radios added later get handles after it.
+    pub fn restore_radio(
+        &mut self,
+        handle: RadioHandle,
+        name: String,
+        port: String,
+        protocol: Protocol,
+    ) {
+        self.insert_radio(handle, name, port, protocol);
+    }
+
+    fn insert_radio(
+        &mut self,
+        handle: RadioHandle,
+        name: String,
+        port: String,
+        protocol: Protocol,
+    ) {
+        self.next_handle = self.next_handle.max(handle.0 + 1);
 
         let state = RadioState::new(handle, name.clone(), port, protocol);
         self.radios.insert(handle, state);
@@ -101,22 +165,46 @@ impl Multiplexer {
         }
 
         info!("Added radio: {} (handle {})", name, handle.0);
-
-        handle
     }
 
     /// Remove a radio from the multiplexer
     pub fn remove_radio(&mut self, handle: RadioHandle) -> Option<RadioState> {
         let state = self.radios.remove(&handle)?;
 
-        // If this was the active radio, select another
+        // If this was the active radio, select another (preferring one that is still alive)
         if self.active_radio == Some(handle) {
-            self.active_radio = self.radios.keys().next().copied();
+            self.active_radio = self.fallback_radio();
         }
 
         Some(state)
     }
 
+    /// A radio to make active when the active one goes away: one that can
+    /// transmit, preferably still alive
+    fn fallback_radio(&self) -> Option<RadioHandle> {
+        let candidates = || self.radios.values().filter(|r| !r.rx_only);
+        candidates()
+            .find(|r| !self.is_radio_stale(r.handle))
+            .or_else(|| candidates().next())
+            .map(|r| r.handle)
+    }
+
+    /// Make a radio a receive-only monitor channel (or a normal one again)
+    ///
+    /// A receive-only radio that was active hands over to another radio, or
+    /// leaves none active.
+    pub fn set_rx_only(&mut self, handle: RadioHandle, rx_only: bool) {
+        let Some(radio) = self.radios.get_mut(&handle) else {
+            return;
+        };
+        radio.rx_only = rx_only;
+        if rx_only && self.active_radio == Some(handle) {
+            self.active_radio = self.fallback_radio();
+        } else if !rx_only && self.active_radio.is_none() {
+            self.active_radio = Some(handle);
+        }
+    }
+
     /// Rename a radio (e.g., after ID probing identifies the actual model)
     pub fn rename_radio(&mut self, handle: RadioHandle, new_name: String) {
         if let Some(radio) = self.radios.get_mut(&handle) {
@@ -128,6 +216,13 @@ impl Multiplexer {
         }
     }
 
+    /// Change the protocol recorded for a radio
+    pub fn set_radio_protocol(&mut self, handle: RadioHandle, protocol: Protocol) {
+        if let Some(radio) = self.radios.get_mut(&handle) {
+            radio.protocol = protocol;
+        }
+    }
+
     /// Get a radio's state
     pub fn get_radio(&self, handle: RadioHandle) -> Option<&RadioState> {
         self.radios.get(&handle)
@@ -158,12 +253,16 @@ impl Multiplexer {
         if !self.radios.contains_key(&handle) {
             return Err(MuxError::RadioNotFound(format!("handle {}", handle.0)));
         }
+        if self.radios.get(&handle).is_some_and(|r| r.rx_only) {
+            return Err(MuxError::ReceiveOnly(format!("handle {}", handle.0)));
+        }
 
         // Check lockout
         if let Some(until) = self.lockout_until {
             if Instant::now() < until {
                 let remaining_ms = until.duration_since(Instant::now()).as_millis() as u64;
                 if let Some(current) = self.active_radio {
+                    self.held_switch = Some((handle, TransitionReason::Lockout));
                     return Err(MuxError::SwitchingLocked {
                         requested: handle,
                         current,
@@ -173,17 +272,73 @@ impl Multiplexer {
             }
         }
 
-        self.switch_to(handle);
+        self.switch_to(handle, SwitchTrigger::Manual, SwitchRule::Selected);
         Ok(())
     }
 
+    /// Set the same-band guard (see [`crate::band_guard`])
+    pub fn set_band_guard_config(&mut self, config: BandGuardConfig) {
+        self.band_guard.set_config(config);
+    }
+
+    /// The same-band guard configuration
+    pub fn band_guard_config(&self) -> BandGuardConfig {
+        self.band_guard.config()
+    }
+
+    /// The band a radio shares with the active radio, if the guard counts it
+    pub fn band_conflict(&self, handle: RadioHandle) -> Option<&'static str> {
+        let radio = self.radios.get(&handle)?;
+        self.band_guard.conflict(radio, self.active_radio_state()?)
+    }
+
+    /// Re-check which radios share the active radio's band; returns what changed
+    pub fn update_band_conflicts(&mut self) -> Vec<BandConflict> {
+        let active = self.active_radio.and_then(|h| self.radios.get(&h));
+        self.band_guard.update(active, self.radios.values())
+    }
+
+    /// Take the record of the last switch (see [`crate::switch_audit`])
+    pub fn take_switch_record(&mut self) -> Option<SwitchRecord> {
+        self.last_switch.take()
+    }
+
     /// Internal switch implementation
-    fn switch_to(&mut self, handle: RadioHandle) {
+    fn switch_to(&mut self, handle: RadioHandle, trigger: SwitchTrigger, rule: SwitchRule) {
         let old = self.active_radio;
         if old == Some(handle) {
             return;
         }
 
+        let now = Instant::now();
+        let lockout = match self.lockout_until {
+            None => LockoutState::NeverArmed,
+            Some(until) if until <= now => LockoutState::Expired { ago: now - until },
+            Some(until) => LockoutState::NoActiveRadio {
+                remaining: until - now,
+            },
+        };
+        let outgoing = old.and_then(|h| {
+            let radio = self.radios.get(&h)?;
+            Some(OutgoingRadio {
+                handle: h,
+                ptt: radio.ptt,
+                stale: self.is_radio_stale(h),
+                since_tuned: radio.last_freq_change.map(|t| now.duration_since(t)),
+            })
+        });
+        self.last_switch = Some(SwitchRecord {
+            id: 0,
+            timestamp: SystemTime::now(),
+            from: old,
+            to: handle,
+            mode: self.config.switching_mode,
+            trigger,
+            rule,
+            lockout,
+            outgoing,
+        });
+
         self.active_radio = Some(handle);
         self.lockout_until = Some(Instant::now() + Duration::from_millis(self.config.lockout_ms));
 
@@ -200,8 +355,9 @@ impl Multiplexer {
         handle: RadioHandle,
         response: &RadioResponse,
     ) -> Option<Vec<u8>> {
-        // Capture old frequency before updating state
+        // Capture old frequency and staleness before updating state
         let old_freq = self.radios.get(&handle).and_then(|r| r.frequency_hz);
+        let was_stale = self.is_radio_stale(handle);
 
         // Update radio state based on response
         if let Some(radio) = self.radios.get_mut(&handle) {
@@ -215,6 +371,12 @@ impl Multiplexer {
                 RadioResponse::Ptt { active } => {
                     radio.set_ptt(*active);
                 }
+                RadioResponse::TxPower { watts } => {
+                    radio.set_power(*watts);
+                }
+                RadioResponse::KeyerSpeed { wpm } => {
+                    radio.set_keyer_speed(*wpm);
+                }
                 RadioResponse::Status {
                     frequency_hz,
                     mode,
@@ -239,10 +401,13 @@ impl Multiplexer {
 
         // Determine if frequency changed (ignore initial report when old_freq is None)
         let new_freq = self.radios.get(&handle).and_then(|r| r.frequency_hz);
-        let freq_changed = old_freq.is_some() && old_freq != new_freq;
+        let freq_change = match (old_freq, new_freq) {
+            (Some(from_hz), Some(to_hz)) if from_hz != to_hz => Some((from_hz, to_hz)),
+            _ => None,
+        };
 
         // Check if we should switch radios
-        self.check_auto_switch(handle, response, freq_changed);
+        self.check_auto_switch(handle, response, freq_change, was_stale);
 
         // Only forward if this is the active radio
         if self.active_radio != Some(handle) {
@@ -253,7 +418,7 @@ impl Multiplexer {
         // Filter and translate for amplifier
         let filtered = filter_response_for_amplifier(response)?;
 
-        match translate_response(&filtered, self.config.amplifier.protocol) {
+        match translate_response(&filtered, self.config.amplifier.encode_protocol()) {
             Ok(bytes) => Some(bytes),
             Err(e) => {
                 error!("Translation failed: {}", e);
@@ -267,10 +432,11 @@ impl Multiplexer {
         &mut self,
         handle: RadioHandle,
         response: &RadioResponse,
-        freq_changed: bool,
+        freq_change: Option<(Frequency, Frequency)>,
+        was_stale: bool,
     ) {
-        // Don't switch to a radio that doesn't exist
-        if !self.radios.contains_key(&handle) {
+        // Don't switch to a radio that doesn't exist or can't transmit
+        if self.radios.get(&handle).is_none_or(|r| r.rx_only) {
             return;
         }
 
@@ -278,32 +444,57 @@ impl Multiplexer {
             return;
         }
 
-        // Check lockout
-        if let Some(until) = self.lockout_until {
-            if Instant::now() < until {
-                return;
-            }
+        // A radio waking up after a long silence (likely just powered on)
+        // shouldn't grab the amplifier with its initial reports
+        if was_stale {
+            debug!("Not auto-switching to stale radio {}", handle.0);
+            return;
         }
 
-        let should_switch = match self.config.switching_mode {
-            SwitchingMode::Manual => false,
-            SwitchingMode::FrequencyTriggered => {
-                // Frequency response triggers switch if frequency actually changed
-                matches!(response, RadioResponse::Frequency { .. }) && freq_changed
+        // Frequency response triggers switch if frequency actually changed
+        let retuned = match (response, freq_change) {
+            (RadioResponse::Frequency { .. }, Some((from_hz, to_hz))) => {
+                Some(SwitchRule::FrequencyChanged { from_hz, to_hz })
             }
+            _ => None,
+        };
+        let rule = match self.config.switching_mode {
+            SwitchingMode::Manual => None,
+            SwitchingMode::FrequencyTriggered => retuned,
             SwitchingMode::Automatic => {
-                matches!(response, RadioResponse::Ptt { active: true })
-                    || (matches!(response, RadioResponse::Frequency { .. }) && freq_changed)
+                if matches!(response, RadioResponse::Ptt { active: true }) {
+                    if self.band_guard.blocks_ptt() && self.band_conflict(handle).is_some() {
+                        debug!(
+                            "Not switching to radio {}: keyed on the active radio's band",
+                            handle.0
+                        );
+                        self.held_switch = Some((handle, TransitionReason::Transmitting));
+                        return;
+                    }
+                    Some(SwitchRule::PttPressed)
+                } else {
+                    retuned
+                }
             }
         };
 
-        if should_switch {
+        if rule.is_some() && self.is_locked() {
+            debug!("Not switching to radio {}: lockout active", handle.0);
+            self.held_switch = Some((handle, TransitionReason::Lockout));
+            return;
+        }
+
+        if let Some(rule) = rule {
             debug!(
                 "Auto-switching to radio {} due to {:?}",
                 handle.0,
                 std::mem::discriminant(response)
             );
-            self.switch_to(handle);
+            let trigger = SwitchTrigger::Report {
+                response: format!("{:?}", response),
+                frame: None,
+            };
+            self.switch_to(handle, trigger, rule);
         }
     }
 
@@ -313,6 +504,16 @@ impl Multiplexer {
             .is_some_and(|until| Instant::now() < until)
     }
 
+    /// When the lockout expires, if it is active
+    pub fn lockout_expires(&self) -> Option<Instant> {
+        self.lockout_until.filter(|until| Instant::now() < *until)
+    }
+
+    /// Take the switch held off since the last call, if any
+    pub fn take_held_switch(&mut self) -> Option<(RadioHandle, TransitionReason)> {
+        self.held_switch.take()
+    }
+
     /// Get remaining lockout time in ms
     pub fn lockout_remaining_ms(&self) -> u64 {
         self.lockout_until
@@ -358,6 +559,142 @@ mod tests {
         assert_eq!(mux.active_radio(), Some(h2));
     }
 
+    #[test]
+    fn test_repeated_reports_are_recognised() {
+        use cat_protocol::OperatingMode;
+
+        let mut mux = Multiplexer::new();
+        let h = mux.add_radio("Radio".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
+        mux.process_radio_response(
+            h,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_074_000),
+            },
+        );
+        mux.process_radio_response(
+            h,
+            &RadioResponse::Mode {
+                mode: OperatingMode::Usb,
+            },
+        );
+        let radio = mux.get_radio(h).unwrap();
+
+        assert!(radio.repeats(&RadioResponse::Frequency {
+            hz: Frequency::from_hz(14_074_000)
+        }));
+        assert!(!radio.repeats(&RadioResponse::Frequency {
+            hz: Frequency::from_hz(14_074_010)
+        }));
+        // Keying and unkeying are always acted on
+        assert!(!radio.repeats(&RadioResponse::Ptt { active: false }));
+        assert!(!radio.repeats(&RadioResponse::Ptt { active: true }));
+        assert!(radio.repeats(&RadioResponse::Status {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: Some(OperatingMode::Usb),
+            ptt: None,
+            vfo: None,
+        }));
+        assert!(!radio.repeats(&RadioResponse::Status {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: Some(OperatingMode::Usb),
+            ptt: Some(false),
+            vfo: None,
+        }));
+        assert!(!radio.repeats(&RadioResponse::Status {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: Some(OperatingMode::Cw),
+            ptt: None,
+            vfo: None,
+        }));
+        // Nothing tracked yet
+        assert!(!radio.repeats(&RadioResponse::TxPower { watts: 100 }));
+    }
+
+    #[test]
+    fn test_rx_only_radio_is_never_active() {
+        let mut mux = Multiplexer::new();
+        mux.set_switching_mode(SwitchingMode::Automatic);
+        mux.config.lockout_ms = 0;
+
+        // Registered first, so it starts out active until marked
+        let sdr = mux.add_radio("SDR".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
+        mux.set_rx_only(sdr, true);
+        assert_eq!(mux.active_radio(), None);
+
+        let rig = mux.add_radio("Rig".into(), "/dev/ttyUSB1".into(), Protocol::Kenwood);
+        assert_eq!(mux.active_radio(), Some(rig));
+
+        // Tuning and keying don't switch, and it can't be selected
+        mux.process_radio_response(
+            sdr,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+        );
+        mux.process_radio_response(
+            sdr,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_075_000),
+            },
+        );
+        mux.process_radio_response(sdr, &RadioResponse::Ptt { active: true });
+        assert_eq!(mux.active_radio(), Some(rig));
+        assert!(matches!(
+            mux.select_radio(sdr),
+            Err(MuxError::ReceiveOnly(_))
+        ));
+        // Its state is still tracked for display
+        assert_eq!(
+            mux.get_radio(sdr).unwrap().frequency_hz,
+            Some(Frequency::from_hz(7_075_000))
+        );
+
+        mux.remove_radio(rig);
+        assert_eq!(mux.active_radio(), None);
+    }
+
+    #[test]
+    fn test_band_guard_refuses_ptt_switch_on_same_band() {
+        use crate::band_guard::BandGuardStrictness;
+
+        let mut mux = Multiplexer::new();
+        mux.set_switching_mode(SwitchingMode::Automatic);
+        mux.config.lockout_ms = 0;
+        mux.set_band_guard_config(BandGuardConfig {
+            strictness: BandGuardStrictness::BlockPtt,
+            per_mode: false,
+        });
+
+        let run = mux.add_radio("Run".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
+        let mult = mux.add_radio("Mult".into(), "/dev/ttyUSB1".into(), Protocol::Kenwood);
+        mux.process_radio_response(
+            run,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_025_000),
+            },
+        );
+        mux.process_radio_response(
+            mult,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
+        assert_eq!(mux.band_conflict(mult), Some("20m"));
+
+        // Keying on the run radio's band doesn't take the amp
+        mux.process_radio_response(mult, &RadioResponse::Ptt { active: true });
+        assert_eq!(mux.active_radio(), Some(run));
+        mux.process_radio_response(mult, &RadioResponse::Ptt { active: false });
+
+        // When only warning, it does
+        mux.set_band_guard_config(BandGuardConfig {
+            strictness: BandGuardStrictness::Warn,
+            per_mode: false,
+        });
+        mux.process_radio_response(mult, &RadioResponse::Ptt { active: true });
+        assert_eq!(mux.active_radio(), Some(mult));
+    }
+
     #[test]
     fn test_manual_switching() {
         let mut mux = Multiplexer::new();
@@ -394,10 +731,27 @@ mod tests {
         let mut mux = Multiplexer::new();
         let h1 = mux.add_radio("Radio 1".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
 
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         let state = mux.get_radio(h1).unwrap();
-        assert_eq!(state.frequency_hz, Some(14_250_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(14_250_000)));
+    }
+
+    #[test]
+    fn test_power_update() {
+        let mut mux = Multiplexer::new();
+        mux.config.amplifier.protocol = Protocol::Kenwood;
+        let h1 = mux.add_radio("Radio 1".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
+
+        // Tracked in state but not forwarded to the amplifier
+        let result = mux.process_radio_response(h1, &RadioResponse::TxPower { watts: 50 });
+        assert!(result.is_none());
+        assert_eq!(mux.get_radio(h1).unwrap().power_watts, Some(50));
     }
 
     #[test]
@@ -407,10 +761,38 @@ mod tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
 
-        let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        let result = mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert!(result.is_some());
         let bytes = result.unwrap();
         assert!(bytes.ends_with(b";"));
     }
+
+    #[test]
+    fn test_no_auto_switch_to_stale_radio() {
+        let mut mux = Multiplexer::new();
+        mux.set_switching_mode(SwitchingMode::Automatic);
+        mux.config.lockout_ms = 0;
+        mux.set_stale_after_secs(Some(30));
+
+        let h1 = mux.add_radio("Radio 1".into(), "/dev/ttyUSB0".into(), Protocol::Kenwood);
+        let h2 = mux.add_radio("Radio 2".into(), "/dev/ttyUSB1".into(), Protocol::Kenwood);
+
+        // Radio 2 last heard from a minute ago
+        mux.get_radio_mut(h2).unwrap().last_activity = Instant::now() - Duration::from_secs(60);
+        assert!(mux.is_radio_stale(h2));
+
+        mux.process_radio_response(h2, &RadioResponse::Ptt { active: true });
+        assert_eq!(mux.active_radio(), Some(h1));
+
+        // The frame refreshed it, so the next one may switch
+        assert!(!mux.is_radio_stale(h2));
+        mux.process_radio_response(h2, &RadioResponse::Ptt { active: true });
+        assert_eq!(mux.active_radio(), Some(h2));
+    }
 }
@@ -15,6 +15,10 @@ pub enum MuxError {
     #[error("radio already exists: {0}")]
     RadioExists(String),
 
+    /// Receive-only radios can't be made active
+    #[error("radio is receive-only: {0}")]
+    ReceiveOnly(String),
+
     /// No active radio
     #[error("no active radio selected")]
     NoActiveRadio,
@@ -4,13 +4,38 @@
 //! are emitted through a single event channel. This enables a unified traffic
 //! monitor and simplifies state observation.
 
-use std::time::SystemTime;
+use std::fmt;
+use std::time::{Duration, SystemTime};
 
-use cat_protocol::{OperatingMode, Protocol};
+use cat_protocol::{CustomEvent, Frequency, MeterKind, OperatingMode, Protocol};
+use tokio_serial::FlowControl;
 
+use crate::amp_failover::AmpRole;
+use crate::amp_queries::AmpQueryProfile;
 use crate::amplifier::AmplifierChannelMeta;
+use crate::analyzer::AnalyzerDirection;
 use crate::channel::RadioChannelMeta;
-use crate::state::{RadioHandle, SwitchingMode};
+use crate::dx_cluster::DxSpot;
+use crate::peer_sync::PeerStatus;
+use crate::reassembly::{FrameSource, ReassemblyStats};
+use crate::shadow::{ShadowDiff, ShadowStats};
+use crate::state::{AmplifierEmulatedState, RadioHandle, SwitchingMode};
+use crate::switch_audit::SwitchRecord;
+use crate::switch_confirm::SwitchConfirmOutcome;
+use crate::sync_plan::SyncStepStatus;
+
+/// Number of a frame the mux received, unique for the session
+///
+/// Frames the mux sends because of one it received carry its id as their
+/// cause, linking an amplifier write back to the radio report behind it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct FrameId(pub u64);
+
+impl fmt::Display for FrameId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
 
 /// Unified event enum for all multiplexer activity
 ///
@@ -35,16 +60,26 @@ pub enum MuxEvent {
         handle: RadioHandle,
     },
 
-    /// A radio's state has changed (frequency, mode, or PTT)
+    /// A connected radio was switched to a different protocol
+    RadioProtocolChanged {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Protocol now used to parse and encode its traffic
+        protocol: Protocol,
+    },
+
+    /// A radio's state has changed (frequency, mode, PTT, or output power)
     RadioStateChanged {
         /// Handle of the radio
         handle: RadioHandle,
-        /// New frequency in Hz (if changed)
-        freq: Option<u64>,
+        /// New frequency (if changed)
+        freq: Option<Frequency>,
         /// New operating mode (if changed)
         mode: Option<OperatingMode>,
         /// New PTT state (if changed)
         ptt: Option<bool>,
+        /// New output power setting in watts (if changed)
+        power_watts: Option<u16>,
     },
 
     /// The active radio has changed
@@ -55,6 +90,93 @@ pub enum MuxEvent {
         to: RadioHandle,
     },
 
+    /// Why the active radio just changed (follows `ActiveRadioChanged`)
+    SwitchAudited { record: SwitchRecord },
+
+    /// A radio has gone silent past the staleness threshold, or come back
+    RadioStaleChanged {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// True if the radio's data is now considered stale
+        stale: bool,
+        /// When the radio last sent a frame (None if never)
+        last_seen: Option<SystemTime>,
+    },
+
+    /// A radio PTT edge has been passed on to the amplifier
+    PttLatency {
+        /// Handle of the radio that keyed or unkeyed
+        handle: RadioHandle,
+        /// New PTT state
+        active: bool,
+        /// From the radio task reading the frame to the amp being notified
+        latency: Duration,
+    },
+
+    /// A user command handler reported a frame from a radio
+    /// (see [`cat_protocol::hooks`])
+    RadioCustomEvent {
+        /// Handle of the radio the frame came from
+        handle: RadioHandle,
+        /// What the handler reported
+        event: CustomEvent,
+    },
+
+    /// Meter reading from a radio, throttled (see [`crate::telemetry`])
+    RadioMeter {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// Which meter
+        meter: MeterKind,
+        /// Highest reading since the previous event (0-255 of full scale)
+        level: u8,
+    },
+
+    /// A step of a radio's initial sync plan has finished
+    RadioSyncProgress {
+        /// Handle of the radio
+        handle: RadioHandle,
+        /// 1-based index of the finished step
+        step: usize,
+        /// Steps in the plan (may grow once the model is identified)
+        total: usize,
+        /// What the step did
+        label: String,
+        /// How it went
+        status: SyncStepStatus,
+    },
+
+    /// How a radio's frames have been arriving across reads (reported about
+    /// once a second while it changes)
+    RadioReassembly {
+        /// Radio handle
+        handle: RadioHandle,
+        /// Statistics since the radio connected
+        stats: ReassemblyStats,
+    },
+
+    /// Whether an Icom radio echoes the mux's CI-V frames back was worked
+    /// out, or changed (see [`crate::civ_echo`])
+    RadioEchoBack {
+        /// Radio handle
+        handle: RadioHandle,
+        /// The radio repeats what it's sent; echoes are being filtered
+        echo_back: bool,
+    },
+
+    /// A partial frame stalled in a codec and was thrown away so it can't
+    /// corrupt the next frame
+    PartialFrameDiscarded {
+        /// Whose stream it was
+        source: FrameSource,
+        /// The bytes that were discarded
+        data: Vec<u8>,
+        /// Protocol of the codec
+        protocol: Protocol,
+        /// How long the frame waited for its next byte
+        stalled: Duration,
+    },
+
     // -------------------------------------------------------------------------
     // Traffic events (for traffic monitor)
     // -------------------------------------------------------------------------
@@ -62,6 +184,8 @@ pub enum MuxEvent {
     RadioDataIn {
         /// Handle of the source radio
         handle: RadioHandle,
+        /// Id that frames sent because of this one refer to
+        id: FrameId,
         /// Raw data bytes
         data: Vec<u8>,
         /// Protocol of the radio
@@ -86,8 +210,13 @@ pub enum MuxEvent {
     AmpDataOut {
         /// Raw data bytes
         data: Vec<u8>,
+        /// Received frame this write answers or passes on (None for the
+        /// mux's own timers and heartbeats)
+        cause: Option<FrameId>,
         /// Protocol used for the amplifier
         protocol: Protocol,
+        /// Held back by dry run: what would have been written, but wasn't
+        suppressed: bool,
         /// Timestamp when the data was sent (captured at source for accurate ordering)
         timestamp: SystemTime,
     },
@@ -96,12 +225,52 @@ pub enum MuxEvent {
     AmpDataIn {
         /// Raw data bytes
         data: Vec<u8>,
+        /// Id that frames sent because of this one refer to
+        id: FrameId,
         /// Protocol used for the amplifier
         protocol: Protocol,
         /// Timestamp when the data was received (captured at source for accurate ordering)
         timestamp: SystemTime,
     },
 
+    /// Data sent to the shadow amplifier (mux -> shadow amp)
+    ShadowAmpDataOut {
+        /// Raw data bytes
+        data: Vec<u8>,
+        /// Received frame this write answers or passes on
+        cause: Option<FrameId>,
+        /// Protocol used for the amplifier
+        protocol: Protocol,
+        /// Timestamp when the data was sent
+        timestamp: SystemTime,
+    },
+
+    /// Data received from the shadow amplifier (shadow amp -> mux)
+    ShadowAmpDataIn {
+        /// Raw data bytes
+        data: Vec<u8>,
+        /// Id that frames sent because of this one refer to
+        id: FrameId,
+        /// Protocol used for the amplifier
+        protocol: Protocol,
+        /// Timestamp when the data was received
+        timestamp: SystemTime,
+    },
+
+    /// A frame relayed by the analyzer passthrough (logger <-> radio)
+    AnalyzerData {
+        /// Which way the frame travelled
+        direction: AnalyzerDirection,
+        /// Raw frame bytes
+        data: Vec<u8>,
+        /// Protocol the analyzer decodes with
+        protocol: Protocol,
+        /// Decoded command name ("Unknown" if the codec didn't recognize it)
+        command: String,
+        /// Timestamp when the frame was read
+        timestamp: SystemTime,
+    },
+
     // -------------------------------------------------------------------------
     // Amplifier lifecycle events
     // -------------------------------------------------------------------------
@@ -114,6 +283,76 @@ pub enum MuxEvent {
     /// The amplifier has disconnected from the multiplexer
     AmpDisconnected,
 
+    /// A safety frame was written to the amplifier ahead of queued writes
+    AmpWritePreempted {
+        /// Frames it jumped ahead of
+        queued: usize,
+    },
+
+    /// A shadow amplifier has connected for A/B comparison
+    ShadowAmpConnected {
+        /// Metadata about the shadow amplifier
+        meta: AmplifierChannelMeta,
+    },
+
+    /// The shadow amplifier has disconnected
+    ShadowAmpDisconnected,
+
+    /// A backup amplifier is standing by for failover
+    BackupAmpConnected {
+        /// Metadata about the backup amplifier
+        meta: AmplifierChannelMeta,
+    },
+
+    /// The backup amplifier has disconnected
+    BackupAmpDisconnected,
+
+    /// The amp in service failed and translation output moved
+    AmpFailedOver {
+        /// The amp that failed
+        from: AmpRole,
+        /// Where output goes now
+        to: AmpRole,
+        /// What went wrong
+        reason: String,
+    },
+
+    /// The operator acknowledged a failover; output is back on the primary
+    AmpFailoverAcknowledged,
+
+    /// What the amp has been told changed (after an update or a query answer)
+    AmpStateChanged {
+        /// The amp's view now
+        state: AmplifierEmulatedState,
+    },
+
+    /// The amplifier's learned query profile changed (reported about once a second)
+    AmpQueryProfileUpdated {
+        /// Queries seen and their recent rates
+        profile: AmpQueryProfile,
+    },
+
+    /// Writes to the amplifier started or stopped backing up
+    ///
+    /// While backed up, frequency and mode updates are held back and only
+    /// the latest is sent once the output drains.
+    AmpBacklogChanged {
+        /// Whether the output is above its high watermark
+        backed_up: bool,
+        /// Bytes queued but not yet written
+        pending_bytes: usize,
+        /// Flow control on the amp's port, which can be what's holding it
+        flow_control: FlowControl,
+    },
+
+    /// A shadow comparison window closed
+    ShadowAmpCompared {
+        /// Totals for the session so far
+        stats: ShadowStats,
+        /// What differed (None when both amps behaved the same)
+        diff: Option<ShadowDiff>,
+    },
+
     // -------------------------------------------------------------------------
     // Control events
     // -------------------------------------------------------------------------
@@ -133,6 +372,113 @@ pub enum MuxEvent {
         remaining_ms: u64,
     },
 
+    /// A manual switch away from a transmitting radio waits for confirmation
+    ///
+    /// Answer with [`crate::MuxActorCommand::ConfirmSwitch`] and this token.
+    SwitchPendingConfirmation {
+        /// Identifies the request
+        token: u64,
+        /// Radio that was selected
+        requested: RadioHandle,
+        /// Radio that is transmitting
+        current: RadioHandle,
+        /// How long the request waits (milliseconds)
+        timeout_ms: u64,
+    },
+
+    /// A switch pending confirmation was answered, expired or replaced
+    SwitchConfirmationClosed {
+        /// Identifies the request
+        token: u64,
+        /// How it ended
+        outcome: SwitchConfirmOutcome,
+    },
+
+    /// The operator dropped a bookmark ("that just happened")
+    Bookmark {
+        /// What triggered it (PTT double-tap, a serial pin, the UI)
+        source: String,
+        /// What the active radio was doing at the time
+        note: String,
+        /// When it was dropped
+        timestamp: SystemTime,
+    },
+
+    /// The active radio unkeyed, with snapshots on (a minimal frequency log)
+    ContactSnapshot {
+        /// Radio that unkeyed
+        handle: RadioHandle,
+        /// Its display name
+        radio: String,
+        /// Frequency it was on (None if not reported yet)
+        frequency_hz: Option<Frequency>,
+        /// Mode it was in (None if not reported yet)
+        mode: Option<OperatingMode>,
+        /// When it unkeyed
+        timestamp: SystemTime,
+    },
+
+    /// The actor panicked or stopped responding and was restarted with its
+    /// channels (see [`crate::supervisor`]); pushed settings must be resent
+    MuxRestarted {
+        /// What happened to the previous actor
+        reason: String,
+        /// Restarts so far this session
+        restarts: u32,
+    },
+
+    /// The active radio landed on or left a DX cluster spot
+    SpotMatched {
+        /// Radio on (or leaving) the spot
+        handle: RadioHandle,
+        /// The spot, or `None` when the radio moved off it
+        spot: Option<DxSpot>,
+    },
+
+    /// A radio entered or left the active radio's band (see
+    /// [`crate::band_guard`])
+    BandConflict {
+        /// Radio sharing the band
+        handle: RadioHandle,
+        /// The active radio
+        active: RadioHandle,
+        /// The shared band, or `None` when it left
+        band: Option<&'static str>,
+    },
+
+    /// The amplifier was locked out of (or released from) transmit because
+    /// another radio on the active radio's band was already transmitting
+    BandLockoutChanged {
+        /// The active radio
+        handle: RadioHandle,
+        /// The radio holding the band, or `None` when released
+        holder: Option<RadioHandle>,
+    },
+
+    /// A peer instance joined, left, or changed band or TX state
+    /// (see [`crate::peer_sync`])
+    PeersChanged {
+        /// Peers still being heard, by instance name
+        peers: Vec<PeerStatus>,
+    },
+
+    /// The amplifier was locked out of (or released from) transmit because a
+    /// peer is transmitting on the same band
+    PeerInterlockChanged {
+        /// The peer holding the band, or `None` when released
+        blocked_by: Option<PeerStatus>,
+    },
+
+    /// A radio's transmit duty cycle went over (or back under) the alert
+    /// threshold (see [`crate::tx_stats`])
+    DutyCycleAlert {
+        handle: RadioHandle,
+        /// Duty cycle over the alert window, 0.0 to 1.0
+        duty_cycle: f32,
+        /// Over the threshold (false when it dropped back below)
+        over: bool,
+    },
+
     /// An error occurred in the multiplexer
     Error {
         /// Source of the error
@@ -151,6 +497,9 @@ impl MuxEvent {
                 | MuxEvent::RadioDataOut { .. }
                 | MuxEvent::AmpDataOut { .. }
                 | MuxEvent::AmpDataIn { .. }
+                | MuxEvent::ShadowAmpDataOut { .. }
+                | MuxEvent::ShadowAmpDataIn { .. }
+                | MuxEvent::AnalyzerData { .. }
         )
     }
 
@@ -160,7 +509,9 @@ impl MuxEvent {
             self,
             MuxEvent::RadioConnected { .. }
                 | MuxEvent::RadioDisconnected { .. }
+                | MuxEvent::RadioProtocolChanged { .. }
                 | MuxEvent::ActiveRadioChanged { .. }
+                | MuxEvent::RadioStaleChanged { .. }
         )
     }
 
@@ -168,20 +519,60 @@ impl MuxEvent {
     pub fn is_amp_lifecycle(&self) -> bool {
         matches!(
             self,
-            MuxEvent::AmpConnected { .. } | MuxEvent::AmpDisconnected
+            MuxEvent::AmpConnected { .. }
+                | MuxEvent::AmpDisconnected
+                | MuxEvent::ShadowAmpConnected { .. }
+                | MuxEvent::ShadowAmpDisconnected
+                | MuxEvent::BackupAmpConnected { .. }
+                | MuxEvent::BackupAmpDisconnected
         )
     }
 
+    /// When the event happened, for events that carry their own time
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        match self {
+            MuxEvent::RadioDataIn { timestamp, .. }
+            | MuxEvent::RadioDataOut { timestamp, .. }
+            | MuxEvent::AmpDataOut { timestamp, .. }
+            | MuxEvent::AmpDataIn { timestamp, .. }
+            | MuxEvent::ShadowAmpDataOut { timestamp, .. }
+            | MuxEvent::ShadowAmpDataIn { timestamp, .. }
+            | MuxEvent::AnalyzerData { timestamp, .. }
+            | MuxEvent::Bookmark { timestamp, .. }
+            | MuxEvent::ContactSnapshot { timestamp, .. } => Some(*timestamp),
+            _ => None,
+        }
+    }
+
     /// Get the radio handle if this event is associated with a specific radio
     pub fn radio_handle(&self) -> Option<RadioHandle> {
         match self {
             MuxEvent::RadioConnected { handle, .. }
             | MuxEvent::RadioDisconnected { handle }
+            | MuxEvent::RadioProtocolChanged { handle, .. }
             | MuxEvent::RadioStateChanged { handle, .. }
+            | MuxEvent::RadioStaleChanged { handle, .. }
+            | MuxEvent::RadioSyncProgress { handle, .. }
+            | MuxEvent::PttLatency { handle, .. }
+            | MuxEvent::RadioCustomEvent { handle, .. }
+            | MuxEvent::RadioMeter { handle, .. }
+            | MuxEvent::RadioReassembly { handle, .. }
+            | MuxEvent::RadioEchoBack { handle, .. }
             | MuxEvent::RadioDataIn { handle, .. }
-            | MuxEvent::RadioDataOut { handle, .. } => Some(*handle),
+            | MuxEvent::RadioDataOut { handle, .. }
+            | MuxEvent::SpotMatched { handle, .. }
+            | MuxEvent::BandConflict { handle, .. }
+            | MuxEvent::BandLockoutChanged { handle, .. }
+            | MuxEvent::DutyCycleAlert { handle, .. }
+            | MuxEvent::ContactSnapshot { handle, .. } => Some(*handle),
             MuxEvent::ActiveRadioChanged { to, .. } => Some(*to),
-            MuxEvent::SwitchingBlocked { requested, .. } => Some(*requested),
+            MuxEvent::SwitchAudited { record } => Some(record.to),
+            MuxEvent::SwitchingBlocked { requested, .. }
+            | MuxEvent::SwitchPendingConfirmation { requested, .. } => Some(*requested),
+            MuxEvent::PartialFrameDiscarded {
+                source: FrameSource::Radio(handle),
+                ..
+            } => Some(*handle),
             _ => None,
         }
     }
@@ -195,6 +586,7 @@ mod tests {
     fn test_traffic_event_classification() {
         let radio_in = MuxEvent::RadioDataIn {
             handle: RadioHandle(1),
+            id: FrameId(1),
             data: vec![0x01, 0x02],
             protocol: Protocol::Kenwood,
             timestamp: SystemTime::now(),
@@ -204,7 +596,9 @@ mod tests {
 
         let amp_out = MuxEvent::AmpDataOut {
             data: vec![0x03, 0x04],
+            cause: Some(FrameId(1)),
             protocol: Protocol::Kenwood,
+            suppressed: false,
             timestamp: SystemTime::now(),
         };
         assert!(amp_out.is_traffic());
@@ -226,6 +620,7 @@ mod tests {
     fn test_radio_handle_extraction() {
         let event = MuxEvent::RadioDataIn {
             handle: RadioHandle(42),
+            id: FrameId(1),
             data: vec![],
             protocol: Protocol::Kenwood,
             timestamp: SystemTime::now(),
@@ -234,7 +629,9 @@ mod tests {
 
         let amp_event = MuxEvent::AmpDataOut {
             data: vec![],
+            cause: None,
             protocol: Protocol::Kenwood,
+            suppressed: false,
             timestamp: SystemTime::now(),
         };
         assert_eq!(amp_event.radio_handle(), None);
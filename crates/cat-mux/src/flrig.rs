@@ -0,0 +1,630 @@
+//! flrig XML-RPC client
+//!
+//! A radio that flrig already controls can't have its serial port opened a
+//! second time. [`run_flrig_client`] talks to flrig's XML-RPC server instead:
+//! it polls `rig.get_ptt`, `rig.get_vfo` and `rig.get_mode`, hands whatever
+//! changed to the mux as already-decoded responses
+//! ([`MuxActorCommand::RadioResponses`]), and with pushing enabled turns the
+//! mux's frequency, mode and PTT requests into `rig.set_*` calls. Anything
+//! else the mux asks of the radio is dropped.
+//!
+//! Traffic for these radios is the XML-RPC method and value as text (e.g.
+//! `rig.get_vfo 14074000`), since the radio's own frames never reach us.
+
+use std::io;
+use std::time::Duration;
+
+use cat_protocol::{Frequency, OperatingMode, RadioRequest, RadioResponse};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc as tokio_mpsc;
+use tracing::{debug, info, warn};
+
+use crate::async_radio::{RadioTaskCommand, DEFAULT_POLL_INTERVAL};
+use crate::client::MuxClient;
+use crate::{MuxActorCommand, MuxEvent, RadioHandle};
+
+/// flrig's default XML-RPC address
+pub const DEFAULT_FLRIG_ADDR: &str = "127.0.0.1:12345";
+
+/// Longest a single XML-RPC call may take
+const CALL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Largest reply read from flrig
+const MAX_REPLY_BYTES: u64 = 64 * 1024;
+
+/// How the client follows and drives the radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlrigOptions {
+    /// Time between polls
+    pub poll_interval: Duration,
+    /// Send the mux's frequency, mode and PTT changes to flrig
+    pub push: bool,
+}
+
+impl Default for FlrigOptions {
+    fn default() -> Self {
+        Self {
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            push: false,
+        }
+    }
+}
+
+/// A parameter of an XML-RPC call
+#[derive(Debug, Clone, PartialEq)]
+enum Param {
+    Int(i64),
+    Double(f64),
+    Str(String),
+}
+
+/// Connection details for one flrig instance
+#[derive(Debug, Clone)]
+pub struct FlrigClient {
+    address: String,
+    /// Mode names flrig offers for the radio (empty if it wouldn't say)
+    modes: Vec<String>,
+}
+
+impl FlrigClient {
+    /// Reach flrig at `address` ("host:port") and ask which radio it runs
+    ///
+    /// Returns the client and the transceiver name flrig reports.
+    pub async fn connect(address: &str) -> io::Result<(Self, String)> {
+        let mut client = Self {
+            address: address.trim().to_string(),
+            modes: Vec::new(),
+        };
+        let name = client
+            .call("rig.get_xcvr", &[])
+            .await?
+            .into_iter()
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| "flrig".to_string());
+        match client.call("rig.get_modes", &[]).await {
+            Ok(modes) => client.modes = modes,
+            Err(e) => debug!("flrig at {} has no mode list: {}", client.address, e),
+        }
+        Ok((client, name))
+    }
+
+    /// Make one XML-RPC call, returning the reply's values (arrays flattened)
+    async fn call(&self, method: &str, params: &[Param]) -> io::Result<Vec<String>> {
+        let body = method_call(method, params);
+        let request = format!(
+            "POST /RPC2 HTTP/1.1\r\nHost: {}\r\nContent-Type: text/xml\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.address,
+            body.len(),
+            body
+        );
+        let exchange = async {
+            let mut stream = TcpStream::connect(&self.address).await?;
+            stream.write_all(request.as_bytes()).await?;
+            let mut reply = Vec::new();
+            stream.take(MAX_REPLY_BYTES).read_to_end(&mut reply).await?;
+            Ok::<_, io::Error>(reply)
+        };
+        let reply = tokio::time::timeout(CALL_TIMEOUT, exchange)
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "flrig timed out"))??;
+        let reply = String::from_utf8_lossy(&reply);
+        let status = reply.lines().next().unwrap_or_default();
+        if !status
+            .split_whitespace()
+            .nth(1)
+            .is_some_and(|code| code.starts_with('2'))
+        {
+            return Err(io::Error::other(format!("flrig answered '{}'", status)));
+        }
+        let body = reply.split_once("\r\n\r\n").map_or("", |(_, body)| body);
+        parse_response(body)
+    }
+
+    /// Name flrig uses for `mode`, preferring one it listed for the radio
+    fn mode_name(&self, mode: OperatingMode) -> &'static str {
+        let names = mode_names(mode);
+        names
+            .iter()
+            .find(|name| self.modes.iter().any(|m| m.eq_ignore_ascii_case(name)))
+            .unwrap_or(&names[0])
+    }
+
+    /// The XML-RPC calls that carry out `request` (empty if flrig can't)
+    fn calls_for(&self, request: &RadioRequest) -> Vec<(&'static str, Param)> {
+        let freq = |hz: u64| ("rig.set_vfo", Param::Double(hz as f64));
+        let mode = |mode| ("rig.set_mode", Param::Str(self.mode_name(mode).to_string()));
+        match *request {
+            RadioRequest::SetFrequency { hz } => vec![freq(hz.hz())],
+            RadioRequest::SetMode { mode: m } => vec![mode(m)],
+            RadioRequest::SetFrequencyMode { hz, mode: m } => vec![mode(m), freq(hz.hz())],
+            RadioRequest::SetPtt { active } => {
+                vec![("rig.set_ptt", Param::Int(active as i64))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Last state reported to the mux
+#[derive(Debug, Default)]
+struct Reported {
+    ptt: Option<bool>,
+    hz: Option<Frequency>,
+    mode: Option<String>,
+}
+
+impl Reported {
+    /// Poll flrig, returning responses for whatever changed since last time
+    async fn poll(&mut self, client: &FlrigClient) -> io::Result<Vec<(RadioResponse, Vec<u8>)>> {
+        let mut frames = Vec::new();
+
+        let ptt = first(client.call("rig.get_ptt", &[]).await?)?;
+        let active = ptt.trim() != "0";
+        if self.ptt != Some(active) {
+            self.ptt = Some(active);
+            frames.push((RadioResponse::Ptt { active }, traffic("rig.get_ptt", &ptt)));
+        }
+
+        let vfo = first(client.call("rig.get_vfo", &[]).await?)?;
+        let hz = vfo
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|hz| *hz > 0.0)
+            .and_then(Frequency::from_hz_f64);
+        if let Some(hz) = hz.filter(|&hz| self.hz != Some(hz)) {
+            self.hz = Some(hz);
+            frames.push((
+                RadioResponse::Frequency { hz },
+                traffic("rig.get_vfo", &vfo),
+            ));
+        }
+
+        let mode = first(client.call("rig.get_mode", &[]).await?)?;
+        if self.mode.as_deref() != Some(mode.as_str()) {
+            // Not passed on as unknown: the amp must never see flrig's text
+            match parse_mode(&mode) {
+                Some(parsed) => frames.push((
+                    RadioResponse::Mode { mode: parsed },
+                    traffic("rig.get_mode", &mode),
+                )),
+                None => debug!("flrig mode '{}' has no equivalent", mode),
+            }
+            self.mode = Some(mode);
+        }
+
+        Ok(frames)
+    }
+}
+
+/// Follow (and optionally drive) a radio through flrig until shutdown or
+/// until flrig stops answering
+///
+/// Emits [`MuxEvent::RadioDisconnected`] when it stops, like a radio's read
+/// loop.
+pub async fn run_flrig_client(
+    handle: RadioHandle,
+    client: FlrigClient,
+    options: FlrigOptions,
+    mux: MuxClient,
+    mut cmd_rx: tokio_mpsc::Receiver<RadioTaskCommand>,
+) {
+    use tokio::time::{interval, MissedTickBehavior};
+
+    info!(
+        "Following radio {:?} through flrig at {}",
+        handle, client.address
+    );
+
+    let mut reported = Reported::default();
+    let mut poll_timer = interval(options.poll_interval);
+    poll_timer.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+    let error = loop {
+        tokio::select! {
+            cmd = cmd_rx.recv() => {
+                match cmd {
+                    Some(RadioTaskCommand::Shutdown) | None => {
+                        info!("Shutdown requested for radio {:?}", handle);
+                        break None;
+                    }
+                    Some(RadioTaskCommand::SendRequest { request }) => {
+                        if matches!(
+                            request,
+                            RadioRequest::GetFrequency | RadioRequest::GetMode | RadioRequest::GetPtt
+                        ) {
+                            // Answer on the next poll even if nothing changed
+                            reported = Reported::default();
+                            poll_timer.reset_immediately();
+                            continue;
+                        }
+                        let calls = client.calls_for(&request);
+                        if calls.is_empty() || !options.push {
+                            debug!("Not sending {:?} to radio {:?} through flrig", request, handle);
+                            continue;
+                        }
+                        for (method, param) in calls {
+                            let data = traffic(method, &param_text(&param));
+                            match client.call(method, &[param]).await {
+                                Ok(_) => {
+                                    let _ = mux
+                                        .send(MuxActorCommand::RadioRawDataOut { handle, data })
+                                        .await;
+                                }
+                                Err(e) => warn!("flrig {} for radio {:?} failed: {}", method, handle, e),
+                            }
+                        }
+                    }
+                    Some(RadioTaskCommand::SendData { .. } | RadioTaskCommand::SetProtocol { .. }) => {
+                        // flrig speaks to the radio itself; raw frames have nowhere to go
+                    }
+                }
+            }
+
+            _ = poll_timer.tick() => {
+                match reported.poll(&client).await {
+                    // Sent even when nothing changed: it keeps the radio from going stale
+                    Ok(frames) => {
+                        let _ = mux
+                            .send(MuxActorCommand::RadioResponses { handle, frames })
+                            .await;
+                    }
+                    Err(e) => break Some(e),
+                }
+            }
+        }
+    };
+
+    if let Some(e) = error {
+        warn!("flrig poll for radio {:?} failed: {}", handle, e);
+        let _ = mux
+            .emit(MuxEvent::Error {
+                source: format!("flrig {}", client.address),
+                message: format!("Poll failed: {}", e),
+            })
+            .await;
+    }
+    info!("flrig client ended for radio {:?}", handle);
+    let _ = mux.emit(MuxEvent::RadioDisconnected { handle }).await;
+}
+
+/// Operating mode for one of flrig's mode names (these come from the
+/// radio's own menus, so several spellings mean the same thing)
+pub fn parse_mode(name: &str) -> Option<OperatingMode> {
+    let mode = match name.trim().to_ascii_uppercase().as_str() {
+        "LSB" => OperatingMode::Lsb,
+        "USB" => OperatingMode::Usb,
+        "CW" | "CW-U" | "CW-USB" => OperatingMode::Cw,
+        "CW-R" | "CWR" | "CW-L" | "CW-LSB" => OperatingMode::CwR,
+        "AM" | "AM-N" | "AMN" => OperatingMode::Am,
+        "FM" | "FM-W" | "WFM" => OperatingMode::Fm,
+        "FM-N" | "NFM" | "FMN" => OperatingMode::FmN,
+        "RTTY" | "RTTY-L" | "FSK" | "RTTY-LSB" => OperatingMode::Rtty,
+        "RTTY-R" | "RTTY-U" | "FSK-R" | "RTTYR" | "RTTY-USB" => OperatingMode::RttyR,
+        "DATA" | "DIG" => OperatingMode::Data,
+        "USB-D" | "USB-D1" | "D-USB" | "DATA-U" | "DATA-USB" | "PKT-U" | "DIGU" => {
+            OperatingMode::DataU
+        }
+        "LSB-D" | "LSB-D1" | "D-LSB" | "DATA-L" | "DATA-LSB" | "PKT-L" | "DIGL" => {
+            OperatingMode::DataL
+        }
+        "PKT" | "PKT-FM" | "DATA-FM" | "FM-D" => OperatingMode::Pkt,
+        _ => return None,
+    };
+    Some(mode)
+}
+
+/// flrig's names for `mode`, most common first
+fn mode_names(mode: OperatingMode) -> &'static [&'static str] {
+    match mode {
+        OperatingMode::Lsb => &["LSB"],
+        OperatingMode::Usb => &["USB"],
+        OperatingMode::Cw => &["CW", "CW-U", "CW-USB"],
+        OperatingMode::CwR => &["CW-R", "CWR", "CW-L", "CW-LSB"],
+        OperatingMode::Am => &["AM"],
+        OperatingMode::Fm => &["FM"],
+        OperatingMode::FmN => &["FM-N", "NFM", "FMN"],
+        OperatingMode::Rtty => &["RTTY", "FSK", "RTTY-L"],
+        OperatingMode::RttyR => &["RTTY-R", "FSK-R", "RTTY-U"],
+        OperatingMode::Dig | OperatingMode::Data | OperatingMode::DataU | OperatingMode::DigU => {
+            &["USB-D", "DATA-U", "PKT-U", "D-USB", "USB-D1", "DIGU"]
+        }
+        OperatingMode::DataL | OperatingMode::DigL => {
+            &["LSB-D", "DATA-L", "PKT-L", "D-LSB", "LSB-D1", "DIGL"]
+        }
+        OperatingMode::Pkt => &["PKT-FM", "DATA-FM", "FM-D", "PKT"],
+    }
+}
+
+/// Traffic bytes for a call or reply
+fn traffic(method: &str, value: &str) -> Vec<u8> {
+    format!("{} {}", method, value.trim()).into_bytes()
+}
+
+fn param_text(param: &Param) -> String {
+    match param {
+        Param::Int(v) => v.to_string(),
+        Param::Double(v) => v.to_string(),
+        Param::Str(v) => v.clone(),
+    }
+}
+
+/// The single value a getter returns
+fn first(values: Vec<String>) -> io::Result<String> {
+    values
+        .into_iter()
+        .next()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "flrig returned no value"))
+}
+
+/// XML body of a method call
+fn method_call(method: &str, params: &[Param]) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\"?><methodCall><methodName>{}</methodName><params>",
+        method
+    );
+    for param in params {
+        let value = match param {
+            Param::Int(v) => format!("<i4>{}</i4>", v),
+            Param::Double(v) => format!("<double>{}</double>", v),
+            Param::Str(v) => format!("<string>{}</string>", xml_escape(v)),
+        };
+        xml.push_str(&format!("<param><value>{}</value></param>", value));
+    }
+    xml.push_str("</params></methodCall>");
+    xml
+}
+
+/// Scalar values in a method response, in order (array members flattened)
+///
+/// A fault becomes an error carrying flrig's fault string.
+fn parse_response(body: &str) -> io::Result<Vec<String>> {
+    let values = scalar_values(body);
+    if body.contains("<fault>") {
+        // The fault struct's members are faultCode then faultString
+        let message = values.last().cloned().unwrap_or_default();
+        return Err(io::Error::other(format!("flrig fault: {}", message)));
+    }
+    if !body.contains("<methodResponse>") {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "not an XML-RPC response",
+        ));
+    }
+    Ok(values)
+}
+
+/// Text of every `<value>` that holds a scalar
+fn scalar_values(body: &str) -> Vec<String> {
+    let mut values = Vec::new();
+    let mut rest = body;
+    while let Some(start) = rest.find("<value>") {
+        rest = &rest[start + "<value>".len()..];
+        let Some(end) = rest.find("</value>") else {
+            break;
+        };
+        let inner = &rest[..end];
+        // Arrays and structs nest further values; step into them instead
+        if inner.contains("<array>") || inner.contains("<struct>") || inner.contains("<value>") {
+            continue;
+        }
+        values.push(xml_unescape(strip_tags(inner).trim()));
+        rest = &rest[end..];
+    }
+    values
+}
+
+/// `s` with its markup removed (`<string>USB</string>` becomes `USB`)
+fn strip_tags(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut in_tag = false;
+    for c in s.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::net::TcpListener;
+
+    fn reply(value: &str) -> String {
+        format!(
+            "<?xml version=\"1.0\"?><methodResponse><params><param>\
+             <value>{}</value></param></params></methodResponse>",
+            value
+        )
+    }
+
+    #[test]
+    fn test_parse_response_values() {
+        assert_eq!(
+            parse_response(&reply("<string>14074000</string>")).unwrap(),
+            vec!["14074000"]
+        );
+        // Untyped values are strings
+        assert_eq!(parse_response(&reply("USB")).unwrap(), vec!["USB"]);
+        assert_eq!(
+            parse_response(&reply(
+                "<array><data><value>LSB</value><value><string>USB-D</string></value>\
+                 <value>CW&amp;R</value></data></array>"
+            ))
+            .unwrap(),
+            vec!["LSB", "USB-D", "CW&R"]
+        );
+
+        let fault = "<?xml version=\"1.0\"?><methodResponse><fault><value><struct>\
+                     <member><name>faultCode</name><value><i4>-1</i4></value></member>\
+                     <member><name>faultString</name><value>no such method</value></member>\
+                     </struct></value></fault></methodResponse>";
+        let err = parse_response(fault).unwrap_err();
+        assert!(err.to_string().contains("no such method"));
+    }
+
+    #[test]
+    fn test_method_call_encodes_params() {
+        assert_eq!(
+            method_call(
+                "rig.set_mode",
+                &[Param::Str("CW<R>".into()), Param::Double(7074000.0)]
+            ),
+            "<?xml version=\"1.0\"?><methodCall><methodName>rig.set_mode</methodName><params>\
+             <param><value><string>CW&lt;R&gt;</string></value></param>\
+             <param><value><double>7074000</double></value></param></params></methodCall>"
+        );
+    }
+
+    #[test]
+    fn test_mode_names_round_trip() {
+        assert_eq!(parse_mode("usb-d"), Some(OperatingMode::DataU));
+        assert_eq!(parse_mode("CW-R"), Some(OperatingMode::CwR));
+        assert_eq!(parse_mode("C4FM"), None);
+
+        let client = FlrigClient {
+            address: String::new(),
+            modes: vec!["LSB".into(), "USB".into(), "DATA-U".into()],
+        };
+        // Prefers the spelling the radio uses
+        assert_eq!(client.mode_name(OperatingMode::DataU), "DATA-U");
+        assert_eq!(client.mode_name(OperatingMode::Cw), "CW");
+    }
+
+    /// A fake flrig answering from fixed values and recording set calls
+    async fn fake_flrig() -> (String, tokio_mpsc::UnboundedReceiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let (calls_tx, calls_rx) = tokio_mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let mut stream = tokio::io::BufReader::new(stream);
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    stream.read_line(&mut line).await.unwrap();
+                    if let Some(v) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = v.trim().parse().unwrap();
+                    }
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut body = vec![0; length];
+                stream.read_exact(&mut body).await.unwrap();
+                let body = String::from_utf8(body).unwrap();
+                let method = body
+                    .split("<methodName>")
+                    .nth(1)
+                    .and_then(|s| s.split("</methodName>").next())
+                    .unwrap()
+                    .to_string();
+                let value = match method.as_str() {
+                    "rig.get_xcvr" => "FT-991A",
+                    "rig.get_modes" => {
+                        "<array><data><value>USB</value><value>DATA-U</value></data></array>"
+                    }
+                    "rig.get_ptt" => "<i4>0</i4>",
+                    "rig.get_vfo" => "14074000",
+                    "rig.get_mode" => "DATA-U",
+                    _ => {
+                        let _ = calls_tx.send(body.clone());
+                        "<i4>0</i4>"
+                    }
+                };
+                let body = reply(value);
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.get_mut().write_all(response.as_bytes()).await;
+            }
+        });
+        (address, calls_rx)
+    }
+
+    #[tokio::test]
+    async fn test_client_reports_changes_and_pushes_sets() {
+        let (address, mut calls) = fake_flrig().await;
+        let (client, name) = FlrigClient::connect(&address).await.unwrap();
+        assert_eq!(name, "FT-991A");
+
+        let (mux, mut mux_rx, mut event_rx) = MuxClient::channel(256);
+        let (cmd_tx, cmd_rx) = tokio_mpsc::channel(8);
+        let options = FlrigOptions {
+            poll_interval: Duration::from_millis(20),
+            push: true,
+        };
+        let task = tokio::spawn(run_flrig_client(
+            RadioHandle(1),
+            client,
+            options,
+            mux,
+            cmd_rx,
+        ));
+
+        let Some(MuxActorCommand::RadioResponses { frames, .. }) = mux_rx.recv().await else {
+            panic!("expected responses");
+        };
+        let responses: Vec<_> = frames.into_iter().map(|(r, _)| r).collect();
+        assert_eq!(
+            responses,
+            vec![
+                RadioResponse::Ptt { active: false },
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_074_000)
+                },
+                RadioResponse::Mode {
+                    mode: OperatingMode::DataU
+                },
+            ]
+        );
+        // Nothing changed: the next poll only keeps the radio alive
+        let Some(MuxActorCommand::RadioResponses { frames, .. }) = mux_rx.recv().await else {
+            panic!("expected responses");
+        };
+        assert!(frames.is_empty());
+
+        cmd_tx
+            .send(RadioTaskCommand::SendRequest {
+                request: RadioRequest::SetMode {
+                    mode: OperatingMode::DataU,
+                },
+            })
+            .await
+            .unwrap();
+        let call = calls.recv().await.unwrap();
+        assert!(call.contains("rig.set_mode"));
+        assert!(call.contains("<string>DATA-U</string>"));
+
+        cmd_tx.send(RadioTaskCommand::Shutdown).await.unwrap();
+        task.await.unwrap();
+        assert!(matches!(
+            event_rx.recv().await,
+            Some(MuxEvent::RadioDisconnected { .. })
+        ));
+    }
+}
@@ -0,0 +1,285 @@
+//! Direct frequency entry
+//!
+//! A typed frequency is checked against the radio it is for before it is
+//! sent: the tuning range and step from its [`RadioCapabilities`], and, for
+//! a frequency to transmit on, the amateur bands in the operator's ITU region
+//! (see [`crate::band_plan`]). A radio told to tune
+//! somewhere it can't clamps or ignores the command without saying so;
+//! these checks say what was wrong instead.
+//!
+//! Frequencies here are on-air. A radio behind a transverter is checked at
+//! its dial frequency, and errors report its limits shifted back on-air.
+
+use std::fmt;
+
+use cat_protocol::display::format_frequency;
+use cat_protocol::{Frequency, RadioCapabilities};
+
+use crate::band_plan::{amateur_band, BandPlanRegion};
+
+/// Why a typed frequency wasn't sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FrequencyEntryError {
+    /// The text isn't a frequency
+    Unparsable(String),
+    /// Outside what the radio can tune
+    OutOfRange {
+        /// The frequency asked for
+        hz: Frequency,
+        /// Lowest the radio tunes
+        min_hz: Frequency,
+        /// Highest the radio tunes
+        max_hz: Frequency,
+    },
+    /// Between the radio's tuning steps
+    OffStep {
+        /// The frequency asked for
+        hz: Frequency,
+        /// The radio's tuning step
+        step_hz: Frequency,
+        /// The closest frequency the radio can tune
+        nearest: Frequency,
+    },
+    /// Outside the amateur bands, asked for as a transmit frequency
+    TxNotPermitted {
+        /// The frequency asked for
+        hz: Frequency,
+        /// Band plan it was checked against
+        region: BandPlanRegion,
+    },
+    /// No radio with that handle
+    UnknownRadio,
+    /// The radio has no command channel to send it on
+    NotConnected,
+}
+
+impl fmt::Display for FrequencyEntryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unparsable(text) => write!(f, "'{}' is not a frequency", text),
+            Self::OutOfRange { hz, min_hz, max_hz } => write!(
+                f,
+                "{} is outside the radio's range ({} to {})",
+                format_frequency(*hz),
+                format_frequency(*min_hz),
+                format_frequency(*max_hz)
+            ),
+            Self::OffStep {
+                hz,
+                step_hz,
+                nearest,
+            } => write!(
+                f,
+                "{} is not on the radio's {} Hz step (nearest {})",
+                format_frequency(*hz),
+                step_hz.hz(),
+                format_frequency(*nearest)
+            ),
+            Self::TxNotPermitted { hz, region } => write!(
+                f,
+                "{} is outside the amateur bands ({}); not permitted for transmit",
+                format_frequency(*hz),
+                region.name()
+            ),
+            Self::UnknownRadio => f.write_str("no such radio"),
+            Self::NotConnected => f.write_str("radio is not connected"),
+        }
+    }
+}
+
+impl std::error::Error for FrequencyEntryError {}
+
+/// Parse a typed frequency
+///
+/// A unit suffix (`MHz`, `kHz`, `Hz`, or just `M`/`k`) is optional. Without
+/// one, numbers below 1000 are MHz (`14.074`), below 1,000,000 kHz
+/// (`14074`), and anything larger Hz. Anything the app displays parses
+/// back: with both `.` and `,`, the last is the decimal separator
+/// (`14,250.00 kHz`, `14.250,00 kHz`); with one repeated, the first is
+/// (`14.250.000 MHz`).
+pub fn parse_frequency(text: &str) -> Result<Frequency, FrequencyEntryError> {
+    let unparsable = || FrequencyEntryError::Unparsable(text.trim().to_string());
+    let lower = text.trim().to_ascii_lowercase();
+    let (number, scale) = if let Some(n) = lower.strip_suffix("mhz").or(lower.strip_suffix('m')) {
+        (n, Some(1_000_000.0))
+    } else if let Some(n) = lower.strip_suffix("khz").or(lower.strip_suffix('k')) {
+        (n, Some(1_000.0))
+    } else if let Some(n) = lower.strip_suffix("hz") {
+        (n, Some(1.0))
+    } else {
+        (lower.as_str(), None)
+    };
+
+    let number: String = number.chars().filter(|c| !c.is_whitespace()).collect();
+    let decimal = match (number.rfind('.'), number.rfind(',')) {
+        (Some(dot), Some(comma)) => Some(dot.max(comma)),
+        _ => number.find(['.', ',']),
+    };
+    let number: String = number
+        .char_indices()
+        .filter_map(|(i, c)| match c {
+            '.' | ',' if Some(i) == decimal => Some('.'),
+            '.' | ',' => None,
+            c => Some(c),
+        })
+        .collect();
+    let value: f64 = number.parse().map_err(|_| unparsable())?;
+    let scale = scale.unwrap_or(if value < 1_000.0 {
+        1_000_000.0
+    } else if value < 1_000_000.0 {
+        1_000.0
+    } else {
+        1.0
+    });
+    Frequency::from_hz_f64(value * scale)
+        .filter(|hz| *hz > Frequency::ZERO)
+        .ok_or_else(unparsable)
+}
+
+/// Check an on-air frequency against a radio before tuning it there
+///
+/// `offset_hz` is the radio's dial-to-air offset (0 without a transverter).
+/// Without `capabilities` (the model isn't known) only the band plan is
+/// checked, and only when `transmit` is set.
+pub fn check_frequency(
+    hz: Frequency,
+    offset_hz: i64,
+    capabilities: Option<&RadioCapabilities>,
+    transmit: bool,
+    region: BandPlanRegion,
+) -> Result<(), FrequencyEntryError> {
+    if let Some(caps) = capabilities {
+        let out_of_range = || FrequencyEntryError::OutOfRange {
+            hz,
+            min_hz: caps.min_frequency_hz.saturating_offset(offset_hz),
+            max_hz: caps.max_frequency_hz.saturating_offset(offset_hz),
+        };
+        let dial = hz
+            .checked_offset(offset_hz.saturating_neg())
+            .ok_or_else(out_of_range)?;
+        if dial < caps.min_frequency_hz || dial > caps.max_frequency_hz {
+            return Err(out_of_range());
+        }
+
+        let step = caps.frequency_step_hz.hz();
+        if step > 1 && dial.hz() % step != 0 {
+            let down = dial.round_down(step);
+            let nearest = if dial.hz() - down.hz() >= step / 2
+                && down.hz() + step <= caps.max_frequency_hz.hz()
+            {
+                Frequency::from_hz(down.hz() + step)
+            } else {
+                down
+            };
+            return Err(FrequencyEntryError::OffStep {
+                hz,
+                step_hz: caps.frequency_step_hz,
+                nearest: nearest.saturating_offset(offset_hz),
+            });
+        }
+    }
+
+    if transmit && amateur_band(hz, region).is_none() {
+        return Err(FrequencyEntryError::TxNotPermitted { hz, region });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const R2: BandPlanRegion = BandPlanRegion::Region2;
+
+    #[test]
+    fn test_parse_units() {
+        let ft8 = Ok(Frequency::from_hz(14_074_000));
+        assert_eq!(parse_frequency("14.074"), ft8);
+        assert_eq!(parse_frequency("14,074"), ft8);
+        assert_eq!(parse_frequency("14074"), ft8);
+        assert_eq!(parse_frequency("14074000"), ft8);
+        assert_eq!(parse_frequency("14.074.000 MHz"), ft8);
+        assert_eq!(parse_frequency("14,074.00 kHz"), ft8);
+        assert_eq!(parse_frequency("14.074,00 kHz"), ft8);
+        assert_eq!(parse_frequency(" 14074 kHz "), ft8);
+        assert_eq!(parse_frequency("14.074M"), ft8);
+        assert_eq!(parse_frequency("14074000 Hz"), ft8);
+        assert_eq!(parse_frequency("1900"), Ok(Frequency::from_khz(1_900)));
+        assert_eq!(parse_frequency("144.3"), Ok(Frequency::from_khz(144_300)));
+
+        for bad in ["", "abc", "-7", "0", "14.0.7x"] {
+            assert!(matches!(
+                parse_frequency(bad),
+                Err(FrequencyEntryError::Unparsable(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_range_and_step() {
+        let caps = RadioCapabilities {
+            min_frequency_hz: Frequency::from_khz(30),
+            max_frequency_hz: Frequency::from_mhz(60),
+            frequency_step_hz: Frequency::from_hz(10),
+            ..RadioCapabilities::default()
+        };
+        assert_eq!(
+            check_frequency(Frequency::from_hz(14_074_000), 0, Some(&caps), true, R2),
+            Ok(())
+        );
+        assert_eq!(
+            check_frequency(Frequency::from_mhz(144), 0, Some(&caps), false, R2),
+            Err(FrequencyEntryError::OutOfRange {
+                hz: Frequency::from_mhz(144),
+                min_hz: Frequency::from_khz(30),
+                max_hz: Frequency::from_mhz(60),
+            })
+        );
+        assert_eq!(
+            check_frequency(Frequency::from_hz(14_074_006), 0, Some(&caps), false, R2),
+            Err(FrequencyEntryError::OffStep {
+                hz: Frequency::from_hz(14_074_006),
+                step_hz: Frequency::from_hz(10),
+                nearest: Frequency::from_hz(14_074_010),
+            })
+        );
+
+        // Behind a 2 m transverter with a 28 MHz IF, 144 MHz is a 28 MHz dial
+        let offset = 116_000_000;
+        assert_eq!(
+            check_frequency(Frequency::from_mhz(144), offset, Some(&caps), true, R2),
+            Ok(())
+        );
+        assert!(matches!(
+            check_frequency(Frequency::from_mhz(14), offset, Some(&caps), false, R2),
+            Err(FrequencyEntryError::OutOfRange { min_hz, .. }) if min_hz == Frequency::from_khz(116_030)
+        ));
+    }
+
+    #[test]
+    fn test_transmit_needs_an_amateur_band() {
+        let broadcast = Frequency::from_khz(9_500);
+        assert_eq!(check_frequency(broadcast, 0, None, false, R2), Ok(()));
+        assert_eq!(
+            check_frequency(broadcast, 0, None, true, R2),
+            Err(FrequencyEntryError::TxNotPermitted {
+                hz: broadcast,
+                region: R2
+            })
+        );
+        // 3.9 MHz is in the band in the Americas, not in Europe
+        let phone = Frequency::from_khz(3_900);
+        assert_eq!(check_frequency(phone, 0, None, true, R2), Ok(()));
+        assert_eq!(
+            check_frequency(phone, 0, None, true, BandPlanRegion::Region1),
+            Err(FrequencyEntryError::TxNotPermitted {
+                hz: phone,
+                region: BandPlanRegion::Region1
+            })
+        );
+        assert_eq!(
+            check_frequency(broadcast, 0, Some(&RadioCapabilities::default()), false, R2),
+            Ok(())
+        );
+    }
+}
@@ -0,0 +1,751 @@
+//! gRPC control API
+//!
+//! For integrators who want a typed API rather than rigctl's text protocol.
+//! The `Mux` service defined in `proto/catapult/v1/mux.proto` mirrors the
+//! actor: its RPCs are the commands and queries [`MuxClient`] offers, and
+//! `StreamEvents` passes on the [`MuxEvent`] stream. Server reflection is
+//! on, so `grpcurl` and similar tools can list and call the RPCs without the
+//! proto file. Built only with the `grpc` feature.
+//!
+//! As with the metrics server, the host forwards mux events through a
+//! channel and the server runs until it closes. Each `StreamEvents` call
+//! gets its own copy of the events; one that falls more than
+//! [`EVENT_BUFFER`] events behind is sent an `EventsDropped` count instead
+//! of being cut off. Events the host couldn't queue for the server at all
+//! are counted too, and every stream is sent their count the same way.
+
+// Handlers answer with tonic's `Status`, large as it is
+#![allow(clippy::result_large_err)]
+
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use cat_protocol::{Frequency, OperatingMode, RadioRequest};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tracing::info;
+
+use crate::actor::{MuxActorCommand, RadioStateSummary};
+use crate::client::{ClientError, MuxClient, Reply};
+use crate::events::MuxEvent;
+use crate::peer_sync::unix_ms;
+use crate::state::{AmplifierEmulatedState, RadioHandle, SwitchingMode};
+use crate::switch_audit::SwitchRecord;
+use crate::tx_stats::TxBandStats;
+
+/// Code generated from `proto/catapult/v1/mux.proto`
+pub mod proto {
+    tonic::include_proto!("catapult.v1");
+
+    /// Encoded descriptors of the service, for reflection
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        tonic::include_file_descriptor_set!("catapult_descriptor");
+}
+
+use proto::mux_server::{Mux, MuxServer};
+
+/// Default listen address (50051 is the customary gRPC port)
+pub const DEFAULT_GRPC_ADDR: &str = "127.0.0.1:50051";
+
+/// Events a `StreamEvents` call may fall behind by before some are dropped
+pub const EVENT_BUFFER: usize = 1024;
+
+/// Bookmark source when a client doesn't give one
+const DEFAULT_BOOKMARK_SOURCE: &str = "gRPC";
+
+impl From<ClientError> for Status {
+    fn from(e: ClientError) -> Self {
+        match e {
+            ClientError::Full => Status::resource_exhausted(e.to_string()),
+            ClientError::Closed | ClientError::NoReply => Status::unavailable(e.to_string()),
+        }
+    }
+}
+
+/// Queue a query and wait for the actor's reply
+async fn ask<T>(reply: Result<Reply<T>, ClientError>) -> Result<T, Status> {
+    Ok(reply?.await?)
+}
+
+/// The `Mux` service, backed by a running actor
+struct MuxService {
+    mux: MuxClient,
+    /// Kept only to subscribe new event streams; never read
+    events: broadcast::Receiver<proto::Event>,
+}
+
+impl MuxService {
+    /// Queue a command that has no reply
+    fn send(&self, cmd: MuxActorCommand) -> Result<Response<proto::Ack>, Status> {
+        self.mux.try_send(cmd)?;
+        Ok(Response::new(proto::Ack {}))
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<proto::Event, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl Mux for MuxService {
+    async fn get_active_radio(
+        &self,
+        _request: Request<proto::GetActiveRadioRequest>,
+    ) -> Result<Response<proto::GetActiveRadioResponse>, Status> {
+        let active = ask(self.mux.active_radio()).await?;
+        Ok(Response::new(proto::GetActiveRadioResponse {
+            radio: active.map(|(handle, summary)| radio_state(handle, &summary)),
+        }))
+    }
+
+    async fn get_radio_state(
+        &self,
+        request: Request<proto::GetRadioStateRequest>,
+    ) -> Result<Response<proto::RadioState>, Status> {
+        let handle = RadioHandle(request.into_inner().handle);
+        match ask(self.mux.radio_state(handle)).await? {
+            Some(summary) => Ok(Response::new(radio_state(handle, &summary))),
+            None => Err(Status::not_found(format!("no radio {}", handle.0))),
+        }
+    }
+
+    async fn get_amp_state(
+        &self,
+        _request: Request<proto::GetAmpStateRequest>,
+    ) -> Result<Response<proto::AmpState>, Status> {
+        let state = ask(self.mux.amp_state()).await?;
+        Ok(Response::new(amp_state(&state)))
+    }
+
+    async fn get_switch_audit(
+        &self,
+        request: Request<proto::GetSwitchAuditRequest>,
+    ) -> Result<Response<proto::GetSwitchAuditResponse>, Status> {
+        let limit = match request.into_inner().limit {
+            0 => usize::MAX,
+            limit => limit as usize,
+        };
+        let records = ask(self.mux.switch_audit(limit)).await?;
+        Ok(Response::new(proto::GetSwitchAuditResponse {
+            records: records.iter().map(switch_record).collect(),
+        }))
+    }
+
+    async fn get_tx_stats(
+        &self,
+        _request: Request<proto::GetTxStatsRequest>,
+    ) -> Result<Response<proto::GetTxStatsResponse>, Status> {
+        let stats = ask(self.mux.tx_stats()).await?;
+        Ok(Response::new(proto::GetTxStatsResponse {
+            stats: stats.iter().map(tx_band_stats).collect(),
+        }))
+    }
+
+    async fn control_active_radio(
+        &self,
+        request: Request<proto::ControlActiveRadioRequest>,
+    ) -> Result<Response<proto::ControlActiveRadioResponse>, Status> {
+        let request = radio_request(request.into_inner())?;
+        let delivered = ask(self.mux.control_active_radio(request)).await?;
+        Ok(Response::new(proto::ControlActiveRadioResponse {
+            delivered,
+        }))
+    }
+
+    async fn set_active_radio(
+        &self,
+        request: Request<proto::SetActiveRadioRequest>,
+    ) -> Result<Response<proto::Ack>, Status> {
+        let handle = RadioHandle(request.into_inner().handle);
+        self.send(MuxActorCommand::SetActiveRadio { handle })
+    }
+
+    async fn confirm_switch(
+        &self,
+        request: Request<proto::ConfirmSwitchRequest>,
+    ) -> Result<Response<proto::Ack>, Status> {
+        let proto::ConfirmSwitchRequest { token, confirm } = request.into_inner();
+        self.send(MuxActorCommand::ConfirmSwitch { token, confirm })
+    }
+
+    async fn set_switching_mode(
+        &self,
+        request: Request<proto::SetSwitchingModeRequest>,
+    ) -> Result<Response<proto::Ack>, Status> {
+        let mode = switching_mode_from_proto(request.into_inner().mode)
+            .ok_or_else(|| Status::invalid_argument("unknown switching mode"))?;
+        self.send(MuxActorCommand::SetSwitchingMode { mode })
+    }
+
+    async fn request_ptt(
+        &self,
+        request: Request<proto::RequestPttRequest>,
+    ) -> Result<Response<proto::Ack>, Status> {
+        let proto::RequestPttRequest { handle, active } = request.into_inner();
+        self.send(MuxActorCommand::RequestPtt {
+            handle: RadioHandle(handle),
+            active,
+        })
+    }
+
+    async fn add_bookmark(
+        &self,
+        request: Request<proto::AddBookmarkRequest>,
+    ) -> Result<Response<proto::Ack>, Status> {
+        let source = request.into_inner().source.trim().to_string();
+        let source = if source.is_empty() {
+            DEFAULT_BOOKMARK_SOURCE.to_string()
+        } else {
+            source
+        };
+        self.send(MuxActorCommand::AddBookmark { source })
+    }
+
+    type StreamEventsStream = EventStream;
+
+    async fn stream_events(
+        &self,
+        request: Request<proto::StreamEventsRequest>,
+    ) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let include_traffic = request.into_inner().include_traffic;
+        let stream =
+            BroadcastStream::new(self.events.resubscribe()).filter_map(move |item| match item {
+                Ok(event) => {
+                    let traffic = matches!(event.kind, Some(proto::event::Kind::Traffic(_)));
+                    (include_traffic || !traffic).then_some(Ok(event))
+                }
+                Err(BroadcastStreamRecvError::Lagged(count)) => Some(Ok(proto::Event {
+                    timestamp_ms: unix_ms(SystemTime::now()),
+                    kind: Some(proto::event::Kind::EventsDropped(proto::EventsDropped {
+                        count,
+                    })),
+                })),
+            });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// `EventsDropped` for the events counted in `dropped` since the last call
+fn dropped_notice(dropped: &AtomicU64, now: SystemTime) -> Option<proto::Event> {
+    let count = dropped.swap(0, Ordering::Relaxed);
+    (count > 0).then(|| proto::Event {
+        timestamp_ms: unix_ms(now),
+        kind: Some(proto::event::Kind::EventsDropped(proto::EventsDropped {
+            count,
+        })),
+    })
+}
+
+/// Serve the `Mux` service (and reflection) until the event channel closes
+///
+/// The host forwards every event the actor emits through `events`, and adds
+/// to `dropped` the ones it had to drop because the channel was full. Open
+/// event streams end when the server stops.
+pub async fn run_grpc_server(
+    listener: TcpListener,
+    mux: MuxClient,
+    mut events: mpsc::Receiver<MuxEvent>,
+    dropped: Arc<AtomicU64>,
+) -> Result<(), tonic::transport::Error> {
+    let (event_tx, event_rx) = broadcast::channel(EVENT_BUFFER);
+    let service = MuxService {
+        mux,
+        events: event_rx,
+    };
+    // The descriptor set is compiled in, so these can't fail
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .build_v1()
+        .expect("valid file descriptor set");
+    let reflection_alpha = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+        .build_v1alpha()
+        .expect("valid file descriptor set");
+
+    let forward = async move {
+        while let Some(event) = events.recv().await {
+            // Fails only when no one is streaming
+            if let Some(notice) = dropped_notice(&dropped, SystemTime::now()) {
+                let _ = event_tx.send(notice);
+            }
+            if let Some(event) = event_to_proto(&event, SystemTime::now()) {
+                // Fails only when no one is streaming
+                let _ = event_tx.send(event);
+            }
+        }
+        // Dropping the sender ends open event streams
+    };
+
+    if let Ok(addr) = listener.local_addr() {
+        info!("Serving gRPC on {}", addr);
+    }
+    tonic::transport::Server::builder()
+        .add_service(MuxServer::new(service))
+        .add_service(reflection)
+        .add_service(reflection_alpha)
+        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), forward)
+        .await?;
+    info!("gRPC server stopped");
+    Ok(())
+}
+
+fn mode_to_proto(mode: OperatingMode) -> proto::Mode {
+    match mode {
+        OperatingMode::Lsb => proto::Mode::Lsb,
+        OperatingMode::Usb => proto::Mode::Usb,
+        OperatingMode::Cw => proto::Mode::Cw,
+        OperatingMode::CwR => proto::Mode::CwR,
+        OperatingMode::Am => proto::Mode::Am,
+        OperatingMode::Fm => proto::Mode::Fm,
+        OperatingMode::FmN => proto::Mode::FmN,
+        OperatingMode::Dig => proto::Mode::Dig,
+        OperatingMode::DigU => proto::Mode::DigU,
+        OperatingMode::DigL => proto::Mode::DigL,
+        OperatingMode::Pkt => proto::Mode::Pkt,
+        OperatingMode::Data => proto::Mode::Data,
+        OperatingMode::DataU => proto::Mode::DataU,
+        OperatingMode::DataL => proto::Mode::DataL,
+        OperatingMode::Rtty => proto::Mode::Rtty,
+        OperatingMode::RttyR => proto::Mode::RttyR,
+    }
+}
+
+fn mode_from_proto(value: i32) -> Option<OperatingMode> {
+    Some(match proto::Mode::try_from(value).ok()? {
+        proto::Mode::Unspecified => return None,
+        proto::Mode::Lsb => OperatingMode::Lsb,
+        proto::Mode::Usb => OperatingMode::Usb,
+        proto::Mode::Cw => OperatingMode::Cw,
+        proto::Mode::CwR => OperatingMode::CwR,
+        proto::Mode::Am => OperatingMode::Am,
+        proto::Mode::Fm => OperatingMode::Fm,
+        proto::Mode::FmN => OperatingMode::FmN,
+        proto::Mode::Dig => OperatingMode::Dig,
+        proto::Mode::DigU => OperatingMode::DigU,
+        proto::Mode::DigL => OperatingMode::DigL,
+        proto::Mode::Pkt => OperatingMode::Pkt,
+        proto::Mode::Data => OperatingMode::Data,
+        proto::Mode::DataU => OperatingMode::DataU,
+        proto::Mode::DataL => OperatingMode::DataL,
+        proto::Mode::Rtty => OperatingMode::Rtty,
+        proto::Mode::RttyR => OperatingMode::RttyR,
+    })
+}
+
+/// Proto enum value of an optional mode (unset is `MODE_UNSPECIFIED`)
+fn mode_value(mode: Option<OperatingMode>) -> i32 {
+    mode.map_or(proto::Mode::Unspecified, mode_to_proto) as i32
+}
+
+fn switching_mode_to_proto(mode: SwitchingMode) -> proto::SwitchingMode {
+    match mode {
+        SwitchingMode::Manual => proto::SwitchingMode::Manual,
+        SwitchingMode::FrequencyTriggered => proto::SwitchingMode::FrequencyTriggered,
+        SwitchingMode::Automatic => proto::SwitchingMode::Automatic,
+    }
+}
+
+fn switching_mode_from_proto(value: i32) -> Option<SwitchingMode> {
+    match proto::SwitchingMode::try_from(value).ok()? {
+        proto::SwitchingMode::Unspecified => None,
+        proto::SwitchingMode::Manual => Some(SwitchingMode::Manual),
+        proto::SwitchingMode::FrequencyTriggered => Some(SwitchingMode::FrequencyTriggered),
+        proto::SwitchingMode::Automatic => Some(SwitchingMode::Automatic),
+    }
+}
+
+fn radio_state(handle: RadioHandle, summary: &RadioStateSummary) -> proto::RadioState {
+    proto::RadioState {
+        handle: handle.0,
+        frequency_hz: summary.frequency_hz.map(Frequency::hz),
+        mode: mode_value(summary.mode),
+        ptt: summary.ptt,
+        power_watts: summary.power_watts.map(u32::from),
+        last_seen_ms: summary.last_seen.map(unix_ms),
+    }
+}
+
+fn amp_state(state: &AmplifierEmulatedState) -> proto::AmpState {
+    proto::AmpState {
+        frequency_hz: state.frequency_hz.map(Frequency::hz),
+        mode: mode_value(state.mode),
+        keyed: state.keyed,
+        band: state.band().map(str::to_string),
+    }
+}
+
+fn switch_record(record: &SwitchRecord) -> proto::SwitchRecord {
+    proto::SwitchRecord {
+        id: record.id,
+        timestamp_ms: unix_ms(record.timestamp),
+        from: record.from.map(|h| h.0),
+        to: record.to.0,
+        switching_mode: switching_mode_to_proto(record.mode) as i32,
+        chain: record.chain(|h| format!("radio {}", h.0)),
+    }
+}
+
+fn tx_band_stats(stats: &TxBandStats) -> proto::TxBandStats {
+    proto::TxBandStats {
+        handle: stats.handle.0,
+        band: stats.band.map(str::to_string),
+        transmissions: stats.transmissions,
+        total_ms: stats.total.as_millis() as u64,
+        longest_ms: stats.longest.as_millis() as u64,
+        duty_cycle: stats.duty_cycle,
+    }
+}
+
+/// The radio request a `ControlActiveRadio` call asks for
+fn radio_request(request: proto::ControlActiveRadioRequest) -> Result<RadioRequest, Status> {
+    use proto::control_active_radio_request::Request as R;
+
+    let invalid = |what: &str| Status::invalid_argument(what.to_string());
+    Ok(
+        match request.request.ok_or_else(|| invalid("no request"))? {
+            R::SetFrequencyHz(0) => return Err(invalid("frequency must be above 0 Hz")),
+            R::SetFrequencyHz(hz) => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(hz),
+            },
+            R::SetMode(mode) => RadioRequest::SetMode {
+                mode: mode_from_proto(mode).ok_or_else(|| invalid("unknown mode"))?,
+            },
+            R::SetPtt(active) => RadioRequest::SetPtt { active },
+            R::SetTxPowerWatts(watts) => RadioRequest::SetTxPower {
+                watts: u16::try_from(watts).map_err(|_| invalid("power out of range"))?,
+            },
+            R::SetKeyerSpeedWpm(wpm) => RadioRequest::SetKeyerSpeed {
+                wpm: u8::try_from(wpm).map_err(|_| invalid("keyer speed out of range"))?,
+            },
+        },
+    )
+}
+
+/// The event as sent to `StreamEvents` clients, or None if it isn't offered
+///
+/// Events without a time of their own are stamped `now`.
+fn event_to_proto(event: &MuxEvent, now: SystemTime) -> Option<proto::Event> {
+    use proto::event::Kind;
+    use proto::traffic::Direction;
+
+    let traffic = |direction: Direction,
+                   handle: Option<RadioHandle>,
+                   data: &[u8],
+                   protocol: cat_protocol::Protocol| {
+        Kind::Traffic(proto::Traffic {
+            direction: direction as i32,
+            handle: handle.map(|h| h.0),
+            data: data.to_vec(),
+            protocol: protocol.name().to_string(),
+        })
+    };
+
+    let kind = match event {
+        MuxEvent::RadioConnected { handle, meta } => Kind::RadioConnected(proto::RadioConnected {
+            handle: handle.0,
+            name: meta.display_name.clone(),
+            port: meta.port_name.clone(),
+            protocol: meta.protocol.name().to_string(),
+        }),
+        MuxEvent::RadioDisconnected { handle } => {
+            Kind::RadioDisconnected(proto::RadioDisconnected { handle: handle.0 })
+        }
+        MuxEvent::RadioStateChanged {
+            handle,
+            freq,
+            mode,
+            ptt,
+            power_watts,
+        } => Kind::RadioStateChanged(proto::RadioStateChanged {
+            handle: handle.0,
+            frequency_hz: freq.map(Frequency::hz),
+            mode: mode_value(*mode),
+            ptt: *ptt,
+            power_watts: power_watts.map(u32::from),
+        }),
+        MuxEvent::ActiveRadioChanged { from, to } => {
+            Kind::ActiveRadioChanged(proto::ActiveRadioChanged {
+                from: from.map(|h| h.0),
+                to: to.0,
+            })
+        }
+        MuxEvent::RadioStaleChanged { handle, stale, .. } => {
+            Kind::RadioStaleChanged(proto::RadioStaleChanged {
+                handle: handle.0,
+                stale: *stale,
+            })
+        }
+        MuxEvent::SwitchingModeChanged { mode } => {
+            Kind::SwitchingModeChanged(proto::SwitchingModeChanged {
+                mode: switching_mode_to_proto(*mode) as i32,
+            })
+        }
+        MuxEvent::SwitchingBlocked {
+            requested,
+            current,
+            remaining_ms,
+        } => Kind::SwitchingBlocked(proto::SwitchingBlocked {
+            requested: requested.0,
+            current: current.0,
+            remaining_ms: *remaining_ms,
+        }),
+        MuxEvent::SwitchPendingConfirmation {
+            token,
+            requested,
+            current,
+            timeout_ms,
+        } => Kind::SwitchPendingConfirmation(proto::SwitchPendingConfirmation {
+            token: *token,
+            requested: requested.0,
+            current: current.0,
+            timeout_ms: *timeout_ms,
+        }),
+        MuxEvent::AmpConnected { meta } => Kind::AmpConnected(proto::AmpConnected {
+            protocol: meta.protocol.name().to_string(),
+        }),
+        MuxEvent::AmpDisconnected => Kind::AmpDisconnected(proto::AmpDisconnected {}),
+        MuxEvent::AmpStateChanged { state } => Kind::AmpStateChanged(amp_state(state)),
+        MuxEvent::Bookmark { source, note, .. } => Kind::Bookmark(proto::Bookmark {
+            source: source.clone(),
+            note: note.clone(),
+        }),
+        MuxEvent::ContactSnapshot {
+            handle,
+            radio,
+            frequency_hz,
+            mode,
+            ..
+        } => Kind::ContactSnapshot(proto::ContactSnapshot {
+            handle: handle.0,
+            radio: radio.clone(),
+            frequency_hz: frequency_hz.map(Frequency::hz),
+            mode: mode_value(*mode),
+        }),
+        MuxEvent::Error { source, message } => Kind::Error(proto::Error {
+            source: source.clone(),
+            message: message.clone(),
+        }),
+        MuxEvent::RadioDataIn {
+            handle,
+            data,
+            protocol,
+            ..
+        } => traffic(Direction::RadioIn, Some(*handle), data, *protocol),
+        MuxEvent::RadioDataOut {
+            handle,
+            data,
+            protocol,
+            ..
+        } => traffic(Direction::RadioOut, Some(*handle), data, *protocol),
+        MuxEvent::AmpDataIn { data, protocol, .. } => {
+            traffic(Direction::AmpIn, None, data, *protocol)
+        }
+        // Writes held back by dry run never reached the amp
+        MuxEvent::AmpDataOut {
+            data,
+            protocol,
+            suppressed: false,
+            ..
+        } => traffic(Direction::AmpOut, None, data, *protocol),
+        _ => return None,
+    };
+    Some(proto::Event {
+        timestamp_ms: unix_ms(event.timestamp().unwrap_or(now)),
+        kind: Some(kind),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cat_protocol::Protocol;
+    use tokio::runtime::Handle;
+
+    use super::proto::event::Kind;
+    use super::*;
+    use crate::channel::RadioChannelMeta;
+
+    #[test]
+    fn test_events_are_mapped() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_millis(1_500);
+        let event = event_to_proto(
+            &MuxEvent::RadioStateChanged {
+                handle: RadioHandle(2),
+                freq: Some(Frequency::from_hz(14_074_000)),
+                mode: Some(OperatingMode::DataU),
+                ptt: None,
+                power_watts: None,
+            },
+            now,
+        )
+        .unwrap();
+        assert_eq!(event.timestamp_ms, 1_500);
+        let Some(Kind::RadioStateChanged(changed)) = event.kind else {
+            panic!("unexpected {:?}", event.kind);
+        };
+        assert_eq!(changed.handle, 2);
+        assert_eq!(changed.frequency_hz, Some(14_074_000));
+        assert_eq!(changed.mode, proto::Mode::DataU as i32);
+        assert_eq!(changed.ptt, None);
+
+        // Traffic keeps its own time
+        let event = event_to_proto(
+            &MuxEvent::RadioDataIn {
+                handle: RadioHandle(2),
+                id: crate::events::FrameId(1),
+                data: b"FA00014074000;".to_vec(),
+                protocol: Protocol::Kenwood,
+                timestamp: SystemTime::UNIX_EPOCH + Duration::from_millis(7),
+            },
+            now,
+        )
+        .unwrap();
+        assert_eq!(event.timestamp_ms, 7);
+        assert!(matches!(event.kind, Some(Kind::Traffic(ref t))
+            if t.direction == proto::traffic::Direction::RadioIn as i32
+                && t.handle == Some(2)));
+
+        // Dry-run writes and events outside the API are left out
+        let suppressed = MuxEvent::AmpDataOut {
+            data: b"FA;".to_vec(),
+            cause: None,
+            protocol: Protocol::Kenwood,
+            suppressed: true,
+            timestamp: now,
+        };
+        assert!(event_to_proto(&suppressed, now).is_none());
+        let meter = MuxEvent::RadioMeter {
+            handle: RadioHandle(2),
+            meter: cat_protocol::MeterKind::Swr,
+            level: 10,
+        };
+        assert!(event_to_proto(&meter, now).is_none());
+    }
+
+    #[test]
+    fn test_control_requests_are_checked() {
+        use proto::control_active_radio_request::Request as R;
+
+        let request = |r| proto::ControlActiveRadioRequest { request: Some(r) };
+        assert_eq!(
+            radio_request(request(R::SetMode(proto::Mode::CwR as i32))).unwrap(),
+            RadioRequest::SetMode {
+                mode: OperatingMode::CwR
+            }
+        );
+        assert_eq!(
+            radio_request(request(R::SetFrequencyHz(7_074_000))).unwrap(),
+            RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
+        for bad in [
+            R::SetFrequencyHz(0),
+            R::SetMode(proto::Mode::Unspecified as i32),
+            R::SetMode(99),
+            R::SetKeyerSpeedWpm(300),
+        ] {
+            let status = radio_request(request(bad)).unwrap_err();
+            assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        }
+        assert!(radio_request(proto::ControlActiveRadioRequest { request: None }).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_commands_and_event_stream_over_grpc() {
+        let (mux, events) = MuxClient::start(&Handle::current(), 64);
+        let meta = |name: &str| {
+            RadioChannelMeta::new_virtual(name.into(), "sim".into(), Protocol::Kenwood)
+        };
+        let run = mux
+            .register_radio(meta("Run"), None)
+            .unwrap()
+            .await
+            .unwrap();
+        let mult = mux
+            .register_radio(meta("Mult"), None)
+            .unwrap()
+            .await
+            .unwrap();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let dropped = Arc::new(AtomicU64::new(0));
+        tokio::spawn(run_grpc_server(
+            listener,
+            mux.clone(),
+            events,
+            dropped.clone(),
+        ));
+
+        let mut client = proto::mux_client::MuxClient::connect(format!("http://{}", addr))
+            .await
+            .unwrap();
+        let mut stream = client
+            .stream_events(proto::StreamEventsRequest {
+                include_traffic: false,
+            })
+            .await
+            .unwrap()
+            .into_inner();
+
+        client
+            .set_active_radio(proto::SetActiveRadioRequest { handle: mult.0 })
+            .await
+            .unwrap();
+        let changed = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let event = stream.message().await.unwrap().unwrap();
+                if let Some(Kind::ActiveRadioChanged(changed)) = event.kind {
+                    return changed;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(changed.to, mult.0);
+
+        // Events the host couldn't queue are reported before the next one
+        dropped.fetch_add(3, Ordering::Relaxed);
+        client
+            .set_active_radio(proto::SetActiveRadioRequest { handle: run.0 })
+            .await
+            .unwrap();
+        let count = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let event = stream.message().await.unwrap().unwrap();
+                if let Some(Kind::EventsDropped(dropped)) = event.kind {
+                    return dropped.count;
+                }
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(count, 3);
+
+        let active = client
+            .get_active_radio(proto::GetActiveRadioRequest {})
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(active.radio.map(|r| r.handle), Some(mult.0));
+
+        let missing = client
+            .get_radio_state(proto::GetRadioStateRequest { handle: 99 })
+            .await
+            .unwrap_err();
+        assert_eq!(missing.code(), tonic::Code::NotFound);
+        let bad_mode = client
+            .set_switching_mode(proto::SetSwitchingModeRequest { mode: 0 })
+            .await
+            .unwrap_err();
+        assert_eq!(bad_mode.code(), tonic::Code::InvalidArgument);
+
+        mux.shutdown().unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+//! Gapless handover between radios
+//!
+//! A switch normally lets the new radio's traffic through at once. A radio
+//! that keys up as it becomes active (automatic switching on PTT, a PTT
+//! switch) can then key the amp before the amp has heard its frequency, for
+//! a moment at full power on the old radio's band. With the handover on, a
+//! switch completes in two phases:
+//!
+//! 1. The new radio's cached frequency and mode go to the amp, and keying
+//!    the amp is held off.
+//! 2. Once the amp's port has written them (its write backlog is empty), or
+//!    after [`HANDOVER_TIMEOUT`], keying is released. A radio already
+//!    transmitting keys the amp then.
+//!
+//! None of the supported amp protocols acknowledge an unsolicited frequency
+//! or mode update, so "written to the port" is the completion waited for.
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// Longest keying is held off waiting for the amp's port
+pub const HANDOVER_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How often a pending handover re-checks the amp's write backlog
+pub const HANDOVER_POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    radio: RadioHandle,
+    started: Instant,
+    next_poll: Instant,
+}
+
+/// A handover that finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandoverDone {
+    /// The radio switched to
+    pub radio: RadioHandle,
+    /// From the switch until keying was released
+    pub elapsed: Duration,
+    /// Released by [`HANDOVER_TIMEOUT`] rather than the port draining
+    pub timed_out: bool,
+}
+
+/// Two-phase switch state: whether it is on, and the switch in progress
+#[derive(Debug, Default)]
+pub struct Handover {
+    enabled: bool,
+    pending: Option<Pending>,
+}
+
+impl Handover {
+    /// Create with the handover off
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether switches wait for the amp
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn the handover on or off (off releases a switch in progress)
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pending = None;
+        }
+    }
+
+    /// A switch to `radio` has started; returns false when the handover is
+    /// off. A switch in progress is replaced.
+    pub fn begin(&mut self, radio: RadioHandle, now: Instant) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        self.pending = Some(Pending {
+            radio,
+            started: now,
+            next_poll: now,
+        });
+        true
+    }
+
+    /// The radio being switched to, while keying is held off
+    pub fn pending(&self) -> Option<RadioHandle> {
+        self.pending.map(|p| p.radio)
+    }
+
+    /// Whether keying the amp is held off
+    pub fn holds_ptt(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// When to check on the switch in progress, if there is one
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending
+            .map(|p| p.next_poll.min(p.started + HANDOVER_TIMEOUT))
+    }
+
+    /// Check on the switch in progress with the bytes the amp still has to
+    /// write; returns it once keying can be released
+    pub fn poll(&mut self, pending_bytes: usize, now: Instant) -> Option<HandoverDone> {
+        let pending = self.pending.as_mut()?;
+        let elapsed = now.saturating_duration_since(pending.started);
+        let timed_out = elapsed >= HANDOVER_TIMEOUT;
+        if pending_bytes > 0 && !timed_out {
+            pending.next_poll = now + HANDOVER_POLL_INTERVAL;
+            return None;
+        }
+        let radio = pending.radio;
+        self.pending = None;
+        Some(HandoverDone {
+            radio,
+            elapsed,
+            timed_out: pending_bytes > 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_releases_when_drained() {
+        let t0 = Instant::now();
+        let mut handover = Handover::new();
+        assert!(!handover.begin(RadioHandle(1), t0));
+        assert!(!handover.holds_ptt());
+
+        handover.set_enabled(true);
+        assert!(handover.begin(RadioHandle(1), t0));
+        assert!(handover.holds_ptt());
+        assert_eq!(handover.next_deadline(), Some(t0));
+
+        let t1 = t0 + Duration::from_millis(3);
+        assert_eq!(handover.poll(14, t1), None);
+        assert_eq!(handover.next_deadline(), Some(t1 + HANDOVER_POLL_INTERVAL));
+
+        let t2 = t0 + Duration::from_millis(30);
+        assert_eq!(
+            handover.poll(0, t2),
+            Some(HandoverDone {
+                radio: RadioHandle(1),
+                elapsed: Duration::from_millis(30),
+                timed_out: false,
+            })
+        );
+        assert!(!handover.holds_ptt());
+        assert_eq!(handover.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_times_out_on_a_stuck_port() {
+        let t0 = Instant::now();
+        let mut handover = Handover::new();
+        handover.set_enabled(true);
+        handover.begin(RadioHandle(2), t0);
+        // A second switch restarts the wait
+        let t1 = t0 + Duration::from_millis(200);
+        handover.begin(RadioHandle(3), t1);
+        assert_eq!(handover.poll(40, t0 + HANDOVER_TIMEOUT), None);
+
+        let done = handover.poll(40, t1 + HANDOVER_TIMEOUT).unwrap();
+        assert_eq!(done.radio, RadioHandle(3));
+        assert!(done.timed_out);
+
+        // Turning it off releases a switch in progress
+        handover.begin(RadioHandle(2), t1);
+        handover.set_enabled(false);
+        assert!(!handover.holds_ptt());
+    }
+}
@@ -0,0 +1,263 @@
+//! Per-radio input throttling
+//!
+//! Radios in transceive mode (an IC-7610 with the dial spinning, say) can
+//! report dozens of frequencies a second, and every one of them would be
+//! translated and written to the amplifier. An [`InputThrottle`] sits in front
+//! of the mux processing: the first report of a kind passes straight through
+//! and opens a window, later reports of the same kind inside the window only
+//! replace a held value, and when the window ends the held (final) value is
+//! released. Nothing is lost except intermediate values, and a single report
+//! is never delayed. A key-up releases the held frequency and mode ahead of
+//! itself, so the amp never keys on a band it hasn't been told about.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cat_protocol::{MeterKind, RadioResponse};
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// Kinds of report that can be collapsed (PTT and status never are)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ThrottleKey {
+    Frequency,
+    Mode,
+    TxPower,
+    Meter(MeterKind),
+}
+
+impl ThrottleKey {
+    fn of(response: &RadioResponse) -> Option<Self> {
+        match response {
+            RadioResponse::Frequency { .. } => Some(Self::Frequency),
+            RadioResponse::Mode { .. } => Some(Self::Mode),
+            RadioResponse::TxPower { .. } => Some(Self::TxPower),
+            RadioResponse::Meter { meter, .. } => Some(Self::Meter(*meter)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Slot {
+    /// End of the current window
+    until: Instant,
+    /// Latest report received inside the window
+    held: Option<RadioResponse>,
+}
+
+/// Collapses bursts of same-kind radio reports into their final value
+#[derive(Debug, Default)]
+pub struct InputThrottle {
+    windows: HashMap<RadioHandle, Duration>,
+    slots: HashMap<(RadioHandle, ThrottleKey), Slot>,
+}
+
+impl InputThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set a radio's window (zero disables throttling for it)
+    pub fn set_window(&mut self, handle: RadioHandle, window: Duration) {
+        if window.is_zero() {
+            self.windows.remove(&handle);
+        } else {
+            self.windows.insert(handle, window);
+        }
+        // Held values are dropped with the old window; the radio's next
+        // report starts afresh
+        self.slots.retain(|(h, _), _| *h != handle);
+    }
+
+    /// Offer a report; returns what should be processed now, in order
+    ///
+    /// A key-up comes back behind any frequency and mode still held for the
+    /// radio.
+    pub fn offer(
+        &mut self,
+        handle: RadioHandle,
+        response: RadioResponse,
+        now: Instant,
+    ) -> Vec<RadioResponse> {
+        let Some(&window) = self.windows.get(&handle) else {
+            return vec![response];
+        };
+        let Some(key) = ThrottleKey::of(&response) else {
+            // A status report carries newer values than anything held
+            if response.frequency().is_some() {
+                self.drop_held(handle, ThrottleKey::Frequency);
+            }
+            if response.mode().is_some() {
+                self.drop_held(handle, ThrottleKey::Mode);
+            }
+            let mut ready = Vec::new();
+            if response.ptt() == Some(true) {
+                for key in [ThrottleKey::Frequency, ThrottleKey::Mode] {
+                    if let Some(held) = self
+                        .slots
+                        .get_mut(&(handle, key))
+                        .and_then(|slot| slot.held.take())
+                    {
+                        ready.push(held);
+                    }
+                }
+            }
+            ready.push(response);
+            return ready;
+        };
+
+        match self.slots.get_mut(&(handle, key)) {
+            Some(slot) if now < slot.until => {
+                slot.held = Some(response);
+                Vec::new()
+            }
+            _ => {
+                self.slots.insert(
+                    (handle, key),
+                    Slot {
+                        until: now + window,
+                        held: None,
+                    },
+                );
+                vec![response]
+            }
+        }
+    }
+
+    /// Earliest time a held report is due
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.slots
+            .values()
+            .filter(|slot| slot.held.is_some())
+            .map(|slot| slot.until)
+            .min()
+    }
+
+    /// Release held reports whose window has ended
+    ///
+    /// A released report opens a new window, so a long burst is passed on
+    /// once per window rather than only at its end.
+    pub fn poll(&mut self, now: Instant) -> Vec<(RadioHandle, RadioResponse)> {
+        let mut released = Vec::new();
+        for (&(handle, _), slot) in self.slots.iter_mut() {
+            if now < slot.until {
+                continue;
+            }
+            if let Some(response) = slot.held.take() {
+                let window = self.windows.get(&handle).copied().unwrap_or_default();
+                slot.until = now + window;
+                released.push((handle, response));
+            }
+        }
+        self.slots
+            .retain(|_, slot| now < slot.until || slot.held.is_some());
+        released
+    }
+
+    /// Drop a radio's held reports and open windows, keeping its window length
+    pub fn reset_radio(&mut self, handle: RadioHandle) {
+        self.slots.retain(|(h, _), _| *h != handle);
+    }
+
+    /// Forget a radio (e.g. when it disconnects)
+    pub fn remove_radio(&mut self, handle: RadioHandle) {
+        self.windows.remove(&handle);
+        self.slots.retain(|(h, _), _| *h != handle);
+    }
+
+    fn drop_held(&mut self, handle: RadioHandle, key: ThrottleKey) {
+        if let Some(slot) = self.slots.get_mut(&(handle, key)) {
+            slot.held = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::Frequency;
+
+    #[test]
+    fn test_burst_collapses_to_final_value() {
+        let radio = RadioHandle(1);
+        let window = Duration::from_millis(50);
+        let mut throttle = InputThrottle::new();
+        throttle.set_window(radio, window);
+        let t0 = Instant::now();
+        let freq = |hz| RadioResponse::Frequency { hz };
+
+        // The first report passes straight through
+        assert_eq!(
+            throttle.offer(radio, freq(Frequency::from_hz(14_000_000)), t0),
+            vec![freq(Frequency::from_hz(14_000_000))]
+        );
+        // The rest of the burst is held, last value wins
+        for hz in [14_000_100, 14_000_200, 14_000_300] {
+            assert!(throttle
+                .offer(radio, freq(Frequency::from_hz(hz)), t0)
+                .is_empty());
+        }
+        // An unkey passes alone
+        let unkey = RadioResponse::Ptt { active: false };
+        assert_eq!(throttle.offer(radio, unkey.clone(), t0), vec![unkey]);
+
+        assert_eq!(throttle.next_deadline(), Some(t0 + window));
+        assert!(throttle.poll(t0).is_empty());
+        assert_eq!(
+            throttle.poll(t0 + window),
+            vec![(radio, freq(Frequency::from_hz(14_000_300)))]
+        );
+        assert_eq!(throttle.next_deadline(), None);
+
+        // Still inside the window opened by the release
+        assert!(throttle
+            .offer(radio, freq(Frequency::from_hz(14_000_400)), t0 + window)
+            .is_empty());
+        // A status report supersedes the held frequency
+        let status = RadioResponse::Status {
+            frequency_hz: Some(Frequency::from_hz(14_000_500)),
+            mode: None,
+            ptt: Some(false),
+            vfo: None,
+        };
+        assert_eq!(throttle.offer(radio, status, t0 + window).len(), 1);
+        assert!(throttle.poll(t0 + window * 2).is_empty());
+    }
+
+    #[test]
+    fn test_key_up_releases_held_frequency_first() {
+        let radio = RadioHandle(1);
+        let mut throttle = InputThrottle::new();
+        throttle.set_window(radio, Duration::from_millis(50));
+        let t0 = Instant::now();
+        let freq = |hz| RadioResponse::Frequency {
+            hz: Frequency::from_hz(hz),
+        };
+
+        assert_eq!(throttle.offer(radio, freq(7_074_000), t0).len(), 1);
+        assert!(throttle.offer(radio, freq(14_074_000), t0).is_empty());
+
+        // The amp hears the new band before it keys
+        let key_up = RadioResponse::Ptt { active: true };
+        assert_eq!(
+            throttle.offer(radio, key_up.clone(), t0),
+            vec![freq(14_074_000), key_up]
+        );
+        assert_eq!(throttle.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_unthrottled_radio_passes_everything() {
+        let mut throttle = InputThrottle::new();
+        let t0 = Instant::now();
+        for hz in [7_000_000, 7_000_100] {
+            let resp = RadioResponse::Frequency {
+                hz: Frequency::from_hz(hz),
+            };
+            assert_eq!(throttle.offer(RadioHandle(2), resp.clone(), t0), vec![resp]);
+        }
+        assert_eq!(throttle.next_deadline(), None);
+    }
+}
@@ -0,0 +1,74 @@
+//! PTT latency metric
+//!
+//! Measures how long a PTT edge takes to get through the mux: from the radio
+//! task reading the bytes to the actor having notified the amplifier. The
+//! actor handles PTT frames ahead of queued bulk traffic (see
+//! [`crate::actor`]), so this should stay well inside [`PTT_LATENCY_BUDGET`]
+//! even while a radio is streaming scope or meter data.
+
+use std::time::Duration;
+
+/// Target for key-up to amp-notify latency
+pub const PTT_LATENCY_BUDGET: Duration = Duration::from_millis(10);
+
+/// Running PTT latency statistics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PttLatencyStats {
+    /// PTT edges measured
+    pub samples: u64,
+    /// Latency of the most recent edge
+    pub last: Duration,
+    /// Worst latency seen
+    pub max: Duration,
+    /// Edges that took longer than the budget
+    pub over_budget: u64,
+    total: Duration,
+}
+
+impl PttLatencyStats {
+    /// Count one PTT edge
+    pub fn record(&mut self, latency: Duration) {
+        self.samples += 1;
+        self.last = latency;
+        self.max = self.max.max(latency);
+        self.total += latency;
+        if latency > PTT_LATENCY_BUDGET {
+            self.over_budget += 1;
+        }
+    }
+
+    /// Mean latency (zero before any edge is measured)
+    pub fn mean(&self) -> Duration {
+        match u32::try_from(self.samples) {
+            Ok(0) => Duration::ZERO,
+            Ok(n) => self.total / n,
+            Err(_) => Duration::from_secs_f64(self.total.as_secs_f64() / self.samples as f64),
+        }
+    }
+
+    /// Forget everything measured so far
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_stats() {
+        let mut stats = PttLatencyStats::default();
+        assert_eq!(stats.mean(), Duration::ZERO);
+
+        stats.record(Duration::from_millis(2));
+        stats.record(Duration::from_millis(4));
+        stats.record(Duration::from_millis(12));
+
+        assert_eq!(stats.samples, 3);
+        assert_eq!(stats.last, Duration::from_millis(12));
+        assert_eq!(stats.max, Duration::from_millis(12));
+        assert_eq!(stats.mean(), Duration::from_millis(6));
+        assert_eq!(stats.over_budget, 1);
+    }
+}
@@ -39,35 +39,149 @@
 //! ```
 
 pub mod actor;
+pub mod amp_bypass;
+pub mod amp_failover;
+pub mod amp_queries;
 pub mod amplifier;
+pub mod analyzer;
 pub mod async_amp;
 pub mod async_radio;
+pub mod band_decoder;
+pub mod band_guard;
+pub mod band_plan;
+pub mod bookmark;
 pub mod channel;
+pub mod civ_echo;
+pub mod client;
+pub mod dx_cluster;
+pub mod emulated_radio;
 pub mod engine;
 pub mod error;
 pub mod events;
+pub mod flrig;
+pub mod freq_entry;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod handover;
+pub mod input_throttle;
+pub mod latency;
+pub mod link;
+pub mod memory;
+pub mod metrics;
+pub mod monitor;
+pub mod peer_sync;
+pub mod ptt_switch;
+pub mod radio_profile;
+pub mod reassembly;
+pub mod rigctl;
+pub mod sequencer;
+pub mod shadow;
+pub mod smoothing;
 pub mod state;
+pub mod state_history;
+pub mod status_page;
+pub mod supervisor;
+pub mod switch_audit;
+pub mod switch_confirm;
+pub mod sync_plan;
+pub mod telemetry;
+pub mod traffic_log;
+pub mod transition;
 pub mod translation;
+pub mod tx_stats;
+pub mod watch;
+pub mod write_backlog;
 
 // Re-export actor types
 pub use actor::{run_mux_actor, MuxActorCommand, RadioStateSummary};
+pub use client::{ClientError, MuxClient, Reply};
+pub use supervisor::{run_supervised_mux, ChannelRegistry, WatchdogConfig};
 
 // Re-export channel types
+pub use amp_bypass::{AmpBypass, AmpBypassConfig, AmpPersonality};
+pub use amp_failover::{kpa_fault, AmpFailover, AmpRole, KPA_FAULT_POLL_INTERVAL, KPA_FAULT_QUERY};
+pub use amp_queries::{
+    AmpQueryKind, AmpQueryProfile, AmpQueryRate, AmpQueryTracker, AGGRESSIVE_POLL_HZ,
+};
 pub use amplifier::{AmplifierChannel, AmplifierChannelMeta, AmplifierType};
+pub use analyzer::{AnalyzerDirection, AnalyzerPassthrough, AnalyzerStats};
+pub use bookmark::{run_pin_trigger, PttDoubleTap, SerialPin, DEFAULT_DOUBLE_TAP_MS};
 pub use channel::{
-    is_virtual_port, sim_id_from_port, virtual_port_name, RadioChannelMeta, VIRTUAL_PORT_PREFIX,
+    is_virtual_port, sim_id_from_port, virtual_port_name, ChannelId, RadioChannelMeta,
+    VIRTUAL_PORT_PREFIX,
 };
 
 // Re-export event types
-pub use events::MuxEvent;
+pub use events::{FrameId, MuxEvent};
+pub use handover::{Handover, HandoverDone, HANDOVER_TIMEOUT};
+pub use input_throttle::InputThrottle;
 
 // Re-export async connection types
 pub use async_amp::AsyncAmpConnection;
 pub use async_radio::{AsyncRadioConnection, RadioTaskCommand};
-pub use tokio_serial::FlowControl;
+pub use band_decoder::{BandDecoderConnection, BandDecoderSink};
+pub use band_guard::{BandConflict, BandGuard, BandGuardConfig, BandGuardStrictness, ModeClass};
+pub use band_plan::BandPlanRegion;
+pub use tokio_serial::{DataBits, FlowControl, Parity, StopBits};
 
 // Re-export engine types
+pub use dx_cluster::{
+    run_dx_cluster_client, DxSpot, SpotCache, MAX_SPOTS, SPOT_MAX_AGE, SPOT_TOLERANCE_HZ,
+};
+pub use emulated_radio::{
+    run_emulated_radio_serial, run_emulated_radio_server, serve_emulated_radio,
+    EmulatedPersonality, DEFAULT_EMULATED_RADIO_ADDR,
+};
 pub use engine::{Multiplexer, MultiplexerConfig};
 pub use error::MuxError;
-pub use state::{AmplifierConfig, RadioHandle, RadioState, SwitchingMode};
-pub use translation::{ProtocolTranslator, TranslationConfig};
+pub use flrig::{run_flrig_client, FlrigClient, FlrigOptions, DEFAULT_FLRIG_ADDR};
+pub use freq_entry::{check_frequency, parse_frequency, FrequencyEntryError};
+#[cfg(feature = "grpc")]
+pub use grpc::{run_grpc_server, DEFAULT_GRPC_ADDR};
+pub use latency::{PttLatencyStats, PTT_LATENCY_BUDGET};
+pub use link::{RadioTransport, SerialLine};
+pub use memory::{MemoryBudget, MemoryLimits, MemoryUsage, MIN_BUDGET_MB};
+pub use metrics::{run_metrics_server, MetricsInput, MuxMetrics, DEFAULT_METRICS_ADDR};
+pub use monitor::{MonitorConfig, MonitorFollowers};
+pub use peer_sync::{
+    run_peer_sync, sanitize_instance, PeerState, PeerStatus, PeerTable, DEFAULT_PEER_PORT,
+    PEER_HEARTBEAT, PEER_TIMEOUT,
+};
+pub use ptt_switch::{ptt_switch_source, run_ptt_switch};
+pub use radio_profile::{ProfileQuery, RadioProfile};
+pub use reassembly::{
+    partial_frame_timeout, FrameSource, PartialFrameTimer, ReassemblyStats, ReassemblyTracker,
+};
+pub use rigctl::{run_rigctl_server, DEFAULT_RIGCTL_ADDR};
+pub use sequencer::{PttSequencer, SequencerConfig};
+pub use shadow::{
+    frame_text, ShadowComparator, ShadowDiff, ShadowSide, ShadowStats, SHADOW_SETTLE,
+};
+pub use smoothing::{FrequencySmoother, FrequencySmoothing, OutputSink, SmoothingPolicy};
+pub use state::{AmplifierConfig, AmplifierEmulatedState, RadioHandle, RadioState, SwitchingMode};
+pub use state_history::{MuxStateAt, RadioStateAt, StateHistory, STATE_HISTORY_CHANGES};
+pub use status_page::{
+    observe_status, push_ntfy, push_webhook, run_status_server, AlertKind, AlertTargets,
+    SharedStatusBoard, StatusAlert, StatusBoard, DEFAULT_STATUS_ADDR,
+};
+pub use switch_audit::{
+    LockoutState, OutgoingRadio, SwitchAuditLog, SwitchRecord, SwitchRule, SwitchTrigger,
+    AUDIT_LOG_CAPACITY,
+};
+pub use switch_confirm::{
+    PendingSwitch, SwitchConfirmOutcome, SwitchConfirmation, SWITCH_CONFIRM_TIMEOUT,
+};
+pub use sync_plan::{SyncPlan, SyncStep, SyncStepStatus};
+pub use telemetry::{MeterCache, MeterThrottle, METER_EVENT_INTERVAL, METER_READING_MAX_AGE};
+pub use traffic_log::{TrafficLogReader, TrafficLogWriter, TrafficRecord};
+pub use transition::{Transition, TransitionPolicy, TransitionReason};
+pub use translation::{
+    same_protocol_family, AmpUpdate, BurstCoalescer, ProtocolTranslator, TranslationConfig,
+    TranslationStrictness,
+};
+pub use tx_stats::{DutyCycleAlert, DutyCycleAlertConfig, TxBandStats, TxStats};
+pub use watch::{post_webhook, WatchEngine, WatchExpr, WatchParseError, WatchTransition};
+pub use write_backlog::{BacklogMonitor, WriteBacklog};
+
+/// This crate's version, for reports
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,162 @@
+//! How a radio is reached, and how its frequencies map to the air
+//!
+//! Most radios sit on a local serial port. A radio behind a serial server
+//! (ser2net, a remote station's network interface) exposes the same byte
+//! stream over TCP instead.
+//!
+//! A radio's dial isn't always the frequency on the air: a transverter moves
+//! it by its local oscillator, and a radio with an off-frequency reference
+//! reads a few Hz away from the truth. A channel's frequency offset is added
+//! to everything the radio reports and taken off everything sent to it, so
+//! the rest of the mux only ever sees on-air frequencies.
+
+use cat_protocol::{Frequency, RadioRequest, RadioResponse};
+use tokio_serial::{DataBits, FlowControl, Parity, StopBits};
+
+/// Serial line settings for a radio's port
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SerialLine {
+    pub baud_rate: u32,
+    pub flow_control: FlowControl,
+    pub data_bits: DataBits,
+    pub parity: Parity,
+    pub stop_bits: StopBits,
+    /// Level to hold DTR at once the port is open (None = leave it alone)
+    pub dtr: Option<bool>,
+    /// Level to hold RTS at once the port is open (None = leave it alone)
+    ///
+    /// Ignored with hardware flow control, which drives RTS itself.
+    pub rts: Option<bool>,
+}
+
+impl SerialLine {
+    /// 8N1 at `baud_rate`, leaving DTR and RTS alone
+    pub fn new(baud_rate: u32, flow_control: FlowControl) -> Self {
+        Self {
+            baud_rate,
+            flow_control,
+            data_bits: DataBits::Eight,
+            parity: Parity::None,
+            stop_bits: StopBits::One,
+            dtr: None,
+            rts: None,
+        }
+    }
+
+    /// Short description, e.g. "9600 8N1"
+    pub fn describe(&self) -> String {
+        let data_bits = match self.data_bits {
+            DataBits::Five => 5,
+            DataBits::Six => 6,
+            DataBits::Seven => 7,
+            DataBits::Eight => 8,
+        };
+        let parity = match self.parity {
+            Parity::None => 'N',
+            Parity::Odd => 'O',
+            Parity::Even => 'E',
+        };
+        let stop_bits = match self.stop_bits {
+            StopBits::One => 1,
+            StopBits::Two => 2,
+        };
+        format!("{} {}{}{}", self.baud_rate, data_bits, parity, stop_bits)
+    }
+}
+
+/// How to reach a radio
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RadioTransport {
+    /// Local serial port
+    Serial { port: String, line: SerialLine },
+    /// Raw TCP byte stream, e.g. a serial server ("host:port")
+    Tcp { address: String },
+}
+
+/// Translate a radio's report from its dial to the on-air frequency
+pub fn response_to_air(response: RadioResponse, offset_hz: i64) -> RadioResponse {
+    if offset_hz == 0 {
+        return response;
+    }
+    match response {
+        RadioResponse::Frequency { hz } => RadioResponse::Frequency {
+            hz: hz.saturating_offset(offset_hz),
+        },
+        RadioResponse::Status {
+            frequency_hz,
+            mode,
+            ptt,
+            vfo,
+        } => RadioResponse::Status {
+            frequency_hz: frequency_hz.map(|hz| hz.saturating_offset(offset_hz)),
+            mode,
+            ptt,
+            vfo,
+        },
+        other => other,
+    }
+}
+
+/// Translate a request from the on-air frequency to the radio's dial
+pub fn request_to_dial(request: RadioRequest, offset_hz: i64) -> RadioRequest {
+    if offset_hz == 0 {
+        return request;
+    }
+    let to_dial = |hz: Frequency| hz.saturating_offset(offset_hz.saturating_neg());
+    match request {
+        RadioRequest::SetFrequency { hz } => RadioRequest::SetFrequency { hz: to_dial(hz) },
+        RadioRequest::SetFrequencyMode { hz, mode } => RadioRequest::SetFrequencyMode {
+            hz: to_dial(hz),
+            mode,
+        },
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::OperatingMode;
+
+    #[test]
+    fn test_transverter_offset_round_trip() {
+        // 2 m on a 28 MHz IF
+        let lo = 116_000_000;
+        assert_eq!(
+            response_to_air(
+                RadioResponse::Frequency {
+                    hz: Frequency::from_hz(28_174_000)
+                },
+                lo
+            ),
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(144_174_000)
+            }
+        );
+        assert_eq!(
+            request_to_dial(
+                RadioRequest::SetFrequencyMode {
+                    hz: Frequency::from_hz(144_174_000),
+                    mode: OperatingMode::Usb
+                },
+                lo
+            ),
+            RadioRequest::SetFrequencyMode {
+                hz: Frequency::from_hz(28_174_000),
+                mode: OperatingMode::Usb
+            }
+        );
+        // Only frequencies move
+        assert_eq!(
+            request_to_dial(RadioRequest::GetFrequency, lo),
+            RadioRequest::GetFrequency
+        );
+    }
+
+    #[test]
+    fn test_line_description() {
+        let mut line = SerialLine::new(4800, FlowControl::None);
+        line.stop_bits = StopBits::Two;
+        assert_eq!(line.describe(), "4800 8N2");
+    }
+}
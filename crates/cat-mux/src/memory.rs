@@ -0,0 +1,158 @@
+//! Memory budget
+//!
+//! Everything kept for the life of a session is a bounded buffer: traffic
+//! history, decoded-frame caches, the switch audit and DX spots. A
+//! [`MemoryBudget`] derives all of those bounds from one number so a station
+//! left running for a week, on a Raspberry Pi say, has a known ceiling.
+//!
+//! Sizes are estimates (a fixed cost per item plus any bytes it owns), good
+//! enough to keep a long run well clear of the host's limit but not an
+//! allocator count. The host reports a [`MemoryUsage`] periodically and trims
+//! whatever has grown past its share.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+
+/// Estimated cost of a traffic entry, before the bytes it holds
+pub const TRAFFIC_ENTRY_BYTES: usize = 128;
+
+/// Estimated cost of a cached frame decode
+pub const ANNOTATION_BYTES: usize = 1024;
+
+/// Estimated cost of a switch audit record
+pub const SWITCH_RECORD_BYTES: usize = 256;
+
+/// Estimated cost of a change kept for reconstructing past mux state
+pub const STATE_CHANGE_BYTES: usize = 64;
+
+/// Estimated cost of a DX spot
+pub const DX_SPOT_BYTES: usize = 192;
+
+/// Smallest budget accepted; below this the buffers stop being useful
+pub const MIN_BUDGET_MB: u32 = 8;
+
+/// A ceiling for everything the app keeps in memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryBudget {
+    pub megabytes: u32,
+}
+
+/// Buffer bounds derived from a [`MemoryBudget`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLimits {
+    /// Traffic monitor entries
+    pub traffic_entries: usize,
+    /// Traffic monitor estimated size, trimmed past this
+    pub traffic_bytes: usize,
+    /// Decoded frames cached for display
+    pub annotation_cache_entries: usize,
+    /// Switch audit records
+    pub switch_audit_records: usize,
+    /// DX spots
+    pub dx_spots: usize,
+}
+
+impl MemoryBudget {
+    pub fn new(megabytes: u32) -> Self {
+        Self {
+            megabytes: megabytes.max(MIN_BUDGET_MB),
+        }
+    }
+
+    pub fn bytes(&self) -> usize {
+        self.megabytes as usize * 1024 * 1024
+    }
+
+    /// Split the budget between the buffers
+    ///
+    /// Traffic history gets 60%, decode caches 10%, the switch audit and DX
+    /// spots 2% each. The rest is left for the code, the UI and everything
+    /// that isn't a long-lived buffer.
+    pub fn limits(&self) -> MemoryLimits {
+        let share = |percent: usize| self.bytes() / 100 * percent;
+        let traffic_bytes = share(60);
+        MemoryLimits {
+            // Frames are small; most of an entry is its fixed cost
+            traffic_entries: traffic_bytes / (TRAFFIC_ENTRY_BYTES + 16),
+            traffic_bytes,
+            annotation_cache_entries: (share(10) / ANNOTATION_BYTES).max(64),
+            switch_audit_records: (share(2) / SWITCH_RECORD_BYTES).max(16),
+            dx_spots: (share(2) / DX_SPOT_BYTES).max(16),
+        }
+    }
+}
+
+/// Estimated memory held by each buffer
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Buffer name, items held, estimated bytes
+    pub parts: Vec<(&'static str, usize, usize)>,
+}
+
+impl MemoryUsage {
+    pub fn add(&mut self, name: &'static str, items: usize, bytes: usize) {
+        self.parts.push((name, items, bytes));
+    }
+
+    /// Estimated total
+    pub fn total(&self) -> usize {
+        self.parts.iter().map(|(_, _, bytes)| bytes).sum()
+    }
+
+    /// One line, e.g. `~3.2 of 64 MiB: traffic 2.9 MiB (18000), ...`
+    pub fn summary(&self, budget: MemoryBudget) -> String {
+        let mut out = format!("~{:.1} of {} MiB:", mib(self.total()), budget.megabytes);
+        for (i, (name, items, bytes)) in self.parts.iter().enumerate() {
+            let _ = write!(
+                out,
+                "{} {} {:.1} MiB ({})",
+                if i == 0 { "" } else { "," },
+                name,
+                mib(*bytes),
+                items
+            );
+        }
+        out
+    }
+}
+
+fn mib(bytes: usize) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limits_scale_with_budget() {
+        let small = MemoryBudget::new(16).limits();
+        let large = MemoryBudget::new(256).limits();
+        assert!(large.traffic_entries > small.traffic_entries * 15);
+        assert!(small.traffic_bytes < 16 * 1024 * 1024);
+
+        // Everything fits with room to spare
+        let budget = MemoryBudget::new(64);
+        let limits = budget.limits();
+        let worst = limits.traffic_bytes
+            + limits.annotation_cache_entries * ANNOTATION_BYTES
+            + limits.switch_audit_records * SWITCH_RECORD_BYTES
+            + limits.dx_spots * DX_SPOT_BYTES;
+        assert!(worst < budget.bytes() * 3 / 4);
+
+        // Too small to be useful is raised to the minimum
+        assert_eq!(MemoryBudget::new(1).megabytes, MIN_BUDGET_MB);
+    }
+
+    #[test]
+    fn test_usage_summary() {
+        let mut usage = MemoryUsage::default();
+        usage.add("traffic", 1000, 3 * 1024 * 1024);
+        usage.add("decodes", 10, 10 * 1024);
+        assert_eq!(
+            usage.summary(MemoryBudget::new(64)),
+            "~3.0 of 64 MiB: traffic 3.0 MiB (1000), decodes 0.0 MiB (10)"
+        );
+    }
+}
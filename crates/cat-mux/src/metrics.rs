@@ -0,0 +1,569 @@
+//! Prometheus metrics export
+//!
+//! [`MuxMetrics`] folds the [`MuxEvent`] stream into counters and gauges
+//! (frames per radio, PTT time, switches, errors, event queue depth) and
+//! renders them in the Prometheus text exposition format.
+//! [`run_metrics_server`] owns a `MuxMetrics` and answers `GET /metrics` on
+//! a TCP listener, so Grafana and friends can alert when a radio goes silent
+//! or its error rate spikes. Rates (frames/s) are left to PromQL's `rate()`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::io;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info};
+
+use crate::events::MuxEvent;
+use crate::reassembly::ReassemblyStats;
+use crate::state::RadioHandle;
+
+/// Default listen address (9464 is the customary port for custom exporters)
+pub const DEFAULT_METRICS_ADDR: &str = "127.0.0.1:9464";
+
+/// Longest a client gets to send its request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest request read
+const MAX_REQUEST_BYTES: usize = 8192;
+
+/// Input to the metrics server
+#[derive(Debug, Clone)]
+pub enum MetricsInput {
+    /// An event from the mux
+    Event(Box<MuxEvent>),
+    /// Events waiting in the host's mux event queue
+    EventQueueDepth(usize),
+}
+
+#[derive(Debug, Clone, Default)]
+struct RadioMetrics {
+    name: String,
+    connected: bool,
+    stale: bool,
+    frames_in: u64,
+    bytes_in: u64,
+    frames_out: u64,
+    last_frame: Option<Instant>,
+    ptt_time: Duration,
+    ptt_since: Option<Instant>,
+    reassembly: Option<ReassemblyStats>,
+}
+
+impl RadioMetrics {
+    fn ptt_seconds(&self, now: Instant) -> f64 {
+        let ongoing = self
+            .ptt_since
+            .map(|t| now.duration_since(t))
+            .unwrap_or_default();
+        (self.ptt_time + ongoing).as_secs_f64()
+    }
+
+    fn set_ptt(&mut self, active: bool, now: Instant) {
+        match (active, self.ptt_since) {
+            (true, None) => self.ptt_since = Some(now),
+            (false, Some(since)) => {
+                self.ptt_time += now.duration_since(since);
+                self.ptt_since = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Counters and gauges accumulated from the mux event stream
+#[derive(Debug, Default)]
+pub struct MuxMetrics {
+    radios: HashMap<RadioHandle, RadioMetrics>,
+    errors: BTreeMap<String, u64>,
+    events: u64,
+    switches: u64,
+    switches_blocked: u64,
+    amp_connected: bool,
+    amp_frames_out: u64,
+    amp_frames_in: u64,
+    amp_preemptions: u64,
+    event_queue_depth: usize,
+}
+
+impl MuxMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the host's current mux event queue depth
+    pub fn set_event_queue_depth(&mut self, depth: usize) {
+        self.event_queue_depth = depth;
+    }
+
+    /// Fold one event into the metrics
+    pub fn observe(&mut self, event: &MuxEvent, now: Instant) {
+        self.events += 1;
+        match event {
+            MuxEvent::RadioConnected { handle, meta } => {
+                let radio = self.radios.entry(*handle).or_default();
+                radio.name = meta.display_name.clone();
+                radio.connected = true;
+            }
+            MuxEvent::RadioDisconnected { handle } => {
+                if let Some(radio) = self.radios.get_mut(handle) {
+                    radio.connected = false;
+                    radio.set_ptt(false, now);
+                }
+            }
+            MuxEvent::RadioStateChanged {
+                handle,
+                ptt: Some(active),
+                ..
+            } => {
+                self.radios
+                    .entry(*handle)
+                    .or_default()
+                    .set_ptt(*active, now);
+            }
+            MuxEvent::RadioStaleChanged { handle, stale, .. } => {
+                self.radios.entry(*handle).or_default().stale = *stale;
+            }
+            MuxEvent::RadioDataIn { handle, data, .. } => {
+                let radio = self.radios.entry(*handle).or_default();
+                radio.frames_in += 1;
+                radio.bytes_in += data.len() as u64;
+                radio.last_frame = Some(now);
+            }
+            MuxEvent::RadioDataOut { handle, .. } => {
+                self.radios.entry(*handle).or_default().frames_out += 1;
+            }
+            MuxEvent::RadioReassembly { handle, stats } => {
+                self.radios.entry(*handle).or_default().reassembly = Some(*stats);
+            }
+            MuxEvent::ActiveRadioChanged { .. } => self.switches += 1,
+            MuxEvent::SwitchingBlocked { .. } => self.switches_blocked += 1,
+            MuxEvent::AmpConnected { .. } => self.amp_connected = true,
+            MuxEvent::AmpDisconnected => self.amp_connected = false,
+            MuxEvent::AmpDataOut {
+                suppressed: false, ..
+            } => self.amp_frames_out += 1,
+            MuxEvent::AmpDataIn { .. } => self.amp_frames_in += 1,
+            MuxEvent::AmpWritePreempted { .. } => self.amp_preemptions += 1,
+            MuxEvent::Error { source, .. } => {
+                *self.errors.entry(source.clone()).or_default() += 1;
+            }
+            _ => {}
+        }
+    }
+
+    /// Render in the Prometheus text exposition format (version 0.0.4)
+    pub fn render(&self, now: Instant) -> String {
+        let mut out = String::new();
+        let mut handles: Vec<_> = self.radios.iter().collect();
+        handles.sort_by_key(|(handle, _)| handle.0);
+        let radios: Vec<_> = handles
+            .into_iter()
+            .map(|(handle, radio)| {
+                let labels = format!(
+                    "radio=\"{}\",handle=\"{}\"",
+                    escape_label(&radio.name),
+                    handle.0
+                );
+                (labels, radio)
+            })
+            .collect();
+
+        let mut radio_metric =
+            |name: &str, kind: &str, help: &str, value: &dyn Fn(&RadioMetrics) -> Option<f64>| {
+                header(&mut out, name, kind, help);
+                for (labels, radio) in &radios {
+                    if let Some(v) = value(radio) {
+                        let _ = writeln!(out, "{}{{{}}} {}", name, labels, v);
+                    }
+                }
+            };
+        radio_metric(
+            "catapult_radio_connected",
+            "gauge",
+            "Whether the radio is connected",
+            &|r| Some(flag(r.connected)),
+        );
+        radio_metric(
+            "catapult_radio_stale",
+            "gauge",
+            "Whether the radio has gone silent past the staleness threshold",
+            &|r| Some(flag(r.stale)),
+        );
+        radio_metric(
+            "catapult_radio_frames_received_total",
+            "counter",
+            "Frames received from the radio",
+            &|r| Some(r.frames_in as f64),
+        );
+        radio_metric(
+            "catapult_radio_bytes_received_total",
+            "counter",
+            "Bytes received from the radio",
+            &|r| Some(r.bytes_in as f64),
+        );
+        radio_metric(
+            "catapult_radio_frames_sent_total",
+            "counter",
+            "Frames sent to the radio",
+            &|r| Some(r.frames_out as f64),
+        );
+        radio_metric(
+            "catapult_radio_reads_total",
+            "counter",
+            "Reads from the radio's port",
+            &|r| r.reassembly.map(|s| s.reads as f64),
+        );
+        radio_metric(
+            "catapult_radio_split_frames_total",
+            "counter",
+            "Frames from the radio that arrived over more than one read",
+            &|r| r.reassembly.map(|s| s.split_frames as f64),
+        );
+        radio_metric(
+            "catapult_radio_max_frame_span_seconds",
+            "gauge",
+            "Longest time a frame from the radio took to arrive in full",
+            &|r| r.reassembly.map(|s| s.max_frame_span.as_secs_f64()),
+        );
+        radio_metric(
+            "catapult_radio_seconds_since_last_frame",
+            "gauge",
+            "Seconds since the radio last sent a frame",
+            &|r| r.last_frame.map(|t| now.duration_since(t).as_secs_f64()),
+        );
+        radio_metric(
+            "catapult_radio_transmitting",
+            "gauge",
+            "Whether the radio is keyed",
+            &|r| Some(flag(r.ptt_since.is_some())),
+        );
+        radio_metric(
+            "catapult_radio_ptt_seconds_total",
+            "counter",
+            "Time the radio has spent keyed",
+            &|r| Some(r.ptt_seconds(now)),
+        );
+
+        header(
+            &mut out,
+            "catapult_errors_total",
+            "counter",
+            "Errors reported by the mux, by source",
+        );
+        for (source, count) in &self.errors {
+            let _ = writeln!(
+                out,
+                "catapult_errors_total{{source=\"{}\"}} {}",
+                escape_label(source),
+                count
+            );
+        }
+
+        for (name, kind, help, value) in [
+            (
+                "catapult_active_radio_switches_total",
+                "counter",
+                "Changes of the active radio",
+                self.switches as f64,
+            ),
+            (
+                "catapult_switches_blocked_total",
+                "counter",
+                "Radio switches blocked by the lockout",
+                self.switches_blocked as f64,
+            ),
+            (
+                "catapult_amp_connected",
+                "gauge",
+                "Whether the amplifier is connected",
+                flag(self.amp_connected),
+            ),
+            (
+                "catapult_amp_frames_sent_total",
+                "counter",
+                "Frames sent to the amplifier",
+                self.amp_frames_out as f64,
+            ),
+            (
+                "catapult_amp_frames_received_total",
+                "counter",
+                "Frames received from the amplifier",
+                self.amp_frames_in as f64,
+            ),
+            (
+                "catapult_amp_write_preemptions_total",
+                "counter",
+                "Safety frames written to the amplifier ahead of queued writes",
+                self.amp_preemptions as f64,
+            ),
+            (
+                "catapult_events_total",
+                "counter",
+                "Events emitted by the mux",
+                self.events as f64,
+            ),
+            (
+                "catapult_event_queue_depth",
+                "gauge",
+                "Mux events waiting to be processed",
+                self.event_queue_depth as f64,
+            ),
+        ] {
+            header(&mut out, name, kind, help);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        out
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn flag(b: bool) -> f64 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serve `GET /metrics` until the input channel closes
+///
+/// The task owns the metrics; the host forwards mux events (and its queue
+/// depth) through `input`. Each connection is answered with a snapshot
+/// taken when it was accepted.
+pub async fn run_metrics_server(
+    listener: TcpListener,
+    mut input: mpsc::Receiver<MetricsInput>,
+) -> io::Result<()> {
+    let mut metrics = MuxMetrics::new();
+    info!("Serving metrics on {}", listener.local_addr()?);
+    loop {
+        tokio::select! {
+            // Take in queued input first so a snapshot is never behind it
+            biased;
+            msg = input.recv() => match msg {
+                Some(MetricsInput::Event(event)) => metrics.observe(&event, Instant::now()),
+                Some(MetricsInput::EventQueueDepth(depth)) => metrics.set_event_queue_depth(depth),
+                None => break,
+            },
+            accepted = listener.accept() => {
+                let (socket, peer) = accepted?;
+                let body = metrics.render(Instant::now());
+                tokio::spawn(async move {
+                    if let Err(e) = answer(socket, body).await {
+                        debug!("Metrics request from {} failed: {}", peer, e);
+                    }
+                });
+            }
+        }
+    }
+    info!("Metrics server stopped");
+    Ok(())
+}
+
+/// Read one HTTP request and answer it
+async fn answer(mut socket: TcpStream, body: String) -> io::Result<()> {
+    let (method, path) = read_request(&mut socket).await?;
+    let response = match (method.as_str(), path.as_str()) {
+        ("GET" | "HEAD", "/metrics") => {
+            ok_response(&method, "text/plain; version=0.0.4; charset=utf-8", &body)
+        }
+        _ => error_response(&method),
+    };
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await
+}
+
+/// Read the head of an HTTP request; returns its method and path (without
+/// the query string)
+pub(crate) async fn read_request(socket: &mut TcpStream) -> io::Result<(String, String)> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    tokio::time::timeout(REQUEST_TIMEOUT, async {
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") && buf.len() < MAX_REQUEST_BYTES {
+            let n = socket.read(&mut chunk).await?;
+            if n == 0 {
+                break;
+            }
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        Ok::<_, io::Error>(())
+    })
+    .await
+    .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))??;
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut parts = request.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default();
+    let path = path.split('?').next().unwrap_or_default().to_string();
+    Ok((method, path))
+}
+
+/// A 200 response carrying `body` (just the head for HEAD)
+pub(crate) fn ok_response(method: &str, content_type: &str, body: &str) -> String {
+    let head = format!(
+        "HTTP/1.1 200 OK\r\n\
+         Content-Type: {}\r\n\
+         Content-Length: {}\r\n\
+         Connection: close\r\n\r\n",
+        content_type,
+        body.len()
+    );
+    if method == "HEAD" {
+        head
+    } else {
+        head + body
+    }
+}
+
+/// 404 for an unknown path, 405 for anything but GET and HEAD
+pub(crate) fn error_response(method: &str) -> String {
+    match method {
+        "GET" | "HEAD" => {
+            "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+        }
+        _ => "HTTP/1.1 405 Method Not Allowed\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::RadioChannelMeta;
+    use crate::events::FrameId;
+    use cat_protocol::Protocol;
+    use std::time::SystemTime;
+
+    fn connected(handle: u32, name: &str) -> MuxEvent {
+        MuxEvent::RadioConnected {
+            handle: RadioHandle(handle),
+            meta: RadioChannelMeta::new_real(
+                name.to_string(),
+                "/dev/ttyUSB0".to_string(),
+                Protocol::Kenwood,
+                None,
+            ),
+        }
+    }
+
+    fn ptt(handle: u32, active: bool) -> MuxEvent {
+        MuxEvent::RadioStateChanged {
+            handle: RadioHandle(handle),
+            freq: None,
+            mode: None,
+            ptt: Some(active),
+            power_watts: None,
+        }
+    }
+
+    #[test]
+    fn test_render_counts_frames_and_ptt_time() {
+        let mut metrics = MuxMetrics::new();
+        let t0 = Instant::now();
+        metrics.observe(&connected(1, "TS-590 \"Run\""), t0);
+        for _ in 0..3 {
+            metrics.observe(
+                &MuxEvent::RadioDataIn {
+                    handle: RadioHandle(1),
+                    id: FrameId(1),
+                    data: b"FA00014074000;".to_vec(),
+                    protocol: Protocol::Kenwood,
+                    timestamp: SystemTime::now(),
+                },
+                t0,
+            );
+        }
+        metrics.observe(&ptt(1, true), t0);
+        metrics.observe(&ptt(1, false), t0 + Duration::from_secs(2));
+        metrics.observe(&ptt(1, true), t0 + Duration::from_secs(3));
+        metrics.observe(
+            &MuxEvent::Error {
+                source: "Amplifier".to_string(),
+                message: "write failed".to_string(),
+            },
+            t0,
+        );
+        metrics.observe(&MuxEvent::AmpWritePreempted { queued: 3 }, t0);
+        metrics.set_event_queue_depth(7);
+
+        let text = metrics.render(t0 + Duration::from_secs(4));
+        let labels = "radio=\"TS-590 \\\"Run\\\"\",handle=\"1\"";
+        assert!(text.contains(&format!(
+            "catapult_radio_frames_received_total{{{}}} 3",
+            labels
+        )));
+        assert!(text.contains(&format!(
+            "catapult_radio_bytes_received_total{{{}}} 42",
+            labels
+        )));
+        // Two finished seconds plus one in progress
+        assert!(text.contains(&format!("catapult_radio_ptt_seconds_total{{{}}} 3", labels)));
+        assert!(text.contains(&format!(
+            "catapult_radio_seconds_since_last_frame{{{}}} 4",
+            labels
+        )));
+        assert!(text.contains("catapult_errors_total{source=\"Amplifier\"} 1"));
+        assert!(text.contains("catapult_event_queue_depth 7"));
+        assert!(text.contains("catapult_amp_write_preemptions_total 1"));
+        assert!(text.contains("# TYPE catapult_radio_ptt_seconds_total counter"));
+
+        // Disconnecting ends the transmission and keeps the series
+        metrics.observe(
+            &MuxEvent::RadioDisconnected {
+                handle: RadioHandle(1),
+            },
+            t0 + Duration::from_secs(5),
+        );
+        let text = metrics.render(t0 + Duration::from_secs(9));
+        assert!(text.contains(&format!("catapult_radio_connected{{{}}} 0", labels)));
+        assert!(text.contains(&format!("catapult_radio_ptt_seconds_total{{{}}} 4", labels)));
+    }
+
+    #[tokio::test]
+    async fn test_server_answers_metrics_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, rx) = mpsc::channel(16);
+        let server = tokio::spawn(run_metrics_server(listener, rx));
+        tx.send(MetricsInput::Event(Box::new(connected(2, "K3"))))
+            .await
+            .unwrap();
+        tx.send(MetricsInput::EventQueueDepth(1)).await.unwrap();
+
+        let get = |path: &'static str| async move {
+            let mut socket = TcpStream::connect(addr).await.unwrap();
+            socket
+                .write_all(format!("GET {} HTTP/1.1\r\nHost: x\r\n\r\n", path).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            socket.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let response = get("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("catapult_radio_connected{radio=\"K3\",handle=\"2\"} 1"));
+        assert!(response.contains("catapult_event_queue_depth 1"));
+        assert!(get("/other").await.starts_with("HTTP/1.1 404"));
+
+        drop(tx);
+        server.await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+//! Receive-only monitor channels
+//!
+//! An SDR (or any receiver with its own CAT port) can be registered as a
+//! monitor channel. Its frequency and mode are tracked and shown like any
+//! other radio's, but it never becomes the active radio: selecting it is
+//! refused and its reports never trigger a switch, so the amplifier never
+//! follows it. With [`MonitorConfig::follow_active`] set, the mux retunes it
+//! to the active radio's frequency whenever that changes.
+
+use cat_protocol::Frequency;
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::state::RadioHandle;
+
+/// How a monitor channel behaves
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorConfig {
+    /// Retune to the active radio's frequency
+    #[serde(default)]
+    pub follow_active: bool,
+}
+
+/// Monitor channels that follow the active radio
+#[derive(Debug, Default)]
+pub struct MonitorFollowers {
+    /// Frequency last sent to each follower
+    last_sent: HashMap<RadioHandle, Option<Frequency>>,
+}
+
+impl MonitorFollowers {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start or stop a radio following the active radio
+    pub fn set(&mut self, handle: RadioHandle, follow: bool) {
+        if follow {
+            self.last_sent.entry(handle).or_insert(None);
+        } else {
+            self.last_sent.remove(&handle);
+        }
+    }
+
+    pub fn remove(&mut self, handle: RadioHandle) {
+        self.last_sent.remove(&handle);
+    }
+
+    /// Followers to tune to `hz`
+    ///
+    /// Each is sent a frequency once; one already reporting `hz` (per
+    /// `current`) is skipped, so a follower tuned by hand to the same spot
+    /// isn't written to.
+    pub fn retune(
+        &mut self,
+        hz: Frequency,
+        current: impl Fn(RadioHandle) -> Option<Frequency>,
+    ) -> Vec<RadioHandle> {
+        let mut due = Vec::new();
+        for (handle, last_sent) in &mut self.last_sent {
+            if *last_sent == Some(hz) {
+                continue;
+            }
+            *last_sent = Some(hz);
+            if current(*handle) != Some(hz) {
+                due.push(*handle);
+            }
+        }
+        due.sort_by_key(|h| h.0);
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_followers_are_tuned_once_per_frequency() {
+        let mut followers = MonitorFollowers::new();
+        followers.set(RadioHandle(2), true);
+        followers.set(RadioHandle(3), true);
+
+        // Radio 3 is already there
+        let current = |h: RadioHandle| (h.0 == 3).then_some(Frequency::from_hz(14_074_000));
+        assert_eq!(
+            followers.retune(Frequency::from_hz(14_074_000), current),
+            vec![RadioHandle(2)]
+        );
+        assert!(followers
+            .retune(Frequency::from_hz(14_074_000), current)
+            .is_empty());
+        assert_eq!(
+            followers.retune(Frequency::from_hz(7_074_000), current),
+            vec![RadioHandle(2), RadioHandle(3)]
+        );
+
+        followers.set(RadioHandle(2), false);
+        assert_eq!(
+            followers.retune(Frequency::from_hz(3_573_000), current),
+            vec![RadioHandle(3)]
+        );
+    }
+}
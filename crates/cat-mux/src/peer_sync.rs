@@ -0,0 +1,416 @@
+//! Multi-op station sync between catapult instances
+//!
+//! In a multi-op station each operating position (run, mult, ...) has its
+//! own catapult and amplifier. [`run_peer_sync`] shares each instance's
+//! active frequency and TX state with the others over UDP, one datagram per
+//! change plus a heartbeat, and hands what it hears to the mux actor as
+//! [`PeerStatus`]es. The actor keeps them in a [`PeerTable`] and enforces the
+//! interlock: its amplifier is not keyed while a peer transmits on the same
+//! band.
+//!
+//! Both sides must agree on who keeps the band without a round trip, so each
+//! status carries the wall-clock time its station went into transmit. The
+//! station that keyed first keeps the band; a tie goes to the lower instance
+//! name. This relies on the machines' clocks being in step (NTP is plenty).
+
+use cat_protocol::Frequency;
+use std::collections::HashMap;
+use std::io;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::net::{lookup_host, UdpSocket};
+use tokio::sync::mpsc;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{debug, info};
+
+use crate::actor::MuxActorCommand;
+use crate::band_plan::band_name;
+use crate::client::MuxClient;
+
+/// Default UDP port for peer sync (clear of rigctld's 4532 and rotctld's 4533)
+pub const DEFAULT_PEER_PORT: u16 = 47533;
+
+/// How often the local state is re-sent when nothing changes
+pub const PEER_HEARTBEAT: Duration = Duration::from_secs(1);
+
+/// How long a peer is remembered after its last datagram
+pub const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// First word of every datagram (format version 1)
+const MAGIC: &str = "CATPEER1";
+
+/// What an instance shares with its peers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerState {
+    /// Active radio's frequency in Hz
+    pub frequency_hz: Option<Frequency>,
+    /// When the active radio went into transmit (ms since the Unix epoch),
+    /// `None` while receiving
+    pub tx_since_ms: Option<u64>,
+}
+
+impl PeerState {
+    /// Amateur band of the frequency, e.g. "20m"
+    pub fn band(&self) -> Option<&'static str> {
+        self.frequency_hz.and_then(band_name)
+    }
+
+    /// Whether the station is transmitting
+    pub fn transmitting(&self) -> bool {
+        self.tx_since_ms.is_some()
+    }
+}
+
+/// A state as received from a peer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerStatus {
+    /// The peer's instance name, e.g. "run"
+    pub instance: String,
+    /// Counts up with every datagram the peer sends
+    pub seq: u64,
+    /// When the peer sent it (ms since the Unix epoch)
+    pub sent_ms: u64,
+    /// What the peer is doing
+    pub state: PeerState,
+}
+
+impl PeerStatus {
+    /// Encode as a datagram
+    ///
+    /// `CATPEER1 <instance> <seq> <sent_ms> <frequency_hz|-> <tx_since_ms|->`
+    pub fn encode(&self) -> Vec<u8> {
+        let opt = |v: Option<u64>| v.map_or_else(|| "-".to_string(), |v| v.to_string());
+        format!(
+            "{} {} {} {} {} {}\n",
+            MAGIC,
+            self.instance,
+            self.seq,
+            self.sent_ms,
+            opt(self.state.frequency_hz.map(Frequency::hz)),
+            opt(self.state.tx_since_ms)
+        )
+        .into_bytes()
+    }
+
+    /// Decode a datagram, `None` if it isn't one
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(data).ok()?;
+        let mut fields = text.split_whitespace();
+        if fields.next()? != MAGIC {
+            return None;
+        }
+        let opt = |field: &str| match field {
+            "-" => Some(None),
+            v => v.parse().ok().map(Some),
+        };
+        let status = Self {
+            instance: fields.next()?.to_string(),
+            seq: fields.next()?.parse().ok()?,
+            sent_ms: fields.next()?.parse().ok()?,
+            state: PeerState {
+                frequency_hz: opt(fields.next()?)?.map(Frequency::from_hz),
+                tx_since_ms: opt(fields.next()?)?,
+            },
+        };
+        fields.next().is_none().then_some(status)
+    }
+
+    /// One-line description for display, e.g. `mult: 20m TX`
+    pub fn summary(&self) -> String {
+        format!(
+            "{}: {} {}",
+            self.instance,
+            self.state.band().unwrap_or("out of band"),
+            if self.state.transmitting() {
+                "TX"
+            } else {
+                "RX"
+            }
+        )
+    }
+}
+
+/// Milliseconds since the Unix epoch
+pub fn unix_ms(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Instance names are sent as one word
+pub fn sanitize_instance(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_whitespace() { '-' } else { c })
+        .collect();
+    if name.is_empty() {
+        "catapult".to_string()
+    } else {
+        name
+    }
+}
+
+/// The latest status of each peer that is still being heard
+#[derive(Debug)]
+pub struct PeerTable {
+    peers: HashMap<String, (PeerStatus, Instant)>,
+    timeout: Duration,
+}
+
+impl Default for PeerTable {
+    fn default() -> Self {
+        Self {
+            peers: HashMap::new(),
+            timeout: PEER_TIMEOUT,
+        }
+    }
+}
+
+impl PeerTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a status; returns whether the peer's band or TX state changed
+    ///
+    /// A status sent before the last one seen from that peer (a datagram
+    /// that arrived out of order) is dropped.
+    pub fn update(&mut self, status: PeerStatus, now: Instant) -> bool {
+        match self.peers.get_mut(&status.instance) {
+            Some((last, heard)) => {
+                if (status.sent_ms, status.seq) <= (last.sent_ms, last.seq) {
+                    return false;
+                }
+                let changed = (last.state.band(), last.state.transmitting())
+                    != (status.state.band(), status.state.transmitting());
+                *last = status;
+                *heard = now;
+                changed
+            }
+            None => {
+                info!("Peer {} joined", status.instance);
+                self.peers.insert(status.instance.clone(), (status, now));
+                true
+            }
+        }
+    }
+
+    /// Forget peers not heard from lately; returns whether any were
+    pub fn expire(&mut self, now: Instant) -> bool {
+        let before = self.peers.len();
+        let timeout = self.timeout;
+        self.peers.retain(|name, (_, heard)| {
+            let alive = now.duration_since(*heard) < timeout;
+            if !alive {
+                info!("Peer {} timed out", name);
+            }
+            alive
+        });
+        self.peers.len() != before
+    }
+
+    /// Peers by instance name
+    pub fn peers(&self) -> Vec<PeerStatus> {
+        let mut peers: Vec<_> = self.peers.values().map(|(s, _)| s.clone()).collect();
+        peers.sort_by(|a, b| a.instance.cmp(&b.instance));
+        peers
+    }
+
+    /// The peer that keeps the band when `local` (instance `instance`) wants to transmit
+    ///
+    /// `None` if the local station isn't transmitting in a band, or no peer
+    /// transmitting on that band keyed before it.
+    pub fn blocking(&self, local: &PeerState, instance: &str) -> Option<&PeerStatus> {
+        let (Some(band), Some(since)) = (local.band(), local.tx_since_ms) else {
+            return None;
+        };
+        self.peers
+            .values()
+            .map(|(s, _)| s)
+            .filter(|s| s.state.band() == Some(band))
+            .filter_map(|s| s.state.tx_since_ms.map(|t| (t, s)))
+            .filter(|(t, s)| (*t, s.instance.as_str()) < (since, instance))
+            .min_by_key(|(t, _)| *t)
+            .map(|(_, s)| s)
+    }
+
+    /// Forget every peer
+    pub fn clear(&mut self) {
+        self.peers.clear();
+    }
+
+    /// Whether no peers are known
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+}
+
+/// Share local state with peers and pass theirs to the mux actor
+///
+/// Listens on `bind` (e.g. `0.0.0.0:47533`) and sends to each of `peers`
+/// (`host:port`, a broadcast address works too) whenever the actor reports
+/// a new local state on `local_rx`, and every [`PEER_HEARTBEAT`]. Runs until
+/// the actor goes away or the socket fails.
+pub async fn run_peer_sync(
+    bind: &str,
+    peers: &[String],
+    instance: &str,
+    mux: MuxClient,
+    mut local_rx: mpsc::Receiver<PeerState>,
+) -> io::Result<()> {
+    let socket = UdpSocket::bind(bind).await?;
+    socket.set_broadcast(true)?;
+    let mut targets: Vec<SocketAddr> = Vec::new();
+    for peer in peers {
+        targets.extend(lookup_host(peer.as_str()).await?);
+    }
+    info!(
+        "Peer sync as {} on {}, sending to {} peer(s)",
+        instance,
+        socket.local_addr()?,
+        targets.len()
+    );
+
+    let mut status = PeerStatus {
+        instance: instance.to_string(),
+        seq: 0,
+        sent_ms: 0,
+        state: PeerState::default(),
+    };
+    let mut heartbeat = interval(PEER_HEARTBEAT);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    let mut buf = [0u8; 512];
+
+    loop {
+        tokio::select! {
+            local = local_rx.recv() => {
+                let Some(state) = local else {
+                    return Ok(());
+                };
+                status.state = state;
+                send_status(&socket, &targets, &mut status).await;
+            }
+            _ = heartbeat.tick() => {
+                send_status(&socket, &targets, &mut status).await;
+            }
+            received = socket.recv_from(&mut buf) => {
+                let (n, from) = match received {
+                    Ok(received) => received,
+                    // An earlier send to a peer that isn't listening
+                    Err(e) if e.kind() == io::ErrorKind::ConnectionReset
+                        || e.kind() == io::ErrorKind::ConnectionRefused => continue,
+                    Err(e) => return Err(e),
+                };
+                let Some(peer) = PeerStatus::decode(&buf[..n]) else {
+                    debug!("Ignoring datagram from {} that isn't peer sync", from);
+                    continue;
+                };
+                // Our own broadcast coming back
+                if peer.instance == status.instance {
+                    continue;
+                }
+                if mux.send(MuxActorCommand::PeerStatus { status: peer }).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Stamp and send the local status to every peer
+async fn send_status(socket: &UdpSocket, targets: &[SocketAddr], status: &mut PeerStatus) {
+    status.seq += 1;
+    status.sent_ms = unix_ms(SystemTime::now());
+    let data = status.encode();
+    for target in targets {
+        if let Err(e) = socket.send_to(&data, target).await {
+            debug!("Peer sync send to {} failed: {}", target, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(instance: &str, sent_ms: u64, hz: u64, tx_since_ms: Option<u64>) -> PeerStatus {
+        PeerStatus {
+            instance: instance.to_string(),
+            seq: sent_ms,
+            sent_ms,
+            state: PeerState {
+                frequency_hz: Some(Frequency::from_hz(hz)),
+                tx_since_ms,
+            },
+        }
+    }
+
+    #[test]
+    fn test_status_round_trips() {
+        let s = status(
+            "mult",
+            1_700_000_000_123,
+            14_025_000,
+            Some(1_700_000_000_100),
+        );
+        assert_eq!(PeerStatus::decode(&s.encode()), Some(s.clone()));
+        assert_eq!(s.summary(), "mult: 20m TX");
+
+        let idle = PeerStatus {
+            state: PeerState::default(),
+            ..s
+        };
+        assert_eq!(PeerStatus::decode(&idle.encode()), Some(idle));
+
+        assert!(PeerStatus::decode(b"CATPEER1 run 1 2 3").is_none());
+        assert!(PeerStatus::decode(b"CATPEER9 run 1 2 3 -").is_none());
+        assert!(PeerStatus::decode(b"CATPEER1 run 1 2 3 - extra").is_none());
+        assert_eq!(sanitize_instance(" run 2 "), "run-2");
+    }
+
+    #[test]
+    fn test_first_to_key_keeps_the_band() {
+        let t0 = Instant::now();
+        let mut table = PeerTable::new();
+        assert!(table.update(status("mult", 1_000, 14_200_000, Some(900)), t0));
+
+        // We keyed after the peer on the same band
+        let local = PeerState {
+            frequency_hz: Some(Frequency::from_hz(14_025_000)),
+            tx_since_ms: Some(950),
+        };
+        assert_eq!(table.blocking(&local, "run").unwrap().instance, "mult");
+
+        // Keyed first, or on another band: not blocked
+        let first = PeerState {
+            tx_since_ms: Some(800),
+            ..local
+        };
+        assert!(table.blocking(&first, "run").is_none());
+        let other_band = PeerState {
+            frequency_hz: Some(Frequency::from_hz(7_025_000)),
+            ..local
+        };
+        assert!(table.blocking(&other_band, "run").is_none());
+
+        // A tie goes to the lower instance name
+        let tie = PeerState {
+            tx_since_ms: Some(900),
+            ..local
+        };
+        assert!(table.blocking(&tie, "alpha").is_none());
+        assert!(table.blocking(&tie, "run").is_some());
+
+        // A late datagram doesn't undo a newer one
+        assert!(table.update(status("mult", 1_100, 14_200_000, None), t0));
+        assert!(!table.update(status("mult", 1_050, 14_200_000, Some(900)), t0));
+        assert!(table.blocking(&local, "run").is_none());
+
+        // Peers drop out when they go quiet
+        assert!(!table.expire(t0 + PEER_TIMEOUT / 2));
+        assert!(table.expire(t0 + PEER_TIMEOUT));
+        assert!(table.is_empty());
+    }
+}
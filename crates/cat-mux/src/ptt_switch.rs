@@ -0,0 +1,80 @@
+//! PTT foot switch
+//!
+//! A foot switch (or any contact closure) wired to a modem-status line of a
+//! spare serial port keys whichever radio is active. [`run_ptt_switch`]
+//! samples the pin and sends [`MuxActorCommand::SwitchPtt`] on each
+//! debounced change. The actor keys the active radio, through the sequencer
+//! when it is enabled. The amplifier follows once the radio reports that it
+//! is transmitting, as it does for any other key-up. Releasing unkeys the
+//! radio the switch keyed, even if another radio has become active since.
+//!
+//! An asserted pin is a press; a normally-closed switch holds the pin
+//! asserted at rest, so it is watched `inverted`.
+//!
+//! A switch that goes away while pressed must not leave the station
+//! transmitting, so when the port fails the watcher sends a release before
+//! it returns.
+//!
+//! USB HID pedals (the ones that present as a keyboard or joystick) are out
+//! of scope: only a contact closure on a serial port is watched.
+
+use std::io;
+
+use tokio_serial::SerialPortBuilderExt;
+use tracing::info;
+
+use crate::actor::MuxActorCommand;
+use crate::bookmark::{PinEdge, SerialPin, PIN_POLL_INTERVAL};
+use crate::client::MuxClient;
+
+/// Key the active radio while a pin on a spare serial port is asserted
+///
+/// Runs until the port fails or the actor goes away. With `inverted` the
+/// switch is pressed while the pin is deasserted. A switch already pressed
+/// when watching starts is ignored until it is released.
+pub async fn run_ptt_switch(
+    port_name: &str,
+    pin: SerialPin,
+    inverted: bool,
+    mux: MuxClient,
+) -> io::Result<()> {
+    let mut port = tokio_serial::new(port_name, 9600).open_native_async()?;
+    info!("Watching {} on {} as a PTT switch", pin.name(), port_name);
+
+    let source = ptt_switch_source(port_name, pin);
+    let mut edge = PinEdge::default();
+    let mut pressed = false;
+    let mut interval = tokio::time::interval(PIN_POLL_INTERVAL);
+    let result = loop {
+        interval.tick().await;
+        let high = match pin.read(&mut port) {
+            Ok(high) => high,
+            Err(e) => break Err(io::Error::from(e)),
+        };
+        if let Some(level) = edge.change(high) {
+            let active = level != inverted;
+            pressed = active;
+            let command = MuxActorCommand::SwitchPtt {
+                source: source.clone(),
+                active,
+            };
+            if mux.send(command).await.is_err() {
+                return Ok(());
+            }
+        }
+    };
+    if pressed {
+        let _ = mux
+            .send(MuxActorCommand::SwitchPtt {
+                source,
+                active: false,
+            })
+            .await;
+    }
+    result
+}
+
+/// How a PTT switch names itself to the mux
+pub fn ptt_switch_source(port_name: &str, pin: SerialPin) -> String {
+    format!("{} on {}", pin.name(), port_name)
+}
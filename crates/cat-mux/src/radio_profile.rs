@@ -0,0 +1,403 @@
+//! Capabilities learned from the radio itself
+//!
+//! The model database says what a model should support; firmware versions,
+//! menu settings and clones don't always agree. The first time a radio
+//! connects, [`AsyncRadioConnection::discover_profile`] asks it each
+//! [`ProfileQuery`] in turn (twice before giving up on one) and records which
+//! ones it answered, whether auto-information stays on and at what level, and
+//! what it reports. The host stores the resulting [`RadioProfile`] under
+//! [`RadioProfile::key`] and hands it back on later connections
+//! ([`AsyncRadioConnection::set_profile`]), where it:
+//!
+//! - drops queries the radio never answers instead of sending them again
+//! - turns idle polling on for radios whose auto-information doesn't stick
+//!
+//! A profile older than [`PROFILE_MAX_AGE`] is learned again, so a query
+//! that once went unanswered (a busy radio, a menu setting since changed)
+//! isn't given up on for good; the host can also forget one on request.
+//!
+//! [`AsyncRadioConnection::discover_profile`]: crate::AsyncRadioConnection::discover_profile
+//! [`AsyncRadioConnection::set_profile`]: crate::AsyncRadioConnection::set_profile
+
+use std::collections::BTreeSet;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cat_protocol::{OperatingMode, Protocol, RadioRequest, RadioResponse, Vfo};
+use serde::{Deserialize, Serialize};
+
+/// Times discovery asks a query before recording it as unanswered
+pub const PROFILE_ATTEMPTS: usize = 2;
+
+/// How long a learned profile is trusted before the radio is profiled again
+pub const PROFILE_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// A query whose answer (or silence) a profile records
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ProfileQuery {
+    Frequency,
+    Mode,
+    Vfo,
+    Ptt,
+    TxPower,
+    KeyerSpeed,
+    AutoInfo,
+    ControlBand,
+    TransmitBand,
+}
+
+impl ProfileQuery {
+    /// Every query, in the order discovery asks them
+    pub const ALL: [ProfileQuery; 9] = [
+        ProfileQuery::Frequency,
+        ProfileQuery::Mode,
+        ProfileQuery::Vfo,
+        ProfileQuery::Ptt,
+        ProfileQuery::TxPower,
+        ProfileQuery::KeyerSpeed,
+        ProfileQuery::AutoInfo,
+        ProfileQuery::ControlBand,
+        ProfileQuery::TransmitBand,
+    ];
+
+    /// Request that asks it
+    pub fn request(&self) -> RadioRequest {
+        match self {
+            Self::Frequency => RadioRequest::GetFrequency,
+            Self::Mode => RadioRequest::GetMode,
+            Self::Vfo => RadioRequest::GetVfo,
+            Self::Ptt => RadioRequest::GetPtt,
+            Self::TxPower => RadioRequest::GetTxPower,
+            Self::KeyerSpeed => RadioRequest::GetKeyerSpeed,
+            Self::AutoInfo => RadioRequest::GetAutoInfo,
+            Self::ControlBand => RadioRequest::GetControlBand,
+            Self::TransmitBand => RadioRequest::GetTransmitBand,
+        }
+    }
+
+    /// Query a request asks (None for set commands and unprofiled queries)
+    pub fn of(request: &RadioRequest) -> Option<Self> {
+        Self::ALL.into_iter().find(|q| q.request() == *request)
+    }
+
+    /// Whether a response answers it
+    pub fn answered_by(&self, response: &RadioResponse) -> bool {
+        match self {
+            Self::Frequency => response.frequency().is_some(),
+            Self::Mode => response.mode().is_some(),
+            Self::Vfo => response.vfo_state().is_some(),
+            Self::Ptt => matches!(response, RadioResponse::Ptt { .. }),
+            Self::TxPower => response.tx_power().is_some(),
+            Self::KeyerSpeed => response.keyer_speed().is_some(),
+            Self::AutoInfo => matches!(response, RadioResponse::AutoInfo { .. }),
+            Self::ControlBand => matches!(response, RadioResponse::ControlBand { .. }),
+            Self::TransmitBand => matches!(response, RadioResponse::TransmitBand { .. }),
+        }
+    }
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Frequency => "Frequency",
+            Self::Mode => "Mode",
+            Self::Vfo => "VFO",
+            Self::Ptt => "PTT",
+            Self::TxPower => "TX power",
+            Self::KeyerSpeed => "Keyer speed",
+            Self::AutoInfo => "Auto-info",
+            Self::ControlBand => "Control band",
+            Self::TransmitBand => "Transmit band",
+        }
+    }
+}
+
+/// What one radio turned out to support
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RadioProfile {
+    /// Model name the radio identified as
+    pub identity: String,
+    pub protocol: Protocol,
+    /// Queries it answered
+    pub answered: BTreeSet<ProfileQuery>,
+    /// Queries it ignored (those it can't be sent at all are in neither set)
+    pub unanswered: BTreeSet<ProfileQuery>,
+    /// Whether auto-information was still on when asked (None = couldn't ask)
+    pub auto_info: Option<bool>,
+    /// Auto-information level it settled on when asked for the highest
+    /// (`AI2` radios say 2, older `AI1` ones 1; None = not an ASCII protocol)
+    #[serde(default)]
+    pub auto_info_level: Option<u8>,
+    /// VFOs it has reported using
+    pub vfos: Vec<Vfo>,
+    /// Modes it has reported
+    pub modes: Vec<OperatingMode>,
+    /// When it was learned, in seconds since the Unix epoch (0 = by a version
+    /// that didn't record it)
+    #[serde(default)]
+    pub learned_at: u64,
+}
+
+impl RadioProfile {
+    /// An empty profile for a radio identified as `identity`
+    pub fn new(identity: impl Into<String>, protocol: Protocol) -> Self {
+        Self {
+            identity: identity.into(),
+            protocol,
+            answered: BTreeSet::new(),
+            unanswered: BTreeSet::new(),
+            auto_info: None,
+            auto_info_level: None,
+            vfos: Vec::new(),
+            modes: Vec::new(),
+            learned_at: 0,
+        }
+    }
+
+    /// Stamp the profile as learned at `now`
+    pub fn learned(mut self, now: SystemTime) -> Self {
+        self.learned_at = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        self
+    }
+
+    /// Whether the profile is too old to trust (or of unknown age)
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        let learned = UNIX_EPOCH + Duration::from_secs(self.learned_at);
+        self.learned_at == 0
+            || now
+                .duration_since(learned)
+                .is_ok_and(|age| age > PROFILE_MAX_AGE)
+    }
+
+    /// Key to store the profile under: radios that identify alike share one
+    pub fn key(protocol: Protocol, identity: &str) -> String {
+        format!("{}/{}", protocol.name(), identity)
+    }
+
+    /// Record a query's outcome
+    pub fn record(&mut self, query: ProfileQuery, answered: bool) {
+        if answered {
+            self.unanswered.remove(&query);
+            self.answered.insert(query);
+        } else if !self.answered.contains(&query) {
+            self.unanswered.insert(query);
+        }
+    }
+
+    /// Note what a response reports; returns whether the profile changed
+    pub fn observe(&mut self, response: &RadioResponse) -> bool {
+        let mut changed = false;
+        if let Some(mode) = response.mode() {
+            if !self.modes.contains(&mode) {
+                self.modes.push(mode);
+                changed = true;
+            }
+        }
+        if let Some(vfo) = response.vfo_state().and_then(|state| state.legacy()) {
+            if !self.vfos.contains(&vfo) {
+                self.vfos.push(vfo);
+                changed = true;
+            }
+        }
+        if let RadioResponse::AutoInfo { enabled } = response {
+            changed |= self.auto_info != Some(*enabled);
+            self.auto_info = Some(*enabled);
+        }
+        changed
+    }
+
+    /// Whether to send `request`: false only for queries the radio ignores
+    pub fn should_send(&self, request: &RadioRequest) -> bool {
+        ProfileQuery::of(request).is_none_or(|q| !self.unanswered.contains(&q))
+    }
+
+    /// Whether the radio must be polled to follow it
+    pub fn needs_polling(&self) -> bool {
+        self.auto_info == Some(false) || self.auto_info_level == Some(0)
+    }
+
+    /// Number of VFOs the radio has: those it reported, and at least two if
+    /// it answers which one is selected
+    pub fn vfo_count(&self) -> usize {
+        let selectable = if self.answered.contains(&ProfileQuery::Vfo) {
+            2
+        } else {
+            1
+        };
+        self.vfos.len().max(selectable)
+    }
+
+    /// One line for display, e.g. "Answers Frequency, Mode; ignores TX power; auto-info on"
+    pub fn summary(&self) -> String {
+        let names = |set: &BTreeSet<ProfileQuery>| {
+            set.iter().map(|q| q.name()).collect::<Vec<_>>().join(", ")
+        };
+        let mut parts = vec![format!("Answers {}", names(&self.answered))];
+        if !self.unanswered.is_empty() {
+            parts.push(format!("ignores {}", names(&self.unanswered)));
+        }
+        match (self.auto_info, self.auto_info_level) {
+            (Some(false), _) | (_, Some(0)) => parts.push("auto-info off, polled".to_string()),
+            (Some(true), Some(level)) => parts.push(format!("auto-info on (AI{})", level)),
+            (Some(true), None) => parts.push("auto-info on".to_string()),
+            (None, _) => {}
+        }
+        parts.push(match self.vfo_count() {
+            1 => "1 VFO".to_string(),
+            n => format!("{} VFOs", n),
+        });
+        parts.join("; ")
+    }
+}
+
+/// Level in an ASCII auto-information reply (`AI2;`), if `data` holds one
+pub(crate) fn auto_info_level(data: &[u8]) -> Option<u8> {
+    data.split(|&b| b == b';').find_map(|frame| match frame {
+        [b'A', b'I', level @ b'0'..=b'9'] => Some(level - b'0'),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unanswered_queries_are_dropped() {
+        let mut profile = RadioProfile::new("TS-590SG", Protocol::Kenwood);
+        profile.record(ProfileQuery::Frequency, true);
+        profile.record(ProfileQuery::TxPower, false);
+
+        assert!(profile.should_send(&RadioRequest::GetFrequency));
+        assert!(!profile.should_send(&RadioRequest::GetTxPower));
+        // Set commands always go through
+        assert!(profile.should_send(&RadioRequest::SetTxPower { watts: 50 }));
+
+        // A later answer wins
+        profile.record(ProfileQuery::TxPower, true);
+        assert!(profile.should_send(&RadioRequest::GetTxPower));
+        profile.record(ProfileQuery::TxPower, false);
+        assert!(profile.should_send(&RadioRequest::GetTxPower));
+    }
+
+    #[test]
+    fn test_observe_learns_modes_and_auto_info() {
+        let mut profile = RadioProfile::new("IC-7300", Protocol::IcomCIV);
+        assert!(profile.observe(&RadioResponse::Mode {
+            mode: OperatingMode::Cw
+        }));
+        assert!(!profile.observe(&RadioResponse::Mode {
+            mode: OperatingMode::Cw
+        }));
+        assert!(profile.observe(&RadioResponse::AutoInfo { enabled: false }));
+        assert_eq!(profile.modes, vec![OperatingMode::Cw]);
+        assert!(profile.needs_polling());
+    }
+
+    #[test]
+    fn test_profiles_expire() {
+        let now = SystemTime::now();
+        let profile = RadioProfile::new("TS-590SG", Protocol::Kenwood);
+        // Saved before profiles were dated
+        assert!(profile.is_expired(now));
+
+        let profile = profile.learned(now);
+        assert!(!profile.is_expired(now + Duration::from_secs(60)));
+        assert!(profile.is_expired(now + PROFILE_MAX_AGE + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_auto_info_level_is_read_from_the_reply() {
+        assert_eq!(auto_info_level(b"FA00014074000;AI2;"), Some(2));
+        assert_eq!(auto_info_level(b"AI1;"), Some(1));
+        assert_eq!(auto_info_level(b"AI;"), None);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_against_kenwood_radio() {
+        use crate::{AsyncRadioConnection, MuxClient, RadioHandle};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Answers frequency, mode, VFO and auto-info; ignores everything else
+        let (ours, mut radio) = tokio::io::duplex(256);
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok(n) = radio.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                for cmd in String::from_utf8_lossy(&buf[..n]).split_terminator(';') {
+                    let reply: &[u8] = match cmd {
+                        "FA" => b"FA00014074000;",
+                        "MD" => b"MD2;",
+                        "FR" => b"FR0;",
+                        "AI" => b"AI2;",
+                        _ => continue,
+                    };
+                    let _ = radio.write_all(reply).await;
+                }
+            }
+        });
+
+        let (mux, _mux_rx, _event_rx) = MuxClient::channel(64);
+        let mut conn =
+            AsyncRadioConnection::new(RadioHandle(1), "test".into(), ours, Protocol::Kenwood, &mux);
+        let profile = conn.discover_profile("TS-590SG").await;
+
+        assert!(profile.answered.contains(&ProfileQuery::Frequency));
+        assert!(profile.answered.contains(&ProfileQuery::Vfo));
+        assert!(profile.unanswered.contains(&ProfileQuery::TxPower));
+        assert_eq!(profile.auto_info, Some(true));
+        assert_eq!(profile.auto_info_level, Some(2));
+        assert_eq!(profile.modes, vec![OperatingMode::Usb]);
+        assert_eq!(profile.vfos, vec![Vfo::A]);
+        assert_eq!(profile.vfo_count(), 2);
+        assert!(!profile.is_expired(std::time::SystemTime::now()));
+    }
+
+    #[tokio::test]
+    async fn test_discovery_asks_again_before_giving_up() {
+        use crate::{AsyncRadioConnection, MuxClient, RadioHandle};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // Too busy to answer the first power query, and never reports PTT
+        let (ours, mut radio) = tokio::io::duplex(256);
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            let mut power_asked = 0;
+            while let Ok(n) = radio.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                for cmd in String::from_utf8_lossy(&buf[..n]).split_terminator(';') {
+                    let reply: &[u8] = match cmd {
+                        "PC" => {
+                            power_asked += 1;
+                            if power_asked == 1 {
+                                continue;
+                            }
+                            b"PC050;"
+                        }
+                        "FA" => b"FA00014074000;",
+                        "MD" => b"MD2;",
+                        "FR" => b"FR0;",
+                        "AI" => b"AI2;",
+                        "KS" => b"KS025;",
+                        "CB" => b"CB0;",
+                        "TB" => b"TB0;",
+                        _ => continue,
+                    };
+                    let _ = radio.write_all(reply).await;
+                }
+            }
+        });
+
+        let (mux, _mux_rx, _event_rx) = MuxClient::channel(64);
+        let mut conn =
+            AsyncRadioConnection::new(RadioHandle(1), "test".into(), ours, Protocol::Kenwood, &mux);
+        let profile = conn.discover_profile("TS-590SG").await;
+
+        assert!(profile.answered.contains(&ProfileQuery::TxPower));
+        assert_eq!(profile.unanswered, BTreeSet::from([ProfileQuery::Ptt]));
+    }
+}
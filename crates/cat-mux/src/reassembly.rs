@@ -0,0 +1,310 @@
+//! Read-side frame reassembly statistics
+//!
+//! USB serial adapters don't deliver whole frames. An FTDI chip holds
+//! received bytes until its buffer fills or its latency timer (16 ms by
+//! default) expires, so a frame can arrive in several reads, and the gap
+//! between its pieces adds jitter to everything downstream. The codecs
+//! buffer across reads, so parsing is unaffected, but how often it happens
+//! (and how long a frame sits half-received) says whether the adapter's
+//! latency timer is worth tuning (see [`cat_detect::latency_timer`]).
+//!
+//! A frame that starts arriving and never finishes (a cable pulled mid-frame,
+//! a radio power-cycled mid-report) would otherwise sit in the codec and be
+//! glued onto the front of the next frame. [`PartialFrameTimer`] notices when
+//! a codec has held a partial frame with no new bytes for longer than its
+//! protocol's [`partial_frame_timeout`], so the actor can discard it.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cat_protocol::Protocol;
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// How frames from one radio were split across reads
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ReassemblyStats {
+    /// Reads from the port
+    pub reads: u64,
+    /// Bytes read
+    pub bytes: u64,
+    /// Frames parsed
+    pub frames: u64,
+    /// Frames that arrived over more than one read
+    pub split_frames: u64,
+    /// Most reads one frame arrived over
+    pub max_reads_per_frame: u32,
+    /// Longest time from a split frame's first piece to its last
+    pub max_frame_span: Duration,
+}
+
+impl ReassemblyStats {
+    /// Fraction of frames that arrived over more than one read
+    pub fn split_ratio(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.split_frames as f64 / self.frames as f64
+    }
+}
+
+/// Tracks reassembly for one radio's read stream
+#[derive(Debug, Default)]
+pub struct ReassemblyTracker {
+    stats: ReassemblyStats,
+    /// Statistics as of the last [`Self::take_update`]
+    reported: ReassemblyStats,
+    /// When the current partial frame's first piece arrived, and how many reads so far
+    partial: Option<(Instant, u32)>,
+}
+
+impl ReassemblyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Statistics so far
+    pub fn stats(&self) -> ReassemblyStats {
+        self.stats
+    }
+
+    /// Statistics, if they changed since the last call
+    pub fn take_update(&mut self) -> Option<ReassemblyStats> {
+        if self.stats == self.reported {
+            return None;
+        }
+        self.reported = self.stats;
+        Some(self.stats)
+    }
+
+    /// Record one read and the raw bytes of the frames the codec completed from it
+    ///
+    /// A frame whose bytes aren't all in this read must have started in an
+    /// earlier one. Bytes after the last complete frame start a partial frame
+    /// (or are skipped noise, which the next whole frame clears).
+    pub fn record<'a>(
+        &mut self,
+        data: &[u8],
+        frames: impl IntoIterator<Item = &'a [u8]>,
+        received: Instant,
+    ) {
+        self.stats.reads += 1;
+        self.stats.bytes += data.len() as u64;
+
+        let mut consumed_to = None;
+        for (i, frame) in frames.into_iter().enumerate() {
+            self.stats.frames += 1;
+            let pos = find(data, frame);
+            if i == 0 {
+                if let Some((started, reads)) = self.partial.take().filter(|_| pos.is_none()) {
+                    let reads = reads + 1;
+                    let span = received.saturating_duration_since(started);
+                    self.stats.split_frames += 1;
+                    self.stats.max_reads_per_frame = self.stats.max_reads_per_frame.max(reads);
+                    self.stats.max_frame_span = self.stats.max_frame_span.max(span);
+                }
+            }
+            let end = match pos {
+                Some(pos) => pos + frame.len(),
+                None => tail_len(data, frame),
+            };
+            consumed_to = Some(consumed_to.map_or(end, |c: usize| c.max(end)));
+        }
+
+        match consumed_to {
+            // Nothing completed: still receiving the same frame
+            None => {
+                if let Some((_, reads)) = &mut self.partial {
+                    *reads += 1;
+                } else if !data.is_empty() {
+                    self.partial = Some((received, 1));
+                }
+            }
+            Some(end) if end < data.len() => self.partial = Some((received, 1)),
+            Some(_) => self.partial = None,
+        }
+    }
+}
+
+/// A codec the mux actor feeds from a serial stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameSource {
+    /// A radio's incoming data
+    Radio(RadioHandle),
+    /// The amplifier's requests
+    Amplifier,
+    /// The shadow amplifier's requests
+    ShadowAmplifier,
+}
+
+/// How long a partial frame may wait for its next byte
+///
+/// Far longer than any USB latency timer or the time a frame takes on the
+/// wire at 4800 baud. Yaesu's fixed five-byte binary frames have no marker to
+/// resynchronise on, so a stale partial shifts every later frame and is
+/// dropped sooner.
+pub fn partial_frame_timeout(protocol: Protocol) -> Duration {
+    match protocol {
+        Protocol::Yaesu => Duration::from_millis(300),
+        Protocol::Kenwood
+        | Protocol::Elecraft
+        | Protocol::FlexRadio
+        | Protocol::YaesuAscii
+        | Protocol::IcomCIV => Duration::from_secs(1),
+    }
+}
+
+/// Deadlines for partial frames stalled in the actor's codecs
+#[derive(Debug, Default)]
+pub struct PartialFrameTimer {
+    /// When each codec last received bytes, and its timeout
+    partial: HashMap<FrameSource, (Instant, Duration)>,
+}
+
+impl PartialFrameTimer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record how much a codec holds after a read
+    ///
+    /// Each read that leaves a partial frame restarts its timeout.
+    pub fn note(&mut self, source: FrameSource, buffered: usize, timeout: Duration, now: Instant) {
+        if buffered == 0 {
+            self.partial.remove(&source);
+        } else {
+            self.partial.insert(source, (now, timeout));
+        }
+    }
+
+    /// Stop tracking a codec (disconnected or replaced)
+    pub fn forget(&mut self, source: FrameSource) {
+        self.partial.remove(&source);
+    }
+
+    /// When the next stalled frame is due to be discarded
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.partial
+            .values()
+            .map(|(last, timeout)| *last + *timeout)
+            .min()
+    }
+
+    /// Codecs whose partial frame has timed out, with how long each stalled;
+    /// they are no longer tracked
+    pub fn expired(&mut self, now: Instant) -> Vec<(FrameSource, Duration)> {
+        let expired: Vec<_> = self
+            .partial
+            .iter()
+            .filter(|(_, (last, timeout))| now >= *last + *timeout)
+            .map(|(source, (last, _))| (*source, now.saturating_duration_since(*last)))
+            .collect();
+        for (source, _) in &expired {
+            self.partial.remove(source);
+        }
+        expired
+    }
+}
+
+/// Position of `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() || needle.len() > haystack.len() {
+        return None;
+    }
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Length of the end of a frame completed by the start of this read
+fn tail_len(data: &[u8], frame: &[u8]) -> usize {
+    (1..=frame.len().min(data.len()))
+        .rev()
+        .find(|&k| data.starts_with(&frame[frame.len() - k..]))
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::{Protocol, RadioSideCodec};
+
+    /// Feed reads through a codec and the tracker, as the mux actor does
+    fn replay(protocol: Protocol, reads: &[(&[u8], u64)]) -> ReassemblyStats {
+        let t0 = Instant::now();
+        let mut codec = RadioSideCodec::new(protocol);
+        let mut tracker = ReassemblyTracker::new();
+        for (data, at_ms) in reads {
+            codec.push_bytes(data);
+            let frames: Vec<_> = std::iter::from_fn(|| codec.next_response_with_bytes())
+                .map(|f| f.1)
+                .collect();
+            tracker.record(
+                data,
+                frames.iter().map(Vec::as_slice),
+                t0 + Duration::from_millis(*at_ms),
+            );
+        }
+        tracker.stats()
+    }
+
+    #[test]
+    fn test_whole_frames_are_not_split() {
+        let stats = replay(
+            Protocol::Kenwood,
+            &[(b"FA00014074000;MD2;", 0), (b"TX0;", 20)],
+        );
+        assert_eq!(stats.reads, 2);
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.split_frames, 0);
+        assert_eq!(stats.split_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_frame_split_across_latency_timer_reads() {
+        let stats = replay(
+            Protocol::Kenwood,
+            &[
+                (b"MD2;FA000140", 0),
+                (b"74", 16),
+                (b"000;FA0001407", 32),
+                (b"5000;", 48),
+            ],
+        );
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.split_frames, 2);
+        assert_eq!(stats.max_reads_per_frame, 3);
+        assert_eq!(stats.max_frame_span, Duration::from_millis(32));
+    }
+
+    #[test]
+    fn test_stalled_partial_frame_times_out() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let radio = FrameSource::Radio(RadioHandle(1));
+        let mut timer = PartialFrameTimer::new();
+
+        timer.note(radio, 5, ms(300), t0);
+        timer.note(FrameSource::Amplifier, 2, ms(1000), t0);
+        // More of the frame arrives: the clock restarts
+        timer.note(radio, 8, ms(300), t0 + ms(200));
+        assert_eq!(timer.next_deadline(), Some(t0 + ms(500)));
+        assert!(timer.expired(t0 + ms(400)).is_empty());
+        assert_eq!(timer.expired(t0 + ms(500)), vec![(radio, ms(300))]);
+
+        // The amp's frame completed before its deadline
+        timer.note(FrameSource::Amplifier, 0, ms(1000), t0 + ms(600));
+        assert_eq!(timer.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_one_byte_reads() {
+        let frame = [
+            0xFE, 0xFE, 0xE0, 0x94, 0x03, 0x00, 0x40, 0x07, 0x14, 0x00, 0xFD,
+        ];
+        let reads: Vec<(&[u8], u64)> = frame.chunks(1).map(|b| (b, 1)).collect();
+        let stats = replay(Protocol::IcomCIV, &reads);
+        assert_eq!(stats.frames, 1);
+        assert_eq!(stats.split_frames, 1);
+        assert_eq!(stats.max_reads_per_frame, frame.len() as u32);
+    }
+}
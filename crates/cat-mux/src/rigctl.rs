@@ -0,0 +1,525 @@
+//! Hamlib NET rigctl server
+//!
+//! Loggers and digital-mode programs that speak Hamlib's network protocol
+//! (rig model 2, "Hamlib NET rigctl") can connect to [`run_rigctl_server`]
+//! instead of a serial port. Every command acts on whichever radio is
+//! active: `f` reads its frequency, `F` retunes it, `T 1` keys it through
+//! the PTT sequencer. Both the short (`f`) and long (`\get_freq`) command
+//! names are understood, as is the `+` prefix for extended responses.
+
+use std::fmt::Write as _;
+use std::io;
+use std::time::Duration;
+
+use cat_protocol::{Frequency, OperatingMode, RadioRequest};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tracing::{debug, info};
+
+use crate::client::{ActiveRadio, MuxClient};
+
+/// Default listen address (4532 is rigctld's port)
+pub const DEFAULT_RIGCTL_ADDR: &str = "127.0.0.1:4532";
+
+/// Longest command line accepted
+const MAX_LINE_BYTES: usize = 1024;
+
+/// Longest a client may stay silent before it is dropped
+const IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Hamlib return codes
+const RIG_OK: i32 = 0;
+const RIG_EINVAL: i32 = -1;
+const RIG_ENIMPL: i32 = -4;
+const RIG_ENAVAIL: i32 = -11;
+
+/// Hamlib mode bits for the modes Catapult reports (AM, CW, USB, LSB, RTTY,
+/// FM, CWR, RTTYR, PKTLSB, PKTUSB, PKTFM)
+const MODE_MASK: u32 = 0x1DBF;
+
+/// Hamlib mode names offered by `M ?`
+const MODE_NAMES: &str = "AM CW USB LSB RTTY FM CWR RTTYR PKTLSB PKTUSB PKTFM";
+
+/// One parsed command
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    GetFreq,
+    SetFreq(Frequency),
+    GetMode,
+    SetMode(OperatingMode),
+    ListModes,
+    GetPtt,
+    SetPtt(bool),
+    GetVfo,
+    SetVfo(String),
+    GetSplitVfo,
+    SetSplitVfo(bool),
+    GetInfo,
+    GetPowerstat,
+    ChkVfo,
+    DumpState,
+    Quit,
+}
+
+impl Command {
+    /// Long name, used in extended responses
+    fn name(&self) -> &'static str {
+        match self {
+            Self::GetFreq => "get_freq",
+            Self::SetFreq(_) => "set_freq",
+            Self::GetMode => "get_mode",
+            Self::SetMode(_) | Self::ListModes => "set_mode",
+            Self::GetPtt => "get_ptt",
+            Self::SetPtt(_) => "set_ptt",
+            Self::GetVfo => "get_vfo",
+            Self::SetVfo(_) => "set_vfo",
+            Self::GetSplitVfo => "get_split_vfo",
+            Self::SetSplitVfo(_) => "set_split_vfo",
+            Self::GetInfo => "get_info",
+            Self::GetPowerstat => "get_powerstat",
+            Self::ChkVfo => "chk_vfo",
+            Self::DumpState => "dump_state",
+            Self::Quit => "quit",
+        }
+    }
+}
+
+/// A command line that can't be run, as a Hamlib return code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Rejected(i32);
+
+/// Parse one line into a command and whether an extended response was asked for
+fn parse_line(line: &str) -> Result<(Command, bool), Rejected> {
+    let line = line.trim();
+    let (line, extended) = match line.strip_prefix('+') {
+        Some(rest) => (rest.trim_start(), true),
+        None => (line, false),
+    };
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or(Rejected(RIG_EINVAL))?;
+    let args: Vec<&str> = words.collect();
+    let arg = |i: usize| args.get(i).copied().ok_or(Rejected(RIG_EINVAL));
+
+    let command = match name {
+        "f" | "\\get_freq" => Command::GetFreq,
+        "F" | "\\set_freq" => {
+            let hz: f64 = arg(0)?.parse().map_err(|_| Rejected(RIG_EINVAL))?;
+            if hz <= 0.0 {
+                return Err(Rejected(RIG_EINVAL));
+            }
+            Command::SetFreq(Frequency::from_hz_f64(hz).ok_or(Rejected(RIG_EINVAL))?)
+        }
+        "m" | "\\get_mode" => Command::GetMode,
+        "M" | "\\set_mode" => match arg(0)? {
+            "?" => Command::ListModes,
+            mode => Command::SetMode(parse_mode(mode).ok_or(Rejected(RIG_EINVAL))?),
+        },
+        "t" | "\\get_ptt" => Command::GetPtt,
+        "T" | "\\set_ptt" => {
+            let ptt: u8 = arg(0)?.parse().map_err(|_| Rejected(RIG_EINVAL))?;
+            // 1 is PTT, 2 and 3 are PTT from the mic or data jack
+            Command::SetPtt(ptt != 0)
+        }
+        "v" | "\\get_vfo" => Command::GetVfo,
+        "V" | "\\set_vfo" => Command::SetVfo(arg(0)?.to_string()),
+        "s" | "\\get_split_vfo" => Command::GetSplitVfo,
+        "S" | "\\set_split_vfo" => Command::SetSplitVfo(arg(0)? != "0"),
+        "_" | "\\get_info" => Command::GetInfo,
+        "\\get_powerstat" => Command::GetPowerstat,
+        "\\chk_vfo" => Command::ChkVfo,
+        "\\dump_state" => Command::DumpState,
+        "q" | "Q" | "\\quit" => Command::Quit,
+        _ => return Err(Rejected(RIG_ENIMPL)),
+    };
+    Ok((command, extended))
+}
+
+/// Hamlib name for a mode
+fn mode_name(mode: OperatingMode) -> &'static str {
+    match mode {
+        OperatingMode::Lsb => "LSB",
+        OperatingMode::Usb => "USB",
+        OperatingMode::Cw => "CW",
+        OperatingMode::CwR => "CWR",
+        OperatingMode::Am => "AM",
+        OperatingMode::Fm | OperatingMode::FmN => "FM",
+        OperatingMode::Dig | OperatingMode::DigU | OperatingMode::Data | OperatingMode::DataU => {
+            "PKTUSB"
+        }
+        OperatingMode::DigL | OperatingMode::DataL => "PKTLSB",
+        OperatingMode::Pkt => "PKTFM",
+        OperatingMode::Rtty => "RTTY",
+        OperatingMode::RttyR => "RTTYR",
+    }
+}
+
+/// Mode for a Hamlib mode name
+fn parse_mode(name: &str) -> Option<OperatingMode> {
+    Some(match name.to_ascii_uppercase().as_str() {
+        "LSB" => OperatingMode::Lsb,
+        "USB" => OperatingMode::Usb,
+        "CW" => OperatingMode::Cw,
+        "CWR" => OperatingMode::CwR,
+        "AM" => OperatingMode::Am,
+        "FM" => OperatingMode::Fm,
+        "PKTUSB" => OperatingMode::DataU,
+        "PKTLSB" => OperatingMode::DataL,
+        "PKTFM" => OperatingMode::Pkt,
+        "RTTY" => OperatingMode::Rtty,
+        "RTTYR" => OperatingMode::RttyR,
+        _ => return None,
+    })
+}
+
+/// Nominal passband reported with a mode, in Hz
+fn passband(mode: OperatingMode) -> u32 {
+    match mode {
+        OperatingMode::Cw | OperatingMode::CwR | OperatingMode::Rtty | OperatingMode::RttyR => 500,
+        OperatingMode::Am => 6000,
+        OperatingMode::Fm | OperatingMode::Pkt => 15000,
+        OperatingMode::FmN => 9000,
+        OperatingMode::Lsb | OperatingMode::Usb => 2400,
+        _ => 3000,
+    }
+}
+
+/// `\dump_state` reply in protocol version 0, which every Hamlib release reads
+///
+/// Describes a generic HF/6m transceiver: the real limits belong to whichever
+/// radio is active at the time.
+fn dump_state() -> String {
+    let mut out = String::new();
+    let lines = [
+        "0".to_string(), // protocol version
+        "2".to_string(), // rig model (NET rigctl)
+        "2".to_string(), // ITU region
+        // RX range: start end modes low_power high_power vfo ant
+        format!("100000.000000 60000000.000000 {MODE_MASK:#x} -1 -1 0x3 0x1"),
+        "0 0 0 0 0 0 0".to_string(),
+        // TX range (power in mW)
+        format!("1800000.000000 54000000.000000 {MODE_MASK:#x} 1000 100000 0x3 0x1"),
+        "0 0 0 0 0 0 0".to_string(),
+        // Tuning steps: modes step
+        format!("{MODE_MASK:#x} 1"),
+        "0 0".to_string(),
+        // Filters: modes width
+        "0xc 2400".to_string(),
+        "0x192 500".to_string(),
+        "0x1 6000".to_string(),
+        "0x1020 15000".to_string(),
+        "0xc00 3000".to_string(),
+        "0 0".to_string(),
+        "0".to_string(),   // max RIT
+        "0".to_string(),   // max XIT
+        "0".to_string(),   // max IF shift
+        "0".to_string(),   // announces
+        String::new(),     // preamps
+        String::new(),     // attenuators
+        "0x0".to_string(), // has_get_func
+        "0x0".to_string(), // has_set_func
+        "0x0".to_string(), // has_get_level
+        "0x0".to_string(), // has_set_level
+        "0x0".to_string(), // has_get_parm
+        "0x0".to_string(), // has_set_parm
+    ];
+    for line in lines {
+        let _ = writeln!(out, "{}", line);
+    }
+    out
+}
+
+/// Run a command; the reply is labeled values, or a return code
+async fn execute(
+    command: &Command,
+    radio: &impl ActiveRadio,
+) -> Result<Vec<(&'static str, String)>, Rejected> {
+    let set = |ok: bool| {
+        if ok {
+            Ok(Vec::new())
+        } else {
+            Err(Rejected(RIG_ENAVAIL))
+        }
+    };
+    match command {
+        Command::GetFreq => {
+            let (_, state) = radio.state().await.ok_or(Rejected(RIG_ENAVAIL))?;
+            let hz = state.frequency_hz.ok_or(Rejected(RIG_ENAVAIL))?;
+            Ok(vec![("Frequency", hz.hz().to_string())])
+        }
+        Command::SetFreq(hz) => set(radio.control(RadioRequest::SetFrequency { hz: *hz }).await),
+        Command::GetMode => {
+            let (_, state) = radio.state().await.ok_or(Rejected(RIG_ENAVAIL))?;
+            let mode = state.mode.ok_or(Rejected(RIG_ENAVAIL))?;
+            Ok(vec![
+                ("Mode", mode_name(mode).to_string()),
+                ("Passband", passband(mode).to_string()),
+            ])
+        }
+        Command::SetMode(mode) => set(radio.control(RadioRequest::SetMode { mode: *mode }).await),
+        Command::ListModes => Ok(vec![("Modes", MODE_NAMES.to_string())]),
+        Command::GetPtt => {
+            let (_, state) = radio.state().await.ok_or(Rejected(RIG_ENAVAIL))?;
+            Ok(vec![("PTT", u8::from(state.ptt).to_string())])
+        }
+        Command::SetPtt(active) => set(radio
+            .control(RadioRequest::SetPtt { active: *active })
+            .await),
+        Command::GetVfo => Ok(vec![("VFO", "VFOA".to_string())]),
+        // Catapult follows the active radio's operating VFO, whichever it is
+        Command::SetVfo(vfo) => match vfo.as_str() {
+            "VFOA" | "currVFO" | "Main" | "VFO" => Ok(Vec::new()),
+            _ => Err(Rejected(RIG_EINVAL)),
+        },
+        Command::GetSplitVfo => Ok(vec![
+            ("Split", "0".to_string()),
+            ("TX VFO", "VFOA".to_string()),
+        ]),
+        Command::SetSplitVfo(split) => {
+            if *split {
+                Err(Rejected(RIG_ENIMPL))
+            } else {
+                Ok(Vec::new())
+            }
+        }
+        Command::GetInfo => Ok(vec![("Info", "Catapult".to_string())]),
+        Command::GetPowerstat => Ok(vec![("Power Status", "1".to_string())]),
+        Command::ChkVfo => Ok(vec![("ChkVFO", "0".to_string())]),
+        Command::DumpState | Command::Quit => Ok(Vec::new()),
+    }
+}
+
+/// Answer one command line
+///
+/// Plain replies are the bare values, one per line, or `RPRT <code>` for
+/// commands that return nothing. Extended replies echo the command and label
+/// every value, then always end in `RPRT <code>`.
+async fn respond(line: &str, radio: &impl ActiveRadio) -> (String, bool) {
+    let (command, extended) = match parse_line(line) {
+        Ok(parsed) => parsed,
+        Err(Rejected(code)) => return (format!("RPRT {}\n", code), false),
+    };
+    if command == Command::Quit {
+        return (String::new(), true);
+    }
+    if command == Command::DumpState {
+        return (dump_state(), false);
+    }
+
+    let result = execute(&command, radio).await;
+    let mut out = String::new();
+    if extended {
+        let args: Vec<&str> = line.split_whitespace().skip(1).collect();
+        let _ = writeln!(out, "{}: {}", command.name(), args.join(" "));
+        let code = match result {
+            Ok(values) => {
+                for (label, value) in values {
+                    let _ = writeln!(out, "{}: {}", label, value);
+                }
+                RIG_OK
+            }
+            Err(Rejected(code)) => code,
+        };
+        let _ = writeln!(out, "RPRT {}", code);
+    } else {
+        match result {
+            Ok(values) if values.is_empty() => {
+                let _ = writeln!(out, "RPRT {}", RIG_OK);
+            }
+            Ok(values) => {
+                for (_, value) in values {
+                    let _ = writeln!(out, "{}", value);
+                }
+            }
+            Err(Rejected(code)) => {
+                let _ = writeln!(out, "RPRT {}", code);
+            }
+        }
+    }
+    (out, false)
+}
+
+/// Serve one client until it quits, disconnects or goes idle
+async fn serve_client(socket: TcpStream, mux: MuxClient) -> io::Result<()> {
+    let (read, mut write) = socket.into_split();
+    let mut reader = BufReader::new(read);
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let n = tokio::time::timeout(
+            IDLE_TIMEOUT,
+            (&mut reader)
+                .take(MAX_LINE_BYTES as u64)
+                .read_line(&mut line),
+        )
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "client idle"))??;
+        if n == 0 {
+            return Ok(());
+        }
+        if !line.ends_with('\n') && n >= MAX_LINE_BYTES {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "line too long"));
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let (reply, quit) = respond(&line, &mux).await;
+        if quit {
+            return Ok(());
+        }
+        write.write_all(reply.as_bytes()).await?;
+    }
+}
+
+/// Accept rigctl clients on `listener` until an accept fails
+///
+/// Each client is served concurrently; dropping the returned future
+/// disconnects them all.
+pub async fn run_rigctl_server(listener: TcpListener, mux: MuxClient) -> io::Result<()> {
+    info!("Serving Hamlib rigctl on {}", listener.local_addr()?);
+    let mut clients = JoinSet::new();
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (socket, peer) = accepted?;
+                info!("rigctl client {} connected", peer);
+                let mux = mux.clone();
+                clients.spawn(async move {
+                    match serve_client(socket, mux).await {
+                        Ok(()) => info!("rigctl client {} disconnected", peer),
+                        Err(e) => debug!("rigctl client {} dropped: {}", peer, e),
+                    }
+                });
+            }
+            // Reap finished clients so the set doesn't grow
+            Some(_) = clients.join_next(), if !clients.is_empty() => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::{MuxActorCommand, RadioStateSummary};
+    use crate::state::RadioHandle;
+    use std::sync::Mutex;
+
+    /// Active radio stand-in that records what it was asked to do
+    struct FakeRadio {
+        state: Option<RadioStateSummary>,
+        sent: Mutex<Vec<RadioRequest>>,
+    }
+
+    impl FakeRadio {
+        fn new(state: Option<RadioStateSummary>) -> Self {
+            Self {
+                state,
+                sent: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl ActiveRadio for FakeRadio {
+        async fn state(&self) -> Option<(RadioHandle, RadioStateSummary)> {
+            self.state.clone().map(|s| (RadioHandle(1), s))
+        }
+
+        async fn control(&self, request: RadioRequest) -> bool {
+            self.sent.lock().unwrap().push(request);
+            self.state.is_some()
+        }
+    }
+
+    fn on_20m() -> RadioStateSummary {
+        RadioStateSummary {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: Some(OperatingMode::DataU),
+            ptt: false,
+            power_watts: None,
+            last_seen: None,
+        }
+    }
+
+    #[test]
+    fn test_parse_short_and_long_names() {
+        assert_eq!(parse_line("f\n"), Ok((Command::GetFreq, false)));
+        assert_eq!(parse_line("+\\get_freq"), Ok((Command::GetFreq, true)));
+        assert_eq!(
+            parse_line("F 14074000.000000"),
+            Ok((Command::SetFreq(Frequency::from_hz(14_074_000)), false))
+        );
+        assert_eq!(
+            parse_line("\\set_mode PKTUSB 3000"),
+            Ok((Command::SetMode(OperatingMode::DataU), false))
+        );
+        assert_eq!(parse_line("T 2"), Ok((Command::SetPtt(true), false)));
+        assert_eq!(parse_line("F abc"), Err(Rejected(RIG_EINVAL)));
+        assert_eq!(parse_line("M WFM 0"), Err(Rejected(RIG_EINVAL)));
+        assert_eq!(parse_line("\\get_level"), Err(Rejected(RIG_ENIMPL)));
+    }
+
+    #[tokio::test]
+    async fn test_replies() {
+        let radio = FakeRadio::new(Some(on_20m()));
+        assert_eq!(respond("f", &radio).await.0, "14074000\n");
+        assert_eq!(respond("m", &radio).await.0, "PKTUSB\n3000\n");
+        assert_eq!(respond("t", &radio).await.0, "0\n");
+        assert_eq!(
+            respond("+f", &radio).await.0,
+            "get_freq: \nFrequency: 14074000\nRPRT 0\n"
+        );
+        assert_eq!(respond("q", &radio).await, (String::new(), true));
+
+        assert_eq!(respond("F 7074000", &radio).await.0, "RPRT 0\n");
+        assert_eq!(respond("T 1", &radio).await.0, "RPRT 0\n");
+        assert_eq!(
+            *radio.sent.lock().unwrap(),
+            vec![
+                RadioRequest::SetFrequency {
+                    hz: Frequency::from_hz(7_074_000)
+                },
+                RadioRequest::SetPtt { active: true },
+            ]
+        );
+
+        let dump = respond("\\dump_state", &radio).await.0;
+        assert!(dump.starts_with("0\n2\n2\n"));
+        assert_eq!(dump.lines().count(), 27);
+    }
+
+    #[tokio::test]
+    async fn test_no_active_radio() {
+        let radio = FakeRadio::new(None);
+        assert_eq!(respond("f", &radio).await.0, "RPRT -11\n");
+        assert_eq!(respond("F 7074000", &radio).await.0, "RPRT -11\n");
+        assert_eq!(
+            respond("+\\set_ptt 1", &radio).await.0,
+            "set_ptt: 1\nRPRT -11\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_answers_over_tcp() {
+        let (mux, mut cmd_rx, _events) = MuxClient::channel(8);
+        // Stands in for the mux actor
+        tokio::spawn(async move {
+            while let Some(cmd) = cmd_rx.recv().await {
+                if let MuxActorCommand::QueryActiveRadio { response } = cmd {
+                    let _ = response.send(Some((RadioHandle(1), on_20m())));
+                }
+            }
+        });
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(run_rigctl_server(listener, mux));
+
+        let mut client = TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"f\nv\nq\n").await.unwrap();
+        let mut reply = String::new();
+        client.read_to_string(&mut reply).await.unwrap();
+        assert_eq!(reply, "14074000\nVFOA\n");
+    }
+}
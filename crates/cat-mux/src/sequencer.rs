@@ -0,0 +1,338 @@
+//! PTT sequencer
+//!
+//! Orders amplifier keying around radio transmit so the amp's relays are
+//! settled before RF arrives and stay closed until it has gone:
+//!
+//! ```text
+//! key:    amp ON -> (lead) -> radio TX
+//! unkey:  radio RX -> (tail) -> amp OFF
+//! ```
+//!
+//! Keying requested through the mux is fully gated: the radio is only told to
+//! transmit once the lead time has elapsed. When the operator keys the radio
+//! locally (foot switch, mic PTT) it is already transmitting by the time the
+//! mux hears about it, so only the amp keying and the tail delay apply.
+//!
+//! The sequencer is a pure state machine driven by explicit timestamps; the
+//! mux actor executes the returned [`SequencerAction`]s and calls
+//! [`PttSequencer::poll`] when [`PttSequencer::next_deadline`] passes.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// Sequencer timing (both zero = disabled, PTT is mirrored straight through)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SequencerConfig {
+    /// Delay between keying the amplifier and keying the radio (ms)
+    pub lead_ms: u64,
+    /// Delay between unkeying the radio and unkeying the amplifier (ms)
+    pub tail_ms: u64,
+}
+
+impl SequencerConfig {
+    /// Whether the sequencer should gate PTT at all
+    pub fn is_enabled(&self) -> bool {
+        self.lead_ms > 0 || self.tail_ms > 0
+    }
+
+    fn lead(&self) -> Duration {
+        Duration::from_millis(self.lead_ms)
+    }
+
+    fn tail(&self) -> Duration {
+        Duration::from_millis(self.tail_ms)
+    }
+}
+
+/// Something the mux must do on the sequencer's behalf
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequencerAction {
+    /// Key or unkey the amplifier
+    KeyAmp(bool),
+    /// Key or unkey a radio
+    KeyRadio {
+        /// Radio to key
+        handle: RadioHandle,
+        /// True to transmit
+        active: bool,
+    },
+}
+
+/// Where the sequencer is in a transmit cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Phase {
+    /// Amp and radio unkeyed
+    Idle,
+    /// Amp keyed, waiting for the lead time before keying the radio
+    Lead { handle: RadioHandle, until: Instant },
+    /// Amp and radio keyed
+    Transmit { handle: RadioHandle },
+    /// Radio unkeyed, amp held until the tail time passes
+    Tail { until: Instant },
+}
+
+/// PTT sequencer state machine
+#[derive(Debug)]
+pub struct PttSequencer {
+    config: SequencerConfig,
+    phase: Phase,
+}
+
+impl Default for PttSequencer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PttSequencer {
+    /// Create a disabled sequencer
+    pub fn new() -> Self {
+        Self {
+            config: SequencerConfig::default(),
+            phase: Phase::Idle,
+        }
+    }
+
+    /// Current timing
+    pub fn config(&self) -> SequencerConfig {
+        self.config
+    }
+
+    /// Change timing; takes effect from the next transition
+    pub fn set_config(&mut self, config: SequencerConfig) {
+        self.config = config;
+    }
+
+    /// Forget any transmit cycle in progress
+    pub fn reset(&mut self) {
+        self.phase = Phase::Idle;
+    }
+
+    /// Whether the sequencer gates PTT
+    pub fn is_enabled(&self) -> bool {
+        self.config.is_enabled()
+    }
+
+    /// Whether the sequencer currently holds the amplifier keyed
+    pub fn amp_keyed(&self) -> bool {
+        !matches!(self.phase, Phase::Idle)
+    }
+
+    /// The radio being keyed, or waiting out the lead time to be
+    pub fn keying(&self) -> Option<RadioHandle> {
+        match self.phase {
+            Phase::Lead { handle, .. } | Phase::Transmit { handle } => Some(handle),
+            Phase::Idle | Phase::Tail { .. } => None,
+        }
+    }
+
+    /// When [`poll`](Self::poll) next needs to run, if a delay is pending
+    pub fn next_deadline(&self) -> Option<Instant> {
+        match self.phase {
+            Phase::Lead { until, .. } | Phase::Tail { until } => Some(until),
+            Phase::Idle | Phase::Transmit { .. } => None,
+        }
+    }
+
+    /// Key or unkey a radio through the sequencer
+    pub fn request(
+        &mut self,
+        handle: RadioHandle,
+        active: bool,
+        now: Instant,
+    ) -> Vec<SequencerAction> {
+        let key_radio = |active| SequencerAction::KeyRadio { handle, active };
+
+        match (self.phase, active) {
+            (Phase::Idle, true) => {
+                if self.config.lead_ms == 0 {
+                    self.phase = Phase::Transmit { handle };
+                    vec![SequencerAction::KeyAmp(true), key_radio(true)]
+                } else {
+                    self.phase = Phase::Lead {
+                        handle,
+                        until: now + self.config.lead(),
+                    };
+                    vec![SequencerAction::KeyAmp(true)]
+                }
+            }
+            // Amp is still held from the last transmission, no lead needed
+            (Phase::Tail { .. }, true) => {
+                self.phase = Phase::Transmit { handle };
+                vec![key_radio(true)]
+            }
+            // Released before the radio was ever keyed
+            (Phase::Lead { .. }, false) => {
+                self.phase = Phase::Idle;
+                vec![SequencerAction::KeyAmp(false)]
+            }
+            (Phase::Transmit { handle: keyed }, false) => {
+                let mut actions = vec![SequencerAction::KeyRadio {
+                    handle: keyed,
+                    active: false,
+                }];
+                actions.extend(self.release(now));
+                actions
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// A radio reported its own PTT state (keyed locally or echoing a request)
+    pub fn on_radio_ptt(
+        &mut self,
+        handle: RadioHandle,
+        active: bool,
+        now: Instant,
+    ) -> Vec<SequencerAction> {
+        match (self.phase, active) {
+            // Keyed at the radio: too late for a lead, just key the amp
+            (Phase::Idle, true) => {
+                self.phase = Phase::Transmit { handle };
+                vec![SequencerAction::KeyAmp(true)]
+            }
+            (Phase::Lead { .. } | Phase::Tail { .. }, true) => {
+                self.phase = Phase::Transmit { handle };
+                Vec::new()
+            }
+            (Phase::Transmit { handle: keyed }, false) if keyed == handle => self.release(now),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Advance past any expired delay
+    pub fn poll(&mut self, now: Instant) -> Vec<SequencerAction> {
+        match self.phase {
+            Phase::Lead { handle, until } if now >= until => {
+                self.phase = Phase::Transmit { handle };
+                vec![SequencerAction::KeyRadio {
+                    handle,
+                    active: true,
+                }]
+            }
+            Phase::Tail { until } if now >= until => {
+                self.phase = Phase::Idle;
+                vec![SequencerAction::KeyAmp(false)]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Drop the amp now or after the tail delay
+    fn release(&mut self, now: Instant) -> Vec<SequencerAction> {
+        if self.config.tail_ms == 0 {
+            self.phase = Phase::Idle;
+            vec![SequencerAction::KeyAmp(false)]
+        } else {
+            self.phase = Phase::Tail {
+                until: now + self.config.tail(),
+            };
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequencer(lead_ms: u64, tail_ms: u64) -> PttSequencer {
+        let mut seq = PttSequencer::new();
+        seq.set_config(SequencerConfig { lead_ms, tail_ms });
+        seq
+    }
+
+    #[test]
+    fn test_request_waits_for_lead_and_tail() {
+        let mut seq = sequencer(50, 100);
+        let h = RadioHandle(1);
+        let t0 = Instant::now();
+
+        assert_eq!(
+            seq.request(h, true, t0),
+            vec![SequencerAction::KeyAmp(true)]
+        );
+        assert_eq!(seq.next_deadline(), Some(t0 + Duration::from_millis(50)));
+
+        // Lead not yet elapsed
+        assert!(seq.poll(t0 + Duration::from_millis(10)).is_empty());
+        assert_eq!(
+            seq.poll(t0 + Duration::from_millis(50)),
+            vec![SequencerAction::KeyRadio {
+                handle: h,
+                active: true
+            }]
+        );
+
+        let t1 = t0 + Duration::from_millis(500);
+        assert_eq!(
+            seq.request(h, false, t1),
+            vec![SequencerAction::KeyRadio {
+                handle: h,
+                active: false
+            }]
+        );
+        assert!(seq.amp_keyed());
+        // Radio echoing its unkey must not cut the tail short
+        assert!(seq.on_radio_ptt(h, false, t1).is_empty());
+        assert_eq!(
+            seq.poll(t1 + Duration::from_millis(100)),
+            vec![SequencerAction::KeyAmp(false)]
+        );
+        assert!(!seq.amp_keyed());
+    }
+
+    #[test]
+    fn test_release_during_lead_never_keys_radio() {
+        let mut seq = sequencer(50, 100);
+        let h = RadioHandle(1);
+        let t0 = Instant::now();
+
+        seq.request(h, true, t0);
+        assert_eq!(
+            seq.request(h, false, t0 + Duration::from_millis(10)),
+            vec![SequencerAction::KeyAmp(false)]
+        );
+        assert!(seq.poll(t0 + Duration::from_millis(60)).is_empty());
+    }
+
+    #[test]
+    fn test_rekey_during_tail_skips_lead() {
+        let mut seq = sequencer(50, 100);
+        let h = RadioHandle(1);
+        let t0 = Instant::now();
+
+        seq.on_radio_ptt(h, true, t0);
+        seq.on_radio_ptt(h, false, t0);
+        assert_eq!(
+            seq.request(h, true, t0 + Duration::from_millis(20)),
+            vec![SequencerAction::KeyRadio {
+                handle: h,
+                active: true
+            }]
+        );
+        assert_eq!(seq.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_local_keying_keys_amp_immediately() {
+        let mut seq = sequencer(50, 0);
+        let h = RadioHandle(1);
+        let t0 = Instant::now();
+
+        assert_eq!(
+            seq.on_radio_ptt(h, true, t0),
+            vec![SequencerAction::KeyAmp(true)]
+        );
+        // Another radio unkeying doesn't release the amp
+        assert!(seq.on_radio_ptt(RadioHandle(2), false, t0).is_empty());
+        assert_eq!(
+            seq.on_radio_ptt(h, false, t0),
+            vec![SequencerAction::KeyAmp(false)]
+        );
+    }
+}
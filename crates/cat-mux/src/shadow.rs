@@ -0,0 +1,260 @@
+//! Shadow amplifier comparison
+//!
+//! While validating a new amplifier personality, a second "shadow" amp can be
+//! connected next to the real one. Every write to the primary amp is mirrored
+//! to the shadow, and what each amp sends back is collected in comparison
+//! windows: a window opens with the first write (or the first amp frame while
+//! idle) and closes once both amps have been quiet for [`SHADOW_SETTLE`], or
+//! when a new write arrives after the amps have already answered. Frames are
+//! compared as multisets so two amps that send the same queries in a different
+//! order still match.
+
+use std::time::{Duration, SystemTime};
+
+use tokio::time::Instant;
+
+/// Quiet time after which a comparison window is closed
+pub const SHADOW_SETTLE: Duration = Duration::from_millis(500);
+
+/// Which of the two compared amplifiers a frame came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShadowSide {
+    /// The amplifier the mux is actually driving
+    Primary,
+    /// The amplifier under validation
+    Shadow,
+}
+
+/// Running totals for a shadow comparison session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShadowStats {
+    /// Comparison windows closed so far
+    pub windows: u64,
+    /// Windows where the two amps behaved differently
+    pub mismatches: u64,
+}
+
+/// A comparison window in which the two amplifiers behaved differently
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShadowDiff {
+    /// When the window opened
+    pub timestamp: SystemTime,
+    /// Writes sent to both amps during the window (empty for idle polling)
+    pub stimulus: Vec<Vec<u8>>,
+    /// Frames only the primary amp sent
+    pub primary_only: Vec<Vec<u8>>,
+    /// Frames only the shadow amp sent
+    pub shadow_only: Vec<Vec<u8>>,
+}
+
+impl ShadowDiff {
+    /// One-line description for logs and reports
+    pub fn summary(&self) -> String {
+        let list = |frames: &[Vec<u8>]| {
+            if frames.is_empty() {
+                "-".to_string()
+            } else {
+                frames
+                    .iter()
+                    .map(|f| frame_text(f))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+        };
+        format!(
+            "after {}: primary only {}, shadow only {}",
+            if self.stimulus.is_empty() {
+                "idle".to_string()
+            } else {
+                list(&self.stimulus)
+            },
+            list(&self.primary_only),
+            list(&self.shadow_only)
+        )
+    }
+}
+
+/// Show a frame as text when it is printable ASCII, otherwise as hex
+pub fn frame_text(frame: &[u8]) -> String {
+    if frame.iter().all(|b| b.is_ascii_graphic()) {
+        String::from_utf8_lossy(frame).into_owned()
+    } else {
+        frame
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Collects primary and shadow amp frames per window and compares them
+#[derive(Debug, Default)]
+pub struct ShadowComparator {
+    opened: Option<SystemTime>,
+    stimulus: Vec<Vec<u8>>,
+    primary: Vec<Vec<u8>>,
+    shadow: Vec<Vec<u8>>,
+    deadline: Option<Instant>,
+    stats: ShadowStats,
+}
+
+impl ShadowComparator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Totals since the comparator was created or reset
+    pub fn stats(&self) -> ShadowStats {
+        self.stats
+    }
+
+    /// When the open window should be closed, if one is open
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Record bytes written to both amps
+    ///
+    /// Writes in a burst share a window; a write after the amps have answered
+    /// closes the current window first and returns its result.
+    pub fn on_stimulus(
+        &mut self,
+        data: Vec<u8>,
+        now: Instant,
+    ) -> Option<(ShadowStats, Option<ShadowDiff>)> {
+        let closed = if self.primary.is_empty() && self.shadow.is_empty() {
+            None
+        } else {
+            self.close()
+        };
+        self.open(now);
+        self.stimulus.push(data);
+        closed
+    }
+
+    /// Record a frame received from one of the amps
+    pub fn on_frame(&mut self, side: ShadowSide, data: Vec<u8>, now: Instant) {
+        self.open(now);
+        match side {
+            ShadowSide::Primary => self.primary.push(data),
+            ShadowSide::Shadow => self.shadow.push(data),
+        }
+    }
+
+    /// Close the window if its settle time has passed
+    pub fn poll(&mut self, now: Instant) -> Option<(ShadowStats, Option<ShadowDiff>)> {
+        match self.deadline {
+            Some(at) if now >= at => self.close(),
+            _ => None,
+        }
+    }
+
+    /// Drop the open window and all totals (new shadow connection)
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn open(&mut self, now: Instant) {
+        if self.opened.is_none() {
+            self.opened = Some(SystemTime::now());
+        }
+        self.deadline = Some(now + SHADOW_SETTLE);
+    }
+
+    /// Compare and clear the open window
+    fn close(&mut self) -> Option<(ShadowStats, Option<ShadowDiff>)> {
+        let timestamp = self.opened.take()?;
+        self.deadline = None;
+        let stimulus = std::mem::take(&mut self.stimulus);
+        let mut primary_only = std::mem::take(&mut self.primary);
+        let mut shadow_only = Vec::new();
+        for frame in std::mem::take(&mut self.shadow) {
+            match primary_only.iter().position(|f| *f == frame) {
+                Some(i) => {
+                    primary_only.remove(i);
+                }
+                None => shadow_only.push(frame),
+            }
+        }
+
+        self.stats.windows += 1;
+        let diff = if primary_only.is_empty() && shadow_only.is_empty() {
+            None
+        } else {
+            self.stats.mismatches += 1;
+            Some(ShadowDiff {
+                timestamp,
+                stimulus,
+                primary_only,
+                shadow_only,
+            })
+        };
+        Some((self.stats, diff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_window_in_any_order() {
+        let t0 = Instant::now();
+        let mut cmp = ShadowComparator::new();
+        assert!(cmp.on_stimulus(b"FA00014074000;".to_vec(), t0).is_none());
+        cmp.on_frame(ShadowSide::Primary, b"MD;".to_vec(), t0);
+        cmp.on_frame(ShadowSide::Primary, b"IF;".to_vec(), t0);
+        cmp.on_frame(ShadowSide::Shadow, b"IF;".to_vec(), t0);
+        cmp.on_frame(ShadowSide::Shadow, b"MD;".to_vec(), t0);
+
+        // Still settling
+        assert!(cmp.poll(t0 + SHADOW_SETTLE / 2).is_none());
+        let (stats, diff) = cmp.poll(t0 + SHADOW_SETTLE).unwrap();
+        assert_eq!(
+            stats,
+            ShadowStats {
+                windows: 1,
+                mismatches: 0
+            }
+        );
+        assert!(diff.is_none());
+        assert!(cmp.next_deadline().is_none());
+    }
+
+    #[test]
+    fn test_new_stimulus_closes_answered_window() {
+        let t0 = Instant::now();
+        let mut cmp = ShadowComparator::new();
+        cmp.on_stimulus(b"FA00014074000;".to_vec(), t0);
+        // A burst before any answer stays in one window
+        assert!(cmp.on_stimulus(b"MD2;".to_vec(), t0).is_none());
+        cmp.on_frame(ShadowSide::Primary, b"FA;".to_vec(), t0);
+        cmp.on_frame(ShadowSide::Shadow, b"IF;".to_vec(), t0);
+
+        let (stats, diff) = cmp.on_stimulus(b"TX;".to_vec(), t0).unwrap();
+        assert_eq!(
+            stats,
+            ShadowStats {
+                windows: 1,
+                mismatches: 1
+            }
+        );
+        let diff = diff.unwrap();
+        assert_eq!(
+            diff.stimulus,
+            vec![b"FA00014074000;".to_vec(), b"MD2;".to_vec()]
+        );
+        assert_eq!(diff.primary_only, vec![b"FA;".to_vec()]);
+        assert_eq!(diff.shadow_only, vec![b"IF;".to_vec()]);
+        assert_eq!(
+            diff.summary(),
+            "after FA00014074000; MD2;: primary only FA;, shadow only IF;"
+        );
+    }
+
+    #[test]
+    fn test_frame_text() {
+        assert_eq!(frame_text(b"FA;"), "FA;");
+        assert_eq!(frame_text(&[0xFE, 0xFE, 0x94, 0xFD]), "FE FE 94 FD");
+    }
+}
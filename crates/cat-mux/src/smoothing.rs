@@ -0,0 +1,294 @@
+//! Frequency-change smoothing for output sinks
+//!
+//! Spinning a VFO produces a report every few hertz. The amplifier and the
+//! band decoder only need to follow where the radio ends up, and hammering
+//! them with every intermediate frequency makes relays chatter and keeps the
+//! amp's tuner busy. A [`FrequencySmoother`] sits in front of each output and
+//! applies a [`SmoothingPolicy`]:
+//!
+//! - **Immediate**: every change goes out (the default)
+//! - **Trailing**: a change goes out once the frequency has been stable for
+//!   the window
+//! - **Sample**: the first change goes out at once, then at most one per
+//!   window, always ending on the latest frequency
+//!
+//! This is separate from the switching lockout (which decides *which* radio
+//! the outputs follow) and from burst coalescing (which merges one report
+//! burst into one write): smoothing limits how often a single radio's
+//! frequency is passed on. A frequency still held when the radio keys up is
+//! released first, so nothing transmits on the wrong band.
+
+use cat_protocol::Frequency;
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+/// How often frequency changes are passed on to an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SmoothingPolicy {
+    /// Forward every change
+    Immediate,
+    /// Forward once the frequency has held still this long
+    Trailing { ms: u64 },
+    /// Forward at most once per period (leading and trailing edge)
+    Sample { ms: u64 },
+}
+
+impl SmoothingPolicy {
+    /// The window, if the policy has one
+    pub fn window(&self) -> Option<Duration> {
+        match self {
+            Self::Immediate => None,
+            Self::Trailing { ms } | Self::Sample { ms } => Some(Duration::from_millis(*ms)),
+        }
+    }
+}
+
+impl fmt::Display for SmoothingPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Immediate => f.write_str("immediate"),
+            Self::Trailing { ms } => write!(f, "after {}ms stable", ms),
+            Self::Sample { ms } => write!(f, "every {}ms", ms),
+        }
+    }
+}
+
+/// An output frequency changes are smoothed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputSink {
+    /// The amplifier's frequency updates
+    Amplifier,
+    /// OTRSP band data for antenna switching
+    BandDecoder,
+}
+
+impl OutputSink {
+    /// Policy to suggest when smoothing is turned on for the output
+    ///
+    /// The amplifier should start retuning as soon as the radio moves, so it
+    /// is sampled. Antenna relays only care where the radio settles.
+    pub fn suggested_policy(&self) -> SmoothingPolicy {
+        match self {
+            Self::Amplifier => SmoothingPolicy::Sample { ms: 100 },
+            Self::BandDecoder => SmoothingPolicy::Trailing { ms: 250 },
+        }
+    }
+}
+
+/// Smoothing policy per output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FrequencySmoothing {
+    pub amplifier: SmoothingPolicy,
+    pub band_decoder: SmoothingPolicy,
+}
+
+/// Off: every output forwards immediately until smoothing is chosen
+impl Default for FrequencySmoothing {
+    fn default() -> Self {
+        Self::OFF
+    }
+}
+
+impl FrequencySmoothing {
+    /// Every output forwards immediately
+    pub const OFF: FrequencySmoothing = FrequencySmoothing {
+        amplifier: SmoothingPolicy::Immediate,
+        band_decoder: SmoothingPolicy::Immediate,
+    };
+}
+
+/// Applies a [`SmoothingPolicy`] to one radio's frequency for one output
+#[derive(Debug)]
+pub struct FrequencySmoother {
+    policy: SmoothingPolicy,
+    /// The frequency the output has been given
+    released: Option<Frequency>,
+    /// When it was given
+    released_at: Option<Instant>,
+    /// A newer frequency waiting for the window
+    pending: Option<Frequency>,
+    deadline: Option<Instant>,
+}
+
+impl FrequencySmoother {
+    pub fn new(policy: SmoothingPolicy) -> Self {
+        Self {
+            policy,
+            released: None,
+            released_at: None,
+            pending: None,
+            deadline: None,
+        }
+    }
+
+    /// Current policy
+    pub fn policy(&self) -> SmoothingPolicy {
+        self.policy
+    }
+
+    /// Change the policy; anything pending is released
+    pub fn set_policy(&mut self, policy: SmoothingPolicy, now: Instant) -> Option<Frequency> {
+        self.policy = policy;
+        let pending = self.pending.take();
+        self.deadline = None;
+        if let Some(hz) = pending {
+            self.release(hz, now);
+        }
+        pending
+    }
+
+    /// Offer a new frequency; true when it may be forwarded now
+    ///
+    /// The first frequency always goes straight out. When false the smoother
+    /// holds it and releases it from [`Self::poll`].
+    pub fn offer(&mut self, hz: Frequency, now: Instant) -> bool {
+        if self.pending == Some(hz) {
+            return false;
+        }
+        if self.released.is_none() {
+            // Nothing to chatter against yet
+            self.release(hz, now);
+            return true;
+        }
+        if self.released == Some(hz) {
+            // Spun away and back before anything went out
+            self.pending = None;
+            self.deadline = None;
+            return true;
+        }
+        match self.policy {
+            SmoothingPolicy::Immediate => {
+                self.release(hz, now);
+                true
+            }
+            SmoothingPolicy::Trailing { ms } => {
+                self.pending = Some(hz);
+                self.deadline = Some(now + Duration::from_millis(ms));
+                false
+            }
+            SmoothingPolicy::Sample { ms } => {
+                let window = Duration::from_millis(ms);
+                match self.released_at {
+                    Some(at) if now < at + window => {
+                        self.pending = Some(hz);
+                        self.deadline = Some(at + window);
+                        false
+                    }
+                    _ => {
+                        self.release(hz, now);
+                        true
+                    }
+                }
+            }
+        }
+    }
+
+    /// Record a frequency the output was given some other way (e.g. along
+    /// with a mode change), dropping anything pending
+    pub fn force(&mut self, hz: Frequency, now: Instant) {
+        self.release(hz, now);
+    }
+
+    fn release(&mut self, hz: Frequency, now: Instant) {
+        self.released = Some(hz);
+        self.released_at = Some(now);
+        self.pending = None;
+        self.deadline = None;
+    }
+
+    /// The frequency the output should have
+    pub fn released(&self) -> Option<Frequency> {
+        self.released
+    }
+
+    /// A held frequency, if any
+    pub fn pending(&self) -> Option<Frequency> {
+        self.pending
+    }
+
+    /// When the held frequency is due
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Release the held frequency if its window has passed
+    pub fn poll(&mut self, now: Instant) -> Option<Frequency> {
+        let hz = self
+            .pending
+            .filter(|_| self.deadline.is_some_and(|d| now >= d))?;
+        self.release(hz, now);
+        Some(hz)
+    }
+
+    /// Release the held frequency now, whatever the window
+    pub fn flush(&mut self, now: Instant) -> Option<Frequency> {
+        let hz = self.pending?;
+        self.release(hz, now);
+        Some(hz)
+    }
+
+    /// Forget everything (e.g. the output now follows another radio)
+    pub fn clear(&mut self) {
+        *self = Self::new(self.policy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_immediate_forwards_everything() {
+        let t0 = Instant::now();
+        let mut s = FrequencySmoother::new(SmoothingPolicy::Immediate);
+        assert!(s.offer(Frequency::from_hz(14_074_000), t0));
+        assert!(s.offer(Frequency::from_hz(14_074_010), t0));
+        assert_eq!(s.released(), Some(Frequency::from_hz(14_074_010)));
+        assert_eq!(s.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_trailing_waits_for_stability() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut s = FrequencySmoother::new(SmoothingPolicy::Trailing { ms: 200 });
+        assert!(s.offer(Frequency::from_hz(13_999_000), t0));
+        assert!(!s.offer(Frequency::from_hz(14_000_000), t0));
+        assert!(!s.offer(Frequency::from_hz(14_001_000), t0 + ms(100)));
+        // Each change restarts the window
+        assert_eq!(s.poll(t0 + ms(250)), None);
+        assert_eq!(s.next_deadline(), Some(t0 + ms(300)));
+        assert_eq!(s.poll(t0 + ms(300)), Some(Frequency::from_hz(14_001_000)));
+        assert_eq!(s.released(), Some(Frequency::from_hz(14_001_000)));
+
+        // Spinning away and back cancels the pending change
+        assert!(!s.offer(Frequency::from_hz(14_002_000), t0 + ms(400)));
+        assert!(s.offer(Frequency::from_hz(14_001_000), t0 + ms(450)));
+        assert_eq!(s.poll(t0 + ms(700)), None);
+    }
+
+    #[test]
+    fn test_sample_forwards_leading_edge_then_once_per_period() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let mut s = FrequencySmoother::new(SmoothingPolicy::Sample { ms: 100 });
+        assert!(s.offer(Frequency::from_hz(7_000_000), t0));
+        assert!(!s.offer(Frequency::from_hz(7_001_000), t0 + ms(20)));
+        assert!(!s.offer(Frequency::from_hz(7_002_000), t0 + ms(40)));
+        assert_eq!(s.next_deadline(), Some(t0 + ms(100)));
+        assert_eq!(s.poll(t0 + ms(100)), Some(Frequency::from_hz(7_002_000)));
+        // Quiet for a period: the next change goes straight out
+        assert!(s.offer(Frequency::from_hz(7_003_000), t0 + ms(250)));
+
+        // A policy change releases what is held
+        assert!(!s.offer(Frequency::from_hz(7_004_000), t0 + ms(260)));
+        assert_eq!(
+            s.set_policy(SmoothingPolicy::Immediate, t0 + ms(270)),
+            Some(Frequency::from_hz(7_004_000))
+        );
+    }
+}
@@ -1,12 +1,18 @@
 //! Radio state tracking
 
-use std::time::Instant;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
 
-use cat_protocol::{OperatingMode, Protocol, RadioModel};
+use cat_protocol::display::format_frequency;
+use cat_protocol::{Frequency, OperatingMode, Protocol, RadioModel, RadioResponse};
 use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::band_plan::band_name;
+use crate::translation::TranslationStrictness;
 
 /// Unique identifier for a radio in the multiplexer
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct RadioHandle(pub u32);
 
 impl RadioHandle {
@@ -30,19 +36,29 @@ pub struct RadioState {
     /// Identified radio model
     pub model: Option<RadioModel>,
     /// Current frequency in Hz
-    pub frequency_hz: Option<u64>,
+    pub frequency_hz: Option<Frequency>,
     /// Current operating mode
     pub mode: Option<OperatingMode>,
     /// PTT active
     pub ptt: bool,
+    /// Output power setting in watts (None until the radio reports it)
+    pub power_watts: Option<u16>,
+    /// Keyer speed in WPM (None until the radio reports it)
+    pub keyer_wpm: Option<u8>,
     /// CI-V address (for Icom)
     pub civ_address: Option<u8>,
     /// Last activity timestamp
     pub last_activity: Instant,
+    /// Wall-clock time the last frame was received (None until the first frame)
+    pub last_seen: Option<SystemTime>,
     /// Last frequency change timestamp
     pub last_freq_change: Option<Instant>,
     /// Whether this is a simulated radio
     pub is_simulated: bool,
+    /// Receive-only monitor channel, never made active
+    pub rx_only: bool,
+    /// Last mode used on each band, e.g. "20m" -> USB
+    pub band_modes: HashMap<&'static str, OperatingMode>,
 }
 
 impl RadioState {
@@ -57,10 +73,15 @@ impl RadioState {
             frequency_hz: None,
             mode: None,
             ptt: false,
+            power_watts: None,
+            keyer_wpm: None,
             civ_address: None,
             last_activity: Instant::now(),
+            last_seen: None,
             last_freq_change: None,
             is_simulated: false,
+            rx_only: false,
+            band_modes: HashMap::new(),
         }
     }
 
@@ -75,20 +96,33 @@ impl RadioState {
             frequency_hz: None,
             mode: None,
             ptt: false,
+            power_watts: None,
+            keyer_wpm: None,
             civ_address: None,
             last_activity: Instant::now(),
+            last_seen: None,
             last_freq_change: None,
             is_simulated: true,
+            rx_only: false,
+            band_modes: HashMap::new(),
         }
     }
 
     /// Update activity timestamp
     pub fn touch(&mut self) {
         self.last_activity = Instant::now();
+        self.last_seen = Some(SystemTime::now());
+    }
+
+    /// Whether no data has arrived from this radio for longer than `max_age`
+    ///
+    /// A radio that has been silent this long is likely powered off.
+    pub fn is_stale(&self, max_age: Duration) -> bool {
+        self.last_activity.elapsed() > max_age
     }
 
     /// Update frequency
-    pub fn set_frequency(&mut self, hz: u64) {
+    pub fn set_frequency(&mut self, hz: Frequency) {
         if self.frequency_hz != Some(hz) {
             self.frequency_hz = Some(hz);
             self.last_freq_change = Some(Instant::now());
@@ -102,19 +136,73 @@ impl RadioState {
         self.touch();
     }
 
+    /// Amateur band the radio is on, e.g. "20m"
+    pub fn band(&self) -> Option<&'static str> {
+        self.frequency_hz.and_then(band_name)
+    }
+
+    /// Note the current mode as the one last used on the current band
+    pub fn remember_band_mode(&mut self) {
+        if let (Some(band), Some(mode)) = (self.band(), self.mode) {
+            self.band_modes.insert(band, mode);
+        }
+    }
+
+    /// Mode last used on `band`, if the radio has been there
+    pub fn band_mode(&self, band: &str) -> Option<OperatingMode> {
+        self.band_modes.get(band).copied()
+    }
+
     /// Update PTT state
     pub fn set_ptt(&mut self, ptt: bool) {
         self.ptt = ptt;
         self.touch();
     }
 
+    /// Update output power setting
+    pub fn set_power(&mut self, watts: u16) {
+        self.power_watts = Some(watts);
+        self.touch();
+    }
+
+    /// Update keyer speed
+    pub fn set_keyer_speed(&mut self, wpm: u8) {
+        self.keyer_wpm = Some(wpm);
+        self.touch();
+    }
+
+    /// Whether `response` only repeats what is already known: a frequency,
+    /// mode, power or keyer speed equal to the current one
+    ///
+    /// PTT is never a repeat: keying, so a radio still transmitting when a
+    /// lockout ends can take over, and unkeying, which is resent on purpose
+    /// as a safety net. Nor is anything not tracked here (VFO, meters).
+    pub fn repeats(&self, response: &RadioResponse) -> bool {
+        match *response {
+            RadioResponse::Frequency { hz } => self.frequency_hz == Some(hz),
+            RadioResponse::Mode { mode } => self.mode == Some(mode),
+            RadioResponse::Ptt { .. } => false,
+            RadioResponse::TxPower { watts } => self.power_watts == Some(watts),
+            RadioResponse::KeyerSpeed { wpm } => self.keyer_wpm == Some(wpm),
+            RadioResponse::Status {
+                frequency_hz,
+                mode,
+                ptt,
+                ..
+            } => {
+                ptt.is_none()
+                    && (frequency_hz.is_some() || mode.is_some())
+                    && frequency_hz.is_none_or(|hz| self.frequency_hz == Some(hz))
+                    && mode.is_none_or(|m| self.mode == Some(m))
+            }
+            _ => false,
+        }
+    }
+
     /// Format frequency for display
     pub fn frequency_display(&self) -> String {
         match self.frequency_hz {
-            Some(hz) => {
-                let mhz = hz as f64 / 1_000_000.0;
-                format!("{:.3} MHz", mhz)
-            }
+            Some(hz) => format_frequency(hz),
             None => "---".to_string(),
         }
     }
@@ -160,6 +248,57 @@ impl SwitchingMode {
     }
 }
 
+/// What the mux believes the amplifier currently knows
+///
+/// The amp only learns about the radios through the mux: auto-info updates
+/// written to it and answers to its queries. The mux keeps this as it writes
+/// and answers from it, so it is also what the amp would be told if it asked
+/// now. Cleared when an amplifier connects or disconnects, since a new or
+/// power-cycled amp knows nothing yet.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AmplifierEmulatedState {
+    /// Frequency in Hz
+    pub frequency_hz: Option<Frequency>,
+    /// Operating mode
+    pub mode: Option<OperatingMode>,
+    /// Keyed (transmitting)
+    pub keyed: bool,
+    /// Control band (0=Main/A, 1=Sub/B) - which VFO has front panel control
+    pub control_band: Option<u8>,
+    /// Transmit band (0=Main/A, 1=Sub/B) - which VFO is selected for TX
+    pub tx_band: Option<u8>,
+    /// A switch is held off and the amp has been told to wait (see
+    /// [`crate::transition`])
+    #[serde(skip)]
+    pub transitional: bool,
+}
+
+impl AmplifierEmulatedState {
+    /// Amateur band of the frequency, e.g. "20m"
+    pub fn band(&self) -> Option<&'static str> {
+        self.frequency_hz.and_then(band_name)
+    }
+
+    /// One line, e.g. `14.074000 MHz USB (20m), RX`
+    pub fn summary(&self) -> String {
+        let mut out = match self.frequency_hz {
+            Some(hz) => format_frequency(hz),
+            None => "no frequency".to_string(),
+        };
+        if let Some(mode) = self.mode {
+            out.push_str(&format!(" {:?}", mode).to_uppercase());
+        }
+        if let Some(band) = self.band() {
+            out.push_str(&format!(" ({})", band));
+        }
+        out.push_str(if self.keyed { ", TX" } else { ", RX" });
+        if self.transitional {
+            out.push_str(", switching");
+        }
+        out
+    }
+}
+
 /// Amplifier output configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AmplifierConfig {
@@ -171,6 +310,25 @@ pub struct AmplifierConfig {
     pub baud_rate: u32,
     /// CI-V address (if using Icom)
     pub civ_address: Option<u8>,
+    /// Protocol to answer in, when the amp parses one protocol but expects
+    /// replies in another (`None` = same as `protocol`)
+    #[serde(default)]
+    pub response_protocol: Option<Protocol>,
+    /// Translation strictness for this amp (`None` = the mux-wide setting)
+    #[serde(default)]
+    pub strictness: Option<TranslationStrictness>,
+}
+
+impl AmplifierConfig {
+    /// Protocol the amp's own requests are parsed as
+    pub fn parse_protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    /// Protocol everything sent to the amp is encoded in
+    pub fn encode_protocol(&self) -> Protocol {
+        self.response_protocol.unwrap_or(self.protocol)
+    }
 }
 
 impl Default for AmplifierConfig {
@@ -180,6 +338,8 @@ impl Default for AmplifierConfig {
             protocol: Protocol::Kenwood,
             baud_rate: 38400,
             civ_address: None,
+            response_protocol: None,
+            strictness: None,
         }
     }
 }
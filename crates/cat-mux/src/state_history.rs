@@ -0,0 +1,400 @@
+//! The mux's state at a past moment
+//!
+//! A post-mortem ("why did the amp go to 20 m while I was on 40?") needs the
+//! state the mux was acting on at the time, not what it holds now. A
+//! [`StateHistory`] follows the [`MuxEvent`] stream and keeps each change
+//! that matters for switching (radios coming and going, their frequency, mode
+//! and PTT, the active radio, the switching mode and the audit record of each
+//! switch) with the time it was seen. Events arrive in the order the actor
+//! emitted them, so a change is never stamped later than traffic that came
+//! after it: an amp write is sent (and stamped) before the host sees it, and
+//! must show the change that caused it. Every [`SNAPSHOT_EVERY`] changes it
+//! takes a full snapshot, so [`StateHistory::at`] starts from the nearest
+//! snapshot and replays at most that many changes. The oldest snapshots go
+//! with their changes once [`STATE_HISTORY_CHANGES`] are held.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::SystemTime;
+
+use cat_protocol::{Frequency, OperatingMode};
+
+use crate::events::MuxEvent;
+use crate::state::{RadioHandle, SwitchingMode};
+use crate::switch_audit::SwitchRecord;
+
+/// Changes kept
+pub const STATE_HISTORY_CHANGES: usize = 100_000;
+
+/// Changes between snapshots
+pub const SNAPSHOT_EVERY: usize = 500;
+
+/// One radio as the mux knew it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RadioStateAt {
+    pub name: String,
+    pub connected: bool,
+    pub frequency_hz: Option<Frequency>,
+    pub mode: Option<OperatingMode>,
+    pub ptt: bool,
+}
+
+/// The mux's state at a moment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MuxStateAt {
+    pub active_radio: Option<RadioHandle>,
+    pub switching_mode: SwitchingMode,
+    /// Every radio seen so far, including disconnected ones
+    pub radios: HashMap<RadioHandle, RadioStateAt>,
+    /// The switch that made the active radio active
+    pub last_switch: Option<SwitchRecord>,
+    /// When the newest change it includes was seen (None = none yet)
+    pub as_of: Option<SystemTime>,
+}
+
+impl MuxStateAt {
+    /// No radios, in `switching_mode`
+    pub fn new(switching_mode: SwitchingMode) -> Self {
+        Self {
+            active_radio: None,
+            switching_mode,
+            radios: HashMap::new(),
+            last_switch: None,
+            as_of: None,
+        }
+    }
+
+    fn apply(&mut self, timestamp: SystemTime, change: &Change) {
+        self.as_of = Some(timestamp);
+        match change {
+            Change::Connected { handle, name } => {
+                let radio = self.radios.entry(*handle).or_default();
+                radio.name.clone_from(name);
+                radio.connected = true;
+            }
+            Change::Disconnected { handle } => {
+                if let Some(radio) = self.radios.get_mut(handle) {
+                    radio.connected = false;
+                    radio.ptt = false;
+                }
+            }
+            Change::Radio {
+                handle,
+                freq,
+                mode,
+                ptt,
+            } => {
+                let radio = self.radios.entry(*handle).or_default();
+                if freq.is_some() {
+                    radio.frequency_hz = *freq;
+                }
+                if mode.is_some() {
+                    radio.mode = *mode;
+                }
+                if let Some(ptt) = ptt {
+                    radio.ptt = *ptt;
+                }
+            }
+            Change::Active { to } => self.active_radio = Some(*to),
+            Change::SwitchingMode { mode } => self.switching_mode = *mode,
+            Change::Switched { record } => self.last_switch = Some((**record).clone()),
+        }
+    }
+}
+
+/// A change [`StateHistory`] keeps
+#[derive(Debug, Clone)]
+enum Change {
+    Connected {
+        handle: RadioHandle,
+        name: String,
+    },
+    Disconnected {
+        handle: RadioHandle,
+    },
+    Radio {
+        handle: RadioHandle,
+        freq: Option<Frequency>,
+        mode: Option<OperatingMode>,
+        ptt: Option<bool>,
+    },
+    Active {
+        to: RadioHandle,
+    },
+    SwitchingMode {
+        mode: SwitchingMode,
+    },
+    Switched {
+        record: Box<SwitchRecord>,
+    },
+}
+
+impl Change {
+    fn of(event: &MuxEvent) -> Option<Self> {
+        Some(match event {
+            MuxEvent::RadioConnected { handle, meta } => Change::Connected {
+                handle: *handle,
+                name: meta.display_name.clone(),
+            },
+            MuxEvent::RadioDisconnected { handle } => Change::Disconnected { handle: *handle },
+            MuxEvent::RadioStateChanged {
+                handle,
+                freq,
+                mode,
+                ptt,
+                ..
+            } if freq.is_some() || mode.is_some() || ptt.is_some() => Change::Radio {
+                handle: *handle,
+                freq: *freq,
+                mode: *mode,
+                ptt: *ptt,
+            },
+            MuxEvent::ActiveRadioChanged { to, .. } => Change::Active { to: *to },
+            MuxEvent::SwitchingModeChanged { mode } => Change::SwitchingMode { mode: *mode },
+            MuxEvent::SwitchAudited { record } => Change::Switched {
+                record: Box::new(record.clone()),
+            },
+            _ => return None,
+        })
+    }
+}
+
+/// A snapshot and the changes seen after it
+#[derive(Debug)]
+struct Segment {
+    start: MuxStateAt,
+    changes: Vec<(SystemTime, Change)>,
+}
+
+/// Recent mux state changes, for reconstructing the state at a past moment
+#[derive(Debug)]
+pub struct StateHistory {
+    segments: VecDeque<Segment>,
+    current: MuxStateAt,
+    /// Segments kept
+    capacity: usize,
+    /// Old changes have been dropped, so the start of history is unknown
+    trimmed: bool,
+}
+
+impl StateHistory {
+    /// Follow a mux starting out with no radios, in `switching_mode`
+    pub fn new(switching_mode: SwitchingMode) -> Self {
+        Self {
+            segments: VecDeque::new(),
+            current: MuxStateAt::new(switching_mode),
+            capacity: STATE_HISTORY_CHANGES.div_ceil(SNAPSHOT_EVERY),
+            trimmed: false,
+        }
+    }
+
+    /// Note `event`, seen at `timestamp`, if it changes the state
+    pub fn record(&mut self, timestamp: SystemTime, event: &MuxEvent) {
+        let Some(change) = Change::of(event) else {
+            if let Some(happened) = event.timestamp() {
+                self.happened_at(happened);
+            }
+            return;
+        };
+        if self
+            .segments
+            .back()
+            .is_none_or(|s| s.changes.len() >= SNAPSHOT_EVERY)
+        {
+            if self.segments.len() >= self.capacity {
+                self.segments.pop_front();
+                self.trimmed = true;
+            }
+            self.segments.push_back(Segment {
+                start: self.current.clone(),
+                changes: Vec::with_capacity(SNAPSHOT_EVERY),
+            });
+        }
+        self.current.apply(timestamp, &change);
+        if let Some(segment) = self.segments.back_mut() {
+            segment.changes.push((timestamp, change));
+        }
+    }
+
+    /// Pull back the stamps of changes seen before an event that happened at
+    /// `timestamp`, so they come before it
+    fn happened_at(&mut self, timestamp: SystemTime) {
+        if self.current.as_of.is_some_and(|t| t > timestamp) {
+            self.current.as_of = Some(timestamp);
+        }
+        for segment in self.segments.iter_mut().rev() {
+            for (t, _) in segment.changes.iter_mut().rev() {
+                if *t <= timestamp {
+                    return;
+                }
+                *t = timestamp;
+            }
+        }
+    }
+
+    /// The state as of `timestamp`: every change seen at or before it
+    ///
+    /// None if `timestamp` is older than the history kept.
+    pub fn at(&self, timestamp: SystemTime) -> Option<MuxStateAt> {
+        let idx = self
+            .segments
+            .partition_point(|s| s.changes.first().is_some_and(|(t, _)| *t <= timestamp));
+        let Some(segment) = idx.checked_sub(1).and_then(|i| self.segments.get(i)) else {
+            // Before the first change kept
+            return (!self.trimmed).then(|| {
+                self.segments
+                    .front()
+                    .map_or_else(|| self.current.clone(), |s| s.start.clone())
+            });
+        };
+        let mut state = segment.start.clone();
+        for (t, change) in segment.changes.iter().take_while(|(t, _)| *t <= timestamp) {
+            state.apply(*t, change);
+        }
+        Some(state)
+    }
+
+    /// The state now
+    pub fn current(&self) -> &MuxStateAt {
+        &self.current
+    }
+
+    /// Changes held
+    pub fn len(&self) -> usize {
+        self.segments.iter().map(|s| s.changes.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::switch_audit::{LockoutState, SwitchRule, SwitchTrigger};
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    fn tuned(handle: u32, hz: Frequency) -> MuxEvent {
+        MuxEvent::RadioStateChanged {
+            handle: RadioHandle(handle),
+            freq: Some(hz),
+            mode: None,
+            ptt: None,
+            power_watts: None,
+        }
+    }
+
+    #[test]
+    fn test_state_is_reconstructed_at_a_moment() {
+        let mut history = StateHistory::new(SwitchingMode::FrequencyTriggered);
+        history.record(at(10), &tuned(1, Frequency::from_hz(7_074_000)));
+        history.record(at(11), &tuned(2, Frequency::from_hz(14_074_000)));
+        history.record(
+            at(12),
+            &MuxEvent::ActiveRadioChanged {
+                from: None,
+                to: RadioHandle(2),
+            },
+        );
+        history.record(
+            at(13),
+            &MuxEvent::SwitchAudited {
+                record: SwitchRecord {
+                    id: 1,
+                    timestamp: at(12),
+                    from: None,
+                    to: RadioHandle(2),
+                    mode: SwitchingMode::FrequencyTriggered,
+                    trigger: SwitchTrigger::Manual,
+                    rule: SwitchRule::Selected,
+                    lockout: LockoutState::NeverArmed,
+                    outgoing: None,
+                },
+            },
+        );
+        history.record(
+            at(20),
+            &MuxEvent::SwitchingModeChanged {
+                mode: SwitchingMode::Manual,
+            },
+        );
+        // Not a state change
+        history.record(at(21), &MuxEvent::AmpDisconnected);
+        assert_eq!(history.len(), 5);
+
+        let before = history.at(at(5)).unwrap();
+        assert!(before.radios.is_empty());
+        assert_eq!(before.as_of, None);
+
+        let mid = history.at(at(12)).unwrap();
+        assert_eq!(mid.active_radio, Some(RadioHandle(2)));
+        assert_eq!(
+            mid.radios[&RadioHandle(2)].frequency_hz,
+            Some(Frequency::from_hz(14_074_000))
+        );
+        assert_eq!(mid.switching_mode, SwitchingMode::FrequencyTriggered);
+        assert!(mid.last_switch.is_none());
+        assert_eq!(mid.as_of, Some(at(12)));
+
+        let later = history.at(at(30)).unwrap();
+        assert_eq!(later.switching_mode, SwitchingMode::Manual);
+        assert_eq!(later.last_switch.map(|r| r.id), Some(1));
+        assert_eq!(&later.switching_mode, &history.current().switching_mode);
+    }
+
+    #[test]
+    fn test_change_comes_before_the_write_it_caused() {
+        let mut history = StateHistory::new(SwitchingMode::FrequencyTriggered);
+        // The report arrives, the actor retunes the amp, and the host only
+        // gets to the state change afterwards
+        history.record(at(10), &tuned(1, Frequency::from_hz(7_074_000)));
+        history.record(at(12), &tuned(1, Frequency::from_hz(14_074_000)));
+        history.record(
+            at(12),
+            &MuxEvent::AmpDataOut {
+                data: b"FA00014074000;".to_vec(),
+                timestamp: at(11),
+                cause: None,
+                protocol: cat_protocol::Protocol::Kenwood,
+                suppressed: false,
+            },
+        );
+
+        let state = history.at(at(11)).unwrap();
+        assert_eq!(
+            state.radios[&RadioHandle(1)].frequency_hz,
+            Some(Frequency::from_hz(14_074_000))
+        );
+        assert_eq!(state.as_of, Some(at(11)));
+        let before = history.at(at(10)).unwrap();
+        assert_eq!(
+            before.radios[&RadioHandle(1)].frequency_hz,
+            Some(Frequency::from_hz(7_074_000))
+        );
+    }
+
+    #[test]
+    fn test_replay_crosses_snapshots_and_old_history_goes() {
+        let mut history = StateHistory::new(SwitchingMode::FrequencyTriggered);
+        let total = STATE_HISTORY_CHANGES + SNAPSHOT_EVERY;
+        for i in 0..total as u64 {
+            history.record(at(i), &tuned(1, Frequency::from_hz(1_000_000 + i)));
+        }
+        assert!(history.len() <= STATE_HISTORY_CHANGES);
+
+        // Between snapshots
+        let t = total as u64 - SNAPSHOT_EVERY as u64 / 2 - 3;
+        let state = history.at(at(t)).unwrap();
+        assert_eq!(
+            state.radios[&RadioHandle(1)].frequency_hz,
+            Some(Frequency::from_hz(1_000_000 + t))
+        );
+        // Dropped
+        assert!(history.at(at(0)).is_none());
+    }
+}
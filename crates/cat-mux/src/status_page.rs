@@ -0,0 +1,830 @@
+//! Remote status page and push alerts
+//!
+//! [`StatusBoard`] follows the mux event stream (like [`crate::MuxMetrics`])
+//! and keeps what an operator away from the shack wants at a glance: each
+//! radio's frequency, mode and TX state, the active radio, the amplifier, and
+//! the last few critical alerts. [`run_status_server`] serves a board the
+//! host shares with it (see [`SharedStatusBoard`]) read-only,
+//! as a page sized for a phone that refreshes itself (`GET /`) and as JSON
+//! (`GET /status.json`).
+//!
+//! Critical events are the ones worth interrupting the operator for:
+//!
+//! | Alert | Raised when |
+//! |-------|-------------|
+//! | TX watchdog | a radio goes over the duty-cycle limit, or the mux actor is restarted by its watchdog |
+//! | Interlock | the amplifier is locked out by another radio or a peer transmitting on the band |
+//! | Amp fault | the amplifier fails over, or reports an error |
+//! | Radio disconnected | a connected radio drops off |
+//!
+//! [`StatusBoard::observe`] hands each new alert back so the host can push it
+//! to its [`AlertTargets`] (an ntfy topic, see [`push_ntfy`], and a webhook,
+//! see [`push_webhook`]). The same alert raised again within
+//! [`ALERT_REPEAT_INTERVAL`] is only counted once, so a lockout that trips on
+//! every over doesn't buzz the phone each time.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cat_protocol::display::{format_frequency, format_mode};
+use cat_protocol::{Frequency, OperatingMode};
+use tokio::io::AsyncWriteExt;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+use tracing::{debug, info, warn};
+
+use crate::amp_failover::AmpRole;
+use crate::events::MuxEvent;
+use crate::metrics::{error_response, ok_response, read_request};
+use crate::state::RadioHandle;
+use crate::watch::{json_escape, post, post_webhook};
+
+/// Default listen address (loopback; listen on `0.0.0.0` to reach it from a phone)
+pub const DEFAULT_STATUS_ADDR: &str = "127.0.0.1:8073";
+
+/// Alerts kept for the page
+pub const MAX_ALERTS: usize = 20;
+
+/// An alert repeated within this long is not raised again
+pub const ALERT_REPEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often the page reloads itself, in seconds
+const REFRESH_SECS: u32 = 5;
+
+/// What a critical alert is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertKind {
+    /// A radio over its transmit duty-cycle limit, or the mux restarted
+    TxWatchdog,
+    /// The amplifier locked out of transmit
+    Interlock,
+    /// The amplifier failed over or reported an error
+    AmpFault,
+    /// A radio dropped off
+    RadioDisconnected,
+}
+
+impl AlertKind {
+    /// Short heading (also the ntfy title)
+    pub fn title(&self) -> &'static str {
+        match self {
+            AlertKind::TxWatchdog => "TX watchdog",
+            AlertKind::Interlock => "Interlock",
+            AlertKind::AmpFault => "Amp fault",
+            AlertKind::RadioDisconnected => "Radio disconnected",
+        }
+    }
+
+    /// Identifier in JSON
+    pub fn id(&self) -> &'static str {
+        match self {
+            AlertKind::TxWatchdog => "tx_watchdog",
+            AlertKind::Interlock => "interlock",
+            AlertKind::AmpFault => "amp_fault",
+            AlertKind::RadioDisconnected => "radio_disconnected",
+        }
+    }
+}
+
+/// A critical event, as shown on the page and pushed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusAlert {
+    pub kind: AlertKind,
+    /// What happened, e.g. "IC-7300 disconnected"
+    pub message: String,
+    /// When it was raised
+    pub at: SystemTime,
+}
+
+#[derive(Debug, Clone, Default)]
+struct RadioStatus {
+    name: String,
+    connected: bool,
+    stale: bool,
+    frequency: Option<Frequency>,
+    mode: Option<OperatingMode>,
+    ptt: bool,
+}
+
+/// Station state and recent alerts, folded from the mux event stream
+#[derive(Debug, Default)]
+pub struct StatusBoard {
+    radios: HashMap<RadioHandle, RadioStatus>,
+    active: Option<RadioHandle>,
+    amp_connected: bool,
+    amp_role: AmpRole,
+    alerts: VecDeque<StatusAlert>,
+}
+
+impl StatusBoard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Recent alerts, newest first
+    pub fn alerts(&self) -> impl Iterator<Item = &StatusAlert> {
+        self.alerts.iter()
+    }
+
+    /// Fold one event in; returns the alert it raises, if any
+    pub fn observe(&mut self, event: &MuxEvent, now: SystemTime) -> Option<StatusAlert> {
+        let (kind, message) = match event {
+            MuxEvent::RadioConnected { handle, meta } => {
+                let radio = self.radios.entry(*handle).or_default();
+                radio.name = meta.display_name.clone();
+                radio.connected = true;
+                radio.stale = false;
+                return None;
+            }
+            MuxEvent::RadioDisconnected { handle } => match self.radios.get_mut(handle) {
+                Some(radio) if radio.connected => {
+                    radio.connected = false;
+                    radio.ptt = false;
+                    let message = format!("{} disconnected", radio.name);
+                    (AlertKind::RadioDisconnected, message)
+                }
+                _ => return None,
+            },
+            MuxEvent::RadioStateChanged {
+                handle,
+                freq,
+                mode,
+                ptt,
+                ..
+            } => {
+                let radio = self.radios.entry(*handle).or_default();
+                radio.frequency = freq.or(radio.frequency);
+                radio.mode = mode.or(radio.mode);
+                radio.ptt = ptt.unwrap_or(radio.ptt);
+                return None;
+            }
+            MuxEvent::RadioStaleChanged { handle, stale, .. } => {
+                self.radios.entry(*handle).or_default().stale = *stale;
+                return None;
+            }
+            MuxEvent::ActiveRadioChanged { to, .. } => {
+                self.active = Some(*to);
+                return None;
+            }
+            MuxEvent::AmpConnected { .. } => {
+                self.amp_connected = true;
+                return None;
+            }
+            MuxEvent::AmpDisconnected => {
+                self.amp_connected = false;
+                return None;
+            }
+            MuxEvent::AmpFailoverAcknowledged => {
+                self.amp_role = AmpRole::Primary;
+                return None;
+            }
+            MuxEvent::AmpFailedOver { from, to, reason } => {
+                self.amp_role = *to;
+                let message = format!(
+                    "{} failed ({}); output moved to {}",
+                    capitalize(from.name()),
+                    reason,
+                    to.name()
+                );
+                (AlertKind::AmpFault, message)
+            }
+            MuxEvent::Error { source, message } if source == "Amplifier" => {
+                (AlertKind::AmpFault, message.clone())
+            }
+            MuxEvent::DutyCycleAlert {
+                handle,
+                duty_cycle,
+                over: true,
+            } => {
+                let message = format!(
+                    "{} transmitting {:.0}% of the time",
+                    self.name(*handle),
+                    duty_cycle * 100.0
+                );
+                (AlertKind::TxWatchdog, message)
+            }
+            MuxEvent::MuxRestarted { reason, .. } => (
+                AlertKind::TxWatchdog,
+                format!("Multiplexer restarted: {}", reason),
+            ),
+            MuxEvent::BandLockoutChanged {
+                holder: Some(holder),
+                ..
+            } => {
+                let message = format!(
+                    "Amplifier locked out: {} is transmitting on the band",
+                    self.name(*holder)
+                );
+                (AlertKind::Interlock, message)
+            }
+            MuxEvent::PeerInterlockChanged {
+                blocked_by: Some(peer),
+            } => {
+                let message = format!(
+                    "Amplifier locked out: peer {} is transmitting on the band",
+                    peer.instance
+                );
+                (AlertKind::Interlock, message)
+            }
+            _ => return None,
+        };
+
+        let repeated = self.alerts.iter().any(|alert| {
+            alert.kind == kind
+                && alert.message == message
+                // A clock step backwards counts as recent
+                && now
+                    .duration_since(alert.at)
+                    .map_or(true, |age| age < ALERT_REPEAT_INTERVAL)
+        });
+        if repeated {
+            return None;
+        }
+        let alert = StatusAlert {
+            kind,
+            message,
+            at: now,
+        };
+        self.alerts.push_front(alert.clone());
+        self.alerts.truncate(MAX_ALERTS);
+        Some(alert)
+    }
+
+    fn name(&self, handle: RadioHandle) -> String {
+        self.radios
+            .get(&handle)
+            .map(|radio| radio.name.clone())
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(|| format!("Radio {}", handle.0))
+    }
+
+    fn sorted_radios(&self) -> Vec<(&RadioHandle, &RadioStatus)> {
+        let mut radios: Vec<_> = self.radios.iter().collect();
+        radios.sort_by_key(|(handle, _)| handle.0);
+        radios
+    }
+
+    fn amp_summary(&self) -> &'static str {
+        match (self.amp_connected, self.amp_role) {
+            (_, AmpRole::Bypass) => "Bypassed",
+            (true, AmpRole::Backup) => "On the backup amplifier",
+            (true, AmpRole::Primary) => "Connected",
+            (false, _) => "Disconnected",
+        }
+    }
+
+    /// The page
+    pub fn render_html(&self, now: SystemTime) -> String {
+        let mut out = String::new();
+        let _ = write!(
+            out,
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+             <meta http-equiv=\"refresh\" content=\"{}\">\n\
+             <title>Catapult status</title>\n<style>{}</style>\n</head>\n<body>\n\
+             <h1>Catapult</h1>\n",
+            REFRESH_SECS, STYLE
+        );
+
+        out.push_str("<section>\n<h2>Radios</h2>\n");
+        let radios = self.sorted_radios();
+        if radios.is_empty() {
+            out.push_str("<p class=\"dim\">No radios</p>\n");
+        }
+        for (handle, radio) in radios {
+            let class = if !radio.connected {
+                "card off"
+            } else if radio.ptt {
+                "card tx"
+            } else {
+                "card"
+            };
+            let active = if self.active == Some(*handle) {
+                " <span class=\"badge\">Active</span>"
+            } else {
+                ""
+            };
+            let frequency = radio
+                .frequency
+                .map_or_else(|| "-".to_string(), format_frequency);
+            let mut detail = vec![radio.mode.map_or("-", format_mode).to_string()];
+            detail.push(if radio.ptt { "TX" } else { "RX" }.to_string());
+            if !radio.connected {
+                detail.push("disconnected".to_string());
+            } else if radio.stale {
+                detail.push("not responding".to_string());
+            }
+            let _ = writeln!(
+                out,
+                "<div class=\"{}\"><h3>{}{}</h3><p class=\"freq\">{}</p><p>{}</p></div>",
+                class,
+                html_escape(&self.name(*handle)),
+                active,
+                html_escape(&frequency),
+                detail.join(" &middot; ")
+            );
+        }
+        out.push_str("</section>\n");
+
+        let _ = write!(
+            out,
+            "<section>\n<h2>Amplifier</h2>\n<div class=\"card{}\"><p>{}</p></div>\n</section>\n",
+            if self.amp_role == AmpRole::Primary {
+                ""
+            } else {
+                " alert"
+            },
+            self.amp_summary()
+        );
+
+        out.push_str("<section>\n<h2>Alerts</h2>\n");
+        if self.alerts.is_empty() {
+            out.push_str("<p class=\"dim\">None</p>\n");
+        }
+        for alert in &self.alerts {
+            let _ = writeln!(
+                out,
+                "<div class=\"card alert\"><h3>{}</h3><p>{}</p><p class=\"dim\">{}</p></div>",
+                alert.kind.title(),
+                html_escape(&alert.message),
+                age(now, alert.at)
+            );
+        }
+        out.push_str("</section>\n</body>\n</html>\n");
+        out
+    }
+
+    /// The same as JSON
+    pub fn render_json(&self, now: SystemTime) -> String {
+        let radios: Vec<String> = self
+            .sorted_radios()
+            .into_iter()
+            .map(|(handle, radio)| {
+                format!(
+                    "{{\"handle\":{},\"name\":\"{}\",\"connected\":{},\"stale\":{},\
+                     \"transmitting\":{},\"frequency_hz\":{},\"mode\":{}}}",
+                    handle.0,
+                    json_escape(&radio.name),
+                    radio.connected,
+                    radio.stale,
+                    radio.ptt,
+                    radio
+                        .frequency
+                        .map_or_else(|| "null".to_string(), |f| f.hz().to_string()),
+                    radio
+                        .mode
+                        .map_or_else(|| "null".to_string(), |m| format!("\"{}\"", format_mode(m)))
+                )
+            })
+            .collect();
+        let alerts: Vec<String> = self
+            .alerts
+            .iter()
+            .map(|alert| {
+                format!(
+                    "{{\"kind\":\"{}\",\"message\":\"{}\",\"age_secs\":{}}}",
+                    alert.kind.id(),
+                    json_escape(&alert.message),
+                    now.duration_since(alert.at).unwrap_or_default().as_secs()
+                )
+            })
+            .collect();
+        let in_service = match self.amp_role {
+            AmpRole::Primary => "primary",
+            AmpRole::Backup => "backup",
+            AmpRole::Bypass => "bypass",
+        };
+        format!(
+            "{{\"active\":{},\"radios\":[{}],\"amplifier\":{{\"connected\":{},\"in_service\":\"{}\"}},\
+             \"alerts\":[{}]}}",
+            self.active
+                .map_or_else(|| "null".to_string(), |h| h.0.to_string()),
+            radios.join(","),
+            self.amp_connected,
+            in_service,
+            alerts.join(",")
+        )
+    }
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:0 auto;padding:12px;max-width:640px;\
+background:#fff;color:#111}h1{font-size:1.4em;margin:0 0 8px}h2{font-size:1.1em;margin:16px 0 6px}\
+h3{font-size:1em;margin:0 0 4px}p{margin:2px 0}.card{border:1px solid #ccc;border-radius:8px;\
+padding:10px;margin:6px 0}.card.tx{border-color:#d22;background:#fee}.card.off{opacity:.55}\
+.card.alert{border-color:#e80;background:#fff4e0}.freq{font-size:1.6em;font-variant-numeric:tabular-nums}\
+.badge{font-size:.75em;background:#26c;color:#fff;border-radius:4px;padding:1px 6px}.dim{color:#777}\
+@media (prefers-color-scheme:dark){body{background:#111;color:#eee}.card{border-color:#444}\
+.card.tx{background:#401010}.card.alert{background:#3a2a08}}";
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+fn html_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// "12 s ago", "4 min ago", "2 h ago"
+fn age(now: SystemTime, at: SystemTime) -> String {
+    let secs = now.duration_since(at).unwrap_or_default().as_secs();
+    match secs {
+        0..=59 => format!("{} s ago", secs),
+        60..=3599 => format!("{} min ago", secs / 60),
+        _ => format!("{} h ago", secs / 3600),
+    }
+}
+
+/// JSON body for an alert webhook
+pub fn alert_body(alert: &StatusAlert) -> String {
+    let at = alert
+        .at
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    format!(
+        "{{\"alert\":\"{}\",\"title\":\"{}\",\"message\":\"{}\",\"time\":{}}}",
+        alert.kind.id(),
+        alert.kind.title(),
+        json_escape(&alert.message),
+        at
+    )
+}
+
+/// Publish an alert to an ntfy topic (`http://ntfy.sh/<topic>` or a
+/// self-hosted server) at high priority
+pub async fn push_ntfy(url: &str, alert: &StatusAlert) -> io::Result<()> {
+    post(
+        url,
+        &[
+            ("Content-Type", "text/plain; charset=utf-8"),
+            ("Title", alert.kind.title()),
+            ("Priority", "high"),
+            ("Tags", "warning"),
+        ],
+        &alert.message,
+    )
+    .await
+}
+
+/// POST an alert to a webhook as JSON (see [`alert_body`])
+pub async fn push_webhook(url: &str, alert: &StatusAlert) -> io::Result<()> {
+    post_webhook(url, &alert_body(alert)).await
+}
+
+/// Where critical alerts are pushed (an empty URL is not pushed to)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlertTargets {
+    /// ntfy topic URL
+    pub ntfy_url: String,
+    /// URL the alert is POSTed to as JSON
+    pub webhook_url: String,
+}
+
+impl AlertTargets {
+    /// Whether there is nowhere to push to
+    pub fn is_empty(&self) -> bool {
+        self.ntfy_url.trim().is_empty() && self.webhook_url.trim().is_empty()
+    }
+
+    /// Push `alert` to each target; returns what went wrong, one line per
+    /// target that failed
+    pub async fn push(&self, alert: &StatusAlert) -> Vec<String> {
+        let mut failures = Vec::new();
+        let ntfy_url = self.ntfy_url.trim();
+        if !ntfy_url.is_empty() {
+            if let Err(e) = push_ntfy(ntfy_url, alert).await {
+                failures.push(format!("ntfy: {}", e));
+            }
+        }
+        let webhook_url = self.webhook_url.trim();
+        if !webhook_url.is_empty() {
+            if let Err(e) = push_webhook(webhook_url, alert).await {
+                failures.push(format!("webhook: {}", e));
+            }
+        }
+        failures
+    }
+}
+
+/// A [`StatusBoard`] the host keeps fed with mux events and the status
+/// server reads
+///
+/// Sharing one board means a page started late still shows the radios and
+/// amplifier that connected before it.
+pub type SharedStatusBoard = Arc<Mutex<StatusBoard>>;
+
+/// Fold a mux event into a shared board; returns the alert it raises, if any
+///
+/// Traffic isn't shown on the page, so it is skipped without taking the lock.
+pub fn observe_status(board: &SharedStatusBoard, event: &MuxEvent) -> Option<StatusAlert> {
+    if event.is_traffic() {
+        return None;
+    }
+    board
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .observe(event, SystemTime::now())
+}
+
+/// Pause after a failed accept (e.g. out of file descriptors) before trying again
+const ACCEPT_RETRY_DELAY: Duration = Duration::from_millis(100);
+
+/// Serve the status page until `shutdown` fires or its sender is dropped
+///
+/// Each connection is answered with a snapshot of `board` taken when it was
+/// accepted. A failed accept is logged and the server carries on.
+pub async fn run_status_server(
+    listener: TcpListener,
+    board: SharedStatusBoard,
+    mut shutdown: oneshot::Receiver<()>,
+) -> io::Result<()> {
+    info!("Serving status page on {}", listener.local_addr()?);
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => match accepted {
+                Ok((socket, peer)) => {
+                    let now = SystemTime::now();
+                    let (page, json) = {
+                        let board = board.lock().unwrap_or_else(|e| e.into_inner());
+                        (board.render_html(now), board.render_json(now))
+                    };
+                    tokio::spawn(async move {
+                        if let Err(e) = answer(socket, page, json).await {
+                            debug!("Status request from {} failed: {}", peer, e);
+                        }
+                    });
+                }
+                Err(e) => {
+                    warn!("Status page couldn't accept a connection: {}", e);
+                    tokio::time::sleep(ACCEPT_RETRY_DELAY).await;
+                }
+            },
+        }
+    }
+    info!("Status page stopped");
+    Ok(())
+}
+
+/// Read one HTTP request and answer it
+async fn answer(mut socket: TcpStream, page: String, json: String) -> io::Result<()> {
+    let (method, path) = read_request(&mut socket).await?;
+    let response = match (method.as_str(), path.as_str()) {
+        ("GET" | "HEAD", "/" | "/index.html") => {
+            ok_response(&method, "text/html; charset=utf-8", &page)
+        }
+        ("GET" | "HEAD", "/status.json") => ok_response(&method, "application/json", &json),
+        _ => error_response(&method),
+    };
+    socket.write_all(response.as_bytes()).await?;
+    socket.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channel::RadioChannelMeta;
+    use cat_protocol::Protocol;
+    use tokio::io::AsyncReadExt;
+
+    fn connected(handle: u32, name: &str) -> MuxEvent {
+        MuxEvent::RadioConnected {
+            handle: RadioHandle(handle),
+            meta: RadioChannelMeta::new_real(
+                name.to_string(),
+                "/dev/ttyUSB0".to_string(),
+                Protocol::Kenwood,
+                None,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_board_raises_critical_alerts_once() {
+        let mut board = StatusBoard::new();
+        let t0 = SystemTime::now();
+        assert_eq!(board.observe(&connected(1, "IC-7300 <Run>"), t0), None);
+        board.observe(
+            &MuxEvent::RadioStateChanged {
+                handle: RadioHandle(1),
+                freq: Some(Frequency::from_khz(14_074)),
+                mode: Some(OperatingMode::DataU),
+                ptt: Some(true),
+                power_watts: None,
+            },
+            t0,
+        );
+
+        let alert = board
+            .observe(
+                &MuxEvent::DutyCycleAlert {
+                    handle: RadioHandle(1),
+                    duty_cycle: 0.62,
+                    over: true,
+                },
+                t0,
+            )
+            .unwrap();
+        assert_eq!(alert.kind, AlertKind::TxWatchdog);
+        assert_eq!(alert.message, "IC-7300 <Run> transmitting 62% of the time");
+
+        // Dropping back under is not an alert
+        let under = MuxEvent::DutyCycleAlert {
+            handle: RadioHandle(1),
+            duty_cycle: 0.3,
+            over: false,
+        };
+        assert_eq!(board.observe(&under, t0), None);
+
+        let failed = MuxEvent::AmpFailedOver {
+            from: AmpRole::Primary,
+            to: AmpRole::Bypass,
+            reason: "Fault code 4".to_string(),
+        };
+        let alert = board.observe(&failed, t0).unwrap();
+        assert_eq!(alert.kind, AlertKind::AmpFault);
+        assert_eq!(
+            alert.message,
+            "Primary amplifier failed (Fault code 4); output moved to bypass"
+        );
+
+        let disconnected = MuxEvent::RadioDisconnected {
+            handle: RadioHandle(1),
+        };
+        let alert = board.observe(&disconnected, t0).unwrap();
+        assert_eq!(alert.kind, AlertKind::RadioDisconnected);
+        // Already disconnected: nothing new
+        assert_eq!(board.observe(&disconnected, t0), None);
+
+        // The same alert again is held back until the repeat interval passes
+        let lockout = MuxEvent::BandLockoutChanged {
+            handle: RadioHandle(2),
+            holder: Some(RadioHandle(1)),
+        };
+        assert!(board.observe(&lockout, t0).is_some());
+        assert_eq!(board.observe(&lockout, t0 + Duration::from_secs(5)), None);
+        assert!(board
+            .observe(&lockout, t0 + ALERT_REPEAT_INTERVAL)
+            .is_some());
+
+        let kinds: Vec<_> = board.alerts().map(|a| a.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                AlertKind::Interlock,
+                AlertKind::Interlock,
+                AlertKind::RadioDisconnected,
+                AlertKind::AmpFault,
+                AlertKind::TxWatchdog,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_and_reports_state() {
+        let mut board = StatusBoard::new();
+        let t0 = SystemTime::now();
+        board.observe(&connected(3, "K3 \"Mult\" <2>"), t0);
+        board.observe(
+            &MuxEvent::ActiveRadioChanged {
+                from: None,
+                to: RadioHandle(3),
+            },
+            t0,
+        );
+        board.observe(
+            &MuxEvent::RadioStateChanged {
+                handle: RadioHandle(3),
+                freq: Some(Frequency::from_hz(7_030_000)),
+                mode: Some(OperatingMode::Cw),
+                ptt: None,
+                power_watts: None,
+            },
+            t0,
+        );
+        board.observe(
+            &MuxEvent::Error {
+                source: "Amplifier".to_string(),
+                message: "write failed".to_string(),
+            },
+            t0,
+        );
+
+        let now = t0 + Duration::from_secs(125);
+        let html = board.render_html(now);
+        assert!(html.contains("name=\"viewport\""));
+        assert!(html.contains("K3 &quot;Mult&quot; &lt;2&gt;"));
+        assert!(html.contains("Active"));
+        assert!(html.contains("CW &middot; RX"));
+        assert!(html.contains("write failed"));
+        assert!(html.contains("2 min ago"));
+
+        let json = board.render_json(now);
+        assert!(json.starts_with("{\"active\":3,"));
+        assert!(json.contains("\"name\":\"K3 \\\"Mult\\\" <2>\""));
+        assert!(json.contains("\"frequency_hz\":7030000,\"mode\":\"CW\""));
+        assert!(json.contains("\"amplifier\":{\"connected\":false,\"in_service\":\"primary\"}"));
+        assert!(
+            json.contains("{\"kind\":\"amp_fault\",\"message\":\"write failed\",\"age_secs\":125}")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_answers_page_and_json() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Connected before the server started
+        let board = SharedStatusBoard::default();
+        board
+            .lock()
+            .unwrap()
+            .observe(&connected(1, "FTdx10"), SystemTime::now());
+        let (stop_tx, stop_rx) = oneshot::channel();
+        let server = tokio::spawn(run_status_server(listener, board, stop_rx));
+
+        let get = |path: &'static str| async move {
+            let mut socket = TcpStream::connect(addr).await.unwrap();
+            socket
+                .write_all(format!("GET {} HTTP/1.1\r\nHost: x\r\n\r\n", path).as_bytes())
+                .await
+                .unwrap();
+            let mut response = String::new();
+            socket.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let page = get("/").await;
+        assert!(page.starts_with("HTTP/1.1 200 OK"));
+        assert!(page.contains("Content-Type: text/html"));
+        assert!(page.contains("FTdx10"));
+        let json = get("/status.json?x=1").await;
+        assert!(json.contains("\"name\":\"FTdx10\""));
+        assert!(get("/metrics").await.starts_with("HTTP/1.1 404"));
+
+        stop_tx.send(()).unwrap();
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_push_ntfy_sends_title_and_priority() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let receiver = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut chunk = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).ends_with("disconnected") {
+                let n = socket.read(&mut chunk).await.unwrap();
+                request.extend_from_slice(&chunk[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let alert = StatusAlert {
+            kind: AlertKind::RadioDisconnected,
+            message: "TS-890 disconnected".to_string(),
+            at: SystemTime::now(),
+        };
+        push_ntfy(&format!("http://{}/shack", addr), &alert)
+            .await
+            .unwrap();
+        let request = receiver.await.unwrap();
+        assert!(request.starts_with("POST /shack HTTP/1.1\r\n"));
+        assert!(request.contains("Title: Radio disconnected\r\n"));
+        assert!(request.contains("Priority: high\r\n"));
+        assert!(request.ends_with("\r\n\r\nTS-890 disconnected"));
+    }
+
+    #[tokio::test]
+    async fn test_alert_targets_report_each_failure() {
+        let alert = StatusAlert {
+            kind: AlertKind::AmpFault,
+            message: "Amplifier failed".to_string(),
+            at: SystemTime::now(),
+        };
+        assert!(AlertTargets::default().is_empty());
+        assert!(AlertTargets::default().push(&alert).await.is_empty());
+
+        let targets = AlertTargets {
+            ntfy_url: "https://ntfy.sh/shack".to_string(),
+            webhook_url: " ".to_string(),
+        };
+        assert!(!targets.is_empty());
+        let failures = targets.push(&alert).await;
+        assert_eq!(failures.len(), 1);
+        assert!(failures[0].starts_with("ntfy: "));
+    }
+}
@@ -0,0 +1,606 @@
+//! Watchdog for the multiplexer actor
+//!
+//! If the actor panics its task ends, and if it deadlocks it stops taking
+//! commands; either way the app keeps running but nothing reaches the radios
+//! or the amp. [`run_supervised_mux`] runs the actor under a supervisor that
+//! owns the command queue and passes each command on. The actor beats a
+//! heartbeat while its loop turns; when its task ends or the heartbeat
+//! goes quiet for [`WatchdogConfig::timeout`], the supervisor starts a fresh
+//! actor and emits [`MuxEvent::MuxRestarted`].
+//!
+//! Everything the actor was connected to (radios, the amplifier, the shadow
+//! amplifier, the band decoder) is noted in a [`ChannelRegistry`] that
+//! outlives it, so the new actor re-registers the same channels under the
+//! same handles. The amp is unkeyed and told what it last heard, and every
+//! radio is asked for its frequency, mode and PTT. Settings the app pushes
+//! (thresholds, policies) are not kept; the app sends them again when it
+//! sees the restart.
+//!
+//! The heartbeat shows the actor's loop turning, not that its events are
+//! read: while the event queue is full the actor waits on the app, and that
+//! isn't taken for a hang. An actor that keeps failing is restarted after a
+//! growing delay, [`WatchdogConfig::restart_backoff`] doubling up to
+//! [`WatchdogConfig::max_restart_backoff`], so a fault it hits on every
+//! start can't spin the supervisor.
+//!
+//! A deadlock is only caught at an `.await`: a task spinning without
+//! yielding can't be stopped, and keeps its worker thread.
+
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use cat_protocol::CommandHooks;
+use tokio::sync::mpsc;
+use tokio::task::JoinError;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+use crate::actor::{run_actor, MuxActorCommand};
+use crate::amp_failover::AmpFailover;
+use crate::amplifier::AmplifierChannelMeta;
+use crate::async_radio::RadioTaskCommand;
+use crate::channel::RadioChannelMeta;
+use crate::events::MuxEvent;
+use crate::state::{AmplifierConfig, AmplifierEmulatedState, RadioHandle, SwitchingMode};
+use crate::write_backlog::WriteBacklog;
+
+/// How often the actor beats its heartbeat, and how long a silence is tolerated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchdogConfig {
+    /// Time between beats while the actor is idle
+    pub heartbeat_interval: Duration,
+    /// Silence after which the actor is taken for dead
+    pub timeout: Duration,
+    /// Delay before the second of two restarts in a row
+    pub restart_backoff: Duration,
+    /// Longest delay before a restart
+    pub max_restart_backoff: Duration,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_interval: Duration::from_secs(1),
+            timeout: Duration::from_secs(5),
+            restart_backoff: Duration::from_millis(250),
+            max_restart_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl WatchdogConfig {
+    /// Delay before restarting an actor that failed `failures` times in a
+    /// row (the first restart is immediate)
+    fn backoff(&self, failures: u32) -> Duration {
+        if failures <= 1 {
+            return Duration::ZERO;
+        }
+        let doublings = (failures - 2).min(16);
+        self.restart_backoff
+            .saturating_mul(1 << doublings)
+            .min(self.max_restart_backoff)
+    }
+}
+
+/// An actor that ran this long before failing starts a fresh backoff
+const STABLE_RUN: Duration = Duration::from_secs(60);
+
+/// Liveness signal the actor gives off while its loop turns
+#[derive(Debug, Clone)]
+pub(crate) struct Heartbeat {
+    start: Instant,
+    /// Milliseconds from `start` to the last beat
+    last_ms: Arc<AtomicU64>,
+    interval: Duration,
+}
+
+impl Heartbeat {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            last_ms: Arc::new(AtomicU64::new(0)),
+            interval,
+        }
+    }
+
+    /// Time between beats while idle
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub(crate) fn beat(&self) {
+        let ms = self.start.elapsed().as_millis() as u64;
+        self.last_ms.store(ms, Ordering::Relaxed);
+    }
+
+    /// Time since the last beat
+    fn silence(&self) -> Duration {
+        let last = Duration::from_millis(self.last_ms.load(Ordering::Relaxed));
+        self.start.elapsed().saturating_sub(last)
+    }
+}
+
+/// A radio as it was registered, with what was set on it since
+#[derive(Debug, Clone)]
+pub(crate) struct SavedRadio {
+    pub meta: RadioChannelMeta,
+    pub cmd_tx: Option<mpsc::Sender<RadioTaskCommand>>,
+    pub hooks: Option<CommandHooks>,
+    /// Input throttle window in milliseconds, if one was set
+    pub input_throttle_ms: Option<u64>,
+}
+
+/// An amplifier connection (primary or shadow)
+#[derive(Debug, Clone)]
+pub(crate) struct SavedAmp {
+    pub meta: AmplifierChannelMeta,
+    pub command_tx: mpsc::Sender<Vec<u8>>,
+    pub backlog: WriteBacklog,
+    pub urgent_tx: Option<mpsc::Sender<Vec<u8>>>,
+}
+
+/// What the actor was connected to
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SavedChannels {
+    pub radios: BTreeMap<RadioHandle, SavedRadio>,
+    pub amp: Option<SavedAmp>,
+    pub shadow_amp: Option<SavedAmp>,
+    pub backup_amp: Option<SavedAmp>,
+    pub amp_config: Option<AmplifierConfig>,
+    pub band_decoder: Option<mpsc::Sender<Vec<u8>>>,
+    pub switching_mode: Option<SwitchingMode>,
+    pub active: Option<RadioHandle>,
+    /// Which amp is in service, so a restart doesn't put a failed one back
+    pub amp_failover: AmpFailover,
+    /// A primary reconnected while output is off it
+    pub standby_primary: Option<SavedAmp>,
+    /// Whether the amp in service asked for auto-information
+    pub amp_auto_info: bool,
+    /// What the amp in service was last told
+    pub amp_state: AmplifierEmulatedState,
+}
+
+/// The actor's channels, kept where a restart can find them
+///
+/// Clones share the same record. The actor keeps it up to date as channels
+/// come and go.
+#[derive(Debug, Clone, Default)]
+pub struct ChannelRegistry {
+    saved: Arc<Mutex<SavedChannels>>,
+}
+
+impl ChannelRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The record, even if an actor panicked while holding it
+    pub(crate) fn lock(&self) -> MutexGuard<'_, SavedChannels> {
+        self.saved.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Copy of the record, to restore from
+    pub(crate) fn snapshot(&self) -> SavedChannels {
+        self.lock().clone()
+    }
+
+    /// Number of radios registered
+    pub fn radio_count(&self) -> usize {
+        self.lock().radios.len()
+    }
+}
+
+/// Run the multiplexer actor, restarting it if it panics or stops responding
+///
+/// Returns once `cmd_rx` closes or a [`MuxActorCommand::Shutdown`] has been
+/// handled.
+pub async fn run_supervised_mux(
+    mut cmd_rx: mpsc::Receiver<MuxActorCommand>,
+    event_tx: mpsc::Sender<MuxEvent>,
+    config: WatchdogConfig,
+) {
+    let registry = ChannelRegistry::new();
+    let capacity = cmd_rx.max_capacity();
+    let mut restarts = 0u32;
+    // Failures in a row, each after a short run
+    let mut failures = 0u32;
+    // Taken from the queue but not yet handed over (kept across a restart)
+    let mut pending: Option<MuxActorCommand> = None;
+    let mut shutting_down = false;
+
+    loop {
+        let (actor_tx, actor_rx) = mpsc::channel(capacity);
+        let heartbeat = Heartbeat::new(config.heartbeat_interval);
+        let mut actor = tokio::spawn(run_actor(
+            actor_rx,
+            event_tx.clone(),
+            registry.clone(),
+            heartbeat.clone(),
+        ));
+        let mut check = interval(config.heartbeat_interval.min(config.timeout));
+        check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let started = Instant::now();
+
+        let reason = loop {
+            tokio::select! {
+                cmd = cmd_rx.recv(), if pending.is_none() && !shutting_down => {
+                    // With every client gone, stop the actor as if asked to
+                    let cmd = cmd.unwrap_or(MuxActorCommand::Shutdown);
+                    shutting_down = matches!(cmd, MuxActorCommand::Shutdown);
+                    pending = Some(cmd);
+                }
+                permit = actor_tx.reserve(), if pending.is_some() => {
+                    // A closed queue means the actor's task is ending; the
+                    // next arm finds out why
+                    if let Ok(permit) = permit {
+                        permit.send(pending.take().expect("pending command"));
+                    }
+                }
+                result = &mut actor => {
+                    if shutting_down {
+                        return;
+                    }
+                    break match result {
+                        Ok(()) => "stopped unexpectedly".to_string(),
+                        Err(e) => describe_failure(e),
+                    };
+                }
+                _ = check.tick() => {
+                    // Blocked on a full event queue, the actor waits on the
+                    // app rather than being hung
+                    if event_tx.capacity() == 0 {
+                        heartbeat.beat();
+                    }
+                    if heartbeat.silence() > config.timeout {
+                        actor.abort();
+                        if shutting_down {
+                            warn!("Multiplexer actor hung while shutting down");
+                            return;
+                        }
+                        break format!("stopped responding for over {:?}", config.timeout);
+                    }
+                }
+            }
+        };
+
+        restarts += 1;
+        error!(
+            "Multiplexer actor {}; restarting with {} radio(s) (restart {})",
+            reason,
+            registry.radio_count(),
+            restarts
+        );
+        let _ = event_tx
+            .send(MuxEvent::MuxRestarted { reason, restarts })
+            .await;
+
+        if started.elapsed() >= STABLE_RUN {
+            failures = 0;
+        }
+        failures += 1;
+        let backoff = config.backoff(failures);
+        if !backoff.is_zero() {
+            warn!(
+                "Multiplexer actor failing repeatedly; restarting in {:?}",
+                backoff
+            );
+            let wake = tokio::time::sleep(backoff);
+            tokio::pin!(wake);
+            loop {
+                tokio::select! {
+                    _ = &mut wake => break,
+                    cmd = cmd_rx.recv(), if pending.is_none() && !shutting_down => {
+                        match cmd {
+                            None | Some(MuxActorCommand::Shutdown) => return,
+                            cmd => pending = cmd,
+                        }
+                    }
+                }
+            }
+        }
+        info!("Multiplexer actor restarting");
+    }
+}
+
+/// Why the actor's task ended
+fn describe_failure(e: JoinError) -> String {
+    if e.is_cancelled() {
+        return "was cancelled".to_string();
+    }
+    match panic_message(e.into_panic()) {
+        Some(message) => format!("panicked: {}", message),
+        None => "panicked".to_string(),
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> Option<String> {
+    match payload.downcast::<String>() {
+        Ok(message) => Some(*message),
+        Err(payload) => payload.downcast_ref::<&str>().map(|s| s.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::amp_failover::AmpRole;
+    use crate::amplifier::AmplifierChannel;
+    use cat_protocol::{Protocol, RadioRequest};
+    use tokio::sync::oneshot;
+    use tokio::time::timeout;
+
+    fn quick() -> WatchdogConfig {
+        WatchdogConfig {
+            heartbeat_interval: Duration::from_millis(20),
+            timeout: Duration::from_millis(200),
+            restart_backoff: Duration::from_millis(50),
+            max_restart_backoff: Duration::from_millis(200),
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_to_its_limit() {
+        let config = quick();
+        let ms = |failures| config.backoff(failures).as_millis();
+        assert_eq!([1, 2, 3, 4, 5, 40].map(ms), [0, 50, 100, 200, 200, 200]);
+    }
+
+    async fn register(cmd_tx: &mpsc::Sender<MuxActorCommand>, name: &str) -> RadioHandle {
+        let (resp_tx, resp_rx) = oneshot::channel();
+        let meta =
+            RadioChannelMeta::new_virtual(name.to_string(), name.to_string(), Protocol::Kenwood);
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: None,
+            })
+            .await
+            .unwrap();
+        resp_rx.await.unwrap()
+    }
+
+    async fn active(cmd_tx: &mpsc::Sender<MuxActorCommand>) -> Option<RadioHandle> {
+        let (resp_tx, resp_rx) = oneshot::channel();
+        cmd_tx
+            .send(MuxActorCommand::QueryActiveRadio { response: resp_tx })
+            .await
+            .unwrap();
+        resp_rx.await.unwrap().map(|(handle, _)| handle)
+    }
+
+    /// Wait for the next restart, returning its reason and the radios
+    /// reconnected after it
+    async fn next_restart(event_rx: &mut mpsc::Receiver<MuxEvent>) -> (String, Vec<RadioHandle>) {
+        let reason = timeout(Duration::from_secs(5), async {
+            loop {
+                if let MuxEvent::MuxRestarted { reason, .. } = event_rx.recv().await.unwrap() {
+                    return reason;
+                }
+            }
+        })
+        .await
+        .expect("no restart");
+        let mut restored = Vec::new();
+        while let Ok(Some(event)) = timeout(Duration::from_millis(100), event_rx.recv()).await {
+            if let MuxEvent::RadioConnected { handle, .. } = event {
+                restored.push(handle);
+            }
+        }
+        (reason, restored)
+    }
+
+    #[tokio::test]
+    async fn test_panic_restores_radios() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        let a = register(&cmd_tx, "A").await;
+        let b = register(&cmd_tx, "B").await;
+        assert_eq!(active(&cmd_tx).await, Some(a));
+
+        cmd_tx
+            .send(MuxActorCommand::InjectFault { hang: false })
+            .await
+            .unwrap();
+        let (reason, restored) = next_restart(&mut event_rx).await;
+        assert_eq!(reason, "panicked: injected fault");
+        assert_eq!(restored, vec![a, b]);
+
+        // Same active radio, and new radios don't reuse the old handles
+        assert_eq!(active(&cmd_tx).await, Some(a));
+        let c = register(&cmd_tx, "C").await;
+        assert_eq!(c.0, b.0 + 1);
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_failover_survives_restart() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        let virtual_amp = || {
+            let (cmd_tx, cmd_rx) = mpsc::channel(16);
+            let (resp_tx, resp_rx) = mpsc::channel(16);
+            let meta = AmplifierChannelMeta::new_virtual(Protocol::Kenwood, None);
+            (
+                AmplifierChannel::new(meta, cmd_tx, resp_rx),
+                resp_tx,
+                cmd_rx,
+            )
+        };
+        let (amp, _amp_resp_tx, _amp_rx) = virtual_amp();
+        let (backup, _backup_resp_tx, mut backup_rx) = virtual_amp();
+        for cmd in [
+            MuxActorCommand::ConnectAmplifier { channel: amp },
+            MuxActorCommand::ConnectBackupAmplifier { channel: backup },
+            MuxActorCommand::SetAmpFailover { enabled: true },
+            MuxActorCommand::AmpFailed {
+                role: AmpRole::Primary,
+                reason: "Read error".to_string(),
+            },
+            MuxActorCommand::InjectFault { hang: false },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+        next_restart(&mut event_rx).await;
+
+        // The backup is still in service, and a reconnected primary stands by
+        let (amp, _amp_resp_tx, mut amp_rx) = virtual_amp();
+        for cmd in [
+            MuxActorCommand::ConnectAmplifier { channel: amp },
+            MuxActorCommand::AmpRawData {
+                data: b"ID;".to_vec(),
+            },
+            MuxActorCommand::BackupAmpRawData {
+                data: b"ID;".to_vec(),
+            },
+        ] {
+            cmd_tx.send(cmd).await.unwrap();
+        }
+        // Unkeyed by the restored actor, then answered as the amp in service
+        assert_eq!(backup_rx.recv().await.unwrap(), b"TX0;");
+        assert_eq!(backup_rx.recv().await.unwrap(), b"ID022;");
+        assert!(amp_rx.try_recv().is_err());
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_restart_unkeys_amp_and_requeries_radios() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        let (radio_tx, mut radio_rx) = mpsc::channel(16);
+        let (resp_tx, resp_rx) = oneshot::channel();
+        let meta =
+            RadioChannelMeta::new_virtual("A".to_string(), "A".to_string(), Protocol::Kenwood);
+        cmd_tx
+            .send(MuxActorCommand::RegisterRadio {
+                meta,
+                response: resp_tx,
+                cmd_tx: Some(radio_tx),
+            })
+            .await
+            .unwrap();
+        resp_rx.await.unwrap();
+        let (amp_tx, mut amp_rx) = mpsc::channel(16);
+        let (_amp_resp_tx, amp_resp_rx) = mpsc::channel(16);
+        let meta = AmplifierChannelMeta::new_virtual(Protocol::Kenwood, None);
+        cmd_tx
+            .send(MuxActorCommand::ConnectAmplifier {
+                channel: AmplifierChannel::new(meta, amp_tx, amp_resp_rx),
+            })
+            .await
+            .unwrap();
+        cmd_tx
+            .send(MuxActorCommand::InjectFault { hang: false })
+            .await
+            .unwrap();
+        next_restart(&mut event_rx).await;
+
+        assert_eq!(amp_rx.recv().await.unwrap(), b"TX0;");
+        let mut requests = Vec::new();
+        while requests.len() < 3 {
+            if let RadioTaskCommand::SendRequest { request } = radio_rx.recv().await.unwrap() {
+                requests.push(request);
+            }
+        }
+        assert_eq!(
+            requests,
+            [
+                RadioRequest::GetFrequency,
+                RadioRequest::GetMode,
+                RadioRequest::GetPtt
+            ]
+        );
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_full_event_queue_is_not_a_hang() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        // Nobody reads events while the actor registers two radios
+        let (event_tx, mut event_rx) = mpsc::channel(1);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        register(&cmd_tx, "A").await;
+        register(&cmd_tx, "B").await;
+        tokio::time::sleep(quick().timeout * 3).await;
+
+        while let Ok(Some(event)) = timeout(Duration::from_millis(100), event_rx.recv()).await {
+            assert!(
+                !matches!(event, MuxEvent::MuxRestarted { .. }),
+                "{:?}",
+                event
+            );
+        }
+
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hang_is_detected() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        let a = register(&cmd_tx, "A").await;
+        cmd_tx
+            .send(MuxActorCommand::InjectFault { hang: true })
+            .await
+            .unwrap();
+        let (reason, restored) = next_restart(&mut event_rx).await;
+        assert!(reason.starts_with("stopped responding"), "{}", reason);
+        assert_eq!(restored, vec![a]);
+        assert_eq!(active(&cmd_tx).await, Some(a));
+
+        drop(cmd_tx);
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_is_not_restarted() {
+        let (cmd_tx, cmd_rx) = mpsc::channel(16);
+        let (event_tx, mut event_rx) = mpsc::channel(64);
+        let supervisor = tokio::spawn(run_supervised_mux(cmd_rx, event_tx, quick()));
+
+        register(&cmd_tx, "A").await;
+        cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+        timeout(Duration::from_secs(5), supervisor)
+            .await
+            .unwrap()
+            .unwrap();
+
+        while let Some(event) = event_rx.recv().await {
+            assert!(!matches!(event, MuxEvent::MuxRestarted { .. }));
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! Switching audit log
+//!
+//! Every change of active radio is recorded with the chain of causes behind
+//! it: what triggered it (the operator, or a report from the radio and the
+//! frame it came in), the switching rule that accepted it, the state of the
+//! post-switch lockout, and how the outgoing radio compared. The engine
+//! describes its decision as a [`SwitchRecord`]; the actor fills in the raw
+//! frame, keeps the last [`AUDIT_LOG_CAPACITY`] in a [`SwitchAuditLog`],
+//! emits each as [`crate::MuxEvent::SwitchAudited`] and answers
+//! `MuxActorCommand::QuerySwitchAudit`.
+//!
+//! Radios have no priority order: the first radio to satisfy the rule once
+//! the lockout has expired wins. The outgoing radio's state is recorded so a
+//! switch away from a radio that was still in use stands out.
+
+use cat_protocol::Frequency;
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime};
+
+use cat_protocol::display::format_frequency;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{RadioHandle, SwitchingMode};
+
+/// Records kept by the actor
+pub const AUDIT_LOG_CAPACITY: usize = 200;
+
+/// What set the switch off
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchTrigger {
+    /// The operator (or a client) selected the radio
+    Manual,
+    /// A report from the incoming radio
+    Report {
+        /// The decoded report
+        response: String,
+        /// The last frame received from the radio, if known
+        frame: Option<Vec<u8>>,
+    },
+}
+
+/// The switching rule that accepted the trigger
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwitchRule {
+    /// Manual selection always applies once the lockout allows it
+    Selected,
+    /// The radio's frequency changed
+    FrequencyChanged {
+        from_hz: Frequency,
+        to_hz: Frequency,
+    },
+    /// The radio keyed up (Automatic mode)
+    PttPressed,
+}
+
+/// The post-switch lockout when the decision was made
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LockoutState {
+    /// No switch had happened yet
+    NeverArmed,
+    /// The previous switch's lockout had run out
+    Expired {
+        /// How long ago
+        ago: Duration,
+    },
+    /// Lockout still running, but no radio was active to protect
+    NoActiveRadio {
+        /// What was left of it
+        remaining: Duration,
+    },
+}
+
+/// The outgoing radio at the moment of the switch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutgoingRadio {
+    pub handle: RadioHandle,
+    /// It was transmitting
+    pub ptt: bool,
+    /// It had gone silent past the staleness threshold
+    pub stale: bool,
+    /// Time since it last changed frequency (None if it never has)
+    pub since_tuned: Option<Duration>,
+}
+
+/// One change of active radio and why it happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwitchRecord {
+    /// Sequence number, counting from 1 for the actor's lifetime
+    pub id: u64,
+    pub timestamp: SystemTime,
+    pub from: Option<RadioHandle>,
+    pub to: RadioHandle,
+    /// Switching mode in force
+    pub mode: SwitchingMode,
+    pub trigger: SwitchTrigger,
+    pub rule: SwitchRule,
+    pub lockout: LockoutState,
+    pub outgoing: Option<OutgoingRadio>,
+}
+
+impl SwitchRecord {
+    /// The cause chain, one line per link, with radios named by `name`
+    pub fn chain(&self, name: impl Fn(RadioHandle) -> String) -> Vec<String> {
+        let mut lines = Vec::with_capacity(4);
+        lines.push(match &self.trigger {
+            SwitchTrigger::Manual => format!("Trigger: {} selected by hand", name(self.to)),
+            SwitchTrigger::Report { response, frame } => match frame {
+                Some(frame) => format!(
+                    "Trigger: {} reported {} in frame {}",
+                    name(self.to),
+                    response,
+                    cat_protocol::display::format_hex(frame)
+                ),
+                None => format!("Trigger: {} reported {}", name(self.to), response),
+            },
+        });
+        lines.push(match self.rule {
+            SwitchRule::Selected => format!("Rule: manual selection ({:?} mode)", self.mode),
+            SwitchRule::FrequencyChanged { from_hz, to_hz } => format!(
+                "Rule: {:?} mode switches on a frequency change ({} -> {})",
+                self.mode,
+                format_frequency(from_hz),
+                format_frequency(to_hz)
+            ),
+            SwitchRule::PttPressed => format!("Rule: {:?} mode switches on PTT", self.mode),
+        });
+        lines.push(match self.lockout {
+            LockoutState::NeverArmed => "Lockout: not armed (first switch)".to_string(),
+            LockoutState::Expired { ago } => {
+                format!("Lockout: expired {} ms earlier", ago.as_millis())
+            }
+            LockoutState::NoActiveRadio { remaining } => format!(
+                "Lockout: {} ms left, ignored with no active radio",
+                remaining.as_millis()
+            ),
+        });
+        lines.push(match self.outgoing {
+            None => "Outgoing: no radio was active".to_string(),
+            Some(out) => {
+                let mut line = format!("Outgoing: {}", name(out.handle));
+                line.push_str(if out.ptt {
+                    " was transmitting"
+                } else {
+                    " was receiving"
+                });
+                if out.stale {
+                    line.push_str(", silent past the stale threshold");
+                }
+                match out.since_tuned {
+                    Some(since) => line.push_str(&format!(
+                        ", last tuned {:.1} s earlier",
+                        since.as_secs_f64()
+                    )),
+                    None => line.push_str(", never tuned"),
+                }
+                line.push_str("; no priority order, first trigger wins");
+                line
+            }
+        });
+        lines
+    }
+}
+
+/// The most recent switch records
+#[derive(Debug)]
+pub struct SwitchAuditLog {
+    records: VecDeque<SwitchRecord>,
+    capacity: usize,
+    next_id: u64,
+}
+
+impl Default for SwitchAuditLog {
+    fn default() -> Self {
+        Self::new(AUDIT_LOG_CAPACITY)
+    }
+}
+
+impl SwitchAuditLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity.min(AUDIT_LOG_CAPACITY)),
+            capacity: capacity.max(1),
+            next_id: 1,
+        }
+    }
+
+    /// Number a record and keep it, dropping the oldest when full
+    pub fn push(&mut self, mut record: SwitchRecord) -> &SwitchRecord {
+        record.id = self.next_id;
+        self.next_id += 1;
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+        self.records.back().expect("just pushed")
+    }
+
+    /// Keep at most `capacity` records, dropping the oldest
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let excess = self.records.len().saturating_sub(self.capacity);
+        self.records.drain(..excess);
+    }
+
+    /// Records, oldest first
+    pub fn records(&self) -> impl Iterator<Item = &SwitchRecord> {
+        self.records.iter()
+    }
+
+    /// The last `limit` records, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<SwitchRecord> {
+        let skip = self.records.len().saturating_sub(limit);
+        self.records.iter().skip(skip).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(to: u32) -> SwitchRecord {
+        SwitchRecord {
+            id: 0,
+            timestamp: SystemTime::UNIX_EPOCH,
+            from: None,
+            to: RadioHandle(to),
+            mode: SwitchingMode::FrequencyTriggered,
+            trigger: SwitchTrigger::Manual,
+            rule: SwitchRule::Selected,
+            lockout: LockoutState::NeverArmed,
+            outgoing: None,
+        }
+    }
+
+    #[test]
+    fn test_log_is_bounded_and_numbered() {
+        let mut log = SwitchAuditLog::new(2);
+        for to in 1..=3 {
+            log.push(record(to));
+        }
+        let kept: Vec<_> = log.records().map(|r| (r.id, r.to.0)).collect();
+        assert_eq!(kept, vec![(2, 2), (3, 3)]);
+        assert_eq!(log.recent(1)[0].id, 3);
+    }
+}
@@ -0,0 +1,186 @@
+//! Confirmation of manual switches away from a transmitting radio
+//!
+//! Selecting another radio mid-over would move the amplifier off the radio
+//! that is on the air. With confirmation on, such a selection doesn't
+//! happen straight away: the actor emits
+//! [`MuxEvent::SwitchPendingConfirmation`](crate::MuxEvent::SwitchPendingConfirmation)
+//! with a token, and the switch completes only if
+//! [`MuxActorCommand::ConfirmSwitch`](crate::MuxActorCommand::ConfirmSwitch)
+//! confirms that token before [`SWITCH_CONFIRM_TIMEOUT`] runs out. Every
+//! request ends with
+//! [`MuxEvent::SwitchConfirmationClosed`](crate::MuxEvent::SwitchConfirmationClosed).
+
+use std::time::Duration;
+
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// How long a pending switch waits to be confirmed
+pub const SWITCH_CONFIRM_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How a pending switch ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwitchConfirmOutcome {
+    /// Confirmed: the switch went ahead
+    Confirmed,
+    /// Declined: the transmitting radio stays active
+    Declined,
+    /// Not answered in time
+    Expired,
+    /// Replaced by a newer selection, or confirmation was turned off
+    Superseded,
+}
+
+/// A manual switch waiting for confirmation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingSwitch {
+    /// Identifies this request in the confirmation
+    pub token: u64,
+    /// Radio that was selected
+    pub requested: RadioHandle,
+    /// Radio that was transmitting at the time
+    pub current: RadioHandle,
+    /// When it expires unanswered
+    pub expires: Instant,
+}
+
+/// At most one pending switch, and whether switches need confirming at all
+#[derive(Debug)]
+pub struct SwitchConfirmation {
+    enabled: bool,
+    next_token: u64,
+    pending: Option<PendingSwitch>,
+}
+
+impl Default for SwitchConfirmation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SwitchConfirmation {
+    /// Confirmation off, nothing pending
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            next_token: 1,
+            pending: None,
+        }
+    }
+
+    /// Whether manual switches during TX need confirming
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turn confirmation on or off; returns a pending switch that turning it off dropped
+    pub fn set_enabled(&mut self, enabled: bool) -> Option<PendingSwitch> {
+        self.enabled = enabled;
+        if enabled {
+            None
+        } else {
+            self.pending.take()
+        }
+    }
+
+    /// The switch waiting for confirmation, if any
+    pub fn pending(&self) -> Option<&PendingSwitch> {
+        self.pending.as_ref()
+    }
+
+    /// Hold a switch from `current` to `requested` for confirmation
+    ///
+    /// Returns the new request, and the one it replaced if there was one.
+    pub fn request(
+        &mut self,
+        requested: RadioHandle,
+        current: RadioHandle,
+        now: Instant,
+    ) -> (PendingSwitch, Option<PendingSwitch>) {
+        let pending = PendingSwitch {
+            token: self.next_token,
+            requested,
+            current,
+            expires: now + SWITCH_CONFIRM_TIMEOUT,
+        };
+        self.next_token += 1;
+        (pending, self.pending.replace(pending))
+    }
+
+    /// Answer the request with `token`; returns it if it was still pending
+    pub fn answer(&mut self, token: u64, now: Instant) -> Option<PendingSwitch> {
+        let pending = self
+            .pending
+            .filter(|p| p.token == token && now < p.expires)?;
+        self.pending = None;
+        Some(pending)
+    }
+
+    /// When the pending switch expires
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.pending.map(|p| p.expires)
+    }
+
+    /// Take the pending switch if it has expired
+    pub fn poll(&mut self, now: Instant) -> Option<PendingSwitch> {
+        if self.pending.is_some_and(|p| now >= p.expires) {
+            self.pending.take()
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer_matches_token() {
+        let mut confirm = SwitchConfirmation::new();
+        let now = Instant::now();
+        let (pending, replaced) = confirm.request(RadioHandle(2), RadioHandle(1), now);
+        assert!(replaced.is_none());
+
+        assert_eq!(confirm.answer(pending.token + 1, now), None);
+        assert_eq!(confirm.answer(pending.token, now), Some(pending));
+        assert_eq!(confirm.answer(pending.token, now), None);
+    }
+
+    #[test]
+    fn test_newer_request_supersedes() {
+        let mut confirm = SwitchConfirmation::new();
+        let now = Instant::now();
+        let (first, _) = confirm.request(RadioHandle(2), RadioHandle(1), now);
+        let (second, replaced) = confirm.request(RadioHandle(3), RadioHandle(1), now);
+        assert_eq!(replaced, Some(first));
+        assert_ne!(first.token, second.token);
+        assert_eq!(confirm.answer(first.token, now), None);
+        assert_eq!(confirm.pending(), Some(&second));
+    }
+
+    #[test]
+    fn test_expiry() {
+        let mut confirm = SwitchConfirmation::new();
+        let now = Instant::now();
+        let (pending, _) = confirm.request(RadioHandle(2), RadioHandle(1), now);
+        assert_eq!(confirm.next_deadline(), Some(now + SWITCH_CONFIRM_TIMEOUT));
+        assert_eq!(confirm.poll(now), None);
+
+        let later = now + SWITCH_CONFIRM_TIMEOUT;
+        assert_eq!(confirm.answer(pending.token, later), None);
+        assert_eq!(confirm.poll(later), Some(pending));
+        assert_eq!(confirm.next_deadline(), None);
+    }
+
+    #[test]
+    fn test_disabling_drops_pending() {
+        let mut confirm = SwitchConfirmation::new();
+        confirm.set_enabled(true);
+        let (pending, _) = confirm.request(RadioHandle(2), RadioHandle(1), Instant::now());
+        assert_eq!(confirm.set_enabled(false), Some(pending));
+        assert!(!confirm.is_enabled());
+        assert!(confirm.pending().is_none());
+    }
+}
@@ -0,0 +1,336 @@
+//! Initial sync plans
+//!
+//! Describes, per protocol and model, what the mux asks a radio right after
+//! connecting: identify it, read its frequency and mode, and switch on
+//! auto-information. A plan is an ordered list of [`SyncStep`]s; the shared
+//! executor ([`AsyncRadioConnection::run_sync_plan`]) writes each request,
+//! waits for the expected response where there is one, and reports progress
+//! as [`MuxEvent::RadioSyncProgress`] events.
+//!
+//! Once a radio identifies itself, [`SyncPlan::add_model_steps`] appends the
+//! queries that only make sense for that model.
+//!
+//! [`AsyncRadioConnection::run_sync_plan`]: crate::AsyncRadioConnection::run_sync_plan
+//! [`MuxEvent::RadioSyncProgress`]: crate::MuxEvent::RadioSyncProgress
+
+use std::time::Duration;
+
+use cat_protocol::{OperatingMode, Protocol, RadioModel, RadioRequest, RadioResponse};
+
+/// How long to wait for a step's expected response
+pub const DEFAULT_STEP_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Response a sync step waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Expect {
+    /// An identification the model database recognizes (or at least parses)
+    Id,
+    /// Anything carrying the current frequency
+    Frequency,
+    /// Anything carrying the current mode
+    Mode,
+    /// Output power setting
+    TxPower,
+    /// Keyer speed
+    KeyerSpeed,
+}
+
+impl Expect {
+    /// Whether a parsed response satisfies this expectation
+    ///
+    /// [`Expect::Id`] is matched against raw bytes by the executor instead, since
+    /// identification needs the protocol's ID parser rather than the codec.
+    pub fn matches(&self, response: &RadioResponse) -> bool {
+        match self {
+            Expect::Id => matches!(response, RadioResponse::Id { .. }),
+            Expect::Frequency => response.frequency().is_some(),
+            Expect::Mode => response.mode().is_some(),
+            Expect::TxPower => response.tx_power().is_some(),
+            Expect::KeyerSpeed => response.keyer_speed().is_some(),
+        }
+    }
+}
+
+/// One request in a sync plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncStep {
+    /// Short description for progress reporting
+    pub label: &'static str,
+    /// Request to send
+    pub request: RadioRequest,
+    /// Response to wait for (None = fire and forget)
+    pub expect: Option<Expect>,
+    /// Whether the plan carries on if this step gets no answer
+    pub optional: bool,
+}
+
+impl SyncStep {
+    /// A query that must be answered
+    pub fn query(label: &'static str, request: RadioRequest, expect: Expect) -> Self {
+        Self {
+            label,
+            request,
+            expect: Some(expect),
+            optional: false,
+        }
+    }
+
+    /// A request that gets no reply
+    pub fn send(label: &'static str, request: RadioRequest) -> Self {
+        Self {
+            label,
+            request,
+            expect: None,
+            optional: false,
+        }
+    }
+
+    /// Mark the step as allowed to go unanswered
+    pub fn optional(mut self) -> Self {
+        self.optional = true;
+        self
+    }
+}
+
+/// Outcome of a single step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStepStatus {
+    /// Sent (and answered, if an answer was expected)
+    Done,
+    /// Could not be encoded for this protocol, or an optional step went unanswered
+    Skipped,
+    /// A required step went unanswered; the rest of the plan still runs
+    Failed,
+}
+
+/// Ordered initial sync steps for one radio
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncPlan {
+    /// Steps in execution order
+    pub steps: Vec<SyncStep>,
+    /// How long each step waits for its response
+    pub step_timeout: Duration,
+}
+
+impl SyncPlan {
+    /// Base plan for a protocol, before the model is known
+    pub fn for_protocol(protocol: Protocol) -> Self {
+        let auto_info = SyncStep::send(
+            "enable auto-info",
+            RadioRequest::SetAutoInfo { enabled: true },
+        );
+        let frequency = SyncStep::query("frequency", RadioRequest::GetFrequency, Expect::Frequency);
+        let mode = SyncStep::query("mode", RadioRequest::GetMode, Expect::Mode);
+
+        let steps = match protocol {
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio | Protocol::YaesuAscii => {
+                vec![
+                    SyncStep::query("identify", RadioRequest::GetId, Expect::Id).optional(),
+                    frequency,
+                    mode,
+                    auto_info,
+                ]
+            }
+            // Model comes from the CI-V address; no ID query
+            Protocol::IcomCIV => vec![frequency, mode, auto_info.optional()],
+            // One read returns both frequency and mode; the 5-byte reply carries no
+            // header to match against, so let the read loop pick it up
+            Protocol::Yaesu => vec![SyncStep::send(
+                "frequency and mode",
+                RadioRequest::GetFrequency,
+            )],
+        };
+
+        Self {
+            steps,
+            step_timeout: DEFAULT_STEP_TIMEOUT,
+        }
+    }
+
+    /// Append the steps that depend on the identified model
+    pub fn add_model_steps(&mut self, model: &RadioModel) {
+        let has_pc = matches!(
+            model.protocol,
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::YaesuAscii
+        );
+        if has_pc && model.capabilities.max_power_watts.is_some() {
+            self.steps.push(
+                SyncStep::query("output power", RadioRequest::GetTxPower, Expect::TxPower)
+                    .optional(),
+            );
+        }
+        // Known up front so it can be carried to the next radio (see
+        // MuxActorCommand::SetCarryKeyerSpeed)
+        if model.protocol != Protocol::Yaesu
+            && model.capabilities.modes.contains(&OperatingMode::Cw)
+        {
+            self.steps.push(
+                SyncStep::query(
+                    "keyer speed",
+                    RadioRequest::GetKeyerSpeed,
+                    Expect::KeyerSpeed,
+                )
+                .optional(),
+            );
+        }
+    }
+
+    /// Number of steps
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// Whether the plan has no steps
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cat_protocol::Frequency;
+    use cat_protocol::RadioDatabase;
+
+    #[test]
+    fn test_kenwood_plan_identifies_first() {
+        let plan = SyncPlan::for_protocol(Protocol::Kenwood);
+        assert_eq!(plan.steps[0].request, RadioRequest::GetId);
+        assert!(plan.steps[0].optional);
+        assert!(plan
+            .steps
+            .iter()
+            .any(|s| s.request == RadioRequest::SetAutoInfo { enabled: true }));
+    }
+
+    #[test]
+    fn test_model_steps_add_power_query() {
+        let mut plan = SyncPlan::for_protocol(Protocol::Kenwood);
+        let base = plan.len();
+        let model = RadioDatabase::by_kenwood_id("022").unwrap();
+        plan.add_model_steps(&model);
+        assert_eq!(plan.len(), base + 2);
+        assert_eq!(plan.steps[base].expect, Some(Expect::TxPower));
+        assert_eq!(plan.steps[base + 1].expect, Some(Expect::KeyerSpeed));
+
+        // CI-V has no PC command, but does read the keyer speed
+        let mut plan = SyncPlan::for_protocol(Protocol::IcomCIV);
+        let base = plan.len();
+        plan.add_model_steps(&RadioDatabase::by_civ_address(0x94).unwrap());
+        assert_eq!(plan.len(), base + 1);
+        assert_eq!(plan.steps[base].request, RadioRequest::GetKeyerSpeed);
+    }
+
+    #[test]
+    fn test_expect_matches_status() {
+        let status = RadioResponse::Status {
+            frequency_hz: Some(Frequency::from_hz(14_074_000)),
+            mode: None,
+            ptt: None,
+            vfo: None,
+        };
+        assert!(Expect::Frequency.matches(&status));
+        assert!(!Expect::Mode.matches(&status));
+    }
+
+    #[tokio::test]
+    async fn test_plan_runs_against_kenwood_radio() {
+        use crate::{AsyncRadioConnection, MuxClient, MuxEvent, RadioHandle};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let (ours, mut radio) = tokio::io::duplex(256);
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok(n) = radio.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                for cmd in String::from_utf8_lossy(&buf[..n]).split_terminator(';') {
+                    let reply: &[u8] = match cmd {
+                        "ID" => b"ID022;",
+                        "FA" => b"FA00014074000;",
+                        "MD" => b"MD2;",
+                        "PC" => b"PC100;",
+                        "KS" => b"KS025;",
+                        _ => continue,
+                    };
+                    let _ = radio.write_all(reply).await;
+                }
+            }
+        });
+
+        let (mux, _mux_rx, mut event_rx) = MuxClient::channel(64);
+        let mut conn =
+            AsyncRadioConnection::new(RadioHandle(1), "test".into(), ours, Protocol::Kenwood, &mux);
+
+        let name = conn
+            .run_sync_plan(SyncPlan::for_protocol(Protocol::Kenwood))
+            .await;
+        assert!(name.is_some());
+
+        let mut progress = Vec::new();
+        while let Ok(MuxEvent::RadioSyncProgress {
+            step,
+            total,
+            status,
+            ..
+        }) = event_rx.try_recv()
+        {
+            progress.push((step, total, status));
+        }
+        // Identification added the output power and keyer speed queries
+        assert_eq!(progress.len(), 6);
+        assert!(progress
+            .iter()
+            .all(|&(_, total, status)| total == 6 && status == SyncStepStatus::Done));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_step_still_enables_auto_info() {
+        use crate::{AsyncRadioConnection, MuxClient, MuxEvent, RadioHandle};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::sync::mpsc;
+
+        let (ours, mut radio) = tokio::io::duplex(256);
+        let (sent_tx, mut sent_rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+            while let Ok(n) = radio.read(&mut buf).await {
+                if n == 0 {
+                    break;
+                }
+                for cmd in String::from_utf8_lossy(&buf[..n]).split_terminator(';') {
+                    let _ = sent_tx.send(cmd.to_string());
+                    // Never answers the mode query
+                    let reply: &[u8] = match cmd {
+                        "ID" => b"ID022;",
+                        "FA" => b"FA00014074000;",
+                        _ => continue,
+                    };
+                    let _ = radio.write_all(reply).await;
+                }
+            }
+        });
+
+        let (mux, _mux_rx, mut event_rx) = MuxClient::channel(64);
+        let mut conn =
+            AsyncRadioConnection::new(RadioHandle(1), "test".into(), ours, Protocol::Kenwood, &mux);
+
+        let mut plan = SyncPlan::for_protocol(Protocol::Kenwood);
+        plan.step_timeout = Duration::from_millis(20);
+        conn.run_sync_plan(plan).await;
+
+        let mut statuses = Vec::new();
+        while let Ok(MuxEvent::RadioSyncProgress { label, status, .. }) = event_rx.try_recv() {
+            statuses.push((label, status));
+        }
+        assert!(statuses.contains(&("mode".to_string(), SyncStepStatus::Failed)));
+        assert!(statuses.contains(&("enable auto-info".to_string(), SyncStepStatus::Done)));
+
+        let mut sent = Vec::new();
+        while let Ok(cmd) = sent_rx.try_recv() {
+            sent.push(cmd);
+        }
+        assert!(sent.contains(&"AI2".to_string()));
+    }
+}
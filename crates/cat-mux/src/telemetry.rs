@@ -0,0 +1,156 @@
+//! Meter telemetry throttling
+//!
+//! Radios polled for meters (or streaming them) can report many readings a
+//! second. The actor passes each reading through a [`MeterThrottle`] and only
+//! emits a [`crate::MuxEvent::RadioMeter`] once per [`METER_EVENT_INTERVAL`]
+//! for each radio and meter, carrying the highest reading seen since the last
+//! event so short peaks still reach the UI.
+//!
+//! Every reading also goes into a [`MeterCache`], so amplifiers that display
+//! the exciter's meters can have their polls answered with the active radio's
+//! latest level (see [`crate::AmpBypassConfig::forwards_meters`]).
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use cat_protocol::MeterKind;
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// Minimum spacing between meter events for one radio and meter
+pub const METER_EVENT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a reading is passed on to the amp before it counts as gone
+pub const METER_READING_MAX_AGE: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy)]
+struct MeterSlot {
+    last_emit: Instant,
+    peak: u8,
+}
+
+/// Rate limiter for meter readings
+#[derive(Debug, Default)]
+pub struct MeterThrottle {
+    slots: HashMap<(RadioHandle, MeterKind), MeterSlot>,
+}
+
+impl MeterThrottle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Offer a reading; returns the level to emit if an event is due
+    pub fn offer(
+        &mut self,
+        handle: RadioHandle,
+        meter: MeterKind,
+        level: u8,
+        now: Instant,
+    ) -> Option<u8> {
+        match self.slots.get_mut(&(handle, meter)) {
+            Some(slot) if now.duration_since(slot.last_emit) < METER_EVENT_INTERVAL => {
+                slot.peak = slot.peak.max(level);
+                None
+            }
+            Some(slot) => {
+                let emit = slot.peak.max(level);
+                *slot = MeterSlot {
+                    last_emit: now,
+                    peak: 0,
+                };
+                Some(emit)
+            }
+            None => {
+                self.slots.insert(
+                    (handle, meter),
+                    MeterSlot {
+                        last_emit: now,
+                        peak: 0,
+                    },
+                );
+                Some(level)
+            }
+        }
+    }
+
+    /// Forget a radio's meters (e.g. when it disconnects)
+    pub fn remove_radio(&mut self, handle: RadioHandle) {
+        self.slots.retain(|(h, _), _| *h != handle);
+    }
+}
+
+/// Latest reading of each radio's meters
+#[derive(Debug, Default)]
+pub struct MeterCache {
+    readings: HashMap<(RadioHandle, MeterKind), (u8, Instant)>,
+}
+
+impl MeterCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Note a reading
+    pub fn record(&mut self, handle: RadioHandle, meter: MeterKind, level: u8, now: Instant) {
+        self.readings.insert((handle, meter), (level, now));
+    }
+
+    /// The radio's last reading of `meter`, unless it is older than [`METER_READING_MAX_AGE`]
+    pub fn latest(&self, handle: RadioHandle, meter: MeterKind, now: Instant) -> Option<u8> {
+        self.readings
+            .get(&(handle, meter))
+            .filter(|(_, at)| now.duration_since(*at) <= METER_READING_MAX_AGE)
+            .map(|(level, _)| *level)
+    }
+
+    /// Forget a radio's readings (e.g. when it disconnects)
+    pub fn remove_radio(&mut self, handle: RadioHandle) {
+        self.readings.retain(|(h, _), _| *h != handle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_throttle_carries_peak() {
+        let mut throttle = MeterThrottle::new();
+        let radio = RadioHandle(1);
+        let t0 = Instant::now();
+
+        assert_eq!(throttle.offer(radio, MeterKind::Signal, 40, t0), Some(40));
+        let t = t0 + Duration::from_millis(20);
+        assert_eq!(throttle.offer(radio, MeterKind::Signal, 200, t), None);
+        // Other meters are throttled independently
+        assert_eq!(throttle.offer(radio, MeterKind::Swr, 10, t), Some(10));
+        let t = t0 + Duration::from_millis(50);
+        assert_eq!(throttle.offer(radio, MeterKind::Signal, 60, t), None);
+
+        let t = t0 + METER_EVENT_INTERVAL;
+        assert_eq!(throttle.offer(radio, MeterKind::Signal, 50, t), Some(200));
+        let t = t + METER_EVENT_INTERVAL;
+        assert_eq!(throttle.offer(radio, MeterKind::Signal, 30, t), Some(30));
+    }
+
+    #[test]
+    fn test_cache_keeps_latest_fresh_reading() {
+        let mut cache = MeterCache::new();
+        let radio = RadioHandle(1);
+        let t0 = Instant::now();
+
+        cache.record(radio, MeterKind::Signal, 120, t0);
+        cache.record(radio, MeterKind::Signal, 90, t0);
+        assert_eq!(cache.latest(radio, MeterKind::Signal, t0), Some(90));
+        assert_eq!(cache.latest(radio, MeterKind::Power, t0), None);
+        assert_eq!(cache.latest(RadioHandle(2), MeterKind::Signal, t0), None);
+
+        let later = t0 + METER_READING_MAX_AGE + Duration::from_millis(1);
+        assert_eq!(cache.latest(radio, MeterKind::Signal, later), None);
+
+        cache.remove_radio(radio);
+        assert_eq!(cache.latest(radio, MeterKind::Signal, t0), None);
+    }
+}
@@ -0,0 +1,797 @@
+//! Compact on-disk traffic log
+//!
+//! Always-on logging writes every frame to disk, so the format has to stay
+//! small over days of operation and still load quickly. Frames are gathered
+//! into blocks of about [`BLOCK_TARGET_BYTES`]. Each block names its channels
+//! once in a dictionary, stores timestamps as varint deltas and is
+//! compressed with zstd. A sidecar index (`<log>.idx`) lists every block's
+//! time span and offset, so [`TrafficLogReader::read_from`] seeks straight
+//! to a moment without decompressing anything before it.
+//!
+//! Blocks are self-contained. A block cut short by a crash is dropped when
+//! the log is reopened, and a missing or stale index is rebuilt from the
+//! block headers.
+//!
+//! ```text
+//! file:   "CATLOG\0\x01" block*
+//! block:  "BLK1" raw_len:u32 packed_len:u32 count:u32 first_us:u64 last_us:u64
+//!         zstd(payload)
+//! payload: channels:varint (len:varint utf8)*
+//!          (dt_us:zigzag-varint channel:varint flags:u8 len:varint data)*
+//! index:  "CATIDX\0\x01" (offset:u64 first_us:u64 last_us:u64 count:u32)*
+//! ```
+//!
+//! Integers in headers are little-endian. `first_us` and `last_us` are the
+//! earliest and latest timestamps in the block. `dt_us` is relative to the
+//! previous record, or to `first_us` for the first; timestamps reported out
+//! of order make it negative. `flags` holds the direction in bit 0 and the
+//! protocol in bits 1-3.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cat_protocol::Protocol;
+
+use crate::analyzer::AnalyzerDirection;
+use crate::events::MuxEvent;
+use crate::state::RadioHandle;
+
+/// File extension for traffic logs
+pub const LOG_EXTENSION: &str = "catlog";
+
+/// Uncompressed payload size at which a block is written out
+pub const BLOCK_TARGET_BYTES: usize = 64 * 1024;
+
+const FILE_MAGIC: &[u8; 8] = b"CATLOG\0\x01";
+const INDEX_MAGIC: &[u8; 8] = b"CATIDX\0\x01";
+const BLOCK_MAGIC: &[u8; 4] = b"BLK1";
+const BLOCK_HEADER_LEN: u64 = 32;
+const INDEX_ENTRY_LEN: usize = 28;
+
+/// Largest block payload accepted when reading (guards against corrupt headers)
+const MAX_BLOCK_BYTES: u32 = 64 * 1024 * 1024;
+
+/// zstd level: fast enough to run on every flush, most of the gain of higher levels
+const ZSTD_LEVEL: i32 = 3;
+
+/// One logged frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrafficRecord {
+    /// When the frame was read or written
+    pub timestamp: SystemTime,
+    /// Radio name, "Amplifier", "Amplifier (dry run)", "Shadow amplifier" or "Analyzer"
+    pub channel: String,
+    /// Sent by the mux (true) or received by it (false)
+    pub outgoing: bool,
+    /// Protocol the frame is in, if known
+    pub protocol: Option<Protocol>,
+    /// Raw frame bytes
+    pub data: Vec<u8>,
+}
+
+impl TrafficRecord {
+    /// The record for a traffic event; `None` for other events
+    ///
+    /// `radio_name` names the radio a frame came from or went to.
+    pub fn from_event(
+        event: &MuxEvent,
+        radio_name: impl Fn(RadioHandle) -> String,
+    ) -> Option<Self> {
+        let (channel, outgoing, data, protocol, timestamp) = match event {
+            MuxEvent::RadioDataIn {
+                handle,
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => (radio_name(*handle), false, data, protocol, timestamp),
+            MuxEvent::RadioDataOut {
+                handle,
+                data,
+                protocol,
+                timestamp,
+            } => (radio_name(*handle), true, data, protocol, timestamp),
+            MuxEvent::AmpDataOut {
+                data,
+                protocol,
+                suppressed,
+                timestamp,
+                ..
+            } => {
+                let channel = if *suppressed {
+                    "Amplifier (dry run)"
+                } else {
+                    "Amplifier"
+                };
+                (channel.to_string(), true, data, protocol, timestamp)
+            }
+            MuxEvent::AmpDataIn {
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => ("Amplifier".to_string(), false, data, protocol, timestamp),
+            MuxEvent::ShadowAmpDataOut {
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => (
+                "Shadow amplifier".to_string(),
+                true,
+                data,
+                protocol,
+                timestamp,
+            ),
+            MuxEvent::ShadowAmpDataIn {
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => (
+                "Shadow amplifier".to_string(),
+                false,
+                data,
+                protocol,
+                timestamp,
+            ),
+            MuxEvent::AnalyzerData {
+                direction,
+                data,
+                protocol,
+                timestamp,
+                ..
+            } => (
+                "Analyzer".to_string(),
+                *direction == AnalyzerDirection::LoggerToRadio,
+                data,
+                protocol,
+                timestamp,
+            ),
+            _ => return None,
+        };
+        Some(Self {
+            timestamp: *timestamp,
+            channel,
+            outgoing,
+            protocol: Some(*protocol),
+            data: data.clone(),
+        })
+    }
+}
+
+/// Where a block sits in the log and the time it covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockInfo {
+    /// Offset of the block header in the log file
+    pub offset: u64,
+    /// Earliest timestamp in the block (µs since the Unix epoch)
+    pub first_us: u64,
+    /// Latest timestamp in the block (µs since the Unix epoch)
+    pub last_us: u64,
+    /// Records in the block
+    pub count: u32,
+}
+
+impl BlockInfo {
+    fn encode(&self) -> [u8; INDEX_ENTRY_LEN] {
+        let mut out = [0u8; INDEX_ENTRY_LEN];
+        out[0..8].copy_from_slice(&self.offset.to_le_bytes());
+        out[8..16].copy_from_slice(&self.first_us.to_le_bytes());
+        out[16..24].copy_from_slice(&self.last_us.to_le_bytes());
+        out[24..28].copy_from_slice(&self.count.to_le_bytes());
+        out
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let u64_at = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+        Self {
+            offset: u64_at(0),
+            first_us: u64_at(8),
+            last_us: u64_at(16),
+            count: u32::from_le_bytes(bytes[24..28].try_into().unwrap()),
+        }
+    }
+}
+
+/// Path of the index that goes with a log
+pub fn index_path(log: &Path) -> PathBuf {
+    let mut name = log.as_os_str().to_os_string();
+    name.push(".idx");
+    PathBuf::from(name)
+}
+
+fn to_micros(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_micros() as u64)
+        .unwrap_or(0)
+}
+
+fn from_micros(us: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_micros(us)
+}
+
+fn protocol_code(protocol: Option<Protocol>) -> u8 {
+    match protocol {
+        None => 0,
+        Some(Protocol::Yaesu) => 1,
+        Some(Protocol::YaesuAscii) => 2,
+        Some(Protocol::IcomCIV) => 3,
+        Some(Protocol::Kenwood) => 4,
+        Some(Protocol::Elecraft) => 5,
+        Some(Protocol::FlexRadio) => 6,
+    }
+}
+
+fn protocol_from_code(code: u8) -> Option<Protocol> {
+    match code {
+        1 => Some(Protocol::Yaesu),
+        2 => Some(Protocol::YaesuAscii),
+        3 => Some(Protocol::IcomCIV),
+        4 => Some(Protocol::Kenwood),
+        5 => Some(Protocol::Elecraft),
+        6 => Some(Protocol::FlexRadio),
+        _ => None,
+    }
+}
+
+fn put_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn put_signed_varint(out: &mut Vec<u8>, value: i64) {
+    put_varint(out, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Cursor over a decompressed block payload
+struct Payload<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Payload<'_> {
+    fn byte(&mut self) -> io::Result<u8> {
+        let b = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| invalid("block payload ends early"))?;
+        self.pos += 1;
+        Ok(b)
+    }
+
+    fn varint(&mut self) -> io::Result<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let b = self.byte()?;
+            value |= u64::from(b & 0x7F) << shift;
+            if b & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid("varint too long"))
+    }
+
+    fn signed_varint(&mut self) -> io::Result<i64> {
+        let v = self.varint()?;
+        Ok(((v >> 1) as i64) ^ -((v & 1) as i64))
+    }
+
+    fn bytes(&mut self, len: usize) -> io::Result<&[u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| invalid("block payload ends early"))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+}
+
+/// Records collected for the next block
+#[derive(Debug, Default)]
+struct PendingBlock {
+    channels: Vec<String>,
+    channel_ids: HashMap<String, u64>,
+    /// Encoded records, without the first record's delta
+    records: Vec<u8>,
+    count: u32,
+    start_us: u64,
+    first_us: u64,
+    last_us: u64,
+    prev_us: u64,
+}
+
+impl PendingBlock {
+    fn push(&mut self, record: &TrafficRecord) {
+        let us = to_micros(record.timestamp);
+        if self.count == 0 {
+            self.start_us = us;
+            self.first_us = us;
+            self.last_us = us;
+        } else {
+            put_signed_varint(&mut self.records, us as i64 - self.prev_us as i64);
+        }
+        self.first_us = self.first_us.min(us);
+        self.last_us = self.last_us.max(us);
+
+        let channel = match self.channel_ids.get(&record.channel) {
+            Some(&id) => id,
+            None => {
+                let id = self.channels.len() as u64;
+                self.channels.push(record.channel.clone());
+                self.channel_ids.insert(record.channel.clone(), id);
+                id
+            }
+        };
+
+        put_varint(&mut self.records, channel);
+        self.records
+            .push(u8::from(record.outgoing) | (protocol_code(record.protocol) << 1));
+        put_varint(&mut self.records, record.data.len() as u64);
+        self.records.extend_from_slice(&record.data);
+        self.prev_us = us;
+        self.count += 1;
+    }
+
+    /// Uncompressed payload: the channel dictionary, then the records
+    fn payload(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.records.len() + 64);
+        put_varint(&mut out, self.channels.len() as u64);
+        for name in &self.channels {
+            put_varint(&mut out, name.len() as u64);
+            out.extend_from_slice(name.as_bytes());
+        }
+        // Only now is the block's earliest timestamp known
+        put_signed_varint(&mut out, self.start_us as i64 - self.first_us as i64);
+        out.extend_from_slice(&self.records);
+        out
+    }
+
+    fn raw_len(&self) -> usize {
+        self.records.len() + self.channels.iter().map(|c| c.len() + 2).sum::<usize>()
+    }
+}
+
+/// Appends records to a traffic log
+///
+/// Records are held in memory until [`BLOCK_TARGET_BYTES`] have built up or
+/// [`TrafficLogWriter::flush`] is called; call it periodically so a crash
+/// loses at most one flush interval.
+pub struct TrafficLogWriter {
+    path: PathBuf,
+    file: File,
+    index: File,
+    pending: PendingBlock,
+    /// Bytes written to the log, including the header
+    len: u64,
+}
+
+impl TrafficLogWriter {
+    /// Open a log for appending, creating it if needed
+    ///
+    /// A block torn by a crash is cut off and the index is rebuilt if it
+    /// doesn't match the log.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+
+        let blocks = if file.metadata()?.len() == 0 {
+            file.write_all(FILE_MAGIC)?;
+            Vec::new()
+        } else {
+            let (blocks, valid_len) = load_blocks(&mut file, &path)?;
+            if valid_len < file.metadata()?.len() {
+                tracing::warn!(
+                    "Traffic log {} ends in a partial block, truncating",
+                    path.display()
+                );
+                file.set_len(valid_len)?;
+            }
+            blocks
+        };
+        let len = file.seek(SeekFrom::End(0))?;
+
+        let mut index = File::create(index_path(&path))?;
+        let mut bytes = INDEX_MAGIC.to_vec();
+        for block in &blocks {
+            bytes.extend_from_slice(&block.encode());
+        }
+        index.write_all(&bytes)?;
+
+        Ok(Self {
+            path,
+            file,
+            index,
+            pending: PendingBlock::default(),
+            len,
+        })
+    }
+
+    /// The log's path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Bytes on disk, not counting records waiting to be flushed
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether nothing has been logged yet
+    pub fn is_empty(&self) -> bool {
+        self.len <= FILE_MAGIC.len() as u64 && self.pending.count == 0
+    }
+
+    /// Add a record; writes a block once enough have built up
+    pub fn append(&mut self, record: &TrafficRecord) -> io::Result<()> {
+        self.pending.push(record);
+        if self.pending.raw_len() >= BLOCK_TARGET_BYTES {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write out whatever records are waiting
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.pending.count == 0 {
+            return Ok(());
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let payload = pending.payload();
+        let packed = zstd::bulk::compress(&payload, ZSTD_LEVEL)?;
+
+        let mut block = Vec::with_capacity(BLOCK_HEADER_LEN as usize + packed.len());
+        block.extend_from_slice(BLOCK_MAGIC);
+        block.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        block.extend_from_slice(&(packed.len() as u32).to_le_bytes());
+        block.extend_from_slice(&pending.count.to_le_bytes());
+        block.extend_from_slice(&pending.first_us.to_le_bytes());
+        block.extend_from_slice(&pending.last_us.to_le_bytes());
+        block.extend_from_slice(&packed);
+
+        let info = BlockInfo {
+            offset: self.len,
+            first_us: pending.first_us,
+            last_us: pending.last_us,
+            count: pending.count,
+        };
+        self.file.write_all(&block)?;
+        self.file.flush()?;
+        self.len += block.len() as u64;
+        self.index.write_all(&info.encode())?;
+        self.index.flush()
+    }
+}
+
+impl Drop for TrafficLogWriter {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::warn!("Failed to flush traffic log {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Block header fields
+struct BlockHeader {
+    raw_len: u32,
+    packed_len: u32,
+    count: u32,
+    first_us: u64,
+    last_us: u64,
+}
+
+fn read_block_header(file: &mut File, offset: u64) -> io::Result<Option<BlockHeader>> {
+    let mut header = [0u8; BLOCK_HEADER_LEN as usize];
+    file.seek(SeekFrom::Start(offset))?;
+    match file.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    if &header[0..4] != BLOCK_MAGIC {
+        return Ok(None);
+    }
+    let u32_at = |i: usize| u32::from_le_bytes(header[i..i + 4].try_into().unwrap());
+    let header = BlockHeader {
+        raw_len: u32_at(4),
+        packed_len: u32_at(8),
+        count: u32_at(12),
+        first_us: u64::from_le_bytes(header[16..24].try_into().unwrap()),
+        last_us: u64::from_le_bytes(header[24..32].try_into().unwrap()),
+    };
+    if header.raw_len > MAX_BLOCK_BYTES || header.packed_len > MAX_BLOCK_BYTES {
+        return Ok(None);
+    }
+    Ok(Some(header))
+}
+
+/// Walk block headers from the end of `known`; returns every complete block
+/// and the length of the log they make up
+fn scan_blocks(file: &mut File, known: &[BlockInfo]) -> io::Result<(Vec<BlockInfo>, u64)> {
+    let file_len = file.metadata()?.len();
+    let mut magic = [0u8; 8];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut magic)
+        .map_err(|_| invalid("not a traffic log"))?;
+    if &magic != FILE_MAGIC {
+        return Err(invalid("not a traffic log"));
+    }
+
+    let mut blocks = known.to_vec();
+    let mut offset = FILE_MAGIC.len() as u64;
+    if let Some(last) = known.last() {
+        let header = read_block_header(file, last.offset)?
+            .ok_or_else(|| invalid("index does not match log"))?;
+        offset = last.offset + BLOCK_HEADER_LEN + u64::from(header.packed_len);
+        if offset > file_len {
+            return Err(invalid("index does not match log"));
+        }
+    }
+
+    while let Some(header) = read_block_header(file, offset)? {
+        let end = offset + BLOCK_HEADER_LEN + u64::from(header.packed_len);
+        if end > file_len {
+            break;
+        }
+        blocks.push(BlockInfo {
+            offset,
+            first_us: header.first_us,
+            last_us: header.last_us,
+            count: header.count,
+        });
+        offset = end;
+    }
+    Ok((blocks, offset))
+}
+
+/// Blocks in a log, using its index where it is still good
+fn load_blocks(file: &mut File, path: &Path) -> io::Result<(Vec<BlockInfo>, u64)> {
+    let known = read_index(&index_path(path)).unwrap_or_default();
+    match scan_blocks(file, &known) {
+        // An index that points at the wrong place is rebuilt from scratch
+        Err(_) if !known.is_empty() => scan_blocks(file, &[]),
+        scanned => scanned,
+    }
+}
+
+fn read_block(
+    file: &mut File,
+    offset: u64,
+    header: &BlockHeader,
+) -> io::Result<Vec<TrafficRecord>> {
+    let mut packed = vec![0u8; header.packed_len as usize];
+    file.seek(SeekFrom::Start(offset + BLOCK_HEADER_LEN))?;
+    file.read_exact(&mut packed)?;
+    let payload = zstd::bulk::decompress(&packed, header.raw_len as usize)?;
+
+    let mut p = Payload {
+        bytes: &payload,
+        pos: 0,
+    };
+    let channel_count = p.varint()?;
+    let mut channels = Vec::new();
+    for _ in 0..channel_count {
+        let len = p.varint()? as usize;
+        let name = std::str::from_utf8(p.bytes(len)?).map_err(|_| invalid("bad channel name"))?;
+        channels.push(name.to_string());
+    }
+
+    let mut records = Vec::with_capacity(header.count as usize);
+    let mut us = header.first_us as i64;
+    for _ in 0..header.count {
+        us += p.signed_varint()?;
+        let channel = channels
+            .get(p.varint()? as usize)
+            .ok_or_else(|| invalid("unknown channel"))?
+            .clone();
+        let flags = p.byte()?;
+        let len = p.varint()? as usize;
+        records.push(TrafficRecord {
+            timestamp: from_micros(us.max(0) as u64),
+            channel,
+            outgoing: flags & 1 != 0,
+            protocol: protocol_from_code(flags >> 1),
+            data: p.bytes(len)?.to_vec(),
+        });
+    }
+    Ok(records)
+}
+
+/// Reads a traffic log through its index
+pub struct TrafficLogReader {
+    file: File,
+    blocks: Vec<BlockInfo>,
+}
+
+impl TrafficLogReader {
+    /// Open a log, rebuilding its index in memory if it is missing or stale
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut file = File::open(path)?;
+        let (blocks, _) = load_blocks(&mut file, path)?;
+        Ok(Self { file, blocks })
+    }
+
+    /// Blocks in the log, in file order
+    pub fn blocks(&self) -> &[BlockInfo] {
+        &self.blocks
+    }
+
+    /// Records in the log
+    pub fn record_count(&self) -> u64 {
+        self.blocks.iter().map(|b| u64::from(b.count)).sum()
+    }
+
+    /// Earliest and latest timestamps in the log
+    pub fn time_range(&self) -> Option<(SystemTime, SystemTime)> {
+        let first = self.blocks.iter().map(|b| b.first_us).min()?;
+        let last = self.blocks.iter().map(|b| b.last_us).max()?;
+        Some((from_micros(first), from_micros(last)))
+    }
+
+    /// Up to `limit` records at or after `start`, in log order
+    ///
+    /// Only blocks that can hold such records are decompressed.
+    pub fn read_from(&mut self, start: SystemTime, limit: usize) -> io::Result<Vec<TrafficRecord>> {
+        let start_us = to_micros(start);
+        // Blocks are written in time order, so the first block that reaches
+        // `start` is where reading begins
+        let first = self.blocks.partition_point(|b| b.last_us < start_us);
+        let mut out = Vec::new();
+        for i in first..self.blocks.len() {
+            if out.len() >= limit {
+                break;
+            }
+            let offset = self.blocks[i].offset;
+            let header = read_block_header(&mut self.file, offset)?
+                .ok_or_else(|| invalid("index does not match log"))?;
+            let records = read_block(&mut self.file, offset, &header)?;
+            out.extend(
+                records
+                    .into_iter()
+                    .filter(|r| to_micros(r.timestamp) >= start_us)
+                    .take(limit - out.len()),
+            );
+        }
+        Ok(out)
+    }
+}
+
+/// Index entries on disk; `None` if the index is missing or unreadable
+fn read_index(path: &Path) -> Option<Vec<BlockInfo>> {
+    let bytes = std::fs::read(path).ok()?;
+    let entries = bytes.strip_prefix(INDEX_MAGIC.as_slice())?;
+    // A torn final entry is ignored
+    Some(
+        entries
+            .chunks_exact(INDEX_ENTRY_LEN)
+            .map(BlockInfo::decode)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("catapult-log-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("traffic.catlog")
+    }
+
+    fn record(secs: u64, channel: &str, data: &[u8]) -> TrafficRecord {
+        TrafficRecord {
+            timestamp: UNIX_EPOCH + Duration::from_secs(1_700_000_000 + secs),
+            channel: channel.to_string(),
+            outgoing: channel == "Amplifier",
+            protocol: Some(Protocol::Kenwood),
+            data: data.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_and_seek() {
+        let path = temp_log("seek");
+        let records: Vec<_> = (0..5000)
+            .map(|i| {
+                let channel = if i % 2 == 0 { "IC-7300" } else { "Amplifier" };
+                record(i, channel, format!("FA{:011};", 14_000_000 + i).as_bytes())
+            })
+            .collect();
+        {
+            let mut writer = TrafficLogWriter::open(&path).unwrap();
+            for r in &records {
+                writer.append(r).unwrap();
+            }
+        }
+
+        let raw: usize = records.iter().map(|r| r.data.len() + 16).sum();
+        let on_disk = std::fs::metadata(&path).unwrap().len() as usize;
+        assert!(
+            on_disk * 5 < raw,
+            "{} bytes on disk for {} raw",
+            on_disk,
+            raw
+        );
+
+        let mut reader = TrafficLogReader::open(&path).unwrap();
+        assert!(reader.blocks().len() > 1);
+        assert_eq!(reader.record_count(), 5000);
+        assert_eq!(
+            reader.time_range(),
+            Some((records[0].timestamp, records[4999].timestamp))
+        );
+        assert_eq!(reader.read_from(UNIX_EPOCH, usize::MAX).unwrap(), records);
+
+        let from = reader.read_from(records[4000].timestamp, 3).unwrap();
+        assert_eq!(from, records[4000..4003]);
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_recovers_from_torn_block_and_lost_index() {
+        let path = temp_log("torn");
+        {
+            let mut writer = TrafficLogWriter::open(&path).unwrap();
+            writer.append(&record(0, "K3", b"FA00007074000;")).unwrap();
+            writer.flush().unwrap();
+            writer.append(&record(1, "K3", b"MD2;")).unwrap();
+        }
+        // Cut the second block short, as a crash mid-write would
+        let len = std::fs::metadata(&path).unwrap().len();
+        OpenOptions::new()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 3)
+            .unwrap();
+        std::fs::remove_file(index_path(&path)).unwrap();
+
+        let mut reader = TrafficLogReader::open(&path).unwrap();
+        let records = reader.read_from(UNIX_EPOCH, usize::MAX).unwrap();
+        assert_eq!(records, vec![record(0, "K3", b"FA00007074000;")]);
+
+        // Reopening for append drops the torn block and carries on
+        {
+            let mut writer = TrafficLogWriter::open(&path).unwrap();
+            writer.append(&record(2, "K3", b"TX;")).unwrap();
+        }
+        let mut reader = TrafficLogReader::open(&path).unwrap();
+        let records = reader.read_from(UNIX_EPOCH, usize::MAX).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].data, b"TX;");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_out_of_order_timestamps() {
+        let mut block = PendingBlock::default();
+        let late = record(10, "A", b"1");
+        let early = record(9, "A", b"2");
+        block.push(&late);
+        block.push(&early);
+        assert_eq!(block.first_us, to_micros(early.timestamp));
+        assert_eq!(block.last_us, to_micros(late.timestamp));
+    }
+}
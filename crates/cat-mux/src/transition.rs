@@ -0,0 +1,200 @@
+//! Transitional amplifier state while a switch is held off
+//!
+//! A radio can ask for the amp while switching is held off: inside the
+//! lockout after the previous switch, or while the same-band guard refuses
+//! a radio keying on the active radio's band. By default the mux just
+//! leaves the amp on the active radio. With a [`TransitionPolicy`] the amp
+//! is told things are in flux until the hold ends:
+//!
+//! - **Hold updates**: frequency and mode changes are held like a backed-up
+//!   amp's, and only the latest goes out once the hold ends. PTT is never
+//!   held: a key-up goes out together with, and after, whatever was held.
+//! - **Busy**: also answer the amp's frequency and mode queries with the
+//!   protocol's busy reply (see [`busy_reply`]), so it retries rather than
+//!   acting on a value about to change. Protocols without one fall back to
+//!   holding updates.
+
+use cat_protocol::icom::{CivCommand, CivCommandType, CONTROLLER_ADDR};
+use cat_protocol::{EncodeCommand, Protocol, RadioRequest};
+use serde::{Deserialize, Serialize};
+
+use crate::state::RadioHandle;
+
+/// What the amp is told while a switch is held off
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TransitionPolicy {
+    /// Nothing; the amp keeps following the active radio
+    #[default]
+    Off,
+    /// Hold frequency and mode updates until the hold ends
+    HoldUpdates,
+    /// Hold updates and answer frequency and mode queries with "busy"
+    Busy,
+}
+
+impl TransitionPolicy {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Off => "Off",
+            Self::HoldUpdates => "Hold updates",
+            Self::Busy => "Hold updates, answer busy",
+        }
+    }
+}
+
+/// Why a switch is held off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransitionReason {
+    /// The lockout after the previous switch hasn't expired
+    Lockout,
+    /// The radio keyed on the active radio's band and the band guard refused it
+    Transmitting,
+}
+
+impl TransitionReason {
+    /// Short description for logs and the UI
+    pub fn describe(&self) -> &'static str {
+        match self {
+            Self::Lockout => "switch lockout",
+            Self::Transmitting => "transmitting on the active radio's band",
+        }
+    }
+}
+
+/// The amp's busy reply in `protocol`, if it has one
+///
+/// Kenwood-style protocols answer a command they can't act on with `?;`
+/// and CI-V with an NG frame. Legacy Yaesu has no reply to send.
+pub fn busy_reply(protocol: Protocol) -> Option<Vec<u8>> {
+    match protocol {
+        Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio | Protocol::YaesuAscii => {
+            Some(b"?;".to_vec())
+        }
+        // Addressed like the mux's other CI-V output to the amp
+        Protocol::IcomCIV => {
+            Some(CivCommand::new(0x00, CONTROLLER_ADDR, CivCommandType::Ng).encode())
+        }
+        Protocol::Yaesu => None,
+    }
+}
+
+/// Tracks a held-off switch and what the amp is told about it
+#[derive(Debug, Default)]
+pub struct Transition {
+    policy: TransitionPolicy,
+    /// Radio waiting for the amp, and why
+    held: Option<(RadioHandle, TransitionReason)>,
+}
+
+impl Transition {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current policy
+    pub fn policy(&self) -> TransitionPolicy {
+        self.policy
+    }
+
+    /// Change policy; turning it off ends any transition
+    pub fn set_policy(&mut self, policy: TransitionPolicy) {
+        self.policy = policy;
+        if policy == TransitionPolicy::Off {
+            self.held = None;
+        }
+    }
+
+    /// The radio waiting for the amp, and why
+    pub fn held(&self) -> Option<(RadioHandle, TransitionReason)> {
+        self.held
+    }
+
+    /// Whether the amp is in the transitional state
+    pub fn is_active(&self) -> bool {
+        self.held.is_some()
+    }
+
+    /// Note a held-off switch; returns whether the amp's state changes
+    pub fn begin(&mut self, handle: RadioHandle, reason: TransitionReason) -> bool {
+        if self.policy == TransitionPolicy::Off || self.held == Some((handle, reason)) {
+            return false;
+        }
+        let was_active = self.is_active();
+        self.held = Some((handle, reason));
+        !was_active
+    }
+
+    /// End the transition; returns whether one was in progress
+    pub fn end(&mut self) -> bool {
+        self.held.take().is_some()
+    }
+
+    /// Whether frequency and mode updates to the amp are held
+    pub fn holds_updates(&self) -> bool {
+        self.is_active()
+    }
+
+    /// The reply to an amp query, if it should be answered busy
+    pub fn busy_reply_to(&self, query: &RadioRequest, protocol: Protocol) -> Option<Vec<u8>> {
+        if !self.is_active() || self.policy != TransitionPolicy::Busy {
+            return None;
+        }
+        if !matches!(query, RadioRequest::GetFrequency | RadioRequest::GetMode) {
+            return None;
+        }
+        busy_reply(protocol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_busy_replies() {
+        assert_eq!(busy_reply(Protocol::Kenwood), Some(b"?;".to_vec()));
+        assert_eq!(
+            busy_reply(Protocol::IcomCIV),
+            Some(vec![0xFE, 0xFE, 0x00, 0xE0, 0xFA, 0xFD])
+        );
+        assert_eq!(busy_reply(Protocol::Yaesu), None);
+    }
+
+    #[test]
+    fn test_transition_by_policy() {
+        let radio = RadioHandle(2);
+        let mut t = Transition::new();
+        assert!(!t.begin(radio, TransitionReason::Lockout));
+        assert!(!t.is_active());
+
+        t.set_policy(TransitionPolicy::HoldUpdates);
+        assert!(t.begin(radio, TransitionReason::Lockout));
+        // Already transitional: the amp's state doesn't change again
+        assert!(!t.begin(radio, TransitionReason::Transmitting));
+        assert_eq!(t.held(), Some((radio, TransitionReason::Transmitting)));
+        assert!(t.holds_updates());
+        assert_eq!(
+            t.busy_reply_to(&RadioRequest::GetFrequency, Protocol::Kenwood),
+            None
+        );
+
+        t.set_policy(TransitionPolicy::Busy);
+        assert_eq!(
+            t.busy_reply_to(&RadioRequest::GetFrequency, Protocol::Kenwood),
+            Some(b"?;".to_vec())
+        );
+        // PTT is always answered truthfully
+        assert_eq!(
+            t.busy_reply_to(&RadioRequest::GetPtt, Protocol::Kenwood),
+            None
+        );
+
+        assert!(t.end());
+        assert!(!t.end());
+        assert_eq!(
+            t.busy_reply_to(&RadioRequest::GetFrequency, Protocol::Kenwood),
+            None
+        );
+    }
+}
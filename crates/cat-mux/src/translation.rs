@@ -11,9 +11,13 @@ use cat_protocol::{
     kenwood::{KenwoodCodec, KenwoodCommand},
     yaesu::{YaesuCodec, YaesuCommand},
     yaesu_ascii::YaesuAsciiCommand,
-    EncodeCommand, FromRadioResponse, Protocol, ProtocolCodec, RadioResponse, ToRadioResponse,
+    EncodeCommand, Frequency, FromRadioResponse, OperatingMode, Protocol, ProtocolCodec,
+    RadioResponse, ToRadioResponse,
 };
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
 
 use crate::error::MuxError;
 
@@ -38,6 +42,40 @@ impl Default for TranslationConfig {
     }
 }
 
+/// What happens to radio frames with no translation for the amplifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TranslationStrictness {
+    /// Only what is explicitly supported reaches the amp; the rest is dropped
+    #[default]
+    Strict,
+    /// Frames the radio's codec doesn't recognise are forwarded verbatim, as
+    /// long as the radio and amp speak the same protocol family
+    Permissive,
+}
+
+impl TranslationStrictness {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Permissive => "Permissive",
+        }
+    }
+}
+
+/// Whether a frame in one protocol means the same thing in the other
+///
+/// Elecraft and FlexRadio extend the Kenwood command set, so the three share
+/// a family; every other protocol is a family of its own.
+pub fn same_protocol_family(a: Protocol, b: Protocol) -> bool {
+    let kenwood_like = |p| {
+        matches!(
+            p,
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio
+        )
+    };
+    a == b || (kenwood_like(a) && kenwood_like(b))
+}
+
 /// Protocol translator
 pub struct ProtocolTranslator {
     config: TranslationConfig,
@@ -159,8 +197,7 @@ impl ProtocolTranslator {
     fn normalize_response(&self, resp: &RadioResponse) -> RadioResponse {
         match resp {
             RadioResponse::Frequency { hz } => {
-                let rounded =
-                    (*hz / self.config.frequency_precision_hz) * self.config.frequency_precision_hz;
+                let rounded = hz.round_down(self.config.frequency_precision_hz);
                 RadioResponse::Frequency { hz: rounded }
             }
             RadioResponse::Status {
@@ -169,8 +206,7 @@ impl ProtocolTranslator {
                 ptt,
                 vfo,
             } => {
-                let rounded =
-                    (*hz / self.config.frequency_precision_hz) * self.config.frequency_precision_hz;
+                let rounded = hz.round_down(self.config.frequency_precision_hz);
                 RadioResponse::Status {
                     frequency_hz: Some(rounded),
                     mode: *mode,
@@ -304,6 +340,171 @@ pub fn translate_response(resp: &RadioResponse, protocol: Protocol) -> Result<Ve
     }
 }
 
+/// Translate a simultaneous frequency + mode change to the target protocol bytes
+///
+/// The result is a single buffer so the amplifier never acts on the new
+/// frequency paired with the previous mode. Legacy Yaesu has a combined
+/// frequency/mode report and uses it; other protocols get the frequency
+/// and mode frames back-to-back.
+pub fn translate_frequency_mode(
+    hz: Frequency,
+    mode: OperatingMode,
+    protocol: Protocol,
+) -> Result<Vec<u8>, MuxError> {
+    match protocol {
+        Protocol::Yaesu => translate_response(
+            &RadioResponse::Status {
+                frequency_hz: Some(hz),
+                mode: Some(mode),
+                ptt: None,
+                vfo: None,
+            },
+            protocol,
+        ),
+        _ => {
+            let mut out = translate_response(&RadioResponse::Frequency { hz }, protocol)?;
+            out.extend(translate_response(&RadioResponse::Mode { mode }, protocol)?);
+            Ok(out)
+        }
+    }
+}
+
+/// A consolidated set of changes for the amplifier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AmpUpdate {
+    /// New frequency
+    pub frequency_hz: Option<Frequency>,
+    /// New mode
+    pub mode: Option<OperatingMode>,
+    /// New PTT state
+    pub ptt: Option<bool>,
+}
+
+impl AmpUpdate {
+    /// Whether there is nothing to send
+    pub fn is_empty(&self) -> bool {
+        self.frequency_hz.is_none() && self.mode.is_none() && self.ptt.is_none()
+    }
+
+    /// Drop fields the amplifier already has
+    pub fn without_known(
+        self,
+        frequency_hz: Option<Frequency>,
+        mode: Option<OperatingMode>,
+        ptt: bool,
+    ) -> Self {
+        Self {
+            frequency_hz: self.frequency_hz.filter(|hz| Some(*hz) != frequency_hz),
+            mode: self.mode.filter(|m| Some(*m) != mode),
+            ptt: self.ptt.filter(|p| *p != ptt),
+        }
+    }
+
+    /// Encode the update as a single buffer in the amplifier's protocol
+    ///
+    /// Frequency and mode go out together via [`translate_frequency_mode`] when
+    /// both changed; PTT always comes last so the amp has retuned before keying.
+    pub fn encode(&self, protocol: Protocol) -> Result<Vec<u8>, MuxError> {
+        let mut out = match (self.frequency_hz, self.mode) {
+            (Some(hz), Some(mode)) => translate_frequency_mode(hz, mode, protocol)?,
+            (Some(hz), None) => translate_response(&RadioResponse::Frequency { hz }, protocol)?,
+            (None, Some(mode)) => translate_response(&RadioResponse::Mode { mode }, protocol)?,
+            (None, None) => Vec::new(),
+        };
+        if let Some(active) = self.ptt {
+            out.extend(translate_response(
+                &RadioResponse::Ptt { active },
+                protocol,
+            )?);
+        }
+        Ok(out)
+    }
+}
+
+/// Collects amplifier-bound changes over a short window
+///
+/// Radios like the Yaesu FT-991/FTDX series answer a single poll or knob turn
+/// with an `IF`, `FA` and `MD` burst. Forwarding each one makes the amp retune
+/// up to three times; instead the mux records the normalized frequency, mode
+/// and PTT from every response, and when the window (started by the first
+/// change) closes, sends the latest value of each in one write.
+#[derive(Debug, Default)]
+pub struct BurstCoalescer {
+    window: Duration,
+    pending: AmpUpdate,
+    deadline: Option<Instant>,
+}
+
+impl BurstCoalescer {
+    /// Create a coalescer (a zero window disables it)
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            ..Self::default()
+        }
+    }
+
+    /// Whether responses should be held back at all
+    pub fn is_enabled(&self) -> bool {
+        !self.window.is_zero()
+    }
+
+    /// Current window
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    /// Change the window; takes effect from the next burst
+    pub fn set_window(&mut self, window: Duration) {
+        self.window = window;
+    }
+
+    /// Record a response, returning whether it carried anything for the amp
+    pub fn record(&mut self, resp: &RadioResponse, include_ptt: bool, now: Instant) -> bool {
+        let frequency_hz = resp.frequency();
+        let mode = resp.mode();
+        let ptt = resp.ptt().filter(|_| include_ptt);
+        if frequency_hz.is_none() && mode.is_none() && ptt.is_none() {
+            return false;
+        }
+
+        // Later values in the burst win
+        self.pending.frequency_hz = frequency_hz.or(self.pending.frequency_hz);
+        self.pending.mode = mode.or(self.pending.mode);
+        self.pending.ptt = ptt.or(self.pending.ptt);
+        self.deadline.get_or_insert(now + self.window);
+        true
+    }
+
+    /// When the pending burst should be flushed
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// The update held so far
+    pub fn pending(&self) -> &AmpUpdate {
+        &self.pending
+    }
+
+    /// Hold the pending update until a later time (e.g. the amp is backed up)
+    pub fn postpone(&mut self, until: Instant) {
+        if self.deadline.is_some() {
+            self.deadline = Some(until);
+        }
+    }
+
+    /// Take the pending update and close the window
+    pub fn take(&mut self) -> AmpUpdate {
+        self.deadline = None;
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Drop anything pending (e.g. the active radio changed)
+    pub fn clear(&mut self) {
+        self.take();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -312,7 +513,9 @@ mod tests {
     fn test_translate_frequency_kenwood_to_icom() {
         let translator = ProtocolTranslator::new(Protocol::IcomCIV);
 
-        let resp = RadioResponse::Frequency { hz: 14_250_000 };
+        let resp = RadioResponse::Frequency {
+            hz: Frequency::from_hz(14_250_000),
+        };
         let result = translator.translate_response(&resp).unwrap();
 
         // Should be a valid CI-V frame
@@ -341,7 +544,9 @@ mod tests {
         let translator = ProtocolTranslator::with_config(Protocol::Kenwood, config);
 
         // 14.250.123 should round to 14.250.100
-        let resp = RadioResponse::Frequency { hz: 14_250_123 };
+        let resp = RadioResponse::Frequency {
+            hz: Frequency::from_hz(14_250_123),
+        };
         let result = translator.translate_response(&resp).unwrap();
 
         // Check the frequency in the encoded result
@@ -352,7 +557,7 @@ mod tests {
     #[test]
     fn test_should_forward() {
         assert!(should_forward_to_amp(&RadioResponse::Frequency {
-            hz: 14_250_000
+            hz: Frequency::from_hz(14_250_000)
         }));
         assert!(should_forward_to_amp(&RadioResponse::Ptt { active: true }));
         assert!(!should_forward_to_amp(&RadioResponse::Id {
@@ -362,4 +567,73 @@ mod tests {
             vfo: cat_protocol::Vfo::A
         }));
     }
+
+    #[test]
+    fn test_translate_frequency_mode_kenwood_single_buffer() {
+        let result = translate_frequency_mode(
+            Frequency::from_hz(7_074_000),
+            OperatingMode::DataU,
+            Protocol::Kenwood,
+        )
+        .unwrap();
+        assert_eq!(result, b"FA00007074000;MD10;");
+    }
+
+    #[test]
+    fn test_translate_frequency_mode_yaesu_combined_report() {
+        let result = translate_frequency_mode(
+            Frequency::from_hz(14_250_000),
+            OperatingMode::Cw,
+            Protocol::Yaesu,
+        )
+        .unwrap();
+        // One 5-byte frame carrying both frequency and mode
+        assert_eq!(result.len(), 5);
+        assert_eq!(result[4], 0x02);
+    }
+
+    #[test]
+    fn test_burst_coalescer_merges_yaesu_burst() {
+        let mut coalescer = BurstCoalescer::new(Duration::from_millis(5));
+        let t0 = Instant::now();
+
+        // IF report, then FA and MD carrying the same values
+        let burst = [
+            RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(7_074_000)),
+                mode: Some(OperatingMode::DataU),
+                ptt: Some(false),
+                vfo: None,
+            },
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+            RadioResponse::Mode {
+                mode: OperatingMode::DataU,
+            },
+        ];
+        for resp in &burst {
+            assert!(coalescer.record(resp, true, t0));
+        }
+        assert!(!coalescer.record(&RadioResponse::AutoInfo { enabled: true }, true, t0));
+        assert_eq!(
+            coalescer.next_deadline(),
+            Some(t0 + Duration::from_millis(5))
+        );
+
+        let update = coalescer.take();
+        assert_eq!(coalescer.next_deadline(), None);
+        assert_eq!(
+            update.encode(Protocol::Kenwood).unwrap(),
+            b"FA00007074000;MD10;TX0;"
+        );
+
+        // Only the changed field survives against what the amp already has
+        let update = update.without_known(
+            Some(Frequency::from_hz(7_074_000)),
+            Some(OperatingMode::Usb),
+            false,
+        );
+        assert_eq!(update.encode(Protocol::Kenwood).unwrap(), b"MD10;");
+    }
 }
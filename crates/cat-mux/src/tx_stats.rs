@@ -0,0 +1,327 @@
+//! Transmit time and duty cycle per radio and band
+//!
+//! Digital modes key a radio for long stretches at full output: FT8 runs at
+//! half duty, an RTTY contest close to continuous. [`TxStats`] keeps each
+//! radio's transmit count, cumulative transmit time and longest transmission
+//! per band, and its duty cycle over a sliding window. With the alert on, a
+//! radio transmitting more than the threshold share of the window is reported
+//! as [`crate::MuxEvent::DutyCycleAlert`] (and again when it drops back).
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::state::RadioHandle;
+
+/// Duty-cycle alert settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DutyCycleAlertConfig {
+    /// Raise alerts at all
+    pub enabled: bool,
+    /// Alert above this share of the window spent transmitting, in percent
+    pub threshold_pct: u8,
+    /// Sliding window the duty cycle is measured over, in seconds
+    pub window_secs: u32,
+}
+
+impl Default for DutyCycleAlertConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold_pct: 50,
+            window_secs: 600,
+        }
+    }
+}
+
+impl DutyCycleAlertConfig {
+    /// The measurement window
+    pub fn window(&self) -> Duration {
+        Duration::from_secs(u64::from(self.window_secs.max(1)))
+    }
+}
+
+/// A radio's duty cycle crossing the alert threshold
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DutyCycleAlert {
+    pub handle: RadioHandle,
+    /// Duty cycle over the window, 0.0 to 1.0
+    pub duty_cycle: f32,
+    /// Above the threshold (false when it has dropped back below)
+    pub over: bool,
+}
+
+/// Transmit statistics for one radio on one band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TxBandStats {
+    pub handle: RadioHandle,
+    /// Band the radio was on at key-up (None = outside the amateur bands)
+    pub band: Option<&'static str>,
+    /// Times keyed
+    pub transmissions: u64,
+    /// Time spent transmitting, including a transmission in progress
+    pub total: Duration,
+    /// Longest single transmission, including one in progress
+    pub longest: Duration,
+    /// Share of the window spent transmitting on this band, 0.0 to 1.0
+    pub duty_cycle: f32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Totals {
+    transmissions: u64,
+    total: Duration,
+    longest: Duration,
+}
+
+/// One key-down, open until the radio unkeys
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    band: Option<&'static str>,
+    start: Instant,
+    end: Option<Instant>,
+}
+
+impl Span {
+    /// Time transmitting within `from..now`
+    fn overlap(&self, from: Instant, now: Instant) -> Duration {
+        let end = self.end.unwrap_or(now);
+        end.saturating_duration_since(self.start.max(from))
+    }
+}
+
+/// Transmit time tracker for all radios
+#[derive(Debug, Default)]
+pub struct TxStats {
+    config: DutyCycleAlertConfig,
+    totals: HashMap<(RadioHandle, Option<&'static str>), Totals>,
+    /// Key-downs still inside the window, oldest first
+    spans: HashMap<RadioHandle, VecDeque<Span>>,
+    /// Radios currently over the threshold
+    alerting: HashSet<RadioHandle>,
+}
+
+impl TxStats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn config(&self) -> DutyCycleAlertConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: DutyCycleAlertConfig) {
+        self.config = config;
+    }
+
+    /// A radio keyed up (on `band`) or unkeyed
+    pub fn on_ptt(
+        &mut self,
+        handle: RadioHandle,
+        active: bool,
+        band: Option<&'static str>,
+        now: Instant,
+    ) {
+        let spans = self.spans.entry(handle).or_default();
+        let open = spans.back_mut().filter(|s| s.end.is_none());
+        match (active, open) {
+            (true, None) => {
+                spans.push_back(Span {
+                    band,
+                    start: now,
+                    end: None,
+                });
+                self.totals.entry((handle, band)).or_default().transmissions += 1;
+            }
+            (false, Some(span)) => {
+                span.end = Some(now);
+                let length = now.saturating_duration_since(span.start);
+                let totals = self.totals.entry((handle, span.band)).or_default();
+                totals.total += length;
+                totals.longest = totals.longest.max(length);
+            }
+            // Repeated reports of the same state
+            _ => {}
+        }
+    }
+
+    /// Forget a radio that has gone away
+    pub fn remove_radio(&mut self, handle: RadioHandle) {
+        self.totals.retain(|(h, _), _| *h != handle);
+        self.spans.remove(&handle);
+        self.alerting.remove(&handle);
+    }
+
+    /// Start counting again from now (a radio still keyed keeps its key-down)
+    pub fn reset(&mut self, now: Instant) {
+        self.totals.clear();
+        for (handle, spans) in &mut self.spans {
+            spans.retain(|s| s.end.is_none());
+            for span in spans.iter_mut() {
+                span.start = now;
+                self.totals
+                    .entry((*handle, span.band))
+                    .or_default()
+                    .transmissions = 1;
+            }
+        }
+    }
+
+    /// Duty cycle of a radio over the window, on one band or (None) all of them
+    pub fn duty_cycle(
+        &self,
+        handle: RadioHandle,
+        band: Option<Option<&'static str>>,
+        now: Instant,
+    ) -> f32 {
+        let window = self.config.window();
+        let from = now.checked_sub(window).unwrap_or(now);
+        let keyed: Duration = self
+            .spans
+            .get(&handle)
+            .into_iter()
+            .flatten()
+            .filter(|s| band.is_none_or(|b| s.band == b))
+            .map(|s| s.overlap(from, now))
+            .sum();
+        (keyed.as_secs_f32() / window.as_secs_f32()).min(1.0)
+    }
+
+    /// Statistics for every radio and band, ordered by radio then band
+    pub fn snapshot(&self, now: Instant) -> Vec<TxBandStats> {
+        let mut stats: Vec<TxBandStats> = self
+            .totals
+            .iter()
+            .map(|(&(handle, band), totals)| {
+                let keyed = self
+                    .spans
+                    .get(&handle)
+                    .and_then(|s| s.back())
+                    .filter(|s| s.end.is_none() && s.band == band)
+                    .map_or(Duration::ZERO, |s| now.saturating_duration_since(s.start));
+                TxBandStats {
+                    handle,
+                    band,
+                    transmissions: totals.transmissions,
+                    total: totals.total + keyed,
+                    longest: totals.longest.max(keyed),
+                    duty_cycle: self.duty_cycle(handle, Some(band), now),
+                }
+            })
+            .collect();
+        stats.sort_by_key(|s| (s.handle.0, s.band));
+        stats
+    }
+
+    /// Drop old key-downs and report radios crossing the alert threshold
+    pub fn check(&mut self, now: Instant) -> Vec<DutyCycleAlert> {
+        let from = now.checked_sub(self.config.window()).unwrap_or(now);
+        for spans in self.spans.values_mut() {
+            while spans
+                .front()
+                .is_some_and(|s| s.end.is_some_and(|e| e < from))
+            {
+                spans.pop_front();
+            }
+        }
+
+        let threshold = f32::from(self.config.threshold_pct) / 100.0;
+        let mut alerts = Vec::new();
+        let mut handles: Vec<RadioHandle> = self
+            .spans
+            .keys()
+            .chain(self.alerting.iter())
+            .copied()
+            .collect();
+        handles.sort_by_key(|h| h.0);
+        handles.dedup();
+        for handle in handles {
+            let duty_cycle = self.duty_cycle(handle, None, now);
+            let over = self.config.enabled && duty_cycle > threshold;
+            let was_over = self.alerting.contains(&handle);
+            if over != was_over {
+                if over {
+                    self.alerting.insert(handle);
+                } else {
+                    self.alerting.remove(&handle);
+                }
+                alerts.push(DutyCycleAlert {
+                    handle,
+                    duty_cycle,
+                    over,
+                });
+            }
+        }
+        alerts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIO: RadioHandle = RadioHandle(1);
+
+    fn secs(n: u64) -> Duration {
+        Duration::from_secs(n)
+    }
+
+    #[test]
+    fn test_totals_and_longest_per_band() {
+        let t0 = Instant::now();
+        let mut stats = TxStats::new();
+
+        stats.on_ptt(RADIO, true, Some("20m"), t0);
+        stats.on_ptt(RADIO, true, Some("20m"), t0 + secs(5)); // repeated report
+        stats.on_ptt(RADIO, false, None, t0 + secs(15));
+        stats.on_ptt(RADIO, true, Some("40m"), t0 + secs(30));
+        stats.on_ptt(RADIO, false, None, t0 + secs(32));
+        stats.on_ptt(RADIO, true, Some("20m"), t0 + secs(60));
+
+        let snapshot = stats.snapshot(t0 + secs(80));
+        let twenty = snapshot.iter().find(|s| s.band == Some("20m")).unwrap();
+        assert_eq!(twenty.transmissions, 2);
+        // 15 s done plus 20 s in progress
+        assert_eq!(twenty.total, secs(35));
+        assert_eq!(twenty.longest, secs(20));
+
+        let forty = snapshot.iter().find(|s| s.band == Some("40m")).unwrap();
+        assert_eq!((forty.transmissions, forty.total), (1, secs(2)));
+    }
+
+    #[test]
+    fn test_duty_cycle_alert_raises_and_clears() {
+        let t0 = Instant::now();
+        let mut stats = TxStats::new();
+        stats.set_config(DutyCycleAlertConfig {
+            enabled: true,
+            threshold_pct: 50,
+            window_secs: 60,
+        });
+
+        // FT8 cycles: 15 s on, 15 s off is right at 50%
+        for cycle in 0..2 {
+            let start = t0 + secs(30 * cycle);
+            stats.on_ptt(RADIO, true, Some("20m"), start);
+            stats.on_ptt(RADIO, false, None, start + secs(15));
+        }
+        assert!(stats.check(t0 + secs(60)).is_empty());
+
+        // Back to back for 40 s takes it over
+        stats.on_ptt(RADIO, true, Some("20m"), t0 + secs(60));
+        let alerts = stats.check(t0 + secs(100));
+        assert_eq!(alerts.len(), 1);
+        assert!(alerts[0].over);
+        assert!(alerts[0].duty_cycle > 0.6);
+
+        // Reported once, cleared once the radio has rested
+        assert!(stats.check(t0 + secs(101)).is_empty());
+        stats.on_ptt(RADIO, false, None, t0 + secs(100));
+        let alerts = stats.check(t0 + secs(150));
+        assert_eq!(alerts.len(), 1);
+        assert!(!alerts[0].over);
+    }
+}
@@ -0,0 +1,909 @@
+//! Watch expressions
+//!
+//! A watch is a condition over the normalized radio and amplifier state that
+//! the operator wants to hear about as soon as it holds, e.g.
+//!
+//! ```text
+//! mode != USB && freq in 14.150..14.350
+//! amp_lag > 1000
+//! ```
+//!
+//! [`WatchExpr::parse`] compiles the text; a [`WatchEngine`] follows the mux
+//! event stream (like [`crate::MuxMetrics`]) and reports each watch as it
+//! starts and stops holding. What happens then (an alert, a sound, a
+//! webhook) is up to the host; [`post_webhook`] covers the last.
+//!
+//! # Fields
+//!
+//! Radio fields are evaluated for every connected radio, and a watch holds
+//! when it is true for any of them:
+//!
+//! | Field | Type | Meaning |
+//! |-------|------|---------|
+//! | `freq` | number (MHz) | Radio frequency |
+//! | `mode` | mode name (`USB`, `CW`, `DATA-U`, ...) | Operating mode |
+//! | `band` | band name (`20m`, `6m`, ...) | Band of the frequency |
+//! | `ptt` | `true`/`false` | Transmitting |
+//! | `active` | `true`/`false` | The radio the amplifier follows |
+//! | `amp_lag` | number (ms) | How long the amplifier has been on a different band than the active radio |
+//!
+//! Comparisons are `==`, `!=`, `<`, `<=`, `>`, `>=` and `field in lo..hi`
+//! (inclusive), combined with `&&`, `||`, `!` and parentheses. A bare
+//! boolean field (`ptt`) means `ptt == true`. Comparisons against a value
+//! the radio hasn't reported yet are false.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
+use cat_protocol::display::{format_frequency, format_mode};
+use cat_protocol::{Frequency, OperatingMode, Protocol, RadioSideCodec};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+use crate::band_plan::{band_name, band_names};
+use crate::state::RadioHandle;
+use crate::MuxEvent;
+
+/// How long a webhook (or push) request may take
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// All operating modes, for parsing mode names
+const MODES: [OperatingMode; 16] = [
+    OperatingMode::Lsb,
+    OperatingMode::Usb,
+    OperatingMode::Cw,
+    OperatingMode::CwR,
+    OperatingMode::Am,
+    OperatingMode::Fm,
+    OperatingMode::FmN,
+    OperatingMode::Dig,
+    OperatingMode::DigU,
+    OperatingMode::DigL,
+    OperatingMode::Pkt,
+    OperatingMode::Data,
+    OperatingMode::DataU,
+    OperatingMode::DataL,
+    OperatingMode::Rtty,
+    OperatingMode::RttyR,
+];
+
+/// Why a watch expression didn't parse
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchParseError(String);
+
+impl fmt::Display for WatchParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for WatchParseError {}
+
+fn err<T>(message: impl Into<String>) -> Result<T, WatchParseError> {
+    Err(WatchParseError(message.into()))
+}
+
+/// A field a watch can test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Freq,
+    Mode,
+    Band,
+    Ptt,
+    Active,
+    AmpLag,
+}
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "freq" => Self::Freq,
+            "mode" => Self::Mode,
+            "band" => Self::Band,
+            "ptt" => Self::Ptt,
+            "active" => Self::Active,
+            "amp_lag" => Self::AmpLag,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn holds<T: PartialOrd>(self, a: T, b: T) -> bool {
+        match self {
+            Self::Eq => a == b,
+            Self::Ne => a != b,
+            Self::Lt => a < b,
+            Self::Le => a <= b,
+            Self::Gt => a > b,
+            Self::Ge => a >= b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Test {
+    Number(Field, CmpOp, f64),
+    Range(Field, f64, f64),
+    Mode(CmpOp, OperatingMode),
+    Band(CmpOp, &'static str),
+    Flag(Field, bool),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Or(Box<Node>, Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Test(Test),
+}
+
+/// Values one radio's watches are evaluated against
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WatchContext {
+    /// Radio frequency in Hz
+    pub frequency_hz: Option<Frequency>,
+    /// Operating mode
+    pub mode: Option<OperatingMode>,
+    /// Transmitting
+    pub ptt: bool,
+    /// The radio the amplifier follows
+    pub active: bool,
+    /// How long the amplifier has been on a different band than the active radio
+    pub amp_lag: Duration,
+}
+
+impl Node {
+    fn eval(&self, ctx: &WatchContext) -> bool {
+        match self {
+            Self::Or(a, b) => a.eval(ctx) || b.eval(ctx),
+            Self::And(a, b) => a.eval(ctx) && b.eval(ctx),
+            Self::Not(a) => !a.eval(ctx),
+            Self::Test(test) => test.eval(ctx),
+        }
+    }
+}
+
+impl Test {
+    fn number(field: Field, ctx: &WatchContext) -> Option<f64> {
+        match field {
+            Field::Freq => ctx.frequency_hz.map(Frequency::mhz),
+            Field::AmpLag => Some(ctx.amp_lag.as_secs_f64() * 1000.0),
+            _ => None,
+        }
+    }
+
+    fn eval(&self, ctx: &WatchContext) -> bool {
+        match self {
+            Self::Number(field, op, value) => {
+                Self::number(*field, ctx).is_some_and(|v| op.holds(v, *value))
+            }
+            Self::Range(field, lo, hi) => {
+                Self::number(*field, ctx).is_some_and(|v| (*lo..=*hi).contains(&v))
+            }
+            Self::Mode(op, mode) => ctx.mode.is_some_and(|m| op.holds(m == *mode, true)),
+            Self::Band(op, band) => ctx
+                .frequency_hz
+                .map(band_name)
+                .is_some_and(|b| op.holds(b == Some(*band), true)),
+            Self::Flag(Field::Ptt, want) => ctx.ptt == *want,
+            Self::Flag(_, want) => ctx.active == *want,
+        }
+    }
+}
+
+/// A compiled watch expression
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchExpr {
+    source: String,
+    root: Node,
+}
+
+impl WatchExpr {
+    /// Compile a watch expression (see the module docs for the syntax)
+    pub fn parse(source: &str) -> Result<Self, WatchParseError> {
+        let tokens = tokenize(source)?;
+        if tokens.is_empty() {
+            return err("empty expression");
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let root = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return err(format!("unexpected {}", token));
+        }
+        Ok(Self {
+            source: source.trim().to_string(),
+            root,
+        })
+    }
+
+    /// The expression as written
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Whether the expression holds for one radio
+    pub fn eval(&self, ctx: &WatchContext) -> bool {
+        self.root.eval(ctx)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Number(f64),
+    Op(&'static str),
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Word(w) => write!(f, "'{}'", w),
+            Self::Number(n) => write!(f, "'{}'", n),
+            Self::Op(op) => write!(f, "'{}'", op),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, WatchParseError> {
+    const OPS: [&str; 13] = [
+        "&&", "||", "==", "!=", "<=", ">=", "..", "<", ">", "!", "(", ")", "=",
+    ];
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit() {
+            // Numbers stop at `..` so `14.150..14.350` is a range; a
+            // trailing unit makes a word (`20m`)
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || (chars[i] == '.' && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())))
+            {
+                i += 1;
+            }
+            if i < chars.len() && chars[i].is_ascii_alphabetic() {
+                while i < chars.len() && chars[i].is_ascii_alphanumeric() {
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            } else {
+                let text: String = chars[start..i].iter().collect();
+                let n = text
+                    .parse()
+                    .map_err(|_| WatchParseError(format!("bad number '{}'", text)))?;
+                tokens.push(Token::Number(n));
+            }
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_ascii_alphanumeric() || chars[i] == '_' || chars[i] == '-')
+            {
+                i += 1;
+            }
+            tokens.push(Token::Word(chars[start..i].iter().collect()));
+        } else {
+            let rest: String = chars[i..chars.len().min(i + 2)].iter().collect();
+            let Some(op) = OPS.iter().find(|op| rest.starts_with(**op)) else {
+                return err(format!("unexpected '{}'", c));
+            };
+            if *op == "=" {
+                return err("use '==' to compare");
+            }
+            tokens.push(Token::Op(op));
+            i += op.len();
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, op: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Op(o)) if *o == op);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    fn or(&mut self) -> Result<Node, WatchParseError> {
+        let mut node = self.and()?;
+        while self.eat("||") {
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        Ok(node)
+    }
+
+    fn and(&mut self) -> Result<Node, WatchParseError> {
+        let mut node = self.unary()?;
+        while self.eat("&&") {
+            node = Node::And(Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    fn unary(&mut self) -> Result<Node, WatchParseError> {
+        if self.eat("!") {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(") {
+            let node = self.or()?;
+            if !self.eat(")") {
+                return err("missing ')'");
+            }
+            return Ok(node);
+        }
+        self.test().map(Node::Test)
+    }
+
+    fn number(&mut self) -> Result<f64, WatchParseError> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n),
+            Some(token) => err(format!("expected a number, found {}", token)),
+            None => err("expected a number"),
+        }
+    }
+
+    fn test(&mut self) -> Result<Test, WatchParseError> {
+        let name = match self.next() {
+            Some(Token::Word(w)) => w,
+            Some(token) => return err(format!("expected a field, found {}", token)),
+            None => return err("expected a field"),
+        };
+        let Some(field) = Field::parse(&name.to_ascii_lowercase()) else {
+            return err(format!(
+                "unknown field '{}' (freq, mode, band, ptt, active, amp_lag)",
+                name
+            ));
+        };
+
+        if let Some(Token::Word(w)) = self.peek() {
+            if w.eq_ignore_ascii_case("in") {
+                self.pos += 1;
+                if !matches!(field, Field::Freq | Field::AmpLag) {
+                    return err(format!("'{}' is not a number", name));
+                }
+                let lo = self.number()?;
+                if !self.eat("..") {
+                    return err("expected '..' in range");
+                }
+                let hi = self.number()?;
+                return Ok(Test::Range(field, lo, hi));
+            }
+        }
+
+        let op = match self.peek() {
+            Some(Token::Op("==")) => CmpOp::Eq,
+            Some(Token::Op("!=")) => CmpOp::Ne,
+            Some(Token::Op("<")) => CmpOp::Lt,
+            Some(Token::Op("<=")) => CmpOp::Le,
+            Some(Token::Op(">")) => CmpOp::Gt,
+            Some(Token::Op(">=")) => CmpOp::Ge,
+            // A bare flag
+            _ if matches!(field, Field::Ptt | Field::Active) => {
+                return Ok(Test::Flag(field, true));
+            }
+            Some(token) => return err(format!("expected a comparison, found {}", token)),
+            None => return err(format!("expected a comparison after '{}'", name)),
+        };
+        self.pos += 1;
+        let equality = matches!(op, CmpOp::Eq | CmpOp::Ne);
+
+        match field {
+            Field::Freq | Field::AmpLag => Ok(Test::Number(field, op, self.number()?)),
+            Field::Mode if equality => {
+                let value = self.word()?;
+                let mode = MODES
+                    .into_iter()
+                    .find(|m| format_mode(*m).eq_ignore_ascii_case(&value))
+                    .ok_or_else(|| WatchParseError(format!("unknown mode '{}'", value)))?;
+                Ok(Test::Mode(op, mode))
+            }
+            Field::Band if equality => {
+                let value = self.word()?;
+                let band = band_names()
+                    .find(|b| b.eq_ignore_ascii_case(&value))
+                    .ok_or_else(|| WatchParseError(format!("unknown band '{}'", value)))?;
+                Ok(Test::Band(op, band))
+            }
+            Field::Ptt | Field::Active if equality => {
+                let value = self.word()?.to_ascii_lowercase();
+                let want = match value.as_str() {
+                    "true" => true,
+                    "false" => false,
+                    _ => return err(format!("expected true or false, found '{}'", value)),
+                };
+                Ok(Test::Flag(field, want == (op == CmpOp::Eq)))
+            }
+            _ => err(format!("'{}' can only be compared with == or !=", name)),
+        }
+    }
+
+    fn word(&mut self) -> Result<String, WatchParseError> {
+        match self.next() {
+            Some(Token::Word(w)) => Ok(w),
+            Some(token) => err(format!("expected a name, found {}", token)),
+            None => err("expected a name"),
+        }
+    }
+}
+
+/// A watch starting or stopping to hold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WatchTransition {
+    /// Name of the watch
+    pub name: String,
+    /// True when the watch started holding, false when it stopped
+    pub firing: bool,
+    /// Which radios it holds for, with what they were doing
+    pub detail: String,
+}
+
+#[derive(Debug, Default)]
+struct WatchedRadio {
+    name: String,
+    frequency_hz: Option<Frequency>,
+    mode: Option<OperatingMode>,
+    ptt: bool,
+}
+
+/// Evaluates watches against the mux event stream
+#[derive(Default)]
+pub struct WatchEngine {
+    watches: Vec<(String, WatchExpr)>,
+    firing: HashSet<String>,
+    radios: HashMap<RadioHandle, WatchedRadio>,
+    active: Option<RadioHandle>,
+    amp_connected: bool,
+    /// Decodes what the amplifier is told
+    amp_codec: Option<(Protocol, RadioSideCodec)>,
+    amp_hz: Option<Frequency>,
+    /// When the amplifier's band last stopped matching the active radio's
+    amp_mismatch_since: Option<Instant>,
+}
+
+impl WatchEngine {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the watches; ones that were firing and still exist keep firing
+    pub fn set_watches(&mut self, watches: Vec<(String, WatchExpr)>) {
+        self.firing
+            .retain(|name| watches.iter().any(|(n, _)| n == name));
+        self.watches = watches;
+    }
+
+    /// Number of watches
+    pub fn len(&self) -> usize {
+        self.watches.len()
+    }
+
+    /// Whether there are no watches
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Whether a watch is currently holding
+    pub fn is_firing(&self, name: &str) -> bool {
+        self.firing.contains(name)
+    }
+
+    /// Whether the amplifier is behind the active radio's band
+    ///
+    /// `amp_lag` grows without further events while this holds, so the
+    /// host should keep evaluating.
+    pub fn has_pending_timer(&self) -> bool {
+        self.amp_mismatch_since.is_some()
+    }
+
+    /// Fold one event into the watched state
+    pub fn observe(&mut self, event: &MuxEvent, now: Instant) {
+        match event {
+            MuxEvent::RadioConnected { handle, meta } => {
+                self.radios.entry(*handle).or_default().name = meta.display_name.clone();
+            }
+            MuxEvent::RadioDisconnected { handle } => {
+                self.radios.remove(handle);
+                if self.active == Some(*handle) {
+                    self.active = None;
+                }
+            }
+            MuxEvent::RadioStateChanged {
+                handle,
+                freq,
+                mode,
+                ptt,
+                ..
+            } => {
+                let radio = self.radios.entry(*handle).or_default();
+                if freq.is_some() {
+                    radio.frequency_hz = *freq;
+                }
+                if mode.is_some() {
+                    radio.mode = *mode;
+                }
+                if let Some(ptt) = ptt {
+                    radio.ptt = *ptt;
+                }
+            }
+            MuxEvent::ActiveRadioChanged { to, .. } => self.active = Some(*to),
+            MuxEvent::AmpConnected { .. } => self.amp_connected = true,
+            MuxEvent::AmpDisconnected => {
+                self.amp_connected = false;
+                self.amp_codec = None;
+                self.amp_hz = None;
+            }
+            MuxEvent::AmpDataOut {
+                data,
+                protocol,
+                suppressed: false,
+                ..
+            } => {
+                let codec = match &mut self.amp_codec {
+                    Some((p, codec)) if p == protocol => codec,
+                    _ => {
+                        &mut self
+                            .amp_codec
+                            .insert((*protocol, RadioSideCodec::new(*protocol)))
+                            .1
+                    }
+                };
+                codec.push_bytes(data);
+                while let Some(response) = codec.next_response() {
+                    if let Some(hz) = response.frequency() {
+                        self.amp_hz = Some(hz);
+                    }
+                }
+            }
+            _ => return,
+        }
+        self.update_amp_lag(now);
+    }
+
+    fn update_amp_lag(&mut self, now: Instant) {
+        let radio_band = self
+            .active
+            .and_then(|h| self.radios.get(&h))
+            .and_then(|r| r.frequency_hz)
+            .and_then(band_name);
+        let mismatch = self.amp_connected
+            && radio_band.is_some()
+            && radio_band != self.amp_hz.and_then(band_name);
+        if !mismatch {
+            self.amp_mismatch_since = None;
+        } else if self.amp_mismatch_since.is_none() {
+            self.amp_mismatch_since = Some(now);
+        }
+    }
+
+    /// Re-evaluate every watch, returning the ones that changed
+    pub fn evaluate(&mut self, now: Instant) -> Vec<WatchTransition> {
+        let amp_lag = self
+            .amp_mismatch_since
+            .map(|since| now.saturating_duration_since(since))
+            .unwrap_or_default();
+        let mut handles: Vec<&RadioHandle> = self.radios.keys().collect();
+        handles.sort_by_key(|h| h.0);
+        let contexts: Vec<(&WatchedRadio, WatchContext)> = handles
+            .into_iter()
+            .map(|handle| (handle, &self.radios[handle]))
+            .map(|(handle, radio)| {
+                let ctx = WatchContext {
+                    frequency_hz: radio.frequency_hz,
+                    mode: radio.mode,
+                    ptt: radio.ptt,
+                    active: self.active == Some(*handle),
+                    amp_lag,
+                };
+                (radio, ctx)
+            })
+            .collect();
+
+        let mut transitions = Vec::new();
+        for (name, expr) in &self.watches {
+            let matching: Vec<String> = contexts
+                .iter()
+                .filter(|(_, ctx)| expr.eval(ctx))
+                .map(|(radio, ctx)| describe(radio, ctx))
+                .collect();
+            let firing = !matching.is_empty();
+            if firing == self.firing.contains(name) {
+                continue;
+            }
+            if firing {
+                self.firing.insert(name.clone());
+            } else {
+                self.firing.remove(name);
+            }
+            transitions.push(WatchTransition {
+                name: name.clone(),
+                firing,
+                detail: if firing {
+                    matching.join("; ")
+                } else {
+                    "cleared".to_string()
+                },
+            });
+        }
+        transitions
+    }
+}
+
+fn describe(radio: &WatchedRadio, ctx: &WatchContext) -> String {
+    let mut parts = vec![if radio.name.is_empty() {
+        "Radio".to_string()
+    } else {
+        radio.name.clone()
+    }];
+    if let Some(hz) = ctx.frequency_hz {
+        parts.push(format_frequency(hz));
+    }
+    if let Some(mode) = ctx.mode {
+        parts.push(format_mode(mode).to_string());
+    }
+    if ctx.ptt {
+        parts.push("TX".to_string());
+    }
+    if !ctx.amp_lag.is_zero() {
+        parts.push(format!("amp {} ms behind", ctx.amp_lag.as_millis()));
+    }
+    parts.join(" ")
+}
+
+/// POST a JSON body to an `http://` URL
+///
+/// Only plain HTTP is supported (a local relay such as Home Assistant or
+/// Node-RED can forward elsewhere). Fails on anything but a 2xx status.
+pub async fn post_webhook(url: &str, body: &str) -> io::Result<()> {
+    post(url, &[("Content-Type", "application/json")], body).await
+}
+
+/// POST `body` to an `http://` URL with the given headers
+pub(crate) async fn post(url: &str, headers: &[(&str, &str)], body: &str) -> io::Result<()> {
+    let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, msg.to_string());
+    let rest = url
+        .trim()
+        .strip_prefix("http://")
+        .ok_or_else(|| invalid("URL must start with http://"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    if authority.is_empty() {
+        return Err(invalid("URL has no host"));
+    }
+    let address = if authority.contains(':') {
+        authority.to_string()
+    } else {
+        format!("{}:80", authority)
+    };
+
+    let headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}\r\n", name, value))
+        .collect();
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: {}\r\n{}\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        authority,
+        headers,
+        body.len(),
+        body
+    );
+    let exchange = async {
+        let mut stream = TcpStream::connect(&address).await?;
+        stream.write_all(request.as_bytes()).await?;
+        let mut reply = Vec::new();
+        stream.read_to_end(&mut reply).await?;
+        Ok::<_, io::Error>(reply)
+    };
+    let reply = tokio::time::timeout(WEBHOOK_TIMEOUT, exchange)
+        .await
+        .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "request timed out"))??;
+    let status_line = String::from_utf8_lossy(&reply);
+    let status = status_line.lines().next().unwrap_or_default();
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(io::Error::other(format!("server answered '{}'", status))),
+    }
+}
+
+/// JSON body for a watch webhook
+pub fn webhook_body(transition: &WatchTransition) -> String {
+    format!(
+        "{{\"watch\":\"{}\",\"state\":\"{}\",\"detail\":\"{}\"}}",
+        json_escape(&transition.name),
+        if transition.firing {
+            "firing"
+        } else {
+            "cleared"
+        },
+        json_escape(&transition.detail)
+    )
+}
+
+pub(crate) fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn ctx(mhz: f64, mode: OperatingMode) -> WatchContext {
+        WatchContext {
+            frequency_hz: Frequency::from_mhz_f64(mhz),
+            mode: Some(mode),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_and_eval() {
+        let expr = WatchExpr::parse("mode != USB && freq in 14.150..14.350").unwrap();
+        assert!(expr.eval(&ctx(14.200, OperatingMode::Lsb)));
+        assert!(!expr.eval(&ctx(14.200, OperatingMode::Usb)));
+        assert!(!expr.eval(&ctx(14.074, OperatingMode::DataU)));
+        // Unknown values never match
+        assert!(!expr.eval(&WatchContext::default()));
+
+        let expr = WatchExpr::parse("band == 20m && !(ptt || active)").unwrap();
+        assert!(expr.eval(&ctx(14.074, OperatingMode::DataU)));
+        let mut tx = ctx(14.074, OperatingMode::DataU);
+        tx.ptt = true;
+        assert!(!expr.eval(&tx));
+
+        let expr = WatchExpr::parse("ptt == false || mode == data-u").unwrap();
+        assert!(expr.eval(&tx));
+
+        assert!(WatchExpr::parse("mode = USB").is_err());
+        assert!(WatchExpr::parse("mode > USB").is_err());
+        assert!(WatchExpr::parse("volume > 3").is_err());
+        assert!(WatchExpr::parse("freq in 14..").is_err());
+        assert!(WatchExpr::parse("(ptt").is_err());
+        assert!(WatchExpr::parse("band == 11m").is_err());
+        assert!(WatchExpr::parse("").is_err());
+    }
+
+    #[test]
+    fn test_engine_reports_edges_and_amp_lag() {
+        let t0 = Instant::now();
+        let ms = Duration::from_millis;
+        let radio = RadioHandle(1);
+        let mut engine = WatchEngine::new();
+        engine.set_watches(vec![
+            (
+                "Phone band plan".to_string(),
+                WatchExpr::parse("mode != USB && freq in 14.150..14.350").unwrap(),
+            ),
+            (
+                "Amp behind".to_string(),
+                WatchExpr::parse("amp_lag > 1000").unwrap(),
+            ),
+        ]);
+
+        let state = |freq, mode| MuxEvent::RadioStateChanged {
+            handle: radio,
+            freq,
+            mode,
+            ptt: None,
+            power_watts: None,
+        };
+        let amp_out = |data: &[u8]| MuxEvent::AmpDataOut {
+            data: data.to_vec(),
+            cause: None,
+            protocol: Protocol::Kenwood,
+            suppressed: false,
+            timestamp: SystemTime::now(),
+        };
+
+        engine.observe(
+            &state(
+                Some(Frequency::from_hz(14_200_000)),
+                Some(OperatingMode::Usb),
+            ),
+            t0,
+        );
+        engine.observe(
+            &MuxEvent::ActiveRadioChanged {
+                from: None,
+                to: radio,
+            },
+            t0,
+        );
+        engine.observe(
+            &MuxEvent::AmpConnected {
+                meta: crate::AmplifierChannelMeta::new_virtual(Protocol::Kenwood, None),
+            },
+            t0,
+        );
+        engine.observe(&amp_out(b"FA00014200000;"), t0);
+        assert!(engine.evaluate(t0).is_empty());
+
+        engine.observe(&state(None, Some(OperatingMode::Lsb)), t0 + ms(10));
+        let fired = engine.evaluate(t0 + ms(10));
+        assert_eq!(fired.len(), 1);
+        assert!(fired[0].firing);
+        assert_eq!(fired[0].name, "Phone band plan");
+        assert!(fired[0].detail.contains("14.200 MHz LSB"));
+        // Reported once, not on every evaluation
+        assert!(engine.evaluate(t0 + ms(20)).is_empty());
+
+        // Radio QSYs to 40m; the amp isn't told
+        engine.observe(
+            &state(Some(Frequency::from_hz(7_150_000)), None),
+            t0 + ms(100),
+        );
+        let changes = engine.evaluate(t0 + ms(100));
+        assert_eq!(changes.len(), 1);
+        assert!(!changes[0].firing);
+        assert!(engine.evaluate(t0 + ms(1000)).is_empty());
+        let late = engine.evaluate(t0 + ms(1200));
+        assert_eq!(late.len(), 1);
+        assert_eq!(late[0].name, "Amp behind");
+        assert!(late[0].firing);
+
+        // The amp catches up
+        engine.observe(&amp_out(b"FA00007150000;"), t0 + ms(1300));
+        let caught_up = engine.evaluate(t0 + ms(1300));
+        assert_eq!(caught_up.len(), 1);
+        assert!(!caught_up[0].firing);
+    }
+
+    #[test]
+    fn test_webhook_body_is_escaped_json() {
+        let body = webhook_body(&WatchTransition {
+            name: "Say \"hi\"".to_string(),
+            firing: true,
+            detail: "a\\b".to_string(),
+        });
+        assert_eq!(
+            body,
+            r#"{"watch":"Say \"hi\"","state":"firing","detail":"a\\b"}"#
+        );
+    }
+}
@@ -0,0 +1,168 @@
+//! Amplifier write backlog tracking
+//!
+//! Bytes queued for the amplifier sit in the connection's channel and then in
+//! the serial driver until the port drains them. At 4800 baud an amp takes
+//! about 2ms per byte, so a transceive storm can easily outrun it. The mux
+//! actor counts bytes into a [`WriteBacklog`] as it queues them and the
+//! connection task counts them out once written; a [`BacklogMonitor`] turns
+//! that count into a backed-up/drained state with hysteresis so the actor
+//! can hold back frequency and mode updates instead of queueing stale ones.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+/// Baud rate assumed for connections without one (virtual amplifiers)
+const DEFAULT_BAUD: u32 = 115_200;
+
+/// Backlog that counts as backed up, as time to drain at the port's speed
+const HIGH_WATERMARK_MS: usize = 500;
+
+/// Smallest high watermark, so a slow port still takes a few frames
+const MIN_HIGH_WATERMARK: usize = 64;
+
+/// Bytes queued for a connection but not yet written
+///
+/// Cloned handles share the same count.
+#[derive(Debug, Clone, Default)]
+pub struct WriteBacklog {
+    queued: Arc<AtomicUsize>,
+}
+
+impl WriteBacklog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count bytes handed to the connection
+    pub fn queued(&self, bytes: usize) {
+        self.queued.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    /// Count bytes the connection has finished with (written or failed)
+    pub fn written(&self, bytes: usize) {
+        let _ = self
+            .queued
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| {
+                Some(n.saturating_sub(bytes))
+            });
+    }
+
+    /// Bytes still waiting to be written
+    pub fn pending(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+}
+
+/// Backed-up state of a [`WriteBacklog`] with high/low watermarks
+#[derive(Debug, Clone)]
+pub struct BacklogMonitor {
+    backlog: WriteBacklog,
+    high: usize,
+    low: usize,
+    backed_up: bool,
+}
+
+impl BacklogMonitor {
+    /// Monitor a backlog with watermarks sized for the port's baud rate
+    ///
+    /// The high watermark is half a second of output (10 bits per byte);
+    /// the backlog counts as drained again below a quarter of that.
+    pub fn new(backlog: WriteBacklog, baud_rate: u32) -> Self {
+        let baud = if baud_rate == 0 {
+            DEFAULT_BAUD
+        } else {
+            baud_rate
+        };
+        let bytes_per_sec = baud as usize / 10;
+        let high = (bytes_per_sec * HIGH_WATERMARK_MS / 1000).max(MIN_HIGH_WATERMARK);
+        Self {
+            backlog,
+            high,
+            low: high / 4,
+            backed_up: false,
+        }
+    }
+
+    /// The monitored backlog
+    pub fn backlog(&self) -> &WriteBacklog {
+        &self.backlog
+    }
+
+    /// Bytes above which the backlog counts as backed up
+    pub fn high_watermark(&self) -> usize {
+        self.high
+    }
+
+    /// Bytes below which a backed-up backlog counts as drained
+    pub fn low_watermark(&self) -> usize {
+        self.low
+    }
+
+    /// Bytes still waiting to be written
+    pub fn pending(&self) -> usize {
+        self.backlog.pending()
+    }
+
+    /// Whether the backlog was backed up at the last check
+    pub fn is_backed_up(&self) -> bool {
+        self.backed_up
+    }
+
+    /// Re-check the backlog; returns the new state if it changed
+    pub fn check(&mut self) -> Option<bool> {
+        let pending = self.backlog.pending();
+        let backed_up = if self.backed_up {
+            pending > self.low
+        } else {
+            pending > self.high
+        };
+        if backed_up == self.backed_up {
+            return None;
+        }
+        self.backed_up = backed_up;
+        Some(backed_up)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watermarks_follow_baud_rate() {
+        let slow = BacklogMonitor::new(WriteBacklog::new(), 4800);
+        assert_eq!(slow.high_watermark(), 240);
+        assert_eq!(slow.low_watermark(), 60);
+
+        let virt = BacklogMonitor::new(WriteBacklog::new(), 0);
+        assert_eq!(virt.high_watermark(), 5760);
+
+        let tiny = BacklogMonitor::new(WriteBacklog::new(), 300);
+        assert_eq!(tiny.high_watermark(), MIN_HIGH_WATERMARK);
+    }
+
+    #[test]
+    fn test_backed_up_with_hysteresis() {
+        let backlog = WriteBacklog::new();
+        let mut monitor = BacklogMonitor::new(backlog.clone(), 4800);
+
+        backlog.queued(200);
+        assert_eq!(monitor.check(), None);
+        backlog.queued(100);
+        assert_eq!(monitor.check(), Some(true));
+        assert!(monitor.is_backed_up());
+
+        // Still above the low watermark
+        backlog.written(200);
+        assert_eq!(monitor.pending(), 100);
+        assert_eq!(monitor.check(), None);
+
+        backlog.written(50);
+        assert_eq!(monitor.check(), Some(false));
+        assert!(!monitor.is_backed_up());
+
+        // Never goes negative
+        backlog.written(1000);
+        assert_eq!(monitor.pending(), 0);
+    }
+}
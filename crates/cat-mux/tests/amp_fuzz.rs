@@ -0,0 +1,339 @@
+//! Adversarial amplifier against the mux's amp-facing responder
+//!
+//! Each case starts a mux actor with one radio on 14.250 MHz USB and a
+//! virtual amplifier, then plays a hostile amplifier: malformed queries,
+//! set commands with illegal values, rapid-fire polls and garbage dropped
+//! into the middle of frames, in chunks split at arbitrary points. Then it
+//! asks for the frequency twice, the way an amp keeps polling. The mux must:
+//!
+//! - never panic (the actor task ends cleanly on shutdown)
+//! - only write whole, well-formed frames that decode as responses in the
+//!   amp's protocol, with no bytes left over
+//! - be back in sync by the end, answering the last poll with the frequency
+//!
+//! The first of the two closing polls may be swallowed by the tail of the
+//! garbage; an amp's next poll has to be answered.
+//!
+//! Only protocols with frame delimiters are covered: Yaesu's fixed 5-byte
+//! frames have no way to find a frame boundary in the byte stream, and only
+//! resync after the partial-frame timeout.
+//!
+//! Set `PROPTEST_CASES` for a longer run, e.g.
+//! `PROPTEST_CASES=5000 cargo test -p cat-mux --test amp_fuzz`.
+
+use cat_mux::{
+    run_mux_actor, AmplifierChannel, AmplifierChannelMeta, MuxActorCommand, MuxEvent,
+    RadioChannelMeta,
+};
+use cat_protocol::{Frequency, OperatingMode, Protocol, RadioResponse, RadioSideCodec};
+use proptest::prelude::*;
+use tokio::sync::{mpsc, oneshot};
+
+/// Frequency the radio is on, and every frequency answer must carry
+const RADIO_HZ: u64 = 14_250_000;
+
+/// The amp's CI-V address
+const AMP_CIV: u8 = 0x94;
+
+// ============================================================================
+// Harness
+// ============================================================================
+
+/// Everything the mux wrote to the amp, in order
+type AmpWrites = Vec<Vec<u8>>;
+
+/// Run one hostile session against a fresh mux and return what it wrote to
+/// the amp
+async fn run_session(protocol: Protocol, chunks: Vec<Vec<u8>>) -> AmpWrites {
+    let (cmd_tx, cmd_rx) = mpsc::channel(64);
+    let (event_tx, mut event_rx) = mpsc::channel::<MuxEvent>(256);
+    let actor = tokio::spawn(run_mux_actor(cmd_rx, event_tx));
+    tokio::spawn(async move { while event_rx.recv().await.is_some() {} });
+
+    let civ_address = (protocol == Protocol::IcomCIV).then_some(AMP_CIV);
+    cmd_tx
+        .send(MuxActorCommand::SetAmplifierConfig {
+            port: "[VIRTUAL]".to_string(),
+            protocol,
+            baud_rate: 0,
+            civ_address,
+            response_protocol: None,
+            strictness: None,
+        })
+        .await
+        .unwrap();
+
+    // Collect writes as they come, so a flood of replies can't stall the actor
+    let (amp_tx, mut amp_rx) = mpsc::channel(64);
+    let (_amp_resp_tx, amp_resp_rx) = mpsc::channel(1);
+    let channel = AmplifierChannel::new(
+        AmplifierChannelMeta::new_virtual(protocol, civ_address),
+        amp_tx,
+        amp_resp_rx,
+    );
+    let collector = tokio::spawn(async move {
+        let mut writes = AmpWrites::new();
+        while let Some(data) = amp_rx.recv().await {
+            writes.push(data);
+        }
+        writes
+    });
+    cmd_tx
+        .send(MuxActorCommand::ConnectAmplifier { channel })
+        .await
+        .unwrap();
+
+    // After the amp connects: it is told the radio's state from here on
+    let meta = RadioChannelMeta::new_virtual("Radio".to_string(), "fuzz".to_string(), protocol);
+    let (resp_tx, resp_rx) = oneshot::channel();
+    cmd_tx
+        .send(MuxActorCommand::RegisterRadio {
+            meta,
+            response: resp_tx,
+            cmd_tx: None,
+        })
+        .await
+        .unwrap();
+    let handle = resp_rx.await.unwrap();
+    for response in [
+        RadioResponse::Frequency {
+            hz: Frequency::from_hz(RADIO_HZ),
+        },
+        RadioResponse::Mode {
+            mode: OperatingMode::Usb,
+        },
+    ] {
+        cmd_tx
+            .send(MuxActorCommand::RadioResponse { handle, response })
+            .await
+            .unwrap();
+    }
+
+    let poll = frequency_poll(protocol);
+    for data in chunks.into_iter().chain([poll.clone(), poll]) {
+        cmd_tx
+            .send(MuxActorCommand::AmpRawData { data })
+            .await
+            .unwrap();
+    }
+
+    cmd_tx.send(MuxActorCommand::Shutdown).await.unwrap();
+    actor.await.expect("mux actor panicked");
+    collector.await.unwrap()
+}
+
+/// Decode one write to the amp, failing on anything that isn't whole frames
+fn decode_write(protocol: Protocol, data: &[u8]) -> Result<Vec<RadioResponse>, String> {
+    let mut codec = RadioSideCodec::new(protocol);
+    codec.push_bytes(data);
+    let responses: Vec<_> = std::iter::from_fn(|| codec.next_response()).collect();
+    if responses.is_empty() {
+        return Err(format!("no frame in write {:02X?}", data));
+    }
+    if let Some(unknown) = responses
+        .iter()
+        .find(|r| matches!(r, RadioResponse::Unknown { .. }))
+    {
+        return Err(format!("malformed frame {:?} in {:02X?}", unknown, data));
+    }
+    if !codec.buffered().is_empty() {
+        return Err(format!(
+            "partial frame {:02X?} left over from {:02X?}",
+            codec.buffered(),
+            data
+        ));
+    }
+    Ok(responses)
+}
+
+/// Check a session's writes: all well-formed, the last one the frequency
+fn check_writes(protocol: Protocol, writes: &AmpWrites) -> Result<(), TestCaseError> {
+    let mut last = None;
+    for data in writes {
+        let responses = decode_write(protocol, data).map_err(TestCaseError::fail)?;
+        last = responses.last().cloned();
+    }
+    prop_assert_eq!(
+        last,
+        Some(RadioResponse::Frequency {
+            hz: Frequency::from_hz(RADIO_HZ)
+        })
+    );
+    Ok(())
+}
+
+fn fuzz(protocol: Protocol, chunks: Vec<Vec<u8>>) -> Result<(), TestCaseError> {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap();
+    let writes = runtime.block_on(run_session(protocol, chunks));
+    check_writes(protocol, &writes)
+}
+
+// ============================================================================
+// Hostile traffic
+// ============================================================================
+
+fn frequency_poll(protocol: Protocol) -> Vec<u8> {
+    match protocol {
+        Protocol::IcomCIV => civ_frame(&[0x03]),
+        _ => b"FA;".to_vec(),
+    }
+}
+
+/// A CI-V frame from a controller to the amp's address
+fn civ_frame(body: &[u8]) -> Vec<u8> {
+    let mut frame = vec![0xFE, 0xFE, AMP_CIV, 0xE0];
+    frame.extend_from_slice(body);
+    frame.push(0xFD);
+    frame
+}
+
+/// Queries a Kenwood-style amp sends
+fn ascii_query() -> impl Strategy<Value = Vec<u8>> {
+    prop::sample::select(vec![
+        "FA;", "FB;", "MD;", "IF;", "ID;", "AI;", "TX;", "RX;", "FR;", "FT;", "PS;", "PC;", "KS;",
+        "CB;", "TB;", "K2;", "K3;", "OM;", "SM0;",
+    ])
+    .prop_map(|q| q.as_bytes().to_vec())
+}
+
+/// Set commands with values no radio would accept
+fn ascii_illegal() -> impl Strategy<Value = Vec<u8>> {
+    let digits = prop::collection::vec(b'0'..=b'9', 0..20);
+    let junk = prop::collection::vec(0x20u8..0x7F, 0..12);
+    (
+        prop::sample::select(vec![
+            "FA", "FB", "MD", "AI", "PC", "KS", "TX", "IF", "FR", "SM",
+        ]),
+        prop_oneof![digits, junk],
+    )
+        .prop_map(|(cmd, value)| {
+            let mut frame = cmd.as_bytes().to_vec();
+            frame.extend(value.into_iter().filter(|&b| b != b';'));
+            frame.push(b';');
+            frame
+        })
+}
+
+/// Queries a CI-V amp sends
+fn civ_query() -> impl Strategy<Value = Vec<u8>> {
+    prop::sample::select(vec![
+        vec![0x03],
+        vec![0x04],
+        vec![0x19, 0x00],
+        vec![0x1C, 0x00],
+        vec![0x15, 0x02],
+        vec![0x14, 0x0A],
+        vec![0x07, 0xD2],
+    ])
+    .prop_map(|body| civ_frame(&body))
+}
+
+/// CI-V commands with random payloads
+fn civ_illegal() -> impl Strategy<Value = Vec<u8>> {
+    (
+        prop::sample::select(vec![0x00u8, 0x01, 0x03, 0x04, 0x05, 0x06, 0x07, 0x1A, 0x1C]),
+        prop::collection::vec(any::<u8>().prop_filter("not FD", |&b| b != 0xFD), 0..10),
+    )
+        .prop_map(|(cmd, payload)| {
+            let mut body = vec![cmd];
+            body.extend(payload);
+            civ_frame(&body)
+        })
+}
+
+/// Bytes weighted towards the protocol's delimiters
+fn garbage(protocol: Protocol) -> impl Strategy<Value = Vec<u8>> {
+    let special = match protocol {
+        Protocol::IcomCIV => vec![0xFE, 0xFD, 0xE0, AMP_CIV, 0x00],
+        _ => vec![b';', b'F', b'A', b'I', b'0'],
+    };
+    prop::collection::vec(
+        prop_oneof![any::<u8>(), prop::sample::select(special)],
+        1..48,
+    )
+}
+
+/// One piece of a hostile session, before splitting into reads
+fn hostile_frame(protocol: Protocol) -> BoxedStrategy<Vec<u8>> {
+    let (query, illegal) = match protocol {
+        Protocol::IcomCIV => (civ_query().boxed(), civ_illegal().boxed()),
+        _ => (ascii_query().boxed(), ascii_illegal().boxed()),
+    };
+    // Garbage spliced into the middle of a query
+    let spliced = (
+        query.clone(),
+        garbage(protocol),
+        any::<prop::sample::Index>(),
+    )
+        .prop_map(|(query, junk, at)| {
+            let at = at.index(query.len() + 1);
+            let mut frame = query[..at].to_vec();
+            frame.extend(junk);
+            frame.extend_from_slice(&query[at..]);
+            frame
+        });
+    // The same poll over and over with no pause
+    let rapid = (query.clone(), 10usize..200).prop_map(|(query, n)| query.repeat(n));
+    prop_oneof![
+        3 => query,
+        3 => illegal,
+        2 => garbage(protocol),
+        2 => spliced,
+        1 => rapid,
+    ]
+    .boxed()
+}
+
+/// A hostile session, split into reads at arbitrary points
+fn session(protocol: Protocol) -> impl Strategy<Value = Vec<Vec<u8>>> {
+    (
+        prop::collection::vec(hostile_frame(protocol), 1..24),
+        prop::collection::vec(1usize..32, 1..64),
+    )
+        .prop_map(|(frames, sizes)| {
+            let bytes = frames.concat();
+            let mut chunks = Vec::new();
+            let mut rest = bytes.as_slice();
+            for size in sizes.iter().cycle() {
+                if rest.is_empty() {
+                    break;
+                }
+                let (chunk, tail) = rest.split_at((*size).min(rest.len()));
+                chunks.push(chunk.to_vec());
+                rest = tail;
+            }
+            chunks
+        })
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+proptest! {
+    #[test]
+    fn kenwood_amp_cannot_break_the_mux(chunks in session(Protocol::Kenwood)) {
+        fuzz(Protocol::Kenwood, chunks)?;
+    }
+
+    #[test]
+    fn elecraft_amp_cannot_break_the_mux(chunks in session(Protocol::Elecraft)) {
+        fuzz(Protocol::Elecraft, chunks)?;
+    }
+
+    #[test]
+    fn icom_amp_cannot_break_the_mux(chunks in session(Protocol::IcomCIV)) {
+        fuzz(Protocol::IcomCIV, chunks)?;
+    }
+}
+
+/// The harness itself: a well-behaved amp gets well-formed answers
+#[test]
+fn polite_amp_is_answered() {
+    for protocol in [Protocol::Kenwood, Protocol::Elecraft, Protocol::IcomCIV] {
+        fuzz(protocol, vec![frequency_poll(protocol)]).unwrap();
+    }
+}
@@ -7,7 +7,7 @@
 //! - Lockout behavior and edge cases
 
 use cat_mux::{Multiplexer, MultiplexerConfig, MuxError, SwitchingMode};
-use cat_protocol::{OperatingMode, Protocol, RadioResponse};
+use cat_protocol::{Frequency, OperatingMode, Protocol, RadioResponse};
 
 // ============================================================================
 // Helper Functions
@@ -116,7 +116,12 @@ mod switching_tests {
         let h2 = mux.add_radio("Radio 2".into(), "/dev/tty1".into(), Protocol::Kenwood);
 
         // Initial frequency report does NOT trigger switch
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
         assert_eq!(
             mux.active_radio(),
             Some(h1),
@@ -124,7 +129,12 @@ mod switching_tests {
         );
 
         // Frequency CHANGE from h2 should switch in automatic mode
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 7_074_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+        );
 
         assert_eq!(
             mux.active_radio(),
@@ -144,7 +154,12 @@ mod switching_tests {
         assert_eq!(mux.active_radio(), Some(h1), "Should start on first radio");
 
         // Initial frequency report does NOT trigger switch
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 7_150_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_150_000),
+            },
+        );
         assert_eq!(
             mux.active_radio(),
             Some(h1),
@@ -152,7 +167,12 @@ mod switching_tests {
         );
 
         // Frequency CHANGE from h2 should trigger switch
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_074_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_074_000),
+            },
+        );
 
         assert_eq!(mux.active_radio(), Some(h2));
     }
@@ -182,7 +202,12 @@ mod switching_tests {
         assert_eq!(mux.active_radio(), Some(h1));
 
         // First, establish h2's initial frequency (initial reports don't trigger switch)
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 7_000_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_000_000),
+            },
+        );
         assert_eq!(
             mux.active_radio(),
             Some(h1),
@@ -191,7 +216,12 @@ mod switching_tests {
 
         // Now a FrequencyReport with a DIFFERENT frequency should trigger switch
         // (simulates user changing frequency on the radio)
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert_eq!(
             mux.active_radio(),
@@ -209,7 +239,12 @@ mod switching_tests {
         let h2 = mux.add_radio("Radio 2".into(), "/dev/tty1".into(), Protocol::Kenwood);
 
         // Set h2's frequency first (initial report doesn't trigger switch)
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 7_000_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_000_000),
+            },
+        );
         assert_eq!(
             mux.active_radio(),
             Some(h1),
@@ -217,7 +252,12 @@ mod switching_tests {
         );
 
         // Now change h2's frequency to trigger a switch to h2
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_000_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_000_000),
+            },
+        );
         assert_eq!(
             mux.active_radio(),
             Some(h2),
@@ -230,7 +270,12 @@ mod switching_tests {
 
         // Another FrequencyReport with the SAME frequency should NOT switch
         // (this simulates a poll response returning unchanged frequency)
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_000_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_000_000),
+            },
+        );
 
         assert_eq!(
             mux.active_radio(),
@@ -251,7 +296,12 @@ mod switching_tests {
 
         // Initial FrequencyReport (when radio has no known frequency yet)
         // should NOT trigger a switch - this prevents switching on first poll
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert_eq!(
             mux.active_radio(),
@@ -269,7 +319,12 @@ mod switching_tests {
         let h2 = mux.add_radio("Radio 2".into(), "/dev/tty1".into(), Protocol::Kenwood);
 
         // Command from inactive radio should return None (not forwarded to amp)
-        let result = mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 14_250_000 });
+        let result = mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert!(result.is_none());
     }
@@ -281,7 +336,12 @@ mod switching_tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-        let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        let result = mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert!(result.is_some());
         let bytes = result.unwrap();
@@ -400,10 +460,15 @@ mod state_tracking_tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         let state = mux.get_radio(h1).unwrap();
-        assert_eq!(state.frequency_hz, Some(14_250_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(14_250_000)));
     }
 
     #[test]
@@ -412,10 +477,15 @@ mod state_tracking_tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 7_074_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+        );
 
         let state = mux.get_radio(h1).unwrap();
-        assert_eq!(state.frequency_hz, Some(7_074_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(7_074_000)));
     }
 
     #[test]
@@ -478,7 +548,7 @@ mod state_tracking_tests {
         mux.process_radio_response(
             h1,
             &RadioResponse::Status {
-                frequency_hz: Some(28_500_000),
+                frequency_hz: Some(Frequency::from_hz(28_500_000)),
                 mode: Some(OperatingMode::Fm),
                 ptt: Some(true),
                 vfo: None,
@@ -486,7 +556,7 @@ mod state_tracking_tests {
         );
 
         let state = mux.get_radio(h1).unwrap();
-        assert_eq!(state.frequency_hz, Some(28_500_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(28_500_000)));
         assert_eq!(state.mode, Some(OperatingMode::Fm));
         assert!(state.ptt);
     }
@@ -498,8 +568,18 @@ mod state_tracking_tests {
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
         let h2 = mux.add_radio("Radio 2".into(), "/dev/tty1".into(), Protocol::IcomCIV);
 
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 7_074_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000),
+            },
+        );
 
         mux.process_radio_response(
             h1,
@@ -517,10 +597,10 @@ mod state_tracking_tests {
         let state1 = mux.get_radio(h1).unwrap();
         let state2 = mux.get_radio(h2).unwrap();
 
-        assert_eq!(state1.frequency_hz, Some(14_250_000));
+        assert_eq!(state1.frequency_hz, Some(Frequency::from_hz(14_250_000)));
         assert_eq!(state1.mode, Some(OperatingMode::Usb));
 
-        assert_eq!(state2.frequency_hz, Some(7_074_000));
+        assert_eq!(state2.frequency_hz, Some(Frequency::from_hz(7_074_000)));
         assert_eq!(state2.mode, Some(OperatingMode::Lsb));
     }
 
@@ -533,10 +613,15 @@ mod state_tracking_tests {
         let h2 = mux.add_radio("Radio 2".into(), "/dev/tty1".into(), Protocol::Kenwood);
 
         // h2 is inactive, but state should still be tracked
-        mux.process_radio_response(h2, &RadioResponse::Frequency { hz: 21_300_000 });
+        mux.process_radio_response(
+            h2,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(21_300_000),
+            },
+        );
 
         let state = mux.get_radio(h2).unwrap();
-        assert_eq!(state.frequency_hz, Some(21_300_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(21_300_000)));
     }
 }
 
@@ -555,7 +640,12 @@ mod translation_tests {
         let h1 = mux.add_radio("IC-7300".into(), "/dev/tty0".into(), Protocol::IcomCIV);
 
         let result = mux
-            .process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 })
+            .process_radio_response(
+                h1,
+                &RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            )
             .unwrap();
 
         // Kenwood format: FA00014250000;
@@ -609,7 +699,12 @@ mod translation_tests {
         let h1 = mux.add_radio("TS-590".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
         let result = mux
-            .process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 })
+            .process_radio_response(
+                h1,
+                &RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            )
             .unwrap();
 
         // CI-V format: FE FE <to> <from> <cmd> <data...> FD
@@ -626,7 +721,12 @@ mod translation_tests {
         let h1 = mux.add_radio("TS-590".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
         let result = mux
-            .process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 })
+            .process_radio_response(
+                h1,
+                &RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            )
             .unwrap();
 
         // Yaesu format: 5 bytes (BCD frequency + mode for responses)
@@ -642,7 +742,12 @@ mod translation_tests {
         let h1 = mux.add_radio("IC-7300".into(), "/dev/tty0".into(), Protocol::IcomCIV);
 
         let result = mux
-            .process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 })
+            .process_radio_response(
+                h1,
+                &RadioResponse::Frequency {
+                    hz: Frequency::from_hz(14_250_000),
+                },
+            )
             .unwrap();
 
         // Elecraft uses Kenwood-compatible format
@@ -726,10 +831,15 @@ mod radio_management_tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         let state = mux.get_radio(h1).unwrap();
-        assert_eq!(state.frequency_hz, Some(14_250_000));
+        assert_eq!(state.frequency_hz, Some(Frequency::from_hz(14_250_000)));
     }
 
     #[test]
@@ -738,7 +848,12 @@ mod radio_management_tests {
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-        let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        let result = mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert!(result.is_some());
         assert!(result.unwrap().ends_with(b";"));
@@ -802,7 +917,12 @@ mod edge_case_tests {
         mux.remove_radio(h1);
 
         // This should not panic or cause issues
-        let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        let result = mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         assert!(result.is_none());
     }
@@ -840,7 +960,12 @@ mod edge_case_tests {
         let mut mux = helpers::mux_no_lockout();
 
         let h1 = mux.add_radio("Radio 1".into(), "/dev/tty0".into(), Protocol::Kenwood);
-        mux.process_radio_response(h1, &RadioResponse::Frequency { hz: 14_250_000 });
+        mux.process_radio_response(
+            h1,
+            &RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000),
+            },
+        );
 
         let state = mux.get_radio(h1).unwrap();
         let display = state.frequency_display();
@@ -913,12 +1038,12 @@ mod proptest_tests {
             let mut mux = helpers::mux_no_lockout();
             let h1 = mux.add_radio("Radio".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-            mux.process_radio_response(h1, &RadioResponse::Frequency { hz });
+            mux.process_radio_response(h1, &RadioResponse::Frequency { hz: Frequency::from_hz(hz) });
 
             let state = mux.get_radio(h1).unwrap();
             // Frequency may be rounded by translator, but should be close
             let tracked = state.frequency_hz.unwrap();
-            prop_assert!((tracked as i64 - hz as i64).abs() < 100);
+            prop_assert!((tracked.hz() as i64 - hz as i64).abs() < 100);
         }
 
         #[test]
@@ -951,7 +1076,7 @@ mod proptest_tests {
             let mut mux = helpers::mux_with_amp_protocol(target);
             let h1 = mux.add_radio("Radio".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
-            let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz });
+            let result = mux.process_radio_response(h1, &RadioResponse::Frequency { hz: Frequency::from_hz(hz) });
 
             // Should produce Some output for frequency commands
             prop_assert!(result.is_some());
@@ -982,7 +1107,7 @@ mod proptest_tests {
             let h1 = mux.add_radio("Radio".into(), "/dev/tty0".into(), Protocol::Kenwood);
 
             for &hz in &freqs {
-                mux.process_radio_response(h1, &RadioResponse::Frequency { hz });
+                mux.process_radio_response(h1, &RadioResponse::Frequency { hz: Frequency::from_hz(hz) });
             }
 
             let state = mux.get_radio(h1).unwrap();
@@ -990,7 +1115,7 @@ mod proptest_tests {
             let tracked = state.frequency_hz.unwrap();
 
             // Should have the last frequency (with possible rounding)
-            prop_assert!((tracked as i64 - expected as i64).abs() < 100);
+            prop_assert!((tracked.hz() as i64 - expected as i64).abs() < 100);
         }
 
         #[test]
@@ -0,0 +1,12 @@
+//! Renders the protocol conformance matrix for the crate documentation
+
+#[allow(dead_code)]
+#[path = "src/conformance.rs"]
+mod conformance;
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/conformance.rs");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let path = std::path::Path::new(&out_dir).join("protocol_matrix.md");
+    std::fs::write(path, conformance::render_matrix()).expect("write protocol matrix");
+}
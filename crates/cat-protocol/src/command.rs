@@ -6,6 +6,8 @@
 //! - `RadioRequest`: Commands/queries sent TO a radio (from mux or amplifier)
 //! - `RadioResponse`: Reports/responses FROM a radio (to mux or amplifier)
 
+use crate::Frequency;
+
 /// Operating modes supported by amateur radio transceivers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -79,24 +81,61 @@ impl OperatingMode {
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RadioRequest {
-    /// Set the VFO frequency in Hz
-    SetFrequency { hz: u64 },
+    /// Set the VFO frequency
+    SetFrequency { hz: Frequency },
 
     /// Set the operating mode
     SetMode { mode: OperatingMode },
 
+    /// Set frequency and mode together
+    ///
+    /// CI-V radios get `0x25` and `0x26` back-to-back. Kenwood, Elecraft,
+    /// FlexRadio and Yaesu radios get the frequency and mode frames in a
+    /// single write, with no band-stack or auto-information suppression.
+    SetFrequencyMode { hz: Frequency, mode: OperatingMode },
+
     /// Set PTT state
     SetPtt { active: bool },
 
+    /// Set the receive/transmit VFOs or the active receiver
+    SetVfoState { state: VfoState },
+
     /// Set VFO (A, B, or split)
+    ///
+    /// Older form of [`RadioRequest::SetVfoState`]; encoders accept it and
+    /// codecs no longer produce it.
     SetVfo { vfo: Vfo },
 
     /// Power on/off command
     SetPower { on: bool },
 
+    /// Set transmitter output power in watts
+    SetTxPower { watts: u16 },
+
     /// Enable/disable auto-information mode
     SetAutoInfo { enabled: bool },
 
+    /// Set the internal keyer speed in words per minute
+    SetKeyerSpeed { wpm: u8 },
+
+    /// Set CW break-in
+    SetBreakIn { mode: BreakIn },
+
+    /// Set the break-in (semi break-in hang) delay in milliseconds
+    SetBreakInDelay { ms: u16 },
+
+    /// Set the CW sidetone pitch in Hz
+    SetSidetonePitch { hz: u16 },
+
+    /// Turn RIT (the receive clarifier) on or off
+    SetRit { on: bool },
+
+    /// Move the RIT offset by `hz` (negative moves it down)
+    AdjustRit { hz: i32 },
+
+    /// Set the RIT offset to zero
+    ClearRit,
+
     /// Get the current VFO frequency
     GetFrequency,
 
@@ -115,6 +154,9 @@ pub enum RadioRequest {
     /// Request radio status (comprehensive)
     GetStatus,
 
+    /// Get transmitter output power setting
+    GetTxPower,
+
     /// Query auto-information state
     GetAutoInfo,
 
@@ -124,6 +166,27 @@ pub enum RadioRequest {
     /// Get transmit band (which VFO is selected for transmit)
     GetTransmitBand,
 
+    /// Get the keyer speed
+    GetKeyerSpeed,
+
+    /// Get the break-in setting
+    GetBreakIn,
+
+    /// Get the break-in delay
+    GetBreakInDelay,
+
+    /// Get the sidetone pitch
+    GetSidetonePitch,
+
+    /// Get whether RIT is on
+    GetRit,
+
+    /// Get the RIT offset
+    GetRitOffset,
+
+    /// Read a meter
+    GetMeter { meter: MeterKind },
+
     /// Unknown or unparseable request (preserves raw data)
     Unknown { data: Vec<u8> },
 }
@@ -133,7 +196,7 @@ pub enum RadioRequest {
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RadioResponse {
     /// Frequency report
-    Frequency { hz: u64 },
+    Frequency { hz: Frequency },
 
     /// Mode report
     Mode { mode: OperatingMode },
@@ -141,7 +204,13 @@ pub enum RadioResponse {
     /// PTT state report
     Ptt { active: bool },
 
+    /// Receive/transmit VFO or active receiver report
+    VfoState { state: VfoState },
+
     /// VFO selection report
+    ///
+    /// Older form of [`RadioResponse::VfoState`]; encoders accept it and
+    /// codecs no longer produce it.
     Vfo { vfo: Vfo },
 
     /// Radio identification response
@@ -149,12 +218,15 @@ pub enum RadioResponse {
 
     /// Radio status report (comprehensive)
     Status {
-        frequency_hz: Option<u64>,
+        frequency_hz: Option<Frequency>,
         mode: Option<OperatingMode>,
         ptt: Option<bool>,
         vfo: Option<Vfo>,
     },
 
+    /// Transmitter output power setting report (watts)
+    TxPower { watts: u16 },
+
     /// Auto-information state report
     AutoInfo { enabled: bool },
 
@@ -164,10 +236,55 @@ pub enum RadioResponse {
     /// Transmit band report (0=Main/A, 1=Sub/B)
     TransmitBand { band: u8 },
 
+    /// Meter reading, scaled to 0-255 of full scale (S9 is about 120)
+    Meter { meter: MeterKind, level: u8 },
+
+    /// Keyer speed report (words per minute)
+    KeyerSpeed { wpm: u8 },
+
+    /// Break-in setting report
+    BreakIn { mode: BreakIn },
+
+    /// Break-in delay report (milliseconds)
+    BreakInDelay { ms: u16 },
+
+    /// Sidetone pitch report (Hz)
+    SidetonePitch { hz: u16 },
+
+    /// RIT on/off report
+    Rit { on: bool },
+
+    /// RIT offset report (Hz, negative below the dial)
+    RitOffset { hz: i32 },
+
     /// Unknown or unparseable response (preserves raw data)
     Unknown { data: Vec<u8> },
 }
 
+/// Which meter a reading comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MeterKind {
+    /// Receive signal strength (S-meter)
+    Signal,
+    /// Transmit output power
+    Power,
+    /// Transmit SWR
+    Swr,
+}
+
+/// CW break-in: whether keying switches the transmitter, and how
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BreakIn {
+    /// Keying doesn't transmit
+    Off,
+    /// Semi break-in: back to receive once the delay runs out
+    Semi,
+    /// Full break-in (QSK): receive between elements
+    Full,
+}
+
 /// VFO selection
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -182,6 +299,103 @@ pub enum Vfo {
     Memory,
 }
 
+/// Receiver on a dual-receiver radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Receiver {
+    /// Main receiver (Icom MAIN, Yaesu MAIN, VFO A side)
+    Main,
+    /// Sub receiver (Icom SUB, Yaesu SUB, VFO B side)
+    Sub,
+}
+
+/// Which VFO a radio receives and transmits on, and which receiver has the controls
+///
+/// Protocols say this in different ways. Kenwood sets the receive VFO with
+/// `FR` and the transmit VFO with `FT`; Icom selects VFO A/B or the MAIN/SUB
+/// receiver and turns split on and off separately; Yaesu selects MAIN/SUB
+/// and turns split on and off. Each field holds what a frame says and is
+/// `None` where the frame says nothing about it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct VfoState {
+    /// VFO the radio receives (and tunes) on
+    pub rx_vfo: Option<Vfo>,
+    /// VFO the radio transmits on
+    pub tx_vfo: Option<Vfo>,
+    /// Receiver that has the controls
+    pub active_receiver: Option<Receiver>,
+    /// Transmit on the other VFO, for protocols that only turn split on and
+    /// off without naming the VFOs
+    pub split: Option<bool>,
+}
+
+impl VfoState {
+    /// Receive and transmit on `vfo`
+    pub fn select(vfo: Vfo) -> Self {
+        Self {
+            rx_vfo: Some(vfo),
+            tx_vfo: Some(vfo),
+            ..Self::default()
+        }
+    }
+
+    /// Transmit on `vfo`, receive VFO unchanged
+    pub fn transmit_on(vfo: Vfo) -> Self {
+        Self {
+            tx_vfo: Some(vfo),
+            ..Self::default()
+        }
+    }
+
+    /// Split on or off
+    pub fn with_split(on: bool) -> Self {
+        Self {
+            split: Some(on),
+            ..Self::default()
+        }
+    }
+
+    /// Give controls to a receiver
+    pub fn receiver(receiver: Receiver) -> Self {
+        Self {
+            active_receiver: Some(receiver),
+            ..Self::default()
+        }
+    }
+
+    /// Whether the radio transmits on a different VFO than it receives on,
+    /// if the state says
+    pub fn is_split(&self) -> Option<bool> {
+        match (self.rx_vfo, self.tx_vfo) {
+            (Some(rx), Some(tx)) => Some(rx != tx),
+            _ => self.split,
+        }
+    }
+
+    /// The state in the older single-[`Vfo`] form, where it has one
+    ///
+    /// Split reads as [`Vfo::Split`]; otherwise the receive VFO, or the
+    /// transmit VFO when only that is known.
+    pub fn legacy(&self) -> Option<Vfo> {
+        if self.is_split() == Some(true) {
+            return Some(Vfo::Split);
+        }
+        self.rx_vfo.or(self.tx_vfo)
+    }
+}
+
+impl From<Vfo> for VfoState {
+    /// The older single-VFO form: selecting A, B or memory receives and
+    /// transmits there (ending split), [`Vfo::Split`] turns split on
+    fn from(vfo: Vfo) -> Self {
+        match vfo {
+            Vfo::Split => Self::with_split(true),
+            vfo => Self::select(vfo),
+        }
+    }
+}
+
 impl RadioRequest {
     /// Returns true if this is a query command (Get*)
     pub fn is_query(&self) -> bool {
@@ -193,9 +407,15 @@ impl RadioRequest {
                 | Self::GetVfo
                 | Self::GetId
                 | Self::GetStatus
+                | Self::GetTxPower
                 | Self::GetAutoInfo
                 | Self::GetControlBand
                 | Self::GetTransmitBand
+                | Self::GetKeyerSpeed
+                | Self::GetBreakIn
+                | Self::GetBreakInDelay
+                | Self::GetSidetonePitch
+                | Self::GetMeter { .. }
         )
     }
 
@@ -205,17 +425,23 @@ impl RadioRequest {
             self,
             Self::SetFrequency { .. }
                 | Self::SetMode { .. }
+                | Self::SetFrequencyMode { .. }
                 | Self::SetPtt { .. }
                 | Self::SetVfo { .. }
                 | Self::SetPower { .. }
+                | Self::SetTxPower { .. }
                 | Self::SetAutoInfo { .. }
+                | Self::SetKeyerSpeed { .. }
+                | Self::SetBreakIn { .. }
+                | Self::SetBreakInDelay { .. }
+                | Self::SetSidetonePitch { .. }
         )
     }
 
     /// Extract frequency from request if present
-    pub fn frequency(&self) -> Option<u64> {
+    pub fn frequency(&self) -> Option<Frequency> {
         match self {
-            Self::SetFrequency { hz } => Some(*hz),
+            Self::SetFrequency { hz } | Self::SetFrequencyMode { hz, .. } => Some(*hz),
             _ => None,
         }
     }
@@ -223,7 +449,48 @@ impl RadioRequest {
     /// Extract mode from request if present
     pub fn mode(&self) -> Option<OperatingMode> {
         match self {
-            Self::SetMode { mode } => Some(*mode),
+            Self::SetMode { mode } | Self::SetFrequencyMode { mode, .. } => Some(*mode),
+            _ => None,
+        }
+    }
+
+    /// Split a compound request into the single-field requests it is made of
+    ///
+    /// Protocols without a combined command can encode each part in turn and
+    /// write them back-to-back. Simple requests are returned unchanged.
+    pub fn components(&self) -> Vec<RadioRequest> {
+        match self {
+            Self::SetFrequencyMode { hz, mode } => vec![
+                Self::SetFrequency { hz: *hz },
+                Self::SetMode { mode: *mode },
+            ],
+            // Receive first: selecting it moves the transmit VFO along too
+            Self::SetVfoState { state }
+                if state.rx_vfo.is_some()
+                    && state.tx_vfo.is_some()
+                    && state.rx_vfo != state.tx_vfo =>
+            {
+                vec![
+                    Self::SetVfoState {
+                        state: VfoState {
+                            tx_vfo: None,
+                            ..*state
+                        },
+                    },
+                    Self::SetVfoState {
+                        state: VfoState::transmit_on(state.tx_vfo.unwrap_or(Vfo::A)),
+                    },
+                ]
+            }
+            _ => vec![self.clone()],
+        }
+    }
+
+    /// VFO arrangement this request sets, in either form
+    pub fn vfo_state(&self) -> Option<VfoState> {
+        match self {
+            Self::SetVfoState { state } => Some(*state),
+            Self::SetVfo { vfo } => Some(VfoState::from(*vfo)),
             _ => None,
         }
     }
@@ -239,7 +506,7 @@ impl RadioRequest {
 
 impl RadioResponse {
     /// Extract frequency from response if present
-    pub fn frequency(&self) -> Option<u64> {
+    pub fn frequency(&self) -> Option<Frequency> {
         match self {
             Self::Frequency { hz } => Some(*hz),
             Self::Status { frequency_hz, .. } => *frequency_hz,
@@ -265,12 +532,46 @@ impl RadioResponse {
         }
     }
 
-    /// Extract VFO from response if present
+    /// Extract VFO from response if present, in the older single-VFO form
     pub fn vfo(&self) -> Option<Vfo> {
         match self {
             Self::Vfo { vfo } => Some(*vfo),
+            Self::VfoState { state } => state.legacy(),
             Self::Status { vfo, .. } => *vfo,
             _ => None,
         }
     }
+
+    /// VFO arrangement this response reports, in either form
+    pub fn vfo_state(&self) -> Option<VfoState> {
+        match self {
+            Self::VfoState { state } => Some(*state),
+            Self::Vfo { vfo } => Some(VfoState::from(*vfo)),
+            _ => None,
+        }
+    }
+
+    /// Extract output power setting (watts) from response if present
+    pub fn tx_power(&self) -> Option<u16> {
+        match self {
+            Self::TxPower { watts } => Some(*watts),
+            _ => None,
+        }
+    }
+
+    /// Extract a meter reading from response if present
+    pub fn meter(&self) -> Option<(MeterKind, u8)> {
+        match self {
+            Self::Meter { meter, level } => Some((*meter, *level)),
+            _ => None,
+        }
+    }
+
+    /// Extract keyer speed (wpm) from response if present
+    pub fn keyer_speed(&self) -> Option<u8> {
+        match self {
+            Self::KeyerSpeed { wpm } => Some(*wpm),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,972 @@
+//! Executable protocol conformance spec
+//!
+//! [`CASES`](crate::conformance::CASES) lists, for every protocol, the
+//! commands Catapult claims to support: the bytes on the wire, what they
+//! decode to, and what encoding the decoded value produces. The tests in this
+//! module run every case through the codecs, so a claim in the table cannot
+//! silently stop being true.
+//!
+//! The build script renders the same table with
+//! [`render_matrix`](crate::conformance::render_matrix); the result is this
+//! module's documentation and the checked-in
+//! `docs/src/protocols/conformance.md` page. A test fails when that page is
+//! out of date; regenerate it with
+//! `UPDATE_PROTOCOL_MATRIX=1 cargo test -p cat-protocol conformance`.
+//!
+//! This file is also compiled into the build script, so outside of tests it
+//! must only use `std`.
+//!
+//! Decoding uses [`create_radio_codec`], as the mux
+//! does, which parses Elecraft and FlexRadio with the Kenwood codec. Encoding
+//! uses each protocol's own command type; Icom frames are addressed to the
+//! broadcast address `00` from the controller `E0`, as the encoders produce.
+
+use std::fmt::Write as _;
+
+/// Which way a frame travels relative to the radio
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Sent to the radio; decoded as a `RadioRequest`
+    ToRadio,
+    /// Sent by the radio; decoded as a `RadioResponse`
+    FromRadio,
+}
+
+/// What encoding the decoded value produces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// Encodes back to the same bytes
+    RoundTrip,
+    /// Encodes to a different (canonical) frame
+    As(&'static [u8]),
+    /// Decoded only; the value has no encoding in this protocol
+    DecodeOnly,
+}
+
+/// One supported command and its wire behaviour
+#[derive(Debug, Clone, Copy)]
+pub struct ConformanceCase {
+    /// Protocol, as returned by `Protocol::name`
+    pub protocol: &'static str,
+    /// Command mnemonic or opcode
+    pub command: &'static str,
+    /// What the frame means
+    pub description: &'static str,
+    pub direction: Direction,
+    /// Frame bytes
+    pub wire: &'static [u8],
+    /// `Debug` form of the decoded request or response
+    pub decoded: &'static str,
+    pub encoding: Encoding,
+}
+
+const fn case(
+    protocol: &'static str,
+    command: &'static str,
+    description: &'static str,
+    direction: Direction,
+    wire: &'static [u8],
+    decoded: &'static str,
+    encoding: Encoding,
+) -> ConformanceCase {
+    ConformanceCase {
+        protocol,
+        command,
+        description,
+        direction,
+        wire,
+        decoded,
+        encoding,
+    }
+}
+
+use Direction::{FromRadio, ToRadio};
+use Encoding::{As, DecodeOnly, RoundTrip};
+
+const KENWOOD: &str = "Kenwood";
+const ELECRAFT: &str = "Elecraft";
+const FLEX: &str = "FlexRadio SmartSDR";
+const ICOM: &str = "Icom CI-V";
+const YAESU: &str = "Yaesu CAT";
+const YAESU_ASCII: &str = "Yaesu ASCII";
+
+/// The conformance table, grouped by protocol
+pub static CASES: &[ConformanceCase] = &[
+    // Kenwood
+    case(
+        KENWOOD,
+        "FA",
+        "Set VFO A frequency",
+        ToRadio,
+        b"FA00014250000;",
+        "SetFrequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "FA",
+        "Query frequency",
+        ToRadio,
+        b"FA;",
+        "GetFrequency",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "FA",
+        "VFO A frequency report",
+        FromRadio,
+        b"FA00014250000;",
+        "Frequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "FB",
+        "VFO B frequency report",
+        FromRadio,
+        b"FB00007074000;",
+        "Frequency { hz: 7074000 }",
+        As(b"FA00007074000;"),
+    ),
+    case(
+        KENWOOD,
+        "MD",
+        "Set mode",
+        ToRadio,
+        b"MD2;",
+        "SetMode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "MD",
+        "Query mode",
+        ToRadio,
+        b"MD;",
+        "GetMode",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "MD",
+        "Mode report",
+        FromRadio,
+        b"MD2;",
+        "Mode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "TX",
+        "Key transmitter",
+        ToRadio,
+        b"TX;",
+        "SetPtt { active: true }",
+        As(b"TX1;"),
+    ),
+    case(
+        KENWOOD,
+        "RX",
+        "Unkey transmitter",
+        ToRadio,
+        b"RX;",
+        "SetPtt { active: false }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "IF",
+        "Query status",
+        ToRadio,
+        b"IF;",
+        "GetStatus",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "ID",
+        "Query radio ID",
+        ToRadio,
+        b"ID;",
+        "GetId",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "ID",
+        "Radio ID report",
+        FromRadio,
+        b"ID019;",
+        "Id { id: \"019\" }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "AI",
+        "Enable auto-information",
+        ToRadio,
+        b"AI2;",
+        "SetAutoInfo { enabled: true }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "AI",
+        "Query auto-information",
+        ToRadio,
+        b"AI;",
+        "GetAutoInfo",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "FR",
+        "Select VFO B",
+        ToRadio,
+        b"FR1;",
+        "SetVfoState { state: VfoState { rx_vfo: Some(B), tx_vfo: Some(B), active_receiver: None, split: None } }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "FT",
+        "Transmit on VFO B (split)",
+        ToRadio,
+        b"FT1;",
+        "SetVfoState { state: VfoState { rx_vfo: None, tx_vfo: Some(B), active_receiver: None, split: None } }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "PC",
+        "Set output power",
+        ToRadio,
+        b"PC100;",
+        "SetTxPower { watts: 100 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "PC",
+        "Output power report",
+        FromRadio,
+        b"PC100;",
+        "TxPower { watts: 100 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "KS",
+        "Set keyer speed",
+        ToRadio,
+        b"KS020;",
+        "SetKeyerSpeed { wpm: 20 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "KS",
+        "Keyer speed report",
+        FromRadio,
+        b"KS020;",
+        "KeyerSpeed { wpm: 20 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "SD",
+        "Set break-in delay",
+        ToRadio,
+        b"SD0300;",
+        "SetBreakInDelay { ms: 300 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "PT",
+        "Set sidetone pitch",
+        ToRadio,
+        b"PT06;",
+        "SetSidetonePitch { hz: 600 }",
+        RoundTrip,
+    ),
+    case(
+        KENWOOD,
+        "PS",
+        "Power on",
+        ToRadio,
+        b"PS1;",
+        "SetPower { on: true }",
+        RoundTrip,
+    ),
+    // Elecraft
+    case(
+        ELECRAFT,
+        "FA",
+        "Set VFO A frequency",
+        ToRadio,
+        b"FA00014250000;",
+        "SetFrequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        ELECRAFT,
+        "FA",
+        "VFO A frequency report",
+        FromRadio,
+        b"FA00014250000;",
+        "Frequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        ELECRAFT,
+        "MD",
+        "Set mode",
+        ToRadio,
+        b"MD3;",
+        "SetMode { mode: Cw }",
+        RoundTrip,
+    ),
+    case(
+        ELECRAFT,
+        "TX",
+        "Key transmitter",
+        ToRadio,
+        b"TX;",
+        "SetPtt { active: true }",
+        As(b"TX1;"),
+    ),
+    case(
+        ELECRAFT,
+        "TX",
+        "Transmitting report",
+        FromRadio,
+        b"TX;",
+        "Ptt { active: true }",
+        As(b"TQ1;"),
+    ),
+    case(
+        ELECRAFT,
+        "KS",
+        "Set keyer speed",
+        ToRadio,
+        b"KS020;",
+        "SetKeyerSpeed { wpm: 20 }",
+        RoundTrip,
+    ),
+    // FlexRadio
+    case(
+        FLEX,
+        "FA",
+        "Set frequency",
+        ToRadio,
+        b"FA00014250000;",
+        "SetFrequency { hz: 14250000 }",
+        As(b"ZZFA00014250000;"),
+    ),
+    case(
+        FLEX,
+        "FA",
+        "Frequency report",
+        FromRadio,
+        b"FA00014250000;",
+        "Frequency { hz: 14250000 }",
+        As(b"ZZFA00014250000;"),
+    ),
+    // Icom CI-V
+    case(
+        ICOM,
+        "0x05",
+        "Set frequency",
+        ToRadio,
+        &[
+            0xFE, 0xFE, 0x00, 0xE0, 0x05, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ],
+        "SetFrequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x03",
+        "Query frequency",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x03, 0xFD],
+        "GetFrequency",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x03",
+        "Frequency report",
+        FromRadio,
+        &[
+            0xFE, 0xFE, 0x00, 0xE0, 0x03, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ],
+        "Frequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x00",
+        "Transceive frequency report",
+        FromRadio,
+        &[
+            0xFE, 0xFE, 0x00, 0xE0, 0x00, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ],
+        "Frequency { hz: 14250000 }",
+        As(&[
+            0xFE, 0xFE, 0x00, 0xE0, 0x03, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ]),
+    ),
+    case(
+        ICOM,
+        "0x06",
+        "Set mode",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x06, 0x01, 0x01, 0xFD],
+        "SetMode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x04",
+        "Query mode",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x04, 0xFD],
+        "GetMode",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x04",
+        "Mode report",
+        FromRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x04, 0x01, 0x01, 0xFD],
+        "Mode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x01",
+        "Transceive mode report",
+        FromRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x01, 0x01, 0x01, 0xFD],
+        "Mode { mode: Usb }",
+        As(&[0xFE, 0xFE, 0x00, 0xE0, 0x04, 0x01, 0x01, 0xFD]),
+    ),
+    case(
+        ICOM,
+        "0x1C 00",
+        "Key transmitter",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x1C, 0x00, 0x01, 0xFD],
+        "SetPtt { active: true }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x1C 00",
+        "Query transmit state",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x1C, 0x00, 0xFD],
+        "GetPtt",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x1C 00",
+        "Transmit state report",
+        FromRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x1C, 0x00, 0x01, 0xFD],
+        "Ptt { active: true }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x07",
+        "Select VFO B",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x07, 0x01, 0xFD],
+        "SetVfoState { state: VfoState { rx_vfo: Some(B), tx_vfo: Some(B), active_receiver: None, split: None } }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x07 D1",
+        "Select SUB receiver",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x07, 0xD1, 0xFD],
+        "SetVfoState { state: VfoState { rx_vfo: None, tx_vfo: None, active_receiver: Some(Sub), split: None } }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x0F",
+        "Split on",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x0F, 0x01, 0xFD],
+        "SetVfoState { state: VfoState { rx_vfo: None, tx_vfo: None, active_receiver: None, split: Some(true) } }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x0F",
+        "Query split",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x0F, 0xFD],
+        "GetVfo",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x0F",
+        "Split report",
+        FromRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x0F, 0x00, 0xFD],
+        "VfoState { state: VfoState { rx_vfo: None, tx_vfo: None, active_receiver: None, split: Some(false) } }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x15 02",
+        "S-meter report",
+        FromRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x15, 0x02, 0x01, 0x20, 0xFD],
+        "Meter { meter: Signal, level: 120 }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x14 0C",
+        "Set keyer speed",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x14, 0x0C, 0x00, 0x85, 0xFD],
+        "SetKeyerSpeed { wpm: 20 }",
+        RoundTrip,
+    ),
+    case(
+        ICOM,
+        "0x16 47",
+        "Full break-in",
+        ToRadio,
+        &[0xFE, 0xFE, 0x00, 0xE0, 0x16, 0x47, 0x02, 0xFD],
+        "SetBreakIn { mode: Full }",
+        RoundTrip,
+    ),
+    // Yaesu CAT
+    case(
+        YAESU,
+        "0x01",
+        "Set frequency",
+        ToRadio,
+        &[0x01, 0x42, 0x50, 0x00, 0x01],
+        "SetFrequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU,
+        "0x03",
+        "Query frequency and mode",
+        ToRadio,
+        &[0x00, 0x00, 0x00, 0x00, 0x03],
+        "GetFrequency",
+        RoundTrip,
+    ),
+    case(
+        YAESU,
+        "0x07",
+        "Set mode",
+        ToRadio,
+        &[0x01, 0x00, 0x00, 0x00, 0x07],
+        "SetMode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        YAESU,
+        "0x08",
+        "Key transmitter",
+        ToRadio,
+        &[0x00, 0x00, 0x00, 0x00, 0x08],
+        "SetPtt { active: true }",
+        RoundTrip,
+    ),
+    case(
+        YAESU,
+        "0x88",
+        "Unkey transmitter",
+        ToRadio,
+        &[0x00, 0x00, 0x00, 0x00, 0x88],
+        "SetPtt { active: false }",
+        RoundTrip,
+    ),
+    case(
+        YAESU,
+        "0x81",
+        "Toggle VFO",
+        ToRadio,
+        &[0x00, 0x00, 0x00, 0x00, 0x81],
+        "SetVfoState { state: VfoState { rx_vfo: Some(B), tx_vfo: Some(B), active_receiver: None, split: None } }",
+        RoundTrip,
+    ),
+    // Yaesu ASCII
+    case(
+        YAESU_ASCII,
+        "FA",
+        "Set VFO A frequency",
+        ToRadio,
+        b"FA014250000;",
+        "SetFrequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "FA",
+        "Query frequency",
+        ToRadio,
+        b"FA;",
+        "GetFrequency",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "FA",
+        "VFO A frequency report",
+        FromRadio,
+        b"FA014250000;",
+        "Frequency { hz: 14250000 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "FB",
+        "VFO B frequency report",
+        FromRadio,
+        b"FB007074000;",
+        "Frequency { hz: 7074000 }",
+        As(b"FA007074000;"),
+    ),
+    case(
+        YAESU_ASCII,
+        "MD",
+        "Set mode",
+        ToRadio,
+        b"MD02;",
+        "SetMode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "MD",
+        "Query mode",
+        ToRadio,
+        b"MD0;",
+        "GetMode",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "MD",
+        "Mode report",
+        FromRadio,
+        b"MD02;",
+        "Mode { mode: Usb }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "TX",
+        "Key transmitter",
+        ToRadio,
+        b"TX1;",
+        "SetPtt { active: true }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "TX",
+        "Receiving report",
+        FromRadio,
+        b"TX0;",
+        "Ptt { active: false }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "ID",
+        "Query radio ID",
+        ToRadio,
+        b"ID;",
+        "GetId",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "ID",
+        "Radio ID report",
+        FromRadio,
+        b"ID0670;",
+        "Id { id: \"0670\" }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "AI",
+        "Enable auto-information",
+        ToRadio,
+        b"AI1;",
+        "SetAutoInfo { enabled: true }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "PC",
+        "Set output power",
+        ToRadio,
+        b"PC100;",
+        "SetTxPower { watts: 100 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "KS",
+        "Set keyer speed",
+        ToRadio,
+        b"KS020;",
+        "SetKeyerSpeed { wpm: 20 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "BI",
+        "Break-in on",
+        ToRadio,
+        b"BI1;",
+        "SetBreakIn { mode: Semi }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "SD",
+        "Set break-in delay",
+        ToRadio,
+        b"SD0300;",
+        "SetBreakInDelay { ms: 300 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "KP",
+        "Set key pitch",
+        ToRadio,
+        b"KP30;",
+        "SetSidetonePitch { hz: 600 }",
+        RoundTrip,
+    ),
+    case(
+        YAESU_ASCII,
+        "VS",
+        "Select VFO B",
+        ToRadio,
+        b"VS1;",
+        "SetVfoState { state: VfoState { rx_vfo: Some(B), tx_vfo: None, active_receiver: Some(Sub), split: None } }",
+        RoundTrip,
+    ),
+];
+
+/// Show a frame as text when it is printable ASCII, otherwise as hex
+fn wire_text(bytes: &[u8]) -> String {
+    if bytes.iter().all(|b| b.is_ascii_graphic()) {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes
+            .iter()
+            .map(|b| format!("{:02X}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Render [`CASES`] as a markdown page, one table per protocol
+pub fn render_matrix() -> String {
+    let mut out = String::new();
+    out.push_str("# Protocol Conformance Matrix\n\n");
+    out.push_str(
+        "Generated from `crates/cat-protocol/src/conformance.rs`; every row is \
+         checked by the cat-protocol tests. Do not edit by hand.\n",
+    );
+
+    let mut protocols: Vec<&str> = Vec::new();
+    for case in CASES {
+        if !protocols.contains(&case.protocol) {
+            protocols.push(case.protocol);
+        }
+    }
+
+    for protocol in protocols {
+        let _ = write!(
+            out,
+            "\n## {}\n\n\
+             | Command | Description | Direction | Wire | Decodes to | Encodes as |\n\
+             |---------|-------------|-----------|------|------------|------------|\n",
+            protocol
+        );
+        for case in CASES.iter().filter(|c| c.protocol == protocol) {
+            let direction = match case.direction {
+                ToRadio => "to radio",
+                FromRadio => "from radio",
+            };
+            let encoding = match case.encoding {
+                RoundTrip => "same".to_string(),
+                As(bytes) => format!("`{}`", wire_text(bytes)),
+                DecodeOnly => "-".to_string(),
+            };
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | `{}` | `{}` | {} |",
+                case.command,
+                case.description,
+                direction,
+                wire_text(case.wire),
+                case.decoded,
+                encoding
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elecraft::ElecraftCommand;
+    use crate::flex::FlexCommand;
+    use crate::icom::CivCommand;
+    use crate::kenwood::KenwoodCommand;
+    use crate::yaesu::YaesuCommand;
+    use crate::yaesu_ascii::YaesuAsciiCommand;
+    use crate::{
+        create_radio_codec, encode_request_sequence, EncodeCommand, FromRadioResponse, Protocol,
+        RadioRequest, RadioResponse,
+    };
+
+    const PROTOCOLS: [Protocol; 6] = [
+        Protocol::Kenwood,
+        Protocol::Elecraft,
+        Protocol::FlexRadio,
+        Protocol::IcomCIV,
+        Protocol::Yaesu,
+        Protocol::YaesuAscii,
+    ];
+
+    fn protocol(name: &str) -> Protocol {
+        PROTOCOLS
+            .into_iter()
+            .find(|p| p.name() == name)
+            .unwrap_or_else(|| panic!("unknown protocol {name:?}"))
+    }
+
+    fn encode_request(protocol: Protocol, req: &RadioRequest) -> Option<Vec<u8>> {
+        match protocol {
+            Protocol::Kenwood => encode_request_sequence::<KenwoodCommand>(req),
+            Protocol::Elecraft => encode_request_sequence::<ElecraftCommand>(req),
+            Protocol::FlexRadio => encode_request_sequence::<FlexCommand>(req),
+            Protocol::IcomCIV => encode_request_sequence::<CivCommand>(req),
+            Protocol::Yaesu => encode_request_sequence::<YaesuCommand>(req),
+            Protocol::YaesuAscii => encode_request_sequence::<YaesuAsciiCommand>(req),
+        }
+    }
+
+    fn encode_response(protocol: Protocol, resp: &RadioResponse) -> Option<Vec<u8>> {
+        match protocol {
+            Protocol::Kenwood => KenwoodCommand::from_radio_response(resp).map(|c| c.encode()),
+            Protocol::Elecraft => ElecraftCommand::from_radio_response(resp).map(|c| c.encode()),
+            Protocol::FlexRadio => FlexCommand::from_radio_response(resp).map(|c| c.encode()),
+            Protocol::IcomCIV => CivCommand::from_radio_response(resp).map(|c| c.encode()),
+            Protocol::Yaesu => YaesuCommand::from_radio_response(resp).map(|c| c.encode()),
+            Protocol::YaesuAscii => {
+                YaesuAsciiCommand::from_radio_response(resp).map(|c| c.encode())
+            }
+        }
+    }
+
+    /// Decode one frame; returns the `Debug` form and the encoding of the result
+    fn run(case: &ConformanceCase) -> Result<(String, Option<Vec<u8>>), String> {
+        let protocol = protocol(case.protocol);
+        let mut codec = create_radio_codec(protocol);
+        codec.push_bytes(case.wire);
+        let (decoded, bytes, encoded) = match case.direction {
+            ToRadio => {
+                let (req, bytes) = codec.next_request_with_bytes().ok_or("no frame")?;
+                let encoded = encode_request(protocol, &req);
+                (format!("{:?}", req), bytes, encoded)
+            }
+            FromRadio => {
+                let (resp, bytes) = codec.next_response_with_bytes().ok_or("no frame")?;
+                let encoded = encode_response(protocol, &resp);
+                (format!("{:?}", resp), bytes, encoded)
+            }
+        };
+        if bytes != case.wire {
+            return Err(format!("frame was {}", wire_text(&bytes)));
+        }
+        Ok((decoded, encoded))
+    }
+
+    #[test]
+    fn test_conformance_cases() {
+        let mut failures = Vec::new();
+        for case in CASES {
+            let label = format!(
+                "{} {} ({:?}) {}",
+                case.protocol,
+                case.command,
+                case.direction,
+                wire_text(case.wire)
+            );
+            let (decoded, encoded) = match run(case) {
+                Ok(result) => result,
+                Err(e) => {
+                    failures.push(format!("{label}: {e}"));
+                    continue;
+                }
+            };
+            if decoded != case.decoded {
+                failures.push(format!(
+                    "{label}: decoded {decoded}, expected {}",
+                    case.decoded
+                ));
+            }
+            let expected = match case.encoding {
+                RoundTrip => Some(case.wire),
+                As(bytes) => Some(bytes),
+                DecodeOnly => None,
+            };
+            if encoded.as_deref() != expected {
+                failures.push(format!(
+                    "{label}: encoded {:?}, expected {:?}",
+                    encoded.as_deref().map(wire_text),
+                    expected.map(wire_text)
+                ));
+            }
+        }
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_docs_matrix_is_current() {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../../docs/src/protocols/conformance.md");
+        let rendered = render_matrix();
+        if std::env::var_os("UPDATE_PROTOCOL_MATRIX").is_some() {
+            std::fs::write(&path, &rendered).unwrap();
+            return;
+        }
+        let current = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            current == rendered,
+            "{} is out of date; rerun with UPDATE_PROTOCOL_MATRIX=1",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,151 @@
+//! Codecs that only parse one direction of traffic
+//!
+//! The same bytes mean different things depending on who sent them (see the
+//! crate docs): `FA;` from an amplifier asks for the frequency, but read as a
+//! radio's report it is a frequency of nothing. A [`RadioCodec`] will parse
+//! either way, so mixing them up compiles and fails quietly.
+//!
+//! [`RadioSideCodec`] reads what a radio sends and only yields
+//! [`RadioResponse`]s; [`ControllerSideCodec`] reads what a controller (an
+//! amplifier, logger or the mux itself) sends to a radio and only yields
+//! [`RadioRequest`]s. Holding one of these instead of a `Box<dyn RadioCodec>`
+//! makes the direction part of the type.
+
+use crate::{
+    create_model_codec, create_radio_codec, CommandHooks, CustomEvent, HookedCodec, Protocol,
+    RadioCodec, RadioModel, RadioRequest, RadioResponse,
+};
+
+/// Parses traffic from a radio into [`RadioResponse`]s
+pub struct RadioSideCodec {
+    inner: Box<dyn RadioCodec>,
+}
+
+impl RadioSideCodec {
+    /// A codec for the given protocol
+    pub fn new(protocol: Protocol) -> Self {
+        Self::from_codec(create_radio_codec(protocol))
+    }
+
+    /// A codec for the given protocol, adjusted for a known radio model
+    pub fn for_model(protocol: Protocol, model: Option<&RadioModel>) -> Self {
+        Self::from_codec(create_model_codec(protocol, model))
+    }
+
+    /// Read radio traffic with an existing codec
+    pub fn from_codec(inner: Box<dyn RadioCodec>) -> Self {
+        Self { inner }
+    }
+
+    /// Offer frames the codec doesn't know to user command handlers
+    pub fn with_hooks(self, hooks: CommandHooks) -> Self {
+        Self::from_codec(Box::new(HookedCodec::new(self.inner, hooks)))
+    }
+
+    /// Push raw bytes from the radio
+    pub fn push_bytes(&mut self, data: &[u8]) {
+        self.inner.push_bytes(data);
+    }
+
+    /// Try to extract the next complete response
+    pub fn next_response(&mut self) -> Option<RadioResponse> {
+        self.inner.next_response()
+    }
+
+    /// Try to extract the next complete response along with its raw bytes
+    pub fn next_response_with_bytes(&mut self) -> Option<(RadioResponse, Vec<u8>)> {
+        self.inner.next_response_with_bytes()
+    }
+
+    /// Clear the internal buffer
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Bytes buffered towards a frame that hasn't completed yet
+    pub fn buffered(&self) -> &[u8] {
+        self.inner.buffered()
+    }
+
+    /// Events reported by user command handlers since the last call
+    pub fn take_custom_events(&mut self) -> Vec<CustomEvent> {
+        self.inner.take_custom_events()
+    }
+}
+
+/// Parses traffic sent to a radio into [`RadioRequest`]s
+pub struct ControllerSideCodec {
+    inner: Box<dyn RadioCodec>,
+}
+
+impl ControllerSideCodec {
+    /// A codec for the given protocol
+    pub fn new(protocol: Protocol) -> Self {
+        Self::from_codec(create_radio_codec(protocol))
+    }
+
+    /// A codec for the given protocol, adjusted for a known radio model
+    pub fn for_model(protocol: Protocol, model: Option<&RadioModel>) -> Self {
+        Self::from_codec(create_model_codec(protocol, model))
+    }
+
+    /// Read controller traffic with an existing codec
+    pub fn from_codec(inner: Box<dyn RadioCodec>) -> Self {
+        Self { inner }
+    }
+
+    /// Push raw bytes from the controller
+    pub fn push_bytes(&mut self, data: &[u8]) {
+        self.inner.push_bytes(data);
+    }
+
+    /// Try to extract the next complete request
+    pub fn next_request(&mut self) -> Option<RadioRequest> {
+        self.inner.next_request()
+    }
+
+    /// Try to extract the next complete request along with its raw bytes
+    pub fn next_request_with_bytes(&mut self) -> Option<(RadioRequest, Vec<u8>)> {
+        self.inner.next_request_with_bytes()
+    }
+
+    /// Clear the internal buffer
+    pub fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    /// Bytes buffered towards a frame that hasn't completed yet
+    pub fn buffered(&self) -> &[u8] {
+        self.inner.buffered()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frequency;
+
+    #[test]
+    fn test_same_bytes_each_side() {
+        let mut controller = ControllerSideCodec::new(Protocol::Kenwood);
+        controller.push_bytes(b"FA;FA00014250000;");
+        assert_eq!(controller.next_request(), Some(RadioRequest::GetFrequency));
+        assert_eq!(
+            controller.next_request(),
+            Some(RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(14_250_000)
+            })
+        );
+
+        let mut radio = RadioSideCodec::new(Protocol::Kenwood);
+        radio.push_bytes(b"FA00014250000;FA0001425");
+        assert_eq!(
+            radio.next_response(),
+            Some(RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000)
+            })
+        );
+        assert_eq!(radio.next_response(), None);
+        assert_eq!(radio.buffered(), b"FA0001425");
+    }
+}
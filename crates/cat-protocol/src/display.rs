@@ -2,17 +2,23 @@
 //!
 //! This module provides types and traits for annotating decoded protocol frames
 //! with human-readable information. The UI can use these annotations to display
-//! colored hex dumps with tooltips and decoded summaries.
+//! colored hex dumps with tooltips and decoded summaries; [`format_frame`] and
+//! [`format_frame_detailed`] render the same annotations as plain text for
+//! logs, exports and command-line tools.
 
 use std::ops::Range;
+use std::sync::atomic::{AtomicU8, Ordering};
 
-use crate::command::OperatingMode;
+use crate::command::{BreakIn, OperatingMode, RadioRequest};
+use crate::elecraft::{ElecraftCodec, ElecraftCommand};
 use crate::flex::{FlexCodec, FlexCommand, FlexMode};
 use crate::icom::{CivCodec, CivCommand, CivCommandType, PREAMBLE, TERMINATOR};
 use crate::kenwood::{KenwoodCodec, KenwoodCommand};
 use crate::yaesu::YaesuCommand;
 use crate::yaesu_ascii::{YaesuAsciiCodec, YaesuAsciiCommand};
-use crate::ProtocolCodec;
+use crate::yaesu_menu::{menu_table_for_digits, menu_table_for_model, DEFAULT_MENU_DIGITS};
+use crate::RadioModel;
+use crate::{Frequency, ProtocolCodec, ToRadioRequest};
 
 /// Type of segment for UI coloring
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -103,6 +109,13 @@ pub struct AnnotatedFrame {
     pub segments: Vec<FrameSegment>,
 }
 
+impl AnnotatedFrame {
+    /// The summary as plain text
+    pub fn summary_text(&self) -> String {
+        self.summary.iter().map(|p| p.text.as_str()).collect()
+    }
+}
+
 /// Trait for commands that can describe their display representation
 pub trait FrameAnnotation {
     /// Create an annotated frame from this command and its raw bytes
@@ -113,18 +126,201 @@ pub trait FrameAnnotation {
 // Format Helpers
 // ============================================================================
 
-/// Format frequency in MHz with appropriate precision
-pub fn format_frequency(hz: u64) -> String {
-    let mhz = hz as f64 / 1_000_000.0;
-    if hz.is_multiple_of(1000) {
-        // kHz resolution
-        format!("{:.3} MHz", mhz)
-    } else {
-        // Hz resolution
-        format!("{:.6} MHz", mhz)
+/// How frequencies are laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FrequencyStyle {
+    /// `14.250 MHz`, or `14.074500 MHz` off a kHz boundary
+    #[default]
+    Mhz,
+    /// `14.250.000 MHz`, grouped like a radio's display
+    MhzDotted,
+    /// `14250.00 kHz`, as contest loggers show it
+    Khz,
+    /// `14,250.00 kHz`
+    KhzGrouped,
+}
+
+impl FrequencyStyle {
+    pub const ALL: [FrequencyStyle; 4] = [
+        FrequencyStyle::Mhz,
+        FrequencyStyle::MhzDotted,
+        FrequencyStyle::Khz,
+        FrequencyStyle::KhzGrouped,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FrequencyStyle::Mhz => "MHz (14.250)",
+            FrequencyStyle::MhzDotted => "MHz dotted (14.250.000)",
+            FrequencyStyle::Khz => "kHz (14250.00)",
+            FrequencyStyle::KhzGrouped => "kHz grouped (14,250.00)",
+        }
+    }
+}
+
+/// Decimal separator for frequencies
+///
+/// With a comma, digit groups are separated with a dot instead
+/// (`14.250,00 kHz`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DecimalSeparator {
+    #[default]
+    Point,
+    Comma,
+}
+
+impl DecimalSeparator {
+    pub const ALL: [DecimalSeparator; 2] = [DecimalSeparator::Point, DecimalSeparator::Comma];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            DecimalSeparator::Point => "Point (14.250)",
+            DecimalSeparator::Comma => "Comma (14,250)",
+        }
+    }
+
+    fn decimal(self) -> char {
+        match self {
+            DecimalSeparator::Point => '.',
+            DecimalSeparator::Comma => ',',
+        }
+    }
+
+    /// Write `value` with `places` decimals and this separator
+    pub fn format_decimal(self, value: f64, places: usize) -> String {
+        let text = format!("{:.*}", places, value);
+        match self {
+            DecimalSeparator::Point => text,
+            DecimalSeparator::Comma => text.replace('.', ","),
+        }
+    }
+
+    fn group(self) -> char {
+        match self {
+            DecimalSeparator::Point => ',',
+            DecimalSeparator::Comma => '.',
+        }
+    }
+}
+
+/// How [`format_frequency`] writes frequencies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FrequencyFormat {
+    pub style: FrequencyStyle,
+    pub decimal: DecimalSeparator,
+}
+
+impl FrequencyFormat {
+    /// Format a frequency, with its unit
+    ///
+    /// Digits are cut off rather than rounded, as a radio's display does.
+    pub fn format(&self, frequency: Frequency) -> String {
+        let mut out = self.format_number(frequency);
+        match self.style {
+            FrequencyStyle::Mhz | FrequencyStyle::MhzDotted => out.push_str(" MHz"),
+            FrequencyStyle::Khz | FrequencyStyle::KhzGrouped => out.push_str(" kHz"),
+        }
+        out
+    }
+
+    /// Format a frequency without its unit
+    pub fn format_number(&self, frequency: Frequency) -> String {
+        let hz = frequency.hz();
+        let decimal = self.decimal.decimal();
+        match self.style {
+            FrequencyStyle::Mhz => {
+                let (mhz, rest) = (hz / 1_000_000, hz % 1_000_000);
+                if hz.is_multiple_of(1000) {
+                    // kHz resolution
+                    format!("{}{}{:03}", mhz, decimal, rest / 1000)
+                } else {
+                    // Hz resolution
+                    format!("{}{}{:06}", mhz, decimal, rest)
+                }
+            }
+            FrequencyStyle::MhzDotted => {
+                let (mhz, rest) = (hz / 1_000_000, hz % 1_000_000);
+                format!("{}{}{:03}.{:03}", mhz, decimal, rest / 1000, rest % 1000)
+            }
+            FrequencyStyle::Khz => format!("{}{}{:02}", hz / 1000, decimal, hz % 1000 / 10),
+            FrequencyStyle::KhzGrouped => format!(
+                "{}{}{:02}",
+                group_thousands(hz / 1000, self.decimal.group()),
+                decimal,
+                hz % 1000 / 10
+            ),
+        }
+    }
+
+    fn to_bits(self) -> u8 {
+        let style = FrequencyStyle::ALL
+            .iter()
+            .position(|s| *s == self.style)
+            .unwrap_or(0) as u8;
+        style | (u8::from(self.decimal == DecimalSeparator::Comma) << 4)
+    }
+
+    fn from_bits(bits: u8) -> Self {
+        Self {
+            style: FrequencyStyle::ALL
+                .get(usize::from(bits & 0x0F))
+                .copied()
+                .unwrap_or_default(),
+            decimal: if bits & 0x10 != 0 {
+                DecimalSeparator::Comma
+            } else {
+                DecimalSeparator::Point
+            },
+        }
     }
 }
 
+/// `14250` -> `14,250`
+fn group_thousands(value: u64, separator: char) -> String {
+    let digits = value.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// The format every frequency shown to the user goes through
+///
+/// Annotations are produced deep inside the decoders, so the user's choice is
+/// held here for the whole process rather than passed down to each of them.
+static FREQUENCY_FORMAT: AtomicU8 = AtomicU8::new(0);
+
+/// Choose how [`format_frequency`] writes frequencies from now on
+///
+/// Text already produced (cached annotations, log lines) keeps its format.
+pub fn set_frequency_format(format: FrequencyFormat) {
+    FREQUENCY_FORMAT.store(format.to_bits(), Ordering::Relaxed);
+}
+
+/// The format [`format_frequency`] currently uses
+pub fn frequency_format() -> FrequencyFormat {
+    FrequencyFormat::from_bits(FREQUENCY_FORMAT.load(Ordering::Relaxed))
+}
+
+/// Format a frequency for display in the chosen [`FrequencyFormat`]
+pub fn format_frequency(frequency: Frequency) -> String {
+    frequency_format().format(frequency)
+}
+
+/// Format any other number shown to the user with the same decimal
+/// separator as frequencies
+pub fn format_decimal(value: f64, places: usize) -> String {
+    frequency_format().decimal.format_decimal(value, places)
+}
+
 /// Format an operating mode as a human-readable string
 pub fn format_mode(mode: OperatingMode) -> &'static str {
     match mode {
@@ -213,6 +409,7 @@ fn format_yaesu_ascii_mode(mode: u8) -> &'static str {
         12 => "DATA-U",
         13 => "AM-N",
         14 => "C4FM",
+        15 => "DATA-FM-N",
         _ => "?",
     }
 }
@@ -221,6 +418,71 @@ fn format_yaesu_ascii_mode(mode: u8) -> &'static str {
 // FrameAnnotation for CivCommand
 // ============================================================================
 
+/// Summary of a CW keying setting, from the request it decodes to
+fn cw_setting_summary(request: &RadioRequest, cmd_range: Range<usize>) -> Vec<SummaryPart> {
+    let (label, value) = match request {
+        RadioRequest::SetKeyerSpeed { wpm } => ("Keyer Speed", Some(format!("{} WPM", wpm))),
+        RadioRequest::GetKeyerSpeed => ("Keyer Speed", None),
+        RadioRequest::SetBreakIn { mode } => (
+            "Break-in",
+            Some(
+                match mode {
+                    BreakIn::Off => "OFF",
+                    BreakIn::Semi => "SEMI",
+                    BreakIn::Full => "FULL",
+                }
+                .to_string(),
+            ),
+        ),
+        RadioRequest::GetBreakIn => ("Break-in", None),
+        RadioRequest::SetBreakInDelay { ms } => ("Break-in Delay", Some(format!("{} ms", ms))),
+        RadioRequest::GetBreakInDelay => ("Break-in Delay", None),
+        RadioRequest::SetSidetonePitch { hz } => ("CW Pitch", Some(format!("{} Hz", hz))),
+        RadioRequest::GetSidetonePitch => ("CW Pitch", None),
+        _ => ("CW Setting", None),
+    };
+    match value {
+        Some(value) => vec![
+            SummaryPart::with_range(label, SegmentType::Command, cmd_range),
+            SummaryPart::plain(" "),
+            SummaryPart::typed(value, SegmentType::Data),
+        ],
+        None => vec![SummaryPart::with_range(
+            format!("Get {}", label),
+            SegmentType::Command,
+            cmd_range,
+        )],
+    }
+}
+
+/// Summary for a RIT (clarifier) command, from its normalized request
+fn rit_summary(request: &RadioRequest, cmd_range: Range<usize>) -> Vec<SummaryPart> {
+    let value = match request {
+        RadioRequest::SetRit { on } => Some(if *on { "ON" } else { "OFF" }.to_string()),
+        RadioRequest::AdjustRit { hz } => Some(format!("{:+} Hz", hz)),
+        RadioRequest::ClearRit => {
+            return vec![SummaryPart::with_range(
+                "Clear RIT",
+                SegmentType::Command,
+                cmd_range,
+            )]
+        }
+        _ => None,
+    };
+    match value {
+        Some(value) => vec![
+            SummaryPart::with_range("RIT", SegmentType::Command, cmd_range),
+            SummaryPart::plain(" "),
+            SummaryPart::typed(value, SegmentType::Data),
+        ],
+        None => vec![SummaryPart::with_range(
+            "Get RIT",
+            SegmentType::Command,
+            cmd_range,
+        )],
+    }
+}
+
 /// Format CI-V address as descriptive string
 fn format_civ_address(addr: u8) -> String {
     match addr {
@@ -283,7 +545,7 @@ impl FrameAnnotation for CivCommand {
                     segments.push(FrameSegment {
                         range: 5..(data_len - 1),
                         label: "freq",
-                        value: format_frequency(*hz),
+                        value: format_frequency(Frequency::from_hz(*hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(5..(data_len - 1))
@@ -294,9 +556,16 @@ impl FrameAnnotation for CivCommand {
                     SummaryPart::with_range("Set Freq", SegmentType::Command, cmd_range),
                     SummaryPart::plain(" "),
                     if let Some(r) = freq_range {
-                        SummaryPart::with_range(format_frequency(*hz), SegmentType::Frequency, r)
+                        SummaryPart::with_range(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                            r,
+                        )
                     } else {
-                        SummaryPart::typed(format_frequency(*hz), SegmentType::Frequency)
+                        SummaryPart::typed(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                        )
                     },
                 ]
             }
@@ -310,7 +579,7 @@ impl FrameAnnotation for CivCommand {
                     segments.push(FrameSegment {
                         range: 5..(data_len - 1),
                         label: "freq",
-                        value: format_frequency(*hz),
+                        value: format_frequency(Frequency::from_hz(*hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(5..(data_len - 1))
@@ -321,9 +590,16 @@ impl FrameAnnotation for CivCommand {
                     SummaryPart::with_range("Freq", SegmentType::Command, cmd_range),
                     SummaryPart::plain(" "),
                     if let Some(r) = freq_range {
-                        SummaryPart::with_range(format_frequency(*hz), SegmentType::Frequency, r)
+                        SummaryPart::with_range(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                            r,
+                        )
                     } else {
-                        SummaryPart::typed(format_frequency(*hz), SegmentType::Frequency)
+                        SummaryPart::typed(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                        )
                     },
                 ]
             }
@@ -468,6 +744,11 @@ impl FrameAnnotation for CivCommand {
                     },
                 ]
             }
+            CivCommandType::GetPtt => vec![SummaryPart::with_range(
+                "Get PTT",
+                SegmentType::Command,
+                cmd_range,
+            )],
             CivCommandType::PttReport { on } => {
                 let state = if *on { "ON" } else { "OFF" };
                 vec![
@@ -499,6 +780,11 @@ impl FrameAnnotation for CivCommand {
                     },
                 ]
             }
+            CivCommandType::GetSplit => vec![SummaryPart::with_range(
+                "Get Split",
+                SegmentType::Command,
+                cmd_range,
+            )],
             CivCommandType::Transceive { enabled } => {
                 let state = if *enabled { "ON" } else { "OFF" };
                 let state_range = if data_len > 7 {
@@ -528,6 +814,106 @@ impl FrameAnnotation for CivCommand {
                     },
                 ]
             }
+            CivCommandType::SelectedFrequency { hz }
+            | CivCommandType::UnselectedFrequency { hz } => {
+                let selected = matches!(self.command, CivCommandType::SelectedFrequency { .. });
+                let freq_range = if data_len > 7 {
+                    segments.push(FrameSegment {
+                        range: 5..6,
+                        label: "subcmd",
+                        value: if selected {
+                            "Selected VFO"
+                        } else {
+                            "Unselected VFO"
+                        }
+                        .to_string(),
+                        segment_type: SegmentType::Command,
+                    });
+                    segments.push(FrameSegment {
+                        range: 6..(data_len - 1),
+                        label: "freq",
+                        value: format_frequency(Frequency::from_hz(*hz)),
+                        segment_type: SegmentType::Frequency,
+                    });
+                    Some(6..(data_len - 1))
+                } else {
+                    None
+                };
+                vec![
+                    SummaryPart::with_range(
+                        if selected { "Sel Freq" } else { "Unsel Freq" },
+                        SegmentType::Command,
+                        cmd_range,
+                    ),
+                    SummaryPart::plain(" "),
+                    if let Some(r) = freq_range {
+                        SummaryPart::with_range(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                            r,
+                        )
+                    } else {
+                        SummaryPart::typed(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                        )
+                    },
+                ]
+            }
+            CivCommandType::SelectedMode { mode, data, filter } => {
+                let mode_range = if data_len > 7 {
+                    segments.push(FrameSegment {
+                        range: 5..6,
+                        label: "subcmd",
+                        value: "Selected VFO".to_string(),
+                        segment_type: SegmentType::Command,
+                    });
+                    segments.push(FrameSegment {
+                        range: 6..7,
+                        label: "mode",
+                        value: format_civ_mode(*mode).to_string(),
+                        segment_type: SegmentType::Mode,
+                    });
+                    Some(6..7)
+                } else {
+                    None
+                };
+                if data_len > 8 {
+                    segments.push(FrameSegment {
+                        range: 7..8,
+                        label: "data",
+                        value: if *data != 0 { "ON" } else { "OFF" }.to_string(),
+                        segment_type: SegmentType::Status,
+                    });
+                }
+                if data_len > 9 {
+                    segments.push(FrameSegment {
+                        range: 8..9,
+                        label: "filter",
+                        value: format!("{}", filter),
+                        segment_type: SegmentType::Data,
+                    });
+                }
+                let mut parts = vec![
+                    SummaryPart::with_range("Sel Mode", SegmentType::Command, cmd_range),
+                    SummaryPart::plain(" "),
+                    if let Some(r) = mode_range {
+                        SummaryPart::with_range(format_civ_mode(*mode), SegmentType::Mode, r)
+                    } else {
+                        SummaryPart::typed(format_civ_mode(*mode), SegmentType::Mode)
+                    },
+                ];
+                if *data != 0 {
+                    parts.push(SummaryPart::typed("-D", SegmentType::Mode));
+                }
+                parts.push(SummaryPart::plain(format!(" (filter {})", filter)));
+                parts
+            }
+            CivCommandType::KeyerSpeed { .. }
+            | CivCommandType::CwPitch { .. }
+            | CivCommandType::BreakIn { .. } => {
+                cw_setting_summary(&self.to_radio_request(), cmd_range)
+            }
             CivCommandType::Ok => vec![SummaryPart::with_range("OK", SegmentType::Data, cmd_range)],
             CivCommandType::Ng => vec![SummaryPart::with_range(
                 "NG (Error)",
@@ -596,7 +982,7 @@ impl FrameAnnotation for YaesuCommand {
                 segments.push(FrameSegment {
                     range: freq_range.clone(),
                     label: "freq",
-                    value: format_frequency(*hz),
+                    value: format_frequency(Frequency::from_hz(*hz)),
                     segment_type: SegmentType::Frequency,
                 });
                 add_cmd_segment(&mut segments, raw_bytes.get(4).copied().unwrap_or(0));
@@ -604,7 +990,7 @@ impl FrameAnnotation for YaesuCommand {
                     SummaryPart::with_range("Set Freq", SegmentType::Command, cmd_range),
                     SummaryPart::plain(" "),
                     SummaryPart::with_range(
-                        format_frequency(*hz),
+                        format_frequency(Frequency::from_hz(*hz)),
                         SegmentType::Frequency,
                         freq_range,
                     ),
@@ -632,7 +1018,7 @@ impl FrameAnnotation for YaesuCommand {
                 segments.push(FrameSegment {
                     range: freq_range.clone(),
                     label: "freq",
-                    value: format_frequency(*hz),
+                    value: format_frequency(Frequency::from_hz(*hz)),
                     segment_type: SegmentType::Frequency,
                 });
                 segments.push(FrameSegment {
@@ -645,7 +1031,7 @@ impl FrameAnnotation for YaesuCommand {
                     SummaryPart::typed("Freq", SegmentType::Data),
                     SummaryPart::plain(" "),
                     SummaryPart::with_range(
-                        format_frequency(*hz),
+                        format_frequency(Frequency::from_hz(*hz)),
                         SegmentType::Frequency,
                         freq_range,
                     ),
@@ -1017,7 +1403,7 @@ impl KenwoodCommand {
                     segments.push(FrameSegment {
                         range: params_start..params_end,
                         label: "freq",
-                        value: format_frequency(*hz),
+                        value: format_frequency(Frequency::from_hz(*hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(params_start..params_end)
@@ -1032,9 +1418,16 @@ impl KenwoodCommand {
                     ),
                     SummaryPart::plain(" "),
                     if let Some(r) = freq_range {
-                        SummaryPart::with_range(format_frequency(*hz), SegmentType::Frequency, r)
+                        SummaryPart::with_range(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                            r,
+                        )
                     } else {
-                        SummaryPart::typed(format_frequency(*hz), SegmentType::Frequency)
+                        SummaryPart::typed(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                        )
                     },
                 ]
             }
@@ -1092,6 +1485,12 @@ impl KenwoodCommand {
                 SegmentType::Command,
                 cmd_range,
             )],
+            KenwoodCommand::TransmitOn(vfo) => vec![
+                SummaryPart::with_range("PTT", SegmentType::Command, cmd_range),
+                SummaryPart::plain(" "),
+                SummaryPart::typed("ON", SegmentType::Status),
+                SummaryPart::plain(if *vfo == 0 { " (Main)" } else { " (Sub)" }),
+            ],
             KenwoodCommand::Id(Some(id)) => {
                 let id_range = if params_start < params_end {
                     segments.push(FrameSegment {
@@ -1125,7 +1524,7 @@ impl KenwoodCommand {
                     segments.push(FrameSegment {
                         range: params_start..(params_start + 11),
                         label: "freq",
-                        value: format_frequency(info.frequency_hz),
+                        value: format_frequency(Frequency::from_hz(info.frequency_hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(params_start..(params_start + 11))
@@ -1145,13 +1544,13 @@ impl KenwoodCommand {
                     SummaryPart::plain(": "),
                     if let Some(r) = freq_range {
                         SummaryPart::with_range(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                             r,
                         )
                     } else {
                         SummaryPart::typed(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                         )
                     },
@@ -1246,6 +1645,34 @@ impl KenwoodCommand {
                 SegmentType::Command,
                 cmd_range,
             )],
+            KenwoodCommand::PowerControl(Some(watts)) => {
+                let value = format!("{} W", watts);
+                let watts_range = if params_start < params_end {
+                    segments.push(FrameSegment {
+                        range: params_start..params_end,
+                        label: "watts",
+                        value: value.clone(),
+                        segment_type: SegmentType::Data,
+                    });
+                    Some(params_start..params_end)
+                } else {
+                    None
+                };
+                vec![
+                    SummaryPart::with_range("TX Power", SegmentType::Command, cmd_range),
+                    SummaryPart::plain(" "),
+                    if let Some(r) = watts_range {
+                        SummaryPart::with_range(value, SegmentType::Data, r)
+                    } else {
+                        SummaryPart::typed(value, SegmentType::Data)
+                    },
+                ]
+            }
+            KenwoodCommand::PowerControl(None) => vec![SummaryPart::with_range(
+                "Get TX Power",
+                SegmentType::Command,
+                cmd_range,
+            )],
             KenwoodCommand::AutoInfo(Some(enabled)) => {
                 let state = if *enabled { "ON" } else { "OFF" };
                 let ai_range = if params_start < params_end {
@@ -1330,6 +1757,23 @@ impl KenwoodCommand {
                 SegmentType::Command,
                 cmd_range,
             )],
+            KenwoodCommand::SMeter(Some(v)) => vec![
+                SummaryPart::with_range("S-Meter", SegmentType::Command, cmd_range),
+                SummaryPart::plain(" "),
+                SummaryPart::typed(format!("{}", v), SegmentType::Data),
+            ],
+            KenwoodCommand::SMeter(None) => vec![SummaryPart::with_range(
+                "Read S-Meter",
+                SegmentType::Command,
+                cmd_range,
+            )],
+            KenwoodCommand::KeyerSpeed(_)
+            | KenwoodCommand::BreakInDelay(_)
+            | KenwoodCommand::Pitch(_) => cw_setting_summary(&self.to_radio_request(), cmd_range),
+            KenwoodCommand::Rit(_)
+            | KenwoodCommand::RitClear
+            | KenwoodCommand::RitUp(_)
+            | KenwoodCommand::RitDown(_) => rit_summary(&self.to_radio_request(), cmd_range),
             KenwoodCommand::Unknown(s) => {
                 if params_start < params_end {
                     segments.push(FrameSegment {
@@ -1391,7 +1835,7 @@ impl FrameAnnotation for FlexCommand {
                             segments.push(FrameSegment {
                                 range: params_start..params_end,
                                 label: "freq",
-                                value: format_frequency(*hz),
+                                value: format_frequency(Frequency::from_hz(*hz)),
                                 segment_type: SegmentType::Frequency,
                             });
                             Some(params_start..params_end)
@@ -1407,12 +1851,15 @@ impl FrameAnnotation for FlexCommand {
                             SummaryPart::plain(" "),
                             if let Some(r) = freq_range {
                                 SummaryPart::with_range(
-                                    format_frequency(*hz),
+                                    format_frequency(Frequency::from_hz(*hz)),
                                     SegmentType::Frequency,
                                     r,
                                 )
                             } else {
-                                SummaryPart::typed(format_frequency(*hz), SegmentType::Frequency)
+                                SummaryPart::typed(
+                                    format_frequency(Frequency::from_hz(*hz)),
+                                    SegmentType::Frequency,
+                                )
                             },
                         ]
                     }
@@ -1443,6 +1890,12 @@ impl FrameAnnotation for FlexCommand {
                         SegmentType::Command,
                         cmd_range,
                     )],
+                    KenwoodCommand::TransmitOn(vfo) => vec![
+                        SummaryPart::with_range("PTT", SegmentType::Command, cmd_range),
+                        SummaryPart::plain(" "),
+                        SummaryPart::typed("ON", SegmentType::Status),
+                        SummaryPart::plain(if *vfo == 0 { " (Main)" } else { " (Sub)" }),
+                    ],
                     KenwoodCommand::Id(Some(id)) => {
                         let id_range = if params_start < params_end {
                             segments.push(FrameSegment {
@@ -1596,7 +2049,7 @@ impl FrameAnnotation for FlexCommand {
                     segments.push(FrameSegment {
                         range: params_start..(params_start + 11),
                         label: "freq",
-                        value: format_frequency(info.frequency_hz),
+                        value: format_frequency(Frequency::from_hz(info.frequency_hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(params_start..(params_start + 11))
@@ -1616,13 +2069,13 @@ impl FrameAnnotation for FlexCommand {
                     SummaryPart::plain(": "),
                     if let Some(r) = freq_range {
                         SummaryPart::with_range(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                             r,
                         )
                     } else {
                         SummaryPart::typed(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                         )
                     },
@@ -1812,7 +2265,7 @@ impl FrameAnnotation for YaesuAsciiCommand {
                     segments.push(FrameSegment {
                         range: params_start..params_end,
                         label: "freq",
-                        value: format_frequency(*hz),
+                        value: format_frequency(Frequency::from_hz(*hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some(params_start..params_end)
@@ -1827,9 +2280,16 @@ impl FrameAnnotation for YaesuAsciiCommand {
                     ),
                     SummaryPart::plain(" "),
                     if let Some(r) = freq_range {
-                        SummaryPart::with_range(format_frequency(*hz), SegmentType::Frequency, r)
+                        SummaryPart::with_range(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                            r,
+                        )
                     } else {
-                        SummaryPart::typed(format_frequency(*hz), SegmentType::Frequency)
+                        SummaryPart::typed(
+                            format_frequency(Frequency::from_hz(*hz)),
+                            SegmentType::Frequency,
+                        )
                     },
                 ]
             }
@@ -1954,7 +2414,7 @@ impl FrameAnnotation for YaesuAsciiCommand {
                     segments.push(FrameSegment {
                         range: (params_start + 3)..(params_start + 12),
                         label: "freq",
-                        value: format_frequency(info.frequency_hz),
+                        value: format_frequency(Frequency::from_hz(info.frequency_hz)),
                         segment_type: SegmentType::Frequency,
                     });
                     Some((params_start + 3)..(params_start + 12))
@@ -1974,13 +2434,13 @@ impl FrameAnnotation for YaesuAsciiCommand {
                     SummaryPart::plain(": "),
                     if let Some(r) = freq_range {
                         SummaryPart::with_range(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                             r,
                         )
                     } else {
                         SummaryPart::typed(
-                            format_frequency(info.frequency_hz),
+                            format_frequency(Frequency::from_hz(info.frequency_hz)),
                             SegmentType::Frequency,
                         )
                     },
@@ -2141,6 +2601,148 @@ impl FrameAnnotation for YaesuAsciiCommand {
                     cmd_range,
                 )]
             }
+            YaesuAsciiCommand::KeyerSpeed(_)
+            | YaesuAsciiCommand::BreakIn(_)
+            | YaesuAsciiCommand::BreakInDelay(_)
+            | YaesuAsciiCommand::KeyPitch(_) => {
+                cw_setting_summary(&self.to_radio_request(), cmd_range)
+            }
+            YaesuAsciiCommand::Clarifier(_)
+            | YaesuAsciiCommand::ClarifierClear
+            | YaesuAsciiCommand::ClarifierUp(_)
+            | YaesuAsciiCommand::ClarifierDown(_) => {
+                rit_summary(&self.to_radio_request(), cmd_range)
+            }
+            YaesuAsciiCommand::Keyer(Some(on)) => {
+                let state = if *on { "ON" } else { "OFF" };
+                let keyer_range = if params_start < params_end {
+                    segments.push(FrameSegment {
+                        range: params_start..params_end,
+                        label: "keyer",
+                        value: state.to_string(),
+                        segment_type: SegmentType::Status,
+                    });
+                    Some(params_start..params_end)
+                } else {
+                    None
+                };
+                vec![
+                    SummaryPart::with_range("Keyer", SegmentType::Command, cmd_range),
+                    SummaryPart::plain(" "),
+                    if let Some(r) = keyer_range {
+                        SummaryPart::with_range(state, SegmentType::Status, r)
+                    } else {
+                        SummaryPart::typed(state, SegmentType::Status)
+                    },
+                ]
+            }
+            YaesuAsciiCommand::Keyer(None) => {
+                vec![SummaryPart::with_range(
+                    "Get Keyer",
+                    SegmentType::Command,
+                    cmd_range,
+                )]
+            }
+            YaesuAsciiCommand::ManualNotch {
+                receiver,
+                function,
+                value,
+            } => {
+                let selector_end = (params_start + 2).min(params_end);
+                if params_start < selector_end {
+                    segments.push(FrameSegment {
+                        range: params_start..selector_end,
+                        label: "function",
+                        value: format!("{}{}", receiver, function),
+                        segment_type: SegmentType::Data,
+                    });
+                }
+                let what = if *function == 0 { "" } else { " Frequency" };
+                match value {
+                    Some(v) => {
+                        let (text, segment_type) = if *function == 0 {
+                            let state = if *v == 0 { "OFF" } else { "ON" };
+                            (state.to_string(), SegmentType::Status)
+                        } else {
+                            (format!("{} Hz", u32::from(*v) * 10), SegmentType::Data)
+                        };
+                        let value_range = selector_end..params_end;
+                        let value_part = if selector_end < params_end {
+                            segments.push(FrameSegment {
+                                range: value_range.clone(),
+                                label: "value",
+                                value: text.clone(),
+                                segment_type,
+                            });
+                            SummaryPart::with_range(text, segment_type, value_range)
+                        } else {
+                            SummaryPart::typed(text, segment_type)
+                        };
+                        vec![
+                            SummaryPart::with_range(
+                                format!("Manual Notch{}", what),
+                                SegmentType::Command,
+                                cmd_range,
+                            ),
+                            SummaryPart::plain(" "),
+                            value_part,
+                        ]
+                    }
+                    None => vec![SummaryPart::with_range(
+                        format!("Get Manual Notch{}", what),
+                        SegmentType::Command,
+                        cmd_range,
+                    )],
+                }
+            }
+            YaesuAsciiCommand::Menu {
+                item,
+                digits,
+                value,
+            } => {
+                let param = menu_table_for_digits(*digits).and_then(|t| t.param(*item));
+                let item_end = (params_start + digits).min(params_end);
+                let mut item_text = format!("{:0width$}", item, width = *digits);
+                if let Some(param) = param {
+                    item_text = format!("{} {}", item_text, param.name);
+                }
+                if params_start < item_end {
+                    segments.push(FrameSegment {
+                        range: params_start..item_end,
+                        label: "item",
+                        value: item_text.clone(),
+                        segment_type: SegmentType::Data,
+                    });
+                }
+                match value {
+                    Some(v) => {
+                        let value_text = match param.and_then(|p| p.value_label(v)) {
+                            Some(label) => label.to_string(),
+                            None => v.clone(),
+                        };
+                        if item_end < params_end {
+                            segments.push(FrameSegment {
+                                range: item_end..params_end,
+                                label: "value",
+                                value: value_text.clone(),
+                                segment_type: SegmentType::Status,
+                            });
+                        }
+                        vec![
+                            SummaryPart::with_range("Menu", SegmentType::Command, cmd_range),
+                            SummaryPart::plain(" "),
+                            SummaryPart::typed(item_text, SegmentType::Data),
+                            SummaryPart::plain(" = "),
+                            SummaryPart::typed(value_text, SegmentType::Status),
+                        ]
+                    }
+                    None => vec![
+                        SummaryPart::with_range("Read Menu", SegmentType::Command, cmd_range),
+                        SummaryPart::plain(" "),
+                        SummaryPart::typed(item_text, SegmentType::Data),
+                    ],
+                }
+            }
             YaesuAsciiCommand::Unknown(s) => {
                 if params_start < params_end {
                     segments.push(FrameSegment {
@@ -2172,6 +2774,116 @@ impl FrameAnnotation for YaesuAsciiCommand {
     }
 }
 
+// ============================================================================
+// Plain-text formatting
+// ============================================================================
+
+/// Format bytes as space-separated hex
+pub fn format_hex(data: &[u8]) -> String {
+    data.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse a text hex dump such as `FE FE 94 E0 03 FD` or `FEFE94E003FD`
+///
+/// Lines starting with `#` are comments; spaces, commas and line breaks
+/// between bytes are ignored.
+pub fn parse_hex_dump(text: &str) -> Result<Vec<u8>, String> {
+    let digits: String = text
+        .lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .flat_map(|line| line.chars())
+        .filter(|c| !c.is_whitespace() && *c != ',')
+        .collect();
+
+    if !digits.is_ascii() {
+        return Err("not a hex dump".to_string());
+    }
+    if !digits.len().is_multiple_of(2) {
+        return Err("odd number of hex digits".to_string());
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&digits[i..i + 2], 16)
+                .map_err(|_| format!("invalid hex byte '{}'", &digits[i..i + 2]))
+        })
+        .collect()
+}
+
+/// Decode a frame to one line of text, e.g. `Kenwood: VFO A 14.250 MHz`
+///
+/// Frames the protocol cannot decode are shown as hex.
+pub fn format_frame(data: &[u8], protocol: crate::Protocol) -> String {
+    match decode_and_annotate_with_hint(data, Some(protocol)) {
+        Some(frame) => format!("{}: {}", frame.protocol, frame.summary_text()),
+        None => format!("{}: undecoded {}", protocol.name(), format_hex(data)),
+    }
+}
+
+/// Decode a frame to a multi-line dissection
+///
+/// The first line is the [`format_frame`] summary, followed by one line per
+/// field with the field's bytes in an aligned hex column, its label and its
+/// decoded value. Bytes no field covers are listed with a `-` label.
+///
+/// ```text
+/// Kenwood: VFO A 14.250 MHz
+///   46 41                             cmd   FA
+///   30 30 30 31 34 32 35 30 30 30 30  freq  14.250 MHz
+///   3B                                end   ;
+/// ```
+pub fn format_frame_detailed(data: &[u8], protocol: crate::Protocol) -> String {
+    let summary = format_frame(data, protocol);
+    let Some(frame) = decode_and_annotate_with_hint(data, Some(protocol)) else {
+        return summary;
+    };
+
+    let mut segments: Vec<&FrameSegment> = frame
+        .segments
+        .iter()
+        .filter(|s| s.range.start < s.range.end && s.range.end <= data.len())
+        .collect();
+    segments.sort_by_key(|s| s.range.start);
+
+    // Fields in byte order, with uncovered gaps filled in
+    let mut rows: Vec<(String, &str, &str)> = Vec::new();
+    let mut pos = 0;
+    for segment in segments {
+        if segment.range.start < pos {
+            continue; // overlaps an earlier field
+        }
+        if segment.range.start > pos {
+            rows.push((format_hex(&data[pos..segment.range.start]), "-", ""));
+        }
+        rows.push((
+            format_hex(&data[segment.range.clone()]),
+            segment.label,
+            &segment.value,
+        ));
+        pos = segment.range.end;
+    }
+    if pos < data.len() {
+        rows.push((format_hex(&data[pos..]), "-", ""));
+    }
+
+    let hex_width = rows.iter().map(|(hex, _, _)| hex.len()).max().unwrap_or(0);
+    let label_width = rows
+        .iter()
+        .map(|(_, label, _)| label.len())
+        .max()
+        .unwrap_or(0);
+    let mut out = summary;
+    for (hex, label, value) in rows {
+        let line = format!("  {:hex_width$}  {:label_width$}  {}", hex, label, value);
+        out.push('\n');
+        out.push_str(line.trim_end());
+    }
+    out
+}
+
 // ============================================================================
 // Top-level decode function
 // ============================================================================
@@ -2200,7 +2912,7 @@ pub fn decode_and_annotate_with_hint(
         return match protocol {
             Protocol::IcomCIV => try_decode_civ(data),
             Protocol::Yaesu => try_decode_yaesu(data),
-            Protocol::YaesuAscii => try_decode_yaesu_ascii(data),
+            Protocol::YaesuAscii => try_decode_yaesu_ascii(data, DEFAULT_MENU_DIGITS),
             Protocol::Kenwood => try_decode_kenwood_only(data),
             Protocol::Elecraft => try_decode_elecraft(data),
             Protocol::FlexRadio => try_decode_flex(data),
@@ -2225,6 +2937,25 @@ pub fn decode_and_annotate_with_hint(
     None
 }
 
+/// Decode raw data from a radio whose model is known
+///
+/// Like [`decode_and_annotate_with_hint`], but Yaesu `EX` commands are split
+/// at the model's menu item width, as the mux's codec for it does (see
+/// [`crate::create_model_codec`]).
+pub fn decode_and_annotate_for_model(
+    data: &[u8],
+    protocol_hint: Option<crate::Protocol>,
+    model: Option<&RadioModel>,
+) -> Option<AnnotatedFrame> {
+    let table = model.and_then(|m| menu_table_for_model(&m.model));
+    match (protocol_hint, table) {
+        (Some(crate::Protocol::YaesuAscii), Some(table)) => {
+            try_decode_yaesu_ascii(data, table.digits)
+        }
+        _ => decode_and_annotate_with_hint(data, protocol_hint),
+    }
+}
+
 /// Try to decode CI-V frame
 fn try_decode_civ(data: &[u8]) -> Option<AnnotatedFrame> {
     if data.len() < 6 || data[0] != PREAMBLE || data[1] != PREAMBLE {
@@ -2366,6 +3097,10 @@ fn try_decode_elecraft(data: &[u8]) -> Option<AnnotatedFrame> {
         return None;
     }
 
+    if let Some(frame) = annotate_elecraft_extension(data) {
+        return Some(frame);
+    }
+
     let mut codec = KenwoodCodec::new();
     codec.push_bytes(data);
 
@@ -2376,6 +3111,134 @@ fn try_decode_elecraft(data: &[u8]) -> Option<AnnotatedFrame> {
     })
 }
 
+/// Annotate Elecraft commands that have no Kenwood equivalent
+fn annotate_elecraft_extension(data: &[u8]) -> Option<AnnotatedFrame> {
+    let mut codec = ElecraftCodec::new();
+    codec.push_bytes(data);
+    let cmd = codec.next_command()?;
+
+    let has_terminator = data.last() == Some(&b';');
+    let params_end = if has_terminator {
+        data.len() - 1
+    } else {
+        data.len()
+    };
+    let mut segments = Vec::new();
+
+    let summary = match &cmd {
+        ElecraftCommand::TransmitQuery(state) => {
+            segments.push(FrameSegment {
+                range: 0..2,
+                label: "cmd",
+                value: "TQ".to_string(),
+                segment_type: SegmentType::Command,
+            });
+            match state {
+                Some(tx) => {
+                    let value = if *tx { "TX" } else { "RX" };
+                    segments.push(FrameSegment {
+                        range: 2..params_end,
+                        label: "state",
+                        value: value.to_string(),
+                        segment_type: SegmentType::Status,
+                    });
+                    vec![
+                        SummaryPart::with_range("TX State", SegmentType::Command, 0..2),
+                        SummaryPart::with_range(value, SegmentType::Status, 2..params_end),
+                    ]
+                }
+                None => vec![SummaryPart::with_range(
+                    "Get TX State",
+                    SegmentType::Command,
+                    0..2,
+                )],
+            }
+        }
+        ElecraftCommand::TextBuffer(buffer) => {
+            segments.push(FrameSegment {
+                range: 0..2,
+                label: "cmd",
+                value: "TB".to_string(),
+                segment_type: SegmentType::Command,
+            });
+            match buffer {
+                Some(tb) => {
+                    segments.push(FrameSegment {
+                        range: 2..params_end,
+                        label: "text",
+                        value: tb.text.clone(),
+                        segment_type: SegmentType::Data,
+                    });
+                    vec![
+                        SummaryPart::with_range("Text Buffer", SegmentType::Command, 0..2),
+                        SummaryPart::with_range(
+                            format!("\"{}\" ({} to send)", tb.text, tb.tx_pending),
+                            SegmentType::Data,
+                            2..params_end,
+                        ),
+                    ]
+                }
+                None => vec![SummaryPart::with_range(
+                    "Get Text Buffer",
+                    SegmentType::Command,
+                    0..2,
+                )],
+            }
+        }
+        ElecraftCommand::Accessory { device, command } => {
+            segments.push(FrameSegment {
+                range: 0..1,
+                label: "addr",
+                value: device.name().to_string(),
+                segment_type: SegmentType::Address,
+            });
+            segments.push(FrameSegment {
+                range: 1..params_end,
+                label: "cmd",
+                value: command.clone(),
+                segment_type: SegmentType::Command,
+            });
+            let mut summary = vec![SummaryPart::with_range(
+                device.name(),
+                SegmentType::Address,
+                0..1,
+            )];
+            match cmd.accessory_band_number() {
+                Some((_, band)) => {
+                    summary.push(SummaryPart::with_range("Band", SegmentType::Command, 1..3));
+                    summary.push(SummaryPart::with_range(
+                        band.to_string(),
+                        SegmentType::Status,
+                        3..params_end,
+                    ));
+                }
+                None => summary.push(SummaryPart::with_range(
+                    command.clone(),
+                    SegmentType::Command,
+                    1..params_end,
+                )),
+            }
+            summary
+        }
+        _ => return None,
+    };
+
+    if has_terminator {
+        segments.push(FrameSegment {
+            range: params_end..data.len(),
+            label: "end",
+            value: ";".to_string(),
+            segment_type: SegmentType::Terminator,
+        });
+    }
+
+    Some(AnnotatedFrame {
+        protocol: "Elecraft",
+        summary,
+        segments,
+    })
+}
+
 /// Try to decode FlexRadio ASCII frame
 fn try_decode_flex(data: &[u8]) -> Option<AnnotatedFrame> {
     let s = std::str::from_utf8(data).ok()?;
@@ -2395,7 +3258,7 @@ fn try_decode_flex(data: &[u8]) -> Option<AnnotatedFrame> {
 }
 
 /// Try to decode Yaesu ASCII frame
-fn try_decode_yaesu_ascii(data: &[u8]) -> Option<AnnotatedFrame> {
+fn try_decode_yaesu_ascii(data: &[u8], menu_digits: usize) -> Option<AnnotatedFrame> {
     let s = std::str::from_utf8(data).ok()?;
     if !s.chars().all(|c| c.is_ascii_graphic() || c == ';') {
         return None;
@@ -2406,7 +3269,7 @@ fn try_decode_yaesu_ascii(data: &[u8]) -> Option<AnnotatedFrame> {
         return None;
     }
 
-    let mut codec = YaesuAsciiCodec::new();
+    let mut codec = YaesuAsciiCodec::with_menu_digits(menu_digits);
     codec.push_bytes(data);
 
     codec.next_command().map(|cmd| cmd.annotate(data))
@@ -2418,9 +3281,115 @@ mod tests {
 
     #[test]
     fn test_format_frequency() {
-        assert_eq!(format_frequency(14_250_000), "14.250 MHz");
-        assert_eq!(format_frequency(7_074_000), "7.074 MHz");
-        assert_eq!(format_frequency(14_074_500), "14.074500 MHz");
+        assert_eq!(
+            format_frequency(Frequency::from_hz(14_250_000)),
+            "14.250 MHz"
+        );
+        assert_eq!(format_frequency(Frequency::from_hz(7_074_000)), "7.074 MHz");
+        assert_eq!(
+            format_frequency(Frequency::from_hz(14_074_500)),
+            "14.074500 MHz"
+        );
+    }
+
+    #[test]
+    fn test_frequency_formats() {
+        let format = |style, decimal| FrequencyFormat { style, decimal };
+        let point = DecimalSeparator::Point;
+        let comma = DecimalSeparator::Comma;
+
+        let dotted = format(FrequencyStyle::MhzDotted, point);
+        assert_eq!(
+            dotted.format(Frequency::from_hz(14_074_500)),
+            "14.074.500 MHz"
+        );
+        assert_eq!(
+            dotted.format(Frequency::from_hz(1_840_000)),
+            "1.840.000 MHz"
+        );
+
+        let khz = format(FrequencyStyle::Khz, point);
+        assert_eq!(khz.format(Frequency::from_hz(14_250_000)), "14250.00 kHz");
+        assert_eq!(khz.format(Frequency::from_hz(7_074_159)), "7074.15 kHz");
+        assert_eq!(
+            format(FrequencyStyle::Khz, comma).format(Frequency::from_hz(3_573_000)),
+            "3573,00 kHz"
+        );
+
+        let grouped = format(FrequencyStyle::KhzGrouped, point);
+        assert_eq!(
+            grouped.format(Frequency::from_hz(14_250_000)),
+            "14,250.00 kHz"
+        );
+        assert_eq!(
+            grouped.format(Frequency::from_hz(144_300_000)),
+            "144,300.00 kHz"
+        );
+        assert_eq!(grouped.format(Frequency::from_hz(475_500)), "475.50 kHz");
+        assert_eq!(
+            format(FrequencyStyle::KhzGrouped, comma).format(Frequency::from_hz(14_250_000)),
+            "14.250,00 kHz"
+        );
+
+        assert_eq!(
+            format(FrequencyStyle::Mhz, comma).format(Frequency::from_hz(14_250_000)),
+            "14,250 MHz"
+        );
+        assert_eq!(point.format_decimal(12.345, 1), "12.3");
+        assert_eq!(comma.format_decimal(0.5, 2), "0,50");
+        assert_eq!(comma.format_decimal(42.0, 0), "42");
+
+        // Round-trips through the process-wide setting
+        for style in FrequencyStyle::ALL {
+            for decimal in DecimalSeparator::ALL {
+                let f = format(style, decimal);
+                assert_eq!(FrequencyFormat::from_bits(f.to_bits()), f);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_dump() {
+        let text = "# IC-7300 capture\nFE FE 94 E0 03 FD\nfefe,e0,94,fb,fd\n";
+        assert_eq!(
+            parse_hex_dump(text).unwrap(),
+            [0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD, 0xFE, 0xFE, 0xE0, 0x94, 0xFB, 0xFD]
+        );
+        assert!(parse_hex_dump("FE F").is_err());
+        assert!(parse_hex_dump("ZZ").is_err());
+        assert!(parse_hex_dump("FE\u{e9}").is_err());
+    }
+
+    #[test]
+    fn test_format_frame() {
+        assert_eq!(
+            format_frame(b"FA00014250000;", crate::Protocol::Kenwood),
+            "Kenwood: VFO A 14.250 MHz"
+        );
+        assert_eq!(
+            format_frame(&[0x01, 0x02], crate::Protocol::Yaesu),
+            "Yaesu CAT: undecoded 01 02"
+        );
+    }
+
+    #[test]
+    fn test_format_frame_detailed() {
+        let data = [
+            0xFE, 0xFE, 0xE0, 0x94, 0x03, 0x00, 0x00, 0x25, 0x14, 0x00, 0xFD,
+        ];
+        let text = format_frame_detailed(&data, crate::Protocol::IcomCIV);
+        let lines: Vec<&str> = text.lines().collect();
+        assert!(lines[0].starts_with("CI-V: "));
+        // Every byte appears exactly once, in order
+        let hex: Vec<&str> = lines[1..]
+            .iter()
+            .map(|l| l[2..].split("  ").next().unwrap().trim())
+            .collect();
+        assert_eq!(hex.join(" "), format_hex(&data));
+        // Value columns line up
+        let freq = lines[1..].iter().find(|l| l.contains("14.250")).unwrap();
+        let end = lines[1..].iter().find(|l| l.contains(" end ")).unwrap();
+        assert_eq!(freq.find("freq"), end.find("end "));
     }
 
     #[test]
@@ -2515,6 +3484,19 @@ mod tests {
         assert!(frame.summary.iter().any(|p| p.text.contains("14.250")));
     }
 
+    #[test]
+    fn test_elecraft_accessory_annotation() {
+        use crate::Protocol;
+
+        let frame = decode_and_annotate_with_hint(b"~BN05;", Some(Protocol::Elecraft)).unwrap();
+        let summary: Vec<_> = frame.summary.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(summary, ["KAT500", "Band", "5"]);
+
+        let frame = decode_and_annotate_with_hint(b"TQ1;", Some(Protocol::Elecraft)).unwrap();
+        let summary: Vec<_> = frame.summary.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(summary, ["TX State", "TX"]);
+    }
+
     #[test]
     fn test_protocol_hint_flex() {
         use crate::Protocol;
@@ -2535,6 +3517,60 @@ mod tests {
         assert_eq!(frame.protocol, "Yaesu ASCII");
     }
 
+    #[test]
+    fn test_yaesu_ascii_menu_names() {
+        use crate::Protocol;
+
+        let frame = decode_and_annotate_with_hint(b"EX0331;", Some(Protocol::YaesuAscii)).unwrap();
+        let summary: String = frame.summary.iter().map(|p| p.text.as_str()).collect();
+        assert_eq!(summary, "Menu 033 CAT RTS = ON");
+    }
+
+    #[test]
+    fn test_yaesu_ascii_menu_width_follows_model() {
+        use crate::yaesu_menu::NEWCAT_MENU;
+        use crate::{Protocol, RadioDatabase};
+
+        let ftdx10 = RadioDatabase::by_yaesu_ascii_id("0690").unwrap();
+        assert_eq!(
+            menu_table_for_model(&ftdx10.model).unwrap().digits,
+            NEWCAT_MENU.digits
+        );
+        let decode = |data: &[u8], model| {
+            decode_and_annotate_for_model(data, Some(Protocol::YaesuAscii), model).unwrap()
+        };
+        let summary = |frame: &AnnotatedFrame| -> String {
+            frame.summary.iter().map(|p| p.text.as_str()).collect()
+        };
+
+        let query = decode(b"EX030101;", Some(&ftdx10));
+        assert_eq!(summary(&query), "Read Menu 030101");
+        let item = query.segments.iter().find(|s| s.label == "item").unwrap();
+        assert_eq!(item.range, 2..8);
+
+        let set = decode(b"EX0301010;", Some(&ftdx10));
+        assert_eq!(summary(&set), "Menu 030101 = 0");
+        let value = set.segments.iter().find(|s| s.label == "value").unwrap();
+        assert_eq!(value.range, 8..9);
+
+        // Without the model the item is split at the FT-991's three digits
+        assert_eq!(summary(&decode(b"EX030101;", None)), "Menu 030 = 101");
+    }
+
+    #[test]
+    fn test_yaesu_ascii_keyer_and_notch() {
+        use crate::Protocol;
+
+        let summary = |data: &[u8]| -> String {
+            let frame = decode_and_annotate_with_hint(data, Some(Protocol::YaesuAscii)).unwrap();
+            frame.summary.iter().map(|p| p.text.as_str()).collect()
+        };
+        assert_eq!(summary(b"KR1;"), "Keyer ON");
+        assert_eq!(summary(b"BP00001;"), "Manual Notch ON");
+        assert_eq!(summary(b"BP01150;"), "Manual Notch Frequency 1500 Hz");
+        assert_eq!(summary(b"BP01;"), "Get Manual Notch Frequency");
+    }
+
     #[test]
     fn test_protocol_hint_civ() {
         use crate::Protocol;
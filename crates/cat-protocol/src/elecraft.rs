@@ -10,10 +10,22 @@
 //! - `K2;` / `K3;` - Radio identification
 //! - `DS;` - Display string
 //! - `IC;` - Icon status
+//! - `TQ;` - Transmit query (TX/RX state)
+//! - `TB;` - CW/data text buffer (replaces Kenwood's transmit band select)
 //! - Extended parameter ranges and additional commands
+//!
+//! # Accessory Addressing
+//! A KPA500 amplifier or KAT500 tuner sharing the line with the radio is
+//! addressed by a one-character prefix in front of an ordinary command:
+//! `^` for the KPA500 and `~` for the KAT500 (e.g. `~BN05;` band-switches the
+//! tuner to 20m). Addressed frames decode as [`ElecraftCommand::Accessory`] and
+//! never map onto radio requests or responses, so accessory chatter cannot be
+//! mistaken for radio state.
 
 use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
 use crate::kenwood::{KenwoodCodec, KenwoodCommand};
+use crate::mode_map::{ModeCode, ModeMap};
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -47,6 +59,61 @@ pub enum ElecraftCommand {
     RitOffset(Option<i32>),
     /// TX meter reading: TMx;
     TxMeter(Option<u8>),
+    /// Transmit query: TQ; / TQ0; / TQ1;
+    TransmitQuery(Option<bool>),
+    /// Text buffer: TB; / TBtrrs;
+    TextBuffer(Option<TextBuffer>),
+    /// Command addressed to an accessory on the shared line: ^xx...; / ~xx...;
+    Accessory {
+        device: ElecraftAccessory,
+        /// Command body without the address prefix or terminator
+        command: String,
+    },
+}
+
+/// Elecraft accessory that can share the radio's CAT line
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ElecraftAccessory {
+    /// KPA500 amplifier
+    Kpa500,
+    /// KAT500 antenna tuner
+    Kat500,
+}
+
+impl ElecraftAccessory {
+    /// Address prefix character for this accessory
+    pub fn prefix(self) -> char {
+        match self {
+            ElecraftAccessory::Kpa500 => '^',
+            ElecraftAccessory::Kat500 => '~',
+        }
+    }
+
+    /// Accessory addressed by a prefix character
+    pub fn from_prefix(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(ElecraftAccessory::Kpa500),
+            '~' => Some(ElecraftAccessory::Kat500),
+            _ => None,
+        }
+    }
+
+    /// Display name
+    pub fn name(self) -> &'static str {
+        match self {
+            ElecraftAccessory::Kpa500 => "KPA500",
+            ElecraftAccessory::Kat500 => "KAT500",
+        }
+    }
+}
+
+/// Text buffer contents (response to TB;)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextBuffer {
+    /// Characters still waiting to be sent (0-9)
+    pub tx_pending: u8,
+    /// Decoded receive text
+    pub text: String,
 }
 
 /// VFO information (extended)
@@ -56,6 +123,45 @@ pub struct VfoInfo {
     pub mode: OperatingMode,
 }
 
+impl ElecraftCommand {
+    /// Band-switch command addressed to an accessory (band numbers as for `BN`)
+    pub fn accessory_band(device: ElecraftAccessory, band: u8) -> Self {
+        ElecraftCommand::Accessory {
+            device,
+            command: format!("BN{:02}", band),
+        }
+    }
+
+    /// Band number carried by an accessory `BN` command or response
+    pub fn accessory_band_number(&self) -> Option<(ElecraftAccessory, u8)> {
+        match self {
+            ElecraftCommand::Accessory { device, command } => command
+                .strip_prefix("BN")
+                .and_then(|b| b.parse().ok())
+                .map(|band| (*device, band)),
+            _ => None,
+        }
+    }
+}
+
+/// Elecraft band number (as used by `BN`) for a frequency
+pub fn band_number(frequency: Frequency) -> Option<u8> {
+    match frequency.hz() {
+        1_800_000..=2_000_000 => Some(0),
+        3_500_000..=4_000_000 => Some(1),
+        5_330_000..=5_410_000 => Some(2),
+        7_000_000..=7_300_000 => Some(3),
+        10_100_000..=10_150_000 => Some(4),
+        14_000_000..=14_350_000 => Some(5),
+        18_068_000..=18_168_000 => Some(6),
+        21_000_000..=21_450_000 => Some(7),
+        24_890_000..=24_990_000 => Some(8),
+        28_000_000..=29_700_000 => Some(9),
+        50_000_000..=54_000_000 => Some(10),
+        _ => None,
+    }
+}
+
 /// Streaming Elecraft protocol codec
 pub struct ElecraftCodec {
     inner: KenwoodCodec,
@@ -71,7 +177,7 @@ impl ElecraftCodec {
 
     /// Parse Elecraft-specific commands
     fn parse_elecraft(cmd_str: &str) -> Option<ElecraftCommand> {
-        if cmd_str.len() < 2 {
+        if cmd_str.len() < 2 || !cmd_str.is_ascii() {
             return None;
         }
 
@@ -108,9 +214,33 @@ impl ElecraftCodec {
                 let offset = parse_rit_offset(params);
                 Some(ElecraftCommand::RitOffset(offset))
             }
+            "TQ" => Some(ElecraftCommand::TransmitQuery(match params {
+                "" => None,
+                p => Some(p == "1"),
+            })),
+            "TB" => Some(ElecraftCommand::TextBuffer(parse_text_buffer(params))),
             _ => None, // Fall through to Kenwood parsing
         }
     }
+
+    /// Parse commands whose Elecraft meaning replaces the Kenwood one
+    ///
+    /// Checked before the Kenwood parser: `TB` is the text buffer rather than
+    /// transmit band select, and accessory-addressed frames must never be
+    /// read as radio commands.
+    fn parse_override(cmd_str: &str) -> Option<ElecraftCommand> {
+        let mut chars = cmd_str.chars();
+        if let Some(device) = chars.next().and_then(ElecraftAccessory::from_prefix) {
+            return Some(ElecraftCommand::Accessory {
+                device,
+                command: chars.as_str().to_string(),
+            });
+        }
+        if cmd_str.starts_with("TB") {
+            return Self::parse_elecraft(cmd_str);
+        }
+        None
+    }
 }
 
 impl Default for ElecraftCodec {
@@ -134,6 +264,11 @@ impl ProtocolCodec for ElecraftCodec {
         // Try to get the next Kenwood command with its raw bytes
         let (kenwood_cmd, raw_bytes) = self.inner.next_command_with_bytes()?;
 
+        let cmd_str = String::from_utf8_lossy(&raw_bytes);
+        if let Some(elecraft_cmd) = Self::parse_override(cmd_str.trim_end_matches(';')) {
+            return Some((elecraft_cmd, raw_bytes));
+        }
+
         // Check if it's an Elecraft-specific command
         if let KenwoodCommand::Unknown(s) = &kenwood_cmd {
             // Try to parse as Elecraft-specific
@@ -149,6 +284,10 @@ impl ProtocolCodec for ElecraftCodec {
     fn clear(&mut self) {
         self.inner.clear();
     }
+
+    fn buffered(&self) -> &[u8] {
+        self.inner.buffered()
+    }
 }
 
 impl ToRadioResponse for ElecraftCommand {
@@ -172,17 +311,20 @@ impl ToRadioResponse for ElecraftCommand {
             },
             ElecraftCommand::Display(None) => RadioResponse::Unknown { data: vec![] },
             ElecraftCommand::VfoAInfo(Some(info)) => RadioResponse::Status {
-                frequency_hz: Some(info.frequency_hz),
+                frequency_hz: Some(Frequency::from_hz(info.frequency_hz)),
                 mode: Some(info.mode),
                 ptt: None,
                 vfo: Some(Vfo::A),
             },
             ElecraftCommand::VfoBInfo(Some(info)) => RadioResponse::Status {
-                frequency_hz: Some(info.frequency_hz),
+                frequency_hz: Some(Frequency::from_hz(info.frequency_hz)),
                 mode: Some(info.mode),
                 ptt: None,
                 vfo: Some(Vfo::B),
             },
+            ElecraftCommand::TransmitQuery(Some(active)) => RadioResponse::Ptt { active: *active },
+            ElecraftCommand::KeyerSpeed(Some(wpm)) => RadioResponse::KeyerSpeed { wpm: *wpm },
+            ElecraftCommand::RitOffset(Some(hz)) => RadioResponse::RitOffset { hz: *hz },
             _ => RadioResponse::Unknown { data: vec![] },
         }
     }
@@ -201,23 +343,53 @@ impl ToRadioRequest for ElecraftCommand {
             ElecraftCommand::Display(Some(_)) => RadioRequest::Unknown { data: vec![] },
             ElecraftCommand::Display(None) => RadioRequest::GetStatus,
             ElecraftCommand::VfoAInfo(Some(info)) => RadioRequest::SetFrequency {
-                hz: info.frequency_hz,
+                hz: Frequency::from_hz(info.frequency_hz),
             },
             ElecraftCommand::VfoBInfo(Some(info)) => RadioRequest::SetFrequency {
-                hz: info.frequency_hz,
+                hz: Frequency::from_hz(info.frequency_hz),
             },
             ElecraftCommand::VfoAInfo(None) => RadioRequest::GetStatus,
             ElecraftCommand::VfoBInfo(None) => RadioRequest::GetStatus,
+            ElecraftCommand::TransmitQuery(None) => RadioRequest::GetPtt,
+            ElecraftCommand::KeyerSpeed(Some(wpm)) => RadioRequest::SetKeyerSpeed { wpm: *wpm },
+            ElecraftCommand::KeyerSpeed(None) => RadioRequest::GetKeyerSpeed,
+            ElecraftCommand::RitOffset(None) => RadioRequest::GetRitOffset,
             _ => RadioRequest::Unknown { data: vec![] },
         }
     }
 }
 
+impl ModeCode for ElecraftCommand {
+    fn mode_code(&self) -> Option<u8> {
+        match self {
+            ElecraftCommand::Kenwood(cmd) => cmd.mode_code(),
+            _ => None,
+        }
+    }
+
+    fn set_mode_code(&mut self, code: u8) {
+        if let ElecraftCommand::Kenwood(cmd) = self {
+            cmd.set_mode_code(code);
+        }
+    }
+}
+
 impl FromRadioRequest for ElecraftCommand {
     fn from_radio_request(req: &RadioRequest) -> Option<Self> {
         // First try Elecraft-specific mappings
         match req {
             RadioRequest::GetId => Some(ElecraftCommand::K3Id(None)),
+            RadioRequest::GetPtt => Some(ElecraftCommand::TransmitQuery(None)),
+            // TB is the text buffer on Elecraft radios
+            RadioRequest::GetTransmitBand => None,
+            RadioRequest::SetKeyerSpeed { wpm } => Some(ElecraftCommand::KeyerSpeed(Some(*wpm))),
+            RadioRequest::GetKeyerSpeed => Some(ElecraftCommand::KeyerSpeed(None)),
+            RadioRequest::GetRitOffset => Some(ElecraftCommand::RitOffset(None)),
+            // Break-in delay and pitch are menu settings, not Kenwood SD/PT
+            RadioRequest::SetBreakInDelay { .. }
+            | RadioRequest::GetBreakInDelay
+            | RadioRequest::SetSidetonePitch { .. }
+            | RadioRequest::GetSidetonePitch => None,
             _ => {
                 // Fall back to Kenwood
                 KenwoodCommand::from_radio_request(req).map(ElecraftCommand::Kenwood)
@@ -239,6 +411,11 @@ impl FromRadioResponse for ElecraftCommand {
             RadioResponse::Id { id } if id.starts_with("KX:") => Some(ElecraftCommand::KxId(Some(
                 id.strip_prefix("KX:").unwrap().to_string(),
             ))),
+            RadioResponse::Ptt { active } => Some(ElecraftCommand::TransmitQuery(Some(*active))),
+            RadioResponse::TransmitBand { .. } => None,
+            RadioResponse::KeyerSpeed { wpm } => Some(ElecraftCommand::KeyerSpeed(Some(*wpm))),
+            RadioResponse::RitOffset { hz } => Some(ElecraftCommand::RitOffset(Some(*hz))),
+            RadioResponse::BreakInDelay { .. } | RadioResponse::SidetonePitch { .. } => None,
             _ => {
                 // Fall back to Kenwood
                 KenwoodCommand::from_radio_response(resp).map(ElecraftCommand::Kenwood)
@@ -281,20 +458,48 @@ impl EncodeCommand for ElecraftCommand {
             ElecraftCommand::VfoAInfo(Some(info)) => format!(
                 "VA{:011}{};",
                 info.frequency_hz,
-                elecraft_mode_code(info.mode)
+                ModeMap::ELECRAFT.code(info.mode)
             )
             .into_bytes(),
             ElecraftCommand::VfoBInfo(None) => b"VB;".to_vec(),
             ElecraftCommand::VfoBInfo(Some(info)) => format!(
                 "VB{:011}{};",
                 info.frequency_hz,
-                elecraft_mode_code(info.mode)
+                ModeMap::ELECRAFT.code(info.mode)
+            )
+            .into_bytes(),
+            ElecraftCommand::TransmitQuery(None) => b"TQ;".to_vec(),
+            ElecraftCommand::TransmitQuery(Some(tx)) => {
+                format!("TQ{};", u8::from(*tx)).into_bytes()
+            }
+            ElecraftCommand::TextBuffer(None) => b"TB;".to_vec(),
+            ElecraftCommand::TextBuffer(Some(tb)) => format!(
+                "TB{}{:02}{};",
+                tb.tx_pending,
+                tb.text.chars().count(),
+                tb.text
             )
             .into_bytes(),
+            ElecraftCommand::Accessory { device, command } => {
+                format!("{}{};", device.prefix(), command).into_bytes()
+            }
         }
     }
 }
 
+/// Parse text buffer response params like "005HELLO"
+fn parse_text_buffer(s: &str) -> Option<TextBuffer> {
+    if s.len() < 3 || !s.is_char_boundary(3) || !s.is_char_boundary(1) {
+        return None;
+    }
+    let tx_pending = s[..1].parse().ok()?;
+    let count: usize = s[1..3].parse().ok()?;
+    Some(TextBuffer {
+        tx_pending,
+        text: s[3..].chars().take(count).collect(),
+    })
+}
+
 /// Parse RIT offset from string like "+00100" or "-00050"
 fn parse_rit_offset(s: &str) -> Option<i32> {
     if s.is_empty() {
@@ -304,21 +509,6 @@ fn parse_rit_offset(s: &str) -> Option<i32> {
     s.parse().ok()
 }
 
-/// Get Elecraft mode code for encoding
-fn elecraft_mode_code(mode: OperatingMode) -> u8 {
-    match mode {
-        OperatingMode::Lsb => 1,
-        OperatingMode::Usb => 2,
-        OperatingMode::Cw => 3,
-        OperatingMode::Fm => 4,
-        OperatingMode::Am => 5,
-        OperatingMode::Data | OperatingMode::DataL => 6,
-        OperatingMode::CwR => 7,
-        OperatingMode::DataU => 9,
-        _ => 2, // Default to USB
-    }
-}
-
 /// Generate probe commands to detect Elecraft radios
 /// Returns multiple commands to try in sequence
 pub fn probe_commands() -> Vec<Vec<u8>> {
@@ -392,7 +582,12 @@ mod tests {
     fn test_to_radio_response() {
         let cmd = ElecraftCommand::Kenwood(KenwoodCommand::FrequencyA(Some(7_074_000)));
         let response = cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
@@ -403,7 +598,12 @@ mod tests {
 
         let cmd = ElecraftCommand::Kenwood(KenwoodCommand::FrequencyA(Some(14_250_000)));
         let request = cmd.to_radio_request();
-        assert_eq!(request, RadioRequest::SetFrequency { hz: 14_250_000 });
+        assert_eq!(
+            request,
+            RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(14_250_000)
+            }
+        );
     }
 
     #[test]
@@ -412,7 +612,9 @@ mod tests {
         let cmd = ElecraftCommand::from_radio_request(&req).unwrap();
         assert_eq!(cmd, ElecraftCommand::K3Id(None));
 
-        let req = RadioRequest::SetFrequency { hz: 14_250_000 };
+        let req = RadioRequest::SetFrequency {
+            hz: Frequency::from_hz(14_250_000),
+        };
         let cmd = ElecraftCommand::from_radio_request(&req).unwrap();
         assert_eq!(
             cmd,
@@ -428,7 +630,9 @@ mod tests {
         let cmd = ElecraftCommand::from_radio_response(&resp).unwrap();
         assert_eq!(cmd, ElecraftCommand::K3Id(Some("123".to_string())));
 
-        let resp = RadioResponse::Frequency { hz: 7_074_000 };
+        let resp = RadioResponse::Frequency {
+            hz: Frequency::from_hz(7_074_000),
+        };
         let cmd = ElecraftCommand::from_radio_response(&resp).unwrap();
         assert_eq!(
             cmd,
@@ -447,4 +651,118 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_text_buffer_overrides_kenwood_transmit_band() {
+        let mut codec = ElecraftCodec::new();
+        codec.push_bytes(b"TB000;TB102HI;");
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(
+            cmd,
+            ElecraftCommand::TextBuffer(Some(TextBuffer {
+                tx_pending: 0,
+                text: String::new(),
+            }))
+        );
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::Unknown { data: vec![] }
+        );
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(cmd.encode(), b"TB102HI;");
+    }
+
+    #[test]
+    fn test_transmit_query() {
+        let mut codec = ElecraftCodec::new();
+        codec.push_bytes(b"TQ1;");
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(cmd, ElecraftCommand::TransmitQuery(Some(true)));
+        assert_eq!(cmd.to_radio_response(), RadioResponse::Ptt { active: true });
+        assert_eq!(cmd.encode(), b"TQ1;");
+
+        codec.push_bytes(b"TQ0;");
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::Ptt { active: false }
+        );
+        assert_eq!(
+            ElecraftCommand::from_radio_response(&RadioResponse::Ptt { active: false })
+                .unwrap()
+                .encode(),
+            b"TQ0;"
+        );
+        assert_eq!(
+            ElecraftCommand::from_radio_request(&RadioRequest::GetPtt)
+                .unwrap()
+                .encode(),
+            b"TQ;"
+        );
+    }
+
+    #[test]
+    fn test_accessory_frames_do_not_affect_radio_state() {
+        let mut codec = ElecraftCodec::new();
+        codec.push_bytes(b"~BN05;^FA00014250000;FA00014250000;");
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(
+            cmd.accessory_band_number(),
+            Some((ElecraftAccessory::Kat500, 5))
+        );
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::Unknown { data: vec![] }
+        );
+
+        let cmd = codec.next_command().unwrap();
+        assert!(matches!(
+            cmd,
+            ElecraftCommand::Accessory {
+                device: ElecraftAccessory::Kpa500,
+                ..
+            }
+        ));
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::Unknown { data: vec![] }
+        );
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000)
+            }
+        );
+    }
+
+    #[test]
+    fn test_encode_accessory_band() {
+        let band = band_number(Frequency::from_hz(14_074_000)).unwrap();
+        let cmd = ElecraftCommand::accessory_band(ElecraftAccessory::Kat500, band);
+        assert_eq!(cmd.encode(), b"~BN05;");
+        assert_eq!(band_number(Frequency::from_hz(9_000_000)), None);
+    }
+
+    #[test]
+    fn test_rit_offset_and_kenwood_rit() {
+        let mut codec = ElecraftCodec::new();
+        codec.push_bytes(b"RO-00120;RT1;");
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_response(),
+            RadioResponse::RitOffset { hz: -120 }
+        );
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_response(),
+            RadioResponse::Rit { on: true }
+        );
+
+        let cmd = ElecraftCommand::from_radio_request(&RadioRequest::GetRitOffset).unwrap();
+        assert_eq!(cmd.encode(), b"RO;");
+    }
 }
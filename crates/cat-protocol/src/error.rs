@@ -33,6 +33,14 @@ pub enum ParseError {
     #[error("invalid address: 0x{0:02X}")]
     InvalidAddress(u8),
 
+    /// Frame ended before a fixed-length payload was complete
+    #[error("truncated {command}: expected {expected} data bytes, got {actual}")]
+    Truncated {
+        command: String,
+        expected: usize,
+        actual: usize,
+    },
+
     /// Checksum mismatch
     #[error("checksum mismatch: expected 0x{expected:02X}, got 0x{actual:02X}")]
     ChecksumMismatch { expected: u8, actual: u8 },
@@ -41,8 +41,9 @@
 //! - 912 = FLEX-8400
 //! - 913 = FLEX-8600
 
-use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+use crate::command::{MeterKind, OperatingMode, RadioRequest, RadioResponse, Vfo};
 use crate::kenwood::{KenwoodCodec, KenwoodCommand};
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -446,6 +447,10 @@ impl ProtocolCodec for FlexCodec {
     fn clear(&mut self) {
         self.inner.clear();
     }
+
+    fn buffered(&self) -> &[u8] {
+        self.inner.buffered()
+    }
 }
 
 impl ToRadioResponse for FlexCommand {
@@ -457,15 +462,20 @@ impl ToRadioResponse for FlexCommand {
             },
             FlexCommand::Mode(None) => RadioResponse::Unknown { data: vec![] },
             FlexCommand::Info(Some(info)) => RadioResponse::Status {
-                frequency_hz: Some(info.frequency_hz),
+                frequency_hz: Some(Frequency::from_hz(info.frequency_hz)),
                 mode: Some(info.mode.to_operating_mode()),
                 ptt: Some(info.tx),
                 vfo: Some(if info.vfo == 0 { Vfo::A } else { Vfo::B }),
             },
             FlexCommand::Info(None) => RadioResponse::Unknown { data: vec![] },
+            // ZZSM is 0-260 in half-dB steps from -140 dBm; S9 (-73 dBm) reads 134
+            FlexCommand::SMeter(Some(v)) => RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level: ((*v).clamp(0, 260) as u32 * 120 / 134).min(255) as u8,
+            },
             FlexCommand::AudioGain(_)
             | FlexCommand::RfPower(_)
-            | FlexCommand::SMeter(_)
+            | FlexCommand::SMeter(None)
             | FlexCommand::AgcMode(_)
             | FlexCommand::NoiseReduction(_) => RadioResponse::Unknown { data: vec![] },
             FlexCommand::AutoInfo(Some(enabled)) => RadioResponse::AutoInfo { enabled: *enabled },
@@ -487,9 +497,12 @@ impl ToRadioRequest for FlexCommand {
             FlexCommand::Mode(None) => RadioRequest::GetMode,
             FlexCommand::Info(Some(_)) => RadioRequest::Unknown { data: vec![] },
             FlexCommand::Info(None) => RadioRequest::GetStatus,
+            FlexCommand::SMeter(None) => RadioRequest::GetMeter {
+                meter: MeterKind::Signal,
+            },
             FlexCommand::AudioGain(_)
             | FlexCommand::RfPower(_)
-            | FlexCommand::SMeter(_)
+            | FlexCommand::SMeter(Some(_))
             | FlexCommand::AgcMode(_)
             | FlexCommand::NoiseReduction(_) => RadioRequest::Unknown { data: vec![] },
             FlexCommand::AutoInfo(Some(enabled)) => RadioRequest::SetAutoInfo { enabled: *enabled },
@@ -733,12 +746,19 @@ mod tests {
     fn test_to_radio_response() {
         let cmd = FlexCommand::Kenwood(KenwoodCommand::FrequencyA(Some(7_074_000)));
         let response = cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
     fn test_from_radio_request() {
-        let req = RadioRequest::SetFrequency { hz: 14_250_000 };
+        let req = RadioRequest::SetFrequency {
+            hz: Frequency::from_hz(14_250_000),
+        };
         let cmd = FlexCommand::from_radio_request(&req).unwrap();
         match cmd {
             FlexCommand::Kenwood(KenwoodCommand::FrequencyA(Some(14_250_000))) => {}
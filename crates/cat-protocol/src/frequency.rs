@@ -0,0 +1,174 @@
+//! Radio frequencies
+//!
+//! [`Frequency`] holds a frequency in whole hertz. Building one says which
+//! unit a number is in (`Frequency::from_khz(14_250)`), and reading it back
+//! does too, so a kHz value can't end up where Hz was meant. Offsets
+//! (transverters, RIT-style shifts, calibration) are signed hertz and are
+//! applied with checked or saturating arithmetic.
+//!
+//! There is deliberately no `Display`: text for the user goes through
+//! [`format_frequency`](crate::display::format_frequency), and text for
+//! another program (rigctl, logs it parses) says which unit it writes.
+//!
+//! The per-protocol command enums keep the digits of the frame as plain
+//! integers; the normalized [`RadioRequest`](crate::RadioRequest) and
+//! [`RadioResponse`](crate::RadioResponse) carry a `Frequency`.
+
+use std::fmt;
+
+/// A radio frequency, in whole hertz
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+pub struct Frequency(u64);
+
+impl Frequency {
+    /// 0 Hz
+    pub const ZERO: Frequency = Frequency(0);
+
+    /// A frequency in hertz
+    pub const fn from_hz(hz: u64) -> Self {
+        Self(hz)
+    }
+
+    /// A frequency in kilohertz
+    pub const fn from_khz(khz: u64) -> Self {
+        Self(khz.saturating_mul(1_000))
+    }
+
+    /// A frequency in megahertz
+    pub const fn from_mhz(mhz: u64) -> Self {
+        Self(mhz.saturating_mul(1_000_000))
+    }
+
+    /// A fractional kilohertz value, rounded to the nearest hertz (None if
+    /// negative or not a number)
+    pub fn from_khz_f64(khz: f64) -> Option<Self> {
+        Self::from_hz_f64(khz * 1_000.0)
+    }
+
+    /// A fractional megahertz value, rounded to the nearest hertz (None if
+    /// negative or not a number)
+    pub fn from_mhz_f64(mhz: f64) -> Option<Self> {
+        Self::from_hz_f64(mhz * 1_000_000.0)
+    }
+
+    /// A fractional hertz value, rounded (None if negative or not a number)
+    pub fn from_hz_f64(hz: f64) -> Option<Self> {
+        let hz = hz.round();
+        (hz.is_finite() && hz >= 0.0 && hz <= u64::MAX as f64).then_some(Self(hz as u64))
+    }
+
+    /// The frequency in hertz
+    pub const fn hz(self) -> u64 {
+        self.0
+    }
+
+    /// The frequency in kilohertz, with any fraction
+    pub fn khz(self) -> f64 {
+        self.0 as f64 / 1_000.0
+    }
+
+    /// The frequency in megahertz, with any fraction
+    pub fn mhz(self) -> f64 {
+        self.0 as f64 / 1_000_000.0
+    }
+
+    /// Whole kilohertz, rounding down
+    pub const fn whole_khz(self) -> u64 {
+        self.0 / 1_000
+    }
+
+    /// Shift by a signed number of hertz (None below 0 Hz or on overflow)
+    pub const fn checked_offset(self, offset_hz: i64) -> Option<Self> {
+        match self.0.checked_add_signed(offset_hz) {
+            Some(hz) => Some(Self(hz)),
+            None => None,
+        }
+    }
+
+    /// Shift by a signed number of hertz, stopping at 0 Hz and the maximum
+    pub const fn saturating_offset(self, offset_hz: i64) -> Self {
+        Self(self.0.saturating_add_signed(offset_hz))
+    }
+
+    /// The signed offset that takes `other` to `self`, in hertz (saturating)
+    pub fn offset_from(self, other: Frequency) -> i64 {
+        let diff = i128::from(self.0) - i128::from(other.0);
+        diff.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+    }
+
+    /// How far apart two frequencies are, in hertz
+    pub const fn abs_diff(self, other: Frequency) -> u64 {
+        self.0.abs_diff(other.0)
+    }
+
+    /// Round down to a multiple of `step_hz` (unchanged for a 0 Hz step)
+    pub const fn round_down(self, step_hz: u64) -> Self {
+        if step_hz == 0 {
+            self
+        } else {
+            Self(self.0 - self.0 % step_hz)
+        }
+    }
+}
+
+/// Bare hertz, so normalized commands read as they always have
+/// (`Frequency { hz: 14250000 }` in the conformance matrix and switch audit)
+impl fmt::Debug for Frequency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units() {
+        let f = Frequency::from_khz(14_250);
+        assert_eq!(f, Frequency::from_hz(14_250_000));
+        assert_eq!(f.hz(), 14_250_000);
+        assert_eq!(f.khz(), 14_250.0);
+        assert_eq!(f.mhz(), 14.25);
+        assert_eq!(Frequency::from_mhz(144), Frequency::from_hz(144_000_000));
+        assert_eq!(Frequency::from_hz(7_074_999).whole_khz(), 7_074);
+
+        assert_eq!(
+            Frequency::from_mhz_f64(14.074),
+            Some(Frequency::from_hz(14_074_000))
+        );
+        assert_eq!(
+            Frequency::from_khz_f64(7074.5),
+            Some(Frequency::from_hz(7_074_500))
+        );
+        assert_eq!(Frequency::from_mhz_f64(-1.0), None);
+        assert_eq!(Frequency::from_mhz_f64(f64::NAN), None);
+        assert_eq!(format!("{:?}", f), "14250000");
+    }
+
+    #[test]
+    fn test_offsets() {
+        // A 2 m transverter with a 28 MHz IF
+        let dial = Frequency::from_mhz(28);
+        let offset = Frequency::from_mhz(116).hz() as i64;
+        let rf = dial.checked_offset(offset).unwrap();
+        assert_eq!(rf, Frequency::from_mhz(144));
+        assert_eq!(rf.offset_from(dial), offset);
+        assert_eq!(dial.offset_from(rf), -offset);
+
+        assert_eq!(dial.checked_offset(-30_000_000), None);
+        assert_eq!(dial.saturating_offset(-30_000_000), Frequency::ZERO);
+        assert_eq!(Frequency::from_hz(u64::MAX).checked_offset(1), None);
+        assert_eq!(dial.abs_diff(rf), 116_000_000);
+    }
+
+    #[test]
+    fn test_round_down() {
+        let f = Frequency::from_hz(14_074_260);
+        assert_eq!(f.round_down(500), Frequency::from_hz(14_074_000));
+        assert_eq!(f.round_down(10), Frequency::from_hz(14_074_260));
+        assert_eq!(f.round_down(0), f);
+    }
+}
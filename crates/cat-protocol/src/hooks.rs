@@ -0,0 +1,233 @@
+//! User-defined command handlers
+//!
+//! Homebrew accessories (a band switch, a rotator, a keyer hung off the CAT
+//! line) often speak in frames that no protocol module knows, so they decode
+//! as `Unknown`. [`CommandHooks`] holds closures that get a look at those
+//! frames first; [`HookedCodec`] wraps any [`RadioCodec`] with them. A handler
+//! can turn a frame into a normalized request or response, report it as a
+//! [`CustomEvent`], or pass it on to the next handler.
+//!
+//! ```rust
+//! use cat_protocol::hooks::{CommandHooks, HookOutput, HookedCodec};
+//! use cat_protocol::{create_radio_codec, Protocol, RadioCodec, RadioResponse};
+//!
+//! let mut hooks = CommandHooks::new();
+//! hooks.register(|frame: &[u8]| {
+//!     let band = frame.strip_prefix(b"XB")?.strip_suffix(b";")?;
+//!     Some(HookOutput::event("band-switch", band.to_vec()))
+//! });
+//!
+//! let mut codec = HookedCodec::new(create_radio_codec(Protocol::Kenwood), hooks);
+//! codec.push_bytes(b"XB20;");
+//! assert!(matches!(codec.next_response(), Some(RadioResponse::Unknown { .. })));
+//! assert_eq!(codec.take_custom_events()[0].name, "band-switch");
+//! ```
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::{RadioCodec, RadioRequest, RadioResponse};
+
+/// What a handler made of a frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutput {
+    /// A request, used when the codec is parsing requests (amplifier → mux)
+    Request(RadioRequest),
+    /// A response, used when the codec is parsing responses (radio → mux)
+    Response(RadioResponse),
+    /// Something with no normalized form
+    Event(CustomEvent),
+}
+
+impl HookOutput {
+    pub fn event(name: impl Into<String>, data: Vec<u8>) -> Self {
+        HookOutput::Event(CustomEvent {
+            name: name.into(),
+            data,
+        })
+    }
+}
+
+/// A frame a handler reported as its own event
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CustomEvent {
+    /// Chosen by the handler, e.g. "band-switch"
+    pub name: String,
+    /// Whatever the handler wants to pass on
+    pub data: Vec<u8>,
+}
+
+/// A handler: the raw frame (terminator included) in, `None` to leave it to the next handler
+pub type CommandHandler = Arc<dyn Fn(&[u8]) -> Option<HookOutput> + Send + Sync>;
+
+/// Handlers for frames a codec doesn't understand, tried in order
+#[derive(Clone, Default)]
+pub struct CommandHooks {
+    handlers: Vec<CommandHandler>,
+}
+
+impl fmt::Debug for CommandHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CommandHooks")
+            .field("handlers", &self.handlers.len())
+            .finish()
+    }
+}
+
+impl CommandHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a handler, tried after those already registered
+    pub fn register<F>(&mut self, handler: F) -> &mut Self
+    where
+        F: Fn(&[u8]) -> Option<HookOutput> + Send + Sync + 'static,
+    {
+        self.handlers.push(Arc::new(handler));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    /// The first handler's answer for a frame
+    pub fn handle(&self, frame: &[u8]) -> Option<HookOutput> {
+        self.handlers.iter().find_map(|handler| handler(frame))
+    }
+}
+
+/// A codec that offers its `Unknown` frames to [`CommandHooks`]
+///
+/// A frame a handler turns into a [`HookOutput::Event`] is still returned as
+/// `Unknown` (so it shows up in traffic as before) and the event is queued
+/// for [`RadioCodec::take_custom_events`]. An output for the other direction
+/// is treated as no answer.
+pub struct HookedCodec {
+    inner: Box<dyn RadioCodec>,
+    hooks: CommandHooks,
+    events: VecDeque<CustomEvent>,
+}
+
+impl HookedCodec {
+    pub fn new(inner: Box<dyn RadioCodec>, hooks: CommandHooks) -> Self {
+        Self {
+            inner,
+            hooks,
+            events: VecDeque::new(),
+        }
+    }
+
+    /// Run a frame through the hooks, returning what replaces it (if anything)
+    fn hook(&mut self, frame: &[u8]) -> Option<HookOutput> {
+        match self.hooks.handle(frame)? {
+            HookOutput::Event(event) => {
+                self.events.push_back(event);
+                None
+            }
+            output => Some(output),
+        }
+    }
+}
+
+impl RadioCodec for HookedCodec {
+    fn push_bytes(&mut self, data: &[u8]) {
+        self.inner.push_bytes(data);
+    }
+
+    fn next_response(&mut self) -> Option<RadioResponse> {
+        self.next_response_with_bytes().map(|(resp, _)| resp)
+    }
+
+    fn next_response_with_bytes(&mut self) -> Option<(RadioResponse, Vec<u8>)> {
+        let (resp, bytes) = self.inner.next_response_with_bytes()?;
+        if let RadioResponse::Unknown { .. } = &resp {
+            if let Some(HookOutput::Response(hooked)) = self.hook(&bytes) {
+                return Some((hooked, bytes));
+            }
+        }
+        Some((resp, bytes))
+    }
+
+    fn next_request(&mut self) -> Option<RadioRequest> {
+        self.next_request_with_bytes().map(|(req, _)| req)
+    }
+
+    fn next_request_with_bytes(&mut self) -> Option<(RadioRequest, Vec<u8>)> {
+        let (req, bytes) = self.inner.next_request_with_bytes()?;
+        if let RadioRequest::Unknown { .. } = &req {
+            if let Some(HookOutput::Request(hooked)) = self.hook(&bytes) {
+                return Some((hooked, bytes));
+            }
+        }
+        Some((req, bytes))
+    }
+
+    fn clear(&mut self) {
+        self.inner.clear();
+    }
+
+    fn buffered(&self) -> &[u8] {
+        self.inner.buffered()
+    }
+
+    fn take_custom_events(&mut self) -> Vec<CustomEvent> {
+        self.events.drain(..).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Frequency;
+    use crate::{create_radio_codec, Protocol};
+
+    #[test]
+    fn test_hooks_translate_unknown_frames() {
+        let mut hooks = CommandHooks::new();
+        // Handlers are tried in order; the first answer wins
+        hooks
+            .register(|frame: &[u8]| {
+                (frame == b"ZQ1;")
+                    .then_some(HookOutput::Response(RadioResponse::Ptt { active: true }))
+            })
+            .register(|frame: &[u8]| {
+                (frame == b"ZQ1;").then(|| HookOutput::event("never", Vec::new()))
+            })
+            .register(|frame: &[u8]| {
+                (frame == b"ZR;").then_some(HookOutput::Request(RadioRequest::GetFrequency))
+            });
+
+        let mut codec = HookedCodec::new(create_radio_codec(Protocol::Kenwood), hooks);
+        codec.push_bytes(b"ZQ1;FA00014250000;XY9;");
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Ptt { active: true })
+        );
+        // Known frames never reach the hooks
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Frequency {
+                hz: Frequency::from_hz(14_250_000)
+            })
+        );
+        // Nobody claimed it
+        assert!(matches!(
+            codec.next_response(),
+            Some(RadioResponse::Unknown { .. })
+        ));
+        assert!(codec.take_custom_events().is_empty());
+
+        // A request answer only applies when parsing requests
+        codec.push_bytes(b"ZR;");
+        assert!(matches!(
+            codec.next_response(),
+            Some(RadioResponse::Unknown { .. })
+        ));
+        codec.push_bytes(b"ZR;");
+        assert_eq!(codec.next_request(), Some(RadioRequest::GetFrequency));
+    }
+}
@@ -20,8 +20,12 @@
 //! Frequencies are encoded in BCD (Binary Coded Decimal), little-endian.
 //! Example: 14.250.000 Hz = 00 00 25 41 00 (reversed: 00 14 25 00 00)
 
-use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+use crate::command::{
+    BreakIn, MeterKind, OperatingMode, RadioRequest, RadioResponse, Receiver, Vfo, VfoState,
+};
 use crate::error::ParseError;
+use crate::mode_map::{ModeCode, ModeMap};
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -39,6 +43,50 @@ pub const BROADCAST_ADDR: u8 = 0x00;
 /// Maximum frame length (reasonable limit)
 const MAX_FRAME_LEN: usize = 64;
 
+/// Payload lengths for commands whose set/report form has a fixed size
+///
+/// `(command, sub-command, query length, minimum full length)`, lengths
+/// counted after the command byte. A frame longer than the query form but
+/// shorter than the full form was cut off on the wire.
+const FIXED_LENGTHS: &[(u8, Option<u8>, usize, usize)] = &[
+    // Frequency: 4 BCD bytes on older radios, 5 on current ones
+    (0x00, None, 0, 4),
+    (0x03, None, 0, 4),
+    (0x05, None, 0, 4),
+    // Levels: sub-command + 2 BCD bytes
+    (0x14, None, 1, 3),
+    // Meters (S, Po, SWR, ALC, COMP, Vd, Id): sub-command + 2 BCD bytes.
+    // The status reads (squelch, OVF) answer with a single byte.
+    (0x15, Some(0x02), 1, 3),
+    (0x15, Some(0x11), 1, 3),
+    (0x15, Some(0x12), 1, 3),
+    (0x15, Some(0x13), 1, 3),
+    (0x15, Some(0x14), 1, 3),
+    (0x15, Some(0x15), 1, 3),
+    (0x15, Some(0x16), 1, 3),
+    // Selected/unselected VFO frequency: sub-command + 5 BCD bytes
+    (0x25, Some(0x00), 1, 6),
+    (0x25, Some(0x01), 1, 6),
+    // Selected/unselected VFO mode: sub-command + mode, data flag, filter
+    (0x26, Some(0x00), 1, 4),
+    (0x26, Some(0x01), 1, 4),
+    // Transmit state: sub-command + on/off
+    (0x1C, Some(0x00), 1, 2),
+    // RIT offset: sub-command + 2 BCD bytes + sign; RIT on/off: sub-command + flag
+    (0x21, Some(RIT_OFFSET), 1, 4),
+    (0x21, Some(RIT_SWITCH), 1, 2),
+];
+
+/// Minimum complete payload length for a command, if it has a fixed size
+///
+/// Returns `(query length, full length)`; see [`CivCodec::parse_frame`].
+pub fn expected_data_len(cmd: u8, subcmd: Option<u8>) -> Option<(usize, usize)> {
+    FIXED_LENGTHS
+        .iter()
+        .find(|(c, sc, _, _)| *c == cmd && (sc.is_none() || *sc == subcmd))
+        .map(|&(_, _, query, full)| (query, full))
+}
+
 /// CI-V command codes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CivCommandCode {
@@ -82,6 +130,10 @@ pub enum CivCommandCode {
     Ptt = 0x1C,
     /// Transceive mode
     Transceive = 0x1A,
+    /// Selected/unselected VFO frequency
+    SelectedFrequency = 0x25,
+    /// Selected/unselected VFO mode with data mode flag
+    SelectedMode = 0x26,
     /// OK response from radio
     Ok = 0xFB,
     /// NG (error) response from radio
@@ -113,6 +165,8 @@ impl TryFrom<u8> for CivCommandCode {
             0x18 => Ok(Self::NoiseBlanker),
             0x1C => Ok(Self::Ptt),
             0x1A => Ok(Self::Transceive),
+            0x25 => Ok(Self::SelectedFrequency),
+            0x26 => Ok(Self::SelectedMode),
             0xFB => Ok(Self::Ok),
             0xFA => Ok(Self::Ng),
             _ => Err(ParseError::UnknownCommand(format!(
@@ -149,17 +203,33 @@ pub enum CivCommandType {
     GetMode,
     /// Mode response
     ModeReport { mode: u8, filter: u8 },
-    /// Select VFO
+    /// Select VFO (0x00 = A, 0x01 = B) or receiver (0xD0 = MAIN, 0xD1 = SUB)
     VfoSelect { vfo: u8 },
     /// Set PTT
     SetPtt { on: bool },
+    /// Read PTT: 0x1C 0x00 with no value
+    GetPtt,
     /// PTT status
     PttReport { on: bool },
     /// Split on/off
     Split { on: bool },
+    /// Read split: 0x0F with no value
+    GetSplit,
     /// Transceive mode (auto-information): 0x1A 0x05
     /// When enabled, radio sends unsolicited updates
     Transceive { enabled: bool },
+    /// Selected VFO frequency: 0x25 0x00
+    SelectedFrequency { hz: u64 },
+    /// Unselected VFO frequency: 0x25 0x01
+    UnselectedFrequency { hz: u64 },
+    /// Selected VFO mode: 0x26 0x00 (mode, data mode flag, filter)
+    SelectedMode { mode: u8, data: u8, filter: u8 },
+    /// Keyer speed level: 0x14 0x0C (0-255 spans 6-48 WPM; None = query)
+    KeyerSpeed { level: Option<u16> },
+    /// CW pitch level: 0x14 0x09 (0-255 spans 300-900 Hz; None = query)
+    CwPitch { level: Option<u16> },
+    /// Break-in: 0x16 0x47 (0=off, 1=semi, 2=full; None = query)
+    BreakIn { mode: Option<u8> },
     /// OK acknowledgment
     Ok,
     /// Error/NG response
@@ -175,6 +245,8 @@ pub enum CivCommandType {
 /// Streaming CI-V protocol codec
 pub struct CivCodec {
     buffer: Vec<u8>,
+    /// Mode codes for the connected model
+    mode_map: ModeMap,
 }
 
 impl CivCodec {
@@ -182,9 +254,16 @@ impl CivCodec {
     pub fn new() -> Self {
         Self {
             buffer: Vec::with_capacity(64),
+            mode_map: ModeMap::CIV,
         }
     }
 
+    /// Decode mode codes with a model's table (see [`crate::RadioModel::mode_map`])
+    pub fn with_mode_map(mut self, mode_map: ModeMap) -> Self {
+        self.mode_map = mode_map;
+        self
+    }
+
     /// Find the start of a valid frame (FE FE sequence)
     fn find_preamble(&self) -> Option<usize> {
         self.buffer
@@ -193,7 +272,10 @@ impl CivCodec {
     }
 
     /// Parse a complete frame
-    fn parse_frame(frame: &[u8]) -> Result<CivCommand, ParseError> {
+    ///
+    /// Frames whose payload stops short of the command's fixed length (see
+    /// [`expected_data_len`]) are rejected with [`ParseError::Truncated`].
+    pub fn parse_frame(frame: &[u8]) -> Result<CivCommand, ParseError> {
         // Minimum frame: FE FE to from cmd FD = 6 bytes
         if frame.len() < 6 {
             return Err(ParseError::Incomplete {
@@ -216,6 +298,16 @@ impl CivCodec {
         let cmd = frame[4];
         let data = &frame[5..frame.len() - 1];
 
+        if let Some((query, full)) = expected_data_len(cmd, data.first().copied()) {
+            if data.len() > query && data.len() < full {
+                return Err(ParseError::Truncated {
+                    command: format!("CI-V cmd 0x{:02X}", cmd),
+                    expected: full,
+                    actual: data.len(),
+                });
+            }
+        }
+
         let command = Self::parse_command(cmd, data)?;
 
         Ok(CivCommand {
@@ -275,6 +367,9 @@ impl CivCodec {
                 // PTT control
                 if data.is_empty() {
                     Ok(CivCommandType::SetPtt { on: false })
+                } else if data == [0x00] {
+                    // Sub-command without a value reads the transmit state
+                    Ok(CivCommandType::GetPtt)
                 } else {
                     // Subcmd 0x00 = PTT, data[1] = on/off
                     let on = data.get(1).map(|&v| v != 0).unwrap_or(false);
@@ -282,9 +377,11 @@ impl CivCodec {
                 }
             }
             0x0F => {
-                // Split
-                let on = data.first().map(|&v| v != 0).unwrap_or(false);
-                Ok(CivCommandType::Split { on })
+                // Split query (no data), or set/report
+                match data.first() {
+                    None => Ok(CivCommandType::GetSplit),
+                    Some(&v) => Ok(CivCommandType::Split { on: v != 0 }),
+                }
             }
             0x1A => {
                 // Transceive mode and other settings
@@ -307,6 +404,43 @@ impl CivCodec {
                     })
                 }
             }
+            0x25 if data.len() > 1 && data[0] == 0x00 => {
+                // Selected VFO frequency
+                let hz = bcd_to_frequency(&data[1..])?;
+                Ok(CivCommandType::SelectedFrequency { hz })
+            }
+            0x25 if data.len() > 1 && data[0] == 0x01 => {
+                let hz = bcd_to_frequency(&data[1..])?;
+                Ok(CivCommandType::UnselectedFrequency { hz })
+            }
+            0x26 if data.len() > 1 && data[0] == 0x00 => {
+                // Selected VFO mode, data mode flag, filter
+                let mode = data[1];
+                let data_mode = data.get(2).copied().unwrap_or(0);
+                let filter = data.get(3).copied().unwrap_or(1);
+                Ok(CivCommandType::SelectedMode {
+                    mode,
+                    data: data_mode,
+                    filter,
+                })
+            }
+            0x14 if matches!(data.first(), Some(&(LEVEL_CW_PITCH | LEVEL_KEYER_SPEED))) => {
+                let level = match &data[1..] {
+                    [] => None,
+                    bcd => Some(
+                        bcd_to_level(bcd)
+                            .ok_or_else(|| ParseError::InvalidFrame("invalid level".into()))?,
+                    ),
+                };
+                Ok(if data[0] == LEVEL_KEYER_SPEED {
+                    CivCommandType::KeyerSpeed { level }
+                } else {
+                    CivCommandType::CwPitch { level }
+                })
+            }
+            0x16 if data.first() == Some(&FUNC_BREAK_IN) => Ok(CivCommandType::BreakIn {
+                mode: data.get(1).copied(),
+            }),
             0xFB => Ok(CivCommandType::Ok),
             0xFA => Ok(CivCommandType::Ng),
             _ => {
@@ -350,25 +484,25 @@ impl ProtocolCodec for CivCodec {
     }
 
     fn next_command_with_bytes(&mut self) -> Option<(Self::Command, Vec<u8>)> {
-        // Find preamble
-        let preamble_pos = self.find_preamble()?;
+        loop {
+            // Find preamble
+            let preamble_pos = self.find_preamble()?;
 
-        // Discard bytes before preamble
-        if preamble_pos > 0 {
-            self.buffer.drain(..preamble_pos);
-        }
+            // Discard bytes before preamble
+            if preamble_pos > 0 {
+                self.buffer.drain(..preamble_pos);
+            }
 
-        // Find terminator
-        let term_pos = self.buffer.iter().position(|&b| b == TERMINATOR)?;
+            // Find terminator
+            let term_pos = self.buffer.iter().position(|&b| b == TERMINATOR)?;
 
-        // Extract complete frame
-        let frame: Vec<u8> = self.buffer.drain(..=term_pos).collect();
+            // Extract complete frame
+            let frame: Vec<u8> = self.buffer.drain(..=term_pos).collect();
 
-        match Self::parse_frame(&frame) {
-            Ok(cmd) => Some((cmd, frame)),
-            Err(e) => {
-                tracing::warn!("Failed to parse CI-V frame: {}", e);
-                None
+            // Drop a bad frame and carry on with whatever follows it
+            match Self::parse_frame(&frame) {
+                Ok(cmd) => return Some((cmd, frame)),
+                Err(e) => tracing::warn!("Failed to parse CI-V frame {:02X?}: {}", frame, e),
             }
         }
     }
@@ -376,35 +510,91 @@ impl ProtocolCodec for CivCodec {
     fn clear(&mut self) {
         self.buffer.clear();
     }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
 }
 
 impl ToRadioResponse for CivCommand {
     fn to_radio_response(&self) -> RadioResponse {
         match &self.command {
-            CivCommandType::SetFrequency { hz } => RadioResponse::Frequency { hz: *hz },
+            CivCommandType::SetFrequency { hz } => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             CivCommandType::GetFrequency => RadioResponse::Unknown { data: vec![] },
-            CivCommandType::FrequencyReport { hz } => RadioResponse::Frequency { hz: *hz },
+            CivCommandType::FrequencyReport { hz } => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             CivCommandType::SetMode { mode, .. } => RadioResponse::Mode {
-                mode: civ_mode_to_operating_mode(*mode),
+                mode: ModeMap::CIV.mode(*mode),
             },
             CivCommandType::GetMode => RadioResponse::Unknown { data: vec![] },
             CivCommandType::ModeReport { mode, .. } => RadioResponse::Mode {
-                mode: civ_mode_to_operating_mode(*mode),
+                mode: ModeMap::CIV.mode(*mode),
             },
-            CivCommandType::VfoSelect { vfo } => RadioResponse::Vfo {
-                vfo: match vfo {
-                    0x00 => Vfo::A,
-                    0x01 => Vfo::B,
-                    _ => Vfo::A,
-                },
+            CivCommandType::VfoSelect { vfo } => match vfo_select_state(*vfo) {
+                Some(state) => RadioResponse::VfoState { state },
+                None => RadioResponse::Unknown { data: vec![] },
             },
             CivCommandType::SetPtt { on } => RadioResponse::Ptt { active: *on },
             CivCommandType::PttReport { on } => RadioResponse::Ptt { active: *on },
-            CivCommandType::Split { on } => RadioResponse::Vfo {
-                vfo: if *on { Vfo::Split } else { Vfo::A },
+            CivCommandType::Split { on } => RadioResponse::VfoState {
+                state: VfoState::with_split(*on),
             },
+            CivCommandType::GetPtt | CivCommandType::GetSplit => {
+                RadioResponse::Unknown { data: vec![] }
+            }
             CivCommandType::Transceive { enabled } => RadioResponse::AutoInfo { enabled: *enabled },
+            CivCommandType::SelectedFrequency { hz } => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
+            // The other VFO is not what the radio is tuned to
+            CivCommandType::UnselectedFrequency { .. } => RadioResponse::Unknown { data: vec![] },
+            CivCommandType::SelectedMode { mode, data, .. } => RadioResponse::Mode {
+                mode: civ_data_mode_to_operating_mode(*mode, *data),
+            },
+            CivCommandType::KeyerSpeed { level: Some(level) } => RadioResponse::KeyerSpeed {
+                wpm: keyer_level_to_wpm(*level),
+            },
+            CivCommandType::CwPitch { level: Some(level) } => RadioResponse::SidetonePitch {
+                hz: pitch_level_to_hz(*level),
+            },
+            CivCommandType::BreakIn { mode: Some(mode) } => RadioResponse::BreakIn {
+                mode: civ_break_in(*mode),
+            },
+            CivCommandType::KeyerSpeed { level: None }
+            | CivCommandType::CwPitch { level: None }
+            | CivCommandType::BreakIn { mode: None } => RadioResponse::Unknown { data: vec![] },
             CivCommandType::Ok | CivCommandType::Ng => RadioResponse::Unknown { data: vec![] },
+            CivCommandType::Unknown {
+                cmd: 0x15,
+                subcmd: Some(subcmd),
+                data,
+            } if data.len() == 2 => match (meter_kind(*subcmd), bcd_to_level(data)) {
+                (Some(meter), Some(level)) => RadioResponse::Meter {
+                    meter,
+                    level: level.min(255) as u8,
+                },
+                (Some(_), None) => RadioResponse::Unknown { data: vec![] },
+                // Not a meter: the same as any other unknown command
+                (None, _) => RadioResponse::Unknown {
+                    data: std::iter::once(0x15).chain(data.iter().copied()).collect(),
+                },
+            },
+            CivCommandType::Unknown {
+                cmd: 0x21,
+                subcmd: Some(RIT_SWITCH),
+                data,
+            } if data.len() == 1 => RadioResponse::Rit { on: data[0] != 0 },
+            CivCommandType::Unknown {
+                cmd: 0x21,
+                subcmd: Some(RIT_OFFSET),
+                data,
+            } if civ_rit_offset(data).is_some() => match civ_rit_offset(data) {
+                Some(hz) => RadioResponse::RitOffset { hz },
+                None => RadioResponse::Unknown { data: vec![] },
+            },
             CivCommandType::Unknown { cmd, data, .. } => RadioResponse::Unknown {
                 data: std::iter::once(*cmd).chain(data.iter().copied()).collect(),
             },
@@ -412,33 +602,104 @@ impl ToRadioResponse for CivCommand {
     }
 }
 
+impl ModeCode for CivCommand {
+    fn mode_code(&self) -> Option<u8> {
+        match &self.command {
+            CivCommandType::SetMode { mode, .. } | CivCommandType::ModeReport { mode, .. } => {
+                Some(*mode)
+            }
+            // With the data flag set the mode byte is only the sideband
+            CivCommandType::SelectedMode { mode, data: 0, .. } => Some(*mode),
+            _ => None,
+        }
+    }
+
+    fn set_mode_code(&mut self, code: u8) {
+        match &mut self.command {
+            CivCommandType::SetMode { mode, .. }
+            | CivCommandType::ModeReport { mode, .. }
+            | CivCommandType::SelectedMode { mode, data: 0, .. } => *mode = code,
+            _ => {}
+        }
+    }
+}
+
 impl ToRadioRequest for CivCommand {
     fn to_radio_request(&self) -> RadioRequest {
         match &self.command {
-            CivCommandType::SetFrequency { hz } => RadioRequest::SetFrequency { hz: *hz },
+            CivCommandType::SetFrequency { hz } => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             CivCommandType::GetFrequency => RadioRequest::GetFrequency,
             CivCommandType::FrequencyReport { .. } => RadioRequest::Unknown { data: vec![] },
             CivCommandType::SetMode { mode, .. } => RadioRequest::SetMode {
-                mode: civ_mode_to_operating_mode(*mode),
+                mode: ModeMap::CIV.mode(*mode),
             },
             CivCommandType::GetMode => RadioRequest::GetMode,
             CivCommandType::ModeReport { .. } => RadioRequest::Unknown { data: vec![] },
-            CivCommandType::VfoSelect { vfo } => RadioRequest::SetVfo {
-                vfo: match vfo {
-                    0x00 => Vfo::A,
-                    0x01 => Vfo::B,
-                    _ => Vfo::A,
-                },
+            CivCommandType::VfoSelect { vfo } => match vfo_select_state(*vfo) {
+                Some(state) => RadioRequest::SetVfoState { state },
+                None => RadioRequest::Unknown { data: vec![] },
             },
             CivCommandType::SetPtt { on } => RadioRequest::SetPtt { active: *on },
+            CivCommandType::GetPtt => RadioRequest::GetPtt,
             CivCommandType::PttReport { .. } => RadioRequest::Unknown { data: vec![] },
-            CivCommandType::Split { on } => RadioRequest::SetVfo {
-                vfo: if *on { Vfo::Split } else { Vfo::A },
+            CivCommandType::Split { on } => RadioRequest::SetVfoState {
+                state: VfoState::with_split(*on),
             },
+            // As Kenwood's split query, read as a VFO query
+            CivCommandType::GetSplit => RadioRequest::GetVfo,
             CivCommandType::Transceive { enabled } => {
                 RadioRequest::SetAutoInfo { enabled: *enabled }
             }
+            CivCommandType::SelectedFrequency { hz } => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
+            CivCommandType::UnselectedFrequency { .. } => RadioRequest::Unknown { data: vec![] },
+            CivCommandType::SelectedMode { mode, data, .. } => RadioRequest::SetMode {
+                mode: civ_data_mode_to_operating_mode(*mode, *data),
+            },
+            CivCommandType::KeyerSpeed { level: Some(level) } => RadioRequest::SetKeyerSpeed {
+                wpm: keyer_level_to_wpm(*level),
+            },
+            CivCommandType::KeyerSpeed { level: None } => RadioRequest::GetKeyerSpeed,
+            CivCommandType::CwPitch { level: Some(level) } => RadioRequest::SetSidetonePitch {
+                hz: pitch_level_to_hz(*level),
+            },
+            CivCommandType::CwPitch { level: None } => RadioRequest::GetSidetonePitch,
+            CivCommandType::BreakIn { mode: Some(mode) } => RadioRequest::SetBreakIn {
+                mode: civ_break_in(*mode),
+            },
+            CivCommandType::BreakIn { mode: None } => RadioRequest::GetBreakIn,
             CivCommandType::Ok | CivCommandType::Ng => RadioRequest::Unknown { data: vec![] },
+            CivCommandType::Unknown {
+                cmd: 0x15,
+                subcmd: Some(subcmd),
+                data,
+            } if data.is_empty() => match meter_kind(*subcmd) {
+                Some(meter) => RadioRequest::GetMeter { meter },
+                // Not a meter: the same as any other unknown command
+                None => RadioRequest::Unknown { data: vec![0x15] },
+            },
+            CivCommandType::Unknown {
+                cmd: 0x21,
+                subcmd: Some(RIT_SWITCH),
+                data,
+            } if data.len() <= 1 => match data.first() {
+                Some(on) => RadioRequest::SetRit { on: *on != 0 },
+                None => RadioRequest::GetRit,
+            },
+            CivCommandType::Unknown {
+                cmd: 0x21,
+                subcmd: Some(RIT_OFFSET),
+                data,
+            } if data.is_empty() => RadioRequest::GetRitOffset,
+            // Setting the offset to zero is the only offset write there is a request for
+            CivCommandType::Unknown {
+                cmd: 0x21,
+                subcmd: Some(RIT_OFFSET),
+                data,
+            } if civ_rit_offset(data) == Some(0) => RadioRequest::ClearRit,
             CivCommandType::Unknown { cmd, data, .. } => RadioRequest::Unknown {
                 data: std::iter::once(*cmd).chain(data.iter().copied()).collect(),
             },
@@ -467,33 +728,96 @@ impl CivCommand {
     }
 }
 
+/// VFO arrangement a `0x07` sub-command selects
+fn vfo_select_state(vfo: u8) -> Option<VfoState> {
+    match vfo {
+        0x00 => Some(VfoState::select(Vfo::A)),
+        0x01 => Some(VfoState::select(Vfo::B)),
+        0xD0 => Some(VfoState::receiver(Receiver::Main)),
+        0xD1 => Some(VfoState::receiver(Receiver::Sub)),
+        // Equalize, exchange and dualwatch don't select anything
+        _ => None,
+    }
+}
+
+/// `0x07` for the receive VFO or receiver, otherwise `0x0F` for split
+///
+/// CI-V has no transmit-VFO select; transmitting on VFO B is sent as split
+/// on, anything else as split off.
+fn vfo_state_command(state: &VfoState) -> Option<CivCommandType> {
+    if let Some(rx) = state.rx_vfo {
+        return Some(match rx {
+            Vfo::A => CivCommandType::VfoSelect { vfo: 0x00 },
+            Vfo::B => CivCommandType::VfoSelect { vfo: 0x01 },
+            Vfo::Memory => CivCommandType::VfoSelect { vfo: 0x02 },
+            Vfo::Split => CivCommandType::Split { on: true },
+        });
+    }
+    if let Some(receiver) = state.active_receiver {
+        let vfo = match receiver {
+            Receiver::Main => 0xD0,
+            Receiver::Sub => 0xD1,
+        };
+        return Some(CivCommandType::VfoSelect { vfo });
+    }
+    state
+        .is_split()
+        .or(state.tx_vfo.map(|tx| tx == Vfo::B))
+        .map(|on| CivCommandType::Split { on })
+}
+
 impl FromRadioRequest for CivCommand {
     fn from_radio_request(req: &RadioRequest) -> Option<Self> {
         let civ_cmd = match req {
-            RadioRequest::SetFrequency { hz } => CivCommandType::SetFrequency { hz: *hz },
+            RadioRequest::SetFrequency { hz } => CivCommandType::SetFrequency { hz: hz.hz() },
             RadioRequest::GetFrequency => CivCommandType::GetFrequency,
             RadioRequest::SetMode { mode } => CivCommandType::SetMode {
-                mode: operating_mode_to_civ(*mode),
+                mode: ModeMap::CIV.code(*mode),
                 filter: 1,
             },
             RadioRequest::GetMode => CivCommandType::GetMode,
+            // Compound request: see encode_frequency_mode
+            RadioRequest::SetFrequencyMode { .. } => return None,
             RadioRequest::SetPtt { active } => CivCommandType::SetPtt { on: *active },
-            RadioRequest::GetPtt => return None, // No direct query in CI-V
-            RadioRequest::SetVfo { vfo } => match vfo {
-                Vfo::Split => CivCommandType::Split { on: true },
-                Vfo::A => CivCommandType::VfoSelect { vfo: 0x00 },
-                Vfo::B => CivCommandType::VfoSelect { vfo: 0x01 },
-                Vfo::Memory => CivCommandType::VfoSelect { vfo: 0x02 },
-            },
-            RadioRequest::GetVfo => return None, // No direct query in CI-V
+            RadioRequest::GetPtt => CivCommandType::GetPtt,
+            RadioRequest::SetVfoState { state } => vfo_state_command(state)?,
+            RadioRequest::SetVfo { vfo } => vfo_state_command(&VfoState::from(*vfo))?,
+            // No VFO query in CI-V; split is the closest
+            RadioRequest::GetVfo => CivCommandType::GetSplit,
             RadioRequest::GetId => return None,
             RadioRequest::GetStatus => return None,
             RadioRequest::SetPower { .. } => return None,
+            RadioRequest::SetTxPower { .. } | RadioRequest::GetTxPower => return None,
             RadioRequest::SetAutoInfo { enabled } => {
                 CivCommandType::Transceive { enabled: *enabled }
             }
             RadioRequest::GetAutoInfo => return None,
             RadioRequest::GetControlBand | RadioRequest::GetTransmitBand => return None,
+            RadioRequest::SetKeyerSpeed { wpm } => CivCommandType::KeyerSpeed {
+                level: Some(wpm_to_keyer_level(*wpm)),
+            },
+            RadioRequest::GetKeyerSpeed => CivCommandType::KeyerSpeed { level: None },
+            RadioRequest::SetBreakIn { mode } => CivCommandType::BreakIn {
+                mode: Some(break_in_code(*mode)),
+            },
+            RadioRequest::GetBreakIn => CivCommandType::BreakIn { mode: None },
+            // The delay is set in dot lengths, not milliseconds
+            RadioRequest::SetBreakInDelay { .. } | RadioRequest::GetBreakInDelay => return None,
+            RadioRequest::SetSidetonePitch { hz } => CivCommandType::CwPitch {
+                level: Some(hz_to_pitch_level(*hz)),
+            },
+            RadioRequest::GetSidetonePitch => CivCommandType::CwPitch { level: None },
+            RadioRequest::SetRit { on } => rit_command(RIT_SWITCH, vec![u8::from(*on)]),
+            RadioRequest::GetRit => rit_command(RIT_SWITCH, Vec::new()),
+            RadioRequest::ClearRit => rit_command(RIT_OFFSET, rit_offset_data(0)),
+            RadioRequest::GetRitOffset => rit_command(RIT_OFFSET, Vec::new()),
+            // CI-V sets the offset outright; moving it needs the current offset
+            RadioRequest::AdjustRit { .. } => return None,
+            RadioRequest::GetMeter { meter } => CivCommandType::Unknown {
+                cmd: 0x15,
+                subcmd: Some(meter_subcmd(*meter)),
+                data: Vec::new(),
+            },
             RadioRequest::Unknown { .. } => return None,
         };
 
@@ -504,24 +828,38 @@ impl FromRadioRequest for CivCommand {
 impl FromRadioResponse for CivCommand {
     fn from_radio_response(resp: &RadioResponse) -> Option<Self> {
         let civ_cmd = match resp {
-            RadioResponse::Frequency { hz } => CivCommandType::FrequencyReport { hz: *hz },
+            RadioResponse::Frequency { hz } => CivCommandType::FrequencyReport { hz: hz.hz() },
             RadioResponse::Mode { mode } => CivCommandType::ModeReport {
-                mode: operating_mode_to_civ(*mode),
+                mode: ModeMap::CIV.code(*mode),
                 filter: 1,
             },
             RadioResponse::Ptt { active } => CivCommandType::PttReport { on: *active },
-            RadioResponse::Vfo { vfo } => match vfo {
-                Vfo::Split => CivCommandType::Split { on: true },
-                Vfo::A => CivCommandType::VfoSelect { vfo: 0x00 },
-                Vfo::B => CivCommandType::VfoSelect { vfo: 0x01 },
-                Vfo::Memory => CivCommandType::VfoSelect { vfo: 0x02 },
-            },
+            RadioResponse::VfoState { state } => vfo_state_command(state)?,
+            RadioResponse::Vfo { vfo } => vfo_state_command(&VfoState::from(*vfo))?,
             RadioResponse::Id { .. } => return None,
             RadioResponse::Status { frequency_hz, .. } => {
-                frequency_hz.map(|hz| CivCommandType::FrequencyReport { hz })?
+                frequency_hz.map(|hz| CivCommandType::FrequencyReport { hz: hz.hz() })?
             }
             RadioResponse::AutoInfo { enabled } => CivCommandType::Transceive { enabled: *enabled },
             RadioResponse::ControlBand { .. } | RadioResponse::TransmitBand { .. } => return None,
+            RadioResponse::TxPower { .. } => return None,
+            RadioResponse::Meter { meter, level } => CivCommandType::Unknown {
+                cmd: 0x15,
+                subcmd: Some(meter_subcmd(*meter)),
+                data: level_to_bcd(u16::from(*level)),
+            },
+            RadioResponse::KeyerSpeed { wpm } => CivCommandType::KeyerSpeed {
+                level: Some(wpm_to_keyer_level(*wpm)),
+            },
+            RadioResponse::BreakIn { mode } => CivCommandType::BreakIn {
+                mode: Some(break_in_code(*mode)),
+            },
+            RadioResponse::BreakInDelay { .. } => return None,
+            RadioResponse::SidetonePitch { hz } => CivCommandType::CwPitch {
+                level: Some(hz_to_pitch_level(*hz)),
+            },
+            RadioResponse::Rit { on } => rit_command(RIT_SWITCH, vec![u8::from(*on)]),
+            RadioResponse::RitOffset { hz } => rit_command(RIT_OFFSET, rit_offset_data(*hz)),
             RadioResponse::Unknown { .. } => return None,
         };
 
@@ -567,6 +905,10 @@ impl EncodeCommand for CivCommand {
                 frame.push(0x00);
                 frame.push(if *on { 0x01 } else { 0x00 });
             }
+            CivCommandType::GetPtt => {
+                frame.push(0x1C);
+                frame.push(0x00);
+            }
             CivCommandType::PttReport { on } => {
                 frame.push(0x1C);
                 frame.push(0x00);
@@ -576,11 +918,46 @@ impl EncodeCommand for CivCommand {
                 frame.push(0x0F);
                 frame.push(if *on { 0x01 } else { 0x00 });
             }
+            CivCommandType::GetSplit => {
+                frame.push(0x0F);
+            }
             CivCommandType::Transceive { enabled } => {
                 frame.push(0x1A);
                 frame.push(0x05); // Subcmd for transceive
                 frame.push(if *enabled { 0x01 } else { 0x00 });
             }
+            CivCommandType::SelectedFrequency { hz } => {
+                frame.push(0x25);
+                frame.push(0x00); // Selected VFO
+                frame.extend(frequency_to_bcd(*hz));
+            }
+            CivCommandType::UnselectedFrequency { hz } => {
+                frame.push(0x25);
+                frame.push(0x01); // Unselected VFO
+                frame.extend(frequency_to_bcd(*hz));
+            }
+            CivCommandType::SelectedMode { mode, data, filter } => {
+                frame.push(0x26);
+                frame.push(0x00); // Selected VFO
+                frame.push(*mode);
+                frame.push(*data);
+                frame.push(*filter);
+            }
+            CivCommandType::KeyerSpeed { level } => {
+                frame.push(0x14);
+                frame.push(LEVEL_KEYER_SPEED);
+                frame.extend(level.map(level_to_bcd).unwrap_or_default());
+            }
+            CivCommandType::CwPitch { level } => {
+                frame.push(0x14);
+                frame.push(LEVEL_CW_PITCH);
+                frame.extend(level.map(level_to_bcd).unwrap_or_default());
+            }
+            CivCommandType::BreakIn { mode } => {
+                frame.push(0x16);
+                frame.push(FUNC_BREAK_IN);
+                frame.extend(*mode);
+            }
             CivCommandType::Ok => {
                 frame.push(0xFB);
             }
@@ -601,6 +978,218 @@ impl EncodeCommand for CivCommand {
     }
 }
 
+/// Incremental builder for CI-V frames
+///
+/// For commands whose payload is assembled piece by piece, such as memory
+/// channel writes or scope configuration. CI-V has no escaping, so payload
+/// bytes must never be `0xFD` (BCD data never is).
+///
+/// ```
+/// use cat_protocol::icom::CivFrameBuilder;
+///
+/// let mut builder = CivFrameBuilder::new(0x94, 0xE0, 0x1A);
+/// builder.push(0x00).extend(&[0x00, 0x01]).frequency(14_074_000);
+/// let frame = builder.finish();
+/// assert_eq!(frame.len(), 4 + 1 + 3 + 5 + 1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CivFrameBuilder {
+    frame: Vec<u8>,
+}
+
+impl CivFrameBuilder {
+    /// Start a frame with addresses and command byte
+    pub fn new(to_addr: u8, from_addr: u8, cmd: u8) -> Self {
+        Self {
+            frame: vec![PREAMBLE, PREAMBLE, to_addr, from_addr, cmd],
+        }
+    }
+
+    /// Append one payload byte
+    pub fn push(&mut self, byte: u8) -> &mut Self {
+        debug_assert_ne!(byte, TERMINATOR, "CI-V payload cannot contain 0xFD");
+        self.frame.push(byte);
+        self
+    }
+
+    /// Append payload bytes
+    pub fn extend(&mut self, bytes: &[u8]) -> &mut Self {
+        debug_assert!(
+            !bytes.contains(&TERMINATOR),
+            "CI-V payload cannot contain 0xFD"
+        );
+        self.frame.extend_from_slice(bytes);
+        self
+    }
+
+    /// Append a frequency as 5 BCD bytes
+    pub fn frequency(&mut self, hz: u64) -> &mut Self {
+        self.frame.extend(frequency_to_bcd(hz));
+        self
+    }
+
+    /// Payload bytes so far (after the command byte)
+    pub fn data_len(&self) -> usize {
+        self.frame.len() - 5
+    }
+
+    /// Terminate the frame
+    pub fn finish(mut self) -> Vec<u8> {
+        self.frame.push(TERMINATOR);
+        self.frame
+    }
+
+    /// Terminate the frame and split it into writes of at most `max_write` bytes
+    ///
+    /// For links with small transmit buffers; the radio only acts on the
+    /// frame once the terminator arrives, so the pieces can be sent separately.
+    pub fn finish_chunked(self, max_write: usize) -> Vec<Vec<u8>> {
+        self.finish()
+            .chunks(max_write.max(1))
+            .map(<[u8]>::to_vec)
+            .collect()
+    }
+}
+
+/// Meter read by a `0x15` sub-command
+fn meter_kind(subcmd: u8) -> Option<MeterKind> {
+    match subcmd {
+        0x02 => Some(MeterKind::Signal),
+        0x11 => Some(MeterKind::Power),
+        0x12 => Some(MeterKind::Swr),
+        _ => None,
+    }
+}
+
+/// `0x15` sub-command that reads a meter
+fn meter_subcmd(meter: MeterKind) -> u8 {
+    match meter {
+        MeterKind::Signal => 0x02,
+        MeterKind::Power => 0x11,
+        MeterKind::Swr => 0x12,
+    }
+}
+
+/// `0x21` sub-command for the RIT offset
+const RIT_OFFSET: u8 = 0x00;
+/// `0x21` sub-command for RIT on/off
+const RIT_SWITCH: u8 = 0x01;
+/// Largest RIT offset a `0x21 0x00` frame carries
+const RIT_MAX_HZ: u32 = 9999;
+
+/// RIT offset from `0x21 0x00` data: 2 little-endian BCD bytes, then the sign
+fn civ_rit_offset(data: &[u8]) -> Option<i32> {
+    let [low, high, sign] = data else {
+        return None;
+    };
+    let hz = bcd_to_frequency(&[*low, *high]).ok()? as i32;
+    match sign {
+        0x00 => Some(hz),
+        0x01 => Some(-hz),
+        _ => None,
+    }
+}
+
+/// `0x21 0x00` data for a RIT offset
+fn rit_offset_data(hz: i32) -> Vec<u8> {
+    let mut data = frequency_to_bcd(u64::from(hz.unsigned_abs().min(RIT_MAX_HZ)));
+    data.truncate(2);
+    data.push(u8::from(hz < 0));
+    data
+}
+
+/// `0x21` frame for a RIT sub-command
+fn rit_command(subcmd: u8, data: Vec<u8>) -> CivCommandType {
+    CivCommandType::Unknown {
+        cmd: 0x21,
+        subcmd: Some(subcmd),
+        data,
+    }
+}
+
+/// `0x14` sub-command for the CW pitch level
+const LEVEL_CW_PITCH: u8 = 0x09;
+/// `0x14` sub-command for the keyer speed level
+const LEVEL_KEYER_SPEED: u8 = 0x0C;
+/// `0x16` sub-command for break-in
+const FUNC_BREAK_IN: u8 = 0x47;
+
+/// Keyer speed range spanned by levels 0-255
+const KEYER_MIN_WPM: u16 = 6;
+const KEYER_MAX_WPM: u16 = 48;
+/// CW pitch range spanned by levels 0-255
+const PITCH_MIN_HZ: u16 = 300;
+const PITCH_MAX_HZ: u16 = 900;
+
+/// Scale a 0-255 level onto `min..=max`, rounding to nearest
+fn level_to_range(level: u16, min: u16, max: u16) -> u16 {
+    let span = u32::from(max - min);
+    min + ((u32::from(level.min(255)) * span + 127) / 255) as u16
+}
+
+/// Nearest 0-255 level for a value in `min..=max`
+fn range_to_level(value: u16, min: u16, max: u16) -> u16 {
+    let span = u32::from(max - min);
+    let offset = u32::from(value.clamp(min, max) - min);
+    ((offset * 255 + span / 2) / span) as u16
+}
+
+/// Keyer speed in WPM for a `0x14 0x0C` level
+fn keyer_level_to_wpm(level: u16) -> u8 {
+    level_to_range(level, KEYER_MIN_WPM, KEYER_MAX_WPM) as u8
+}
+
+/// `0x14 0x0C` level for a keyer speed in WPM
+fn wpm_to_keyer_level(wpm: u8) -> u16 {
+    range_to_level(u16::from(wpm), KEYER_MIN_WPM, KEYER_MAX_WPM)
+}
+
+/// CW pitch in Hz for a `0x14 0x09` level
+fn pitch_level_to_hz(level: u16) -> u16 {
+    level_to_range(level, PITCH_MIN_HZ, PITCH_MAX_HZ)
+}
+
+/// `0x14 0x09` level for a CW pitch in Hz
+fn hz_to_pitch_level(hz: u16) -> u16 {
+    range_to_level(hz, PITCH_MIN_HZ, PITCH_MAX_HZ)
+}
+
+/// Break-in for a `0x16 0x47` setting
+fn civ_break_in(mode: u8) -> BreakIn {
+    match mode {
+        0x00 => BreakIn::Off,
+        0x01 => BreakIn::Semi,
+        _ => BreakIn::Full,
+    }
+}
+
+/// `0x16 0x47` setting for a break-in
+fn break_in_code(mode: BreakIn) -> u8 {
+    match mode {
+        BreakIn::Off => 0x00,
+        BreakIn::Semi => 0x01,
+        BreakIn::Full => 0x02,
+    }
+}
+
+/// Convert a 2-byte big-endian BCD level (0000-0255) to a number
+fn bcd_to_level(data: &[u8]) -> Option<u16> {
+    if data.len() > 2 {
+        return None;
+    }
+    data.iter().try_fold(0u16, |acc, &byte| {
+        let high = u16::from(byte >> 4);
+        let low = u16::from(byte & 0x0F);
+        (high <= 9 && low <= 9).then(|| acc * 100 + high * 10 + low)
+    })
+}
+
+/// Convert a level (0-9999) to 2-byte big-endian BCD
+fn level_to_bcd(level: u16) -> Vec<u8> {
+    let digits = |v: u16| (((v / 10) % 10) << 4 | (v % 10)) as u8;
+    vec![digits(level / 100), digits(level % 100)]
+}
+
 /// Convert BCD-encoded bytes to frequency in Hz
 /// CI-V uses little-endian BCD (least significant digit first)
 fn bcd_to_frequency(data: &[u8]) -> Result<u64, ParseError> {
@@ -641,49 +1230,109 @@ fn frequency_to_bcd(hz: u64) -> Vec<u8> {
     result
 }
 
-/// Convert CI-V mode number to OperatingMode
-fn civ_mode_to_operating_mode(mode: u8) -> OperatingMode {
-    match mode {
-        0x00 => OperatingMode::Lsb,
-        0x01 => OperatingMode::Usb,
-        0x02 => OperatingMode::Am,
-        0x03 => OperatingMode::Cw,
-        0x04 => OperatingMode::Rtty,
-        0x05 => OperatingMode::Fm,
-        0x06 => OperatingMode::CwR,
-        0x07 => OperatingMode::RttyR,
-        0x08 => OperatingMode::DataL,
-        0x09 => OperatingMode::DataU, // Icom calls this DATA-FM sometimes
-        0x11 => OperatingMode::DataL, // Some Icoms use different codes
-        0x12 => OperatingMode::DataU,
-        _ => OperatingMode::Usb,
-    }
-}
-
-/// Convert OperatingMode to CI-V mode number
-fn operating_mode_to_civ(mode: OperatingMode) -> u8 {
+/// Convert a 0x26 mode byte and data mode flag to OperatingMode
+fn civ_data_mode_to_operating_mode(mode: u8, data: u8) -> OperatingMode {
+    match (mode, data) {
+        (0x00, 1..) => OperatingMode::DataL,
+        (0x01, 1..) => OperatingMode::DataU,
+        (0x05, 1..) => OperatingMode::Data,
+        _ => ModeMap::CIV.mode(mode),
+    }
+}
+
+/// Convert OperatingMode to a 0x26 mode byte and data mode flag
+pub fn operating_mode_to_civ_data(mode: OperatingMode) -> (u8, u8) {
     match mode {
-        OperatingMode::Lsb => 0x00,
-        OperatingMode::Usb => 0x01,
-        OperatingMode::Am => 0x02,
-        OperatingMode::Cw => 0x03,
-        OperatingMode::Rtty => 0x04,
-        OperatingMode::Fm | OperatingMode::FmN => 0x05,
-        OperatingMode::CwR => 0x06,
-        OperatingMode::RttyR => 0x07,
-        OperatingMode::DataL | OperatingMode::DigL | OperatingMode::Dig => 0x08,
-        OperatingMode::DataU | OperatingMode::DigU | OperatingMode::Data | OperatingMode::Pkt => {
-            0x09
-        }
+        OperatingMode::DataL | OperatingMode::DigL | OperatingMode::Dig => (0x00, 0x01),
+        OperatingMode::DataU | OperatingMode::DigU | OperatingMode::Pkt => (0x01, 0x01),
+        OperatingMode::Data => (0x05, 0x01),
+        _ => (ModeMap::CIV.code(mode), 0x00),
     }
 }
 
+/// Encode an atomic frequency + mode change for the selected VFO
+///
+/// Emits the 0x25 (selected VFO frequency) and 0x26 (selected VFO mode)
+/// frames back-to-back so the radio receives both in a single write.
+pub fn encode_frequency_mode(radio_addr: u8, hz: u64, mode: OperatingMode) -> Vec<u8> {
+    let (civ_mode, data) = operating_mode_to_civ_data(mode);
+    let mut out =
+        CivCommand::to_radio(radio_addr, CivCommandType::SelectedFrequency { hz }).encode();
+    out.extend(
+        CivCommand::to_radio(
+            radio_addr,
+            CivCommandType::SelectedMode {
+                mode: civ_mode,
+                data,
+                filter: 1,
+            },
+        )
+        .encode(),
+    );
+    out
+}
+
 /// Generate a probe command to detect CI-V radios
 /// This reads the frequency, which should work on any Icom radio
 pub fn probe_command(radio_addr: u8) -> Vec<u8> {
     CivCommand::to_radio(radio_addr, CivCommandType::GetFrequency).encode()
 }
 
+/// CI-V command that reads the transceiver ID (`0x19 0x00`)
+const READ_ID: [u8; 2] = [0x19, 0x00];
+
+/// NG reply command byte
+const NG: u8 = 0xFA;
+
+/// Generate a read-ID command (`0x19 0x00`) for the radio at `radio_addr`
+pub fn read_id_command(radio_addr: u8) -> Vec<u8> {
+    let mut frame = CivFrameBuilder::new(radio_addr, CONTROLLER_ADDR, READ_ID[0]);
+    frame.push(READ_ID[1]);
+    frame.finish()
+}
+
+/// A radio's answer to [`read_id_command`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IdReply {
+    /// Address the radio answered from
+    pub address: u8,
+    /// Its ID: the model's default CI-V address, whatever `address` is set
+    /// to. `None` if it answered NG (older radios don't support read ID).
+    pub id: Option<u8>,
+}
+
+/// Find read-ID replies (and NGs) addressed to the controller in `data`
+///
+/// Anything else on the bus is skipped: our own commands echoed back on a
+/// single-wire bus, transceive broadcasts, stray bytes.
+pub fn parse_id_replies(data: &[u8]) -> Vec<IdReply> {
+    let mut replies = Vec::new();
+    let mut rest = data;
+    while let Some(start) = rest.windows(2).position(|w| w == [PREAMBLE, PREAMBLE]) {
+        let body = &rest[start + 2..];
+        let Some(end) = body.iter().position(|&b| b == TERMINATOR) else {
+            break;
+        };
+        match &body[..end] {
+            [CONTROLLER_ADDR, address, 0x19, 0x00, id] if *address != CONTROLLER_ADDR => {
+                replies.push(IdReply {
+                    address: *address,
+                    id: Some(*id),
+                });
+            }
+            [CONTROLLER_ADDR, address, NG] if *address != CONTROLLER_ADDR => {
+                replies.push(IdReply {
+                    address: *address,
+                    id: None,
+                });
+            }
+            _ => {}
+        }
+        rest = &body[end + 1..];
+    }
+    replies
+}
+
 /// Check if a response looks like a valid CI-V frame
 pub fn is_valid_frame(data: &[u8]) -> bool {
     data.len() >= 6
@@ -692,6 +1341,21 @@ pub fn is_valid_frame(data: &[u8]) -> bool {
         && data[data.len() - 1] == TERMINATOR
 }
 
+/// Address a reply frame back to the sender of `query`
+///
+/// The reply comes from the address the query was sent to, as if from the
+/// radio it asked. A query sent to the broadcast address keeps the reply's
+/// own source. Does nothing unless both are CI-V frames.
+pub fn address_reply(reply: &mut [u8], query: &[u8]) {
+    if !is_valid_frame(reply) || !is_valid_frame(query) {
+        return;
+    }
+    reply[2] = query[3];
+    if query[2] != BROADCAST_ADDR {
+        reply[3] = query[2];
+    }
+}
+
 /// Extract the source address from a CI-V frame
 pub fn extract_source_address(data: &[u8]) -> Option<u8> {
     if is_valid_frame(data) {
@@ -701,16 +1365,52 @@ pub fn extract_source_address(data: &[u8]) -> Option<u8> {
     }
 }
 
-crate::impl_radio_codec!(CivCodec);
+crate::impl_radio_codec!(CivCodec, mode_map);
 
 #[cfg(test)]
 mod tests {
-    use super::{bcd_to_frequency, frequency_to_bcd, CivCodec, CivCommand, CivCommandType};
+    use super::{
+        address_reply, bcd_to_frequency, encode_frequency_mode, frequency_to_bcd, CivCodec,
+        CivCommand, CivCommandType, CivFrameBuilder, CONTROLLER_ADDR,
+    };
+    use crate::error::ParseError;
+    use crate::Frequency;
     use crate::{
-        EncodeCommand, FromRadioRequest, ProtocolCodec, RadioRequest, RadioResponse,
-        ToRadioResponse,
+        EncodeCommand, FromRadioRequest, FromRadioResponse, MeterKind, OperatingMode,
+        ProtocolCodec, RadioRequest, RadioResponse, Receiver, ToRadioRequest, ToRadioResponse, Vfo,
+        VfoState,
     };
 
+    #[test]
+    fn test_read_id_replies() {
+        use super::{parse_id_replies, read_id_command, IdReply};
+
+        assert_eq!(
+            read_id_command(0x94),
+            vec![0xFE, 0xFE, 0x94, 0xE0, 0x19, 0x00, 0xFD]
+        );
+        let mut bus = read_id_command(0x70); // our own echo
+        bus.extend([0xFE, 0xFE, 0xE0, 0x70, 0x19, 0x00, 0x94, 0xFD]);
+        bus.extend([
+            0xFE, 0xFE, 0x00, 0x76, 0x00, 0x00, 0x40, 0x07, 0x14, 0x00, 0xFD,
+        ]);
+        bus.extend([0x00, 0xFE, 0xFE, 0xE0, 0x58, 0xFA, 0xFD]);
+        bus.extend([0xFE, 0xFE, 0xE0, 0x88, 0x19]); // cut off
+        assert_eq!(
+            parse_id_replies(&bus),
+            vec![
+                IdReply {
+                    address: 0x70,
+                    id: Some(0x94)
+                },
+                IdReply {
+                    address: 0x58,
+                    id: None
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_bcd_to_frequency() {
         // 14.250.000 Hz in BCD little-endian
@@ -791,7 +1491,12 @@ mod tests {
         let civ_cmd =
             CivCommand::from_radio(0x94, CivCommandType::FrequencyReport { hz: 7_074_000 });
         let response = civ_cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
@@ -853,4 +1558,239 @@ mod tests {
             CivCommandType::Transceive { enabled: true }
         ));
     }
+
+    #[test]
+    fn test_truncated_frame_rejected() {
+        // 0x25 0x00 with only 3 of 5 BCD bytes
+        let frame = [0xFE, 0xFE, 0xE0, 0x94, 0x25, 0x00, 0x00, 0x40, 0x07, 0xFD];
+        assert_eq!(
+            CivCodec::parse_frame(&frame),
+            Err(ParseError::Truncated {
+                command: "CI-V cmd 0x25".into(),
+                expected: 6,
+                actual: 4,
+            })
+        );
+
+        // Query form is not truncated
+        let query = [0xFE, 0xFE, 0x94, 0xE0, 0x25, 0x00, 0xFD];
+        assert!(CivCodec::parse_frame(&query).is_ok());
+
+        // A truncated frame doesn't stop the codec from reaching the next one
+        let mut codec = CivCodec::new();
+        codec.push_bytes(&frame);
+        codec.push_bytes(&[0xFE, 0xFE, 0xE0, 0x94, 0xFB, 0xFD]);
+        assert_eq!(codec.next_command().unwrap().command, CivCommandType::Ok);
+    }
+
+    #[test]
+    fn test_frame_builder_chunks() {
+        let mut builder = CivFrameBuilder::new(0x94, CONTROLLER_ADDR, 0x05);
+        builder.frequency(14_250_000);
+        assert_eq!(builder.data_len(), 5);
+
+        let chunks = builder.clone().finish_chunked(4);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.concat(), builder.finish());
+    }
+
+    #[test]
+    fn test_unselected_frequency_roundtrip() {
+        let cmd = CivCommand::new(
+            0xE0,
+            0x94,
+            CivCommandType::UnselectedFrequency { hz: 7_010_000 },
+        );
+        let data = cmd.encode();
+        assert_eq!(data[4..6], [0x25, 0x01]);
+
+        let mut codec = CivCodec::new();
+        codec.push_bytes(&data);
+        let parsed = codec.next_command().unwrap();
+        assert_eq!(parsed, cmd);
+        // Not the frequency the radio is operating on
+        assert_eq!(
+            parsed.to_radio_response(),
+            RadioResponse::Unknown { data: vec![] }
+        );
+    }
+
+    #[test]
+    fn test_encode_frequency_mode_selected_vfo() {
+        let data = encode_frequency_mode(0x94, 7_074_000, OperatingMode::DataU);
+
+        let mut codec = CivCodec::new();
+        codec.push_bytes(&data);
+
+        let freq = codec.next_command().unwrap();
+        assert_eq!(
+            freq.command,
+            CivCommandType::SelectedFrequency { hz: 7_074_000 }
+        );
+        assert_eq!(freq.to_addr, 0x94);
+
+        let mode = codec.next_command().unwrap();
+        assert_eq!(
+            mode.command,
+            CivCommandType::SelectedMode {
+                mode: 0x01,
+                data: 0x01,
+                filter: 1
+            }
+        );
+        assert_eq!(
+            mode.to_radio_response(),
+            RadioResponse::Mode {
+                mode: OperatingMode::DataU
+            }
+        );
+        assert!(codec.next_command().is_none());
+    }
+
+    #[test]
+    fn test_meter_report() {
+        // S-meter reading of 0120 (S9) from an IC-7300
+        let frame = [0xFE, 0xFE, 0xE0, 0x94, 0x15, 0x02, 0x01, 0x20, 0xFD];
+        let cmd = CivCodec::parse_frame(&frame).unwrap();
+        let response = cmd.to_radio_response();
+        assert_eq!(
+            response,
+            RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level: 120,
+            }
+        );
+
+        let encoded = CivCommand::from_radio_response(&response).unwrap().encode();
+        assert_eq!(&encoded[4..8], &[0x15, 0x02, 0x01, 0x20]);
+
+        // A level longer than two BCD bytes is refused, not overflowed
+        let long = [0xFE, 0xFE, 0xE0, 0x94, 0x14, 0x0C, 0x99, 0x99, 0x99, 0xFD];
+        assert!(CivCodec::parse_frame(&long).is_err());
+
+        // Squelch status is one byte, not a meter cut short
+        let squelch = [0xFE, 0xFE, 0xE0, 0x94, 0x15, 0x01, 0x01, 0xFD];
+        assert!(CivCodec::parse_frame(&squelch).is_ok());
+        let cut = [0xFE, 0xFE, 0xE0, 0x94, 0x15, 0x12, 0x01, 0xFD];
+        assert!(matches!(
+            CivCodec::parse_frame(&cut),
+            Err(ParseError::Truncated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rit_frames() {
+        // RIT offset of -120 Hz from an IC-7300
+        let frame = [0xFE, 0xFE, 0xE0, 0x94, 0x21, 0x00, 0x20, 0x01, 0x01, 0xFD];
+        let response = CivCodec::parse_frame(&frame).unwrap().to_radio_response();
+        assert_eq!(response, RadioResponse::RitOffset { hz: -120 });
+        let encoded = CivCommand::from_radio_response(&response).unwrap().encode();
+        assert_eq!(&encoded[4..9], &[0x21, 0x00, 0x20, 0x01, 0x01]);
+
+        let frame = [0xFE, 0xFE, 0x94, 0xE0, 0x21, 0x01, 0x01, 0xFD];
+        let request = CivCodec::parse_frame(&frame).unwrap().to_radio_request();
+        assert_eq!(request, RadioRequest::SetRit { on: true });
+
+        let clear = CivCommand::from_radio_request(&RadioRequest::ClearRit).unwrap();
+        assert_eq!(&clear.encode()[4..9], &[0x21, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(clear.to_radio_request(), RadioRequest::ClearRit);
+        assert!(CivCommand::from_radio_request(&RadioRequest::AdjustRit { hz: 10 }).is_none());
+
+        // Cut off after the first offset byte
+        assert_eq!(super::expected_data_len(0x21, Some(0x00)), Some((1, 4)));
+    }
+
+    #[test]
+    fn test_meter_query() {
+        // An amp reading the S-meter
+        let frame = [0xFE, 0xFE, 0x94, 0xE0, 0x15, 0x02, 0xFD];
+        let cmd = CivCodec::parse_frame(&frame).unwrap();
+        let request = cmd.to_radio_request();
+        assert_eq!(
+            request,
+            RadioRequest::GetMeter {
+                meter: MeterKind::Signal
+            }
+        );
+
+        let encoded = CivCommand::from_radio_request(&request).unwrap().encode();
+        assert_eq!(&encoded[4..], &[0x15, 0x02, 0xFD]);
+    }
+
+    #[test]
+    fn test_vfo_and_receiver_select() {
+        let decode = |sub: u8| {
+            let frame = [0xFE, 0xFE, 0x94, 0xE0, 0x07, sub, 0xFD];
+            CivCodec::parse_frame(&frame).unwrap().to_radio_response()
+        };
+        assert_eq!(
+            decode(0x01),
+            RadioResponse::VfoState {
+                state: VfoState::select(Vfo::B)
+            }
+        );
+        assert_eq!(
+            decode(0xD1),
+            RadioResponse::VfoState {
+                state: VfoState::receiver(Receiver::Sub)
+            }
+        );
+        // Exchange MAIN/SUB selects nothing
+        assert!(matches!(decode(0xB0), RadioResponse::Unknown { .. }));
+
+        let encode = |state| {
+            CivCommand::from_radio_request(&RadioRequest::SetVfoState { state })
+                .unwrap()
+                .encode()
+        };
+        assert_eq!(
+            &encode(VfoState::receiver(Receiver::Main))[4..],
+            &[0x07, 0xD0, 0xFD]
+        );
+        // No transmit-VFO select: transmitting on B is split
+        assert_eq!(
+            &encode(VfoState::transmit_on(Vfo::B))[4..],
+            &[0x0F, 0x01, 0xFD]
+        );
+    }
+
+    #[test]
+    fn test_ptt_and_split_queries() {
+        let request = |frame: &[u8]| CivCodec::parse_frame(frame).unwrap().to_radio_request();
+        // 0x1C 0x00 without a value reads PTT instead of unkeying
+        assert_eq!(
+            request(&[0xFE, 0xFE, 0x94, 0xE0, 0x1C, 0x00, 0xFD]),
+            RadioRequest::GetPtt
+        );
+        assert_eq!(
+            request(&[0xFE, 0xFE, 0x94, 0xE0, 0x1C, 0x00, 0x01, 0xFD]),
+            RadioRequest::SetPtt { active: true }
+        );
+        assert_eq!(
+            request(&[0xFE, 0xFE, 0x94, 0xE0, 0x0F, 0xFD]),
+            RadioRequest::GetVfo
+        );
+
+        let encode = |req| CivCommand::from_radio_request(&req).unwrap().encode();
+        assert_eq!(&encode(RadioRequest::GetPtt)[4..], &[0x1C, 0x00, 0xFD]);
+        assert_eq!(&encode(RadioRequest::GetVfo)[4..], &[0x0F, 0xFD]);
+    }
+
+    #[test]
+    fn test_address_reply() {
+        let reply = || CivCommand::new(0x00, CONTROLLER_ADDR, CivCommandType::Ok).encode();
+
+        let mut to_amp = reply();
+        address_reply(&mut to_amp, &[0xFE, 0xFE, 0x94, 0x54, 0x03, 0xFD]);
+        assert_eq!(to_amp, [0xFE, 0xFE, 0x54, 0x94, 0xFB, 0xFD]);
+
+        // Asked by broadcast: the reply keeps its own source
+        let mut broadcast = reply();
+        address_reply(&mut broadcast, &[0xFE, 0xFE, 0x00, 0x54, 0x03, 0xFD]);
+        assert_eq!(broadcast, [0xFE, 0xFE, 0x54, 0xE0, 0xFB, 0xFD]);
+
+        let mut untouched = reply();
+        address_reply(&mut untouched, b"FA;");
+        assert_eq!(untouched, reply());
+    }
 }
@@ -16,9 +16,13 @@
 //! - `RX` - Receive
 //! - `ID` - Radio identification
 //! - `IF` - Information (status)
+//! - `PC` - Output power
+//! - `KS`, `SD`, `PT` - Keyer speed, break-in delay, CW pitch
 
-use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+use crate::command::{MeterKind, RadioRequest, RadioResponse, Vfo, VfoState};
 use crate::error::ParseError;
+use crate::mode_map::{ModeCode, ModeMap};
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -27,6 +31,17 @@ use crate::{
 /// Maximum command length (reasonable limit to prevent buffer overflow)
 const MAX_COMMAND_LEN: usize = 64;
 
+/// Full-scale S-meter reading (TS-590 scale, S9 at 15)
+const SMETER_FULL_SCALE: u32 = 30;
+
+/// CW pitch of `PT00;` and the step per code (TS-590: 300-1000 Hz)
+const PITCH_BASE_HZ: u16 = 300;
+const PITCH_STEP_HZ: u16 = 50;
+const PITCH_MAX_CODE: u16 = 14;
+
+/// Largest step `RU`/`RD` take (five digits)
+const RIT_MAX_STEP_HZ: u32 = 99999;
+
 /// Kenwood protocol command
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum KenwoodCommand {
@@ -40,22 +55,42 @@ pub enum KenwoodCommand {
     Transmit(Option<bool>),
     /// Receive: RX;
     Receive,
+    /// Transmit on a receiver (TS-990 dual TX): TX0; (main) or TX1; (sub)
+    TransmitOn(u8),
     /// Radio identification query: ID;
     Id(Option<String>),
     /// Information/status query: IF...;
     Info(Option<KenwoodInfo>),
-    /// VFO select: FR0; (0=VFO A, 1=VFO B)
+    /// Receive VFO select: FR0; (0=VFO A, 1=VFO B, 2=memory)
     VfoSelect(Option<u8>),
-    /// Split mode: FT0; or FT1;
+    /// Transmit VFO select: FT0; (VFO A) or FT1; (VFO B)
     Split(Option<bool>),
     /// Power on/off: PS0; or PS1;
     Power(Option<bool>),
+    /// Output power in watts: PC; (query) or PC050;
+    PowerControl(Option<u16>),
     /// Auto-information mode: AI0; (off) or AI2; (on) or AI; (query)
     AutoInfo(Option<bool>),
     /// Control band (which VFO has front panel control): CB; (query), CB0; or CB1;
     ControlBand(Option<u8>),
     /// Transmit band (which VFO is selected for TX): TB; (query), TB0; or TB1;
     TransmitBand(Option<u8>),
+    /// S-meter: SM0; (query) or SM00015; (reading, 0-30 full scale)
+    SMeter(Option<u16>),
+    /// Keyer speed in WPM: KS; (query) or KS025;
+    KeyerSpeed(Option<u8>),
+    /// Break-in delay in ms: SD; (query) or SD0300;
+    BreakInDelay(Option<u16>),
+    /// CW pitch code: PT; (query) or PT06; (300 Hz + 50 Hz per step)
+    Pitch(Option<u8>),
+    /// RIT on/off: RT; (query), RT0; or RT1;
+    Rit(Option<bool>),
+    /// Clear the RIT offset: RC;
+    RitClear,
+    /// Move the RIT offset up in Hz: RU00050;
+    RitUp(u32),
+    /// Move the RIT offset down in Hz: RD00050;
+    RitDown(u32),
     /// Unknown/unrecognized command
     Unknown(String),
 }
@@ -90,6 +125,14 @@ pub struct KenwoodInfo {
 /// Streaming Kenwood protocol codec
 pub struct KenwoodCodec {
     buffer: Vec<u8>,
+    /// Parse `TX0;`/`TX1;` as the transmitting receiver (TS-990)
+    dual_tx: bool,
+    /// Last reported VFO A/B frequencies (dual TX only)
+    vfo_hz: [Option<u64>; 2],
+    /// Receiver transmitting since the last `TX0;`/`TX1;` (dual TX only)
+    tx_vfo: Option<u8>,
+    /// Mode codes for the connected model
+    mode_map: ModeMap,
 }
 
 impl KenwoodCodec {
@@ -97,7 +140,60 @@ impl KenwoodCodec {
     pub fn new() -> Self {
         Self {
             buffer: Vec::with_capacity(64),
+            dual_tx: false,
+            vfo_hz: [None; 2],
+            tx_vfo: None,
+            mode_map: ModeMap::KENWOOD,
+        }
+    }
+
+    /// Decode mode codes with a model's table (see [`crate::RadioModel::mode_map`])
+    pub fn with_mode_map(mut self, mode_map: ModeMap) -> Self {
+        self.mode_map = mode_map;
+        self
+    }
+
+    /// Parse for a radio where either receiver can transmit (TS-990)
+    ///
+    /// `TX0;`/`TX1;` then select the transmitting receiver instead of
+    /// meaning PTT off/on, and PTT reports come out as a status carrying
+    /// that receiver's VFO and last reported frequency, so the amplifier
+    /// follows the frequency actually being transmitted on.
+    pub fn with_dual_tx(mut self) -> Self {
+        self.dual_tx = true;
+        self
+    }
+
+    /// Convert a parsed command to a response, applying dual-TX tracking
+    fn radio_response(&mut self, cmd: &KenwoodCommand) -> RadioResponse {
+        if !self.dual_tx {
+            return self.mode_map.response(cmd);
+        }
+        match cmd {
+            KenwoodCommand::FrequencyA(Some(hz)) => self.vfo_hz[0] = Some(*hz),
+            KenwoodCommand::FrequencyB(Some(hz)) => self.vfo_hz[1] = Some(*hz),
+            KenwoodCommand::TransmitOn(vfo) => {
+                let vfo = (*vfo).min(1);
+                self.tx_vfo = Some(vfo);
+                return RadioResponse::Status {
+                    frequency_hz: self.vfo_hz[vfo as usize].map(Frequency::from_hz),
+                    mode: None,
+                    ptt: Some(true),
+                    vfo: Some(if vfo == 0 { Vfo::A } else { Vfo::B }),
+                };
+            }
+            // Back on the main receiver's frequency after transmitting on sub
+            KenwoodCommand::Receive if self.tx_vfo.take() == Some(1) => {
+                return RadioResponse::Status {
+                    frequency_hz: self.vfo_hz[0].map(Frequency::from_hz),
+                    mode: None,
+                    ptt: Some(false),
+                    vfo: Some(Vfo::A),
+                };
+            }
+            _ => {}
         }
+        self.mode_map.response(cmd)
     }
 
     /// Parse a complete command string (without terminator)
@@ -105,6 +201,10 @@ impl KenwoodCodec {
         if cmd.len() < 2 {
             return Err(ParseError::InvalidFrame("command too short".into()));
         }
+        // Line noise; the fields below are sliced by byte
+        if !cmd.is_ascii() {
+            return Err(ParseError::InvalidFrame("not ASCII".into()));
+        }
 
         let prefix = &cmd[..2];
         let params = &cmd[2..];
@@ -190,6 +290,16 @@ impl KenwoodCodec {
                     Ok(KenwoodCommand::Power(Some(on)))
                 }
             }
+            "PC" => {
+                if params.is_empty() {
+                    Ok(KenwoodCommand::PowerControl(None))
+                } else {
+                    let watts = params
+                        .parse::<u16>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid power level".into()))?;
+                    Ok(KenwoodCommand::PowerControl(Some(watts)))
+                }
+            }
             "AI" => {
                 if params.is_empty() {
                     Ok(KenwoodCommand::AutoInfo(None))
@@ -218,6 +328,67 @@ impl KenwoodCodec {
                     Ok(KenwoodCommand::TransmitBand(Some(band)))
                 }
             }
+            "KS" => {
+                if params.is_empty() {
+                    Ok(KenwoodCommand::KeyerSpeed(None))
+                } else {
+                    let wpm = params
+                        .parse::<u8>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid keyer speed".into()))?;
+                    Ok(KenwoodCommand::KeyerSpeed(Some(wpm)))
+                }
+            }
+            "SD" => {
+                if params.is_empty() {
+                    Ok(KenwoodCommand::BreakInDelay(None))
+                } else {
+                    let ms = params
+                        .parse::<u16>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid break-in delay".into()))?;
+                    Ok(KenwoodCommand::BreakInDelay(Some(ms)))
+                }
+            }
+            "PT" => {
+                if params.is_empty() {
+                    Ok(KenwoodCommand::Pitch(None))
+                } else {
+                    let code = params
+                        .parse::<u8>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid CW pitch".into()))?;
+                    Ok(KenwoodCommand::Pitch(Some(code)))
+                }
+            }
+            "RT" => match params {
+                "" => Ok(KenwoodCommand::Rit(None)),
+                "0" => Ok(KenwoodCommand::Rit(Some(false))),
+                "1" => Ok(KenwoodCommand::Rit(Some(true))),
+                _ => Err(ParseError::InvalidFrame("invalid RIT state".into())),
+            },
+            "RC" => Ok(KenwoodCommand::RitClear),
+            // Without a step the radio moves by its own tuning step, which
+            // isn't known here
+            "RU" | "RD" if params.is_empty() => Ok(KenwoodCommand::Unknown(cmd.to_string())),
+            "RU" | "RD" => {
+                let hz = params
+                    .parse::<u32>()
+                    .map_err(|_| ParseError::InvalidFrame("invalid RIT step".into()))?;
+                if prefix == "RU" {
+                    Ok(KenwoodCommand::RitUp(hz))
+                } else {
+                    Ok(KenwoodCommand::RitDown(hz))
+                }
+            }
+            "SM" => {
+                if params.len() <= 1 {
+                    Ok(KenwoodCommand::SMeter(None))
+                } else {
+                    // SMpnnnn; format - skip meter selector digit
+                    let value = params[1..]
+                        .parse::<u16>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid S-meter".into()))?;
+                    Ok(KenwoodCommand::SMeter(Some(value)))
+                }
+            }
             _ => Ok(KenwoodCommand::Unknown(cmd.to_string())),
         }
     }
@@ -314,6 +485,9 @@ impl ProtocolCodec for KenwoodCodec {
         let cmd_str = String::from_utf8_lossy(&cmd_bytes[..cmd_bytes.len() - 1]);
 
         let cmd = match Self::parse_command(&cmd_str) {
+            Ok(_) if self.dual_tx && matches!(&*cmd_str, "TX0" | "TX1") => {
+                KenwoodCommand::TransmitOn(cmd_str.as_bytes()[2] - b'0')
+            }
             Ok(cmd) => cmd,
             Err(e) => {
                 tracing::warn!("Failed to parse Kenwood command: {}", e);
@@ -327,40 +501,56 @@ impl ProtocolCodec for KenwoodCodec {
     fn clear(&mut self) {
         self.buffer.clear();
     }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
 }
 
 impl ToRadioResponse for KenwoodCommand {
     fn to_radio_response(&self) -> RadioResponse {
         match self {
-            KenwoodCommand::FrequencyA(Some(hz)) => RadioResponse::Frequency { hz: *hz },
+            KenwoodCommand::FrequencyA(Some(hz)) => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             KenwoodCommand::FrequencyA(None) => RadioResponse::Unknown { data: vec![] },
-            KenwoodCommand::FrequencyB(Some(hz)) => RadioResponse::Frequency { hz: *hz },
+            KenwoodCommand::FrequencyB(Some(hz)) => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             KenwoodCommand::FrequencyB(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Mode(Some(m)) => RadioResponse::Mode {
-                mode: kenwood_mode_to_operating_mode(*m),
+                mode: ModeMap::KENWOOD.mode(*m),
             },
             KenwoodCommand::Mode(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Transmit(Some(tx)) => RadioResponse::Ptt { active: *tx },
             KenwoodCommand::Transmit(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Receive => RadioResponse::Ptt { active: false },
+            KenwoodCommand::TransmitOn(vfo) => RadioResponse::Status {
+                frequency_hz: None,
+                mode: None,
+                ptt: Some(true),
+                vfo: Some(if *vfo == 0 { Vfo::A } else { Vfo::B }),
+            },
             KenwoodCommand::Id(Some(id)) => RadioResponse::Id { id: id.clone() },
             KenwoodCommand::Id(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Info(Some(info)) => RadioResponse::Status {
-                frequency_hz: Some(info.frequency_hz),
-                mode: Some(kenwood_mode_to_operating_mode(info.mode)),
+                frequency_hz: Some(Frequency::from_hz(info.frequency_hz)),
+                mode: Some(ModeMap::KENWOOD.mode(info.mode)),
                 ptt: Some(info.tx),
                 vfo: Some(if info.vfo == 0 { Vfo::A } else { Vfo::B }),
             },
             KenwoodCommand::Info(None) => RadioResponse::Unknown { data: vec![] },
-            KenwoodCommand::VfoSelect(Some(v)) => RadioResponse::Vfo {
-                vfo: if *v == 0 { Vfo::A } else { Vfo::B },
+            KenwoodCommand::VfoSelect(Some(v)) => RadioResponse::VfoState {
+                state: VfoState::select(fr_vfo(*v)),
             },
             KenwoodCommand::VfoSelect(None) => RadioResponse::Unknown { data: vec![] },
-            KenwoodCommand::Split(Some(s)) => RadioResponse::Vfo {
-                vfo: if *s { Vfo::Split } else { Vfo::A },
+            KenwoodCommand::Split(Some(s)) => RadioResponse::VfoState {
+                state: VfoState::transmit_on(ft_vfo(*s)),
             },
             KenwoodCommand::Split(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Power(_) => RadioResponse::Unknown { data: vec![] },
+            KenwoodCommand::PowerControl(Some(watts)) => RadioResponse::TxPower { watts: *watts },
+            KenwoodCommand::PowerControl(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::AutoInfo(Some(enabled)) => {
                 RadioResponse::AutoInfo { enabled: *enabled }
             }
@@ -369,6 +559,24 @@ impl ToRadioResponse for KenwoodCommand {
             KenwoodCommand::ControlBand(None) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::TransmitBand(Some(band)) => RadioResponse::TransmitBand { band: *band },
             KenwoodCommand::TransmitBand(None) => RadioResponse::Unknown { data: vec![] },
+            KenwoodCommand::SMeter(Some(v)) => RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level: (u32::from(*v) * 255 / SMETER_FULL_SCALE).min(255) as u8,
+            },
+            KenwoodCommand::SMeter(None) => RadioResponse::Unknown { data: vec![] },
+            KenwoodCommand::KeyerSpeed(Some(wpm)) => RadioResponse::KeyerSpeed { wpm: *wpm },
+            KenwoodCommand::BreakInDelay(Some(ms)) => RadioResponse::BreakInDelay { ms: *ms },
+            KenwoodCommand::Pitch(Some(code)) => RadioResponse::SidetonePitch {
+                hz: pitch_hz(*code),
+            },
+            KenwoodCommand::Rit(Some(on)) => RadioResponse::Rit { on: *on },
+            KenwoodCommand::KeyerSpeed(None)
+            | KenwoodCommand::BreakInDelay(None)
+            | KenwoodCommand::Pitch(None)
+            | KenwoodCommand::Rit(None)
+            | KenwoodCommand::RitClear
+            | KenwoodCommand::RitUp(_)
+            | KenwoodCommand::RitDown(_) => RadioResponse::Unknown { data: vec![] },
             KenwoodCommand::Unknown(s) => RadioResponse::Unknown {
                 data: s.as_bytes().to_vec(),
             },
@@ -376,34 +584,59 @@ impl ToRadioResponse for KenwoodCommand {
     }
 }
 
+impl ModeCode for KenwoodCommand {
+    fn mode_code(&self) -> Option<u8> {
+        match self {
+            KenwoodCommand::Mode(Some(m)) => Some(*m),
+            KenwoodCommand::Info(Some(info)) => Some(info.mode),
+            _ => None,
+        }
+    }
+
+    fn set_mode_code(&mut self, code: u8) {
+        match self {
+            KenwoodCommand::Mode(Some(m)) => *m = code,
+            KenwoodCommand::Info(Some(info)) => info.mode = code,
+            _ => {}
+        }
+    }
+}
+
 impl ToRadioRequest for KenwoodCommand {
     fn to_radio_request(&self) -> RadioRequest {
         match self {
-            KenwoodCommand::FrequencyA(Some(hz)) => RadioRequest::SetFrequency { hz: *hz },
+            KenwoodCommand::FrequencyA(Some(hz)) => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             KenwoodCommand::FrequencyA(None) => RadioRequest::GetFrequency,
-            KenwoodCommand::FrequencyB(Some(hz)) => RadioRequest::SetFrequency { hz: *hz },
+            KenwoodCommand::FrequencyB(Some(hz)) => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             KenwoodCommand::FrequencyB(None) => RadioRequest::GetFrequency,
             KenwoodCommand::Mode(Some(m)) => RadioRequest::SetMode {
-                mode: kenwood_mode_to_operating_mode(*m),
+                mode: ModeMap::KENWOOD.mode(*m),
             },
             KenwoodCommand::Mode(None) => RadioRequest::GetMode,
             KenwoodCommand::Transmit(Some(tx)) => RadioRequest::SetPtt { active: *tx },
             KenwoodCommand::Transmit(None) => RadioRequest::GetPtt,
             KenwoodCommand::Receive => RadioRequest::SetPtt { active: false },
+            KenwoodCommand::TransmitOn(_) => RadioRequest::SetPtt { active: true },
             KenwoodCommand::Id(Some(_)) => RadioRequest::Unknown { data: vec![] },
             KenwoodCommand::Id(None) => RadioRequest::GetId,
             KenwoodCommand::Info(Some(_)) => RadioRequest::Unknown { data: vec![] },
             KenwoodCommand::Info(None) => RadioRequest::GetStatus,
-            KenwoodCommand::VfoSelect(Some(v)) => RadioRequest::SetVfo {
-                vfo: if *v == 0 { Vfo::A } else { Vfo::B },
+            KenwoodCommand::VfoSelect(Some(v)) => RadioRequest::SetVfoState {
+                state: VfoState::select(fr_vfo(*v)),
             },
             KenwoodCommand::VfoSelect(None) => RadioRequest::GetVfo,
-            KenwoodCommand::Split(Some(s)) => RadioRequest::SetVfo {
-                vfo: if *s { Vfo::Split } else { Vfo::A },
+            KenwoodCommand::Split(Some(s)) => RadioRequest::SetVfoState {
+                state: VfoState::transmit_on(ft_vfo(*s)),
             },
             KenwoodCommand::Split(None) => RadioRequest::GetVfo,
             KenwoodCommand::Power(Some(on)) => RadioRequest::SetPower { on: *on },
             KenwoodCommand::Power(None) => RadioRequest::Unknown { data: vec![] },
+            KenwoodCommand::PowerControl(Some(watts)) => RadioRequest::SetTxPower { watts: *watts },
+            KenwoodCommand::PowerControl(None) => RadioRequest::GetTxPower,
             KenwoodCommand::AutoInfo(Some(enabled)) => {
                 RadioRequest::SetAutoInfo { enabled: *enabled }
             }
@@ -412,6 +645,23 @@ impl ToRadioRequest for KenwoodCommand {
             KenwoodCommand::ControlBand(None) => RadioRequest::GetControlBand,
             KenwoodCommand::TransmitBand(Some(_)) => RadioRequest::Unknown { data: vec![] },
             KenwoodCommand::TransmitBand(None) => RadioRequest::GetTransmitBand,
+            KenwoodCommand::SMeter(Some(_)) => RadioRequest::Unknown { data: vec![] },
+            KenwoodCommand::SMeter(None) => RadioRequest::GetMeter {
+                meter: MeterKind::Signal,
+            },
+            KenwoodCommand::KeyerSpeed(Some(wpm)) => RadioRequest::SetKeyerSpeed { wpm: *wpm },
+            KenwoodCommand::KeyerSpeed(None) => RadioRequest::GetKeyerSpeed,
+            KenwoodCommand::BreakInDelay(Some(ms)) => RadioRequest::SetBreakInDelay { ms: *ms },
+            KenwoodCommand::BreakInDelay(None) => RadioRequest::GetBreakInDelay,
+            KenwoodCommand::Pitch(Some(code)) => RadioRequest::SetSidetonePitch {
+                hz: pitch_hz(*code),
+            },
+            KenwoodCommand::Pitch(None) => RadioRequest::GetSidetonePitch,
+            KenwoodCommand::Rit(Some(on)) => RadioRequest::SetRit { on: *on },
+            KenwoodCommand::Rit(None) => RadioRequest::GetRit,
+            KenwoodCommand::RitClear => RadioRequest::ClearRit,
+            KenwoodCommand::RitUp(hz) => RadioRequest::AdjustRit { hz: rit_step(*hz) },
+            KenwoodCommand::RitDown(hz) => RadioRequest::AdjustRit { hz: -rit_step(*hz) },
             KenwoodCommand::Unknown(s) => RadioRequest::Unknown {
                 data: s.as_bytes().to_vec(),
             },
@@ -422,56 +672,126 @@ impl ToRadioRequest for KenwoodCommand {
 impl FromRadioRequest for KenwoodCommand {
     fn from_radio_request(req: &RadioRequest) -> Option<Self> {
         match req {
-            RadioRequest::SetFrequency { hz } => Some(KenwoodCommand::FrequencyA(Some(*hz))),
+            RadioRequest::SetFrequency { hz } => Some(KenwoodCommand::FrequencyA(Some(hz.hz()))),
             RadioRequest::GetFrequency => Some(KenwoodCommand::FrequencyA(None)),
             RadioRequest::SetMode { mode } => {
-                Some(KenwoodCommand::Mode(Some(operating_mode_to_kenwood(*mode))))
+                Some(KenwoodCommand::Mode(Some(ModeMap::KENWOOD.code(*mode))))
             }
             RadioRequest::GetMode => Some(KenwoodCommand::Mode(None)),
+            // Compound request: see encode_request_sequence
+            RadioRequest::SetFrequencyMode { .. } => None,
             RadioRequest::SetPtt { active: true } => Some(KenwoodCommand::Transmit(Some(true))),
             RadioRequest::SetPtt { active: false } => Some(KenwoodCommand::Receive),
             RadioRequest::GetPtt => Some(KenwoodCommand::Transmit(None)),
-            RadioRequest::SetVfo { vfo } => match vfo {
-                Vfo::A => Some(KenwoodCommand::VfoSelect(Some(0))),
-                Vfo::B => Some(KenwoodCommand::VfoSelect(Some(1))),
-                Vfo::Split => Some(KenwoodCommand::Split(Some(true))),
-                Vfo::Memory => Some(KenwoodCommand::VfoSelect(Some(2))),
-            },
+            RadioRequest::SetVfoState { state } => vfo_state_command(state),
+            RadioRequest::SetVfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioRequest::GetVfo => Some(KenwoodCommand::VfoSelect(None)),
             RadioRequest::GetId => Some(KenwoodCommand::Id(None)),
             RadioRequest::GetStatus => Some(KenwoodCommand::Info(None)),
             RadioRequest::SetPower { on } => Some(KenwoodCommand::Power(Some(*on))),
+            RadioRequest::SetTxPower { watts } => Some(KenwoodCommand::PowerControl(Some(*watts))),
+            RadioRequest::GetTxPower => Some(KenwoodCommand::PowerControl(None)),
             RadioRequest::SetAutoInfo { enabled } => Some(KenwoodCommand::AutoInfo(Some(*enabled))),
             RadioRequest::GetAutoInfo => Some(KenwoodCommand::AutoInfo(None)),
             RadioRequest::GetControlBand => Some(KenwoodCommand::ControlBand(None)),
             RadioRequest::GetTransmitBand => Some(KenwoodCommand::TransmitBand(None)),
+            RadioRequest::SetKeyerSpeed { wpm } => Some(KenwoodCommand::KeyerSpeed(Some(*wpm))),
+            RadioRequest::GetKeyerSpeed => Some(KenwoodCommand::KeyerSpeed(None)),
+            // Break-in on/off is a menu setting
+            RadioRequest::SetBreakIn { .. } | RadioRequest::GetBreakIn => None,
+            RadioRequest::SetBreakInDelay { ms } => Some(KenwoodCommand::BreakInDelay(Some(*ms))),
+            RadioRequest::GetBreakInDelay => Some(KenwoodCommand::BreakInDelay(None)),
+            RadioRequest::SetSidetonePitch { hz } => {
+                Some(KenwoodCommand::Pitch(Some(pitch_code(*hz))))
+            }
+            RadioRequest::GetSidetonePitch => Some(KenwoodCommand::Pitch(None)),
+            RadioRequest::SetRit { on } => Some(KenwoodCommand::Rit(Some(*on))),
+            RadioRequest::GetRit => Some(KenwoodCommand::Rit(None)),
+            RadioRequest::ClearRit => Some(KenwoodCommand::RitClear),
+            RadioRequest::AdjustRit { hz } => rit_adjust(*hz),
+            // The offset is only reported in IF
+            RadioRequest::GetRitOffset => None,
+            RadioRequest::GetMeter {
+                meter: MeterKind::Signal,
+            } => Some(KenwoodCommand::SMeter(None)),
+            RadioRequest::GetMeter { .. } => None,
             RadioRequest::Unknown { .. } => None,
         }
     }
 }
 
+/// VFO an `FR` parameter selects
+fn fr_vfo(param: u8) -> Vfo {
+    match param {
+        0 => Vfo::A,
+        2 => Vfo::Memory,
+        _ => Vfo::B,
+    }
+}
+
+/// VFO an `FT` parameter transmits on
+fn ft_vfo(param: bool) -> Vfo {
+    if param {
+        Vfo::B
+    } else {
+        Vfo::A
+    }
+}
+
+/// `FR` for the receive VFO, otherwise `FT` for the transmit VFO or split
+///
+/// `FR` moves the transmit VFO along with it, so a state naming both VFOs
+/// goes out as `FR` then `FT` via [`RadioRequest::components`].
+fn vfo_state_command(state: &VfoState) -> Option<KenwoodCommand> {
+    if let Some(rx) = state.rx_vfo {
+        let param = match rx {
+            Vfo::A => 0,
+            Vfo::B => 1,
+            Vfo::Memory => 2,
+            Vfo::Split => return Some(KenwoodCommand::Split(Some(true))),
+        };
+        return Some(KenwoodCommand::VfoSelect(Some(param)));
+    }
+    if let Some(tx) = state.tx_vfo {
+        return Some(KenwoodCommand::Split(Some(tx == Vfo::B)));
+    }
+    state.split.map(|on| KenwoodCommand::Split(Some(on)))
+}
+
 impl FromRadioResponse for KenwoodCommand {
     fn from_radio_response(resp: &RadioResponse) -> Option<Self> {
         match resp {
-            RadioResponse::Frequency { hz } => Some(KenwoodCommand::FrequencyA(Some(*hz))),
+            RadioResponse::Frequency { hz } => Some(KenwoodCommand::FrequencyA(Some(hz.hz()))),
             RadioResponse::Mode { mode } => {
-                Some(KenwoodCommand::Mode(Some(operating_mode_to_kenwood(*mode))))
+                Some(KenwoodCommand::Mode(Some(ModeMap::KENWOOD.code(*mode))))
             }
             RadioResponse::Ptt { active } => Some(KenwoodCommand::Transmit(Some(*active))),
-            RadioResponse::Vfo { vfo } => match vfo {
-                Vfo::A => Some(KenwoodCommand::VfoSelect(Some(0))),
-                Vfo::B => Some(KenwoodCommand::VfoSelect(Some(1))),
-                Vfo::Split => Some(KenwoodCommand::Split(Some(true))),
-                Vfo::Memory => Some(KenwoodCommand::VfoSelect(Some(2))),
-            },
+            RadioResponse::VfoState { state } => vfo_state_command(state),
+            RadioResponse::Vfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioResponse::Id { id } => Some(KenwoodCommand::Id(Some(id.clone()))),
             RadioResponse::Status { frequency_hz, .. } => {
                 // For status, we just send the frequency as the primary info
-                frequency_hz.map(|hz| KenwoodCommand::FrequencyA(Some(hz)))
+                frequency_hz.map(|hz| KenwoodCommand::FrequencyA(Some(hz.hz())))
             }
+            RadioResponse::TxPower { watts } => Some(KenwoodCommand::PowerControl(Some(*watts))),
             RadioResponse::AutoInfo { enabled } => Some(KenwoodCommand::AutoInfo(Some(*enabled))),
             RadioResponse::ControlBand { band } => Some(KenwoodCommand::ControlBand(Some(*band))),
             RadioResponse::TransmitBand { band } => Some(KenwoodCommand::TransmitBand(Some(*band))),
+            RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level,
+            } => Some(KenwoodCommand::SMeter(Some(
+                (u32::from(*level) * SMETER_FULL_SCALE / 255) as u16,
+            ))),
+            RadioResponse::Meter { .. } => None,
+            RadioResponse::KeyerSpeed { wpm } => Some(KenwoodCommand::KeyerSpeed(Some(*wpm))),
+            RadioResponse::BreakIn { .. } => None,
+            RadioResponse::BreakInDelay { ms } => Some(KenwoodCommand::BreakInDelay(Some(*ms))),
+            RadioResponse::SidetonePitch { hz } => {
+                Some(KenwoodCommand::Pitch(Some(pitch_code(*hz))))
+            }
+            RadioResponse::Rit { on } => Some(KenwoodCommand::Rit(Some(*on))),
+            RadioResponse::RitOffset { .. } => None,
             RadioResponse::Unknown { .. } => None,
         }
     }
@@ -490,6 +810,7 @@ impl EncodeCommand for KenwoodCommand {
             KenwoodCommand::Transmit(Some(false)) => "TX0".to_string(),
             KenwoodCommand::Transmit(None) => "TX".to_string(),
             KenwoodCommand::Receive => "RX".to_string(),
+            KenwoodCommand::TransmitOn(vfo) => format!("TX{}", vfo),
             KenwoodCommand::Id(Some(id)) => format!("ID{}", id),
             KenwoodCommand::Id(None) => "ID".to_string(),
             KenwoodCommand::Info(_) => "IF".to_string(),
@@ -499,6 +820,8 @@ impl EncodeCommand for KenwoodCommand {
             KenwoodCommand::Split(None) => "FT".to_string(),
             KenwoodCommand::Power(Some(on)) => format!("PS{}", if *on { 1 } else { 0 }),
             KenwoodCommand::Power(None) => "PS".to_string(),
+            KenwoodCommand::PowerControl(Some(watts)) => format!("PC{:03}", watts),
+            KenwoodCommand::PowerControl(None) => "PC".to_string(),
             KenwoodCommand::AutoInfo(Some(enabled)) => {
                 format!("AI{}", if *enabled { 2 } else { 0 })
             }
@@ -507,46 +830,51 @@ impl EncodeCommand for KenwoodCommand {
             KenwoodCommand::ControlBand(None) => "CB".to_string(),
             KenwoodCommand::TransmitBand(Some(band)) => format!("TB{}", band),
             KenwoodCommand::TransmitBand(None) => "TB".to_string(),
+            KenwoodCommand::SMeter(Some(v)) => format!("SM0{:04}", v),
+            KenwoodCommand::SMeter(None) => "SM0".to_string(),
+            KenwoodCommand::KeyerSpeed(Some(wpm)) => format!("KS{:03}", wpm),
+            KenwoodCommand::KeyerSpeed(None) => "KS".to_string(),
+            KenwoodCommand::BreakInDelay(Some(ms)) => format!("SD{:04}", ms),
+            KenwoodCommand::BreakInDelay(None) => "SD".to_string(),
+            KenwoodCommand::Pitch(Some(code)) => format!("PT{:02}", code),
+            KenwoodCommand::Pitch(None) => "PT".to_string(),
+            KenwoodCommand::Rit(Some(on)) => format!("RT{}", if *on { 1 } else { 0 }),
+            KenwoodCommand::Rit(None) => "RT".to_string(),
+            KenwoodCommand::RitClear => "RC".to_string(),
+            KenwoodCommand::RitUp(hz) => format!("RU{:05}", hz),
+            KenwoodCommand::RitDown(hz) => format!("RD{:05}", hz),
             KenwoodCommand::Unknown(s) => s.clone(),
         };
         format!("{};", cmd).into_bytes()
     }
 }
 
-/// Convert Kenwood mode number to OperatingMode
-fn kenwood_mode_to_operating_mode(mode: u8) -> OperatingMode {
-    match mode {
-        1 => OperatingMode::Lsb,
-        2 => OperatingMode::Usb,
-        3 => OperatingMode::Cw,
-        4 => OperatingMode::Fm,
-        5 => OperatingMode::Am,
-        6 => OperatingMode::Rtty,
-        7 => OperatingMode::CwR,
-        8 => OperatingMode::DataL,
-        9 => OperatingMode::RttyR,
-        10 => OperatingMode::DataU,
-        _ => OperatingMode::Usb,
-    }
+/// Signed RIT step for an `RU`/`RD` parameter
+fn rit_step(hz: u32) -> i32 {
+    i32::try_from(hz).unwrap_or(i32::MAX)
 }
 
-/// Convert OperatingMode to Kenwood mode number
-fn operating_mode_to_kenwood(mode: OperatingMode) -> u8 {
-    match mode {
-        OperatingMode::Lsb => 1,
-        OperatingMode::Usb => 2,
-        OperatingMode::Cw => 3,
-        OperatingMode::Fm => 4,
-        OperatingMode::FmN => 4,
-        OperatingMode::Am => 5,
-        OperatingMode::Rtty => 6,
-        OperatingMode::CwR => 7,
-        OperatingMode::DataL | OperatingMode::DigL | OperatingMode::Dig => 8,
-        OperatingMode::RttyR => 9,
-        OperatingMode::DataU | OperatingMode::DigU | OperatingMode::Data | OperatingMode::Pkt => 10,
+/// `RU`/`RD` command moving RIT by `hz` (none for a zero step)
+fn rit_adjust(hz: i32) -> Option<KenwoodCommand> {
+    let step = hz.unsigned_abs().min(RIT_MAX_STEP_HZ);
+    match hz.signum() {
+        1 => Some(KenwoodCommand::RitUp(step)),
+        -1 => Some(KenwoodCommand::RitDown(step)),
+        _ => None,
     }
 }
 
+/// CW pitch in Hz for a `PT` code
+fn pitch_hz(code: u8) -> u16 {
+    PITCH_BASE_HZ + u16::from(code) * PITCH_STEP_HZ
+}
+
+/// Nearest `PT` code for a CW pitch in Hz
+fn pitch_code(hz: u16) -> u8 {
+    let steps = (hz.saturating_sub(PITCH_BASE_HZ) + PITCH_STEP_HZ / 2) / PITCH_STEP_HZ;
+    steps.min(PITCH_MAX_CODE) as u8
+}
+
 /// Generate a probe command to detect Kenwood radios
 pub fn probe_command() -> Vec<u8> {
     b"ID;".to_vec()
@@ -563,14 +891,46 @@ pub fn is_valid_id_response(data: &[u8]) -> bool {
     }
 }
 
-crate::impl_radio_codec!(KenwoodCodec);
+impl crate::RadioCodec for KenwoodCodec {
+    fn push_bytes(&mut self, data: &[u8]) {
+        ProtocolCodec::push_bytes(self, data);
+    }
+
+    fn next_response(&mut self) -> Option<RadioResponse> {
+        self.next_response_with_bytes().map(|(resp, _)| resp)
+    }
+
+    fn next_response_with_bytes(&mut self) -> Option<(RadioResponse, Vec<u8>)> {
+        let (cmd, bytes) = self.next_command_with_bytes()?;
+        Some((self.radio_response(&cmd), bytes))
+    }
+
+    fn next_request(&mut self) -> Option<RadioRequest> {
+        self.next_command().map(|cmd| cmd.to_radio_request())
+    }
+
+    fn next_request_with_bytes(&mut self) -> Option<(RadioRequest, Vec<u8>)> {
+        self.next_command_with_bytes()
+            .map(|(cmd, bytes)| (cmd.to_radio_request(), bytes))
+    }
+
+    fn clear(&mut self) {
+        ProtocolCodec::clear(self);
+    }
+
+    fn buffered(&self) -> &[u8] {
+        ProtocolCodec::buffered(self)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::{KenwoodCodec, KenwoodCommand};
+    use crate::Frequency;
     use crate::{
-        EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, RadioRequest,
-        RadioResponse, ToRadioRequest, ToRadioResponse,
+        encode_request_sequence, EncodeCommand, FromRadioRequest, FromRadioResponse, MeterKind,
+        OperatingMode, ProtocolCodec, RadioRequest, RadioResponse, ToRadioRequest, ToRadioResponse,
+        Vfo, VfoState,
     };
 
     #[test]
@@ -591,6 +951,23 @@ mod tests {
         assert_eq!(cmd, KenwoodCommand::Mode(Some(2)));
     }
 
+    #[test]
+    fn test_non_ascii_frame_is_unknown() {
+        let mut codec = KenwoodCodec::new();
+        // A multi-byte character straddling the end of the prefix
+        codec.push_bytes(b"\xE2\x82\xAC0;SM\xC3\xA9;FA;");
+
+        assert!(matches!(
+            codec.next_command(),
+            Some(KenwoodCommand::Unknown(_))
+        ));
+        assert!(matches!(
+            codec.next_command(),
+            Some(KenwoodCommand::Unknown(_))
+        ));
+        assert_eq!(codec.next_command(), Some(KenwoodCommand::FrequencyA(None)));
+    }
+
     #[test]
     fn test_parse_id_query() {
         let mut codec = KenwoodCodec::new();
@@ -621,6 +998,52 @@ mod tests {
         assert_eq!(cmd, KenwoodCommand::FrequencyA(Some(14_250_000)));
     }
 
+    #[test]
+    fn test_dual_tx_reports_transmitting_receiver() {
+        use crate::{RadioCodec, Vfo};
+
+        let mut codec: Box<dyn RadioCodec> = Box::new(KenwoodCodec::new().with_dual_tx());
+        codec.push_bytes(b"FA00014250000;FB00021300000;TX1;RX;TX0;");
+        let responses: Vec<_> = std::iter::from_fn(|| codec.next_response()).collect();
+        assert_eq!(
+            responses[2],
+            RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(21_300_000)),
+                mode: None,
+                ptt: Some(true),
+                vfo: Some(Vfo::B),
+            }
+        );
+        // Back to the main receiver's frequency
+        assert_eq!(
+            responses[3],
+            RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(14_250_000)),
+                mode: None,
+                ptt: Some(false),
+                vfo: Some(Vfo::A),
+            }
+        );
+        assert_eq!(
+            responses[4],
+            RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(14_250_000)),
+                mode: None,
+                ptt: Some(true),
+                vfo: Some(Vfo::A),
+            }
+        );
+        assert_eq!(KenwoodCommand::TransmitOn(1).encode(), b"TX1;");
+
+        // Without dual TX, TX0 still means PTT off
+        let mut codec: Box<dyn RadioCodec> = Box::new(KenwoodCodec::new());
+        codec.push_bytes(b"TX0;");
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Ptt { active: false })
+        );
+    }
+
     #[test]
     fn test_multiple_commands() {
         let mut codec = KenwoodCodec::new();
@@ -642,7 +1065,12 @@ mod tests {
     fn test_to_radio_response() {
         let cmd = KenwoodCommand::FrequencyA(Some(7_074_000));
         let response = cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
@@ -653,19 +1081,40 @@ mod tests {
 
         let cmd = KenwoodCommand::FrequencyA(Some(14_250_000));
         let request = cmd.to_radio_request();
-        assert_eq!(request, RadioRequest::SetFrequency { hz: 14_250_000 });
+        assert_eq!(
+            request,
+            RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(14_250_000)
+            }
+        );
     }
 
     #[test]
     fn test_from_radio_request() {
-        let req = RadioRequest::SetFrequency { hz: 14_250_000 };
+        let req = RadioRequest::SetFrequency {
+            hz: Frequency::from_hz(14_250_000),
+        };
         let cmd = KenwoodCommand::from_radio_request(&req).unwrap();
         assert_eq!(cmd, KenwoodCommand::FrequencyA(Some(14_250_000)));
     }
 
+    #[test]
+    fn test_encode_frequency_mode_sequence() {
+        let req = RadioRequest::SetFrequencyMode {
+            hz: Frequency::from_hz(7_074_000),
+            mode: OperatingMode::Usb,
+        };
+        assert!(KenwoodCommand::from_radio_request(&req).is_none());
+
+        let data = encode_request_sequence::<KenwoodCommand>(&req).unwrap();
+        assert_eq!(data, b"FA00007074000;MD2;");
+    }
+
     #[test]
     fn test_from_radio_response() {
-        let resp = RadioResponse::Frequency { hz: 7_074_000 };
+        let resp = RadioResponse::Frequency {
+            hz: Frequency::from_hz(7_074_000),
+        };
         let cmd = KenwoodCommand::from_radio_response(&resp).unwrap();
         assert_eq!(cmd, KenwoodCommand::FrequencyA(Some(7_074_000)));
     }
@@ -771,6 +1220,28 @@ mod tests {
         assert_eq!(cmd.to_radio_request(), RadioRequest::GetTransmitBand);
     }
 
+    #[test]
+    fn test_parse_smeter_query() {
+        let mut codec = KenwoodCodec::new();
+        codec.push_bytes(b"SM0;");
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(cmd, KenwoodCommand::SMeter(None));
+        let request = cmd.to_radio_request();
+        assert_eq!(
+            request,
+            RadioRequest::GetMeter {
+                meter: MeterKind::Signal
+            }
+        );
+        assert_eq!(
+            KenwoodCommand::from_radio_request(&request)
+                .unwrap()
+                .encode(),
+            b"SM0;".to_vec()
+        );
+    }
+
     #[test]
     fn test_parse_transmit_band_set() {
         let mut codec = KenwoodCodec::new();
@@ -824,4 +1295,100 @@ mod tests {
             KenwoodCommand::from_radio_response(&RadioResponse::TransmitBand { band: 1 }).unwrap();
         assert_eq!(cmd, KenwoodCommand::TransmitBand(Some(1)));
     }
+
+    #[test]
+    fn test_parse_power_control() {
+        let mut codec = KenwoodCodec::new();
+        codec.push_bytes(b"PC;PC050;");
+
+        assert_eq!(
+            codec.next_command().unwrap(),
+            KenwoodCommand::PowerControl(None)
+        );
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(cmd, KenwoodCommand::PowerControl(Some(50)));
+        assert_eq!(
+            cmd.to_radio_response(),
+            RadioResponse::TxPower { watts: 50 }
+        );
+        assert_eq!(
+            cmd.to_radio_request(),
+            RadioRequest::SetTxPower { watts: 50 }
+        );
+    }
+
+    #[test]
+    fn test_encode_power_control() {
+        assert_eq!(KenwoodCommand::PowerControl(None).encode(), b"PC;");
+        assert_eq!(KenwoodCommand::PowerControl(Some(5)).encode(), b"PC005;");
+
+        let cmd =
+            KenwoodCommand::from_radio_request(&RadioRequest::SetTxPower { watts: 100 }).unwrap();
+        assert_eq!(cmd.encode(), b"PC100;");
+    }
+
+    #[test]
+    fn test_receive_and_transmit_vfo() {
+        let mut codec = KenwoodCodec::new();
+        codec.push_bytes(b"FR2;FT0;");
+
+        // FR selects the receive VFO and takes the transmit VFO with it
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_response(),
+            RadioResponse::VfoState {
+                state: VfoState::select(Vfo::Memory)
+            }
+        );
+        // FT names the transmit VFO only; whether that is split depends on FR
+        let state = codec.next_command().unwrap().to_radio_response();
+        assert_eq!(
+            state,
+            RadioResponse::VfoState {
+                state: VfoState::transmit_on(Vfo::A)
+            }
+        );
+        assert_eq!(state.vfo(), Some(Vfo::A));
+
+        // Receive on A, transmit on B goes out as FR then FT
+        let req = RadioRequest::SetVfoState {
+            state: VfoState {
+                rx_vfo: Some(Vfo::A),
+                tx_vfo: Some(Vfo::B),
+                ..VfoState::default()
+            },
+        };
+        let data = encode_request_sequence::<KenwoodCommand>(&req).unwrap();
+        assert_eq!(data, b"FR0;FT1;");
+
+        // The older form still encodes
+        let cmd = KenwoodCommand::from_radio_request(&RadioRequest::SetVfo { vfo: Vfo::Split });
+        assert_eq!(cmd.unwrap().encode(), b"FT1;");
+    }
+
+    #[test]
+    fn test_rit_commands() {
+        let mut codec = KenwoodCodec::new();
+        codec.push_bytes(b"RT1;RC;RD00050;RT;");
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_request(),
+            RadioRequest::SetRit { on: true }
+        );
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_request(),
+            RadioRequest::ClearRit
+        );
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_request(),
+            RadioRequest::AdjustRit { hz: -50 }
+        );
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_request(),
+            RadioRequest::GetRit
+        );
+
+        let cmd = KenwoodCommand::from_radio_request(&RadioRequest::AdjustRit { hz: 20 });
+        assert_eq!(cmd.unwrap().encode(), b"RU00020;");
+        assert!(KenwoodCommand::from_radio_request(&RadioRequest::AdjustRit { hz: 0 }).is_none());
+        assert!(KenwoodCodec::parse_command("RT2").is_err());
+    }
 }
@@ -21,10 +21,13 @@
 //! - `FA00014250000;` TO radio = Request (set frequency)
 //! - `FA;` FROM amplifier = Request (query frequency)
 //!
+//! [`RadioSideCodec`] and [`ControllerSideCodec`] each parse only one of these
+//! directions, so a stream can't be read the wrong way round.
+//!
 //! # Example
 //!
 //! ```rust
-//! use cat_protocol::{RadioResponse, OperatingMode, ProtocolCodec, ToRadioResponse};
+//! use cat_protocol::{Frequency, RadioResponse, OperatingMode, ProtocolCodec, ToRadioResponse};
 //! use cat_protocol::kenwood::{KenwoodCodec, KenwoodCommand};
 //!
 //! // Parse a Kenwood frequency response from a radio
@@ -33,25 +36,41 @@
 //!
 //! if let Some(cmd) = codec.next_command() {
 //!     let response = cmd.to_radio_response();
-//!     assert!(matches!(response, RadioResponse::Frequency { hz: 14_250_000 }));
+//!     assert_eq!(response, RadioResponse::Frequency { hz: Frequency::from_khz(14_250) });
 //! }
 //! ```
 
 pub mod command;
+#[doc = include_str!(concat!(env!("OUT_DIR"), "/protocol_matrix.md"))]
+pub mod conformance;
+pub mod direction;
 pub mod display;
 pub mod elecraft;
 pub mod error;
 pub mod flex;
+pub mod frequency;
+pub mod hooks;
 pub mod icom;
 pub mod kenwood;
+pub mod mode_map;
 pub mod models;
 pub mod yaesu;
 pub mod yaesu_ascii;
+pub mod yaesu_menu;
 
-pub use command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+pub use command::{
+    BreakIn, MeterKind, OperatingMode, RadioRequest, RadioResponse, Receiver, Vfo, VfoState,
+};
+pub use direction::{ControllerSideCodec, RadioSideCodec};
 pub use error::{ParseError, ProtocolError};
+pub use frequency::Frequency;
+pub use hooks::{CommandHooks, CustomEvent, HookOutput, HookedCodec};
+pub use mode_map::{ModeCode, ModeMap, ModeTable};
 pub use models::{ProtocolId, RadioCapabilities, RadioDatabase, RadioModel};
 
+/// This crate's version, for reports
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Identifies which CAT protocol variant a radio uses
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -103,6 +122,9 @@ pub trait ProtocolCodec {
 
     /// Clear the internal buffer
     fn clear(&mut self);
+
+    /// Bytes buffered towards a frame that hasn't completed yet
+    fn buffered(&self) -> &[u8];
 }
 
 /// Parse protocol command as a response (radio → mux)
@@ -135,12 +157,49 @@ pub trait EncodeCommand {
     fn encode(&self) -> Vec<u8>;
 }
 
+/// Encode a request to protocol bytes, expanding compound requests
+///
+/// Compound requests such as [`RadioRequest::SetFrequencyMode`] have no single
+/// frame in most protocols. They are encoded as back-to-back frames in one
+/// buffer so the radio receives them in a single write, with no other traffic
+/// in between. Returns `None` if any component cannot be encoded.
+pub fn encode_request_sequence<C>(req: &RadioRequest) -> Option<Vec<u8>>
+where
+    C: FromRadioRequest + EncodeCommand,
+{
+    let mut out = Vec::new();
+    for part in req.components() {
+        out.extend(C::from_radio_request(&part)?.encode());
+    }
+    Some(out)
+}
+
+/// Encode a request for a specific radio model's mode codes
+///
+/// Like [`encode_request_sequence`], but modes are encoded with `mode_map`
+/// (see [`RadioModel::mode_map`]) instead of the protocol's default table.
+pub fn encode_model_request_sequence<C>(req: &RadioRequest, mode_map: &ModeMap) -> Option<Vec<u8>>
+where
+    C: FromRadioRequest + EncodeCommand + ModeCode,
+{
+    let mut out = Vec::new();
+    for part in req.components() {
+        let mut cmd = C::from_radio_request(&part)?;
+        if let Some(mode) = part.mode() {
+            cmd.set_mode_code(mode_map.code(mode));
+        }
+        out.extend(cmd.encode());
+    }
+    Some(out)
+}
+
 /// Object-safe trait for codecs that parse raw bytes into [`RadioResponse`]s
 ///
 /// Unlike [`ProtocolCodec`], this trait returns the normalized `RadioResponse`
 /// directly, making it object-safe and usable as `Box<dyn RadioCodec>`.
 ///
-/// This is used for data FROM radios (radio → mux direction).
+/// It parses both directions; hold a [`RadioSideCodec`] or
+/// [`ControllerSideCodec`] to fix which one a stream is read as.
 pub trait RadioCodec: Send + Sync {
     /// Push raw bytes into the codec's buffer
     fn push_bytes(&mut self, data: &[u8]);
@@ -160,12 +219,59 @@ pub trait RadioCodec: Send + Sync {
 
     /// Clear the internal buffer
     fn clear(&mut self);
+
+    /// Bytes buffered towards a frame that hasn't completed yet
+    fn buffered(&self) -> &[u8];
+
+    /// Events reported by user command handlers since the last call
+    ///
+    /// Always empty unless the codec is a [`HookedCodec`].
+    fn take_custom_events(&mut self) -> Vec<CustomEvent> {
+        Vec::new()
+    }
 }
 
 /// Implements [`RadioCodec`] for a type that already implements [`ProtocolCodec`]
 /// with a command type implementing [`ToRadioResponse`] and [`ToRadioRequest`].
+///
+/// With `, mode_map` the codec's `mode_map` field ([`ModeMap`]) decodes the
+/// mode codes of responses, for which the command type must implement
+/// [`ModeCode`].
 #[macro_export]
 macro_rules! impl_radio_codec {
+    ($codec:ty, mode_map) => {
+        impl $crate::RadioCodec for $codec {
+            fn push_bytes(&mut self, data: &[u8]) {
+                $crate::ProtocolCodec::push_bytes(self, data);
+            }
+
+            fn next_response(&mut self) -> Option<$crate::RadioResponse> {
+                $crate::RadioCodec::next_response_with_bytes(self).map(|(resp, _)| resp)
+            }
+
+            fn next_response_with_bytes(&mut self) -> Option<($crate::RadioResponse, Vec<u8>)> {
+                $crate::ProtocolCodec::next_command_with_bytes(self)
+                    .map(|(cmd, bytes)| (self.mode_map.response(&cmd), bytes))
+            }
+
+            fn next_request(&mut self) -> Option<$crate::RadioRequest> {
+                $crate::ProtocolCodec::next_command(self).map(|cmd| cmd.to_radio_request())
+            }
+
+            fn next_request_with_bytes(&mut self) -> Option<($crate::RadioRequest, Vec<u8>)> {
+                $crate::ProtocolCodec::next_command_with_bytes(self)
+                    .map(|(cmd, bytes)| (cmd.to_radio_request(), bytes))
+            }
+
+            fn clear(&mut self) {
+                $crate::ProtocolCodec::clear(self);
+            }
+
+            fn buffered(&self) -> &[u8] {
+                $crate::ProtocolCodec::buffered(self)
+            }
+        }
+    };
     ($codec:ty) => {
         impl $crate::RadioCodec for $codec {
             fn push_bytes(&mut self, data: &[u8]) {
@@ -193,6 +299,10 @@ macro_rules! impl_radio_codec {
             fn clear(&mut self) {
                 $crate::ProtocolCodec::clear(self);
             }
+
+            fn buffered(&self) -> &[u8] {
+                $crate::ProtocolCodec::buffered(self)
+            }
         }
     };
 }
@@ -200,11 +310,41 @@ macro_rules! impl_radio_codec {
 /// Create a codec for the given protocol
 pub fn create_radio_codec(protocol: Protocol) -> Box<dyn RadioCodec> {
     match protocol {
-        Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio => {
-            Box::new(kenwood::KenwoodCodec::new())
+        Protocol::Kenwood | Protocol::FlexRadio => Box::new(kenwood::KenwoodCodec::new()),
+        Protocol::Elecraft => {
+            Box::new(kenwood::KenwoodCodec::new().with_mode_map(ModeMap::ELECRAFT))
         }
         Protocol::IcomCIV => Box::new(icom::CivCodec::new()),
         Protocol::Yaesu => Box::new(yaesu::YaesuCodec::new()),
         Protocol::YaesuAscii => Box::new(yaesu_ascii::YaesuAsciiCodec::new()),
     }
 }
+
+/// Create a codec for the given protocol, adjusted for a known radio model
+///
+/// Falls back to [`create_radio_codec`] when the model needs nothing special.
+pub fn create_model_codec(protocol: Protocol, model: Option<&RadioModel>) -> Box<dyn RadioCodec> {
+    let Some(model) = model else {
+        return create_radio_codec(protocol);
+    };
+    let mode_map = model.mode_map();
+    match protocol {
+        Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio => {
+            let codec = kenwood::KenwoodCodec::new().with_mode_map(mode_map);
+            if protocol == Protocol::Kenwood && model.capabilities.has_dual_tx {
+                Box::new(codec.with_dual_tx())
+            } else {
+                Box::new(codec)
+            }
+        }
+        Protocol::IcomCIV => Box::new(icom::CivCodec::new().with_mode_map(mode_map)),
+        Protocol::Yaesu => Box::new(yaesu::YaesuCodec::new().with_mode_map(mode_map)),
+        Protocol::YaesuAscii => {
+            let codec = match yaesu_menu::menu_table_for_model(&model.model) {
+                Some(table) => yaesu_ascii::YaesuAsciiCodec::with_menu_digits(table.digits),
+                None => yaesu_ascii::YaesuAsciiCodec::new(),
+            };
+            Box::new(codec.with_mode_map(mode_map))
+        }
+    }
+}
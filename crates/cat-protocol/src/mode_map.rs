@@ -0,0 +1,410 @@
+//! Mode code tables
+//!
+//! Every protocol carries the operating mode as a small code (`MD2;`, CI-V
+//! `0x01`, ...), and which code means which mode varies between protocols
+//! and, within a protocol, between radio models. A [`ModeMap`] holds the
+//! protocol's table plus any per-model overrides from the model database
+//! (see [`crate::RadioModel::mode_map`]). Codecs use it both to parse codes
+//! from the radio and to encode modes sent to it.
+
+use crate::{OperatingMode, Protocol, RadioResponse};
+
+/// Mode code mappings in both directions
+///
+/// Lookups take the first matching entry, so a code can decode to one mode
+/// while several modes encode to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeTable {
+    /// Protocol code to mode (parsing)
+    pub decode: &'static [(u8, OperatingMode)],
+    /// Mode to protocol code (encoding)
+    pub encode: &'static [(OperatingMode, u8)],
+}
+
+impl ModeTable {
+    /// A table with no entries
+    pub const EMPTY: ModeTable = ModeTable {
+        decode: &[],
+        encode: &[],
+    };
+
+    fn decode(&self, code: u8) -> Option<OperatingMode> {
+        self.decode
+            .iter()
+            .find(|(c, _)| *c == code)
+            .map(|(_, mode)| *mode)
+    }
+
+    fn encode(&self, mode: OperatingMode) -> Option<u8> {
+        self.encode
+            .iter()
+            .find(|(m, _)| *m == mode)
+            .map(|(_, code)| *code)
+    }
+}
+
+/// A protocol's mode table with a radio model's overrides applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ModeMap {
+    base: ModeTable,
+    overrides: ModeTable,
+    /// Used for codes and modes neither table covers
+    fallback: (u8, OperatingMode),
+}
+
+impl ModeMap {
+    /// Kenwood `MD` codes
+    pub const KENWOOD: ModeMap = ModeMap::new(
+        ModeTable {
+            decode: &[
+                (1, OperatingMode::Lsb),
+                (2, OperatingMode::Usb),
+                (3, OperatingMode::Cw),
+                (4, OperatingMode::Fm),
+                (5, OperatingMode::Am),
+                (6, OperatingMode::Rtty),
+                (7, OperatingMode::CwR),
+                (8, OperatingMode::DataL),
+                (9, OperatingMode::RttyR),
+                (10, OperatingMode::DataU),
+            ],
+            encode: &[
+                (OperatingMode::Lsb, 1),
+                (OperatingMode::Usb, 2),
+                (OperatingMode::Cw, 3),
+                (OperatingMode::Fm, 4),
+                (OperatingMode::FmN, 4),
+                (OperatingMode::Am, 5),
+                (OperatingMode::Rtty, 6),
+                (OperatingMode::CwR, 7),
+                (OperatingMode::DataL, 8),
+                (OperatingMode::DigL, 8),
+                (OperatingMode::Dig, 8),
+                (OperatingMode::RttyR, 9),
+                (OperatingMode::DataU, 10),
+                (OperatingMode::DigU, 10),
+                (OperatingMode::Data, 10),
+                (OperatingMode::Pkt, 10),
+            ],
+        },
+        (2, OperatingMode::Usb),
+    );
+
+    /// Elecraft `MD` codes (6 is DATA, 9 is DATA-REV)
+    pub const ELECRAFT: ModeMap = ModeMap::new(
+        ModeTable {
+            decode: &[
+                (1, OperatingMode::Lsb),
+                (2, OperatingMode::Usb),
+                (3, OperatingMode::Cw),
+                (4, OperatingMode::Fm),
+                (5, OperatingMode::Am),
+                (6, OperatingMode::Data),
+                (7, OperatingMode::CwR),
+                (9, OperatingMode::DataU),
+            ],
+            encode: &[
+                (OperatingMode::Lsb, 1),
+                (OperatingMode::Usb, 2),
+                (OperatingMode::Cw, 3),
+                (OperatingMode::Fm, 4),
+                (OperatingMode::FmN, 4),
+                (OperatingMode::Am, 5),
+                (OperatingMode::Data, 6),
+                (OperatingMode::DataL, 6),
+                (OperatingMode::Rtty, 6),
+                (OperatingMode::Dig, 6),
+                (OperatingMode::DigL, 6),
+                (OperatingMode::Pkt, 6),
+                (OperatingMode::CwR, 7),
+                (OperatingMode::DataU, 9),
+                (OperatingMode::RttyR, 9),
+                (OperatingMode::DigU, 9),
+            ],
+        },
+        (2, OperatingMode::Usb),
+    );
+
+    /// Icom CI-V mode bytes (0x01/0x04/0x06 and 0x26 without the data flag)
+    pub const CIV: ModeMap = ModeMap::new(
+        ModeTable {
+            decode: &[
+                (0x00, OperatingMode::Lsb),
+                (0x01, OperatingMode::Usb),
+                (0x02, OperatingMode::Am),
+                (0x03, OperatingMode::Cw),
+                (0x04, OperatingMode::Rtty),
+                (0x05, OperatingMode::Fm),
+                (0x06, OperatingMode::CwR),
+                (0x07, OperatingMode::RttyR),
+                (0x08, OperatingMode::DataL),
+                // Icom calls this DATA-FM sometimes
+                (0x09, OperatingMode::DataU),
+                // Some Icoms use different codes
+                (0x11, OperatingMode::DataL),
+                (0x12, OperatingMode::DataU),
+            ],
+            encode: &[
+                (OperatingMode::Lsb, 0x00),
+                (OperatingMode::Usb, 0x01),
+                (OperatingMode::Am, 0x02),
+                (OperatingMode::Cw, 0x03),
+                (OperatingMode::Rtty, 0x04),
+                (OperatingMode::Fm, 0x05),
+                (OperatingMode::FmN, 0x05),
+                (OperatingMode::CwR, 0x06),
+                (OperatingMode::RttyR, 0x07),
+                (OperatingMode::DataL, 0x08),
+                (OperatingMode::DigL, 0x08),
+                (OperatingMode::Dig, 0x08),
+                (OperatingMode::DataU, 0x09),
+                (OperatingMode::DigU, 0x09),
+                (OperatingMode::Data, 0x09),
+                (OperatingMode::Pkt, 0x09),
+            ],
+        },
+        (0x01, OperatingMode::Usb),
+    );
+
+    /// Yaesu binary CAT mode bytes (FT-817/857/897)
+    pub const YAESU: ModeMap = ModeMap::new(
+        ModeTable {
+            decode: &[
+                (0x00, OperatingMode::Lsb),
+                (0x01, OperatingMode::Usb),
+                (0x02, OperatingMode::Cw),
+                (0x03, OperatingMode::CwR),
+                (0x04, OperatingMode::Am),
+                // Wide FM
+                (0x06, OperatingMode::Fm),
+                (0x08, OperatingMode::Fm),
+                (0x0A, OperatingMode::Dig),
+                (0x0C, OperatingMode::Pkt),
+            ],
+            encode: &[
+                (OperatingMode::Lsb, 0x00),
+                (OperatingMode::Usb, 0x01),
+                (OperatingMode::Cw, 0x02),
+                (OperatingMode::CwR, 0x03),
+                (OperatingMode::Am, 0x04),
+                (OperatingMode::Fm, 0x08),
+                (OperatingMode::FmN, 0x08),
+                (OperatingMode::Dig, 0x0A),
+                (OperatingMode::DigU, 0x0A),
+                (OperatingMode::DigL, 0x0A),
+                (OperatingMode::Data, 0x0A),
+                (OperatingMode::DataU, 0x0A),
+                (OperatingMode::DataL, 0x0A),
+                (OperatingMode::Pkt, 0x0C),
+                (OperatingMode::Rtty, 0x0A),
+                (OperatingMode::RttyR, 0x0A),
+            ],
+        },
+        (0x01, OperatingMode::Usb),
+    );
+
+    /// Yaesu ASCII `MD` codes (`A`-`F` as 10-15)
+    pub const YAESU_ASCII: ModeMap = ModeMap::new(
+        ModeTable {
+            decode: &[
+                (1, OperatingMode::Lsb),
+                (2, OperatingMode::Usb),
+                // CW-U
+                (3, OperatingMode::Cw),
+                (4, OperatingMode::Fm),
+                (5, OperatingMode::Am),
+                // RTTY-LSB
+                (6, OperatingMode::Rtty),
+                // CW-L
+                (7, OperatingMode::CwR),
+                // DATA-LSB
+                (8, OperatingMode::DataL),
+                // RTTY-USB
+                (9, OperatingMode::RttyR),
+                // DATA-FM
+                (10, OperatingMode::Data),
+                (11, OperatingMode::FmN),
+                // DATA-USB
+                (12, OperatingMode::DataU),
+                // AM-N
+                (13, OperatingMode::Am),
+                // C4FM (digital FM)
+                (14, OperatingMode::Fm),
+                // DATA-FM-N
+                (15, OperatingMode::Data),
+            ],
+            encode: &[
+                (OperatingMode::Lsb, 1),
+                (OperatingMode::Usb, 2),
+                (OperatingMode::Cw, 3),
+                (OperatingMode::Fm, 4),
+                (OperatingMode::Am, 5),
+                (OperatingMode::Rtty, 6),
+                (OperatingMode::CwR, 7),
+                (OperatingMode::DataL, 8),
+                (OperatingMode::DigL, 8),
+                (OperatingMode::RttyR, 9),
+                (OperatingMode::Data, 10),
+                (OperatingMode::Dig, 10),
+                (OperatingMode::Pkt, 10),
+                (OperatingMode::FmN, 11),
+                (OperatingMode::DataU, 12),
+                (OperatingMode::DigU, 12),
+            ],
+        },
+        (2, OperatingMode::Usb),
+    );
+
+    /// A map from a protocol table and the code/mode used when nothing matches
+    pub const fn new(base: ModeTable, fallback: (u8, OperatingMode)) -> Self {
+        Self {
+            base,
+            overrides: ModeTable::EMPTY,
+            fallback,
+        }
+    }
+
+    /// The protocol's own table, with no model overrides
+    pub fn for_protocol(protocol: Protocol) -> Self {
+        match protocol {
+            Protocol::Kenwood | Protocol::FlexRadio => Self::KENWOOD,
+            Protocol::Elecraft => Self::ELECRAFT,
+            Protocol::IcomCIV => Self::CIV,
+            Protocol::Yaesu => Self::YAESU,
+            Protocol::YaesuAscii => Self::YAESU_ASCII,
+        }
+    }
+
+    /// This map with a model's overrides consulted first
+    pub const fn with_overrides(mut self, overrides: ModeTable) -> Self {
+        self.overrides = overrides;
+        self
+    }
+
+    /// Mode for a code received from the radio
+    pub fn mode(&self, code: u8) -> OperatingMode {
+        self.overrides
+            .decode(code)
+            .or_else(|| self.base.decode(code))
+            .unwrap_or(self.fallback.1)
+    }
+
+    /// Code to send the radio for a mode
+    pub fn code(&self, mode: OperatingMode) -> u8 {
+        self.overrides
+            .encode(mode)
+            .or_else(|| self.base.encode(mode))
+            .unwrap_or(self.fallback.0)
+    }
+
+    /// Whether any model overrides apply
+    pub fn has_overrides(&self) -> bool {
+        self.overrides != ModeTable::EMPTY
+    }
+
+    /// Convert a parsed command to a response, decoding its mode with this map
+    pub fn response<C>(&self, cmd: &C) -> RadioResponse
+    where
+        C: crate::ToRadioResponse + ModeCode,
+    {
+        let response = cmd.to_radio_response();
+        match (cmd.mode_code(), response) {
+            (Some(code), RadioResponse::Mode { .. }) => RadioResponse::Mode {
+                mode: self.mode(code),
+            },
+            (
+                Some(code),
+                RadioResponse::Status {
+                    frequency_hz,
+                    mode: Some(_),
+                    ptt,
+                    vfo,
+                },
+            ) => RadioResponse::Status {
+                frequency_hz,
+                mode: Some(self.mode(code)),
+                ptt,
+                vfo,
+            },
+            (_, response) => response,
+        }
+    }
+}
+
+/// Raw mode code carried by a protocol command
+///
+/// Lets a codec apply a model's [`ModeMap`] on top of the protocol's
+/// default conversions.
+pub trait ModeCode {
+    /// The command's mode code, if it carries a plain one
+    fn mode_code(&self) -> Option<u8>;
+
+    /// Replace the command's mode code (no-op for commands without one)
+    fn set_mode_code(&mut self, code: u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_take_precedence_per_direction() {
+        let map = ModeMap::YAESU_ASCII.with_overrides(ModeTable {
+            decode: &[(14, OperatingMode::DigU)],
+            encode: &[(OperatingMode::Data, 12)],
+        });
+        assert!(map.has_overrides());
+        assert!(!ModeMap::YAESU_ASCII.has_overrides());
+
+        assert_eq!(map.mode(14), OperatingMode::DigU);
+        assert_eq!(ModeMap::YAESU_ASCII.mode(14), OperatingMode::Fm);
+        // Decode-only override leaves encoding alone
+        assert_eq!(map.code(OperatingMode::DigU), 12);
+
+        assert_eq!(map.code(OperatingMode::Data), 12);
+        assert_eq!(map.mode(10), OperatingMode::Data);
+
+        // Unknown codes fall back to USB
+        assert_eq!(map.mode(0), OperatingMode::Usb);
+        let bare = ModeMap::new(ModeTable::EMPTY, (2, OperatingMode::Usb));
+        assert_eq!(bare.code(OperatingMode::Pkt), 2);
+    }
+
+    #[test]
+    fn test_every_mode_encodes_to_a_listed_code() {
+        let modes = [
+            OperatingMode::Lsb,
+            OperatingMode::Usb,
+            OperatingMode::Cw,
+            OperatingMode::CwR,
+            OperatingMode::Am,
+            OperatingMode::Fm,
+            OperatingMode::FmN,
+            OperatingMode::Dig,
+            OperatingMode::DigU,
+            OperatingMode::DigL,
+            OperatingMode::Pkt,
+            OperatingMode::Data,
+            OperatingMode::DataU,
+            OperatingMode::DataL,
+            OperatingMode::Rtty,
+            OperatingMode::RttyR,
+        ];
+        for map in [
+            ModeMap::KENWOOD,
+            ModeMap::ELECRAFT,
+            ModeMap::CIV,
+            ModeMap::YAESU,
+            ModeMap::YAESU_ASCII,
+        ] {
+            for mode in modes {
+                assert!(
+                    map.base.encode(mode).is_some(),
+                    "{:?} has no code in {:?}",
+                    mode,
+                    map.base
+                );
+            }
+        }
+    }
+}
@@ -3,25 +3,28 @@
 //! This module contains information about specific radio models,
 //! their capabilities, and protocol-specific details.
 
-use crate::{OperatingMode, Protocol};
+use crate::mode_map::{ModeMap, ModeTable};
+use crate::{Frequency, OperatingMode, Protocol};
 
 /// Capabilities of a specific radio model (internal static version for database)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 struct RadioCapabilitiesStatic {
     /// Supported operating modes (as a slice)
     pub modes: &'static [OperatingMode],
-    /// Minimum frequency in Hz
-    pub min_frequency_hz: u64,
-    /// Maximum frequency in Hz
-    pub max_frequency_hz: u64,
-    /// Frequency resolution in Hz
-    pub frequency_step_hz: u64,
+    /// Minimum frequency
+    pub min_frequency_hz: Frequency,
+    /// Maximum frequency
+    pub max_frequency_hz: Frequency,
+    /// Frequency resolution
+    pub frequency_step_hz: Frequency,
     /// Supports split operation
     pub has_split: bool,
     /// Number of VFOs
     pub vfo_count: u8,
     /// Has built-in antenna tuner
     pub has_tuner: bool,
+    /// Either receiver can transmit, selected with `TX0;`/`TX1;` (TS-990)
+    pub has_dual_tx: bool,
     /// Maximum TX power in watts
     pub max_power_watts: Option<u16>,
 }
@@ -32,18 +35,21 @@ struct RadioCapabilitiesStatic {
 pub struct RadioCapabilities {
     /// Supported operating modes
     pub modes: Vec<OperatingMode>,
-    /// Minimum frequency in Hz
-    pub min_frequency_hz: u64,
-    /// Maximum frequency in Hz
-    pub max_frequency_hz: u64,
-    /// Frequency resolution in Hz
-    pub frequency_step_hz: u64,
+    /// Minimum frequency
+    pub min_frequency_hz: Frequency,
+    /// Maximum frequency
+    pub max_frequency_hz: Frequency,
+    /// Frequency resolution
+    pub frequency_step_hz: Frequency,
     /// Supports split operation
     pub has_split: bool,
     /// Number of VFOs
     pub vfo_count: u8,
     /// Has built-in antenna tuner
     pub has_tuner: bool,
+    /// Either receiver can transmit, selected with `TX0;`/`TX1;` (TS-990)
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub has_dual_tx: bool,
     /// Maximum TX power in watts
     pub max_power_watts: Option<u16>,
 }
@@ -58,6 +64,7 @@ impl From<RadioCapabilitiesStatic> for RadioCapabilities {
             has_split: s.has_split,
             vfo_count: s.vfo_count,
             has_tuner: s.has_tuner,
+            has_dual_tx: s.has_dual_tx,
             max_power_watts: s.max_power_watts,
         }
     }
@@ -73,12 +80,13 @@ impl Default for RadioCapabilities {
                 OperatingMode::Am,
                 OperatingMode::Fm,
             ],
-            min_frequency_hz: 100_000,
-            max_frequency_hz: 60_000_000,
-            frequency_step_hz: 10,
+            min_frequency_hz: Frequency::from_hz(100_000),
+            max_frequency_hz: Frequency::from_hz(60_000_000),
+            frequency_step_hz: Frequency::from_hz(10),
             has_split: true,
             vfo_count: 2,
             has_tuner: false,
+            has_dual_tx: false,
             max_power_watts: Some(100),
         }
     }
@@ -127,6 +135,19 @@ impl From<&RadioModelStatic> for RadioModel {
     }
 }
 
+impl RadioModel {
+    /// Mode code table for this model: the protocol's, with any overrides
+    pub fn mode_map(&self) -> ModeMap {
+        let map = ModeMap::for_protocol(self.protocol);
+        MODE_OVERRIDES
+            .iter()
+            .find(|(protocol, models, _)| {
+                *protocol == self.protocol && models.contains(&self.model.as_str())
+            })
+            .map_or(map, |(_, _, overrides)| map.with_overrides(*overrides))
+    }
+}
+
 /// Protocol-specific radio identifier (internal static version)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ProtocolIdStatic {
@@ -203,6 +224,13 @@ impl RadioDatabase {
             .map(|(_, model)| model.into())
     }
 
+    /// Look up a radio model by protocol and model name (e.g. "TS-990S")
+    pub fn by_model_name(protocol: Protocol, name: &str) -> Option<RadioModel> {
+        Self::radios_for_protocol(protocol)
+            .into_iter()
+            .find(|m| m.model == name)
+    }
+
     /// Get all known Icom radios
     pub fn icom_radios() -> impl Iterator<Item = RadioModel> {
         ICOM_RADIOS.iter().map(|(_, model)| model.into())
@@ -316,6 +344,29 @@ static MODES_NO_FM: &[OperatingMode] = &[
     OperatingMode::DataL,
 ];
 
+// Mode code mappings that differ from the protocol's table, by model
+static MODE_OVERRIDES: &[(Protocol, &[&str], ModeTable)] = &[
+    (
+        // DATA-FM is FM packet; generic data and digital modes belong on
+        // DATA-USB rather than switching the radio to FM
+        Protocol::YaesuAscii,
+        &["FT-991", "FT-991A"],
+        ModeTable {
+            decode: &[(10, OperatingMode::Pkt)],
+            encode: &[(OperatingMode::Data, 12), (OperatingMode::Dig, 12)],
+        },
+    ),
+    (
+        // `E` is the built-in PSK mode rather than C4FM
+        Protocol::YaesuAscii,
+        &["FTDX101D", "FTDX101MP", "FTDX10", "FT-710"],
+        ModeTable {
+            decode: &[(14, OperatingMode::DigU)],
+            encode: &[],
+        },
+    ),
+];
+
 // Icom CI-V address database
 static ICOM_RADIOS: &[(u8, RadioModelStatic)] = &[
     (
@@ -327,12 +378,13 @@ static ICOM_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::CivAddress(0x94),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 74_800_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(74_800_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -346,12 +398,13 @@ static ICOM_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::CivAddress(0xA4),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(10),
             },
         },
@@ -365,12 +418,13 @@ static ICOM_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::CivAddress(0x98),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 60_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(60_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -384,12 +438,13 @@ static ICOM_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::CivAddress(0x70),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -407,12 +462,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("022"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 60_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(60_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: true,
                 max_power_watts: Some(200),
             },
         },
@@ -426,12 +482,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("023"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 60_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(60_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -445,12 +502,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("019"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 1_300_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(1_300_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -466,12 +524,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("005"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -485,12 +544,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("006"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(150),
             },
         },
@@ -504,12 +564,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("007"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -523,12 +584,13 @@ static KENWOOD_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::KenwoodId("008"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -546,12 +608,13 @@ static ELECRAFT_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::ElecraftId("K3"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 500_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(500_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -565,12 +628,13 @@ static ELECRAFT_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::ElecraftId("K3S"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 500_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(500_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -584,12 +648,13 @@ static ELECRAFT_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::ElecraftId("KX3"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_STANDARD,
-                min_frequency_hz: 500_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(500_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(15),
             },
         },
@@ -603,12 +668,13 @@ static ELECRAFT_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::ElecraftId("KX2"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_NO_FM,
-                min_frequency_hz: 500_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(500_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(12),
             },
         },
@@ -644,12 +710,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("904"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 8, // Up to 8 slice receivers
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -663,12 +730,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("905"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 4, // Up to 4 slice receivers
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -682,12 +750,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("906"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
-                vfo_count: 8,          // Up to 8 slice receivers
-                has_tuner: false,      // Receiver only
+                vfo_count: 8,     // Up to 8 slice receivers
+                has_tuner: false, // Receiver only
+                has_dual_tx: false,
                 max_power_watts: None, // Receiver only
             },
         },
@@ -701,12 +770,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("907"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,     // Up to 2 slice receivers
                 has_tuner: false, // Optional ATU
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -721,12 +791,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("908"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,     // Up to 2 slice receivers
                 has_tuner: false, // Optional ATU
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -740,12 +811,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("909"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 4,     // Up to 4 slice receivers
                 has_tuner: false, // Optional ATU
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -759,12 +831,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("910"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -778,12 +851,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("911"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 4,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -798,12 +872,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("912"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2, // Up to 2 slice receivers
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -817,12 +892,13 @@ static FLEX_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::FlexId("913"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FLEX_SDR,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 77_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(77_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 4, // Up to 4 slice receivers
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -841,12 +917,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x01),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(5),
             },
         },
@@ -860,12 +937,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x02),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -879,12 +957,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x03),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -898,12 +977,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x04),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -918,12 +998,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x05),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_BASIC,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: false,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -937,12 +1018,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x10),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -956,12 +1038,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x11),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(200),
             },
         },
@@ -975,12 +1058,13 @@ static YAESU_RADIOS: &[(u8, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuCode(0x12),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 100_000,
-                max_frequency_hz: 30_000_000,
-                frequency_step_hz: 10,
+                min_frequency_hz: Frequency::from_hz(100_000),
+                max_frequency_hz: Frequency::from_hz(30_000_000),
+                frequency_step_hz: Frequency::from_hz(10),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -999,12 +1083,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0670"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -1018,12 +1103,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0671"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 450_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(450_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -1037,12 +1123,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0681"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(200),
             },
         },
@@ -1056,12 +1143,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0682"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(200),
             },
         },
@@ -1075,12 +1163,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0690"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
@@ -1094,12 +1183,13 @@ static YAESU_ASCII_RADIOS: &[(&str, RadioModelStatic)] = &[
             protocol_id: ProtocolIdStatic::YaesuAsciiId("0700"),
             capabilities: RadioCapabilitiesStatic {
                 modes: MODES_FULL_HF,
-                min_frequency_hz: 30_000,
-                max_frequency_hz: 54_000_000,
-                frequency_step_hz: 1,
+                min_frequency_hz: Frequency::from_hz(30_000),
+                max_frequency_hz: Frequency::from_hz(54_000_000),
+                frequency_step_hz: Frequency::from_hz(1),
                 has_split: true,
                 vfo_count: 2,
                 has_tuner: true,
+                has_dual_tx: false,
                 max_power_watts: Some(100),
             },
         },
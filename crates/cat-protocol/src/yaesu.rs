@@ -19,8 +19,10 @@
 //! - FT-817/857/897: 10 Hz resolution (4 BCD bytes = 8 digits)
 //! - FT-991/FTDX: 1 Hz resolution (extended commands)
 
-use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+use crate::command::{RadioRequest, RadioResponse, Vfo, VfoState};
 use crate::error::ParseError;
+use crate::mode_map::{ModeCode, ModeMap};
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -166,6 +168,8 @@ pub struct YaesuCodec {
     buffer: Vec<u8>,
     /// Expected response length (for handling variable responses)
     expected_response_len: Option<usize>,
+    /// Mode codes for the connected model
+    mode_map: ModeMap,
 }
 
 impl YaesuCodec {
@@ -174,9 +178,16 @@ impl YaesuCodec {
         Self {
             buffer: Vec::with_capacity(16),
             expected_response_len: None,
+            mode_map: ModeMap::YAESU,
         }
     }
 
+    /// Decode mode codes with a model's table (see [`crate::RadioModel::mode_map`])
+    pub fn with_mode_map(mut self, mode_map: ModeMap) -> Self {
+        self.mode_map = mode_map;
+        self
+    }
+
     /// Set expected response length for next read
     pub fn expect_response(&mut self, len: usize) {
         self.expected_response_len = Some(len);
@@ -280,27 +291,39 @@ impl ProtocolCodec for YaesuCodec {
         self.buffer.clear();
         self.expected_response_len = None;
     }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
 }
 
 impl ToRadioResponse for YaesuCommand {
     fn to_radio_response(&self) -> RadioResponse {
         match self {
-            YaesuCommand::SetFrequency { hz } => RadioResponse::Frequency { hz: *hz },
+            YaesuCommand::SetFrequency { hz } => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuCommand::GetFrequencyMode => RadioResponse::Unknown { data: vec![] },
             YaesuCommand::FrequencyModeReport { hz, mode } => RadioResponse::Status {
-                frequency_hz: Some(*hz),
-                mode: Some(yaesu_mode_to_operating_mode(*mode)),
+                frequency_hz: Some(Frequency::from_hz(*hz)),
+                mode: Some(ModeMap::YAESU.mode(*mode)),
                 ptt: None,
                 vfo: None,
             },
             YaesuCommand::SetMode { mode } => RadioResponse::Mode {
-                mode: yaesu_mode_to_operating_mode(*mode),
+                mode: ModeMap::YAESU.mode(*mode),
             },
             YaesuCommand::PttOn => RadioResponse::Ptt { active: true },
             YaesuCommand::PttOff => RadioResponse::Ptt { active: false },
-            YaesuCommand::ToggleVfo => RadioResponse::Vfo { vfo: Vfo::B },
-            YaesuCommand::SplitOn => RadioResponse::Vfo { vfo: Vfo::Split },
-            YaesuCommand::SplitOff => RadioResponse::Vfo { vfo: Vfo::A },
+            YaesuCommand::ToggleVfo => RadioResponse::VfoState {
+                state: VfoState::select(Vfo::B),
+            },
+            YaesuCommand::SplitOn => RadioResponse::VfoState {
+                state: VfoState::with_split(true),
+            },
+            YaesuCommand::SplitOff => RadioResponse::VfoState {
+                state: VfoState::with_split(false),
+            },
             YaesuCommand::ReadRxStatus => RadioResponse::Unknown { data: vec![] },
             YaesuCommand::RxStatusReport { status } => {
                 let ptt = (*status & rx_status::SQUELCH_OPEN) != 0;
@@ -327,20 +350,49 @@ impl ToRadioResponse for YaesuCommand {
     }
 }
 
+impl ModeCode for YaesuCommand {
+    fn mode_code(&self) -> Option<u8> {
+        match self {
+            YaesuCommand::FrequencyModeReport { mode, .. } | YaesuCommand::SetMode { mode } => {
+                Some(*mode)
+            }
+            _ => None,
+        }
+    }
+
+    fn set_mode_code(&mut self, code: u8) {
+        if let YaesuCommand::FrequencyModeReport { mode, .. } | YaesuCommand::SetMode { mode } =
+            self
+        {
+            *mode = code;
+        }
+    }
+}
+
 impl ToRadioRequest for YaesuCommand {
     fn to_radio_request(&self) -> RadioRequest {
         match self {
-            YaesuCommand::SetFrequency { hz } => RadioRequest::SetFrequency { hz: *hz },
+            YaesuCommand::SetFrequency { hz } => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuCommand::GetFrequencyMode => RadioRequest::GetFrequency,
-            YaesuCommand::FrequencyModeReport { hz, .. } => RadioRequest::SetFrequency { hz: *hz },
+            YaesuCommand::FrequencyModeReport { hz, .. } => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuCommand::SetMode { mode } => RadioRequest::SetMode {
-                mode: yaesu_mode_to_operating_mode(*mode),
+                mode: ModeMap::YAESU.mode(*mode),
             },
             YaesuCommand::PttOn => RadioRequest::SetPtt { active: true },
             YaesuCommand::PttOff => RadioRequest::SetPtt { active: false },
-            YaesuCommand::ToggleVfo => RadioRequest::SetVfo { vfo: Vfo::B },
-            YaesuCommand::SplitOn => RadioRequest::SetVfo { vfo: Vfo::Split },
-            YaesuCommand::SplitOff => RadioRequest::SetVfo { vfo: Vfo::A },
+            YaesuCommand::ToggleVfo => RadioRequest::SetVfoState {
+                state: VfoState::select(Vfo::B),
+            },
+            YaesuCommand::SplitOn => RadioRequest::SetVfoState {
+                state: VfoState::with_split(true),
+            },
+            YaesuCommand::SplitOff => RadioRequest::SetVfoState {
+                state: VfoState::with_split(false),
+            },
             YaesuCommand::ReadRxStatus => RadioRequest::GetStatus,
             YaesuCommand::RxStatusReport { .. } => RadioRequest::Unknown { data: vec![] },
             YaesuCommand::ReadTxStatus => RadioRequest::GetPtt,
@@ -358,18 +410,18 @@ impl ToRadioRequest for YaesuCommand {
 impl FromRadioRequest for YaesuCommand {
     fn from_radio_request(req: &RadioRequest) -> Option<Self> {
         match req {
-            RadioRequest::SetFrequency { hz } => Some(YaesuCommand::SetFrequency { hz: *hz }),
+            RadioRequest::SetFrequency { hz } => Some(YaesuCommand::SetFrequency { hz: hz.hz() }),
             RadioRequest::GetFrequency => Some(YaesuCommand::GetFrequencyMode),
             RadioRequest::SetMode { mode } => Some(YaesuCommand::SetMode {
-                mode: operating_mode_to_yaesu(*mode),
+                mode: ModeMap::YAESU.code(*mode),
             }),
             RadioRequest::GetMode => Some(YaesuCommand::GetFrequencyMode),
             RadioRequest::SetPtt { active: true } => Some(YaesuCommand::PttOn),
             RadioRequest::SetPtt { active: false } => Some(YaesuCommand::PttOff),
             RadioRequest::GetPtt => Some(YaesuCommand::ReadTxStatus),
             RadioRequest::GetStatus => Some(YaesuCommand::ReadRxStatus),
-            RadioRequest::SetVfo { vfo: Vfo::Split } => Some(YaesuCommand::SplitOn),
-            RadioRequest::SetVfo { .. } => Some(YaesuCommand::ToggleVfo),
+            RadioRequest::SetVfoState { state } => vfo_state_command(state),
+            RadioRequest::SetVfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioRequest::SetPower { on: true } => Some(YaesuCommand::PowerOn),
             RadioRequest::SetPower { on: false } => Some(YaesuCommand::PowerOff),
             _ => None,
@@ -377,26 +429,45 @@ impl FromRadioRequest for YaesuCommand {
     }
 }
 
+/// A/B toggle for a receive VFO, otherwise split on or off
+///
+/// The binary protocol can only toggle VFOs, so it cannot select a
+/// particular one. Transmitting on VFO B is sent as split on.
+fn vfo_state_command(state: &VfoState) -> Option<YaesuCommand> {
+    match state.rx_vfo {
+        Some(Vfo::Split) => return Some(YaesuCommand::SplitOn),
+        Some(_) => return Some(YaesuCommand::ToggleVfo),
+        None => {}
+    }
+    let on = state.is_split().or(state.tx_vfo.map(|tx| tx == Vfo::B))?;
+    Some(if on {
+        YaesuCommand::SplitOn
+    } else {
+        YaesuCommand::SplitOff
+    })
+}
+
 impl FromRadioResponse for YaesuCommand {
     fn from_radio_response(resp: &RadioResponse) -> Option<Self> {
         match resp {
-            RadioResponse::Frequency { hz } => {
-                Some(YaesuCommand::FrequencyModeReport { hz: *hz, mode: 0 })
-            }
+            RadioResponse::Frequency { hz } => Some(YaesuCommand::FrequencyModeReport {
+                hz: hz.hz(),
+                mode: 0,
+            }),
             RadioResponse::Mode { mode } => Some(YaesuCommand::SetMode {
-                mode: operating_mode_to_yaesu(*mode),
+                mode: ModeMap::YAESU.code(*mode),
             }),
             RadioResponse::Ptt { active: true } => Some(YaesuCommand::PttOn),
             RadioResponse::Ptt { active: false } => Some(YaesuCommand::PttOff),
-            RadioResponse::Vfo { vfo: Vfo::Split } => Some(YaesuCommand::SplitOn),
-            RadioResponse::Vfo { .. } => Some(YaesuCommand::ToggleVfo),
+            RadioResponse::VfoState { state } => vfo_state_command(state),
+            RadioResponse::Vfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioResponse::Status {
                 frequency_hz: Some(hz),
                 mode,
                 ..
             } => Some(YaesuCommand::FrequencyModeReport {
-                hz: *hz,
-                mode: mode.map(operating_mode_to_yaesu).unwrap_or(0),
+                hz: hz.hz(),
+                mode: mode.map(|m| ModeMap::YAESU.code(m)).unwrap_or(0),
             }),
             _ => None,
         }
@@ -469,38 +540,6 @@ fn frequency_to_bcd_be(hz: u64) -> Vec<u8> {
     result
 }
 
-/// Convert Yaesu mode byte to OperatingMode
-fn yaesu_mode_to_operating_mode(mode: u8) -> OperatingMode {
-    match mode {
-        0x00 => OperatingMode::Lsb,
-        0x01 => OperatingMode::Usb,
-        0x02 => OperatingMode::Cw,
-        0x03 => OperatingMode::CwR,
-        0x04 => OperatingMode::Am,
-        0x06 => OperatingMode::Fm, // Wide FM
-        0x08 => OperatingMode::Fm, // FM
-        0x0A => OperatingMode::Dig,
-        0x0C => OperatingMode::Pkt,
-        _ => OperatingMode::Usb,
-    }
-}
-
-/// Convert OperatingMode to Yaesu mode byte
-fn operating_mode_to_yaesu(mode: OperatingMode) -> u8 {
-    match mode {
-        OperatingMode::Lsb => 0x00,
-        OperatingMode::Usb => 0x01,
-        OperatingMode::Cw => 0x02,
-        OperatingMode::CwR => 0x03,
-        OperatingMode::Am => 0x04,
-        OperatingMode::Fm | OperatingMode::FmN => 0x08,
-        OperatingMode::Dig | OperatingMode::DigU | OperatingMode::DigL => 0x0A,
-        OperatingMode::Data | OperatingMode::DataU | OperatingMode::DataL => 0x0A,
-        OperatingMode::Pkt => 0x0C,
-        OperatingMode::Rtty | OperatingMode::RttyR => 0x0A,
-    }
-}
-
 /// Generate a probe command to detect Yaesu radios
 /// Uses the read frequency/mode command
 pub fn probe_command() -> Vec<u8> {
@@ -512,11 +551,12 @@ pub fn probe_response_len() -> usize {
     5 // Frequency (4 bytes) + Mode (1 byte)
 }
 
-crate::impl_radio_codec!(YaesuCodec);
+crate::impl_radio_codec!(YaesuCodec, mode_map);
 
 #[cfg(test)]
 mod tests {
     use super::{bcd_to_frequency_be, frequency_to_bcd_be, YaesuCodec, YaesuCommand};
+    use crate::Frequency;
     use crate::{
         EncodeCommand, FromRadioRequest, ProtocolCodec, RadioRequest, RadioResponse,
         ToRadioResponse,
@@ -563,7 +603,12 @@ mod tests {
     fn test_to_radio_response() {
         let cmd = YaesuCommand::SetFrequency { hz: 7_074_000 };
         let response = cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
@@ -13,8 +13,11 @@
 //! - [FT-991A CAT Manual](https://yaesu.com/Files/4CB893D7-1018-01AF-FA97E9E9AD48B50C/FT-991A_CAT_OM_ENG_1711-D.pdf)
 //! - [FTDX-10 CAT Manual](https://www.yaesu.com/Files/4CB893D7-1018-01AF-FA97E9E9AD48B50C/FTDX10_CAT_OM_ENG_2308-F.pdf)
 
-use crate::command::{OperatingMode, RadioRequest, RadioResponse, Vfo};
+use crate::command::{BreakIn, MeterKind, RadioRequest, RadioResponse, Receiver, Vfo, VfoState};
 use crate::error::ParseError;
+use crate::mode_map::{ModeCode, ModeMap};
+use crate::yaesu_menu::DEFAULT_MENU_DIGITS;
+use crate::Frequency;
 use crate::{
     EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, ToRadioRequest,
     ToRadioResponse,
@@ -26,6 +29,14 @@ const MAX_COMMAND_LEN: usize = 64;
 /// Yaesu ASCII frequency digit count (9 digits = 1 Hz resolution up to 999 MHz)
 const FREQ_DIGITS: usize = 9;
 
+/// CW pitch of `KP00;` and the step per code (300-1050 Hz)
+const KEY_PITCH_BASE_HZ: u16 = 300;
+const KEY_PITCH_STEP_HZ: u16 = 10;
+const KEY_PITCH_MAX_CODE: u16 = 75;
+
+/// Largest clarifier move `RU`/`RD` can send (four digits)
+const CLARIFIER_MAX_STEP_HZ: u32 = 9999;
+
 /// Yaesu ASCII protocol command
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum YaesuAsciiCommand {
@@ -46,7 +57,7 @@ pub enum YaesuAsciiCommand {
     Id(Option<String>),
     /// Information/status query: IF...;
     Info(Option<YaesuAsciiInfo>),
-    /// VFO select: VS0; (0=VFO A, 1=VFO B)
+    /// VFO select: VS0; (0=VFO A / MAIN, 1=VFO B / SUB)
     VfoSelect(Option<u8>),
     /// Split mode: ST0; or ST1;
     Split(Option<bool>),
@@ -58,6 +69,42 @@ pub enum YaesuAsciiCommand {
     SMeter(Option<u16>),
     /// RF power output setting: PC000-100;
     RfPower(Option<u8>),
+    /// Keyer speed in WPM: KS; (query) or KS025;
+    KeyerSpeed(Option<u8>),
+    /// Break-in on/off: BI; (query), BI0; or BI1;
+    BreakIn(Option<bool>),
+    /// Break-in delay in ms: SD; (query) or SD0300;
+    BreakInDelay(Option<u16>),
+    /// Key pitch code: KP; (query) or KP30; (300 Hz + 10 Hz per step)
+    KeyPitch(Option<u8>),
+    /// Internal keyer on/off: KR; (query), KR0; or KR1;
+    Keyer(Option<bool>),
+    /// Receive clarifier (RIT) on/off: RT; (query), RT0; or RT1;
+    Clarifier(Option<bool>),
+    /// Clear the clarifier offset: RC;
+    ClarifierClear,
+    /// Move the clarifier up in Hz: RU0100;
+    ClarifierUp(u16),
+    /// Move the clarifier down in Hz: RD0100;
+    ClarifierDown(u16),
+    /// Manual notch: BP00; (query on/off), BP00001; (on) or BP01150; (1500 Hz)
+    ManualNotch {
+        /// Receiver selector (0=main, 1=sub if available)
+        receiver: u8,
+        /// What the value is: 0 = on/off, 1 = notch frequency in 10 Hz steps
+        function: u8,
+        /// Value (None = query)
+        value: Option<u16>,
+    },
+    /// Extended menu read/write: EX033; (query) or EX0331; (set/answer)
+    Menu {
+        /// Menu item number
+        item: u32,
+        /// Width of the item number as sent (model dependent)
+        digits: usize,
+        /// Raw value digits (None = query)
+        value: Option<String>,
+    },
     /// Unknown/unrecognized command
     Unknown(String),
 }
@@ -90,21 +137,43 @@ pub struct YaesuAsciiInfo {
 /// Streaming Yaesu ASCII protocol codec
 pub struct YaesuAsciiCodec {
     buffer: Vec<u8>,
+    menu_digits: usize,
+    /// Mode codes for the connected model
+    mode_map: ModeMap,
 }
 
 impl YaesuAsciiCodec {
     /// Create a new Yaesu ASCII codec
     pub fn new() -> Self {
+        Self::with_menu_digits(DEFAULT_MENU_DIGITS)
+    }
+
+    /// Create a codec that splits `EX` commands at the given item number width
+    ///
+    /// See [`crate::yaesu_menu`] for the width used by each model.
+    pub fn with_menu_digits(menu_digits: usize) -> Self {
         Self {
             buffer: Vec::with_capacity(64),
+            menu_digits,
+            mode_map: ModeMap::YAESU_ASCII,
         }
     }
 
+    /// Decode mode codes with a model's table (see [`crate::RadioModel::mode_map`])
+    pub fn with_mode_map(mut self, mode_map: ModeMap) -> Self {
+        self.mode_map = mode_map;
+        self
+    }
+
     /// Parse a complete command string (without terminator)
-    fn parse_command(cmd: &str) -> Result<YaesuAsciiCommand, ParseError> {
+    fn parse_command(cmd: &str, menu_digits: usize) -> Result<YaesuAsciiCommand, ParseError> {
         if cmd.len() < 2 {
             return Err(ParseError::InvalidFrame("command too short".into()));
         }
+        // Line noise; the fields below are sliced by byte
+        if !cmd.is_ascii() {
+            return Err(ParseError::InvalidFrame("not ASCII".into()));
+        }
 
         let prefix = &cmd[..2];
         let params = &cmd[2..];
@@ -240,6 +309,98 @@ impl YaesuAsciiCodec {
                     Ok(YaesuAsciiCommand::RfPower(Some(power)))
                 }
             }
+            "KS" => {
+                if params.is_empty() {
+                    Ok(YaesuAsciiCommand::KeyerSpeed(None))
+                } else {
+                    let wpm = params
+                        .parse::<u8>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid keyer speed".into()))?;
+                    Ok(YaesuAsciiCommand::KeyerSpeed(Some(wpm)))
+                }
+            }
+            "BI" => {
+                if params.is_empty() {
+                    Ok(YaesuAsciiCommand::BreakIn(None))
+                } else {
+                    Ok(YaesuAsciiCommand::BreakIn(Some(params != "0")))
+                }
+            }
+            "SD" => {
+                if params.is_empty() {
+                    Ok(YaesuAsciiCommand::BreakInDelay(None))
+                } else {
+                    let ms = params
+                        .parse::<u16>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid break-in delay".into()))?;
+                    Ok(YaesuAsciiCommand::BreakInDelay(Some(ms)))
+                }
+            }
+            "KP" => {
+                if params.is_empty() {
+                    Ok(YaesuAsciiCommand::KeyPitch(None))
+                } else {
+                    let code = params
+                        .parse::<u8>()
+                        .map_err(|_| ParseError::InvalidFrame("invalid key pitch".into()))?;
+                    Ok(YaesuAsciiCommand::KeyPitch(Some(code)))
+                }
+            }
+            "KR" => match params {
+                "" => Ok(YaesuAsciiCommand::Keyer(None)),
+                p => Ok(YaesuAsciiCommand::Keyer(Some(parse_switch(p, "keyer")?))),
+            },
+            "RT" => match params {
+                "" => Ok(YaesuAsciiCommand::Clarifier(None)),
+                p => Ok(YaesuAsciiCommand::Clarifier(Some(parse_switch(
+                    p,
+                    "clarifier",
+                )?))),
+            },
+            "RC" => Ok(YaesuAsciiCommand::ClarifierClear),
+            "RU" | "RD" => {
+                let hz = params
+                    .parse::<u16>()
+                    .map_err(|_| ParseError::InvalidFrame("invalid clarifier step".into()))?;
+                Ok(if prefix == "RU" {
+                    YaesuAsciiCommand::ClarifierUp(hz)
+                } else {
+                    YaesuAsciiCommand::ClarifierDown(hz)
+                })
+            }
+            "BP" => {
+                let digit = |i: usize| {
+                    params
+                        .get(i..i + 1)
+                        .and_then(|d| d.parse::<u8>().ok())
+                        .ok_or_else(|| ParseError::InvalidFrame("invalid manual notch".into()))
+                };
+                let receiver = digit(0)?;
+                let function = digit(1)?;
+                let value = match &params[2..] {
+                    "" => None,
+                    v => Some(v.parse::<u16>().map_err(|_| {
+                        ParseError::InvalidFrame("invalid manual notch value".into())
+                    })?),
+                };
+                Ok(YaesuAsciiCommand::ManualNotch {
+                    receiver,
+                    function,
+                    value,
+                })
+            }
+            "EX" => {
+                let item = params
+                    .get(..menu_digits)
+                    .and_then(|n| n.parse::<u32>().ok())
+                    .ok_or_else(|| ParseError::InvalidFrame("invalid menu item".into()))?;
+                let value = &params[menu_digits..];
+                Ok(YaesuAsciiCommand::Menu {
+                    item,
+                    digits: menu_digits,
+                    value: (!value.is_empty()).then(|| value.to_string()),
+                })
+            }
             _ => Ok(YaesuAsciiCommand::Unknown(cmd.to_string())),
         }
     }
@@ -364,7 +525,7 @@ impl ProtocolCodec for YaesuAsciiCodec {
         // Parse as ASCII (strip terminator)
         let cmd_str = String::from_utf8_lossy(&cmd_bytes[..cmd_bytes.len() - 1]);
 
-        let cmd = match Self::parse_command(&cmd_str) {
+        let cmd = match Self::parse_command(&cmd_str, self.menu_digits) {
             Ok(cmd) => cmd,
             Err(e) => {
                 tracing::warn!("Failed to parse Yaesu ASCII command: {}", e);
@@ -378,20 +539,28 @@ impl ProtocolCodec for YaesuAsciiCodec {
     fn clear(&mut self) {
         self.buffer.clear();
     }
+
+    fn buffered(&self) -> &[u8] {
+        &self.buffer
+    }
 }
 
 impl ToRadioResponse for YaesuAsciiCommand {
     fn to_radio_response(&self) -> RadioResponse {
         match self {
-            YaesuAsciiCommand::FrequencyA(Some(hz)) => RadioResponse::Frequency { hz: *hz },
+            YaesuAsciiCommand::FrequencyA(Some(hz)) => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuAsciiCommand::FrequencyA(None) => RadioResponse::Unknown { data: vec![] },
-            YaesuAsciiCommand::FrequencyB(Some(hz)) => RadioResponse::Frequency { hz: *hz },
+            YaesuAsciiCommand::FrequencyB(Some(hz)) => RadioResponse::Frequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuAsciiCommand::FrequencyB(None) => RadioResponse::Unknown { data: vec![] },
             YaesuAsciiCommand::Mode {
                 mode: Some(m),
                 receiver: _,
             } => RadioResponse::Mode {
-                mode: yaesu_mode_to_operating_mode(*m),
+                mode: ModeMap::YAESU_ASCII.mode(*m),
             },
             YaesuAsciiCommand::Mode {
                 mode: None,
@@ -402,18 +571,18 @@ impl ToRadioResponse for YaesuAsciiCommand {
             YaesuAsciiCommand::Id(Some(id)) => RadioResponse::Id { id: id.clone() },
             YaesuAsciiCommand::Id(None) => RadioResponse::Unknown { data: vec![] },
             YaesuAsciiCommand::Info(Some(info)) => RadioResponse::Status {
-                frequency_hz: Some(info.frequency_hz),
-                mode: Some(yaesu_mode_to_operating_mode(info.mode)),
+                frequency_hz: Some(Frequency::from_hz(info.frequency_hz)),
+                mode: Some(ModeMap::YAESU_ASCII.mode(info.mode)),
                 ptt: Some(info.tx),
                 vfo: Some(if info.vfo_memory == 0 { Vfo::A } else { Vfo::B }),
             },
             YaesuAsciiCommand::Info(None) => RadioResponse::Unknown { data: vec![] },
-            YaesuAsciiCommand::VfoSelect(Some(v)) => RadioResponse::Vfo {
-                vfo: if *v == 0 { Vfo::A } else { Vfo::B },
+            YaesuAsciiCommand::VfoSelect(Some(v)) => RadioResponse::VfoState {
+                state: vs_state(*v),
             },
             YaesuAsciiCommand::VfoSelect(None) => RadioResponse::Unknown { data: vec![] },
-            YaesuAsciiCommand::Split(Some(s)) => RadioResponse::Vfo {
-                vfo: if *s { Vfo::Split } else { Vfo::A },
+            YaesuAsciiCommand::Split(Some(s)) => RadioResponse::VfoState {
+                state: VfoState::with_split(*s),
             },
             YaesuAsciiCommand::Split(None) => RadioResponse::Unknown { data: vec![] },
             YaesuAsciiCommand::Power(_) => RadioResponse::Unknown { data: vec![] },
@@ -421,9 +590,33 @@ impl ToRadioResponse for YaesuAsciiCommand {
                 RadioResponse::AutoInfo { enabled: *enabled }
             }
             YaesuAsciiCommand::AutoInfo(None) => RadioResponse::Unknown { data: vec![] },
-            YaesuAsciiCommand::SMeter(_) | YaesuAsciiCommand::RfPower(_) => {
-                RadioResponse::Unknown { data: vec![] }
-            }
+            YaesuAsciiCommand::RfPower(Some(p)) => RadioResponse::TxPower { watts: *p as u16 },
+            YaesuAsciiCommand::SMeter(Some(v)) => RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level: (*v).min(255) as u8,
+            },
+            YaesuAsciiCommand::KeyerSpeed(Some(wpm)) => RadioResponse::KeyerSpeed { wpm: *wpm },
+            YaesuAsciiCommand::BreakIn(Some(on)) => RadioResponse::BreakIn {
+                mode: break_in_mode(*on),
+            },
+            YaesuAsciiCommand::BreakInDelay(Some(ms)) => RadioResponse::BreakInDelay { ms: *ms },
+            YaesuAsciiCommand::KeyPitch(Some(code)) => RadioResponse::SidetonePitch {
+                hz: key_pitch_hz(*code),
+            },
+            YaesuAsciiCommand::Clarifier(Some(on)) => RadioResponse::Rit { on: *on },
+            YaesuAsciiCommand::SMeter(None)
+            | YaesuAsciiCommand::RfPower(None)
+            | YaesuAsciiCommand::KeyerSpeed(None)
+            | YaesuAsciiCommand::BreakIn(None)
+            | YaesuAsciiCommand::BreakInDelay(None)
+            | YaesuAsciiCommand::KeyPitch(None)
+            | YaesuAsciiCommand::Keyer(_)
+            | YaesuAsciiCommand::Clarifier(None)
+            | YaesuAsciiCommand::ClarifierClear
+            | YaesuAsciiCommand::ClarifierUp(_)
+            | YaesuAsciiCommand::ClarifierDown(_)
+            | YaesuAsciiCommand::ManualNotch { .. }
+            | YaesuAsciiCommand::Menu { .. } => RadioResponse::Unknown { data: vec![] },
             YaesuAsciiCommand::Unknown(s) => RadioResponse::Unknown {
                 data: s.as_bytes().to_vec(),
             },
@@ -431,18 +624,40 @@ impl ToRadioResponse for YaesuAsciiCommand {
     }
 }
 
+impl ModeCode for YaesuAsciiCommand {
+    fn mode_code(&self) -> Option<u8> {
+        match self {
+            YaesuAsciiCommand::Mode { mode: Some(m), .. } => Some(*m),
+            YaesuAsciiCommand::Info(Some(info)) => Some(info.mode),
+            _ => None,
+        }
+    }
+
+    fn set_mode_code(&mut self, code: u8) {
+        match self {
+            YaesuAsciiCommand::Mode { mode: Some(m), .. } => *m = code,
+            YaesuAsciiCommand::Info(Some(info)) => info.mode = code,
+            _ => {}
+        }
+    }
+}
+
 impl ToRadioRequest for YaesuAsciiCommand {
     fn to_radio_request(&self) -> RadioRequest {
         match self {
-            YaesuAsciiCommand::FrequencyA(Some(hz)) => RadioRequest::SetFrequency { hz: *hz },
+            YaesuAsciiCommand::FrequencyA(Some(hz)) => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuAsciiCommand::FrequencyA(None) => RadioRequest::GetFrequency,
-            YaesuAsciiCommand::FrequencyB(Some(hz)) => RadioRequest::SetFrequency { hz: *hz },
+            YaesuAsciiCommand::FrequencyB(Some(hz)) => RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(*hz),
+            },
             YaesuAsciiCommand::FrequencyB(None) => RadioRequest::GetFrequency,
             YaesuAsciiCommand::Mode {
                 mode: Some(m),
                 receiver: _,
             } => RadioRequest::SetMode {
-                mode: yaesu_mode_to_operating_mode(*m),
+                mode: ModeMap::YAESU_ASCII.mode(*m),
             },
             YaesuAsciiCommand::Mode {
                 mode: None,
@@ -454,12 +669,12 @@ impl ToRadioRequest for YaesuAsciiCommand {
             YaesuAsciiCommand::Id(None) => RadioRequest::GetId,
             YaesuAsciiCommand::Info(Some(_)) => RadioRequest::Unknown { data: vec![] },
             YaesuAsciiCommand::Info(None) => RadioRequest::GetStatus,
-            YaesuAsciiCommand::VfoSelect(Some(v)) => RadioRequest::SetVfo {
-                vfo: if *v == 0 { Vfo::A } else { Vfo::B },
+            YaesuAsciiCommand::VfoSelect(Some(v)) => RadioRequest::SetVfoState {
+                state: vs_state(*v),
             },
             YaesuAsciiCommand::VfoSelect(None) => RadioRequest::GetVfo,
-            YaesuAsciiCommand::Split(Some(s)) => RadioRequest::SetVfo {
-                vfo: if *s { Vfo::Split } else { Vfo::A },
+            YaesuAsciiCommand::Split(Some(s)) => RadioRequest::SetVfoState {
+                state: VfoState::with_split(*s),
             },
             YaesuAsciiCommand::Split(None) => RadioRequest::GetVfo,
             YaesuAsciiCommand::Power(Some(on)) => RadioRequest::SetPower { on: *on },
@@ -468,9 +683,34 @@ impl ToRadioRequest for YaesuAsciiCommand {
                 RadioRequest::SetAutoInfo { enabled: *enabled }
             }
             YaesuAsciiCommand::AutoInfo(None) => RadioRequest::GetAutoInfo,
-            YaesuAsciiCommand::SMeter(_) | YaesuAsciiCommand::RfPower(_) => {
-                RadioRequest::Unknown { data: vec![] }
-            }
+            YaesuAsciiCommand::RfPower(Some(p)) => RadioRequest::SetTxPower { watts: *p as u16 },
+            YaesuAsciiCommand::RfPower(None) => RadioRequest::GetTxPower,
+            YaesuAsciiCommand::KeyerSpeed(Some(wpm)) => RadioRequest::SetKeyerSpeed { wpm: *wpm },
+            YaesuAsciiCommand::KeyerSpeed(None) => RadioRequest::GetKeyerSpeed,
+            YaesuAsciiCommand::BreakIn(Some(on)) => RadioRequest::SetBreakIn {
+                mode: break_in_mode(*on),
+            },
+            YaesuAsciiCommand::BreakIn(None) => RadioRequest::GetBreakIn,
+            YaesuAsciiCommand::BreakInDelay(Some(ms)) => RadioRequest::SetBreakInDelay { ms: *ms },
+            YaesuAsciiCommand::BreakInDelay(None) => RadioRequest::GetBreakInDelay,
+            YaesuAsciiCommand::KeyPitch(Some(code)) => RadioRequest::SetSidetonePitch {
+                hz: key_pitch_hz(*code),
+            },
+            YaesuAsciiCommand::KeyPitch(None) => RadioRequest::GetSidetonePitch,
+            YaesuAsciiCommand::Clarifier(Some(on)) => RadioRequest::SetRit { on: *on },
+            YaesuAsciiCommand::Clarifier(None) => RadioRequest::GetRit,
+            YaesuAsciiCommand::ClarifierClear => RadioRequest::ClearRit,
+            YaesuAsciiCommand::ClarifierUp(hz) => RadioRequest::AdjustRit { hz: i32::from(*hz) },
+            YaesuAsciiCommand::ClarifierDown(hz) => RadioRequest::AdjustRit {
+                hz: -i32::from(*hz),
+            },
+            YaesuAsciiCommand::SMeter(None) => RadioRequest::GetMeter {
+                meter: MeterKind::Signal,
+            },
+            YaesuAsciiCommand::SMeter(Some(_))
+            | YaesuAsciiCommand::Keyer(_)
+            | YaesuAsciiCommand::ManualNotch { .. }
+            | YaesuAsciiCommand::Menu { .. } => RadioRequest::Unknown { data: vec![] },
             YaesuAsciiCommand::Unknown(s) => RadioRequest::Unknown {
                 data: s.as_bytes().to_vec(),
             },
@@ -478,28 +718,66 @@ impl ToRadioRequest for YaesuAsciiCommand {
     }
 }
 
+/// What `VS` selects: VFO A on the MAIN side or VFO B on the SUB side
+///
+/// `VS` changes the receive VFO only; split (`ST`) decides the transmit VFO.
+fn vs_state(param: u8) -> VfoState {
+    let (vfo, receiver) = if param == 0 {
+        (Vfo::A, Receiver::Main)
+    } else {
+        (Vfo::B, Receiver::Sub)
+    };
+    VfoState {
+        rx_vfo: Some(vfo),
+        active_receiver: Some(receiver),
+        ..VfoState::default()
+    }
+}
+
+/// `VS` for the receive VFO or receiver, otherwise `ST` for split
+///
+/// There is no transmit-VFO select; transmitting on VFO B is sent as split
+/// on, anything else as split off. Memory has no `VS` form and selects VFO A.
+fn vfo_state_command(state: &VfoState) -> Option<YaesuAsciiCommand> {
+    if let Some(rx) = state.rx_vfo {
+        return Some(match rx {
+            Vfo::A | Vfo::Memory => YaesuAsciiCommand::VfoSelect(Some(0)),
+            Vfo::B => YaesuAsciiCommand::VfoSelect(Some(1)),
+            Vfo::Split => YaesuAsciiCommand::Split(Some(true)),
+        });
+    }
+    if let Some(receiver) = state.active_receiver {
+        return Some(YaesuAsciiCommand::VfoSelect(Some(match receiver {
+            Receiver::Main => 0,
+            Receiver::Sub => 1,
+        })));
+    }
+    state
+        .is_split()
+        .or(state.tx_vfo.map(|tx| tx == Vfo::B))
+        .map(|on| YaesuAsciiCommand::Split(Some(on)))
+}
+
 impl FromRadioRequest for YaesuAsciiCommand {
     fn from_radio_request(req: &RadioRequest) -> Option<Self> {
         match req {
-            RadioRequest::SetFrequency { hz } => Some(YaesuAsciiCommand::FrequencyA(Some(*hz))),
+            RadioRequest::SetFrequency { hz } => Some(YaesuAsciiCommand::FrequencyA(Some(hz.hz()))),
             RadioRequest::GetFrequency => Some(YaesuAsciiCommand::FrequencyA(None)),
             RadioRequest::SetMode { mode } => Some(YaesuAsciiCommand::Mode {
                 receiver: 0,
-                mode: Some(operating_mode_to_yaesu(*mode)),
+                mode: Some(ModeMap::YAESU_ASCII.code(*mode)),
             }),
             RadioRequest::GetMode => Some(YaesuAsciiCommand::Mode {
                 receiver: 0,
                 mode: None,
             }),
+            // Compound request: see encode_request_sequence
+            RadioRequest::SetFrequencyMode { .. } => None,
             RadioRequest::SetPtt { active: true } => Some(YaesuAsciiCommand::Transmit(Some(1))),
             RadioRequest::SetPtt { active: false } => Some(YaesuAsciiCommand::Transmit(Some(0))),
             RadioRequest::GetPtt => Some(YaesuAsciiCommand::Transmit(None)),
-            RadioRequest::SetVfo { vfo } => match vfo {
-                Vfo::A => Some(YaesuAsciiCommand::VfoSelect(Some(0))),
-                Vfo::B => Some(YaesuAsciiCommand::VfoSelect(Some(1))),
-                Vfo::Split => Some(YaesuAsciiCommand::Split(Some(true))),
-                Vfo::Memory => Some(YaesuAsciiCommand::VfoSelect(Some(0))),
-            },
+            RadioRequest::SetVfoState { state } => vfo_state_command(state),
+            RadioRequest::SetVfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioRequest::GetVfo => Some(YaesuAsciiCommand::VfoSelect(None)),
             RadioRequest::GetId => Some(YaesuAsciiCommand::Id(None)),
             RadioRequest::GetStatus => Some(YaesuAsciiCommand::Info(None)),
@@ -508,7 +786,35 @@ impl FromRadioRequest for YaesuAsciiCommand {
                 Some(YaesuAsciiCommand::AutoInfo(Some(*enabled)))
             }
             RadioRequest::GetAutoInfo => Some(YaesuAsciiCommand::AutoInfo(None)),
+            RadioRequest::SetTxPower { watts } => u8::try_from(*watts)
+                .ok()
+                .map(|p| YaesuAsciiCommand::RfPower(Some(p))),
+            RadioRequest::GetTxPower => Some(YaesuAsciiCommand::RfPower(None)),
+            RadioRequest::SetKeyerSpeed { wpm } => Some(YaesuAsciiCommand::KeyerSpeed(Some(*wpm))),
+            RadioRequest::GetKeyerSpeed => Some(YaesuAsciiCommand::KeyerSpeed(None)),
+            RadioRequest::SetBreakIn { mode } => {
+                Some(YaesuAsciiCommand::BreakIn(Some(*mode != BreakIn::Off)))
+            }
+            RadioRequest::GetBreakIn => Some(YaesuAsciiCommand::BreakIn(None)),
+            RadioRequest::SetBreakInDelay { ms } => {
+                Some(YaesuAsciiCommand::BreakInDelay(Some(*ms)))
+            }
+            RadioRequest::GetBreakInDelay => Some(YaesuAsciiCommand::BreakInDelay(None)),
+            RadioRequest::SetSidetonePitch { hz } => {
+                Some(YaesuAsciiCommand::KeyPitch(Some(key_pitch_code(*hz))))
+            }
+            RadioRequest::GetSidetonePitch => Some(YaesuAsciiCommand::KeyPitch(None)),
+            RadioRequest::SetRit { on } => Some(YaesuAsciiCommand::Clarifier(Some(*on))),
+            RadioRequest::GetRit => Some(YaesuAsciiCommand::Clarifier(None)),
+            RadioRequest::ClearRit => Some(YaesuAsciiCommand::ClarifierClear),
+            RadioRequest::AdjustRit { hz } => clarifier_step(*hz),
+            // The offset is only reported in IF
+            RadioRequest::GetRitOffset => None,
             RadioRequest::GetControlBand | RadioRequest::GetTransmitBand => None,
+            RadioRequest::GetMeter {
+                meter: MeterKind::Signal,
+            } => Some(YaesuAsciiCommand::SMeter(None)),
+            RadioRequest::GetMeter { .. } => None,
             RadioRequest::Unknown { .. } => None,
         }
     }
@@ -517,36 +823,50 @@ impl FromRadioRequest for YaesuAsciiCommand {
 impl FromRadioResponse for YaesuAsciiCommand {
     fn from_radio_response(resp: &RadioResponse) -> Option<Self> {
         match resp {
-            RadioResponse::Frequency { hz } => Some(YaesuAsciiCommand::FrequencyA(Some(*hz))),
+            RadioResponse::Frequency { hz } => Some(YaesuAsciiCommand::FrequencyA(Some(hz.hz()))),
             RadioResponse::Mode { mode } => Some(YaesuAsciiCommand::Mode {
                 receiver: 0,
-                mode: Some(operating_mode_to_yaesu(*mode)),
+                mode: Some(ModeMap::YAESU_ASCII.code(*mode)),
             }),
             RadioResponse::Ptt { active } => Some(YaesuAsciiCommand::Transmit(Some(if *active {
                 1
             } else {
                 0
             }))),
-            RadioResponse::Vfo { vfo } => match vfo {
-                Vfo::A => Some(YaesuAsciiCommand::VfoSelect(Some(0))),
-                Vfo::B => Some(YaesuAsciiCommand::VfoSelect(Some(1))),
-                Vfo::Split => Some(YaesuAsciiCommand::Split(Some(true))),
-                Vfo::Memory => Some(YaesuAsciiCommand::VfoSelect(Some(0))),
-            },
+            RadioResponse::VfoState { state } => vfo_state_command(state),
+            RadioResponse::Vfo { vfo } => vfo_state_command(&VfoState::from(*vfo)),
             RadioResponse::Id { id } => Some(YaesuAsciiCommand::Id(Some(id.clone()))),
             RadioResponse::Status { frequency_hz, .. } => {
-                frequency_hz.map(|hz| YaesuAsciiCommand::FrequencyA(Some(hz)))
+                frequency_hz.map(|hz| YaesuAsciiCommand::FrequencyA(Some(hz.hz())))
             }
             RadioResponse::AutoInfo { enabled } => {
                 Some(YaesuAsciiCommand::AutoInfo(Some(*enabled)))
             }
+            RadioResponse::TxPower { watts } => u8::try_from(*watts)
+                .ok()
+                .map(|p| YaesuAsciiCommand::RfPower(Some(p))),
             RadioResponse::ControlBand { .. } | RadioResponse::TransmitBand { .. } => None,
+            RadioResponse::Meter {
+                meter: MeterKind::Signal,
+                level,
+            } => Some(YaesuAsciiCommand::SMeter(Some(u16::from(*level)))),
+            RadioResponse::Meter { .. } => None,
+            RadioResponse::KeyerSpeed { wpm } => Some(YaesuAsciiCommand::KeyerSpeed(Some(*wpm))),
+            RadioResponse::BreakIn { mode } => {
+                Some(YaesuAsciiCommand::BreakIn(Some(*mode != BreakIn::Off)))
+            }
+            RadioResponse::BreakInDelay { ms } => Some(YaesuAsciiCommand::BreakInDelay(Some(*ms))),
+            RadioResponse::SidetonePitch { hz } => {
+                Some(YaesuAsciiCommand::KeyPitch(Some(key_pitch_code(*hz))))
+            }
+            RadioResponse::Rit { on } => Some(YaesuAsciiCommand::Clarifier(Some(*on))),
+            RadioResponse::RitOffset { .. } => None,
             RadioResponse::Unknown { .. } => None,
         }
     }
 }
 
-crate::impl_radio_codec!(YaesuAsciiCodec);
+crate::impl_radio_codec!(YaesuAsciiCodec, mode_map);
 
 impl EncodeCommand for YaesuAsciiCommand {
     fn encode(&self) -> Vec<u8> {
@@ -586,12 +906,89 @@ impl EncodeCommand for YaesuAsciiCommand {
             YaesuAsciiCommand::SMeter(None) => "SM0".to_string(),
             YaesuAsciiCommand::RfPower(Some(p)) => format!("PC{:03}", p),
             YaesuAsciiCommand::RfPower(None) => "PC".to_string(),
+            YaesuAsciiCommand::KeyerSpeed(Some(wpm)) => format!("KS{:03}", wpm),
+            YaesuAsciiCommand::KeyerSpeed(None) => "KS".to_string(),
+            YaesuAsciiCommand::BreakIn(Some(on)) => format!("BI{}", if *on { 1 } else { 0 }),
+            YaesuAsciiCommand::BreakIn(None) => "BI".to_string(),
+            YaesuAsciiCommand::BreakInDelay(Some(ms)) => format!("SD{:04}", ms),
+            YaesuAsciiCommand::BreakInDelay(None) => "SD".to_string(),
+            YaesuAsciiCommand::KeyPitch(Some(code)) => format!("KP{:02}", code),
+            YaesuAsciiCommand::KeyPitch(None) => "KP".to_string(),
+            YaesuAsciiCommand::Keyer(Some(on)) => format!("KR{}", if *on { 1 } else { 0 }),
+            YaesuAsciiCommand::Keyer(None) => "KR".to_string(),
+            YaesuAsciiCommand::Clarifier(Some(on)) => format!("RT{}", if *on { 1 } else { 0 }),
+            YaesuAsciiCommand::Clarifier(None) => "RT".to_string(),
+            YaesuAsciiCommand::ClarifierClear => "RC".to_string(),
+            YaesuAsciiCommand::ClarifierUp(hz) => format!("RU{:04}", hz),
+            YaesuAsciiCommand::ClarifierDown(hz) => format!("RD{:04}", hz),
+            YaesuAsciiCommand::ManualNotch {
+                receiver,
+                function,
+                value: Some(v),
+            } => format!("BP{}{}{:03}", receiver, function, v),
+            YaesuAsciiCommand::ManualNotch {
+                receiver,
+                function,
+                value: None,
+            } => format!("BP{}{}", receiver, function),
+            YaesuAsciiCommand::Menu {
+                item,
+                digits,
+                value,
+            } => format!(
+                "EX{:0width$}{}",
+                item,
+                value.as_deref().unwrap_or(""),
+                width = *digits
+            ),
             YaesuAsciiCommand::Unknown(s) => s.clone(),
         };
         format!("{};", cmd).into_bytes()
     }
 }
 
+/// Break-in for `BI1;`/`BI0;`
+///
+/// Full or semi break-in is chosen in the radio's menu; `BI` only switches
+/// it on, so on reads as semi break-in.
+fn break_in_mode(on: bool) -> BreakIn {
+    if on {
+        BreakIn::Semi
+    } else {
+        BreakIn::Off
+    }
+}
+
+/// `0`/`1` of an on/off command; anything else is line noise
+fn parse_switch(params: &str, what: &str) -> Result<bool, ParseError> {
+    match params {
+        "0" => Ok(false),
+        "1" => Ok(true),
+        _ => Err(ParseError::InvalidFrame(format!("invalid {} state", what))),
+    }
+}
+
+/// `RU`/`RD` for a clarifier move; steps beyond 9999 Hz are cut to it
+fn clarifier_step(hz: i32) -> Option<YaesuAsciiCommand> {
+    let step = hz.unsigned_abs().min(CLARIFIER_MAX_STEP_HZ) as u16;
+    match hz.signum() {
+        1 => Some(YaesuAsciiCommand::ClarifierUp(step)),
+        -1 => Some(YaesuAsciiCommand::ClarifierDown(step)),
+        _ => None,
+    }
+}
+
+/// CW pitch in Hz for a `KP` code
+fn key_pitch_hz(code: u8) -> u16 {
+    KEY_PITCH_BASE_HZ + u16::from(code) * KEY_PITCH_STEP_HZ
+}
+
+/// Nearest `KP` code for a CW pitch in Hz
+fn key_pitch_code(hz: u16) -> u8 {
+    let steps = (hz.saturating_sub(KEY_PITCH_BASE_HZ) + KEY_PITCH_STEP_HZ / 2) / KEY_PITCH_STEP_HZ;
+    steps.min(KEY_PITCH_MAX_CODE) as u8
+}
+
 /// Parse Yaesu mode character to numeric value
 fn parse_yaesu_mode_char(c: char) -> Result<u8, ParseError> {
     match c {
@@ -608,7 +1005,8 @@ fn parse_yaesu_mode_char(c: char) -> Result<u8, ParseError> {
         'B' | 'b' => Ok(11), // FM-N
         'C' | 'c' => Ok(12), // DATA-USB
         'D' | 'd' => Ok(13), // AM-N
-        'E' | 'e' => Ok(14), // C4FM
+        'E' | 'e' => Ok(14), // C4FM (PSK on FTDX10/FTDX101/FT-710)
+        'F' | 'f' => Ok(15), // DATA-FM-N
         '0' => Ok(0),        // Sometimes used
         _ => Err(ParseError::InvalidMode(c.to_string())),
     }
@@ -631,49 +1029,11 @@ fn yaesu_mode_to_char(mode: u8) -> char {
         12 => 'C',
         13 => 'D',
         14 => 'E',
+        15 => 'F',
         _ => '2', // Default to USB
     }
 }
 
-/// Convert Yaesu ASCII mode number to OperatingMode
-fn yaesu_mode_to_operating_mode(mode: u8) -> OperatingMode {
-    match mode {
-        1 => OperatingMode::Lsb,
-        2 => OperatingMode::Usb,
-        3 => OperatingMode::Cw, // CW-U
-        4 => OperatingMode::Fm,
-        5 => OperatingMode::Am,
-        6 => OperatingMode::Rtty,   // RTTY-LSB
-        7 => OperatingMode::CwR,    // CW-L
-        8 => OperatingMode::DataL,  // DATA-LSB
-        9 => OperatingMode::RttyR,  // RTTY-USB
-        10 => OperatingMode::Data,  // DATA-FM
-        11 => OperatingMode::FmN,   // FM-N
-        12 => OperatingMode::DataU, // DATA-USB
-        13 => OperatingMode::Am,    // AM-N (narrow)
-        14 => OperatingMode::Fm,    // C4FM (digital FM)
-        _ => OperatingMode::Usb,
-    }
-}
-
-/// Convert OperatingMode to Yaesu ASCII mode number
-fn operating_mode_to_yaesu(mode: OperatingMode) -> u8 {
-    match mode {
-        OperatingMode::Lsb => 1,
-        OperatingMode::Usb => 2,
-        OperatingMode::Cw => 3,
-        OperatingMode::Fm => 4,
-        OperatingMode::Am => 5,
-        OperatingMode::Rtty => 6,
-        OperatingMode::CwR => 7,
-        OperatingMode::DataL | OperatingMode::DigL => 8,
-        OperatingMode::RttyR => 9,
-        OperatingMode::Data | OperatingMode::Dig | OperatingMode::Pkt => 10,
-        OperatingMode::FmN => 11,
-        OperatingMode::DataU | OperatingMode::DigU => 12,
-    }
-}
-
 /// Known Yaesu ASCII radio ID responses
 pub mod radio_ids {
     /// FT-991 ID
@@ -725,8 +1085,10 @@ mod tests {
     use super::{
         is_known_yaesu_ascii_id, is_valid_id_response, YaesuAsciiCodec, YaesuAsciiCommand,
     };
+    use crate::Frequency;
     use crate::{
-        EncodeCommand, FromRadioRequest, FromRadioResponse, ProtocolCodec, RadioRequest,
+        create_model_codec, encode_model_request_sequence, EncodeCommand, FromRadioRequest,
+        FromRadioResponse, OperatingMode, Protocol, ProtocolCodec, RadioDatabase, RadioRequest,
         RadioResponse, ToRadioRequest, ToRadioResponse,
     };
 
@@ -849,7 +1211,12 @@ mod tests {
     fn test_to_radio_response() {
         let cmd = YaesuAsciiCommand::FrequencyA(Some(7_074_000));
         let response = cmd.to_radio_response();
-        assert_eq!(response, RadioResponse::Frequency { hz: 7_074_000 });
+        assert_eq!(
+            response,
+            RadioResponse::Frequency {
+                hz: Frequency::from_hz(7_074_000)
+            }
+        );
     }
 
     #[test]
@@ -861,14 +1228,18 @@ mod tests {
 
     #[test]
     fn test_from_radio_request() {
-        let req = RadioRequest::SetFrequency { hz: 14_250_000 };
+        let req = RadioRequest::SetFrequency {
+            hz: Frequency::from_hz(14_250_000),
+        };
         let cmd = YaesuAsciiCommand::from_radio_request(&req).unwrap();
         assert_eq!(cmd, YaesuAsciiCommand::FrequencyA(Some(14_250_000)));
     }
 
     #[test]
     fn test_from_radio_response() {
-        let resp = RadioResponse::Frequency { hz: 7_074_000 };
+        let resp = RadioResponse::Frequency {
+            hz: Frequency::from_hz(7_074_000),
+        };
         let cmd = YaesuAsciiCommand::from_radio_response(&resp).unwrap();
         assert_eq!(cmd, YaesuAsciiCommand::FrequencyA(Some(7_074_000)));
     }
@@ -886,6 +1257,123 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_menu_read_and_write() {
+        let mut codec = YaesuAsciiCodec::new();
+        codec.push_bytes(b"EX033;EX0331;");
+
+        assert_eq!(
+            codec.next_command().unwrap(),
+            YaesuAsciiCommand::Menu {
+                item: 33,
+                digits: 3,
+                value: None
+            }
+        );
+        let answer = codec.next_command().unwrap();
+        assert_eq!(
+            answer,
+            YaesuAsciiCommand::Menu {
+                item: 33,
+                digits: 3,
+                value: Some("1".to_string())
+            }
+        );
+        assert_eq!(answer.encode(), b"EX0331;");
+    }
+
+    #[test]
+    fn test_keyer_and_manual_notch() {
+        let mut codec = YaesuAsciiCodec::new();
+        codec.push_bytes(b"KR0;KR7;BP00;BP01150;");
+
+        let keyer = codec.next_command().unwrap();
+        assert_eq!(keyer, YaesuAsciiCommand::Keyer(Some(false)));
+        assert_eq!(keyer.encode(), b"KR0;");
+        // Not a state; kept as is rather than read as on
+        assert_eq!(
+            codec.next_command().unwrap(),
+            YaesuAsciiCommand::Unknown("KR7".into())
+        );
+        assert_eq!(
+            codec.next_command().unwrap(),
+            YaesuAsciiCommand::ManualNotch {
+                receiver: 0,
+                function: 0,
+                value: None
+            }
+        );
+        let notch = codec.next_command().unwrap();
+        assert_eq!(
+            notch,
+            YaesuAsciiCommand::ManualNotch {
+                receiver: 0,
+                function: 1,
+                value: Some(150)
+            }
+        );
+        assert_eq!(notch.encode(), b"BP01150;");
+        // No normalized equivalent; left for same-protocol passthrough
+        assert_eq!(
+            notch.to_radio_request(),
+            RadioRequest::Unknown { data: vec![] }
+        );
+    }
+
+    #[test]
+    fn test_clarifier_maps_to_rit() {
+        let mut codec = YaesuAsciiCodec::new();
+        codec.push_bytes(b"RT1;RC;RU0150;RD0020;RT;");
+
+        let expected = [
+            RadioRequest::SetRit { on: true },
+            RadioRequest::ClearRit,
+            RadioRequest::AdjustRit { hz: 150 },
+            RadioRequest::AdjustRit { hz: -20 },
+            RadioRequest::GetRit,
+        ];
+        for request in expected {
+            let cmd = codec.next_command().unwrap();
+            assert_eq!(cmd.to_radio_request(), request);
+            // And back to the same frame
+            assert_eq!(
+                YaesuAsciiCommand::from_radio_request(&request).unwrap(),
+                cmd
+            );
+        }
+
+        codec.push_bytes(b"RT0;");
+        assert_eq!(
+            codec.next_command().unwrap().to_radio_response(),
+            RadioResponse::Rit { on: false }
+        );
+
+        // RU/RD carry four digits
+        assert_eq!(
+            YaesuAsciiCommand::from_radio_request(&RadioRequest::AdjustRit { hz: 20_000 })
+                .unwrap()
+                .encode(),
+            b"RU9999;"
+        );
+    }
+
+    #[test]
+    fn test_menu_six_digit_items() {
+        let mut codec = YaesuAsciiCodec::with_menu_digits(6);
+        codec.push_bytes(b"EX0301010;");
+
+        let cmd = codec.next_command().unwrap();
+        assert_eq!(
+            cmd,
+            YaesuAsciiCommand::Menu {
+                item: 30101,
+                digits: 6,
+                value: Some("0".to_string())
+            }
+        );
+        assert_eq!(cmd.encode(), b"EX0301010;");
+    }
+
     #[test]
     fn test_is_valid_id_response() {
         assert!(is_valid_id_response(b"ID0570;"));
@@ -900,4 +1388,50 @@ mod tests {
         assert!(is_known_yaesu_ascii_id("0681")); // FTDX-101D
         assert!(!is_known_yaesu_ascii_id("019")); // Kenwood ID
     }
+
+    #[test]
+    fn test_model_mode_overrides() {
+        let ft991 = RadioDatabase::by_yaesu_ascii_id("0670").unwrap();
+        let ftdx10 = RadioDatabase::by_yaesu_ascii_id("0690").unwrap();
+
+        let mut codec = create_model_codec(Protocol::YaesuAscii, Some(&ft991));
+        codec.push_bytes(b"MD0A;MD0E;");
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Mode {
+                mode: OperatingMode::Pkt
+            })
+        );
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Mode {
+                mode: OperatingMode::Fm
+            })
+        );
+
+        let mut codec = create_model_codec(Protocol::YaesuAscii, Some(&ftdx10));
+        codec.push_bytes(b"MD0E;IF001014074000+0000000E000000;");
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Mode {
+                mode: OperatingMode::DigU
+            })
+        );
+        assert_eq!(
+            codec.next_response().unwrap().mode(),
+            Some(OperatingMode::DigU)
+        );
+
+        let data = RadioRequest::SetMode {
+            mode: OperatingMode::Data,
+        };
+        assert_eq!(
+            encode_model_request_sequence::<YaesuAsciiCommand>(&data, &ft991.mode_map()),
+            Some(b"MD0C;".to_vec())
+        );
+        assert_eq!(
+            encode_model_request_sequence::<YaesuAsciiCommand>(&data, &ftdx10.mode_map()),
+            Some(b"MD0A;".to_vec())
+        );
+    }
 }
@@ -0,0 +1,146 @@
+//! Yaesu ASCII extended menu (`EX`) parameter tables
+//!
+//! The `EX` command reads and writes the radio's setup menu by item number:
+//! `EX033;` queries item 33 and the radio answers `EX0331;`. The item number
+//! is fixed-width, but the width differs between model families:
+//!
+//! - FT-991 / FT-991A: 3 digits (`EX031`)
+//! - FTDX-101 / FTDX-10 / FT-710: 6 digits, group/section/item (`EX030101`)
+//!
+//! Values are left as the radio's digit string; where a parameter is an
+//! enumeration its labels are listed in the table, indexed by value.
+//!
+//! # References
+//! - [FT-991A CAT Manual](https://yaesu.com/Files/4CB893D7-1018-01AF-FA97E9E9AD48B50C/FT-991A_CAT_OM_ENG_1711-D.pdf)
+
+use crate::yaesu_ascii::radio_ids;
+
+/// Item number width used when the model is unknown (FT-991 family)
+pub const DEFAULT_MENU_DIGITS: usize = 3;
+
+/// A named menu parameter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuParam {
+    /// Menu item number
+    pub number: u32,
+    /// Name as shown on the radio's menu
+    pub name: &'static str,
+    /// Labels for enumerated values, indexed by value (empty = numeric)
+    pub values: &'static [&'static str],
+}
+
+impl MenuParam {
+    /// Label for a raw value, if the parameter is an enumeration
+    pub fn value_label(&self, value: &str) -> Option<&'static str> {
+        let index: usize = value.parse().ok()?;
+        self.values.get(index).copied()
+    }
+}
+
+/// Menu layout for one model family
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuTable {
+    /// Width of the item number in `EX` commands
+    pub digits: usize,
+    /// Known parameters, in menu order
+    pub params: &'static [MenuParam],
+}
+
+impl MenuTable {
+    /// Look up a parameter by item number
+    pub fn param(&self, number: u32) -> Option<&'static MenuParam> {
+        self.params.iter().find(|p| p.number == number)
+    }
+}
+
+/// FT-991 / FT-991A
+pub static FT991_MENU: MenuTable = MenuTable {
+    digits: 3,
+    params: &[
+        MenuParam {
+            number: 31,
+            name: "CAT RATE",
+            values: &["4800", "9600", "19200", "38400"],
+        },
+        MenuParam {
+            number: 32,
+            name: "CAT TOT",
+            values: &["10 ms", "100 ms", "1000 ms", "3000 ms"],
+        },
+        MenuParam {
+            number: 33,
+            name: "CAT RTS",
+            values: &["OFF", "ON"],
+        },
+        MenuParam {
+            number: 137,
+            name: "HF TX MAX POWER",
+            values: &[],
+        },
+        MenuParam {
+            number: 144,
+            name: "VOX DELAY",
+            values: &[],
+        },
+    ],
+};
+
+/// FTDX-101 / FTDX-10 / FT-710 (names not yet tabulated)
+pub static NEWCAT_MENU: MenuTable = MenuTable {
+    digits: 6,
+    params: &[],
+};
+
+/// Menu table for a Yaesu ASCII ID response (e.g. "0670")
+pub fn menu_table_for_id(id: &str) -> Option<&'static MenuTable> {
+    match id {
+        radio_ids::FT_991 | radio_ids::FT_991A => Some(&FT991_MENU),
+        radio_ids::FTDX_101D | radio_ids::FTDX_101MP | radio_ids::FTDX_10 | radio_ids::FT_710 => {
+            Some(&NEWCAT_MENU)
+        }
+        _ => None,
+    }
+}
+
+/// Menu table for a model name from the radio database (e.g. "FT-991A")
+pub fn menu_table_for_model(model: &str) -> Option<&'static MenuTable> {
+    if model.starts_with("FT-991") {
+        Some(&FT991_MENU)
+    } else if model.starts_with("FTDX") || model.starts_with("FT-710") {
+        Some(&NEWCAT_MENU)
+    } else {
+        None
+    }
+}
+
+/// Menu table for an item number width
+///
+/// Used when decoding captures, where only the width the codec was set up
+/// with is known.
+pub fn menu_table_for_digits(digits: usize) -> Option<&'static MenuTable> {
+    [&FT991_MENU, &NEWCAT_MENU]
+        .into_iter()
+        .find(|t| t.digits == digits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_by_id_and_model() {
+        let table = menu_table_for_id("0670").unwrap();
+        assert_eq!(table.digits, 3);
+        assert_eq!(table.param(33).unwrap().name, "CAT RTS");
+        assert_eq!(menu_table_for_model("FTDX10").unwrap().digits, 6);
+        assert!(menu_table_for_model("TS-590S").is_none());
+    }
+
+    #[test]
+    fn test_value_labels() {
+        let rate = FT991_MENU.param(31).unwrap();
+        assert_eq!(rate.value_label("2"), Some("19200"));
+        assert_eq!(rate.value_label("9"), None);
+        assert_eq!(FT991_MENU.param(144).unwrap().value_label("0030"), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! Test vector corpus for real-world captures
+//!
+//! Every capture listed in `tests/corpus/manifest.txt` is fed through the
+//! codec for its protocol and every frame must decode to something other than
+//! `Unknown`. Bytes the codec skipped or left incomplete at the end of the file
+//! count as failures too.
+//!
+//! Every capture is also replayed one byte per read, the way a USB serial
+//! adapter with a short latency timer can deliver it, and must decode to
+//! exactly the same frames.
+//!
+//! See `tests/corpus/README.md` for the manifest format. Run with
+//! `cargo test -p cat-protocol --test corpus -- --nocapture` to see per-file
+//! pass rates.
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use cat_protocol::{create_radio_codec, Protocol, RadioRequest, RadioResponse};
+
+/// Which side of the link a capture was recorded on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    /// Bytes sent by the radio (parsed as responses)
+    FromRadio,
+    /// Bytes sent to the radio by a controller/amplifier (parsed as requests)
+    ToRadio,
+}
+
+/// One manifest entry
+#[derive(Debug)]
+struct CorpusEntry {
+    file: String,
+    protocol: Protocol,
+    direction: Direction,
+}
+
+/// Result of decoding one capture file
+#[derive(Debug, Default)]
+struct FileReport {
+    frames: usize,
+    unknown: Vec<Vec<u8>>,
+    /// Bytes not claimed by any frame (skipped garbage or a trailing partial frame)
+    unclaimed_bytes: usize,
+}
+
+impl FileReport {
+    fn passed(&self) -> usize {
+        self.frames - self.unknown.len()
+    }
+
+    /// Fraction of frames that decoded cleanly
+    fn pass_rate(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.passed() as f64 / self.frames as f64
+    }
+
+    fn is_clean(&self) -> bool {
+        self.frames > 0 && self.unknown.is_empty() && self.unclaimed_bytes == 0
+    }
+}
+
+impl fmt::Display for FileReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} frames ({:.1}%), {} unclaimed bytes",
+            self.passed(),
+            self.frames,
+            self.pass_rate() * 100.0,
+            self.unclaimed_bytes
+        )
+    }
+}
+
+fn corpus_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus")
+}
+
+fn parse_protocol(s: &str) -> Option<Protocol> {
+    match s {
+        "yaesu" => Some(Protocol::Yaesu),
+        "yaesu-ascii" => Some(Protocol::YaesuAscii),
+        "icom" => Some(Protocol::IcomCIV),
+        "kenwood" => Some(Protocol::Kenwood),
+        "elecraft" => Some(Protocol::Elecraft),
+        "flex" => Some(Protocol::FlexRadio),
+        _ => None,
+    }
+}
+
+fn parse_direction(s: &str) -> Option<Direction> {
+    match s {
+        "from-radio" => Some(Direction::FromRadio),
+        "to-radio" => Some(Direction::ToRadio),
+        _ => None,
+    }
+}
+
+/// Parse the manifest: `<file> <protocol> <direction>` per line, `#` comments
+fn load_manifest(text: &str) -> Result<Vec<CorpusEntry>, String> {
+    let mut entries = Vec::new();
+
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("").trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [file, protocol, direction] = fields[..] else {
+            return Err(format!(
+                "manifest line {}: expected `<file> <protocol> <direction>`",
+                lineno + 1
+            ));
+        };
+
+        entries.push(CorpusEntry {
+            file: file.to_string(),
+            protocol: parse_protocol(protocol).ok_or_else(|| {
+                format!(
+                    "manifest line {}: unknown protocol {protocol:?}",
+                    lineno + 1
+                )
+            })?,
+            direction: parse_direction(direction).ok_or_else(|| {
+                format!(
+                    "manifest line {}: unknown direction {direction:?}",
+                    lineno + 1
+                )
+            })?,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Decode a capture with the codec for `protocol`
+fn decode_capture(data: &[u8], protocol: Protocol, direction: Direction) -> FileReport {
+    let mut codec = create_radio_codec(protocol);
+    codec.push_bytes(data);
+
+    let mut report = FileReport::default();
+    let mut claimed = 0;
+
+    match direction {
+        Direction::FromRadio => {
+            while let Some((response, bytes)) = codec.next_response_with_bytes() {
+                report.frames += 1;
+                claimed += bytes.len();
+                if matches!(response, RadioResponse::Unknown { .. }) {
+                    report.unknown.push(bytes);
+                }
+            }
+        }
+        Direction::ToRadio => {
+            while let Some((request, bytes)) = codec.next_request_with_bytes() {
+                report.frames += 1;
+                claimed += bytes.len();
+                if matches!(request, RadioRequest::Unknown { .. }) {
+                    report.unknown.push(bytes);
+                }
+            }
+        }
+    }
+
+    report.unclaimed_bytes = data.len().saturating_sub(claimed);
+    report
+}
+
+#[test]
+fn corpus_captures_decode_cleanly() {
+    let dir = corpus_dir();
+    let manifest = fs::read_to_string(dir.join("manifest.txt")).expect("read corpus manifest");
+    let entries = load_manifest(&manifest).unwrap();
+    assert!(!entries.is_empty(), "corpus manifest lists no captures");
+
+    let mut failures = Vec::new();
+
+    for entry in &entries {
+        let data = fs::read(dir.join(&entry.file))
+            .unwrap_or_else(|e| panic!("read capture {}: {e}", entry.file));
+        let report = decode_capture(&data, entry.protocol, entry.direction);
+
+        println!("{:<40} {}", entry.file, report);
+
+        if !report.is_clean() {
+            let samples: Vec<String> = report
+                .unknown
+                .iter()
+                .take(5)
+                .map(|b| format!("{:02X?}", b))
+                .collect();
+            failures.push(format!(
+                "{}: {} (unknown frames: {})",
+                entry.file,
+                report,
+                samples.join(", ")
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "corpus captures with undecoded data:\n  {}",
+        failures.join("\n  ")
+    );
+}
+
+/// Raw bytes of every frame decoded when `data` arrives `chunk` bytes at a time
+fn decode_chunked(
+    data: &[u8],
+    protocol: Protocol,
+    direction: Direction,
+    chunk: usize,
+) -> Vec<Vec<u8>> {
+    let mut codec = create_radio_codec(protocol);
+    let mut frames = Vec::new();
+
+    for piece in data.chunks(chunk) {
+        codec.push_bytes(piece);
+        match direction {
+            Direction::FromRadio => {
+                frames.extend(std::iter::from_fn(|| codec.next_response_with_bytes()).map(|f| f.1))
+            }
+            Direction::ToRadio => {
+                frames.extend(std::iter::from_fn(|| codec.next_request_with_bytes()).map(|f| f.1))
+            }
+        }
+    }
+
+    frames
+}
+
+#[test]
+fn corpus_captures_decode_the_same_one_byte_at_a_time() {
+    let dir = corpus_dir();
+    let manifest = fs::read_to_string(dir.join("manifest.txt")).expect("read corpus manifest");
+    let entries = load_manifest(&manifest).unwrap();
+
+    // FlexRadio and Elecraft share the Kenwood codec, so this covers every codec
+    for entry in &entries {
+        let data = fs::read(dir.join(&entry.file))
+            .unwrap_or_else(|e| panic!("read capture {}: {e}", entry.file));
+        let whole = decode_chunked(&data, entry.protocol, entry.direction, data.len().max(1));
+        for chunk in [1, 2, 3, 7] {
+            let split = decode_chunked(&data, entry.protocol, entry.direction, chunk);
+            assert_eq!(
+                split, whole,
+                "{} decodes differently in {}-byte reads",
+                entry.file, chunk
+            );
+        }
+    }
+}
+
+#[test]
+fn manifest_rejects_malformed_lines() {
+    assert!(load_manifest("capture.bin kenwood").is_err());
+    assert!(load_manifest("capture.bin morse from-radio").is_err());
+    assert!(load_manifest("capture.bin kenwood sideways").is_err());
+
+    let entries = load_manifest("# comment\n\ncapture.bin icom to-radio # trailing\n").unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].protocol, Protocol::IcomCIV);
+    assert_eq!(entries[0].direction, Direction::ToRadio);
+}
+
+#[test]
+fn unclaimed_bytes_are_counted() {
+    // Complete frame followed by a truncated one
+    let report = decode_capture(
+        b"FA00014074000;FA0001",
+        Protocol::Kenwood,
+        Direction::FromRadio,
+    );
+    assert_eq!(report.frames, 1);
+    assert_eq!(report.unclaimed_bytes, 6);
+    assert!(!report.is_clean());
+}
@@ -0,0 +1,124 @@
+//! catapult-sim: run a scripted multiplexer session without the app
+//!
+//! Loads a scenario file (virtual radios, the amplifier, timed actions and
+//! expected results), runs the real multiplexer headlessly and writes the
+//! traffic and switching audit logs to files. Useful in CI to catch
+//! switching regressions, and for sharing a reproducible bug report.
+
+mod output;
+mod runner;
+mod scenario;
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use scenario::Scenario;
+
+const USAGE: &str = "\
+Usage: catapult-sim [OPTIONS] SCENARIO
+
+Run a scenario file against the multiplexer with virtual radios and a
+virtual amplifier, and write what happened to OUT:
+
+  traffic.catlog  every frame, for the app's traffic log viewer
+  traffic.txt     every frame decoded, with the scenario's actions
+  audit.txt       every change of active radio and why
+  audit.jsonl     the same, one JSON object per switch
+
+Exits 1 if the scenario's `expect` block isn't met.
+
+Options:
+  -o, --out <DIR>  Directory for the logs (default: current directory)
+  -q, --quiet      Don't print the summary
+  -h, --help       Show this help
+";
+
+struct Options {
+    scenario: PathBuf,
+    out: PathBuf,
+    quiet: bool,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Option<Options>, String> {
+    let mut scenario = None;
+    let mut out = PathBuf::from(".");
+    let mut quiet = false;
+
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        // Accept `--option=value` as well as `--option value`
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--out" => {
+                let dir = inline
+                    .map(str::to_string)
+                    .or_else(|| args.next())
+                    .ok_or("--out needs a value")?;
+                out = PathBuf::from(dir);
+            }
+            "-q" | "--quiet" => quiet = true,
+            _ if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ if scenario.is_some() => return Err("only one scenario file can be run".into()),
+            _ => scenario = Some(PathBuf::from(arg)),
+        }
+    }
+
+    let scenario = scenario.ok_or("no scenario file given")?;
+    Ok(Some(Options {
+        scenario,
+        out,
+        quiet,
+    }))
+}
+
+/// Run the scenario; `Ok(false)` when its expectations weren't met
+fn run(options: &Options) -> Result<bool, String> {
+    let source = options.scenario.display();
+    let text =
+        std::fs::read_to_string(&options.scenario).map_err(|e| format!("{}: {}", source, e))?;
+    let scenario = Scenario::parse(&text).map_err(|e| format!("{}: {}", source, e))?;
+
+    let runtime = runner::runtime().map_err(|e| e.to_string())?;
+    let outcome = runtime.block_on(runner::run(&scenario))?;
+
+    std::fs::create_dir_all(&options.out)
+        .map_err(|e| format!("{}: {}", options.out.display(), e))?;
+    output::write_logs(&outcome, &options.out)
+        .map_err(|e| format!("{}: {}", options.out.display(), e))?;
+
+    let failures = outcome.failures(&scenario);
+    if !options.quiet {
+        print!("{}", output::summary(&scenario, &outcome));
+    }
+    for failure in &failures {
+        eprintln!("catapult-sim: expectation failed: {}", failure);
+    }
+    Ok(failures.is_empty())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("catapult-sim: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&options) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("catapult-sim: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,163 @@
+//! Log files and the summary written after a run
+//!
+//! Times in the text logs are milliseconds since the clock started, so two
+//! runs of the same scenario line up when diffed.
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+use cat_mux::traffic_log::LOG_EXTENSION;
+use cat_mux::TrafficLogWriter;
+use cat_protocol::display::format_frame;
+
+use crate::runner::Outcome;
+use crate::scenario::Scenario;
+
+/// Milliseconds from `start` to `at` (0 if earlier)
+fn offset_ms(start: SystemTime, at: SystemTime) -> u128 {
+    at.duration_since(start).unwrap_or_default().as_millis()
+}
+
+/// Write the traffic and audit logs into `dir`
+pub fn write_logs(outcome: &Outcome, dir: &Path) -> io::Result<()> {
+    let mut log = TrafficLogWriter::open(dir.join(format!("traffic.{}", LOG_EXTENSION)))?;
+    for record in &outcome.traffic {
+        log.append(record)?;
+    }
+    log.flush()?;
+
+    let mut text = BufWriter::new(File::create(dir.join("traffic.txt"))?);
+    for line in traffic_lines(outcome) {
+        writeln!(text, "{}", line)?;
+    }
+    text.flush()?;
+
+    let mut audit = BufWriter::new(File::create(dir.join("audit.txt"))?);
+    let mut json = BufWriter::new(File::create(dir.join("audit.jsonl"))?);
+    for record in &outcome.switches {
+        writeln!(
+            audit,
+            "#{} +{} ms: {} -> {}",
+            record.id,
+            offset_ms(outcome.started, record.timestamp),
+            record
+                .from
+                .map(|handle| outcome.name(handle))
+                .unwrap_or_else(|| "none".to_string()),
+            outcome.name(record.to)
+        )?;
+        for link in record.chain(|handle| outcome.name(handle)) {
+            writeln!(audit, "  {}", link)?;
+        }
+        let line = serde_json::to_string(record).map_err(io::Error::other)?;
+        writeln!(json, "{}", line)?;
+    }
+    audit.flush()?;
+    json.flush()
+}
+
+/// Frames and actions in time order, one line each
+///
+/// Frames from before the clock started (connect-time sync) are shown at
+/// +0 ms. `<-` is a frame the mux received, `->` one it sent.
+pub fn traffic_lines(outcome: &Outcome) -> Vec<String> {
+    let mut lines: Vec<(SystemTime, String)> = outcome
+        .traffic
+        .iter()
+        .map(|record| {
+            let decoded = match record.protocol {
+                Some(protocol) => format_frame(&record.data, protocol),
+                None => cat_protocol::display::format_hex(&record.data),
+            };
+            let arrow = if record.outgoing { "->" } else { "<-" };
+            (
+                record.timestamp,
+                format!("{:<12} {} {}", record.channel, arrow, decoded),
+            )
+        })
+        .collect();
+    lines.extend(
+        outcome
+            .actions
+            .iter()
+            .map(|(at, action)| (*at, format!("** {} **", action))),
+    );
+    // Stable, so a frame and an action at the same instant keep their order
+    lines.sort_by_key(|(at, _)| *at);
+    lines
+        .into_iter()
+        .map(|(at, line)| format!("{:>7} ms  {}", offset_ms(outcome.started, at), line))
+        .collect()
+}
+
+/// What to print when the run finishes
+pub fn summary(scenario: &Scenario, outcome: &Outcome) -> String {
+    let mut out = String::new();
+    if !scenario.name.is_empty() {
+        let _ = writeln!(out, "{}", scenario.name);
+    }
+    let _ = writeln!(
+        out,
+        "{} frames, {} actions, {} switches over {} ms",
+        outcome.traffic.len(),
+        outcome.actions.len(),
+        outcome.switches.len(),
+        scenario.duration_ms()
+    );
+    let _ = writeln!(
+        out,
+        "Active radio: {}",
+        outcome.active_radio.as_deref().unwrap_or("none")
+    );
+    if scenario.amplifier.is_some() {
+        let _ = writeln!(out, "Amplifier: {}", outcome.amp_state.summary());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::time::Duration;
+
+    use cat_mux::{AmplifierEmulatedState, RadioHandle, TrafficRecord};
+    use cat_protocol::Protocol;
+
+    use super::*;
+
+    #[test]
+    fn test_traffic_lines_interleave_actions() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let at = |ms| start + Duration::from_millis(ms);
+        let record = |ms, channel: &str, outgoing, data: &[u8]| TrafficRecord {
+            timestamp: at(ms),
+            channel: channel.to_string(),
+            outgoing,
+            protocol: Some(Protocol::Kenwood),
+            data: data.to_vec(),
+        };
+        let outcome = Outcome {
+            started: start,
+            traffic: vec![
+                record(0, "K3", true, b"ID;"),
+                record(520, "Amplifier", true, b"FA00014076000;"),
+                record(510, "K3", false, b"FA00014076000;"),
+            ],
+            switches: Vec::new(),
+            actions: vec![(at(500), "K3 tunes to 14076000 Hz".to_string())],
+            names: HashMap::from([(RadioHandle(1), "K3".to_string())]),
+            active_radio: Some("K3".to_string()),
+            amp_state: AmplifierEmulatedState::default(),
+        };
+
+        let lines = traffic_lines(&outcome);
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("      0 ms  K3           -> Kenwood: "));
+        assert_eq!(lines[1], "    500 ms  ** K3 tunes to 14076000 Hz **");
+        assert!(lines[2].starts_with("    510 ms  K3           <- "));
+        assert!(lines[3].starts_with("    520 ms  Amplifier    -> "));
+    }
+}
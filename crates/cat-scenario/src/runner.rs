@@ -0,0 +1,420 @@
+//! Running a scenario against the real multiplexer
+//!
+//! Virtual radios and the virtual amplifier are wired to the mux exactly as
+//! the desktop app wires its simulated devices: each talks CAT over an
+//! in-memory duplex stream to an `AsyncRadioConnection` or
+//! `AsyncAmpConnection`. Radios finish their connect-time sync before the
+//! clock starts, so `at_ms: 0` means "once everything is connected".
+//!
+//! Runs on a runtime with paused time ([`runtime`]), so waits in the
+//! scenario, the devices and the mux's own timers take no real time and a
+//! run comes out the same on a loaded CI machine. Frames and switches are
+//! stamped with that virtual time as they arrive.
+
+use cat_protocol::Frequency;
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use cat_mux::switch_audit::SwitchRecord;
+use cat_mux::{
+    AmplifierChannel, AmplifierChannelMeta, AmplifierEmulatedState, AsyncAmpConnection,
+    AsyncRadioConnection, MuxActorCommand, MuxClient, MuxEvent, RadioChannelMeta, RadioHandle,
+    RadioTaskCommand, SyncPlan, TrafficRecord,
+};
+use cat_sim::{
+    run_virtual_amp_task, run_virtual_radio_task, VirtualAmpCommand, VirtualAmpMode,
+    VirtualAmplifier, VirtualRadio, VirtualRadioCommand,
+};
+use tokio::runtime::Handle;
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::time::Instant;
+
+use crate::scenario::{Action, AmplifierSpec, Scenario};
+
+/// How long radios get to finish their connect-time sync
+const SYNC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A runtime for [`run`], with time paused so it advances as soon as
+/// everything is waiting
+pub fn runtime() -> std::io::Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .start_paused(true)
+        .build()
+}
+
+/// Maps the runtime's (virtual) instants to wall-clock times for the logs
+#[derive(Debug, Clone, Copy)]
+struct VirtualWallClock {
+    instant: Instant,
+    time: SystemTime,
+}
+
+impl VirtualWallClock {
+    fn new() -> Self {
+        Self {
+            instant: Instant::now(),
+            time: SystemTime::now(),
+        }
+    }
+
+    fn at(&self, instant: Instant) -> SystemTime {
+        self.time + instant.saturating_duration_since(self.instant)
+    }
+
+    fn now(&self) -> SystemTime {
+        self.at(Instant::now())
+    }
+}
+
+/// What happened during a run
+#[derive(Debug)]
+pub struct Outcome {
+    /// When the clock started (after the radios connected)
+    pub started: SystemTime,
+    /// Every frame to and from the radios and the amplifier
+    pub traffic: Vec<TrafficRecord>,
+    /// Every change of active radio
+    pub switches: Vec<SwitchRecord>,
+    /// Actions taken, as `(when, description)`
+    pub actions: Vec<(SystemTime, String)>,
+    /// Radio names by handle
+    pub names: HashMap<RadioHandle, String>,
+    /// Radio active at the end
+    pub active_radio: Option<String>,
+    /// What the amplifier had been told at the end
+    pub amp_state: AmplifierEmulatedState,
+}
+
+impl Outcome {
+    /// A radio's name, or its handle if it isn't known
+    pub fn name(&self, handle: RadioHandle) -> String {
+        self.names
+            .get(&handle)
+            .cloned()
+            .unwrap_or_else(|| format!("radio {}", handle.0))
+    }
+
+    /// Expectations the run did not meet, one line each
+    pub fn failures(&self, scenario: &Scenario) -> Vec<String> {
+        let expect = &scenario.expect;
+        let mut failures = Vec::new();
+        if let Some(radio) = &expect.active_radio {
+            if self.active_radio.as_ref() != Some(radio) {
+                failures.push(format!(
+                    "active radio: expected {}, got {}",
+                    radio,
+                    self.active_radio.as_deref().unwrap_or("none")
+                ));
+            }
+        }
+        if let Some(switches) = expect.switches {
+            if self.switches.len() != switches {
+                failures.push(format!(
+                    "switches: expected {}, got {}",
+                    switches,
+                    self.switches.len()
+                ));
+            }
+        }
+        if let Some(hz) = expect.amp_frequency_hz {
+            if self.amp_state.frequency_hz != Some(Frequency::from_hz(hz)) {
+                failures.push(format!(
+                    "amp frequency: expected {} Hz, got {:?}",
+                    hz, self.amp_state.frequency_hz
+                ));
+            }
+        }
+        if let Some(mode) = expect.amp_mode {
+            if self.amp_state.mode != Some(mode) {
+                failures.push(format!(
+                    "amp mode: expected {:?}, got {:?}",
+                    mode, self.amp_state.mode
+                ));
+            }
+        }
+        failures
+    }
+}
+
+/// A connected virtual radio
+struct SimRadio {
+    handle: RadioHandle,
+    commands: mpsc::Sender<VirtualRadioCommand>,
+    task: mpsc::Sender<RadioTaskCommand>,
+}
+
+/// Run a scenario to the end and collect what the mux did
+///
+/// Expects a [`runtime`] with paused time; on a normal one it runs in real
+/// time.
+pub async fn run(scenario: &Scenario) -> Result<Outcome, String> {
+    let clock = VirtualWallClock::new();
+    let (client, mut event_rx) = MuxClient::start(&Handle::current(), 256);
+    let send = |cmd| client.try_send(cmd).map_err(|e| e.to_string());
+
+    // Keep the event channel drained for the whole run, noting when each
+    // event arrived
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
+    let collector = tokio::spawn(async move {
+        let mut events = Vec::new();
+        loop {
+            tokio::select! {
+                Some(event) = event_rx.recv() => events.push((Instant::now(), event)),
+                _ = &mut stop_rx => break,
+            }
+        }
+        while let Ok(event) = event_rx.try_recv() {
+            events.push((Instant::now(), event));
+        }
+        events
+    });
+
+    send(MuxActorCommand::SetSwitchingMode {
+        mode: scenario.switching_mode,
+    })?;
+    let amp = match &scenario.amplifier {
+        Some(spec) => Some(connect_amplifier(&client, spec)?),
+        None => None,
+    };
+
+    let mut radios = HashMap::new();
+    let mut names = HashMap::new();
+    let mut synced = Vec::new();
+    for config in &scenario.radios {
+        let (radio, sync_rx) = connect_radio(&client, config.clone()).await?;
+        names.insert(radio.handle, config.id.clone());
+        radios.insert(config.id.clone(), radio);
+        synced.push(sync_rx);
+    }
+    for sync_rx in synced {
+        let _ = tokio::time::timeout(SYNC_TIMEOUT, sync_rx).await;
+    }
+
+    let start = Instant::now();
+    let started = clock.at(start);
+    let mut actions = Vec::new();
+    for timed in scenario.schedule() {
+        tokio::time::sleep_until(start + Duration::from_millis(timed.at_ms)).await;
+        actions.push((clock.now(), timed.action.to_string()));
+        let radio = |name: &str| &radios[name];
+        match &timed.action {
+            Action::SetFrequency { radio: name, hz } => {
+                command(radio(name), VirtualRadioCommand::SetFrequency(*hz)).await
+            }
+            Action::SetMode { radio: name, mode } => {
+                command(radio(name), VirtualRadioCommand::SetMode(*mode)).await
+            }
+            Action::SetPtt { radio: name, on } => {
+                command(radio(name), VirtualRadioCommand::SetPtt(*on)).await
+            }
+            Action::SelectVfo { radio: name, vfo } => {
+                command(radio(name), VirtualRadioCommand::SelectVfo(*vfo)).await
+            }
+            Action::SetSplit { radio: name, on } => {
+                command(radio(name), VirtualRadioCommand::SetSplit(*on)).await
+            }
+            Action::GoSilent { radio: name, ms } => {
+                let silence = Duration::from_millis(*ms);
+                command(radio(name), VirtualRadioCommand::GoSilent(silence)).await
+            }
+            Action::SelectRadio { radio: name } => send(MuxActorCommand::SetActiveRadio {
+                handle: radio(name).handle,
+            })?,
+            Action::SetSwitchingMode { mode } => {
+                send(MuxActorCommand::SetSwitchingMode { mode: *mode })?
+            }
+        }
+    }
+    tokio::time::sleep_until(start + Duration::from_millis(scenario.duration_ms())).await;
+
+    let query_err = |e: cat_mux::ClientError| e.to_string();
+    let active = client.active_radio().map_err(query_err)?.await;
+    let active_radio = active
+        .map_err(query_err)?
+        .and_then(|(handle, _)| names.get(&handle).cloned());
+    let amp_state = client
+        .amp_state()
+        .map_err(query_err)?
+        .await
+        .map_err(query_err)?;
+
+    // Stop the devices before the mux so nothing reports a lost connection
+    for radio in radios.values() {
+        let _ = radio.commands.send(VirtualRadioCommand::Shutdown).await;
+        let _ = radio.task.send(RadioTaskCommand::Shutdown).await;
+    }
+    if let Some(amp) = amp {
+        amp.shutdown().await;
+    }
+    let _ = client.shutdown();
+    let _ = stop_tx.send(());
+    let events = collector.await.map_err(|e| e.to_string())?;
+
+    let radio_name = |handle: RadioHandle| {
+        names
+            .get(&handle)
+            .cloned()
+            .unwrap_or_else(|| format!("radio {}", handle.0))
+    };
+    // The mux stamps records with the system clock, which paused time
+    // doesn't move
+    let traffic = events
+        .iter()
+        .filter_map(|(at, event)| {
+            let mut record = TrafficRecord::from_event(event, radio_name)?;
+            record.timestamp = clock.at(*at);
+            Some(record)
+        })
+        .collect();
+    let switches = events
+        .into_iter()
+        .filter_map(|(at, event)| match event {
+            MuxEvent::SwitchAudited { mut record } => {
+                record.timestamp = clock.at(at);
+                Some(record)
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(Outcome {
+        started,
+        traffic,
+        switches,
+        actions,
+        names,
+        active_radio,
+        amp_state,
+    })
+}
+
+/// Send a command to a virtual radio; a radio that has stopped is ignored
+async fn command(radio: &SimRadio, cmd: VirtualRadioCommand) {
+    let _ = radio.commands.send(cmd).await;
+}
+
+/// Register a virtual radio and start it and its connection
+///
+/// The receiver fires once the connection's sync plan has run.
+async fn connect_radio(
+    client: &MuxClient,
+    config: cat_sim::VirtualRadioConfig,
+) -> Result<(SimRadio, oneshot::Receiver<()>), String> {
+    let name = config.id.clone();
+    let protocol = config.protocol;
+    let civ_address = config.civ_address;
+
+    let mut meta = RadioChannelMeta::new_virtual(name.clone(), name.clone(), protocol);
+    meta.civ_address = civ_address;
+    let (task_tx, task_rx) = mpsc::channel::<RadioTaskCommand>(32);
+    let handle = client
+        .register_radio(meta, Some(task_tx.clone()))
+        .map_err(|e| e.to_string())?
+        .await
+        .map_err(|e| format!("registering {}: {}", name, e))?;
+
+    let (connection_stream, radio_stream) = tokio::io::duplex(1024);
+    let (commands, commands_rx) = mpsc::channel::<VirtualRadioCommand>(32);
+    let radio = VirtualRadio::from_config(config);
+    tokio::spawn(async move {
+        let _ = run_virtual_radio_task(radio_stream, radio, commands_rx).await;
+    });
+
+    let mut conn = AsyncRadioConnection::new(handle, name, connection_stream, protocol, client);
+    if let Some(addr) = civ_address {
+        conn.set_civ_address(addr);
+    }
+    let (synced_tx, synced_rx) = oneshot::channel();
+    tokio::spawn(async move {
+        conn.run_sync_plan(SyncPlan::for_protocol(protocol)).await;
+        let _ = synced_tx.send(());
+        conn.run_read_loop(task_rx).await;
+    });
+
+    Ok((
+        SimRadio {
+            handle,
+            commands,
+            task: task_tx,
+        },
+        synced_rx,
+    ))
+}
+
+/// A connected virtual amplifier
+struct SimAmp {
+    commands: mpsc::Sender<VirtualAmpCommand>,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl SimAmp {
+    async fn shutdown(self) {
+        let _ = self.commands.send(VirtualAmpCommand::Shutdown).await;
+        let _ = self.shutdown.send(());
+    }
+}
+
+/// Configure and connect the virtual amplifier
+fn connect_amplifier(client: &MuxClient, spec: &AmplifierSpec) -> Result<SimAmp, String> {
+    let send = |cmd| client.try_send(cmd).map_err(|e| e.to_string());
+    send(MuxActorCommand::SetAmplifierConfig {
+        port: "[VIRTUAL]".to_string(),
+        protocol: spec.protocol,
+        baud_rate: 0,
+        civ_address: spec.civ_address,
+        response_protocol: None,
+        strictness: None,
+    })?;
+    if let Some(config) = spec.bypass {
+        send(MuxActorCommand::SetAmpBypassConfig { config })?;
+    }
+
+    let (amp_data_tx, amp_data_rx) = mpsc::channel::<Vec<u8>>(64);
+    let (urgent_tx, urgent_rx) = mpsc::channel::<Vec<u8>>(8);
+    let (_response_tx, response_rx) = mpsc::channel::<Vec<u8>>(64);
+    let meta = AmplifierChannelMeta::new_virtual(spec.protocol, spec.civ_address);
+    let channel = AmplifierChannel::new(meta, amp_data_tx, response_rx).with_urgent(urgent_tx);
+    let backlog = channel.backlog.clone();
+    send(MuxActorCommand::ConnectAmplifier { channel })?;
+
+    let (mux_stream, amp_stream) = tokio::io::duplex(4096);
+    let amp = VirtualAmplifier::new("virtual-amp", spec.protocol, spec.civ_address);
+    let (commands, commands_rx) = mpsc::channel::<VirtualAmpCommand>(32);
+    let (state_tx, _) = broadcast::channel(32);
+    let mode = if spec.polling {
+        VirtualAmpMode::Polling
+    } else {
+        VirtualAmpMode::AutoInfo
+    };
+    tokio::spawn(async move {
+        let _ = run_virtual_amp_task(amp_stream, amp, commands_rx, state_tx, mode).await;
+    });
+
+    let (shutdown, shutdown_rx) = oneshot::channel();
+    let conn = AsyncAmpConnection::new(mux_stream, client)
+        .with_backlog(backlog)
+        .with_urgent(urgent_rx);
+    tokio::spawn(conn.run(shutdown_rx, amp_data_rx));
+
+    Ok(SimAmp { commands, shutdown })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_example_scenario() {
+        let text = include_str!("../scenarios/switch-on-tune.json");
+        let scenario = Scenario::parse(text).unwrap();
+
+        let outcome = run(&scenario).await.unwrap();
+        assert_eq!(outcome.failures(&scenario), Vec::<String>::new());
+        assert!(outcome
+            .traffic
+            .iter()
+            .any(|record| record.channel == "Amplifier" && record.outgoing));
+        assert_eq!(outcome.actions.len(), scenario.actions.len());
+    }
+}
@@ -0,0 +1,275 @@
+//! Scenario files: what to simulate and what should come of it
+//!
+//! A scenario is JSON. It names the virtual radios (the same fields as the
+//! desktop's virtual radio config), the amplifier, the actions to take and
+//! when, and optionally what the mux should have ended up doing:
+//!
+//! ```json
+//! {
+//!   "name": "Tune the second radio",
+//!   "radios": [
+//!     { "id": "K3", "protocol": "Elecraft", "initial_frequency_hz": 14074000,
+//!       "initial_mode": "Usb", "civ_address": null },
+//!     { "id": "IC-7300", "protocol": "IcomCIV", "initial_frequency_hz": 7074000,
+//!       "initial_mode": "Usb", "civ_address": 148 }
+//!   ],
+//!   "amplifier": { "protocol": "Kenwood" },
+//!   "actions": [
+//!     { "at_ms": 1000, "action": "set_frequency", "radio": "IC-7300", "hz": 7075000 }
+//!   ],
+//!   "expect": { "active_radio": "IC-7300", "amp_frequency_hz": 7075000 }
+//! }
+//! ```
+
+use std::collections::HashSet;
+use std::fmt;
+
+use cat_mux::{AmpBypassConfig, SwitchingMode};
+use cat_protocol::{OperatingMode, Protocol, Vfo};
+use cat_sim::VirtualRadioConfig;
+use serde::{Deserialize, Serialize};
+
+/// How long the mux runs after the last action when no duration is given
+pub const DEFAULT_SETTLE_MS: u64 = 1000;
+
+/// A scripted session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    /// Shown in the summary
+    #[serde(default)]
+    pub name: String,
+    /// Switching mode for the whole run
+    #[serde(default)]
+    pub switching_mode: SwitchingMode,
+    /// Virtual radios, registered in order
+    pub radios: Vec<VirtualRadioConfig>,
+    /// Virtual amplifier (none if omitted)
+    #[serde(default)]
+    pub amplifier: Option<AmplifierSpec>,
+    /// Actions, in any order; they run by `at_ms`
+    #[serde(default)]
+    pub actions: Vec<TimedAction>,
+    /// Run length from the start, in milliseconds (default: the last action
+    /// plus [`DEFAULT_SETTLE_MS`])
+    #[serde(default)]
+    pub duration_ms: Option<u64>,
+    /// What the run must end with
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+/// The virtual amplifier
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AmplifierSpec {
+    pub protocol: Protocol,
+    #[serde(default)]
+    pub civ_address: Option<u8>,
+    /// Poll the mux for frequency instead of turning on auto-info
+    #[serde(default)]
+    pub polling: bool,
+    /// Out-of-range bypass, including the amp's personality
+    #[serde(default)]
+    pub bypass: Option<AmpBypassConfig>,
+}
+
+/// An action and when to take it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedAction {
+    /// Milliseconds from the start of the run
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub action: Action,
+}
+
+/// Something done to a virtual radio or the mux
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum Action {
+    /// Tune a radio
+    SetFrequency { radio: String, hz: u64 },
+    /// Change a radio's mode
+    SetMode { radio: String, mode: OperatingMode },
+    /// Key or unkey a radio
+    SetPtt { radio: String, on: bool },
+    /// Select a radio's receive VFO
+    SelectVfo { radio: String, vfo: Vfo },
+    /// Turn a radio's split on or off
+    SetSplit { radio: String, on: bool },
+    /// Stop a radio answering for a while
+    GoSilent { radio: String, ms: u64 },
+    /// Select the active radio by hand
+    SelectRadio { radio: String },
+    /// Change the switching mode
+    SetSwitchingMode { mode: SwitchingMode },
+}
+
+impl Action {
+    /// The radio the action names, if any
+    pub fn radio(&self) -> Option<&str> {
+        match self {
+            Action::SetFrequency { radio, .. }
+            | Action::SetMode { radio, .. }
+            | Action::SetPtt { radio, .. }
+            | Action::SelectVfo { radio, .. }
+            | Action::SetSplit { radio, .. }
+            | Action::GoSilent { radio, .. }
+            | Action::SelectRadio { radio } => Some(radio),
+            Action::SetSwitchingMode { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Action::SetFrequency { radio, hz } => write!(f, "{} tunes to {} Hz", radio, hz),
+            Action::SetMode { radio, mode } => write!(f, "{} changes to {:?}", radio, mode),
+            Action::SetPtt { radio, on: true } => write!(f, "{} keys up", radio),
+            Action::SetPtt { radio, on: false } => write!(f, "{} unkeys", radio),
+            Action::SelectVfo { radio, vfo } => write!(f, "{} selects VFO {:?}", radio, vfo),
+            Action::SetSplit { radio, on } => write!(
+                f,
+                "{} turns split {}",
+                radio,
+                if *on { "on" } else { "off" }
+            ),
+            Action::GoSilent { radio, ms } => write!(f, "{} goes silent for {} ms", radio, ms),
+            Action::SelectRadio { radio } => write!(f, "{} selected by hand", radio),
+            Action::SetSwitchingMode { mode } => write!(f, "switching mode set to {:?}", mode),
+        }
+    }
+}
+
+/// What the mux must have done by the end of the run
+///
+/// Unset fields aren't checked.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Expectations {
+    /// Radio active at the end
+    #[serde(default)]
+    pub active_radio: Option<String>,
+    /// Number of active-radio changes
+    #[serde(default)]
+    pub switches: Option<usize>,
+    /// Frequency last sent to the amplifier
+    #[serde(default)]
+    pub amp_frequency_hz: Option<u64>,
+    /// Mode last sent to the amplifier
+    #[serde(default)]
+    pub amp_mode: Option<OperatingMode>,
+}
+
+impl Scenario {
+    /// Parse and check a scenario file's contents
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let scenario: Scenario = serde_json::from_str(text).map_err(|e| e.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Check that radio names are unique and every action names one
+    pub fn validate(&self) -> Result<(), String> {
+        if self.radios.is_empty() {
+            return Err("scenario has no radios".to_string());
+        }
+        let mut names = HashSet::new();
+        for radio in &self.radios {
+            if !names.insert(radio.id.as_str()) {
+                return Err(format!("radio '{}' is listed twice", radio.id));
+            }
+        }
+        let known = |name: &str| names.contains(name);
+        for timed in &self.actions {
+            if let Some(radio) = timed.action.radio() {
+                if !known(radio) {
+                    return Err(format!(
+                        "action at {} ms names unknown radio '{}'",
+                        timed.at_ms, radio
+                    ));
+                }
+            }
+        }
+        if let Some(radio) = &self.expect.active_radio {
+            if !known(radio) {
+                return Err(format!("expect names unknown radio '{}'", radio));
+            }
+        }
+        Ok(())
+    }
+
+    /// Actions in the order they run (stable for equal times)
+    pub fn schedule(&self) -> Vec<&TimedAction> {
+        let mut actions: Vec<_> = self.actions.iter().collect();
+        actions.sort_by_key(|timed| timed.at_ms);
+        actions
+    }
+
+    /// Run length in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.duration_ms.unwrap_or_else(|| {
+            self.actions.iter().map(|a| a.at_ms).max().unwrap_or(0) + DEFAULT_SETTLE_MS
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TWO_RADIOS: &str = r#"{
+        "radios": [
+            { "id": "K3", "protocol": "Elecraft", "initial_frequency_hz": 14074000,
+              "initial_mode": "Usb", "civ_address": null },
+            { "id": "IC-7300", "protocol": "IcomCIV", "initial_frequency_hz": 7074000,
+              "initial_mode": "Usb", "civ_address": 148 }
+        ],
+        "amplifier": { "protocol": "Kenwood" },
+        "actions": [
+            { "at_ms": 2000, "action": "set_ptt", "radio": "IC-7300", "on": true },
+            { "at_ms": 500, "action": "set_frequency", "radio": "IC-7300", "hz": 7075000 },
+            { "at_ms": 500, "action": "set_switching_mode", "mode": "Automatic" }
+        ],
+        "expect": { "active_radio": "IC-7300", "switches": 1 }
+    }"#;
+
+    #[test]
+    fn test_parse_and_schedule() {
+        let scenario = Scenario::parse(TWO_RADIOS).unwrap();
+        assert_eq!(scenario.switching_mode, SwitchingMode::FrequencyTriggered);
+        assert_eq!(
+            scenario.amplifier.as_ref().unwrap().protocol,
+            Protocol::Kenwood
+        );
+        assert_eq!(scenario.duration_ms(), 3000);
+        assert_eq!(scenario.expect.switches, Some(1));
+
+        let order: Vec<_> = scenario
+            .schedule()
+            .iter()
+            .map(|timed| timed.action.to_string())
+            .collect();
+        assert_eq!(
+            order,
+            [
+                "IC-7300 tunes to 7075000 Hz",
+                "switching mode set to Automatic",
+                "IC-7300 keys up",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_radio_is_rejected() {
+        let text = TWO_RADIOS.replace(r#""radio": "IC-7300", "on""#, r#""radio": "FT-991", "on""#);
+        assert_eq!(
+            Scenario::parse(&text).unwrap_err(),
+            "action at 2000 ms names unknown radio 'FT-991'"
+        );
+
+        let text = TWO_RADIOS.replace(r#""id": "IC-7300""#, r#""id": "K3""#);
+        assert_eq!(
+            Scenario::parse(&text).unwrap_err(),
+            "radio 'K3' is listed twice"
+        );
+    }
+}
@@ -4,9 +4,13 @@
 //! and can echo or respond to commands. Useful for testing multiplexer logic
 //! without real hardware.
 
+use std::sync::Arc;
+
 use cat_protocol::{OperatingMode, Protocol};
 use tracing::error;
 
+use crate::clock::{system_clock, Clock};
+
 /// Virtual amplifier for testing
 ///
 /// Tracks frequency/mode/PTT state based on commands received. Used by the
@@ -21,6 +25,8 @@ pub struct VirtualAmplifier {
     ptt: bool,
     /// Commands received (for test verification)
     received_commands: Vec<Vec<u8>>,
+    /// Time source for polling
+    clock: Arc<dyn Clock>,
 }
 
 impl VirtualAmplifier {
@@ -34,9 +40,21 @@ impl VirtualAmplifier {
             mode: OperatingMode::Usb,
             ptt: false,
             received_commands: Vec::new(),
+            clock: system_clock(),
         }
     }
 
+    /// Run the amplifier on another clock (e.g. a paused [`crate::SimClock`])
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The amplifier's time source
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
     /// Get the identifier
     pub fn id(&self) -> &str {
         &self.id
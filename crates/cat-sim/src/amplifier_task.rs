@@ -10,11 +10,10 @@ use std::io;
 use std::time::Duration;
 
 use cat_protocol::{
-    create_radio_codec, EncodeCommand, FromRadioRequest, OperatingMode, Protocol, RadioRequest,
+    EncodeCommand, FromRadioRequest, OperatingMode, Protocol, RadioRequest, RadioSideCodec,
 };
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::{broadcast, mpsc};
-use tokio::time::{interval, Interval};
 use tracing::{debug, info, warn};
 
 use crate::VirtualAmplifier;
@@ -71,7 +70,7 @@ pub async fn run_virtual_amp_task<S>(
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    let mut codec = create_radio_codec(amp.protocol());
+    let mut codec = RadioSideCodec::new(amp.protocol());
     let mut buf = [0u8; 1024];
 
     info!(
@@ -82,7 +81,9 @@ where
 
     // Set up based on mode
     let polling_enabled = mode == VirtualAmpMode::Polling;
-    let mut poll_timer: Interval = interval(Duration::from_millis(POLLING_INTERVAL_MS));
+    let poll_interval = Duration::from_millis(POLLING_INTERVAL_MS);
+    // The first poll goes out at once, like a tokio interval's first tick
+    let mut next_poll = amp.clock().now();
 
     // Send auto-info enable request if in AutoInfo mode
     if mode == VirtualAmpMode::AutoInfo {
@@ -114,6 +115,7 @@ where
     });
 
     loop {
+        let poll_sleep = amp.clock().sleep_until(next_poll);
         tokio::select! {
             // Read CAT commands from the connection stream
             result = stream.read(&mut buf) => {
@@ -172,7 +174,12 @@ where
             }
 
             // Polling timer - send frequency query when enabled
-            _ = poll_timer.tick(), if polling_enabled => {
+            _ = poll_sleep, if polling_enabled => {
+                // Skip missed polls (e.g. the clock was stepped) rather than bursting
+                let now = amp.clock().now();
+                while next_poll <= now {
+                    next_poll += poll_interval;
+                }
                 // Amps only care about frequency for band switching
                 if let Some(encoded) = encode_request(amp.protocol(), amp.civ_address(), &RadioRequest::GetFrequency) {
                     debug!(
@@ -332,4 +339,42 @@ mod tests {
 
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_polling_follows_sim_clock() {
+        let clock = crate::SimClock::paused();
+        let (mut connection_stream, amp_stream) = tokio::io::duplex(64 * 1024);
+        let amp = VirtualAmplifier::new("Test", Protocol::Kenwood, None)
+            .with_clock(std::sync::Arc::new(clock.clone()));
+        let (cmd_tx, cmd_rx) = mpsc::channel(32);
+        let (state_tx, _state_rx) = broadcast::channel(32);
+        let task_handle = tokio::spawn(run_virtual_amp_task(
+            amp_stream,
+            amp,
+            cmd_rx,
+            state_tx,
+            VirtualAmpMode::Polling,
+        ));
+
+        // The first poll goes out at start-up
+        let mut first = [0u8; 3];
+        tokio::io::AsyncReadExt::read_exact(&mut connection_stream, &mut first)
+            .await
+            .unwrap();
+        assert_eq!(&first, b"FA;");
+
+        // Five minutes of 500 ms polls, without waiting five minutes
+        clock
+            .run_for(Duration::from_secs(300), Duration::from_millis(500))
+            .await;
+        cmd_tx.send(VirtualAmpCommand::Shutdown).await.unwrap();
+        task_handle.await.unwrap().unwrap();
+
+        let mut output = Vec::new();
+        tokio::io::AsyncReadExt::read_to_end(&mut connection_stream, &mut output)
+            .await
+            .unwrap();
+        assert_eq!(output.len() % 3, 0);
+        assert_eq!(output.len() / 3, 600);
+    }
 }
@@ -0,0 +1,290 @@
+//! Simulation clock
+//!
+//! Everything time-driven in a simulation (periodic reports scripted by
+//! [`crate::ResponderRule::Periodic`], the virtual amplifier's polling, a
+//! radio's last-change time) reads time from a [`Clock`] rather than the
+//! system clock directly. [`SystemClock`] is the tokio runtime's time: real
+//! time, unless the runtime's clock is paused (as in tests and the headless
+//! scenario runner), when it moves only as tokio advances it. A [`SimClock`] can
+//! be paused, stepped and run faster than real time, so a test can play out
+//! minutes of polling in milliseconds and the Simulation panel can freeze
+//! the virtual devices while you look at something.
+//!
+//! Virtual time is still expressed as [`Instant`]s: a `SimClock` starts at
+//! the real time it was created and only drifts from it while paused or
+//! scaled, so deadlines computed from either clock compare sensibly.
+//!
+//! ```rust
+//! use std::time::Duration;
+//! use cat_sim::{Clock, SimClock};
+//!
+//! let clock = SimClock::paused();
+//! let start = clock.now();
+//! clock.advance(Duration::from_secs(300));
+//! assert_eq!(clock.now() - start, Duration::from_secs(300));
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use tokio::sync::watch;
+
+/// Future returned by [`Clock::sleep_until`]
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// A source of time for simulated devices
+pub trait Clock: Send + Sync + fmt::Debug {
+    /// The current time
+    fn now(&self) -> Instant;
+
+    /// Wait until the clock reaches `deadline`
+    fn sleep_until(&self, deadline: Instant) -> Sleep;
+}
+
+/// The runtime's time, as tokio sees it
+///
+/// Outside a runtime, or on one whose clock isn't paused, this is real time.
+fn runtime_now() -> Instant {
+    tokio::time::Instant::now().into_std()
+}
+
+/// Real time (or a paused runtime's time)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        runtime_now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Sleep {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+}
+
+/// Shared real-time clock
+pub fn system_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ClockState {
+    /// Real time at the last change of rate or pause state
+    real_anchor: Instant,
+    /// Virtual time at that moment
+    virtual_anchor: Instant,
+    /// Virtual seconds per real second
+    rate: f64,
+    paused: bool,
+}
+
+impl ClockState {
+    fn now(&self, real_now: Instant) -> Instant {
+        if self.paused {
+            return self.virtual_anchor;
+        }
+        let elapsed = real_now.saturating_duration_since(self.real_anchor);
+        self.virtual_anchor + elapsed.mul_f64(self.rate)
+    }
+
+    /// Move the anchors to the present so rate and pause changes apply from now
+    fn reanchor(&mut self, real_now: Instant) {
+        self.virtual_anchor = self.now(real_now);
+        self.real_anchor = real_now;
+    }
+}
+
+/// A clock that can be paused, stepped and sped up
+///
+/// Clones share the same time. Sleepers wake as soon as the clock is
+/// advanced past their deadline, whatever the rate.
+#[derive(Debug, Clone)]
+pub struct SimClock {
+    state: Arc<watch::Sender<ClockState>>,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SimClock {
+    /// A clock running at real-time speed
+    pub fn new() -> Self {
+        let now = runtime_now();
+        let (tx, _) = watch::channel(ClockState {
+            real_anchor: now,
+            virtual_anchor: now,
+            rate: 1.0,
+            paused: false,
+        });
+        Self {
+            state: Arc::new(tx),
+        }
+    }
+
+    /// A clock that only moves when [`Self::advance`]d
+    pub fn paused() -> Self {
+        let clock = Self::new();
+        clock.pause();
+        clock
+    }
+
+    /// Stop the clock
+    pub fn pause(&self) {
+        self.state.send_modify(|s| {
+            s.reanchor(runtime_now());
+            s.paused = true;
+        });
+    }
+
+    /// Start the clock again at the current rate
+    pub fn resume(&self) {
+        self.state.send_modify(|s| {
+            s.real_anchor = runtime_now();
+            s.paused = false;
+        });
+    }
+
+    /// Whether the clock is stopped
+    pub fn is_paused(&self) -> bool {
+        self.state.borrow().paused
+    }
+
+    /// Run at `rate` times real time (clamped to at least 0.01)
+    pub fn set_rate(&self, rate: f64) {
+        self.state.send_modify(|s| {
+            s.reanchor(runtime_now());
+            s.rate = rate.max(0.01);
+        });
+    }
+
+    /// Current speed relative to real time
+    pub fn rate(&self) -> f64 {
+        self.state.borrow().rate
+    }
+
+    /// Jump the clock forward, waking everything due in between
+    ///
+    /// Sleepers run against the new time at once, so a periodic report
+    /// scheduled several times within the step fires once (its task skips
+    /// missed intervals). Step in intervals no longer than the shortest
+    /// period when every firing matters; see [`Self::run_for`].
+    pub fn advance(&self, by: Duration) {
+        self.state.send_modify(|s| {
+            s.reanchor(runtime_now());
+            s.virtual_anchor += by;
+        });
+    }
+
+    /// Advance by `total` in `step`s, yielding to the runtime after each so
+    /// tasks waiting on the clock can run
+    pub async fn run_for(&self, total: Duration, step: Duration) {
+        let step = step.max(Duration::from_millis(1));
+        let mut elapsed = Duration::ZERO;
+        while elapsed < total {
+            let by = step.min(total - elapsed);
+            self.advance(by);
+            elapsed += by;
+            // Let woken tasks do their work before the next step
+            for _ in 0..8 {
+                tokio::task::yield_now().await;
+            }
+        }
+    }
+}
+
+impl Clock for SimClock {
+    fn now(&self) -> Instant {
+        self.state.borrow().now(runtime_now())
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> Sleep {
+        let mut rx = self.state.subscribe();
+        Box::pin(async move {
+            loop {
+                let state = *rx.borrow_and_update();
+                let real_now = runtime_now();
+                let now = state.now(real_now);
+                if now >= deadline {
+                    return;
+                }
+                if state.paused {
+                    if rx.changed().await.is_err() {
+                        // The clock is gone; nothing will ever advance it
+                        std::future::pending::<()>().await;
+                    }
+                    continue;
+                }
+                let real_wait = (deadline - now).div_f64(state.rate);
+                tokio::select! {
+                    _ = tokio::time::sleep(real_wait) => {}
+                    changed = rx.changed() => {
+                        if changed.is_err() {
+                            tokio::time::sleep(real_wait).await;
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_clock_only_moves_when_advanced() {
+        let clock = SimClock::paused();
+        let t0 = clock.now();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.now(), t0);
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(clock.now() - t0, Duration::from_secs(60));
+
+        // Clones share time
+        let other = clock.clone();
+        other.advance(Duration::from_secs(1));
+        assert_eq!(clock.now() - t0, Duration::from_secs(61));
+    }
+
+    #[test]
+    fn test_rate_scales_elapsed_time() {
+        let clock = SimClock::new();
+        clock.set_rate(100.0);
+        let t0 = clock.now();
+        std::thread::sleep(Duration::from_millis(20));
+        // At least 2 virtual seconds have passed
+        assert!(clock.now() - t0 >= Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_clocks_follow_a_paused_runtime() {
+        let system = SystemClock;
+        let sim = SimClock::new();
+        let (t0, s0) = (system.now(), sim.now());
+        tokio::time::sleep(Duration::from_secs(3600)).await;
+        assert_eq!(system.now() - t0, Duration::from_secs(3600));
+        assert_eq!(sim.now() - s0, Duration::from_secs(3600));
+    }
+
+    #[tokio::test]
+    async fn test_sleep_wakes_on_advance() {
+        let clock = SimClock::paused();
+        let deadline = clock.now() + Duration::from_secs(3600);
+        let sleep = tokio::spawn(clock.sleep_until(deadline));
+        tokio::task::yield_now().await;
+        assert!(!sleep.is_finished());
+
+        clock.advance(Duration::from_secs(3600));
+        tokio::time::timeout(Duration::from_millis(100), sleep)
+            .await
+            .expect("sleep should finish once the clock is advanced")
+            .unwrap();
+    }
+}
@@ -5,6 +5,9 @@
 //!
 //! - **VirtualRadio**: Simulates a radio with protocol-accurate encoding
 //! - **VirtualAmplifier**: Simulates an amplifier that tracks frequency/mode state
+//! - **ResponderRule**: Scripts virtual radio misbehavior (meter ramps,
+//!   dropped queries, unsolicited reports)
+//! - **SimClock**: Virtual time that can be paused, stepped and sped up
 //!
 //! # Example
 //!
@@ -28,12 +31,19 @@
 
 pub mod amplifier;
 pub mod amplifier_task;
+pub mod clock;
 pub mod radio;
 pub mod radio_task;
+pub mod rules;
 
 pub use amplifier::VirtualAmplifier;
 pub use amplifier_task::{
     run_virtual_amp_task, VirtualAmpCommand, VirtualAmpMode, VirtualAmpStateEvent,
 };
+pub use clock::{system_clock, Clock, SimClock, SystemClock};
 pub use radio::{VirtualRadio, VirtualRadioConfig};
 pub use radio_task::{run_virtual_radio_task, VirtualRadioCommand};
+pub use rules::{PeriodicReport, ResponderRule, RuleEngine, RuleOutcome};
+
+/// This crate's version, for reports
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
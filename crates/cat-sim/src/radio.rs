@@ -2,14 +2,28 @@
 //!
 //! Provides a simulated radio that generates protocol-accurate output
 //! when its state changes.
+//!
+//! The radio has two VFOs. `frequency_hz`/`mode` always describe the selected
+//! (receive) VFO and the other VFO is kept alongside, so selecting VFO B swaps
+//! the two. With split on, the radio transmits on the unselected VFO.
 
 use std::collections::VecDeque;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::clock::{system_clock, Clock, SystemClock};
+use crate::rules::{PeriodicReport, ResponderRule, RuleEngine, RuleOutcome};
 
 use cat_protocol::{
-    elecraft::ElecraftCommand, flex::FlexCommand, icom::CivCommand, kenwood::KenwoodCommand,
-    yaesu::YaesuCommand, yaesu_ascii::YaesuAsciiCommand, EncodeCommand, FromRadioResponse,
-    OperatingMode, Protocol, RadioDatabase, RadioModel, RadioRequest, RadioResponse,
+    display::format_frequency,
+    elecraft::ElecraftCommand,
+    flex::FlexCommand,
+    icom::{CivCommand, CivCommandType},
+    kenwood::KenwoodCommand,
+    yaesu::YaesuCommand,
+    yaesu_ascii::YaesuAsciiCommand,
+    EncodeCommand, Frequency, FromRadioResponse, OperatingMode, Protocol, RadioDatabase,
+    RadioModel, RadioRequest, RadioResponse, Receiver, Vfo, VfoState,
 };
 use serde::{Deserialize, Serialize};
 
@@ -22,10 +36,18 @@ pub struct VirtualRadio {
     protocol: Protocol,
     /// Radio model (for ID responses)
     model: Option<RadioModel>,
-    /// Current frequency in Hz
+    /// Frequency of the selected VFO in Hz
     frequency_hz: u64,
-    /// Current operating mode
+    /// Operating mode of the selected VFO
     mode: OperatingMode,
+    /// Frequency of the unselected VFO in Hz
+    other_frequency_hz: u64,
+    /// Operating mode of the unselected VFO
+    other_mode: OperatingMode,
+    /// Selected (receive) VFO, A or B
+    rx_vfo: Vfo,
+    /// Split: transmit on the unselected VFO
+    split: bool,
     /// PTT active state
     ptt: bool,
     /// CI-V address (for Icom protocol)
@@ -37,6 +59,12 @@ pub struct VirtualRadio {
     pending_output: VecDeque<Vec<u8>>,
     /// Last state change timestamp
     last_change: Instant,
+    /// Scripted auto-responder rules
+    rules: RuleEngine,
+    /// Time source for rules and change timestamps
+    clock: Arc<dyn Clock>,
+    /// Ignoring commands and sending nothing until then (injected fault)
+    silent_until: Option<Instant>,
 }
 
 /// Configuration for creating a virtual radio
@@ -55,6 +83,9 @@ pub struct VirtualRadioConfig {
     pub initial_mode: OperatingMode,
     /// CI-V address (for Icom protocol)
     pub civ_address: Option<u8>,
+    /// Auto-responder rules
+    #[serde(default)]
+    pub rules: Vec<ResponderRule>,
 }
 
 impl Default for VirtualRadioConfig {
@@ -66,6 +97,7 @@ impl Default for VirtualRadioConfig {
             initial_frequency_hz: 14_250_000, // 20m
             initial_mode: OperatingMode::Usb,
             civ_address: None,
+            rules: Vec::new(),
         }
     }
 }
@@ -87,11 +119,18 @@ impl VirtualRadio {
             model,
             frequency_hz: 14_250_000,
             mode: OperatingMode::Usb,
+            other_frequency_hz: 14_250_000,
+            other_mode: OperatingMode::Usb,
+            rx_vfo: Vfo::A,
+            split: false,
             ptt: false,
             civ_address,
             auto_info_enabled: false,
             pending_output: VecDeque::new(),
-            last_change: Instant::now(),
+            last_change: SystemClock.now(),
+            rules: RuleEngine::default(),
+            clock: system_clock(),
+            silent_until: None,
         }
     }
 
@@ -125,11 +164,18 @@ impl VirtualRadio {
             model,
             frequency_hz: config.initial_frequency_hz,
             mode: config.initial_mode,
+            other_frequency_hz: config.initial_frequency_hz,
+            other_mode: config.initial_mode,
+            rx_vfo: Vfo::A,
+            split: false,
             ptt: false,
             civ_address,
             auto_info_enabled: false,
             pending_output: VecDeque::new(),
-            last_change: Instant::now(),
+            last_change: SystemClock.now(),
+            rules: RuleEngine::new(config.rules),
+            clock: system_clock(),
+            silent_until: None,
         }
     }
 
@@ -195,15 +241,145 @@ impl VirtualRadio {
 
     /// Set the frequency and queue a protocol-encoded response if auto-info is enabled
     pub fn set_frequency(&mut self, hz: u64) {
-        if self.frequency_hz != hz {
-            self.frequency_hz = hz;
-            self.last_change = Instant::now();
+        self.set_vfo_frequency(self.rx_vfo, hz);
+    }
+
+    /// Get the selected (receive) VFO
+    pub fn rx_vfo(&self) -> Vfo {
+        self.rx_vfo
+    }
+
+    /// Get the split state
+    pub fn split(&self) -> bool {
+        self.split
+    }
+
+    /// Get a VFO's frequency in Hz
+    pub fn vfo_frequency_hz(&self, vfo: Vfo) -> u64 {
+        if vfo == self.rx_vfo {
+            self.frequency_hz
+        } else {
+            self.other_frequency_hz
+        }
+    }
+
+    /// Get a VFO's operating mode
+    pub fn vfo_mode(&self, vfo: Vfo) -> OperatingMode {
+        if vfo == self.rx_vfo {
+            self.mode
+        } else {
+            self.other_mode
+        }
+    }
+
+    /// Get the frequency the radio transmits on (the unselected VFO in split)
+    pub fn tx_frequency_hz(&self) -> u64 {
+        if self.split {
+            self.other_frequency_hz
+        } else {
+            self.frequency_hz
+        }
+    }
+
+    /// Set a VFO's frequency and queue a protocol-encoded response if auto-info is enabled
+    pub fn set_vfo_frequency(&mut self, vfo: Vfo, hz: u64) {
+        let current = if vfo == self.rx_vfo {
+            &mut self.frequency_hz
+        } else {
+            &mut self.other_frequency_hz
+        };
+        if *current != hz {
+            *current = hz;
+            self.last_change = self.clock.now();
+            if self.auto_info_enabled {
+                self.queue_vfo_frequency(vfo);
+            }
+        }
+    }
+
+    /// Set a VFO's operating mode
+    ///
+    /// Radios only report the selected VFO's mode, so changing the other VFO
+    /// is silent until it is selected.
+    pub fn set_vfo_mode(&mut self, vfo: Vfo, mode: OperatingMode) {
+        if vfo == self.rx_vfo {
+            self.set_mode(mode);
+        } else if self.other_mode != mode {
+            self.other_mode = mode;
+            self.last_change = self.clock.now();
+        }
+    }
+
+    /// Select the receive VFO (A or B) and queue responses if auto-info is enabled
+    ///
+    /// The selection is reported first (with split restated if on), followed
+    /// by the newly selected VFO's frequency and mode.
+    pub fn select_vfo(&mut self, vfo: Vfo) {
+        if !matches!(vfo, Vfo::A | Vfo::B) || vfo == self.rx_vfo {
+            return;
+        }
+        self.rx_vfo = vfo;
+        std::mem::swap(&mut self.frequency_hz, &mut self.other_frequency_hz);
+        std::mem::swap(&mut self.mode, &mut self.other_mode);
+        self.last_change = self.clock.now();
+        if self.auto_info_enabled {
+            self.queue_response(RadioResponse::VfoState {
+                state: VfoState::select(vfo),
+            });
+            // A selection report reads as split off; restate it
+            if self.split {
+                self.queue_split();
+            }
+            self.queue_vfo_frequency(vfo);
+            self.queue_response(RadioResponse::Mode { mode: self.mode });
+        }
+    }
+
+    /// Set the split state and queue a protocol-encoded response if auto-info is enabled
+    pub fn set_split(&mut self, on: bool) {
+        if self.split != on {
+            self.split = on;
+            self.last_change = self.clock.now();
             if self.auto_info_enabled {
-                self.queue_response(RadioResponse::Frequency { hz });
+                self.queue_split();
             }
         }
     }
 
+    /// Apply a VFO request: receive VFO (or receiver) first, then split
+    ///
+    /// Returns false if nothing in it applies (memory mode).
+    fn apply_vfo_state(&mut self, state: VfoState) -> bool {
+        let rx = match (state.rx_vfo, state.active_receiver) {
+            (Some(vfo @ (Vfo::A | Vfo::B)), _) => Some(vfo),
+            (None, Some(Receiver::Main)) => Some(Vfo::A),
+            (None, Some(Receiver::Sub)) => Some(Vfo::B),
+            _ => None,
+        };
+        if let Some(rx) = rx {
+            self.select_vfo(rx);
+        }
+        let split = match state.tx_vfo {
+            Some(tx @ (Vfo::A | Vfo::B)) => Some(tx != self.rx_vfo),
+            _ => state.split,
+        };
+        if let Some(split) = split {
+            self.set_split(split);
+        }
+        rx.is_some() || split.is_some()
+    }
+
+    /// Copy the selected VFO's frequency and mode to the other VFO (A=B)
+    pub fn equalize_vfos(&mut self) {
+        let other = if self.rx_vfo == Vfo::A {
+            Vfo::B
+        } else {
+            Vfo::A
+        };
+        self.other_mode = self.mode;
+        self.set_vfo_frequency(other, self.frequency_hz);
+    }
+
     /// Get the current operating mode
     pub fn mode(&self) -> OperatingMode {
         self.mode
@@ -213,7 +389,7 @@ impl VirtualRadio {
     pub fn set_mode(&mut self, mode: OperatingMode) {
         if self.mode != mode {
             self.mode = mode;
-            self.last_change = Instant::now();
+            self.last_change = self.clock.now();
             if self.auto_info_enabled {
                 self.queue_response(RadioResponse::Mode { mode });
             }
@@ -229,7 +405,7 @@ impl VirtualRadio {
     pub fn set_ptt(&mut self, active: bool) {
         if self.ptt != active {
             self.ptt = active;
-            self.last_change = Instant::now();
+            self.last_change = self.clock.now();
             if self.auto_info_enabled {
                 self.queue_response(RadioResponse::Ptt { active });
             }
@@ -261,6 +437,49 @@ impl VirtualRadio {
         }
     }
 
+    /// Get the auto-responder rules
+    pub fn rules(&self) -> &[ResponderRule] {
+        self.rules.rules()
+    }
+
+    /// Replace the auto-responder rules, restarting their counters and timers
+    pub fn set_rules(&mut self, rules: Vec<ResponderRule>) {
+        self.rules = RuleEngine::starting_at(rules, self.clock.now());
+    }
+
+    /// Run the radio on another clock (e.g. a paused [`crate::SimClock`]),
+    /// restarting rule timers from its current time
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self.last_change = self.clock.now();
+        self.set_rules(self.rules.rules().to_vec());
+        self
+    }
+
+    /// The radio's time source
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// When the next periodic rule report is due
+    pub fn next_rule_deadline(&self) -> Option<Instant> {
+        self.rules.next_deadline()
+    }
+
+    /// Queue the periodic rule reports due by `now`
+    pub fn poll_rules(&mut self, now: Instant) {
+        for report in self.rules.due_reports(now) {
+            match report {
+                PeriodicReport::Status => self.queue_status_frame(),
+                PeriodicReport::Frequency => self.queue_vfo_frequency(self.rx_vfo),
+                PeriodicReport::Mode => {
+                    self.queue_response(RadioResponse::Mode { mode: self.mode })
+                }
+                PeriodicReport::Ptt => self.queue_response(RadioResponse::Ptt { active: self.ptt }),
+            }
+        }
+    }
+
     /// Get the time of last state change
     pub fn last_change(&self) -> Instant {
         self.last_change
@@ -273,10 +492,83 @@ impl VirtualRadio {
         }
     }
 
+    /// Queue a VFO's frequency in the protocol's own form
+    ///
+    /// Kenwood-style protocols use FA/FB, CI-V reports the unselected VFO with
+    /// 0x25 0x01, and the Yaesu binary protocol can only report the selected
+    /// VFO.
+    fn queue_vfo_frequency(&mut self, vfo: Vfo) {
+        let hz = self.vfo_frequency_hz(vfo);
+        let selected = vfo == self.rx_vfo;
+        let kenwood = if vfo == Vfo::B {
+            KenwoodCommand::FrequencyB(Some(hz))
+        } else {
+            KenwoodCommand::FrequencyA(Some(hz))
+        };
+        let encoded = match self.protocol {
+            Protocol::Kenwood => Some(kenwood.encode()),
+            Protocol::Elecraft => Some(ElecraftCommand::Kenwood(kenwood).encode()),
+            Protocol::FlexRadio => Some(FlexCommand::Kenwood(kenwood).encode()),
+            Protocol::YaesuAscii => Some(
+                if vfo == Vfo::B {
+                    YaesuAsciiCommand::FrequencyB(Some(hz))
+                } else {
+                    YaesuAsciiCommand::FrequencyA(Some(hz))
+                }
+                .encode(),
+            ),
+            Protocol::IcomCIV if !selected => {
+                let addr = self.civ_address.unwrap_or(0x94);
+                Some(
+                    CivCommand::new(0xE0, addr, CivCommandType::UnselectedFrequency { hz })
+                        .encode(),
+                )
+            }
+            Protocol::IcomCIV | Protocol::Yaesu => selected
+                .then(|| {
+                    self.encode_response(&RadioResponse::Frequency {
+                        hz: Frequency::from_hz(hz),
+                    })
+                })
+                .flatten(),
+        };
+        if let Some(encoded) = encoded {
+            self.pending_output.push_back(encoded);
+        }
+    }
+
+    /// Queue the split state (transmit VFO for Kenwood-style protocols,
+    /// ST for Yaesu ASCII, 0x0F for CI-V)
+    fn queue_split(&mut self) {
+        let on = self.split;
+        // FT names the transmit VFO: the other one while split
+        let ft = (self.rx_vfo == Vfo::B) != on;
+        let encoded = match self.protocol {
+            Protocol::Kenwood => Some(KenwoodCommand::Split(Some(ft)).encode()),
+            Protocol::Elecraft => {
+                Some(ElecraftCommand::Kenwood(KenwoodCommand::Split(Some(ft))).encode())
+            }
+            Protocol::FlexRadio => {
+                Some(FlexCommand::Kenwood(KenwoodCommand::Split(Some(ft))).encode())
+            }
+            Protocol::YaesuAscii => Some(YaesuAsciiCommand::Split(Some(on)).encode()),
+            Protocol::IcomCIV => {
+                let addr = self.civ_address.unwrap_or(0x94);
+                Some(CivCommand::new(0xE0, addr, CivCommandType::Split { on }).encode())
+            }
+            Protocol::Yaesu => self.encode_response(&RadioResponse::VfoState {
+                state: VfoState::with_split(on),
+            }),
+        };
+        if let Some(encoded) = encoded {
+            self.pending_output.push_back(encoded);
+        }
+    }
+
     /// Send a full status report
     pub fn send_status_report(&mut self) {
         let resp = RadioResponse::Status {
-            frequency_hz: Some(self.frequency_hz),
+            frequency_hz: Some(Frequency::from_hz(self.frequency_hz)),
             mode: Some(self.mode),
             ptt: Some(self.ptt),
             vfo: None,
@@ -284,6 +576,37 @@ impl VirtualRadio {
         self.queue_response(resp);
     }
 
+    /// Queue a full status frame in the protocol's own form
+    ///
+    /// Kenwood-style protocols get a complete `IF` report (the generic
+    /// status encoding only carries the frequency); others use their status
+    /// encoding.
+    fn queue_status_frame(&mut self) {
+        let mode =
+            match KenwoodCommand::from_radio_response(&RadioResponse::Mode { mode: self.mode }) {
+                Some(KenwoodCommand::Mode(Some(m))) => m,
+                _ => 2,
+            };
+        let info = format!(
+            "IF{:011}     +0000000000{}{}{}0{}000;",
+            self.frequency_hz,
+            u8::from(self.ptt),
+            char::from_digit(u32::from(mode), 36)
+                .unwrap_or('2')
+                .to_ascii_uppercase(),
+            u8::from(self.rx_vfo == Vfo::B),
+            u8::from(self.split),
+        );
+        match self.protocol {
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio => {
+                self.pending_output.push_back(info.into_bytes());
+            }
+            Protocol::IcomCIV | Protocol::Yaesu | Protocol::YaesuAscii => {
+                self.send_status_report();
+            }
+        }
+    }
+
     /// Send an ID response based on the current model
     pub fn send_id_response(&mut self) {
         let id = self.get_id_string();
@@ -314,11 +637,45 @@ impl VirtualRadio {
         }
     }
 
-    /// Take the next pending output bytes
+    /// Take the next pending output bytes (none while silent)
     pub fn take_output(&mut self) -> Option<Vec<u8>> {
+        if self.is_silent() {
+            self.pending_output.clear();
+            return None;
+        }
         self.pending_output.pop_front()
     }
 
+    /// Stop answering and reporting for `duration` of simulation time, as a
+    /// radio does when it's switched off or its cable is pulled
+    pub fn go_silent(&mut self, duration: Duration) {
+        self.silent_until = Some(self.clock.now() + duration);
+        self.pending_output.clear();
+    }
+
+    /// Whether the radio is ignoring commands and sending nothing
+    pub fn is_silent(&self) -> bool {
+        self.silent_until
+            .is_some_and(|until| self.clock.now() < until)
+    }
+
+    /// Queue a frame the mux can't decode: a frequency report with a letter
+    /// among its digits, or a binary frame cut short
+    pub fn send_malformed_frame(&mut self) {
+        let frame = match self.protocol {
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::YaesuAscii => {
+                b"FA0001407X000;".to_vec()
+            }
+            Protocol::FlexRadio => b"ZZFA0001407X000;".to_vec(),
+            Protocol::IcomCIV => {
+                let addr = self.civ_address.unwrap_or(0x94);
+                vec![0xFE, 0xFE, 0xE0, addr, 0x00, 0x00, 0x40]
+            }
+            Protocol::Yaesu => vec![0x01, 0x40, 0x70],
+        };
+        self.pending_output.push_back(frame);
+    }
+
     /// Check if there is pending output
     pub fn has_output(&self) -> bool {
         !self.pending_output.is_empty()
@@ -356,8 +713,7 @@ impl VirtualRadio {
 
     /// Format frequency for display
     pub fn frequency_display(&self) -> String {
-        let mhz = self.frequency_hz as f64 / 1_000_000.0;
-        format!("{:.3} MHz", mhz)
+        format_frequency(Frequency::from_hz(self.frequency_hz))
     }
 
     /// Format mode for display
@@ -377,27 +733,69 @@ impl VirtualRadio {
         )
     }
 
+    /// Handle an incoming command frame, applying the auto-responder rules
+    /// before the normal request handling
+    ///
+    /// Returns true if the command was handled (a dropped command counts,
+    /// as does one ignored while silent).
+    pub fn handle_command(&mut self, req: &RadioRequest, frame: &[u8]) -> bool {
+        if self.is_silent() {
+            return true;
+        }
+        match self.rules.on_command(self.protocol, frame) {
+            // FA/FB name their VFO, which the normalized request doesn't
+            RuleOutcome::Pass => match (req, self.addressed_vfo(frame)) {
+                (RadioRequest::GetFrequency, Some(vfo)) => {
+                    self.queue_vfo_frequency(vfo);
+                    true
+                }
+                (RadioRequest::SetFrequency { hz }, Some(vfo)) => {
+                    self.set_vfo_frequency(vfo, hz.hz());
+                    true
+                }
+                _ => self.handle_request(req),
+            },
+            RuleOutcome::Drop => true,
+            RuleOutcome::Respond(resp) => {
+                self.queue_response(resp);
+                true
+            }
+        }
+    }
+
+    /// VFO an `FA`/`FB` frame is for, in the protocols that have them
+    fn addressed_vfo(&self, frame: &[u8]) -> Option<Vfo> {
+        let command = match self.protocol {
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::YaesuAscii => frame,
+            Protocol::FlexRadio => frame.strip_prefix(b"ZZ").unwrap_or(frame),
+            Protocol::IcomCIV | Protocol::Yaesu => return None,
+        };
+        match command.get(..2)? {
+            b"FA" => Some(Vfo::A),
+            b"FB" => Some(Vfo::B),
+            _ => None,
+        }
+    }
+
     /// Handle an incoming RadioRequest and generate appropriate responses
     /// Returns true if the request was handled
     pub fn handle_request(&mut self, req: &RadioRequest) -> bool {
         match req {
             RadioRequest::SetFrequency { hz } => {
-                self.frequency_hz = *hz;
-                self.last_change = Instant::now();
+                self.frequency_hz = hz.hz();
+                self.last_change = self.clock.now();
                 if self.auto_info_enabled {
-                    self.queue_response(RadioResponse::Frequency { hz: *hz });
+                    self.queue_vfo_frequency(self.rx_vfo);
                 }
                 true
             }
             RadioRequest::GetFrequency => {
-                self.queue_response(RadioResponse::Frequency {
-                    hz: self.frequency_hz,
-                });
+                self.queue_vfo_frequency(self.rx_vfo);
                 true
             }
             RadioRequest::SetMode { mode } => {
                 self.mode = *mode;
-                self.last_change = Instant::now();
+                self.last_change = self.clock.now();
                 if self.auto_info_enabled {
                     self.queue_response(RadioResponse::Mode { mode: *mode });
                 }
@@ -407,14 +805,37 @@ impl VirtualRadio {
                 self.queue_response(RadioResponse::Mode { mode: self.mode });
                 true
             }
+            RadioRequest::SetFrequencyMode { hz, mode } => {
+                self.frequency_hz = hz.hz();
+                self.mode = *mode;
+                self.last_change = self.clock.now();
+                if self.auto_info_enabled {
+                    self.queue_response(RadioResponse::Frequency { hz: *hz });
+                    self.queue_response(RadioResponse::Mode { mode: *mode });
+                }
+                true
+            }
             RadioRequest::SetPtt { active } => {
                 self.ptt = *active;
-                self.last_change = Instant::now();
+                self.last_change = self.clock.now();
                 if self.auto_info_enabled {
                     self.queue_response(RadioResponse::Ptt { active: *active });
                 }
                 true
             }
+            RadioRequest::SetVfo { .. } | RadioRequest::SetVfoState { .. } => req
+                .vfo_state()
+                .is_some_and(|state| self.apply_vfo_state(state)),
+            RadioRequest::GetVfo => {
+                if self.split {
+                    self.queue_split();
+                } else {
+                    self.queue_response(RadioResponse::VfoState {
+                        state: VfoState::select(self.rx_vfo),
+                    });
+                }
+                true
+            }
             RadioRequest::GetPtt => {
                 self.queue_response(RadioResponse::Ptt { active: self.ptt });
                 true
@@ -457,6 +878,43 @@ mod tests {
         assert!(!radio.ptt());
     }
 
+    #[test]
+    fn test_silent_radio_ignores_commands() {
+        let clock = crate::SimClock::paused();
+        let mut radio =
+            VirtualRadio::new("Test", Protocol::Kenwood).with_clock(Arc::new(clock.clone()));
+        radio.go_silent(Duration::from_secs(5));
+
+        assert!(radio.handle_command(
+            &RadioRequest::SetFrequency {
+                hz: Frequency::from_hz(7_074_000)
+            },
+            b"FA00007074000;"
+        ));
+        radio.handle_command(&RadioRequest::GetFrequency, b"FA;");
+        assert_eq!(radio.frequency_hz(), 14_250_000);
+        assert_eq!(radio.take_output(), None);
+
+        clock.advance(Duration::from_secs(5));
+        assert!(!radio.is_silent());
+        radio.handle_command(&RadioRequest::GetFrequency, b"FA;");
+        assert_eq!(radio.take_output(), Some(b"FA00014250000;".to_vec()));
+    }
+
+    #[test]
+    fn test_malformed_frame_does_not_decode() {
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.send_malformed_frame();
+        let frame = radio.take_output().unwrap();
+
+        let mut codec = cat_protocol::create_radio_codec(Protocol::Kenwood);
+        codec.push_bytes(&frame);
+        assert!(matches!(
+            codec.next_response(),
+            Some(RadioResponse::Unknown { .. }) | None
+        ));
+    }
+
     #[test]
     fn test_set_frequency_no_output_without_auto_info() {
         let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
@@ -627,6 +1085,109 @@ mod tests {
         assert_eq!(radio.output_count(), 0);
     }
 
+    #[test]
+    fn test_dual_vfo_kenwood_output() {
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.set_auto_info(true);
+        radio.clear_output();
+
+        radio.set_vfo_frequency(Vfo::B, 14_255_000);
+        assert_eq!(radio.take_output().unwrap(), b"FB00014255000;");
+        // The other VFO's mode is not reported until it is selected
+        radio.set_vfo_mode(Vfo::B, OperatingMode::Cw);
+        assert!(!radio.has_output());
+
+        radio.set_split(true);
+        assert_eq!(radio.take_output().unwrap(), b"FT1;");
+        assert_eq!(radio.tx_frequency_hz(), 14_255_000);
+
+        radio.select_vfo(Vfo::B);
+        assert_eq!(radio.take_output().unwrap(), b"FR1;");
+        // Still split: transmitting on A
+        assert_eq!(radio.take_output().unwrap(), b"FT0;");
+        assert_eq!(radio.take_output().unwrap(), b"FB00014255000;");
+        assert_eq!(radio.take_output().unwrap(), b"MD3;");
+        assert_eq!(radio.frequency_hz(), 14_255_000);
+        assert_eq!(radio.vfo_frequency_hz(Vfo::A), 14_250_000);
+        assert_eq!(radio.tx_frequency_hz(), 14_250_000);
+
+        // Tuning now moves VFO B
+        radio.set_frequency(14_256_000);
+        assert_eq!(radio.take_output().unwrap(), b"FB00014256000;");
+    }
+
+    #[test]
+    fn test_dual_vfo_icom_output() {
+        let mut radio = VirtualRadio::new("IC-7300", Protocol::IcomCIV);
+        radio.set_civ_address(Some(0x94));
+        radio.set_auto_info(true);
+        radio.clear_output();
+
+        radio.set_vfo_frequency(Vfo::B, 7_010_000);
+        let output = radio.take_output().unwrap();
+        assert_eq!(output[..6], [0xFE, 0xFE, 0xE0, 0x94, 0x25, 0x01]);
+
+        radio.set_split(true);
+        assert_eq!(
+            radio.take_output().unwrap(),
+            [0xFE, 0xFE, 0xE0, 0x94, 0x0F, 0x01, 0xFD]
+        );
+    }
+
+    #[test]
+    fn test_handle_vfo_requests() {
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.set_vfo_frequency(Vfo::B, 7_020_000);
+
+        assert!(radio.handle_request(&RadioRequest::SetVfo { vfo: Vfo::B }));
+        assert_eq!(radio.rx_vfo(), Vfo::B);
+        assert_eq!(radio.frequency_hz(), 7_020_000);
+        assert!(radio.handle_request(&RadioRequest::GetFrequency));
+        assert_eq!(radio.take_output().unwrap(), b"FB00007020000;");
+
+        assert!(radio.handle_request(&RadioRequest::SetVfo { vfo: Vfo::Split }));
+        assert!(radio.split());
+        // Receiving on B, so split transmits on A
+        assert!(radio.handle_request(&RadioRequest::GetVfo));
+        assert_eq!(radio.take_output().unwrap(), b"FT0;");
+
+        // FT1 while receiving on B ends split
+        assert!(radio.handle_request(&RadioRequest::SetVfoState {
+            state: VfoState::transmit_on(Vfo::B)
+        }));
+        assert!(!radio.split());
+    }
+
+    #[test]
+    fn test_frequency_query_answers_the_vfo_asked_for() {
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.set_vfo_frequency(Vfo::B, 7_020_000);
+        radio.select_vfo(Vfo::B);
+        radio.clear_output();
+
+        // Receiving on B, FA; still reads VFO A
+        assert!(radio.handle_command(&RadioRequest::GetFrequency, b"FA;"));
+        assert_eq!(radio.take_output().unwrap(), b"FA00014250000;");
+        assert!(radio.handle_command(&RadioRequest::GetFrequency, b"FB;"));
+        assert_eq!(radio.take_output().unwrap(), b"FB00007020000;");
+
+        // And FA sets VFO A, not the selected one
+        let set_a = RadioRequest::SetFrequency {
+            hz: Frequency::from_hz(14_074_000),
+        };
+        assert!(radio.handle_command(&set_a, b"FA00014074000;"));
+        assert_eq!(radio.vfo_frequency_hz(Vfo::A), 14_074_000);
+        assert_eq!(radio.frequency_hz(), 7_020_000);
+
+        let mut flex = VirtualRadio::new("Flex", Protocol::FlexRadio);
+        flex.set_vfo_frequency(Vfo::B, 7_020_000);
+        flex.clear_output();
+        assert!(flex.handle_command(&RadioRequest::GetFrequency, b"ZZFB;"));
+        assert!(String::from_utf8(flex.take_output().unwrap())
+            .unwrap()
+            .contains("07020000"));
+    }
+
     #[test]
     fn test_from_config() {
         let config = VirtualRadioConfig {
@@ -636,6 +1197,7 @@ mod tests {
             initial_frequency_hz: 10_125_000,
             initial_mode: OperatingMode::Cw,
             civ_address: None,
+            rules: Vec::new(),
         };
 
         let radio = VirtualRadio::from_config(config);
@@ -645,4 +1207,41 @@ mod tests {
         assert_eq!(radio.mode(), OperatingMode::Cw);
         assert_eq!(radio.model_name(), "K3");
     }
+
+    #[test]
+    fn test_rules_drop_queries_and_send_periodic_status() {
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.set_rules(vec![
+            ResponderRule::DropEvery {
+                command: "FA".to_string(),
+                every: 2,
+            },
+            ResponderRule::Periodic {
+                report: PeriodicReport::Status,
+                interval_ms: 500,
+            },
+        ]);
+
+        assert!(radio.handle_command(&RadioRequest::GetFrequency, b"FA;"));
+        assert_eq!(radio.take_output().unwrap(), b"FA00014250000;");
+        assert!(radio.handle_command(&RadioRequest::GetFrequency, b"FA;"));
+        assert!(!radio.has_output());
+
+        // The periodic IF parses back as a full status report
+        let due = radio.next_rule_deadline().unwrap();
+        radio.poll_rules(due);
+        let frame = radio.take_output().unwrap();
+        let mut codec: Box<dyn cat_protocol::RadioCodec> =
+            Box::new(cat_protocol::kenwood::KenwoodCodec::new());
+        codec.push_bytes(&frame);
+        assert_eq!(
+            codec.next_response(),
+            Some(RadioResponse::Status {
+                frequency_hz: Some(Frequency::from_hz(14_250_000)),
+                mode: Some(OperatingMode::Usb),
+                ptt: Some(false),
+                vfo: Some(Vfo::A),
+            })
+        );
+    }
 }
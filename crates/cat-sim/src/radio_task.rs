@@ -4,16 +4,18 @@
 //! via an async stream. The task uses a select! loop to:
 //! - Read CAT commands from the connection stream and process them
 //! - Handle UI commands from a channel for state changes
+//! - Send the periodic reports scripted by auto-responder rules
 //! - Write protocol-encoded responses back to the stream
 
 use std::io;
+use std::time::Duration;
 
-use cat_protocol::{create_radio_codec, OperatingMode, RadioModel};
+use cat_protocol::{ControllerSideCodec, Frequency, OperatingMode, RadioModel, RadioResponse, Vfo};
 use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
-use crate::VirtualRadio;
+use crate::{ResponderRule, VirtualRadio};
 
 /// Commands that can be sent from the UI to a virtual radio actor
 #[derive(Debug)]
@@ -24,8 +26,26 @@ pub enum VirtualRadioCommand {
     SetMode(OperatingMode),
     /// Set the radio's PTT state
     SetPtt(bool),
+    /// Set a VFO's frequency in Hz
+    SetVfoFrequency(Vfo, u64),
+    /// Select the receive VFO (A or B)
+    SelectVfo(Vfo),
+    /// Turn split on or off
+    SetSplit(bool),
+    /// Copy the selected VFO to the other one (A=B)
+    EqualizeVfos,
     /// Set the radio's model
     SetModel(Option<RadioModel>),
+    /// Replace the radio's auto-responder rules
+    SetRules(Vec<ResponderRule>),
+    /// Fault: stop answering and reporting for this long (simulation time)
+    GoSilent(Duration),
+    /// Fault: send a frame that doesn't decode
+    SendMalformedFrame,
+    /// Fault: report this frequency without tuning to it
+    ReportFrequency(u64),
+    /// Fault: report this mode without changing to it
+    ReportMode(OperatingMode),
     /// Shutdown the virtual radio actor
     Shutdown,
 }
@@ -45,7 +65,7 @@ pub async fn run_virtual_radio_task<S>(
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
-    let mut codec = create_radio_codec(radio.protocol());
+    let mut codec = ControllerSideCodec::new(radio.protocol());
     let mut buf = [0u8; 1024];
 
     info!(
@@ -55,6 +75,9 @@ where
     );
 
     loop {
+        let rule_sleep = radio
+            .next_rule_deadline()
+            .map(|deadline| radio.clock().sleep_until(deadline));
         tokio::select! {
             // Read CAT commands from the connection stream
             result = stream.read(&mut buf) => {
@@ -72,9 +95,9 @@ where
 
                         // Parse bytes into requests using the codec
                         codec.push_bytes(data);
-                        while let Some(req) = codec.next_request() {
+                        while let Some((req, frame)) = codec.next_request_with_bytes() {
                             debug!("Virtual radio {} processing request: {:?}", radio.id(), req);
-                            radio.handle_request(&req);
+                            radio.handle_command(&req, &frame);
                         }
 
                         // Write any pending output (responses) to the stream
@@ -111,10 +134,46 @@ where
                         debug!("Virtual radio {} setting PTT to {}", radio.id(), ptt);
                         radio.set_ptt(ptt);
                     }
+                    Some(VirtualRadioCommand::SetVfoFrequency(vfo, hz)) => {
+                        debug!("Virtual radio {} setting VFO {:?} to {} Hz", radio.id(), vfo, hz);
+                        radio.set_vfo_frequency(vfo, hz);
+                    }
+                    Some(VirtualRadioCommand::SelectVfo(vfo)) => {
+                        debug!("Virtual radio {} selecting VFO {:?}", radio.id(), vfo);
+                        radio.select_vfo(vfo);
+                    }
+                    Some(VirtualRadioCommand::SetSplit(on)) => {
+                        debug!("Virtual radio {} setting split to {}", radio.id(), on);
+                        radio.set_split(on);
+                    }
+                    Some(VirtualRadioCommand::EqualizeVfos) => {
+                        debug!("Virtual radio {} copying selected VFO to the other", radio.id());
+                        radio.equalize_vfos();
+                    }
                     Some(VirtualRadioCommand::SetModel(model)) => {
                         debug!("Virtual radio {} setting model to {:?}", radio.id(), model);
                         radio.set_model(model);
                     }
+                    Some(VirtualRadioCommand::SetRules(rules)) => {
+                        debug!("Virtual radio {} setting {} rules", radio.id(), rules.len());
+                        radio.set_rules(rules);
+                    }
+                    Some(VirtualRadioCommand::GoSilent(duration)) => {
+                        info!("Virtual radio {} going silent for {:?}", radio.id(), duration);
+                        radio.go_silent(duration);
+                    }
+                    Some(VirtualRadioCommand::SendMalformedFrame) => {
+                        info!("Virtual radio {} sending a malformed frame", radio.id());
+                        radio.send_malformed_frame();
+                    }
+                    Some(VirtualRadioCommand::ReportFrequency(hz)) => {
+                        info!("Virtual radio {} falsely reporting {} Hz", radio.id(), hz);
+                        radio.queue_response(RadioResponse::Frequency { hz: Frequency::from_hz(hz) });
+                    }
+                    Some(VirtualRadioCommand::ReportMode(mode)) => {
+                        info!("Virtual radio {} falsely reporting {:?}", radio.id(), mode);
+                        radio.queue_response(RadioResponse::Mode { mode });
+                    }
                     Some(VirtualRadioCommand::Shutdown) => {
                         info!("Shutdown requested for virtual radio {}", radio.id());
                         break;
@@ -137,6 +196,27 @@ where
                     }
                 }
             }
+
+            // Send periodic reports scripted by rules
+            _ = async {
+                match rule_sleep {
+                    Some(sleep) => sleep.await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                let now = radio.clock().now();
+                radio.poll_rules(now);
+                while let Some(output) = radio.take_output() {
+                    debug!(
+                        "Virtual radio {} rule output {} bytes: {:02X?}",
+                        radio.id(), output.len(), output
+                    );
+                    if let Err(e) = stream.write_all(&output).await {
+                        warn!("Failed to write rule output to virtual radio stream: {}", e);
+                        return Err(e);
+                    }
+                }
+            }
         }
     }
 
@@ -236,4 +316,60 @@ mod tests {
         let _ = cmd_tx.send(VirtualRadioCommand::Shutdown).await;
         let _ = task_handle.await;
     }
+
+    #[tokio::test]
+    async fn test_virtual_radio_sends_periodic_rule_reports() {
+        let (mut connection_stream, radio_stream) = tokio::io::duplex(1024);
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood);
+        radio.set_rules(vec![ResponderRule::Periodic {
+            report: crate::PeriodicReport::Status,
+            interval_ms: 20,
+        }]);
+        let (cmd_tx, cmd_rx) = mpsc::channel(32);
+        let task_handle = tokio::spawn(run_virtual_radio_task(radio_stream, radio, cmd_rx));
+
+        // Unsolicited IF without any query or auto-info
+        let mut response = vec![0u8; 64];
+        let n = tokio::time::timeout(
+            std::time::Duration::from_millis(500),
+            connection_stream.read(&mut response),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(response[..n].starts_with(b"IF00014250000"));
+
+        let _ = cmd_tx.send(VirtualRadioCommand::Shutdown).await;
+        let _ = task_handle.await;
+    }
+
+    #[tokio::test]
+    async fn test_five_minutes_of_periodic_reports_on_a_sim_clock() {
+        let clock = crate::SimClock::paused();
+        let (mut connection_stream, radio_stream) = tokio::io::duplex(64 * 1024);
+        let mut radio = VirtualRadio::new("Test", Protocol::Kenwood)
+            .with_clock(std::sync::Arc::new(clock.clone()));
+        radio.set_rules(vec![ResponderRule::Periodic {
+            report: crate::PeriodicReport::Frequency,
+            interval_ms: 1000,
+        }]);
+        let (cmd_tx, cmd_rx) = mpsc::channel(32);
+        let task_handle = tokio::spawn(run_virtual_radio_task(radio_stream, radio, cmd_rx));
+
+        let started = std::time::Instant::now();
+        clock
+            .run_for(
+                std::time::Duration::from_secs(300),
+                std::time::Duration::from_secs(1),
+            )
+            .await;
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+
+        let _ = cmd_tx.send(VirtualRadioCommand::Shutdown).await;
+        let _ = task_handle.await;
+        let mut output = Vec::new();
+        connection_stream.read_to_end(&mut output).await.unwrap();
+        let reports = output.split(|b| *b == b';').filter(|f| !f.is_empty());
+        assert_eq!(reports.count(), 300);
+    }
 }
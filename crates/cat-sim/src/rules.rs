@@ -0,0 +1,393 @@
+//! Auto-responder rules for virtual radios
+//!
+//! Real radios misbehave in ways that are hard to reproduce on the bench:
+//! an S-meter that never settles, a rig that silently drops every fifth
+//! `FA;` poll, firmware that spews `IF` reports twice a second whether or
+//! not auto-info is on. A [`ResponderRule`] describes one such behavior and
+//! a [`RuleEngine`] applies a set of them to a [`VirtualRadio`]'s traffic,
+//! so regression tests (and the Simulation controls) can replay it.
+//!
+//! Rules match incoming commands by their protocol-level name: the command
+//! letters for ASCII protocols (`"SM"`, `"FA"`), the command and sub-command
+//! bytes in hex for CI-V (`"15 02"`), and the opcode in hex for the Yaesu
+//! binary protocol (`"03"`).
+//!
+//! [`VirtualRadio`]: crate::VirtualRadio
+
+use std::time::{Duration, Instant};
+
+use cat_protocol::{MeterKind, Protocol, RadioResponse};
+use serde::{Deserialize, Serialize};
+
+use crate::clock::{Clock, SystemClock};
+
+/// Report a [`ResponderRule::Periodic`] rule sends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PeriodicReport {
+    /// Full status (`IF` for Kenwood-style protocols)
+    Status,
+    /// Selected VFO frequency
+    Frequency,
+    /// Operating mode
+    Mode,
+    /// PTT state
+    Ptt,
+}
+
+impl PeriodicReport {
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Status => "status",
+            Self::Frequency => "frequency",
+            Self::Mode => "mode",
+            Self::Ptt => "PTT",
+        }
+    }
+}
+
+/// One scripted behavior of a virtual radio
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ResponderRule {
+    /// Answer a command with a meter reading that climbs by `step` each
+    /// time and wraps back to zero past full scale
+    MeterRamp {
+        command: String,
+        meter: MeterKind,
+        step: u8,
+    },
+    /// Ignore every `every`th matching command (no reply, no state change)
+    DropEvery { command: String, every: u32 },
+    /// Send a report unsolicited every `interval_ms`, regardless of auto-info
+    Periodic {
+        report: PeriodicReport,
+        interval_ms: u64,
+    },
+}
+
+impl ResponderRule {
+    /// One-line description for display
+    pub fn describe(&self) -> String {
+        match self {
+            Self::MeterRamp { command, step, .. } => {
+                format!("Answer {} with a ramp (step {})", command, step)
+            }
+            Self::DropEvery { command, every } => {
+                format!("Ignore every {} {}", ordinal(*every), command)
+            }
+            Self::Periodic {
+                report,
+                interval_ms,
+            } => format!("Send {} every {} ms", report.name(), interval_ms),
+        }
+    }
+
+    /// Ready-made rules for a protocol, with display labels
+    ///
+    /// The Yaesu binary protocol has no meter read, so it gets no ramp.
+    pub fn presets(protocol: Protocol) -> Vec<(&'static str, ResponderRule)> {
+        let (meter_cmd, freq_cmd) = match protocol {
+            Protocol::IcomCIV => (Some("15 02"), "03"),
+            Protocol::Yaesu => (None, "03"),
+            Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio | Protocol::YaesuAscii => {
+                (Some("SM"), "FA")
+            }
+        };
+        let mut presets = Vec::new();
+        if let Some(command) = meter_cmd {
+            presets.push((
+                "S-meter ramp",
+                Self::MeterRamp {
+                    command: command.to_string(),
+                    meter: MeterKind::Signal,
+                    step: 16,
+                },
+            ));
+        }
+        presets.push((
+            "Drop every 5th frequency query",
+            Self::DropEvery {
+                command: freq_cmd.to_string(),
+                every: 5,
+            },
+        ));
+        presets.push((
+            "Status every 500 ms",
+            Self::Periodic {
+                report: PeriodicReport::Status,
+                interval_ms: 500,
+            },
+        ));
+        presets
+    }
+
+    fn command(&self) -> Option<&str> {
+        match self {
+            Self::MeterRamp { command, .. } | Self::DropEvery { command, .. } => Some(command),
+            Self::Periodic { .. } => None,
+        }
+    }
+}
+
+/// What to do with an incoming command
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleOutcome {
+    /// No rule applies; handle it normally
+    Pass,
+    /// Swallow it without replying
+    Drop,
+    /// Reply with this instead of handling it
+    Respond(RadioResponse),
+}
+
+/// Per-rule running state
+#[derive(Debug, Clone, Default)]
+struct RuleState {
+    /// Matching commands seen
+    hits: u64,
+    /// Next meter level to report
+    level: u8,
+    /// When a periodic report is next due
+    next_due: Option<Instant>,
+}
+
+/// Applies a set of [`ResponderRule`]s to a virtual radio's traffic
+#[derive(Debug, Clone, Default)]
+pub struct RuleEngine {
+    rules: Vec<ResponderRule>,
+    states: Vec<RuleState>,
+}
+
+impl RuleEngine {
+    /// Start a set of rules; periodic reports are first due one interval from
+    /// now on the [`SystemClock`]
+    pub fn new(rules: Vec<ResponderRule>) -> Self {
+        Self::starting_at(rules, SystemClock.now())
+    }
+
+    /// Start a set of rules at `now` (as read from a simulation clock)
+    pub fn starting_at(rules: Vec<ResponderRule>, now: Instant) -> Self {
+        let states = rules
+            .iter()
+            .map(|rule| RuleState {
+                next_due: match rule {
+                    ResponderRule::Periodic { interval_ms, .. } => {
+                        Some(now + periodic_interval(*interval_ms))
+                    }
+                    _ => None,
+                },
+                ..RuleState::default()
+            })
+            .collect();
+        Self { rules, states }
+    }
+
+    /// The configured rules
+    pub fn rules(&self) -> &[ResponderRule] {
+        &self.rules
+    }
+
+    /// Whether there are no rules
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Decide what to do with an incoming command frame
+    ///
+    /// Rules are tried in order and the first one that acts wins; a
+    /// [`ResponderRule::DropEvery`] that lets a command through leaves it to
+    /// the rules after it.
+    pub fn on_command(&mut self, protocol: Protocol, frame: &[u8]) -> RuleOutcome {
+        for (rule, state) in self.rules.iter().zip(self.states.iter_mut()) {
+            let Some(command) = rule.command() else {
+                continue;
+            };
+            if !command_matches(protocol, command, frame) {
+                continue;
+            }
+            state.hits += 1;
+            match rule {
+                ResponderRule::MeterRamp { meter, step, .. } => {
+                    let level = state.level;
+                    state.level = level.checked_add(*step).unwrap_or(0);
+                    return RuleOutcome::Respond(RadioResponse::Meter {
+                        meter: *meter,
+                        level,
+                    });
+                }
+                ResponderRule::DropEvery { every, .. } => {
+                    if *every > 0 && state.hits % u64::from(*every) == 0 {
+                        return RuleOutcome::Drop;
+                    }
+                }
+                ResponderRule::Periodic { .. } => {}
+            }
+        }
+        RuleOutcome::Pass
+    }
+
+    /// Periodic reports due by `now`, rescheduling each for its next interval
+    pub fn due_reports(&mut self, now: Instant) -> Vec<PeriodicReport> {
+        let mut due = Vec::new();
+        for (rule, state) in self.rules.iter().zip(self.states.iter_mut()) {
+            let ResponderRule::Periodic {
+                report,
+                interval_ms,
+            } = rule
+            else {
+                continue;
+            };
+            if let Some(next) = state.next_due.filter(|t| *t <= now) {
+                // Skip missed intervals rather than bursting to catch up
+                let interval = periodic_interval(*interval_ms);
+                let mut next = next + interval;
+                while next <= now {
+                    next += interval;
+                }
+                state.next_due = Some(next);
+                due.push(*report);
+            }
+        }
+        due
+    }
+
+    /// Earliest time a periodic report is due
+    pub fn next_deadline(&self) -> Option<Instant> {
+        self.states.iter().filter_map(|s| s.next_due).min()
+    }
+}
+
+/// Shortest periodic interval, so a zero can't spin the radio task
+const MIN_PERIODIC_INTERVAL: Duration = Duration::from_millis(10);
+
+fn periodic_interval(interval_ms: u64) -> Duration {
+    Duration::from_millis(interval_ms).max(MIN_PERIODIC_INTERVAL)
+}
+
+/// Whether a command frame is the one a rule names
+fn command_matches(protocol: Protocol, command: &str, frame: &[u8]) -> bool {
+    match protocol {
+        Protocol::IcomCIV => {
+            // FE FE <to> <from> <cmd> [<subcmd> ...] FD
+            let Some(wanted) = parse_hex(command) else {
+                return false;
+            };
+            frame.len() > 4 && !wanted.is_empty() && frame[4..].starts_with(&wanted)
+        }
+        Protocol::Yaesu => {
+            // Four parameter bytes, then the opcode
+            let Some(wanted) = parse_hex(command) else {
+                return false;
+            };
+            wanted.len() == 1 && frame.get(4) == Some(&wanted[0])
+        }
+        Protocol::Kenwood | Protocol::Elecraft | Protocol::FlexRadio | Protocol::YaesuAscii => {
+            !command.is_empty() && frame.starts_with(command.trim().as_bytes())
+        }
+    }
+}
+
+fn parse_hex(s: &str) -> Option<Vec<u8>> {
+    s.split_whitespace()
+        .map(|b| u8::from_str_radix(b, 16).ok())
+        .collect()
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_meter_ramp_wraps() {
+        let mut engine = RuleEngine::new(vec![ResponderRule::MeterRamp {
+            command: "SM".to_string(),
+            meter: MeterKind::Signal,
+            step: 100,
+        }]);
+        let levels: Vec<_> = (0..4)
+            .map(|_| match engine.on_command(Protocol::Kenwood, b"SM0;") {
+                RuleOutcome::Respond(RadioResponse::Meter { level, .. }) => level,
+                other => panic!("unexpected {:?}", other),
+            })
+            .collect();
+        assert_eq!(levels, vec![0, 100, 200, 0]);
+        assert_eq!(
+            engine.on_command(Protocol::Kenwood, b"FA;"),
+            RuleOutcome::Pass
+        );
+    }
+
+    #[test]
+    fn test_drop_every_nth() {
+        let mut engine = RuleEngine::new(vec![ResponderRule::DropEvery {
+            command: "03".to_string(),
+            every: 3,
+        }]);
+        let fa = [0xFE, 0xFE, 0x94, 0xE0, 0x03, 0xFD];
+        let outcomes: Vec<_> = (0..6)
+            .map(|_| engine.on_command(Protocol::IcomCIV, &fa))
+            .collect();
+        assert_eq!(outcomes[2], RuleOutcome::Drop);
+        assert_eq!(outcomes[5], RuleOutcome::Drop);
+        assert_eq!(
+            outcomes.iter().filter(|o| **o == RuleOutcome::Pass).count(),
+            4
+        );
+        // Mode query (0x04) doesn't match
+        let mode = [0xFE, 0xFE, 0x94, 0xE0, 0x04, 0xFD];
+        for _ in 0..3 {
+            assert_eq!(
+                engine.on_command(Protocol::IcomCIV, &mode),
+                RuleOutcome::Pass
+            );
+        }
+    }
+
+    #[test]
+    fn test_periodic_reports_skip_missed_intervals() {
+        let mut engine = RuleEngine::new(vec![ResponderRule::Periodic {
+            report: PeriodicReport::Status,
+            interval_ms: 500,
+        }]);
+        let first = engine.next_deadline().unwrap();
+        assert!(engine
+            .due_reports(first - Duration::from_millis(1))
+            .is_empty());
+        assert_eq!(engine.due_reports(first), vec![PeriodicReport::Status]);
+        assert_eq!(
+            engine.next_deadline(),
+            Some(first + Duration::from_millis(500))
+        );
+
+        // Late by several intervals: one report, then back on the grid
+        let late = first + Duration::from_millis(1700);
+        assert_eq!(engine.due_reports(late), vec![PeriodicReport::Status]);
+        assert_eq!(
+            engine.next_deadline(),
+            Some(first + Duration::from_millis(2000))
+        );
+    }
+
+    #[test]
+    fn test_describe() {
+        let rule = ResponderRule::DropEvery {
+            command: "FA".to_string(),
+            every: 5,
+        };
+        assert_eq!(rule.describe(), "Ignore every 5th FA");
+        assert_eq!(ordinal(2), "2nd");
+        assert_eq!(ordinal(12), "12th");
+        assert_eq!(ordinal(23), "23rd");
+    }
+}